@@ -0,0 +1,135 @@
+/*
+ * alu - n-bit two's complement add and subtract with status flags.
+ * The same result and flags as computerdesign.alu.ALU, for any width from
+ * 1 to 32 bits, so a 4-bit adder built from gates can be checked against it.
+ *
+ *        0111  (+7)              1001  (-7, or 9 unsigned)
+ *      + 0001  (+1)            + 1001  (-7, or 9 unsigned)
+ *      ------                  ------
+ *        1000  (-8!)          1 0010  (+2!, carry out)
+ *      overflow, no carry      overflow and carry
+ *
+ * CARRY is unsigned overflow: a bit came out of the top. OVERFLOW is signed
+ * overflow: both operands have the same sign and the result the other one.
+ * For subtraction carry means BORROW (a < b unsigned), as in the Java ALU;
+ * ARM sets its C flag the other way round.
+ */
+
+use crate::bits::mask;
+
+/// The flags a status register would hold after the operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Flags {
+    pub zero: bool,
+    pub negative: bool,
+    /// Carry out of the top bit (borrow for a subtraction).
+    pub carry: bool,
+    /// The signed result does not fit in `width` bits.
+    pub overflow: bool,
+}
+
+fn flags(result: u32, width: u32, carry: bool, overflow: bool) -> Flags {
+    Flags { zero: result == 0, negative: (result >> (width - 1)) & 1 == 1, carry, overflow }
+}
+
+fn check(width: u32) -> u32 {
+    assert!((1..=32).contains(&width), "width must be 1-32, got {}", width);
+    mask(width, 0)
+}
+
+/// a + b in `width` bits; operands are taken modulo 2^width.
+pub fn add(a: u32, b: u32, width: u32) -> (u32, Flags) {
+    let m = check(width);
+    let (a, b) = (a & m, b & m);
+    let wide = a as u64 + b as u64;
+    let result = wide as u32 & m;
+    let sign = 1 << (width - 1);
+    let overflow = (a ^ result) & (b ^ result) & sign != 0;
+    (result, flags(result, width, wide > m as u64, overflow))
+}
+
+/// a - b in `width` bits; carry is set when a < b unsigned (a borrow).
+pub fn sub(a: u32, b: u32, width: u32) -> (u32, Flags) {
+    let m = check(width);
+    let (a, b) = (a & m, b & m);
+    let result = a.wrapping_sub(b) & m;
+    let sign = 1 << (width - 1);
+    let overflow = (a ^ b) & (a ^ result) & sign != 0;
+    (result, flags(result, width, a < b, overflow))
+}
+
+/// The low `width` bits of `value` as a signed number.
+pub fn sign_extend(value: u32, width: u32) -> i32 {
+    check(width);
+    let shift = 32 - width;
+    ((value << shift) as i32) >> shift
+}
+
+/// Does `v` fit in a `width`-bit two's complement field?
+pub fn fits_signed(v: i64, width: u32) -> bool {
+    check(width);
+    let half = 1i64 << (width - 1);
+    (-half..half).contains(&v)
+}
+
+/// Does `v` fit in `width` unsigned bits?
+pub fn fits_unsigned(v: u64, width: u32) -> bool {
+    check(width);
+    v <= mask(width, 0) as u64
+}
+
+/// `v` clamped to the signed range of `width` bits.
+pub fn saturate_signed(v: i64, width: u32) -> i64 {
+    check(width);
+    let half = 1i64 << (width - 1);
+    v.clamp(-half, half - 1)
+}
+
+/// `v` clamped to 0 ..= 2^width - 1.
+pub fn saturate_unsigned(v: i64, width: u32) -> u32 {
+    v.clamp(0, check(width) as i64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eight_bits_match_std_for_every_pair() {
+        for a in 0..=255u32 {
+            for b in 0..=255u32 {
+                let (s, f) = add(a, b, 8);
+                assert_eq!(s, (a as u8).wrapping_add(b as u8) as u32);
+                assert_eq!(f.carry, (a as u8).overflowing_add(b as u8).1);
+                assert_eq!(f.overflow, (a as u8 as i8).overflowing_add(b as u8 as i8).1);
+                assert_eq!((f.zero, f.negative), (s == 0, s >= 0x80));
+
+                let (d, f) = sub(a, b, 8);
+                assert_eq!(d, (a as u8).wrapping_sub(b as u8) as u32);
+                assert_eq!(f.carry, a < b);
+                assert_eq!(f.overflow, (a as u8 as i8).overflowing_sub(b as u8 as i8).1);
+            }
+        }
+    }
+
+    #[test]
+    fn the_worked_examples() {
+        assert_eq!(add(0b0111, 0b0001, 4), (0b1000, Flags { zero: false, negative: true, carry: false, overflow: true }));
+        assert_eq!(add(0b1001, 0b1001, 4), (0b0010, Flags { zero: false, negative: false, carry: true, overflow: true }));
+        assert_eq!(add(u32::MAX, 1, 32), (0, Flags { zero: true, negative: false, carry: true, overflow: false }));
+        assert!(sub(0x8000_0000, 1, 32).1.overflow);
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(sign_extend(0xFFF, 12), -1);
+        assert_eq!(sign_extend(0x7FF, 12), 2047);
+        assert_eq!(sign_extend(0x8000_0000, 32), i32::MIN);
+        assert!(fits_signed(-2048, 12) && !fits_signed(2048, 12));
+        assert!(fits_unsigned(255, 8) && !fits_unsigned(256, 8));
+        assert_eq!(saturate_signed(1000, 8), 127);
+        assert_eq!(saturate_signed(-1000, 8), -128);
+        assert_eq!(saturate_unsigned(260, 8), 255);
+        assert_eq!(saturate_unsigned(-5, 8), 0);
+    }
+}
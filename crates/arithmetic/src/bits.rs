@@ -0,0 +1,124 @@
+/*
+ * bits - the bit tricks std has no single method for, on u32.
+ *
+ *   x           = 0101_1000
+ *   x - 1       = 0101_0111     borrowing flips the lowest 1 and the 0s below it
+ *   x & (x - 1) = 0101_0000     lowest set bit cleared
+ *   x & -x      = 0000_1000     lowest set bit isolated
+ *
+ * mask and extract are the field helpers an instruction decoder needs;
+ * the rest are shown and checked against std in the bits demo
+ * (cargo run --bin bits).
+ */
+
+/// Lowest set bit alone (0 for 0): x & -x in two's complement.
+pub fn lowest_set_bit(x: u32) -> u32 {
+    x & x.wrapping_neg()
+}
+
+/// x with its lowest set bit cleared: x & (x - 1).
+pub fn clear_lowest_set_bit(x: u32) -> u32 {
+    x & x.wrapping_sub(1)
+}
+
+/// Power of two? (exactly one bit set)
+pub fn is_power_of_two(x: u32) -> bool {
+    x != 0 && clear_lowest_set_bit(x) == 0
+}
+
+/// Smallest power of two >= x, or None if it does not fit in a u32.
+/// Smear the highest bit downwards, then add one. (std: checked_next_power_of_two)
+pub fn round_up_pow2(x: u32) -> Option<u32> {
+    if x <= 1 {
+        return Some(1);
+    }
+    let mut v = x - 1;
+    v |= v >> 1;
+    v |= v >> 2;
+    v |= v >> 4;
+    v |= v >> 8;
+    v |= v >> 16;
+    v.checked_add(1)
+}
+
+/// Largest power of two <= x (0 for 0).
+pub fn round_down_pow2(x: u32) -> u32 {
+    if x == 0 { 0 } else { 1 << (31 - x.leading_zeros()) }
+}
+
+/// floor(log2(x)): the index of the highest set bit.
+pub fn log2(x: u32) -> Option<u32> {
+    if x == 0 { None } else { Some(31 - x.leading_zeros()) }
+}
+
+/// Population count without the instruction: add bits in pairs, nibbles, bytes.
+pub fn popcount_swar(x: u32) -> u32 {
+    let x = x - ((x >> 1) & 0x5555_5555); // 2-bit sums
+    let x = (x & 0x3333_3333) + ((x >> 2) & 0x3333_3333); // 4-bit sums
+    let x = (x + (x >> 4)) & 0x0F0F_0F0F; // 8-bit sums
+    x.wrapping_mul(0x0101_0101) >> 24 // add the four bytes
+}
+
+/// Bit reversal by swapping halves, quarters, ... (std: reverse_bits).
+pub fn reverse(x: u32) -> u32 {
+    let x = ((x >> 1) & 0x5555_5555) | ((x & 0x5555_5555) << 1);
+    let x = ((x >> 2) & 0x3333_3333) | ((x & 0x3333_3333) << 2);
+    let x = ((x >> 4) & 0x0F0F_0F0F) | ((x & 0x0F0F_0F0F) << 4);
+    x.swap_bytes()
+}
+
+/// Mask of `n` ones starting at bit `lo`: mask(4, 8) = 0x0F00.
+pub fn mask(n: u32, lo: u32) -> u32 {
+    let ones = if n >= 32 { u32::MAX } else { (1u32 << n) - 1 };
+    ones << lo
+}
+
+/// Bits [hi:lo] of x, like a Verilog x[hi:lo].
+pub fn extract(x: u32, hi: u32, lo: u32) -> u32 {
+    (x >> lo) & mask(hi - lo + 1, 0)
+}
+
+/// Next larger number with the same number of set bits (Gosper's hack):
+/// enumerates all k-element subsets of a set in increasing order.
+pub fn next_same_popcount(x: u32) -> Option<u32> {
+    if x == 0 {
+        return None;
+    }
+    let low = lowest_set_bit(x);
+    let ripple = x.checked_add(low)?;
+    Some(ripple | (((x ^ ripple) >> 2) / low))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agree_with_std() {
+        for v in (0..=u32::MAX).step_by(65_537).chain([0, 1, u32::MAX, 1 << 31]) {
+            assert_eq!(popcount_swar(v), v.count_ones());
+            assert_eq!(reverse(v), v.reverse_bits());
+            assert_eq!(round_up_pow2(v), v.checked_next_power_of_two());
+            assert_eq!(is_power_of_two(v), v.is_power_of_two());
+            assert_eq!(log2(v), v.checked_ilog2());
+        }
+    }
+
+    #[test]
+    fn fields() {
+        assert_eq!(mask(4, 8), 0x0F00);
+        assert_eq!(mask(32, 0), u32::MAX);
+        assert_eq!(extract(0x00B5_0533, 11, 7), 10); // rd of add a0, a0, a1
+        assert_eq!(extract(0x8000_0000, 31, 31), 1);
+    }
+
+    #[test]
+    fn gosper_walks_the_subsets_in_order() {
+        let mut v = vec![0b111u32];
+        while let Some(&n) = v.last().filter(|&&n| n < 0b11100) {
+            v.push(next_same_popcount(n).unwrap());
+        }
+        assert_eq!(v.len(), 10); // C(5, 3)
+        assert!(v.windows(2).all(|w| w[0] < w[1] && w[1].count_ones() == 3));
+    }
+}
@@ -0,0 +1,85 @@
+/*
+ * float - the three fields of an IEEE 754 single, by shifts and masks.
+ *
+ *   31  30        23 22                    0
+ *   ┌──┬────────────┬───────────────────────┐
+ *   │s │ exponent   │ mantissa (fraction)   │    -6.25 = -1.5625 × 2^2
+ *   └──┴────────────┴───────────────────────┘
+ *    1   1000_0001    100_1000_0000_...        = 0xC0C80000
+ *
+ * Exponent 0 is zero or subnormal, 255 is infinity or NaN; everything else
+ * is a normal number with an implicit leading 1 and a bias of 127. Built on
+ * f32::to_bits, which needs no unsafe (see the reinterpret demo for why).
+ */
+
+/// IEEE 754 single-precision fields, taken from `to_bits` with shifts and masks.
+pub struct Inspect {
+    pub bits: u32,
+}
+
+impl Inspect {
+    pub fn new(x: f32) -> Self {
+        Inspect { bits: x.to_bits() }
+    }
+
+    pub fn sign(&self) -> u32 {
+        self.bits >> 31
+    }
+
+    pub fn exponent(&self) -> u32 {
+        (self.bits >> 23) & 0xFF
+    }
+
+    pub fn mantissa(&self) -> u32 {
+        self.bits & 0x7F_FFFF
+    }
+
+    pub fn class(&self) -> &'static str {
+        match (self.exponent(), self.mantissa()) {
+            (0, 0) => "zero",
+            (0, _) => "subnormal",
+            (0xFF, 0) => "infinity",
+            (0xFF, _) => "NaN",
+            _ => "normal",
+        }
+    }
+
+    /// The value spelled out from the fields; None for zero, infinity and NaN.
+    pub fn formula(&self) -> Option<String> {
+        match self.class() {
+            "normal" => Some(format!("(-1)^{} × 1.{:023b}₂ × 2^({} - 127 = {})",
+                self.sign(), self.mantissa(), self.exponent(), self.exponent() as i32 - 127)),
+            "subnormal" => Some(format!("(-1)^{} × 0.{:023b}₂ × 2^-126", self.sign(), self.mantissa())),
+            _ => None,
+        }
+    }
+
+    pub fn report(&self) -> String {
+        let value = self.formula().unwrap_or_else(|| self.class().to_string());
+        format!("  bits     0x{:08X}\n  sign     {}\n  exponent {:08b} ({})\n  mantissa {:023b}\n  class    {}\n  value    {}",
+            self.bits, self.sign(), self.exponent(), self.exponent(), self.mantissa(), self.class(), value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Inspect;
+
+    #[test]
+    fn fields_and_classes() {
+        let i = Inspect::new(-6.25); // -1.5625 × 2^2
+        assert_eq!((i.sign(), i.exponent(), i.mantissa()), (1, 129, 0x48_0000));
+        assert_eq!(i.bits, 0xC0C8_0000);
+        assert_eq!(Inspect::new(0.0).class(), "zero");
+        assert_eq!(Inspect::new(1.0e-40).class(), "subnormal");
+        assert_eq!(Inspect::new(f32::NEG_INFINITY).class(), "infinity");
+        assert_eq!(Inspect::new(f32::NAN).class(), "NaN");
+        assert_eq!(Inspect::new(1.0).class(), "normal");
+    }
+
+    #[test]
+    fn report_spells_out_the_value() {
+        let r = Inspect::new(-6.25).report();
+        assert!(r.contains("(-1)^1 × 1.10010000000000000000000₂ × 2^(129 - 127 = 2)"), "{}", r);
+    }
+}
@@ -0,0 +1,8 @@
+//! Integer and floating-point arithmetic as the hardware does it: n-bit
+//! add and subtract with status flags, the bit tricks behind popcount and
+//! masks, SWAR byte lanes and the fields of an IEEE 754 float.
+
+pub mod alu;
+pub mod bits;
+pub mod float;
+pub mod swar;
@@ -0,0 +1,53 @@
+/*
+ * swar - "SIMD within a register": eight u8 lanes in one u64.
+ * Plain integer instructions, no vector unit: mask off the top bit of each
+ * byte so no carry can cross into the next lane, then repair the top bits.
+ *
+ *   lanes   [ 250 | 100 | ... ]  +  [ 10 | 10 | ... ]
+ *   wrap    [   4 | 110 | ... ]      carry out of lane 0
+ *   sat     [ 255 | 110 | ... ]      that lane forced to 0xFF
+ *
+ * Used by the saturating_simd demo next to the SSE2/AVX2/NEON versions.
+ */
+
+/// `k` in every byte lane.
+pub const fn splat(k: u8) -> u64 {
+    k as u64 * 0x0101_0101_0101_0101
+}
+
+/// Eight wrapping byte adds in one u64.
+pub fn add_u8x8(a: u64, b: u64) -> u64 {
+    const HIGH: u64 = 0x8080_8080_8080_8080;
+    let low = (a & !HIGH) + (b & !HIGH);
+    low ^ ((a ^ b) & HIGH)
+}
+
+/// Eight saturating byte adds in one u64. Add the low 7 bits of each byte
+/// (no carry can cross into the next byte), fix the top bit with XOR, then
+/// every byte that carried out is forced to 0xFF.
+pub fn add_sat_u8x8(a: u64, b: u64) -> u64 {
+    const HIGH: u64 = 0x8080_8080_8080_8080;
+    let sum = add_u8x8(a, b);                                // wrapping sum of each byte
+    let carry = ((a & b) | ((a | b) & !sum)) & HIGH;         // top bit set where a byte overflowed
+    sum | ((carry >> 7) * 0xFF)                              // 0x80 → 0xFF in those bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_byte_pair_in_every_lane() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let x = u64::from_le_bytes([a, 1, a, 200, a, 0, a, 255]);
+                let y = splat(b);
+                let (wrap, sat) = (add_u8x8(x, y).to_le_bytes(), add_sat_u8x8(x, y).to_le_bytes());
+                for (i, &lane) in x.to_le_bytes().iter().enumerate() {
+                    assert_eq!(wrap[i], lane.wrapping_add(b));
+                    assert_eq!(sat[i], lane.saturating_add(b));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Cache::access on a 4 KiB 4-way L1: a word-by-word sweep that mostly hits
+//! and random addresses that mostly miss and evict.
+//!
+//!   cargo bench -p cache --bench access -- --save-baseline main
+//!   cargo bench -p cache --bench access -- --baseline main
+
+use cache::{Access, Cache, Config, Replacement, WritePolicy};
+use util::bench::{self, Case};
+use util::rng::Rng;
+
+const N: u32 = 16 * 1024;
+
+fn access(name: &'static str, addresses: Vec<u32>) -> Case {
+    let config = Config { size: 4 * 1024, line: 32, ways: 4, replacement: Replacement::Lru, write: WritePolicy::WriteBackAllocate };
+    let mut cache = Cache::new(config).expect("a valid geometry");
+    Case::new(name, "access", move || {
+        let mut hits = 0;
+        for (i, &a) in addresses.iter().enumerate() {
+            let kind = if i % 4 == 3 { Access::Write } else { Access::Read };
+            hits += u64::from(cache.access(a, kind).hit);
+        }
+        std::hint::black_box(hits);
+        addresses.len() as u64
+    })
+}
+
+fn main() {
+    let mut rng = Rng::new(4160);
+    bench::run("cache", vec![
+        access("cache.sequential", (0..N).map(|i| (i * 4) & 0x3FFF).collect()),
+        access("cache.random", (0..N).map(|_| rng.next() as u32 & 0xFFFC).collect()),
+    ]);
+}
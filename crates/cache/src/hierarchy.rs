@@ -0,0 +1,153 @@
+/*
+ * hierarchy - caches in levels in front of main memory.
+ *
+ *   access ─► L1 ─ miss ─► L2 ─ miss ─► memory
+ *              │            │
+ *              hit          hit           AMAT = t1 + m1 × (t2 + m2 × tmem)
+ *
+ * A miss in one level reads the line from the next; a dirty line evicted
+ * from a write-back level, and every write to a write-through level, is a
+ * write to the next. The miss rates in the formula are local: the misses
+ * of a level over the accesses that reached it, those write-backs from
+ * above included, as the textbook computes them.
+ */
+
+use crate::{Access, Cache, Outcome, WritePolicy};
+
+#[derive(Clone, Debug)]
+pub struct Level {
+    pub name: String,
+    pub cache: Cache,
+    /// Cycles for a hit in this level.
+    pub hit_time: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Hierarchy {
+    /// L1 first.
+    pub levels: Vec<Level>,
+    /// Cycles for main memory, behind the last level.
+    pub memory_time: f64,
+    pub memory_reads: u64,
+    pub memory_writes: u64,
+}
+
+impl Hierarchy {
+    pub fn new(levels: Vec<Level>, memory_time: f64) -> Hierarchy {
+        Hierarchy { levels, memory_time, memory_reads: 0, memory_writes: 0 }
+    }
+
+    /// What L1 did, and the level that had the data: levels.len() for
+    /// main memory.
+    pub fn access(&mut self, addr: u32, kind: Access) -> (Outcome, usize) {
+        self.access_level(0, addr, kind)
+    }
+
+    /// An access to the level below `i`, maybe main memory.
+    fn below(&mut self, i: usize, addr: u32, kind: Access) -> usize {
+        if i + 1 < self.levels.len() {
+            return self.access_level(i + 1, addr, kind).1;
+        }
+        match kind {
+            Access::Read => self.memory_reads += 1,
+            Access::Write => self.memory_writes += 1,
+        }
+        self.levels.len()
+    }
+
+    fn access_level(&mut self, i: usize, addr: u32, kind: Access) -> (Outcome, usize) {
+        let level = &mut self.levels[i];
+        let write_through = level.cache.config().write == WritePolicy::WriteThroughNoAllocate;
+        let out = level.cache.access(addr, kind);
+        match out.evicted {
+            Some(victim) if out.memory_write => {
+                self.below(i, victim, Access::Write);
+            }
+            _ if out.memory_write => {
+                let from = self.below(i, addr, Access::Write);
+                if !out.hit {
+                    return (out, from);
+                }
+            }
+            _ => {}
+        }
+        if out.hit || (kind == Access::Write && write_through) {
+            (out, i)
+        } else {
+            (out, self.below(i, addr, Access::Read))
+        }
+    }
+
+    /// Write back every dirty line, level by level down to memory.
+    pub fn flush(&mut self) -> u64 {
+        let mut total = 0;
+        for i in 0..self.levels.len() {
+            let lines = self.levels[i].cache.flush_lines();
+            total += lines.len() as u64;
+            for addr in lines {
+                self.below(i, addr, Access::Write);
+            }
+        }
+        total
+    }
+
+    /// AMAT from the last level up: t1 + m1 × (t2 + m2 × (... + tmem)).
+    pub fn amat(&self) -> f64 {
+        self.penalty(0)
+    }
+
+    /// The average time of an access that reaches level `i`.
+    pub fn penalty(&self, i: usize) -> f64 {
+        self.levels[i..].iter().rev().fold(self.memory_time, |below, l| crate::amat(l.hit_time, l.cache.stats().miss_rate(), below))
+    }
+
+    /// "1 + 0.250 × (10 + 0.100 × 100)"
+    pub fn formula(&self) -> String {
+        let mut s = self.memory_time.to_string();
+        for (i, l) in self.levels.iter().enumerate().rev() {
+            let below = if i + 1 < self.levels.len() { format!("({})", s) } else { s };
+            s = format!("{} + {:.3} × {}", l.hit_time, l.cache.stats().miss_rate(), below);
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, Replacement};
+
+    fn level(name: &str, size: u32, write: WritePolicy, hit_time: f64) -> Level {
+        let cache = Cache::new(Config { size, line: 16, ways: 1, replacement: Replacement::Lru, write }).unwrap();
+        Level { name: name.to_string(), cache, hit_time }
+    }
+
+    #[test]
+    fn second_pass_hits_in_l2() {
+        let mut h = Hierarchy::new(vec![level("L1", 64, WritePolicy::WriteBackAllocate, 1.0),
+            level("L2", 1024, WritePolicy::WriteBackAllocate, 10.0)], 100.0);
+        // 256 bytes do not fit in L1 but do in L2
+        let served: Vec<usize> = (0..2).flat_map(|_| (0..256).step_by(16).collect::<Vec<u32>>()).map(|a| h.access(a, Access::Read).1).collect();
+        assert_eq!(&served[..16], &[2; 16]);
+        assert_eq!(&served[16..], &[1; 16]);
+        assert_eq!((h.levels[1].cache.stats().hits, h.memory_reads), (16, 16));
+        assert_eq!(h.amat(), 1.0 + 1.0 * (10.0 + 0.5 * 100.0));
+        assert_eq!(h.formula(), "1 + 1.000 × (10 + 0.500 × 100)");
+    }
+
+    #[test]
+    fn write_backs_and_write_through_go_down() {
+        let mut h = Hierarchy::new(vec![level("L1", 32, WritePolicy::WriteBackAllocate, 1.0),
+            level("L2", 256, WritePolicy::WriteThroughNoAllocate, 5.0)], 50.0);
+        h.access(0, Access::Write);
+        // 32 maps to L1's set 0 too and evicts the dirty line at 0
+        h.access(32, Access::Read);
+        let l2 = h.levels[1].cache.stats();
+        assert_eq!((l2.reads, l2.writes), (2, 1));
+        // written through to memory, and both read misses
+        assert_eq!((h.memory_writes, h.memory_reads), (1, 2));
+        h.access(64, Access::Write);
+        assert_eq!(h.flush(), 1);
+        assert_eq!((h.levels[1].cache.stats().writes, h.memory_writes), (2, 2));
+    }
+}
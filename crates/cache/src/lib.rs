@@ -0,0 +1,416 @@
+//! A set-associative cache model that counts hits, misses and write-backs.
+//! It covers the same ground as computerdesign.memory.Cache, but it tracks
+//! only tags, so a trace of addresses is all it needs.
+//!
+//! ```text
+//!   address:  ┌──────────── tag ────────────┬── index ──┬─ offset ─┐
+//!                                                  │
+//!             set 0: [way 0][way 1]...             │ selects the set,
+//!             set 1: [way 0][way 1]...  ◄──────────┘ tag compared in every way
+//! ```
+//!
+//! `trace` reads Dinero address traces to drive it, and `hierarchy` puts
+//! caches in levels in front of main memory.
+
+pub mod hierarchy;
+pub mod trace;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Replacement {
+    Lru,
+    Fifo,
+    /// Pseudo-random, seeded, so runs repeat.
+    Random,
+}
+
+impl Replacement {
+    /// As a config file spells it: `replace = "lru"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Replacement::Lru => "lru",
+            Replacement::Fifo => "fifo",
+            Replacement::Random => "random",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Dirty lines go to memory when evicted; a write miss fetches the line.
+    WriteBackAllocate,
+    /// Every write goes to memory; a write miss does not fill the cache.
+    WriteThroughNoAllocate,
+}
+
+impl WritePolicy {
+    /// As a config file spells it: `write = "back"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            WritePolicy::WriteBackAllocate => "back",
+            WritePolicy::WriteThroughNoAllocate => "through",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Total data capacity in bytes.
+    pub size: u32,
+    /// Bytes per line (block).
+    pub line: u32,
+    /// Lines per set: 1 is direct mapped, size / line is fully associative.
+    pub ways: u32,
+    pub replacement: Replacement,
+    pub write: WritePolicy,
+}
+
+impl Config {
+    pub fn sets(&self) -> u32 {
+        self.size / (self.line * self.ways)
+    }
+
+    /// Bits of (tag, index, offset) in a 32-bit address.
+    pub fn split(&self) -> (u32, u32, u32) {
+        let offset = self.line.trailing_zeros();
+        let index = self.sets().trailing_zeros();
+        (32 - index - offset, index, offset)
+    }
+
+    /// Whether the geometry works out, on the numbers alone: powers of two,
+    /// and at least one set of `ways` lines.
+    pub fn check(&self) -> Result<(), String> {
+        for (name, v) in [("size", self.size), ("line", self.line), ("ways", self.ways)] {
+            if !v.is_power_of_two() {
+                return Err(format!("{} must be a power of two, got {}", name, v));
+            }
+        }
+        // checked: a line and a way count near 2^31 each wrap to 0
+        if self.line.checked_mul(self.ways).is_none_or(|set| set > self.size) {
+            return Err(format!("{} ways of {} B lines do not fit in {} B", self.ways, self.line, self.size));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (t, i, o) = self.split();
+        write!(f, "{} B, {} B lines, {}-way, {} sets (tag {} | index {} | offset {} bits), replace = \"{}\", write = \"{}\"",
+            self.size, self.line, self.ways, self.sets(), t, i, o, self.replacement.name(), self.write.name())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+/// What one access did.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub hit: bool,
+    pub set: u32,
+    /// Address of the line that was evicted to make room, if any.
+    pub evicted: Option<u32>,
+    /// A dirty line was written back (write-back) or the write went to
+    /// memory (write-through).
+    pub memory_write: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub reads: u64,
+    pub writes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub memory_writes: u64,
+}
+
+impl Stats {
+    pub fn accesses(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    pub fn hit_rate(&self) -> f64 {
+        if self.accesses() == 0 { 0.0 } else { self.hits as f64 / self.accesses() as f64 }
+    }
+
+    pub fn miss_rate(&self) -> f64 {
+        if self.accesses() == 0 { 0.0 } else { 1.0 - self.hit_rate() }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} accesses ({} reads, {} writes): {} hits, {} misses, hit rate {:.1}%, {} evictions, {} memory writes",
+            self.accesses(), self.reads, self.writes, self.hits, self.misses,
+            self.hit_rate() * 100.0, self.evictions, self.memory_writes)
+    }
+}
+
+/// AMAT = hit time + miss rate × miss penalty
+pub fn amat(hit_time: f64, miss_rate: f64, miss_penalty: f64) -> f64 {
+    hit_time + miss_rate * miss_penalty
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Line {
+    tag: u32,
+    dirty: bool,
+    /// Last use (LRU) or fill time (FIFO).
+    stamp: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Cache {
+    config: Config,
+    /// Only the sets that have been touched: a 4 GiB cache of 1 B lines has
+    /// 2^32 of them, and a trace fills few.
+    sets: BTreeMap<u32, Vec<Line>>,
+    stats: Stats,
+    clock: u64,
+    seed: u64,
+}
+
+impl Cache {
+    pub fn new(config: Config) -> Result<Cache, String> {
+        config.check()?;
+        Ok(Cache {
+            config,
+            sets: BTreeMap::new(),
+            stats: Stats::default(),
+            clock: 0,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        })
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// (tag, set index) of an address.
+    pub fn locate(&self, addr: u32) -> (u32, u32) {
+        let (_, index, offset) = self.config.split();
+        let set = (addr >> offset) & ((1 << index) - 1);
+        (addr.checked_shr(offset + index).unwrap_or(0), set)
+    }
+
+    fn line_address(&self, tag: u32, set: u32) -> u32 {
+        let (_, index, offset) = self.config.split();
+        (tag.checked_shl(index + offset).unwrap_or(0)) | set << offset
+    }
+
+    pub fn access(&mut self, addr: u32, kind: Access) -> Outcome {
+        self.clock += 1;
+        match kind {
+            Access::Read => self.stats.reads += 1,
+            Access::Write => self.stats.writes += 1,
+        }
+        let (tag, set) = self.locate(addr);
+        let write_back = self.config.write == WritePolicy::WriteBackAllocate;
+        let mut out = Outcome { hit: false, set, evicted: None, memory_write: false };
+
+        let lines = self.sets.entry(set).or_default();
+        if let Some(line) = lines.iter_mut().find(|l| l.tag == tag) {
+            out.hit = true;
+            self.stats.hits += 1;
+            if self.config.replacement == Replacement::Lru {
+                line.stamp = self.clock;
+            }
+            if kind == Access::Write {
+                if write_back {
+                    line.dirty = true;
+                } else {
+                    out.memory_write = true;
+                }
+            }
+        } else {
+            self.stats.misses += 1;
+            if kind == Access::Write && !write_back {
+                out.memory_write = true;
+            } else {
+                let fresh = Line { tag, dirty: kind == Access::Write, stamp: self.clock };
+                if lines.len() < self.config.ways as usize {
+                    lines.push(fresh);
+                } else {
+                    let victim = match self.config.replacement {
+                        Replacement::Random => {
+                            self.seed ^= self.seed << 13;
+                            self.seed ^= self.seed >> 7;
+                            self.seed ^= self.seed << 17;
+                            (self.seed % lines.len() as u64) as usize
+                        }
+                        _ => (0..lines.len()).min_by_key(|&w| lines[w].stamp).unwrap_or(0),
+                    };
+                    let old = std::mem::replace(&mut lines[victim], fresh);
+                    out.memory_write = old.dirty;
+                    out.evicted = Some(self.line_address(old.tag, set));
+                    self.stats.evictions += 1;
+                }
+            }
+        }
+        if out.memory_write {
+            self.stats.memory_writes += 1;
+        }
+        out
+    }
+
+    /// (tag, dirty) of the lines in a set, way by way.
+    pub fn lines(&self, set: u32) -> Vec<(u32, bool)> {
+        self.sets.get(&set).map_or_else(Vec::new, |lines| lines.iter().map(|l| (l.tag, l.dirty)).collect())
+    }
+
+    /// Write back every dirty line; returns how many there were.
+    pub fn flush(&mut self) -> u64 {
+        self.flush_lines().len() as u64
+    }
+
+    /// Write back every dirty line; returns their addresses.
+    pub fn flush_lines(&mut self) -> Vec<u32> {
+        let (_, index, offset) = self.config.split();
+        let mut written = Vec::new();
+        for (&set, lines) in self.sets.iter_mut() {
+            for line in lines.iter_mut().filter(|l| l.dirty) {
+                line.dirty = false;
+                written.push(line.tag.checked_shl(index + offset).unwrap_or(0) | set << offset);
+            }
+        }
+        self.stats.memory_writes += written.len() as u64;
+        written
+    }
+
+    /// Drop the line holding `addr`, written back if dirty, as clflush
+    /// does; false if it was not cached.
+    pub fn invalidate(&mut self, addr: u32) -> bool {
+        let (tag, set) = self.locate(addr);
+        let Some(lines) = self.sets.get_mut(&set) else { return false };
+        let Some(way) = lines.iter().position(|l| l.tag == tag) else { return false };
+        if lines.remove(way).dirty {
+            self.stats.memory_writes += 1;
+        }
+        true
+    }
+
+    pub fn amat(&self, hit_time: f64, miss_penalty: f64) -> f64 {
+        amat(hit_time, self.stats.miss_rate(), miss_penalty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(size: u32, line: u32, ways: u32, replacement: Replacement) -> Cache {
+        Cache::new(Config { size, line, ways, replacement, write: WritePolicy::WriteBackAllocate }).unwrap()
+    }
+
+    #[test]
+    fn address_split() {
+        let c = cache(1024, 16, 2, Replacement::Lru);
+        assert_eq!(c.config().sets(), 32);
+        assert_eq!(c.config().split(), (23, 5, 4));
+        assert_eq!(c.locate(0x1234_5678), (0x1234_5678 >> 9, (0x678 >> 4) & 31));
+        let full = cache(64, 16, 4, Replacement::Lru);
+        assert_eq!(full.config().split(), (28, 0, 4));
+        assert!(Cache::new(Config { size: 1000, ..*c.config() }).is_err());
+        assert!(Cache::new(Config { ways: 128, ..*c.config() }).is_err());
+        assert!(Cache::new(Config { line: 1 << 31, ways: 2, ..*c.config() }).is_err());
+    }
+
+    #[test]
+    fn spatial_locality_and_conflicts() {
+        let mut c = cache(256, 16, 1, Replacement::Lru);
+        for a in 0..64 {
+            c.access(a, Access::Read);
+        }
+        assert_eq!((c.stats().hits, c.stats().misses), (60, 4));
+        // 0 and 256 share set 0 of a direct-mapped cache and keep evicting each other
+        let mut c = cache(256, 16, 1, Replacement::Lru);
+        for _ in 0..4 {
+            c.access(0, Access::Read);
+            c.access(256, Access::Read);
+        }
+        assert_eq!(c.stats().hits, 0);
+        let mut c = cache(256, 16, 2, Replacement::Lru);
+        for _ in 0..4 {
+            c.access(0, Access::Read);
+            c.access(256, Access::Read);
+        }
+        assert_eq!(c.stats().hits, 6);
+    }
+
+    #[test]
+    fn lru_and_fifo_pick_different_victims() {
+        // one set of two ways: A B A C, then is B or A still there?
+        let run = |r| {
+            let mut c = cache(32, 16, 2, r);
+            for a in [0, 16, 0] {
+                c.access(a, Access::Read);
+            }
+            c.access(32, Access::Read).evicted
+        };
+        assert_eq!(run(Replacement::Lru), Some(16));
+        assert_eq!(run(Replacement::Fifo), Some(0));
+    }
+
+    #[test]
+    fn write_policies() {
+        let mut c = cache(32, 16, 1, Replacement::Lru); // two sets
+        assert!(!c.access(0, Access::Write).hit);
+        assert!(c.access(4, Access::Write).hit);
+        let out = c.access(32, Access::Read);
+        assert_eq!((out.evicted, out.memory_write), (Some(0), true));
+        c.access(48, Access::Write);
+        assert_eq!(c.flush(), 1);
+        assert_eq!(c.stats().memory_writes, 2);
+
+        let mut c = Cache::new(Config { write: WritePolicy::WriteThroughNoAllocate, ..*c.config() }).unwrap();
+        assert!(c.access(0, Access::Write).memory_write);
+        assert!(!c.access(0, Access::Read).hit); // no allocate
+        assert!(c.access(0, Access::Write).memory_write);
+        assert_eq!((c.stats().memory_writes, c.flush()), (2, 0));
+    }
+
+    #[test]
+    fn invalidate_one_line() {
+        let mut c = cache(64, 16, 2, Replacement::Lru);
+        c.access(0, Access::Write);
+        c.access(32, Access::Read);
+        assert!(c.invalidate(4));
+        assert!(!c.invalidate(0));
+        assert_eq!(c.stats().memory_writes, 1);
+        assert!(!c.access(0, Access::Read).hit);
+        assert!(c.access(32, Access::Read).hit);
+    }
+
+    #[test]
+    fn huge_geometry_allocates_lazily() {
+        // 2^31 sets of 1 B lines: one Vec each up front would need 48 GiB
+        let mut c = cache(1 << 31, 1, 1, Replacement::Lru);
+        assert_eq!(c.config().sets(), 1 << 31);
+        c.access(0x7FFF_FFFF, Access::Write);
+        assert_eq!(c.access(0xFFFF_FFFF, Access::Read).evicted, Some(0x7FFF_FFFF));
+        assert_eq!(c.lines(0), vec![]);
+        assert_eq!(c.flush_lines(), vec![]);
+    }
+
+    #[test]
+    fn amat_formula() {
+        assert_eq!(amat(1.0, 0.05, 100.0), 6.0);
+        let mut c = cache(256, 16, 4, Replacement::Random);
+        for a in (0..64).step_by(4) {
+            c.access(a, Access::Read);
+        }
+        assert_eq!(c.stats().miss_rate(), 0.25);
+        assert_eq!(c.amat(1.0, 20.0), 6.0);
+    }
+}
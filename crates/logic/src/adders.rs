@@ -0,0 +1,217 @@
+/*
+ * adders - ripple-carry and carry-lookahead adders built from gates.
+ * The same circuits as computerdesign.logic.Adders, with the same delays
+ * (its gate counts also include a zero-delay buffer on carry-out).
+ *
+ *   a3 b3       a2 b2       a1 b1       a0 b0
+ *    │  │        │  │        │  │        │  │
+ *   ┌┴──┴┐      ┌┴──┴┐      ┌┴──┴┐      ┌┴──┴┐
+ *   │ FA │◄─c3──│ FA │◄─c2──│ FA │◄─c1──│ FA │◄── c0
+ *   └┬───┘      └┬───┘      └┬───┘      └┬───┘
+ *  cout s3       s2          s1          s0
+ *
+ * Ripple: each carry waits for the one before it, 2 gate delays per bit.
+ * Lookahead: g_i = a_i·b_i, p_i = a_i ⊕ b_i and
+ *
+ *   c_{i+1} = g_i + p_i·g_{i-1} + ... + p_i···p_0·c_0
+ *
+ * is one AND level and one OR level for every carry. Groups of 4 bits use
+ * the flat equations; wider adders apply the same trick to the groups'
+ * G and P, one more level per factor 4 in width.
+ */
+
+use crate::gates::{Circuit, Signal};
+
+/// Group size of the carry-lookahead tree.
+pub const GROUP: usize = 4;
+
+/// The outputs of an n-bit adder and when each settled.
+#[derive(Debug)]
+pub struct Sum {
+    pub value: u32,
+    pub carry: bool,
+    /// Signed overflow: the carry into the top bit differs from the carry out.
+    pub overflow: bool,
+    /// s_i for every bit.
+    pub sums: Vec<Signal>,
+    /// The carry INTO bit i, then the carry out at index `width`.
+    pub carries: Vec<Signal>,
+    pub gates: usize,
+}
+
+impl Sum {
+    /// When the last output settles: the adder's critical path.
+    pub fn delay(&self) -> u32 {
+        self.sums.iter().chain(self.carries.last()).map(|s| s.at).max().unwrap_or(0)
+    }
+}
+
+pub fn half_adder(c: &mut Circuit, a: Signal, b: Signal) -> (Signal, Signal) {
+    (c.xor(&[a, b]), c.and(&[a, b]))
+}
+
+/// Two half adders and an OR: (sum, carry out).
+pub fn full_adder(c: &mut Circuit, a: Signal, b: Signal, cin: Signal) -> (Signal, Signal) {
+    let (t1, t2) = half_adder(c, a, b);
+    let (sum, t3) = half_adder(c, t1, cin);
+    (sum, c.or(&[t2, t3]))
+}
+
+fn check(width: u32) {
+    assert!((1..=32).contains(&width), "width must be 1-32, got {}", width);
+}
+
+fn finish(c: Circuit, sums: Vec<Signal>, carries: Vec<Signal>) -> Sum {
+    let value = sums.iter().enumerate().fold(0, |v, (i, s)| v | (s.value as u32) << i);
+    let n = sums.len();
+    Sum {
+        value,
+        carry: carries[n].value,
+        overflow: carries[n - 1].value != carries[n].value,
+        sums,
+        carries,
+        gates: c.gates(),
+    }
+}
+
+/// a + b + cin through `width` full adders in a chain.
+pub fn ripple_carry(a: u32, b: u32, cin: bool, width: u32) -> Sum {
+    check(width);
+    let mut c = Circuit::new();
+    let mut carries = vec![Signal::input(cin)];
+    let mut sums = Vec::new();
+    for i in 0..width {
+        let (s, cout) = full_adder(&mut c, Signal::bit(a, i), Signal::bit(b, i), carries[i as usize]);
+        sums.push(s);
+        carries.push(cout);
+    }
+    finish(c, sums, carries)
+}
+
+/// a + b + cin with hierarchical carry lookahead.
+pub fn carry_lookahead(a: u32, b: u32, cin: bool, width: u32) -> Sum {
+    check(width);
+    let mut c = Circuit::new();
+    let (mut g, mut p) = (Vec::new(), Vec::new());
+    for i in 0..width {
+        let (ai, bi) = (Signal::bit(a, i), Signal::bit(b, i));
+        g.push(c.and(&[ai, bi]));
+        p.push(c.xor(&[ai, bi]));
+    }
+    let carries = lookahead(&mut c, &g, &p, Signal::input(cin));
+    let sums = (0..width as usize).map(|i| c.xor(&[p[i], carries[i]])).collect();
+    finish(c, sums, carries)
+}
+
+/// c_0 ..= c_n for n (generate, propagate) pairs: flat for one group,
+/// otherwise the groups' own carries come from a lookahead over their G and P.
+fn lookahead(c: &mut Circuit, g: &[Signal], p: &[Signal], cin: Signal) -> Vec<Signal> {
+    let n = g.len();
+    if n <= GROUP {
+        return flat_carries(c, g, p, cin, n);
+    }
+    let (mut group_g, mut group_p) = (Vec::new(), Vec::new());
+    for (gs, ps) in g.chunks(GROUP).zip(p.chunks(GROUP)) {
+        group_g.push(group_generate(c, gs, ps));
+        group_p.push(if ps.len() == 1 { ps[0] } else { c.and(ps) });
+    }
+    let group_carries = lookahead(c, &group_g, &group_p, cin);
+    let mut carries = Vec::new();
+    for (k, (gs, ps)) in g.chunks(GROUP).zip(p.chunks(GROUP)).enumerate() {
+        // the group's carry out already comes from the level above
+        carries.extend(flat_carries(c, gs, ps, group_carries[k], gs.len() - 1));
+    }
+    carries.push(group_carries[group_carries.len() - 1]);
+    carries
+}
+
+/// cin, then c_1 ..= c_count with c_{i+1} = g_i + p_i·g_{i-1} + ... + p_i···p_0·cin.
+fn flat_carries(c: &mut Circuit, g: &[Signal], p: &[Signal], cin: Signal, count: usize) -> Vec<Signal> {
+    let mut carries = vec![cin];
+    for i in 0..count {
+        let mut terms = vec![g[i]];
+        for j in (0..=i).rev() {
+            let mut factors = p[j..=i].to_vec();
+            factors.push(if j > 0 { g[j - 1] } else { cin });
+            terms.push(c.and(&factors));
+        }
+        carries.push(c.or(&terms));
+    }
+    carries
+}
+
+/// G = g3 + p3·g2 + p3·p2·g1 + p3·p2·p1·g0: the group makes a carry by itself.
+fn group_generate(c: &mut Circuit, g: &[Signal], p: &[Signal]) -> Signal {
+    let top = g.len() - 1;
+    if top == 0 {
+        return g[0];
+    }
+    let mut terms = vec![g[top]];
+    for j in (0..top).rev() {
+        let mut factors = p[j + 1..=top].to_vec();
+        factors.push(g[j]);
+        terms.push(c.and(&factors));
+    }
+    c.or(&terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arithmetic::alu;
+    use arithmetic::bits::mask;
+
+    #[test]
+    fn four_bits_exhaustive() {
+        for a in 0..16 {
+            for b in 0..16 {
+                let (sum, flags) = alu::add(a, b, 4);
+                for adder in [ripple_carry, carry_lookahead] {
+                    let s = adder(a, b, false, 4);
+                    assert_eq!((s.value, s.carry, s.overflow), (sum, flags.carry, flags.overflow), "{} + {}", a, b);
+                    let s = adder(a, b, true, 4);
+                    assert_eq!(s.value as u64 | (s.carry as u64) << 4, a as u64 + b as u64 + 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wide_operands() {
+        let (mut a, mut b) = (0x1234_5678u32, 0xFEDC_BA98u32);
+        for width in [5, 8, 13, 16, 32] {
+            for _ in 0..200 {
+                a = a.wrapping_mul(0x9E37_79B9).wrapping_add(1);
+                b = b.rotate_left(7) ^ a;
+                let m = mask(width, 0);
+                let (sum, flags) = alu::add(a, b, width);
+                for adder in [ripple_carry, carry_lookahead] {
+                    let s = adder(a & m, b & m, false, width);
+                    assert_eq!((s.value, s.carry, s.overflow), (sum, flags.carry, flags.overflow));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn delays_and_gate_counts() {
+        // (width, ripple delay, ripple gates, lookahead delay, lookahead gates)
+        let expected = [(4, 9, 20, 4, 26), (8, 17, 40, 7, 57), (16, 33, 80, 8, 118), (32, 65, 160, 11, 241)];
+        for (width, rd, rg, ld, lg) in expected {
+            let r = ripple_carry(0, 0, false, width);
+            let l = carry_lookahead(0, 0, false, width);
+            assert_eq!((r.delay(), r.gates, l.delay(), l.gates), (rd, rg, ld, lg), "{} bits", width);
+        }
+    }
+
+    #[test]
+    fn eight_bit_settle_times() {
+        let r = ripple_carry(200, 100, false, 8);
+        let l = carry_lookahead(200, 100, false, 8);
+        assert_eq!(r.value, 300 & 0xFF);
+        assert!(r.carry);
+        assert_eq!(r.carries.iter().map(|s| s.at).collect::<Vec<_>>(), [0, 3, 5, 7, 9, 11, 13, 15, 17]);
+        assert_eq!(l.carries.iter().map(|s| s.at).collect::<Vec<_>>(), [0, 3, 3, 3, 4, 6, 6, 6, 5]);
+        assert_eq!(l.sums.iter().map(|s| s.at).collect::<Vec<_>>(), [2, 4, 4, 4, 5, 7, 7, 7]);
+    }
+}
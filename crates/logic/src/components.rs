@@ -0,0 +1,80 @@
+/*
+ * components - decoder and multiplexer from AND, OR and NOT.
+ *
+ *   s1 s0 ──► ┌─────────┐ ──► y0 = !s1·!s0          a ──┐
+ *             │ 2-to-4  │ ──► y1 = !s1· s0              AND ──┐
+ *             │ decoder │ ──► y2 =  s1·!s0         !s ──┘       OR ── y
+ *             └─────────┘ ──► y3 =  s1· s0          b ──┐       │
+ *                                                        AND ──┘
+ *                                                    s ──┘
+ *
+ * A multiplexer is a decoder whose outputs gate the data inputs, ORed
+ * together. In the 2-input mux the inverted select is one gate later than
+ * the select itself - the static hazard in examples/logic_demo/mux_hazard.net.
+ */
+
+use crate::gates::{Circuit, Signal};
+
+/// One-hot outputs for the select bits (select[0] least significant).
+pub fn decoder(c: &mut Circuit, select: &[Signal]) -> Vec<Signal> {
+    let inverted: Vec<Signal> = select.iter().map(|&s| c.not(s)).collect();
+    (0..1usize << select.len())
+        .map(|row| {
+            let terms: Vec<Signal> =
+                (0..select.len()).map(|i| if (row >> i) & 1 == 1 { select[i] } else { inverted[i] }).collect();
+            c.and(&terms)
+        })
+        .collect()
+}
+
+/// y = a·!s + b·s
+pub fn mux2(c: &mut Circuit, a: Signal, b: Signal, s: Signal) -> Signal {
+    let ns = c.not(s);
+    let left = c.and(&[a, ns]);
+    let right = c.and(&[b, s]);
+    c.or(&[left, right])
+}
+
+/// inputs[select]: 2^k data inputs for k select bits.
+pub fn mux(c: &mut Circuit, inputs: &[Signal], select: &[Signal]) -> Signal {
+    assert_eq!(inputs.len(), 1 << select.len(), "{} select bits need {} inputs", select.len(), 1 << select.len());
+    let lines = decoder(c, select);
+    let gated: Vec<Signal> = inputs.iter().zip(&lines).map(|(&d, &l)| c.and(&[d, l])).collect();
+    c.or(&gated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(x: u32, n: u32) -> Vec<Signal> {
+        (0..n).map(|i| Signal::bit(x, i)).collect()
+    }
+
+    #[test]
+    fn decoder_is_one_hot() {
+        for s in 0..8 {
+            let mut c = Circuit::new();
+            let out = decoder(&mut c, &bits(s, 3));
+            let hot: Vec<usize> = out.iter().enumerate().filter(|(_, y)| y.value).map(|(i, _)| i).collect();
+            assert_eq!(hot, [s as usize]);
+            assert_eq!(c.gates(), 3 + 8);
+        }
+    }
+
+    #[test]
+    fn mux_selects() {
+        let data = 0b1011_0010u32;
+        for s in 0..8 {
+            let mut c = Circuit::new();
+            let y = mux(&mut c, &bits(data, 8), &bits(s, 3));
+            assert_eq!(y.value, (data >> s) & 1 == 1);
+            assert_eq!(y.at, 4); // NOT, AND (decode), AND (gate), OR
+        }
+        for (a, b, s) in [(false, true, false), (true, false, false), (true, true, true), (false, true, true)] {
+            let mut c = Circuit::new();
+            let y = mux2(&mut c, Signal::input(a), Signal::input(b), Signal::input(s));
+            assert_eq!(y.value, if s { b } else { a });
+        }
+    }
+}
@@ -0,0 +1,147 @@
+/*
+ * fsm - Moore and Mealy state machines and their state tables.
+ *
+ *   Mealy "101" detector           present  in=0   in=1
+ *                                  S0       S0/0   S1/0
+ *   S0 --1/0--> S1 --0/0--> S2     S1       S2/0   S1/0
+ *   S2 --1/1--> S1                 S2       S0/0   S1/1
+ *
+ * A Moore machine's output belongs to the state, so it shows a cycle
+ * after the input that caused it and needs a state for "just seen it";
+ * a Mealy machine's output belongs to the transition and answers in the
+ * same cycle with one state fewer. computerdesign.logic.StateMachine
+ * loads either kind from a file; here they are built in code, and
+ * detector() builds the overlapping sequence detector of a pattern: state
+ * Sk means the last k inputs are the first k of the pattern, and an input
+ * goes to the longest prefix that is still a suffix of what was seen, as
+ * in the Knuth-Morris-Pratt automaton.
+ */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Moore,
+    Mealy,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transition {
+    pub to: usize,
+    /// Empty in a Moore machine.
+    pub output: String,
+}
+
+/// One clock cycle of a run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub state: usize,
+    pub input: usize,
+    pub next: usize,
+    pub output: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Machine {
+    pub name: String,
+    pub kind: Kind,
+    pub states: Vec<String>,
+    pub inputs: Vec<String>,
+    /// The output of each state in a Moore machine.
+    pub outputs: Vec<String>,
+    /// next[state][input]; the machine starts in state 0.
+    pub next: Vec<Vec<Transition>>,
+}
+
+impl Machine {
+    /// The overlapping detector of a pattern of 0s and 1s: output 1 when
+    /// the inputs so far end with it.
+    pub fn detector(pattern: &str, kind: Kind) -> Result<Machine, String> {
+        if pattern.is_empty() || !pattern.chars().all(|c| c == '0' || c == '1') {
+            return Err(format!("a pattern of 0s and 1s, not '{}'", pattern));
+        }
+        let n = pattern.len();
+        // the longest prefix of the pattern, at most `limit` long, that ends `seen`
+        let longest = |seen: &str, limit: usize| (0..=limit.min(seen.len())).rev().find(|&l| seen.ends_with(&pattern[..l])).unwrap_or(0);
+        let count = if kind == Kind::Moore { n + 1 } else { n };
+        let mut next = Vec::with_capacity(count);
+        for k in 0..count {
+            next.push(["0", "1"].iter().map(|b| {
+                let seen = format!("{}{}", &pattern[..k.min(n)], b);
+                let l = longest(&seen, n);
+                match kind {
+                    Kind::Moore => Transition { to: l, output: String::new() },
+                    Kind::Mealy if l == n => Transition { to: longest(&seen, n - 1), output: "1".to_string() },
+                    Kind::Mealy => Transition { to: l, output: "0".to_string() },
+                }
+            }).collect());
+        }
+        let outputs = match kind {
+            Kind::Moore => (0..count).map(|k| if k == n { "1" } else { "0" }.to_string()).collect(),
+            Kind::Mealy => Vec::new(),
+        };
+        Ok(Machine {
+            name: format!("{} detector", pattern),
+            kind,
+            states: (0..count).map(|k| format!("S{}", k)).collect(),
+            inputs: vec!["0".to_string(), "1".to_string()],
+            outputs,
+            next,
+        })
+    }
+
+    /// The output in `state` on `input`: the state's own in a Moore machine.
+    pub fn output(&self, state: usize, input: usize) -> &str {
+        match self.kind {
+            Kind::Moore => &self.outputs[state],
+            Kind::Mealy => &self.next[state][input].output,
+        }
+    }
+
+    /// A state table cell: "S1", or "S1/0" with the Mealy output.
+    pub fn cell(&self, state: usize, input: usize) -> String {
+        let t = &self.next[state][input];
+        match self.kind {
+            Kind::Moore => self.states[t.to].clone(),
+            Kind::Mealy => format!("{}/{}", self.states[t.to], t.output),
+        }
+    }
+
+    /// The input symbols by index; None for one that is not an input.
+    pub fn symbols(&self, text: &str) -> Option<Vec<usize>> {
+        text.chars().map(|c| self.inputs.iter().position(|i| *i == c.to_string())).collect()
+    }
+
+    pub fn run(&self, inputs: &[usize]) -> Vec<Step> {
+        let mut state = 0;
+        inputs.iter().map(|&input| {
+            let step = Step { state, input, next: self.next[state][input].to, output: self.output(state, input).to_string() };
+            state = step.next;
+            step
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mealy_101_overlaps() {
+        let m = Machine::detector("101", Kind::Mealy).unwrap();
+        assert_eq!(m.states.len(), 3);
+        assert_eq!([m.cell(2, 0), m.cell(2, 1)], ["S0/0", "S1/1"]);
+        let out: String = m.run(&m.symbols("0110101101").unwrap()).iter().map(|s| s.output.as_str()).collect();
+        assert_eq!(out, "0000101001");
+        assert!(Machine::detector("12", Kind::Mealy).is_err());
+    }
+
+    #[test]
+    fn moore_answers_a_cycle_later() {
+        let m = Machine::detector("110", Kind::Moore).unwrap();
+        assert_eq!((m.states.len(), m.outputs[3].as_str()), (4, "1"));
+        // from "110" a 1 leaves "1" matched, a 0 nothing
+        assert_eq!([m.cell(3, 0), m.cell(3, 1)], ["S0", "S1"]);
+        assert_eq!(m.cell(2, 1), "S2");
+        let steps = m.run(&m.symbols("11011").unwrap());
+        assert_eq!(steps.iter().map(|s| s.output.as_str()).collect::<String>(), "00010");
+    }
+}
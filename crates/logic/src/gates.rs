@@ -0,0 +1,183 @@
+/*
+ * gates - logic gates on signals that carry their arrival time.
+ *
+ *   a ──┐                     a, b arrive at t = 0
+ *       AND ── t1 ──┐         t1 settles at t = 1
+ *   b ──┘           OR ── y   y settles at t = 2 (one unit per gate)
+ *   c ──────────────┘
+ *
+ * Every gate costs one unit of delay whatever its fan-in, as in
+ * computerdesign.logic.Netlist, so the latest arrival of a circuit's
+ * outputs is its critical path. A Circuit counts the gates it builds.
+ */
+
+use std::fmt::Write;
+
+/// A wire's value and the gate delays until it settles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signal {
+    pub value: bool,
+    pub at: u32,
+}
+
+impl Signal {
+    /// A circuit input, valid from t = 0.
+    pub fn input(value: bool) -> Signal {
+        Signal { value, at: 0 }
+    }
+
+    /// Bit `i` of `x` as an input.
+    pub fn bit(x: u32, i: u32) -> Signal {
+        Signal::input((x >> i) & 1 == 1)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gate {
+    Not,
+    And,
+    Or,
+    Nand,
+    Nor,
+    Xor,
+    Xnor,
+}
+
+impl Gate {
+    pub const ALL: [Gate; 7] = [Gate::Not, Gate::And, Gate::Or, Gate::Nand, Gate::Nor, Gate::Xor, Gate::Xnor];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Gate::Not => "NOT",
+            Gate::And => "AND",
+            Gate::Or => "OR",
+            Gate::Nand => "NAND",
+            Gate::Nor => "NOR",
+            Gate::Xor => "XOR",
+            Gate::Xnor => "XNOR",
+        }
+    }
+
+    /// The output for these inputs. XOR of many inputs is odd parity; NOT
+    /// takes exactly one.
+    pub fn eval(self, inputs: &[bool]) -> bool {
+        assert!(!inputs.is_empty(), "{} needs an input", self.name());
+        let ones = inputs.iter().filter(|&&x| x).count();
+        match self {
+            Gate::Not => {
+                assert_eq!(inputs.len(), 1, "NOT takes one input");
+                !inputs[0]
+            }
+            Gate::And => ones == inputs.len(),
+            Gate::Or => ones > 0,
+            Gate::Nand => ones != inputs.len(),
+            Gate::Nor => ones == 0,
+            Gate::Xor => ones % 2 == 1,
+            Gate::Xnor => ones % 2 == 0,
+        }
+    }
+}
+
+/// Builds gates on signals and counts them.
+#[derive(Debug, Default)]
+pub struct Circuit {
+    gates: usize,
+}
+
+impl Circuit {
+    pub fn new() -> Circuit {
+        Circuit::default()
+    }
+
+    /// One gate: settles one unit after its last input.
+    pub fn gate(&mut self, gate: Gate, inputs: &[Signal]) -> Signal {
+        self.gates += 1;
+        let values: Vec<bool> = inputs.iter().map(|s| s.value).collect();
+        Signal { value: gate.eval(&values), at: inputs.iter().map(|s| s.at).max().unwrap_or(0) + 1 }
+    }
+
+    pub fn not(&mut self, a: Signal) -> Signal {
+        self.gate(Gate::Not, &[a])
+    }
+
+    pub fn and(&mut self, inputs: &[Signal]) -> Signal {
+        self.gate(Gate::And, inputs)
+    }
+
+    pub fn or(&mut self, inputs: &[Signal]) -> Signal {
+        self.gate(Gate::Or, inputs)
+    }
+
+    pub fn xor(&mut self, inputs: &[Signal]) -> Signal {
+        self.gate(Gate::Xor, inputs)
+    }
+
+    /// Gates built so far.
+    pub fn gates(&self) -> usize {
+        self.gates
+    }
+}
+
+/// Truth table of an n-input function, inputs counting up from all zeros.
+///
+///   a b │ y
+///   ────┼──
+///   0 0 │ 0
+pub fn truth_table<F: Fn(&[bool]) -> bool>(names: &[&str], f: F) -> String {
+    let n = names.len();
+    assert!(n <= 16, "truth table of {} inputs", n);
+    let mut s = format!("  {} │ y\n  {}┼──\n", names.join(" "),
+        "─".repeat(names.iter().map(|x| x.chars().count() + 1).sum::<usize>()));
+    for row in 0..1u32 << n {
+        let inputs: Vec<bool> = (0..n).map(|i| (row >> (n - 1 - i)) & 1 == 1).collect();
+        s.push_str("  ");
+        for (name, &x) in names.iter().zip(&inputs) {
+            let _ = write!(s, "{:<w$} ", x as u8, w = name.chars().count());
+        }
+        let _ = writeln!(s, "│ {}", f(&inputs) as u8);
+    }
+    s
+}
+
+/// Input rows (as numbers, first input most significant) where f is 1.
+pub fn minterms<F: Fn(&[bool]) -> bool>(n: usize, f: F) -> Vec<u32> {
+    (0..1u32 << n)
+        .filter(|&row| f(&(0..n).map(|i| (row >> (n - 1 - i)) & 1 == 1).collect::<Vec<_>>()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_input_truth_tables() {
+        let columns = |g: Gate| [[false, false], [false, true], [true, false], [true, true]].map(|x| g.eval(&x));
+        assert_eq!(columns(Gate::And), [false, false, false, true]);
+        assert_eq!(columns(Gate::Or), [false, true, true, true]);
+        assert_eq!(columns(Gate::Nand), [true, true, true, false]);
+        assert_eq!(columns(Gate::Nor), [true, false, false, false]);
+        assert_eq!(columns(Gate::Xor), [false, true, true, false]);
+        assert_eq!(columns(Gate::Xnor), [true, false, false, true]);
+        assert!(Gate::Not.eval(&[false]));
+    }
+
+    #[test]
+    fn delay_is_the_longest_path() {
+        let mut c = Circuit::new();
+        let (a, b, x) = (Signal::input(true), Signal::input(true), Signal::input(false));
+        let t = c.and(&[a, b]);
+        let y = c.or(&[t, x]);
+        assert_eq!(y, Signal { value: true, at: 2 });
+        assert_eq!(c.gates(), 2);
+    }
+
+    #[test]
+    fn majority() {
+        let maj = |x: &[bool]| x.iter().filter(|&&b| b).count() >= 2;
+        assert_eq!(minterms(3, maj), [3, 5, 6, 7]);
+        let t = truth_table(&["a", "b", "c"], maj);
+        assert!(t.contains("  0 1 1 │ 1\n"), "{}", t);
+        assert_eq!(t.lines().count(), 10);
+    }
+}
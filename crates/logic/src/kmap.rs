@@ -0,0 +1,205 @@
+/*
+ * kmap - Karnaugh maps and minimal sum-of-products, by Quine-McCluskey.
+ *
+ *          CD
+ *   AB     00 01 11 10        f = Σm(0, 2, 5, 7, 8, 10, 13, 15)
+ *   00      1  .  .  1
+ *   01      .  1  1  .        circles: the corners (B'D') and the
+ *   11      .  1  1  .        middle square (BD)
+ *   10      1  .  .  1        f = B'D' + BD
+ *
+ * Rows and columns are in Gray code order, so neighbours - including the
+ * wrap-around from the last row or column to the first - differ in one
+ * variable, and a group of 2^k of them drops k variables. minimize() does
+ * the circling by table as computerdesign.logic.QuineMcCluskey does:
+ * combine terms that differ in one bit until nothing combines (the prime
+ * implicants), take the essential ones, and cover the rest with the
+ * fewest further primes, fewest literals breaking ties. That last step
+ * tries the candidate sets in order of size, which gives the same answer
+ * as Petrick's method for the handful of primes of a map-sized function.
+ *
+ * Minterm numbers read the variables as a binary number, first variable
+ * most significant, as gates::minterms does.
+ */
+
+use std::fmt::Write;
+
+/// A product term: variables in `dashes` are absent, the others must
+/// equal their bit of `bits`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Implicant {
+    pub bits: u32,
+    pub dashes: u32,
+}
+
+impl Implicant {
+    pub fn minterm(m: u32) -> Implicant {
+        Implicant { bits: m, dashes: 0 }
+    }
+
+    pub fn covers(&self, m: u32) -> bool {
+        m & !self.dashes == self.bits
+    }
+
+    pub fn literals(&self, n: u32) -> u32 {
+        n - self.dashes.count_ones()
+    }
+
+    /// "01-1" for A'BD of four variables.
+    pub fn pattern(&self, n: u32) -> String {
+        (0..n).rev().map(|i| match (self.dashes >> i & 1, self.bits >> i & 1) {
+            (1, _) => '-',
+            (_, 1) => '1',
+            _ => '0',
+        }).collect()
+    }
+
+    /// "A'BD"; "1" for the term without variables.
+    pub fn term(&self, names: &[&str]) -> String {
+        let n = names.len() as u32;
+        let mut s = String::new();
+        for (i, name) in names.iter().enumerate() {
+            let bit = n - 1 - i as u32;
+            if self.dashes >> bit & 1 == 0 {
+                s.push_str(name);
+                if self.bits >> bit & 1 == 0 {
+                    s.push('\'');
+                }
+            }
+        }
+        if s.is_empty() { "1".to_string() } else { s }
+    }
+}
+
+/// Every prime implicant of the function with these ones and don't-cares.
+pub fn prime_implicants(ones: &[u32], dont_cares: &[u32]) -> Vec<Implicant> {
+    let mut current: Vec<Implicant> = ones.iter().chain(dont_cares).map(|&m| Implicant::minterm(m)).collect();
+    current.sort();
+    current.dedup();
+    let mut primes = Vec::new();
+    while !current.is_empty() {
+        let mut used = vec![false; current.len()];
+        let mut next = Vec::new();
+        for i in 0..current.len() {
+            for j in i + 1..current.len() {
+                let (a, b) = (current[i], current[j]);
+                let diff = a.bits ^ b.bits;
+                if a.dashes == b.dashes && diff.count_ones() == 1 {
+                    next.push(Implicant { bits: a.bits & !diff, dashes: a.dashes | diff });
+                    used[i] = true;
+                    used[j] = true;
+                }
+            }
+        }
+        primes.extend(current.iter().zip(&used).filter(|(_, &u)| !u).map(|(p, _)| *p));
+        next.sort();
+        next.dedup();
+        current = next;
+    }
+    primes.sort();
+    primes
+}
+
+/// A minimal cover of `ones` among the `primes`: (essential, the rest chosen).
+pub fn cover(n: u32, ones: &[u32], primes: &[Implicant]) -> (Vec<Implicant>, Vec<Implicant>) {
+    let mut essential: Vec<Implicant> = Vec::new();
+    for &m in ones {
+        let mut covering = primes.iter().filter(|p| p.covers(m));
+        if let (Some(&p), None) = (covering.next(), covering.next()) {
+            if !essential.contains(&p) {
+                essential.push(p);
+            }
+        }
+    }
+    let left: Vec<u32> = ones.iter().copied().filter(|&m| !essential.iter().any(|p| p.covers(m))).collect();
+    let candidates: Vec<Implicant> = primes.iter().copied()
+        .filter(|p| !essential.contains(p) && left.iter().any(|&m| p.covers(m))).collect();
+    assert!(candidates.len() <= 20, "{} candidate primes is beyond a Karnaugh map", candidates.len());
+    let chosen = (0u32..1 << candidates.len())
+        .filter(|set| left.iter().all(|&m| candidates.iter().enumerate().any(|(i, p)| set >> i & 1 == 1 && p.covers(m))))
+        .min_by_key(|set| {
+            let literals: u32 = candidates.iter().enumerate().filter(|(i, _)| set >> i & 1 == 1).map(|(_, p)| p.literals(n)).sum();
+            (set.count_ones(), literals, *set)
+        })
+        .unwrap_or(0);
+    let chosen = candidates.iter().enumerate().filter(|(i, _)| chosen >> i & 1 == 1).map(|(_, p)| *p).collect();
+    essential.sort();
+    (essential, chosen)
+}
+
+/// The minimal sum of products of an n-variable function.
+pub fn minimize(n: u32, ones: &[u32], dont_cares: &[u32]) -> Vec<Implicant> {
+    let primes = prime_implicants(ones, dont_cares);
+    let (mut terms, chosen) = cover(n, ones, &primes);
+    terms.extend(chosen);
+    terms.sort();
+    terms
+}
+
+/// "B'D' + BD"; "0" for no terms.
+pub fn sum_of_products(terms: &[Implicant], names: &[&str]) -> String {
+    if terms.is_empty() {
+        return "0".to_string();
+    }
+    terms.iter().map(|t| t.term(names)).collect::<Vec<_>>().join(" + ")
+}
+
+/// 0, 1, 3, 2: the reflected Gray code of `bits` bits.
+fn gray(bits: u32) -> Vec<u32> {
+    (0..1 << bits).map(|i| i ^ (i >> 1)).collect()
+}
+
+/// The map of a 2- to 4-variable function: 1, . for 0 and x for don't care.
+pub fn map(names: &[&str], ones: &[u32], dont_cares: &[u32]) -> String {
+    let n = names.len() as u32;
+    assert!((2..=4).contains(&n), "a Karnaugh map of {} variables", n);
+    let (row_bits, col_bits) = (n / 2, n - n / 2);
+    let rows = names[..row_bits as usize].concat();
+    let cols = names[row_bits as usize..].concat();
+    let label = |v: u32, bits: u32| format!("{:0w$b}", v, w = bits as usize);
+    let width = col_bits as usize + 1;
+    let mut s = format!("  {:w$}  {}\n", "", cols, w = rows.len().max(row_bits as usize));
+    let _ = write!(s, "  {:w$} ", rows, w = rows.len().max(row_bits as usize));
+    for c in gray(col_bits) {
+        let _ = write!(s, " {:>w$}", label(c, col_bits), w = width - 1);
+    }
+    s.push('\n');
+    for r in gray(row_bits) {
+        let _ = write!(s, "  {:w$} ", label(r, row_bits), w = rows.len().max(row_bits as usize));
+        for c in gray(col_bits) {
+            let m = r << col_bits | c;
+            let cell = if ones.contains(&m) { "1" } else if dont_cares.contains(&m) { "x" } else { "." };
+            let _ = write!(s, " {:>w$}", cell, w = width - 1);
+        }
+        s.push('\n');
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABCD: [&str; 4] = ["A", "B", "C", "D"];
+
+    #[test]
+    fn corners_and_middle() {
+        let ones = [0, 2, 5, 7, 8, 10, 13, 15];
+        let terms = minimize(4, &ones, &[]);
+        assert_eq!(sum_of_products(&terms, &ABCD), "B'D' + BD");
+        assert_eq!(terms.iter().map(|t| t.pattern(4)).collect::<Vec<_>>(), ["-0-0", "-1-1"].map(String::from));
+        assert_eq!(map(&ABCD, &ones, &[]).lines().nth(2), Some("  00   1  .  .  1"));
+    }
+
+    #[test]
+    fn dont_cares_and_cyclic_cores() {
+        // Σm(1, 3, 7, 11, 15) + d(0, 2, 5): A'B' + CD
+        assert_eq!(sum_of_products(&minimize(4, &[1, 3, 7, 11, 15], &[0, 2, 5]), &ABCD), "A'B' + CD");
+        // the cyclic function Σm(0, 1, 2, 5, 6, 7) has no essential prime and needs three terms
+        let primes = prime_implicants(&[0, 1, 2, 5, 6, 7], &[]);
+        let (essential, chosen) = cover(3, &[0, 1, 2, 5, 6, 7], &primes);
+        assert_eq!((primes.len(), essential.len(), chosen.len()), (6, 0, 3));
+        assert_eq!(sum_of_products(&minimize(2, &[0, 1, 2, 3], &[]), &["A", "B"]), "1");
+        assert_eq!(sum_of_products(&minimize(3, &[], &[]), &["A", "B", "C"]), "0");
+    }
+}
@@ -0,0 +1,10 @@
+//! Combinational logic at gate level: gates on signals that carry their
+//! settle time, adders (ripple-carry and carry-lookahead), decoders and
+//! multiplexers, with gate counts and critical-path delays, Karnaugh maps
+//! minimized by Quine-McCluskey, and Moore and Mealy state machines.
+
+pub mod adders;
+pub mod components;
+pub mod fsm;
+pub mod gates;
+pub mod kmap;
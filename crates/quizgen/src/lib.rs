@@ -0,0 +1,534 @@
+//! Exam-style problems with worked solutions: two's complement, cache
+//! address breakdown, pipeline stalls and Karnaugh maps. The solutions are
+//! computed by the workspace's own models - arithmetic::alu, cache::Cache,
+//! riscv::pipeline and logic::kmap - so a problem and its answer cannot
+//! disagree with what the simulators show.
+//!
+//! Everything is drawn from a util::rng::Rng, so the same seed gives the
+//! same problem set: hand out the problems, print the solutions later.
+//! A pipeline problem is also there as `Hazards`, its code and schedules,
+//! for checking an answer as it is given (tdt4160 quiz --ask). The text is
+//! in the language of util::lang, and the answers are the same in both.
+//!
+//! ```text
+//! let set = quizgen::problem_set(4160, 4, &quizgen::Topic::ALL);
+//! ```
+
+use std::fmt::Write;
+
+use arithmetic::alu;
+use arithmetic::bits::mask;
+use cache::{Access, Cache, Config, Replacement, WritePolicy};
+use logic::kmap::{self, Implicant};
+use riscv::instruction::{AluOp, Instruction, Width};
+use riscv::pipeline::Schedule;
+use util::lang::{self, fill, Catalog, Lang};
+use util::rng::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topic {
+    TwosComplement,
+    Cache,
+    Pipeline,
+    Kmap,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 4] = [Topic::TwosComplement, Topic::Cache, Topic::Pipeline, Topic::Kmap];
+
+    /// The short name used on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Topic::TwosComplement => "twos",
+            Topic::Cache => "cache",
+            Topic::Pipeline => "pipeline",
+            Topic::Kmap => "kmap",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        tr(match self {
+            Topic::TwosComplement => "two's complement",
+            Topic::Cache => "cache addressing",
+            Topic::Pipeline => "pipeline hazards",
+            Topic::Kmap => "Karnaugh map",
+        })
+    }
+
+    pub fn parse(name: &str) -> Option<Topic> {
+        Topic::ALL.into_iter().find(|t| t.name() == name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub topic: Topic,
+    pub question: String,
+    /// The worked solution, step by step.
+    pub solution: String,
+    /// The final answer on one line.
+    pub answer: String,
+}
+
+/// `count` problems, taking the topics in turn.
+pub fn problem_set(seed: u64, count: usize, topics: &[Topic]) -> Vec<Problem> {
+    let mut rng = Rng::new(seed);
+    (0..count).map(|i| generate(topics[i % topics.len()], &mut rng)).collect()
+}
+
+pub fn generate(topic: Topic, rng: &mut Rng) -> Problem {
+    match topic {
+        Topic::TwosComplement => twos_complement(rng),
+        Topic::Cache => cache_addressing(rng),
+        Topic::Pipeline => pipeline_stalls(rng),
+        Topic::Kmap => karnaugh(rng),
+    }
+}
+
+fn pick<T: Copy>(rng: &mut Rng, items: &[T]) -> T {
+    items[rng.below(items.len())]
+}
+
+/// 1010_0011: binary in groups of four.
+fn bin(v: u32, width: u32) -> String {
+    let digits = format!("{:0w$b}", v & mask(width, 0), w = width as usize);
+    let mut s = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (width as usize - i).is_multiple_of(4) {
+            s.push('_');
+        }
+        s.push(c);
+    }
+    s
+}
+
+/// "1 stall", "2 stalls"; in Norwegian from NOUNS.
+pub fn count(n: impl std::fmt::Display + PartialEq<u32>, what: &str) -> String {
+    let one = n == 1;
+    match NOUNS.iter().find(|(en, _, _)| *en == what) {
+        Some((_, singular, plural)) if lang::current() == Lang::No => format!("{} {}", n, if one { singular } else { plural }),
+        _ => format!("{} {}{}", n, what, if one { "" } else { "s" }),
+    }
+}
+
+fn hex(v: u32, width: u32) -> String {
+    format!("0x{:0w$X}", v & mask(width, 0), w = width.div_ceil(4) as usize)
+}
+
+// ==================== TWO'S COMPLEMENT ====================
+
+fn twos_complement(rng: &mut Rng) -> Problem {
+    let width = pick(rng, &[8, 8, 8, 16]);
+    let m = mask(width, 0);
+    let sign = 1u32 << (width - 1);
+    let mut s = String::new();
+    let topic = Topic::TwosComplement;
+    match rng.below(3) {
+        0 => {
+            let magnitude = 1 + rng.below(sign as usize) as u32;
+            let v = -(magnitude as i64);
+            let pattern = v as u32 & m;
+            let _ = writeln!(s, "|{}| = {} = {}", v, magnitude, bin(magnitude, width));
+            let _ = writeln!(s, "{}", fill(tr("invert every bit:  {}"), &[&bin(!magnitude, width)]));
+            let _ = writeln!(s, "{}", fill(tr("add 1:             {} = {}"), &[&bin(pattern, width), &hex(pattern, width)]));
+            let _ = writeln!(s, "{}", fill(tr("check: the sign bit weighs -{}, so -{} + {} = {}"),
+                &[&sign, &sign, &(pattern & !sign), &alu::sign_extend(pattern, width)]));
+            Problem {
+                topic,
+                question: fill(tr("Write {} as a {}-bit two's complement number, in binary and in hex."), &[&v, &width]),
+                solution: s,
+                answer: format!("{} = {}", bin(pattern, width), hex(pattern, width)),
+            }
+        }
+        1 => {
+            let pattern = rng.next() as u32 & m | if rng.below(4) > 0 { sign } else { 0 };
+            let value = alu::sign_extend(pattern, width);
+            if pattern & sign != 0 {
+                let _ = writeln!(s, "{}", fill(tr("the sign bit is 1, so the number is negative; it weighs -{}"), &[&sign]));
+                let _ = writeln!(s, "{}", fill(tr("the other bits are {} = {}"), &[&bin(pattern & !sign, width), &(pattern & !sign)]));
+                let _ = writeln!(s, "-{} + {} = {}", sign, pattern & !sign, value);
+                let _ = writeln!(s, "{}", fill(tr("(or: unsigned it is {}, minus 2^{} = {} gives {})"), &[&pattern, &width, &(m as u64 + 1), &value]));
+            } else {
+                let _ = writeln!(s, "{}", fill(tr("the sign bit is 0, so it reads as an ordinary binary number: {}"), &[&value]));
+            }
+            Problem {
+                topic,
+                question: fill(tr("What signed decimal value does the {}-bit two's complement pattern {} ({}) hold?"),
+                    &[&width, &bin(pattern, width), &hex(pattern, width)]),
+                solution: s,
+                answer: value.to_string(),
+            }
+        }
+        _ => {
+            let (a, b) = (rng.next() as u32 & m, rng.next() as u32 & m);
+            let (result, flags) = alu::add(a, b, width);
+            let (sa, sb) = (alu::sign_extend(a, width) as i64, alu::sign_extend(b, width) as i64);
+            let _ = writeln!(s, "  {}\n+ {}\n= {}", bin(a, width), bin(b, width), bin(result, width));
+            let _ = writeln!(s, "{}", fill(tr("unsigned: {} + {} = {}, {} {} bits: C = {}"),
+                &[&a, &b, &(a as u64 + b as u64), &tr(if flags.carry { "more than fits in" } else { "fits in" }), &width, &(flags.carry as u8)]));
+            let _ = writeln!(s, "{}", fill(tr("signed:   {} + {} = {}, {} {} bits: V = {}"),
+                &[&sa, &sb, &(sa + sb), &tr(if flags.overflow { "does not fit in" } else { "fits in" }), &width, &(flags.overflow as u8)]));
+            if flags.overflow {
+                let _ = writeln!(s, "{}", tr("(both operands have the same sign and the result's sign differs)"));
+            }
+            let _ = writeln!(s, "{}", fill(tr("the result reads as {} unsigned and {} signed"), &[&result, &alu::sign_extend(result, width)]));
+            Problem {
+                topic,
+                question: fill(tr("Add {} and {} in {} bits. Give the result and say whether C (carry out) \
+                    and V (signed overflow) are set."), &[&bin(a, width), &bin(b, width), &width]),
+                solution: s,
+                answer: format!("{}, C = {}, V = {}", bin(result, width), flags.carry as u8, flags.overflow as u8),
+            }
+        }
+    }
+}
+
+// ==================== CACHE ADDRESSING ====================
+
+fn cache_addressing(rng: &mut Rng) -> Problem {
+    let config = Config {
+        size: 1 << (10 + rng.below(6)),
+        line: pick(rng, &[16, 32, 64]),
+        ways: pick(rng, &[1, 1, 2, 4]),
+        replacement: Replacement::Lru,
+        write: WritePolicy::WriteBackAllocate,
+    };
+    let mut cache = Cache::new(config).expect("a valid configuration");
+    let (tag_bits, index_bits, offset_bits) = config.split();
+    let same_set = config.sets() * config.line;
+
+    let mut addresses = vec![0x1000_0000 | (rng.next() as u32 & 0x00FF_FFFC)];
+    while addresses.len() < 6 {
+        let last = *addresses.last().unwrap_or(&0);
+        let next = match rng.below(5) {
+            0 => last & !(config.line - 1) | (rng.below(config.line as usize) as u32 & !3),
+            1 | 2 => last.wrapping_add(same_set * (1 + rng.below(2) as u32)),
+            3 => last.wrapping_add(config.line),
+            _ => addresses[rng.below(addresses.len())],
+        };
+        addresses.push(next);
+    }
+
+    let mut s = String::new();
+    let _ = writeln!(s, "{}", fill(tr("offset: {} B lines -> log2({}) = {} bits"), &[&config.line, &config.line, &offset_bits]));
+    let _ = writeln!(s, "{}", fill(tr("sets:   {} B / ({} B x {}) = {} -> {} index bits"),
+        &[&config.size, &config.line, &count(config.ways, "way"), &count(config.sets(), "set"), &index_bits]));
+    let _ = writeln!(s, "{}\n", fill(tr("tag:    32 - {} - {} = {} bits"), &[&index_bits, &offset_bits, &tag_bits]));
+    let _ = writeln!(s, "  {:<12} {:>10} {:>5} {:>6}  {}", tr("address"), "tag", tr("set"), "offset", tr("result"));
+    let mut seen = Vec::new();
+    let mut results = Vec::new();
+    for &addr in &addresses {
+        let (tag, set) = cache.locate(addr);
+        let outcome = cache.access(addr, Access::Read);
+        let result = tr(match (outcome.hit, seen.contains(&(tag, set))) {
+            (true, _) => "hit",
+            (false, false) => "miss (first use of the line)",
+            (false, true) => "miss (the line was evicted)",
+        });
+        seen.push((tag, set));
+        let evicted = outcome.evicted.map_or(String::new(), |line| fill(tr(", evicts the line at {}"), &[&format!("0x{:08X}", line)]));
+        let _ = writeln!(s, "  0x{:08X} {:>10} {:>5} {:>6}  {}{}", addr, format!("0x{:X}", tag), set, addr & (config.line - 1), result, evicted);
+        results.push(tr(if outcome.hit { "hit" } else { "miss" }));
+    }
+    let _ = writeln!(s, "\n{}", fill(tr("{} of {} accesses hit"), &[&cache.stats().hits, &addresses.len()]));
+
+    let list: Vec<String> = addresses.iter().map(|a| format!("0x{:08X}", a)).collect();
+    let kind = if config.ways == 1 { tr("direct-mapped").to_string() } else { fill(tr("{}-way set-associative"), &[&config.ways]) };
+    Problem {
+        topic: Topic::Cache,
+        question: fill(tr("A {} B {} cache has {} B lines and LRU replacement; addresses are 32 bits.\n\
+            a) How many bits are tag, index and offset?\n\
+            b) Starting empty, the CPU reads {}. Give the tag and set of each address and whether it hits."),
+            &[&config.size, &kind, &config.line, &list.join(", ")]),
+        solution: s,
+        answer: fill(tr("tag {} / index {} / offset {} bits; {}"), &[&tag_bits, &index_bits, &offset_bits, &results.join(", ")]),
+    }
+}
+
+// ==================== PIPELINE HAZARDS ====================
+
+const T: [u8; 4] = [5, 6, 7, 28];
+const A0: u8 = 10;
+
+fn pipeline_stalls(rng: &mut Rng) -> Problem {
+    Hazards::generate(rng).problem()
+}
+
+/// A pipeline problem: a few instructions and their schedules without and
+/// with forwarding, from which the answers are read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hazards {
+    pub code: Vec<Instruction>,
+    pub plain: Schedule,
+    pub forwarded: Schedule,
+}
+
+impl Hazards {
+    /// 4 to 6 loads, stores and ALU instructions, mostly reading what was
+    /// just written, so there are hazards to find.
+    pub fn generate(rng: &mut Rng) -> Hazards {
+        let n = 4 + rng.below(3);
+        let mut written: Vec<u8> = Vec::new();
+        let mut code = Vec::new();
+        for _ in 0..n {
+            let source = |rng: &mut Rng| match written.len() {
+                0 => pick(rng, &T),
+                len if rng.below(3) > 0 => written[len - 1 - rng.below(len.min(2))],
+                _ => pick(rng, &T),
+            };
+            let offset = 4 * rng.below(8) as i32;
+            let instruction = match rng.below(if code.is_empty() { 2 } else { 5 }) {
+                0 => Instruction::Load { width: Width::Word, rd: pick(rng, &T), rs1: A0, offset },
+                1 => Instruction::OpImm { op: AluOp::Add, rd: pick(rng, &T), rs1: source(rng), imm: 1 + rng.below(15) as i32 },
+                2 => Instruction::Op { op: pick(rng, &[AluOp::Add, AluOp::Sub, AluOp::Or]), rd: pick(rng, &T), rs1: source(rng), rs2: source(rng) },
+                3 => Instruction::Store { width: Width::Word, rs1: A0, rs2: source(rng), offset },
+                _ => Instruction::Load { width: Width::Word, rd: pick(rng, &T), rs1: A0, offset },
+            };
+            written.extend(instruction.dest());
+            code.push(instruction);
+        }
+        Hazards { plain: Schedule::new(&code, false), forwarded: Schedule::new(&code, true), code }
+    }
+
+    pub fn schedule(&self, forwarding: bool) -> &Schedule {
+        if forwarding { &self.forwarded } else { &self.plain }
+    }
+
+    /// The instructions that wait in ID, numbered from 1 as in the listing.
+    pub fn stalled(&self, forwarding: bool) -> Vec<usize> {
+        self.schedule(forwarding).slots.iter().enumerate().filter(|(_, s)| s.stalls > 0).map(|(i, _)| i + 1).collect()
+    }
+
+    /// "  1. lw t0, 0(a0)" and so on, one line each.
+    pub fn listing(&self) -> String {
+        self.code.iter().enumerate().map(|(i, c)| format!("  {}. {}\n", i + 1, c)).collect()
+    }
+
+    /// The pipeline diagram, who waits for what, and the totals.
+    pub fn explain(&self, forwarding: bool) -> String {
+        let schedule = self.schedule(forwarding);
+        let mut s = String::new();
+        for line in schedule.diagram().lines() {
+            let _ = writeln!(s, "  {}", line);
+        }
+        for (i, slot) in schedule.slots.iter().enumerate() {
+            if let Some((producer, reg)) = slot.waits_for {
+                let _ = writeln!(s, "  {}", fill(tr("{}. {}: {}, waits for {} from {}. {}"), &[&(i + 1), &slot.instruction, &count(slot.stalls, "stall"),
+                    &riscv::instruction::ABI_NAMES[reg as usize], &(producer + 1), &schedule.slots[producer].instruction]));
+            }
+        }
+        let stalls = count(schedule.stalls(), "stall");
+        let _ = writeln!(s, "  {}", fill(tr("{}; {} cycles = 5 + {} more instructions + {}"), &[&stalls, &schedule.cycles(), &(self.code.len() - 1), &stalls]));
+        s
+    }
+
+    pub fn problem(&self) -> Problem {
+        let mut s = String::new();
+        let mut answer = Vec::new();
+        for forwarding in [false, true] {
+            let schedule = self.schedule(forwarding);
+            let _ = writeln!(s, "{}:\n\n{}", tr(if forwarding { "With forwarding" } else { "Without forwarding" }), self.explain(forwarding));
+            answer.push(fill(tr("{}: {}, {} cycles"), &[&tr(if forwarding { "with forwarding" } else { "without" }), &count(schedule.stalls(), "stall"), &schedule.cycles()]));
+        }
+        Problem {
+            topic: Topic::Pipeline,
+            question: fill(tr("The code below runs on the classic five-stage RISC-V pipeline (IF ID EX MEM WB; \
+                registers are written in the first half of a cycle and read in the second).\n\
+                How many stall cycles are there, and how many cycles from the first IF to the last WB,\n\
+                a) without forwarding and b) with forwarding?\n\n{}"), &[&self.listing().trim_end()]),
+            solution: s.trim_end().to_string() + "\n",
+            answer: answer.join("; "),
+        }
+    }
+}
+
+// ==================== KARNAUGH MAP ====================
+
+fn karnaugh(rng: &mut Rng) -> Problem {
+    let n = pick(rng, &[3, 4, 4, 4]);
+    let names = &["A", "B", "C", "D"][..n as usize];
+    let with_dont_cares = rng.below(2) == 0;
+    let (ones, dont_cares) = loop {
+        let (mut ones, mut dont_cares) = (Vec::new(), Vec::new());
+        for m in 0..1u32 << n {
+            match rng.below(20) {
+                0..=8 => ones.push(m),
+                9..=10 if with_dont_cares => dont_cares.push(m),
+                _ => {}
+            }
+        }
+        if ones.len() >= 2 && ones.len() + dont_cares.len() < (1 << n) {
+            break (ones, dont_cares);
+        }
+    };
+
+    let primes = kmap::prime_implicants(&ones, &dont_cares);
+    let (essential, chosen) = kmap::cover(n, &ones, &primes);
+    let terms = kmap::minimize(n, &ones, &dont_cares);
+    let covered = |p: &Implicant| {
+        let ms: Vec<String> = ones.iter().filter(|&&m| p.covers(m)).map(|m| m.to_string()).collect();
+        if ms.is_empty() { tr("only don't-cares").to_string() } else { format!("m{}", ms.join(", m")) }
+    };
+
+    let mut s = kmap::map(names, &ones, &dont_cares);
+    s.push_str(tr("\nprime implicants (groups that cannot grow):\n"));
+    for p in &primes {
+        let mark = tr(if essential.contains(p) { "essential" } else if chosen.contains(p) { "chosen" } else { "" });
+        let _ = writeln!(s, "{}", format!("  {}  {:<6} {:<26} {}", p.pattern(n), p.term(names), covered(p), mark).trim_end());
+    }
+    s.push_str(tr("\nessential: the only prime covering one of its minterms; "));
+    if chosen.is_empty() {
+        s.push_str(tr("they cover every 1.\n"));
+    } else {
+        s.push_str(tr("the chosen ones cover the rest with the fewest terms.\n"));
+    }
+    let literals: u32 = terms.iter().map(|t| t.literals(n)).sum();
+    let f = kmap::sum_of_products(&terms, names);
+    let _ = writeln!(s, "\nf = {}  ({}, {})", f, count(terms.len() as u32, "term"), count(literals, "literal"));
+
+    let list = |ms: &[u32]| ms.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(", ");
+    let d = if dont_cares.is_empty() { String::new() } else { format!(" + d({})", list(&dont_cares)) };
+    Problem {
+        topic: Topic::Kmap,
+        question: fill(tr("Minimize f({}) = Σm({}){} to a sum of products with a Karnaugh map{}."),
+            &[&names.join(", "), &list(&ones), &d, &tr(if dont_cares.is_empty() { "" } else { " (d: don't care)" })]),
+        solution: s,
+        answer: format!("f = {}", f),
+    }
+}
+
+// ==================== NORSK ====================
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+/// (English, Norwegian singular, Norwegian plural) for count().
+const NOUNS: &[(&str, &str, &str)] = &[
+    ("stall", "stopp", "stopp"),
+    ("stall cycle", "stoppsyklus", "stoppsykluser"),
+    ("way", "vei", "veier"),
+    ("set", "sett", "sett"),
+    ("term", "ledd", "ledd"),
+    ("literal", "literal", "literaler"),
+];
+
+const NO: Catalog = &[
+    ("two's complement", "toerkomplement"),
+    ("cache addressing", "cacheadressering"),
+    ("pipeline hazards", "samlebåndshasarder"),
+    ("Karnaugh map", "Karnaugh-diagram"),
+    ("invert every bit:  {}", "inverter hver bit: {}"),
+    ("add 1:             {} = {}", "legg til 1:        {} = {}"),
+    ("check: the sign bit weighs -{}, so -{} + {} = {}", "kontroll: fortegnsbiten veier -{}, så -{} + {} = {}"),
+    ("Write {} as a {}-bit two's complement number, in binary and in hex.",
+        "Skriv {} som et {}-bits tall i toerkomplement, binært og heksadesimalt."),
+    ("the sign bit is 1, so the number is negative; it weighs -{}", "fortegnsbiten er 1, så tallet er negativt; den veier -{}"),
+    ("the other bits are {} = {}", "de andre bitene er {} = {}"),
+    ("(or: unsigned it is {}, minus 2^{} = {} gives {})", "(eller: uten fortegn er det {}, minus 2^{} = {} gir {})"),
+    ("the sign bit is 0, so it reads as an ordinary binary number: {}", "fortegnsbiten er 0, så det leses som et vanlig binærtall: {}"),
+    ("What signed decimal value does the {}-bit two's complement pattern {} ({}) hold?",
+        "Hvilken desimalverdi med fortegn har {}-bits toerkomplementmønsteret {} ({})?"),
+    ("unsigned: {} + {} = {}, {} {} bits: C = {}", "uten fortegn: {} + {} = {}, {} {} bit: C = {}"),
+    ("signed:   {} + {} = {}, {} {} bits: V = {}", "med fortegn:  {} + {} = {}, {} {} bit: V = {}"),
+    ("more than fits in", "mer enn det er plass til i"),
+    ("fits in", "får plass i"),
+    ("does not fit in", "får ikke plass i"),
+    ("(both operands have the same sign and the result's sign differs)",
+        "(begge operandene har samme fortegn, og resultatet har et annet)"),
+    ("the result reads as {} unsigned and {} signed", "resultatet leses som {} uten fortegn og {} med fortegn"),
+    ("Add {} and {} in {} bits. Give the result and say whether C (carry out) \
+                    and V (signed overflow) are set.",
+        "Legg sammen {} og {} i {} bit. Oppgi resultatet, og si om C (mente ut) og V (overflyt med fortegn) er satt."),
+    ("offset: {} B lines -> log2({}) = {} bits", "offset: linjer på {} B -> log2({}) = {} bit"),
+    ("sets:   {} B / ({} B x {}) = {} -> {} index bits", "sett:   {} B / ({} B x {}) = {} -> {} indeksbit"),
+    ("tag:    32 - {} - {} = {} bits", "tag:    32 - {} - {} = {} bit"),
+    ("address", "adresse"),
+    ("set", "sett"),
+    ("result", "resultat"),
+    ("hit", "treff"),
+    ("miss", "bom"),
+    ("miss (first use of the line)", "bom (linjen brukes for første gang)"),
+    ("miss (the line was evicted)", "bom (linjen ble kastet ut)"),
+    (", evicts the line at {}", ", kaster ut linjen på {}"),
+    ("{} of {} accesses hit", "{} av {} aksesser treffer"),
+    ("direct-mapped", "direkteavbildet"),
+    ("{}-way set-associative", "{}-veis sett-assosiativ"),
+    ("A {} B {} cache has {} B lines and LRU replacement; addresses are 32 bits.\n\
+            a) How many bits are tag, index and offset?\n\
+            b) Starting empty, the CPU reads {}. Give the tag and set of each address and whether it hits.",
+        "En {} B {} cache har linjer på {} B og LRU-utskifting; adressene er på 32 bit.\n\
+        a) Hvor mange bit er tag, indeks og offset?\n\
+        b) Cachen er tom, og prosessoren leser {}. Oppgi tag og sett for hver adresse, og om den treffer."),
+    ("tag {} / index {} / offset {} bits; {}", "tag {} / indeks {} / offset {} bit; {}"),
+    ("{}. {}: {}, waits for {} from {}. {}", "{}. {}: {}, venter på {} fra {}. {}"),
+    ("{}; {} cycles = 5 + {} more instructions + {}", "{}; {} sykluser = 5 + {} instruksjoner til + {}"),
+    ("With forwarding", "Med forwarding"),
+    ("Without forwarding", "Uten forwarding"),
+    ("with forwarding", "med forwarding"),
+    ("without", "uten"),
+    ("{}: {}, {} cycles", "{}: {}, {} sykluser"),
+    ("The code below runs on the classic five-stage RISC-V pipeline (IF ID EX MEM WB; \
+                registers are written in the first half of a cycle and read in the second).\n\
+                How many stall cycles are there, and how many cycles from the first IF to the last WB,\n\
+                a) without forwarding and b) with forwarding?\n\n{}",
+        "Koden under kjører på det klassiske RISC-V-samlebåndet med fem steg (IF ID EX MEM WB; \
+        registre skrives i første halvdel av en syklus og leses i andre).\n\
+        Hvor mange stoppsykluser blir det, og hvor mange sykluser fra første IF til siste WB,\n\
+        a) uten forwarding og b) med forwarding?\n\n{}"),
+    ("only don't-cares", "bare don't care"),
+    ("\nprime implicants (groups that cannot grow):\n", "\nprimimplikanter (grupper som ikke kan bli større):\n"),
+    ("essential", "essensiell"),
+    ("chosen", "valgt"),
+    ("\nessential: the only prime covering one of its minterms; ",
+        "\nessensiell: den eneste primimplikanten som dekker en av mintermene sine; "),
+    ("they cover every 1.\n", "de dekker alle 1-ere.\n"),
+    ("the chosen ones cover the rest with the fewest terms.\n", "de valgte dekker resten med færrest mulig ledd.\n"),
+    ("Minimize f({}) = Σm({}){} to a sum of products with a Karnaugh map{}.",
+        "Forenkle f({}) = Σm({}){} til en sum av produkter med et Karnaugh-diagram{}."),
+    (" (d: don't care)", " (d: don't care)"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_seed_gives_the_same_set() {
+        let a = problem_set(4160, 8, &Topic::ALL);
+        assert_eq!(a, problem_set(4160, 8, &Topic::ALL));
+        assert_ne!(a, problem_set(4161, 8, &Topic::ALL));
+        assert_eq!(a.iter().map(|p| p.topic).collect::<Vec<_>>()[..4], Topic::ALL);
+        assert!(a.iter().all(|p| !p.question.is_empty() && !p.answer.is_empty() && !p.solution.is_empty()));
+    }
+
+    #[test]
+    fn answers_agree_with_the_models() {
+        for seed in 1..200 {
+            let mut rng = Rng::new(seed);
+            let p = generate(Topic::Kmap, &mut rng);
+            // the minimized function has the ones of the question
+            let ones: Vec<u32> = p.question.split("Σm(").nth(1).unwrap().split(')').next().unwrap()
+                .split(", ").map(|m| m.parse().unwrap()).collect();
+            let n = if p.question.contains("D)") { 4 } else { 3 };
+            let terms = kmap::minimize(n, &ones, &[]);
+            assert!(ones.iter().all(|&m| terms.iter().any(|t| t.covers(m))), "seed {}", seed);
+            generate(Topic::Pipeline, &mut rng);
+            generate(Topic::Cache, &mut rng);
+            generate(Topic::TwosComplement, &mut rng);
+        }
+        for seed in 1..200 {
+            let h = Hazards::generate(&mut Rng::new(seed));
+            for forwarding in [false, true] {
+                let stalled = h.stalled(forwarding);
+                assert_eq!(stalled.iter().map(|&i| h.schedule(forwarding).slots[i - 1].stalls).sum::<u32>(), h.schedule(forwarding).stalls());
+                assert!(stalled.iter().all(|&i| h.schedule(forwarding).slots[i - 1].waits_for.is_some()));
+            }
+            // forwarding only ever removes stalls
+            assert!(h.forwarded.stalls() <= h.plain.stalls(), "seed {}", seed);
+        }
+        assert_eq!(bin(0xA3, 8), "1010_0011");
+        assert_eq!(bin(0x5, 6), "00_0101");
+        assert_eq!(hex(0xA3, 8), "0xA3");
+    }
+}
@@ -0,0 +1,53 @@
+//! Cpu::step on a loop that loads, multiplies, stores and branches, so one
+//! number covers fetch, decode and the ALU, memory and branch paths.
+//!
+//!   cargo bench -p riscv --bench step -- --save-baseline main
+//!   cargo bench -p riscv --bench step -- --baseline main
+
+use riscv::instruction::{AluOp, BranchOp, Instruction, Width, HALT};
+use riscv::{Cpu, Memory, Trap};
+use util::bench::{self, Case};
+
+const LOOPS: i32 = 1000;
+const T0: u8 = 5;
+const T1: u8 = 6;
+const T2: u8 = 7;
+const S0: u8 = 8;
+
+/// s0 += mem[0x400] * 3, stored back, LOOPS times, then ebreak.
+fn loop_program() -> Vec<u32> {
+    use Instruction::*;
+    let program = [
+        OpImm { op: AluOp::Add, rd: T0, rs1: 0, imm: LOOPS },
+        OpImm { op: AluOp::Add, rd: T1, rs1: 0, imm: 0x400 },
+        OpImm { op: AluOp::Add, rd: S0, rs1: 0, imm: 0 },
+        Load { width: Width::Word, rd: T2, rs1: T1, offset: 0 },        // loop:
+        Op { op: AluOp::Mul, rd: T2, rs1: T2, rs2: T0 },
+        Op { op: AluOp::Add, rd: S0, rs1: S0, rs2: T2 },
+        Store { width: Width::Word, rs1: T1, rs2: S0, offset: 4 },
+        OpImm { op: AluOp::Add, rd: T0, rs1: T0, imm: -1 },
+        Branch { op: BranchOp::Bne, rs1: T0, rs2: 0, offset: -20 },
+    ];
+    program.iter().map(|i| i.encode()).chain([HALT]).collect()
+}
+
+fn step() -> Case {
+    let mut mem = Memory::new(0, 0x1000);
+    assert!(mem.load_words(0, &loop_program()) && mem.write(0x400, 4, 3));
+    let mut cpu = Cpu::new(mem, 0);
+    Case::new("cpu.step", "instr", move || {
+        cpu.pc = 0;
+        let mut steps = 0;
+        loop {
+            match cpu.step() {
+                Ok(_) => steps += 1,
+                Err(Trap::Breakpoint(_)) => return steps,
+                Err(trap) => panic!("loop program trapped: {}", trap),
+            }
+        }
+    })
+}
+
+fn main() {
+    bench::run("riscv", vec![step()]);
+}
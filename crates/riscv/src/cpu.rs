@@ -0,0 +1,414 @@
+/*
+ * cpu - an RV32IM interpreter: one instruction per step, precise traps.
+ *
+ *   ┌────────┐   fetch    ┌────────┐  decode  ┌─────────┐
+ *   │   pc   │ ─────────► │ memory │ ───────► │ execute │ ──► regs, memory, pc
+ *   └────────┘            └────────┘          └────┬────┘
+ *                                                  │ misaligned / out of range /
+ *                                                  ▼ illegal / ecall / ebreak
+ *                                                 Trap   (pc and state unchanged)
+ *
+ * A trap leaves the registers, memory and pc exactly as before the
+ * instruction, so the caller can handle it and resume: an ecall handler
+ * performs the system call and moves pc past it. ebreak is the HALT of
+ * the Java simulators. Data accesses must be naturally aligned, as
+ * RISC-V allows an implementation to require (see ExceptionHandler.java).
+ * Loads and stores go to the devices attached to the bus first, RAM
+ * otherwise, and every retired instruction is a tick of the devices.
+ */
+
+use crate::device::{Bus, Device};
+use crate::instruction::{decode, AluOp, BranchOp, Instruction, Width};
+use std::fmt;
+
+/// Why execution stopped; the numbers are the mcause exception codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trap {
+    MisalignedFetch(u32),
+    FetchFault(u32),
+    Illegal { pc: u32, word: u32 },
+    Breakpoint(u32),
+    MisalignedLoad(u32),
+    LoadFault(u32),
+    MisalignedStore(u32),
+    StoreFault(u32),
+    Ecall(u32),
+}
+
+impl Trap {
+    pub fn cause(&self) -> u32 {
+        match self {
+            Trap::MisalignedFetch(_) => 0,
+            Trap::FetchFault(_) => 1,
+            Trap::Illegal { .. } => 2,
+            Trap::Breakpoint(_) => 3,
+            Trap::MisalignedLoad(_) => 4,
+            Trap::LoadFault(_) => 5,
+            Trap::MisalignedStore(_) => 6,
+            Trap::StoreFault(_) => 7,
+            Trap::Ecall(_) => 11,
+        }
+    }
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Trap::MisalignedFetch(a) => write!(f, "instruction address misaligned: 0x{:08x}", a),
+            Trap::FetchFault(a) => write!(f, "instruction access fault: 0x{:08x}", a),
+            Trap::Illegal { pc, word } => write!(f, "illegal instruction 0x{:08x} at 0x{:08x}", word, pc),
+            Trap::Breakpoint(pc) => write!(f, "ebreak at 0x{:08x}", pc),
+            Trap::MisalignedLoad(a) => write!(f, "load address misaligned: 0x{:08x}", a),
+            Trap::LoadFault(a) => write!(f, "load access fault: 0x{:08x}", a),
+            Trap::MisalignedStore(a) => write!(f, "store address misaligned: 0x{:08x}", a),
+            Trap::StoreFault(a) => write!(f, "store access fault: 0x{:08x}", a),
+            Trap::Ecall(pc) => write!(f, "ecall at 0x{:08x}", pc),
+        }
+    }
+}
+
+// ==================== MEMORY ====================
+
+/// Little-endian RAM covering `base .. base + size`.
+#[derive(Clone, Debug)]
+pub struct Memory {
+    base: u32,
+    bytes: Vec<u8>,
+}
+
+impl Memory {
+    pub fn new(base: u32, size: usize) -> Memory {
+        Memory { base, bytes: vec![0; size] }
+    }
+
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    pub fn size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn range(&self, addr: u32, len: usize) -> Option<std::ops::Range<usize>> {
+        let start = addr.checked_sub(self.base)? as usize;
+        let end = start.checked_add(len)?;
+        if end <= self.bytes.len() { Some(start..end) } else { None }
+    }
+
+    /// The bytes at `addr`, None if any is outside the memory.
+    pub fn slice(&self, addr: u32, len: usize) -> Option<&[u8]> {
+        self.range(addr, len).map(|r| &self.bytes[r])
+    }
+
+    /// Copy `data` in at `addr`; false if it does not fit.
+    pub fn load(&mut self, addr: u32, data: &[u8]) -> bool {
+        match self.range(addr, data.len()) {
+            Some(r) => {
+                self.bytes[r].copy_from_slice(data);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Instruction words at `addr`, little-endian.
+    pub fn load_words(&mut self, addr: u32, words: &[u32]) -> bool {
+        let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+        self.load(addr, &bytes)
+    }
+
+    pub fn read(&self, addr: u32, len: usize) -> Option<u32> {
+        let s = self.slice(addr, len)?;
+        Some(s.iter().rev().fold(0, |v, &b| v << 8 | b as u32))
+    }
+
+    pub fn write(&mut self, addr: u32, len: usize, value: u32) -> bool {
+        self.load(addr, &value.to_le_bytes()[..len])
+    }
+}
+
+// ==================== THE PROCESSOR ====================
+
+#[derive(Debug)]
+pub struct Cpu {
+    regs: [u32; 32],
+    pub pc: u32,
+    pub mem: Memory,
+    /// Memory-mapped devices, in front of `mem`.
+    pub bus: Bus,
+    /// Instructions completed.
+    pub retired: u64,
+}
+
+impl Cpu {
+    pub fn new(mem: Memory, pc: u32) -> Cpu {
+        Cpu { regs: [0; 32], pc, mem, bus: Bus::default(), retired: 0 }
+    }
+
+    /// Put a device on the bus at `base`; it may not cover RAM or another device.
+    pub fn attach(&mut self, base: u32, irq: Option<u8>, device: Box<dyn Device>) -> Result<(), String> {
+        let (lo, hi) = (u64::from(self.mem.base()), u64::from(self.mem.base()) + self.mem.size() as u64);
+        if u64::from(base) < hi && lo < u64::from(base) + u64::from(device.size()) {
+            return Err(format!("{} at 0x{:08x} overlaps RAM at 0x{:08x}-0x{:08x}", device.name(), base, lo, hi - 1));
+        }
+        self.bus.attach(base, irq, device)
+    }
+
+    pub fn reg(&self, r: u8) -> u32 {
+        self.regs[r as usize & 0x1F]
+    }
+
+    /// x0 stays zero whatever is written to it.
+    pub fn set_reg(&mut self, r: u8, value: u32) {
+        if r & 0x1F != 0 {
+            self.regs[r as usize & 0x1F] = value;
+        }
+    }
+
+    /// The word at pc, decoded, without executing it.
+    pub fn fetch(&self) -> Result<Instruction, Trap> {
+        if !self.pc.is_multiple_of(4) {
+            return Err(Trap::MisalignedFetch(self.pc));
+        }
+        let word = self.mem.read(self.pc, 4).ok_or(Trap::FetchFault(self.pc))?;
+        decode(word).map_err(|_| Trap::Illegal { pc: self.pc, word })
+    }
+
+    /// Execute one instruction. On a trap nothing has changed.
+    pub fn step(&mut self) -> Result<Instruction, Trap> {
+        let inst = self.fetch()?;
+        let pc = self.pc;
+        let mut next = pc.wrapping_add(4);
+        match inst {
+            Instruction::Lui { rd, imm } => self.set_reg(rd, imm as u32),
+            Instruction::Auipc { rd, imm } => self.set_reg(rd, pc.wrapping_add(imm as u32)),
+            Instruction::Jal { rd, offset } => {
+                next = jump_target(pc.wrapping_add(offset as u32))?;
+                self.set_reg(rd, pc.wrapping_add(4));
+            }
+            Instruction::Jalr { rd, rs1, offset } => {
+                next = jump_target(self.reg(rs1).wrapping_add(offset as u32) & !1)?;
+                self.set_reg(rd, pc.wrapping_add(4));
+            }
+            Instruction::Branch { op, rs1, rs2, offset } => {
+                let (a, b) = (self.reg(rs1), self.reg(rs2));
+                let taken = match op {
+                    BranchOp::Beq => a == b,
+                    BranchOp::Bne => a != b,
+                    BranchOp::Blt => (a as i32) < b as i32,
+                    BranchOp::Bge => a as i32 >= b as i32,
+                    BranchOp::Bltu => a < b,
+                    BranchOp::Bgeu => a >= b,
+                };
+                if taken {
+                    next = jump_target(pc.wrapping_add(offset as u32))?;
+                }
+            }
+            Instruction::Load { width, rd, rs1, offset } => {
+                let addr = self.reg(rs1).wrapping_add(offset as u32);
+                let len = bytes(width);
+                if !addr.is_multiple_of(len as u32) {
+                    return Err(Trap::MisalignedLoad(addr));
+                }
+                let raw = match self.bus.read(addr, len) {
+                    Some(value) => value,
+                    None => self.mem.read(addr, len),
+                }.ok_or(Trap::LoadFault(addr))?;
+                let value = match width {
+                    Width::Byte => raw as u8 as i8 as u32,
+                    Width::Half => raw as u16 as i16 as u32,
+                    _ => raw,
+                };
+                self.set_reg(rd, value);
+            }
+            Instruction::Store { width, rs1, rs2, offset } => {
+                let addr = self.reg(rs1).wrapping_add(offset as u32);
+                let len = bytes(width);
+                if !addr.is_multiple_of(len as u32) {
+                    return Err(Trap::MisalignedStore(addr));
+                }
+                let value = self.reg(rs2);
+                if !self.bus.write(addr, len, value).unwrap_or_else(|| self.mem.write(addr, len, value)) {
+                    return Err(Trap::StoreFault(addr));
+                }
+            }
+            Instruction::OpImm { op, rd, rs1, imm } => {
+                let v = alu(op, self.reg(rs1), imm as u32);
+                self.set_reg(rd, v);
+            }
+            Instruction::Op { op, rd, rs1, rs2 } => {
+                let v = alu(op, self.reg(rs1), self.reg(rs2));
+                self.set_reg(rd, v);
+            }
+            Instruction::Fence => {}
+            Instruction::Ecall => return Err(Trap::Ecall(pc)),
+            Instruction::Ebreak => return Err(Trap::Breakpoint(pc)),
+        }
+        self.pc = next;
+        self.retired += 1;
+        self.bus.tick();
+        Ok(inst)
+    }
+
+    /// Step until a trap, or None after `max_steps` instructions.
+    pub fn run(&mut self, max_steps: u64) -> Option<Trap> {
+        for _ in 0..max_steps {
+            if let Err(trap) = self.step() {
+                return Some(trap);
+            }
+        }
+        None
+    }
+}
+
+fn jump_target(target: u32) -> Result<u32, Trap> {
+    if !target.is_multiple_of(4) { Err(Trap::MisalignedFetch(target)) } else { Ok(target) }
+}
+
+pub(crate) fn bytes(width: Width) -> usize {
+    match width {
+        Width::Byte | Width::ByteU => 1,
+        Width::Half | Width::HalfU => 2,
+        Width::Word => 4,
+    }
+}
+
+/// The ALU, including the M extension's corner cases: division by zero
+/// gives all ones (and the dividend as remainder), MIN / -1 gives MIN.
+pub fn alu(op: AluOp, a: u32, b: u32) -> u32 {
+    let (sa, sb) = (a as i32, b as i32);
+    match op {
+        AluOp::Add => a.wrapping_add(b),
+        AluOp::Sub => a.wrapping_sub(b),
+        AluOp::Sll => a << (b & 31),
+        AluOp::Slt => (sa < sb) as u32,
+        AluOp::Sltu => (a < b) as u32,
+        AluOp::Xor => a ^ b,
+        AluOp::Srl => a >> (b & 31),
+        AluOp::Sra => (sa >> (b & 31)) as u32,
+        AluOp::Or => a | b,
+        AluOp::And => a & b,
+        AluOp::Mul => a.wrapping_mul(b),
+        AluOp::Mulh => ((sa as i64 * sb as i64) >> 32) as u32,
+        AluOp::Mulhsu => ((sa as i64 * b as i64) >> 32) as u32,
+        AluOp::Mulhu => ((a as u64 * b as u64) >> 32) as u32,
+        AluOp::Div if b == 0 => u32::MAX,
+        AluOp::Div => sa.wrapping_div(sb) as u32,
+        AluOp::Divu if b == 0 => u32::MAX,
+        AluOp::Divu => a / b,
+        AluOp::Rem if b == 0 => a,
+        AluOp::Rem => sa.wrapping_rem(sb) as u32,
+        AluOp::Remu if b == 0 => a,
+        AluOp::Remu => a % b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::HALT;
+
+    const A0: u8 = 10;
+    const A1: u8 = 11;
+    const T0: u8 = 5;
+
+    fn cpu(program: &[Instruction]) -> Cpu {
+        let mut mem = Memory::new(0x1000, 0x2000);
+        let words: Vec<u32> = program.iter().map(|i| i.encode()).chain([HALT]).collect();
+        assert!(mem.load_words(0x1000, &words));
+        Cpu::new(mem, 0x1000)
+    }
+
+    #[test]
+    fn sum_of_one_to_ten() {
+        use Instruction::*;
+        let mut c = cpu(&[
+            OpImm { op: AluOp::Add, rd: A0, rs1: 0, imm: 0 },
+            OpImm { op: AluOp::Add, rd: T0, rs1: 0, imm: 10 },
+            Op { op: AluOp::Add, rd: A0, rs1: A0, rs2: T0 },      // loop:
+            OpImm { op: AluOp::Add, rd: T0, rs1: T0, imm: -1 },
+            Branch { op: BranchOp::Bne, rs1: T0, rs2: 0, offset: -8 },
+        ]);
+        assert_eq!(c.run(1000), Some(Trap::Breakpoint(0x1014)));
+        assert_eq!(c.reg(A0), 55);
+        assert_eq!(c.retired, 2 + 3 * 10);
+    }
+
+    #[test]
+    fn loads_extend_and_stores_are_little_endian() {
+        use Instruction::*;
+        let mut c = cpu(&[
+            Lui { rd: A1, imm: 0x2000 },
+            OpImm { op: AluOp::Add, rd: T0, rs1: 0, imm: -128 },   // 0xFFFFFF80
+            Store { width: Width::Word, rs1: A1, rs2: T0, offset: 0 },
+            Load { width: Width::Byte, rd: A0, rs1: A1, offset: 0 },
+            Load { width: Width::ByteU, rd: 12, rs1: A1, offset: 0 },
+            Load { width: Width::HalfU, rd: 13, rs1: A1, offset: 2 },
+        ]);
+        assert_eq!(c.run(100), Some(Trap::Breakpoint(0x1018)));
+        assert_eq!((c.reg(A0), c.reg(12), c.reg(13)), (0xFFFF_FF80, 0x80, 0xFFFF));
+        assert_eq!(c.mem.slice(0x2000, 4), Some(&[0x80, 0xFF, 0xFF, 0xFF][..]));
+    }
+
+    #[test]
+    fn traps_are_precise() {
+        use Instruction::*;
+        let mut c = cpu(&[
+            OpImm { op: AluOp::Add, rd: A0, rs1: 0, imm: 0x101 },
+            Load { width: Width::Word, rd: A1, rs1: A0, offset: 0 },
+        ]);
+        assert_eq!(c.run(10), Some(Trap::MisalignedLoad(0x101)));
+        assert_eq!((c.pc, c.reg(A1), c.retired), (0x1004, 0, 1));
+        assert_eq!(Trap::MisalignedLoad(0).cause(), 4);
+
+        let mut c = cpu(&[Store { width: Width::Word, rs1: 0, rs2: 0, offset: 0 }]);
+        assert_eq!(c.run(10), Some(Trap::StoreFault(0)));
+        let mut c = cpu(&[Ecall]);
+        assert_eq!(c.run(10), Some(Trap::Ecall(0x1000)));
+        c.pc += 4;
+        assert_eq!(c.run(10), Some(Trap::Breakpoint(0x1004)));
+    }
+
+    #[test]
+    fn x0_is_hardwired_and_jal_links() {
+        use Instruction::*;
+        let mut c = cpu(&[
+            OpImm { op: AluOp::Add, rd: 0, rs1: 0, imm: 7 },
+            Jal { rd: 1, offset: 8 },
+            OpImm { op: AluOp::Add, rd: A0, rs1: 0, imm: 1 },      // skipped
+        ]);
+        c.mem.load_words(0x100C, &[HALT]);
+        assert_eq!(c.run(10), Some(Trap::Breakpoint(0x100C)));
+        assert_eq!((c.reg(0), c.reg(1), c.reg(A0)), (0, 0x1008, 0));
+    }
+
+    #[test]
+    fn devices_answer_before_ram() {
+        use crate::device::{Ram, Timer};
+        use Instruction::*;
+        let mut c = cpu(&[
+            Lui { rd: A1, imm: 0x4000 },
+            OpImm { op: AluOp::Add, rd: T0, rs1: 0, imm: 42 },
+            Store { width: Width::Word, rs1: A1, rs2: T0, offset: 0 },
+            Load { width: Width::Word, rd: A0, rs1: A1, offset: 0x10 },   // mtime
+        ]);
+        assert!(c.attach(0x2800, None, Box::new(Ram::new("ram", 16))).unwrap_err().contains("overlaps RAM"));
+        c.attach(0x4000, None, Box::new(Ram::new("sram", 16))).unwrap();
+        c.attach(0x4010, Some(7), Box::new(Timer::new("timer"))).unwrap();
+        assert_eq!(c.run(100), Some(Trap::Breakpoint(0x1010)));
+        assert_eq!((c.bus.read(0x4000, 4), c.reg(A0)), (Some(Some(42)), 3));
+    }
+
+    #[test]
+    fn m_extension_corner_cases() {
+        assert_eq!(alu(AluOp::Div, 7, 0), u32::MAX);
+        assert_eq!(alu(AluOp::Rem, 7, 0), 7);
+        assert_eq!(alu(AluOp::Div, i32::MIN as u32, -1i32 as u32), i32::MIN as u32);
+        assert_eq!(alu(AluOp::Rem, i32::MIN as u32, -1i32 as u32), 0);
+        assert_eq!(alu(AluOp::Div, -7i32 as u32, 2), -3i32 as u32);        // rounds towards zero
+        assert_eq!(alu(AluOp::Mulh, -1i32 as u32, -1i32 as u32), 0);
+        assert_eq!(alu(AluOp::Mulhu, u32::MAX, u32::MAX), 0xFFFF_FFFE);
+        assert_eq!(alu(AluOp::Mulhsu, -1i32 as u32, u32::MAX), u32::MAX);
+        assert_eq!(alu(AluOp::Sra, 0x8000_0000, 31), u32::MAX);
+    }
+}
@@ -0,0 +1,316 @@
+/*
+ * device - memory-mapped peripherals on the emulator's bus.
+ *
+ *   lw/sw ──► Bus ──┬── 0x1000_0000 uart0   (irq 1)
+ *                   ├── 0x0200_0000 timer0  (irq 7)
+ *                   └── anything else ──► Memory (RAM)
+ *
+ *   struct Leds(u32);
+ *   impl Device for Leds {
+ *       fn name(&self) -> &str { "leds" }
+ *       fn size(&self) -> u32 { 4 }
+ *       fn read(&mut self, _: u32, _: usize) -> Option<u32> { Some(self.0) }
+ *       fn write(&mut self, _: u32, _: usize, v: u32) -> bool { self.0 = v; true }
+ *   }
+ *   cpu.attach(0x2000_0000, None, Box::new(Leds(0)))?;
+ *
+ * A device answers the loads and stores to its range, with the offset
+ * from its base, and gets a tick() after every instruction the processor
+ * retires, which is its clock. It is a trait object on the bus, so a
+ * peripheral of your own needs no change to the emulator, only to be
+ * attached before the run. A device that raises its interrupt line shows
+ * in Bus::pending(); RV32IM as emulated here has no CSRs and no mtvec to
+ * jump to, so taking the interrupt is the host's business, which polls it
+ * as a real interrupt controller does between instructions.
+ *
+ * The three built in are what a config file may ask for: a UART (data at
+ * +0, status at +4), a timer with mtime and mtimecmp as in the CLINT of
+ * the privileged spec, and plain RAM.
+ */
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::mpsc::Sender;
+
+pub trait Device {
+    fn name(&self) -> &str;
+
+    /// Bytes of address space, from the base it is attached at.
+    fn size(&self) -> u32;
+
+    /// `len` (1, 2 or 4) bytes at `offset`; None is an access fault.
+    fn read(&mut self, offset: u32, len: usize) -> Option<u32>;
+
+    /// False is an access fault.
+    fn write(&mut self, offset: u32, len: usize, value: u32) -> bool;
+
+    /// One clock cycle, after every retired instruction.
+    fn tick(&mut self) {}
+
+    /// Whether it is asking for an interrupt.
+    fn interrupt(&self) -> bool {
+        false
+    }
+}
+
+struct Mapped {
+    base: u32,
+    irq: Option<u8>,
+    device: Box<dyn Device>,
+}
+
+impl Mapped {
+    fn end(&self) -> u64 {
+        u64::from(self.base) + u64::from(self.device.size())
+    }
+}
+
+#[derive(Default)]
+pub struct Bus {
+    devices: Vec<Mapped>,
+}
+
+impl fmt::Debug for Bus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.devices.iter().map(|m| (m.device.name(), m.base, m.irq))).finish()
+    }
+}
+
+impl Bus {
+    /// Map `device` at `base`, raising `irq` (0 to 31) if it has one.
+    pub fn attach(&mut self, base: u32, irq: Option<u8>, device: Box<dyn Device>) -> Result<(), String> {
+        let new = Mapped { base, irq, device };
+        let name = new.device.name().to_string();
+        if new.device.size() == 0 || new.end() > 1 << 32 {
+            return Err(format!("{}: {} bytes at 0x{:08x} do not fit in the address space", name, new.device.size(), base));
+        }
+        if irq.is_some_and(|n| n >= 32) {
+            return Err(format!("{}: no interrupt line {}, there are 32", name, irq.unwrap_or(0)));
+        }
+        if let Some(old) = self.devices.iter().find(|m| u64::from(base) < m.end() && u64::from(m.base) < new.end()) {
+            return Err(format!("{} at 0x{:08x} overlaps {} at 0x{:08x}", name, base, old.device.name(), old.base));
+        }
+        self.devices.push(new);
+        Ok(())
+    }
+
+    /// (name, base, size, irq) of each device, in the order attached.
+    pub fn devices(&self) -> impl Iterator<Item = (&str, u32, u32, Option<u8>)> + '_ {
+        self.devices.iter().map(|m| (m.device.name(), m.base, m.device.size(), m.irq))
+    }
+
+    fn at(&mut self, addr: u32) -> Option<&mut Mapped> {
+        self.devices.iter_mut().find(|m| addr >= m.base && u64::from(addr) < m.end())
+    }
+
+    /// None when no device has `addr`, Some(None) for a fault.
+    pub fn read(&mut self, addr: u32, len: usize) -> Option<Option<u32>> {
+        let m = self.at(addr)?;
+        let offset = addr - m.base;
+        Some(if u64::from(addr) + len as u64 > m.end() { None } else { m.device.read(offset, len) })
+    }
+
+    /// None when no device has `addr`, Some(false) for a fault.
+    pub fn write(&mut self, addr: u32, len: usize, value: u32) -> Option<bool> {
+        let m = self.at(addr)?;
+        let offset = addr - m.base;
+        Some(u64::from(addr) + len as u64 <= m.end() && m.device.write(offset, len, value))
+    }
+
+    pub fn tick(&mut self) {
+        for m in &mut self.devices {
+            m.device.tick();
+        }
+    }
+
+    /// The interrupt lines raised, bit n for irq n.
+    pub fn pending(&self) -> u32 {
+        self.devices.iter().filter(|m| m.device.interrupt()).filter_map(|m| m.irq).fold(0, |bits, n| bits | 1 << n)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+}
+
+// ==================== BUILT IN ====================
+
+/// Transmit by storing a byte at +0; load +0 for the next received byte
+/// (0 when there is none). +4 is the status: bit 0 a byte has come in, bit
+/// 1 ready to send (always). It interrupts while input is waiting.
+pub struct Uart {
+    name: String,
+    tx: Sender<u8>,
+    rx: VecDeque<u8>,
+}
+
+impl Uart {
+    /// Sent bytes go to `tx`; `input` is what arrives.
+    pub fn new(name: &str, tx: Sender<u8>, input: &[u8]) -> Uart {
+        Uart { name: name.to_string(), tx, rx: input.iter().copied().collect() }
+    }
+}
+
+impl Device for Uart {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn size(&self) -> u32 {
+        8
+    }
+
+    fn read(&mut self, offset: u32, _: usize) -> Option<u32> {
+        match offset {
+            0 => Some(self.rx.pop_front().map_or(0, u32::from)),
+            4 => Some(u32::from(!self.rx.is_empty()) | 2),
+            _ => None,
+        }
+    }
+
+    fn write(&mut self, offset: u32, _: usize, value: u32) -> bool {
+        if offset != 0 {
+            return false;
+        }
+        // nobody listening is not the program's fault
+        let _ = self.tx.send(value as u8);
+        true
+    }
+
+    fn interrupt(&self) -> bool {
+        !self.rx.is_empty()
+    }
+}
+
+/// mtime (+0 low, +4 high) counts instructions; it interrupts while
+/// mtime >= mtimecmp (+8 low, +12 high), which starts at the largest value.
+pub struct Timer {
+    name: String,
+    mtime: u64,
+    mtimecmp: u64,
+}
+
+impl Timer {
+    pub fn new(name: &str) -> Timer {
+        Timer { name: name.to_string(), mtime: 0, mtimecmp: u64::MAX }
+    }
+}
+
+fn half(value: u64, offset: u32) -> u32 {
+    (value >> (8 * (offset & 4))) as u32
+}
+
+fn set_half(value: &mut u64, offset: u32, half: u32) {
+    let shift = 8 * (offset & 4);
+    *value = (*value & !(0xFFFF_FFFF << shift)) | u64::from(half) << shift;
+}
+
+impl Device for Timer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn size(&self) -> u32 {
+        16
+    }
+
+    fn read(&mut self, offset: u32, len: usize) -> Option<u32> {
+        match (offset, len) {
+            (0 | 4, 4) => Some(half(self.mtime, offset)),
+            (8 | 12, 4) => Some(half(self.mtimecmp, offset)),
+            _ => None,
+        }
+    }
+
+    fn write(&mut self, offset: u32, len: usize, value: u32) -> bool {
+        match (offset, len) {
+            (0 | 4, 4) => set_half(&mut self.mtime, offset, value),
+            (8 | 12, 4) => set_half(&mut self.mtimecmp, offset, value),
+            _ => return false,
+        }
+        true
+    }
+
+    fn tick(&mut self) {
+        self.mtime = self.mtime.wrapping_add(1);
+    }
+
+    fn interrupt(&self) -> bool {
+        self.mtime >= self.mtimecmp
+    }
+}
+
+/// Memory of its own, zeroed, away from the program's.
+pub struct Ram {
+    name: String,
+    bytes: Vec<u8>,
+}
+
+impl Ram {
+    pub fn new(name: &str, size: u32) -> Ram {
+        Ram { name: name.to_string(), bytes: vec![0; size as usize] }
+    }
+}
+
+impl Device for Ram {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn size(&self) -> u32 {
+        self.bytes.len() as u32
+    }
+
+    fn read(&mut self, offset: u32, len: usize) -> Option<u32> {
+        let s = self.bytes.get(offset as usize..offset as usize + len)?;
+        Some(s.iter().rev().fold(0, |v, &b| v << 8 | u32::from(b)))
+    }
+
+    fn write(&mut self, offset: u32, len: usize, value: u32) -> bool {
+        match self.bytes.get_mut(offset as usize..offset as usize + len) {
+            Some(s) => {
+                s.copy_from_slice(&value.to_le_bytes()[..len]);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn the_bus_routes_by_address() {
+        let (tx, rx) = mpsc::channel();
+        let mut bus = Bus::default();
+        bus.attach(0x100, Some(1), Box::new(Uart::new("uart0", tx, b"k"))).unwrap();
+        bus.attach(0x200, None, Box::new(Ram::new("sram", 64))).unwrap();
+        assert!(bus.attach(0x104, None, Box::new(Ram::new("clash", 4))).unwrap_err().contains("overlaps uart0"));
+        assert_eq!(bus.read(0x0FC, 4), None);
+        assert_eq!((bus.read(0x104, 4), bus.pending()), (Some(Some(3)), 0b10));
+        assert_eq!(bus.read(0x100, 1), Some(Some(u32::from(b'k'))));
+        assert_eq!(bus.pending(), 0);
+        assert_eq!(bus.write(0x100, 1, u32::from(b'!')), Some(true));
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), b"!");
+        assert_eq!(bus.write(0x23C, 4, 0xDEAD_BEEF), Some(true));
+        assert_eq!(bus.read(0x23E, 2), Some(Some(0xDEAD)));
+        assert_eq!(bus.write(0x23E, 4, 0), Some(false)); // runs off the end
+    }
+
+    #[test]
+    fn the_timer_interrupts_at_mtimecmp() {
+        let mut bus = Bus::default();
+        bus.attach(0x0, Some(7), Box::new(Timer::new("timer0"))).unwrap();
+        bus.write(0x8, 4, 3);
+        bus.write(0xC, 4, 0);
+        bus.tick();
+        bus.tick();
+        assert_eq!(bus.pending(), 0);
+        bus.tick();
+        assert_eq!((bus.pending(), bus.read(0x0, 4)), (1 << 7, Some(Some(3))));
+        assert_eq!(bus.read(0x0, 2), Some(None)); // only whole words
+    }
+}
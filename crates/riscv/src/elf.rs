@@ -0,0 +1,328 @@
+/*
+ * elf - load a 32-bit RISC-V ELF executable (or a raw binary) into memory.
+ *
+ *   ┌────────────┐  e_entry, e_phoff, e_shoff
+ *   │ ELF header │ ─────────────────────────────┐
+ *   ├────────────┤                              ▼
+ *   │ program    │  PT_LOAD: copy p_filesz bytes from p_offset to p_vaddr,
+ *   │ headers    │           zero the rest up to p_memsz (.bss)
+ *   ├────────────┤
+ *   │ .text ...  │
+ *   ├────────────┤
+ *   │ section    │  SHT_SYMTAB + its string table: function names, so a
+ *   │ headers    │  trace or a disassembly can say "main+0x8"
+ *   └────────────┘
+ *
+ * Only what `riscv32-unknown-elf-gcc -nostdlib` or `rustc --target
+ * riscv32im-unknown-none-elf` produce is needed: little-endian ELFCLASS32,
+ * EM_RISCV, statically linked. One Memory spans every segment, followed
+ * by the stack; sp starts at its top. A file whose segments are further
+ * apart than MAX_SPAN is refused rather than allocating gigabytes for it.
+ */
+
+use crate::cpu::{Cpu, Memory};
+use std::error::Error;
+use std::fmt;
+
+const EM_RISCV: u16 = 243;
+const PT_LOAD: u32 = 1;
+const PF_X: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const STT_FUNC: u8 = 2;
+
+/// Most bytes from the lowest segment to the end of the highest.
+pub const MAX_SPAN: u64 = 256 << 20;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElfError(pub String);
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "not a loadable RV32 ELF: {}", self.0)
+    }
+}
+
+impl Error for ElfError {}
+
+fn err<T>(msg: impl Into<String>) -> Result<T, ElfError> {
+    Err(ElfError(msg.into()))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub vaddr: u32,
+    /// Bytes in memory; those past `data` are zero.
+    pub memsz: u32,
+    pub data: Vec<u8>,
+    pub executable: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub addr: u32,
+    pub size: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Program {
+    pub entry: u32,
+    pub segments: Vec<Segment>,
+    /// Functions, sorted by address.
+    pub symbols: Vec<Symbol>,
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn u8(&self, at: usize) -> Result<u8, ElfError> {
+        self.0.get(at).copied().ok_or_else(|| ElfError(format!("truncated at byte {}", at)))
+    }
+
+    fn u16(&self, at: usize) -> Result<u16, ElfError> {
+        Ok(self.u8(at)? as u16 | (self.u8(at + 1)? as u16) << 8)
+    }
+
+    fn u32(&self, at: usize) -> Result<u32, ElfError> {
+        Ok(self.u16(at)? as u32 | (self.u16(at + 2)? as u32) << 16)
+    }
+
+    fn bytes(&self, at: u32, len: u32) -> Result<&[u8], ElfError> {
+        let (at, len) = (at as usize, len as usize);
+        match at.checked_add(len) {
+            Some(end) if end <= self.0.len() => Ok(&self.0[at..end]),
+            _ => err(format!("{} bytes at offset {} run past the end of the file", len, at)),
+        }
+    }
+
+    fn c_str(&self, at: usize) -> String {
+        let tail = self.0.get(at..).unwrap_or(&[]);
+        let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+        String::from_utf8_lossy(&tail[..end]).into_owned()
+    }
+}
+
+pub fn is_elf(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"\x7fELF")
+}
+
+impl Program {
+    pub fn parse(bytes: &[u8]) -> Result<Program, ElfError> {
+        let r = Reader(bytes);
+        if !is_elf(bytes) {
+            return err("no \\x7fELF magic");
+        }
+        if r.u8(4)? != 1 {
+            return err("not ELFCLASS32 (a 64-bit file?)");
+        }
+        if r.u8(5)? != 1 {
+            return err("not little-endian");
+        }
+        let machine = r.u16(18)?;
+        if machine != EM_RISCV {
+            return err(format!("e_machine is {}, RISC-V is {}", machine, EM_RISCV));
+        }
+        let entry = r.u32(24)?;
+        let (phoff, shoff) = (r.u32(28)? as usize, r.u32(32)? as usize);
+        let (phentsize, phnum) = (r.u16(42)? as usize, r.u16(44)? as usize);
+        let (shentsize, shnum) = (r.u16(46)? as usize, r.u16(48)? as usize);
+
+        let mut segments = Vec::new();
+        for i in 0..phnum {
+            let ph = phoff + i * phentsize;
+            if r.u32(ph)? != PT_LOAD {
+                continue;
+            }
+            let (offset, vaddr, filesz, memsz) = (r.u32(ph + 4)?, r.u32(ph + 8)?, r.u32(ph + 16)?, r.u32(ph + 20)?);
+            if filesz > memsz {
+                return err(format!("segment at 0x{:08x} has p_filesz > p_memsz", vaddr));
+            }
+            segments.push(Segment {
+                vaddr,
+                memsz,
+                data: r.bytes(offset, filesz)?.to_vec(),
+                executable: r.u32(ph + 24)? & PF_X != 0,
+            });
+        }
+        if segments.is_empty() {
+            return err("no PT_LOAD segment");
+        }
+        let (lo, hi) = extent(&segments);
+        if hi - u64::from(lo) > MAX_SPAN {
+            return err(format!("segments from 0x{:08x} to 0x{:08x} are more than {} MiB apart", lo, hi - 1, MAX_SPAN >> 20));
+        }
+
+        let mut symbols = Vec::new();
+        for i in 0..shnum {
+            let sh = shoff + i * shentsize;
+            if r.u32(sh + 4)? != SHT_SYMTAB {
+                continue;
+            }
+            let (offset, size, entsize) = (r.u32(sh + 16)?, r.u32(sh + 20)?, r.u32(sh + 36)?.max(16));
+            let strtab = shoff + r.u32(sh + 24)? as usize * shentsize;
+            let strings = r.u32(strtab + 16)? as usize;
+            for s in (0..size / entsize).map(|k| (offset + k * entsize) as usize) {
+                if r.u8(s + 12)? & 0xF == STT_FUNC {
+                    symbols.push(Symbol { name: r.c_str(strings + r.u32(s)? as usize), addr: r.u32(s + 4)?, size: r.u32(s + 8)? });
+                }
+            }
+        }
+        symbols.sort_by_key(|s| s.addr);
+        Ok(Program { entry, segments, symbols })
+    }
+
+    /// A flat binary: everything is code and data at `base`, entry at `base`.
+    pub fn raw(base: u32, bytes: &[u8]) -> Program {
+        let segment = Segment { vaddr: base, memsz: bytes.len() as u32, data: bytes.to_vec(), executable: true };
+        Program { entry: base, segments: vec![segment], symbols: Vec::new() }
+    }
+
+    /// The function `addr` is in, and the offset into it.
+    pub fn symbol_at(&self, addr: u32) -> Option<(&Symbol, u32)> {
+        let i = self.symbols.partition_point(|s| s.addr <= addr).checked_sub(1)?;
+        let s = &self.symbols[i];
+        let off = addr - s.addr;
+        if off < s.size.max(1) { Some((s, off)) } else { None }
+    }
+
+    /// "name+0x8", or the bare address outside every function.
+    pub fn describe(&self, addr: u32) -> String {
+        match self.symbol_at(addr) {
+            Some((s, 0)) => s.name.clone(),
+            Some((s, off)) => format!("{}+0x{:x}", s.name, off),
+            None => format!("0x{:08x}", addr),
+        }
+    }
+
+    pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|s| s.name == name)
+    }
+
+    /// A processor with every segment loaded, pc at the entry point and
+    /// `stack` bytes above the highest segment, sp at the top.
+    pub fn cpu(&self, stack: u32) -> Cpu {
+        let (lo, hi) = extent(&self.segments);
+        let top = ((hi + 0xF) & !0xF) + stack as u64;
+        let mut mem = Memory::new(lo, (top - lo as u64) as usize);
+        for s in &self.segments {
+            mem.load(s.vaddr, &s.data);
+        }
+        let mut cpu = Cpu::new(mem, self.entry);
+        cpu.set_reg(2, top.min(u32::MAX as u64 & !0xF) as u32);
+        cpu
+    }
+}
+
+/// The page the lowest segment starts in, and the end of the highest.
+fn extent(segments: &[Segment]) -> (u32, u64) {
+    let lo = segments.iter().map(|s| s.vaddr).min().unwrap_or(0) & !0xFFF;
+    let hi = segments.iter().map(|s| s.vaddr as u64 + s.memsz as u64).max().unwrap_or(0);
+    (lo, hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::{AluOp, Instruction, HALT};
+    use crate::Trap;
+
+    fn put(out: &mut Vec<u8>, at: usize, bytes: &[u8]) {
+        if out.len() < at + bytes.len() {
+            out.resize(at + bytes.len(), 0);
+        }
+        out[at..at + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// A minimal executable: one PT_LOAD with the code, a .symtab with one function.
+    fn elf(base: u32, code: &[u32], func: &str) -> Vec<u8> {
+        let text: Vec<u8> = code.iter().flat_map(|w| w.to_le_bytes()).collect();
+        let strtab = [b"\0", func.as_bytes(), b"\0"].concat();
+        let (text_off, sym_off) = (0x54, 0x54 + text.len());
+        let str_off = sym_off + 32;
+        let sh_off = (str_off + strtab.len() + 3) & !3;
+        let mut f = Vec::new();
+        put(&mut f, 0, b"\x7fELF\x01\x01\x01");
+        put(&mut f, 16, &[2, 0, 243, 0, 1, 0, 0, 0]);
+        put(&mut f, 24, &base.to_le_bytes());
+        put(&mut f, 28, &0x34u32.to_le_bytes());
+        put(&mut f, 32, &(sh_off as u32).to_le_bytes());
+        put(&mut f, 40, &[52, 0, 32, 0, 1, 0, 40, 0, 3, 0, 0, 0]);
+        let ph = [PT_LOAD, text_off as u32, base, base, text.len() as u32, text.len() as u32 + 64, 5, 4];
+        put(&mut f, 0x34, &ph.iter().flat_map(|w| w.to_le_bytes()).collect::<Vec<_>>());
+        put(&mut f, text_off, &text);
+        let mut sym = vec![0u8; 16];
+        sym.extend(1u32.to_le_bytes());
+        sym.extend(base.to_le_bytes());
+        sym.extend((text.len() as u32).to_le_bytes());
+        sym.extend([0x12, 0, 1, 0]);
+        put(&mut f, sym_off, &sym);
+        put(&mut f, str_off, &strtab);
+        let sections: [[u32; 10]; 3] = [
+            [0; 10],
+            [0, SHT_SYMTAB, 0, 0, sym_off as u32, 32, 2, 1, 4, 16],
+            [0, 3, 0, 0, str_off as u32, strtab.len() as u32, 0, 0, 1, 0],
+        ];
+        put(&mut f, sh_off, &sections.iter().flatten().flat_map(|w| w.to_le_bytes()).collect::<Vec<_>>());
+        f
+    }
+
+    #[test]
+    fn loads_and_runs() {
+        let code = [Instruction::OpImm { op: AluOp::Add, rd: 10, rs1: 0, imm: 42 }.encode(), HALT];
+        let p = Program::parse(&elf(0x1_0000, &code, "_start")).unwrap();
+        assert_eq!(p.entry, 0x1_0000);
+        assert_eq!((p.segments[0].data.len(), p.segments[0].memsz, p.segments[0].executable), (8, 72, true));
+        assert_eq!(p.describe(0x1_0004), "_start+0x4");
+        assert_eq!(p.describe(0x2_0000), "0x00020000");
+        let mut cpu = p.cpu(0x1000);
+        assert_eq!(cpu.run(10), Some(Trap::Breakpoint(0x1_0004)));
+        assert_eq!(cpu.reg(10), 42);
+        assert_eq!(cpu.reg(2), 0x1_0050 + 0x1000);
+        assert!(cpu.mem.write(cpu.reg(2) - 4, 4, 7)); // the stack is mapped
+        assert_eq!(cpu.mem.read(0x1_0010, 4), Some(0)); // .bss is zero
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mut f = elf(0, &[HALT], "f");
+        assert!(Program::parse(b"#!/bin/sh").is_err());
+        f[18] = 62; // x86-64
+        assert!(Program::parse(&f).unwrap_err().0.contains("e_machine is 62"));
+        f[18] = 243;
+        f[4] = 2;
+        assert!(Program::parse(&f).is_err());
+        assert!(Program::parse(&f[..40]).is_err());
+        f[4] = 1;
+        f[0x34 + 20..0x34 + 24].copy_from_slice(&u32::MAX.to_le_bytes()); // p_memsz
+        assert!(Program::parse(&f).unwrap_err().0.contains("more than 256 MiB apart"));
+        let raw = Program::raw(0x100, &HALT.to_le_bytes());
+        assert_eq!(raw.cpu(16).run(1), Some(Trap::Breakpoint(0x100)));
+    }
+
+    #[test]
+    fn mangled_files_are_errors_not_panics() {
+        // what fuzz/fuzz_targets/elf.rs does, for a seeded sample of mutations
+        let good = elf(0x1000, &[Instruction::OpImm { op: AluOp::Add, rd: 10, rs1: 0, imm: 42 }.encode(), HALT], "main");
+        let mut x = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = || {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        };
+        for _ in 0..20_000 {
+            let mut f = good.clone();
+            for _ in 0..1 + next() % 4 {
+                let (at, byte) = (next() as usize % f.len(), next() as u8);
+                f[at] = if byte < 0x40 { 0xFF } else { byte };
+            }
+            if next() % 8 == 0 {
+                f.truncate(next() as usize % f.len());
+            }
+            if let Ok(p) = Program::parse(&f) {
+                let _ = p.describe(p.entry);
+                p.cpu(64).run(100);
+            }
+        }
+    }
+}
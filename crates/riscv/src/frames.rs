@@ -0,0 +1,301 @@
+/*
+ * frames - the call stack of a running program, rebuilt from the
+ * instructions it executes, and drawn as the calling-convention lecture
+ * draws it.
+ *
+ *   jal/jalr with rd = ra        a call: a new frame, a0-a7 as its arguments
+ *   jalr zero, 0(ra)             ret: back to the frame it returns into
+ *   addi sp, sp, -16             the frame grows to 16 bytes
+ *   sw ra/s0-s11, k(sp or s0)    the first store of each: saved ra, saved s1 ...
+ *   sw t0, k(sp or s0)           anything else stored in the frame: a local
+ *   lw from above the frame      an argument passed on the caller's stack
+ *   addi s0, sp, k               s0 is the frame pointer
+ *
+ *   0x0001fffc  0x00001008  saved ra, returns to _start+0x8   ┐ fact(3)
+ *   0x0001fff8  0x00000000  saved s0                          │
+ *   0x0001fff4  0x00000003  local                             ┘ sp
+ *
+ * Nothing but the instructions is used: no debug information, so what a
+ * slot means is what the code did with it, as the convention says it will.
+ * A frame is the bytes from the sp its function runs with up to the sp it
+ * was called with; the stack grows down, so the innermost frame is last.
+ */
+
+use std::fmt::Write;
+
+use crate::cpu::Cpu;
+use crate::elf::Program;
+use crate::instruction::{AluOp, Instruction, ABI_NAMES};
+
+const RA: u8 = 1;
+const SP: u8 = 2;
+const FP: u8 = 8;
+
+/// What a word in a frame was used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Slot {
+    /// A callee-saved register (or ra) put away by the function.
+    Saved(u8),
+    Local,
+    /// Read by the function it called, as an argument past a7.
+    Argument,
+}
+
+impl Slot {
+    /// "saved ra, returns to main+0x8", "saved s1", "local" ...; `value`
+    /// is the word in the slot.
+    pub fn describe(self, value: u32, program: &Program) -> String {
+        match self {
+            Slot::Saved(RA) => format!("saved ra, returns to {}", program.describe(value)),
+            Slot::Saved(r) => format!("saved {}", ABI_NAMES[r as usize]),
+            Slot::Local => "local".to_string(),
+            Slot::Argument => "argument on the stack".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    /// Address of the function; the entry point for the first frame.
+    pub function: u32,
+    /// pc of the call, None for the first frame.
+    pub call: Option<u32>,
+    pub returns_to: u32,
+    /// sp when called: the top of the frame.
+    pub top: u32,
+    /// a0-a7 on entry.
+    pub args: [u32; 8],
+    /// s0 once the function has made it the frame pointer.
+    pub fp: Option<u32>,
+    /// (address, slot), in the order they were first written.
+    pub slots: Vec<(u32, Slot)>,
+}
+
+impl Frame {
+    fn new(function: u32, call: Option<u32>, returns_to: u32, cpu: &Cpu) -> Frame {
+        let args = std::array::from_fn(|i| cpu.reg(10 + i as u8));
+        Frame { function, call, returns_to, top: cpu.reg(SP), args, fp: None, slots: Vec::new() }
+    }
+
+    fn slot(&self, addr: u32) -> Option<Slot> {
+        self.slots.iter().find(|s| s.0 == addr).map(|s| s.1)
+    }
+
+    fn mark(&mut self, addr: u32, slot: Slot) {
+        if self.slot(addr).is_none() {
+            self.slots.push((addr, slot));
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallStack {
+    /// Outermost first.
+    pub frames: Vec<Frame>,
+}
+
+impl CallStack {
+    /// The first frame, for a program about to start at cpu.pc.
+    pub fn new(cpu: &Cpu) -> CallStack {
+        CallStack { frames: vec![Frame::new(cpu.pc, None, 0, cpu)] }
+    }
+
+    /// Take note of `inst`, which has just run from `pc`; `cpu` is the
+    /// state after it.
+    pub fn record(&mut self, pc: u32, inst: Instruction, cpu: &Cpu) {
+        match inst {
+            Instruction::Jal { rd: RA, .. } | Instruction::Jalr { rd: RA, .. } => {
+                self.frames.push(Frame::new(cpu.pc, Some(pc), pc.wrapping_add(4), cpu));
+            }
+            Instruction::Jalr { rd: 0, rs1: RA, offset: 0 } => {
+                if let Some(i) = self.frames.iter().rposition(|f| f.call.is_some() && f.returns_to == cpu.pc) {
+                    self.frames.truncate(i);
+                }
+            }
+            Instruction::OpImm { op: AluOp::Add, rd: FP, rs1: SP, .. } => {
+                if let Some(f) = self.frames.last_mut() {
+                    f.fp = Some(cpu.reg(FP));
+                }
+            }
+            Instruction::Store { rs1, rs2, offset, .. } => {
+                let addr = cpu.reg(rs1).wrapping_add(offset as u32) & !3;
+                let sp = cpu.reg(SP);
+                let Some(f) = self.frames.last_mut() else { return };
+                if !(sp..f.top).contains(&addr) {
+                    return;
+                }
+                let saved = rs2 == RA || rs2 == FP || rs2 == 9 || (18..=27).contains(&rs2);
+                let first = !f.slots.iter().any(|s| s.1 == Slot::Saved(rs2));
+                f.mark(addr, if saved && first { Slot::Saved(rs2) } else { Slot::Local });
+            }
+            Instruction::Load { rs1, offset, .. } => {
+                let addr = cpu.reg(rs1).wrapping_add(offset as u32) & !3;
+                let n = self.frames.len();
+                if n < 2 || addr < self.frames[n - 1].top {
+                    return;
+                }
+                let caller = &mut self.frames[n - 2];
+                if addr < caller.top && caller.slot(addr).is_none_or(|s| s == Slot::Local) {
+                    caller.slots.retain(|s| s.0 != addr);
+                    caller.mark(addr, Slot::Argument);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The sp each frame runs with: the top of the one it called, or the
+    /// current sp for the innermost.
+    pub fn bottoms(&self, cpu: &Cpu) -> Vec<u32> {
+        let mut bottoms: Vec<u32> = self.frames.iter().skip(1).map(|f| f.top).collect();
+        bottoms.push(cpu.reg(SP));
+        bottoms
+    }
+
+    /// The frames from the outermost down, word by word: the words no
+    /// instruction touched are counted rather than listed.
+    pub fn diagram(&self, cpu: &Cpu, program: &Program) -> String {
+        let mut s = String::new();
+        for (depth, (f, bottom)) in self.frames.iter().zip(self.bottoms(cpu)).enumerate() {
+            let _ = writeln!(s, "#{} {}", depth, self.heading(depth, program));
+            let mut untouched = 0;
+            for addr in (bottom..f.top).step_by(4).rev() {
+                let slot = f.slot(addr);
+                if slot.is_none() && addr != bottom {
+                    untouched += 1;
+                    continue;
+                }
+                flush(&mut s, &mut untouched);
+                let word = cpu.mem.read(addr, 4);
+                let note = slot.map_or(String::new(), |slot| slot.describe(word.unwrap_or(0), program));
+                let value = word.map_or("?".to_string(), |v| format!("0x{:08x}", v));
+                let sp = if addr == bottom { "<- sp" } else { "" };
+                let _ = writeln!(s, "{}", format!("   0x{:08x}  {}  {:<40} {}", addr, value, note, sp).trim_end());
+            }
+            if f.top == bottom {
+                let _ = writeln!(s, "   (no stack of its own)");
+            }
+        }
+        s
+    }
+
+    /// "fact(a0 = 3), called from main+0x8" or "the entry point _start".
+    pub fn heading(&self, depth: usize, program: &Program) -> String {
+        let f = &self.frames[depth];
+        let Some(call) = f.call else {
+            return format!("{}, the entry point, sp 0x{:08x}", program.describe(f.function), f.top);
+        };
+        let used = f.args.iter().rposition(|&a| a != 0).map_or(1, |i| i + 1);
+        let args: Vec<String> = f.args[..used].iter().enumerate().map(|(i, &a)| format!("a{} = {}", i, shown(a))).collect();
+        let fp = f.fp.map_or(String::new(), |fp| format!(", fp 0x{:08x}", fp));
+        format!("{}({}), called from {}, sp 0x{:08x}{}", program.describe(f.function), args.join(", "), program.describe(call), f.top, fp)
+    }
+}
+
+/// Small numbers in decimal, addresses in hex.
+fn shown(v: u32) -> String {
+    if (v as i32).unsigned_abs() < 0x1000 { (v as i32).to_string() } else { format!("0x{:x}", v) }
+}
+
+fn flush(s: &mut String, untouched: &mut usize) {
+    if *untouched > 0 {
+        let _ = writeln!(s, "   ...         {} untouched", if *untouched == 1 { "1 word".to_string() } else { format!("{} words", untouched) });
+    }
+    *untouched = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::Memory;
+    use crate::instruction::{BranchOp, Width};
+
+    const A0: u8 = 10;
+    const T0: u8 = 5;
+
+    /// fact(n) with a frame pointer, n kept in a local.
+    fn fact() -> Vec<Instruction> {
+        let addi = |rd, rs1, imm| Instruction::OpImm { op: AluOp::Add, rd, rs1, imm };
+        let sw = |rs2, rs1, offset| Instruction::Store { width: Width::Word, rs1, rs2, offset };
+        let lw = |rd, rs1, offset| Instruction::Load { width: Width::Word, rd, rs1, offset };
+        vec![
+            addi(A0, 0, 3),
+            Instruction::Jal { rd: RA, offset: 12 },
+            addi(17, 0, 93),
+            Instruction::Ecall,
+            addi(SP, SP, -16), // fact
+            sw(RA, SP, 12),
+            sw(FP, SP, 8),
+            addi(FP, SP, 16),
+            sw(A0, FP, -12),
+            addi(T0, 0, 1),
+            Instruction::Branch { op: BranchOp::Blt, rs1: T0, rs2: A0, offset: 12 },
+            addi(A0, 0, 1), // the base case
+            Instruction::Jal { rd: 0, offset: 20 },
+            addi(A0, A0, -1),
+            Instruction::Jal { rd: RA, offset: -40 },
+            lw(T0, FP, -12),
+            Instruction::Op { op: AluOp::Mul, rd: A0, rs1: A0, rs2: T0 },
+            lw(RA, SP, 12),
+            lw(FP, SP, 8),
+            addi(SP, SP, 16),
+            Instruction::Jalr { rd: 0, rs1: RA, offset: 0 },
+        ]
+    }
+
+    fn run_to(pc: u32) -> (Cpu, CallStack) {
+        let mut mem = Memory::new(0, 0x200);
+        mem.load_words(0, &fact().iter().map(Instruction::encode).collect::<Vec<_>>());
+        let mut cpu = Cpu::new(mem, 0);
+        cpu.set_reg(SP, 0x200);
+        let mut stack = CallStack::new(&cpu);
+        while cpu.pc != pc {
+            let at = cpu.pc;
+            let inst = cpu.step().expect("no trap before the ecall");
+            stack.record(at, inst, &cpu);
+        }
+        (cpu, stack)
+    }
+
+    #[test]
+    fn recursion_at_its_deepest() {
+        let (cpu, stack) = run_to(44);
+        let args: Vec<u32> = stack.frames.iter().map(|f| f.args[0]).collect();
+        assert_eq!(args, [0, 3, 2, 1]);
+        assert_eq!(stack.bottoms(&cpu), [0x200, 0x1f0, 0x1e0, 0x1d0]);
+        let f = &stack.frames[2];
+        assert_eq!((f.call, f.returns_to, f.top, f.fp), (Some(56), 60, 0x1f0, Some(0x1f0)));
+        assert_eq!(f.slots, [(0x1ec, Slot::Saved(RA)), (0x1e8, Slot::Saved(FP)), (0x1e4, Slot::Local)]);
+        let program = Program::raw(0, &[]);
+        let diagram = stack.diagram(&cpu, &program);
+        let lines: Vec<&str> = diagram.lines().collect();
+        assert_eq!(lines[0], "#0 0x00000000, the entry point, sp 0x00000200");
+        assert_eq!(lines[1], "   (no stack of its own)");
+        assert_eq!(lines[2], "#1 0x00000010(a0 = 3), called from 0x00000004, sp 0x00000200, fp 0x00000200");
+        assert_eq!(lines[3], "   0x000001fc  0x00000008  saved ra, returns to 0x00000008");
+        assert_eq!(lines[5], "   0x000001f4  0x00000003  local");
+        assert!(lines[6].starts_with("   0x000001f0  0x00000000  ") && lines[6].ends_with(" <- sp"));
+        assert_eq!(lines.len(), 1 + 1 + 3 * 5);
+    }
+
+    #[test]
+    fn returns_and_stack_arguments() {
+        let (cpu, stack) = run_to(12);
+        assert_eq!((stack.frames.len(), cpu.reg(A0)), (1, 6));
+        // the caller stores a ninth argument at 0(sp), the callee reads it at 0(sp)
+        let mut cpu = Cpu::new(Memory::new(0, 0x100), 0);
+        cpu.set_reg(SP, 0x100);
+        let mut stack = CallStack::new(&cpu);
+        cpu.set_reg(SP, 0xf0);
+        stack.record(0, Instruction::OpImm { op: AluOp::Add, rd: SP, rs1: SP, imm: -16 }, &cpu);
+        stack.record(4, Instruction::Store { width: Width::Word, rs1: SP, rs2: T0, offset: 0 }, &cpu);
+        assert_eq!(stack.frames[0].slots, [(0xf0, Slot::Local)]);
+        cpu.pc = 0x40;
+        stack.record(8, Instruction::Jal { rd: RA, offset: 0x38 }, &cpu);
+        stack.record(0x40, Instruction::Load { width: Width::Word, rd: T0, rs1: SP, offset: 0 }, &cpu);
+        cpu.pc = 12;
+        stack.record(0x44, Instruction::Jalr { rd: 0, rs1: RA, offset: 0 }, &cpu);
+        assert_eq!(stack.frames.len(), 1);
+        assert_eq!(stack.frames[0].slots, [(0xf0, Slot::Argument)]);
+    }
+}
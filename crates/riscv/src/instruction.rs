@@ -0,0 +1,535 @@
+/*
+ * instruction - RV32IM instructions: decode, encode, disassemble.
+ *
+ *   31        25 24   20 19   15 14  12 11    7 6      0
+ *   ┌───────────┬───────┬───────┬──────┬───────┬────────┐
+ *   │  funct7   │  rs2  │  rs1  │funct3│  rd   │ opcode │  R
+ *   │     imm[11:0]     │  rs1  │funct3│  rd   │ opcode │  I
+ *   │ imm[11:5] │  rs2  │  rs1  │funct3│imm4:0 │ opcode │  S
+ *   │imm12|10:5 │  rs2  │  rs1  │funct3│4:1|11 │ opcode │  B
+ *   │          imm[31:12]               │  rd   │ opcode │  U
+ *   │      imm[20|10:1|11|19:12]        │  rd   │ opcode │  J
+ *   └───────────┴───────┴───────┴──────┴───────┴────────┘
+ *
+ * rs1, rs2 and rd sit in the same place in every format, and the sign of
+ * every immediate is bit 31 - so the register file can be read and sign
+ * extension can start before the format is known. The scrambled B and J
+ * immediates are the price. Same layouts as computerdesign.instruction.
+ *
+ * decode(encode(i)) == i for every instruction, and encode(decode(w)) == w
+ * for every word that decodes (fence and system instructions aside, whose
+ * unused fields are ignored).
+ */
+
+use arithmetic::alu::sign_extend;
+use arithmetic::bits::extract;
+use std::error::Error;
+use std::fmt;
+
+/// ABI register names, x0 to x31.
+pub const ABI_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2",
+    "s0", "s1", "a0", "a1", "a2", "a3", "a4", "a5",
+    "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7",
+    "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6",
+];
+
+/// `ebreak`, which the simulators in this repository treat as HALT.
+pub const HALT: u32 = 0x0010_0073;
+
+/// Register-register and register-immediate ALU operations (M extension included).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AluOp {
+    Add, Sub, Sll, Slt, Sltu, Xor, Srl, Sra, Or, And,
+    Mul, Mulh, Mulhsu, Mulhu, Div, Divu, Rem, Remu,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchOp {
+    Beq, Bne, Blt, Bge, Bltu, Bgeu,
+}
+
+/// Load and store widths; the U variants zero-extend (loads only).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Width {
+    Byte, Half, Word, ByteU, HalfU,
+}
+
+/// What an instruction costs depends mostly on which of these it is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Class {
+    Alu, Mul, Div, Load, Store, Branch, Jump, System,
+}
+
+impl Class {
+    pub const ALL: [Class; 8] = [Class::Alu, Class::Mul, Class::Div, Class::Load, Class::Store, Class::Branch, Class::Jump, Class::System];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Class::Alu => "alu",
+            Class::Mul => "mul",
+            Class::Div => "div",
+            Class::Load => "load",
+            Class::Store => "store",
+            Class::Branch => "branch",
+            Class::Jump => "jump",
+            Class::System => "system",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    /// rd = imm (the low 12 bits of imm are zero)
+    Lui { rd: u8, imm: i32 },
+    /// rd = pc + imm
+    Auipc { rd: u8, imm: i32 },
+    Jal { rd: u8, offset: i32 },
+    Jalr { rd: u8, rs1: u8, offset: i32 },
+    Branch { op: BranchOp, rs1: u8, rs2: u8, offset: i32 },
+    Load { width: Width, rd: u8, rs1: u8, offset: i32 },
+    Store { width: Width, rs1: u8, rs2: u8, offset: i32 },
+    /// addi, slti, sltiu, xori, ori, andi, slli, srli, srai
+    OpImm { op: AluOp, rd: u8, rs1: u8, imm: i32 },
+    Op { op: AluOp, rd: u8, rs1: u8, rs2: u8 },
+    Fence,
+    Ecall,
+    Ebreak,
+}
+
+/// A word that is not an RV32IM instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub word: u32,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "illegal instruction 0x{:08x}: {}", self.word, self.reason)
+    }
+}
+
+impl Error for DecodeError {}
+
+const LUI: u32 = 0b0110111;
+const AUIPC: u32 = 0b0010111;
+const JAL: u32 = 0b1101111;
+const JALR: u32 = 0b1100111;
+const BRANCH: u32 = 0b1100011;
+const LOAD: u32 = 0b0000011;
+const STORE: u32 = 0b0100011;
+const OP_IMM: u32 = 0b0010011;
+const OP: u32 = 0b0110011;
+const MISC_MEM: u32 = 0b0001111;
+const SYSTEM: u32 = 0b1110011;
+
+// ==================== IMMEDIATES ====================
+
+fn imm_i(w: u32) -> i32 {
+    sign_extend(w >> 20, 12)
+}
+
+fn imm_s(w: u32) -> i32 {
+    sign_extend(extract(w, 31, 25) << 5 | extract(w, 11, 7), 12)
+}
+
+fn imm_b(w: u32) -> i32 {
+    sign_extend(extract(w, 31, 31) << 12 | extract(w, 7, 7) << 11 | extract(w, 30, 25) << 5 | extract(w, 11, 8) << 1, 13)
+}
+
+fn imm_j(w: u32) -> i32 {
+    sign_extend(extract(w, 31, 31) << 20 | extract(w, 19, 12) << 12 | extract(w, 20, 20) << 11 | extract(w, 30, 21) << 1, 21)
+}
+
+fn enc_i(imm: i32) -> u32 {
+    (imm as u32 & 0xFFF) << 20
+}
+
+fn enc_s(imm: i32) -> u32 {
+    let i = imm as u32;
+    extract(i, 11, 5) << 25 | extract(i, 4, 0) << 7
+}
+
+fn enc_b(imm: i32) -> u32 {
+    let i = imm as u32;
+    extract(i, 12, 12) << 31 | extract(i, 10, 5) << 25 | extract(i, 4, 1) << 8 | extract(i, 11, 11) << 7
+}
+
+fn enc_j(imm: i32) -> u32 {
+    let i = imm as u32;
+    extract(i, 20, 20) << 31 | extract(i, 10, 1) << 21 | extract(i, 11, 11) << 20 | extract(i, 19, 12) << 12
+}
+
+// ==================== DECODE ====================
+
+/// One 32-bit word to an instruction.
+pub fn decode(w: u32) -> Result<Instruction, DecodeError> {
+    let err = |reason| Err(DecodeError { word: w, reason });
+    let rd = extract(w, 11, 7) as u8;
+    let rs1 = extract(w, 19, 15) as u8;
+    let rs2 = extract(w, 24, 20) as u8;
+    let funct3 = extract(w, 14, 12);
+    let funct7 = extract(w, 31, 25);
+    let i = match w & 0x7F {
+        LUI => Instruction::Lui { rd, imm: (w & 0xFFFF_F000) as i32 },
+        AUIPC => Instruction::Auipc { rd, imm: (w & 0xFFFF_F000) as i32 },
+        JAL => Instruction::Jal { rd, offset: imm_j(w) },
+        JALR if funct3 == 0 => Instruction::Jalr { rd, rs1, offset: imm_i(w) },
+        BRANCH => {
+            let op = match funct3 {
+                0 => BranchOp::Beq,
+                1 => BranchOp::Bne,
+                4 => BranchOp::Blt,
+                5 => BranchOp::Bge,
+                6 => BranchOp::Bltu,
+                7 => BranchOp::Bgeu,
+                _ => return err("unknown branch funct3"),
+            };
+            Instruction::Branch { op, rs1, rs2, offset: imm_b(w) }
+        }
+        LOAD => {
+            let width = match funct3 {
+                0 => Width::Byte,
+                1 => Width::Half,
+                2 => Width::Word,
+                4 => Width::ByteU,
+                5 => Width::HalfU,
+                _ => return err("unknown load width"),
+            };
+            Instruction::Load { width, rd, rs1, offset: imm_i(w) }
+        }
+        STORE => {
+            let width = match funct3 {
+                0 => Width::Byte,
+                1 => Width::Half,
+                2 => Width::Word,
+                _ => return err("unknown store width"),
+            };
+            Instruction::Store { width, rs1, rs2, offset: imm_s(w) }
+        }
+        OP_IMM => {
+            let imm = imm_i(w);
+            let op = match (funct3, funct7) {
+                (0, _) => AluOp::Add,
+                (2, _) => AluOp::Slt,
+                (3, _) => AluOp::Sltu,
+                (4, _) => AluOp::Xor,
+                (6, _) => AluOp::Or,
+                (7, _) => AluOp::And,
+                (1, 0) => AluOp::Sll,
+                (5, 0) => AluOp::Srl,
+                (5, 0b0100000) => AluOp::Sra,
+                _ => return err("unknown shift"),
+            };
+            let imm = if matches!(op, AluOp::Sll | AluOp::Srl | AluOp::Sra) { imm & 0x1F } else { imm };
+            Instruction::OpImm { op, rd, rs1, imm }
+        }
+        OP => {
+            let op = match (funct7, funct3) {
+                (0, 0) => AluOp::Add,
+                (0b0100000, 0) => AluOp::Sub,
+                (0, 1) => AluOp::Sll,
+                (0, 2) => AluOp::Slt,
+                (0, 3) => AluOp::Sltu,
+                (0, 4) => AluOp::Xor,
+                (0, 5) => AluOp::Srl,
+                (0b0100000, 5) => AluOp::Sra,
+                (0, 6) => AluOp::Or,
+                (0, 7) => AluOp::And,
+                (1, 0) => AluOp::Mul,
+                (1, 1) => AluOp::Mulh,
+                (1, 2) => AluOp::Mulhsu,
+                (1, 3) => AluOp::Mulhu,
+                (1, 4) => AluOp::Div,
+                (1, 5) => AluOp::Divu,
+                (1, 6) => AluOp::Rem,
+                (1, 7) => AluOp::Remu,
+                _ => return err("unknown funct7/funct3"),
+            };
+            Instruction::Op { op, rd, rs1, rs2 }
+        }
+        MISC_MEM if funct3 == 0 => Instruction::Fence,
+        SYSTEM => match w >> 7 {
+            0 => Instruction::Ecall,
+            0x2000 => Instruction::Ebreak,
+            _ => return err("CSR instructions are not supported"),
+        },
+        _ => return err("unknown opcode"),
+    };
+    Ok(i)
+}
+
+// ==================== ENCODE ====================
+
+fn alu_funct(op: AluOp) -> (u32, u32) {
+    // (funct7, funct3)
+    match op {
+        AluOp::Add => (0, 0),
+        AluOp::Sub => (0b0100000, 0),
+        AluOp::Sll => (0, 1),
+        AluOp::Slt => (0, 2),
+        AluOp::Sltu => (0, 3),
+        AluOp::Xor => (0, 4),
+        AluOp::Srl => (0, 5),
+        AluOp::Sra => (0b0100000, 5),
+        AluOp::Or => (0, 6),
+        AluOp::And => (0, 7),
+        AluOp::Mul => (1, 0),
+        AluOp::Mulh => (1, 1),
+        AluOp::Mulhsu => (1, 2),
+        AluOp::Mulhu => (1, 3),
+        AluOp::Div => (1, 4),
+        AluOp::Divu => (1, 5),
+        AluOp::Rem => (1, 6),
+        AluOp::Remu => (1, 7),
+    }
+}
+
+fn width_funct3(width: Width) -> u32 {
+    match width {
+        Width::Byte => 0,
+        Width::Half => 1,
+        Width::Word => 2,
+        Width::ByteU => 4,
+        Width::HalfU => 5,
+    }
+}
+
+impl Instruction {
+    pub fn class(&self) -> Class {
+        match *self {
+            Instruction::Op { op: AluOp::Mul | AluOp::Mulh | AluOp::Mulhsu | AluOp::Mulhu, .. } => Class::Mul,
+            Instruction::Op { op: AluOp::Div | AluOp::Divu | AluOp::Rem | AluOp::Remu, .. } => Class::Div,
+            Instruction::Lui { .. } | Instruction::Auipc { .. } | Instruction::OpImm { .. } | Instruction::Op { .. } => Class::Alu,
+            Instruction::Jal { .. } | Instruction::Jalr { .. } => Class::Jump,
+            Instruction::Branch { .. } => Class::Branch,
+            Instruction::Load { .. } => Class::Load,
+            Instruction::Store { .. } => Class::Store,
+            Instruction::Fence | Instruction::Ecall | Instruction::Ebreak => Class::System,
+        }
+    }
+
+    /// The register written, if any (x0 counts as none).
+    pub fn dest(&self) -> Option<u8> {
+        let rd = match *self {
+            Instruction::Lui { rd, .. } | Instruction::Auipc { rd, .. } | Instruction::Jal { rd, .. }
+            | Instruction::Jalr { rd, .. } | Instruction::Load { rd, .. } | Instruction::OpImm { rd, .. }
+            | Instruction::Op { rd, .. } => rd,
+            _ => 0,
+        };
+        (rd != 0).then_some(rd)
+    }
+
+    /// The registers read, x0 left out.
+    pub fn sources(&self) -> Vec<u8> {
+        let regs = match *self {
+            Instruction::Jalr { rs1, .. } | Instruction::Load { rs1, .. } | Instruction::OpImm { rs1, .. } => vec![rs1],
+            Instruction::Branch { rs1, rs2, .. } | Instruction::Store { rs1, rs2, .. } | Instruction::Op { rs1, rs2, .. } => vec![rs1, rs2],
+            _ => Vec::new(),
+        };
+        regs.into_iter().filter(|&r| r != 0).collect()
+    }
+
+    /// The 32-bit encoding. Immediates are cut to their field width, as an
+    /// assembler would after its range check.
+    pub fn encode(&self) -> u32 {
+        let r = |x: u8, at: u32| (x as u32 & 0x1F) << at;
+        match *self {
+            Instruction::Lui { rd, imm } => imm as u32 & 0xFFFF_F000 | r(rd, 7) | LUI,
+            Instruction::Auipc { rd, imm } => imm as u32 & 0xFFFF_F000 | r(rd, 7) | AUIPC,
+            Instruction::Jal { rd, offset } => enc_j(offset) | r(rd, 7) | JAL,
+            Instruction::Jalr { rd, rs1, offset } => enc_i(offset) | r(rs1, 15) | r(rd, 7) | JALR,
+            Instruction::Branch { op, rs1, rs2, offset } => {
+                let f3 = match op {
+                    BranchOp::Beq => 0,
+                    BranchOp::Bne => 1,
+                    BranchOp::Blt => 4,
+                    BranchOp::Bge => 5,
+                    BranchOp::Bltu => 6,
+                    BranchOp::Bgeu => 7,
+                };
+                enc_b(offset) | r(rs2, 20) | r(rs1, 15) | f3 << 12 | BRANCH
+            }
+            Instruction::Load { width, rd, rs1, offset } => {
+                enc_i(offset) | r(rs1, 15) | width_funct3(width) << 12 | r(rd, 7) | LOAD
+            }
+            Instruction::Store { width, rs1, rs2, offset } => {
+                enc_s(offset) | r(rs2, 20) | r(rs1, 15) | width_funct3(width) << 12 | STORE
+            }
+            Instruction::OpImm { op, rd, rs1, imm } => {
+                let (f7, f3) = alu_funct(op);
+                let imm = match op {
+                    AluOp::Sll | AluOp::Srl | AluOp::Sra => f7 << 25 | (imm as u32 & 0x1F) << 20,
+                    _ => enc_i(imm),
+                };
+                imm | r(rs1, 15) | f3 << 12 | r(rd, 7) | OP_IMM
+            }
+            Instruction::Op { op, rd, rs1, rs2 } => {
+                let (f7, f3) = alu_funct(op);
+                f7 << 25 | r(rs2, 20) | r(rs1, 15) | f3 << 12 | r(rd, 7) | OP
+            }
+            Instruction::Fence => 0x0FF0_000F,
+            Instruction::Ecall => 0x0000_0073,
+            Instruction::Ebreak => HALT,
+        }
+    }
+}
+
+// ==================== DISASSEMBLY ====================
+
+fn alu_name(op: AluOp) -> &'static str {
+    match op {
+        AluOp::Add => "add",
+        AluOp::Sub => "sub",
+        AluOp::Sll => "sll",
+        AluOp::Slt => "slt",
+        AluOp::Sltu => "sltu",
+        AluOp::Xor => "xor",
+        AluOp::Srl => "srl",
+        AluOp::Sra => "sra",
+        AluOp::Or => "or",
+        AluOp::And => "and",
+        AluOp::Mul => "mul",
+        AluOp::Mulh => "mulh",
+        AluOp::Mulhsu => "mulhsu",
+        AluOp::Mulhu => "mulhu",
+        AluOp::Div => "div",
+        AluOp::Divu => "divu",
+        AluOp::Rem => "rem",
+        AluOp::Remu => "remu",
+    }
+}
+
+/// `addi a0, zero, 5`, `lw a0, 8(sp)`, `beq a0, a1, 12` (offsets relative to the pc).
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let x = |r: u8| ABI_NAMES[r as usize & 0x1F];
+        match *self {
+            Instruction::Lui { rd, imm } => write!(f, "lui {}, 0x{:x}", x(rd), imm as u32 >> 12),
+            Instruction::Auipc { rd, imm } => write!(f, "auipc {}, 0x{:x}", x(rd), imm as u32 >> 12),
+            Instruction::Jal { rd, offset } => write!(f, "jal {}, {}", x(rd), offset),
+            Instruction::Jalr { rd, rs1, offset } => write!(f, "jalr {}, {}({})", x(rd), offset, x(rs1)),
+            Instruction::Branch { op, rs1, rs2, offset } => {
+                let name = format!("{:?}", op).to_lowercase();
+                write!(f, "{} {}, {}, {}", name, x(rs1), x(rs2), offset)
+            }
+            Instruction::Load { width, rd, rs1, offset } => {
+                let name = ["lb", "lh", "lw", "lbu", "lhu"][width_index(width)];
+                write!(f, "{} {}, {}({})", name, x(rd), offset, x(rs1))
+            }
+            Instruction::Store { width, rs1, rs2, offset } => {
+                let name = ["sb", "sh", "sw", "sb", "sh"][width_index(width)];
+                write!(f, "{} {}, {}({})", name, x(rs2), offset, x(rs1))
+            }
+            Instruction::OpImm { op, rd, rs1, imm } => {
+                let name = if op == AluOp::Sltu { "sltiu".to_string() } else { format!("{}i", alu_name(op)) };
+                write!(f, "{} {}, {}, {}", name, x(rd), x(rs1), imm)
+            }
+            Instruction::Op { op, rd, rs1, rs2 } => write!(f, "{} {}, {}, {}", alu_name(op), x(rd), x(rs1), x(rs2)),
+            Instruction::Fence => write!(f, "fence"),
+            Instruction::Ecall => write!(f, "ecall"),
+            Instruction::Ebreak => write!(f, "ebreak"),
+        }
+    }
+}
+
+fn width_index(width: Width) -> usize {
+    match width {
+        Width::Byte => 0,
+        Width::Half => 1,
+        Width::Word => 2,
+        Width::ByteU => 3,
+        Width::HalfU => 4,
+    }
+}
+
+/// The disassembly of a word, or `.word 0x...` if it does not decode.
+pub fn disassemble(w: u32) -> String {
+    match decode(w) {
+        Ok(i) => i.to_string(),
+        Err(_) => format!(".word 0x{:08x}", w),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_encodings() {
+        let cases = [
+            (0x0050_0093, "addi ra, zero, 5"),
+            (0x00B5_0533, "add a0, a0, a1"),
+            (0x40B5_0533, "sub a0, a0, a1"),
+            (0x0081_2503, "lw a0, 8(sp)"),
+            (0x00A1_2623, "sw a0, 12(sp)"),
+            (0x00B5_0463, "beq a0, a1, 8"),
+            (0xFE0F_0EE3, "beq t5, zero, -4"),
+            (0xFF9F_F0EF, "jal ra, -8"),
+            (0x1234_5537, "lui a0, 0x12345"),
+            (0x0000_8067, "jalr zero, 0(ra)"),
+            (0x02B5_0533, "mul a0, a0, a1"),
+            (0x4015_5513, "srai a0, a0, 1"),
+            (0xFFF5_4513, "xori a0, a0, -1"),
+            (0xFFF5_4503, "lbu a0, -1(a0)"),
+            (0x0000_0073, "ecall"),
+            (HALT, "ebreak"),
+        ];
+        for (word, text) in cases {
+            let i = decode(word).unwrap();
+            assert_eq!(i.to_string(), text);
+            assert_eq!(i.encode(), word, "{}", text);
+        }
+    }
+
+    #[test]
+    fn immediates_at_their_limits() {
+        for offset in [-4096, -2, 0, 2, 4094] {
+            let b = Instruction::Branch { op: BranchOp::Bltu, rs1: 5, rs2: 6, offset };
+            assert_eq!(decode(b.encode()), Ok(b));
+        }
+        for offset in [-(1 << 20), -2, 2, (1 << 20) - 2] {
+            let j = Instruction::Jal { rd: 1, offset };
+            assert_eq!(decode(j.encode()), Ok(j));
+        }
+        for imm in [-2048, -1, 0, 2047] {
+            let s = Instruction::Store { width: Width::Half, rs1: 2, rs2: 31, offset: imm };
+            assert_eq!(decode(s.encode()), Ok(s));
+        }
+    }
+
+    #[test]
+    fn every_decodable_word_round_trips() {
+        let mut w = 0x1357_9BDFu32;
+        let mut decoded = 0;
+        for _ in 0..200_000 {
+            w ^= w << 13;
+            w ^= w >> 17;
+            w ^= w << 5;
+            // bias towards real opcodes so every format is hit
+            let word = (w & !0x7F) | [LUI, AUIPC, JAL, JALR, BRANCH, LOAD, STORE, OP_IMM, OP][(w % 9) as usize];
+            if let Ok(i) = decode(word) {
+                decoded += 1;
+                assert_eq!(i.encode(), word, "{}", i);
+            }
+        }
+        assert!(decoded > 100_000);
+    }
+
+    #[test]
+    fn illegal_words() {
+        assert_eq!(decode(0).unwrap_err().reason, "unknown opcode");
+        assert!(decode(0x0200_5013).is_err()); // srli with funct7 = 1
+        assert!(decode(0x3000_2073).is_err()); // csrr
+        assert_eq!(disassemble(0xFFFF_FFFF), ".word 0xffffffff");
+    }
+
+    #[test]
+    fn classes() {
+        let class = |w| decode(w).unwrap().class();
+        assert_eq!([class(0x02B5_0533), class(0x00B5_0533), class(0x1234_5537)], [Class::Mul, Class::Alu, Class::Alu]);
+        assert_eq!([class(0x0081_2503), class(0xFF9F_F0EF), class(HALT)], [Class::Load, Class::Jump, Class::System]);
+        assert_eq!(class(0x02B5_4533), Class::Div); // div a0, a0, a1
+    }
+}
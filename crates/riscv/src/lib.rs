@@ -0,0 +1,26 @@
+//! RV32IM: instruction encoding and decoding, a disassembler, an
+//! interpreter with precise traps, an ELF loader, the ecall environment
+//! and the hazard timing of the five-stage pipeline - the Rust counterpart
+//! of the Java processor models, for tools that need to run real machine
+//! code. Peripherals of your own implement `device::Device` and are
+//! attached to the processor's bus; `predictor` has the branch predictors
+//! to run the branches of a program through, and `speculate` runs ahead
+//! down their mispredicted paths with a data cache to leave footprints in.
+//! `frames` follows the calls and returns of a run and draws its stack;
+//! `x86` decodes a little x86-64 to set its encodings next to RV32's.
+
+pub mod cpu;
+pub mod device;
+pub mod elf;
+pub mod frames;
+pub mod instruction;
+pub mod pipeline;
+pub mod predictor;
+pub mod speculate;
+pub mod syscall;
+pub mod x86;
+
+pub use cpu::{Cpu, Memory, Trap};
+pub use device::Device;
+pub use elf::Program;
+pub use instruction::{decode, disassemble, Instruction};
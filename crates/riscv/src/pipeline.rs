@@ -0,0 +1,167 @@
+/*
+ * pipeline - the cycles straight-line code takes in the classic five-stage
+ * pipeline, and the bubbles of its data hazards.
+ *
+ *   cycle           1   2   3   4   5   6   7   8
+ *   lw t0, 0(a0)    IF  ID  EX  MEM WB
+ *   add t1, t0, t0      IF  ID  --  EX  MEM WB          load-use: 1 stall
+ *   sw t1, 4(a0)            IF  --  ID  EX  MEM WB      held in IF behind it
+ *
+ * The register file is written in the first half of WB and read in the
+ * second half of ID, as in computerdesign.pipeline.HazardUnit:
+ *
+ *   without forwarding  an instruction leaves ID no earlier than the WB of
+ *                       the instruction that writes its operand: 2 stalls
+ *                       right after it, 1 with one instruction between
+ *   with forwarding     ALU results reach the next EX from EX/MEM, so only
+ *                       a load costs: 1 stall if the next one uses it
+ *
+ * A stalled instruction waits in ID and holds the one behind it in IF.
+ * Control hazards are not modelled: the code runs straight through, as a
+ * basic block or a loop body does. What a core does past a branch it has
+ * not resolved yet is in `speculate`.
+ */
+
+use crate::instruction::Instruction;
+use std::fmt::Write;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Slot {
+    pub instruction: Instruction,
+    /// Cycle of IF, counting from 1.
+    pub fetch: u32,
+    /// First cycle in ID.
+    pub decode: u32,
+    /// Bubbles while waiting in ID; EX is at decode + stalls + 1.
+    pub stalls: u32,
+    /// The earlier instruction (index) and register this one waited for.
+    pub waits_for: Option<(usize, u8)>,
+}
+
+impl Slot {
+    /// Last cycle in ID, in which the operands are read or forwarded.
+    pub fn issue(&self) -> u32 {
+        self.decode + self.stalls
+    }
+
+    pub fn writeback(&self) -> u32 {
+        self.issue() + 3
+    }
+
+    /// The stage in each cycle from 1 to WB: "" before IF, "--" a bubble.
+    pub fn stages(&self) -> Vec<&'static str> {
+        let mut row = vec![""; self.fetch as usize - 1];
+        row.push("IF");
+        row.extend(std::iter::repeat_n("--", (self.decode - self.fetch - 1) as usize));
+        row.push("ID");
+        row.extend(std::iter::repeat_n("--", self.stalls as usize));
+        row.extend(["EX", "MEM", "WB"]);
+        row
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    pub forwarding: bool,
+    pub slots: Vec<Slot>,
+}
+
+impl Schedule {
+    pub fn new(code: &[Instruction], forwarding: bool) -> Schedule {
+        let mut slots: Vec<Slot> = Vec::with_capacity(code.len());
+        for &instruction in code {
+            let (fetch, decode) = match slots.last() {
+                None => (1, 2),
+                Some(prev) => (prev.decode, prev.issue() + 1),
+            };
+            let mut ready = decode;
+            let mut waits_for = None;
+            for r in instruction.sources() {
+                let Some(i) = slots.iter().rposition(|s| s.instruction.dest() == Some(r)) else { continue };
+                let producer = &slots[i];
+                let earliest = match (forwarding, producer.instruction) {
+                    (false, _) => producer.writeback(),
+                    (true, Instruction::Load { .. }) => producer.issue() + 2,
+                    (true, _) => producer.issue() + 1,
+                };
+                if earliest > ready {
+                    ready = earliest;
+                    waits_for = Some((i, r));
+                }
+            }
+            slots.push(Slot { instruction, fetch, decode, stalls: ready - decode, waits_for });
+        }
+        Schedule { forwarding, slots }
+    }
+
+    pub fn stalls(&self) -> u32 {
+        self.slots.iter().map(|s| s.stalls).sum()
+    }
+
+    /// Cycles until the last instruction's WB, the pipeline starting empty.
+    pub fn cycles(&self) -> u32 {
+        self.slots.last().map_or(0, Slot::writeback)
+    }
+
+    /// One row per instruction, one column per cycle; -- is a bubble.
+    pub fn diagram(&self) -> String {
+        let names: Vec<String> = self.slots.iter().map(|s| s.instruction.to_string()).collect();
+        let w = names.iter().map(|n| n.len()).max().unwrap_or(0).max(5);
+        let mut s = format!("{:<w$}", "cycle", w = w + 2);
+        for c in 1..=self.cycles() {
+            let _ = write!(s, "{:<4}", c);
+        }
+        s = s.trim_end().to_string() + "\n";
+        for (slot, name) in self.slots.iter().zip(&names) {
+            let mut row = format!("{:<w$}", name, w = w + 2);
+            for stage in slot.stages() {
+                let _ = write!(row, "{:<4}", stage);
+            }
+            let _ = writeln!(s, "{}", row.trim_end());
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::{AluOp, Width};
+
+    const T0: u8 = 5;
+    const T1: u8 = 6;
+    const A0: u8 = 10;
+
+    fn lw(rd: u8) -> Instruction {
+        Instruction::Load { width: Width::Word, rd, rs1: A0, offset: 0 }
+    }
+
+    fn add(rd: u8, rs1: u8, rs2: u8) -> Instruction {
+        Instruction::Op { op: AluOp::Add, rd, rs1, rs2 }
+    }
+
+    #[test]
+    fn load_use_with_and_without_forwarding() {
+        let code = [lw(T0), add(T1, T0, T0), Instruction::Store { width: Width::Word, rs1: A0, rs2: T1, offset: 4 }];
+        let fwd = Schedule::new(&code, true);
+        assert_eq!(fwd.slots.iter().map(|s| s.stalls).collect::<Vec<_>>(), [0, 1, 0]);
+        assert_eq!((fwd.stalls(), fwd.cycles(), fwd.slots[1].waits_for), (1, 8, Some((0, T0))));
+        let diagram = fwd.diagram();
+        let lines: Vec<&str> = diagram.lines().collect();
+        assert_eq!(lines[2], "add t1, t0, t0      IF  ID  --  EX  MEM WB");
+        assert_eq!(lines[3], "sw t1, 4(a0)            IF  --  ID  EX  MEM WB");
+        assert_eq!(fwd.slots[2].stages(), ["", "", "IF", "--", "ID", "EX", "MEM", "WB"]);
+        let plain = Schedule::new(&code, false);
+        assert_eq!((plain.stalls(), plain.cycles()), (4, 11));
+    }
+
+    #[test]
+    fn distance_and_x0() {
+        // one independent instruction between producer and consumer
+        let code = [add(T0, A0, A0), add(A0, A0, A0), add(T1, T0, 0), add(0, T1, T1), add(T1, 0, 0)];
+        let plain = Schedule::new(&code, false);
+        assert_eq!(plain.slots.iter().map(|s| s.stalls).collect::<Vec<_>>(), [0, 0, 1, 2, 0]);
+        assert_eq!(Schedule::new(&code, true).stalls(), 0);
+        assert_eq!(Schedule::new(&[], true).cycles(), 0);
+    }
+}
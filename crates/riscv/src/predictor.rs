@@ -0,0 +1,201 @@
+/*
+ * predictor - the textbook branch predictors, fed one outcome at a time.
+ *
+ *   pc ──► index ──► table of counters ──► taken?      update with what
+ *          pc bits   (gshare: pc bits           the branch really did
+ *                     xor the last outcomes)
+ *
+ *   2-bit counter   0 ──T──► 1 ──T──► 2 ──T──► 3       0,1 predict not taken
+ *                   0 ◄──N── 1 ◄──N── 2 ◄──N── 3       2,3 predict taken
+ *
+ * The tables have 2^bits entries indexed by the word address of the
+ * branch, so with bits = 0 every branch shares one entry, which is what a
+ * single branch studied on its own needs. A table too small for a program
+ * shows as branches that alias and spoil each other's counters. gshare
+ * keeps the outcomes of the last `bits` branches and xors them into the
+ * index, so it learns patterns that repeat and correlated branches.
+ *
+ * The outcomes come from anything that runs branches: a closure in a demo,
+ * or (pc, taken) pairs recorded while the emulator runs a program.
+ */
+
+pub trait Predictor {
+    fn name(&self) -> &'static str;
+    fn predict(&self, pc: u32) -> bool;
+    fn update(&mut self, pc: u32, taken: bool);
+}
+
+pub const KINDS: &[&str] = &["static", "1bit", "2bit", "gshare"];
+
+/// A fresh predictor of one of KINDS with 2^bits entries.
+pub fn by_name(kind: &str, bits: u32) -> Result<Box<dyn Predictor>, String> {
+    if bits > 20 {
+        return Err(format!("{} bits make a table of 2^{} entries, at most 20 bits", bits, bits));
+    }
+    Ok(match kind {
+        "static" => Box::new(StaticNotTaken),
+        "1bit" => Box::new(OneBit::new(bits)),
+        "2bit" => Box::new(TwoBit::new(bits)),
+        "gshare" => Box::new(GShare::new(bits)),
+        other => return Err(format!("unknown predictor '{}', one of {}", other, KINDS.join(", "))),
+    })
+}
+
+fn index(pc: u32, bits: u32) -> usize {
+    (pc as usize >> 2) & ((1 << bits) - 1)
+}
+
+/// Always predicts not taken (what a simple in-order pipeline does).
+pub struct StaticNotTaken;
+
+impl Predictor for StaticNotTaken {
+    fn name(&self) -> &'static str {
+        "static not-taken"
+    }
+
+    fn predict(&self, _: u32) -> bool {
+        false
+    }
+
+    fn update(&mut self, _: u32, _: bool) {}
+}
+
+/// Predicts whatever the branch did last time.
+pub struct OneBit {
+    bits: u32,
+    last: Vec<bool>,
+}
+
+impl OneBit {
+    pub fn new(bits: u32) -> OneBit {
+        OneBit { bits, last: vec![false; 1 << bits] }
+    }
+}
+
+impl Predictor for OneBit {
+    fn name(&self) -> &'static str {
+        "1-bit last outcome"
+    }
+
+    fn predict(&self, pc: u32) -> bool {
+        self.last[index(pc, self.bits)]
+    }
+
+    fn update(&mut self, pc: u32, taken: bool) {
+        self.last[index(pc, self.bits)] = taken;
+    }
+}
+
+/// Saturating counters starting weakly not taken: one surprise does not
+/// flip a strongly held prediction.
+pub struct TwoBit {
+    bits: u32,
+    counters: Vec<u8>,
+}
+
+impl TwoBit {
+    pub fn new(bits: u32) -> TwoBit {
+        TwoBit { bits, counters: vec![1; 1 << bits] }
+    }
+}
+
+fn count(counter: &mut u8, taken: bool) {
+    if taken && *counter < 3 {
+        *counter += 1;
+    } else if !taken && *counter > 0 {
+        *counter -= 1;
+    }
+}
+
+impl Predictor for TwoBit {
+    fn name(&self) -> &'static str {
+        "2-bit saturating"
+    }
+
+    fn predict(&self, pc: u32) -> bool {
+        self.counters[index(pc, self.bits)] >= 2
+    }
+
+    fn update(&mut self, pc: u32, taken: bool) {
+        count(&mut self.counters[index(pc, self.bits)], taken);
+    }
+}
+
+/// 2-bit counters indexed by the pc xor the last `bits` outcomes.
+pub struct GShare {
+    history: usize,
+    bits: u32,
+    counters: Vec<u8>,
+}
+
+impl GShare {
+    pub fn new(bits: u32) -> GShare {
+        GShare { history: 0, bits, counters: vec![1; 1 << bits] }
+    }
+
+    fn slot(&self, pc: u32) -> usize {
+        index(pc, self.bits) ^ self.history
+    }
+}
+
+impl Predictor for GShare {
+    fn name(&self) -> &'static str {
+        "gshare"
+    }
+
+    fn predict(&self, pc: u32) -> bool {
+        self.counters[self.slot(pc)] >= 2
+    }
+
+    fn update(&mut self, pc: u32, taken: bool) {
+        let slot = self.slot(pc);
+        count(&mut self.counters[slot], taken);
+        self.history = ((self.history << 1) | taken as usize) & ((1 << self.bits) - 1);
+    }
+}
+
+/// The fraction of `outcomes`, (pc, taken), that `p` got wrong.
+pub fn miss_rate(p: &mut dyn Predictor, outcomes: &[(u32, bool)]) -> f64 {
+    let mut misses = 0;
+    for &(pc, taken) in outcomes {
+        if p.predict(pc) != taken {
+            misses += 1;
+        }
+        p.update(pc, taken);
+    }
+    if outcomes.is_empty() {
+        0.0
+    } else {
+        misses as f64 / outcomes.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(kind: &str, bits: u32, outcomes: &[(u32, bool)]) -> f64 {
+        miss_rate(&mut *by_name(kind, bits).unwrap(), outcomes)
+    }
+
+    #[test]
+    fn history_learns_a_pattern_counters_do_not() {
+        // T T T N, over and over, from one branch
+        let pattern: Vec<(u32, bool)> = (0..4000).map(|i| (0x1000, i % 4 != 3)).collect();
+        assert_eq!(rate("static", 0, &pattern), 0.75);
+        assert!((rate("1bit", 0, &pattern) - 0.5).abs() < 0.01);
+        assert!((rate("2bit", 0, &pattern) - 0.25).abs() < 0.01);
+        assert!(rate("gshare", 8, &pattern) < 0.01);
+        assert_eq!(rate("2bit", 4, &[]), 0.0);
+        assert!(by_name("tage", 8).is_err() && by_name("2bit", 40).is_err());
+    }
+
+    #[test]
+    fn branches_alias_in_a_small_table() {
+        // two branches that always go opposite ways, 4 bytes apart
+        let outcomes: Vec<(u32, bool)> = (0..1000).flat_map(|_| [(0x1000, true), (0x1004, false)]).collect();
+        assert_eq!(rate("1bit", 0, &outcomes), 1.0);
+        assert_eq!(rate("1bit", 1, &outcomes), 0.0005);
+        assert!(rate("2bit", 1, &outcomes) < 0.001);
+    }
+}
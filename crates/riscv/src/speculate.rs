@@ -0,0 +1,256 @@
+/*
+ * speculate - the emulator with a branch predictor and a data cache in
+ * front of it, running ahead down the predicted path of every branch, the
+ * way an out-of-order core does before the branch resolves.
+ *
+ *   bgeu a0, t1, done     predicted not taken, really taken
+ *     add  t2, t0, a0   ┐
+ *     lbu  t2, 64(t2)   │ run down the wrong path for `window`
+ *     slli t2, t2, 6    │ instructions, then squashed: registers and pc
+ *     add  t2, t2, t3   │ back as they were ...
+ *     lbu  t2, 0(t2)    ┘ ... but every line they loaded is still cached
+ *
+ * That last line is the whole of Spectre (variant 1). Architecturally the
+ * bounds check held and nothing happened; the cache remembers an address
+ * that was computed from a byte the program was never allowed to read. A
+ * timed load of each candidate line (`time`) finds the one that hits,
+ * and the byte is its index.
+ *
+ * Only what the simulator needs for the lecture is modelled. The wrong
+ * path starts at a mispredicted conditional branch; jumps are assumed
+ * predicted right, and branches met on the wrong path follow the
+ * predictor. It ends after `window` instructions, or early at whatever a
+ * real core would not run speculatively, or would hold back:
+ *
+ *   a store          stays in the store buffer, so is simply not run
+ *   fence            a speculation barrier here, as lfence is on x86
+ *   ecall, ebreak    and any trap: the fault would only be raised at retire
+ *   a device load    MMIO reads have side effects, so cores do not speculate them
+ *
+ * The squashed instructions still tick the devices on the bus. Cycles
+ * count 1 per instruction, hit_time or miss_time more per access and the
+ * window's length for every misprediction.
+ */
+
+use cache::{Access, Cache};
+
+use crate::cpu::{bytes, Cpu, Trap};
+use crate::instruction::Instruction;
+use crate::predictor::Predictor;
+
+/// What ran down the wrong path of one branch and was thrown away.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Squash {
+    /// pc of the mispredicted branch.
+    pub branch: u32,
+    /// (pc, instruction) in the order they ran.
+    pub path: Vec<(u32, Instruction)>,
+    /// Addresses loaded, which the cache keeps.
+    pub loads: Vec<u32>,
+    /// Why the path ended before the window did.
+    pub stopped: Option<&'static str>,
+}
+
+pub struct Speculative {
+    pub cpu: Cpu,
+    pub predictor: Box<dyn Predictor>,
+    pub cache: Cache,
+    /// Instructions run past a branch before it resolves; 0 turns speculation off.
+    pub window: usize,
+    pub hit_time: u32,
+    pub miss_time: u32,
+    pub cycles: u64,
+    pub mispredicted: u64,
+    /// The most recent squashes, oldest first; at most `keep`.
+    pub squashed: Vec<Squash>,
+    pub keep: usize,
+}
+
+impl Speculative {
+    /// A window of 8, hits in 1 cycle and misses in 100.
+    pub fn new(cpu: Cpu, predictor: Box<dyn Predictor>, cache: Cache) -> Speculative {
+        Speculative { cpu, predictor, cache, window: 8, hit_time: 1, miss_time: 100, cycles: 0, mispredicted: 0, squashed: Vec::new(), keep: 16 }
+    }
+
+    /// Execute one instruction, and the wrong path after it if it is a
+    /// mispredicted branch. On a trap nothing has changed.
+    pub fn step(&mut self) -> Result<Instruction, Trap> {
+        let pc = self.cpu.pc;
+        let inst = self.cpu.fetch()?;
+        let addr = self.address(inst);
+        self.cpu.step()?;
+        self.cycles += 1;
+        match (inst, addr) {
+            (Instruction::Load { .. }, Some(addr)) => self.cycles += u64::from(self.load(addr, Access::Read)),
+            (Instruction::Store { .. }, Some(addr)) => self.cycles += u64::from(self.load(addr, Access::Write)),
+            (Instruction::Branch { offset, .. }, _) => {
+                let taken = self.cpu.pc != pc.wrapping_add(4);
+                let predicted = self.predictor.predict(pc);
+                self.predictor.update(pc, taken);
+                if predicted != taken {
+                    self.mispredicted += 1;
+                    self.cycles += self.window as u64;
+                    let wrong = if predicted { pc.wrapping_add(offset as u32) } else { pc.wrapping_add(4) };
+                    self.speculate(pc, wrong);
+                }
+            }
+            _ => {}
+        }
+        Ok(inst)
+    }
+
+    /// Step until a trap, or None after `max_steps` instructions.
+    pub fn run(&mut self, max_steps: u64) -> Option<Trap> {
+        for _ in 0..max_steps {
+            if let Err(trap) = self.step() {
+                return Some(trap);
+            }
+        }
+        None
+    }
+
+    /// A timed load of `addr`, as the attacker measures it: the cycles it
+    /// took. It goes through the cache like any other.
+    pub fn time(&mut self, addr: u32) -> u32 {
+        let cycles = self.load(addr, Access::Read);
+        self.cycles += u64::from(cycles);
+        cycles
+    }
+
+    fn load(&mut self, addr: u32, kind: Access) -> u32 {
+        if self.cache.access(addr, kind).hit { self.hit_time } else { self.miss_time }
+    }
+
+    /// Where a load or store is about to go.
+    fn address(&self, inst: Instruction) -> Option<u32> {
+        match inst {
+            Instruction::Load { rs1, offset, .. } | Instruction::Store { rs1, offset, .. } => Some(self.cpu.reg(rs1).wrapping_add(offset as u32)),
+            _ => None,
+        }
+    }
+
+    /// Run from `from` for up to `window` instructions, then put the
+    /// registers, pc and count back; only the cache keeps what happened.
+    fn speculate(&mut self, branch: u32, from: u32) {
+        if self.window == 0 {
+            return;
+        }
+        let (pc, retired) = (self.cpu.pc, self.cpu.retired);
+        let regs: [u32; 32] = std::array::from_fn(|r| self.cpu.reg(r as u8));
+        self.cpu.pc = from;
+        let mut squash = Squash { branch, path: Vec::new(), loads: Vec::new(), stopped: None };
+        while squash.path.len() < self.window {
+            let at = self.cpu.pc;
+            let Ok(inst) = self.cpu.fetch() else {
+                squash.stopped = Some("a fetch fault");
+                break;
+            };
+            let addr = self.address(inst);
+            squash.stopped = match inst {
+                Instruction::Store { .. } => Some("a store"),
+                Instruction::Fence => Some("fence"),
+                Instruction::Ecall | Instruction::Ebreak => Some("a system instruction"),
+                Instruction::Load { width, .. } if addr.is_some_and(|a| self.cpu.mem.slice(a, bytes(width)).is_none()) => Some("a load outside RAM"),
+                _ => None,
+            };
+            if squash.stopped.is_some() {
+                break;
+            }
+            if self.cpu.step().is_err() {
+                squash.stopped = Some("a trap");
+                break;
+            }
+            squash.path.push((at, inst));
+            match (inst, addr) {
+                (Instruction::Load { .. }, Some(addr)) => {
+                    self.cache.access(addr, Access::Read);
+                    squash.loads.push(addr);
+                }
+                (Instruction::Branch { offset, .. }, _) => {
+                    self.cpu.pc = if self.predictor.predict(at) { at.wrapping_add(offset as u32) } else { at.wrapping_add(4) };
+                }
+                _ => {}
+            }
+        }
+        for (r, &v) in regs.iter().enumerate() {
+            self.cpu.set_reg(r as u8, v);
+        }
+        (self.cpu.pc, self.cpu.retired) = (pc, retired);
+        if self.squashed.len() >= self.keep.max(1) {
+            self.squashed.remove(0);
+        }
+        self.squashed.push(squash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::Memory;
+    use crate::instruction::{AluOp, BranchOp, Width};
+    use crate::predictor::TwoBit;
+    use cache::{Config, Replacement, WritePolicy};
+
+    const A0: u8 = 10;
+    const T0: u8 = 5;
+    const T1: u8 = 6;
+
+    /// if a0 < 4 { t1 = probe[array[a0] * 64] }, probe at 0x400, array at 0x100
+    fn machine(barrier: bool) -> Speculative {
+        let mut code = vec![
+            Instruction::OpImm { op: AluOp::Add, rd: T0, rs1: 0, imm: 4 },
+            Instruction::Branch { op: BranchOp::Bgeu, rs1: A0, rs2: T0, offset: if barrier { 20 } else { 16 } },
+            Instruction::Load { width: Width::ByteU, rd: T1, rs1: A0, offset: 0x100 },
+            Instruction::OpImm { op: AluOp::Sll, rd: T1, rs1: T1, imm: 6 },
+            Instruction::Load { width: Width::ByteU, rd: T1, rs1: T1, offset: 0x400 },
+            Instruction::Ebreak,
+        ];
+        if barrier {
+            code.insert(2, Instruction::Fence);
+        }
+        let mut mem = Memory::new(0, 0x400 + 256 * 64);
+        mem.load_words(0, &code.iter().map(Instruction::encode).collect::<Vec<_>>());
+        mem.load(0x100, &[1, 2, 3, 4, 42]);
+        let config = Config { size: 32 << 10, line: 64, ways: 8, replacement: Replacement::Lru, write: WritePolicy::WriteBackAllocate };
+        Speculative::new(Cpu::new(mem, 0), Box::new(TwoBit::new(4)), Cache::new(config).unwrap())
+    }
+
+    /// Train with in-bounds indices, flush the probe lines, call with 4.
+    fn attack(m: &mut Speculative) -> Vec<u32> {
+        for x in [0, 1, 2, 3, 0, 1, 2, 3, 4] {
+            if x == 4 {
+                for v in 0..256 {
+                    m.cache.invalidate(0x400 + v * 64);
+                }
+            }
+            (m.cpu.pc, m.cpu.retired) = (0, 0);
+            m.cpu.set_reg(A0, x);
+            m.cpu.set_reg(T1, 0);
+            assert_eq!(m.run(100), Some(Trap::Breakpoint(m.cpu.pc)));
+        }
+        (0..256).filter(|&v| m.time(0x400 + v * 64) == m.hit_time).collect()
+    }
+
+    #[test]
+    fn a_mispredicted_bounds_check_leaves_the_byte_in_the_cache() {
+        let mut m = machine(false);
+        assert_eq!(attack(&mut m), [42]);
+        // architecturally the load never happened
+        assert_eq!((m.cpu.reg(T1), m.cpu.retired), (0, 2));
+        let squash = m.squashed.last().unwrap();
+        assert_eq!((squash.branch, squash.loads.clone(), squash.stopped), (4, vec![0x104, 0x400 + 42 * 64], Some("a system instruction")));
+        assert_eq!(m.mispredicted, 1);
+    }
+
+    #[test]
+    fn no_leak_behind_a_barrier_or_a_short_window() {
+        for (mut m, stopped) in [(machine(true), Some("fence")), ({ let mut m = machine(false); m.window = 2; m }, None)] {
+            assert!(attack(&mut m).is_empty());
+            assert_eq!(m.squashed.last().unwrap().stopped, stopped);
+        }
+        let mut m = machine(false);
+        m.window = 0;
+        assert!(attack(&mut m).is_empty());
+        assert!(m.squashed.is_empty());
+    }
+}
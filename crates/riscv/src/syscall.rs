@@ -0,0 +1,121 @@
+/*
+ * syscall - the environment an ecall talks to.
+ *
+ *   a7 = call number, a0..a2 = arguments, result in a0
+ *
+ *   a7   Linux (newlib, -nostdlib)      a7   RARS
+ *   64   write(fd, buf, len)            1    print_int(a0)
+ *   93   exit(code)                     4    print_string(a0)
+ *                                       10   exit
+ *                                       11   print_char(a0)
+ *                                       93   exit2(code)
+ *
+ * The two numberings do not clash, so programs from the RARS exercises
+ * and from a cross compiler both run. Output goes to a closure (dispatch)
+ * or to any io::Write (handle). The pc is moved past the ecall, which then
+ * counts as retired, so execution can go on.
+ */
+
+use crate::cpu::Cpu;
+use std::io::{self, Write};
+
+const A0: u8 = 10;
+const A1: u8 = 11;
+const A2: u8 = 12;
+const A7: u8 = 17;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Continue,
+    Exit(i32),
+    /// A call number this environment does not provide.
+    Unknown(u32),
+}
+
+/// Perform the ecall at pc, passing what the program prints to `out`.
+/// No std::io here, so it runs where there is no stdout (wasm32).
+pub fn dispatch(cpu: &mut Cpu, out: &mut dyn FnMut(&[u8])) -> Outcome {
+    let (a0, a1, a2) = (cpu.reg(A0), cpu.reg(A1), cpu.reg(A2));
+    let outcome = match cpu.reg(A7) {
+        64 => {
+            let written = match cpu.mem.slice(a1, a2 as usize) {
+                Some(bytes) if a0 == 1 || a0 == 2 => {
+                    out(bytes);
+                    a2
+                }
+                _ => -1i32 as u32,
+            };
+            cpu.set_reg(A0, written);
+            Outcome::Continue
+        }
+        93 => Outcome::Exit(a0 as i32),
+        1 => {
+            out((a0 as i32).to_string().as_bytes());
+            Outcome::Continue
+        }
+        4 => {
+            let mut text = Vec::new();
+            let mut addr = a0;
+            while let Some(&b) = cpu.mem.slice(addr, 1).and_then(|s| s.first()) {
+                if b == 0 {
+                    break;
+                }
+                text.push(b);
+                addr = addr.wrapping_add(1);
+            }
+            out(&text);
+            Outcome::Continue
+        }
+        10 => Outcome::Exit(0),
+        11 => {
+            out(&[a0 as u8]);
+            Outcome::Continue
+        }
+        n => return Outcome::Unknown(n),
+    };
+    cpu.pc = cpu.pc.wrapping_add(4);
+    cpu.retired += 1;
+    outcome
+}
+
+/// `dispatch` onto a stream; the first write error is returned.
+pub fn handle(cpu: &mut Cpu, out: &mut dyn Write) -> io::Result<Outcome> {
+    let mut error = None;
+    let outcome = dispatch(cpu, &mut |bytes| {
+        if error.is_none() {
+            error = out.write_all(bytes).err();
+        }
+    });
+    error.map_or(Ok(outcome), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::{Memory, Trap};
+    use crate::instruction::{AluOp, Instruction};
+
+    #[test]
+    fn hello_and_exit() {
+        let li = |rd, imm| Instruction::OpImm { op: AluOp::Add, rd, rs1: 0, imm }.encode();
+        let ecall = Instruction::Ecall.encode();
+        let code = [li(A7, 64), li(A0, 1), li(A1, 0x100), li(A2, 3), ecall, li(A7, 1), li(A0, -5), ecall, li(A7, 93), li(A0, 3), ecall];
+        let mut mem = Memory::new(0, 0x200);
+        mem.load_words(0, &code);
+        mem.load(0x100, b"hi\n");
+        let mut cpu = Cpu::new(mem, 0);
+        let mut out = Vec::new();
+        let code = loop {
+            match cpu.run(100) {
+                Some(Trap::Ecall(_)) => match handle(&mut cpu, &mut out).unwrap() {
+                    Outcome::Continue => continue,
+                    Outcome::Exit(code) => break code,
+                    Outcome::Unknown(n) => panic!("ecall {}", n),
+                },
+                other => panic!("{:?}", other),
+            }
+        };
+        assert_eq!((code, out.as_slice()), (3, &b"hi\n-5"[..]));
+        assert_eq!((cpu.reg(A0), cpu.retired), (3, 11));
+    }
+}
@@ -0,0 +1,631 @@
+/*
+ * x86 - enough of an x86-64 decoder to set the two ISAs side by side.
+ *
+ *   [prefixes] [REX] opcode [ModRM] [SIB] [displacement] [immediate]
+ *      0-4      0-1    1-2    0-1    0-1     0, 1, 4       0, 1, 2, 4, 8    1 to 15 bytes
+ *
+ *   66 C7 03 34 12      mov word ptr [rbx], 0x1234
+ *   │  │  │  └──┴─ imm16: two bytes, not four, because of the 66
+ *   │  │  └─ ModRM 00 000 011: [rbx]; reg 000 picks mov out of group C7
+ *   │  └─ opcode C7: mov r/m, imm - so a ModRM follows, then an immediate
+ *   └─ prefix 66: the operand is 16 bits
+ *
+ * Every byte decides what the next one is: a prefix or not, whether a
+ * ModRM follows, whether that needs a SIB byte or a displacement, how long
+ * the immediate is. The length of an instruction is known only once all
+ * of those have been read (`length_known_after`), so a wide decoder has to
+ * guess where the next instruction starts. RV32 needs two bits: 11 in the
+ * low bits of the first byte is a 32-bit instruction, and its fields sit
+ * at fixed places in it (instruction.rs). `comparisons` pairs the same
+ * operation in both for the ISA-design lecture; x86 saves bytes where one
+ * instruction does the work of several, RV32 decodes each in one step.
+ *
+ * Covered: the legacy prefixes (66 67 F0 F2 F3 and the segments), REX,
+ * every ModRM and SIB form with RIP-relative, the ALU group (00-3D,
+ * 80/81/83), test, mov (88-8B, B0-BF, C6/C7), lea, push/pop, inc, dec,
+ * indirect call and jmp (FE/FF), imul, movzx, jmp, jcc, call, ret, nop,
+ * int3, syscall and the string moves and stores. Anything else is an
+ * error, not a guess. Jump offsets are shown as RV32 shows them, from the
+ * start of the instruction; the encoding counts from its end.
+ */
+
+use std::error::Error;
+use std::fmt::{self, Write};
+
+use crate::instruction::{AluOp, BranchOp, Instruction, Width};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    Prefix,
+    Rex,
+    Opcode,
+    ModRm,
+    Sib,
+    Displacement,
+    Immediate,
+}
+
+impl Part {
+    pub fn name(self) -> &'static str {
+        match self {
+            Part::Prefix => "prefix",
+            Part::Rex => "REX",
+            Part::Opcode => "opcode",
+            Part::ModRm => "ModRM",
+            Part::Sib => "SIB",
+            Part::Displacement => "disp",
+            Part::Immediate => "imm",
+        }
+    }
+}
+
+/// Some bytes of an instruction and what they say.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field {
+    pub part: Part,
+    pub bytes: Vec<u8>,
+    pub meaning: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decoded {
+    pub fields: Vec<Field>,
+    /// Intel syntax.
+    pub text: String,
+}
+
+impl Decoded {
+    pub fn length(&self) -> usize {
+        self.fields.iter().map(|f| f.bytes.len()).sum()
+    }
+
+    pub fn bytes(&self) -> Vec<u8> {
+        self.fields.iter().flat_map(|f| f.bytes.iter().copied()).collect()
+    }
+
+    /// Bytes a decoder has to look at before it knows the length: up to
+    /// the last prefix, REX, opcode or ModRM byte. The displacement and
+    /// immediate only follow, and ModRM already says a SIB byte comes; the
+    /// SIB only matters with mod 00 and base 101, which means a disp32.
+    pub fn length_known_after(&self) -> usize {
+        let mut n = 0;
+        let mut modrm = 0;
+        for f in self.fields.iter().take_while(|f| !matches!(f.part, Part::Displacement | Part::Immediate)) {
+            match f.part {
+                Part::ModRm => modrm = f.bytes[0],
+                Part::Sib if !(modrm >> 6 == 0b00 && f.bytes[0] & 7 == 0b101) => continue,
+                _ => {}
+            }
+            n += f.bytes.len();
+        }
+        n
+    }
+
+    /// "prefix opcode ModRM imm16"
+    pub fn parts(&self) -> String {
+        let names: Vec<String> = self.fields.iter()
+            .map(|f| match f.part {
+                Part::Displacement | Part::Immediate => format!("{}{}", f.part.name(), 8 * f.bytes.len()),
+                part => part.name().to_string(),
+            })
+            .collect();
+        names.join(" ")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    /// Where in the bytes given.
+    pub at: usize,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "x86 byte {}: {}", self.at, self.reason)
+    }
+}
+
+impl Error for DecodeError {}
+
+// ==================== REGISTERS AND OPERANDS ====================
+
+const R64: [&str; 16] = ["rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15"];
+const R32: [&str; 16] = ["eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "r8d", "r9d", "r10d", "r11d", "r12d", "r13d", "r14d", "r15d"];
+const R16: [&str; 16] = ["ax", "cx", "dx", "bx", "sp", "bp", "si", "di", "r8w", "r9w", "r10w", "r11w", "r12w", "r13w", "r14w", "r15w"];
+const R8: [&str; 16] = ["al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil", "r8b", "r9b", "r10b", "r11b", "r12b", "r13b", "r14b", "r15b"];
+/// 4 to 7 as byte registers without a REX prefix.
+const R8_LEGACY: [&str; 4] = ["ah", "ch", "dh", "bh"];
+
+const ALU: [&str; 8] = ["add", "or", "adc", "sbb", "and", "sub", "xor", "cmp"];
+const CONDITIONS: [&str; 16] = ["o", "no", "b", "ae", "e", "ne", "be", "a", "s", "ns", "p", "np", "l", "ge", "le", "g"];
+
+fn hex(v: i64) -> String {
+    if v < 0 { format!("-0x{:x}", v.unsigned_abs()) } else { format!("0x{:x}", v) }
+}
+
+fn ptr(bits: u32) -> &'static str {
+    match bits {
+        8 => "byte ptr ",
+        16 => "word ptr ",
+        64 => "qword ptr ",
+        _ => "dword ptr ",
+    }
+}
+
+/// What the opcode says about its operands.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Form {
+    /// op r/m, reg
+    RmReg,
+    /// op reg, r/m
+    RegRm,
+    /// op al/eax, imm
+    AccImm,
+    /// op r/m, imm; the operation from ModRM.reg for the groups
+    RmImm,
+    /// mov reg, imm with the register in the opcode's low bits
+    RegImm,
+    /// push, pop: the register in the opcode, 64 bits
+    Reg,
+    /// op r/m, the operation from ModRM.reg
+    Rm,
+    /// a jump offset
+    Rel,
+    /// nothing but the opcode
+    Bare,
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    at: usize,
+    fields: Vec<Field>,
+    rex: u8,
+    operand16: bool,
+    address32: bool,
+}
+
+impl Decoder<'_> {
+    fn error<T>(&self, reason: &'static str) -> Result<T, DecodeError> {
+        Err(DecodeError { at: self.at, reason })
+    }
+
+    fn take(&mut self, n: usize) -> Result<Vec<u8>, DecodeError> {
+        match self.bytes.get(self.at..self.at + n) {
+            Some(b) => {
+                self.at += n;
+                Ok(b.to_vec())
+            }
+            None => self.error("the instruction goes on past the bytes given"),
+        }
+    }
+
+    fn push(&mut self, part: Part, bytes: Vec<u8>, meaning: String) {
+        self.fields.push(Field { part, bytes, meaning });
+    }
+
+    /// A little-endian number of `n` bytes, as a field; sign-extended
+    /// when it is narrower than the operand, as the processor does.
+    /// The value, and the value as it is shown.
+    fn number(&mut self, part: Part, n: usize, signed: bool, what: &str) -> Result<(i64, String), DecodeError> {
+        let b = self.take(n)?;
+        let mut v: i64 = 0;
+        for (i, &byte) in b.iter().enumerate() {
+            v |= i64::from(byte) << (8 * i);
+        }
+        let shift = 64 - 8 * n as u32;
+        let (v, shown) = if signed {
+            let v = (v << shift) >> shift;
+            (v, hex(v))
+        } else {
+            (v, format!("0x{:x}", v as u64))
+        };
+        self.push(part, b, format!("{}{} {}", part.name(), 8 * n, what.replace("{}", &shown)));
+        Ok((v, shown))
+    }
+
+    fn rex(&self, bit: u8) -> u8 {
+        u8::from(self.rex & bit != 0)
+    }
+
+    /// 64 with REX.W, 16 with the 66 prefix, 32 otherwise.
+    fn size(&self) -> u32 {
+        if self.rex & 8 != 0 { 64 } else if self.operand16 { 16 } else { 32 }
+    }
+
+    fn reg(&self, n: u8, bits: u32) -> &'static str {
+        match bits {
+            64 => R64[n as usize],
+            16 => R16[n as usize],
+            8 if self.rex == 0 && (4..8).contains(&n) => R8_LEGACY[n as usize - 4],
+            8 => R8[n as usize],
+            _ => R32[n as usize],
+        }
+    }
+
+    /// The ModRM byte, and the SIB and displacement after it: ModRM.reg
+    /// (with REX.R) and the r/m operand, a register of `bits` or memory.
+    fn modrm(&mut self, bits: u32, group: Option<&str>) -> Result<(u8, String), DecodeError> {
+        let m = self.take(1)?[0];
+        let (mode, reg, rm) = (m >> 6, (m >> 3) & 7, m & 7);
+        let reg_full = reg | self.rex(4) << 3;
+        let head = format!("mod {:02b} reg {:03b} rm {:03b}", mode, reg, rm);
+        let what_reg = match group {
+            Some(op) => format!("reg /{} picks {}", reg, op),
+            None => format!("reg {}", self.reg(reg_full, bits)),
+        };
+        if mode == 3 {
+            let name = self.reg(rm | self.rex(1) << 3, bits);
+            self.push(Part::ModRm, vec![m], format!("{}: r/m = {}, {}", head, name, what_reg));
+            return Ok((reg_full, name.to_string()));
+        }
+        let names = if self.address32 { R32 } else { R64 };
+        let addr = |n: u8| names[n as usize];
+        let mut base = None;
+        let mut index = None;
+        let mut disp = match mode {
+            1 => 1,
+            2 => 4,
+            _ => 0,
+        };
+        let mut rip = false;
+        let modrm_at = self.fields.len();
+        self.push(Part::ModRm, vec![m], String::new());
+        let memory = if rm == 4 {
+            let s = self.take(1)?[0];
+            let (scale, idx, b) = (1u8 << (s >> 6), (s >> 3) & 7 | self.rex(2) << 3, s & 7 | self.rex(1) << 3);
+            if idx != 4 {
+                index = Some((addr(idx), scale));
+            }
+            if b & 7 == 5 && mode == 0 {
+                disp = 4;
+            } else {
+                base = Some(addr(b));
+            }
+            let shown = |r: Option<&str>| r.unwrap_or("none").to_string();
+            self.push(Part::Sib, vec![s], format!("scale {} index {} base {}", scale, shown(index.map(|i| i.0)), shown(base)));
+            "base + index * scale"
+        } else if rm == 5 && mode == 0 {
+            rip = true;
+            disp = 4;
+            "rip + disp32"
+        } else {
+            base = Some(addr(rm | self.rex(1) << 3));
+            "a base register"
+        };
+        self.fields[modrm_at].meaning = format!("{}: memory, {}{}, {}", head, memory,
+            match (mode, rm) { (1, _) => " + disp8", (2, _) => " + disp32", _ => "" }, what_reg);
+        let offset = if disp > 0 { self.number(Part::Displacement, disp, true, "{}")?.0 } else { 0 };
+        let mut text = String::from("[");
+        if rip {
+            text.push_str("rip");
+        }
+        if let Some(b) = base {
+            text.push_str(b);
+        }
+        if let Some((i, scale)) = index {
+            let _ = write!(text, "{}{}*{}", if text.len() > 1 { "+" } else { "" }, i, scale);
+        }
+        if offset != 0 || text.len() == 1 {
+            let _ = write!(text, "{}{}", if offset >= 0 && text.len() > 1 { "+" } else { "" }, hex(offset));
+        }
+        text.push(']');
+        Ok((reg_full, format!("{}{}", ptr(bits), text)))
+    }
+}
+
+/// The first instruction in `bytes`.
+pub fn decode(bytes: &[u8]) -> Result<Decoded, DecodeError> {
+    let mut d = Decoder { bytes, at: 0, fields: Vec::new(), rex: 0, operand16: false, address32: false };
+    let mut rep = "";
+    let mut lock = "";
+    loop {
+        let Some(&b) = bytes.get(d.at) else { return d.error("only prefixes") };
+        let meaning = match b {
+            0x66 => "operand size 16 bits",
+            0x67 => "address size 32 bits",
+            0xF0 => "lock: the read-modify-write is atomic",
+            0xF2 => "repne",
+            0xF3 => "rep: repeat rcx times",
+            0x26 | 0x2E | 0x36 | 0x3E | 0x64 | 0x65 => "segment override",
+            _ => break,
+        };
+        match b {
+            0x66 => d.operand16 = true,
+            0x67 => d.address32 = true,
+            0xF0 => lock = "lock ",
+            0xF2 => rep = "repne ",
+            0xF3 => rep = "rep ",
+            _ => {}
+        }
+        let taken = d.take(1)?;
+        d.push(Part::Prefix, taken, meaning.to_string());
+    }
+    if let Some(&b @ 0x40..=0x4F) = bytes.get(d.at) {
+        d.rex = b;
+        let taken = d.take(1)?;
+        let meaning = format!("W {} R {} X {} B {}{}", b >> 3 & 1, b >> 2 & 1, b >> 1 & 1, b & 1,
+            if b & 8 != 0 { ": 64-bit operand" } else { ": registers 8-15" });
+        d.push(Part::Rex, taken, meaning);
+    }
+
+    let first = d.take(1)?[0];
+    let (opcode, two) = if first == 0x0F { (d.take(1)?[0], true) } else { (first, false) };
+    let size = d.size();
+    // (mnemonic, form, operand bits, immediate bytes)
+    let (mut op, form, bits, imm): (String, Form, u32, usize) = match (two, opcode) {
+        (false, 0x00..=0x3F) if opcode & 7 < 6 => {
+            let name = ALU[opcode as usize >> 3].to_string();
+            let byte = opcode & 1 == 0;
+            let bits = if byte { 8 } else { size };
+            match opcode & 7 {
+                0 | 1 => (name, Form::RmReg, bits, 0),
+                2 | 3 => (name, Form::RegRm, bits, 0),
+                _ => (name, Form::AccImm, bits, if byte { 1 } else if size == 16 { 2 } else { 4 }),
+            }
+        }
+        (false, 0x50..=0x57) => ("push".to_string(), Form::Reg, 64, 0),
+        (false, 0x58..=0x5F) => ("pop".to_string(), Form::Reg, 64, 0),
+        (false, 0x70..=0x7F) => (format!("j{}", CONDITIONS[opcode as usize & 15]), Form::Rel, 0, 1),
+        (true, 0x80..=0x8F) => (format!("j{}", CONDITIONS[opcode as usize & 15]), Form::Rel, 0, 4),
+        (false, 0x80) => (String::new(), Form::RmImm, 8, 1),
+        (false, 0x81) => (String::new(), Form::RmImm, size, if size == 16 { 2 } else { 4 }),
+        (false, 0x83) => (String::new(), Form::RmImm, size, 1),
+        (false, 0x84) => ("test".to_string(), Form::RmReg, 8, 0),
+        (false, 0x85) => ("test".to_string(), Form::RmReg, size, 0),
+        (false, 0x88) => ("mov".to_string(), Form::RmReg, 8, 0),
+        (false, 0x89) => ("mov".to_string(), Form::RmReg, size, 0),
+        (false, 0x8A) => ("mov".to_string(), Form::RegRm, 8, 0),
+        (false, 0x8B) => ("mov".to_string(), Form::RegRm, size, 0),
+        (false, 0x8D) => ("lea".to_string(), Form::RegRm, size, 0),
+        (false, 0x90) => ("nop".to_string(), Form::Bare, 0, 0),
+        (false, 0xA4) => ("movsb".to_string(), Form::Bare, 0, 0),
+        (false, 0xA5) => (format!("movs{}", if size == 64 { "q" } else if size == 16 { "w" } else { "d" }), Form::Bare, 0, 0),
+        (false, 0xAA) => ("stosb".to_string(), Form::Bare, 0, 0),
+        (false, 0xAB) => (format!("stos{}", if size == 64 { "q" } else if size == 16 { "w" } else { "d" }), Form::Bare, 0, 0),
+        (false, 0xB0..=0xB7) => ("mov".to_string(), Form::RegImm, 8, 1),
+        (false, 0xB8..=0xBF) => ("mov".to_string(), Form::RegImm, size, (size / 8) as usize),
+        (false, 0xC3) => ("ret".to_string(), Form::Bare, 0, 0),
+        (false, 0xC6) => ("mov".to_string(), Form::RmImm, 8, 1),
+        (false, 0xC7) => ("mov".to_string(), Form::RmImm, size, if size == 16 { 2 } else { 4 }),
+        (false, 0xCC) => ("int3".to_string(), Form::Bare, 0, 0),
+        (false, 0xE8) => ("call".to_string(), Form::Rel, 0, 4),
+        (false, 0xE9) => ("jmp".to_string(), Form::Rel, 0, 4),
+        (false, 0xEB) => ("jmp".to_string(), Form::Rel, 0, 1),
+        (false, 0xFE) => (String::new(), Form::Rm, 8, 0),
+        (false, 0xFF) => (String::new(), Form::Rm, size, 0),
+        (true, 0x05) => ("syscall".to_string(), Form::Bare, 0, 0),
+        (true, 0xAF) => ("imul".to_string(), Form::RegRm, size, 0),
+        (true, 0xB6) => ("movzx".to_string(), Form::RegRm, 8, 0),
+        (true, 0xB7) => ("movzx".to_string(), Form::RegRm, 16, 0),
+        _ => return Err(DecodeError { at: d.at - 1, reason: "an opcode this decoder does not cover" }),
+    };
+    let opcode_bytes = if two { vec![0x0F, opcode] } else { vec![opcode] };
+
+    // the groups take their operation from ModRM.reg, so peek at it
+    let group_reg = bytes.get(d.at).map(|m| (m >> 3) & 7);
+    let group = match (two, opcode, group_reg) {
+        (true, ..) => None,
+        (_, 0x80 | 0x81 | 0x83, r) => r.map(|r| ALU[r as usize]),
+        (_, 0xC6 | 0xC7, Some(0) | None) => Some("mov"),
+        (_, 0xC6 | 0xC7, _) => return d.error("C6 and C7 are mov only with reg 0"),
+        (_, 0xFE | 0xFF, Some(0)) => Some("inc"),
+        (_, 0xFE | 0xFF, Some(1)) => Some("dec"),
+        (_, 0xFF, Some(2)) => Some("call"),
+        (_, 0xFF, Some(4)) => Some("jmp"),
+        (_, 0xFF, Some(6)) => Some("push"),
+        (_, 0xFE | 0xFF, Some(_)) => return d.error("a FE/FF operation this decoder does not cover"),
+        _ => None,
+    };
+    if let Some(g) = group {
+        op = g.to_string();
+    }
+    let bits = match op.as_str() {
+        // near calls, jumps and pushes are 64-bit without REX.W
+        "call" | "jmp" | "push" if form == Form::Rm => 64,
+        _ => bits,
+    };
+    let shape = match form {
+        Form::RmReg => "r/m, reg: a ModRM follows",
+        Form::RegRm => "reg, r/m: a ModRM follows",
+        Form::AccImm => "the accumulator, imm: no ModRM",
+        Form::RmImm => "r/m, imm: a ModRM and an immediate follow",
+        Form::RegImm => "the register in the low 3 bits, imm",
+        Form::Reg => "the register in the low 3 bits",
+        Form::Rm => "r/m, the operation in ModRM.reg",
+        Form::Rel => "a jump offset follows",
+        Form::Bare => "no operands",
+    };
+    let name = if group.is_some() { format!("group {:02X}", opcode) } else { op.clone() };
+    d.push(Part::Opcode, opcode_bytes, format!("{}: {}", name, shape));
+
+    let operands = match form {
+        Form::RmReg => {
+            let (reg, rm) = d.modrm(bits, None)?;
+            format!("{}, {}", rm, d.reg(reg, bits))
+        }
+        Form::RegRm => {
+            // movzx reads a narrow r/m into a full register; lea wants no size
+            let (reg, rm) = d.modrm(bits, None)?;
+            let dest = if op == "movzx" { d.size() } else { bits };
+            let rm = if op == "lea" { rm.trim_start_matches(ptr(bits)).to_string() } else { rm };
+            format!("{}, {}", d.reg(reg, dest), rm)
+        }
+        Form::AccImm => {
+            let (_, v) = d.number(Part::Immediate, imm, 8 * imm < bits as usize, "{}")?;
+            format!("{}, {}", d.reg(0, bits), v)
+        }
+        Form::RmImm => {
+            let (_, rm) = d.modrm(bits, group)?;
+            let what = if 8 * imm < bits as usize { "{}, sign-extended" } else { "{}" };
+            let (_, v) = d.number(Part::Immediate, imm, 8 * imm < bits as usize, what)?;
+            format!("{}, {}", rm, v)
+        }
+        Form::RegImm => {
+            let (_, v) = d.number(Part::Immediate, imm, false, "{}")?;
+            format!("{}, {}", d.reg(opcode & 7 | d.rex(1) << 3, bits), v)
+        }
+        Form::Reg => d.reg(opcode & 7 | d.rex(1) << 3, 64).to_string(),
+        Form::Rm => d.modrm(bits, group)?.1,
+        Form::Rel => {
+            let (v, _) = d.number(Part::Immediate, imm, true, "{} from the next instruction")?;
+            (v + d.at as i64).to_string()
+        }
+        Form::Bare => String::new(),
+    };
+    let text = format!("{}{}{}{}{}", lock, rep, op, if operands.is_empty() { "" } else { " " }, operands);
+    Ok(Decoded { fields: d.fields, text })
+}
+
+/// Every instruction in `bytes`, in order.
+pub fn decode_all(bytes: &[u8]) -> Result<Vec<Decoded>, DecodeError> {
+    let mut at = 0;
+    let mut out = Vec::new();
+    while at < bytes.len() {
+        let d = decode(&bytes[at..]).map_err(|e| DecodeError { at: at + e.at, reason: e.reason })?;
+        at += d.length();
+        out.push(d);
+    }
+    Ok(out)
+}
+
+// ==================== THE SAME OPERATION IN BOTH ====================
+
+/// One operation as x86-64 and as RV32IM code. eax, ebx, ecx stand for
+/// a0, a1, a2 (rdi, rsi, rcx for a0, a1, a2 in the string move), and
+/// t0 is the scratch register RV32 needs where x86 needs none.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    pub operation: &'static str,
+    pub x86: Vec<u8>,
+    pub rv32: Vec<Instruction>,
+}
+
+const ZERO: u8 = 0;
+const RA: u8 = 1;
+const SP: u8 = 2;
+const T0: u8 = 5;
+const A0: u8 = 10;
+const A1: u8 = 11;
+const A2: u8 = 12;
+const A6: u8 = 16;
+const A7: u8 = 17;
+
+fn op(op: AluOp, rd: u8, rs1: u8, rs2: u8) -> Instruction {
+    Instruction::Op { op, rd, rs1, rs2 }
+}
+
+fn addi(rd: u8, rs1: u8, imm: i32) -> Instruction {
+    Instruction::OpImm { op: AluOp::Add, rd, rs1, imm }
+}
+
+fn load(width: Width, rd: u8, rs1: u8, offset: i32) -> Instruction {
+    Instruction::Load { width, rd, rs1, offset }
+}
+
+fn store(width: Width, rs2: u8, rs1: u8, offset: i32) -> Instruction {
+    Instruction::Store { width, rs1, rs2, offset }
+}
+
+/// The operations of the ISA-design lecture, simplest first.
+pub fn comparisons() -> Vec<Comparison> {
+    let c = |operation, x86: &[u8], rv32: Vec<Instruction>| Comparison { operation, x86: x86.to_vec(), rv32 };
+    vec![
+        c("add two registers", &[0x01, 0xD8], vec![op(AluOp::Add, A0, A0, A1)]),
+        c("add a small constant", &[0x83, 0xC0, 0x01], vec![addi(A0, A0, 1)]),
+        c("add a 32-bit constant", &[0x05, 0x78, 0x56, 0x34, 0x12],
+            vec![Instruction::Lui { rd: T0, imm: 0x1234_5000 }, addi(T0, T0, 0x678), op(AluOp::Add, A0, A0, T0)]),
+        c("load a 32-bit constant", &[0xB8, 0x78, 0x56, 0x34, 0x12], vec![Instruction::Lui { rd: A0, imm: 0x1234_5000 }, addi(A0, A0, 0x678)]),
+        c("registers 8 and up", &[0x45, 0x01, 0xC8], vec![op(AluOp::Add, A6, A6, A7)]),
+        c("multiply", &[0x0F, 0xAF, 0xC3], vec![op(AluOp::Mul, A0, A0, A1)]),
+        c("load a word", &[0x8B, 0x43, 0x08], vec![load(Width::Word, A0, A1, 8)]),
+        c("load a byte, zero-extended", &[0x0F, 0xB6, 0x03], vec![load(Width::ByteU, A0, A1, 0)]),
+        c("load an array element", &[0x8B, 0x44, 0x8B, 0x10],
+            vec![Instruction::OpImm { op: AluOp::Sll, rd: T0, rs1: A2, imm: 2 }, op(AluOp::Add, T0, A1, T0), load(Width::Word, A0, T0, 16)]),
+        c("store at a large offset", &[0x89, 0x83, 0x00, 0x10, 0x00, 0x00],
+            vec![Instruction::Lui { rd: T0, imm: 0x1000 }, op(AluOp::Add, T0, A1, T0), store(Width::Word, A0, T0, 0)]),
+        c("store a 16-bit constant", &[0x66, 0xC7, 0x03, 0x34, 0x12],
+            vec![Instruction::Lui { rd: T0, imm: 0x1000 }, addi(T0, T0, 0x234), store(Width::Half, T0, A1, 0)]),
+        c("add a register to memory", &[0x01, 0x03],
+            vec![load(Width::Word, T0, A1, 0), op(AluOp::Add, T0, T0, A0), store(Width::Word, T0, A1, 0)]),
+        c("compare and branch", &[0x39, 0xD8, 0x75, 0xF0], vec![Instruction::Branch { op: BranchOp::Bne, rs1: A0, rs2: A1, offset: -12 }]),
+        c("call a function", &[0xE8, 0xFF, 0x00, 0x00, 0x00], vec![Instruction::Jal { rd: RA, offset: 260 }]),
+        c("return", &[0xC3], vec![Instruction::Jalr { rd: ZERO, rs1: RA, offset: 0 }]),
+        c("push a register", &[0x53], vec![addi(SP, SP, -4), store(Width::Word, A1, SP, 0)]),
+        c("copy rcx bytes", &[0xF3, 0xA4], vec![
+            load(Width::ByteU, T0, A1, 0), store(Width::Byte, T0, A0, 0), addi(A1, A1, 1), addi(A0, A0, 1), addi(A2, A2, -1),
+            Instruction::Branch { op: BranchOp::Bne, rs1: A2, rs2: ZERO, offset: -20 },
+        ]),
+        c("system call", &[0x0F, 0x05], vec![Instruction::Ecall]),
+        c("do nothing", &[0x90], vec![addi(ZERO, ZERO, 0)]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(bytes: &[u8]) -> (String, usize, usize) {
+        let d = decode(bytes).unwrap();
+        (d.text.clone(), d.length(), d.length_known_after())
+    }
+
+    #[test]
+    fn prefixes_rex_modrm_and_sib() {
+        assert_eq!(text(&[0x01, 0xD8]), ("add eax, ebx".to_string(), 2, 2));
+        assert_eq!(text(&[0x66, 0xC7, 0x03, 0x34, 0x12]), ("mov word ptr [rbx], 0x1234".to_string(), 5, 3));
+        assert_eq!(text(&[0x8B, 0x44, 0x8B, 0x10]), ("mov eax, dword ptr [rbx+rcx*4+0x10]".to_string(), 4, 2));
+        assert_eq!(text(&[0x48, 0x8B, 0x05, 0x00, 0x01, 0x00, 0x00]), ("mov rax, qword ptr [rip+0x100]".to_string(), 7, 3));
+        assert_eq!(text(&[0x48, 0x8D, 0x44, 0xC4, 0xF8]), ("lea rax, [rsp+rax*8-0x8]".to_string(), 5, 3));
+        assert_eq!(text(&[0x49, 0xBC, 0xF0, 0xDE, 0xBC, 0x9A, 0x78, 0x56, 0x34, 0x12]), ("mov r12, 0x123456789abcdef0".to_string(), 10, 2));
+        assert_eq!(text(&[0x48, 0x81, 0x45, 0xF8, 0x00, 0x01, 0x00, 0x00]), ("add qword ptr [rbp-0x8], 0x100".to_string(), 8, 3));
+        assert_eq!(text(&[0xF0, 0x01, 0x03]).0, "lock add dword ptr [rbx], eax");
+        assert_eq!(text(&[0xF3, 0xA4]).0, "rep movsb");
+        assert_eq!(text(&[0x45, 0x01, 0xC8]).0, "add r8d, r9d");
+        assert_eq!(text(&[0x0F, 0xB6, 0x03]).0, "movzx eax, byte ptr [rbx]");
+        assert_eq!(text(&[0x88, 0xE0]).0, "mov al, ah");
+        assert_eq!(text(&[0xFF, 0x14, 0x25, 0x00, 0x10, 0x00, 0x00]).0, "call qword ptr [0x1000]");
+        // jumps count from the start of the instruction, as RV32's do
+        assert_eq!(text(&[0x75, 0xF0]).0, "jne -14");
+        assert_eq!(text(&[0x41, 0xB9, 0xEF, 0xBE, 0xAD, 0xDE]).0, "mov r9d, 0xdeadbeef");
+        assert_eq!(text(&[0x48, 0x83, 0xC4, 0xF8]).0, "add rsp, -0x8");
+        assert_eq!(text(&[0xE8, 0xFF, 0x00, 0x00, 0x00]).0, "call 260");
+
+        let d = decode(&[0x66, 0xC7, 0x03, 0x34, 0x12]).unwrap();
+        assert_eq!(d.parts(), "prefix opcode ModRM imm16");
+        assert_eq!(d.fields[2].meaning, "mod 00 reg 000 rm 011: memory, a base register, reg /0 picks mov");
+        assert_eq!(d.bytes(), [0x66, 0xC7, 0x03, 0x34, 0x12]);
+    }
+
+    #[test]
+    fn the_sib_byte_decides_the_length_only_for_a_disp32() {
+        // mod 01: ModRM says SIB and disp8 follow, whatever the SIB holds
+        assert_eq!(text(&[0x8B, 0x44, 0x24, 0x08]), ("mov eax, dword ptr [rsp+0x8]".to_string(), 4, 2));
+        // mod 00 with SIB base 100: no displacement
+        assert_eq!(text(&[0x8B, 0x04, 0x24]).2, 2);
+        // mod 00 with SIB base 101: a disp32 that only the SIB announces
+        assert_eq!(text(&[0x8B, 0x04, 0x25, 0x00, 0x10, 0x00, 0x00]), ("mov eax, dword ptr [0x1000]".to_string(), 7, 3));
+        assert_eq!(text(&[0x8B, 0x04, 0x8D, 0x00, 0x10, 0x00, 0x00]).2, 3);
+    }
+
+    #[test]
+    fn errors_and_sequences() {
+        assert_eq!(decode(&[0x8B, 0x44]), Err(DecodeError { at: 2, reason: "the instruction goes on past the bytes given" }));
+        assert_eq!(decode(&[0x66, 0x66]).unwrap_err().reason, "only prefixes");
+        assert_eq!(decode(&[0xD9, 0x00]).unwrap_err().at, 0);
+        assert_eq!(decode_all(&[0x90, 0x0F, 0x0B]).unwrap_err().at, 2);
+        let all = decode_all(&[0x39, 0xD8, 0x75, 0xF0, 0xC3]).unwrap();
+        assert_eq!(all.iter().map(|d| d.text.as_str()).collect::<Vec<_>>(), ["cmp eax, ebx", "jne -14", "ret"]);
+    }
+
+    #[test]
+    fn every_comparison_decodes_on_both_sides() {
+        for c in comparisons() {
+            let x86 = decode_all(&c.x86).unwrap_or_else(|e| panic!("{}: {}", c.operation, e));
+            assert_eq!(x86.iter().map(Decoded::length).sum::<usize>(), c.x86.len());
+            for i in &c.rv32 {
+                assert_eq!(crate::decode(i.encode()).as_ref(), Ok(i), "{}", c.operation);
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! The gate-level adders of the logic crate, compared by size and depth.
+
+use std::fmt::Write;
+
+use logic::adders::{carry_lookahead, ripple_carry, Sum};
+use util::args::{parse_signed, Args};
+use util::lang::{self, Catalog};
+use util::report::{Format, Json, Report};
+
+pub const USAGE: &str = "\
+usage: tdt4160 adders [a b] [--width N] [--format text|json|markdown|latex]
+
+  a, b       operands to add (default: all ones + 1, the longest carry chain)
+  --width N  adder width in bits, 1-32 (default: a table of 4, 8, 16 and 32)
+
+Delay is in gate delays along the critical path.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 adders [a b] [--width N] [--format text|json|markdown|latex]
+
+  a, b       operander som skal adderes (standard: bare enere + 1, den lengste
+             menteskjeden)
+  --width N  adderens bredde i bit, 1-32 (standard: en tabell for 4, 8, 16 og 32)
+
+Forsinkelsen er i portforsinkelser langs den kritiske stien.
+";
+
+struct Row {
+    width: u32,
+    a: u32,
+    b: u32,
+    ripple: Sum,
+    lookahead: Sum,
+}
+
+struct Table(Vec<Row>);
+
+impl Report for Table {
+    fn text(&self) -> String {
+        let (width, gates, delay) = (tr("width"), tr("gates"), tr("delay"));
+        // the columns are as wide as their headings, which are longer in Norwegian
+        let (w, g, d) = (width.chars().count().max(5), gates.chars().count().max(6), delay.chars().count().max(6));
+        let mut s = format!("{:>w$}  {:>12}  {:>g$} {:>d$}  {:>12}  {:>g$} {:>d$}\n", width, "ripple", gates, delay, "lookahead", gates, delay);
+        for Row { width, ripple: r, lookahead: c, .. } in &self.0 {
+            let _ = writeln!(s, "{:>w$}  {:>12}  {:>g$} {:>d$}  {:>12}  {:>g$} {:>d$}",
+                width, sum(r.value, r.carry), r.gates, r.delay(), sum(c.value, c.carry), c.gates, c.delay());
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        let adder = |s: &Sum| Json::object().with("sum", s.value).with("carry", s.carry).with("gates", s.gates).with("delay", s.delay());
+        Json::array(self.0.iter().map(|r| {
+            Json::object().with("width", r.width).with("a", r.a).with("b", r.b)
+                .with("ripple_carry", adder(&r.ripple)).with("carry_lookahead", adder(&r.lookahead))
+        }))
+    }
+}
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    let mut args = Args::parse(args, &["--width", "--format"])?;
+    let format = Format::from_args(&mut args)?;
+    let width = args.value::<u32>("--width")?;
+    let operands = args.finish()?;
+    if width.is_some_and(|w| !(1..=32).contains(&w)) {
+        return Err("--width must be 1-32".to_string());
+    }
+    let (a, b) = match operands.as_slice() {
+        [] => (None, None),
+        [a, b] => (Some(parse_signed(a)? as u32), Some(parse_signed(b)? as u32)),
+        _ => return Err("expected two operands or none".to_string()),
+    };
+    let widths = match (width, a) {
+        (Some(w), _) => vec![w],
+        (None, Some(_)) => vec![32],
+        (None, None) => vec![4, 8, 16, 32],
+    };
+    let rows = widths.into_iter().map(|w| {
+        let m = arithmetic::bits::mask(w, 0);
+        let (a, b) = (a.unwrap_or(m) & m, b.unwrap_or(1) & m);
+        Row { width: w, a, b, ripple: ripple_carry(a, b, false, w), lookahead: carry_lookahead(a, b, false, w) }
+    });
+    format.print(&Table(rows.collect()));
+    Ok(0)
+}
+
+fn sum(value: u32, carry: bool) -> String {
+    format!("0x{:x}{}", value, if carry { " c" } else { "" })
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+const NO: Catalog = &[
+    ("width", "bredde"),
+    ("gates", "porter"),
+    ("delay", "forsinkelse"),
+];
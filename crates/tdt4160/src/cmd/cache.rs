@@ -0,0 +1,373 @@
+//! The cache model on an address trace: Dinero format, one reference a
+//! line, "label address" with 0 read, 1 write, 2 instruction fetch. One
+//! cache from the options, or the levels of a --config file.
+
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, Read};
+
+use ::cache::trace::{self, Reference};
+use ::cache::hierarchy::{Hierarchy, Level};
+use ::cache::{Access, Cache, Config, Outcome, Replacement, WritePolicy};
+use util::args::Args;
+use util::log::{self, Level as Verbosity};
+use util::report::{Format, Json, Report};
+use util::table::{Align, Table};
+
+use crate::config;
+
+pub const USAGE: &str = "\
+usage: tdt4160 cache [--trace FILE] [--size BYTES] [--line BYTES] [--ways N]
+                     [--replace lru|fifo|random] [--write back|through]
+                     [--hit-time T] [--miss-penalty T] [--config FILE] [-v|-vv|-q]
+                     [--format text|json|markdown|latex]
+
+  --trace FILE      Dinero trace, - for stdin (default: a built-in loop
+                    over two arrays that conflict in a direct-mapped cache)
+  --size BYTES      capacity (default 1K)
+  --line BYTES      line size (default 16)
+  --ways N          associativity, 1 = direct mapped (default 1)
+  --replace P       replacement policy (default lru)
+  --write P         write-back allocate or write-through no-allocate (default back)
+  --hit-time T      cycles for a hit, for AMAT (default 1)
+  --miss-penalty T  extra cycles for a miss, for AMAT (default 100)
+  --config FILE     the [[cache]] levels and the memory latency of a machine
+                    file instead of the options above (see `tdt4160 config`)
+  -v, -vv, -q       print every access; and each set's lines after it; only
+                    the statistics
+
+With more than one level, a miss in one is an access to the next, and AMAT
+is t1 + m1 × (t2 + m2 × (... + memory)) with local miss rates.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 cache [--trace FIL] [--size BYTE] [--line BYTE] [--ways N]
+                    [--replace lru|fifo|random] [--write back|through]
+                    [--hit-time T] [--miss-penalty T] [--config FIL] [-v|-vv|-q]
+                    [--format text|json|markdown|latex]
+
+  --trace FIL       Dinero-spor, - for stdin (standard: en innebygd løkke
+                    over to tabeller som kolliderer i en direkteavbildet cache)
+  --size BYTE       kapasitet (standard 1K)
+  --line BYTE       linjestørrelse (standard 16)
+  --ways N          assosiativitet, 1 = direkteavbildet (standard 1)
+  --replace P       erstatningsstrategi (standard lru)
+  --write P         write-back med allokering eller write-through uten (standard back)
+  --hit-time T      sykluser for et treff, til AMAT (standard 1)
+  --miss-penalty T  ekstra sykluser for en bom, til AMAT (standard 100)
+  --config FIL      [[cache]]-nivåene og minneforsinkelsen i en maskinfil
+                    i stedet for valgene over (se `tdt4160 config`)
+  -v, -vv, -q       skriv ut hver aksess; og linjene i settet etter den; bare
+                    statistikken
+
+Med mer enn ett nivå er en bom i ett nivå en aksess til det neste, og AMAT
+er t1 + m1 × (t2 + m2 × (... + minne)) med lokale bomrater.
+";
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    const GEOMETRY: [&str; 7] = ["--size", "--line", "--ways", "--replace", "--write", "--hit-time", "--miss-penalty"];
+    let mut args = Args::parse(args, &["--trace", "--size", "--line", "--ways", "--replace", "--write", "--hit-time", "--miss-penalty", "--config", "--format"])?;
+    let format = Format::from_args(&mut args)?;
+    let path = args.string("--trace");
+    let verbose = log::enabled(Verbosity::Verbose);
+    let mut hierarchy = match args.string("--config") {
+        Some(file) => {
+            if let Some(option) = GEOMETRY.iter().find(|o| args.has(o)) {
+                return Err(format!("{}: the caches come from --config {}", option, file));
+            }
+            let machine = config::load(&file)?;
+            if machine.caches.is_empty() {
+                return Err(format!("--config: {} has no [[cache]]", file));
+            }
+            Hierarchy::new(machine.caches, machine.memory.latency)
+        }
+        None => single(&mut args)?,
+    };
+    args.finish()?;
+
+    let references = match path.as_deref() {
+        None => demo_trace(),
+        Some(p) => {
+            let text = if p == "-" {
+                let mut s = String::new();
+                io::stdin().read_to_string(&mut s).map_err(|e| format!("stdin: {}", e))?;
+                s
+            } else {
+                fs::read_to_string(p).map_err(|e| format!("{}: {}", p, e))?
+            };
+            trace::parse_din(&text).map_err(|e| format!("{}: {}", p, e))?
+        }
+    };
+
+    let mut accesses = Vec::new();
+    for r in references {
+        match r {
+            Reference::Data(kind, addr) => {
+                let (out, from) = hierarchy.access(addr, kind);
+                accesses.push(Line::Access(addr, kind, out, from));
+                if log::enabled(Verbosity::Debug) {
+                    accesses.push(Line::Set(out.set, hierarchy.levels[0].cache.lines(out.set)));
+                }
+            }
+            Reference::Fetch(addr) => {
+                let (out, from) = hierarchy.access(addr, Access::Read);
+                accesses.push(Line::Access(addr, Access::Read, out, from));
+                if log::enabled(Verbosity::Debug) {
+                    accesses.push(Line::Set(out.set, hierarchy.levels[0].cache.lines(out.set)));
+                }
+            }
+            Reference::Flush => accesses.push(Line::Flush(hierarchy.flush())),
+        }
+    }
+    if !verbose {
+        accesses.clear();
+    }
+    format.print(&Simulation { hierarchy, accesses });
+    Ok(0)
+}
+
+/// The one cache of the options, with main memory behind it.
+fn single(args: &mut Args) -> Result<Hierarchy, String> {
+    let size = args.number("--size")?.unwrap_or(1 << 10);
+    let line = args.number("--line")?.unwrap_or(16);
+    let ways = args.number("--ways")?.unwrap_or(1);
+    let replacement = match args.string("--replace").as_deref() {
+        None | Some("lru") => Replacement::Lru,
+        Some("fifo") => Replacement::Fifo,
+        Some("random") => Replacement::Random,
+        Some(other) => return Err(format!("--replace: expected lru, fifo or random, got '{}'", other)),
+    };
+    let write = match args.string("--write").as_deref() {
+        None | Some("back") => WritePolicy::WriteBackAllocate,
+        Some("through") => WritePolicy::WriteThroughNoAllocate,
+        Some(other) => return Err(format!("--write: expected back or through, got '{}'", other)),
+    };
+    let hit_time = args.value::<f64>("--hit-time")?.unwrap_or(1.0);
+    let miss_penalty = args.value::<f64>("--miss-penalty")?.unwrap_or(100.0);
+    let small = |name: &str, v: u64| u32::try_from(v).map_err(|_| format!("{}: {} is too large", name, v));
+    let config = Config { size: small("--size", size)?, line: small("--line", line)?, ways: small("--ways", ways)?, replacement, write };
+    let cache = Cache::new(config)?;
+    Ok(Hierarchy::new(vec![Level { name: "L1".to_string(), cache, hit_time }], miss_penalty))
+}
+
+/// One line of the -v listing.
+enum Line {
+    /// What L1 did, and the level that had the data.
+    Access(u32, Access, Outcome, usize),
+    /// Dirty lines written back.
+    Flush(u64),
+    /// (tag, dirty) of the lines of an L1 set after the access, with -vv.
+    Set(u32, Vec<(u32, bool)>),
+}
+
+struct Simulation {
+    hierarchy: Hierarchy,
+    accesses: Vec<Line>,
+}
+
+fn config_json(c: &Config) -> Json {
+    let (tag, index, offset) = c.split();
+    Json::object()
+        .with("size", c.size).with("line", c.line).with("ways", c.ways).with("sets", c.sets())
+        .with("tag_bits", tag).with("index_bits", index).with("offset_bits", offset)
+        .with("replacement", c.replacement.name())
+        .with("write", c.write.name())
+}
+
+fn stats_json(cache: &Cache) -> Json {
+    let st = cache.stats();
+    Json::object()
+        .with("accesses", st.accesses()).with("reads", st.reads).with("writes", st.writes)
+        .with("hits", st.hits).with("misses", st.misses).with("hit_rate", st.hit_rate()).with("miss_rate", st.miss_rate())
+        .with("evictions", st.evictions).with("memory_writes", st.memory_writes)
+}
+
+impl Simulation {
+    fn l1(&self) -> &Level {
+        &self.hierarchy.levels[0]
+    }
+
+    /// Levels are named only when there is more than one.
+    fn levels(&self) -> bool {
+        self.hierarchy.levels.len() > 1
+    }
+
+    fn source(&self, from: usize) -> &str {
+        self.hierarchy.levels.get(from).map_or("memory", |l| l.name.as_str())
+    }
+
+    /// "1 + 0.250 × 100 = 26.00 cycles"
+    fn amat(&self) -> String {
+        format!("{} = {:.2} cycles", self.hierarchy.formula(), self.hierarchy.amat())
+    }
+
+    fn config_table(&self, level: &Level) -> Table {
+        let c = level.cache.config();
+        let (tag, index, offset) = c.split();
+        let caption = if self.levels() { format!("Cache configuration, {}", level.name) } else { "Cache configuration".to_string() };
+        let mut config = Table::new(&caption, ["Parameter", "Value"]).align(1.., Align::Right);
+        config.row(["Size".to_string(), format!("{} B", c.size)]);
+        config.row(["Line".to_string(), format!("{} B", c.line)]);
+        config.row(["Ways".to_string(), c.ways.to_string()]);
+        config.row(["Sets".to_string(), c.sets().to_string()]);
+        config.row(["Tag / index / offset bits".to_string(), format!("{} / {} / {}", tag, index, offset)]);
+        config.row(["Replacement".to_string(), c.replacement.name().to_uppercase()]);
+        config.row(["Write policy", match c.write { WritePolicy::WriteBackAllocate => "write-back, allocate", WritePolicy::WriteThroughNoAllocate => "write-through, no allocate" }]
+            .map(String::from));
+        if self.levels() {
+            config.row(["Hit time (cycles)".to_string(), level.hit_time.to_string()]);
+        }
+        config
+    }
+
+    fn stats_table(&self, level: &Level) -> Table {
+        let st = level.cache.stats();
+        let caption = if self.levels() { format!("Cache statistics, {}", level.name) } else { "Cache statistics".to_string() };
+        let mut stats = Table::new(&caption, ["Measure", "Value"]).align(1.., Align::Right);
+        stats.row(["Accesses".to_string(), st.accesses().to_string()]);
+        stats.row(["Reads / writes".to_string(), format!("{} / {}", st.reads, st.writes)]);
+        stats.row(["Hits".to_string(), st.hits.to_string()]);
+        stats.row(["Misses".to_string(), st.misses.to_string()]);
+        stats.row(["Hit rate".to_string(), format!("{:.3}", st.hit_rate())]);
+        stats.row(["Miss rate".to_string(), format!("{:.3}", st.miss_rate())]);
+        stats.row(["Evictions".to_string(), st.evictions.to_string()]);
+        stats.row([if self.levels() { "Writes to the next level" } else { "Memory writes" }.to_string(), st.memory_writes.to_string()]);
+        stats
+    }
+}
+
+impl Report for Simulation {
+    fn text(&self) -> String {
+        let h = &self.hierarchy;
+        let mut s = String::new();
+        let quiet = !log::enabled(Verbosity::Normal);
+        for level in h.levels.iter().filter(|_| !quiet) {
+            let name = if self.levels() { format!("{}: ", level.name) } else { String::new() };
+            let _ = writeln!(s, "{}{}", name, level.cache.config());
+        }
+        for line in &self.accesses {
+            match *line {
+                Line::Access(addr, kind, out, from) => {
+                    let (tag, _) = self.l1().cache.locate(addr);
+                    let _ = write!(s, "  {:<5} 0x{:08x}  tag 0x{:x} set {:<4} {}", format!("{:?}", kind).to_lowercase(), addr, tag, out.set,
+                        if out.hit { "hit" } else { "miss" });
+                    if let Some(victim) = out.evicted {
+                        let _ = write!(s, "  evicts 0x{:08x}", victim);
+                    }
+                    let _ = write!(s, "{}", if out.memory_write { "  (memory write)" } else { "" });
+                    if self.levels() && !out.hit {
+                        let _ = write!(s, "  from {}", self.source(from));
+                    }
+                    s.push('\n');
+                }
+                Line::Flush(n) => {
+                    let _ = writeln!(s, "  flush: {} dirty lines written back", n);
+                }
+                Line::Set(set, ref lines) => {
+                    let tags: Vec<String> = lines.iter().map(|&(tag, dirty)| format!("0x{:x}{}", tag, if dirty { "*" } else { "" })).collect();
+                    let _ = writeln!(s, "        set {} now [{}]", set, tags.join(" "));
+                }
+            }
+        }
+        for level in &h.levels {
+            let name = if self.levels() { format!("{}: ", level.name) } else { String::new() };
+            let _ = writeln!(s, "{}{}", name, level.cache.stats());
+        }
+        if self.levels() && !quiet {
+            let _ = writeln!(s, "memory: {} reads, {} writes, {} cycles each", h.memory_reads, h.memory_writes, h.memory_time);
+        }
+        let _ = writeln!(s, "AMAT = {}", self.amat());
+        s
+    }
+
+    fn json(&self) -> Json {
+        let h = &self.hierarchy;
+        let l1 = self.l1();
+        let mut j = Json::object()
+            .with("config", config_json(l1.cache.config()))
+            .with("stats", stats_json(&l1.cache))
+            .with("amat", Json::object()
+                .with("hit_time", l1.hit_time).with("miss_penalty", if h.levels.len() > 1 { h.penalty(1) } else { h.memory_time })
+                .with("cycles", h.amat()));
+        if self.levels() {
+            j = j.with("name", l1.name.as_str())
+                .with("lower_levels", Json::array(h.levels[1..].iter().map(|l| Json::object()
+                    .with("name", l.name.as_str())
+                    .with("hit_time", l.hit_time)
+                    .with("config", config_json(l.cache.config()))
+                    .with("stats", stats_json(&l.cache)))))
+                .with("memory", Json::object().with("time", h.memory_time).with("reads", h.memory_reads).with("writes", h.memory_writes));
+        }
+        if !self.accesses.is_empty() {
+            j = j.with("accesses", Json::array(self.accesses.iter().filter_map(|line| Some(match *line {
+                Line::Access(addr, kind, out, from) => {
+                    let a = Json::object()
+                        .with("kind", format!("{:?}", kind).to_lowercase())
+                        .with("addr", addr)
+                        .with("tag", l1.cache.locate(addr).0)
+                        .with("set", out.set)
+                        .with("hit", out.hit)
+                        .with("evicted", out.evicted)
+                        .with("memory_write", out.memory_write);
+                    if self.levels() { a.with("from", self.source(from)) } else { a }
+                }
+                Line::Flush(n) => Json::object().with("kind", "flush").with("written_back", n),
+                Line::Set(..) => return None,
+            }))));
+        }
+        j
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        let h = &self.hierarchy;
+        let mut tables: Vec<Table> = h.levels.iter().map(|l| self.config_table(l)).collect();
+        for (i, level) in h.levels.iter().enumerate() {
+            let mut stats = self.stats_table(level);
+            if i == 0 {
+                stats.row(["AMAT".to_string(), self.amat()]);
+            }
+            tables.push(stats);
+        }
+        if self.levels() {
+            let mut memory = Table::new("Main memory", ["Measure", "Value"]).align(1.., Align::Right);
+            memory.row(["Reads / writes".to_string(), format!("{} / {}", h.memory_reads, h.memory_writes)]);
+            memory.row(["Access time (cycles)".to_string(), h.memory_time.to_string()]);
+            tables.push(memory);
+        }
+        if !self.accesses.is_empty() {
+            let mut header = vec!["Access", "Address", "Tag", "Set", "Result", "Evicts"];
+            if self.levels() {
+                header.push("From");
+            }
+            let mut accesses = Table::new("Accesses", header).align(3..4, Align::Right);
+            for line in &self.accesses {
+                match *line {
+                    Line::Access(addr, kind, out, from) => accesses.row([
+                        format!("{:?}", kind).to_lowercase(),
+                        format!("0x{:08x}", addr),
+                        format!("0x{:x}", self.l1().cache.locate(addr).0),
+                        out.set.to_string(),
+                        if out.hit { "hit" } else { "miss" }.to_string(),
+                        out.evicted.map_or(String::new(), |v| format!("0x{:08x}", v)),
+                        self.source(from).to_string(),
+                    ]),
+                    Line::Flush(n) => accesses.row(["flush".to_string(), String::new(), String::new(), String::new(), format!("{} written back", n)]),
+                    Line::Set(..) => {}
+                }
+            }
+            tables.push(accesses);
+        }
+        tables
+    }
+}
+
+/// for i in 0..64 { c[i] = a[i] + b[i] } with a and b 1 KiB apart, so they
+/// map to the same sets of the default cache and c does not.
+fn demo_trace() -> Vec<Reference> {
+    let (a, b, c) = (0x1000, 0x1400, 0x2100);
+    (0..64u32)
+        .flat_map(|i| {
+            [Reference::Data(Access::Read, a + 4 * i), Reference::Data(Access::Read, b + 4 * i), Reference::Data(Access::Write, c + 4 * i)]
+        })
+        .collect()
+}
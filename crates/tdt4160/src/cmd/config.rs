@@ -0,0 +1,126 @@
+//! Check a machine file for --config and print what it sets up: the
+//! memory map, the devices, the cache levels, the pipeline and the cost
+//! model (crate::config).
+
+use ::cache::hierarchy::Level;
+use ::riscv::instruction::Class;
+use util::args::Args;
+use util::report::{Format, Json, Report};
+use util::table::Table;
+
+use crate::config::{self, Machine};
+
+pub const USAGE: &str = "\
+usage: tdt4160 config <file> [--format text|json|markdown|latex]
+
+  file   a TOML machine description, for riscv --config and cache --config
+
+  name = \"Lab 3\"
+  [memory]     base = 0x1000, stack = \"64K\", latency = 100 (cycles)
+  [[device]]   name, kind = uart|timer|ram, base, irq (0-31), size (ram),
+               input (what a uart receives)
+  [[cache]]    name, size, line, ways, replace = lru|fifo|random,
+               write = back|through, hit_time; L1 first
+  [pipeline]   forwarding = true|false, max (instructions to draw)
+  [cost]       clock_mhz, and cycles for alu, mul, div, load, store,
+               branch, jump and system instructions (default 1)
+
+Sizes may be written as in options (\"32K\", \"0x1000\"). A misspelt key, a
+value of the wrong type, a cache geometry that does not work out or
+overlapping devices are errors with the line they are on.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 config <fil> [--format text|json|markdown|latex]
+
+  fil    en TOML-beskrivelse av en maskin, til riscv --config og cache --config
+
+  name = \"Lab 3\"
+  [memory]     base = 0x1000, stack = \"64K\", latency = 100 (sykluser)
+  [[device]]   name, kind = uart|timer|ram, base, irq (0-31), size (ram),
+               input (det en uart mottar)
+  [[cache]]    name, size, line, ways, replace = lru|fifo|random,
+               write = back|through, hit_time; L1 først
+  [pipeline]   forwarding = true|false, max (instruksjoner som tegnes)
+  [cost]       clock_mhz, og sykluser for alu-, mul-, div-, load-, store-,
+               branch-, jump- og system-instruksjoner (standard 1)
+
+Størrelser kan skrives som i valgene (\"32K\", \"0x1000\"). En feilstavet
+nøkkel, en verdi av feil type, en cache-geometri som ikke går opp eller
+overlappende enheter er feil med linjen de står på.
+";
+
+struct Summary(Machine);
+
+fn level(l: &Level) -> String {
+    format!("{}, hit time {}", l.cache.config(), l.hit_time)
+}
+
+impl Summary {
+    fn rows(&self) -> Vec<[String; 2]> {
+        let m = &self.0;
+        let mut rows = vec![["memory".to_string(), format!("raw binaries at 0x{:08x}, {} B stack, {} cycles an access",
+            m.memory.base, m.memory.stack, m.memory.latency)]];
+        for d in &m.devices {
+            let irq = d.irq.map_or(String::new(), |n| format!(", irq {}", n));
+            rows.push([format!("device {}", d.name), format!("{} at 0x{:08x}-0x{:08x}{}", d.kind, d.base, u64::from(d.base) + u64::from(d.size) - 1, irq)]);
+        }
+        for l in &m.caches {
+            rows.push([format!("cache {}", l.name), level(l)]);
+        }
+        rows.push(["pipeline".to_string(), format!("five stages {} forwarding, {} instructions drawn",
+            if m.pipeline.forwarding { "with" } else { "without" }, m.pipeline.max)]);
+        if let Some(cost) = &m.cost {
+            let cycles: Vec<String> = Class::ALL.iter().map(|&c| format!("{} {}", c.name(), cost.cycles(c))).collect();
+            let clock = cost.clock_mhz.map_or(String::new(), |mhz| format!(" at {} MHz", mhz));
+            rows.push(["cost".to_string(), format!("cycles: {}{}", cycles.join(", "), clock)]);
+        }
+        rows
+    }
+}
+
+impl Report for Summary {
+    fn text(&self) -> String {
+        let mut s = format!("{}\n\n", self.0.name);
+        for [part, setting] in self.rows() {
+            s += &format!("  {:<14} {}\n", part, setting);
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        let m = &self.0;
+        Json::object()
+            .with("name", m.name.as_str())
+            .with("memory", Json::object().with("base", m.memory.base).with("stack", m.memory.stack).with("latency", m.memory.latency))
+            .with("devices", Json::array(m.devices.iter().map(|d| Json::object()
+                .with("name", d.name.as_str()).with("kind", d.kind.as_str()).with("base", d.base).with("size", d.size).with("irq", d.irq))))
+            .with("caches", Json::array(m.caches.iter().map(|l| {
+                let c = l.cache.config();
+                Json::object()
+                    .with("name", l.name.as_str()).with("size", c.size).with("line", c.line).with("ways", c.ways).with("sets", c.sets())
+                    .with("replacement", c.replacement.name())
+                    .with("write", c.write.name())
+                    .with("hit_time", l.hit_time)
+            })))
+            .with("pipeline", Json::object().with("forwarding", m.pipeline.forwarding).with("max", m.pipeline.max))
+            .with("cost", m.cost.map(|cost| Class::ALL.iter().fold(Json::object().with("clock_mhz", cost.clock_mhz), |j, &c| j.with(c.name(), cost.cycles(c)))))
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        let mut t = Table::new(&self.0.name, ["Part", "Setting"]);
+        for row in self.rows() {
+            t.row(row);
+        }
+        vec![t]
+    }
+}
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    let mut args = Args::parse(args, &["--format"])?;
+    let format = Format::from_args(&mut args)?;
+    let rest = args.finish()?;
+    let [path] = rest.as_slice() else { return Err("expected one machine file".to_string()) };
+    format.print(&Summary(config::load(path)?));
+    Ok(0)
+}
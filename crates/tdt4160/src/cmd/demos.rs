@@ -0,0 +1,230 @@
+//! The demo programs of `examples/`, which stay separate binaries: a
+//! demo is launched as the executable next to this one, so they have to be
+//! built together (`cargo build --release --workspace`).
+
+use std::env;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+use util::lang::{self, Catalog};
+use util::report::{Format, Json, Report};
+
+pub const USAGE: &str = "\
+usage: tdt4160 demos [topic]       list the demos, optionally only one topic (e.g. T6);
+                                   --format json for a script
+       tdt4160 <demo> [arguments]  run a demo; the arguments are passed on
+
+The demos are the binaries of the `demos` package. Build them next to
+tdt4160 with `cargo build --release --workspace`.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 demos [emne]         list demoene, eventuelt bare ett emne (f.eks. T6);
+                                   --format json for et skript
+      tdt4160 <demo> [argumenter]  kjør en demo; argumentene sendes videre
+
+Demoene er binærfilene i pakken `demos`. Bygg dem ved siden av
+tdt4160 med `cargo build --release --workspace`.
+";
+
+pub struct Demo {
+    pub name: &'static str,
+    /// Course learning goal, as in LEARNING_GOALS.md.
+    pub topic: &'static str,
+    /// The demo's arguments, as in its Run: line; empty if it takes none.
+    pub params: &'static str,
+    pub summary: &'static str,
+}
+
+impl Demo {
+    /// "T6" for topic "T6.3".
+    pub fn group(&self) -> &'static str {
+        self.topic.split('.').next().unwrap_or(self.topic)
+    }
+}
+
+const fn demo(name: &'static str, topic: &'static str, params: &'static str, summary: &'static str) -> Demo {
+    Demo { name, topic, params, summary }
+}
+
+/// Course topics by number, as in LEARNING_GOALS.md.
+pub const TOPICS: [&str; 7] = [
+    "Introduction and performance",
+    "Instruction set",
+    "Single-cycle processor",
+    "Multi-cycle processor",
+    "Pipelined processors",
+    "The memory system",
+    "Parallel computers",
+];
+
+/// "T6.3" -> "T6 The memory system", in the language chosen with --lang.
+pub fn topic_title(topic: &str) -> String {
+    title(topic, tr)
+}
+
+fn title(topic: &str, tr: fn(&'static str) -> &'static str) -> String {
+    let group = topic.split('.').next().unwrap_or(topic);
+    let n: usize = group.trim_start_matches('T').parse().unwrap_or(0);
+    match n.checked_sub(1).and_then(|i| TOPICS.get(i)) {
+        Some(title) => format!("{} {}", group, tr(title)),
+        None => group.to_string(),
+    }
+}
+
+/// A topic title or demo summary in the language chosen with --lang.
+pub fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+pub const DEMOS: &[Demo] = &[
+    demo("asm_view", "T1.2", "[function] [--target T] [--full]", "kernels compiled at -O0 and -O3 side by side"),
+    demo("cycles", "T1.3", "[--format json]", "cycle counter calibration, fenced vs naive timestamp reads"),
+    demo("showasm", "T2.1", "<file.rs> [function] [-O 0,3] [--target x86-64,riscv32]", "one demo function per target and opt level, in columns"),
+    demo("bits", "T2.1", "[number]", "popcount, x & (x - 1), powers of two, masks, Gosper's hack"),
+    demo("reinterpret", "T2.2", "[float]", "five ways to view a float's bits, which are sound"),
+    demo("overflow_rust", "T2.2", "", "checked, wrapping, saturating and overflowing arithmetic"),
+    demo("asm_flags_demo", "T2.2", "[random cases per width]", "the CPU's own add/sub flags next to arithmetic::alu"),
+    demo("length_overflow", "T2.2", "", "an overflowing length, an undersized buffer: unsafe vs safe Rust"),
+    demo("compare_langs", "T2.2", "[topic ...] [--dir DIR] [--format json|markdown]", "overflow, division and shifts in C, Rust and Python, in one table"),
+    demo("endianness", "T2.2", "", "little- vs big-endian bytes, a misread file header"),
+    demo("headers", "T2.2", "[file.bmp | file.wav]", "BMP and WAV headers parsed field by field"),
+    demo("hexdump", "T2.2", "file [--width N] [--group N] [--offset N] [--length N]", "hex + ASCII dump of a file"),
+    demo("layout", "T2.2", "", "struct sizes, alignment, offsets and padding"),
+    demo("branch_predict", "T5.3", "[elements] [--format json]", "sorted vs shuffled data and predictor models"),
+    demo("spectre_sim", "T5.3", "[secret text] [--format json]", "Spectre in the emulator: a mispredicted bounds check leaks through the cache"),
+    demo("ilp_unroll", "T5.3", "[elements] [--format json]", "one accumulator chain vs independent accumulators"),
+    demo("memlat", "T6.1", "[max MiB] [--format json]", "load latency and bandwidth per working-set size (CSV)"),
+    demo("alignment", "T6.1", "[--format json]", "misaligned, line-splitting and page-splitting loads"),
+    demo("alloc_demo", "T6.3", "[--arena N] [name=size | -name]...", "bump and first-fit allocators with their block headers"),
+    demo("buddy", "T6.3", "[name=size | -name]... | --random N", "buddy allocator: splits, merges, internal fragmentation"),
+    demo("gc", "T6.3", "", "mark-sweep collector with a mark and sweep trace"),
+    demo("tracking_demo", "T6.3", "", "which everyday Rust code allocates, and how often"),
+    demo("address_space", "T6.3", "[depth]", "addresses of stack, heap, statics and code"),
+    demo("stack_overflow", "T6.3", "[chain length]", "frame sizes, the guard page, recursion vs explicit stack"),
+    demo("saturating_simd", "T7.1", "[brightness]", "saturating u8 adds: scalar, SWAR and SIMD intrinsics"),
+    demo("parallel_sum", "T7.1", "[millions of elements] [max threads] [--format json]", "overflow-safe parallel sum with a speedup table"),
+    demo("atomics_demo", "T7.2", "[threads] [increments per thread]", "fetch_add wrapping, checked increments, lost updates"),
+    demo("litmus", "T7.2", "[iterations] [--format json]", "SB, MP and IRIW litmus tests per memory ordering"),
+    demo("peterson", "T7.2", "[entries per thread]", "Peterson's algorithm with and without SeqCst"),
+    demo("locks", "T7.2", "[max threads]", "spin and ticket locks vs std::sync::Mutex"),
+    demo("race", "T7.2", "", "a data race and an exhaustive interleaving checker"),
+    demo("channels", "T7.2", "[producers] [items per producer]", "channels vs Arc<Mutex<_>> for one workload"),
+    demo("counters", "T7.2", "[--format json]", "wrapping and saturating statistics counters"),
+    demo("pingpong", "T7.2", "[increments per thread] [--format json]", "cache-line ping-pong and false sharing"),
+];
+
+pub fn find(name: &str) -> Option<&'static Demo> {
+    DEMOS.iter().find(|d| d.name == name)
+}
+
+struct Listing(Vec<&'static Demo>);
+
+impl Report for Listing {
+    fn text(&self) -> String {
+        let mut s = String::new();
+        let mut last = "";
+        for d in &self.0 {
+            if d.group() != last {
+                let _ = writeln!(s, "{}{}", if last.is_empty() { "" } else { "\n" }, topic_title(d.group()));
+                last = d.group();
+            }
+            let _ = writeln!(s, "  {:<16} {:<5} {}", d.name, d.topic, tr(d.summary));
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        Json::array(self.0.iter().map(|d| {
+            Json::object().with("name", d.name).with("topic", d.topic).with("topic_title", title(d.group(), |t| t))
+                .with("params", d.params).with("summary", d.summary)
+        }))
+    }
+}
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    let mut args = util::args::Args::parse(args, &["--format"])?;
+    let format = Format::from_args(&mut args)?;
+    let args = args.finish()?;
+    let filter = args.first().map(|s| s.to_uppercase());
+    let list: Vec<&Demo> = DEMOS.iter().filter(|d| filter.as_ref().is_none_or(|f| d.topic.starts_with(f.as_str()))).collect();
+    if list.is_empty() {
+        return Err(lang::fill(tr("no demo for topic '{}'"), &[&filter.unwrap_or_default()]));
+    }
+    format.print(&Listing(list));
+    Ok(0)
+}
+
+/// The demo's binary, in the same directory as this one.
+pub fn path(demo: &Demo) -> Result<PathBuf, String> {
+    let exe = env::current_exe().map_err(|e| format!("cannot find my own executable: {}", e))?;
+    let path = exe.with_file_name(format!("{}{}", demo.name, env::consts::EXE_SUFFIX));
+    if !path.exists() {
+        return Err(format!("{} is not built; run `cargo build --release --workspace` first", path.display()));
+    }
+    Ok(path)
+}
+
+/// Run the demo with the terminal as its stdin and stdout.
+pub fn launch(demo: &Demo, args: &[String]) -> i32 {
+    let path = match path(demo) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("tdt4160: {}", e);
+            return 1;
+        }
+    };
+    match Command::new(&path).args(args).status() {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            eprintln!("tdt4160: cannot run {}: {}", path.display(), e);
+            1
+        }
+    }
+}
+
+const NO: Catalog = &[
+    ("Introduction and performance", "Introduksjon og ytelse"),
+    ("Instruction set", "Instruksjonssett"),
+    ("Single-cycle processor", "Enkeltsyklusprosessor"),
+    ("Multi-cycle processor", "Flersyklusprosessor"),
+    ("Pipelined processors", "Samlebåndsprosessorer"),
+    ("The memory system", "Minnesystemet"),
+    ("Parallel computers", "Parallelle datamaskiner"),
+    ("no demo for topic '{}'", "ingen demo for emne «{}»"),
+    ("kernels compiled at -O0 and -O3 side by side", "kjerner kompilert med -O0 og -O3 side om side"),
+    ("one demo function per target and opt level, in columns", "én demofunksjon per mål og optimaliseringsnivå, i kolonner"),
+    ("cycle counter calibration, fenced vs naive timestamp reads", "kalibrering av syklustelleren, tidsstempler lest med og uten fence"),
+    ("popcount, x & (x - 1), powers of two, masks, Gosper's hack", "popcount, x & (x - 1), toerpotenser, masker, Gospers triks"),
+    ("five ways to view a float's bits, which are sound", "fem måter å se bitene i et flyttall på, og hvilke som er sikre"),
+    ("checked, wrapping, saturating and overflowing arithmetic", "aritmetikk som sjekker, går rundt, metter eller melder overflyt"),
+    ("the CPU's own add/sub flags next to arithmetic::alu", "prosessorens egne flagg for add/sub ved siden av arithmetic::alu"),
+    ("an overflowing length, an undersized buffer: unsafe vs safe Rust", "en lengde som flyter over, en for liten buffer: unsafe mot trygg Rust"),
+    ("overflow, division and shifts in C, Rust and Python, in one table", "overflyt, divisjon og skift i C, Rust og Python, i én tabell"),
+    ("little- vs big-endian bytes, a misread file header", "little- mot big-endian byterekkefølge, et feillest filhode"),
+    ("BMP and WAV headers parsed field by field", "BMP- og WAV-hoder tolket felt for felt"),
+    ("hex + ASCII dump of a file", "hex- og ASCII-utskrift av en fil"),
+    ("struct sizes, alignment, offsets and padding", "størrelse, justering, forskyvning og utfylling i structer"),
+    ("sorted vs shuffled data and predictor models", "sortert mot stokket data, og modeller av hoppforutsigere"),
+    ("Spectre in the emulator: a mispredicted bounds check leaks through the cache", "Spectre i emulatoren: en feilspådd grensesjekk lekker gjennom hurtigbufferen"),
+    ("one accumulator chain vs independent accumulators", "én akkumulatorkjede mot uavhengige akkumulatorer"),
+    ("load latency and bandwidth per working-set size (CSV)", "lastelatens og båndbredde per størrelse på arbeidssettet (CSV)"),
+    ("misaligned, line-splitting and page-splitting loads", "ujusterte laster, og laster over en cachelinje- eller sidegrense"),
+    ("bump and first-fit allocators with their block headers", "bump- og first-fit-allokatorer med blokkhodene sine"),
+    ("buddy allocator: splits, merges, internal fragmentation", "buddy-allokator: deling, sammenslåing, intern fragmentering"),
+    ("mark-sweep collector with a mark and sweep trace", "mark-sweep-søppeltømmer med spor av merking og feiing"),
+    ("which everyday Rust code allocates, and how often", "hvilken vanlig Rust-kode som allokerer, og hvor ofte"),
+    ("addresses of stack, heap, statics and code", "adressene til stakk, haug, statiske data og kode"),
+    ("frame sizes, the guard page, recursion vs explicit stack", "rammestørrelser, vaktsiden, rekursjon mot eksplisitt stakk"),
+    ("saturating u8 adds: scalar, SWAR and SIMD intrinsics", "mettende u8-addisjon: skalar, SWAR og SIMD-intrinsics"),
+    ("overflow-safe parallel sum with a speedup table", "overflytsikker parallell sum med en tabell over speedup"),
+    ("fetch_add wrapping, checked increments, lost updates", "fetch_add som går rundt, sjekkede økninger, tapte oppdateringer"),
+    ("SB, MP and IRIW litmus tests per memory ordering", "SB-, MP- og IRIW-lakmustester per minneordning"),
+    ("Peterson's algorithm with and without SeqCst", "Petersons algoritme med og uten SeqCst"),
+    ("spin and ticket locks vs std::sync::Mutex", "spinn- og billettlåser mot std::sync::Mutex"),
+    ("a data race and an exhaustive interleaving checker", "et datakappløp og en uttømmende sjekk av alle flettinger"),
+    ("channels vs Arc<Mutex<_>> for one workload", "kanaler mot Arc<Mutex<_>> for samme arbeidslast"),
+    ("wrapping and saturating statistics counters", "statistikktellere som går rundt eller metter"),
+    ("cache-line ping-pong and false sharing", "pingpong med cachelinjer og falsk deling"),
+];
@@ -0,0 +1,101 @@
+//! The IEEE 754 single-precision fields of a number.
+
+use arithmetic::float::Inspect;
+use util::args::{parse_number, Args};
+use util::lang::{self, Catalog};
+use util::report::{Format, Json, Report};
+
+pub const USAGE: &str = "\
+usage: tdt4160 float <x>... [--format text|json|markdown|latex]
+       tdt4160 float --bits <pattern>...
+
+  x           a decimal number: 0.1, -6.25, 1e-40, inf, nan
+  --bits      read the arguments as 32-bit patterns (0x3DCCCCCD) instead
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 float <x>... [--format text|json|markdown|latex]
+      tdt4160 float --bits <mønster>...
+
+  x           et desimaltall: 0.1, -6.25, 1e-40, inf, nan
+  --bits      les argumentene som 32-bits mønstre (0x3DCCCCCD) i stedet
+";
+
+struct Floats(Vec<(String, f32)>);
+
+impl Report for Floats {
+    fn text(&self) -> String {
+        let each: Vec<String> = self.0.iter().map(|(v, x)| format!("{} = {:e}\n{}\n", v, x, fields(&Inspect::new(*x)))).collect();
+        each.join("\n")
+    }
+
+    fn json(&self) -> Json {
+        Json::array(self.0.iter().map(|(v, x)| {
+            let i = Inspect::new(*x);
+            Json::object()
+                .with("input", v.as_str())
+                .with("value", *x)
+                .with("bits", format!("0x{:08X}", i.bits))
+                .with("sign", i.sign())
+                .with("exponent", i.exponent())
+                .with("unbiased_exponent", i.exponent() as i32 - 127)
+                .with("mantissa", i.mantissa())
+                .with("class", i.class())
+        }))
+    }
+}
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    let mut args = Args::parse(args, &["--format"])?;
+    let format = Format::from_args(&mut args)?;
+    let as_bits = args.flag(&["--bits"]);
+    let values = args.finish()?;
+    if values.is_empty() {
+        return Err("expected a number".to_string());
+    }
+    let mut floats = Vec::new();
+    for v in values {
+        let x = if as_bits {
+            let bits = parse_number(&v)?;
+            f32::from_bits(u32::try_from(bits).map_err(|_| format!("'{}' does not fit in 32 bits", v))?)
+        } else {
+            v.parse::<f32>().map_err(|_| format!("'{}' is not a number", v))?
+        };
+        floats.push((v, x));
+    }
+    format.print(&Floats(floats));
+    Ok(0)
+}
+
+/// Inspect::report with its labels in the chosen language.
+fn fields(i: &Inspect) -> String {
+    let class = tr(i.class());
+    let rows = [
+        (tr("bits"), format!("0x{:08X}", i.bits)),
+        (tr("sign"), i.sign().to_string()),
+        (tr("exponent"), format!("{:08b} ({})", i.exponent(), i.exponent())),
+        (tr("mantissa"), format!("{:023b}", i.mantissa())),
+        (tr("class"), class.to_string()),
+        (tr("value"), i.formula().unwrap_or_else(|| class.to_string())),
+    ];
+    let width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+    let lines: Vec<String> = rows.iter().map(|(label, value)| format!("  {:<w$} {}", label, value, w = width)).collect();
+    lines.join("\n")
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+const NO: Catalog = &[
+    ("bits", "bit"),
+    ("sign", "fortegn"),
+    ("exponent", "eksponent"),
+    ("mantissa", "mantisse"),
+    ("class", "klasse"),
+    ("value", "verdi"),
+    ("zero", "null"),
+    ("subnormal", "subnormalt"),
+    ("infinity", "uendelig"),
+    ("normal", "normalisert"),
+];
@@ -0,0 +1,156 @@
+//! The state table of a sequence detector, Moore or Mealy, and a run of
+//! it on an input sequence, cycle by cycle (logic::fsm).
+
+use logic::fsm::{Kind, Machine, Step};
+use util::args::Args;
+use util::lang::{self, Catalog};
+use util::report::{Format, Json, Report};
+use util::table::{Align, Table};
+
+pub const USAGE: &str = "\
+usage: tdt4160 fsm [--detect PATTERN] [--moore] [--input BITS]
+                   [--format text|json|markdown|latex]
+
+  --detect P   the bit pattern to recognize, overlaps counted (default 101)
+  --moore      a Moore machine, output in the state (default: Mealy,
+               output on the transition)
+  --input B    the inputs to run it on, one bit a cycle (default 0110101101)
+
+The machine starts in S0, and Sk means the last k inputs were the first k
+bits of the pattern.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 fsm [--detect MØNSTER] [--moore] [--input BITS]
+                  [--format text|json|markdown|latex]
+
+  --detect M   bitmønsteret som skal gjenkjennes, overlapp telles (standard 101)
+  --moore      en Moore-maskin, utgang i tilstanden (standard: Mealy,
+               utgang på overgangen)
+  --input B    inngangene den kjøres på, én bit per syklus (standard 0110101101)
+
+Maskinen starter i S0, og Sk betyr at de siste k inngangene var de første
+k bitene i mønsteret.
+";
+
+struct Run {
+    machine: Machine,
+    steps: Vec<Step>,
+}
+
+impl Run {
+    fn state_table(&self) -> Table {
+        let m = &self.machine;
+        let kind = if m.kind == Kind::Moore { "Moore" } else { "Mealy" };
+        let mut header = vec![tr("Present").to_string()];
+        header.extend(m.inputs.iter().map(|i| format!("in={}", i)));
+        if m.kind == Kind::Moore {
+            header.push(tr("Output").to_string());
+        }
+        let mut t = Table::new(&lang::fill(tr("State table, {} ({})"), &[&m.name, &kind]), header).align(1.., Align::Center);
+        for (s, name) in m.states.iter().enumerate() {
+            let mut row = vec![name.clone()];
+            row.extend((0..m.inputs.len()).map(|i| m.cell(s, i)));
+            if m.kind == Kind::Moore {
+                row.push(m.outputs[s].clone());
+            }
+            t.row(row);
+        }
+        t
+    }
+
+    fn trace(&self) -> Table {
+        let m = &self.machine;
+        let mut t = Table::new(tr("Run"), ["Cycle", "State", "Input", "Next", "Output"].map(tr)).align(0..1, Align::Right).align(2.., Align::Center);
+        for (c, s) in self.steps.iter().enumerate() {
+            t.row([c.to_string(), m.states[s.state].clone(), m.inputs[s.input].clone(), m.states[s.next].clone(), s.output.clone()]);
+        }
+        // a Moore output shows in the state after the last input too
+        if let (Kind::Moore, Some(last)) = (m.kind, self.steps.last()) {
+            t.row([self.steps.len().to_string(), m.states[last.next].clone(), String::new(), String::new(), m.outputs[last.next].clone()]);
+        }
+        t
+    }
+
+    /// Cycles in which the output is 1.
+    fn detections(&self) -> Vec<usize> {
+        let mut outputs: Vec<&str> = self.steps.iter().map(|s| s.output.as_str()).collect();
+        if let (Kind::Moore, Some(last)) = (self.machine.kind, self.steps.last()) {
+            outputs.push(&self.machine.outputs[last.next]);
+        }
+        outputs.iter().enumerate().filter(|(_, &o)| o == "1").map(|(c, _)| c).collect()
+    }
+}
+
+impl Report for Run {
+    fn text(&self) -> String {
+        let cycles: Vec<String> = self.detections().iter().map(|c| c.to_string()).collect();
+        let when = match cycles.len() {
+            0 => tr("never").to_string(),
+            1 => lang::fill(tr("in cycle {}"), &[&cycles[0]]),
+            _ => lang::fill(tr("in cycles {}"), &[&cycles.join(", ")]),
+        };
+        let lag = if self.machine.kind == Kind::Moore { tr(", a cycle after the last bit of the pattern") } else { "" };
+        format!("{}\n{}\n{} {}{}\n", self.state_table().text(), self.trace().text(), tr("Output 1"), when, lag)
+    }
+
+    fn json(&self) -> Json {
+        let m = &self.machine;
+        Json::object()
+            .with("name", m.name.as_str())
+            .with("kind", if m.kind == Kind::Moore { "moore" } else { "mealy" })
+            .with("states", Json::array(m.states.iter().map(String::as_str)))
+            .with("inputs", Json::array(m.inputs.iter().map(String::as_str)))
+            .with("transitions", Json::array(m.states.iter().enumerate().flat_map(|(s, name)| {
+                m.inputs.iter().enumerate().map(move |(i, input)| Json::object()
+                    .with("from", name.as_str())
+                    .with("input", input.as_str())
+                    .with("to", m.states[m.next[s][i].to].as_str())
+                    .with("output", m.output(s, i)))
+            })))
+            .with("run", Json::array(self.steps.iter().map(|s| Json::object()
+                .with("state", m.states[s.state].as_str())
+                .with("input", m.inputs[s.input].as_str())
+                .with("next", m.states[s.next].as_str())
+                .with("output", s.output.as_str()))))
+            .with("detected", Json::array(self.detections()))
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        vec![self.state_table(), self.trace()]
+    }
+}
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    let mut args = Args::parse(args, &["--detect", "--input", "--format"])?;
+    let format = Format::from_args(&mut args)?;
+    let pattern = args.string("--detect").unwrap_or_else(|| "101".to_string());
+    let kind = if args.flag(&["--moore"]) { Kind::Moore } else { Kind::Mealy };
+    let input = args.string("--input").unwrap_or_else(|| "0110101101".to_string());
+    args.finish()?;
+    let machine = Machine::detector(&pattern, kind).map_err(|e| format!("--detect: {}", e))?;
+    let symbols = machine.symbols(&input).ok_or_else(|| format!("--input: expected 0s and 1s, got '{}'", input))?;
+    let steps = machine.run(&symbols);
+    format.print(&Run { machine, steps });
+    Ok(0)
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+const NO: Catalog = &[
+    ("Present", "Nå"),
+    ("Output", "Utgang"),
+    ("State table, {} ({})", "Tilstandstabell, {} ({})"),
+    ("Run", "Kjøring"),
+    ("Cycle", "Syklus"),
+    ("State", "Tilstand"),
+    ("Input", "Inngang"),
+    ("Next", "Neste"),
+    ("never", "aldri"),
+    ("in cycle {}", "i syklus {}"),
+    ("in cycles {}", "i syklusene {}"),
+    (", a cycle after the last bit of the pattern", ", en syklus etter den siste biten i mønsteret"),
+    ("Output 1", "Utgang 1"),
+];
@@ -0,0 +1,335 @@
+//! The Java compendium's topics (`computerdesign.Main <topic>`) and the
+//! simulators behind them. The sources in src/ are compiled with javac the
+//! first time, and again when one has changed, into java-classes/ next to
+//! this executable; then java runs the topic. Without arguments a topic
+//! prints what `computerdesign.Main <topic>` prints; with arguments they go
+//! to the topic's own main (Netlist, SAP1, DemandPaging, ...).
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+use util::lang::{self, Catalog};
+use util::report::{Format, Json, Report};
+
+use super::demos::topic_title;
+
+pub const USAGE: &str = "\
+usage: tdt4160 java [topic]              list the Java topics, optionally only one
+                                         course topic (e.g. T6); --format json
+       tdt4160 java <name> [arguments]   run one; the arguments go to its main
+       tdt4160 <name> [arguments]        the same, unless a command or demo has the name
+
+A JDK (javac and java on the PATH) is needed. The classes are compiled
+from src/ into java-classes/ next to tdt4160, when a source has changed.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 java [emne]                list Java-emnene, eventuelt bare ett emne
+                                         i faget (f.eks. T6); --format json
+      tdt4160 java <navn> [argumenter]   kjør ett; argumentene går til dets main
+      tdt4160 <navn> [argumenter]        det samme, med mindre en kommando eller demo har navnet
+
+Det trengs en JDK (javac og java i PATH). Klassene kompileres fra src/
+til java-classes/ ved siden av tdt4160 når en kildefil er endret.
+";
+
+pub struct Topic {
+    pub name: &'static str,
+    /// Course learning goal, as in LEARNING_GOALS.md.
+    pub topic: &'static str,
+    /// The class whose main takes the arguments; empty if the topic takes none.
+    pub class: &'static str,
+    /// Its arguments, as in its Usage: line.
+    pub params: &'static str,
+    pub summary: &'static str,
+}
+
+impl Topic {
+    /// "T6" for topic "T6.3".
+    pub fn group(&self) -> &'static str {
+        self.topic.split('.').next().unwrap_or(self.topic)
+    }
+}
+
+const fn topic(name: &'static str, topic: &'static str, class: &'static str, params: &'static str, summary: &'static str) -> Topic {
+    Topic { name, topic, class, params, summary }
+}
+
+/// The topics of computerdesign.Main, by course topic.
+pub const TOPICS: &[Topic] = &[
+    topic("abstraction", "T1.2", "", "", "abstraction levels from transistors to applications"),
+    topic("vm", "T1.2", "", "", "virtual machines: a hypervisor trapping privileged instructions"),
+    topic("performance", "T1.3", "", "", "Iron Law, Amdahl's Law, AMAT calculations"),
+    topic("speedup", "T1.3", "computerdesign.theory.Speedup", "[amdahl|gustafson S N... | fraction T N | cores T S | plot S]", "Amdahl vs Gustafson, the fraction needed for a speedup, a plot"),
+    topic("perfcalc", "T1.3", "computerdesign.theory.PerfCalc", "[--count N] --mix alu=0.5,... --design \"name clock cpis\"...", "Iron Law with per-class CPI; which of two designs is faster"),
+    topic("energy", "T1.3", "computerdesign.theory.EnergyModel", "[--alu pJ] [--reg-read pJ] [--cache pJ] [--dram pJ] [--clock 1GHz]", "energy, power and EDP of pipeline runs from per-event costs"),
+    topic("instructions", "T2.1", "", "", "RISC-V encoding, formats, disassembly"),
+    topic("formats", "T2.1", "computerdesign.instruction.BitLayout", "[hex word]", "the six formats as bit layouts: encode, decode, range checks"),
+    topic("numbers", "T2.2", "", "", "binary, hex, two's complement, floating point"),
+    topic("hamming", "T2.2", "computerdesign.coding.Hamming", "[data 0-15] [position to flip 1-7]", "parity and Hamming codes: the syndrome locates a flipped bit"),
+    topic("crc", "T2.2", "computerdesign.coding.Crc32", "[text]", "CRC-32 as a shift register and as a 256-entry table"),
+    topic("checksum", "T2.2", "computerdesign.coding.Checksum", "file...", "sum, Internet, Fletcher, Adler, CRC: which errors each misses"),
+    topic("utf8", "T2.2", "computerdesign.coding.Utf8", "text... | --bytes e2 82 ac", "UTF-8 bytes in binary, and why invalid sequences fail"),
+    topic("huffman", "T2.2", "computerdesign.coding.Huffman", "[text]", "Huffman tree and codes vs fixed-width encoding"),
+    topic("base64", "T2.2", "computerdesign.coding.Base64", "text | -d base64-text", "Base64: 3 bytes to 4 six-bit digits, padding, strict decoding"),
+    topic("reedsolomon", "T2.2", "computerdesign.coding.ReedSolomon", "[text]", "GF(256) arithmetic and Reed-Solomon recovery of lost bytes"),
+    topic("calling", "T2.3", "", "", "calling conventions, stack, memory layout"),
+    topic("sap", "T3.1", "computerdesign.sap.SAP1", "[microcode.toml|json program.asm]", "SAP-1 bus CPU: fetch/decode/execute as control words"),
+    topic("microcode", "T3.1", "", "", "SAP-1 with new instructions defined in a microcode file"),
+    topic("logic", "T3.2", "", "", "gates, truth tables, latches, flip-flops"),
+    topic("minimize", "T3.2", "computerdesign.logic.QuineMcCluskey", "[vars \"m1,m2,...\" [\"d1,d2,...\"]]", "Quine-McCluskey minimization, Petrick's method"),
+    topic("hazards", "T3.2", "computerdesign.logic.Hazards", "[file.net [before after]]", "glitches from gate delays, consensus-term hazard cover"),
+    topic("sevenseg", "T3.2", "computerdesign.logic.SevenSegment", "[digits]", "BCD to 7-segment decoder with don't-cares, ASCII display"),
+    topic("netlist", "T3.2", "computerdesign.logic.Netlist", "[file.net [--dot]]", "gate-level simulation: adders, latches, counters"),
+    topic("hdl", "T3.2", "computerdesign.logic.Hdl", "[file.hdl [module]]", "mini HDL: modules, instances, registers to netlists"),
+    topic("components", "T3.2", "", "", "MUX, decoder, encoder and demux with truth tables"),
+    topic("adders", "T3.3", "computerdesign.logic.Adders", "[bits]", "ripple-carry vs carry-lookahead gate delays"),
+    topic("gatealu", "T3.3", "computerdesign.logic.GateLevelALU", "[op a b]", "32-bit gate-level ALU driving the single-cycle datapath"),
+    topic("alu", "T3.3", "", "", "arithmetic and logical operations"),
+    topic("processors", "T4.1", "", "", "single-cycle vs multi-cycle vs pipeline"),
+    topic("fsm", "T4.1", "computerdesign.logic.StateMachine", "[file.toml|file.json [inputs | --dot | --markdown | --latex]]", "Moore vs Mealy state machines, state tables"),
+    topic("flipflops", "T4.2", "", "", "latch vs flip-flop timing, forbidden SR input, JK/T"),
+    topic("registers", "T4.2", "", "", "SIPO/PISO shift registers, counters, frequency division"),
+    topic("seqanalysis", "T4.2", "computerdesign.logic.SequentialAnalysis", "[file.net|file.hdl]", "flip-flop circuit to equations, state table, diagram"),
+    topic("timing", "T4.2", "computerdesign.logic.TimingDiagram", "[--svg out.svg]", "ASCII timing diagrams with clock edges marked"),
+    topic("unrolling", "T5.1", "", "", "loop unrolling and scheduling: stalls and flushes on the pipeline"),
+    topic("snapshot", "T5.1", "computerdesign.processor.MachineSnapshot", "[save|dump|load file [cycles] [--stats]]", "save/restore machine state in a versioned little-endian file"),
+    topic("exceptions", "T5.2", "", "", "exception handling in the pipeline"),
+    topic("plic", "T5.2", "", "", "PLIC priorities, threshold and claim/complete with several devices"),
+    topic("realtime", "T5.2", "computerdesign.os.RealTimeScheduling", "[T:C ...] [--length n]", "rate-monotonic vs EDF: utilization bounds, deadline misses"),
+    topic("memory", "T6.1", "", "", "cache hierarchy, hit rates, locality"),
+    topic("ecc", "T6.1", "", "", "ECC DRAM: single flips corrected, double flips detected"),
+    topic("bitband", "T6.2", "", "", "memory-mapped GPIO, atomic single-bit set/clear via bit-banding"),
+    topic("polling", "T6.2", "computerdesign.io.PollingVsInterrupts", "[--period N] [--random] [--fifo N] [--poll N] [--cycles N]", "polling vs interrupts: wasted CPU cycles, lost data, latency"),
+    topic("dma", "T6.2", "", "", "DMA engine vs programmed I/O: cycles, stolen bus cycles, wfi"),
+    topic("busarb", "T6.2", "computerdesign.io.BusArbitration", "[--trace file [--slot N] [--cycles N]]", "shared-bus arbitration: per-master waits, utilization, starvation"),
+    topic("console", "T6.2", "computerdesign.io.Console", "[echo|menu [--input text]]", "keyboard and display MMIO (RARS layout), echo and menu programs"),
+    topic("disk", "T6.2", "computerdesign.io.Disk", "[--trace file [--start cylinder]]", "disk access time; FCFS, SSTF and SCAN scheduling per request"),
+    topic("uart", "T6.2", "computerdesign.io.UartFraming", "[--format 8E1] [--mismatch pct] [text]", "UART frames as waveforms; parity/framing errors, baud mismatch"),
+    topic("spi", "T6.2", "", "", "SPI modes 0-3 as waveforms; mode mismatch; polling a slow sensor"),
+    topic("i2c", "T6.2", "", "", "I2C addressing, ACK/NACK, repeated start, clock stretching"),
+    topic("watchdog", "T6.2", "", "", "watchdog reset of hung firmware, recovery, boot loops"),
+    topic("tlb", "T6.3", "", "", "translation lookaside buffer"),
+    topic("virtual", "T6.3", "", "", "virtual memory, page tables, isolation"),
+    topic("paging", "T6.3", "computerdesign.os.DemandPaging", "[--policy fifo|lru|clock] [--frames n] [--pages n]", "demand paging: page faults, swap, FIFO vs LRU vs Clock"),
+    topic("cow", "T6.3", "", "", "copy-on-write fork: shared frames, reference counts, copy on first write"),
+    topic("parallel", "T7.1", "", "", "Flynn's taxonomy, Roofline model, coherence"),
+    topic("threads", "T7.2", "", "", "processes, threads, scheduling"),
+];
+
+pub fn find(name: &str) -> Option<&'static Topic> {
+    TOPICS.iter().find(|t| t.name == name)
+}
+
+/// A summary in the language chosen with --lang.
+pub fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+struct Listing(Vec<&'static Topic>);
+
+impl Report for Listing {
+    fn text(&self) -> String {
+        let mut s = String::new();
+        let mut last = "";
+        for t in &self.0 {
+            if t.group() != last {
+                let _ = writeln!(s, "{}{}", if last.is_empty() { "" } else { "\n" }, topic_title(t.group()));
+                last = t.group();
+            }
+            let _ = writeln!(s, "  {:<12} {:<5} {}", t.name, t.topic, tr(t.summary));
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        Json::array(self.0.iter().map(|t| {
+            Json::object().with("name", t.name).with("topic", t.topic).with("class", t.class)
+                .with("params", t.params).with("summary", t.summary)
+        }))
+    }
+}
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    // a topic's arguments are its own (uart takes --format 8E1), so pass them on untouched
+    if let Some(t) = args.first().and_then(|name| find(name)) {
+        return Ok(launch(t, &args[1..]));
+    }
+    let mut args = util::args::Args::parse(args, &["--format"])?;
+    let format = Format::from_args(&mut args)?;
+    let args = args.finish()?;
+    let filter = match args.first() {
+        Some(f) if f.starts_with(['T', 't']) => Some(f.to_uppercase()),
+        Some(name) => return Err(lang::fill(tr("no Java topic '{}'"), &[name])),
+        None => None,
+    };
+    let list: Vec<&Topic> = TOPICS.iter().filter(|t| filter.as_ref().is_none_or(|f| t.topic.starts_with(f.as_str()))).collect();
+    if list.is_empty() {
+        return Err(lang::fill(tr("no Java topic for '{}'"), &[&filter.unwrap_or_default()]));
+    }
+    format.print(&Listing(list));
+    Ok(0)
+}
+
+/// Run the topic with the terminal as its stdin and stdout.
+pub fn launch(topic: &Topic, args: &[String]) -> i32 {
+    if !args.is_empty() && topic.class.is_empty() {
+        eprintln!("tdt4160 {}: {}", topic.name, tr("takes no arguments"));
+        return 2;
+    }
+    let mut command = match command(topic, args) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("tdt4160: {}", e);
+            return 1;
+        }
+    };
+    match command.status() {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            eprintln!("tdt4160: cannot run java: {}", e);
+            1
+        }
+    }
+}
+
+/// The java command that runs the topic, compiling the sources first if
+/// they changed.
+pub fn command(topic: &Topic, args: &[String]) -> Result<Command, String> {
+    let (class, args): (&str, Vec<String>) = if args.is_empty() {
+        ("computerdesign.Main", vec![topic.name.to_string()])
+    } else if topic.class.is_empty() {
+        return Err(format!("{} {}", topic.name, tr("takes no arguments")));
+    } else {
+        (topic.class, args.to_vec())
+    };
+    let mut command = Command::new("java");
+    // the topics' text is English, so their numbers are too
+    command.arg("-cp").arg(classes()?)
+        .args(["-Dfile.encoding=UTF-8", "-Dstdout.encoding=UTF-8", "-Duser.language=en", "-Duser.country=US"])
+        .arg(class).args(args);
+    Ok(command)
+}
+
+/// The compiled classes, compiled first if a source is newer than them.
+fn classes() -> Result<PathBuf, String> {
+    let exe = env::current_exe().map_err(|e| format!("cannot find my own executable: {}", e))?;
+    let src = source_dir(&exe)?;
+    let classes = exe.with_file_name("java-classes");
+    let stamp = classes.join(".compiled");
+    let mut sources = Vec::new();
+    collect(&src, &mut sources);
+    let newest = sources.iter().filter_map(|p| modified(p)).max();
+    if modified(&stamp).is_some_and(|built| newest.is_none_or(|n| n <= built)) {
+        return Ok(classes);
+    }
+    if std::io::stderr().is_terminal() {
+        eprintln!("tdt4160: compiling {} Java sources into {}", sources.len(), classes.display());
+    }
+    let fresh = exe.with_file_name(format!("java-classes.{}", std::process::id()));
+    let status = Command::new("javac").args(["--release", "11", "-encoding", "UTF-8", "-nowarn", "-d"]).arg(&fresh)
+        .args(&sources).status()
+        .map_err(|e| format!("the Java topics need a JDK, and javac did not run: {}", e))?;
+    if !status.success() {
+        let _ = fs::remove_dir_all(&fresh);
+        return Err(format!("javac failed on {}", src.display()));
+    }
+    fs::write(fresh.join(".compiled"), "").map_err(|e| format!("{}: {}", fresh.display(), e))?;
+    let _ = fs::remove_dir_all(&classes);
+    fs::rename(&fresh, &classes).map_err(|e| format!("{}: {}", classes.display(), e))?;
+    Ok(classes)
+}
+
+/// src/ of the repository this executable was built in: it lives in
+/// target/<profile>/, so src/ is found by walking up.
+fn source_dir(exe: &Path) -> Result<PathBuf, String> {
+    let cwd = env::current_dir().unwrap_or_default();
+    exe.ancestors().chain(cwd.ancestors())
+        .map(|dir| dir.join("src"))
+        .find(|src| src.join("computerdesign").join("Main.java").exists())
+        .ok_or_else(|| "cannot find the Java sources (src/computerdesign/Main.java) above tdt4160 or here".to_string())
+}
+
+fn collect(dir: &Path, sources: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(&path, sources);
+        } else if path.extension().is_some_and(|e| e == "java") {
+            sources.push(path);
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+const NO: Catalog = &[
+    ("no Java topic '{}'", "ikke noe Java-emne «{}»"),
+    ("no Java topic for '{}'", "ikke noe Java-emne for «{}»"),
+    ("takes no arguments", "tar ingen argumenter"),
+    ("abstraction levels from transistors to applications", "abstraksjonsnivåer fra transistorer til applikasjoner"),
+    ("virtual machines: a hypervisor trapping privileged instructions", "virtuelle maskiner: en hypervisor som fanger privilegerte instruksjoner"),
+    ("Iron Law, Amdahl's Law, AMAT calculations", "jernloven, Amdahls lov og utregning av AMAT"),
+    ("Amdahl vs Gustafson, the fraction needed for a speedup, a plot", "Amdahl mot Gustafson, andelen som trengs for en speedup, et plott"),
+    ("Iron Law with per-class CPI; which of two designs is faster", "jernloven med CPI per instruksjonsklasse; hvilket av to design er raskest"),
+    ("energy, power and EDP of pipeline runs from per-event costs", "energi, effekt og EDP for kjøringer i samlebåndet ut fra kostnad per hendelse"),
+    ("RISC-V encoding, formats, disassembly", "RISC-V-koding, formater, disassemblering"),
+    ("the six formats as bit layouts: encode, decode, range checks", "de seks formatene som bitoppsett: koding, dekoding, sjekk av verdiområder"),
+    ("binary, hex, two's complement, floating point", "binært, heksadesimalt, toerkomplement, flyttall"),
+    ("parity and Hamming codes: the syndrome locates a flipped bit", "paritet og Hamming-koder: syndromet finner en vendt bit"),
+    ("CRC-32 as a shift register and as a 256-entry table", "CRC-32 som skiftregister og som tabell med 256 oppføringer"),
+    ("sum, Internet, Fletcher, Adler, CRC: which errors each misses", "sum, Internett, Fletcher, Adler, CRC: hvilke feil hver av dem overser"),
+    ("UTF-8 bytes in binary, and why invalid sequences fail", "UTF-8-byte i binært, og hvorfor ugyldige sekvenser feiler"),
+    ("Huffman tree and codes vs fixed-width encoding", "Huffman-tre og -koder mot koding med fast bredde"),
+    ("Base64: 3 bytes to 4 six-bit digits, padding, strict decoding", "Base64: 3 byte til 4 sifre på seks bit, utfylling, streng dekoding"),
+    ("GF(256) arithmetic and Reed-Solomon recovery of lost bytes", "aritmetikk i GF(256) og Reed-Solomon-gjenoppretting av tapte byte"),
+    ("calling conventions, stack, memory layout", "kallkonvensjoner, stakk, minneoppsett"),
+    ("SAP-1 bus CPU: fetch/decode/execute as control words", "SAP-1-busprosessor: hent/dekod/utfør som kontrollord"),
+    ("SAP-1 with new instructions defined in a microcode file", "SAP-1 med nye instruksjoner definert i en mikrokodefil"),
+    ("gates, truth tables, latches, flip-flops", "porter, sannhetstabeller, låsekretser, vipper"),
+    ("Quine-McCluskey minimization, Petrick's method", "Quine-McCluskey-forenkling, Petricks metode"),
+    ("glitches from gate delays, consensus-term hazard cover", "glitcher fra portforsinkelser, dekning med konsensusledd"),
+    ("BCD to 7-segment decoder with don't-cares, ASCII display", "BCD-til-7-segment-dekoder med don't-care, ASCII-visning"),
+    ("gate-level simulation: adders, latches, counters", "simulering på portnivå: addere, låsekretser, tellere"),
+    ("mini HDL: modules, instances, registers to netlists", "mini-HDL: moduler, instanser og registre til nettlister"),
+    ("MUX, decoder, encoder and demux with truth tables", "MUX, dekoder, koder og demux med sannhetstabeller"),
+    ("ripple-carry vs carry-lookahead gate delays", "portforsinkelser i ripple-carry mot carry-lookahead"),
+    ("32-bit gate-level ALU driving the single-cycle datapath", "32-bits ALU på portnivå som driver enkeltsyklus-dataveien"),
+    ("arithmetic and logical operations", "aritmetiske og logiske operasjoner"),
+    ("single-cycle vs multi-cycle vs pipeline", "enkeltsyklus mot flersyklus mot samlebånd"),
+    ("Moore vs Mealy state machines, state tables", "Moore- mot Mealy-tilstandsmaskiner, tilstandstabeller"),
+    ("latch vs flip-flop timing, forbidden SR input, JK/T", "tidsforløp i låsekrets mot vippe, forbudt SR-inngang, JK/T"),
+    ("SIPO/PISO shift registers, counters, frequency division", "SIPO/PISO-skiftregistre, tellere, frekvensdeling"),
+    ("flip-flop circuit to equations, state table, diagram", "vippekrets til likninger, tilstandstabell og diagram"),
+    ("ASCII timing diagrams with clock edges marked", "tidsdiagrammer i ASCII med klokkeflankene markert"),
+    ("loop unrolling and scheduling: stalls and flushes on the pipeline", "løkkeutrulling og instruksjonsplanlegging: stopp og tømming i samlebåndet"),
+    ("save/restore machine state in a versioned little-endian file", "lagre og gjenopprett maskintilstanden i en versjonert little-endian-fil"),
+    ("exception handling in the pipeline", "unntakshåndtering i samlebåndet"),
+    ("PLIC priorities, threshold and claim/complete with several devices", "PLIC-prioriteter, terskel og claim/complete med flere enheter"),
+    ("rate-monotonic vs EDF: utilization bounds, deadline misses", "rate-monotonic mot EDF: utnyttelsesgrenser, tapte frister"),
+    ("cache hierarchy, hit rates, locality", "cachehierarki, treffrater, lokalitet"),
+    ("ECC DRAM: single flips corrected, double flips detected", "ECC-DRAM: enkle bitfeil rettes, doble oppdages"),
+    ("memory-mapped GPIO, atomic single-bit set/clear via bit-banding", "minneavbildet GPIO, atomisk setting/nullstilling av én bit med bit-banding"),
+    ("polling vs interrupts: wasted CPU cycles, lost data, latency", "polling mot avbrudd: bortkastede sykluser, tapte data, latens"),
+    ("DMA engine vs programmed I/O: cycles, stolen bus cycles, wfi", "DMA mot programmert I/O: sykluser, stjålne bussykluser, wfi"),
+    ("shared-bus arbitration: per-master waits, utilization, starvation", "arbitrering på en delt buss: ventetid per master, utnyttelse, utsulting"),
+    ("keyboard and display MMIO (RARS layout), echo and menu programs", "tastatur og skjerm som MMIO (RARS-oppsett), ekko- og menyprogram"),
+    ("disk access time; FCFS, SSTF and SCAN scheduling per request", "aksesstid for disk; FCFS-, SSTF- og SCAN-planlegging per forespørsel"),
+    ("UART frames as waveforms; parity/framing errors, baud mismatch", "UART-rammer som bølgeformer; paritets- og rammefeil, ulik baudrate"),
+    ("SPI modes 0-3 as waveforms; mode mismatch; polling a slow sensor", "SPI-modus 0-3 som bølgeformer; ulik modus; polling av en treg sensor"),
+    ("I2C addressing, ACK/NACK, repeated start, clock stretching", "I2C-adressering, ACK/NACK, gjentatt start, klokkestrekking"),
+    ("watchdog reset of hung firmware, recovery, boot loops", "vakthund som tilbakestiller fastlåst fastvare, gjenoppretting, oppstartsløkker"),
+    ("translation lookaside buffer", "adresseoversettelsesbuffer (TLB)"),
+    ("virtual memory, page tables, isolation", "virtuelt minne, sidetabeller, isolasjon"),
+    ("demand paging: page faults, swap, FIFO vs LRU vs Clock", "sideinnlasting ved behov: sidefeil, swap, FIFO mot LRU mot Clock"),
+    ("copy-on-write fork: shared frames, reference counts, copy on first write", "copy-on-write-fork: delte rammer, referansetellere, kopi ved første skriving"),
+    ("Flynn's taxonomy, Roofline model, coherence", "Flynns taksonomi, Roofline-modellen, koherens"),
+    ("processes, threads, scheduling", "prosesser, tråder, planlegging"),
+];
@@ -0,0 +1,385 @@
+//! A menu for lab sessions: pick a topic, pick a demo or a Java topic,
+//! read what its arguments are, change them and run it again, with the
+//! output in a pane that pages instead of scrolling away.
+//!
+//! It is std only, like the rest of the workspace: screens are redrawn
+//! with ANSI escapes and input is read a line at a time, so it needs no
+//! raw mode and works the same in any terminal or over ssh.
+
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use super::demos::{self, Demo, DEMOS};
+use super::java::{self, Topic};
+use crate::session::{Session, Setting};
+use util::args::Args;
+use util::lang::{self, Catalog};
+
+pub const USAGE: &str = "\
+usage: tdt4160 menu [topic] [--record FILE | --replay FILE]
+
+  topic          open one topic straight away, e.g. T6
+  --record FILE  save every screen and everything typed to FILE
+  --replay FILE  go through a recorded session again: the same input, in
+                 the terminal size and language it was recorded in
+
+The topics list the Rust demos and then the Java topics of `tdt4160
+java`. Each screen says what it accepts: a number or a name opens an
+entry, b goes back, q quits. On a demo's screen, Enter runs it with the
+arguments shown, typing new ones changes them and runs it, and - clears
+them. The menu remembers the arguments of every demo until it quits.
+
+A replay at a terminal waits for Enter before each input (q stops it),
+and shows the # notes a TA added to the file above the prompts. It ends
+with exit code 1 at the first screen that is not the one recorded, so a
+walkthrough handed out for a lab can be checked to still hold; a file of
+> inputs and # notes alone, written by hand, replays as well.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 menu [emne] [--record FIL | --replay FIL]
+
+  emne           åpne ett emne med en gang, f.eks. T6
+  --record FIL   lagre hvert skjermbilde og alt som skrives inn til FIL
+  --replay FIL   gå gjennom en innspilt økt igjen: samme inndata, i
+                 terminalstørrelsen og språket den ble spilt inn med
+
+Emnene lister Rust-demoene og så Java-emnene fra `tdt4160 java`. Hvert
+skjermbilde sier hva det tar imot: et nummer eller et navn åpner en
+oppføring, b går tilbake, q avslutter. På skjermbildet til en demo
+kjører Enter den med argumentene som vises, nye argumenter endrer dem og
+kjører den, og - tømmer dem. Menyen husker argumentene til hver demo til
+den avsluttes.
+
+En avspilling i en terminal venter på Enter før hver inndata (q stopper
+den), og viser #-merknadene en læringsassistent har lagt til i filen over
+ledetekstene. Den avslutter med kode 1 ved det første skjermbildet som
+ikke er det som ble tatt opp, så en gjennomgang som deles ut til en lab
+kan sjekkes for om den fortsatt stemmer; en fil med bare > inndata og #
+merknader, skrevet for hånd, kan også spilles av.
+";
+
+enum Screen {
+    Topics,
+    Topic(&'static str),
+    Entry(Entry),
+}
+
+/// A Rust demo or a Java topic: what the menu lists and runs.
+#[derive(Clone, Copy)]
+enum Entry {
+    Demo(&'static Demo),
+    Java(&'static Topic),
+}
+
+impl Entry {
+    fn name(self) -> &'static str {
+        match self {
+            Entry::Demo(d) => d.name,
+            Entry::Java(t) => t.name,
+        }
+    }
+
+    fn topic(self) -> &'static str {
+        match self {
+            Entry::Demo(d) => d.topic,
+            Entry::Java(t) => t.topic,
+        }
+    }
+
+    fn group(self) -> &'static str {
+        self.topic().split('.').next().unwrap_or(self.topic())
+    }
+
+    fn params(self) -> &'static str {
+        match self {
+            Entry::Demo(d) => d.params,
+            Entry::Java(t) => t.params,
+        }
+    }
+
+    /// In the language chosen with --lang.
+    fn summary(self) -> &'static str {
+        match self {
+            Entry::Demo(d) => demos::tr(d.summary),
+            Entry::Java(t) => java::tr(t.summary),
+        }
+    }
+}
+
+/// Every demo and Java topic, by course topic; in a topic the demos come first.
+fn entries() -> Vec<Entry> {
+    let mut all: Vec<Entry> = DEMOS.iter().map(Entry::Demo).chain(java::TOPICS.iter().map(Entry::Java)).collect();
+    all.sort_by_key(|e| e.group().trim_start_matches('T').parse::<u32>().unwrap_or(0));
+    all
+}
+
+fn find(name: &str) -> Option<Entry> {
+    demos::find(name).map(Entry::Demo).or_else(|| java::find(name).map(Entry::Java))
+}
+
+struct Menu {
+    /// Arguments last used per demo or Java topic.
+    args: HashMap<&'static str, String>,
+    rows: usize,
+    cols: usize,
+    ansi: bool,
+    /// Where the output goes and the input comes from (--record, --replay).
+    session: Session,
+}
+
+/// println! through the menu's session, which --record saves.
+macro_rules! say {
+    ($menu:expr, $($arg:tt)*) => {
+        $menu.session.print(&format!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    let mut args = Args::parse(args, &["--record", "--replay"])?;
+    let (record, replay) = (args.string("--record"), args.string("--replay"));
+    let args = args.finish()?;
+    let (session, setting) = match (record, replay) {
+        (Some(_), Some(_)) => return Err("--record and --replay do not go together".to_string()),
+        (_, Some(path)) => {
+            if !args.is_empty() {
+                return Err("--replay opens the topic the session was recorded at".to_string());
+            }
+            let (session, setting) = Session::replay(&path)?;
+            lang::set(setting.lang);
+            (session, setting)
+        }
+        (record, None) => {
+            let (rows, cols) = terminal_size();
+            let setting = Setting { rows, cols, lang: lang::current(), topic: args.first().map(|t| t.to_uppercase()) };
+            (record.map_or_else(|| Ok(Session::live()), |path| Session::record(&path, &setting))?, setting)
+        }
+    };
+    let mut screen = match setting.topic {
+        None => Screen::Topics,
+        Some(t) => match groups().into_iter().find(|g| *g == t) {
+            Some(g) => Screen::Topic(g),
+            None => return Err(lang::fill(tr("no demo for topic '{}'"), &[&t])),
+        },
+    };
+    let mut menu = Menu { args: HashMap::new(), rows: setting.rows, cols: setting.cols, ansi: io::stdout().is_terminal(), session };
+    while let Some(next) = match screen {
+        Screen::Topics => menu.topics(),
+        Screen::Topic(group) => menu.topic(group),
+        Screen::Entry(entry) => menu.entry(entry),
+    } {
+        screen = next;
+    }
+    menu.clear();
+    menu.session.finish()
+}
+
+/// The topic groups that have demos or Java topics, in course order.
+fn groups() -> Vec<&'static str> {
+    let mut groups: Vec<&str> = entries().iter().map(|e| e.group()).collect();
+    groups.dedup();
+    groups
+}
+
+/// (rows, columns) from `stty size`, or the classic 24 × 80.
+fn terminal_size() -> (usize, usize) {
+    let out = Command::new("stty").arg("size").stdin(Stdio::inherit()).stderr(Stdio::null()).output();
+    let text = out.ok().filter(|o| o.status.success()).map(|o| String::from_utf8_lossy(&o.stdout).into_owned());
+    let mut sizes = text.iter().flat_map(|t| t.split_whitespace()).filter_map(|n| n.parse::<usize>().ok());
+    match (sizes.next(), sizes.next()) {
+        (Some(r), Some(c)) if r > 0 && c > 0 => (r, c),
+        _ => {
+            let var = |name, default| env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+            (var("LINES", 24), var("COLUMNS", 80))
+        }
+    }
+}
+
+impl Menu {
+    fn clear(&self) {
+        if self.ansi {
+            print!("\x1b[2J\x1b[H");
+        }
+    }
+
+    /// A line of input, trimmed; None at end of input.
+    fn prompt(&mut self, text: &str) -> Option<String> {
+        self.session.prompt(text)
+    }
+
+    fn topics(&mut self) -> Option<Screen> {
+        let groups = groups();
+        let mut error = None;
+        loop {
+            self.clear();
+            say!(self, "{}\n", tr("TDT4160 demos"));
+            for (i, g) in groups.iter().enumerate() {
+                let n = DEMOS.iter().filter(|d| d.group() == *g).count();
+                let java = java::TOPICS.iter().filter(|t| t.group() == *g).count();
+                say!(self, "  {:>2}  {:<36} {}", i + 1, demos::topic_title(g), lang::fill(tr("{} demos, {} Java"), &[&n, &java]));
+            }
+            if let Some(e) = error.take() {
+                say!(self, "\n{}", e);
+            }
+            let input = self.prompt(tr("\ntopic number, demo name, or q to quit"))?;
+            if input == "q" {
+                return None;
+            }
+            if let Some(g) = pick(&input, &groups) {
+                return Some(Screen::Topic(g));
+            }
+            match find(&input) {
+                Some(e) => return Some(Screen::Entry(e)),
+                None => error = Some(lang::fill(tr("no topic or demo '{}'"), &[&input])),
+            }
+        }
+    }
+
+    fn topic(&mut self, group: &'static str) -> Option<Screen> {
+        let list: Vec<Entry> = entries().into_iter().filter(|e| e.group() == group).collect();
+        let mut error = None;
+        loop {
+            self.clear();
+            say!(self, "{}\n", demos::topic_title(group));
+            for (i, e) in list.iter().enumerate() {
+                if matches!(e, Entry::Java(_)) && (i == 0 || matches!(list[i - 1], Entry::Demo(_))) {
+                    say!(self, "  {}", tr("Java (tdt4160 java <topic>):"));
+                }
+                say!(self, "  {:>2}  {:<16} {:<5} {}", i + 1, e.name(), e.topic(), e.summary());
+            }
+            if let Some(e) = error.take() {
+                say!(self, "\n{}", e);
+            }
+            let input = self.prompt(tr("\ndemo number or name, b back, q quit"))?;
+            match input.as_str() {
+                "q" => return None,
+                "b" => return Some(Screen::Topics),
+                _ => {}
+            }
+            match pick(&input, &list).or_else(|| find(&input)) {
+                Some(e) => return Some(Screen::Entry(e)),
+                None => error = Some(lang::fill(tr("no demo '{}'"), &[&input])),
+            }
+        }
+    }
+
+    fn entry(&mut self, entry: Entry) -> Option<Screen> {
+        loop {
+            self.clear();
+            say!(self, "{}  ({})\n\n  {}\n", entry.name(), demos::topic_title(entry.topic()), entry.summary());
+            let current = self.args.get(entry.name()).map_or("", |s| s.as_str());
+            if entry.params().is_empty() {
+                say!(self, "  {}", tr("takes no arguments"));
+            } else {
+                say!(self, "  {:<11}{}", tr("arguments"), entry.params());
+                say!(self, "  {:<11}{}", tr("current"), if current.is_empty() { tr("(defaults)") } else { current });
+            }
+            let input = self.prompt(tr("\nEnter runs it, new arguments run with those, - clears them, b back, q quit"))?;
+            match input.as_str() {
+                "q" => return None,
+                "b" => return Some(Screen::Topic(entry.group())),
+                "" => {}
+                "-" => {
+                    self.args.remove(entry.name());
+                }
+                _ => {
+                    self.args.insert(entry.name(), input);
+                }
+            }
+            self.execute(entry);
+        }
+    }
+
+    fn execute(&mut self, entry: Entry) {
+        let args: Vec<String> = self.args.get(entry.name()).map(|s| s.split_whitespace().map(String::from).collect()).unwrap_or_default();
+        let title = format!("{} {}", entry.name(), args.join(" ")).trim_end().to_string();
+        let command = match entry {
+            Entry::Demo(demo) => demos::path(demo).map(|path| {
+                let mut c = Command::new(path);
+                c.args(&args);
+                c
+            }),
+            Entry::Java(topic) => java::command(topic, &args),
+        };
+        let mut command = match command {
+            Ok(c) => c,
+            Err(e) => return self.pane(&title, &e),
+        };
+        say!(self, "{}", lang::fill(tr("running {} ..."), &[&title]));
+        let _ = io::stdout().flush();
+        let text = match command.stdin(Stdio::null()).output() {
+            Ok(out) => format!("{}{}\n[exit {}]", String::from_utf8_lossy(&out.stdout), String::from_utf8_lossy(&out.stderr),
+                out.status.code().map_or("by signal".to_string(), |c| c.to_string())),
+            Err(e) => format!("cannot run {}: {}", command.get_program().to_string_lossy(), e),
+        };
+        self.pane(&title, &text);
+    }
+
+    /// Show text in a box, a screenful at a time.
+    fn pane(&mut self, title: &str, text: &str) {
+        let width = self.cols.saturating_sub(4).max(20);
+        let page = self.rows.saturating_sub(4).max(5);
+        let lines: Vec<String> = text.lines().flat_map(|l| wrap(l, width)).collect();
+        let title: String = title.chars().take(width - 2).collect();
+        let mut start = 0;
+        loop {
+            let end = (start + page).min(lines.len());
+            self.clear();
+            say!(self, "┌─ {} {}┐", title, "─".repeat(width - 1 - title.chars().count()));
+            for line in &lines[start..end] {
+                say!(self, "│ {:<w$} │", line, w = width);
+            }
+            let status = lang::fill(tr(" {}-{} of {} "), &[&(start + 1), &end, &lines.len()]);
+            say!(self, "└{}{}┘", status, "─".repeat((width + 2).saturating_sub(status.chars().count())));
+            let more = end < lines.len();
+            match self.prompt(tr(if more { "Enter next page, b previous, q close" } else { "Enter close, b previous" })).as_deref() {
+                None | Some("q") => return,
+                Some("b") => start = start.saturating_sub(page),
+                _ if more => start = end,
+                _ => return,
+            }
+        }
+    }
+}
+
+/// The entry a 1-based number picks.
+fn pick<T: Copy>(input: &str, items: &[T]) -> Option<T> {
+    let n: usize = input.parse().ok()?;
+    items.get(n.checked_sub(1)?).copied()
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+/// Split a line into pieces of at most `width` characters.
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = line.replace('\t', "    ").chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars.chunks(width).map(|c| c.iter().collect()).collect()
+}
+
+const NO: Catalog = &[
+    ("TDT4160 demos", "TDT4160-demoer"),
+    ("{} demos, {} Java", "{} demoer, {} Java"),
+    ("Java (tdt4160 java <topic>):", "Java (tdt4160 java <emne>):"),
+    ("no demo for topic '{}'", "ingen demo for emne «{}»"),
+    ("\ntopic number, demo name, or q to quit", "\nemnenummer, demonavn, eller q for å avslutte"),
+    ("no topic or demo '{}'", "ingen emne eller demo «{}»"),
+    ("\ndemo number or name, b back, q quit", "\ndemonummer eller -navn, b tilbake, q avslutt"),
+    ("no demo '{}'", "ingen demo «{}»"),
+    ("takes no arguments", "tar ingen argumenter"),
+    ("arguments", "argumenter"),
+    ("current", "nå"),
+    ("(defaults)", "(standardverdier)"),
+    ("\nEnter runs it, new arguments run with those, - clears them, b back, q quit",
+        "\nEnter kjører den, nye argumenter kjører med dem, - tømmer dem, b tilbake, q avslutt"),
+    ("running {} ...", "kjører {} ..."),
+    (" {}-{} of {} ", " {}-{} av {} "),
+    ("Enter next page, b previous, q close", "Enter neste side, b forrige, q lukk"),
+    ("Enter close, b previous", "Enter lukk, b forrige"),
+];
@@ -0,0 +1,152 @@
+//! The subcommands. Each module has a USAGE text and a `run` that gets
+//! the arguments after the command name and returns the exit code. The
+//! Norwegian of a USAGE is its module's USAGE_NO, listed in NO below.
+
+pub mod adders;
+pub mod cache;
+pub mod config;
+pub mod demos;
+pub mod float;
+pub mod fsm;
+pub mod java;
+pub mod menu;
+pub mod overflow;
+pub mod quiz;
+pub mod riscv;
+
+use std::fmt::Write;
+
+use util::lang::{self, Catalog};
+
+pub struct Command {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub usage: &'static str,
+    pub run: fn(Vec<String>) -> Result<i32, String>,
+}
+
+pub const COMMANDS: &[Command] = &[
+    Command { name: "overflow", summary: "n-bit add/subtract: result, flags, signed and unsigned views", usage: overflow::USAGE, run: overflow::run },
+    Command { name: "float", summary: "IEEE 754 fields of a float", usage: float::USAGE, run: float::run },
+    Command { name: "adders", summary: "ripple-carry vs carry-lookahead: gates and delay", usage: adders::USAGE, run: adders::run },
+    Command { name: "fsm", summary: "state table and run of a Moore or Mealy sequence detector", usage: fsm::USAGE, run: fsm::run },
+    Command { name: "riscv", summary: "run, disassemble, pipeline-time or draw the stack of an RV32IM program; x86 beside it", usage: riscv::USAGE, run: riscv::run },
+    Command { name: "cache", summary: "set-associative cache simulation of a Dinero trace", usage: cache::USAGE, run: cache::run },
+    Command { name: "config", summary: "check a machine file for --config and show its setup", usage: config::USAGE, run: config::run },
+    Command { name: "quiz", summary: "exam-style problems with worked solutions, by seed", usage: quiz::USAGE, run: quiz::run },
+    Command { name: "demos", summary: "list the demo programs by course topic", usage: demos::USAGE, run: demos::run },
+    Command { name: "java", summary: "list or run the Java compendium's topics and simulators", usage: java::USAGE, run: java::run },
+    Command { name: "menu", summary: "pick, configure and run demos from a menu", usage: menu::USAGE, run: menu::run },
+    Command { name: "help", summary: "this overview, or `help <command>`", usage: HELP_USAGE, run: help_command },
+];
+
+const HELP_USAGE: &str = "usage: tdt4160 help [command]\n";
+
+pub fn find(name: &str) -> Option<&'static Command> {
+    match name {
+        "-h" | "--help" => find("help"),
+        _ => COMMANDS.iter().find(|c| c.name == name),
+    }
+}
+
+pub fn help() -> String {
+    let mut s = String::from(tr("usage: tdt4160 <command> [options] [arguments]\n\nCommands:\n"));
+    for c in COMMANDS {
+        let _ = writeln!(s, "  {:<10} {}", c.name, tr(c.summary));
+    }
+    s.push_str(tr("\nDemos (tdt4160 <demo> [arguments], see `tdt4160 demos`):\n"));
+    let mut line = String::new();
+    for d in demos::DEMOS {
+        if !line.is_empty() && line.len() + d.name.len() > 72 {
+            let _ = writeln!(s, " {}", line);
+            line.clear();
+        }
+        line.push(' ');
+        line.push_str(d.name);
+    }
+    let _ = writeln!(s, " {}", line);
+    s.push_str(tr("\nJava topics (tdt4160 java <topic> [arguments], see `tdt4160 java`):\n"));
+    line.clear();
+    for t in java::TOPICS {
+        if !line.is_empty() && line.len() + t.name.len() > 72 {
+            let _ = writeln!(s, " {}", line);
+            line.clear();
+        }
+        line.push(' ');
+        line.push_str(t.name);
+    }
+    let _ = writeln!(s, " {}", line);
+    s.push_str(tr(CONVENTIONS));
+    s
+}
+
+const CONVENTIONS: &str = "
+Every command takes -h/--help. Options are --name value or --name=value;
+numbers may be 4160, 0x1040, 0b1010 or sizes like 32K. The commands take
+--format json to print their results for scripts instead of people,
+--format markdown|latex to paste tables into a report, and --lang no|en
+prints in Norwegian or English (default: from LANG). -q prints only the
+statistics, -v every step and -vv what each step changed.
+";
+
+/// `english` in the language chosen with --lang.
+pub fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn help_command(args: Vec<String>) -> Result<i32, String> {
+    match args.first() {
+        None => print!("{}", help()),
+        Some(name) => match find(name) {
+            Some(c) => print!("{}", tr(c.usage)),
+            None => match demos::find(name) {
+                Some(d) => println!("{}  ({})\n  {}\n{} tdt4160 {} {}", d.name, d.topic, demos::tr(d.summary), tr("Run:"), d.name, d.params),
+                None => match java::find(name) {
+                    Some(t) => println!("{}  ({})\n  {}\n{} tdt4160 java {} {}", t.name, t.topic, java::tr(t.summary), tr("Run:"), t.name, t.params),
+                    None => return Err(lang::fill(tr("no command, demo or Java topic '{}'"), &[name])),
+                },
+            },
+        },
+    }
+    Ok(0)
+}
+
+const NO: Catalog = &[
+    ("usage: tdt4160 <command> [options] [arguments]\n\nCommands:\n", "bruk: tdt4160 <kommando> [valg] [argumenter]\n\nKommandoer:\n"),
+    ("\nDemos (tdt4160 <demo> [arguments], see `tdt4160 demos`):\n", "\nDemoer (tdt4160 <demo> [argumenter], se `tdt4160 demos`):\n"),
+    (CONVENTIONS, "
+Alle kommandoer tar -h/--help. Valg skrives --navn verdi eller --navn=verdi;
+tall kan være 4160, 0x1040, 0b1010 eller størrelser som 32K. Kommandoene tar
+--format json for å skrive resultatene for skript i stedet for mennesker,
+--format markdown|latex for å lime tabeller inn i en rapport, og --lang no|en
+skriver på norsk eller engelsk (standard: fra LANG). -q skriver bare
+statistikken, -v hvert steg og -vv hva hvert steg endret.
+"),
+    ("n-bit add/subtract: result, flags, signed and unsigned views", "n-bits addisjon/subtraksjon: resultat, flagg, med og uten fortegn"),
+    ("IEEE 754 fields of a float", "IEEE 754-feltene i et flyttall"),
+    ("ripple-carry vs carry-lookahead: gates and delay", "ripple-carry mot carry-lookahead: porter og forsinkelse"),
+    ("state table and run of a Moore or Mealy sequence detector", "tilstandstabell og kjøring av en Moore- eller Mealy-sekvensdetektor"),
+    ("run, disassemble, pipeline-time or draw the stack of an RV32IM program; x86 beside it", "kjør, disassembler, tidsberegn i samlebåndet eller tegn stakken til et RV32IM-program; x86 ved siden av"),
+    ("set-associative cache simulation of a Dinero trace", "simulering av en sett-assosiativ cache med et Dinero-spor"),
+    ("check a machine file for --config and show its setup", "sjekk en maskinfil til --config og vis oppsettet"),
+    ("exam-style problems with worked solutions, by seed", "eksamensoppgaver med løsningsforslag, etter frø"),
+    ("list the demo programs by course topic", "list demoprogrammene etter emne i faget"),
+    ("list or run the Java compendium's topics and simulators", "list eller kjør emnene og simulatorene i Java-kompendiet"),
+    ("\nJava topics (tdt4160 java <topic> [arguments], see `tdt4160 java`):\n", "\nJava-emner (tdt4160 java <emne> [argumenter], se `tdt4160 java`):\n"),
+    ("pick, configure and run demos from a menu", "velg, still inn og kjør demoer fra en meny"),
+    ("this overview, or `help <command>`", "denne oversikten, eller `help <kommando>`"),
+    ("no command, demo or Java topic '{}'", "ingen kommando, demo eller Java-emne «{}»"),
+    ("Run:", "Kjør:"),
+    (HELP_USAGE, "bruk: tdt4160 help [kommando]\n"),
+    (overflow::USAGE, overflow::USAGE_NO),
+    (float::USAGE, float::USAGE_NO),
+    (adders::USAGE, adders::USAGE_NO),
+    (fsm::USAGE, fsm::USAGE_NO),
+    (riscv::USAGE, riscv::USAGE_NO),
+    (cache::USAGE, cache::USAGE_NO),
+    (config::USAGE, config::USAGE_NO),
+    (quiz::USAGE, quiz::USAGE_NO),
+    (demos::USAGE, demos::USAGE_NO),
+    (java::USAGE, java::USAGE_NO),
+    (menu::USAGE, menu::USAGE_NO),
+];
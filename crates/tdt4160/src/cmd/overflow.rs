@@ -0,0 +1,147 @@
+//! n-bit addition and subtraction with everything a student is asked to
+//! read off: the bit pattern, both interpretations, the four flags, and
+//! what wrapping and saturating arithmetic make of it.
+
+use std::fmt::Write;
+
+use arithmetic::alu::{self, Flags};
+use arithmetic::bits::mask;
+use util::args::{parse_signed, Args};
+use util::lang::{self, Catalog};
+use util::report::{Format, Json, Report};
+
+pub const USAGE: &str = "\
+usage: tdt4160 overflow [a b] [--width N] [--sub]
+                        [--format text|json|markdown|latex]
+
+  a, b       operands: 127, -1, 0x7F, 0b1000_0000 (taken modulo 2^width)
+  --width N  register width in bits, 1-32 (default 8)
+  --sub      compute a - b instead of a + b
+
+Without operands, a table of the classic cases.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 overflow [a b] [--width N] [--sub]
+                       [--format text|json|markdown|latex]
+
+  a, b       operander: 127, -1, 0x7F, 0b1000_0000 (tatt modulo 2^bredde)
+  --width N  registerbredde i bit, 1-32 (standard 8)
+  --sub      regn ut a - b i stedet for a + b
+
+Uten operander: en tabell over de klassiske tilfellene.
+";
+
+/// One operation, worked out.
+struct Case {
+    width: u32,
+    subtract: bool,
+    a: u32,
+    b: u32,
+    result: u32,
+    flags: Flags,
+    exact_unsigned: i64,
+    exact_signed: i64,
+}
+
+impl Case {
+    fn new(a: u32, b: u32, width: u32, subtract: bool) -> Case {
+        let m = mask(width, 0);
+        let (a, b) = (a & m, b & m);
+        let (result, flags) = if subtract { alu::sub(a, b, width) } else { alu::add(a, b, width) };
+        let (sa, sb) = (alu::sign_extend(a, width) as i64, alu::sign_extend(b, width) as i64);
+        let (exact_signed, exact_unsigned) = if subtract { (sa - sb, a as i64 - b as i64) } else { (sa + sb, a as i64 + b as i64) };
+        Case { width, subtract, a, b, result, flags, exact_unsigned, exact_signed }
+    }
+
+    fn signed(&self, v: u32) -> i32 {
+        alu::sign_extend(v, self.width)
+    }
+
+    fn value(&self, v: u32) -> Json {
+        Json::object().with("bits", format!("{:0w$b}", v, w = self.width as usize)).with("unsigned", v).with("signed", self.signed(v))
+    }
+}
+
+struct Cases(Vec<Case>);
+
+impl Report for Cases {
+    fn text(&self) -> String {
+        let mut s = String::new();
+        for (i, c) in self.0.iter().enumerate() {
+            if i > 0 {
+                s.push('\n');
+            }
+            let op = if c.subtract { '-' } else { '+' };
+            let bits = |v: u32| format!("{:0w$b}", v, w = c.width as usize);
+            let _ = writeln!(s, "{}-bit {} {} {}", c.width, c.signed(c.a), op, c.signed(c.b));
+            let _ = writeln!(s, "  a       {}  unsigned {:>11}  signed {:>11}", bits(c.a), c.a, c.signed(c.a));
+            let _ = writeln!(s, "  b     {} {}  unsigned {:>11}  signed {:>11}", op, bits(c.b), c.b, c.signed(c.b));
+            let _ = writeln!(s, "  result  {}  unsigned {:>11}  signed {:>11}", bits(c.result), c.result, c.signed(c.result));
+            let _ = writeln!(s, "  flags   {}", describe(c.flags, c.subtract));
+            let _ = writeln!(s, "  exact   unsigned {} signed {}; saturating: unsigned {} signed {}",
+                c.exact_unsigned, c.exact_signed, alu::saturate_unsigned(c.exact_unsigned, c.width), alu::saturate_signed(c.exact_signed, c.width));
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        Json::array(self.0.iter().map(|c| {
+            let f = c.flags;
+            Json::object()
+                .with("width", c.width)
+                .with("operation", if c.subtract { "sub" } else { "add" })
+                .with("a", c.value(c.a))
+                .with("b", c.value(c.b))
+                .with("result", c.value(c.result))
+                .with("flags", Json::object().with("n", f.negative).with("z", f.zero).with("c", f.carry).with("v", f.overflow))
+                .with("exact", Json::object().with("unsigned", c.exact_unsigned).with("signed", c.exact_signed))
+                .with("saturating", Json::object()
+                    .with("unsigned", alu::saturate_unsigned(c.exact_unsigned, c.width))
+                    .with("signed", alu::saturate_signed(c.exact_signed, c.width)))
+        }))
+    }
+}
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    let mut args = Args::parse(args, &["--width", "--format"])?;
+    let format = Format::from_args(&mut args)?;
+    let width = args.value::<u32>("--width")?.unwrap_or(8);
+    let subtract = args.flag(&["--sub"]);
+    let operands = args.finish()?;
+    if !(1..=32).contains(&width) {
+        return Err(format!("--width must be 1-32, got {}", width));
+    }
+    let cases = match operands.as_slice() {
+        [] => [(127, 1, 8, false), (200, 100, 8, false), (-128, 1, 8, true), (0, 1, 8, true), (0x7FFF_FFFF, 1, 32, false), (-1, -1, 32, false)]
+            .iter()
+            .map(|&(a, b, width, subtract)| Case::new(a as u32, b as u32, width, subtract))
+            .collect(),
+        [a, b] => vec![Case::new(parse_signed(a)? as u32, parse_signed(b)? as u32, width, subtract)],
+        _ => return Err("expected two operands or none".to_string()),
+    };
+    format.print(&Cases(cases));
+    Ok(0)
+}
+
+fn describe(f: Flags, subtract: bool) -> String {
+    let on = |set: bool, name: &str| if set { name.to_string() } else { name.to_lowercase() };
+    let mut s = format!("{} {} {} {}", on(f.negative, "N"), on(f.zero, "Z"), on(f.carry, "C"), on(f.overflow, "V"));
+    if f.carry {
+        s.push_str(tr(if subtract { "  borrow: the unsigned result is wrong" } else { "  carry out: the unsigned result is wrong" }));
+    }
+    if f.overflow {
+        s.push_str(tr("  overflow: the signed result is wrong"));
+    }
+    s
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+const NO: Catalog = &[
+    ("  borrow: the unsigned result is wrong", "  lån: resultatet uten fortegn er feil"),
+    ("  carry out: the unsigned result is wrong", "  mente ut: resultatet uten fortegn er feil"),
+    ("  overflow: the signed result is wrong", "  overflyt: resultatet med fortegn er feil"),
+];
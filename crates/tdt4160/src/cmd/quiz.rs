@@ -0,0 +1,230 @@
+//! Problem sets for practice and for exercise sessions, from quizgen: the
+//! problems first, the worked solutions after them, so the first half can
+//! be handed out and the second kept back. With --ask the pipeline
+//! problems are answered at the prompt instead, and each answer is checked
+//! against riscv::pipeline as soon as it is given.
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use quizgen::{Hazards, Problem, Topic};
+use util::args::Args;
+use util::lang::{self, fill, Catalog};
+use util::report::{Format, Json, Report};
+use util::rng::Rng;
+
+use crate::session::Session;
+
+pub const USAGE: &str = "\
+usage: tdt4160 quiz [--topic T,...] [--count N] [--seed N] [--solutions]
+                    [--format text|json|markdown|latex]
+       tdt4160 quiz --ask [--count N] [--seed N]
+
+  --topic T    twos, cache, pipeline or kmap, or a comma-separated list
+               (default: all four, in turn)
+  --count N    number of problems, at most 1000 (default 4)
+  --seed N     which problem set (default: a new one, printed at the top)
+  --solutions  print the worked solutions after the problems
+  --ask        pipeline hazards at the prompt: which instructions stall,
+               and for how many cycles, without and with forwarding; each
+               answer is checked and the pipeline diagram shown
+
+The same seed, count and topics always give the same problems, so a set
+can be handed out and its solutions printed afterwards. The solutions are
+computed by the simulators of this workspace.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 quiz [--topic T,...] [--count N] [--seed N] [--solutions]
+                   [--format text|json|markdown|latex]
+      tdt4160 quiz --ask [--count N] [--seed N]
+
+  --topic T    twos, cache, pipeline eller kmap, eller en kommaseparert liste
+               (standard: alle fire, etter tur)
+  --count N    antall oppgaver, høyst 1000 (standard 4)
+  --seed N     hvilket oppgavesett (standard: et nytt, skrevet øverst)
+  --solutions  skriv løsningsforslagene etter oppgavene
+  --ask        samlebåndshasarder ved ledeteksten: hvilke instruksjoner
+               venter, og hvor mange sykler, uten og med forwarding; hvert
+               svar sjekkes og samlebåndsdiagrammet vises
+
+Samme frø, antall og emner gir alltid de samme oppgavene, så et sett kan
+deles ut og løsningene skrives ut etterpå. Løsningene regnes ut av
+simulatorene i dette arbeidsområdet.
+";
+
+struct Quiz {
+    seed: u64,
+    command: String,
+    problems: Vec<Problem>,
+    solutions: bool,
+}
+
+fn indent(text: &str) -> String {
+    text.lines().map(|l| if l.is_empty() { "\n".to_string() } else { format!("   {}\n", l) }).collect()
+}
+
+impl Report for Quiz {
+    fn text(&self) -> String {
+        let mut s = format!("{}\n", fill(tr("TDT4160 problem set {}  ({})"), &[&self.seed, &self.command]));
+        for (i, p) in self.problems.iter().enumerate() {
+            let _ = write!(s, "\n{}\n{}", fill(tr("Problem {} ({})"), &[&(i + 1), &p.topic.title()]), indent(&p.question));
+        }
+        if self.solutions {
+            let _ = writeln!(s, "\n{}", tr("==== Solutions ===="));
+            for (i, p) in self.problems.iter().enumerate() {
+                let _ = write!(s, "\n{}\n{}\n   {} {}\n", fill(tr("Solution {}"), &[&(i + 1)]), indent(&p.solution).trim_end(),
+                    tr("Answer:"), p.answer);
+            }
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        Json::object()
+            .with("seed", self.seed)
+            .with("problems", Json::array(self.problems.iter().map(|p| {
+                let problem = Json::object().with("topic", p.topic.name()).with("question", p.question.as_str());
+                if self.solutions {
+                    problem.with("solution", p.solution.as_str()).with("answer", p.answer.as_str())
+                } else {
+                    problem
+                }
+            })))
+    }
+}
+
+// ==== --ask ====
+
+/// "2 4", "2,4", "-" or "none": instruction numbers, sorted.
+fn numbers(answer: &str) -> Option<Vec<usize>> {
+    if matches!(answer, "-" | "none" | "ingen") {
+        return Some(Vec::new());
+    }
+    let mut list: Vec<usize> = answer.split([',', ' ']).filter(|n| !n.is_empty()).map(|n| n.parse().ok()).collect::<Option<_>>()?;
+    list.sort_unstable();
+    list.dedup();
+    Some(list)
+}
+
+/// "instructions 2 4 stall", "instruction 3 stalls", "no instruction stalls"
+fn stalling(list: &[usize]) -> String {
+    let numbers: Vec<String> = list.iter().map(|n| n.to_string()).collect();
+    match list.len() {
+        0 => tr("no instruction stalls").to_string(),
+        1 => fill(tr("instruction {} stalls"), &[&numbers[0]]),
+        _ => fill(tr("instructions {} stall"), &[&numbers.join(" ")]),
+    }
+}
+
+/// Ask until the answer parses; None at the end of input.
+fn prompt<T>(session: &mut Session, question: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    loop {
+        let answer = session.prompt(question)?;
+        match parse(&answer) {
+            Some(v) => return Some(v),
+            None => session.print(&format!("  {}\n", fill(tr("'{}' is not an answer to that"), &[&answer]))),
+        }
+    }
+}
+
+/// The pipeline problems of `seed` one at a time: the answers, checked
+/// against the schedules, then the diagram that explains them. The same
+/// seed and count give the problems of `--topic pipeline`.
+fn ask(seed: u64, count: usize, command: &str) -> Result<i32, String> {
+    let mut session = Session::live();
+    session.print(&format!("{}\n\n{}", fill(tr("TDT4160 pipeline quiz {}  ({})"), &[&seed, &command]), tr(INTRO)));
+    let mut rng = Rng::new(seed);
+    let (mut right, mut asked) = (0, 0);
+    'problems: for i in 0..count {
+        let hazards = Hazards::generate(&mut rng);
+        session.print(&format!("\n{}\n{}", fill(tr("Problem {} of {}"), &[&(i + 1), &count]), hazards.listing()));
+        for forwarding in [false, true] {
+            let mode = tr(if forwarding { "with forwarding" } else { "without forwarding" });
+            let Some(stalled) = prompt(&mut session, &format!("\n{}: {}", mode, tr("which instructions stall")), numbers) else { break 'problems };
+            let Some(stalls) = prompt(&mut session, tr("how many stall cycles in all"), |a| a.parse::<u32>().ok()) else { break 'problems };
+            let (want, total) = (hazards.stalled(forwarding), hazards.schedule(forwarding).stalls());
+            let mut verdict = String::new();
+            for (ok, answer) in [(stalled == want, stalling(&want)), (stalls == total, quizgen::count(total, "stall cycle"))] {
+                right += usize::from(ok);
+                asked += 1;
+                let _ = writeln!(verdict, "  {}: {}", tr(if ok { "right" } else { "wrong" }), answer);
+            }
+            session.print(&format!("{}\n{}", verdict, hazards.explain(forwarding)));
+        }
+    }
+    session.print(&format!("\n{}\n", fill(tr("{} of {} answers right"), &[&right, &asked])));
+    Ok(0)
+}
+
+/// More problems than any exam set, few enough to generate at once.
+const MAX_COUNT: usize = 1000;
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    let mut args = Args::parse(args, &["--topic", "--count", "--seed", "--format"])?;
+    let ask_mode = args.flag(&["--ask"]);
+    let solutions = args.flag(&["--solutions"]);
+    if ask_mode && (solutions || args.string("--format").is_some() || args.string("--topic").is_some_and(|t| t != "pipeline")) {
+        return Err("--ask explains each answer itself, and only asks pipeline problems: no --solutions, --format or other --topic".to_string());
+    }
+    let format = Format::from_args(&mut args)?;
+    let count = args.value::<usize>("--count")?.unwrap_or(4);
+    let seed = args.value::<u64>("--seed")?;
+    let topics = match args.string("--topic") {
+        None => Topic::ALL.to_vec(),
+        Some(list) => list.split(',').map(|t| Topic::parse(t.trim()).ok_or_else(|| format!("no topic '{}'", t))).collect::<Result<_, _>>()?,
+    };
+    args.finish()?;
+    if !(1..=MAX_COUNT).contains(&count) {
+        return Err(format!("--count must be 1 to {}", MAX_COUNT));
+    }
+    // a short number that is easy to write on the board
+    let seed = seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map_or(1, |d| d.subsec_nanos() as u64 % 100_000));
+    if ask_mode {
+        return ask(seed, count, &format!("tdt4160 quiz --ask --seed {} --count {}", seed, count));
+    }
+    let names: Vec<&str> = topics.iter().map(|t| t.name()).collect();
+    let command = format!("tdt4160 quiz --seed {} --count {} --topic {}", seed, count, names.join(","));
+    format.print(&Quiz { seed, command, problems: quizgen::problem_set(seed, count, &topics), solutions });
+    Ok(0)
+}
+
+// ==================== NORSK ====================
+
+const INTRO: &str = "\
+The classic five-stage pipeline, IF ID EX MEM WB: registers are written in
+the first half of a cycle and read in the second, and an instruction that
+waits for an operand stalls in ID. Give instruction numbers as 2 4, or -.
+";
+
+const INTRO_NO: &str = "\
+Det klassiske samlebåndet med fem steg, IF ID EX MEM WB: registre skrives i
+første halvdel av en syklus og leses i andre, og en instruksjon som venter
+på en operand, stopper i ID. Oppgi instruksjonsnumre som 2 4, eller -.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+const NO: Catalog = &[
+    ("TDT4160 problem set {}  ({})", "TDT4160 oppgavesett {}  ({})"),
+    ("Problem {} ({})", "Oppgave {} ({})"),
+    ("==== Solutions ====", "==== Løsningsforslag ===="),
+    ("Solution {}", "Løsning {}"),
+    ("Answer:", "Svar:"),
+    ("no instruction stalls", "ingen instruksjon stopper"),
+    ("instruction {} stalls", "instruksjon {} stopper"),
+    ("instructions {} stall", "instruksjonene {} stopper"),
+    ("'{}' is not an answer to that", "'{}' er ikke et svar på det"),
+    ("TDT4160 pipeline quiz {}  ({})", "TDT4160 samlebåndsquiz {}  ({})"),
+    (INTRO, INTRO_NO),
+    ("Problem {} of {}", "Oppgave {} av {}"),
+    ("with forwarding", "med forwarding"),
+    ("without forwarding", "uten forwarding"),
+    ("which instructions stall", "hvilke instruksjoner stopper"),
+    ("how many stall cycles in all", "hvor mange stoppsykluser i alt"),
+    ("right", "riktig"),
+    ("wrong", "feil"),
+    ("{} of {} answers right", "{} av {} svar riktige"),
+];
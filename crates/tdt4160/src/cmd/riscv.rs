@@ -0,0 +1,871 @@
+//! The RV32IM emulator on a program of your own: an ELF from the
+//! riscv32 toolchain, or a flat binary of instruction words, the
+//! five-stage pipeline timing of the instructions it executes, and its
+//! call stack drawn frame by frame; and x86-64 encodings taken apart
+//! next to the RV32 ones for the same operations.
+
+use std::fs;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver};
+
+use ::riscv::device::{Device, Ram, Timer, Uart};
+use ::riscv::elf::{self, Program};
+use ::riscv::frames::CallStack;
+use ::riscv::instruction::{Class, ABI_NAMES};
+use ::riscv::pipeline::Schedule;
+use ::riscv::syscall::{self, Outcome};
+use ::riscv::x86::{self, Comparison, Decoded};
+use ::riscv::{disassemble, Cpu, Instruction, Trap};
+use util::args::{self, Args};
+use util::lang::{self, fill, Catalog};
+use util::log::{self, Level};
+use util::report::{Format, Json, Report};
+use util::table::{Align, Table};
+
+use crate::config::{self, Cost};
+
+pub const USAGE: &str = "\
+usage: tdt4160 riscv run <program> [--max N] [--trace] [--base ADDR] [--stack BYTES]
+                         [-v|-vv|-q]
+       tdt4160 riscv disasm <program> [--base ADDR]
+       tdt4160 riscv pipeline <program> [--max N] [--no-forwarding] [--base ADDR]
+       tdt4160 riscv frames <program> [--at SYMBOL|ADDR] [--hit N] [--max N]
+       tdt4160 riscv x86 [BYTES ...]
+       (all take --config FILE and --format text|json|markdown|latex)
+
+  program      an RV32 ELF executable, or a raw little-endian binary
+  --max N      stop after N instructions (default 10M; for pipeline 12)
+  --trace, -v  print every instruction as it executes
+  -vv          and the registers each one wrote
+  -q           drop the program's output: only how the run ended
+  --base ADDR  load address of a raw binary (default 0x1000)
+  --stack B    stack size above the program (default 64K)
+  --no-forwarding  pipeline without the forwarding paths
+  --at WHERE   frames: stop before the function or address, ebreak otherwise
+  --hit N      the Nth time it gets there (default 1)
+  --config FILE    the machine's memory map, devices, pipeline options
+                   and cost model, from a TOML file (see `tdt4160
+                   config`); the options above override it
+
+pipeline draws the five-stage diagram of the first instructions the
+program executes, with the data-hazard stalls, as if every branch were
+predicted right; with -q only the totals. With a [cost] in the config,
+run adds up the cycles of the instructions it retired, by class, and the
+time at the clock given. The config's [[device]]s are on the bus at
+their base: a byte stored to a UART's +0 is output as the program's, -v
+reports each interrupt line as it is raised. frames draws the call
+stack where the run stopped: each frame's arguments, saved ra and
+s-registers and locals, as the stores and sp adjustments of the calling
+convention laid them out.
+
+x86 sets the same operations side by side as x86-64 and as RV32IM code:
+the bytes each takes, the parts of each x86 instruction (prefixes, REX,
+opcode, ModRM, SIB, displacement, immediate) and how many of its bytes
+a decoder reads before it knows the length - RV32 knows it from two
+bits. Given hex bytes (`x86 66 c7 03 34 12`), it takes those apart field
+by field instead.
+
+ecall provides write (64) and exit (93) as on Linux, and the RARS calls
+print_int (1), print_string (4), exit (10) and print_char (11).
+The exit code is the program's; ebreak stops with 0. As JSON, the
+program's output, the registers and the trace are fields of the result.
+";
+
+pub const USAGE_NO: &str = "\
+bruk: tdt4160 riscv run <program> [--max N] [--trace] [--base ADR] [--stack BYTE]
+                        [-v|-vv|-q]
+      tdt4160 riscv disasm <program> [--base ADR]
+      tdt4160 riscv pipeline <program> [--max N] [--no-forwarding] [--base ADR]
+      tdt4160 riscv frames <program> [--at SYMBOL|ADR] [--hit N] [--max N]
+      tdt4160 riscv x86 [BYTE ...]
+      (alle tar --config FIL og --format text|json|markdown|latex)
+
+  program      en kjørbar RV32 ELF-fil, eller en rå little-endian binærfil
+  --max N      stopp etter N instruksjoner (standard 10M; for pipeline 12)
+  --trace, -v  skriv ut hver instruksjon når den utføres
+  -vv          og registrene hver av dem skrev
+  -q           dropp programmets utdata: bare hvordan kjøringen endte
+  --base ADR   lasteadressen til en rå binærfil (standard 0x1000)
+  --stack B    stakkstørrelse over programmet (standard 64K)
+  --no-forwarding  samlebånd uten forbikoblingene
+  --at HVOR    frames: stopp før funksjonen eller adressen, ellers ved ebreak
+  --hit N      den N-te gangen den kommer dit (standard 1)
+  --config FIL     maskinens minnekart, enheter, samlebåndsvalg og
+                   kostnadsmodell, fra en TOML-fil (se `tdt4160 config`);
+                   valgene over overstyrer den
+
+pipeline tegner femstegsdiagrammet for de første instruksjonene programmet
+utfører, med stoppene for dataavhengigheter, som om hvert hopp ble
+forutsagt riktig; med -q bare summene. Med en [cost] i oppsettet summerer
+run syklusene til instruksjonene den utførte, etter klasse, og tiden ved
+klokken som er gitt. Oppsettets [[device]]-er ligger på bussen ved
+basen sin: en byte lagret til +0 i en UART blir utdata som programmets,
+og -v melder hver avbruddslinje når den heves. frames tegner kallstakken
+der kjøringen stoppet: argumentene, lagret ra og s-registre og de lokale
+variablene i hver ramme, slik lagringene og sp-justeringene i
+kallkonvensjonen la dem ut.
+
+x86 setter de samme operasjonene side om side som x86-64- og RV32IM-kode:
+bytene hver tar, delene av hver x86-instruksjon (prefikser, REX,
+opkode, ModRM, SIB, forskyvning, umiddelbar verdi) og hvor mange av
+bytene en dekoder leser før den kjenner lengden - RV32 kjenner den fra
+to bit. Gitt heksadesimale byte (`x86 66 c7 03 34 12`) tar den i stedet
+dem fra hverandre felt for felt.
+
+ecall gir write (64) og exit (93) som på Linux, og RARS-kallene
+print_int (1), print_string (4), exit (10) og print_char (11).
+Avslutningskoden er programmets; ebreak stopper med 0. Som JSON er
+programmets utdata, registrene og sporet felter i resultatet.
+";
+
+/// How a run ended.
+enum Stop {
+    Exit(i32),
+    Ebreak,
+    Limit,
+    Trap(String),
+}
+
+struct Run<'a> {
+    program: &'a Program,
+    stop: Stop,
+    retired: u64,
+    pc: u32,
+    regs: [u32; 32],
+    /// What the program wrote, when it is not going straight to stdout.
+    output: Vec<u8>,
+    /// (pc, word) of every instruction, with --trace.
+    trace: Vec<(u32, u32)>,
+    /// Instructions retired in each class, in the order of Class::ALL.
+    classes: [u64; 8],
+    cost: Option<Cost>,
+}
+
+impl Run<'_> {
+    fn code(&self) -> i32 {
+        match self.stop {
+            Stop::Exit(code) => code,
+            Stop::Ebreak => 0,
+            _ => 1,
+        }
+    }
+
+    /// (class, count, cycles) of each class that ran, and the total cycles.
+    fn cycles(&self, cost: &Cost) -> (Vec<(Class, u64, f64)>, f64) {
+        let rows: Vec<(Class, u64, f64)> = Class::ALL.iter().zip(self.classes)
+            .filter(|&(_, n)| n > 0)
+            .map(|(&c, n)| (c, n, n as f64 * cost.cycles(c)))
+            .collect();
+        let total = rows.iter().map(|r| r.2).sum();
+        (rows, total)
+    }
+
+    /// "1234 cycles, CPI 1.52, 12.34 µs at 100 MHz"
+    fn cost_summary(&self, cost: &Cost) -> String {
+        let (_, total) = self.cycles(cost);
+        let mut s = format!("{} cycles, CPI {:.2}", total, total / self.retired.max(1) as f64);
+        if let Some(mhz) = cost.clock_mhz {
+            s += &format!(", {:.2} µs at {} MHz", total / mhz, mhz);
+        }
+        s
+    }
+}
+
+impl Report for Run<'_> {
+    /// Only what comes after the program's output.
+    fn text(&self) -> String {
+        let stop = match &self.stop {
+            Stop::Limit => format!("stopped after {} instructions at {}\n", self.retired, self.program.describe(self.pc)),
+            Stop::Trap(e) => format!("{}\n", e),
+            _ => String::new(),
+        };
+        match &self.cost {
+            Some(cost) => format!("{}[{} instructions retired, {}]\n", stop, self.retired, self.cost_summary(cost)),
+            None => format!("{}[{} instructions retired]\n", stop, self.retired),
+        }
+    }
+
+    fn json(&self) -> Json {
+        let (stop, detail) = match &self.stop {
+            Stop::Exit(_) => ("exit", None),
+            Stop::Ebreak => ("ebreak", None),
+            Stop::Limit => ("limit", None),
+            Stop::Trap(e) => ("trap", Some(e.as_str())),
+        };
+        let mut j = Json::object()
+            .with("stop", stop)
+            .with("error", detail)
+            .with("exit_code", self.code())
+            .with("retired", self.retired)
+            .with("pc", self.pc)
+            .with("where", self.program.describe(self.pc))
+            .with("registers", Json::array(self.regs))
+            .with("output", String::from_utf8_lossy(&self.output).into_owned());
+        if let Some(cost) = &self.cost {
+            let (rows, total) = self.cycles(cost);
+            j = j.with("cost", Json::object()
+                .with("classes", Json::array(rows.iter().map(|&(c, n, cycles)| Json::object()
+                    .with("class", c.name()).with("count", n).with("cycles", cycles))))
+                .with("cycles", total)
+                .with("cpi", total / self.retired.max(1) as f64)
+                .with("clock_mhz", cost.clock_mhz)
+                .with("microseconds", cost.clock_mhz.map(|mhz| total / mhz)));
+        }
+        if !self.trace.is_empty() {
+            j = j.with("trace", Json::array(self.trace.iter().map(|&(pc, w)| line(self.program, pc, w))));
+        }
+        j
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        let mut result = Table::new("Run", ["Result", ""]);
+        result.row(["Stopped by".to_string(), match &self.stop {
+            Stop::Exit(code) => format!("exit {}", code),
+            Stop::Ebreak => "ebreak".to_string(),
+            Stop::Limit => "instruction limit".to_string(),
+            Stop::Trap(e) => e.clone(),
+        }]);
+        result.row(["Instructions retired".to_string(), self.retired.to_string()]);
+        result.row(["PC".to_string(), self.program.describe(self.pc)]);
+        if !self.output.is_empty() {
+            result.row(["Output".to_string(), String::from_utf8_lossy(&self.output).trim_end().replace('\n', " / ")]);
+        }
+        let mut regs = Table::new("Registers that are not zero", ["Register", "Hex", "Decimal"]).align(1.., Align::Right);
+        for (r, &v) in self.regs.iter().enumerate().filter(|(_, &v)| v != 0) {
+            regs.row([format!("x{} ({})", r, ABI_NAMES[r]), format!("0x{:08x}", v), (v as i32).to_string()]);
+        }
+        let mut tables = vec![result, regs];
+        if let Some(cost) = &self.cost {
+            let (rows, total) = self.cycles(cost);
+            let mut t = Table::new("Cost model", ["Class", "Instructions", "Cycles each", "Cycles"]).align(1.., Align::Right);
+            for (c, n, cycles) in rows {
+                t.row([c.name().to_string(), n.to_string(), cost.cycles(c).to_string(), cycles.to_string()]);
+            }
+            t.row(["total".to_string(), self.retired.to_string(), format!("CPI {:.2}", total / self.retired.max(1) as f64), total.to_string()]);
+            if let Some(mhz) = cost.clock_mhz {
+                t.row([format!("at {} MHz", mhz), String::new(), String::new(), format!("{:.2} µs", total / mhz)]);
+            }
+            tables.push(t);
+        }
+        if !self.trace.is_empty() {
+            tables.push(listing("Trace", self.program, self.trace.iter().copied()));
+        }
+        tables
+    }
+}
+
+/// Address, word and instruction, the symbol where there is one.
+fn listing(caption: &str, program: &Program, words: impl Iterator<Item = (u32, u32)>) -> Table {
+    let mut t = Table::new(caption, ["Address", "Word", "Instruction", "Symbol"]);
+    for (addr, w) in words {
+        let symbol = program.symbol_at(addr).map_or(String::new(), |_| program.describe(addr));
+        t.row([format!("{:08x}", addr), format!("{:08x}", w), disassemble(w), symbol]);
+    }
+    t
+}
+
+fn line(program: &Program, addr: u32, word: u32) -> Json {
+    Json::object()
+        .with("addr", addr)
+        .with("word", word)
+        .with("symbol", program.symbol_at(addr).map(|_| program.describe(addr)))
+        .with("text", disassemble(word))
+}
+
+struct Listing<'a>(&'a Program);
+
+impl Listing<'_> {
+    fn words(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.0.segments.iter().filter(|s| s.executable).flat_map(|segment| {
+            segment.data.chunks_exact(4).enumerate().map(move |(i, w)| {
+                (segment.vaddr + 4 * i as u32, u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+            })
+        })
+    }
+}
+
+impl Report for Listing<'_> {
+    fn text(&self) -> String {
+        let mut s = String::new();
+        for (addr, w) in self.words() {
+            if let Some((symbol, 0)) = self.0.symbol_at(addr) {
+                let _ = writeln!(s, "\n{:08x} <{}>:", addr, symbol.name);
+            }
+            let _ = writeln!(s, "  {:08x}:  {:08x}  {}", addr, w, disassemble(w));
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        Json::array(self.words().map(|(addr, w)| line(self.0, addr, w)))
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        vec![listing("Disassembly", self.0, self.words())]
+    }
+}
+
+/// The first instructions of a run through the five-stage pipeline, and
+/// the same code with the forwarding paths switched the other way.
+struct Timing {
+    schedule: Schedule,
+    other: Schedule,
+}
+
+fn totals(s: &Schedule) -> (u32, u32, f64) {
+    (s.stalls(), s.cycles(), s.cycles() as f64 / s.slots.len().max(1) as f64)
+}
+
+fn forwarding(s: &Schedule) -> &'static str {
+    tr(if s.forwarding { "with forwarding" } else { "without forwarding" })
+}
+
+impl Timing {
+    /// (index, instruction, index of the producer, register, stalls) of
+    /// each instruction that waited.
+    fn hazards(&self) -> impl Iterator<Item = (usize, String, usize, u8, u32)> + '_ {
+        let slots = &self.schedule.slots;
+        slots.iter().enumerate().filter_map(move |(i, s)| {
+            s.waits_for.map(|(from, r)| (i, s.instruction.to_string(), from, r, s.stalls))
+        })
+    }
+}
+
+impl Report for Timing {
+    fn text(&self) -> String {
+        let quiet = !log::enabled(Level::Normal);
+        let mut s = if quiet { String::new() } else { self.schedule.diagram() };
+        let slots = &self.schedule.slots;
+        for (_, text, from, r, stalls) in self.hazards().filter(|_| !quiet) {
+            let wait = if stalls == 1 { tr("1 cycle").to_string() } else { fill(tr("{} cycles"), &[&stalls]) };
+            let _ = writeln!(s, "  {}", fill(tr("{} waits {} for {} from {}"), &[&text, &wait, &ABI_NAMES[r as usize], &slots[from].instruction]));
+        }
+        for schedule in [&self.schedule, &self.other] {
+            let (stalls, cycles, cpi) = totals(schedule);
+            let _ = writeln!(s, "{:<19} {}", forwarding(schedule), fill(tr("{} instructions, {} stalls, {} cycles, {} cycles per instruction"),
+                &[&slots.len(), &stalls, &cycles, &format!("{:.2}", cpi)]));
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        let summary = |s: &Schedule| {
+            let (stalls, cycles, cpi) = totals(s);
+            Json::object().with("forwarding", s.forwarding).with("stalls", stalls).with("cycles", cycles).with("cpi", cpi)
+        };
+        summary(&self.schedule)
+            .with("instructions", Json::array(self.schedule.slots.iter().map(|s| Json::object()
+                .with("text", s.instruction.to_string())
+                .with("fetch", s.fetch)
+                .with("decode", s.decode)
+                .with("stalls", s.stalls)
+                .with("waits_for", s.waits_for.map(|(i, _)| i))
+                .with("register", s.waits_for.map(|(_, r)| ABI_NAMES[r as usize])))))
+            .with("other", summary(&self.other))
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        let cycles = self.schedule.cycles();
+        let caption = fill(tr("Pipeline diagram, {}"), &[&forwarding(&self.schedule)]);
+        let header = std::iter::once(tr("Instruction").to_string()).chain((1..=cycles).map(|c| c.to_string()));
+        let mut diagram = Table::new(&caption, header).align(1.., Align::Center);
+        for slot in &self.schedule.slots {
+            diagram.row(std::iter::once(slot.instruction.to_string()).chain(slot.stages().into_iter().map(String::from)));
+        }
+        let mut hazards = Table::new(tr("Data hazards"), ["Instruction", "Waits for", "Register", "Stalls"].map(tr)).align(3.., Align::Right);
+        for (i, text, from, r, stalls) in self.hazards() {
+            hazards.row([format!("{}: {}", i + 1, text), format!("{}: {}", from + 1, self.schedule.slots[from].instruction),
+                ABI_NAMES[r as usize].to_string(), stalls.to_string()]);
+        }
+        let mut summary = Table::new(tr("Cycles"), ["Pipeline", "Instructions", "Stalls", "Cycles", "CPI"].map(tr)).align(1.., Align::Right);
+        for schedule in [&self.schedule, &self.other] {
+            let (stalls, cycles, cpi) = totals(schedule);
+            summary.row([forwarding(schedule).to_string(), schedule.slots.len().to_string(), stalls.to_string(), cycles.to_string(), format!("{:.2}", cpi)]);
+        }
+        let mut tables = vec![diagram];
+        if !hazards.rows.is_empty() {
+            tables.push(hazards);
+        }
+        tables.push(summary);
+        tables
+    }
+}
+
+/// The call stack where a run stopped.
+struct Frames<'a> {
+    program: &'a Program,
+    cpu: Cpu,
+    stack: CallStack,
+    /// "before fact, hit 3", "by ebreak at main+0x10", "by exit 0"
+    stop: String,
+}
+
+impl Report for Frames<'_> {
+    fn text(&self) -> String {
+        let n = self.stack.frames.len();
+        format!("stopped {}, {} instructions retired, {} frame{}:\n{}", self.stop, self.cpu.retired, n, if n == 1 { "" } else { "s" },
+            self.stack.diagram(&self.cpu, self.program))
+    }
+
+    fn json(&self) -> Json {
+        let frames = self.stack.frames.iter().zip(self.stack.bottoms(&self.cpu)).map(|(f, bottom)| Json::object()
+            .with("function", f.function)
+            .with("name", self.program.describe(f.function))
+            .with("call", f.call)
+            .with("returns_to", f.call.map(|_| f.returns_to))
+            .with("top", f.top)
+            .with("sp", bottom)
+            .with("fp", f.fp)
+            .with("args", Json::array(f.args))
+            .with("slots", Json::array(f.slots.iter().map(|&(addr, slot)| {
+                let word = self.cpu.mem.read(addr, 4);
+                Json::object().with("addr", addr).with("value", word).with("slot", slot.describe(word.unwrap_or(0), self.program))
+            }))));
+        Json::object()
+            .with("stop", self.stop.as_str())
+            .with("pc", self.cpu.pc)
+            .with("where", self.program.describe(self.cpu.pc))
+            .with("retired", self.cpu.retired)
+            .with("frames", Json::array(frames))
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        self.stack.frames.iter().enumerate().map(|(depth, f)| {
+            let mut t = Table::new(&format!("#{} {}", depth, self.stack.heading(depth, self.program)), ["Address", "Value", "Slot"]);
+            let mut slots = f.slots.clone();
+            slots.sort_by_key(|s| std::cmp::Reverse(s.0));
+            for (addr, slot) in slots {
+                let word = self.cpu.mem.read(addr, 4).unwrap_or(0);
+                t.row([format!("0x{:08x}", addr), format!("0x{:08x}", word), slot.describe(word, self.program)]);
+            }
+            if t.rows.is_empty() {
+                t.row(["", "", "nothing stored yet"]);
+            }
+            t
+        }).collect()
+    }
+}
+
+/// Run until pc gets to `at` for the `hit`th time, or the program stops.
+fn frames<'a>(program: &'a Program, mut cpu: Cpu, at: Option<u32>, hit: u64, max: u64) -> Result<Frames<'a>, String> {
+    let mut stack = CallStack::new(&cpu);
+    let mut hits = 0;
+    let stop = loop {
+        if Some(cpu.pc) == at {
+            hits += 1;
+            if hits == hit {
+                break format!("before {}, hit {}", program.describe(cpu.pc), hits);
+            }
+        }
+        if cpu.retired >= max {
+            break format!("after {} instructions at {}", max, program.describe(cpu.pc));
+        }
+        let pc = cpu.pc;
+        match cpu.step() {
+            Ok(instruction) => stack.record(pc, instruction, &cpu),
+            Err(Trap::Ecall(pc)) => match syscall::dispatch(&mut cpu, &mut |_| {}) {
+                Outcome::Continue => {}
+                Outcome::Exit(code) => break format!("by exit {}", code),
+                Outcome::Unknown(n) => return Err(format!("unknown ecall {} at {}", n, program.describe(pc))),
+            },
+            Err(Trap::Breakpoint(pc)) => break format!("by ebreak at {}", program.describe(pc)),
+            Err(trap) => return Err(format!("trap: {} in {}", trap, program.describe(cpu.pc))),
+        }
+    };
+    Ok(Frames { program, cpu, stack, stop })
+}
+
+/// x86-64 and RV32IM side by side: `riscv::x86::comparisons`.
+struct Isas(Vec<Comparison>);
+
+/// (x86 instructions, RV32 instructions) of one comparison.
+fn sides(c: &Comparison) -> (Vec<Decoded>, &[Instruction]) {
+    (x86::decode_all(&c.x86).unwrap_or_default(), &c.rv32)
+}
+
+impl Isas {
+    /// x86 bytes, the fewest and most bytes a length was known after, RV32 bytes.
+    fn totals(&self) -> (usize, usize, usize, usize) {
+        let decoded: Vec<Decoded> = self.0.iter().flat_map(|c| sides(c).0).collect();
+        let after = decoded.iter().map(Decoded::length_known_after);
+        let rv32 = self.0.iter().map(|c| 4 * c.rv32.len()).sum();
+        (decoded.iter().map(Decoded::length).sum(), after.clone().min().unwrap_or(0), after.max().unwrap_or(0), rv32)
+    }
+
+    fn table(&self) -> Table {
+        let mut t = Table::new(tr("x86-64 and RV32IM"), ["Operation", "x86-64", "Bytes", "Parts", "Known after", "RV32IM", "Bytes"].map(tr))
+            .align(2..3, Align::Right).align(4..5, Align::Right).align(6.., Align::Right);
+        for c in &self.0 {
+            let (x86, rv32) = sides(c);
+            for i in 0..x86.len().max(rv32.len()) {
+                let (text, bytes, parts, after) = match x86.get(i) {
+                    Some(d) => (d.text.clone(), d.length().to_string(), d.parts(), d.length_known_after().to_string()),
+                    None => Default::default(),
+                };
+                let (rv, four) = rv32.get(i).map_or((String::new(), ""), |r| (r.to_string(), "4"));
+                t.row([if i == 0 { tr(c.operation) } else { "" }.to_string(), text, bytes, parts, after, rv, four.to_string()]);
+            }
+        }
+        t
+    }
+}
+
+impl Report for Isas {
+    fn text(&self) -> String {
+        let (x86, fewest, most, rv32) = self.totals();
+        let x86_count: usize = self.0.iter().map(|c| sides(c).0.len()).sum();
+        let rv32_count: usize = self.0.iter().map(|c| c.rv32.len()).sum();
+        let mut s = self.table().text();
+        let _ = writeln!(s, "\nx86-64  {}", fill(tr("{} instructions, {} bytes; each length known after {} to {} of its bytes"),
+            &[&x86_count, &x86, &fewest, &most]));
+        let _ = writeln!(s, "RV32IM  {}", fill(tr("{} instructions, {} bytes; each length known from the low 2 bits of the first"), &[&rv32_count, &rv32]));
+        let _ = writeln!(s, "{}", tr("(`tdt4160 riscv x86 66 c7 03 34 12` takes an x86 instruction apart field by field)"));
+        s
+    }
+
+    fn json(&self) -> Json {
+        let (x86, _, _, rv32) = self.totals();
+        Json::object()
+            .with("operations", Json::array(self.0.iter().map(|c| {
+                let (decoded, rv) = sides(c);
+                Json::object()
+                    .with("operation", c.operation)
+                    .with("x86", Json::array(decoded.iter().map(instruction)))
+                    .with("rv32", Json::array(rv.iter().map(|r| Json::object().with("text", r.to_string()).with("word", r.encode()))))
+            })))
+            .with("x86_bytes", x86)
+            .with("rv32_bytes", rv32)
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        vec![self.table()]
+    }
+}
+
+fn instruction(d: &Decoded) -> Json {
+    Json::object()
+        .with("text", d.text.as_str())
+        .with("bytes", hex_bytes(&d.bytes()))
+        .with("parts", d.parts())
+        .with("length", d.length())
+        .with("known_after", d.length_known_after())
+        .with("fields", Json::array(d.fields.iter().map(|f| Json::object()
+            .with("part", f.part.name()).with("bytes", hex_bytes(&f.bytes)).with("meaning", f.meaning.as_str()))))
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ")
+}
+
+/// x86 bytes given on the command line, taken apart.
+struct Breakdown(Vec<Decoded>);
+
+impl Breakdown {
+    fn table(d: &Decoded, at: usize) -> Table {
+        let n = d.length();
+        let caption = fill(tr(if n == 1 { "+{}: {} - {} byte, the length known after {}" } else { "+{}: {} - {} bytes, the length known after {}" }),
+            &[&at, &d.text, &n, &d.length_known_after()]);
+        let mut t = Table::new(&caption, ["Bytes", "Part", "Says"].map(tr));
+        for f in &d.fields {
+            t.row([hex_bytes(&f.bytes), f.part.name().to_string(), f.meaning.clone()]);
+        }
+        t
+    }
+
+    fn tables(&self) -> impl Iterator<Item = Table> + '_ {
+        self.0.iter().scan(0, |at, d| {
+            let t = Breakdown::table(d, *at);
+            *at += d.length();
+            Some(t)
+        })
+    }
+}
+
+impl Report for Breakdown {
+    fn text(&self) -> String {
+        self.tables().map(|t| t.text()).collect::<Vec<_>>().join("\n")
+    }
+
+    fn json(&self) -> Json {
+        Json::array(self.0.iter().map(instruction))
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        Breakdown::tables(self).collect()
+    }
+}
+
+/// "66 c7 03 34 12", "66c7033412" or "0x66, 0xc7 ...", in one or more arguments.
+fn parse_bytes(args: &[String]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    for arg in args {
+        for word in arg.split([' ', ',']).filter(|w| !w.is_empty()) {
+            let digits = word.trim_start_matches("0x");
+            if digits.len() % 2 != 0 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(format!("x86: '{}' is not hex bytes", word));
+            }
+            bytes.extend((0..digits.len()).step_by(2).map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap()));
+        }
+    }
+    Ok(bytes)
+}
+
+pub fn run(args: Vec<String>) -> Result<i32, String> {
+    let mut args = Args::parse(args, &["--max", "--base", "--stack", "--config", "--format", "--at", "--hit"])?;
+    let format = Format::from_args(&mut args)?;
+    let machine = args.string("--config").map(|path| config::load(&path)).transpose()?;
+    let max = args.number("--max")?;
+    let base = args.number("--base")?.unwrap_or(machine.as_ref().map_or(0x1000, |m| m.memory.base.into()));
+    let stack = args.number("--stack")?.unwrap_or(machine.as_ref().map_or(64 << 10, |m| m.memory.stack.into()));
+    let trace = args.flag(&["--trace"]) || log::enabled(Level::Verbose);
+    let no_forwarding = args.flag(&["--no-forwarding"]) || machine.as_ref().is_some_and(|m| !m.pipeline.forwarding);
+    let at = args.string("--at");
+    let hit = args.number("--hit")?.unwrap_or(1).max(1);
+    let rest = args.finish()?;
+    if rest.first().is_some_and(|a| a == "x86") {
+        let bytes = parse_bytes(&rest[1..])?;
+        if bytes.is_empty() {
+            format.print(&Isas(x86::comparisons()));
+        } else {
+            // the bytes were read; what they say is no usage error
+            match x86::decode_all(&bytes) {
+                Ok(decoded) => format.print(&Breakdown(decoded)),
+                Err(e) => {
+                    eprintln!("tdt4160 riscv: {}", e);
+                    return Ok(1);
+                }
+            }
+        }
+        return Ok(0);
+    }
+    let (action, path) = match rest.as_slice() {
+        [action, path] => (action.as_str(), path),
+        _ => return Err("expected run, disasm, pipeline or frames and a program, or x86".to_string()),
+    };
+    let base = u32::try_from(base).map_err(|_| "--base: not a 32-bit address".to_string())?;
+    let stack = u32::try_from(stack).map_err(|_| "--stack: too large".to_string())?;
+    let bytes = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    let program = if elf::is_elf(&bytes) {
+        Program::parse(&bytes).map_err(|e| format!("{}: {}", path, e))?
+    } else {
+        Program::raw(base, &bytes)
+    };
+    util::verbose!("{}: {}, entry 0x{:08x}, {} segments, {} symbols", path, if elf::is_elf(&bytes) { "ELF" } else { "raw binary" },
+        program.entry, program.segments.len(), program.symbols.len());
+    let devices = machine.as_ref().map_or(&[][..], |m| &m.devices[..]);
+    match action {
+        "run" => {
+            let (cpu, uart) = processor(&program, stack, devices)?;
+            let mut run = execute(&program, cpu, &uart, max.unwrap_or(10_000_000), trace, format);
+            run.cost = machine.and_then(|m| m.cost);
+            match format {
+                Format::Text => eprint!("{}", run.text()),
+                _ => format.print(&run),
+            }
+            Ok(run.code())
+        }
+        "disasm" => {
+            format.print(&Listing(&program));
+            Ok(0)
+        }
+        "pipeline" => {
+            let max = max.map_or(Ok(machine.as_ref().map_or(12, |m| m.pipeline.max)), usize::try_from).map_err(|_| "--max: too large".to_string())?;
+            let (cpu, _) = processor(&program, stack, devices)?;
+            let code = executed(&program, cpu, max)?;
+            let forwarding = !no_forwarding;
+            format.print(&Timing { schedule: Schedule::new(&code, forwarding), other: Schedule::new(&code, !forwarding) });
+            Ok(0)
+        }
+        "frames" => {
+            let at = match at {
+                None => None,
+                Some(at) => Some(match program.symbol(&at) {
+                    Some(symbol) => symbol.addr,
+                    None => args::parse_number(&at).ok().and_then(|a| u32::try_from(a).ok())
+                        .ok_or_else(|| format!("--at: no function '{}' and not an address", at))?,
+                }),
+            };
+            let (cpu, _) = processor(&program, stack, devices)?;
+            format.print(&frames(&program, cpu, at, hit, max.unwrap_or(10_000_000))?);
+            Ok(0)
+        }
+        other => Err(format!("unknown action '{}'", other)),
+    }
+}
+
+/// The first `max` instructions a run executes, in order, for the
+/// pipeline: ecalls are carried out and their output dropped.
+fn executed(program: &Program, mut cpu: Cpu, max: usize) -> Result<Vec<Instruction>, String> {
+    let mut code = Vec::new();
+    while code.len() < max {
+        match cpu.step() {
+            Ok(instruction) => code.push(instruction),
+            Err(Trap::Ecall(_)) => {
+                code.push(Instruction::Ecall);
+                if syscall::dispatch(&mut cpu, &mut |_| {}) != Outcome::Continue {
+                    break;
+                }
+            }
+            Err(Trap::Breakpoint(_)) => {
+                code.push(Instruction::Ebreak);
+                break;
+            }
+            Err(trap) => return Err(format!("trap: {} in {}", trap, program.describe(cpu.pc))),
+        }
+    }
+    Ok(code)
+}
+
+/// The program loaded, with the devices of --config on the bus; what the
+/// UARTs send comes out of the receiver.
+fn processor(program: &Program, stack: u32, devices: &[config::Device]) -> Result<(Cpu, Receiver<u8>), String> {
+    let mut cpu = program.cpu(stack);
+    let (tx, rx) = mpsc::channel();
+    for d in devices {
+        let device: Box<dyn Device> = match d.kind.as_str() {
+            "uart" => Box::new(Uart::new(&d.name, tx.clone(), d.input.as_bytes())),
+            "timer" => Box::new(Timer::new(&d.name)),
+            _ => Box::new(Ram::new(&d.name, d.size)),
+        };
+        cpu.attach(d.base, d.irq, device).map_err(|e| format!("--config: {}", e))?;
+    }
+    Ok((cpu, rx))
+}
+
+/// In text mode the program's output and the trace go to stdout as it
+/// runs; otherwise they are collected into the result.
+fn execute<'a>(program: &'a Program, mut cpu: Cpu, uart: &Receiver<u8>, max: u64, trace: bool, format: Format) -> Run<'a> {
+    let live = format == Format::Text;
+    let registers = live && log::enabled(Level::Debug);
+    let quiet = !log::enabled(Level::Normal);
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut output = Vec::new();
+    let mut steps = Vec::new();
+    let mut classes = [0; 8];
+    let mut count = |class: Class| classes[Class::ALL.iter().position(|&c| c == class).unwrap_or(0)] += 1;
+    let mut pending = 0;
+    let stop = loop {
+        if cpu.retired >= max {
+            break Stop::Limit;
+        }
+        if trace {
+            if let Some(w) = cpu.mem.read(cpu.pc, 4) {
+                if !live {
+                    steps.push((cpu.pc, w));
+                } else {
+                    let _ = match program.symbol_at(cpu.pc) {
+                        Some(_) => writeln!(out, "{:08x}  {:<20} {}", cpu.pc, program.describe(cpu.pc), disassemble(w)),
+                        None => writeln!(out, "{:08x}  {}", cpu.pc, disassemble(w)),
+                    };
+                }
+            }
+        }
+        let before = registers.then(|| (1..32u8).map(|r| cpu.reg(r)).collect::<Vec<_>>());
+        let step = cpu.step();
+        if let Some(before) = before {
+            for (r, old) in (1..32u8).zip(before).filter(|&(r, old)| cpu.reg(r) != old) {
+                let _ = writeln!(out, "{:>20}{:<4} = 0x{:08x} (was 0x{:08x})", "", ABI_NAMES[r as usize], cpu.reg(r), old);
+            }
+        }
+        let sent: Vec<u8> = uart.try_iter().collect();
+        if !quiet && !sent.is_empty() {
+            let _ = if live { out.write_all(&sent) } else { output.write_all(&sent) };
+        }
+        let raised = cpu.bus.pending() & !pending;
+        pending = cpu.bus.pending();
+        for (name, _, _, irq) in cpu.bus.devices() {
+            if let Some(n) = irq.filter(|&n| raised >> n & 1 != 0) {
+                util::verbose!("irq {} raised by {} after {} instructions, at {}", n, name, cpu.retired, program.describe(cpu.pc));
+            }
+        }
+        let trap = match step {
+            Ok(instruction) => {
+                count(instruction.class());
+                continue;
+            }
+            Err(trap) => trap,
+        };
+        let mut dropped = io::sink();
+        let sink: &mut dyn Write = if quiet { &mut dropped } else if live { &mut out } else { &mut output };
+        match trap {
+            Trap::Ecall(pc) => match syscall::handle(&mut cpu, sink) {
+                Ok(Outcome::Continue) => count(Class::System),
+                Ok(Outcome::Exit(code)) => {
+                    count(Class::System);
+                    break Stop::Exit(code);
+                }
+                Ok(Outcome::Unknown(n)) => break Stop::Trap(format!("unknown ecall {} at {}", n, program.describe(pc))),
+                Err(e) => break Stop::Trap(format!("output: {}", e)),
+            },
+            Trap::Breakpoint(_) => break Stop::Ebreak,
+            trap => break Stop::Trap(format!("trap: {} in {}", trap, program.describe(cpu.pc))),
+        }
+    };
+    let _ = out.flush();
+    let mut regs = [0; 32];
+    for (r, v) in regs.iter_mut().enumerate() {
+        *v = cpu.reg(r as u8);
+    }
+    Run { program, stop, retired: cpu.retired, pc: cpu.pc, regs, output, trace: steps, classes, cost: None }
+}
+
+// ==================== NORSK ====================
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+const NO: Catalog = &[
+    ("with forwarding", "med forwarding"),
+    ("without forwarding", "uten forwarding"),
+    ("1 cycle", "1 syklus"),
+    ("{} cycles", "{} sykluser"),
+    ("{} waits {} for {} from {}", "{} venter {} på {} fra {}"),
+    ("{} instructions, {} stalls, {} cycles, {} cycles per instruction", "{} instruksjoner, {} stopp, {} sykluser, {} sykluser per instruksjon"),
+    ("Pipeline diagram, {}", "Samlebåndsdiagram, {}"),
+    ("Instruction", "Instruksjon"),
+    ("Data hazards", "Datahasarder"),
+    ("Waits for", "Venter på"),
+    ("Register", "Register"),
+    ("Stalls", "Stopp"),
+    ("Cycles", "Sykluser"),
+    ("Pipeline", "Samlebånd"),
+    ("Instructions", "Instruksjoner"),
+    ("x86-64 and RV32IM", "x86-64 og RV32IM"),
+    ("Operation", "Operasjon"),
+    ("Bytes", "Byte"),
+    ("Parts", "Deler"),
+    ("Known after", "Kjent etter"),
+    ("{} instructions, {} bytes; each length known after {} to {} of its bytes",
+        "{} instruksjoner, {} byte; hver lengde kjent etter {} til {} av bytene"),
+    ("{} instructions, {} bytes; each length known from the low 2 bits of the first",
+        "{} instruksjoner, {} byte; hver lengde kjent fra de 2 laveste bitene i den første"),
+    ("(`tdt4160 riscv x86 66 c7 03 34 12` takes an x86 instruction apart field by field)",
+        "(`tdt4160 riscv x86 66 c7 03 34 12` tar en x86-instruksjon fra hverandre felt for felt)"),
+    ("+{}: {} - {} byte, the length known after {}", "+{}: {} - {} byte, lengden kjent etter {}"),
+    ("+{}: {} - {} bytes, the length known after {}", "+{}: {} - {} byte, lengden kjent etter {}"),
+    ("Part", "Del"),
+    ("Says", "Betyr"),
+    // riscv::x86::comparisons
+    ("add two registers", "legg sammen to registre"),
+    ("add a small constant", "legg til en liten konstant"),
+    ("add a 32-bit constant", "legg til en 32-bits konstant"),
+    ("load a 32-bit constant", "last en 32-bits konstant"),
+    ("registers 8 and up", "register 8 og oppover"),
+    ("multiply", "multipliser"),
+    ("load a word", "last et ord"),
+    ("load a byte, zero-extended", "last en byte, nullutvidet"),
+    ("load an array element", "last et tabellelement"),
+    ("store at a large offset", "lagre med stor offset"),
+    ("store a 16-bit constant", "lagre en 16-bits konstant"),
+    ("add a register to memory", "legg et register til minnet"),
+    ("compare and branch", "sammenlign og hopp"),
+    ("call a function", "kall en funksjon"),
+    ("return", "returner"),
+    ("push a register", "push et register"),
+    ("copy rcx bytes", "kopier rcx byte"),
+    ("system call", "systemkall"),
+    ("do nothing", "gjør ingenting"),
+];
@@ -0,0 +1,306 @@
+/*
+ * config - a machine described in a TOML file, for --config.
+ *
+ *   name = "Lab 3"                 [[cache]]              [pipeline]
+ *                                  name = "L1"            forwarding = false
+ *   [memory]                       size = "1K"            max = 20
+ *   base = 0x1000                  ways = 2
+ *   stack = "64K"                  hit_time = 1           [cost]
+ *   latency = 100                                         clock_mhz = 100
+ *                                  [[cache]]              load = 2
+ *   [[device]]                     name = "L2"            div = 20
+ *   name = "uart0"                 size = "16K"
+ *   kind = "uart"                  line = 32
+ *   base = 0x1000_0000             hit_time = 10
+ *   irq = 1
+ *   input = "hello\n"
+ *
+ * A lab setup is then a file to hand out rather than a command line to
+ * copy: `riscv` takes the memory map, the pipeline options and the cost
+ * model (cycles per instruction class), `cache` the levels in front of
+ * memory, and `tdt4160 config` checks a file and prints what it says.
+ * Every key is optional but a cache's size, a device's kind and base,
+ * and a RAM device's size: a UART takes 8 bytes and a timer 16
+ * (riscv::device), and a UART may be given the input it receives. The
+ * file is checked as a whole before anything runs: a key that is
+ * misspelt, a value of the wrong type, a cache whose geometry does not
+ * work out, a stack past 4 GiB or devices on top of each other are errors
+ * with the line they are on (util::toml), all of them in the order of the
+ * file. A cache is only built once its numbers add up.
+ */
+
+use std::fs;
+
+use ::cache::hierarchy::Level;
+use ::cache::{Cache, Config, Replacement, WritePolicy};
+use ::riscv::instruction::Class;
+use util::toml::{self, Fields};
+
+#[derive(Clone, Debug)]
+pub struct Machine {
+    pub name: String,
+    pub memory: Memory,
+    pub devices: Vec<Device>,
+    /// L1 first; empty when the file has no [[cache]].
+    pub caches: Vec<Level>,
+    pub pipeline: Pipeline,
+    pub cost: Option<Cost>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Memory {
+    /// Load address of a raw binary.
+    pub base: u32,
+    pub stack: u32,
+    /// Cycles for an access to main memory.
+    pub latency: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Device {
+    pub name: String,
+    pub kind: String,
+    pub base: u32,
+    pub size: u32,
+    pub irq: Option<u8>,
+    /// What a UART receives.
+    pub input: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Pipeline {
+    pub forwarding: bool,
+    pub max: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Cost {
+    pub clock_mhz: Option<f64>,
+    /// Cycles of each class, in the order of Class::ALL.
+    pub cycles: [f64; 8],
+}
+
+impl Cost {
+    pub fn cycles(&self, class: Class) -> f64 {
+        self.cycles[Class::ALL.iter().position(|&c| c == class).unwrap_or(0)]
+    }
+}
+
+pub const DEVICE_KINDS: &[&str] = &["uart", "timer", "ram"];
+
+/// The machine in `path`; errors are "path:line: message", one line each.
+pub fn load(path: &str) -> Result<Machine, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    parse(&text).map_err(|errors| {
+        errors.iter().map(|e| format!("{}:{}: {}", path, e.line, e.message)).collect::<Vec<_>>().join("\n")
+    })
+}
+
+/// Every error of the file, in the order of the lines they are on.
+pub fn parse(text: &str) -> Result<Machine, Vec<toml::Error>> {
+    let doc = toml::parse(text).map_err(|e| vec![e])?;
+    let mut errors = Vec::new();
+    let e = &mut errors;
+    let mut root = Fields::new(&doc, "");
+    let name = keep(e, root.string("name")).unwrap_or("unnamed machine").to_string();
+    let memory = match keep(e, root.table("memory")) {
+        Some(t) => memory(t, e),
+        None => Memory { base: 0x1000, stack: 64 << 10, latency: 100.0 },
+    };
+    let devices: Vec<_> = keep(e, root.tables("device")).into_iter().enumerate()
+        .filter_map(|(i, t)| device(t, i, e).map(|d| (i, d))).collect();
+    let caches = keep(e, root.tables("cache")).into_iter().enumerate().filter_map(|(i, t)| level(t, i, e)).collect();
+    let pipeline = match keep(e, root.table("pipeline")) {
+        Some(t) => {
+            let mut f = Fields::new(t, "pipeline");
+            let forwarding = keep(e, f.bool("forwarding")).unwrap_or(true);
+            let max = keep(e, f.number("max")).unwrap_or(12);
+            let max = keep(e, usize::try_from(max).map_err(|_| f.error("max", "too large")));
+            keep(e, f.finish());
+            Pipeline { forwarding, max }
+        }
+        None => Pipeline { forwarding: true, max: 12 },
+    };
+    let cost = keep(e, root.table("cost")).map(|t| cost(t, e));
+    keep(e, root.finish());
+    check_overlaps(&doc, &devices, e);
+    if !errors.is_empty() {
+        errors.sort_by_key(|e| e.line);
+        return Err(errors);
+    }
+    let devices = devices.into_iter().map(|(_, d)| d).collect();
+    Ok(Machine { name, memory, devices, caches, pipeline, cost })
+}
+
+/// The value of `r`, or its error noted and a default to go on with, so
+/// that one run reports every mistake in the file.
+fn keep<T: Default>(errors: &mut Vec<toml::Error>, r: Result<T, toml::Error>) -> T {
+    r.unwrap_or_else(|e| {
+        errors.push(e);
+        T::default()
+    })
+}
+
+fn address(f: &Fields, key: &str, v: u64) -> Result<u32, toml::Error> {
+    u32::try_from(v).map_err(|_| f.error(key, format!("0x{:x} does not fit in 32 bits", v)))
+}
+
+fn memory(t: &toml::Table, e: &mut Vec<toml::Error>) -> Memory {
+    let mut f = Fields::new(t, "memory");
+    let base = keep(e, f.number("base")).unwrap_or(0x1000);
+    let base = keep(e, address(&f, "base", base));
+    let stack = keep(e, f.number("stack")).unwrap_or(64 << 10);
+    let stack = keep(e, address(&f, "stack", stack));
+    if base.checked_add(stack).is_none() {
+        let key = if t.line_of("stack").is_some() { "stack" } else { "base" };
+        e.push(f.error(key, format!("0x{:x} + a {} B stack runs past 4 GiB", base, stack)));
+    }
+    let latency = keep(e, f.float("latency")).unwrap_or(100.0);
+    if latency < 0.0 {
+        e.push(f.error("latency", "a time cannot be negative"));
+    }
+    keep(e, f.finish());
+    Memory { base, stack, latency }
+}
+
+/// None when the device is broken; the reasons are in `e`.
+fn device(t: &toml::Table, i: usize, e: &mut Vec<toml::Error>) -> Option<Device> {
+    let before = e.len();
+    let mut f = Fields::new(t, &format!("device[{}]", i + 1));
+    let name = keep(e, f.string("name")).map_or_else(|| format!("device{}", i), str::to_string);
+    let kind = keep(e, f.choice("kind", DEVICE_KINDS));
+    if kind.is_none() && t.line_of("kind").is_none() {
+        e.push(f.error("kind", format!("missing, one of {}", DEVICE_KINDS.join(", "))));
+    }
+    let base = keep(e, f.number("base"));
+    if base.is_none() && t.line_of("base").is_none() {
+        e.push(f.error("base", "missing, the first address of the device"));
+    }
+    let base = base.and_then(|b| address(&f, "base", b).map_err(|err| e.push(err)).ok());
+    if let Some(b) = base.filter(|b| b % 4 != 0) {
+        e.push(f.error("base", format!("0x{:x} is not word aligned", b)));
+    }
+    let fixed = match kind {
+        Some("uart") => Some(8),
+        Some("timer") => Some(16),
+        _ => None,
+    };
+    let size = match (keep(e, f.number("size")), fixed) {
+        (Some(n), Some(own)) if n != own => {
+            e.push(f.error("size", format!("a {} takes {} bytes, not {}", kind.unwrap_or(""), own, n)));
+            None
+        }
+        (Some(n), _) | (None, Some(n)) => Some(n),
+        (None, None) => {
+            if kind.is_some() && t.line_of("size").is_none() {
+                e.push(f.error("size", "missing, the bytes of address space the device takes"));
+            }
+            None
+        }
+    };
+    if let (Some(size), Some(base)) = (size, base) {
+        if size == 0 || size > u64::from(u32::MAX) || u64::from(base) + size > 1 << 32 {
+            e.push(f.error("size", format!("{} bytes from 0x{:x} is not a range of 32-bit addresses", size, base)));
+        }
+    }
+    let irq = match keep(e, f.number("irq")) {
+        Some(n) if n < 32 => Some(n as u8),
+        Some(n) => {
+            e.push(f.error("irq", format!("{} is not an interrupt line, 0 to 31", n)));
+            None
+        }
+        None => None,
+    };
+    let input = keep(e, f.string("input")).unwrap_or("");
+    if let Some(kind) = kind.filter(|&k| !input.is_empty() && k != "uart") {
+        e.push(f.error("input", format!("only a uart receives input, not a {}", kind)));
+    }
+    keep(e, f.finish());
+    if e.len() > before {
+        return None;
+    }
+    let (kind, base, size) = (kind?, base?, size?);
+    Some(Device { name, kind: kind.to_string(), base, size: size as u32, irq, input: input.to_string() })
+}
+
+/// None when the level is broken; the reasons are in `e`.
+fn level(t: &toml::Table, i: usize, e: &mut Vec<toml::Error>) -> Option<Level> {
+    let before = e.len();
+    let mut f = Fields::new(t, &format!("cache[{}]", i + 1));
+    let name = keep(e, f.string("name")).map_or_else(|| format!("L{}", i + 1), str::to_string);
+    let size = keep(e, f.number("size"));
+    if size.is_none() && t.line_of("size").is_none() {
+        e.push(f.error("size", "missing, the capacity in bytes"));
+    }
+    let line = keep(e, f.number("line")).unwrap_or(16);
+    let ways = keep(e, f.number("ways")).unwrap_or(1);
+    let replacement = match keep(e, f.choice("replace", &["lru", "fifo", "random"])) {
+        Some("fifo") => Replacement::Fifo,
+        Some("random") => Replacement::Random,
+        _ => Replacement::Lru,
+    };
+    let write = match keep(e, f.choice("write", &["back", "through"])) {
+        Some("through") => WritePolicy::WriteThroughNoAllocate,
+        _ => WritePolicy::WriteBackAllocate,
+    };
+    let hit_time = keep(e, f.float("hit_time")).unwrap_or(1.0);
+    if hit_time < 0.0 {
+        e.push(f.error("hit_time", "a time cannot be negative"));
+    }
+    let mut small = |key: &str, v: u64| u32::try_from(v).map_err(|_| e.push(f.error(key, format!("{} is too large", v)))).ok();
+    let geometry = (size.and_then(|v| small("size", v)), small("line", line), small("ways", ways));
+    let mut config = None;
+    if let (Some(size), Some(line), Some(ways)) = geometry {
+        let c = Config { size, line, ways, replacement, write };
+        // checked on the numbers alone; "line must be a power of two"
+        // belongs on the line of line =
+        match c.check() {
+            Ok(()) => config = Some(c),
+            Err(message) => {
+                let key = ["line", "ways"].into_iter().find(|k| message.starts_with(k)).unwrap_or("size");
+                e.push(f.error(key, message));
+            }
+        }
+    }
+    keep(e, f.finish());
+    if e.len() > before {
+        return None;
+    }
+    let cache = Cache::new(config?).ok()?;
+    Some(Level { name, cache, hit_time })
+}
+
+fn cost(t: &toml::Table, e: &mut Vec<toml::Error>) -> Cost {
+    let mut f = Fields::new(t, "cost");
+    let clock_mhz = keep(e, f.float("clock_mhz"));
+    if clock_mhz.is_some_and(|mhz| mhz <= 0.0) {
+        e.push(f.error("clock_mhz", "a clock must be faster than 0 MHz"));
+    }
+    let mut cycles = [1.0; 8];
+    for (c, class) in cycles.iter_mut().zip(Class::ALL) {
+        if let Some(n) = keep(e, f.float(class.name())) {
+            if n < 0.0 {
+                e.push(f.error(class.name(), "cycles cannot be negative"));
+            }
+            *c = n;
+        }
+    }
+    keep(e, f.finish());
+    Cost { clock_mhz, cycles }
+}
+
+fn check_overlaps(doc: &toml::Table, devices: &[(usize, Device)], e: &mut Vec<toml::Error>) {
+    let Some(toml::Value::Array(tables)) = doc.get("device") else { return };
+    let end = |d: &Device| u64::from(d.base) + u64::from(d.size);
+    for (n, (i, d)) in devices.iter().enumerate() {
+        let toml::Value::Table(t) = &tables[*i] else { continue };
+        let f = Fields::new(t, &format!("device[{}]", i + 1));
+        if let Some((_, other)) = devices[..n].iter().find(|(_, o)| u64::from(d.base) < end(o) && u64::from(o.base) < end(d)) {
+            e.push(f.error("base", format!("{} at 0x{:08x}-0x{:08x} overlaps {} at 0x{:08x}-0x{:08x}",
+                d.name, d.base, end(d) - 1, other.name, other.base, end(other) - 1)));
+        }
+        if devices[..n].iter().any(|(_, o)| o.name == d.name) {
+            e.push(f.error("name", format!("there is already a device called {}", d.name)));
+        }
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ * tdt4160 - one entry point for the Rust simulators and demos.
+ *
+ *   tdt4160 <command> [options] [arguments]
+ *
+ *   overflow, float, adders, fsm arithmetic and logic, computed here
+ *   riscv run|disasm|pipeline|frames|x86, the simulators, on your own programs and traces
+ *   cache, config
+ *   quiz                         exam-style problems, solved by the simulators; --ask: hazards at the prompt
+ *   demos, <demo name>, menu     the demo binaries of examples/, by course topic
+ *
+ * Every command takes -h/--help, options are `--name value` or
+ * `--name=value`, numbers may be hex (0x), binary (0b) or sizes (32K), and
+ * an unknown option is an error (util::args). The exit code is 0 on
+ * success, 1 for a run that failed and 2 for a usage error.
+ *
+ * --lang no|en, anywhere on the line, prints the help, the summaries and
+ * the menu in Norwegian or English (util::lang), and so do the explanations
+ * of the demos it starts. The default comes from LANG. JSON output is the same in both, and --format
+ * markdown|latex gives tables to paste into a report (util::table).
+ * -q, -v and -vv, anywhere too, set how much a run prints, from the
+ * statistics alone to every step and what it changed (util::log).
+ * riscv and cache take --config, a machine described in a TOML file
+ * (config), and menu --record saves a session to --replay (session).
+ *
+ * Run: cargo run --release --bin tdt4160 -- help
+ */
+
+mod cmd;
+mod config;
+mod session;
+
+use std::env;
+use std::process;
+
+use util::{lang, log};
+
+/// Take --lang out of the arguments, for every command and demo alike.
+fn take_lang(args: Vec<String>) -> Result<Vec<String>, String> {
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--lang" {
+            lang::set_from(Some(&args.next().ok_or("--lang needs a value: no or en")?))?;
+        } else if let Some(value) = arg.strip_prefix("--lang=") {
+            lang::set_from(Some(value))?;
+        } else {
+            rest.push(arg);
+        }
+    }
+    Ok(rest)
+}
+
+fn main() {
+    let mut args = take_lang(env::args().skip(1).collect()).and_then(log::take).unwrap_or_else(|e| {
+        eprintln!("tdt4160: {}", e);
+        process::exit(2)
+    }).into_iter();
+    let name = args.next().unwrap_or_else(|| "help".to_string());
+    let rest: Vec<String> = args.collect();
+    let code = match cmd::find(&name) {
+        Some(c) if rest.iter().any(|a| a == "-h" || a == "--help") => {
+            print!("{}", cmd::tr(c.usage));
+            0
+        }
+        Some(c) => match (c.run)(rest) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("tdt4160 {}: {}\n\n{}", c.name, e, cmd::tr(c.usage));
+                2
+            }
+        },
+        None => match cmd::demos::find(&name) {
+            Some(demo) => cmd::demos::launch(demo, &rest),
+            None => match cmd::java::find(&name) {
+                Some(topic) => cmd::java::launch(topic, &rest),
+                None => {
+                    eprintln!("tdt4160: {}\n", lang::fill(cmd::tr("no command, demo or Java topic '{}'"), &[&name]));
+                    eprint!("{}", cmd::help());
+                    2
+                }
+            },
+        },
+    };
+    process::exit(code);
+}
@@ -0,0 +1,254 @@
+/*
+ * session - what a run of the menu read and printed, saved to replay.
+ *
+ *   tdt4160 menu --record t6.session      use the menu as usual
+ *   tdt4160 menu --replay t6.session      the same input, the same screens
+ *
+ *   # tdt4160 menu session                  t6.session
+ *   size 24 80                              the terminal it was recorded in
+ *   lang en
+ *   topic T6                                tdt4160 menu T6
+ *   | T6 The memory system                  what the screen showed
+ *   |
+ *   |    1  memlat  T6.1  load latency ...
+ *   | demo number or name, b back, q quit>
+ *   # buddy merges the halves again         a note, shown on replay
+ *   > buddy                                 what was typed
+ *
+ * A TA answers the prompts once and hands out the file; a student attaches
+ * one to a question and the TA sees exactly the screens the student saw.
+ * Replay reads the input from the file instead of the keyboard, in the
+ * recorded terminal size and language, and shows each input as if typed;
+ * at a terminal it waits for Enter before each one, so a walkthrough goes
+ * at the reader's pace, and its notes are shown above the prompt they
+ * precede. Every screen is compared with the recording, and a replay
+ * that came out different ends with the first line that differs and exit
+ * code 1 - a demo that measures time will, so walkthroughs stick to those
+ * that do not. A file with only > and # lines, written by hand, replays
+ * without comparing anything.
+ */
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufWriter, IsTerminal, Write};
+
+use util::lang::{self, Lang};
+
+enum Step {
+    /// A line of the screen, and its line in the file.
+    Screen(String, usize),
+    Note(String),
+    Input(String),
+}
+
+enum Mode {
+    Live,
+    Record(BufWriter<File>),
+    Replay { path: String, steps: Vec<Step>, next: usize, paced: bool },
+}
+
+pub struct Session {
+    mode: Mode,
+    /// Printed since the last input.
+    screen: String,
+    /// Where the replay first differed: (line of the file, recorded, now).
+    differs: Option<(usize, String, String)>,
+}
+
+/// What a recording was made in; replay uses the same.
+pub struct Setting {
+    pub rows: usize,
+    pub cols: usize,
+    pub lang: Lang,
+    /// The topic the menu opened at.
+    pub topic: Option<String>,
+}
+
+const HEADER: &str = "# tdt4160 menu session";
+
+impl Session {
+    pub fn live() -> Session {
+        Session { mode: Mode::Live, screen: String::new(), differs: None }
+    }
+
+    pub fn record(path: &str, setting: &Setting) -> Result<Session, String> {
+        let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut out = BufWriter::new(file);
+        let topic = setting.topic.as_ref().map_or(String::new(), |t| format!("topic {}\n", t));
+        write!(out, "{}\nsize {} {}\nlang {}\n{}", HEADER, setting.rows, setting.cols, setting.lang.code(), topic).map_err(|e| format!("{}: {}", path, e))?;
+        Ok(Session { mode: Mode::Record(out), screen: String::new(), differs: None })
+    }
+
+    pub fn replay(path: &str) -> Result<(Session, Setting), String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut setting = Setting { rows: 24, cols: 80, lang: lang::current(), topic: None };
+        let mut steps = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let fail = |what: &str| format!("{}:{}: {}", path, n + 1, what);
+            if let Some(screen) = line.strip_prefix('|') {
+                steps.push(Step::Screen(screen.strip_prefix(' ').unwrap_or(screen).to_string(), n + 1));
+            } else if let Some(input) = line.strip_prefix('>') {
+                steps.push(Step::Input(input.strip_prefix(' ').unwrap_or(input).to_string()));
+            } else if let Some(note) = line.strip_prefix('#') {
+                if n > 0 {
+                    steps.push(Step::Note(note.trim().to_string()));
+                }
+            } else if let Some(size) = line.strip_prefix("size ") {
+                let mut size = size.split_whitespace().map(|s| s.parse::<usize>().ok().filter(|&n| n > 0));
+                match (size.next().flatten(), size.next().flatten()) {
+                    (Some(rows), Some(cols)) => (setting.rows, setting.cols) = (rows, cols),
+                    _ => return Err(fail("expected size ROWS COLUMNS")),
+                }
+            } else if let Some(code) = line.strip_prefix("lang ") {
+                setting.lang = Lang::parse(code.trim()).ok_or_else(|| fail("expected lang en or no"))?;
+            } else if let Some(topic) = line.strip_prefix("topic ") {
+                setting.topic = Some(topic.trim().to_string());
+            } else if !line.trim().is_empty() {
+                return Err(fail("expected | a screen line, > an input or # a note"));
+            }
+        }
+        let paced = io::stdin().is_terminal();
+        Ok((Session { mode: Mode::Replay { path: path.to_string(), steps, next: 0, paced }, screen: String::new(), differs: None }, setting))
+    }
+
+    pub fn print(&mut self, text: &str) {
+        print!("{}", text);
+        self.screen.push_str(text);
+    }
+
+    /// Show `text> ` and read a line, trimmed; None at the end of input.
+    pub fn prompt(&mut self, text: &str) -> Option<String> {
+        if let Mode::Replay { steps, next, .. } = &self.mode {
+            // the notes before an input go above its prompt
+            for step in steps[*next..].iter().take_while(|s| !matches!(s, Step::Input(_))) {
+                if let Step::Note(note) = step {
+                    println!("  # {}", note);
+                }
+            }
+        }
+        self.print(&format!("{}> ", text));
+        let _ = io::stdout().flush();
+        let screen = std::mem::take(&mut self.screen);
+        let input = match &mut self.mode {
+            Mode::Live => stdin_line(),
+            Mode::Record(out) => {
+                let input = stdin_line();
+                let _ = write_screen(out, &screen);
+                if let Some(input) = &input {
+                    let _ = writeln!(out, "> {}", input);
+                }
+                input
+            }
+            Mode::Replay { steps, next, paced, .. } => {
+                let mut recorded = Vec::new();
+                let mut input = None;
+                while let Some(step) = steps.get(*next) {
+                    *next += 1;
+                    match step {
+                        Step::Screen(line, at) => recorded.push((*at, line.as_str())),
+                        Step::Note(_) => {}
+                        Step::Input(i) => {
+                            input = Some(i.clone());
+                            break;
+                        }
+                    }
+                }
+                if !recorded.is_empty() && self.differs.is_none() {
+                    self.differs = compare(&recorded, &screen);
+                }
+                match input {
+                    Some(i) if *paced => {
+                        print!("{}", i);
+                        let _ = io::stdout().flush();
+                        // Enter goes on, q stops the replay here
+                        stdin_line().filter(|l| l != "q").map(|_| i)
+                    }
+                    Some(i) => {
+                        println!("{}", i);
+                        Some(i)
+                    }
+                    None => None,
+                }
+            }
+        };
+        if input.is_none() {
+            println!();
+        }
+        input
+    }
+
+    /// Save what is left of a recording, or say how the replay went: the
+    /// exit code for the menu.
+    pub fn finish(mut self) -> Result<i32, String> {
+        let screen = std::mem::take(&mut self.screen);
+        match self.mode {
+            Mode::Live => Ok(0),
+            Mode::Record(mut out) => {
+                write_screen(&mut out, &screen).and_then(|_| out.flush()).map_err(|e| e.to_string())?;
+                Ok(0)
+            }
+            Mode::Replay { path, steps, next, .. } => {
+                let rest: Vec<(usize, &str)> = steps[next..].iter().filter_map(|s| match s {
+                    Step::Screen(line, at) => Some((*at, line.as_str())),
+                    _ => None,
+                }).collect();
+                if self.differs.is_none() && !(rest.is_empty() && screen.is_empty()) {
+                    self.differs = compare(&rest, &screen);
+                }
+                report(&path, self.differs)
+            }
+        }
+    }
+}
+
+fn report(path: &str, differs: Option<(usize, String, String)>) -> Result<i32, String> {
+    match differs {
+        None => Ok(0),
+        Some((at, recorded, now)) => {
+            eprintln!("{}:{}: {}\n  recorded: {}\n  now:      {}", path, at, tr("the screen is not the one recorded"), recorded, now);
+            Ok(1)
+        }
+    }
+}
+
+fn stdin_line() -> Option<String> {
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_string()),
+    }
+}
+
+fn write_screen(out: &mut impl Write, screen: &str) -> io::Result<()> {
+    if screen.is_empty() {
+        return Ok(());
+    }
+    for line in screen.split('\n').map(str::trim_end) {
+        if line.is_empty() {
+            writeln!(out, "|")?;
+        } else {
+            writeln!(out, "| {}", line)?;
+        }
+    }
+    Ok(())
+}
+
+/// The first line where `screen` is not the recorded one, if any;
+/// trailing spaces do not count.
+fn compare(recorded: &[(usize, &str)], screen: &str) -> Option<(usize, String, String)> {
+    let now: Vec<&str> = screen.split('\n').map(str::trim_end).collect();
+    let line = |s: Option<&str>| s.map_or("(nothing)".to_string(), str::to_string);
+    (0..recorded.len().max(now.len()))
+        .find(|&i| recorded.get(i).map(|r| r.1.trim_end()) != now.get(i).copied())
+        .map(|i| {
+            let at = recorded.get(i).or(recorded.last()).map_or(0, |r| r.0);
+            (at, line(recorded.get(i).map(|r| r.1)), line(now.get(i).copied()))
+        })
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+const NO: lang::Catalog = &[
+    ("the screen is not the one recorded", "skjermbildet er ikke det som ble tatt opp"),
+];
@@ -0,0 +1,284 @@
+//! Snapshots of the tdt4160 commands and the simulator traces, which are
+//! deterministic: the quiz takes a seed and the cache's random
+//! replacement is seeded. After an intended change of output:
+//! UPDATE_SNAPSHOTS=1 cargo test -p tdt4160, then review tests/snapshots/
+//! with git diff (util::snapshot).
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use riscv::instruction::{AluOp, Instruction};
+use util::snapshot;
+
+/// stdout, then stderr, of `tdt4160 args`.
+fn tdt4160(args: &[&str]) -> String {
+    typed(args, "")
+}
+
+/// The same, with `input` typed on stdin, in a 24 × 80 terminal.
+fn typed(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tdt4160")).args(args).env("TDT4160_LANG", "en").env("LINES", "24").env("COLUMNS", "80")
+        .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().expect("tdt4160 runs");
+    child.stdin.take().expect("stdin").write_all(input.as_bytes()).expect("input written");
+    let out = child.wait_with_output().expect("tdt4160 runs");
+    let mut text = String::from_utf8_lossy(&out.stdout).into_owned();
+    if !out.stderr.is_empty() {
+        text.push_str("---- stderr ----\n");
+        text.push_str(&String::from_utf8_lossy(&out.stderr));
+    }
+    text.push_str(&format!("---- exit {} ----\n", out.status.code().unwrap_or(-1)));
+    text
+}
+
+fn check(name: &str, args: &[&str]) {
+    snapshot::check(env!("CARGO_MANIFEST_DIR"), name, &tdt4160(args));
+}
+
+#[test]
+fn help_and_listing() {
+    check("help", &["help"]);
+    check("help_no", &["--lang", "no", "help"]);
+    check("demos", &["demos"]);
+    check("unknown_option", &["overflow", "--widht", "4"]);
+}
+
+#[test]
+fn java_topics() {
+    check("java", &["java"]);
+    check("java_t6_json", &["java", "T6", "--format", "json"]);
+    check("java_help_netlist", &["help", "netlist"]);
+    check("java_unknown", &["java", "nope"]);
+    check("java_no_arguments", &["java", "spi", "x"]);
+    // running one needs a JDK; without javac on the PATH only the listing is checked
+    if Command::new("javac").arg("-version").output().is_ok() {
+        check("java_netlist", &["netlist", "../../examples/logic_demo/full_adder.net"]);
+        check("java_fsm", &["java", "fsm", "../../examples/logic_demo/vending.toml", "5 5 10"]);
+    }
+}
+
+#[test]
+fn arithmetic() {
+    check("overflow", &["overflow"]);
+    check("overflow_sub", &["overflow", "3", "5", "--sub", "--width", "4"]);
+    check("float", &["float", "0.1", "-6.25", "1e-40", "inf", "nan"]);
+    check("float_bits", &["float", "--bits", "0x7F800001", "0x00000001"]);
+    check("adders", &["adders"]);
+    check("adders_json", &["adders", "0xFF", "1", "--width", "8", "--format", "json"]);
+}
+
+#[test]
+fn cache_traces() {
+    check("cache", &["cache", "-v"]);
+    check("cache_2way", &["cache", "--ways", "2", "--replace", "random"]);
+    check("cache_json", &["cache", "--ways", "2", "--format", "json"]);
+    check("cache_latex", &["cache", "--ways", "2", "--format", "latex"]);
+    check("cache_quiet", &["cache", "-q", "--ways", "2"]);
+}
+
+/// examples/configs/two_level.toml
+fn two_level() -> String {
+    format!("{}/../../examples/configs/two_level.toml", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn machine_configs() {
+    let path = two_level();
+    check("config", &["config", &path]);
+    check("cache_config", &["cache", "--config", &path, "--format", "markdown"]);
+    let file = std::env::temp_dir().join(format!("tdt4160-snapshot-{}.toml", std::process::id()));
+    std::fs::write(&file, "[[cache]]\nsize = \"1K\"\nwyas = 2\n").unwrap();
+    let bad = file.to_str().unwrap();
+    let error = tdt4160(&["config", bad]);
+    snapshot::check(env!("CARGO_MANIFEST_DIR"), "config_error", &error.replace(bad, "lab.toml"));
+    // every mistake, in the order of the file; a 2 GiB cache is only numbers
+    std::fs::write(&file, "[memory]\nbase = 0xFFFFFFF0\n\n[[cache]]\nsize = \"1K\"\nways = 3\n\n\
+        [[cache]]\nsize = \"2G\"\nline = 1\n\n[[device]]\nkind = \"uart\"\nbase = 0x1000_0000\nirq = 40\n").unwrap();
+    let errors = tdt4160(&["config", bad]);
+    let _ = std::fs::remove_file(&file);
+    snapshot::check(env!("CARGO_MANIFEST_DIR"), "config_errors", &errors.replace(bad, "lab.toml"));
+}
+
+#[test]
+fn menu_sessions() {
+    let file = std::env::temp_dir().join(format!("tdt4160-snapshot-{}.session", std::process::id()));
+    let path = file.to_str().unwrap();
+    let live = typed(&["menu", "T6", "--record", path], "25\nb\nq\n");
+    assert!(live.ends_with("---- exit 0 ----\n"));
+    let recorded = std::fs::read_to_string(&file).unwrap();
+    let replay = typed(&["menu", "--replay", path], "");
+    std::fs::write(&file, recorded.replace("8 demos, 16 Java", "9 demos, 16 Java")).unwrap();
+    let differs = typed(&["menu", "--replay", path], "");
+    std::fs::write(&file, "# by hand\n# the memory system\n> 6\n> q\n").unwrap();
+    let by_hand = typed(&["menu", "--replay", path], "");
+    let _ = std::fs::remove_file(&file);
+    let dir = env!("CARGO_MANIFEST_DIR");
+    snapshot::check(dir, "menu_record", &recorded);
+    snapshot::check(dir, "menu_replay", &replay);
+    snapshot::check(dir, "menu_replay_differs", &differs.replace(path, "lab.session"));
+    snapshot::check(dir, "menu_replay_by_hand", &by_hand);
+}
+
+#[test]
+fn state_machines() {
+    check("fsm", &["fsm"]);
+    check("fsm_moore_markdown", &["fsm", "--moore", "--detect", "110", "--input", "11011", "--format", "markdown"]);
+}
+
+#[test]
+fn quiz() {
+    check("quiz", &["quiz", "--seed", "4160", "--count", "8", "--solutions"]);
+    // right, right, then wrong and an answer that is not a number
+    let ask = typed(&["quiz", "--ask", "--seed", "4160", "--count", "2"], "3\n2\n3\n1\n-\nx\n0\n2,3\n1\n");
+    snapshot::check(env!("CARGO_MANIFEST_DIR"), "quiz_ask", &ask);
+}
+
+/// Sums 5 + 4 + ... + 1, prints it with print_int and "\n" with write, exits with 3.
+fn program() -> Vec<u8> {
+    let li = |rd, imm| Instruction::OpImm { op: AluOp::Add, rd, rs1: 0, imm };
+    let addi = |rd, rs1, imm| Instruction::OpImm { op: AluOp::Add, rd, rs1, imm };
+    let (t0, a0, a1, a2, a7) = (5, 10, 11, 12, 17);
+    let code = [
+        li(t0, 5),
+        li(a0, 0),
+        Instruction::Op { op: AluOp::Add, rd: a0, rs1: a0, rs2: t0 },
+        addi(t0, t0, -1),
+        Instruction::Branch { op: riscv::instruction::BranchOp::Bne, rs1: t0, rs2: 0, offset: -8 },
+        li(a7, 1),
+        Instruction::Ecall,
+        li(a7, 64),
+        li(a0, 1),
+        Instruction::Lui { rd: a1, imm: 0x1000 },
+        addi(a1, a1, 4 * 16),
+        li(a2, 1),
+        Instruction::Ecall,
+        li(a7, 93),
+        li(a0, 3),
+        Instruction::Ecall,
+    ];
+    let mut bytes: Vec<u8> = code.iter().flat_map(|i| i.encode().to_le_bytes()).collect();
+    bytes.push(b'\n');
+    bytes
+}
+
+/// Echoes what uart0 of examples/configs/devices.toml receives, in upper
+/// case, after setting timer0 to go off at 12.
+fn echo() -> Vec<u8> {
+    use riscv::instruction::{BranchOp, Width};
+    let li = |rd, imm| Instruction::OpImm { op: AluOp::Add, rd, rs1: 0, imm };
+    let (t0, t1, t2, a0, a7) = (5, 6, 7, 10, 17);
+    let code = [
+        Instruction::Lui { rd: t1, imm: 0x1000_0000 },
+        Instruction::Lui { rd: t2, imm: 0x0200_0000 },
+        li(t0, 12),
+        Instruction::Store { width: Width::Word, rs1: t2, rs2: t0, offset: 8 },
+        Instruction::Store { width: Width::Word, rs1: t2, rs2: 0, offset: 12 },
+        Instruction::Load { width: Width::Word, rd: t0, rs1: t1, offset: 4 },
+        Instruction::OpImm { op: AluOp::And, rd: t0, rs1: t0, imm: 1 },
+        Instruction::Branch { op: BranchOp::Beq, rs1: t0, rs2: 0, offset: 20 },
+        Instruction::Load { width: Width::ByteU, rd: t0, rs1: t1, offset: 0 },
+        Instruction::OpImm { op: AluOp::Add, rd: t0, rs1: t0, imm: -32 },
+        Instruction::Store { width: Width::Byte, rs1: t1, rs2: t0, offset: 0 },
+        Instruction::Jal { rd: 0, offset: -24 },
+        li(t0, 10),
+        Instruction::Store { width: Width::Byte, rs1: t1, rs2: t0, offset: 0 },
+        li(a7, 93),
+        li(a0, 0),
+        Instruction::Ecall,
+    ];
+    code.iter().flat_map(|i| i.encode().to_le_bytes()).collect()
+}
+
+/// fact(3), recursive, with a frame pointer and n kept in a local; exits with 6.
+fn recursive() -> Vec<u8> {
+    use riscv::instruction::{BranchOp, Width};
+    let addi = |rd, rs1, imm| Instruction::OpImm { op: AluOp::Add, rd, rs1, imm };
+    let sw = |rs2, rs1, offset| Instruction::Store { width: Width::Word, rs1, rs2, offset };
+    let lw = |rd, rs1, offset| Instruction::Load { width: Width::Word, rd, rs1, offset };
+    let (ra, sp, s0, t0, a0, a7) = (1, 2, 8, 5, 10, 17);
+    let code = [
+        addi(a0, 0, 3),
+        Instruction::Jal { rd: ra, offset: 12 },
+        addi(a7, 0, 93),
+        Instruction::Ecall,
+        addi(sp, sp, -16),
+        sw(ra, sp, 12),
+        sw(s0, sp, 8),
+        addi(s0, sp, 16),
+        sw(a0, s0, -12),
+        addi(t0, 0, 1),
+        Instruction::Branch { op: BranchOp::Blt, rs1: t0, rs2: a0, offset: 12 },
+        addi(a0, 0, 1),
+        Instruction::Jal { rd: 0, offset: 20 },
+        addi(a0, a0, -1),
+        Instruction::Jal { rd: ra, offset: -40 },
+        lw(t0, s0, -12),
+        Instruction::Op { op: AluOp::Mul, rd: a0, rs1: a0, rs2: t0 },
+        lw(ra, sp, 12),
+        lw(s0, sp, 8),
+        addi(sp, sp, 16),
+        Instruction::Jalr { rd: 0, rs1: ra, offset: 0 },
+    ];
+    code.iter().flat_map(|i| i.encode().to_le_bytes()).collect()
+}
+
+#[test]
+fn riscv_frames() {
+    let file = std::env::temp_dir().join(format!("tdt4160-snapshot-{}-fact.bin", std::process::id()));
+    std::fs::write(&file, recursive()).unwrap();
+    let path = file.to_str().unwrap();
+    // 0x102c is the base case, fact(1)
+    let deepest = tdt4160(&["riscv", "frames", path, "--at", "0x102c"]);
+    let markdown = tdt4160(&["riscv", "frames", path, "--at", "0x1010", "--hit", "2", "--format", "markdown"]);
+    let exit = tdt4160(&["riscv", "frames", path]);
+    let _ = std::fs::remove_file(&file);
+    let dir = env!("CARGO_MANIFEST_DIR");
+    snapshot::check(dir, "riscv_frames", &deepest);
+    snapshot::check(dir, "riscv_frames_markdown", &markdown);
+    snapshot::check(dir, "riscv_frames_exit", &exit);
+}
+
+#[test]
+fn riscv_devices() {
+    let file = std::env::temp_dir().join(format!("tdt4160-snapshot-{}-echo.bin", std::process::id()));
+    std::fs::write(&file, echo()).unwrap();
+    let path = file.to_str().unwrap();
+    let config = format!("{}/../../examples/configs/devices.toml", env!("CARGO_MANIFEST_DIR"));
+    let run = tdt4160(&["riscv", "run", path, "--config", &config, "-v"]);
+    let _ = std::fs::remove_file(&file);
+    snapshot::check(env!("CARGO_MANIFEST_DIR"), "riscv_devices", &run.replace(path, "echo.bin"));
+    check("config_devices", &["config", &config]);
+}
+
+#[test]
+fn riscv_run_disasm_and_pipeline() {
+    let file = std::env::temp_dir().join(format!("tdt4160-snapshot-{}.bin", std::process::id()));
+    std::fs::write(&file, program()).unwrap();
+    let path = file.to_str().unwrap();
+    let run = tdt4160(&["riscv", "run", path, "--trace"]);
+    let disasm = tdt4160(&["riscv", "disasm", path]);
+    let json = tdt4160(&["riscv", "run", path, "--format", "json"]);
+    let pipeline = tdt4160(&["riscv", "pipeline", path]);
+    let pipeline_md = tdt4160(&["riscv", "pipeline", path, "--max", "6", "--no-forwarding", "--format", "markdown"]);
+    let debug = tdt4160(&["riscv", "run", path, "-vv", "--max", "6"]);
+    let cost = tdt4160(&["riscv", "run", path, "--config", &two_level(), "--format", "markdown"]);
+    let _ = std::fs::remove_file(&file);
+    let dir = env!("CARGO_MANIFEST_DIR");
+    // the temporary file's name is not part of what is tested
+    let scrub = |s: String| s.replace(path, "prog.bin");
+    snapshot::check(dir, "riscv_run", &scrub(run));
+    snapshot::check(dir, "riscv_disasm", &scrub(disasm));
+    snapshot::check(dir, "riscv_json", &scrub(json));
+    snapshot::check(dir, "riscv_pipeline", &scrub(pipeline));
+    snapshot::check(dir, "riscv_pipeline_markdown", &scrub(pipeline_md));
+    snapshot::check(dir, "riscv_run_cost", &scrub(cost));
+    snapshot::check(dir, "riscv_run_debug", &scrub(debug));
+}
+
+#[test]
+fn riscv_x86() {
+    check("riscv_x86", &["riscv", "x86"]);
+    check("riscv_x86_markdown", &["riscv", "x86", "--format", "markdown"]);
+    // a prefix, REX with a SIB, a jump and one byte
+    check("riscv_x86_bytes", &["riscv", "x86", "66 c7 03 34 12", "48 8d 44 c4 f8", "75f0", "c3"]);
+    check("riscv_x86_error", &["riscv", "x86", "8b 44"]);
+}
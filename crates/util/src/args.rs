@@ -0,0 +1,179 @@
+/*
+ * args - the command-line conventions shared by the tools.
+ *
+ *   tdt4160 cache --trace t.din --size 32K --ways=4 -v  -- more positional
+ *                 └─ value ───┘ └─ value ─┘ └ value ┘ └flag┘ └ after --, never options
+ *
+ * Options are `--name value` or `--name=value`; which names take a value
+ * is declared up front, everything else starting with `-` is a flag.
+ * Numbers may be written 4160, 0x1040, 0b1010 or 1_000, and sizes may
+ * add K, M or G (powers of 1024). An option given but never asked for is
+ * an error, so a mistyped flag does not silently do nothing.
+ */
+
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    /// Split `args` (without the program name) into options and positionals.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I, takes_value: &[&str]) -> Result<Args, String> {
+        let mut it = args.into_iter();
+        let (mut positional, mut options) = (Vec::new(), Vec::new());
+        while let Some(arg) = it.next() {
+            if arg == "--" {
+                positional.extend(it);
+                break;
+            }
+            // "-" is stdin and "-5" or "-0x80" a negative number, not options
+            if !arg.starts_with('-') || arg == "-" || arg[1..].starts_with(|c: char| c.is_ascii_digit()) {
+                positional.push(arg);
+                continue;
+            }
+            let (name, inline) = match arg.split_once('=') {
+                Some((n, v)) => (n.to_string(), Some(v.to_string())),
+                None => (arg, None),
+            };
+            if takes_value.contains(&name.as_str()) {
+                let value = match inline {
+                    Some(v) => v,
+                    None => it.next().ok_or_else(|| format!("{} needs a value", name))?,
+                };
+                options.push((name, Some(value)));
+            } else if inline.is_some() {
+                return Err(format!("{} does not take a value", name));
+            } else {
+                options.push((name, None));
+            }
+        }
+        Ok(Args { positional, options })
+    }
+
+    /// Whether any of the flag's spellings (e.g. "-v" and "--verbose") was given.
+    pub fn flag(&mut self, names: &[&str]) -> bool {
+        self.count(names) > 0
+    }
+
+    /// How many times a flag was given: -v -v is 2.
+    pub fn count(&mut self, names: &[&str]) -> usize {
+        let before = self.options.len();
+        self.options.retain(|(n, v)| !(v.is_none() && names.contains(&n.as_str())));
+        before - self.options.len()
+    }
+
+    /// The last value of an option, as text.
+    pub fn string(&mut self, name: &str) -> Option<String> {
+        let mut found = None;
+        self.options.retain(|(n, v)| {
+            if n == name {
+                found = v.clone();
+                false
+            } else {
+                true
+            }
+        });
+        found
+    }
+
+    pub fn value<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, String> {
+        match self.string(name) {
+            None => Ok(None),
+            Some(s) => s.parse().map(Some).map_err(|_| format!("{}: cannot parse '{}'", name, s)),
+        }
+    }
+
+    /// A number or size: 0x/0b prefixes, underscores, K/M/G suffixes.
+    pub fn number(&mut self, name: &str) -> Result<Option<u64>, String> {
+        match self.string(name) {
+            None => Ok(None),
+            Some(s) => parse_number(&s).map(Some).map_err(|e| format!("{}: {}", name, e)),
+        }
+    }
+
+    /// Whether an option was given, without taking it.
+    pub fn has(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| n == name)
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    /// Error on any option nobody asked for.
+    pub fn finish(self) -> Result<Vec<String>, String> {
+        match self.options.first() {
+            Some((name, _)) => Err(format!("unknown option {}", name)),
+            None => Ok(self.positional),
+        }
+    }
+}
+
+/// 4160, 0x1040, 0b1_0000, 32K, 1M
+pub fn parse_number(s: &str) -> Result<u64, String> {
+    let clean = s.replace('_', "");
+    let (digits, scale) = match clean.strip_suffix(['K', 'k']) {
+        Some(d) => (d, 1 << 10),
+        None => match clean.strip_suffix(['M', 'm']) {
+            Some(d) => (d, 1 << 20),
+            None => match clean.strip_suffix(['G', 'g']) {
+                Some(d) => (d, 1 << 30),
+                None => (clean.as_str(), 1),
+            },
+        },
+    };
+    let value = if let Some(h) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        u64::from_str_radix(h, 16)
+    } else if let Some(b) = digits.strip_prefix("0b").or_else(|| digits.strip_prefix("0B")) {
+        u64::from_str_radix(b, 2)
+    } else {
+        digits.parse()
+    };
+    value.ok().and_then(|v| v.checked_mul(scale)).ok_or_else(|| format!("'{}' is not a number", s))
+}
+
+/// Like parse_number, but a leading '-' gives the two's complement.
+pub fn parse_signed(s: &str) -> Result<i64, String> {
+    match s.strip_prefix('-') {
+        Some(rest) => parse_number(rest).map(|v| (v as i64).wrapping_neg()),
+        None => parse_number(s).map(|v| v as i64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str, takes_value: &[&str]) -> Result<Args, String> {
+        Args::parse(line.split_whitespace().map(String::from), takes_value)
+    }
+
+    #[test]
+    fn options_flags_and_positionals() {
+        let mut a = args("run prog.elf --max 100 -v --base=0x1000 -v -0x5 -- --not-an-option", &["--max", "--base"]).unwrap();
+        assert!(!a.flag(&["--trace"]));
+        assert_eq!(a.count(&["-v", "--verbose"]), 2);
+        assert_eq!(a.number("--max").unwrap(), Some(100));
+        assert_eq!(a.number("--base").unwrap(), Some(0x1000));
+        assert_eq!(a.finish().unwrap(), ["run", "prog.elf", "-0x5", "--not-an-option"]);
+
+        let mut a = args("--size 32K --ways x --what", &["--size", "--ways"]).unwrap();
+        assert_eq!(a.number("--size").unwrap(), Some(32 * 1024));
+        assert_eq!(a.value::<u32>("--ways").unwrap_err(), "--ways: cannot parse 'x'");
+        assert_eq!(a.finish().unwrap_err(), "unknown option --what");
+        assert_eq!(args("--size", &["--size"]).unwrap_err(), "--size needs a value");
+        assert_eq!(args("-v=2", &[]).unwrap_err(), "-v does not take a value");
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse_number("0b1_0000"), Ok(16));
+        assert_eq!(parse_number("1M"), Ok(1 << 20));
+        assert_eq!(parse_number("0xFFFF_FFFF"), Ok(0xFFFF_FFFF));
+        assert!(parse_number("12q").is_err());
+        assert_eq!(parse_signed("-0x80"), Ok(-128));
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * asm - functions cut out of the assembly listing `rustc --emit asm` writes.
+ *
+ *   sum_iter:                         Listing { name: "sum_iter",
+ *       .cfi_startproc        ──►         lines: ["xor eax, eax",
+ *       xor eax, eax                              "test rsi, rsi", ...],
+ *       test rsi, rsi                     alias_of: None }
+ *   .Lfunc_end3:
+ *
+ * Every global symbol becomes a Listing with its instructions and local
+ * labels; directives and comments go, mangled names are demangled and
+ * GOT/PLT suffixes dropped, so what is left reads like the textbook. The
+ * same parser takes the x86-64 and the RISC-V listings. A function the
+ * compiler found identical to another is an alias of it, with no lines of
+ * its own.
+ *
+ * Used by: asm_view and showasm in examples/asm_demo.
+ */
+
+/// One function's instructions and local labels, directives and comments removed.
+pub struct Listing {
+    pub name: String,
+    pub lines: Vec<String>,
+    /// Set when the compiler merged this function into an identical one.
+    pub alias_of: Option<String>,
+}
+
+impl Listing {
+    pub fn instructions(&self) -> usize {
+        self.lines.iter().filter(|l| !l.ends_with(':')).count()
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let cut = [line.find('#'), line.find("//")].iter().filter_map(|&p| p).min();
+    match cut {
+        Some(p) => &line[..p],
+        None => line,
+    }
+}
+
+/// The readable part of a mangled symbol: `core::num::<impl i32>::checked_add`
+/// for legacy names (_ZN...E), the last path segment for v0 names (_R...).
+pub fn demangle(sym: &str) -> String {
+    if let Some(body) = sym.strip_prefix("_ZN") {
+        let mut parts = Vec::new();
+        let mut rest = body;
+        while let Some(len_end) = rest.find(|c: char| !c.is_ascii_digit()) {
+            let len: usize = match rest[..len_end].parse() {
+                Ok(n) => n,
+                Err(_) => break,
+            };
+            let part = &rest[len_end..len_end + len.min(rest.len() - len_end)];
+            if !(part.starts_with('h') && part.len() == 17) {   // the hash
+                parts.push(part.trim_start_matches('_').replace("$LT$", "<").replace("$GT$", ">").replace("$u20$", " ")
+                    .replace("$u5b$", "[").replace("$u5d$", "]").replace("..", "::"));
+            }
+            rest = &rest[len_end + len.min(rest.len() - len_end)..];
+        }
+        return parts.join("::");
+    }
+    // v0: the name ends in <decimal length><identifier>
+    for k in 0..sym.len() {
+        let digits = sym[k..].chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits > 0 && (k == 0 || !sym.as_bytes()[k - 1].is_ascii_digit()) {
+            if let Ok(len) = sym[k..k + digits].parse::<usize>() {
+                if k + digits + len == sym.len() {
+                    return sym[k + digits..].to_string();
+                }
+            }
+        }
+    }
+    sym.to_string()
+}
+
+/// Shorter operands: demangled symbols, no GOT suffix, no anon hashes.
+fn tidy(line: &str) -> String {
+    let words: Vec<String> = line
+        .split_whitespace()
+        .map(|w| {
+            let (w, close) = match w.strip_suffix(']') {
+                Some(inner) => (inner, "]"),
+                None => (w, ""),
+            };
+            let w = w.trim_end_matches("@GOTPCREL").trim_end_matches("@PLT");
+            let w = if w.starts_with("__") { &w[1..] } else { w };     // Mach-O adds an underscore
+            let w = if w.starts_with("_ZN") || w.starts_with("_R") {
+                demangle(w)
+            } else if w.starts_with(".Lanon.") {
+                format!(".Lanon.{}", w.rsplit('.').next().unwrap_or(""))
+            } else {
+                w.to_string()
+            };
+            w + close
+        })
+        .collect();
+    words.join(" ")
+}
+
+/// Every global function in an assembly listing, in order.
+pub fn parse(asm: &str) -> Vec<Listing> {
+    let mut out: Vec<Listing> = Vec::new();
+    let mut current: Option<Listing> = None;
+    let mut globals = Vec::new();
+    for raw in asm.lines() {
+        let line = strip_comment(raw).trim();
+        if let Some(sym) = line.strip_prefix(".globl").or_else(|| line.strip_prefix(".global")) {
+            globals.push(sym.trim().to_string());
+            continue;
+        }
+        let is_global = |name: &str| globals.iter().any(|g| g == name);
+        if let Some(label) = line.strip_suffix(':') {
+            if is_global(label) {
+                current = Some(Listing { name: label.trim_start_matches('_').to_string(), lines: Vec::new(), alias_of: None });
+                continue;
+            }
+        }
+        if let Some((alias, target)) = line.split_once(" = ").or_else(|| line.strip_prefix(".set ").and_then(|l| l.split_once(", "))) {
+            if is_global(alias.trim()) {
+                out.push(Listing {
+                    name: alias.trim().trim_start_matches('_').to_string(),
+                    lines: Vec::new(),
+                    alias_of: Some(target.trim().trim_start_matches('_').to_string()),
+                });
+                continue;
+            }
+        }
+        let listing = match current.as_mut() {
+            Some(l) => l,
+            None => continue,
+        };
+        if line == ".cfi_endproc" || line.starts_with(".Lfunc_end") {
+            out.push(current.take().unwrap());
+        } else if line.is_empty() || (line.starts_with('.') && !(line.starts_with(".L") && line.ends_with(':'))) {
+            continue;                                           // a directive
+        } else if line.starts_with(".Lfunc_begin") || line.starts_with(".Ltmp") {
+            continue;                                           // for the unwind tables, not a jump target
+        } else {
+            listing.lines.push(tidy(line));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn functions_come_out_of_a_listing() {
+        let x86 = "\t.globl\tadd_plain\n\t.p2align\t4\nadd_plain:\n\t.cfi_startproc\n\tlea eax, [rdi + rsi]  # sum\n\tret\n.Lfunc_end0:\n\
+                   \t.globl\tcopy\n.set copy, add_plain\n";
+        let fns = parse(x86);
+        assert_eq!(fns.len(), 2);
+        assert_eq!((fns[0].name.as_str(), fns[0].lines.clone()), ("add_plain", vec!["lea eax, [rdi + rsi]".to_string(), "ret".to_string()]));
+        assert_eq!(fns[1].alias_of.as_deref(), Some("add_plain"));
+        let riscv = "\t.globl\tcount\n\t.type\tcount,@function\ncount:\n.Lfunc_begin0:\n\tli\ta1, 0\n.LBB0_1:\n\taddi\ta1, a1, 1\n\tbne\ta1, a0, .LBB0_1\n\
+                     \tcall\t_ZN4core9panicking5panic17h0123456789abcdefE\n.Lfunc_end0:\n";
+        let count = &parse(riscv)[0];
+        assert_eq!(count.instructions(), 4);
+        assert_eq!(count.lines[1], ".LBB0_1:");
+        assert_eq!(count.lines[4], "call core::panicking::panic");
+    }
+}
@@ -0,0 +1,277 @@
+/*
+ * bench - benchmarks with saved baselines, the Rust twin of the Java
+ * harness in computerdesign.bench.
+ *
+ * Each case is warmed up, run as samples of roughly equal duration and
+ * summarized by the median time per operation, with the median absolute
+ * deviation as the noise. Results can be saved as a named baseline and a
+ * later run compared with it; a case counts as REGRESSED only if it got
+ * slower by more than the threshold AND by more than twice the noise.
+ *
+ * A bench target with `harness = false` calls run() with its cases:
+ *
+ *   cargo bench -p riscv --bench step -- --save-baseline main
+ *   ... change the emulator ...
+ *   cargo bench -p riscv --bench step -- --baseline main       (exit 1 on a regression)
+ *
+ * Baselines are the Java format, in bench-baselines/<name>.<suite>.json at
+ * the repository root, and only make sense on the machine that saved them.
+ */
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::args::Args;
+
+const USAGE: &str = "usage: cargo bench -p <crate> --bench <name> -- [--filter text] [--quick] [--save-baseline name] \
+[--baseline name] [--threshold percent] [--dir path]";
+
+/// A named workload; `run` returns how many operations it performed.
+pub struct Case {
+    pub name: &'static str,
+    /// What one operation is, e.g. "instr".
+    pub unit: &'static str,
+    pub run: Box<dyn FnMut() -> u64>,
+}
+
+impl Case {
+    pub fn new(name: &'static str, unit: &'static str, run: impl FnMut() -> u64 + 'static) -> Case {
+        Case { name, unit, run: Box::new(run) }
+    }
+}
+
+/// The summary of one case.
+#[derive(Clone, Debug)]
+pub struct Measured {
+    pub name: String,
+    pub unit: String,
+    /// Median over the samples.
+    pub ns_per_op: f64,
+    /// Median absolute deviation, ns/op.
+    pub noise: f64,
+}
+
+impl Measured {
+    fn new(name: &str, unit: &str, mut samples: Vec<f64>) -> Measured {
+        samples.sort_by(f64::total_cmp);
+        let ns_per_op = median(&samples);
+        let mut dev: Vec<f64> = samples.iter().map(|s| (s - ns_per_op).abs()).collect();
+        dev.sort_by(f64::total_cmp);
+        Measured { name: name.to_string(), unit: unit.to_string(), ns_per_op, noise: median(&dev) }
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let n = sorted.len();
+    if n % 2 == 1 { sorted[n / 2] } else { (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0 }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Bench {
+    pub warmup: Duration,
+    pub sample: Duration,
+    pub samples: usize,
+    /// Relative slowdown counted as a regression (0.10 = 10%).
+    pub threshold: f64,
+}
+
+impl Default for Bench {
+    fn default() -> Bench {
+        Bench { warmup: Duration::from_millis(500), sample: Duration::from_millis(100), samples: 15, threshold: 0.10 }
+    }
+}
+
+impl Bench {
+    /// Short warm-up and fewer samples, for a smoke test.
+    pub fn quick() -> Bench {
+        Bench { warmup: Duration::from_millis(100), sample: Duration::from_millis(20), samples: 5, ..Bench::default() }
+    }
+
+    pub fn measure(&self, case: &mut Case) -> Measured {
+        let (mut batches, start) = (0u64, Instant::now());
+        while batches == 0 || start.elapsed() < self.warmup {
+            black_box((case.run)());
+            batches += 1;
+        }
+        let ns_per_batch = start.elapsed().as_nanos() as f64 / batches as f64;
+        let per_sample = ((self.sample.as_nanos() as f64 / ns_per_batch) as u64).max(1);
+
+        let times = (0..self.samples).map(|_| {
+            let t0 = Instant::now();
+            let done: u64 = (0..per_sample).map(|_| black_box((case.run)())).sum();
+            let elapsed = t0.elapsed().as_nanos() as f64;
+            assert!(done > 0, "{} performed no operations", case.name);
+            elapsed / done as f64
+        }).collect();
+        Measured::new(case.name, case.unit, times)
+    }
+
+    /// "no change", "REGRESSED" or "improved" against a baseline (ns/op, noise).
+    pub fn verdict(&self, r: &Measured, base: (f64, f64)) -> &'static str {
+        let change = (r.ns_per_op - base.0) / base.0;
+        let noise = 2.0 * r.noise.max(base.1);
+        if (r.ns_per_op - base.0).abs() <= noise || change.abs() <= self.threshold {
+            "no change"
+        } else if change > 0.0 {
+            "REGRESSED"
+        } else {
+            "improved"
+        }
+    }
+
+    /// Results side by side with a baseline, with a verdict for each.
+    pub fn compare(&self, results: &[Measured], baseline: &BTreeMap<String, (f64, f64)>) -> String {
+        let mut s = format!("{:<28} {:>12} {:>12} {:>8}  verdict\n", "benchmark", "baseline", "now", "change");
+        for r in results {
+            match baseline.get(&r.name) {
+                None => writeln!(s, "{:<28} {:>12} {:>9.2} ns {:>8}  new", r.name, "-", r.ns_per_op, ""),
+                Some(&base) => writeln!(s, "{:<28} {:>9.2} ns {:>9.2} ns {:>+7.1}%  {}", r.name, base.0, r.ns_per_op,
+                    100.0 * (r.ns_per_op - base.0) / base.0, self.verdict(r, base)),
+            }.unwrap();
+        }
+        s
+    }
+}
+
+pub fn header() -> String {
+    format!("{:<28} {:>12} {:>9} {:>14}", "benchmark", "time/op", "noise", "throughput")
+}
+
+pub fn format(r: &Measured) -> String {
+    format!("{:<28} {:>9.2} ns {:>7.2} ns {:>9.2} M{}/s", r.name, r.ns_per_op, r.noise, 1e3 / r.ns_per_op, r.unit)
+}
+
+// ==================== BASELINES ====================
+
+pub fn baseline_json(results: &[Measured]) -> String {
+    let mut s = String::from("{\n  \"version\": 1,\n  \"benchmarks\": {\n");
+    for (i, r) in results.iter().enumerate() {
+        writeln!(s, "    \"{}\": {{\"unit\": \"{}\", \"ns_per_op\": {:.4}, \"noise\": {:.4}}}{}", r.name, r.unit,
+            r.ns_per_op, r.noise, if i + 1 < results.len() { "," } else { "" }).unwrap();
+    }
+    s + "  }\n}\n"
+}
+
+/// Name → (ns/op, noise) from a baseline written by baseline_json or the
+/// Java harness: one benchmark per line.
+pub fn parse_baseline(text: &str) -> Result<BTreeMap<String, (f64, f64)>, String> {
+    if !text.lines().any(|l| l.trim() == "\"version\": 1,") {
+        return Err("not a version 1 baseline".into());
+    }
+    let field = |line: &str, key: &str| -> Option<f64> {
+        let rest = &line[line.find(&format!("\"{}\": ", key))? + key.len() + 4..];
+        rest[..rest.find([',', '}'])?].trim().parse().ok()
+    };
+    let mut out = BTreeMap::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if !line.contains("\"ns_per_op\"") {
+            continue;
+        }
+        let name = line.strip_prefix('"').and_then(|l| l.split_once('"')).map(|(name, _)| name);
+        match (name, field(line, "ns_per_op"), field(line, "noise")) {
+            (Some(name), Some(ns), Some(noise)) if ns > 0.0 => out.insert(name.to_string(), (ns, noise)),
+            _ => return Err(format!("line {}: not a benchmark entry", n + 1)),
+        };
+    }
+    Ok(out)
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("bench: {}", message);
+    std::process::exit(2)
+}
+
+/// The options of a bench target (see the header); `suite` names its
+/// baseline files. Exits 1 when a case regressed against --baseline.
+pub fn run(suite: &str, mut cases: Vec<Case>) {
+    // cargo bench passes --bench to targets without the libtest harness
+    let args = std::env::args().skip(1).filter(|a| a != "--bench");
+    let mut args = Args::parse(args, &["--filter", "--save-baseline", "--baseline", "--threshold", "--dir"])
+        .unwrap_or_else(|e| fail(&format!("{}\n{}", e, USAGE)));
+    let mut bench = if args.flag(&["--quick"]) { Bench::quick() } else { Bench::default() };
+    let filter = args.string("--filter").unwrap_or_default();
+    let (save, compare) = (args.string("--save-baseline"), args.string("--baseline"));
+    match args.value::<f64>("--threshold") {
+        Ok(Some(p)) if p.is_finite() && p >= 0.0 => bench.threshold = p / 100.0,
+        Ok(None) => {}
+        Ok(Some(p)) => fail(&format!("--threshold: not a percentage: {}\n{}", p, USAGE)),
+        Err(e) => fail(&format!("{}\n{}", e, USAGE)),
+    }
+    let dir = args.string("--dir").map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../bench-baselines"));
+    if let Err(e) = args.finish() {
+        fail(&format!("{}\n{}", e, USAGE));
+    }
+    let file = |name: &str| dir.join(format!("{}.{}.json", name, suite));
+
+    let baseline = compare.as_ref().map(|name| {
+        let path = file(name);
+        match std::fs::read_to_string(&path) {
+            Ok(text) => parse_baseline(&text).unwrap_or_else(|e| fail(&format!("{}: {}", path.display(), e))),
+            Err(_) => fail(&format!("no baseline {} (create one with --save-baseline {})", path.display(), name)),
+        }
+    });
+
+    println!("{}", header());
+    let mut results = Vec::new();
+    for case in cases.iter_mut().filter(|c| c.name.contains(&filter)) {
+        let r = bench.measure(case);
+        println!("{}", format(&r));
+        results.push(r);
+    }
+
+    if let Some(name) = save {
+        let path = file(&name);
+        if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, baseline_json(&results))) {
+            fail(&format!("{}: {}", path.display(), e));
+        }
+        println!("\nSaved baseline {}", path.display());
+    }
+    if let (Some(name), Some(baseline)) = (compare, baseline) {
+        println!("\nAgainst baseline '{}' (threshold {:.0}%, and more than 2x noise):", name, bench.threshold * 100.0);
+        print!("{}", bench.compare(&results, &baseline));
+        let regressed = results.iter().filter(|r| baseline.get(&r.name).is_some_and(|&b| bench.verdict(r, b) == "REGRESSED")).count();
+        if regressed > 0 {
+            println!("{} benchmark(s) regressed", regressed);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measured(ns_per_op: f64, noise: f64) -> Measured {
+        Measured { name: "cpu.step".into(), unit: "instr".into(), ns_per_op, noise }
+    }
+
+    #[test]
+    fn baselines_round_trip() {
+        let json = baseline_json(&[measured(12.5, 0.25), Measured { name: "cache.access".into(), ..measured(3.0, 0.0) }]);
+        let base = parse_baseline(&json).unwrap();
+        assert_eq!(base["cpu.step"], (12.5, 0.25));
+        assert_eq!(base["cache.access"], (3.0, 0.0));
+        assert!(parse_baseline("{}").is_err());
+    }
+
+    #[test]
+    fn median_and_noise() {
+        let m = Measured::new("x", "op", vec![5.0, 1.0, 3.0, 100.0, 2.0]);
+        assert_eq!(m.ns_per_op, 3.0);
+        assert_eq!(m.noise, 2.0);
+    }
+
+    #[test]
+    fn a_regression_must_beat_threshold_and_noise() {
+        let bench = Bench::default();
+        assert_eq!(bench.verdict(&measured(10.5, 0.1), (10.0, 0.1)), "no change");   // 5% < 10%
+        assert_eq!(bench.verdict(&measured(12.0, 2.0), (10.0, 0.1)), "no change");   // within 2x noise
+        assert_eq!(bench.verdict(&measured(12.0, 0.1), (10.0, 0.1)), "REGRESSED");
+        assert_eq!(bench.verdict(&measured(8.0, 0.1), (10.0, 0.1)), "improved");
+    }
+}
@@ -0,0 +1,252 @@
+/*
+ * cycles - Serialized cycle-counter reads for micro-benchmarks
+ * Instant is fine for milliseconds, but a cache miss or a mispredicted
+ * branch lasts nanoseconds. The CPU's own counter is read in a few cycles:
+ *
+ *   x86_64    rdtsc / rdtscp    time-stamp counter, constant rate
+ *   aarch64   mrs cntvct_el0    generic timer, constant rate (cntfrq_el0)
+ *
+ * THE CATCH: an out-of-order core does not wait for earlier instructions
+ * before reading the counter. In
+ *
+ *   t0 = rdtsc; slow work; t1 = rdtsc
+ *
+ * the second rdtsc can execute while the slow work is still in flight, so
+ * a naive measurement comes out shorter than the real latency. Fences make
+ * the reads wait:
+ *
+ *   start:  lfence; rdtsc; lfence      (isb; mrs; isb on ARM)
+ *   stop:   rdtscp; lfence             (rdtscp waits for earlier instructions)
+ *
+ * Both counters tick at a fixed rate, NOT at the core clock: with turbo or
+ * power saving a "tick" is not a cycle. core_hz() estimates the real clock
+ * from a loop that takes one cycle per iteration, so ticks can be turned into
+ * core cycles.
+ *
+ * Usage:  let t = util::cycles::Stopwatch::start(); ...; t.elapsed_ns()
+ * Demo:   cargo run --release --bin cycles   (calibration, fenced vs naive reads)
+ */
+
+use std::hint::black_box;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+// ==================== THE COUNTER ====================
+
+#[cfg(target_arch = "x86_64")]
+pub mod arch {
+    use std::arch::asm;
+    use std::arch::x86_64::{__rdtscp, _mm_lfence, _rdtsc};
+
+    pub const NAME: &str = "rdtsc";
+
+    #[inline(always)]
+    pub fn start() -> u64 {
+        unsafe {
+            _mm_lfence();
+            let t = _rdtsc();
+            _mm_lfence();
+            t
+        }
+    }
+
+    #[inline(always)]
+    pub fn stop() -> u64 {
+        unsafe {
+            let mut aux = 0;
+            let t = __rdtscp(&mut aux);
+            _mm_lfence();
+            t
+        }
+    }
+
+    /// No fences: free to move past the surrounding instructions.
+    #[inline(always)]
+    pub fn unserialized() -> u64 {
+        unsafe { _rdtsc() }
+    }
+
+    /// The TSC rate is not architecturally readable; it is measured instead.
+    pub fn counter_hz() -> Option<f64> {
+        None
+    }
+
+    /// `n` iterations of a loop whose only dependency is a 1-cycle decrement.
+    #[inline(never)]
+    pub fn one_cycle_loop(n: u64) {
+        unsafe {
+            asm!("2:", "dec {n}", "jnz 2b", n = inout(reg) n => _, options(nomem, nostack));
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+pub mod arch {
+    use std::arch::asm;
+
+    pub const NAME: &str = "cntvct_el0";
+
+    #[inline(always)]
+    pub fn start() -> u64 {
+        let t: u64;
+        unsafe { asm!("isb", "mrs {t}, cntvct_el0", "isb", t = out(reg) t, options(nostack)) };
+        t
+    }
+
+    #[inline(always)]
+    pub fn stop() -> u64 {
+        start()
+    }
+
+    #[inline(always)]
+    pub fn unserialized() -> u64 {
+        let t: u64;
+        unsafe { asm!("mrs {t}, cntvct_el0", t = out(reg) t, options(nomem, nostack)) };
+        t
+    }
+
+    pub fn counter_hz() -> Option<f64> {
+        let f: u64;
+        unsafe { asm!("mrs {f}, cntfrq_el0", f = out(reg) f, options(nomem, nostack)) };
+        Some(f as f64)
+    }
+
+    #[inline(never)]
+    pub fn one_cycle_loop(n: u64) {
+        unsafe {
+            asm!("2:", "subs {n}, {n}, #1", "b.ne 2b", n = inout(reg) n => _, options(nomem, nostack));
+        }
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub mod arch {
+    //! No cycle counter wired up: nanoseconds since the first read instead.
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    pub const NAME: &str = "Instant (no cycle counter for this target)";
+
+    fn epoch() -> Instant {
+        static EPOCH: OnceLock<Instant> = OnceLock::new();
+        *EPOCH.get_or_init(Instant::now)
+    }
+
+    pub fn start() -> u64 {
+        epoch().elapsed().as_nanos() as u64
+    }
+
+    pub fn stop() -> u64 {
+        start()
+    }
+
+    pub fn unserialized() -> u64 {
+        start()
+    }
+
+    pub fn counter_hz() -> Option<f64> {
+        Some(1e9)
+    }
+
+    pub fn one_cycle_loop(n: u64) {
+        let mut n = n;
+        while super::black_box(n) > 0 {
+            n -= 1;
+        }
+    }
+}
+
+pub use self::arch::NAME as COUNTER;
+
+// ==================== CALIBRATION ====================
+
+/// Measured once per process: what a start/stop pair costs and how fast the
+/// counter ticks.
+#[derive(Clone, Copy, Debug)]
+pub struct Calibration {
+    /// Ticks an empty start/stop pair reports (best of many).
+    pub overhead: u64,
+    /// Counter ticks per second.
+    pub hz: f64,
+}
+
+pub fn calibration() -> Calibration {
+    static CAL: OnceLock<Calibration> = OnceLock::new();
+    *CAL.get_or_init(|| {
+        let mut overhead = u64::MAX;
+        for _ in 0..10_000 {
+            let t0 = arch::start();
+            let t1 = arch::stop();
+            overhead = overhead.min(t1.wrapping_sub(t0));
+        }
+        let hz = arch::counter_hz().unwrap_or_else(|| {
+            // count ticks across 20 ms of wall-clock time
+            let (w0, t0) = (Instant::now(), arch::start());
+            while w0.elapsed().as_millis() < 20 {}
+            let (t1, w1) = (arch::stop(), w0.elapsed());
+            (t1 - t0) as f64 / w1.as_secs_f64()
+        });
+        Calibration { overhead, hz }
+    })
+}
+
+/// Core clock estimate: a loop of one-cycle iterations timed with the
+/// counter. Best of five, so a frequency ramp-up is not counted.
+pub fn core_hz() -> f64 {
+    static HZ: OnceLock<f64> = OnceLock::new();
+    *HZ.get_or_init(|| {
+        let n = 20_000_000;
+        arch::one_cycle_loop(n);                            // wake the core up
+        let mut best = u64::MAX;
+        for _ in 0..5 {
+            let (_, ticks) = measure(|| arch::one_cycle_loop(black_box(n)));
+            best = best.min(ticks);
+        }
+        n as f64 / best as f64 * calibration().hz
+    })
+}
+
+// ==================== MEASURING ====================
+
+/// Ticks f took, with the read overhead taken off.
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, u64) {
+    let overhead = calibration().overhead;
+    let t0 = arch::start();
+    let result = f();
+    let t1 = arch::stop();
+    (result, t1.wrapping_sub(t0).saturating_sub(overhead))
+}
+
+pub fn ticks_to_ns(ticks: u64) -> f64 {
+    ticks as f64 * 1e9 / calibration().hz
+}
+
+/// Ticks to core cycles at the estimated core clock.
+pub fn ticks_to_cycles(ticks: u64) -> f64 {
+    ticks as f64 * core_hz() / calibration().hz
+}
+
+/// Drop-in for `Instant` in the benchmark loops.
+pub struct Stopwatch(u64);
+
+impl Stopwatch {
+    pub fn start() -> Stopwatch {
+        calibration();
+        Stopwatch(arch::start())
+    }
+
+    pub fn elapsed_ticks(&self) -> u64 {
+        arch::stop().wrapping_sub(self.0).saturating_sub(calibration().overhead)
+    }
+
+    pub fn elapsed_ns(&self) -> f64 {
+        ticks_to_ns(self.elapsed_ticks())
+    }
+}
+
+/// Smallest and median of a set of measurements. The minimum is the run
+/// with the least interference; the median shows how typical it was.
+pub fn min_and_median(mut v: Vec<u64>) -> (u64, u64) {
+    v.sort_unstable();
+    (v[0], v[v.len() / 2])
+}
@@ -0,0 +1,120 @@
+/*
+ * hexdump - offset, hex bytes and ASCII column, like `hexdump -C`.
+ * The same format as the compendium's computerdesign.util.HexDump, so dumps
+ * from the Java simulators and the Rust demos can be compared line by line.
+ *
+ *   00000000  31 4b 44 54 02 00 03 00  00 10 00 00              |1KDT........|
+ *
+ * Usage: util::hexdump::Options::default().format(&bytes, base_address)
+ * Tool:  cargo run --release --bin hexdump -- file [--width N] ...
+ */
+
+use std::fmt::Write;
+
+#[derive(Clone, Copy)]
+pub struct Options {
+    /// Bytes per line.
+    pub width: usize,
+    /// Bytes per group, separated by an extra space (0 = no grouping).
+    pub group: usize,
+    /// Collapse runs of identical lines into a single "*".
+    pub squeeze: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { width: 16, group: 8, squeeze: true }
+    }
+}
+
+impl Options {
+    pub fn width(mut self, width: usize) -> Self {
+        assert!((1..=64).contains(&width), "width must be 1-64, got {}", width);
+        self.width = width;
+        self
+    }
+
+    pub fn group(mut self, group: usize) -> Self {
+        self.group = group;
+        self
+    }
+
+    pub fn squeeze(mut self, squeeze: bool) -> Self {
+        self.squeeze = squeeze;
+        self
+    }
+
+    /// Dump `data`, labelling lines with addresses starting at `base`.
+    pub fn format(&self, data: &[u8], base: u64) -> String {
+        let mut out = String::new();
+        let mut previous: Option<&[u8]> = None;
+        let mut squeezing = false;
+        for (i, line) in data.chunks(self.width).enumerate() {
+            if self.squeeze && line.len() == self.width && previous == Some(line) {
+                if !squeezing {
+                    out.push_str("*\n");
+                }
+                squeezing = true;
+                continue;
+            }
+            squeezing = false;
+            previous = Some(line);
+            self.line(&mut out, line, base + (i * self.width) as u64);
+        }
+        if squeezing {
+            let _ = writeln!(out, "{:08x}", base + data.len() as u64);
+        }
+        out
+    }
+
+    fn line(&self, out: &mut String, bytes: &[u8], address: u64) {
+        let mut hex = String::new();
+        for i in 0..self.width {
+            if i > 0 {
+                hex.push_str(if self.group > 0 && i % self.group == 0 { "  " } else { " " });
+            }
+            match bytes.get(i) {
+                Some(b) => {
+                    let _ = write!(hex, "{:02x}", b);
+                }
+                None => hex.push_str("  "),
+            }
+        }
+        let ascii: String =
+            bytes.iter().map(|&b| if (0x20..0x7f).contains(&b) { b as char } else { '.' }).collect();
+        let _ = writeln!(out, "{:08x}  {}  |{}|", address, hex, ascii);
+    }
+}
+
+/// Dump with the default layout (16 bytes per line in two groups of 8).
+pub fn hexdump(data: &[u8], base: u64) -> String {
+    Options::default().format(data, base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_short_line() {
+        assert_eq!(hexdump(b"1KDT\x02\0", 0x10), "00000010  31 4b 44 54 02 00                                 |1KDT..|\n");
+    }
+
+    #[test]
+    fn repeated_lines_are_squeezed() {
+        let d = hexdump(&[0u8; 64], 0);
+        assert_eq!(d.lines().collect::<Vec<_>>(), [
+            "00000000  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|",
+            "*",
+            "00000040",
+        ]);
+        let full = Options::default().squeeze(false).format(&[0u8; 64], 0);
+        assert_eq!(full.lines().count(), 4);
+    }
+
+    #[test]
+    fn width_and_grouping() {
+        let d = Options::default().width(4).group(2).format(b"abcdef", 0);
+        assert_eq!(d, "00000000  61 62  63 64  |abcd|\n00000004  65 66         |ef|\n");
+    }
+}
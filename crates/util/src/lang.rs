@@ -0,0 +1,177 @@
+/*
+ * lang - English or Norwegian output, picked when the program runs.
+ *
+ *   const NO: Catalog = &[("Reading the table:", "Slik leser du tabellen:")];
+ *   fn tr(s: &'static str) -> &'static str { lang::tr(NO, s) }
+ *
+ *   println!("{}", tr("Reading the table:"));
+ *   println!("{}", lang::fill(tr("{} iterations"), &[&n]));
+ *
+ * The English text is its own key, as in gettext: a program is written in
+ * English as before, and a catalog next to it lists the Norwegian for the
+ * strings that have it. Anything missing prints in English, so
+ * translating is never all or nothing. Placeholders are "{}" in order,
+ * filled at run time by fill(), because format! needs its string at
+ * compile time.
+ *
+ * The language is --lang no|en (tdt4160 and the demos: Format::from_env,
+ * or args() below), else TDT4160_LANG, else the locale (LC_ALL,
+ * LC_MESSAGES, LANG): nb_NO, nn_NO and no select Norwegian. tdt4160
+ * passes its choice on to the demos it starts through TDT4160_LANG.
+ *
+ * tdt4160 and every Rust demo have a catalog for their explanations; the
+ * measured numbers, tables and register names stay as they are. The Java
+ * compendium prints English only.
+ */
+
+use std::env;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    /// Bokmål, as in the lectures.
+    No,
+}
+
+/// (English, Norwegian) pairs.
+pub type Catalog = &'static [(&'static str, &'static str)];
+
+/// The environment variable that carries the choice to child processes.
+pub const ENV: &str = "TDT4160_LANG";
+
+const UNSET: u8 = 0;
+static CURRENT: AtomicU8 = AtomicU8::new(UNSET);
+
+impl Lang {
+    /// "no", "nb", "nn" or a locale such as "nb_NO.UTF-8"; "en" or "en_US".
+    pub fn parse(s: &str) -> Option<Lang> {
+        let s = s.to_ascii_lowercase();
+        if ["no", "nb", "nn"].iter().any(|p| s.starts_with(p)) {
+            Some(Lang::No)
+        } else if s.starts_with("en") || s == "c" || s.starts_with("c.") || s == "posix" {
+            Some(Lang::En)
+        } else {
+            None
+        }
+    }
+
+    /// From TDT4160_LANG or the locale; English if neither says.
+    pub fn from_env() -> Lang {
+        [ENV, "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|v| env::var(v).ok().filter(|s| !s.is_empty()))
+            .find_map(|s| Lang::parse(&s))
+            .unwrap_or(Lang::En)
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::No => "no",
+        }
+    }
+}
+
+/// Choose the language for the rest of the run (and for child processes).
+pub fn set(lang: Lang) {
+    CURRENT.store(lang as u8 + 1, Ordering::Relaxed);
+    env::set_var(ENV, lang.code());
+}
+
+/// The `--lang` option's value, checked; `set` it if given.
+pub fn set_from(value: Option<&str>) -> Result<(), String> {
+    if let Some(v) = value {
+        set(Lang::parse(v).ok_or_else(|| format!("--lang: expected no or en, got '{}'", v))?);
+    }
+    Ok(())
+}
+
+pub fn current() -> Lang {
+    match CURRENT.load(Ordering::Relaxed) {
+        UNSET => {
+            let lang = Lang::from_env();
+            CURRENT.store(lang as u8 + 1, Ordering::Relaxed);
+            lang
+        }
+        n if n == Lang::No as u8 + 1 => Lang::No,
+        _ => Lang::En,
+    }
+}
+
+/// The arguments of a demo without `--lang no|en` (or `--lang=no`), which
+/// sets the language; a bad value prints the error and `usage` and exits
+/// with 2. For the demos that take their arguments by position.
+pub fn args(usage: &str) -> Vec<String> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let found = args.iter().position(|a| a == "--lang" || a.starts_with("--lang="));
+    if let Some(i) = found {
+        let value = match args[i].strip_prefix("--lang=") {
+            Some(v) => Some(v.to_string()),
+            None if i + 1 < args.len() => Some(args.remove(i + 1)),
+            None => None,
+        };
+        args.remove(i);
+        let set = value.ok_or_else(|| "--lang needs a value".to_string()).and_then(|v| set_from(Some(&v)));
+        if let Err(e) = set {
+            eprintln!("{}\nusage: {}", e, usage);
+            std::process::exit(2);
+        }
+    }
+    args
+}
+
+/// `english`, or its entry in `catalog` when the language is Norwegian.
+pub fn tr(catalog: Catalog, english: &'static str) -> &'static str {
+    lookup(current(), catalog, english)
+}
+
+fn lookup(lang: Lang, catalog: Catalog, english: &'static str) -> &'static str {
+    match lang {
+        Lang::En => english,
+        Lang::No => catalog.iter().find(|(en, _)| *en == english).map_or(english, |(_, no)| no),
+    }
+}
+
+/// Replace each "{}" in `template` with the next value.
+pub fn fill(template: &str, values: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut values = values.iter();
+    let mut rest = template;
+    while let Some(i) = rest.find("{}") {
+        out.push_str(&rest[..i]);
+        match values.next() {
+            Some(v) => out.push_str(&v.to_string()),
+            None => out.push_str("{}"),
+        }
+        rest = &rest[i + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO: Catalog = &[("{} hits", "{} treff"), ("Reading the table:", "Slik leser du tabellen:")];
+
+    #[test]
+    fn lookup_falls_back_to_english() {
+        assert_eq!(lookup(Lang::No, NO, "Reading the table:"), "Slik leser du tabellen:");
+        assert_eq!(lookup(Lang::No, NO, "not translated"), "not translated");
+        assert_eq!(lookup(Lang::En, NO, "Reading the table:"), "Reading the table:");
+        assert_eq!(fill(lookup(Lang::No, NO, "{} hits"), &[&3]), "3 treff");
+        assert_eq!(fill("{} of {}", &[&1]), "1 of {}");
+    }
+
+    #[test]
+    fn locale_names() {
+        assert_eq!(Lang::parse("nb_NO.UTF-8"), Some(Lang::No));
+        assert_eq!(Lang::parse("nn"), Some(Lang::No));
+        assert_eq!(Lang::parse("en_GB.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::parse("C.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::parse("de_DE"), None);
+    }
+}
@@ -0,0 +1,122 @@
+/*
+ * Struct Layout, Padding and Alignment
+ * Size, alignment, field offsets and padding bytes of real Rust structs,
+ * measured with std::mem::offset_of! and drawn byte by byte.
+ *
+ *   #[repr(C)] struct { a: u8, b: u32, c: u8 }     12 bytes
+ *     offset  0  1  2  3  4  5  6  7  8  9 10 11
+ *             a  .  .  .  b  b  b  b  c  .  .  .     . = padding
+ *
+ * Every field sits at a multiple of its alignment, and the size is rounded
+ * up to the struct's alignment so arrays of it stay aligned. repr(C) keeps
+ * the declared order (the C ABI); Rust's default repr may reorder fields
+ * to remove padding; repr(packed) drops it at the cost of unaligned fields.
+ *
+ * Usage: let l = util::layout!(Type { field: FieldType, ... }); print!("{}", l.report());
+ * Demo:  cargo run --bin layout
+ */
+
+/// One field as reported by `layout!`.
+pub struct Field {
+    pub name: &'static str,
+    pub ty: &'static str,
+    pub offset: usize,
+    pub size: usize,
+    pub align: usize,
+}
+
+/// The measured layout of a struct.
+pub struct Layout {
+    pub name: &'static str,
+    pub size: usize,
+    pub align: usize,
+    pub fields: Vec<Field>,
+}
+
+/// `layout!(Type { field: FieldType, ... })` - measure a struct's layout.
+/// List every field: the types are repeated because offset_of! gives offsets,
+/// not types, and fields left out would be counted as padding.
+#[macro_export]
+macro_rules! layout {
+    ($t:ident { $($f:ident : $ft:ty),* $(,)? }) => {{
+        let mut fields = vec![$($crate::layout::Field {
+            name: stringify!($f),
+            ty: stringify!($ft),
+            offset: ::std::mem::offset_of!($t, $f),
+            size: ::std::mem::size_of::<$ft>(),
+            align: ::std::mem::align_of::<$ft>(),
+        }),*];
+        fields.sort_by_key(|f| f.offset);
+        $crate::layout::Layout {
+            name: stringify!($t),
+            size: ::std::mem::size_of::<$t>(),
+            align: ::std::mem::align_of::<$t>(),
+            fields,
+        }
+    }};
+}
+
+impl Layout {
+    /// Padding bytes inside and at the end of the struct.
+    pub fn padding(&self) -> usize {
+        self.size - self.fields.iter().map(|f| f.size).sum::<usize>()
+    }
+
+    /// Which field owns each byte (None = padding).
+    fn owners(&self) -> Vec<Option<usize>> {
+        let mut owner = vec![None; self.size];
+        for (i, f) in self.fields.iter().enumerate() {
+            for o in &mut owner[f.offset..f.offset + f.size] {
+                *o = Some(i);
+            }
+        }
+        owner
+    }
+
+    pub fn report(&self) -> String {
+        let mut s = format!("{}: size {}, align {}, {} padding byte(s)\n",
+            self.name, self.size, self.align, self.padding());
+        let owners = self.owners();
+        let mut cursor = 0;
+        for f in &self.fields {
+            if f.offset > cursor {
+                s += &format!("    {:>3}  ({} byte(s) padding)\n", cursor, f.offset - cursor);
+            }
+            s += &format!("    {:>3}  {:<6} {:<5} size {} align {}{}\n", f.offset, f.name, f.ty, f.size, f.align,
+                if f.offset % f.align != 0 { "   UNALIGNED" } else { "" });
+            cursor = f.offset + f.size;
+        }
+        if self.size > cursor {
+            s += &format!("    {:>3}  ({} byte(s) tail padding, size rounded up to align {})\n",
+                cursor, self.size - cursor, self.align);
+        }
+        let bytes: String = owners
+            .iter()
+            .map(|o| match o {
+                Some(i) => self.fields[*i].name.chars().next().unwrap(),
+                None => '.',
+            })
+            .collect();
+        s += &format!("    bytes: {}\n", bytes);
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Abc {
+        a: u8,
+        b: u32,
+        c: u8,
+    }
+
+    #[test]
+    fn repr_c_padding() {
+        let l = layout!(Abc { a: u8, b: u32, c: u8 });
+        assert_eq!((l.size, l.align, l.padding()), (12, 4, 6));
+        assert_eq!(l.fields.iter().map(|f| f.offset).collect::<Vec<_>>(), [0, 4, 8]);
+        assert!(l.report().contains("bytes: a...bbbbc..."));
+    }
+}
@@ -0,0 +1,24 @@
+//! Helpers shared by the Rust demos in `examples/` and the tools: timing,
+//! benchmarks with saved baselines, hardware counters, hex dumps,
+//! reproducible random data, allocation tracking, struct layout,
+//! command-line options, text/JSON reports and tables, TOML setup files,
+//! English or Norwegian messages, verbosity levels, snapshot tests of
+//! printed output and functions cut out of compiler assembly listings.
+//! Each module is small and std-only, so a demo reads as the experiment
+//! and not as its scaffolding.
+
+pub mod args;
+pub mod asm;
+pub mod bench;
+pub mod cycles;
+pub mod hexdump;
+pub mod lang;
+pub mod layout;
+pub mod log;
+pub mod perf;
+pub mod report;
+pub mod rng;
+pub mod snapshot;
+pub mod table;
+pub mod toml;
+pub mod tracking;
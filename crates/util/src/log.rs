@@ -0,0 +1,158 @@
+/*
+ * log - how much a run prints, chosen once: -q, the default, -v or -vv.
+ *
+ *   -q      quiet     the statistics and the result, nothing on the way
+ *           normal    what each tool prints by default
+ *   -v      verbose   a line per step: every instruction, every access
+ *   -vv     debug     and what each step changed: registers, set contents
+ *
+ *   if log::enabled(Level::Verbose) { ... print the trace ... }
+ *   util::debug!("{}: {} segments, entry 0x{:08x}", path, n, entry);
+ *
+ * A tool asks for a level instead of counting its own -v flags, so -v means
+ * the same everywhere. The level is --quiet/--verbose as tdt4160 takes them
+ * anywhere on its line, else TDT4160_LOG (quiet, normal, verbose, debug),
+ * which carries it on to the demos it starts. The note!, verbose! and
+ * debug! macros are eprintln! at a level: remarks about the run go to
+ * stderr, so stdout stays the result.
+ */
+
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Quiet,
+    Normal,
+    Verbose,
+    Debug,
+}
+
+/// The environment variable that carries the level to child processes.
+pub const ENV: &str = "TDT4160_LOG";
+
+const UNSET: u8 = 0;
+static CURRENT: AtomicU8 = AtomicU8::new(UNSET);
+
+const LEVELS: [Level; 4] = [Level::Quiet, Level::Normal, Level::Verbose, Level::Debug];
+
+impl Level {
+    pub fn parse(s: &str) -> Option<Level> {
+        LEVELS.into_iter().find(|l| l.name() == s.to_ascii_lowercase())
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Quiet => "quiet",
+            Level::Normal => "normal",
+            Level::Verbose => "verbose",
+            Level::Debug => "debug",
+        }
+    }
+
+    /// -q, or -v given `verbose` times (-vv is twice).
+    pub fn from_flags(quiet: bool, verbose: usize) -> Result<Level, String> {
+        match (quiet, verbose) {
+            (true, 0) => Ok(Level::Quiet),
+            (true, _) => Err("-q and -v ask for opposite things".to_string()),
+            (false, 0) => Ok(Level::Normal),
+            (false, 1) => Ok(Level::Verbose),
+            _ => Ok(Level::Debug),
+        }
+    }
+}
+
+/// Choose the level for the rest of the run (and for child processes).
+pub fn set(level: Level) {
+    CURRENT.store(level as u8 + 1, Ordering::Relaxed);
+    env::set_var(ENV, level.name());
+}
+
+pub fn level() -> Level {
+    match CURRENT.load(Ordering::Relaxed) {
+        UNSET => {
+            let level = env::var(ENV).ok().and_then(|s| Level::parse(&s)).unwrap_or(Level::Normal);
+            CURRENT.store(level as u8 + 1, Ordering::Relaxed);
+            level
+        }
+        n => LEVELS[usize::from(n - 1).min(3)],
+    }
+}
+
+/// Whether output meant for `at` and above is on.
+pub fn enabled(at: Level) -> bool {
+    level() >= at
+}
+
+/// Take -q/--quiet, -v/--verbose and -vv out of `args` and `set` the
+/// level; arguments after `--` are left alone.
+pub fn take(args: Vec<String>) -> Result<Vec<String>, String> {
+    let (mut quiet, mut verbose) = (false, 0);
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-q" | "--quiet" => quiet = true,
+            "-v" | "--verbose" => verbose += 1,
+            "-vv" => verbose += 2,
+            "--" => {
+                rest.push(arg);
+                rest.extend(args.by_ref());
+            }
+            _ => rest.push(arg),
+        }
+    }
+    if quiet || verbose > 0 {
+        set(Level::from_flags(quiet, verbose)?);
+    }
+    Ok(rest)
+}
+
+/// A remark at the normal level, which -q silences.
+#[macro_export]
+macro_rules! note {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Normal) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Verbose) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_and_names() {
+        assert_eq!(Level::from_flags(false, 2), Ok(Level::Debug));
+        assert_eq!(Level::from_flags(true, 0), Ok(Level::Quiet));
+        assert!(Level::from_flags(true, 1).is_err());
+        assert_eq!(LEVELS.map(|l| Level::parse(l.name())), LEVELS.map(Some));
+        assert!(Level::Verbose > Level::Normal && Level::Quiet < Level::Normal);
+    }
+
+    #[test]
+    fn take_leaves_the_rest() {
+        let args = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+        assert_eq!(take(args("cache -vv --ways 2 -- -v")).unwrap(), args("cache --ways 2 -- -v"));
+        assert_eq!(level(), Level::Debug);
+    }
+}
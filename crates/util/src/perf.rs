@@ -0,0 +1,264 @@
+/*
+ * perf - hardware performance counters around a closure, no crates.
+ * The kernel's perf_event_open(2) interface, reduced to what the demos
+ * need: cycles, instructions, cache references/misses, L1d read misses and
+ * branch misses while a closure runs, on the calling thread, user space only.
+ *
+ *   let mut counters = util::perf::Counters::open()?;
+ *   let (result, counts) = counters.measure(|| work());
+ *   counts.ipc()                                        // instructions per cycle
+ *
+ * The events are opened as one GROUP so they are started and stopped
+ * together and describe the same instructions. Events this CPU lacks are
+ * left out (their field stays None) instead of failing the whole group.
+ *
+ * Counting is optional and off by default: it is only compiled in with
+ * the `perf` feature on Linux x86_64/aarch64. Everywhere else Counters::open()
+ * returns None and the demos fall back to timing alone. Even when compiled
+ * in, the kernel may refuse (virtual machines without a PMU, or
+ * /proc/sys/kernel/perf_event_paranoid above 2); unavailable() says why.
+ *
+ *   cargo run --release --features perf --bin branch_predict
+ */
+
+/// Counter values for one measured run; None if the event is not available.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Counts {
+    pub cycles: Option<u64>,
+    pub instructions: Option<u64>,
+    pub cache_references: Option<u64>,
+    pub cache_misses: Option<u64>,
+    pub l1d_read_misses: Option<u64>,
+    pub branch_misses: Option<u64>,
+}
+
+impl Counts {
+    pub fn ipc(&self) -> Option<f64> {
+        match (self.instructions, self.cycles) {
+            (Some(i), Some(c)) if c > 0 => Some(i as f64 / c as f64),
+            _ => None,
+        }
+    }
+
+    /// Last-level misses per cache reference.
+    pub fn cache_miss_rate(&self) -> Option<f64> {
+        match (self.cache_misses, self.cache_references) {
+            (Some(m), Some(r)) if r > 0 => Some(m as f64 / r as f64),
+            _ => None,
+        }
+    }
+
+    /// The counts as `name value` pairs, for printing.
+    pub fn fields(&self) -> Vec<(&'static str, u64)> {
+        let all = [
+            ("cycles", self.cycles),
+            ("instructions", self.instructions),
+            ("cache-references", self.cache_references),
+            ("cache-misses", self.cache_misses),
+            ("L1d-read-misses", self.l1d_read_misses),
+            ("branch-misses", self.branch_misses),
+        ];
+        all.iter().filter_map(|&(name, v)| v.map(|v| (name, v))).collect()
+    }
+}
+
+/// Convenience for a single measurement: the closure always runs, the
+/// counts are None if counting is unavailable.
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, Option<Counts>) {
+    match Counters::open() {
+        Some(mut counters) => {
+            let (r, counts) = counters.measure(f);
+            (r, Some(counts))
+        }
+        None => (f(), None),
+    }
+}
+
+pub use self::imp::{unavailable, Counters};
+
+// ==================== LINUX: perf_event_open ====================
+
+#[cfg(all(feature = "perf", target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod imp {
+    use super::Counts;
+    use std::fs::{self, File};
+    use std::io::Read;
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+
+    /// The first 64 bytes of struct perf_event_attr (PERF_ATTR_SIZE_VER0
+    /// plus config1), which every kernel since 2.6.32 accepts.
+    #[repr(C)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    extern "C" {
+        fn syscall(number: i64, ...) -> i64;
+        fn ioctl(fd: i32, request: u64, ...) -> i32;
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_PERF_EVENT_OPEN: i64 = 298;
+    #[cfg(target_arch = "aarch64")]
+    const SYS_PERF_EVENT_OPEN: i64 = 241;
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_TYPE_HW_CACHE: u32 = 3;
+    // HW_CACHE config = cache id | (op << 8) | (result << 16): L1D (0), READ (0), MISS (1)
+    const L1D_READ_MISS: u64 = 1 << 16;
+
+    const FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+    const FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+    const FORMAT_GROUP: u64 = 1 << 3;
+    const DISABLED: u64 = 1 << 0;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+    const IOC_ENABLE: u64 = 0x2400;
+    const IOC_DISABLE: u64 = 0x2401;
+    const IOC_RESET: u64 = 0x2403;
+    const IOC_FLAG_GROUP: u64 = 1;
+
+    /// (type, config) of each Counts field, in field order.
+    const EVENTS: [(u32, u64); 6] = [
+        (PERF_TYPE_HARDWARE, 0), // cycles
+        (PERF_TYPE_HARDWARE, 1), // instructions
+        (PERF_TYPE_HARDWARE, 2), // cache references
+        (PERF_TYPE_HARDWARE, 3), // cache misses
+        (PERF_TYPE_HW_CACHE, L1D_READ_MISS),
+        (PERF_TYPE_HARDWARE, 5), // branch misses
+    ];
+
+    fn open_event(kind: u32, config: u64, group: i32) -> Option<File> {
+        let leader = group < 0;
+        let attr = PerfEventAttr {
+            kind,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            sample_period: 0,
+            sample_type: 0,
+            read_format: FORMAT_GROUP | FORMAT_TOTAL_TIME_ENABLED | FORMAT_TOTAL_TIME_RUNNING,
+            // members start with the leader, so only the leader is created disabled
+            flags: if leader { DISABLED } else { 0 } | EXCLUDE_KERNEL | EXCLUDE_HV,
+            wakeup_events: 0,
+            bp_type: 0,
+            config1: 0,
+        };
+        // this thread, any CPU
+        let fd = unsafe { syscall(SYS_PERF_EVENT_OPEN, &attr as *const PerfEventAttr, 0i32, -1i32, group, 0u64) };
+        if fd < 0 {
+            None
+        } else {
+            Some(unsafe { File::from_raw_fd(fd as i32) })
+        }
+    }
+
+    pub struct Counters {
+        leader: File,
+        fd: i32,
+        /// Counts field index of each group member, in read order.
+        slots: Vec<usize>,
+        _members: Vec<File>,
+    }
+
+    impl Counters {
+        /// None if the kernel refuses every event.
+        pub fn open() -> Option<Counters> {
+            let (lead, leader) =
+                EVENTS.iter().enumerate().find_map(|(i, &(kind, config))| open_event(kind, config, -1).map(|f| (i, f)))?;
+            let fd = leader.as_raw_fd();
+            let mut slots = vec![lead];
+            let mut members = Vec::new();
+            for (i, &(kind, config)) in EVENTS.iter().enumerate().skip(lead + 1) {
+                if let Some(member) = open_event(kind, config, fd) {
+                    slots.push(i);
+                    members.push(member);
+                }
+            }
+            Some(Counters { leader, fd, slots, _members: members })
+        }
+
+        /// Run f with the counters on. Values are scaled up if the kernel had
+        /// to multiplex the group with other users of the PMU.
+        pub fn measure<R, F: FnOnce() -> R>(&mut self, f: F) -> (R, Counts) {
+            unsafe {
+                ioctl(self.fd, IOC_RESET, IOC_FLAG_GROUP);
+                ioctl(self.fd, IOC_ENABLE, IOC_FLAG_GROUP);
+            }
+            let result = f();
+            unsafe {
+                ioctl(self.fd, IOC_DISABLE, IOC_FLAG_GROUP);
+            }
+            (result, self.read())
+        }
+
+        fn read(&mut self) -> Counts {
+            // nr, time_enabled, time_running, then one value per member
+            let mut buf = vec![0u8; 8 * (3 + self.slots.len())];
+            let mut counts = Counts::default();
+            if self.leader.read_exact(&mut buf).is_err() {
+                return counts;
+            }
+            let word = |i: usize| {
+                let mut b = [0u8; 8];
+                b.copy_from_slice(&buf[8 * i..8 * i + 8]);
+                u64::from_ne_bytes(b)
+            };
+            let (enabled, running) = (word(1), word(2));
+            if running == 0 {
+                return counts;                                  // never got on the PMU
+            }
+            for (k, &slot) in self.slots.iter().enumerate() {
+                let v = Some((word(3 + k) as f64 * enabled as f64 / running as f64) as u64);
+                match slot {
+                    0 => counts.cycles = v,
+                    1 => counts.instructions = v,
+                    2 => counts.cache_references = v,
+                    3 => counts.cache_misses = v,
+                    4 => counts.l1d_read_misses = v,
+                    _ => counts.branch_misses = v,
+                }
+            }
+            counts
+        }
+    }
+
+    /// Why Counters::open() returned None.
+    pub fn unavailable() -> String {
+        match fs::read_to_string("/proc/sys/kernel/perf_event_paranoid") {
+            Ok(level) => format!("perf_event_open refused (no PMU in a VM, or perf_event_paranoid = {} is too strict)",
+                level.trim()),
+            Err(_) => "perf_event_open refused".to_string(),
+        }
+    }
+}
+
+// ==================== EVERYWHERE ELSE ====================
+
+#[cfg(not(all(feature = "perf", target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+mod imp {
+    use super::Counts;
+
+    pub struct Counters;
+
+    impl Counters {
+        pub fn open() -> Option<Counters> { None }
+        pub fn measure<R, F: FnOnce() -> R>(&mut self, f: F) -> (R, Counts) { (f(), Counts::default()) }
+    }
+
+    pub fn unavailable() -> String {
+        if cfg!(target_os = "linux") {
+            "not compiled in: rebuild with --features perf".to_string()
+        } else {
+            "hardware counters are only wired up for Linux".to_string()
+        }
+    }
+}
@@ -0,0 +1,307 @@
+/*
+ * report - one result, printed as text for people or JSON for scripts.
+ *
+ *   tdt4160 cache --trace t.din --format json | jq .stats.hit_rate
+ *   cargo run --release --bin memlat -- 64 --format json > memlat.json
+ *
+ * A tool collects what it measured or computed in a value that implements
+ * Report: text() is what it has always printed, json() the same facts as a
+ * tree with stable keys (snake_case, numbers as numbers, units in the key
+ * name as in "latency_ns"). --format text|json picks one of the two.
+ *
+ * --format markdown|latex is for exercise reports: a Report that has
+ * tables() (util::table) prints them as Markdown or LaTeX tables; one
+ * without prints its text in a code block or a verbatim environment.
+ *
+ * Report is implemented by the tdt4160 commands and by the demos with a
+ * result to read. Which demos those are is kept in one place, the demo
+ * table of the tdt4160 crate (cmd/demos.rs): `tdt4160 demos` shows
+ * --format in their arguments. The rest walk through an idea step by step
+ * and print text only, as does the Java compendium.
+ *
+ * Json is only a writer: objects keep their keys in insertion order and
+ * floats that are not finite become null, which JSON has no other word for.
+ */
+
+use std::fmt::{self, Write};
+
+use crate::args::Args;
+use crate::lang;
+use crate::table::Table;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object() -> Json {
+        Json::Object(Vec::new())
+    }
+
+    /// Add a key to an object: `Json::object().with("hits", 3)`.
+    pub fn with(mut self, key: &str, value: impl Into<Json>) -> Json {
+        if let Json::Object(fields) = &mut self {
+            fields.push((key.to_string(), value.into()));
+        }
+        self
+    }
+
+    pub fn array<T: Into<Json>>(items: impl IntoIterator<Item = T>) -> Json {
+        Json::Array(items.into_iter().map(Into::into).collect())
+    }
+
+    /// Indented two spaces per level, one field or element per line
+    /// except in arrays of plain values.
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, Some(0));
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: Option<usize>) {
+        let newline = |out: &mut String, level: usize| {
+            if indent.is_some() {
+                out.push('\n');
+                out.push_str(&"  ".repeat(level));
+            }
+        };
+        let level = indent.unwrap_or(0);
+        let inner = indent.map(|i| i + 1);
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            Json::Int(n) => out.push_str(&n.to_string()),
+            Json::UInt(n) => out.push_str(&n.to_string()),
+            Json::Float(x) if x.is_finite() => out.push_str(&format!("{:?}", x)),
+            Json::Float(_) => out.push_str("null"),
+            Json::Str(s) => quote(out, s),
+            Json::Array(items) if items.is_empty() => out.push_str("[]"),
+            // numbers and strings stay on one line: "registers": [0, 0, 4096, ...]
+            Json::Array(items) if indent.is_some() && items.iter().all(|i| !matches!(i, Json::Array(_) | Json::Object(_))) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    item.write(out, None);
+                }
+                out.push(']');
+            }
+            Json::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, level + 1);
+                    item.write(out, inner);
+                }
+                newline(out, level);
+                out.push(']');
+            }
+            Json::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            Json::Object(fields) => {
+                out.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, level + 1);
+                    quote(out, key);
+                    out.push_str(if indent.is_some() { ": " } else { ":" });
+                    value.write(out, inner);
+                }
+                newline(out, level);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn quote(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Compact, on one line.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out, None);
+        f.write_str(&out)
+    }
+}
+
+macro_rules! from {
+    ($variant:ident: $($t:ty),*) => {
+        $(impl From<$t> for Json {
+            fn from(v: $t) -> Json {
+                Json::$variant(v.into())
+            }
+        })*
+    };
+}
+
+from!(Bool: bool);
+from!(Int: i8, i16, i32, i64);
+from!(UInt: u8, u16, u32, u64);
+from!(Float: f32, f64);
+from!(Str: &str, String);
+
+impl From<usize> for Json {
+    fn from(v: usize) -> Json {
+        Json::UInt(v as u64)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(v: Option<T>) -> Json {
+        v.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(v: Vec<T>) -> Json {
+        Json::array(v)
+    }
+}
+
+/// A result that can be shown either way.
+pub trait Report {
+    /// The text the tool prints for people.
+    fn text(&self) -> String;
+    /// The same result for programs.
+    fn json(&self) -> Json;
+    /// The same result for a report: --format markdown|latex.
+    fn tables(&self) -> Vec<Table> {
+        Vec::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Markdown,
+    Latex,
+}
+
+impl Format {
+    /// The --format option; the tool must list "--format" as taking a value.
+    pub fn from_args(args: &mut Args) -> Result<Format, String> {
+        match args.string("--format").as_deref() {
+            None | Some("text") => Ok(Format::Text),
+            Some("json") => Ok(Format::Json),
+            Some("markdown" | "md") => Ok(Format::Markdown),
+            Some("latex" | "tex") => Ok(Format::Latex),
+            Some(other) => Err(format!("--format: expected text, json, markdown or latex, got '{}'", other)),
+        }
+    }
+
+    /// For a demo: --format, --lang (util::lang) and the positional
+    /// arguments of the process; on anything else it prints the error and
+    /// `usage` and exits with 2.
+    pub fn from_env(usage: &str) -> (Format, Vec<String>) {
+        let parsed = Args::parse(std::env::args().skip(1), &["--format", "--lang"]).and_then(|mut args| {
+            lang::set_from(args.string("--lang").as_deref())?;
+            Ok((Format::from_args(&mut args)?, args.finish()?))
+        });
+        parsed.unwrap_or_else(|e| {
+            eprintln!("{}\nusage: {}", e, usage);
+            std::process::exit(2)
+        })
+    }
+
+    pub fn render(self, report: &dyn Report) -> String {
+        match self {
+            Format::Text => report.text(),
+            Format::Json => report.json().pretty() + "\n",
+            Format::Markdown | Format::Latex => {
+                let tables = report.tables();
+                if tables.is_empty() {
+                    return match self {
+                        Format::Markdown => format!("```text\n{}```\n", report.text()),
+                        _ => format!("\\begin{{verbatim}}\n{}\\end{{verbatim}}\n", report.text()),
+                    };
+                }
+                let render = |t: &Table| if self == Format::Markdown { t.markdown() } else { t.latex() };
+                tables.iter().map(render).collect::<Vec<_>>().join("\n")
+            }
+        }
+    }
+
+    pub fn print(self, report: &dyn Report) {
+        print!("{}", self.render(report));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_values_and_escapes() {
+        let j = Json::object()
+            .with("name", "a \"quoted\"\tline\n")
+            .with("hits", 3u32)
+            .with("rate", 0.25)
+            .with("nan", f64::NAN)
+            .with("none", None::<i32>)
+            .with("list", vec![-1i32, 2])
+            .with("empty", Json::object());
+        assert_eq!(j.to_string(), r#"{"name":"a \"quoted\"\tline\n","hits":3,"rate":0.25,"nan":null,"none":null,"list":[-1,2],"empty":{}}"#);
+        assert_eq!(Json::array([1u8, 2]).pretty(), "[1, 2]");
+        assert_eq!(Json::object().with("a", Json::array([true])).pretty(), "{\n  \"a\": [true]\n}");
+        assert_eq!(Json::array([Json::object().with("b", 1)]).pretty(), "[\n  {\n    \"b\": 1\n  }\n]");
+        assert_eq!(Json::from("\u{1}").to_string(), "\"\\u0001\"");
+        assert_eq!(Json::from(1.0).to_string(), "1.0");
+    }
+
+    #[test]
+    fn format_option() {
+        let parse = |line: &str| {
+            let mut args = Args::parse(line.split_whitespace().map(String::from), &["--format"]).unwrap();
+            Format::from_args(&mut args)
+        };
+        assert_eq!(parse(""), Ok(Format::Text));
+        assert_eq!(parse("--format json"), Ok(Format::Json));
+        assert_eq!(parse("--format=md"), Ok(Format::Markdown));
+        assert!(parse("--format=xml").is_err());
+    }
+
+    #[test]
+    fn tables_or_the_text_verbatim() {
+        struct Plain;
+        impl Report for Plain {
+            fn text(&self) -> String {
+                "3 hits\n".to_string()
+            }
+            fn json(&self) -> Json {
+                Json::object().with("hits", 3)
+            }
+        }
+        assert_eq!(Format::Markdown.render(&Plain), "```text\n3 hits\n```\n");
+        assert_eq!(Format::Latex.render(&Plain), "\\begin{verbatim}\n3 hits\n\\end{verbatim}\n");
+    }
+}
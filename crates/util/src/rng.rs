@@ -0,0 +1,58 @@
+/*
+ * rng - a tiny reproducible pseudo-random generator.
+ * xorshift64 (Marsaglia 2003): three shifts and XORs per number, period
+ * 2^64 - 1. Not for cryptography; the point is that a fixed seed gives
+ * the same data, and so the same benchmark results, on every run.
+ *
+ *   let mut rng = util::rng::Rng::new(42);
+ *   let byte = rng.next() as u8;
+ *   let index = rng.below(len);
+ */
+
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Seed 0 would stay 0 forever, so it is replaced by a fixed constant.
+    pub fn new(seed: u64) -> Rng {
+        Rng(if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed })
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform enough in 0..n for demo data (the modulo bias is below 2^-32 for n < 2^32).
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+        for _ in 0..100 {
+            assert_eq!(a.next(), b.next());
+        }
+    }
+
+    #[test]
+    fn zero_seed_does_not_stick() {
+        let mut r = Rng::new(0);
+        assert_ne!(r.next(), 0);
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let mut r = Rng::new(4160);
+        assert!((0..10_000).all(|_| r.below(37) < 37));
+    }
+}
@@ -0,0 +1,98 @@
+/*
+ * snapshot - golden-file tests for the text the tools print.
+ *
+ *   let out = Command::new(env!("CARGO_BIN_EXE_bits")).output().unwrap();
+ *   snapshot::check(env!("CARGO_MANIFEST_DIR"), "bits", &String::from_utf8_lossy(&out.stdout));
+ *
+ * The text is compared with tests/snapshots/<name>.snap of the crate. A
+ * snapshot that does not exist yet is written and the test fails once, so
+ * that a person reads it before it is committed. A mismatch fails with the
+ * lines that changed. When the output was meant to change, run the tests
+ * with UPDATE_SNAPSHOTS=1 to rewrite the files and review them in git diff.
+ * It is the workflow of the insta crate, kept std-only like the rest.
+ */
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The file a snapshot lives in.
+pub fn path(manifest_dir: &str, name: &str) -> PathBuf {
+    Path::new(manifest_dir).join("tests").join("snapshots").join(format!("{}.snap", name))
+}
+
+/// Panic unless `actual` matches the stored snapshot `name`.
+pub fn check(manifest_dir: &str, name: &str, actual: &str) {
+    let file = path(manifest_dir, name);
+    let actual = actual.replace("\r\n", "\n");
+    let update = env::var("UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1");
+    match fs::read_to_string(&file) {
+        Ok(expected) if expected == actual => {}
+        Ok(_) if update => write(&file, &actual),
+        Ok(expected) => panic!("snapshot {} changed ({}); if that is intended, rerun with UPDATE_SNAPSHOTS=1\n{}",
+            name, file.display(), diff(&expected, &actual)),
+        Err(_) => {
+            write(&file, &actual);
+            if !update {
+                panic!("new snapshot {} written to {}; review it and run the tests again", name, file.display());
+            }
+        }
+    }
+}
+
+fn write(file: &Path, text: &str) {
+    if let Some(dir) = file.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    fs::write(file, text).unwrap_or_else(|e| panic!("cannot write {}: {}", file.display(), e));
+}
+
+/// The lines that differ, "-" expected and "+" actual, with common lines
+/// around them left out: a longest-common-subsequence diff.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let (a, b): (Vec<&str>, Vec<&str>) = (expected.lines().collect(), actual.lines().collect());
+    // lcs[i][j]: length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j, mut out) = (0, 0, String::new());
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            let _ = writeln!(out, "{:>5} - {}", i + 1, a[i]);
+            i += 1;
+        } else {
+            let _ = writeln!(out, "{:>5} + {}", j + 1, b[j]);
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_shows_only_changed_lines() {
+        assert_eq!(diff("a\nb\nc\n", "a\nB\nc\nd\n"), "    2 - b\n    2 + B\n    4 + d\n");
+        assert_eq!(diff("same\n", "same\n"), "");
+    }
+
+    #[test]
+    fn written_then_compared() {
+        let dir = env::temp_dir().join(format!("snapshot-test-{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let new = std::panic::catch_unwind(|| check(dir, "t", "one\n"));
+        assert!(new.is_err() && path(dir, "t").exists());
+        check(dir, "t", "one\r\n");
+        assert!(std::panic::catch_unwind(|| check(dir, "t", "two\n")).is_err());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
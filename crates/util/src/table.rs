@@ -0,0 +1,201 @@
+/*
+ * table - a result as rows and columns, for pasting into a report.
+ *
+ *   tdt4160 cache --trace t.din --format markdown >> report.md
+ *   tdt4160 riscv pipeline prog.elf --format latex > pipeline.tex
+ *
+ *   | Measure   | Value |          % Cache statistics
+ *   |:----------|------:|          \begin{tabular}{lr}
+ *   | Accesses  |   192 |          \hline
+ *   | Hit rate  |  0.67 |          Measure & Value \\ \hline
+ *                                  Accesses & 192 \\ ...
+ *
+ * text() is the same table for the terminal. Markdown comes as a pipe
+ * table (GitHub, pandoc) with its columns padded so the source reads as a
+ * table too. LaTeX comes as a bare tabular with the caption as a comment
+ * above it: it goes into a table float, a minipage or a TikZ node (node
+ * {\input{pipeline.tex}}) as it is. _ & % and their like are escaped, and
+ * "--" too, so that a bubble does not become an en dash.
+ */
+
+use std::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    pub caption: String,
+    pub header: Vec<String>,
+    pub align: Vec<Align>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Left-aligned columns with these headings.
+    pub fn new<S: Into<String>>(caption: &str, header: impl IntoIterator<Item = S>) -> Table {
+        let header: Vec<String> = header.into_iter().map(Into::into).collect();
+        Table { caption: caption.to_string(), align: vec![Align::Left; header.len()], header, rows: Vec::new() }
+    }
+
+    /// Numbers read better right-aligned: `.align(1.., Align::Right)`.
+    pub fn align(mut self, columns: impl std::ops::RangeBounds<usize>, align: Align) -> Table {
+        for (i, a) in self.align.iter_mut().enumerate() {
+            if columns.contains(&i) {
+                *a = align;
+            }
+        }
+        self
+    }
+
+    /// A row, cut or padded with empty cells to the number of columns.
+    pub fn row<S: ToString>(&mut self, cells: impl IntoIterator<Item = S>) {
+        let mut row: Vec<String> = cells.into_iter().map(|c| c.to_string()).collect();
+        row.resize(self.header.len(), String::new());
+        self.rows.push(row);
+    }
+
+    /// Padded columns under a ruled header, for the terminal.
+    pub fn text(&self) -> String {
+        let widths: Vec<usize> = (0..self.header.len())
+            .map(|i| self.rows.iter().map(|r| r[i].chars().count()).chain([self.header[i].chars().count()]).max().unwrap_or(0))
+            .collect();
+        let line = |cells: &[String]| {
+            let mut s = String::new();
+            for ((c, &w), &a) in cells.iter().zip(&widths).zip(&self.align) {
+                let _ = write!(s, "{}  ", pad(c, w, a));
+            }
+            s.trim_end().to_string() + "\n"
+        };
+        let mut s = if self.caption.is_empty() { String::new() } else { format!("{}\n\n", self.caption) };
+        s.push_str(&line(&self.header));
+        s.push_str(&line(&widths.iter().map(|&w| "-".repeat(w)).collect::<Vec<_>>()));
+        for r in &self.rows {
+            s.push_str(&line(r));
+        }
+        s
+    }
+
+    pub fn markdown(&self) -> String {
+        let cell = |s: &str| s.replace('|', "\\|");
+        let header: Vec<String> = self.header.iter().map(|h| cell(h)).collect();
+        let rows: Vec<Vec<String>> = self.rows.iter().map(|r| r.iter().map(|c| cell(c)).collect()).collect();
+        let widths: Vec<usize> = (0..header.len())
+            .map(|i| rows.iter().map(|r| r[i].chars().count()).chain([header[i].chars().count(), 3]).max().unwrap_or(3))
+            .collect();
+        let line = |cells: &[String]| {
+            let mut s = String::from("|");
+            for ((c, &w), &a) in cells.iter().zip(&widths).zip(&self.align) {
+                let _ = write!(s, " {} |", pad(c, w, a));
+            }
+            s + "\n"
+        };
+        let mut s = if self.caption.is_empty() { String::new() } else { format!("**{}**\n\n", self.caption) };
+        s.push_str(&line(&header));
+        s.push('|');
+        for (&w, &a) in widths.iter().zip(&self.align) {
+            let _ = write!(s, "{}|", match a {
+                Align::Left => format!(":{}", "-".repeat(w + 1)),
+                Align::Right => format!("{}:", "-".repeat(w + 1)),
+                Align::Center => format!(":{}:", "-".repeat(w)),
+            });
+        }
+        s.push('\n');
+        for r in &rows {
+            s.push_str(&line(r));
+        }
+        s
+    }
+
+    pub fn latex(&self) -> String {
+        let spec: String = self.align.iter().map(|a| match a {
+            Align::Left => 'l',
+            Align::Right => 'r',
+            Align::Center => 'c',
+        }).collect();
+        let line = |cells: &[String]| cells.iter().map(|c| escape(c)).collect::<Vec<_>>().join(" & ") + " \\\\";
+        let mut s = String::new();
+        if !self.caption.is_empty() {
+            let _ = writeln!(s, "% {}", self.caption);
+        }
+        let _ = writeln!(s, "\\begin{{tabular}}{{{}}}\n\\hline", spec);
+        let _ = writeln!(s, "{} \\hline", line(&self.header));
+        for r in &self.rows {
+            let _ = writeln!(s, "{}", line(r));
+        }
+        s + "\\hline\n\\end{tabular}\n"
+    }
+}
+
+fn pad(cell: &str, width: usize, align: Align) -> String {
+    let pad = width - cell.chars().count();
+    let (left, right) = match align {
+        Align::Left => (0, pad),
+        Align::Right => (pad, 0),
+        Align::Center => (pad / 2, pad - pad / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), cell, " ".repeat(right))
+}
+
+/// Text as LaTeX typesets it literally, outside math mode.
+pub fn escape(text: &str) -> String {
+    let mut s = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => s.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                s.push('\\');
+                s.push(c);
+            }
+            '~' => s.push_str("\\textasciitilde{}"),
+            '^' => s.push_str("\\textasciicircum{}"),
+            '<' => s.push_str("\\textless{}"),
+            '>' => s.push_str("\\textgreater{}"),
+            '|' => s.push_str("\\textbar{}"),
+            '-' if chars.peek() == Some(&'-') => s.push_str("-{}"),
+            c => s.push(c),
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> Table {
+        let mut t = Table::new("Cache statistics", ["Measure", "Value"]).align(1.., Align::Right);
+        t.row(["Accesses", "192"]);
+        t.row(["Hit | rate", "0.67"]);
+        t
+    }
+
+    #[test]
+    fn markdown_pads_and_aligns() {
+        assert_eq!(stats().markdown(), "**Cache statistics**\n\n\
+            | Measure     | Value |\n\
+            |:------------|------:|\n\
+            | Accesses    |   192 |\n\
+            | Hit \\| rate |  0.67 |\n");
+        let mut t = Table::new("", ["a", "b", "c"]).align(1..2, Align::Center);
+        t.row(["1"]);
+        assert_eq!(t.markdown(), "| a   |  b  | c   |\n|:----|:---:|:----|\n| 1   |     |     |\n");
+    }
+
+    #[test]
+    fn text_columns() {
+        assert_eq!(stats().text(), "Cache statistics\n\nMeasure     Value\n----------  -----\nAccesses      192\nHit | rate   0.67\n");
+    }
+
+    #[test]
+    fn latex_escapes() {
+        assert_eq!(stats().latex(), "% Cache statistics\n\\begin{tabular}{lr}\n\\hline\nMeasure & Value \\\\ \\hline\n\
+            Accesses & 192 \\\\\nHit \\textbar{} rate & 0.67 \\\\\n\\hline\n\\end{tabular}\n");
+        assert_eq!(escape("a_b & 50% -- x^2 \\ ~{}"), "a\\_b \\& 50\\% -{}- x\\textasciicircum{}2 \\textbackslash{} \\textasciitilde{}\\{\\}");
+    }
+}
@@ -0,0 +1,609 @@
+/*
+ * toml - a small TOML reader for setup files, and checking them against
+ * what a tool expects.
+ *
+ *   # lab3.toml                    let doc = toml::parse(&text)?;
+ *   [memory]                       let mut root = Fields::new(&doc, "");
+ *   stack = "64K"                  let memory = root.table("memory")?;
+ *                                  for (i, level) in root.tables("cache")?.iter().enumerate() {
+ *   [[cache]]                          let mut f = Fields::new(level, &format!("cache[{}]", i + 1));
+ *   size = "4K"                        let ways = f.number("ways")?;
+ *   wyas = 4                           f.finish()?;
+ *                                  }
+ *
+ *   line 7: cache[1].wyas is an unknown key (did you mean ways?)
+ *
+ * The subset is the one computerdesign.util.Toml reads: comments, [table]
+ * and [table.sub], [[array of tables]], dotted and quoted keys, basic and
+ * literal strings, integers (0x, 0o, 0b, _), floats, booleans, arrays
+ * (over several lines too) and inline tables. Dates and multi-line strings
+ * are errors. Every key remembers its line, so that a value that is wrong
+ * for the tool, not just for TOML, is reported where it is written.
+ */
+
+use std::fmt;
+
+use crate::args;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Array(Vec<Value>),
+    Table(Table),
+}
+
+impl Value {
+    /// The word for its type in an error message.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Value::Str(_) => "a string",
+            Value::Int(_) => "an integer",
+            Value::Float(_) => "a float",
+            Value::Bool(_) => "a boolean",
+            Value::Array(_) => "an array",
+            Value::Table(_) => "a table",
+        }
+    }
+}
+
+/// Keys in file order, each with the line it is on.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Table {
+    /// Where the table starts: its [header], or 1 for the document.
+    pub line: usize,
+    pub entries: Vec<(String, usize, Value)>,
+}
+
+impl Table {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().find(|(k, _, _)| k == key).map(|(_, _, v)| v)
+    }
+
+    pub fn line_of(&self, key: &str) -> Option<usize> {
+        self.entries.iter().find(|(k, _, _)| k == key).map(|&(_, line, _)| line)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub fn parse(text: &str) -> Result<Table, Error> {
+    let mut p = Parser { src: text.chars().collect(), pos: 0, line: 1 };
+    p.document().map_err(|message| Error { line: p.line, message })
+}
+
+// ==== Reading ====
+
+struct Parser {
+    src: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+/// The sub-table `key` of `table`, made if it is not there; for an array
+/// of tables, its last one.
+fn child<'t>(table: &'t mut Table, key: &str, line: usize) -> Result<&'t mut Table, String> {
+    let i = match table.entries.iter().position(|(k, _, _)| k == key) {
+        Some(i) => i,
+        None => {
+            table.entries.push((key.to_string(), line, Value::Table(Table { line, entries: Vec::new() })));
+            table.entries.len() - 1
+        }
+    };
+    match &mut table.entries[i].2 {
+        Value::Table(t) => Ok(t),
+        Value::Array(items) => match items.last_mut() {
+            Some(Value::Table(t)) => Ok(t),
+            _ => Err(format!("'{}' is an array, not a table", key)),
+        },
+        _ => Err(format!("'{}' is already a value, not a table", key)),
+    }
+}
+
+fn descend<'t>(mut table: &'t mut Table, path: &[String], line: usize) -> Result<&'t mut Table, String> {
+    for key in path {
+        table = child(table, key, line)?;
+    }
+    Ok(table)
+}
+
+impl Parser {
+    fn document(&mut self) -> Result<Table, String> {
+        let mut root = Table { line: 1, entries: Vec::new() };
+        let mut current: Vec<String> = Vec::new();
+        loop {
+            self.blank_lines();
+            let Some(c) = self.peek() else { return Ok(root) };
+            let line = self.line;
+            if c == '[' {
+                self.pos += 1;
+                let array = self.peek() == Some('[');
+                if array {
+                    self.pos += 1;
+                }
+                let path = self.key_path()?;
+                self.expect(']')?;
+                if array {
+                    self.expect(']')?;
+                    let (last, parents) = path.split_last().expect("a key path is not empty");
+                    let parent = descend(&mut root, parents, line)?;
+                    let fresh = Value::Table(Table { line, entries: Vec::new() });
+                    match parent.entries.iter_mut().find(|(k, _, _)| k == last) {
+                        None => parent.entries.push((last.clone(), line, Value::Array(vec![fresh]))),
+                        Some((_, _, Value::Array(items))) => items.push(fresh),
+                        Some(_) => return Err(format!("'{}' is not an array of tables", last)),
+                    }
+                } else {
+                    descend(&mut root, &path, line)?.line = line;
+                }
+                current = path;
+            } else {
+                let path = self.key_path()?;
+                self.expect('=')?;
+                self.spaces();
+                let value = self.value()?;
+                let (last, parents) = path.split_last().expect("a key path is not empty");
+                let table = descend(descend(&mut root, &current, line)?, parents, line)?;
+                if table.get(last).is_some() {
+                    return Err(format!("duplicate key '{}'", last));
+                }
+                table.entries.push((last.clone(), line, value));
+            }
+            self.end_of_line()?;
+        }
+    }
+
+    fn key_path(&mut self) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        loop {
+            self.spaces();
+            match self.peek() {
+                Some('"') => path.push(self.basic_string()?),
+                Some('\'') => path.push(self.literal_string()?),
+                _ => {
+                    let start = self.pos;
+                    while self.peek().is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                        self.pos += 1;
+                    }
+                    if start == self.pos {
+                        return Err("expected a key".to_string());
+                    }
+                    path.push(self.src[start..self.pos].iter().collect());
+                }
+            }
+            self.spaces();
+            if self.peek() != Some('.') {
+                return Ok(path);
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some('"') => Ok(Value::Str(self.basic_string()?)),
+            Some('\'') => Ok(Value::Str(self.literal_string()?)),
+            Some('[') => self.array(),
+            Some('{') => self.inline_table(),
+            Some('t' | 'f') => self.boolean(),
+            Some(c) if c == '+' || c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(format!("unexpected '{}' where a value should be", c)),
+            None => Err("a value is missing".to_string()),
+        }
+    }
+
+    fn basic_string(&mut self) -> Result<String, String> {
+        if self.src[self.pos..].starts_with(&['"', '"', '"']) {
+            return Err("multi-line strings are not supported".to_string());
+        }
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            let c = match self.next() {
+                None | Some('\n') => return Err("unterminated string".to_string()),
+                Some(c) => c,
+            };
+            match c {
+                '"' => return Ok(s),
+                '\\' => match self.next() {
+                    Some('n') => s.push('\n'),
+                    Some('t') => s.push('\t'),
+                    Some('r') => s.push('\r'),
+                    Some('"') => s.push('"'),
+                    Some('\\') => s.push('\\'),
+                    Some('u') => {
+                        let hex: String = self.src.get(self.pos..self.pos + 4).unwrap_or_default().iter().collect();
+                        let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).ok_or_else(|| format!("bad escape \\u{}", hex))?;
+                        s.push(c);
+                        self.pos += 4;
+                    }
+                    e => return Err(format!("unknown escape \\{}", e.unwrap_or(' '))),
+                },
+                c => s.push(c),
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let start = self.pos;
+        while self.peek() != Some('\'') {
+            if matches!(self.peek(), None | Some('\n')) {
+                return Err("unterminated string".to_string());
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+        Ok(self.src[start..self.pos - 1].iter().collect())
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.blank_lines();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value()?);
+            self.blank_lines();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err("expected ',' or ']' in the array".to_string()),
+            }
+        }
+    }
+
+    fn inline_table(&mut self) -> Result<Value, String> {
+        self.pos += 1;
+        let line = self.line;
+        let mut table = Table { line, entries: Vec::new() };
+        self.spaces();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Value::Table(table));
+        }
+        loop {
+            let path = self.key_path()?;
+            self.expect('=')?;
+            self.spaces();
+            let value = self.value()?;
+            let (last, parents) = path.split_last().expect("a key path is not empty");
+            let target = descend(&mut table, parents, line)?;
+            if target.get(last).is_some() {
+                return Err(format!("duplicate key '{}'", last));
+            }
+            target.entries.push((last.clone(), line, value));
+            self.spaces();
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(Value::Table(table));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn boolean(&mut self) -> Result<Value, String> {
+        for (word, value) in [("true", true), ("false", false)] {
+            if self.src[self.pos..].starts_with(&word.chars().collect::<Vec<_>>()) {
+                self.pos += word.len();
+                return Ok(Value::Bool(value));
+            }
+        }
+        Err("expected true or false".to_string())
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphanumeric() || "+-_.:".contains(c)) {
+            self.pos += 1;
+        }
+        let raw: String = self.src[start..self.pos].iter().filter(|&&c| c != '_').collect();
+        let bad = || format!("'{}' is not a number{}", raw, if raw.contains([':', '-']) && !raw.starts_with('-') { " (dates are not supported)" } else { "" });
+        let (negative, digits) = match raw.strip_prefix('-') {
+            Some(d) => (true, d),
+            None => (false, raw.strip_prefix('+').unwrap_or(&raw)),
+        };
+        let radix = [("0x", 16), ("0o", 8), ("0b", 2)].iter().find(|(p, _)| digits.starts_with(p));
+        if let Some(&(prefix, radix)) = radix {
+            let n = i64::from_str_radix(&digits[prefix.len()..], radix).map_err(|_| bad())?;
+            return Ok(Value::Int(if negative { -n } else { n }));
+        }
+        if digits.contains(['.', 'e', 'E']) {
+            return raw.parse().map(Value::Float).map_err(|_| bad());
+        }
+        raw.parse().map(Value::Int).map_err(|_| bad())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.spaces();
+        if self.peek() != Some(c) {
+            return Err(format!("expected '{}'", c));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn comment(&mut self) {
+        if self.peek() == Some('#') {
+            while !matches!(self.peek(), None | Some('\n')) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn blank_lines(&mut self) {
+        loop {
+            self.spaces();
+            self.comment();
+            if self.peek() == Some('\r') {
+                self.pos += 1;
+            }
+            if self.peek() != Some('\n') {
+                return;
+            }
+            self.pos += 1;
+            self.line += 1;
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), String> {
+        self.spaces();
+        self.comment();
+        if self.peek() == Some('\r') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            None | Some('\n') => Ok(()),
+            Some(c) => Err(format!("unexpected '{}' after the value", c)),
+        }
+    }
+}
+
+// ==== Checking ====
+
+/// The keys of one table as a tool reads them. Each getter takes the key
+/// out, checking its type; finish() then reports whatever is left, which
+/// is a typing error more often than not.
+pub struct Fields<'a> {
+    table: &'a Table,
+    /// "cache[2]" for the messages; empty for the top level.
+    path: String,
+    asked: Vec<&'static str>,
+}
+
+impl<'a> Fields<'a> {
+    pub fn new(table: &'a Table, path: &str) -> Fields<'a> {
+        Fields { table, path: path.to_string(), asked: Vec::new() }
+    }
+
+    /// cache[2].ways
+    pub fn name(&self, key: &str) -> String {
+        if self.path.is_empty() { key.to_string() } else { format!("{}.{}", self.path, key) }
+    }
+
+    /// An error about `key`, on its line or else on the table's.
+    pub fn error(&self, key: &str, message: impl fmt::Display) -> Error {
+        Error { line: self.table.line_of(key).unwrap_or(self.table.line), message: format!("{}: {}", self.name(key), message) }
+    }
+
+    fn get(&mut self, key: &'static str) -> Option<&'a Value> {
+        self.asked.push(key);
+        self.table.get(key)
+    }
+
+    fn wrong(&self, key: &str, expected: &str, got: &Value) -> Error {
+        self.error(key, format!("expected {}, not {}", expected, got.kind()))
+    }
+
+    pub fn string(&mut self, key: &'static str) -> Result<Option<&'a str>, Error> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(Value::Str(s)) => Ok(Some(s)),
+            Some(v) => Err(self.wrong(key, "a string", v)),
+        }
+    }
+
+    /// A string that must be one of `choices`.
+    pub fn choice(&mut self, key: &'static str, choices: &[&str]) -> Result<Option<&'a str>, Error> {
+        match self.string(key)? {
+            Some(s) if !choices.contains(&s) => Err(self.error(key, format!("'{}' is not one of {}", s, choices.join(", ")))),
+            s => Ok(s),
+        }
+    }
+
+    pub fn bool(&mut self, key: &'static str) -> Result<Option<bool>, Error> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(Value::Bool(b)) => Ok(Some(*b)),
+            Some(v) => Err(self.wrong(key, "true or false", v)),
+        }
+    }
+
+    /// A float; an integer will do.
+    pub fn float(&mut self, key: &'static str) -> Result<Option<f64>, Error> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(&Value::Float(x)) => Ok(Some(x)),
+            Some(&Value::Int(n)) => Ok(Some(n as f64)),
+            Some(v) => Err(self.wrong(key, "a number", v)),
+        }
+    }
+
+    /// A count, address or size: a non-negative integer, or a string in
+    /// the notation of the command line ("32K", "0x1000").
+    pub fn number(&mut self, key: &'static str) -> Result<Option<u64>, Error> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(&Value::Int(n)) if n >= 0 => Ok(Some(n as u64)),
+            Some(&Value::Int(n)) => Err(self.error(key, format!("{} is negative", n))),
+            Some(Value::Str(s)) => args::parse_number(s).map(Some).map_err(|e| self.error(key, e)),
+            Some(v) => Err(self.wrong(key, "a number or a size such as \"32K\"", v)),
+        }
+    }
+
+    pub fn table(&mut self, key: &'static str) -> Result<Option<&'a Table>, Error> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(Value::Table(t)) => Ok(Some(t)),
+            Some(v) => Err(self.wrong(key, &format!("a table, [{}]", self.name(key)), v)),
+        }
+    }
+
+    /// The tables of [[key]], in file order.
+    pub fn tables(&mut self, key: &'static str) -> Result<Vec<&'a Table>, Error> {
+        match self.get(key) {
+            None => Ok(Vec::new()),
+            Some(Value::Array(items)) => items.iter().map(|v| match v {
+                Value::Table(t) => Ok(t),
+                v => Err(self.wrong(key, &format!("tables, [[{}]]", self.name(key)), v)),
+            }).collect(),
+            Some(v) => Err(self.wrong(key, &format!("tables, [[{}]]", self.name(key)), v)),
+        }
+    }
+
+    /// The first key nobody asked for, as an error.
+    pub fn finish(self) -> Result<(), Error> {
+        let Some((key, _, _)) = self.table.entries.iter().find(|(k, _, _)| !self.asked.contains(&k.as_str())) else { return Ok(()) };
+        let near = self.asked.iter().min_by_key(|a| distance(a, key)).filter(|a| distance(a, key) <= 2);
+        let hint = match near {
+            Some(a) => format!(" (did you mean {}?)", a),
+            None => format!(" (known: {})", self.asked.join(", ")),
+        };
+        Err(Error { line: self.table.line_of(key).unwrap_or(self.table.line), message: format!("{} is an unknown key{}", self.name(key), hint) })
+    }
+}
+
+/// Levenshtein distance, for "did you mean".
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let next = (prev + usize::from(ca != cb)).min(row[j] + 1).min(row[j + 1] + 1);
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAB: &str = r#"
+# a two-level hierarchy
+name = "lab 3"
+memory.stack = "64K"
+
+[pipeline]
+forwarding = false
+
+[[cache]]
+size = 0x1000   # 4K
+ways = 2
+[[cache]]
+size = "256K"
+latency = { hit = 10, miss = 1_00 }
+list = [1, 2.5,
+        'x']
+"#;
+
+    #[test]
+    fn tables_arrays_and_lines() {
+        let doc = parse(LAB).unwrap();
+        assert_eq!(doc.get("name"), Some(&Value::Str("lab 3".to_string())));
+        let Some(Value::Table(memory)) = doc.get("memory") else { panic!() };
+        assert_eq!((memory.get("stack"), memory.line_of("stack")), (Some(&Value::Str("64K".to_string())), Some(4)));
+        let Some(Value::Array(levels)) = doc.get("cache") else { panic!() };
+        let Value::Table(l2) = &levels[1] else { panic!() };
+        assert_eq!((l2.line, l2.line_of("list")), (12, Some(15)));
+        assert_eq!(l2.get("list"), Some(&Value::Array(vec![Value::Int(1), Value::Float(2.5), Value::Str("x".to_string())])));
+        let Some(Value::Table(latency)) = l2.get("latency") else { panic!() };
+        assert_eq!(latency.get("miss"), Some(&Value::Int(100)));
+        for (bad, line, message) in [
+            ("a = 1\na = 2", 2, "duplicate key 'a'"),
+            ("a = \"open", 1, "unterminated string"),
+            ("\n\nday = 2024-10-14", 3, "'2024-10-14' is not a number (dates are not supported)"),
+            ("a = 1 2", 1, "unexpected '2' after the value"),
+            ("a = 1\n[a]", 2, "'a' is already a value, not a table"),
+        ] {
+            assert_eq!(parse(bad), Err(Error { line, message: message.to_string() }), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn fields_check_types_and_leftovers() {
+        let doc = parse(LAB).unwrap();
+        let mut root = Fields::new(&doc, "");
+        assert_eq!(root.string("name"), Ok(Some("lab 3")));
+        let levels = root.tables("cache").unwrap();
+        let pipeline = root.table("pipeline").unwrap().unwrap();
+        let mut l2 = Fields::new(levels[1], "cache[2]");
+        assert_eq!(l2.number("size"), Ok(Some(256 << 10)));
+        assert_eq!(l2.bool("latency").unwrap_err().to_string(), "line 14: cache[2].latency: expected true or false, not a table");
+        let mut p = Fields::new(pipeline, "pipeline");
+        let _ = p.bool("forwarding");
+        assert!(p.finish().is_ok());
+        let mut l1 = Fields::new(levels[0], "cache[1]");
+        let _ = (l1.number("size"), l1.number("way"));
+        assert_eq!(l1.finish().unwrap_err().to_string(), "line 11: cache[1].ways is an unknown key (did you mean way?)");
+        assert_eq!(root.finish().unwrap_err().message, "memory is an unknown key (known: name, cache, pipeline)");
+    }
+
+    #[test]
+    fn mangled_files_are_errors_not_panics() {
+        // what fuzz/fuzz_targets/toml.rs does, for a seeded sample of mutations
+        let mut rng = crate::rng::Rng::new(4160);
+        let special = b"[]{}=\"'#.,\n\\_-+0x1e: ";
+        for _ in 0..20_000 {
+            let mut text = LAB.as_bytes().to_vec();
+            for _ in 0..1 + rng.below(4) {
+                let at = rng.below(text.len());
+                text[at] = special[rng.below(special.len())];
+            }
+            if rng.below(8) == 0 {
+                text.truncate(rng.below(text.len()));
+            }
+            let text = std::str::from_utf8(&text).unwrap();
+            if let Err(e) = parse(text) {
+                assert!(e.line >= 1 && e.line <= text.lines().count().max(1) + 1, "line {} of {:?}", e.line, text);
+            }
+        }
+    }
+}
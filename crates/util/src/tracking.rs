@@ -0,0 +1,273 @@
+/*
+ * A Tracking Global Allocator
+ * A GlobalAlloc that passes every request on to the system allocator and
+ * counts on the way: allocations, frees, reallocations, bytes, the peak of
+ * live bytes and a histogram of request sizes.
+ *
+ *   Box::new, Vec::push, String, format!, HashMap ...
+ *                │
+ *                ▼
+ *   #[global_allocator] TrackingAllocator ── counts ──► report()
+ *                │
+ *                ▼
+ *   System (malloc / free / realloc)
+ *
+ * A program installs it with
+ *
+ *   #[global_allocator]
+ *   static ALLOC: TrackingAllocator = TrackingAllocator::new();
+ *
+ * and calls ALLOC.report() at the end of main; the report goes to stderr.
+ * The demos put both behind the `track_alloc` feature, so
+ * `cargo run --release --features track_alloc --bin gc` adds the report
+ * and without the feature they are built exactly as before.
+ *
+ * The counters are relaxed atomics: exact totals from any number of
+ * threads, at a few nanoseconds per allocation.
+ */
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+/// Histogram buckets: sizes 1, 2, 3-4, 5-8, ... up to 2^(BUCKETS-2), then everything larger.
+const BUCKETS: usize = 22;
+
+pub struct TrackingAllocator {
+    allocs: AtomicUsize,
+    deallocs: AtomicUsize,
+    reallocs: AtomicUsize,
+    bytes: AtomicUsize,
+    live: AtomicUsize,
+    peak: AtomicUsize,
+    window_peak: AtomicUsize,
+    sizes: [AtomicUsize; BUCKETS],
+}
+
+/// A copy of the counters at one moment.
+#[derive(Clone)]
+pub struct Stats {
+    pub allocs: usize,
+    pub deallocs: usize,
+    pub reallocs: usize,
+    /// Bytes requested, reallocations counted at their new size.
+    pub bytes: usize,
+    pub live: usize,
+    pub peak: usize,
+    pub sizes: [usize; BUCKETS],
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicUsize = AtomicUsize::new(0);
+
+impl TrackingAllocator {
+    pub const fn new() -> TrackingAllocator {
+        TrackingAllocator {
+            allocs: ZERO, deallocs: ZERO, reallocs: ZERO, bytes: ZERO, live: ZERO, peak: ZERO, window_peak: ZERO,
+            sizes: [ZERO; BUCKETS],
+        }
+    }
+
+    fn record(&self, size: usize) {
+        self.bytes.fetch_add(size, Relaxed);
+        let bucket = (size.max(1).next_power_of_two().trailing_zeros() as usize).min(BUCKETS - 1);
+        self.sizes[bucket].fetch_add(1, Relaxed);
+        let live = self.live.fetch_add(size, Relaxed) + size;
+        self.peak.fetch_max(live, Relaxed);
+        self.window_peak.fetch_max(live, Relaxed);
+    }
+
+    pub fn stats(&self) -> Stats {
+        let mut sizes = [0; BUCKETS];
+        for (s, a) in sizes.iter_mut().zip(self.sizes.iter()) {
+            *s = a.load(Relaxed);
+        }
+        Stats {
+            allocs: self.allocs.load(Relaxed),
+            deallocs: self.deallocs.load(Relaxed),
+            reallocs: self.reallocs.load(Relaxed),
+            bytes: self.bytes.load(Relaxed),
+            live: self.live.load(Relaxed),
+            peak: self.peak.load(Relaxed),
+            sizes,
+        }
+    }
+
+    /// Start a second peak from what is live now, for measuring one piece
+    /// of code; the program's own peak is kept.
+    pub fn reset_window(&self) -> usize {
+        let live = self.live.load(Relaxed);
+        self.window_peak.store(live, Relaxed);
+        live
+    }
+
+    pub fn window_peak(&self) -> usize {
+        self.window_peak.load(Relaxed)
+    }
+
+    /// The counters for running `f`, and its result. Peak is the
+    /// most live at once during `f`, above what was live when it started.
+    pub fn measure<R, F: FnOnce() -> R>(&self, f: F) -> (R, Stats) {
+        let before = self.stats();
+        let base = self.reset_window();
+        let r = f();
+        let mut s = self.stats().since(&before);
+        s.peak = self.window_peak() - base;
+        (r, s)
+    }
+
+    /// Totals for the whole program so far, with the size histogram, on stderr.
+    pub fn report(&self) {
+        let s = self.stats();
+        eprintln!("\n--- allocation report (TrackingAllocator) ---");
+        eprintln!("  {}", s.summary());
+        eprintln!("  still live at exit: {} bytes in {} block(s)", s.live, s.allocs - s.deallocs);
+        eprintln!("  request sizes:");
+        eprint!("{}", s.histogram());
+    }
+}
+
+impl Default for TrackingAllocator {
+    fn default() -> Self {
+        TrackingAllocator::new()
+    }
+}
+
+// SAFETY: every request is passed unchanged to System, which upholds the
+// GlobalAlloc contract; the counters never touch the memory itself.
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc(layout);
+        if !p.is_null() {
+            self.allocs.fetch_add(1, Relaxed);
+            self.record(layout.size());
+        }
+        p
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc_zeroed(layout);
+        if !p.is_null() {
+            self.allocs.fetch_add(1, Relaxed);
+            self.record(layout.size());
+        }
+        p
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.deallocs.fetch_add(1, Relaxed);
+        self.live.fetch_sub(layout.size(), Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let p = System.realloc(ptr, layout, new_size);
+        if !p.is_null() {
+            self.reallocs.fetch_add(1, Relaxed);
+            self.live.fetch_sub(layout.size(), Relaxed);
+            self.record(new_size);
+        }
+        p
+    }
+}
+
+impl Stats {
+    /// What happened between `before` and `self` (live and peak as of `self`).
+    pub fn since(&self, before: &Stats) -> Stats {
+        let mut sizes = [0; BUCKETS];
+        for (i, s) in sizes.iter_mut().enumerate() {
+            *s = self.sizes[i] - before.sizes[i];
+        }
+        Stats {
+            allocs: self.allocs - before.allocs,
+            deallocs: self.deallocs - before.deallocs,
+            reallocs: self.reallocs - before.reallocs,
+            bytes: self.bytes - before.bytes,
+            live: self.live,
+            peak: self.peak,
+            sizes,
+        }
+    }
+
+    /// One line: counts, bytes and the peak.
+    pub fn summary(&self) -> String {
+        format!("{} allocs, {} reallocs, {} frees, {} bytes requested, peak {} bytes live",
+            self.allocs, self.reallocs, self.deallocs, self.bytes, self.peak)
+    }
+
+    /// The size histogram as bars, one line per non-empty bucket.
+    pub fn histogram(&self) -> String {
+        let most = self.sizes.iter().copied().max().unwrap_or(0).max(1);
+        let mut s = String::new();
+        for (k, &n) in self.sizes.iter().enumerate().filter(|&(_, &n)| n > 0) {
+            let range = match k {
+                0 => "1".to_string(),
+                _ if k == BUCKETS - 1 => format!("> {}", bytes(1 << (k - 1))),
+                1 => "2".to_string(),
+                _ => format!("{}..{}", bytes((1 << (k - 1)) + 1), bytes(1 << k)),
+            };
+            s += &format!("  {:>15} B  {:>8}  {}\n", range, n, "#".repeat((n * 40).div_ceil(most)));
+        }
+        s
+    }
+}
+
+fn bytes(n: usize) -> String {
+    match n {
+        _ if n >= 1 << 20 && n.is_multiple_of(1 << 20) => format!("{}Mi", n >> 20),
+        _ if n >= 1 << 10 && n.is_multiple_of(1 << 10) => format!("{}Ki", n >> 10),
+        _ => n.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_bytes_peak_and_buckets() {
+        let a = TrackingAllocator::new();
+        let small = Layout::from_size_align(24, 8).unwrap();
+        let big = Layout::from_size_align(4096, 8).unwrap();
+        unsafe {
+            let p = a.alloc(small);
+            let q = a.alloc_zeroed(big);
+            a.dealloc(p, small);
+            let q = a.realloc(q, big, 8192);
+            a.dealloc(q, Layout::from_size_align(8192, 8).unwrap());
+        }
+        let s = a.stats();
+        assert_eq!((s.allocs, s.deallocs, s.reallocs), (2, 2, 1));
+        assert_eq!(s.bytes, 24 + 4096 + 8192);
+        assert_eq!(s.live, 0);
+        assert_eq!(s.peak, 8192);                       // the small block was freed before the realloc
+        assert_eq!(s.sizes[5], 1);                      // 17..32
+        assert_eq!((s.sizes[12], s.sizes[13]), (1, 1)); // 2049..4Ki, 4097..8Ki
+    }
+
+    #[test]
+    fn window_peak_starts_from_what_is_live() {
+        let a = TrackingAllocator::new();
+        let l = Layout::from_size_align(100, 1).unwrap();
+        unsafe {
+            let p = a.alloc(l);
+            assert_eq!(a.reset_window(), 100);
+            let q = a.alloc(l);
+            a.dealloc(q, l);
+            a.dealloc(p, l);
+        }
+        assert_eq!(a.window_peak(), 200);
+        assert_eq!(a.stats().peak, 200);
+    }
+
+    #[test]
+    fn histogram_labels() {
+        let a = TrackingAllocator::new();
+        a.record(1);
+        a.record(3000);
+        a.record(3 << 20);
+        let h = a.stats().histogram();
+        assert!(h.contains(" 1 B"), "{}", h);
+        assert!(h.contains(" 2049..4Ki B"), "{}", h);
+        assert!(h.contains("> 1Mi B"), "{}", h);
+    }
+}
@@ -0,0 +1,209 @@
+//! The functions www/app.js calls. A wasm export takes and returns only
+//! numbers, so bytes go through the module's linear memory:
+//!
+//!   let p = alloc(n)            the page copies a program or trace to p
+//!   riscv_load(p, n, base)      0 = ok, 1 = error; then dealloc(p, n)
+//!   out_ptr(), out_len()        where the JSON answer of the last call is
+//!
+//! Every call leaves its JSON answer (or {"error": "..."}) in one output
+//! buffer, which stays valid until the next call. wasm32 runs the page's
+//! calls one at a time, so the state lives in a thread_local.
+
+use std::cell::RefCell;
+
+use cache::Access;
+use util::report::Json;
+
+use crate::{CacheSim, Emulator};
+
+#[derive(Default)]
+struct State {
+    emulator: Option<Emulator>,
+    cache: Option<CacheSim>,
+    out: Vec<u8>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Store the answer; 0 for a result, 1 for an error.
+fn answer(state: &mut State, result: Result<Json, String>) -> i32 {
+    let (json, code) = match result {
+        Ok(json) => (json, 0),
+        Err(e) => (Json::object().with("error", e), 1),
+    };
+    state.out = json.to_string().into_bytes();
+    code
+}
+
+fn with<T>(f: impl FnOnce(&mut State) -> T) -> T {
+    STATE.with(|s| f(&mut s.borrow_mut()))
+}
+
+/// # Safety
+/// `ptr` must be `len` readable bytes (from `alloc`).
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        // SAFETY: the caller passes a buffer it got from alloc(len) and filled
+        unsafe { std::slice::from_raw_parts(ptr, len) }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    let mut buf = Vec::<u8>::with_capacity(len);
+    let ptr = buf.as_mut_ptr();
+    std::mem::forget(buf);
+    ptr
+}
+
+/// # Safety
+/// `ptr` and `len` must come from one call of `alloc`, freed only once.
+#[no_mangle]
+pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+    // SAFETY: alloc made this allocation with capacity len
+    drop(unsafe { Vec::from_raw_parts(ptr, 0, len) });
+}
+
+#[no_mangle]
+pub extern "C" fn out_ptr() -> *const u8 {
+    with(|s| s.out.as_ptr())
+}
+
+#[no_mangle]
+pub extern "C" fn out_len() -> usize {
+    with(|s| s.out.len())
+}
+
+/// # Safety
+/// `ptr` must be `len` bytes from `alloc`.
+#[no_mangle]
+pub unsafe extern "C" fn riscv_load(ptr: *const u8, len: usize, base: u32) -> i32 {
+    // SAFETY: passed on from the caller
+    let program = unsafe { bytes(ptr, len) };
+    with(|s| {
+        let result = Emulator::load(program, base).map(|emu| {
+            let listing = emu.disassembly();
+            s.emulator = Some(emu);
+            listing
+        });
+        answer(s, result)
+    })
+}
+
+fn emulator(s: &mut State) -> Result<&mut Emulator, String> {
+    s.emulator.as_mut().ok_or_else(|| "no program loaded".to_string())
+}
+
+/// Run up to `n` instructions (1 is single-stepping); answers the state.
+#[no_mangle]
+pub extern "C" fn riscv_step(n: u32) -> i32 {
+    with(|s| {
+        let result = emulator(s).map(|emu| {
+            emu.step(n);
+            emu.state()
+        });
+        answer(s, result)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn riscv_reset() -> i32 {
+    with(|s| {
+        let result = emulator(s).map(|emu| {
+            emu.reset();
+            emu.state()
+        });
+        answer(s, result)
+    })
+}
+
+/// Replacement 0 lru, 1 fifo, 2 random; write 0 back, 1 through.
+#[no_mangle]
+pub extern "C" fn cache_new(size: u32, line: u32, ways: u32, replacement: u32, write: u32) -> i32 {
+    let replacement = ["lru", "fifo", "random"].get(replacement as usize).copied().unwrap_or("?");
+    let write = ["back", "through"].get(write as usize).copied().unwrap_or("?");
+    with(|s| {
+        let result = CacheSim::new(size, line, ways, replacement, write).map(|sim| {
+            let state = sim.state(1.0, 100.0);
+            s.cache = Some(sim);
+            state
+        });
+        answer(s, result)
+    })
+}
+
+fn cache(s: &mut State) -> Result<&mut CacheSim, String> {
+    s.cache.as_mut().ok_or_else(|| "no cache configured".to_string())
+}
+
+#[no_mangle]
+pub extern "C" fn cache_access(addr: u32, write: u32) -> i32 {
+    let kind = if write != 0 { Access::Write } else { Access::Read };
+    with(|s| {
+        let result = cache(s).map(|sim| sim.access(addr, kind));
+        answer(s, result)
+    })
+}
+
+/// # Safety
+/// `ptr` must be `len` bytes from `alloc`, holding a Dinero trace.
+#[no_mangle]
+pub unsafe extern "C" fn cache_trace(ptr: *const u8, len: usize) -> i32 {
+    // SAFETY: passed on from the caller
+    let text = String::from_utf8_lossy(unsafe { bytes(ptr, len) }).into_owned();
+    with(|s| {
+        let result = cache(s).and_then(|sim| sim.trace(&text));
+        answer(s, result)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn cache_state(hit_time: f64, miss_penalty: f64) -> i32 {
+    with(|s| {
+        let result = cache(s).map(|sim| sim.state(hit_time, miss_penalty));
+        answer(s, result)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn logic_adders(a: u32, b: u32, width: u32) -> i32 {
+    with(|s| {
+        let result = crate::adders(a, b, width);
+        answer(s, result)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn out() -> String {
+        // SAFETY: out_ptr/out_len describe the buffer of the last call
+        String::from_utf8(unsafe { bytes(out_ptr(), out_len()) }.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn load_step_and_errors_through_memory() {
+        assert_eq!(riscv_step(1), 1);
+        assert_eq!(out(), r#"{"error":"no program loaded"}"#);
+        let program: Vec<u8> = [0x00a00513u32, 0x05d00893, 0x00000073].iter().flat_map(|w| w.to_le_bytes()).collect();
+        let p = alloc(program.len());
+        // SAFETY: p has room for program.len() bytes and is freed once
+        unsafe {
+            std::ptr::copy_nonoverlapping(program.as_ptr(), p, program.len());
+            assert_eq!(riscv_load(p, program.len(), 0x1000), 0);
+            dealloc(p, program.len());
+        }
+        assert!(out().contains(r#""text":"addi a0, zero, 10""#));
+        assert_eq!(riscv_step(10), 0);
+        assert!(out().contains(r#""exit_code":10"#));
+        assert_eq!(cache_new(64, 16, 3, 0, 0), 1);
+        assert_eq!(cache_new(64, 16, 2, 0, 0), 0);
+        assert_eq!(cache_access(0x40, 1), 0);
+        assert!(out().contains(r#""hit":false"#));
+    }
+}
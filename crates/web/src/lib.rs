@@ -0,0 +1,235 @@
+//! The simulators for the browser: RISC-V emulator, cache model and adders
+//! behind one small API that takes bytes and numbers and answers in JSON,
+//! which is all a page can pass across the wasm boundary.
+//!
+//! ```text
+//!   www/app.js ──(ptr, len, numbers)──► abi.rs exports ──► Emulator / CacheSim / adders
+//!        ▲                                                        │
+//!        └──────────── JSON text in linear memory ◄───────────────┘
+//! ```
+//!
+//! Nothing here touches std::io, files, clocks or threads, so the crate
+//! builds for wasm32-unknown-unknown as it is; the exports themselves are
+//! the `wasm` feature. Build and serve (any static file server works):
+//!
+//! ```text
+//!   rustup target add wasm32-unknown-unknown
+//!   cargo build -p web --release --target wasm32-unknown-unknown --features wasm
+//!   cp target/wasm32-unknown-unknown/release/web.wasm crates/web/www/
+//!   python3 -m http.server -d crates/web/www
+//! ```
+
+#[cfg(feature = "wasm")]
+pub mod abi;
+
+use cache::trace::{self, Reference};
+use cache::{Access, Cache, Config, Outcome, Replacement, WritePolicy};
+use riscv::elf::{self, Program};
+use riscv::syscall::{self, Outcome as Call};
+use riscv::{disassemble, Cpu, Trap};
+use util::report::Json;
+
+/// Stack bytes above the program, as `tdt4160 riscv run` gives it.
+const STACK: u32 = 64 << 10;
+
+/// A loaded program that the page steps through.
+pub struct Emulator {
+    program: Program,
+    cpu: Cpu,
+    output: Vec<u8>,
+    /// Why it stopped: "exit", "ebreak" or the trap.
+    stopped: Option<String>,
+    exit_code: Option<i32>,
+}
+
+impl Emulator {
+    /// An RV32 ELF, or a raw binary loaded at `base`.
+    pub fn load(bytes: &[u8], base: u32) -> Result<Emulator, String> {
+        let program = if elf::is_elf(bytes) { Program::parse(bytes).map_err(|e| e.to_string())? } else { Program::raw(base, bytes) };
+        let cpu = program.cpu(STACK);
+        Ok(Emulator { program, cpu, output: Vec::new(), stopped: None, exit_code: None })
+    }
+
+    /// Back to the entry point with fresh memory.
+    pub fn reset(&mut self) {
+        self.cpu = self.program.cpu(STACK);
+        self.output.clear();
+        self.stopped = None;
+        self.exit_code = None;
+    }
+
+    /// Run up to `n` instructions; false once the program has stopped.
+    pub fn step(&mut self, n: u32) -> bool {
+        for _ in 0..n {
+            if self.stopped.is_some() {
+                break;
+            }
+            let trap = match self.cpu.step() {
+                Ok(_) => continue,
+                Err(trap) => trap,
+            };
+            let output = &mut self.output;
+            match trap {
+                Trap::Ecall(pc) => match syscall::dispatch(&mut self.cpu, &mut |bytes| output.extend_from_slice(bytes)) {
+                    Call::Continue => {}
+                    Call::Exit(code) => {
+                        self.exit_code = Some(code);
+                        self.stopped = Some("exit".to_string());
+                    }
+                    Call::Unknown(n) => self.stopped = Some(format!("unknown ecall {} at {}", n, self.program.describe(pc))),
+                },
+                Trap::Breakpoint(_) => self.stopped = Some("ebreak".to_string()),
+                trap => self.stopped = Some(format!("{} in {}", trap, self.program.describe(self.cpu.pc))),
+            }
+        }
+        self.stopped.is_none()
+    }
+
+    /// Registers, pc and the next instruction, output so far.
+    pub fn state(&self) -> Json {
+        let pc = self.cpu.pc;
+        Json::object()
+            .with("pc", pc)
+            .with("where", self.program.describe(pc))
+            .with("next", self.cpu.mem.read(pc, 4).map(disassemble))
+            .with("retired", self.cpu.retired)
+            .with("registers", Json::array((0..32).map(|r| self.cpu.reg(r))))
+            .with("output", String::from_utf8_lossy(&self.output).into_owned())
+            .with("stopped", self.stopped.clone())
+            .with("exit_code", self.exit_code)
+    }
+
+    /// Every word of the executable segments, with function labels.
+    pub fn disassembly(&self) -> Json {
+        let mut lines = Vec::new();
+        for segment in self.program.segments.iter().filter(|s| s.executable) {
+            for (i, w) in segment.data.chunks_exact(4).enumerate() {
+                let addr = segment.vaddr + 4 * i as u32;
+                let word = u32::from_le_bytes([w[0], w[1], w[2], w[3]]);
+                let label = match self.program.symbol_at(addr) {
+                    Some((s, 0)) => Some(s.name.as_str()),
+                    _ => None,
+                };
+                lines.push(Json::object().with("addr", addr).with("word", word).with("label", label).with("text", disassemble(word)));
+            }
+        }
+        Json::Array(lines)
+    }
+}
+
+/// The cache model with the same keys as `tdt4160 cache --format json`.
+pub struct CacheSim {
+    cache: Cache,
+}
+
+impl CacheSim {
+    pub fn new(size: u32, line: u32, ways: u32, replacement: &str, write: &str) -> Result<CacheSim, String> {
+        let replacement = match replacement {
+            "lru" => Replacement::Lru,
+            "fifo" => Replacement::Fifo,
+            "random" => Replacement::Random,
+            other => return Err(format!("unknown replacement policy '{}'", other)),
+        };
+        let write = match write {
+            "back" => WritePolicy::WriteBackAllocate,
+            "through" => WritePolicy::WriteThroughNoAllocate,
+            other => return Err(format!("unknown write policy '{}'", other)),
+        };
+        Ok(CacheSim { cache: Cache::new(Config { size, line, ways, replacement, write })? })
+    }
+
+    pub fn access(&mut self, addr: u32, kind: Access) -> Json {
+        let out = self.cache.access(addr, kind);
+        self.outcome(addr, kind, out)
+    }
+
+    fn outcome(&self, addr: u32, kind: Access, out: Outcome) -> Json {
+        Json::object()
+            .with("kind", format!("{:?}", kind).to_lowercase())
+            .with("addr", addr)
+            .with("tag", self.cache.locate(addr).0)
+            .with("set", out.set)
+            .with("hit", out.hit)
+            .with("evicted", out.evicted)
+            .with("memory_write", out.memory_write)
+    }
+
+    /// Run a Dinero trace; the accesses, then the totals in `state`.
+    pub fn trace(&mut self, text: &str) -> Result<Json, String> {
+        let mut accesses = Vec::new();
+        for r in trace::parse_din(text).map_err(|e| e.to_string())? {
+            match r {
+                Reference::Data(kind, addr) => accesses.push(self.access(addr, kind)),
+                Reference::Fetch(addr) => accesses.push(self.access(addr, Access::Read)),
+                Reference::Flush => accesses.push(Json::object().with("kind", "flush").with("written_back", self.cache.flush())),
+            }
+        }
+        Ok(Json::Array(accesses))
+    }
+
+    /// Configuration and statistics; AMAT for the given times.
+    pub fn state(&self, hit_time: f64, miss_penalty: f64) -> Json {
+        let c = self.cache.config();
+        let (tag, index, offset) = c.split();
+        let st = self.cache.stats();
+        Json::object()
+            .with("config", Json::object()
+                .with("size", c.size).with("line", c.line).with("ways", c.ways).with("sets", c.sets())
+                .with("tag_bits", tag).with("index_bits", index).with("offset_bits", offset))
+            .with("stats", Json::object()
+                .with("accesses", st.accesses()).with("reads", st.reads).with("writes", st.writes)
+                .with("hits", st.hits).with("misses", st.misses).with("hit_rate", st.hit_rate()).with("miss_rate", st.miss_rate())
+                .with("evictions", st.evictions).with("memory_writes", st.memory_writes))
+            .with("amat", Json::object()
+                .with("hit_time", hit_time).with("miss_penalty", miss_penalty)
+                .with("cycles", self.cache.amat(hit_time, miss_penalty)))
+    }
+}
+
+/// Ripple-carry and carry-lookahead on the same operands.
+pub fn adders(a: u32, b: u32, width: u32) -> Result<Json, String> {
+    if !(1..=32).contains(&width) {
+        return Err(format!("width must be 1-32, got {}", width));
+    }
+    let adder = |s: logic::adders::Sum| Json::object().with("sum", s.value).with("carry", s.carry).with("gates", s.gates).with("delay", s.delay());
+    Ok(Json::object()
+        .with("width", width)
+        .with("ripple_carry", adder(logic::adders::ripple_carry(a, b, false, width)))
+        .with("carry_lookahead", adder(logic::adders::carry_lookahead(a, b, false, width))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use riscv::instruction::{AluOp, Instruction};
+
+    fn program(words: &[Instruction]) -> Vec<u8> {
+        words.iter().flat_map(|i| i.encode().to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn emulator_runs_to_exit_and_resets() {
+        let li = |rd, imm| Instruction::OpImm { op: AluOp::Add, rd, rs1: 0, imm };
+        let bytes = program(&[li(10, 'A' as i32), li(17, 11), Instruction::Ecall, li(10, 7), li(17, 93), Instruction::Ecall]);
+        let mut emu = Emulator::load(&bytes, 0x1000).unwrap();
+        assert!(emu.step(2));
+        assert!(emu.state().to_string().contains(r#""next":"ecall""#));
+        assert!(!emu.step(100));
+        let state = emu.state().to_string();
+        assert!(state.contains(r#""output":"A","stopped":"exit","exit_code":7"#), "{}", state);
+        emu.reset();
+        assert!(emu.state().to_string().contains(r#""retired":0"#));
+        assert!(emu.disassembly().to_string().starts_with(r#"[{"addr":4096,"word":"#));
+    }
+
+    #[test]
+    fn cache_and_adders() {
+        let mut sim = CacheSim::new(256, 16, 1, "lru", "back").unwrap();
+        let accesses = sim.trace("0 0\n0 4\n1 100\n").unwrap().to_string();
+        assert!(accesses.contains(r#""hit":true"#));
+        assert!(sim.state(1.0, 10.0).to_string().contains(r#""hits":1,"misses":2"#));
+        assert!(CacheSim::new(256, 16, 1, "mru", "back").is_err());
+        assert!(adders(0xFF, 1, 8).unwrap().to_string().contains(r#""sum":0,"carry":true"#));
+        assert!(adders(1, 1, 0).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+// The page side of crates/web/src/abi.rs: copy bytes into the module's
+// memory, call an export, read the JSON it leaves in its output buffer.
+
+const $ = (id) => document.getElementById(id);
+const REGS = ["zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4", "a5",
+  "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6"];
+const hex = (n) => "0x" + (n >>> 0).toString(16).padStart(8, "0");
+const number = (id) => Number($(id).value);       // Number() reads 0x and 0b as well
+
+let wasm;
+
+// The answer of the last call; an {"error": ...} answer is thrown.
+function answer(code) {
+  const bytes = new Uint8Array(wasm.memory.buffer, wasm.out_ptr(), wasm.out_len());
+  const json = JSON.parse(new TextDecoder().decode(bytes));
+  if (code !== 0) throw new Error(json.error);
+  $("error").textContent = "";
+  return json;
+}
+
+// Call an export that takes (ptr, len, ...rest) with `bytes` in memory.
+function withBytes(fn, bytes, ...rest) {
+  const ptr = wasm.alloc(bytes.length);
+  new Uint8Array(wasm.memory.buffer, ptr, bytes.length).set(bytes);
+  try {
+    return answer(fn(ptr, bytes.length, ...rest));
+  } finally {
+    wasm.dealloc(ptr, bytes.length);
+  }
+}
+
+function guarded(f) {
+  return (...args) => {
+    try {
+      f(...args);
+    } catch (e) {
+      $("error").textContent = e.message;
+    }
+  };
+}
+
+// ==================== RISC-V ====================
+
+function showListing(lines) {
+  $("listing").innerHTML = lines.map((l) =>
+    (l.label ? `<div><b>&lt;${l.label}&gt;:</b></div>` : "") +
+    `<div id="a${l.addr}"><code>${hex(l.addr)}  ${hex(l.word).slice(2)}  ${l.text}</code></div>`).join("");
+}
+
+function showState(s) {
+  $("registers").innerHTML = [...Array(8).keys()].map((row) => "<tr>" + [0, 8, 16, 24].map((col) =>
+    `<th>${REGS[row + col]}</th><td class="mono">${hex(s.registers[row + col])}</td>`).join("") + "</tr>").join("");
+  $("output").textContent = s.output;
+  $("where").textContent = `pc ${hex(s.pc)} ${s.where}, ${s.retired} retired` +
+    (s.stopped ? `, stopped: ${s.stopped}` + (s.exit_code !== null ? ` (exit ${s.exit_code})` : "") : "");
+  document.querySelectorAll("#listing .pc").forEach((e) => e.classList.remove("pc"));
+  const line = $("a" + s.pc);
+  if (line) {
+    line.classList.add("pc");
+    line.scrollIntoView({ block: "nearest" });
+  }
+}
+
+$("program").onchange = guarded(async () => {
+  const bytes = new Uint8Array(await $("program").files[0].arrayBuffer());
+  showListing(withBytes(wasm.riscv_load, bytes, number("base")));
+  showState(answer(wasm.riscv_step(0)));
+});
+$("step").onclick = guarded(() => showState(answer(wasm.riscv_step(1))));
+$("run").onclick = guarded(() => showState(answer(wasm.riscv_step(10000000))));
+$("reset").onclick = guarded(() => showState(answer(wasm.riscv_reset())));
+
+// ==================== CACHE ====================
+
+$("simulate").onclick = guarded(() => {
+  const replace = ["lru", "fifo", "random"].indexOf($("replace").value);
+  answer(wasm.cache_new(number("size"), number("line"), number("ways"), replace, number("write")));
+  const accesses = withBytes(wasm.cache_trace, new TextEncoder().encode($("trace").value));
+  const s = answer(wasm.cache_state(1, 100));
+  const lines = accesses.map((a) => a.kind === "flush" ? `flush: ${a.written_back} written back` :
+    `${a.kind.padEnd(5)} ${hex(a.addr)}  set ${String(a.set).padEnd(4)} ${a.hit ? "hit " : "miss"}` +
+    (a.evicted !== null ? `  evicts ${hex(a.evicted)}` : ""));
+  const c = s.config, st = s.stats;
+  $("cache-result").textContent = `${c.sets} sets, tag ${c.tag_bits} | index ${c.index_bits} | offset ${c.offset_bits} bits\n\n` +
+    lines.join("\n") + `\n\n${st.hits} hits, ${st.misses} misses, hit rate ${(100 * st.hit_rate).toFixed(1)}%, ` +
+    `AMAT ${s.amat.cycles.toFixed(2)} cycles`;
+});
+
+// ==================== ADDERS ====================
+
+$("add").onclick = guarded(() => {
+  const r = answer(wasm.logic_adders(number("a"), number("b"), number("width")));
+  const row = (name, s) => `${name.padEnd(16)} sum ${hex(s.sum)}${s.carry ? " carry" : "      "}  ` +
+    `${String(s.gates).padStart(4)} gates  delay ${s.delay}`;
+  $("adders-result").textContent = row("ripple-carry", r.ripple_carry) + "\n" + row("carry-lookahead", r.carry_lookahead);
+});
+
+WebAssembly.instantiateStreaming(fetch("web.wasm"), {})
+  .then(({ instance }) => { wasm = instance.exports; })
+  .catch((e) => { $("error").textContent = "cannot load web.wasm (built and copied here?): " + e; });
@@ -0,0 +1,464 @@
+/*
+ * Bump and Free-List Allocators
+ * Two heap allocators over one fixed byte arena, with the block headers
+ * they write into it dumped after every alloc and free - so fragmentation
+ * can be watched as it happens.
+ *
+ *   bump       |hdr|A.....|hdr|B..|hdr|C.......|   unused   |
+ *                                               ^ next
+ *              allocating = moving `next` forward; free only counts down,
+ *              the space comes back when every block is free
+ *
+ *   free list  |hdr|A.....|hdr| free |hdr|C.......|hdr| free     |
+ *                              └──── next free ────┘
+ *              first fit: take the first free block big enough, split
+ *              off the rest; free merges a block with free neighbours
+ *
+ * Every block starts with an 8-byte header in the arena itself:
+ *   bytes 0..4  block size including the header, a multiple of 8;
+ *               bit 0 = in use
+ *   bytes 4..8  free list: offset of the next free block (free blocks only)
+ *
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin alloc_demo [-- --lang no]             built-in trace
+ *      cargo run --release --bin alloc_demo -- A=40 B=24 -A C=16 ...   name=size allocates, -name frees
+ *      cargo run --release --bin alloc_demo -- --arena 128 A=40 ...
+ */
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use util::lang::{self, fill, Catalog};
+
+const HEADER: usize = 8;
+const ALIGN: usize = 8;
+const USED: u32 = 1;
+const NIL: u32 = u32::MAX;
+
+fn round_up(n: usize) -> usize {
+    n.div_ceil(ALIGN) * ALIGN
+}
+
+/// Block size for a payload of `size` bytes: header plus payload, rounded up.
+fn block_size(size: usize) -> usize {
+    HEADER + round_up(size.max(1))
+}
+
+/// The byte arena both allocators carve up, with header accessors.
+pub struct Arena {
+    bytes: Vec<u8>,
+}
+
+impl Arena {
+    pub fn new(size: usize) -> Arena {
+        assert!(size >= 2 * HEADER && size.is_multiple_of(ALIGN), "arena size must be a multiple of 8, at least 16");
+        Arena { bytes: vec![0; size] }
+    }
+
+    #[allow(clippy::len_without_is_empty)] // an arena is never empty
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn word(&self, at: usize) -> u32 {
+        u32::from_le_bytes(self.bytes[at..at + 4].try_into().unwrap())
+    }
+
+    fn set_word(&mut self, at: usize, value: u32) {
+        self.bytes[at..at + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// (block size, in use) from the header at `block`.
+    fn header(&self, block: usize) -> (usize, bool) {
+        let w = self.word(block);
+        ((w & !USED) as usize, w & USED != 0)
+    }
+
+    fn set_header(&mut self, block: usize, size: usize, used: bool) {
+        self.set_word(block, size as u32 | if used { USED } else { 0 });
+    }
+
+    fn next_free(&self, block: usize) -> Option<usize> {
+        match self.word(block + 4) {
+            NIL => None,
+            n => Some(n as usize),
+        }
+    }
+
+    fn set_next_free(&mut self, block: usize, next: Option<usize>) {
+        self.set_word(block + 4, next.map_or(NIL, |n| n as u32));
+    }
+}
+
+/// What both allocators offer: pointers are payload offsets into the arena.
+pub trait Allocator {
+    fn name(&self) -> &'static str;
+    fn alloc(&mut self, size: usize) -> Option<usize>;
+    fn free(&mut self, ptr: usize);
+    fn arena(&self) -> &Arena;
+    /// Every block from the start of the arena: (offset, size, in use).
+    fn blocks(&self) -> Vec<(usize, usize, bool)>;
+    /// Free bytes in total (headers included) and the largest free block.
+    fn free_space(&self) -> (usize, usize);
+}
+
+// ==================== BUMP ALLOCATOR ====================
+
+/// Hands out memory by moving a pointer; frees only count live blocks.
+pub struct BumpAllocator {
+    arena: Arena,
+    next: usize,
+    live: usize,
+}
+
+impl BumpAllocator {
+    pub fn new(size: usize) -> BumpAllocator {
+        BumpAllocator { arena: Arena::new(size), next: 0, live: 0 }
+    }
+}
+
+impl Allocator for BumpAllocator {
+    fn name(&self) -> &'static str {
+        "bump"
+    }
+
+    fn alloc(&mut self, size: usize) -> Option<usize> {
+        let need = block_size(size);
+        if self.next + need > self.arena.len() {
+            return None;
+        }
+        let block = self.next;
+        self.arena.set_header(block, need, true);
+        self.next += need;
+        self.live += 1;
+        Some(block + HEADER)
+    }
+
+    fn free(&mut self, ptr: usize) {
+        let (size, used) = self.arena.header(ptr - HEADER);
+        assert!(used, "double free at {}", ptr);
+        self.arena.set_header(ptr - HEADER, size, false);
+        self.live -= 1;
+        if self.live == 0 {
+            self.next = 0;                  // everything is free: start over
+        }
+    }
+
+    fn arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    fn blocks(&self) -> Vec<(usize, usize, bool)> {
+        let mut blocks = Vec::new();
+        let mut at = 0;
+        while at < self.next {
+            let (size, used) = self.arena.header(at);
+            blocks.push((at, size, used));
+            at += size;
+        }
+        blocks
+    }
+
+    fn free_space(&self) -> (usize, usize) {
+        let tail = self.arena.len() - self.next;
+        (tail, tail)
+    }
+}
+
+// ==================== FIRST-FIT FREE-LIST ALLOCATOR ====================
+
+/// Free blocks on a singly linked list in address order; first fit, split, coalesce.
+pub struct FreeListAllocator {
+    arena: Arena,
+    head: Option<usize>,
+}
+
+impl FreeListAllocator {
+    pub fn new(size: usize) -> FreeListAllocator {
+        let mut arena = Arena::new(size);
+        arena.set_header(0, size, false);
+        arena.set_next_free(0, None);
+        FreeListAllocator { arena, head: Some(0) }
+    }
+
+    /// Point `prev`'s link (or the head) at `next`.
+    fn link(&mut self, prev: Option<usize>, next: Option<usize>) {
+        match prev {
+            Some(p) => self.arena.set_next_free(p, next),
+            None => self.head = next,
+        }
+    }
+
+    fn free_blocks(&self) -> Vec<usize> {
+        let mut list = Vec::new();
+        let mut cur = self.head;
+        while let Some(b) = cur {
+            list.push(b);
+            cur = self.arena.next_free(b);
+        }
+        list
+    }
+}
+
+impl Allocator for FreeListAllocator {
+    fn name(&self) -> &'static str {
+        "free list"
+    }
+
+    fn alloc(&mut self, size: usize) -> Option<usize> {
+        let need = block_size(size);
+        let (mut prev, mut cur) = (None, self.head);
+        while let Some(b) = cur {
+            let (bsize, _) = self.arena.header(b);
+            let next = self.arena.next_free(b);
+            if bsize >= need {
+                if bsize - need >= HEADER + ALIGN {
+                    // split: the rest stays on the list in b's place
+                    let rest = b + need;
+                    self.arena.set_header(rest, bsize - need, false);
+                    self.arena.set_next_free(rest, next);
+                    self.link(prev, Some(rest));
+                    self.arena.set_header(b, need, true);
+                } else {
+                    // too small to split: the caller gets the slack too
+                    self.link(prev, next);
+                    self.arena.set_header(b, bsize, true);
+                }
+                return Some(b + HEADER);
+            }
+            prev = Some(b);
+            cur = next;
+        }
+        None
+    }
+
+    fn free(&mut self, ptr: usize) {
+        let block = ptr - HEADER;
+        let (mut size, used) = self.arena.header(block);
+        assert!(used, "double free at {}", ptr);
+        // find the free neighbours in address order
+        let (mut prev, mut next) = (None, self.head);
+        while let Some(n) = next {
+            if n > block {
+                break;
+            }
+            prev = Some(n);
+            next = self.arena.next_free(n);
+        }
+        let mut start = block;
+        if let Some(n) = next {
+            if block + size == n {
+                size += self.arena.header(n).0;         // merge with the block after
+                next = self.arena.next_free(n);
+            }
+        }
+        match prev {
+            Some(p) if p + self.arena.header(p).0 == block => {
+                start = p;                               // merge into the block before
+                size += self.arena.header(p).0;
+            }
+            _ => self.link(prev, Some(start)),
+        }
+        self.arena.set_header(start, size, false);
+        self.arena.set_next_free(start, next);
+    }
+
+    fn arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    fn blocks(&self) -> Vec<(usize, usize, bool)> {
+        let mut blocks = Vec::new();
+        let mut at = 0;
+        while at < self.arena.len() {
+            let (size, used) = self.arena.header(at);
+            blocks.push((at, size, used));
+            at += size;
+        }
+        blocks
+    }
+
+    fn free_space(&self) -> (usize, usize) {
+        let sizes: Vec<usize> = self.free_blocks().iter().map(|&b| self.arena.header(b).0).collect();
+        (sizes.iter().sum(), sizes.iter().copied().max().unwrap_or(0))
+    }
+}
+
+// ==================== DUMPS ====================
+
+/// One character per 8 bytes: '|' header, the owner's name for payload,
+/// '.' free, ' ' never handed out (bump) - then the headers as the arena holds them.
+pub fn dump(a: &dyn Allocator, names: &BTreeMap<usize, String>) -> String {
+    let arena = a.arena();
+    let mut map = vec![' '; arena.len() / ALIGN];
+    for &(at, size, used) in &a.blocks() {
+        map[at / ALIGN] = '|';
+        let fill = if used { names.get(&(at + HEADER)).and_then(|n| n.chars().next()).unwrap_or('#') } else { '.' };
+        for c in &mut map[at / ALIGN + 1..(at + size) / ALIGN] {
+            *c = fill;
+        }
+    }
+    let mut s = format!("    [{}]\n", map.iter().collect::<String>());
+    for &(at, size, used) in &a.blocks() {
+        let raw = arena.word(at);
+        let what = if used {
+            format!("used  {}", names.get(&(at + HEADER)).map_or("?", |n| n.as_str()))
+        } else if a.name() == "bump" {
+            "freed, not reusable".to_string()
+        } else {
+            match arena.next_free(at) {
+                Some(n) => format!("free  next free {}", n),
+                None => "free  next free -".to_string(),
+            }
+        };
+        s += &format!("    {:>4}: header {:08x}  size {:>4}  {}\n", at, raw, size, what);
+    }
+    let (free, largest) = a.free_space();
+    let frag = if free == 0 { 0.0 } else { 100.0 * (1.0 - largest as f64 / free as f64) };
+    s += &format!("    {}\n", fill(tr("free {} bytes, largest block {} (fits a request of {}), external fragmentation {}%"),
+        &[&free, &largest, &largest.saturating_sub(HEADER), &format!("{:.0}", frag)]));
+    s
+}
+
+// ==================== TRACES ====================
+
+pub enum Op {
+    Alloc(String, usize),
+    Free(String),
+}
+
+fn parse(arg: &str) -> Op {
+    if let Some(name) = arg.strip_prefix('-') {
+        return Op::Free(name.to_string());
+    }
+    match arg.split_once('=') {
+        Some((name, size)) if !name.is_empty() => {
+            Op::Alloc(name.to_string(), size.parse().unwrap_or_else(|_| fail(&format!("bad size in {}", arg))))
+        }
+        _ => fail(&format!("expected name=size or -name, got {}", arg)),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("alloc_demo: {}", message);
+    std::process::exit(2)
+}
+
+/// Run `ops` on `a`, dumping the heap after each one.
+pub fn run(a: &mut dyn Allocator, ops: &[Op]) {
+    println!("--- {} allocator, {} byte arena ---\n", a.name(), a.arena().len());
+    let mut ptrs: BTreeMap<String, usize> = BTreeMap::new();
+    let mut names: BTreeMap<usize, String> = BTreeMap::new();
+    for op in ops {
+        match op {
+            Op::Alloc(name, size) => match a.alloc(*size) {
+                Some(p) => {
+                    println!("alloc {} ({} bytes) -> {}", name, size, p);
+                    ptrs.insert(name.clone(), p);
+                    names.insert(p, name.clone());
+                }
+                None => {
+                    let (free, largest) = a.free_space();
+                    println!("alloc {} ({} bytes) -> {}", name, size,
+                        fill(tr("FAILED: needs a {}-byte block; {} bytes free, largest block {}"), &[&block_size(*size), &free, &largest]));
+                }
+            },
+            Op::Free(name) => match ptrs.remove(name) {
+                Some(p) => {
+                    println!("free {} ({})", name, p);
+                    a.free(p);
+                    names.remove(&p);
+                }
+                None => println!("free {}: {}", name, tr("not allocated, skipped")),
+            },
+        }
+        print!("{}", dump(a, &names));
+        println!();
+    }
+}
+
+fn builtin() -> Vec<Op> {
+    ["A=40", "B=24", "C=40", "D=24", "E=40", "-B", "-D", "F=64", "-C", "F=64", "G=8"]
+        .iter()
+        .map(|s| parse(s))
+        .collect()
+}
+
+const MAP: &str = "\
+Map: one character per 8 bytes. | = header, letter = that block's data,
+. = free, blank = never handed out. A header is size | in-use bit.
+
+";
+
+const HAPPENED: &str = "\
+What happened:
+- Free list: after freeing B and D there were 112 free bytes, yet F (64)
+  failed - no single hole was big enough. That is external fragmentation.
+  Freeing C merged B, C and D into one 112-byte block, and F fitted.
+- G (8) went into the first hole that fits (first fit), splitting it.
+- Bump: allocation is one addition, but B and D stay dead weight until
+  every block is freed, so F never fits. Good for arenas that are
+  thrown away as a whole (a compiler pass, a frame of a game).
+- Both lose space to headers and rounding up to 8 bytes: internal
+  fragmentation.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("alloc_demo [--arena bytes] [name=size | -name ...] [--lang no|en]");
+    let mut size = 256;
+    let mut ops = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--arena" => {
+                let v = rest.next().unwrap_or_else(|| fail("missing value after --arena"));
+                size = v.parse().unwrap_or_else(|_| fail(&format!("bad arena size: {}", v)));
+            }
+            other => ops.push(parse(other)),
+        }
+    }
+    let custom = !ops.is_empty();
+    if !custom {
+        ops = builtin();
+    }
+
+    println!("=== Bump and Free-List Allocators ===\n");
+    print!("{}", tr(MAP));
+    run(&mut FreeListAllocator::new(size), &ops);
+    run(&mut BumpAllocator::new(size), &ops);
+
+    if !custom {
+        print!("{}", tr(HAPPENED));
+    }
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("FAILED: needs a {}-byte block; {} bytes free, largest block {}",
+     "FEILET: trenger en blokk på {} byte; {} byte ledig, største blokk {}"),
+    ("not allocated, skipped", "ikke allokert, hoppet over"),
+    ("free {} bytes, largest block {} (fits a request of {}), external fragmentation {}%",
+     "ledig {} byte, største blokk {} (rommer en forespørsel på {}), ekstern fragmentering {}%"),
+    (MAP, "\
+Kart: ett tegn per 8 byte. | = header, bokstav = blokkens data,
+. = ledig, blank = aldri delt ut. En header er størrelse | i-bruk-bit.
+
+"),
+    (HAPPENED, "\
+Hva som skjedde:
+- Friliste: etter å ha frigjort B og D var det 112 ledige byte, likevel feilet
+  F (64) - ingen enkelt luke var stor nok. Det er ekstern fragmentering.
+  Å frigjøre C slo B, C og D sammen til én blokk på 112 byte, og F fikk plass.
+- G (8) havnet i den første luken som passer (first fit), og delte den.
+- Bump: allokering er én addisjon, men B og D blir dødvekt til
+  hver blokk er frigjort, så F får aldri plass. Bra for arenaer som
+  kastes som en helhet (et kompilatorpass, en ramme i et spill).
+- Begge taper plass til headere og avrunding opp til 8 byte: intern
+  fragmentering.
+"),
+];
@@ -0,0 +1,316 @@
+/*
+ * The Buddy System
+ * A buddy allocator simulation: blocks of 2^k units, one free list per
+ * order, split on the way down and coalesced on the way back up - with the
+ * free lists printed after every operation and the internal fragmentation
+ * of a whole request trace added up.
+ *
+ *   alloc 100 bytes, 32-byte units:  needs 128 = order 2
+ *
+ *   order 5  [               1024               ]        split
+ *   order 4  [       512       ][       512      ]       split
+ *   order 3  [  256  ][  256  ]                          split
+ *   order 2  [128][128]                                  take one
+ *
+ * A block of order k at offset a has its buddy at a XOR (unit << k): the
+ * other half of the block it was split from. On free, if the buddy is
+ * free as well, the two merge and the check repeats one order up. Finding
+ * and merging are a few bit operations, so splitting and coalescing are
+ * cheap - the price is rounding every request up to a power of two.
+ * Linux hands out physical pages this way (orders 0..10 of 4 KiB pages).
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin buddy [-- --lang no]            built-in trace
+ *      cargo run --release --bin buddy -- A=100 B=40 -A ...      name=size allocates, -name frees
+ *      cargo run --release --bin buddy -- --random 1000          internal fragmentation of a random trace
+ */
+
+use std::collections::{BTreeMap, BTreeSet};
+use util::lang::{self, fill, Catalog};
+
+pub struct Buddy {
+    unit: usize,
+    max_order: usize,
+    free: Vec<BTreeSet<usize>>,
+    /// offset -> (order, bytes requested)
+    used: BTreeMap<usize, (usize, usize)>,
+    pub log: Vec<String>,
+}
+
+impl Buddy {
+    /// 2^max_order units of `unit` bytes, all free as one block.
+    pub fn new(unit: usize, max_order: usize) -> Buddy {
+        assert!(unit.is_power_of_two(), "unit must be a power of two");
+        let mut free = vec![BTreeSet::new(); max_order + 1];
+        free[max_order].insert(0);
+        Buddy { unit, max_order, free, used: BTreeMap::new(), log: Vec::new() }
+    }
+
+    pub fn size(&self) -> usize {
+        self.unit << self.max_order
+    }
+
+    fn block(&self, order: usize) -> usize {
+        self.unit << order
+    }
+
+    /// The smallest order whose blocks hold `bytes`.
+    pub fn order_for(&self, bytes: usize) -> usize {
+        let units = bytes.max(1).div_ceil(self.unit);
+        units.next_power_of_two().trailing_zeros() as usize
+    }
+
+    pub fn alloc(&mut self, bytes: usize) -> Option<usize> {
+        let want = self.order_for(bytes);
+        let from = (want..=self.max_order).find(|&k| !self.free[k].is_empty())?;
+        let at = *self.free[from].iter().next().unwrap();
+        self.free[from].remove(&at);
+        for k in (want..from).rev() {
+            let upper = at + self.block(k);
+            self.free[k].insert(upper);
+            self.log.push(format!("split {}@{} -> {}@{} + {}@{} (upper half free)",
+                self.block(k + 1), at, self.block(k), at, self.block(k), upper));
+        }
+        self.used.insert(at, (want, bytes));
+        Some(at)
+    }
+
+    pub fn free(&mut self, at: usize) {
+        let (mut order, _) = self.used.remove(&at).expect("free of a block not allocated");
+        let mut at = at;
+        while order < self.max_order {
+            let buddy = at ^ self.block(order);
+            if !self.free[order].remove(&buddy) {
+                self.log.push(format!("buddy {}@{} is not free: stop", self.block(order), buddy));
+                break;
+            }
+            let merged = at.min(buddy);
+            self.log.push(format!("merge {}@{} + buddy {}@{} -> {}@{}",
+                self.block(order), at, self.block(order), buddy, self.block(order + 1), merged));
+            at = merged;
+            order += 1;
+        }
+        self.free[order].insert(at);
+    }
+
+    /// Free lists from the largest order down.
+    pub fn free_lists(&self) -> String {
+        let mut s = String::new();
+        for k in (0..=self.max_order).rev() {
+            let list: Vec<String> = self.free[k].iter().map(|a| a.to_string()).collect();
+            s += &format!("    order {} ({:>4} B): {}\n", k, self.block(k),
+                if list.is_empty() { "-".to_string() } else { list.join(", ") });
+        }
+        s
+    }
+
+    /// One character per unit: a letter for requested bytes, '+' for
+    /// rounding waste, '.' for free.
+    pub fn map(&self, names: &BTreeMap<usize, String>) -> String {
+        let mut cells = vec!['.'; self.size() / self.unit];
+        for (&at, &(order, bytes)) in &self.used {
+            let letter = names.get(&at).and_then(|n| n.chars().next()).unwrap_or('#');
+            let used_units = bytes.div_ceil(self.unit);
+            for u in 0..(1 << order) {
+                cells[at / self.unit + u] = if u < used_units { letter } else { '+' };
+            }
+        }
+        format!("    [{}]\n", cells.iter().collect::<String>())
+    }
+
+    /// (bytes requested, bytes in blocks) of everything allocated now.
+    pub fn usage(&self) -> (usize, usize) {
+        self.used.values().fold((0, 0), |(r, b), &(order, bytes)| (r + bytes, b + self.block(order)))
+    }
+
+    pub fn take_log(&mut self) -> String {
+        let s: String = self.log.iter().map(|l| format!("    {}\n", l)).collect();
+        self.log.clear();
+        s
+    }
+}
+
+// ==================== TRACES ====================
+
+pub enum Op {
+    Alloc(String, usize),
+    Free(String),
+}
+
+fn parse(arg: &str) -> Op {
+    if let Some(name) = arg.strip_prefix('-') {
+        return Op::Free(name.to_string());
+    }
+    match arg.split_once('=') {
+        Some((name, size)) if !name.is_empty() => {
+            Op::Alloc(name.to_string(), size.parse().unwrap_or_else(|_| fail(&format!("bad size in {}", arg))))
+        }
+        _ => fail(&format!("expected name=size or -name, got {}", arg)),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("buddy: {}", message);
+    std::process::exit(2)
+}
+
+/// Run `ops`, printing each step; returns the summed (requested, allocated) bytes.
+fn run(b: &mut Buddy, ops: &[Op]) -> (usize, usize) {
+    let mut ptrs: BTreeMap<String, usize> = BTreeMap::new();
+    let mut names: BTreeMap<usize, String> = BTreeMap::new();
+    let (mut requested, mut allocated) = (0, 0);
+    println!("  {}\n", fill(tr("start: one free block of {} bytes"), &[&b.size()]));
+    print!("{}", b.free_lists());
+    println!();
+    for op in ops {
+        match op {
+            Op::Alloc(name, size) => match b.alloc(*size) {
+                Some(at) => {
+                    let block = b.block(b.order_for(*size));
+                    println!("alloc {} ({} bytes) -> order {}, {} bytes at {} ({} wasted)",
+                        name, size, b.order_for(*size), block, at, block - size);
+                    requested += size;
+                    allocated += block;
+                    ptrs.insert(name.clone(), at);
+                    names.insert(at, name.clone());
+                }
+                None => println!("alloc {} ({} bytes) -> {}", name, size,
+                    fill(tr("FAILED: {} bytes free, but no block of order {} or more"),
+                        &[&(b.size() - b.usage().1), &b.order_for(*size)])),
+            },
+            Op::Free(name) => match ptrs.remove(name) {
+                Some(at) => {
+                    println!("free {} ({})", name, at);
+                    b.free(at);
+                    names.remove(&at);
+                }
+                None => println!("free {}: {}", name, tr("not allocated, skipped")),
+            },
+        }
+        print!("{}", b.take_log());
+        print!("{}", b.map(&names));
+        print!("{}", b.free_lists());
+        let (r, a) = b.usage();
+        if a > 0 {
+            println!("    {}", fill(tr("in use: {} bytes requested in {} bytes of blocks"), &[&r, &a]));
+        }
+        println!();
+    }
+    (requested, allocated)
+}
+
+fn report(requested: usize, allocated: usize) {
+    print!("{}", fill(tr(FRAGMENTATION), &[&requested, &allocated, &(allocated - requested),
+        &format!("{:.1}", 100.0 * (allocated - requested) as f64 / allocated.max(1) as f64)]));
+}
+
+/// Sizes uniform in 1..=max: the expected waste of rounding up to a power of two.
+fn random_trace(count: usize) {
+    let mut seed: u64 = 4160;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 33) as usize
+    };
+    println!("--- {} ---\n", fill(tr("Internal fragmentation of {} random requests"), &[&count]));
+    println!("  size range    requested    allocated   wasted");
+    for max in [64, 500, 1000, 4096] {
+        let b = Buddy::new(32, 20);
+        let (mut req, mut got) = (0, 0);
+        for _ in 0..count {
+            let size = 1 + next() % max;
+            req += size;
+            got += b.block(b.order_for(size));
+        }
+        println!("  1..{:<8} {:>11} {:>12}   {:>5.1}%", max, req, got, 100.0 * (got - req) as f64 / got as f64);
+    }
+    print!("{}", tr(QUARTER));
+}
+
+const FRAGMENTATION: &str = "\
+Internal fragmentation over the trace: {} bytes requested, {} allocated,
+{} wasted = {}% of what was handed out.
+";
+
+const QUARTER: &str = "
+  About a quarter of every block is lost on average for sizes spread
+  evenly: a request just over a power of two wastes almost half its block.
+  Small requests are worse still, as the 32-byte unit is the least you get.
+";
+
+const HAPPENED: &str = "
+What happened:
+- A (100) needed order 2: 1024 split into 512, 256 and 128, leaving the
+  upper halves on the free lists on the way down.
+- Freeing B merged it with its free buddy at 192; freeing C merged
+  nothing, as its buddy (0..256) was still split for A.
+- E (400) then failed with 384 bytes free: no free block of 512. The
+  buddy system fragments externally too, only in power-of-two steps.
+- Freeing A merged 128 + 128, then 256 + 256: E fitted in the 512.
+- Freeing D and E merged the two halves back into one 1024 block.
+
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("buddy [name=size | -name ...] [--random count] [--lang no|en]");
+    if args.first().map(String::as_str) == Some("--random") {
+        let count = args.get(1).map_or(1000, |s| s.parse().unwrap_or_else(|_| fail(&format!("bad count: {}", s))));
+        random_trace(count);
+        return;
+    }
+    let custom = !args.is_empty();
+    let ops: Vec<Op> = if custom {
+        args.iter().map(|s| parse(s)).collect()
+    } else {
+        ["A=100", "B=40", "C=240", "D=300", "-B", "-C", "E=400", "-A", "E=400", "-D", "-E"].iter().map(|s| parse(s)).collect()
+    };
+
+    println!("=== The Buddy System ===\n");
+    println!("{}", tr("1024 bytes in 32-byte units; blocks of order k are 32 << k bytes."));
+    println!("{}\n", tr("Map: one character per unit - letter = requested bytes, + = rounding waste, . = free."));
+    let mut b = Buddy::new(32, 5);
+    let (requested, allocated) = run(&mut b, &ops);
+    report(requested, allocated);
+    if !custom {
+        print!("{}", tr(HAPPENED));
+        random_trace(1000);
+    }
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("start: one free block of {} bytes", "start: én ledig blokk på {} byte"),
+    ("FAILED: {} bytes free, but no block of order {} or more", "FEILET: {} byte ledig, men ingen blokk av orden {} eller mer"),
+    ("not allocated, skipped", "ikke allokert, hoppet over"),
+    ("in use: {} bytes requested in {} bytes of blocks", "i bruk: {} byte forespurt i {} byte med blokker"),
+    (FRAGMENTATION, "\
+Intern fragmentering over sporet: {} byte forespurt, {} allokert,
+{} bortkastet = {}% av det som ble delt ut.
+"),
+    ("Internal fragmentation of {} random requests", "Intern fragmentering for {} tilfeldige forespørsler"),
+    (QUARTER, "
+  Omtrent en fjerdedel av hver blokk går tapt i snitt når størrelsene er jevnt
+  fordelt: en forespørsel like over en toerpotens kaster bort nesten halve blokken.
+  Små forespørsler er enda verre, siden enheten på 32 byte er det minste du får.
+"),
+    ("1024 bytes in 32-byte units; blocks of order k are 32 << k bytes.",
+     "1024 byte i enheter på 32 byte; blokker av orden k er 32 << k byte."),
+    ("Map: one character per unit - letter = requested bytes, + = rounding waste, . = free.",
+     "Kart: ett tegn per enhet - bokstav = forespurte byte, + = avrundingssløsing, . = ledig."),
+    (HAPPENED, "
+Hva som skjedde:
+- A (100) trengte orden 2: 1024 ble delt i 512, 256 og 128, og de øvre
+  halvdelene havnet på frilistene på veien ned.
+- Å frigjøre B slo den sammen med den ledige buddyen på 192; å frigjøre C slo
+  ingenting sammen, siden buddyen (0..256) fortsatt var delt for A.
+- E (400) feilet så med 384 byte ledig: ingen ledig blokk på 512. Buddy-
+  systemet fragmenterer eksternt også, bare i toerpotens-steg.
+- Å frigjøre A slo sammen 128 + 128, så 256 + 256: E fikk plass i 512.
+- Å frigjøre D og E slo de to halvdelene sammen til én blokk på 1024 igjen.
+
+"),
+];
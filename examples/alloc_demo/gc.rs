@@ -0,0 +1,309 @@
+/*
+ * A Toy Mark-Sweep Garbage Collector
+ * Objects in an arena, pointing at each other by index; a collector that
+ * marks everything reachable from the roots and sweeps the rest - with a
+ * trace of every object it marks and frees.
+ *
+ *   roots: a ──► A ──► B ──► C          mark: follow pointers from the
+ *          b ──► B                            roots, set a mark bit
+ *                                       sweep: walk the whole heap, free
+ *                D ◄──► E   (cycle)           every unmarked object,
+ *                F                            clear the marks
+ *
+ * D, E and F are garbage: nothing reachable points to them. Reference
+ * counting would free F but never the D-E cycle, as each still holds a
+ * reference to the other; tracing from the roots does not care about
+ * cycles. The price: the program stops while the collector runs, and
+ * memory is only reclaimed when it does.
+ *
+ * Rust itself has no collector: each value has one owner and is freed
+ * when the owner goes out of scope - deterministic, but Rc cycles leak,
+ * as the last section shows. The explanations are printed in Norwegian
+ * with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin gc [-- --lang no]
+ *      cargo run --release --features track_alloc --bin gc   (Rust's own allocation counts)
+ */
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use util::lang::{self, fill, Catalog};
+
+#[cfg(feature = "track_alloc")]
+#[global_allocator]
+static ALLOC: util::tracking::TrackingAllocator = util::tracking::TrackingAllocator::new();
+
+/// A reference to an object: its slot in the arena.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ref(usize);
+
+pub struct Object {
+    pub name: String,
+    pub fields: Vec<Option<Ref>>,
+    marked: bool,
+}
+
+/// Statistics of one collection.
+pub struct Collection {
+    pub marked: Vec<String>,
+    pub swept: Vec<String>,
+}
+
+pub struct Heap {
+    slots: Vec<Option<Object>>,
+    roots: BTreeMap<String, Ref>,
+    pub log: Vec<String>,
+    pub collections: usize,
+}
+
+impl Heap {
+    pub fn new(capacity: usize) -> Heap {
+        Heap { slots: (0..capacity).map(|_| None).collect(), roots: BTreeMap::new(), log: Vec::new(), collections: 0 }
+    }
+
+    /// A new object with `fields` null pointers; collects first if the heap is full.
+    pub fn alloc(&mut self, name: &str, fields: usize) -> Result<Ref, String> {
+        if !self.slots.iter().any(Option::is_none) {
+            self.log.push(format!("alloc {}: heap full, collecting", name));
+            self.collect();
+        }
+        let slot = self.slots.iter().position(Option::is_none)
+            .ok_or_else(|| format!("out of memory: all {} objects are reachable", self.slots.len()))?;
+        self.slots[slot] = Some(Object { name: name.to_string(), fields: vec![None; fields], marked: false });
+        Ok(Ref(slot))
+    }
+
+    pub fn get(&self, r: Ref) -> &Object {
+        self.slots[r.0].as_ref().expect("dangling reference")
+    }
+
+    pub fn set_field(&mut self, obj: Ref, field: usize, target: Option<Ref>) {
+        self.slots[obj.0].as_mut().expect("dangling reference").fields[field] = target;
+    }
+
+    /// A root is a variable of the running program: a local, a global.
+    pub fn set_root(&mut self, name: &str, target: Option<Ref>) {
+        match target {
+            Some(r) => self.roots.insert(name.to_string(), r),
+            None => self.roots.remove(name),
+        };
+    }
+
+    // ==================== MARK ====================
+
+    fn mark(&mut self) -> Vec<String> {
+        let mut marked = Vec::new();
+        let mut work: Vec<(Ref, String)> = self.roots.iter().map(|(n, &r)| (r, format!("root {}", n))).collect();
+        work.reverse();
+        while let Some((r, via)) = work.pop() {
+            let obj = self.slots[r.0].as_mut().expect("dangling reference");
+            if obj.marked {
+                self.log.push(format!("  mark  {:<3} via {:<10} already marked", obj.name, via));
+                continue;
+            }
+            obj.marked = true;
+            self.log.push(format!("  mark  {:<3} via {}", obj.name, via));
+            marked.push(obj.name.clone());
+            let name = obj.name.clone();
+            for (i, f) in obj.fields.iter().enumerate().rev() {
+                if let Some(t) = f {
+                    work.push((*t, format!("{}.{}", name, i)));
+                }
+            }
+        }
+        marked
+    }
+
+    // ==================== SWEEP ====================
+
+    fn sweep(&mut self) -> Vec<String> {
+        let mut swept = Vec::new();
+        for slot in self.slots.iter_mut() {
+            match slot {
+                Some(obj) if obj.marked => obj.marked = false,
+                Some(obj) => {
+                    self.log.push(format!("  sweep {}", obj.name));
+                    swept.push(obj.name.clone());
+                    *slot = None;
+                }
+                None => {}
+            }
+        }
+        swept
+    }
+
+    pub fn collect(&mut self) -> Collection {
+        self.collections += 1;
+        self.log.push(format!("collection #{}: mark from {} root(s)", self.collections, self.roots.len()));
+        let marked = self.mark();
+        let swept = self.sweep();
+        self.log.push(format!("  -> {} live, {} freed", marked.len(), swept.len()));
+        Collection { marked, swept }
+    }
+
+    /// The arena slot by slot, and every object's pointers.
+    pub fn dump(&self) -> String {
+        let cells: Vec<String> = self.slots.iter()
+            .map(|s| s.as_ref().map_or("·".to_string(), |o| o.name.clone()))
+            .collect();
+        let mut s = format!("    heap  [{}]\n", cells.iter().map(|c| format!("{:<4}", c)).collect::<String>().trim_end());
+        let roots: Vec<String> = self.roots.iter().map(|(n, r)| format!("{} → {}", n, self.get(*r).name)).collect();
+        s += &format!("    roots {}\n", if roots.is_empty() { "(none)".to_string() } else { roots.join(", ") });
+        for o in self.slots.iter().flatten() {
+            let fields: Vec<String> = o.fields.iter()
+                .map(|f| f.map_or("null".to_string(), |r| self.get(r).name.clone()))
+                .collect();
+            if !fields.is_empty() {
+                s += &format!("    {:<3} → {}\n", o.name, fields.join(", "));
+            }
+        }
+        s
+    }
+
+    pub fn take_log(&mut self) -> String {
+        let s: String = self.log.iter().map(|l| format!("    {}\n", l)).collect();
+        self.log.clear();
+        s
+    }
+}
+
+// ==================== RUST'S WAY: OWNERSHIP AND Rc ====================
+
+/// A node that says when it is dropped.
+struct Node {
+    name: &'static str,
+    next: RefCell<Option<Rc<Node>>>,
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        println!("    drop {}", self.name);
+    }
+}
+
+fn ownership() {
+    println!("--- Without a collector: Rust ownership ---\n");
+    println!("  {}", tr("a local and a Box going out of scope:"));
+    {
+        let _c = Node { name: "C", next: RefCell::new(None) };
+        let _b = Box::new(Node { name: "B", next: RefCell::new(None) });
+        println!("    {}", tr("end of scope"));
+    }
+    println!("  {}\n", tr("freed at once, in reverse order of creation - no tracing, no pause."));
+
+    println!("  {}", tr("an Rc cycle D <-> E:"));
+    {
+        let d = Rc::new(Node { name: "D", next: RefCell::new(None) });
+        let e = Rc::new(Node { name: "E", next: RefCell::new(Some(Rc::clone(&d))) });
+        *d.next.borrow_mut() = Some(Rc::clone(&e));
+        println!("    {}", fill(tr("strong counts: D {}, E {}"), &[&Rc::strong_count(&d), &Rc::strong_count(&e)]));
+        println!("    {}", tr("end of scope"));
+    }
+    print!("{}", tr(CYCLE_LEAKS));
+}
+
+const CYCLE_LEAKS: &str = "  nothing dropped: each count only fell to 1. Reference counting cannot
+  see that the pair is unreachable; the collector above freed the same
+  cycle. (Rust's fix: make one direction a Weak reference.)
+";
+
+const HEAP_FULL: &str = "  The odd N objects were garbage as soon as they were made; the collector
+  ran only when the heap filled up and reclaimed them then. Once every
+  object was reachable, collecting could not help: out of memory.
+
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+// ==================== DEMONSTRATION ====================
+
+fn main() -> Result<(), String> {
+    lang::args("gc [--lang no|en]");
+    println!("=== A Toy Mark-Sweep Garbage Collector ===\n");
+    let mut heap = Heap::new(8);
+
+    let a = heap.alloc("A", 1)?;
+    let b = heap.alloc("B", 2)?;
+    let c = heap.alloc("C", 0)?;
+    let d = heap.alloc("D", 1)?;
+    let e = heap.alloc("E", 1)?;
+    heap.alloc("F", 0)?;
+    heap.set_field(a, 0, Some(b));
+    heap.set_field(b, 0, Some(c));
+    heap.set_field(d, 0, Some(e));
+    heap.set_field(e, 0, Some(d));
+    heap.set_root("a", Some(a));
+    heap.set_root("b", Some(b));
+
+    println!("--- 1) Six objects, two roots ---\n");
+    print!("{}", heap.dump());
+    println!("\n  {}", tr("collect:"));
+    heap.collect();
+    print!("{}", heap.take_log());
+    print!("{}", heap.dump());
+    println!("  {}\n", tr("B was reached twice and marked once. The D-E cycle and F were swept."));
+
+    println!("--- 2) The program drops root a and makes B point to itself ---\n");
+    heap.set_root("a", None);
+    heap.set_field(b, 1, Some(b));
+    heap.collect();
+    print!("{}", heap.take_log());
+    print!("{}", heap.dump());
+    println!("  {}\n", tr("A is garbage now; B survives its own self-reference only because b holds it."));
+
+    println!("--- 3) Allocating until the heap is full ---\n");
+    let mut last = b;
+    for i in 0.. {
+        let name = format!("N{}", i);
+        match heap.alloc(&name, 1) {
+            Ok(n) => {
+                if i % 2 == 0 {
+                    heap.set_field(n, 0, Some(last));   // even ones join a chain from root "list"
+                    heap.set_root("list", Some(n));
+                    last = n;
+                }
+            }
+            Err(msg) => {
+                print!("{}", heap.take_log());
+                println!("    alloc {}: {}", name, msg);
+                break;
+            }
+        }
+    }
+    print!("{}", heap.take_log());
+    print!("{}", heap.dump());
+    print!("{}", tr(HEAP_FULL));
+
+    ownership();
+    #[cfg(feature = "track_alloc")]
+    ALLOC.report();
+    Ok(())
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("a local and a Box going out of scope:", "en lokal variabel og en Box som går ut av skop:"),
+    ("end of scope", "slutten av skopet"),
+    ("freed at once, in reverse order of creation - no tracing, no pause.",
+     "frigjort med en gang, i motsatt rekkefølge av opprettelsen - ingen sporing, ingen pause."),
+    ("an Rc cycle D <-> E:", "en Rc-syklus D <-> E:"),
+    ("strong counts: D {}, E {}", "sterke tellere: D {}, E {}"),
+    (CYCLE_LEAKS, "  ingenting droppet: hver teller falt bare til 1. Referansetelling kan ikke
+  se at paret er uoppnåelig; samleren over frigjorde den samme
+  syklusen. (Rusts løsning: gjør den ene retningen til en Weak-referanse.)
+"),
+    ("collect:", "samle:"),
+    ("B was reached twice and marked once. The D-E cycle and F were swept.",
+     "B ble nådd to ganger og merket én gang. D-E-syklusen og F ble feid bort."),
+    ("A is garbage now; B survives its own self-reference only because b holds it.",
+     "A er søppel nå; B overlever sin egen selvreferanse bare fordi b holder den."),
+    (HEAP_FULL, "  De odde N-objektene var søppel så snart de ble laget; samleren
+  kjørte først da heapen ble full og tok dem tilbake da. Når hvert
+  objekt var oppnåelig, kunne ikke innsamling hjelpe: tomt for minne.
+
+"),
+];
@@ -0,0 +1,139 @@
+/*
+ * What Allocates? Counting Every Heap Request
+ * Everyday Rust snippets measured with the TrackingAllocator from
+ * util::tracking: how many times each one calls the allocator, for how many
+ * bytes, and how much was live at the peak.
+ *
+ *   snippet                          allocs  reallocs   bytes   peak
+ *   Vec::new + 1000 pushes of u64         1         8   16352   8192
+ *   Vec::with_capacity(1000) + pushes     1         0    8000   8000
+ *
+ * A growing Vec doubles its capacity when it is full, so n pushes cost
+ * about log2(n) reallocations, each of which may copy everything so far,
+ * and it can end up with nearly twice the memory it needs. Knowing the
+ * size up front makes it one allocation. Iterators, slices, arrays and
+ * most integer and float work allocate nothing at all.
+ *
+ * The explanation is printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin tracking_demo -- [--lang no]
+ */
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use util::lang::{self, Catalog};
+use util::tracking::{Stats, TrackingAllocator};
+
+#[global_allocator]
+static ALLOC: TrackingAllocator = TrackingAllocator::new();
+
+fn row(name: &str, s: &Stats) {
+    println!("  {:<38} {:>6} {:>8} {:>9} {:>9}", name, s.allocs, s.reallocs, s.bytes, s.peak);
+}
+
+const READING: &str = "
+  bytes = requested in total (a reallocation counts its new size);
+  peak  = most bytes live at once during the snippet.
+
+  Growing a Vec one push at a time takes its capacity to 4, 8, 16, ...:
+  8 reallocations for 1000, each free to move and copy the lot. collect()
+  knows the length of a range and allocates once. A HashMap that grows
+  rehashes every element into a new table each time it doubles. Rc::clone
+  copies a pointer and bumps a count; clone() copies every String.
+  Zero-sized values never reach the allocator.
+";
+
+fn main() {
+    lang::args("tracking_demo [--lang no|en]");
+    println!("=== What Allocates? Counting Every Heap Request ===\n");
+    println!("  {:<38} {:>6} {:>8} {:>9} {:>9}", "snippet", "allocs", "reallocs", "bytes", "peak");
+    println!("  {}", "-".repeat(74));
+
+    let (_, s) = ALLOC.measure(|| {
+        let mut v = Vec::new();
+        for i in 0..1000u64 {
+            v.push(i);
+        }
+        v.len()
+    });
+    row("Vec::new + 1000 pushes of u64", &s);
+    let (_, s) = ALLOC.measure(|| {
+        let mut v = Vec::with_capacity(1000);
+        for i in 0..1000u64 {
+            v.push(i);
+        }
+        v.len()
+    });
+    row("Vec::with_capacity(1000) + 1000 pushes", &s);
+    let (_, s) = ALLOC.measure(|| (0..1000u64).collect::<Vec<_>>().len());
+    row("(0..1000).collect::<Vec<_>>()", &s);
+    let (_, s) = ALLOC.measure(|| (0..1000u64).map(|x| x * x).sum::<u64>());
+    row("(0..1000).map(|x| x * x).sum()", &s);
+    let (_, s) = ALLOC.measure(|| [0u64; 1000].iter().sum::<u64>());
+    row("[0u64; 1000] on the stack", &s);
+
+    let (_, s) = ALLOC.measure(|| {
+        let mut out = String::new();
+        for i in 0..100 {
+            out += &format!("{},", i);
+        }
+        out.len()
+    });
+    row("100 x `out += &format!(..)`", &s);
+    let (_, s) = ALLOC.measure(|| {
+        use std::fmt::Write;
+        let mut out = String::with_capacity(400);
+        for i in 0..100 {
+            write!(out, "{},", i).unwrap();
+        }
+        out.len()
+    });
+    row("100 x write! into String::with_capacity", &s);
+
+    let words: Vec<String> = (0..100).map(|i| format!("word{}", i)).collect();
+    let (_, s) = ALLOC.measure(|| words.clone().len());
+    row("clone a Vec of 100 Strings", &s);
+    let shared = Rc::new(words);
+    let (_, s) = ALLOC.measure(|| Rc::clone(&shared).len());
+    row("Rc::clone of the same Vec", &s);
+    let (_, s) = ALLOC.measure(|| Box::new(42u64));
+    row("Box::new(42u64)", &s);
+    let (_, s) = ALLOC.measure(|| Box::new(()));
+    row("Box::new(()) - zero-sized", &s);
+
+    let (_, s) = ALLOC.measure(|| {
+        let mut m = HashMap::new();
+        for i in 0..1000u32 {
+            m.insert(i, i);
+        }
+        m.len()
+    });
+    row("HashMap: 1000 inserts", &s);
+    let (_, s) = ALLOC.measure(|| {
+        let mut m = HashMap::with_capacity(1000);
+        for i in 0..1000u32 {
+            m.insert(i, i);
+        }
+        m.len()
+    });
+    row("HashMap::with_capacity(1000) + inserts", &s);
+
+    print!("{}", lang::tr(NO, READING));
+
+    drop(shared);
+    ALLOC.report();
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[(READING, "
+  bytes = forespurt i alt (en reallokering teller sin nye størrelse);
+  peak  = flest byte i live samtidig under kodebiten.
+
+  En Vec som vokser ett push om gangen, får kapasitet 4, 8, 16, ...:
+  8 reallokeringer for 1000, og hver av dem kan flytte og kopiere alt. collect()
+  vet lengden av et range og allokerer én gang. En HashMap som vokser,
+  hasher hvert element inn i en ny tabell hver gang den dobles. Rc::clone
+  kopierer en peker og teller opp; clone() kopierer hver String.
+  Verdier med størrelse null når aldri allokatoren.
+")];
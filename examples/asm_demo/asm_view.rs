@@ -0,0 +1,266 @@
+/*
+ * asm_view - What the compiler made of kernels.rs, at -O0 and -O3
+ * Compiles kernels.rs twice with `rustc --emit asm`, cuts each function out
+ * of the listings and shows them side by side, marking the instructions
+ * that matter:
+ *
+ *   overflow   the flag test after an add (jo / seto / b.vs), only at -O0 for `+`
+ *   bounds     the compare and jump to panic_bounds_check guarding a v[i]
+ *   panic      calls into the panic machinery
+ *   simd       vector registers: the loop was vectorized
+ *
+ *   --- sum_gather ---   -O0: 46 instr, bounds   -O3: 19 instr, bounds
+ *   --- sum_iter ---     -O0: 8 instr            -O3: 35 instr, simd
+ *
+ * -O0 keeps every value on the stack, calls every iterator method and
+ * checks every `+` - that is what a debug build runs. -O3 keeps values in
+ * registers, inlines, removes checks it can prove unnecessary, hoists the
+ * ones it can out of the loop and vectorizes; a check it cannot move
+ * (an index read from memory) stays in the loop and keeps it scalar.
+ *
+ * The listing is Intel syntax on x86_64. With --target any installed target
+ * works, e.g. riscv64gc-unknown-linux-gnu after `rustup target add`, to see
+ * the course's own instruction set. If cargo-show-asm is installed it is
+ * pointed out: inside a Cargo crate `cargo asm` gives the same view.
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin asm_view -- [function] [--target T] [--full] [--source kernels.rs] [--lang no]
+ *      (needs rustc on PATH; kernels.rs is found next to this file)
+ */
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{self, Command};
+
+use util::asm::{parse, Listing};
+use util::lang::{self, fill, Catalog};
+
+const LEVELS: [u32; 2] = [0, 3];
+const KERNELS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/asm_demo/kernels.rs");
+/// Functions this short are shown whole; longer ones as marked excerpts.
+const SHORT: usize = 16;
+
+// ==================== COMPILING ====================
+
+fn compile(source: &str, level: u32, target: Option<&str>) -> Result<String, String> {
+    let dir = env::temp_dir().join("asm_view");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let out: PathBuf = dir.join(format!("kernels-O{}.s", level));
+    let mut cmd = Command::new("rustc");
+    cmd.args(["--crate-type=lib", "--emit", "asm", "-C", "debuginfo=0"])
+        .arg("-C").arg(format!("opt-level={}", level))
+        .arg("-o").arg(&out)
+        .arg(source);
+    let x86 = match target {
+        Some(t) => {
+            cmd.arg("--target").arg(t);
+            t.starts_with("x86_64")
+        }
+        None => cfg!(target_arch = "x86_64"),
+    };
+    if x86 {
+        cmd.args(["-C", "llvm-args=-x86-asm-syntax=intel"]);
+    }
+    let result = cmd.output().map_err(|e| format!("cannot run rustc: {}", e))?;
+    if !result.status.success() {
+        return Err(String::from_utf8_lossy(&result.stderr).into_owned());
+    }
+    fs::read_to_string(&out).map_err(|e| e.to_string())
+}
+
+// ==================== PARSING ====================
+
+// ==================== MARKING ====================
+
+fn own_mark(line: &str) -> Option<&'static str> {
+    let op = line.split_whitespace().next().unwrap_or("");
+    if line.contains("panic_bounds_check") {
+        Some("bounds")
+    } else if line.contains("overflow") || ["jo", "jno", "seto", "setno", "b.vs", "b.vc"].contains(&op) {
+        Some("overflow")
+    } else if line.contains("panic") {
+        Some("panic")
+    } else if ["xmm", "ymm", "zmm"].iter().any(|r| line.contains(r)) || line.contains(".4s") || op.starts_with("vadd") {
+        Some("simd")
+    } else {
+        None
+    }
+}
+
+/// Each line's mark. A jump to a block that ends in a panic gets that
+/// block's mark, so the compare-and-branch guarding v[i] shows as "bounds".
+fn marks(listing: &Listing) -> Vec<Option<&'static str>> {
+    let mut out: Vec<Option<&'static str>> = listing.lines.iter().map(|l| own_mark(l)).collect();
+    let mut block_mark: Vec<(String, &'static str)> = Vec::new();
+    let mut label: Option<&str> = None;
+    for (line, m) in listing.lines.iter().zip(out.iter()) {
+        if let Some(l) = line.strip_suffix(':') {
+            label = Some(l);
+        } else if let (Some(l), Some(m)) = (label, *m) {
+            if m != "simd" && !block_mark.iter().any(|(b, _)| b == l) {
+                block_mark.push((l.to_string(), m));
+            }
+        }
+    }
+    for (line, m) in listing.lines.iter().zip(out.iter_mut()) {
+        let mut words = line.split_whitespace();
+        let op = words.next().unwrap_or("");
+        let jump = (op.starts_with('j') && op != "jmp") || op.starts_with("b.") || op.starts_with("cb") || op.starts_with("tb")
+            || ["beq", "bne", "blt", "bge", "bltu", "bgeu"].contains(&op);
+        if jump && m.is_none() {
+            if let Some(&(_, bm)) = block_mark.iter().find(|(b, _)| line.ends_with(b.as_str())) {
+                *m = Some(bm);
+            }
+        }
+    }
+    out
+}
+
+/// The whole function if it is short, else the marked lines with one line of
+/// context and "..." for what is left out.
+fn excerpt(listing: &Listing, full: bool) -> Vec<String> {
+    let n = listing.lines.len();
+    let marked = marks(listing);
+    let keep: Vec<bool> = if full || n <= SHORT {
+        vec![true; n]
+    } else {
+        (0..n).map(|i| marked[i].is_some() || (i > 0 && marked[i - 1].is_some()) || (i + 1 < n && marked[i + 1].is_some())).collect()
+    };
+    let mut out = Vec::new();
+    let mut skipped = 0;
+    for i in 0..n {
+        if !keep[i] {
+            skipped += 1;
+            continue;
+        }
+        if skipped > 0 {
+            out.push(format!("      ... {} more", skipped));
+            skipped = 0;
+        }
+        let line = &listing.lines[i];
+        let tag = marked[i].map_or(String::new(), |t| format!("  <- {}", t));
+        let indent = if line.ends_with(':') { "  " } else { "      " };
+        out.push(format!("{}{}{}", indent, line, tag));
+    }
+    if skipped > 0 {
+        out.push(format!("      ... {} more", skipped));
+    }
+    out
+}
+
+fn summary(listing: &Listing) -> String {
+    if let Some(ref other) = listing.alias_of {
+        return format!("merged into {} (identical code)", other);
+    }
+    let mut tags: Vec<&str> = marks(listing).into_iter().flatten().collect();
+    tags.sort();
+    tags.dedup();
+    format!("{} instr{}", listing.instructions(),
+        if tags.is_empty() { String::new() } else { format!(", {}", tags.join(" ")) })
+}
+
+const LOOK_FOR: &str = "
+What to look for:
+  add_plain      -O0 tests the overflow flag and calls a panic; -O3 is one add
+                 (release builds turn overflow checks off unless asked).
+  add_checked    the flag test survives -O3: it IS the result (Some / None).
+  sum_indexed    one compare of n against v.len() BEFORE the loop covers every
+                 v[i]: the check is hoisted and the loop is still vectorized.
+  sum_gather     v[idx[k]] cannot be proven in range: compare + branch in the
+                 loop, one element at a time.
+  sum_indexed_len / sum_iter   no check left, vector registers at -O3;
+                 at -O0 the iterator is two calls - debug builds are slow.
+";
+
+const CARGO_ASM: &str = "
+cargo-show-asm is installed: in a Cargo crate, `cargo asm --lib <function>`
+shows the same listing with the Rust source interleaved.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("asm_view [function] [--target T] [--full] [--source kernels.rs] [--lang no|en]");
+    let mut target = None;
+    let mut source = KERNELS.to_string();
+    let mut only = None;
+    let mut full = false;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--target" => { i += 1; target = args.get(i).cloned(); }
+            "--source" => { i += 1; source = args.get(i).cloned().unwrap_or(source); }
+            "--full" => full = true,
+            name => only = Some(name.to_string()),
+        }
+        i += 1;
+    }
+
+    let mut listings = Vec::new();
+    for &level in LEVELS.iter() {
+        match compile(&source, level, target.as_deref()) {
+            Ok(asm) => listings.push(parse(&asm)),
+            Err(e) => {
+                eprintln!("rustc failed for {} at -O{}:\n{}", source, level, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    println!("=== {} compiled at -O0 and -O3 ({}) ===", source,
+        target.as_ref().map_or(if cfg!(target_arch = "x86_64") { "host, Intel syntax" } else { "host" }, |t| t.as_str()));
+    // in the order they appear in the source
+    let text = fs::read_to_string(&source).unwrap_or_default();
+    let mut names: Vec<String> = listings[0].iter().map(|l| l.name.clone()).collect();
+    names.sort_by_key(|n| text.find(&format!("fn {}(", n)).unwrap_or(usize::MAX));
+    for name in names.iter().filter(|n| only.as_ref().is_none_or(|o| o == *n)) {
+        let at = |k: usize| listings[k].iter().find(|l| &l.name == name);
+        let (o0, o3) = match (at(0), at(1)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => continue,
+        };
+        println!("\n--- {} ---   -O0: {}   -O3: {}", name, summary(o0), summary(o3));
+        for (&level, listing) in LEVELS.iter().zip([o0, o3].iter()) {
+            if let Some(ref other) = listing.alias_of {
+                println!("  -O{}: {}", level, fill(tr("the same instructions as {}"), &[other]));
+                continue;
+            }
+            println!("  -O{}:", level);
+            for line in excerpt(listing, full) {
+                println!("{}", line);
+            }
+        }
+    }
+
+    print!("{}", tr(LOOK_FOR));
+
+    let cargo_asm = Command::new("cargo").args(["asm", "--version"]).output();
+    if cargo_asm.map(|o| o.status.success()).unwrap_or(false) {
+        print!("{}", tr(CARGO_ASM));
+    }
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("the same instructions as {}", "de samme instruksjonene som {}"),
+    (LOOK_FOR, "
+Hva du skal se etter:
+  add_plain      -O0 tester overflytflagget og kaller en panic; -O3 er én add
+                 (release-bygg slår av overflytsjekkene med mindre du ber om dem).
+  add_checked    flaggtesten overlever -O3: den ER resultatet (Some / None).
+  sum_indexed    én sammenligning av n mot v.len() FØR løkken dekker hver
+                 v[i]: sjekken er løftet ut, og løkken er fortsatt vektorisert.
+  sum_gather     v[idx[k]] kan ikke bevises å være innenfor: sammenligning +
+                 hopp i løkken, ett element om gangen.
+  sum_indexed_len / sum_iter   ingen sjekk igjen, vektorregistre på -O3;
+                 på -O0 er iteratoren to kall - debug-bygg er trege.
+"),
+    (CARGO_ASM, "
+cargo-show-asm er installert: i en Cargo-crate viser `cargo asm --lib <funksjon>`
+den samme listingen med Rust-kildekoden flettet inn.
+"),
+];
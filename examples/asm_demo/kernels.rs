@@ -0,0 +1,80 @@
+/*
+ * The functions asm_view.rs compiles and shows. Each pair does the same
+ * work two ways; the assembly shows what the difference costs.
+ *
+ * #[no_mangle] keeps the symbol names readable in the listing, and
+ * #![no_std] means only `core` is needed, so any installed target works:
+ *
+ *   rustc --crate-type=lib --emit asm -C opt-level=3 kernels.rs
+ *   rustc --crate-type=lib --emit asm -C opt-level=3 --target riscv64gc-unknown-linux-gnu kernels.rs
+ */
+
+#![no_std]
+
+// ==================== ARITHMETIC ====================
+
+/// `+` on i32: panics on overflow when overflow checks are on (the default
+/// without optimization), wraps silently when they are off.
+#[no_mangle]
+pub fn add_plain(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+/// Always wraps: one add instruction at any optimization level.
+#[no_mangle]
+pub fn add_wrapping(a: i32, b: i32) -> i32 {
+    a.wrapping_add(b)
+}
+
+/// Overflow becomes a value: the add plus a test of the overflow flag.
+#[no_mangle]
+pub fn add_checked(a: i32, b: i32) -> Option<i32> {
+    a.checked_add(b)
+}
+
+/// Clamps instead: the add plus a conditional select of MIN/MAX.
+#[no_mangle]
+pub fn add_saturating(a: i32, b: i32) -> i32 {
+    a.saturating_add(b)
+}
+
+// ==================== LOOPS ====================
+
+/// Indexing with an unrelated bound: i < v.len() is not known, but since i
+/// only counts up, one compare of n against v.len() before the loop covers
+/// every v[i] - the compiler hoists the check and still vectorizes.
+#[no_mangle]
+pub fn sum_indexed(v: &[u32], n: usize) -> u32 {
+    let mut s = 0u32;
+    for i in 0..n {
+        s = s.wrapping_add(v[i]);
+    }
+    s
+}
+
+/// Indexing up to v.len(): the compiler proves i < len and drops the check.
+#[no_mangle]
+pub fn sum_indexed_len(v: &[u32]) -> u32 {
+    let mut s = 0u32;
+    for i in 0..v.len() {
+        s = s.wrapping_add(v[i]);
+    }
+    s
+}
+
+/// Indices from another array: nothing is known about idx[k], so every
+/// load keeps its own compare and branch and the loop stays scalar.
+#[no_mangle]
+pub fn sum_gather(v: &[u32], idx: &[u32]) -> u32 {
+    let mut s = 0u32;
+    for &k in idx {
+        s = s.wrapping_add(v[k as usize]);
+    }
+    s
+}
+
+/// The iterator: no index, nothing to check, vectorized at -O3.
+#[no_mangle]
+pub fn sum_iter(v: &[u32]) -> u32 {
+    v.iter().fold(0u32, |s, &x| s.wrapping_add(x))
+}
@@ -0,0 +1,425 @@
+/*
+ * showasm - One function of any demo, as each target and -O level compiles it
+ * Cuts the named function out of a demo's source, together with the items
+ * of the same file it uses, compiles that on its own with
+ * `rustc --emit asm` for every target and opt level asked for, and prints
+ * the listings in columns:
+ *
+ *   showasm benchmark_demo/branch_predict.rs sum_branchy -O 0,3
+ *
+ *   host -O0, 40 instr                               host -O3, 17 instr
+ *   ------------------                               ------------------
+ *       sub rsp, 56                                      xor eax, eax
+ *       mov qword ptr [rsp + 24], 0                      test rsi, rsi
+ *       call qword ptr [rip + core::slice::iter::…       je .LBB0_4
+ *
+ * The function is made #[no_mangle] and #[inline(never)] so it survives
+ * optimization under its own name; the rest is what the demo has. Putting
+ * x86-64 next to riscv32 shows the same loop in the ISA the course teaches
+ * and in the one the laptop runs: compare and branch in one instruction,
+ * or a flag-setting compare and a conditional jump.
+ *
+ * Targets: x86-64 (Intel syntax), riscv32 (riscv32im-unknown-none-elf, the
+ * RV32IM of the lectures), riscv64, aarch64, or any installed target
+ * triple; `rustup target add riscv32im-unknown-none-elf` first. For a
+ * bare-metal target the crate is #![no_std], so the function may only use
+ * core. On the host the repository's crates (util, arithmetic, riscv, ...)
+ * are there too, from the build showasm is part of; for other targets only
+ * std or core and the file's own items are. The labels are printed in
+ * Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin showasm -- <file.rs> [function] [-O 0,1,2,3,s,z]
+ *          [--target T,...] [--width N] [--lang no]
+ *      (file relative to examples/ or a path; without a function, the list of them)
+ */
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command};
+
+use util::asm::{parse, Listing};
+use util::lang::{self, fill, Catalog};
+
+const EXAMPLES: &str = env!("CARGO_MANIFEST_DIR");
+
+// ==================== THE FUNCTION AND WHAT IT NEEDS ====================
+
+/// A top-level item of a source file: its text, the name it defines
+/// (for an impl, the type), and the keyword it starts with.
+struct Item {
+    text: String,
+    kind: String,
+    name: String,
+}
+
+/// Braces opened minus closed on a line, outside strings, chars and
+/// comments, and the last character of its code; `block` is whether a
+/// /* comment is still open.
+fn scan(line: &str, block: &mut bool) -> (i32, Option<char>) {
+    let chars: Vec<char> = line.chars().collect();
+    let (mut depth, mut i, mut string, mut last) = (0, 0, false, None);
+    while i < chars.len() {
+        let (c, next) = (chars[i], chars.get(i + 1).copied());
+        if *block {
+            if c == '*' && next == Some('/') {
+                *block = false;
+                i += 1;
+            }
+        } else if string {
+            if c == '\\' {
+                i += 1;
+            } else if c == '"' {
+                string = false;
+            }
+        } else {
+            match (c, next) {
+                ('/', Some('/')) => break,
+                ('/', Some('*')) => *block = true,
+                ('"', _) => string = true,
+                // '{' and '\'' but not a lifetime
+                ('\'', Some('\\')) => i += 3,
+                ('\'', Some(_)) if chars.get(i + 2) == Some(&'\'') => i += 2,
+                ('{', _) => depth += 1,
+                ('}', _) => depth -= 1,
+                _ => {}
+            }
+            if !c.is_whitespace() && !*block {
+                last = Some(chars[i.min(chars.len() - 1)]);
+            }
+        }
+        i += 1;
+    }
+    (depth, last)
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_')).filter(|w| !w.is_empty())
+}
+
+impl Item {
+    fn new(text: String) -> Item {
+        let head = text.lines().map(str::trim).find(|l| !(l.starts_with('#') || l.starts_with("//"))).unwrap_or("");
+        let keywords = ["fn", "struct", "enum", "union", "const", "static", "type", "trait", "impl", "mod", "use", "macro_rules"];
+        let mut ws = words(head).skip_while(|w| !keywords.contains(w));
+        let kind = ws.next().unwrap_or("").to_string();
+        let name = match kind.as_str() {
+            // impl<T> Trait for Type<T>: the type; impl Type: the type
+            "impl" => {
+                let rest = head.split_once("impl").map_or("", |(_, r)| r);
+                let rest = rest.trim_start().strip_prefix('<').map_or(rest, |r| r.split_once('>').map_or(r, |(_, r)| r));
+                let rest = rest.split_once(" for ").map_or(rest, |(_, r)| r);
+                words(rest).next().unwrap_or("")
+            }
+            "const" if head.contains("const fn") => words(head).skip_while(|&w| w != "fn").nth(1).unwrap_or(""),
+            "use" => "",
+            _ => ws.next().unwrap_or(""),
+        }.to_string();
+        drop(ws);
+        Item { text, kind, name }
+    }
+}
+
+/// The items of a file, outer comments, blank lines and #![...] left out.
+fn items(source: &str) -> Vec<Item> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let (mut depth, mut block) = (0, false);
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if depth == 0 && current.is_empty() {
+            let was_block = block;
+            if was_block || trimmed.starts_with("/*") {
+                scan(line, &mut block);
+                continue;
+            }
+            if trimmed.is_empty() || trimmed.starts_with("#!") || (trimmed.starts_with("//") && !trimmed.starts_with("///")) {
+                continue;
+            }
+        }
+        current.push_str(line);
+        current.push('\n');
+        let (change, last) = scan(line, &mut block);
+        depth += change;
+        let attribute = trimmed.starts_with("#[") || trimmed.starts_with("///");
+        if depth == 0 && !block && !attribute && matches!(last, Some('}' | ';')) {
+            items.push(Item::new(std::mem::take(&mut current)));
+        }
+    }
+    items
+}
+
+/// `use a::{b, c::d as e};` as (path, the name it binds): (a::b, b), (a::c::d, e).
+fn use_paths(tree: &str, prefix: &str, out: &mut Vec<(String, String)>) {
+    let tree = tree.trim();
+    if let Some((head, inner)) = tree.split_once('{') {
+        let inner = inner.trim_end().strip_suffix('}').unwrap_or(inner);
+        let prefix = format!("{}{}", prefix, head.trim());
+        let mut level = 0;
+        let mut start = 0;
+        for (i, c) in inner.char_indices() {
+            match c {
+                '{' => level += 1,
+                '}' => level -= 1,
+                ',' if level == 0 => {
+                    use_paths(&inner[start..i], &prefix, out);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        use_paths(&inner[start..], &prefix, out);
+    } else if !tree.is_empty() {
+        let (path, alias) = match tree.split_once(" as ") {
+            Some((p, a)) => (p.trim(), a.trim()),
+            None => (tree, tree.rsplit("::").next().unwrap_or(tree)),
+        };
+        let path = format!("{}{}", prefix, path);
+        let path = path.strip_suffix("::self").map_or(path.clone(), str::to_string);
+        let alias = if alias == "self" { path.rsplit("::").next().unwrap_or("").to_string() } else { alias.to_string() };
+        out.push((path, alias));
+    }
+}
+
+/// A crate of its own: `function` exported, the items it uses
+/// (transitively) and the imports those need.
+fn extract(source: &str, function: &str, bare: bool) -> Result<String, String> {
+    let items = items(source);
+    let Some(start) = items.iter().position(|it| it.kind == "fn" && it.name == function) else {
+        return Err(format!("no function {} in the file", function));
+    };
+    let text = &items[start].text;
+    let signature = &text[text.find(&format!("fn {}", function)).unwrap_or(0)..];
+    let signature = &signature[..signature.find('{').unwrap_or(signature.len())];
+    if signature.contains(&format!("fn {}<", function)) || signature.contains("impl ") {
+        return Err(format!("{} is generic: there is no code for it until it is called with a type", function));
+    }
+    let mut used: BTreeSet<usize> = BTreeSet::from([start]);
+    let mut names: BTreeSet<String> = words(&items[start].text).map(str::to_string).collect();
+    loop {
+        let more: Vec<usize> = (0..items.len())
+            .filter(|i| !used.contains(i) && !items[*i].name.is_empty() && items[*i].name != "main" && names.contains(&items[*i].name))
+            .collect();
+        if more.is_empty() {
+            break;
+        }
+        for i in more {
+            names.extend(words(&items[i].text).map(str::to_string));
+            used.insert(i);
+        }
+    }
+
+    let mut out = String::from("#![allow(dead_code, unused)]\n");
+    if bare {
+        out.push_str("#![no_std]\n");
+    }
+    for it in items.iter().filter(|it| it.kind == "use") {
+        let cfg: String = it.text.lines().map(str::trim).filter(|l| l.starts_with("#[cfg")).map(|l| format!("{}\n", l)).collect();
+        let tree = it.text.lines().map(str::trim).filter(|l| !l.starts_with('#')).collect::<Vec<_>>().join(" ");
+        let tree = tree.trim().trim_start_matches("pub ").trim_start_matches("use ").trim_end_matches(';');
+        let mut paths = Vec::new();
+        use_paths(tree, "", &mut paths);
+        for (path, alias) in paths.into_iter().filter(|(_, alias)| names.contains(alias)) {
+            let path = if bare { path.replacen("std::", "core::", 1) } else { path };
+            out.push_str(&cfg);
+            if path.ends_with(&format!("::{}", alias)) {
+                out.push_str(&format!("use {};\n", path));
+            } else {
+                out.push_str(&format!("use {} as {};\n", path, alias));
+            }
+        }
+    }
+    for &i in &used {
+        out.push('\n');
+        if i != start {
+            out.push_str(&items[i].text);
+            continue;
+        }
+        out.push_str("#[no_mangle]\n#[inline(never)]\n");
+        for line in items[i].text.lines() {
+            let t = line.trim();
+            if t.starts_with("#[inline") || t == "#[no_mangle]" {
+                continue;
+            }
+            match line.strip_prefix("fn ") {
+                Some(rest) => out.push_str(&format!("pub fn {}\n", rest)),
+                None => out.push_str(&format!("{}\n", line)),
+            }
+        }
+    }
+    Ok(out)
+}
+
+// ==================== COMPILING ====================
+
+/// (label, triple) for a name on the command line.
+fn target(name: &str) -> (String, Option<String>) {
+    let triple = match name {
+        "host" => None,
+        "x86-64" | "x86_64" => Some("x86_64-unknown-linux-gnu"),
+        "riscv32" | "rv32" => Some("riscv32im-unknown-none-elf"),
+        "riscv64" | "rv64" => Some("riscv64gc-unknown-linux-gnu"),
+        "aarch64" | "arm64" => Some("aarch64-unknown-linux-gnu"),
+        triple => Some(triple),
+    };
+    (name.to_string(), triple.map(str::to_string))
+}
+
+/// --extern for the repository's crates, from the build of the demos this
+/// binary belongs to: for the host only, they are not built for the others.
+fn crates() -> Vec<String> {
+    let Some(deps) = env::current_exe().ok().and_then(|exe| Some(exe.parent()?.join("deps"))) else { return Vec::new() };
+    let mut args = vec!["-L".to_string(), format!("dependency={}", deps.display())];
+    let files: Vec<_> = fs::read_dir(&deps).map(|d| d.flatten().collect()).unwrap_or_default();
+    for name in ["arithmetic", "cache", "logic", "riscv", "util"] {
+        let newest = files.iter()
+            .filter(|f| f.file_name().to_string_lossy().starts_with(&format!("lib{}-", name)) && f.path().extension().is_some_and(|e| e == "rlib"))
+            .max_by_key(|f| f.metadata().and_then(|m| m.modified()).ok());
+        if let Some(f) = newest {
+            args.extend(["--extern".to_string(), format!("{}={}", name, f.path().display())]);
+        }
+    }
+    args
+}
+
+fn compile(crate_text: &str, level: &str, triple: Option<&str>) -> Result<String, String> {
+    let dir = env::temp_dir().join("showasm");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let tag = triple.unwrap_or("host");
+    let src = dir.join(format!("{}.rs", tag));
+    let out = dir.join(format!("{}-O{}.s", tag, level));
+    fs::write(&src, crate_text).map_err(|e| e.to_string())?;
+    let mut cmd = Command::new("rustc");
+    cmd.args(["--crate-type=lib", "--edition=2021", "--emit", "asm", "-C", "debuginfo=0"])
+        .env("CARGO_MANIFEST_DIR", EXAMPLES)
+        .arg("-C").arg(format!("opt-level={}", level))
+        .arg("-o").arg(&out)
+        .arg(&src);
+    let x86 = match triple {
+        Some(t) => {
+            cmd.arg("--target").arg(t);
+            t.starts_with("x86_64")
+        }
+        None => {
+            cmd.args(crates());
+            cfg!(target_arch = "x86_64")
+        }
+    };
+    if x86 {
+        cmd.args(["-C", "llvm-args=-x86-asm-syntax=intel"]);
+    }
+    let result = cmd.output().map_err(|e| format!("cannot run rustc: {}", e))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr).into_owned();
+        let hint = match triple {
+            Some(t) if stderr.contains("can't find crate for `core`") || stderr.contains("can't find crate for `std`") => {
+                format!("\nthe {} target is not installed: rustup target add {}", t, t)
+            }
+            _ => String::new(),
+        };
+        return Err(format!("{}{}", stderr.trim_end(), hint));
+    }
+    fs::read_to_string(&out).map_err(|e| e.to_string())
+}
+
+// ==================== SIDE BY SIDE ====================
+
+fn cell(text: &str, width: usize) -> String {
+    let n = text.chars().count();
+    if n <= width {
+        format!("{}{}", text, " ".repeat(width - n))
+    } else {
+        format!("{}…", text.chars().take(width - 1).collect::<String>())
+    }
+}
+
+fn print_columns(columns: &[(String, Vec<String>)], width: usize) {
+    let gap = 3;
+    let col = (width.saturating_sub(gap * (columns.len() - 1)) / columns.len()).max(12);
+    let row = |cells: Vec<String>| println!("{}", cells.join(&" ".repeat(gap)).trim_end());
+    row(columns.iter().map(|(title, _)| cell(title, col)).collect());
+    row(columns.iter().map(|(title, _)| cell(&"-".repeat(title.chars().count()), col)).collect());
+    let height = columns.iter().map(|(_, lines)| lines.len()).max().unwrap_or(0);
+    for i in 0..height {
+        row(columns.iter().map(|(_, lines)| cell(lines.get(i).map_or("", String::as_str), col)).collect());
+    }
+}
+
+fn body(listing: &Listing) -> Vec<String> {
+    if let Some(ref other) = listing.alias_of {
+        return vec![fill(tr("the same code as {}"), &[other])];
+    }
+    listing.lines.iter().map(|l| if l.ends_with(':') { l.clone() } else { format!("    {}", l) }).collect()
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("showasm: {}", message);
+    process::exit(1)
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+const USAGE: &str = "showasm <file.rs> [function] [-O 0,3] [--target x86-64,riscv32] [--width N] [--lang no|en]";
+
+fn main() {
+    let args = lang::args(USAGE);
+    let mut levels = vec!["0".to_string(), "3".to_string()];
+    let mut targets = vec!["host".to_string()];
+    let mut width: usize = env::var("COLUMNS").ok().and_then(|c| c.parse().ok()).unwrap_or(120);
+    let mut positional = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let value = |i: usize| args.get(i + 1).cloned().unwrap_or_else(|| fail(&format!("{} needs a value", args[i])));
+        match args[i].as_str() {
+            "-O" | "--opt" => { levels = value(i).split(',').map(str::to_string).collect(); i += 1; }
+            "--target" => { targets = value(i).split(',').map(str::to_string).collect(); i += 1; }
+            "--width" => { width = value(i).parse().unwrap_or_else(|_| fail("--width takes a number")); i += 1; }
+            a if a.starts_with("-O") && a.len() > 2 => levels = a[2..].split(',').map(str::to_string).collect(),
+            a => positional.push(a.to_string()),
+        }
+        i += 1;
+    }
+    if let Some(bad) = levels.iter().find(|l| !["0", "1", "2", "3", "s", "z"].contains(&l.as_str())) {
+        fail(&format!("no opt level {}: 0, 1, 2, 3, s or z", bad));
+    }
+    let Some(file) = positional.first() else {
+        fail(&format!("usage: {}", USAGE))
+    };
+    let path = if Path::new(file).exists() { file.clone() } else { format!("{}/{}", EXAMPLES, file) };
+    let source = fs::read_to_string(&path).unwrap_or_else(|e| fail(&format!("{}: {}", file, e)));
+
+    let Some(function) = positional.get(1) else {
+        let fns: Vec<String> = items(&source).into_iter().filter(|it| it.kind == "fn" && it.name != "main").map(|it| it.name).collect();
+        println!("{}\n  {}", fill(tr("functions in {}:"), &[file]), fns.join("\n  "));
+        return;
+    };
+
+    let mut columns = Vec::new();
+    for name in &targets {
+        let (label, triple) = target(name);
+        let bare = triple.as_deref().is_some_and(|t| t.contains("-none"));
+        let crate_text = extract(&source, function, bare).unwrap_or_else(|e| fail(&e));
+        for level in &levels {
+            let asm = compile(&crate_text, level, triple.as_deref())
+                .unwrap_or_else(|e| fail(&format!("rustc failed for {} at -O{}:\n{}", label, level, e)));
+            let listing = parse(&asm).into_iter().find(|l| l.name == *function)
+                .unwrap_or_else(|| fail(&format!("no {} in the {} listing: is it behind a #[cfg]?", function, label)));
+            let title = format!("{} -O{}, {}", label, level, fill(tr("{} instr"), &[&listing.instructions()]));
+            columns.push((title, body(&listing)));
+        }
+    }
+
+    println!("=== {} in {} ===\n", function, file);
+    print_columns(&columns, width);
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("the same code as {}", "samme kode som {}"),
+    ("functions in {}:", "funksjoner i {}:"),
+    ("{} instr", "{} instr."),
+];
@@ -0,0 +1,175 @@
+/*
+ * Atomic Operations and Overflow in Rust
+ * fetch_add wraps silently (even in debug builds), compare_exchange loops
+ * give checked and saturating atomic increments, and a plain load + store
+ * counter loses updates when several threads use it at once.
+ *
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin atomics_demo -- [threads] [increments per thread] [--lang no]
+ */
+
+use std::sync::atomic::{AtomicI32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use util::lang::{self, Catalog};
+
+/// Run `threads` threads that each call `work` `per_thread` times.
+fn hammer<F>(threads: usize, per_thread: u32, work: F)
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let work = Arc::new(work);
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let work = Arc::clone(&work);
+            thread::spawn(move || {
+                for _ in 0..per_thread {
+                    work();
+                }
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+}
+
+/// Checked atomic increment: Err(current) instead of passing `limit`.
+/// The classic compare_exchange loop: read, compute, swap only if unchanged.
+fn checked_increment(counter: &AtomicI32, limit: i32, retries: &AtomicU64) -> Result<i32, i32> {
+    let mut current = counter.load(Ordering::Relaxed);
+    loop {
+        if current >= limit {
+            return Err(current);
+        }
+        match counter.compare_exchange_weak(current, current + 1, Ordering::AcqRel, Ordering::Relaxed) {
+            Ok(previous) => return Ok(previous),
+            Err(actual) => {
+                // another thread got there first: retry with its value
+                retries.fetch_add(1, Ordering::Relaxed);
+                current = actual;
+            }
+        }
+    }
+}
+
+/// Saturating atomic increment: stays at i32::MAX instead of wrapping.
+/// fetch_update is the same loop as above, packaged by the standard library.
+fn saturating_increment(counter: &AtomicI32) -> i32 {
+    counter
+        .fetch_update(Ordering::AcqRel, Ordering::Relaxed, |v| Some(v.saturating_add(1)))
+        .unwrap()
+}
+
+// ==================== EXPLANATIONS ====================
+
+const WRAPS: &str = "\
+No increment was lost, but the counter silently went past i32::MAX
+to negative numbers - the same two's complement wrap as in the ALU.
+";
+
+const LIMIT: &str = "  The limit is never passed, even with all threads racing for the last slot.\n";
+
+const LOST: &str = "
+The load + store version is safe Rust (each access is atomic) and still
+wrong: two threads read the same value and both write value + 1.
+fetch_add is ONE indivisible instruction (lock xadd on x86, amoadd.w on RISC-V).
+On a single core, updates are lost only when a thread is switched out
+between its load and its store - the yield_now above forces that now and then.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("atomics_demo [threads] [increments per thread] [--lang no|en]");
+    let threads: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(4);
+    let per_thread: u32 = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(1_000_000);
+    let total = threads as i64 * per_thread as i64;
+
+    println!("=== Atomic Operations and Overflow ===");
+    println!("{} threads x {} increments = {} increments\n", threads, per_thread, total);
+
+    // 1. fetch_add wraps: atomics have no overflow check, not even in debug builds
+    println!("--- 1. fetch_add wraps around ---");
+    let start = i32::MAX - (total / 2) as i32;
+    let wrapping = Arc::new(AtomicI32::new(start));
+    let c = Arc::clone(&wrapping);
+    hammer(threads, per_thread, move || {
+        c.fetch_add(1, Ordering::Relaxed);
+    });
+    let value = wrapping.load(Ordering::SeqCst);
+    println!("start        = {}", start);
+    println!("final        = {}", value);
+    println!("expected     = {} (start.wrapping_add(total))", start.wrapping_add(total as i32));
+    println!("{}", tr(WRAPS));
+
+    // 2. checked and saturating increments with compare_exchange loops
+    println!("--- 2. compare_exchange: checked and saturating increments ---");
+    let limit = (total / 2) as i32;
+    let checked = Arc::new(AtomicI32::new(0));
+    let retries = Arc::new(AtomicU64::new(0));
+    let refused = Arc::new(AtomicU64::new(0));
+    let (c, r, f) = (Arc::clone(&checked), Arc::clone(&retries), Arc::clone(&refused));
+    hammer(threads, per_thread, move || {
+        if checked_increment(&c, limit, &r).is_err() {
+            f.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    println!("checked, limit {}: final = {}, refused = {}, CAS retries = {}",
+        limit, checked.load(Ordering::SeqCst), refused.load(Ordering::SeqCst),
+        retries.load(Ordering::SeqCst));
+    print!("{}", tr(LIMIT));
+
+    let saturating = Arc::new(AtomicI32::new(start));
+    let c = Arc::clone(&saturating);
+    hammer(threads, per_thread, move || {
+        saturating_increment(&c);
+    });
+    println!("saturating from {}: final = {} (i32::MAX = {})\n",
+        start, saturating.load(Ordering::SeqCst), i32::MAX);
+
+    // 3. a counter that is not atomic: separate load and store
+    println!("--- 3. Non-atomic read-modify-write loses updates ---");
+    let racy = Arc::new(AtomicI32::new(0));
+    let c = Arc::clone(&racy);
+    hammer(threads, per_thread, move || {
+        // counter = counter + 1 as two steps: another thread may run in between
+        let v = c.load(Ordering::Relaxed);
+        if v % 256 == 0 {
+            thread::yield_now(); // widen the window, so a single core shows it too
+        }
+        c.store(v.wrapping_add(1), Ordering::Relaxed);
+    });
+    let atomic = Arc::new(AtomicI32::new(0));
+    let c = Arc::clone(&atomic);
+    hammer(threads, per_thread, move || {
+        c.fetch_add(1, Ordering::Relaxed);
+    });
+    let lost = total - racy.load(Ordering::SeqCst) as i64;
+    println!("load + store: {:>10}  ({} updates lost, {:.2}%)",
+        racy.load(Ordering::SeqCst), lost, 100.0 * lost as f64 / total as f64);
+    println!("fetch_add:    {:>10}  {}", atomic.load(Ordering::SeqCst), tr("(exact)"));
+    print!("{}", tr(LOST));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    (WRAPS, "\
+Ingen økning gikk tapt, men telleren gikk stille forbi i32::MAX
+til negative tall - det samme toerkomplement-omløpet som i ALU-en.
+"),
+    (LIMIT, "  Grensen passeres aldri, selv om alle trådene kappes om den siste plassen.\n"),
+    ("(exact)", "(nøyaktig)"),
+    (LOST, "
+load + store-versjonen er trygg Rust (hver aksess er atomisk) og likevel
+feil: to tråder leser samme verdi, og begge skriver verdi + 1.
+fetch_add er ÉN udelelig instruksjon (lock xadd på x86, amoadd.w på RISC-V).
+På én kjerne går oppdateringer tapt bare når en tråd byttes ut mellom
+lesingen og lagringen - yield_now over tvinger fram det nå og da.
+"),
+];
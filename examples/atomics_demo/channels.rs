@@ -0,0 +1,211 @@
+/*
+ * Message Passing vs Shared State
+ * The same producer/consumer workload two ways: producers SEND their values
+ * over a std::sync::mpsc channel to one consumer that owns the total, or they
+ * ADD them to a total behind Arc<Mutex<_>>. Each design is run per item and
+ * batched, and each has to deal with a sum that does not fit in an i32.
+ *
+ *   channel:  producer ──send──► [queue] ──recv──► consumer (owns the sum)
+ *   mutex:    producer ──lock──► sum ◄──lock── producer
+ *
+ * The explanation is printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin channels -- [producers] [items per producer] [--lang no]
+ */
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use util::lang::{self, Catalog};
+
+const BATCH: usize = 1024;
+
+/// The work item: large values, so an i32 running total overflows quickly.
+fn value(producer: usize, i: u64) -> i32 {
+    1_000_000_000 - (producer as i32 * 7 + (i % 1000) as i32)
+}
+
+/// Running total that refuses to wrap: the first overflow is remembered, and
+/// the sum carries on in i64 so the correct answer is still available.
+#[derive(Default)]
+struct Total {
+    sum: i64,
+    items: u64,
+    /// Item count at which an i32 accumulator would first have overflowed.
+    i32_overflow_at: Option<u64>,
+}
+
+impl Total {
+    fn add(&mut self, v: i32) {
+        self.items += 1;
+        self.sum += v as i64; // cannot overflow: 2^32 items of at most 2^31
+        if self.i32_overflow_at.is_none() && !fits_i32(self.sum) {
+            self.i32_overflow_at = Some(self.items);
+        }
+    }
+
+    /// Merge a partial total computed elsewhere (one producer's batch).
+    fn add_partial(&mut self, sum: i64, items: u64) {
+        self.items += items;
+        self.sum = self.sum.checked_add(sum).expect("i64 total overflowed");
+        if self.i32_overflow_at.is_none() && !fits_i32(self.sum) {
+            self.i32_overflow_at = Some(self.items); // only known to batch precision
+        }
+    }
+}
+
+fn fits_i32(v: i64) -> bool {
+    v >= i32::MIN as i64 && v <= i32::MAX as i64
+}
+
+// ==================== MESSAGE PASSING ====================
+
+fn channel_per_item(producers: usize, per_producer: u64) -> Total {
+    let (tx, rx) = mpsc::channel::<i32>();
+    for p in 0..producers {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for i in 0..per_producer {
+                tx.send(value(p, i)).unwrap();
+            }
+        });
+    }
+    drop(tx); // the loop below ends when every sender is gone
+    let mut total = Total::default();
+    for v in rx {
+        total.add(v);
+    }
+    total
+}
+
+fn channel_batched(producers: usize, per_producer: u64) -> Total {
+    let (tx, rx) = mpsc::sync_channel::<Vec<i32>>(16); // bounded: back-pressure on producers
+    for p in 0..producers {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let mut batch = Vec::with_capacity(BATCH);
+            for i in 0..per_producer {
+                batch.push(value(p, i));
+                if batch.len() == BATCH {
+                    tx.send(std::mem::replace(&mut batch, Vec::with_capacity(BATCH))).unwrap();
+                }
+            }
+            if !batch.is_empty() {
+                tx.send(batch).unwrap();
+            }
+        });
+    }
+    drop(tx);
+    let mut total = Total::default();
+    for batch in rx {
+        for v in batch {
+            total.add(v);
+        }
+    }
+    total
+}
+
+// ==================== SHARED STATE ====================
+
+fn mutex_per_item(producers: usize, per_producer: u64) -> Total {
+    let total = Arc::new(Mutex::new(Total::default()));
+    let handles: Vec<_> = (0..producers)
+        .map(|p| {
+            let total = Arc::clone(&total);
+            thread::spawn(move || {
+                for i in 0..per_producer {
+                    total.lock().unwrap().add(value(p, i));
+                }
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    Arc::try_unwrap(total).ok().unwrap().into_inner().unwrap()
+}
+
+fn mutex_batched(producers: usize, per_producer: u64) -> Total {
+    let total = Arc::new(Mutex::new(Total::default()));
+    let handles: Vec<_> = (0..producers)
+        .map(|p| {
+            let total = Arc::clone(&total);
+            thread::spawn(move || {
+                let (mut sum, mut items) = (0i64, 0u64);
+                for i in 0..per_producer {
+                    sum += value(p, i) as i64;
+                    items += 1;
+                    if items as usize == BATCH {
+                        total.lock().unwrap().add_partial(sum, items);
+                        sum = 0;
+                        items = 0;
+                    }
+                }
+                total.lock().unwrap().add_partial(sum, items);
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    Arc::try_unwrap(total).ok().unwrap().into_inner().unwrap()
+}
+
+// ==================== MAIN ====================
+
+const DESIGNS: &str = "
+Channels: the consumer owns the total, so overflow is handled in ONE place
+and nobody can see a half-updated sum. Every item is a message, though,
+so batching is what makes it fast.
+Mutex: every producer touches the total and must follow the same rules;
+summing locally in i64 and merging once per batch is both faster and
+keeps the lock held only for a single checked_add.
+";
+
+fn main() {
+    let args = lang::args("channels [producers] [items per producer] [--lang no|en]");
+    let producers: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(4);
+    let per_producer: u64 = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(500_000);
+    let items = producers as u64 * per_producer;
+    let expected: i64 = (0..producers)
+        .map(|p| (0..per_producer).map(|i| value(p, i) as i64).sum::<i64>())
+        .sum();
+
+    println!("=== Message Passing vs Shared State ===");
+    println!("{} producers x {} items, expected sum {}\n", producers, per_producer, expected);
+    println!("  {:<16} {:>12} {:>8}  {:<20} sum", "design", "Mitems/s", "correct", "i32 would wrap at");
+
+    type Design = fn(usize, u64) -> Total;
+    let designs: [(&str, Design); 4] = [
+        ("channel/item", channel_per_item),
+        ("channel/batch", channel_batched),
+        ("mutex/item", mutex_per_item),
+        ("mutex/batch", mutex_batched),
+    ];
+    for (name, run) in designs {
+        let start = Instant::now();
+        let total = run(producers, per_producer);
+        let seconds = start.elapsed().as_secs_f64();
+        let wrap = match total.i32_overflow_at {
+            Some(n) => format!("item {}", n),
+            None => "never".to_string(),
+        };
+        println!("  {:<16} {:>12.1} {:>8}  {:<20} {}", name, items as f64 / seconds / 1e6,
+            if total.sum == expected && total.items == items { "yes" } else { "NO" }, wrap, total.sum);
+    }
+
+    print!("{}", lang::tr(NO, DESIGNS));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[(DESIGNS, "
+Kanaler: konsumenten eier totalen, så overflyt håndteres på ETT sted,
+og ingen kan se en halvveis oppdatert sum. Men hvert element er en melding,
+så det er buntingen som gjør det raskt.
+Mutex: hver produsent rører totalen og må følge de samme reglene;
+å summere lokalt i i64 og slå sammen én gang per bunt er både raskere og
+holder låsen bare for én enkelt checked_add.
+")];
@@ -0,0 +1,404 @@
+/*
+ * Statistics Counters with Explicit Overflow Semantics
+ * Counters for telemetry (packets, bytes, errors) have to decide what
+ * happens at the top of their range, and a reader wants several of them
+ * from the same moment.
+ *
+ *   WrappingCounter    wraps to 0 like an SNMP Counter32. The absolute value
+ *                      is meaningless after a wrap, but the difference
+ *                      between two samples (taken less than one wrap apart)
+ *                      is still exact: now.wrapping_sub(then).
+ *   SaturatingCounter  sticks at the maximum. The value never lies by being
+ *                      small, but once saturated it stops counting: deltas
+ *                      become 0.
+ *   StatsBlock         several counters behind a sequence lock, so a
+ *                      snapshot never mixes values from before and after an
+ *                      update (readers retry instead of blocking writers).
+ *
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin counters -- [--format json] [--lang no]
+ * Model check:  see loom_tests at the end of the file
+ */
+
+#[cfg(loom)]
+extern crate loom;
+
+use std::fmt::Write;
+use std::sync::Arc;
+use std::thread;
+
+use util::lang::{self, fill, Catalog};
+use util::report::{Format, Json, Report};
+
+// The counters are built on these; with --cfg loom they come from loom, whose
+// model checker then explores every interleaving (see the tests at the end).
+#[cfg(loom)]
+use loom::{hint, sync::atomic::{fence, AtomicU32, AtomicU64, Ordering}, thread::yield_now};
+#[cfg(not(loom))]
+use std::{hint, sync::atomic::{fence, AtomicU32, AtomicU64, Ordering}, thread::yield_now};
+
+// ==================== COUNTERS ====================
+
+/// A u32 counter that wraps to 0 on overflow. Use `delta` to get rates.
+pub struct WrappingCounter(AtomicU32);
+
+impl WrappingCounter {
+    pub fn new(value: u32) -> Self {
+        WrappingCounter(AtomicU32::new(value))
+    }
+
+    /// Add `n`; wraps around modulo 2^32 (fetch_add never panics).
+    pub fn add(&self, n: u32) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Events between two samples. Exact as long as fewer than 2^32 events
+    /// happened in between, even if the counter wrapped.
+    pub fn delta(now: u32, then: u32) -> u32 {
+        now.wrapping_sub(then)
+    }
+}
+
+/// A u32 counter that stops at u32::MAX instead of wrapping.
+pub struct SaturatingCounter(AtomicU32);
+
+impl SaturatingCounter {
+    pub fn new(value: u32) -> Self {
+        SaturatingCounter(AtomicU32::new(value))
+    }
+
+    /// Add `n`, clamping at u32::MAX. A compare_exchange loop, so concurrent
+    /// adds never push it past the top.
+    pub fn add(&self, n: u32) {
+        let _ = self.0.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+            if v == u32::MAX { None } else { Some(v.saturating_add(n)) }
+        });
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// True once the value is clamped: from here on it means "at least this many".
+    pub fn is_saturated(&self) -> bool {
+        self.get() == u32::MAX
+    }
+}
+
+// ==================== SNAPSHOTS ====================
+
+/// Counters updated together and read as one consistent snapshot.
+///
+/// Sequence lock: an update makes `seq` odd, changes the counters and makes
+/// it even again. A reader that saw the same even `seq` before and after
+/// reading knows no update overlapped its reads.
+pub struct StatsBlock {
+    seq: AtomicU64,
+    pub packets: WrappingCounter,
+    pub bytes: WrappingCounter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Snapshot {
+    pub packets: u32,
+    pub bytes: u32,
+}
+
+impl Default for StatsBlock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatsBlock {
+    pub fn new() -> Self {
+        StatsBlock { seq: AtomicU64::new(0), packets: WrappingCounter::new(0), bytes: WrappingCounter::new(0) }
+    }
+
+    /// Count one packet of `size` bytes; both counters change together.
+    pub fn record(&self, size: u32) {
+        // take the write side: even → odd (writers exclude each other here)
+        let mut s = self.seq.load(Ordering::Relaxed);
+        loop {
+            if s.is_multiple_of(2) {
+                match self.seq.compare_exchange_weak(s, s + 1, Ordering::Acquire, Ordering::Relaxed) {
+                    Ok(_) => break,
+                    Err(actual) => s = actual,
+                }
+            } else {
+                hint::spin_loop();
+                s = self.seq.load(Ordering::Relaxed);
+            }
+        }
+        fence(Ordering::Release); // the odd seq is visible before the counter writes
+        self.packets.add(1);
+        self.bytes.add(size);
+        self.seq.store(s + 2, Ordering::Release);
+    }
+
+    /// Read all counters from the same moment, retrying while an update is in progress.
+    pub fn snapshot(&self) -> (Snapshot, u32) {
+        let mut retries = 0;
+        loop {
+            let before = self.seq.load(Ordering::Acquire);
+            if before.is_multiple_of(2) {
+                let snap = Snapshot { packets: self.packets.get(), bytes: self.bytes.get() };
+                fence(Ordering::Acquire); // the counter reads happen before the second seq load
+                if self.seq.load(Ordering::Relaxed) == before {
+                    return (snap, retries);
+                }
+            }
+            retries += 1;
+            yield_now();
+        }
+    }
+
+    /// Read the counters one by one, without the sequence check.
+    pub fn torn_read(&self) -> Snapshot {
+        Snapshot { packets: self.packets.get(), bytes: self.bytes.get() }
+    }
+}
+
+// ==================== DEMO ====================
+
+const BYTES_PER_SECOND: u32 = 125_000_000;
+
+/// The two byte counters after one more second of traffic.
+struct Second {
+    wrapping: u32,
+    wrapping_mbps: f64,
+    saturating: u32,
+    saturating_mbps: f64,
+    saturated: bool,
+}
+
+fn telemetry_wrap() -> Vec<Second> {
+    // start shortly before the top, as if the box had been up for a while
+    let wrapping = WrappingCounter::new(u32::MAX - 3 * (BYTES_PER_SECOND / 2));
+    let saturating = SaturatingCounter::new(u32::MAX - 3 * (BYTES_PER_SECOND / 2));
+    let (mut prev_w, mut prev_s) = (wrapping.get(), saturating.get());
+    (1..=5).map(|_| {
+        wrapping.add(BYTES_PER_SECOND);
+        saturating.add(BYTES_PER_SECOND);
+        let (w, s) = (wrapping.get(), saturating.get());
+        let second = Second {
+            wrapping: w,
+            wrapping_mbps: WrappingCounter::delta(w, prev_w) as f64 / 1e6,
+            saturating: s,
+            saturating_mbps: s.saturating_sub(prev_s) as f64 / 1e6,
+            saturated: saturating.is_saturated(),
+        };
+        prev_w = w;
+        prev_s = s;
+        second
+    }).collect()
+}
+
+/// What the reader saw while two writers recorded packets.
+struct Reads {
+    reads: u64,
+    torn: u64,
+    inconsistent: u64,
+    retries: u64,
+    last: Snapshot,
+}
+
+fn snapshots() -> Reads {
+    let stats = Arc::new(StatsBlock::new());
+    let writers: Vec<_> = (0..2)
+        .map(|_| {
+            let stats = Arc::clone(&stats);
+            thread::spawn(move || {
+                for _ in 0..500_000 {
+                    stats.record(64);
+                }
+            })
+        })
+        .collect();
+
+    let (mut torn, mut inconsistent, mut retries, mut reads) = (0u64, 0u64, 0u64, 0u64);
+    while writers.iter().any(|w| !w.is_finished()) {
+        let t = stats.torn_read();
+        if t.bytes != t.packets.wrapping_mul(64) {
+            torn += 1;
+        }
+        let (s, r) = stats.snapshot();
+        if s.bytes != s.packets.wrapping_mul(64) {
+            inconsistent += 1;
+        }
+        retries += r as u64;
+        reads += 1;
+    }
+    for w in writers {
+        w.join().unwrap();
+    }
+    let (last, _) = stats.snapshot();
+    Reads { reads, torn, inconsistent, retries, last }
+}
+
+struct Results {
+    seconds: Vec<Second>,
+    reads: Reads,
+    cores: usize,
+}
+
+const WRAP_TIMES: &str = "  u32 bytes wrap after {} s; u32 packets at 1 Mpps after {} min;
+  a u32 millisecond uptime wraps after {} days.
+
+";
+
+const PICK: &str = "  The wrapping counter keeps giving the right rate through the wrap;
+  the saturating one reports 0 MB/s forever after. Pick per use: rates
+  want wrap + delta, error totals (\"at least 4 billion\") want saturate.
+
+";
+
+const SEQLOCK: &str = "  Each counter is atomic on its own; the pair is not. The sequence lock
+  makes the reader detect an overlapping update and try again.
+";
+
+const ONE_CORE: &str = "  (On one core a torn read needs a thread switch in the middle of an
+  update, so the first count is often 0 here - the retries show the overlaps.)
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+impl Report for Results {
+    fn text(&self) -> String {
+        let mut s = String::from("=== Statistics Counters with Explicit Overflow Semantics ===\n\n");
+        s.push_str("--- 1. A byte counter after long uptime (1 Gbit/s link) ---\n");
+        s.push_str(&fill(tr(WRAP_TIMES), &[&format!("{:.1}", u32::MAX as f64 / BYTES_PER_SECOND as f64),
+            &format!("{:.1}", u32::MAX as f64 / 1e6 / 60.0), &format!("{:.1}", u32::MAX as f64 / 1000.0 / 86400.0)]));
+        let _ = writeln!(s, "  {:>6} {:>12} {:>14} {:>12} {:>14}", "second", "wrapping", "rate (MB/s)", "saturating", "rate (MB/s)");
+        for (i, t) in self.seconds.iter().enumerate() {
+            let _ = writeln!(s, "  {:>6} {:>12} {:>14.0} {:>12} {:>14.0}{}", i + 1,
+                t.wrapping, t.wrapping_mbps, t.saturating, t.saturating_mbps,
+                if t.saturated { tr("  (saturated)") } else { "" });
+        }
+        s.push_str(tr(PICK));
+
+        let r = &self.reads;
+        s.push_str("--- 2. Consistent snapshots while 2 writers record 64-byte packets ---\n");
+        let _ = writeln!(s, "  {}", fill(tr("{} reads each way; final {} packets, {} bytes"), &[&r.reads, &r.last.packets, &r.last.bytes]));
+        let _ = writeln!(s, "  {}", fill(tr("field by field: {} reads with bytes != 64 x packets"), &[&format!("{:>6}", r.torn)]));
+        let _ = writeln!(s, "  {}", fill(tr("seqlock:        {} inconsistent ({} retries)"), &[&format!("{:>6}", r.inconsistent), &r.retries]));
+        s.push_str(tr(SEQLOCK));
+        if self.cores == 1 {
+            s.push_str(tr(ONE_CORE));
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        let r = &self.reads;
+        Json::object()
+            .with("bytes_per_second", BYTES_PER_SECOND)
+            .with("seconds", Json::array(self.seconds.iter().map(|t| {
+                Json::object().with("wrapping", t.wrapping).with("wrapping_mb_per_s", t.wrapping_mbps)
+                    .with("saturating", t.saturating).with("saturating_mb_per_s", t.saturating_mbps)
+                    .with("saturated", t.saturated)
+            })))
+            .with("snapshots", Json::object()
+                .with("reads", r.reads)
+                .with("torn_reads", r.torn)
+                .with("seqlock_inconsistent", r.inconsistent)
+                .with("seqlock_retries", r.retries)
+                .with("final_packets", r.last.packets)
+                .with("final_bytes", r.last.bytes))
+            .with("cores", self.cores)
+    }
+}
+
+fn main() {
+    let (format, _) = Format::from_env("counters [--format text|json] [--lang no|en]");
+    let seconds = telemetry_wrap();
+    let reads = snapshots();
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    format.print(&Results { seconds, reads, cores });
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    (WRAP_TIMES, "  u32-byte går rundt etter {} s; u32-pakker ved 1 Mpps etter {} min;
+  en u32-oppetid i millisekunder går rundt etter {} dager.
+
+"),
+    ("  (saturated)", "  (mettet)"),
+    (PICK, "  Den wrappende telleren gir fortsatt riktig rate gjennom omslaget;
+  den mettende melder 0 MB/s for alltid etterpå. Velg etter bruk: rater
+  vil ha wrap + delta, feiltotaler («minst 4 milliarder») vil ha metning.
+
+"),
+    ("{} reads each way; final {} packets, {} bytes", "{} lesinger hver vei; til slutt {} pakker, {} byte"),
+    ("field by field: {} reads with bytes != 64 x packets", "felt for felt:  {} lesinger med bytes != 64 x pakker"),
+    ("seqlock:        {} inconsistent ({} retries)", "seqlock:        {} inkonsistente ({} nye forsøk)"),
+    (SEQLOCK, "  Hver teller er atomisk for seg; paret er det ikke. Sekvenslåsen
+  lar leseren oppdage en overlappende oppdatering og prøve igjen.
+"),
+    (ONE_CORE, "  (På én kjerne krever en revet lesing et trådbytte midt i en
+  oppdatering, så den første tellingen er ofte 0 her - de nye forsøkene viser overlappene.)
+"),
+];
+
+// ==================== LOOM TESTS ====================
+
+/// Exhaustive checks under loom, built like the ones in locks.rs:
+///
+///   rustc --edition 2021 --test --cfg loom -O counters.rs \
+///         --extern loom=path/to/libloom.rlib -L path/to/deps \
+///         --extern util=target/release/libutil.rlib -o counters_loom
+#[cfg(loom)]
+mod loom_tests {
+    use super::*;
+    use loom::sync::Arc;
+
+    #[test]
+    fn wrapping_counter_wraps_exactly() {
+        loom::model(|| {
+            let c = Arc::new(WrappingCounter::new(u32::MAX));
+            let other = c.clone();
+            let t = loom::thread::spawn(move || other.add(1));
+            c.add(1);
+            t.join().unwrap();
+            assert_eq!(c.get(), 1);
+            assert_eq!(WrappingCounter::delta(c.get(), u32::MAX), 2);
+        });
+    }
+
+    #[test]
+    fn saturating_counter_never_passes_max() {
+        loom::model(|| {
+            let c = Arc::new(SaturatingCounter::new(u32::MAX - 1));
+            let other = c.clone();
+            let t = loom::thread::spawn(move || other.add(1));
+            c.add(1);
+            t.join().unwrap();
+            assert!(c.is_saturated());
+        });
+    }
+
+    #[test]
+    fn snapshot_is_consistent() {
+        loom::model(|| {
+            let stats = Arc::new(StatsBlock::new());
+            let writer = {
+                let stats = stats.clone();
+                loom::thread::spawn(move || {
+                    stats.record(64);
+                    stats.record(64);
+                })
+            };
+            let (snap, _) = stats.snapshot();
+            assert_eq!(snap.bytes, snap.packets * 64, "snapshot mixed two updates");
+            writer.join().unwrap();
+        });
+    }
+}
@@ -0,0 +1,301 @@
+/*
+ * Memory Ordering Litmus Tests
+ * Runs the classic litmus tests thousands of times under Relaxed,
+ * Acquire/Release and SeqCst orderings and counts how often each outcome
+ * is observed on THIS machine.
+ *
+ *   SB   (store buffering)  T0: x = 1; r0 = y      T1: y = 1; r1 = x
+ *        weak outcome r0 = 0, r1 = 0: both loads passed the other thread's store.
+ *        Seen on x86 and ARM with Relaxed or AcqRel; only SeqCst forbids it.
+ *
+ *   MP   (message passing)  T0: data = 1; flag = 1   T1: r0 = flag; r1 = data
+ *        weak outcome r0 = 1, r1 = 0: the flag arrived before the data.
+ *        Allowed with Relaxed (seen on ARM/POWER, never on x86); forbidden by
+ *        Release/Acquire.
+ *
+ *   IRIW (independent reads of independent writes)
+ *        T0: x = 1   T1: y = 1   T2: r0 = x; r1 = y   T3: r2 = y; r3 = x
+ *        weak outcome 1 0 1 0: the readers disagree on which write came first.
+ *        Allowed with AcqRel (POWER can show it), forbidden by SeqCst.
+ *
+ * An outcome the model allows may still never appear: x86 hardware is stronger
+ * than the Rust model, and on a single core all threads share one view of
+ * memory, so only plain interleavings show up there. The explanations are
+ * printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin litmus -- [iterations] [--format json] [--lang no]
+ */
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use util::lang::{self, fill, Catalog};
+use util::report::{Format, Json, Report};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Relaxed,
+    AcqRel,
+    SeqCst,
+}
+
+impl Mode {
+    fn store(self) -> Ordering {
+        match self {
+            Mode::Relaxed => Ordering::Relaxed,
+            Mode::AcqRel => Ordering::Release,
+            Mode::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    fn load(self) -> Ordering {
+        match self {
+            Mode::Relaxed => Ordering::Relaxed,
+            Mode::AcqRel => Ordering::Acquire,
+            Mode::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Relaxed => "Relaxed",
+            Mode::AcqRel => "Acquire/Release",
+            Mode::SeqCst => "SeqCst",
+        }
+    }
+}
+
+/// The two shared locations every test uses (x/y, or data/flag for MP).
+struct Shared {
+    x: AtomicU32,
+    y: AtomicU32,
+}
+
+/// One thread of a litmus test: runs its accesses, returns the registers it read.
+type Body = fn(&Shared, Mode) -> Vec<u32>;
+
+struct Litmus {
+    name: &'static str,
+    threads: Vec<Body>,
+    /// Outcome (all registers, thread by thread) that needs a weak memory model.
+    weak: Vec<u32>,
+    /// Orderings under which the Rust (C++) model allows the weak outcome.
+    allowed: fn(Mode) -> bool,
+}
+
+fn store_buffering() -> Litmus {
+    Litmus {
+        name: "SB  store buffering",
+        threads: vec![
+            |s, m| {
+                s.x.store(1, m.store());
+                vec![s.y.load(m.load())]
+            },
+            |s, m| {
+                s.y.store(1, m.store());
+                vec![s.x.load(m.load())]
+            },
+        ],
+        weak: vec![0, 0],
+        allowed: |m| m != Mode::SeqCst,
+    }
+}
+
+fn message_passing() -> Litmus {
+    Litmus {
+        name: "MP  message passing",
+        threads: vec![
+            |s, m| {
+                s.x.store(1, Ordering::Relaxed); // data
+                s.y.store(1, m.store()); // flag
+                vec![]
+            },
+            |s, m| {
+                let flag = s.y.load(m.load());
+                let data = s.x.load(Ordering::Relaxed);
+                vec![flag, data]
+            },
+        ],
+        weak: vec![1, 0],
+        allowed: |m| m == Mode::Relaxed,
+    }
+}
+
+fn iriw() -> Litmus {
+    Litmus {
+        name: "IRIW independent reads of independent writes",
+        threads: vec![
+            |s, m| {
+                s.x.store(1, m.store());
+                vec![]
+            },
+            |s, m| {
+                s.y.store(1, m.store());
+                vec![]
+            },
+            |s, m| vec![s.x.load(m.load()), s.y.load(m.load())],
+            |s, m| vec![s.y.load(m.load()), s.x.load(m.load())],
+        ],
+        weak: vec![1, 0, 1, 0],
+        allowed: |m| m != Mode::SeqCst,
+    }
+}
+
+/// How often each outcome (all registers, thread by thread) was seen.
+type Counts = BTreeMap<Vec<u32>, usize>;
+
+/// Run the test `iterations` times and count each outcome.
+///
+/// The threads stay alive for the whole run. A barrier lines them up before
+/// every iteration, so their accesses overlap as closely as possible; after a
+/// second barrier thread 0 resets x and y for the next round.
+fn run(test: &Litmus, mode: Mode, iterations: usize) -> Counts {
+    let shared = Arc::new(Shared { x: AtomicU32::new(0), y: AtomicU32::new(0) });
+    let barrier = Arc::new(Barrier::new(test.threads.len()));
+    let handles: Vec<_> = test
+        .threads
+        .iter()
+        .enumerate()
+        .map(|(id, &body)| {
+            let shared = Arc::clone(&shared);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                let mut registers = Vec::with_capacity(iterations);
+                for _ in 0..iterations {
+                    barrier.wait();
+                    registers.push(body(&shared, mode));
+                    barrier.wait();
+                    if id == 0 {
+                        shared.x.store(0, Ordering::Relaxed);
+                        shared.y.store(0, Ordering::Relaxed);
+                    }
+                }
+                registers
+            })
+        })
+        .collect();
+    let per_thread: Vec<Vec<Vec<u32>>> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    let mut counts = BTreeMap::new();
+    for i in 0..iterations {
+        let outcome: Vec<u32> = per_thread.iter().flat_map(|t| t[i].iter().copied()).collect();
+        *counts.entry(outcome).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Most frequent outcomes listed per test and ordering (IRIW has up to 16).
+const SHOWN: usize = 4;
+
+fn format_outcome(outcome: &[u32]) -> String {
+    outcome
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!("r{}={}", i, v))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+struct Results {
+    iterations: usize,
+    cores: usize,
+    /// Every test with the outcome counts per ordering.
+    tests: Vec<(Litmus, Vec<(Mode, Counts)>)>,
+}
+
+const NOT_A_BUG: &str = "
+An allowed weak outcome that never shows up is not a bug: the hardware
+(x86 is close to SeqCst except for SB) or the timing just didn't produce it.
+A forbidden outcome must never appear, however many iterations you run.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+impl Report for Results {
+    fn text(&self) -> String {
+        let percent = |n: usize| 100.0 * n as f64 / self.iterations as f64;
+        let mut s = String::from("=== Memory Ordering Litmus Tests ===\n");
+        let _ = writeln!(s, "{}", fill(tr("{} iterations per test and ordering, {} core(s) available"), &[&self.iterations, &self.cores]));
+        if self.cores == 1 {
+            let _ = writeln!(s, "{}", tr("Single core: threads only interleave, so weak outcomes cannot appear here."));
+        }
+        for (test, modes) in &self.tests {
+            let _ = writeln!(s, "\n--- {} ---", test.name);
+            for (mode, counts) in modes {
+                let allowed = (test.allowed)(*mode);
+                let _ = writeln!(s, "  {} ({}):", mode.name(), tr(if allowed { "weak outcome allowed" } else { "weak outcome forbidden" }));
+                let mut sorted: Vec<_> = counts.iter().collect();
+                sorted.sort_by(|a, b| b.1.cmp(a.1));
+                for (outcome, n) in sorted.iter().take(SHOWN) {
+                    let marker = if **outcome == test.weak { tr("  ← weak") } else { "" };
+                    let _ = writeln!(s, "    {:<24} {:>8}  {:>6.2}%{}", format_outcome(outcome), n, percent(**n), marker);
+                }
+                if sorted.len() > SHOWN {
+                    let _ = writeln!(s, "    ... {}", fill(tr("{} less frequent outcomes"), &[&(sorted.len() - SHOWN)]));
+                }
+                let weak = counts.get(&test.weak).copied().unwrap_or(0);
+                let _ = writeln!(s, "    weak {:<19} {:>8}  {:>6.2}%", format_outcome(&test.weak), weak, percent(weak));
+                if counts.contains_key(&test.weak) && !allowed {
+                    let _ = writeln!(s, "    !! {}", tr("forbidden outcome observed - the implementation is broken"));
+                }
+            }
+        }
+        s.push_str(tr(NOT_A_BUG));
+        s
+    }
+
+    fn json(&self) -> Json {
+        Json::object()
+            .with("iterations", self.iterations)
+            .with("cores", self.cores)
+            .with("tests", Json::array(self.tests.iter().map(|(test, modes)| {
+                Json::object()
+                    .with("name", test.name)
+                    .with("weak", test.weak.clone())
+                    .with("orderings", Json::array(modes.iter().map(|(mode, counts)| {
+                        Json::object()
+                            .with("ordering", mode.name())
+                            .with("weak_allowed", (test.allowed)(*mode))
+                            .with("weak_seen", counts.get(&test.weak).copied().unwrap_or(0))
+                            .with("outcomes", Json::array(counts.iter().map(|(outcome, n)| {
+                                Json::object().with("registers", outcome.clone()).with("count", *n)
+                            })))
+                    })))
+            })))
+    }
+}
+
+fn main() {
+    let (format, args) = Format::from_env("litmus [iterations] [--format text|json] [--lang no|en]");
+    let iterations: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(10_000);
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+    let tests = [store_buffering(), message_passing(), iriw()].into_iter().map(|test| {
+        let modes = [Mode::Relaxed, Mode::AcqRel, Mode::SeqCst].into_iter().map(|mode| (mode, run(&test, mode, iterations))).collect();
+        (test, modes)
+    });
+    format.print(&Results { iterations, cores, tests: tests.collect() });
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("{} iterations per test and ordering, {} core(s) available", "{} iterasjoner per test og ordning, {} kjerne(r) tilgjengelig"),
+    ("Single core: threads only interleave, so weak outcomes cannot appear here.",
+     "Én kjerne: trådene bare fletter seg inn i hverandre, så svake utfall kan ikke vises her."),
+    ("weak outcome allowed", "svakt utfall tillatt"),
+    ("weak outcome forbidden", "svakt utfall forbudt"),
+    ("  ← weak", "  ← svakt"),
+    ("{} less frequent outcomes", "{} sjeldnere utfall"),
+    ("forbidden outcome observed - the implementation is broken", "forbudt utfall observert - implementasjonen er feil"),
+    (NOT_A_BUG, "
+Et tillatt svakt utfall som aldri dukker opp, er ikke en feil: maskinvaren
+(x86 er nær SeqCst bortsett fra SB) eller timingen ga det bare ikke.
+Et forbudt utfall må aldri vises, uansett hvor mange iterasjoner du kjører.
+"),
+];
@@ -0,0 +1,418 @@
+/*
+ * Spin Locks from Atomics
+ * Two locks built from nothing but atomic instructions, checked for mutual
+ * exclusion, compared for fairness and benchmarked against std::sync::Mutex.
+ *
+ *   SpinLock    test-and-test-and-set: swap(true) to take the lock, spin on
+ *               plain loads while it is held (the cache line stays shared
+ *               instead of bouncing between cores on every attempt).
+ *   TicketLock  take a number, wait until it is served: fetch_add on
+ *               next_ticket, spin until now_serving reaches it. FIFO, so no
+ *               thread can starve - but a waiting thread that is switched out
+ *               stalls everyone queued behind it.
+ *
+ *   Acquire on lock and Release on unlock make the lock a fence: writes done
+ *   while holding it are visible to the next thread that takes it.
+ *
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin locks -- [max threads] [--lang no]
+ * Model check:  see loom_tests at the end of the file
+ */
+
+#[cfg(loom)]
+extern crate loom;
+
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use util::lang::{self, fill, Catalog};
+
+// The locks are built on these; with --cfg loom they come from loom, whose
+// model checker then explores every interleaving (see the tests at the end).
+#[cfg(loom)]
+use loom::{hint, sync::atomic::{AtomicBool, AtomicU32, Ordering}, thread::yield_now};
+#[cfg(not(loom))]
+use std::{hint, sync::atomic::{AtomicBool, AtomicU32, Ordering}, thread::yield_now};
+
+/// Spin iterations before giving the core away. Pure spinning is right when
+/// the holder is running on another core; when it is not (or on a single core)
+/// the spinner only burns its time slice, so we yield after a while.
+const SPINS_BEFORE_YIELD: u32 = 100;
+
+fn backoff(spins: &mut u32) {
+    *spins += 1;
+    if *spins < SPINS_BEFORE_YIELD {
+        hint::spin_loop(); // PAUSE on x86, YIELD on ARM: tells the core we are spinning
+    } else {
+        *spins = 0;
+        yield_now();
+    }
+}
+
+// ==================== SPIN LOCK ====================
+
+pub struct SpinLock<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// Safety: the lock hands out &mut T to one thread at a time.
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    pub fn new(value: T) -> Self {
+        SpinLock { locked: AtomicBool::new(false), value: UnsafeCell::new(value) }
+    }
+
+    pub fn lock(&self) -> SpinGuard<'_, T> {
+        let mut spins = 0;
+        // Acquire: nothing from the critical section may move before this swap
+        while self.locked.swap(true, Ordering::Acquire) {
+            while self.locked.load(Ordering::Relaxed) {
+                backoff(&mut spins);
+            }
+        }
+        SpinGuard { lock: self }
+    }
+}
+
+pub struct SpinGuard<'a, T> {
+    lock: &'a SpinLock<T>,
+}
+
+impl<T> Deref for SpinGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> DerefMut for SpinGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for SpinGuard<'_, T> {
+    fn drop(&mut self) {
+        // Release: everything written in the critical section is published
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+// ==================== TICKET LOCK ====================
+
+pub struct TicketLock<T> {
+    next_ticket: AtomicU32,
+    now_serving: AtomicU32,
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for TicketLock<T> {}
+
+impl<T> TicketLock<T> {
+    pub fn new(value: T) -> Self {
+        TicketLock {
+            next_ticket: AtomicU32::new(0),
+            now_serving: AtomicU32::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn lock(&self) -> TicketGuard<'_, T> {
+        // Relaxed is enough for taking a number; the Acquire load below synchronizes
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        let mut spins = 0;
+        while self.now_serving.load(Ordering::Acquire) != ticket {
+            backoff(&mut spins);
+        }
+        TicketGuard { lock: self }
+    }
+}
+
+pub struct TicketGuard<'a, T> {
+    lock: &'a TicketLock<T>,
+}
+
+impl<T> Deref for TicketGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> DerefMut for TicketGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for TicketGuard<'_, T> {
+    fn drop(&mut self) {
+        // only the holder writes now_serving, so a load + store is safe here
+        let next = self.lock.now_serving.load(Ordering::Relaxed).wrapping_add(1);
+        self.lock.now_serving.store(next, Ordering::Release);
+    }
+}
+
+// ==================== COMMON INTERFACE ====================
+
+/// The three locks behind one interface, so each experiment is written once.
+trait Lock: Send + Sync + 'static {
+    const NAME: &'static str;
+    fn new(value: u64) -> Self;
+    fn with<R>(&self, f: impl FnOnce(&mut u64) -> R) -> R;
+}
+
+impl Lock for SpinLock<u64> {
+    const NAME: &'static str = "SpinLock";
+    fn new(value: u64) -> Self {
+        SpinLock::new(value)
+    }
+    fn with<R>(&self, f: impl FnOnce(&mut u64) -> R) -> R {
+        f(&mut self.lock())
+    }
+}
+
+impl Lock for TicketLock<u64> {
+    const NAME: &'static str = "TicketLock";
+    fn new(value: u64) -> Self {
+        TicketLock::new(value)
+    }
+    fn with<R>(&self, f: impl FnOnce(&mut u64) -> R) -> R {
+        f(&mut self.lock())
+    }
+}
+
+impl Lock for Mutex<u64> {
+    const NAME: &'static str = "std Mutex";
+    fn new(value: u64) -> Self {
+        Mutex::new(value)
+    }
+    fn with<R>(&self, f: impl FnOnce(&mut u64) -> R) -> R {
+        f(&mut self.lock().unwrap())
+    }
+}
+
+// ==================== EXPERIMENTS ====================
+
+/// Mutual exclusion check: non-atomic increments under the lock must all survive.
+fn correctness<L: Lock>(threads: usize, per_thread: u64) -> bool {
+    let lock = Arc::new(L::new(0));
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                for _ in 0..per_thread {
+                    lock.with(|v| *v += 1);
+                }
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    lock.with(|v| *v) == threads as u64 * per_thread
+}
+
+/// Acquisitions and handovers per thread while all threads compete for `window`.
+/// The protected value holds the id of the last owner, so a thread can tell
+/// whether it got the lock from someone else (a handover) or re-took it itself.
+fn fairness<L: Lock>(threads: usize, window: Duration) -> Vec<(u64, u64)> {
+    let lock = Arc::new(L::new(u64::MAX));
+    let start = Instant::now();
+    let handles: Vec<_> = (0..threads as u64)
+        .map(|id| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                let (mut acquired, mut handovers) = (0u64, 0u64);
+                while start.elapsed() < window {
+                    lock.with(|owner| {
+                        if *owner != id {
+                            handovers += 1;
+                            *owner = id;
+                        }
+                        acquired += 1;
+                    });
+                }
+                (acquired, handovers)
+            })
+        })
+        .collect();
+    handles.into_iter().map(|h| h.join().unwrap()).collect()
+}
+
+/// Nanoseconds per lock/unlock pair with `threads` threads sharing `total` acquisitions.
+fn benchmark<L: Lock>(threads: usize, total: u64) -> f64 {
+    let lock = Arc::new(L::new(0));
+    let per_thread = total / threads as u64;
+    let start = Instant::now();
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                for _ in 0..per_thread {
+                    lock.with(|v| *v = v.wrapping_mul(31).wrapping_add(7));
+                }
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    start.elapsed().as_nanos() as f64 / (per_thread * threads as u64) as f64
+}
+
+fn fairness_row<L: Lock>(threads: usize) {
+    let counts = fairness::<L>(threads, Duration::from_millis(200));
+    let total: u64 = counts.iter().map(|c| c.0).sum();
+    let handovers: u64 = counts.iter().map(|c| c.1).sum();
+    let shares: Vec<String> =
+        counts.iter().map(|c| format!("{:.0}%", 100.0 * c.0 as f64 / total as f64)).collect();
+    println!("  {:<11} {:>9} {:>10.1}   {}",
+        L::NAME, total, total as f64 / handovers.max(1) as f64, shares.join(" "));
+}
+
+const RUN_LENGTH: &str = "  Run length = acquisitions in a row by the same thread. The ticket lock
+  hands the lock to the next thread in line (run length near 1), so no
+  one starves. The spin lock and Mutex let whoever is running re-take it:
+  long runs, fewer cache-line transfers, more throughput, no guarantees.
+
+";
+
+const CONTENDED: &str = "
+  Uncontended (1 thread) all three cost one atomic RMW per lock.
+  With more threads than cores, the ticket lock suffers most: the next
+  thread in line may not be running, and nobody else may go first.
+  std Mutex puts waiters to sleep in the kernel instead of spinning.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn verdict(ok: bool) -> &'static str {
+    tr(if ok { "ok" } else { "LOST UPDATES" })
+}
+
+fn main() {
+    let args = lang::args("locks [max threads] [--lang no|en]");
+    let max_threads: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(8);
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+    println!("=== Spin Locks from Atomics ===");
+    println!("{}\n", fill(tr("{} core(s) available"), &[&cores]));
+
+    println!("--- Mutual exclusion: 4 threads x 100 000 increments ---");
+    println!("  SpinLock:   {}", verdict(correctness::<SpinLock<u64>>(4, 100_000)));
+    println!("  TicketLock: {}", verdict(correctness::<TicketLock<u64>>(4, 100_000)));
+    println!("  std Mutex:  {}\n", verdict(correctness::<Mutex<u64>>(4, 100_000)));
+
+    println!("--- Fairness: 4 threads compete for 200 ms ---");
+    println!("  {:<11} {:>9} {:>10}   share per thread", "", "acquired", "run length");
+    fairness_row::<SpinLock<u64>>(4);
+    fairness_row::<TicketLock<u64>>(4);
+    fairness_row::<Mutex<u64>>(4);
+    print!("{}", tr(RUN_LENGTH));
+
+    println!("--- Benchmark: ns per lock/unlock, 400 000 acquisitions ---");
+    println!("  {:>7} {:>10} {:>10} {:>10}", "threads", "SpinLock", "TicketLock", "std Mutex");
+    let mut threads = 1;
+    while threads <= max_threads {
+        println!("  {:>7} {:>10.1} {:>10.1} {:>10.1}", threads,
+            benchmark::<SpinLock<u64>>(threads, 400_000),
+            benchmark::<TicketLock<u64>>(threads, 400_000),
+            benchmark::<Mutex<u64>>(threads, 400_000));
+        threads *= 2;
+    }
+    print!("{}", tr(CONTENDED));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("LOST UPDATES", "TAPTE OPPDATERINGER"),
+    ("{} core(s) available", "{} kjerne(r) tilgjengelig"),
+    (RUN_LENGTH, "  Run length = antall tilganger på rad av samme tråd. Billettlåsen
+  gir låsen til neste tråd i køen (run length nær 1), så ingen
+  sulter. Spinnlåsen og Mutex lar den som kjører ta den igjen:
+  lange serier, færre cachelinjeoverføringer, mer gjennomstrømning, ingen garantier.
+
+"),
+    (CONTENDED, "
+  Uten konkurranse (1 tråd) koster alle tre én atomisk RMW per låsing.
+  Med flere tråder enn kjerner lider billettlåsen mest: neste tråd
+  i køen kjører kanskje ikke, og ingen andre får gå først.
+  std Mutex legger ventende tråder til å sove i kjernen i stedet for å spinne.
+"),
+];
+
+// ==================== LOOM TESTS ====================
+
+/// Exhaustive checks of both locks under loom. Not part of the normal build:
+///
+///   rustc --edition 2021 --test --cfg loom -O locks.rs \
+///         --extern loom=path/to/libloom.rlib -L path/to/deps \
+///         --extern util=target/release/libutil.rlib -o locks_loom
+///   ./locks_loom
+///
+/// (or, in a Cargo project with loom as a dev-dependency:
+///  RUSTFLAGS="--cfg loom" cargo test --release)
+///
+/// The protected data is a loom atomic updated with a Relaxed load + store,
+/// so a lock with too weak orderings lets loom produce a stale read and lose
+/// an update. Weakening the Acquire in lock() or the Release in unlock() to
+/// Relaxed is the experiment to try.
+#[cfg(loom)]
+mod loom_tests {
+    use super::*;
+    use loom::sync::atomic::AtomicU64;
+    use loom::sync::Arc;
+
+    /// Two threads each increment once under the lock; `inside` catches two
+    /// threads in the critical section at the same time.
+    fn check<L: Send + Sync + 'static>(new: fn() -> L, with: fn(&L, &mut dyn FnMut())) {
+        loom::model(move || {
+            let lock = Arc::new(new());
+            let counter = Arc::new(AtomicU64::new(0));
+            let inside = Arc::new(AtomicBool::new(false));
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    let (lock, counter, inside) = (lock.clone(), counter.clone(), inside.clone());
+                    loom::thread::spawn(move || {
+                        with(&lock, &mut || {
+                            assert!(!inside.swap(true, Ordering::Relaxed), "two threads in the critical section");
+                            let v = counter.load(Ordering::Relaxed);
+                            counter.store(v + 1, Ordering::Relaxed);
+                            inside.store(false, Ordering::Relaxed);
+                        })
+                    })
+                })
+                .collect();
+            for h in handles {
+                h.join().unwrap();
+            }
+            assert_eq!(counter.load(Ordering::Relaxed), 2, "an update was lost");
+        });
+    }
+
+    #[test]
+    fn spin_lock_excludes() {
+        check(|| SpinLock::new(()), |l, f| {
+            let _guard = l.lock();
+            f()
+        });
+    }
+
+    #[test]
+    fn ticket_lock_excludes() {
+        check(|| TicketLock::new(()), |l, f| {
+            let _guard = l.lock();
+            f()
+        });
+    }
+}
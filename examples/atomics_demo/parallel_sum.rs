@@ -0,0 +1,201 @@
+/*
+ * Parallel Overflow-Safe Summation
+ * Sums a large i32 slice across N threads. The naive version keeps the
+ * running total in an i32 and wraps (silently, in a release build); the safe
+ * versions give every chunk an i64 accumulator, or use checked_add and
+ * report the overflow. The timing table measures speedup per thread count
+ * and estimates the serial fraction for Amdahl's law.
+ *
+ *   [ chunk 0 | chunk 1 | chunk 2 | chunk 3 ]    one thread per chunk
+ *       │         │         │         │          i64 partial sums
+ *       └─────────┴────┬────┴─────────┘
+ *                      +                         combined by the main thread
+ *
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin parallel_sum -- [millions of elements] [max threads] [--format json] [--lang no]
+ */
+
+use std::fmt::Write;
+use std::thread;
+use std::time::Instant;
+
+use util::lang::{self, Catalog};
+use util::report::{Format, Json, Report};
+
+/// Deterministic pseudo-random data in [-2^30, 2^30): each value fits easily,
+/// but the i32 running total overflows many times along the way.
+fn make_data(n: usize) -> Vec<i32> {
+    let mut x: u32 = 0x2545_F491;
+    (0..n)
+        .map(|i| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            // a small per-element bias so the total drifts far beyond i32
+            (x >> 1) as i32 - (1 << 30) + (i % 3) as i32 * 1000
+        })
+        .collect()
+}
+
+// ==================== SUMS ====================
+
+/// The bug: an i32 accumulator. Release builds wrap; debug builds would panic.
+fn naive_sum(data: &[i32]) -> i32 {
+    data.iter().fold(0i32, |acc, &v| acc.wrapping_add(v))
+}
+
+/// One i64 per chunk: at most 2^32 elements of 2^31 each cannot overflow it.
+fn chunk_sum(data: &[i32]) -> i64 {
+    data.iter().map(|&v| v as i64).sum()
+}
+
+/// Checked accumulation for callers that need the result as i32.
+fn checked_sum(data: &[i32]) -> Result<i32, usize> {
+    let mut acc: i32 = 0;
+    for (i, &v) in data.iter().enumerate() {
+        acc = acc.checked_add(v).ok_or(i)?;
+    }
+    Ok(acc)
+}
+
+/// Split `data` into `threads` chunks, sum each in its own thread, combine.
+fn parallel_sum<T: Send>(data: &[i32], threads: usize, sum: fn(&[i32]) -> T) -> Vec<T> {
+    let chunk = data.len().div_ceil(threads);
+    thread::scope(|s| {
+        let handles: Vec<_> = data.chunks(chunk.max(1)).map(|c| s.spawn(move || sum(c))).collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+fn parallel_i64(data: &[i32], threads: usize) -> i64 {
+    parallel_sum(data, threads, chunk_sum).iter().sum()
+}
+
+/// Fastest of a few runs, to keep the scheduler's noise out of the table.
+fn best_time(runs: usize, mut f: impl FnMut()) -> f64 {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed().as_secs_f64()
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+// ==================== REPORTING ====================
+
+const WRAPPING: &str = "  Wrapping is consistent (two's complement): the naive result is right
+  modulo 2^32 and wrong as a number. Splitting into chunks does not help
+  unless each chunk gets a wider accumulator.
+";
+
+const AMDAHL: &str = "
+  Amdahl: speedup = 1 / (f + (1 - f) / p). Summing is memory-bound, so
+  the speedup flattens once the threads saturate memory bandwidth, and
+  more threads than cores ({}) only add overhead.
+";
+
+struct Results {
+    millions: usize,
+    cores: usize,
+    exact: i64,
+    parallel: i64,
+    naive: i32,
+    checked: Result<Vec<i32>, usize>,
+    /// (threads, best time in seconds)
+    times: Vec<(usize, f64)>,
+}
+
+impl Results {
+    /// (speedup, efficiency, Karp-Flatt serial fraction) of a row.
+    fn derived(&self, p: usize, t: f64) -> (f64, f64, Option<f64>) {
+        let speedup = self.times[0].1 / t;
+        // the serial fraction f that Amdahl's law needs to explain this speedup
+        let serial = (p > 1).then(|| (1.0 / speedup - 1.0 / p as f64) / (1.0 - 1.0 / p as f64));
+        (speedup, speedup / p as f64, serial)
+    }
+}
+
+impl Report for Results {
+    fn text(&self) -> String {
+        let mut s = String::from("=== Parallel Overflow-Safe Summation ===\n");
+        let _ = writeln!(s, "{} million i32 values, {} core(s) available\n", self.millions, self.cores);
+        s.push_str("--- Correctness ---\n");
+        let _ = writeln!(s, "  i64 accumulator (1 thread): {}", self.exact);
+        let _ = writeln!(s, "  i64 per chunk (4 threads):  {}", self.parallel);
+        let _ = writeln!(s, "  i32 accumulator (naive):    {}   ← wrapped: {} = exact mod 2^32", self.naive, self.exact as i32);
+        let _ = match &self.checked {
+            Ok(parts) => writeln!(s, "  checked i32 per chunk:      ok, parts {:?}", parts),
+            Err(i) => writeln!(s, "  checked i32 per chunk:      Err: overflow at element {} of a chunk", i),
+        };
+        s.push_str(lang::tr(NO, WRAPPING));
+        s.push_str("\n--- Speedup (i64 per chunk, best of 5 runs) ---\n");
+        let _ = writeln!(s, "  {:>7} {:>10} {:>8} {:>10} {:>15}", "threads", "time (ms)", "speedup", "efficiency", "serial fraction");
+        for &(p, t) in &self.times {
+            let (speedup, efficiency, serial) = self.derived(p, t);
+            let serial = serial.map_or("-".to_string(), |f| format!("{:.3}", f));
+            let _ = writeln!(s, "  {:>7} {:>10.2} {:>8.2} {:>9.0}% {:>15}", p, t * 1e3, speedup, 100.0 * efficiency, serial);
+        }
+        s.push_str(&lang::fill(lang::tr(NO, AMDAHL), &[&self.cores]));
+        s
+    }
+
+    fn json(&self) -> Json {
+        let checked = match &self.checked {
+            Ok(parts) => Json::object().with("ok", true).with("parts", parts.clone()),
+            Err(i) => Json::object().with("ok", false).with("overflow_at", *i),
+        };
+        Json::object()
+            .with("elements", self.millions * 1_000_000)
+            .with("cores", self.cores)
+            .with("sum_i64", self.exact)
+            .with("sum_i64_parallel", self.parallel)
+            .with("sum_i32_wrapped", self.naive)
+            .with("checked_i32", checked)
+            .with("speedup", Json::array(self.times.iter().map(|&(p, t)| {
+                let (speedup, efficiency, serial) = self.derived(p, t);
+                Json::object().with("threads", p).with("seconds", t).with("speedup", speedup)
+                    .with("efficiency", efficiency).with("serial_fraction", serial)
+            })))
+    }
+}
+
+fn main() {
+    let (format, args) = Format::from_env("parallel_sum [millions of elements] [max threads] [--format text|json] [--lang no|en]");
+    let millions: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(20);
+    let max_threads: usize = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(8);
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let data = make_data(millions * 1_000_000);
+
+    let exact = chunk_sum(&data);
+    let mut results = Results {
+        millions,
+        cores,
+        exact,
+        parallel: parallel_i64(&data, 4),
+        naive: naive_sum(&data),
+        checked: parallel_sum(&data, 4, checked_sum).into_iter().collect(),
+        times: Vec::new(),
+    };
+    let mut p = 1;
+    while p <= max_threads {
+        results.times.push((p, best_time(5, || { std::hint::black_box(parallel_i64(&data, p)); })));
+        p *= 2;
+    }
+    format.print(&results);
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    (WRAPPING, "  Omløpet er konsistent (toerkomplement): det naive resultatet er riktig
+  modulo 2^32 og feil som tall. Å dele opp i biter hjelper ikke med mindre
+  hver bit får en bredere akkumulator.
+"),
+    (AMDAHL, "
+  Amdahl: speedup = 1 / (f + (1 - f) / p). Summering er begrenset av minnet,
+  så speedup flater ut når trådene bruker opp minnebåndbredden, og flere
+  tråder enn kjerner ({}) gir bare ekstra overhead.
+"),
+];
@@ -0,0 +1,156 @@
+/*
+ * Peterson's Algorithm and Weak Memory
+ * Mutual exclusion for two threads from plain loads and stores - correct on
+ * a sequentially consistent machine, broken on a real one unless the
+ * orderings say otherwise.
+ *
+ *   lock(me):   flag[me] = true          // I want in
+ *               turn = other             // but you go first
+ *               while flag[other] && turn == other { spin }
+ *   unlock(me): flag[me] = false
+ *
+ * The entry protocol is the store buffering (SB) litmus test from litmus.rs:
+ * each thread stores its flag, then loads the other's. With Relaxed (or even
+ * Acquire/Release) orderings both loads may run before the other thread's
+ * store is visible - both read false and both enter. x86 does exactly this,
+ * because stores wait in the store buffer. SeqCst forbids the SB outcome, so
+ * the SeqCst version is correct.
+ *
+ * The explanation is printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin peterson -- [entries per thread] [--lang no]
+ */
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use util::lang::{self, Catalog};
+
+struct Peterson {
+    flag: [AtomicBool; 2],
+    turn: AtomicU32,
+    store: Ordering,
+    load: Ordering,
+}
+
+impl Peterson {
+    fn new(store: Ordering, load: Ordering) -> Self {
+        Peterson { flag: [AtomicBool::new(false), AtomicBool::new(false)], turn: AtomicU32::new(0), store, load }
+    }
+
+    fn lock(&self, me: usize) {
+        let other = 1 - me;
+        self.flag[me].store(true, self.store);
+        self.turn.store(other as u32, self.store);
+        let mut spins = 0u32;
+        while self.flag[other].load(self.load) && self.turn.load(self.load) == other as u32 {
+            spins += 1;
+            if spins.is_multiple_of(100) {
+                thread::yield_now(); // the other thread may not be running at all
+            } else {
+                std::hint::spin_loop();
+            }
+        }
+    }
+
+    fn unlock(&self, me: usize) {
+        self.flag[me].store(false, self.store);
+    }
+}
+
+/// Both threads enter the critical section `entries` times. Inside, they
+/// register in `inside`; finding the other thread already there is a
+/// violation. Returns (violations, lost updates on a plain counter).
+fn stress(store: Ordering, load: Ordering, entries: u64) -> (u64, u64) {
+    let lock = Arc::new(Peterson::new(store, load));
+    let inside = Arc::new(AtomicU32::new(0));
+    let violations = Arc::new(AtomicU64::new(0));
+    let counter = Arc::new(AtomicU64::new(0)); // updated with load + store: relies on the lock
+    let handles: Vec<_> = (0..2)
+        .map(|me| {
+            let (lock, inside, violations, counter) =
+                (Arc::clone(&lock), Arc::clone(&inside), Arc::clone(&violations), Arc::clone(&counter));
+            thread::spawn(move || {
+                for _ in 0..entries {
+                    lock.lock(me);
+                    if inside.fetch_add(1, Ordering::SeqCst) != 0 {
+                        violations.fetch_add(1, Ordering::Relaxed);
+                    }
+                    let v = counter.load(Ordering::Relaxed);
+                    counter.store(v + 1, Ordering::Relaxed);
+                    inside.fetch_sub(1, Ordering::SeqCst);
+                    lock.unlock(me);
+                }
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    (violations.load(Ordering::SeqCst), 2 * entries - counter.load(Ordering::SeqCst))
+}
+
+const ONLY_SEQCST: &str = "
+Only SeqCst is guaranteed to show 0: Peterson needs every thread's store
+to flag[me] to be visible before it loads flag[other], which is exactly the
+store -> load ordering that Release/Acquire does not give (see SB in litmus.rs).
+";
+
+const ONE_CORE: &str = "\
+On a single core every thread switch drains the store buffer, so the broken
+variants also show 0 here - run it on a multicore machine to see them fail.
+";
+
+const REAL_LOCKS: &str = "\
+Real locks use an atomic read-modify-write (swap, fetch_add, CAS) instead,
+see locks.rs.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("peterson [entries per thread] [--lang no|en]");
+    let entries: u64 = args.first().and_then(|s| s.parse().ok()).unwrap_or(200_000);
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+    println!("=== Peterson's Algorithm and Weak Memory ===");
+    println!("2 threads x {} entries, {} core(s) available\n", entries, cores);
+    println!("  {:<16} {:>12} {:>14}", "orderings", "violations", "lost updates");
+
+    let variants = [
+        ("Relaxed", Ordering::Relaxed, Ordering::Relaxed),
+        ("Acquire/Release", Ordering::Release, Ordering::Acquire),
+        ("SeqCst", Ordering::SeqCst, Ordering::SeqCst),
+    ];
+    for (name, store, load) in variants {
+        let (violations, lost) = stress(store, load, entries);
+        println!("  {:<16} {:>12} {:>14}", name, violations, lost);
+    }
+
+    print!("{}", tr(ONLY_SEQCST));
+    if cores == 1 {
+        print!("{}", tr(ONE_CORE));
+    }
+    print!("{}", tr(REAL_LOCKS));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    (ONLY_SEQCST, "
+Bare SeqCst gir garantert 0: Peterson trenger at hver tråds lagring til
+flag[me] er synlig før den leser flag[other], og det er nettopp den
+lagring -> lesing-ordningen som Release/Acquire ikke gir (se SB i litmus.rs).
+"),
+    (ONE_CORE, "\
+På én kjerne tømmer hvert trådbytte lagringsbufferen, så de feilaktige
+variantene viser også 0 her - kjør på en flerkjernemaskin for å se dem feile.
+"),
+    (REAL_LOCKS, "\
+Ekte låser bruker heller en atomisk les-endre-skriv (swap, fetch_add, CAS),
+se locks.rs.
+"),
+];
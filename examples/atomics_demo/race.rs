@@ -0,0 +1,288 @@
+/*
+ * A Data Race, Caught by Tools
+ * An unsynchronized counter shared between threads, and two ways to catch
+ * the bug instead of hoping a test run happens to lose an update:
+ *
+ *   1. ThreadSanitizer instruments every memory access at run time and
+ *      reports two threads touching the same location without ordering.
+ *   2. An exhaustive interleaving checker (the idea behind loom) runs the
+ *      threads under EVERY possible schedule and reports the one that fails.
+ *
+ * The racy counter is undefined behaviour, so it is only compiled with
+ * the racy feature. The default build contains the fixed (atomic) counter only.
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin race [-- --lang no]   # fixed version + checker
+ *      cargo run --release --features racy --bin race  # add the racy counter
+ * ThreadSanitizer (nightly; race.rs needs only std, so plain rustc works):
+ *   rustc +nightly -Zsanitizer=thread -Cunsafe-allow-abi-mismatch=sanitizer \
+ *         --cfg 'feature="racy"' -g race.rs -o race_tsan && ./race_tsan
+ * Tests:        cargo test -p demos [--features racy] --bin race (the checker)
+ * Model check:  see loom_tests at the end of the file
+ */
+
+#[cfg(loom)]
+extern crate loom;
+
+use std::sync::Arc;
+use std::thread;
+
+use util::lang::{self, fill, Catalog};
+
+// With --cfg loom the counter's atomic comes from loom, whose model checker
+// then explores every interleaving of real code (see the tests at the end).
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicU64, Ordering};
+#[cfg(not(loom))]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// ==================== THE COUNTERS ====================
+
+/// Fixed version: one indivisible fetch_add per increment.
+pub struct AtomicCounter(AtomicU64);
+
+impl AtomicCounter {
+    pub fn increment(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Racy version: a plain u64 that several threads write without a lock.
+/// `unsafe impl Sync` is the lie that lets it compile; the compiler would
+/// otherwise refuse to share it between threads.
+#[cfg(feature = "racy")]
+pub struct RacyCounter(std::cell::UnsafeCell<u64>);
+
+#[cfg(feature = "racy")]
+unsafe impl Sync for RacyCounter {}
+
+#[cfg(feature = "racy")]
+impl RacyCounter {
+    pub fn increment(&self) {
+        unsafe { *self.0.get() += 1 } // load, add, store: no exclusion, no ordering
+    }
+}
+
+fn run_threads(threads: usize, per_thread: u64, increment: Arc<dyn Fn() + Send + Sync>) {
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let increment = Arc::clone(&increment);
+            thread::spawn(move || {
+                for _ in 0..per_thread {
+                    increment();
+                }
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+}
+
+// ==================== INTERLEAVING CHECKER ====================
+
+/// One step of a modelled thread. An increment is either one atomic step or
+/// two separate steps with a point in between where another thread may run.
+#[derive(Clone, Copy, Debug)]
+enum Step {
+    FetchAdd,
+    Load,
+    StorePlusOne,
+}
+
+/// Explores every schedule of the threads' steps (depth first).
+struct Checker {
+    programs: Vec<Vec<Step>>,
+    schedules: usize,
+    /// First schedule found for each distinct final value.
+    outcomes: std::collections::BTreeMap<u64, Vec<usize>>,
+}
+
+impl Checker {
+    fn new(threads: usize, increments: usize, atomic: bool) -> Self {
+        let one: &[Step] = if atomic { &[Step::FetchAdd] } else { &[Step::Load, Step::StorePlusOne] };
+        let program: Vec<Step> = one.iter().copied().cycle().take(one.len() * increments).collect();
+        Checker { programs: vec![program; threads], schedules: 0, outcomes: Default::default() }
+    }
+
+    fn run(mut self) -> Self {
+        let n = self.programs.len();
+        self.explore(0, &mut vec![0; n], &mut vec![0; n], &mut Vec::new());
+        self
+    }
+
+    fn explore(&mut self, memory: u64, pcs: &mut Vec<usize>, registers: &mut Vec<u64>, schedule: &mut Vec<usize>) {
+        let mut finished = true;
+        for t in 0..self.programs.len() {
+            let Some(&step) = self.programs[t].get(pcs[t]) else { continue };
+            finished = false;
+            let (saved_register, mut next_memory) = (registers[t], memory);
+            match step {
+                Step::FetchAdd => next_memory += 1,
+                Step::Load => registers[t] = memory,
+                Step::StorePlusOne => next_memory = registers[t] + 1,
+            }
+            pcs[t] += 1;
+            schedule.push(t);
+            self.explore(next_memory, pcs, registers, schedule);
+            schedule.pop();
+            pcs[t] -= 1;
+            registers[t] = saved_register;
+        }
+        if finished {
+            self.schedules += 1;
+            self.outcomes.entry(memory).or_insert_with(|| schedule.clone());
+        }
+    }
+
+    /// The final value if no update is lost: one per increment.
+    fn expected(&self) -> u64 {
+        self.programs.iter().map(|p| p.iter().filter(|s| !matches!(s, Step::Load)).count() as u64).sum()
+    }
+
+    fn report(&self, name: &str) -> bool {
+        let expected = self.expected();
+        println!("  {}: {} {:?}", name, fill(tr("{} schedules, final values"), &[&self.schedules]),
+            self.outcomes.keys().collect::<Vec<_>>());
+        let mut ok = true;
+        for (value, schedule) in &self.outcomes {
+            if *value != expected {
+                ok = false;
+                let steps: Vec<String> = schedule
+                    .iter()
+                    .scan(vec![0; self.programs.len()], |pcs, &t| {
+                        let step = self.programs[t][pcs[t]];
+                        pcs[t] += 1;
+                        Some(format!("T{}:{:?}", t, step))
+                    })
+                    .collect();
+                println!("    {}", fill(tr("FAIL: counter = {} (expected {}) with schedule"), &[value, &expected]));
+                println!("          {}", steps.join(" → "));
+            }
+        }
+        if ok {
+            println!("    {}", fill(tr("ok: every schedule gives {}"), &[&expected]));
+        }
+        ok
+    }
+}
+
+// ==================== MAIN ====================
+
+#[cfg(feature = "racy")]
+const PROVES_NOTHING: &str = "\
+  A run that happens to print the right number proves nothing.
+  Build with -Zsanitizer=thread to have every racy access reported.
+";
+
+const EVIDENCE: &str = "
+The checker turns \"it usually works\" into a yes/no answer, with the
+failing schedule as evidence.
+loom does the same for real Rust code (and models weak memory too);
+Miri detects the data race itself when the racy version is run under it.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    lang::args("race [--lang no|en]");
+    let (threads, per_thread) = (4, 1_000_000);
+    println!("=== A Data Race, Caught by Tools ===\n");
+
+    println!("--- Running it: {} threads x {} increments ---", threads, per_thread);
+    let atomic = Arc::new(AtomicCounter(AtomicU64::new(0)));
+    let a = Arc::clone(&atomic);
+    run_threads(threads, per_thread, Arc::new(move || a.increment()));
+    println!("  atomic counter: {}", atomic.0.load(Ordering::SeqCst));
+
+    #[cfg(feature = "racy")]
+    {
+        let racy = Arc::new(RacyCounter(std::cell::UnsafeCell::new(0)));
+        let r = Arc::clone(&racy);
+        run_threads(threads, per_thread, Arc::new(move || r.increment()));
+        println!("  racy counter:   {}  {}", unsafe { *racy.0.get() }, tr("(any value is possible: this is undefined behaviour)"));
+        print!("{}", tr(PROVES_NOTHING));
+    }
+    #[cfg(not(feature = "racy"))]
+    println!("  racy counter:   {}", tr("not compiled (rebuild with --features racy)"));
+
+    println!("\n--- Checking it: every interleaving of 2 threads x 2 increments ---");
+    let racy_ok = Checker::new(2, 2, false).run().report("load + store");
+    let atomic_ok = Checker::new(2, 2, true).run().report("fetch_add");
+    assert!(!racy_ok && atomic_ok, "the checker should reject load + store and accept fetch_add");
+
+    print!("{}", tr(EVIDENCE));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("{} schedules, final values", "{} rekkefølger, sluttverdier"),
+    ("FAIL: counter = {} (expected {}) with schedule", "FEIL: telleren = {} (forventet {}) med rekkefølgen"),
+    ("ok: every schedule gives {}", "ok: hver rekkefølge gir {}"),
+    ("(any value is possible: this is undefined behaviour)", "(enhver verdi er mulig: dette er udefinert oppførsel)"),
+    #[cfg(feature = "racy")]
+    (PROVES_NOTHING, "\
+  En kjøring som tilfeldigvis skriver riktig tall, beviser ingenting.
+  Bygg med -Zsanitizer=thread for å få hver kappløpsaksess rapportert.
+"),
+    ("not compiled (rebuild with --features racy)", "ikke kompilert (bygg på nytt med --features racy)"),
+    (EVIDENCE, "
+Sjekkeren gjør «det virker som regel» om til et ja/nei-svar, med den
+feilende rekkefølgen som bevis.
+loom gjør det samme for ekte Rust-kode (og modellerer svakt minne også);
+Miri oppdager selve datakappløpet når den racy versjonen kjøres under den.
+"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fetch_add_passes_every_interleaving() {
+        let checker = Checker::new(2, 2, true).run();
+        assert_eq!(checker.schedules, 6, "4 steps of 2 threads: 4 choose 2 orders");
+        assert_eq!(checker.outcomes.keys().copied().collect::<Vec<_>>(), [checker.expected()]);
+    }
+
+    /// The model of the racy counter, which is only compiled with the feature.
+    #[cfg(feature = "racy")]
+    #[test]
+    fn load_store_loses_an_update() {
+        let checker = Checker::new(2, 2, false).run();
+        let lost = checker.outcomes.keys().find(|&&v| v < checker.expected()).expect("a lost update");
+        // the failing schedule must be a real one: every step of both threads
+        assert_eq!(checker.outcomes[lost].len(), 8);
+        assert_eq!(checker.outcomes.keys().next(), Some(&2), "the worst case loses one update per thread");
+    }
+}
+
+// ==================== LOOM TESTS ====================
+
+/// The real AtomicCounter under loom, built like the tests in counters.rs:
+///
+///   rustc --edition 2021 --test --cfg loom -O race.rs \
+///         --extern loom=path/to/libloom.rlib -L path/to/deps -o race_loom
+#[cfg(loom)]
+mod loom_tests {
+    use super::*;
+    use loom::sync::Arc;
+
+    #[test]
+    fn atomic_counter_never_loses_an_update() {
+        loom::model(|| {
+            let c = Arc::new(AtomicCounter(AtomicU64::new(0)));
+            let other = c.clone();
+            let t = loom::thread::spawn(move || {
+                other.increment();
+                other.increment();
+            });
+            c.increment();
+            t.join().unwrap();
+            assert_eq!(c.0.load(Ordering::SeqCst), 3);
+        });
+    }
+}
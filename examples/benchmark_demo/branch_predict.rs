@@ -0,0 +1,275 @@
+/*
+ * Branch Misprediction: Sorted vs Shuffled
+ * The classic experiment: sum the elements >= 128 of an array of random
+ * bytes. The work is identical whether the array is sorted or not, yet the
+ * shuffled version is several times slower - because of ONE branch.
+ *
+ *   for x in data { if x >= 128 { sum += x } }
+ *
+ *   sorted:    N N N N ... N N T T T ... T T    predictor right almost always
+ *   shuffled:  T N N T N T T N ... (coin flips) predictor wrong ~50% of the time
+ *
+ * A modern core fetches 10-20 cycles ahead of where it executes, guessing
+ * every branch. Each wrong guess throws that work away: a misprediction
+ * costs roughly the pipeline depth in cycles.
+ *
+ * The same outcome sequences are fed to software models of the textbook
+ * predictors (static, 1-bit, 2-bit saturating counter, gshare; see
+ * riscv::predictor), so the measured slowdown can be set against the
+ * predicted miss rates. Built with --features perf on Linux, the real
+ * branch-miss counter is read too (see util::perf), so measured and
+ * modelled miss rates sit side by side.
+ *
+ * The compiler would happily turn the `if` into a branchless cmov or
+ * vectorize the loop - which is the standard FIX, shown as the last row.
+ * black_box inside the branch keeps the measured loop a real branch.
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin branch_predict -- [elements] [--format json] [--lang no]
+ *      cargo run --release --features perf --bin branch_predict   (hardware counters)
+ */
+
+use std::fmt::Write;
+use std::hint::black_box;
+
+use riscv::predictor::{self, GShare, OneBit, Predictor, StaticNotTaken, TwoBit};
+use util::lang::{self, fill, Catalog};
+use util::rng::Rng;
+use util::report::{Format, Json, Report};
+use util::{cycles, perf};
+
+const REPEATS: usize = 100;
+
+// ==================== THE MEASURED LOOPS ====================
+
+#[inline(never)]
+fn sum_branchy(data: &[u8]) -> u64 {
+    let mut sum = 0u64;
+    for &x in data {
+        if x >= 128 {
+            sum += black_box(x) as u64;
+        }
+    }
+    sum
+}
+
+#[inline(never)]
+fn sum_branchless(data: &[u8]) -> u64 {
+    let mut sum = 0u64;
+    for &x in data {
+        let keep = ((x >= 128) as u64).wrapping_neg(); // all ones or all zeros
+        sum += x as u64 & keep;
+    }
+    sum
+}
+
+/// Best of five runs of REPEATS passes, in ns per element.
+fn time_per_element<F: Fn(&[u8]) -> u64>(data: &[u8], f: F) -> (f64, u64) {
+    let mut best = f64::MAX;
+    let mut result = 0;
+    for _ in 0..5 {
+        let start = cycles::Stopwatch::start();
+        for _ in 0..REPEATS {
+            result = f(black_box(data));
+        }
+        let ns = start.elapsed_ns() / (REPEATS * data.len()) as f64;
+        if ns < best {
+            best = ns;
+        }
+    }
+    (best, result)
+}
+
+// ==================== PREDICTOR MODELS ====================
+
+/// A fresh predictor of each kind (no state carried between sequences);
+/// all the outcomes are of one branch, so the tables have one entry and
+/// gshare 8 bits of history.
+fn model(kind: usize) -> (Box<dyn Predictor>, &'static str) {
+    match kind {
+        0 => (Box::new(StaticNotTaken), "static not-taken"),
+        1 => (Box::new(OneBit::new(0)), "1-bit last outcome"),
+        2 => (Box::new(TwoBit::new(0)), "2-bit saturating"),
+        _ => (Box::new(GShare::new(8)), "gshare, 8-bit history"),
+    }
+}
+
+fn miss_rate(kind: usize, outcomes: &[bool]) -> f64 {
+    let branch: Vec<(u32, bool)> = outcomes.iter().map(|&taken| (0, taken)).collect();
+    predictor::miss_rate(&mut *model(kind).0, &branch)
+}
+
+// ==================== REPORTING ====================
+
+const DATA: [&str; 3] = ["sorted", "shuffled", "TTTN"];
+
+/// One pass over each data set with the hardware counters on.
+struct Measured {
+    branch_misses: u64,
+    ipc: Option<f64>,
+}
+
+struct Results {
+    elements: usize,
+    /// ns per element: sorted, shuffled and TTTN with the branch, shuffled branchless
+    sorted: f64,
+    shuffled: f64,
+    periodic: f64,
+    branchless: f64,
+    /// (predictor, miss rate per data set)
+    models: Vec<(&'static str, [f64; 3])>,
+    ghz: f64,
+    /// per data set, or why the counters could not be read
+    counters: Result<Vec<Measured>, String>,
+}
+
+impl Results {
+    /// The 2-bit model's extra misses per element on shuffled data.
+    fn two_bit_gap(&self) -> f64 {
+        let rates = self.models[2].1;
+        rates[1] - rates[0]
+    }
+
+    fn penalty_ns(&self) -> f64 {
+        (self.shuffled - self.sorted) / self.two_bit_gap()
+    }
+}
+
+const HISTORY: &str = "   Only history-based prediction learns TTTN; nothing predicts coin flips.\n";
+
+const PENALTY: &str = "   ({} ns extra per element / {}% more misses) - at the measured {} GHz that is ~{} cycles,
+   about the depth of a modern pipeline.
+";
+
+const COUNTERS: &str = "   The loop's own back-edge branch is predicted almost perfectly and adds ~0.
+   Real predictors keep far longer histories than the models: TTTN costs them nothing.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+impl Report for Results {
+    fn text(&self) -> String {
+        let mut s = String::from("=== Branch Misprediction: Sorted vs Shuffled ===\n\n");
+        let _ = writeln!(s, "{} random bytes, branch `if x >= 128`, best of 5 x {} passes\n", self.elements, REPEATS);
+
+        s.push_str("1) Measured time per element:\n");
+        let _ = writeln!(s, "   sorted, branch           {:6.2} ns", self.sorted);
+        let _ = writeln!(s, "   shuffled, branch         {:6.2} ns   {}", self.shuffled,
+            fill(tr("{}x slower, same work"), &[&format!("{:.1}", self.shuffled / self.sorted)]));
+        let _ = writeln!(s, "   pattern TTTN, branch     {:6.2} ns", self.periodic);
+        let _ = writeln!(s, "   shuffled, branchless     {:6.2} ns   {}", self.branchless, tr("no branch to mispredict"));
+
+        s.push_str("\n2) The same outcome sequences through predictor models (miss rate):\n");
+        let _ = writeln!(s, "   {:<24} {:>8} {:>9} {:>9}", "predictor", "sorted", "shuffled", "TTTN");
+        for (name, rates) in &self.models {
+            let _ = writeln!(s, "   {:<24} {:>7.1}% {:>8.1}% {:>8.1}%", name, 100.0 * rates[0], 100.0 * rates[1], 100.0 * rates[2]);
+        }
+        s.push_str(tr(HISTORY));
+
+        let penalty_ns = self.penalty_ns();
+        let _ = writeln!(s, "\n3) Implied cost of one misprediction (2-bit model): {:.1} ns", penalty_ns);
+        s.push_str(&fill(tr(PENALTY), &[&format!("{:.2}", self.shuffled - self.sorted), &format!("{:.0}", 100.0 * self.two_bit_gap()),
+            &format!("{:.1}", self.ghz), &format!("{:.0}", penalty_ns * self.ghz)]));
+
+        s.push_str("\n4) Hardware counters for one pass, next to the models:\n");
+        match &self.counters {
+            Ok(rows) => {
+                let _ = writeln!(s, "   {:<9} {:>8} {:>9} {:>8} {:>8} {:>6}", "data", "misses", "measured", "2-bit", "gshare", "IPC");
+                for (i, row) in rows.iter().enumerate() {
+                    let _ = writeln!(s, "   {:<9} {:>8} {:>8.1}% {:>7.1}% {:>7.1}% {:>6}",
+                        DATA[i], row.branch_misses, 100.0 * row.branch_misses as f64 / self.elements as f64,
+                        100.0 * self.models[2].1[i], 100.0 * self.models[3].1[i],
+                        row.ipc.map_or("-".to_string(), |ipc| format!("{:.2}", ipc)));
+                }
+                s.push_str(tr(COUNTERS));
+            }
+            Err(why) => {
+                let _ = writeln!(s, "   {} {}", tr("not available:"), why);
+            }
+        }
+        s
+    }
+
+    fn json(&self) -> Json {
+        let counters = match &self.counters {
+            Ok(rows) => Json::array(rows.iter().enumerate().map(|(i, row)| {
+                Json::object().with("data", DATA[i]).with("branch_misses", row.branch_misses)
+                    .with("miss_rate", row.branch_misses as f64 / self.elements as f64).with("ipc", row.ipc)
+            })),
+            Err(_) => Json::Null,
+        };
+        Json::object()
+            .with("elements", self.elements)
+            .with("ns_per_element", Json::object()
+                .with("sorted", self.sorted)
+                .with("shuffled", self.shuffled)
+                .with("tttn", self.periodic)
+                .with("shuffled_branchless", self.branchless))
+            .with("models", Json::array(self.models.iter().map(|(name, rates)| {
+                Json::object().with("predictor", *name).with("miss_rate", Json::object()
+                    .with("sorted", rates[0]).with("shuffled", rates[1]).with("tttn", rates[2]))
+            })))
+            .with("clock_ghz", self.ghz)
+            .with("misprediction_ns", self.penalty_ns())
+            .with("misprediction_cycles", self.penalty_ns() * self.ghz)
+            .with("counters", counters)
+    }
+}
+
+// ==================== MAIN ====================
+
+fn main() {
+    let (format, args) = Format::from_env("branch_predict [elements] [--format text|json] [--lang no|en]");
+    let n: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(32 * 1024);
+    let mut rng = Rng::new(4160);
+    let shuffled: Vec<u8> = (0..n).map(|_| rng.next() as u8).collect();
+    let mut sorted = shuffled.clone();
+    sorted.sort();
+    let periodic: Vec<u8> = (0..n).map(|i| if i % 4 == 3 { 0 } else { 200 }).collect(); // T T T N ...
+
+    let (t_sorted, s1) = time_per_element(&sorted, sum_branchy);
+    let (t_shuffled, s2) = time_per_element(&shuffled, sum_branchy);
+    let (t_periodic, _) = time_per_element(&periodic, sum_branchy);
+    let (t_branchless, s3) = time_per_element(&shuffled, sum_branchless);
+    assert!(s1 == s2 && s2 == s3, "all versions must compute the same sum");
+
+    let outcomes = |d: &[u8]| -> Vec<bool> { d.iter().map(|&x| x >= 128).collect() };
+    let all = [outcomes(&sorted), outcomes(&shuffled), outcomes(&periodic)];
+    let models = (0..4).map(|kind| (model(kind).1, [0, 1, 2].map(|i| miss_rate(kind, &all[i])))).collect();
+
+    let counters = match perf::Counters::open() {
+        Some(mut counters) => Ok([&sorted, &shuffled, &periodic].iter().map(|data| {
+            let (_, counts) = counters.measure(|| black_box(sum_branchy(black_box(data))));
+            Measured { branch_misses: counts.branch_misses.unwrap_or(0), ipc: counts.ipc() }
+        }).collect()),
+        None => Err(perf::unavailable()),
+    };
+
+    format.print(&Results {
+        elements: n,
+        sorted: t_sorted,
+        shuffled: t_shuffled,
+        periodic: t_periodic,
+        branchless: t_branchless,
+        models,
+        ghz: cycles::core_hz() / 1e9,
+        counters,
+    });
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("{}x slower, same work", "{}x tregere, samme arbeid"),
+    ("no branch to mispredict", "ingen hopp å feilpredikere"),
+    (HISTORY, "   Bare historiebasert prediksjon lærer TTTN; ingenting predikerer myntkast.\n"),
+    (PENALTY, "   ({} ns ekstra per element / {}% flere bom) - ved målte {} GHz er det ~{} sykluser,
+   omtrent dybden av et moderne samlebånd.
+"),
+    (COUNTERS, "   Løkkens eget bakoverhopp predikeres nesten perfekt og legger til ~0.
+   Ekte prediktorer har mye lengre historie enn modellene: TTTN koster dem ingenting.
+"),
+    ("not available:", "ikke tilgjengelig:"),
+];
@@ -0,0 +1,165 @@
+/*
+ * cycles - Serialized cycle-counter reads for micro-benchmarks
+ * Instant is fine for milliseconds, but a cache miss or a mispredicted
+ * branch lasts nanoseconds. The CPU's own counter is read in a few cycles:
+ *
+ *   x86_64    rdtsc / rdtscp    time-stamp counter, constant rate
+ *   aarch64   mrs cntvct_el0    generic timer, constant rate (cntfrq_el0)
+ *
+ * THE CATCH: an out-of-order core does not wait for earlier instructions
+ * before reading the counter. In
+ *
+ *   t0 = rdtsc; slow work; t1 = rdtsc
+ *
+ * the second rdtsc can execute while the slow work is still in flight, so
+ * a naive measurement comes out shorter than the real latency. Fences make
+ * the reads wait:
+ *
+ *   start:  lfence; rdtsc; lfence      (isb; mrs; isb on ARM)
+ *   stop:   rdtscp; lfence             (rdtscp waits for earlier instructions)
+ *
+ * Both counters tick at a fixed rate, NOT at the core clock: with turbo or
+ * power saving a "tick" is not a cycle. core_hz() estimates the real clock
+ * from a loop that takes one cycle per iteration, so ticks can be turned into
+ * core cycles.
+ *
+ * The counter code is util::cycles, shared by the Rust benchmarks; this
+ * program calibrates it and shows what the fences are for. The explanation
+ * is printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin cycles -- [--format json] [--lang no]
+ */
+
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::Instant;
+use util::cycles::{self, calibration, core_hz, measure, min_and_median, ticks_to_cycles, Calibration, COUNTER};
+use util::lang::{self, Catalog};
+use util::report::{Format, Json, Report};
+
+// ==================== DEMONSTRATION ====================
+
+/// Dependent integer divisions: each needs the previous quotient, so the
+/// chain takes `DIVS` times the divider's latency.
+const DIVS: usize = 16;
+
+#[inline(always)]
+fn division_chain(x: u64, d: u64) -> u64 {
+    let mut x = x;
+    for _ in 0..DIVS {
+        x = x / d + 0x1234_5678_9abc_def0;
+    }
+    x
+}
+
+// ==================== REPORTING ====================
+
+const READING: &str = "
+Reading the numbers:
+  - Without fences the second read does not wait for the divisions: it
+    can execute as soon as it is fetched, and the chain looks shorter
+    (or the two reads overlap earlier work and the numbers jitter).
+  - The fences cost a few tens of cycles; that is the calibrated overhead,
+    taken off every measurement.
+  - Ticks are not cycles: the counter runs at a fixed rate while the core
+    clock moves with turbo and power saving. Time many repetitions and
+    take the minimum, as the other benchmarks in this directory do.
+";
+
+struct Results {
+    cal: Calibration,
+    reported: bool,
+    core_hz: f64,
+    instant_ns: u128,
+    /// (min, median) ticks of the division chain
+    naive: (u64, u64),
+    fenced: (u64, u64),
+}
+
+impl Report for Results {
+    fn text(&self) -> String {
+        let mut s = format!("=== Cycle Counters: {} ===\n\n", COUNTER);
+        s.push_str("1) Calibration:\n");
+        let _ = writeln!(s, "   counter rate           {:8.3} GHz ({})", self.cal.hz / 1e9,
+            if self.reported { "reported by the CPU" } else { "measured against Instant" });
+        let _ = writeln!(s, "   core clock (estimate)  {:8.3} GHz (1-cycle loop)", self.core_hz / 1e9);
+        let _ = writeln!(s, "   empty start/stop pair  {:8} ticks, subtracted from every measurement", self.cal.overhead);
+        let _ = writeln!(s, "   empty Instant pair     {:8} ns", self.instant_ns);
+
+        let _ = writeln!(s, "\n2) Timing {} dependent 64-bit divisions, 10 000 runs:", DIVS);
+        let (naive_min, naive_med) = self.naive;
+        let (fenced_min, fenced_med) = self.fenced;
+        let _ = writeln!(s, "   {:<26} {:>6} {:>8} {:>14}", "", "min", "median", "core cycles");
+        let _ = writeln!(s, "   {:<26} {:>6} {:>8} {:>14.0}", "plain reads (no fences)", naive_min, naive_med, ticks_to_cycles(naive_min));
+        let _ = writeln!(s, "   {:<26} {:>6} {:>8} {:>14.0}", "serialized (fences)", fenced_min, fenced_med, ticks_to_cycles(fenced_min));
+        let _ = writeln!(s, "   → {:.1} cycles per division", ticks_to_cycles(fenced_min) / DIVS as f64);
+
+        s.push_str(lang::tr(NO, READING));
+        s
+    }
+
+    fn json(&self) -> Json {
+        let chain = |(min, median): (u64, u64)| {
+            Json::object().with("min_ticks", min).with("median_ticks", median).with("min_cycles", ticks_to_cycles(min))
+        };
+        Json::object()
+            .with("counter", COUNTER)
+            .with("counter_hz", self.cal.hz)
+            .with("counter_hz_reported", self.reported)
+            .with("core_hz", self.core_hz)
+            .with("overhead_ticks", self.cal.overhead)
+            .with("instant_overhead_ns", self.instant_ns as u64)
+            .with("divisions", DIVS)
+            .with("unserialized", chain(self.naive))
+            .with("serialized", chain(self.fenced))
+            .with("cycles_per_division", ticks_to_cycles(self.fenced.0) / DIVS as f64)
+    }
+}
+
+fn main() {
+    let (format, _) = Format::from_env("cycles [--format text|json] [--lang no|en]");
+    let cal = calibration();
+    let core = core_hz();
+    let mut instant_best = u128::MAX;
+    for _ in 0..10_000 {
+        let w = Instant::now();
+        instant_best = instant_best.min(w.elapsed().as_nanos());
+    }
+
+    let d = black_box(7u64);
+    let (mut naive, mut fenced) = (Vec::new(), Vec::new());
+    let mut sink = 1u64;
+    for _ in 0..10_000 {
+        let t0 = cycles::arch::unserialized();
+        sink = division_chain(sink, d);
+        let t1 = cycles::arch::unserialized();
+        naive.push(t1.wrapping_sub(t0));
+
+        let ((), ticks) = measure(|| sink = division_chain(sink, d));
+        fenced.push(ticks);
+    }
+    black_box(sink);
+
+    format.print(&Results {
+        cal,
+        reported: cycles::arch::counter_hz().is_some(),
+        core_hz: core,
+        instant_ns: instant_best,
+        naive: min_and_median(naive),
+        fenced: min_and_median(fenced),
+    });
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[(READING, "
+Slik leser du tallene:
+  - Uten fences venter ikke den andre lesingen på divisjonene: den kan
+    utføres så snart den er hentet, og kjeden ser kortere ut (eller de
+    to lesingene overlapper tidligere arbeid, og tallene hopper).
+  - Fencene koster noen titalls sykluser; det er den kalibrerte overheaden,
+    som trekkes fra hver måling.
+  - Tikk er ikke sykluser: telleren går med fast takt, mens kjerneklokken
+    endres med turbo og strømsparing. Ta tiden på mange repetisjoner og
+    bruk minimum, slik de andre benchmarkene i denne katalogen gjør.
+")];
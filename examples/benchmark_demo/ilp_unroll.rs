@@ -0,0 +1,213 @@
+/*
+ * Instruction-Level Parallelism: One Accumulator vs Several
+ * Summing an array of f64 looks like one add per element, but
+ *
+ *   for x in data { sum += x }
+ *
+ * is a single DEPENDENCY CHAIN: every add needs the previous sum. A modern
+ * core can start two FP adds per cycle, yet each takes ~3-4 cycles to
+ * produce its result, so the chain runs at one add per 4 cycles and most
+ * of the adders sit idle.
+ *
+ *   1 accumulator:   s += a0 → s += a1 → s += a2 → ...        latency-bound
+ *   4 accumulators:  s0 += a0   s1 += a1   s2 += a2   s3 += a3 (in flight together)
+ *                    s0 += a4   s1 += a5   ...                 throughput-bound
+ *
+ * The compiler may not do this itself: FP addition is not associative, so
+ * (a + b) + c and a + (b + c) can round differently. Unrolling with
+ * independent accumulators is the programmer telling it that is fine.
+ *
+ * The array fits in L1 so memory is not the limit. The Java pipeline
+ * simulator runs the equivalent RISC-V loops and shows the other half of
+ * the story - loop overhead and load-use stalls:
+ *
+ *   java -cp out computerdesign.Main unrolling
+ *
+ * Timing uses the cycle counter (util::cycles), so the table can also show
+ * core cycles per element: ~4 for the chain is the FP adder's latency.
+ *
+ * The explanation is printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin ilp_unroll -- [elements] [--format json] [--lang no]
+ */
+
+use std::fmt::Write;
+use std::hint::black_box;
+
+use util::cycles;
+use util::lang::{self, Catalog};
+use util::report::{Format, Json, Report};
+
+const PASSES: usize = 2000;
+
+// ==================== THE KERNELS ====================
+
+#[inline(never)]
+fn sum_chain(data: &[f64]) -> f64 {
+    let mut s = 0.0;
+    for &x in data {
+        s += x;
+    }
+    s
+}
+
+#[inline(never)]
+fn sum_2(data: &[f64]) -> f64 {
+    let (mut s0, mut s1) = (0.0, 0.0);
+    for c in data.chunks_exact(2) {
+        s0 += c[0];
+        s1 += c[1];
+    }
+    s0 + s1
+}
+
+#[inline(never)]
+fn sum_4(data: &[f64]) -> f64 {
+    let (mut s0, mut s1, mut s2, mut s3) = (0.0, 0.0, 0.0, 0.0);
+    for c in data.chunks_exact(4) {
+        s0 += c[0];
+        s1 += c[1];
+        s2 += c[2];
+        s3 += c[3];
+    }
+    (s0 + s1) + (s2 + s3)
+}
+
+#[inline(never)]
+fn sum_8(data: &[f64]) -> f64 {
+    let (mut s0, mut s1, mut s2, mut s3) = (0.0, 0.0, 0.0, 0.0);
+    let (mut s4, mut s5, mut s6, mut s7) = (0.0, 0.0, 0.0, 0.0);
+    for c in data.chunks_exact(8) {
+        s0 += c[0];
+        s1 += c[1];
+        s2 += c[2];
+        s3 += c[3];
+        s4 += c[4];
+        s5 += c[5];
+        s6 += c[6];
+        s7 += c[7];
+    }
+    ((s0 + s1) + (s2 + s3)) + ((s4 + s5) + (s6 + s7))
+}
+
+/// Unrolled 4x but still ONE accumulator: less loop overhead, same chain.
+#[inline(never)]
+fn sum_unrolled_chain(data: &[f64]) -> f64 {
+    let mut s = 0.0;
+    for c in data.chunks_exact(4) {
+        s += c[0];
+        s += c[1];
+        s += c[2];
+        s += c[3];
+    }
+    s
+}
+
+// ==================== MEASURING ====================
+
+/// Best of five runs, in ns per element.
+fn time_per_element(data: &[f64], f: fn(&[f64]) -> f64) -> (f64, f64) {
+    let mut best = f64::MAX;
+    let mut result = 0.0;
+    for _ in 0..5 {
+        let start = cycles::Stopwatch::start();
+        for _ in 0..PASSES {
+            result = f(black_box(data));
+        }
+        best = best.min(start.elapsed_ns() / (PASSES * data.len()) as f64);
+    }
+    (best, result)
+}
+
+// ==================== REPORTING ====================
+
+const READING: &str = "\nReading the table:
+  - Unrolling alone barely helps: the adds still wait for each other.
+    Loop overhead was never the bottleneck - the dependency chain is.
+  - Each independent accumulator gives the core another add to start
+    while the others are in flight, until the FP units are saturated:
+    speedup levels off near latency x adds-per-cycle (~4-8 on x86/ARM).
+  - All versions give the same sum only because these values add exactly;
+    in general reassociation changes the rounding.
+
+In the 5-stage pipeline simulator an add takes 1 cycle and forwarding
+hides the chain; there unrolling wins by removing loop overhead and
+load-use stalls instead (java -cp out computerdesign.Main unrolling).
+";
+
+
+struct Results {
+    elements: usize,
+    ghz: f64,
+    /// (kernel, ns per element)
+    rows: Vec<(&'static str, f64)>,
+}
+
+impl Report for Results {
+    fn text(&self) -> String {
+        let mut s = String::from("=== Instruction-Level Parallelism: Independent Accumulators ===\n\n");
+        let _ = writeln!(s, "{} f64 values ({} KB, fits in L1), best of 5 x {} passes, core clock ~{:.2} GHz\n",
+            self.elements, self.elements * 8 / 1024, PASSES, self.ghz);
+        let _ = writeln!(s, "   {:<28} {:>10} {:>12} {:>9}", "kernel", "ns/elem", "cycles/elem", "speedup");
+        let chain = self.rows[0].1;
+        for &(name, t) in &self.rows {
+            let _ = writeln!(s, "   {:<28} {:>10.3} {:>12.2} {:>8.2}x", name, t, t * self.ghz, chain / t);
+        }
+        s.push_str(lang::tr(NO, READING));
+        s
+    }
+
+    fn json(&self) -> Json {
+        let chain = self.rows[0].1;
+        Json::object()
+            .with("elements", self.elements)
+            .with("passes", PASSES)
+            .with("clock_ghz", self.ghz)
+            .with("kernels", Json::array(self.rows.iter().map(|&(name, t)| {
+                Json::object().with("kernel", name).with("ns_per_element", t)
+                    .with("cycles_per_element", t * self.ghz).with("speedup", chain / t)
+            })))
+    }
+}
+
+fn main() {
+    let (format, args) = Format::from_env("ilp_unroll [elements] [--format text|json] [--lang no|en]");
+    let n: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(2048);
+    let n = n.div_ceil(8) * 8;                   // every kernel handles the whole array
+    let data: Vec<f64> = (0..n).map(|i| (i % 100) as f64 * 0.25).collect(); // exact in binary
+    let expected: f64 = data.iter().sum();
+
+    type Kernel = fn(&[f64]) -> f64;
+    let kernels: [(&str, Kernel); 5] = [
+        ("1 accumulator (chain)", sum_chain),
+        ("unrolled 4x, 1 accumulator", sum_unrolled_chain),
+        ("2 accumulators", sum_2),
+        ("4 accumulators", sum_4),
+        ("8 accumulators", sum_8),
+    ];
+
+    let mut results = Results { elements: n, ghz: cycles::core_hz() / 1e9, rows: Vec::new() };
+    for &(name, f) in kernels.iter() {
+        let (t, sum) = time_per_element(&data, f);
+        assert!(sum == expected, "{} computed {} instead of {}", name, sum, expected);
+        results.rows.push((name, t));
+    }
+    format.print(&results);
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[(READING, "
+Slik leser du tabellen:
+  - Utrulling alene hjelper knapt: addisjonene venter fortsatt på hverandre.
+    Løkkeoverheaden var aldri flaskehalsen - avhengighetskjeden er det.
+  - Hver uavhengige akkumulator gir kjernen en ny addisjon å starte
+    mens de andre er underveis, til flyttallsenhetene er fullt utnyttet:
+    speedup flater ut nær latens x addisjoner per syklus (~4-8 på x86/ARM).
+  - Alle versjonene gir samme sum bare fordi disse verdiene summeres eksakt;
+    generelt endrer omgruppering avrundingen.
+
+I simulatoren med fem samlebåndstrinn tar en addisjon 1 syklus, og
+forwarding skjuler kjeden; der vinner utrulling ved å fjerne løkkeoverhead
+og load-use-stopp i stedet (java -cp out computerdesign.Main unrolling).
+")];
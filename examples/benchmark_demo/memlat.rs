@@ -0,0 +1,329 @@
+/*
+ * memlat - Memory latency and bandwidth across working-set sizes
+ * The memory hierarchy made visible: walk a buffer of growing size and
+ * watch the time per access jump each time the buffer outgrows a cache.
+ *
+ *   LATENCY: pointer chasing. Every cache line of the buffer holds the index
+ *   of the next line to visit, in a random cycle:
+ *
+ *       p = next[p]; p = next[p]; ...
+ *
+ *   Each load needs the previous one's result, so loads cannot overlap and
+ *   the random order defeats the prefetcher: the time per step IS the load
+ *   latency of whichever level the buffer fits in.
+ *
+ *   BANDWIDTH: a streaming sum over the same buffer. Independent loads in
+ *   address order - prefetchers and many outstanding misses keep the bus busy.
+ *
+ *   size       latency              the plateaus are the levels:
+ *   16 KiB     ~1 ns     L1         ~4-5 cycles
+ *   256 KiB    ~3-4 ns   L2         ~12-16 cycles
+ *   4 MiB      ~10-15 ns L3         ~40-60 cycles
+ *   256 MiB    ~80+ ns   DRAM       a few hundred cycles (plus TLB misses)
+ *
+ * CSV goes to stdout (plot it, or put it next to the cache simulator's AMAT
+ * for the same sizes); the cache sizes the OS reports and the detected
+ * jumps go to stderr.
+ *
+ * Built with --features perf on Linux, each chase is also run under the hardware
+ * counters (util::perf) and the L1d misses per load are printed next to those
+ * of a simulated LRU set-associative L1d with the geometry the OS reports -
+ * the same organization as the compendium's computerdesign.memory.Cache.
+ * The model switches from 0 to 1 miss per load exactly at the L1 size; the
+ * real cache, with its pseudo-LRU and prefetchers, blurs the step.
+ * The notes on stderr are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin memlat -- [max-MiB] [--lang no] > memlat.csv
+ *      cargo run --release --bin memlat -- [max-MiB] --format json > memlat.json
+ *      cargo run --release --features perf --bin memlat   (hardware counters)
+ */
+
+use std::fs;
+use std::hint::black_box;
+
+use util::lang::{self, fill, Catalog};
+use util::report::{Format, Json, Report};
+use util::rng::Rng;
+use util::{cycles, perf};
+
+const LINE: usize = 64;
+const WORDS_PER_LINE: usize = LINE / 8;
+
+// ==================== LATENCY ====================
+
+/// One u64 slot per cache line links the lines into a single random cycle
+/// (Sattolo's shuffle: every line is visited before the walk repeats).
+fn build_chain(bytes: usize, rng: &mut Rng) -> Vec<usize> {
+    let lines = bytes / LINE;
+    let mut order: Vec<usize> = (0..lines).collect();
+    for i in (1..lines).rev() {
+        let j = rng.below(i);
+        order.swap(i, j);
+    }
+    let mut next = vec![0usize; lines * WORDS_PER_LINE];
+    for i in 0..lines {
+        next[order[i] * WORDS_PER_LINE] = order[(i + 1) % lines] * WORDS_PER_LINE;
+    }
+    next
+}
+
+#[inline(never)]
+fn chase(next: &[usize], steps: usize) -> usize {
+    let mut p = 0;
+    for _ in 0..steps {
+        p = next[p];
+    }
+    p
+}
+
+/// Nanoseconds per dependent load, best of three runs of roughly 50 ms.
+fn latency_ns(next: &[usize]) -> f64 {
+    let lines = next.len() / WORDS_PER_LINE;
+    chase(next, lines);                                   // warm caches and TLB
+    let mut steps = 1 << 16;
+    loop {
+        let start = cycles::Stopwatch::start();
+        black_box(chase(next, steps));
+        if start.elapsed_ns() >= 50e6 {
+            break;
+        }
+        steps *= 2;
+    }
+    let mut best = f64::MAX;
+    for _ in 0..3 {
+        let start = cycles::Stopwatch::start();
+        black_box(chase(next, steps));
+        best = best.min(start.elapsed_ns() / steps as f64);
+    }
+    best
+}
+
+// ==================== SIMULATED L1d ====================
+
+/// (sets, ways) of the L1 data cache from sysfs, or a typical 32 KiB 8-way.
+fn l1d_geometry() -> (usize, usize) {
+    for i in 0..8 {
+        let dir = format!("/sys/devices/system/cpu/cpu0/cache/index{}", i);
+        let read = |f: &str| fs::read_to_string(format!("{}/{}", dir, f)).map(|s| s.trim().to_string());
+        if let (Ok(level), Ok(kind)) = (read("level"), read("type")) {
+            if level == "1" && kind == "Data" {
+                let sets = read("number_of_sets").ok().and_then(|s| s.parse().ok());
+                let ways = read("ways_of_associativity").ok().and_then(|s| s.parse().ok());
+                if let (Some(sets), Some(ways)) = (sets, ways) {
+                    return (sets, ways);
+                }
+            }
+        }
+    }
+    (64, 8)
+}
+
+/// Misses per load of the chase through an LRU set-associative cache,
+/// counted over the second lap so that cold misses are left out.
+fn simulated_miss_rate(next: &[usize], sets: usize, ways: usize) -> f64 {
+    let lines = next.len() / WORDS_PER_LINE;
+    let mut cache: Vec<Vec<usize>> = vec![Vec::with_capacity(ways); sets]; // most recent first
+    let mut misses = 0;
+    let mut p = 0;
+    for step in 0..2 * lines {
+        let line = p / WORDS_PER_LINE;
+        let set = &mut cache[line % sets];
+        match set.iter().position(|&l| l == line) {
+            Some(i) => {
+                set.remove(i);
+            }
+            None => {
+                if step >= lines {
+                    misses += 1;
+                }
+                if set.len() == ways {
+                    set.pop();
+                }
+            }
+        }
+        set.insert(0, line);
+        p = next[p];
+    }
+    misses as f64 / lines as f64
+}
+
+// ==================== BANDWIDTH ====================
+
+#[inline(never)]
+fn stream(data: &[u64]) -> u64 {
+    let (mut s0, mut s1, mut s2, mut s3) = (0u64, 0u64, 0u64, 0u64);
+    for c in data.chunks_exact(4) {
+        s0 = s0.wrapping_add(c[0]);
+        s1 = s1.wrapping_add(c[1]);
+        s2 = s2.wrapping_add(c[2]);
+        s3 = s3.wrapping_add(c[3]);
+    }
+    s0 ^ s1 ^ s2 ^ s3
+}
+
+/// Read bandwidth in GB/s (10^9 bytes), best of three ~50 ms runs.
+fn bandwidth_gbs(data: &[u64]) -> f64 {
+    let bytes = (data.len() * 8) as f64;
+    let mut passes = 1;
+    loop {
+        let start = cycles::Stopwatch::start();
+        for _ in 0..passes {
+            black_box(stream(black_box(data)));
+        }
+        if start.elapsed_ns() >= 50e6 {
+            break;
+        }
+        passes *= 2;
+    }
+    let mut best = f64::MAX;
+    for _ in 0..3 {
+        let start = cycles::Stopwatch::start();
+        for _ in 0..passes {
+            black_box(stream(black_box(data)));
+        }
+        best = best.min(start.elapsed_ns() / passes as f64);
+    }
+    bytes / best
+}
+
+// ==================== REPORTING ====================
+
+/// Data and unified cache sizes from sysfs (Linux), e.g. "L1d 48K".
+fn os_cache_sizes() -> Vec<String> {
+    let mut out = Vec::new();
+    for i in 0..8 {
+        let dir = format!("/sys/devices/system/cpu/cpu0/cache/index{}", i);
+        let read = |f: &str| fs::read_to_string(format!("{}/{}", dir, f)).map(|s| s.trim().to_string());
+        if let (Ok(level), Ok(kind), Ok(size)) = (read("level"), read("type"), read("size")) {
+            if kind != "Instruction" {
+                out.push(format!("L{}{} {}", level, if kind == "Data" { "d" } else { "" }, size));
+            }
+        }
+    }
+    out
+}
+
+struct Row {
+    bytes: usize,
+    latency_ns: f64,
+    read_gbps: f64,
+}
+
+struct Results {
+    ghz: f64,
+    caches: Vec<String>,
+    rows: Vec<Row>,
+}
+
+impl Row {
+    fn csv(&self, ghz: f64) -> String {
+        format!("{},{:.2},{:.1},{:.2}", self.bytes, self.latency_ns, self.latency_ns * ghz, self.read_gbps)
+    }
+}
+
+const CSV_HEADER: &str = "size_bytes,latency_ns,latency_cycles,read_gbps";
+
+impl Report for Results {
+    fn text(&self) -> String {
+        let rows: Vec<String> = self.rows.iter().map(|r| r.csv(self.ghz) + "\n").collect();
+        format!("{}\n{}", CSV_HEADER, rows.concat())
+    }
+
+    fn json(&self) -> Json {
+        Json::object()
+            .with("clock_ghz", self.ghz)
+            .with("caches", Json::array(self.caches.iter().map(String::as_str)))
+            .with("rows", Json::array(self.rows.iter().map(|r| {
+                Json::object().with("size_bytes", r.bytes).with("latency_ns", r.latency_ns)
+                    .with("latency_cycles", r.latency_ns * self.ghz).with("read_gbps", r.read_gbps)
+            })))
+    }
+}
+
+fn human(bytes: usize) -> String {
+    if bytes >= 1 << 20 {
+        format!("{} MiB", bytes >> 20)
+    } else {
+        format!("{} KiB", bytes >> 10)
+    }
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let (format, args) = Format::from_env("memlat [max-MiB] [--format text|json] [--lang no|en]");
+    let max_mib: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(256);
+    let mut sizes = Vec::new();
+    let mut size = 4 << 10;
+    while size <= max_mib << 20 {
+        sizes.push(size);
+        sizes.push(size + size / 2);                       // 4K, 6K, 8K, 12K, ...
+        size *= 2;
+    }
+    sizes.retain(|&s| s <= max_mib << 20);
+
+    let ghz = cycles::core_hz() / 1e9;
+    eprintln!("# {}", fill(tr("core clock ~{} GHz (util::cycles), used for latency_cycles"), &[&format!("{:.2}", ghz)]));
+    let caches = os_cache_sizes();
+    if !caches.is_empty() {
+        eprintln!("# {} {}", tr("caches reported by the OS:"), caches.join(", "));
+    }
+
+    let mut counters = perf::Counters::open();
+    let (l1_sets, l1_ways) = l1d_geometry();
+    match counters {
+        Some(_) => eprintln!("# {}", fill(tr("counters on; simulated L1d: {} sets x {} ways x {} B"), &[&l1_sets, &l1_ways, &LINE])),
+        None => eprintln!("# {} {}", tr("hardware counters not available:"), perf::unavailable()),
+    }
+
+    // text streams the CSV as each size is measured; JSON comes at the end
+    if format == Format::Text {
+        println!("{}", CSV_HEADER);
+    }
+    let mut results = Results { ghz, caches, rows: Vec::new() };
+    let mut rng = Rng::new(4160);
+    let mut previous: Option<f64> = None;
+    for &bytes in sizes.iter() {
+        let next = build_chain(bytes, &mut rng);
+        let lat = latency_ns(&next);
+        if let Some(ref mut c) = counters {
+            let steps = 4 * (bytes / LINE);
+            let (_, counts) = c.measure(|| black_box(chase(&next, steps)));
+            let per_load = |v: Option<u64>| v.map_or("-".to_string(), |v| format!("{:.2}", v as f64 / steps as f64));
+            eprintln!("# {:>8}: {}", human(bytes), fill(tr("L1d misses/load {} (LRU model {}), LLC misses/load {}"),
+                &[&per_load(counts.l1d_read_misses), &format!("{:.2}", simulated_miss_rate(&next, l1_sets, l1_ways)),
+                  &per_load(counts.cache_misses)]));
+        }
+        drop(next);
+        let data = vec![1u64; bytes / 8];
+        let bw = bandwidth_gbs(&data);
+        let row = Row { bytes, latency_ns: lat, read_gbps: bw };
+        if format == Format::Text {
+            println!("{}", row.csv(ghz));
+        }
+        results.rows.push(row);
+        if let Some(p) = previous {
+            if lat > 1.4 * p {
+                eprintln!("# {}", fill(tr("latency jump at {}: {} → {} ns (outgrew a level)"),
+                    &[&format!("{:>8}", human(bytes)), &format!("{:.1}", p), &format!("{:.1}", lat)]));
+            }
+        }
+        previous = Some(lat);
+    }
+    if format == Format::Json {
+        format.print(&results);
+    }
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("core clock ~{} GHz (util::cycles), used for latency_cycles", "kjerneklokke ~{} GHz (util::cycles), brukt til latency_cycles"),
+    ("caches reported by the OS:", "cacher rapportert av OS-et:"),
+    ("counters on; simulated L1d: {} sets x {} ways x {} B", "tellere på; simulert L1d: {} sett x {} veier x {} B"),
+    ("hardware counters not available:", "maskinvaretellere ikke tilgjengelig:"),
+    ("L1d misses/load {} (LRU model {}), LLC misses/load {}", "L1d-bom/lasting {} (LRU-modell {}), LLC-bom/lasting {}"),
+    ("latency jump at {}: {} → {} ns (outgrew a level)", "latenshopp ved {}: {} → {} ns (vokste ut av et nivå)"),
+];
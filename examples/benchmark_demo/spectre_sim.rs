@@ -0,0 +1,271 @@
+/*
+ * Spectre, Inside the Simulator
+ * The bounds-check bypass (Spectre variant 1) played out on the RV32
+ * emulator with a branch predictor and a data cache (riscv::speculate),
+ * so every step can be printed. Nothing here runs on, or reads from, the
+ * real machine: the "secret" is a string in the simulated memory.
+ *
+ *   victim(x):  if x < array1_size { y = probe[array1[x] * 64] }
+ *
+ *   1. train      call it with x in bounds: the branch is learnt not taken
+ *   2. flush      evict the 256 probe lines, one per possible byte value
+ *   3. attack     x = secret - array1: out of bounds, really taken, but the
+ *                 predictor says not taken and the core runs ahead, loading
+ *                 array1[x] (the secret byte) and then its probe line
+ *   4. measure    time a load of every probe line: one of them hits
+ *
+ *   probe line   0     1    ...   'T'   ...   255
+ *   cycles       100   100  ...    1    ...   100     -> the byte is 'T'
+ *
+ * Architecturally step 3 did nothing: the branch was taken, the loads
+ * were squashed, the registers are as before. Only the cache changed. The
+ * wrong path is 6 instructions long, so a speculation window shorter than
+ * that leaks nothing, and neither does a speculation barrier right after
+ * the bounds check (fence here, lfence on x86) - the sweep at the end.
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin spectre_sim -- [secret text] [--format json] [--lang no]
+ */
+
+use std::fmt::Write;
+
+use cache::{Cache, Config, Replacement, WritePolicy};
+use riscv::cpu::Memory;
+use riscv::instruction::{AluOp, BranchOp, Width};
+use riscv::predictor::TwoBit;
+use riscv::speculate::{Speculative, Squash};
+use riscv::{Cpu, Instruction, Trap};
+use util::lang::{self, fill, Catalog};
+use util::report::{Format, Json, Report};
+
+const CODE: u32 = 0x1000;
+const ARRAY1_SIZE: u32 = 0x2000;
+const ARRAY1: u32 = 0x2040;
+const SECRET: u32 = 0x2100;
+const PROBE: u32 = 0x4000;
+const LINE: u32 = 64;
+
+const A0: u8 = 10;
+const T0: u8 = 5;
+const T1: u8 = 6;
+const T2: u8 = 7;
+const T3: u8 = 28;
+
+// ==================== THE SIMULATED MACHINE ====================
+
+/// victim(a0), ending in ebreak; `barrier` puts a fence after the check.
+fn victim(barrier: bool) -> Vec<Instruction> {
+    let mut code = vec![
+        Instruction::Lui { rd: T0, imm: ARRAY1_SIZE as i32 },
+        Instruction::Load { width: Width::Word, rd: T1, rs1: T0, offset: 0 },
+        Instruction::Branch { op: BranchOp::Bgeu, rs1: A0, rs2: T1, offset: if barrier { 32 } else { 28 } },
+        Instruction::Op { op: AluOp::Add, rd: T2, rs1: T0, rs2: A0 },
+        Instruction::Load { width: Width::ByteU, rd: T2, rs1: T2, offset: (ARRAY1 - ARRAY1_SIZE) as i32 },
+        Instruction::OpImm { op: AluOp::Sll, rd: T2, rs1: T2, imm: LINE.trailing_zeros() as i32 },
+        Instruction::Lui { rd: T3, imm: PROBE as i32 },
+        Instruction::Op { op: AluOp::Add, rd: T2, rs1: T2, rs2: T3 },
+        Instruction::Load { width: Width::ByteU, rd: T2, rs1: T2, offset: 0 },
+        Instruction::Ebreak,
+    ];
+    if barrier {
+        code.insert(3, Instruction::Fence);
+    }
+    code
+}
+
+fn machine(secret: &[u8], window: usize, barrier: bool) -> Speculative {
+    let mut mem = Memory::new(CODE, (PROBE + 256 * LINE - CODE) as usize);
+    let words: Vec<u32> = victim(barrier).iter().map(Instruction::encode).collect();
+    mem.load_words(CODE, &words);
+    mem.write(ARRAY1_SIZE, 4, 16);
+    mem.load(ARRAY1, &(1..=16).collect::<Vec<u8>>());
+    mem.load(SECRET, secret);
+    // 32 KiB, 8-way: the probe array fits, so only the flush evicts it
+    let config = Config { size: 32 << 10, line: LINE, ways: 8, replacement: Replacement::Lru, write: WritePolicy::WriteBackAllocate };
+    let mut m = Speculative::new(Cpu::new(mem, CODE), Box::new(TwoBit::new(4)), Cache::new(config).expect("a valid cache"));
+    m.window = window;
+    m
+}
+
+fn call(m: &mut Speculative, x: u32) {
+    m.cpu.pc = CODE;
+    m.cpu.set_reg(A0, x);
+    m.cpu.set_reg(T2, 0);
+    match m.run(100) {
+        Some(Trap::Breakpoint(_)) => {}
+        other => panic!("victim({}) stopped with {:?}", x, other),
+    }
+}
+
+/// Train, flush, attack, measure: the probe lines that hit, and the
+/// cycles of each timed load.
+fn leak(m: &mut Speculative, i: u32) -> (Vec<u8>, Vec<u32>) {
+    for x in 0..6 {
+        call(m, x);
+    }
+    for v in 0..256 {
+        m.cache.invalidate(PROBE + v * LINE);
+    }
+    call(m, SECRET - ARRAY1 + i);
+    let times: Vec<u32> = (0..256).map(|v| m.time(PROBE + v * LINE)).collect();
+    let hits = (0..=255u8).filter(|&v| times[v as usize] == m.hit_time).collect();
+    (hits, times)
+}
+
+fn shown(b: u8) -> String {
+    if b.is_ascii_graphic() || b == b' ' { format!("'{}'", b as char) } else { format!("0x{:02x}", b) }
+}
+
+/// The secret as read from the side channel, '?' where nothing hit.
+fn recover(secret: &[u8], window: usize, barrier: bool) -> String {
+    let mut m = machine(secret, window, barrier);
+    (0..secret.len() as u32)
+        .map(|i| match leak(&mut m, i).0[..] {
+            [b] if b.is_ascii() => b as char,
+            _ => '?',
+        })
+        .collect()
+}
+
+// ==================== REPORTING ====================
+
+const WINDOWS: [usize; 7] = [0, 2, 4, 5, 6, 8, 16];
+
+struct Results {
+    /// the attack on the first byte, with a window of 8
+    window: usize,
+    squash: Option<Squash>,
+    t2: u32,
+    times: Vec<u32>,
+    hits: Vec<u8>,
+    hit_time: u32,
+    miss_time: u32,
+    leaked: String,
+    /// what each window of WINDOWS recovers, then 16 with the fence
+    sweep: Vec<String>,
+    fenced: String,
+}
+
+const BARRIER: &str = "
+The wrong path needs 6 instructions to reach the probe load; real cores run
+ahead by a hundred instructions or more, and only a barrier (or masking x)
+closes the leak.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+impl Report for Results {
+    fn text(&self) -> String {
+        let mut s = String::from("=== Spectre, Inside the Simulator ===\n\n");
+        let _ = writeln!(s, "victim(a0) at 0x{:04x}; array1 (16 bytes) at 0x{:04x}, the secret at 0x{:04x}, probe[256 * {}] at 0x{:04x}:\n",
+            CODE, ARRAY1, SECRET, LINE, PROBE);
+        for (i, inst) in victim(false).iter().enumerate() {
+            let note = match i {
+                2 => "   if x >= array1_size: return",
+                4 => "   array1[x]",
+                8 => "   probe[array1[x] * 64]",
+                _ => "",
+            };
+            let line = format!("  {:04x}  {:<22}{}", CODE + 4 * i as u32, inst.to_string(), note);
+            let _ = writeln!(s, "{}", line.trim_end());
+        }
+
+        let _ = writeln!(s, "\n1) Trained with x = 0..5, probe flushed, then victim(0x{:x}), window {}:", SECRET - ARRAY1, self.window);
+        if let Some(squash) = &self.squash {
+            let _ = writeln!(s, "   {}", fill(tr("bgeu at {} mispredicted; run down the wrong path and squashed:"), &[&format!("0x{:04x}", squash.branch)]));
+            for (pc, inst) in &squash.path {
+                let _ = writeln!(s, "     {:04x}  {}", pc, inst);
+            }
+            if let Some(why) = squash.stopped {
+                let _ = writeln!(s, "     {}", fill(tr("(stopped at {})"), &[&why]));
+            }
+            let loads: Vec<String> = squash.loads.iter().map(|a| format!("0x{:04x}", a)).collect();
+            let _ = writeln!(s, "   {} {}", tr("loads left in the cache:"), loads.join(", "));
+        }
+        let _ = writeln!(s, "   {}", fill(tr("afterwards t2 = {}: architecturally the loads never happened"), &[&self.t2]));
+
+        s.push_str("\n2) Timed loads of the 256 probe lines:\n");
+        let slow = self.times.iter().filter(|&&t| t == self.miss_time).count();
+        let _ = writeln!(s, "   {}", fill(tr("{} lines at {} cycles (misses), {} at {}:"), &[&slow, &self.miss_time, &self.hits.len(), &self.hit_time]));
+        for &b in &self.hits {
+            let _ = writeln!(s, "     line {:>3} = {}   {}", b, shown(b), tr("the first byte of the secret"));
+        }
+
+        let _ = writeln!(s, "\n3) Byte by byte: \"{}\"", self.leaked);
+
+        s.push_str("\n4) What stops it:\n");
+        let _ = writeln!(s, "   {:<26} recovered", "window (instructions)");
+        for (window, recovered) in WINDOWS.iter().zip(&self.sweep) {
+            let _ = writeln!(s, "   {:<26} \"{}\"", window, recovered);
+        }
+        let _ = writeln!(s, "   {:<26} \"{}\"", "16, fence after the check", self.fenced);
+        s.push_str(tr(BARRIER));
+        s
+    }
+
+    fn json(&self) -> Json {
+        let squash = self.squash.as_ref().map(|q| {
+            Json::object()
+                .with("branch", q.branch)
+                .with("wrong_path", Json::array(q.path.iter().map(|(pc, inst)| {
+                    Json::object().with("pc", *pc).with("instruction", inst.to_string())
+                })))
+                .with("stopped", q.stopped)
+                .with("loads", q.loads.clone())
+        });
+        Json::object()
+            .with("window", self.window)
+            .with("squashed", squash)
+            .with("t2", self.t2)
+            .with("hit_cycles", self.hit_time)
+            .with("miss_cycles", self.miss_time)
+            .with("probe_cycles", self.times.clone())
+            .with("hits", self.hits.clone())
+            .with("leaked", self.leaked.as_str())
+            .with("windows", Json::array(WINDOWS.iter().zip(&self.sweep).map(|(window, recovered)| {
+                Json::object().with("window", *window).with("recovered", recovered.as_str())
+            })))
+            .with("fence_recovered", self.fenced.as_str())
+    }
+}
+
+// ==================== MAIN ====================
+
+fn main() {
+    let (format, args) = Format::from_env("spectre_sim [secret text] [--format text|json] [--lang no|en]");
+    let secret = args.first().cloned().unwrap_or_else(|| "TDT4160 in the cache".to_string());
+    let bytes = secret.as_bytes();
+
+    let mut m = machine(bytes, 8, false);
+    let (hits, times) = leak(&mut m, 0);
+    format.print(&Results {
+        window: m.window,
+        squash: m.squashed.pop(),
+        t2: m.cpu.reg(T2),
+        times,
+        hits,
+        hit_time: m.hit_time,
+        miss_time: m.miss_time,
+        leaked: recover(bytes, 8, false),
+        sweep: WINDOWS.iter().map(|&window| recover(bytes, window, false)).collect(),
+        fenced: recover(bytes, 16, true),
+    });
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("bgeu at {} mispredicted; run down the wrong path and squashed:", "bgeu på {} feilpredikert; kjørt et stykke ned feil vei og forkastet:"),
+    ("(stopped at {})", "(stoppet ved {})"),
+    ("loads left in the cache:", "lesinger som ble igjen i cachen:"),
+    ("afterwards t2 = {}: architecturally the loads never happened", "etterpå er t2 = {}: arkitektonisk skjedde lesingene aldri"),
+    ("{} lines at {} cycles (misses), {} at {}:", "{} linjer på {} sykluser (bom), {} på {}:"),
+    ("the first byte of the secret", "den første byten av hemmeligheten"),
+    (BARRIER, "
+Feil vei trenger 6 instruksjoner for å nå probe-lesingen; ekte kjerner kjører
+hundre instruksjoner eller mer i forveien, og bare en barriere (eller å maskere x)
+tetter lekkasjen.
+"),
+];
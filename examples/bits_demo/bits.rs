@@ -0,0 +1,117 @@
+/*
+ * Bit Manipulation Tricks
+ * What count_ones, leading_zeros and trailing_zeros compute, the classic
+ * x & (x - 1) family of tricks, and helpers for the ones std lacks - each
+ * shown on its bit patterns.
+ *
+ *   x           = 0101_1000
+ *   x - 1       = 0101_0111     borrowing flips the lowest 1 and the 0s below it
+ *   x & (x - 1) = 0101_0000     lowest set bit cleared
+ *   x & -x      = 0000_1000     lowest set bit isolated
+ *
+ * Most of these are single instructions on modern CPUs (POPCNT, LZCNT,
+ * TZCNT on x86; cpop, clz, ctz in the RISC-V Zbb extension).
+ *
+ * The helpers themselves are arithmetic::bits; this program shows them.
+ * The notes are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --bin bits -- [number] [--lang no]
+ */
+
+use arithmetic::bits;
+use util::lang::{self, Catalog};
+
+fn b8(x: u32) -> String {
+    format!("{:04b}_{:04b}", (x >> 4) & 0xF, x & 0xF)
+}
+
+fn b32(x: u32) -> String {
+    let s = format!("{:032b}", x);
+    (0..8).map(|i| &s[i * 4..i * 4 + 4]).collect::<Vec<_>>().join("_")
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("bits [number] [--lang no|en]");
+    let x: u32 = args.first().and_then(|s| s.parse().ok()).unwrap_or(0b0101_1000);
+    println!("=== Bit Manipulation Tricks ===\n");
+    println!("x = {} = {}\n", x, b32(x));
+
+    println!("--- Counting (std, usually one instruction) ---");
+    println!("  x.count_ones()     = {:>2}   {}", x.count_ones(), tr("set bits (popcount)"));
+    println!("  popcount_swar(x)   = {:>2}   {}",
+        bits::popcount_swar(x), tr("the same without the instruction: add pairs, nibbles, bytes"));
+    println!("  x.leading_zeros()  = {:>2}   {}", x.leading_zeros(), tr("zeros above the highest 1 (clz)"));
+    println!("  x.trailing_zeros() = {:>2}   {}", x.trailing_zeros(), tr("zeros below the lowest 1 (ctz)"));
+    println!("  log2(x)            = {:?}   {}\n", bits::log2(x), tr("= 31 - leading_zeros: index of the highest 1"));
+
+    println!("--- The lowest set bit ---");
+    println!("  x           = {}", b8(x));
+    println!("  x - 1       = {}   {}", b8(x.wrapping_sub(1)), tr("borrow flips the lowest 1 and the 0s below it"));
+    println!("  x & (x - 1) = {}   {}", b8(bits::clear_lowest_set_bit(x)), tr("lowest 1 cleared"));
+    println!("  -x          = {}   {}", b8(x.wrapping_neg()), tr("(low 8 bits) = !x + 1"));
+    println!("  x & -x      = {}   {}", b8(bits::lowest_set_bit(x)), tr("lowest 1 isolated"));
+    println!("  {} {}  {}\n", tr("power of two?"), bits::is_power_of_two(x), tr("(x & (x - 1) == 0 with x != 0)"));
+
+    println!("--- Powers of two ---");
+    for v in [0u32, 1, 5, 64, 100, 1000, 0x8000_0001] {
+        println!("  {:>10}: round up {:>12}   round down {:>10}", v,
+            bits::round_up_pow2(v).map_or("overflow".to_string(), |p| p.to_string()), bits::round_down_pow2(v));
+    }
+    println!("  {}", tr("Round up smears the highest 1 into every bit below it, then adds 1:"));
+    println!("    100 - 1 = {}  →  smeared {}  →  +1 = {}\n",
+        b8(99), b8(127), bits::round_up_pow2(100).unwrap());
+
+    println!("--- Reversal and masks ---");
+    println!("  x                 = {}", b32(x));
+    println!("  reverse(x)        = {}   {} {}",
+        b32(bits::reverse(x)), tr("swap halves, quarters, ... = x.reverse_bits():"), bits::reverse(x) == x.reverse_bits());
+    println!("  x.swap_bytes()    = {}   {}", b32(x.swap_bytes()), tr("(endianness conversion)"));
+    println!("  mask(5, 7)        = {}   {}", b32(bits::mask(5, 7)), tr("the rd field of an instruction"));
+    let add = 0x00B5_0533u32; // add a0, a0, a1
+    println!("  extract(0x{:08x}, 11, 7) = {}   {}\n", add, bits::extract(add, 11, 7), tr("rd of add a0, a0, a1 (a0 = x10)"));
+
+    println!("--- Gosper's hack: all 3-element subsets of {{0..4}} ---");
+    let mut subset = 0b111u32;
+    let mut line = Vec::new();
+    while subset < 1 << 5 {
+        line.push(format!("{:05b}", subset));
+        subset = bits::next_same_popcount(subset).unwrap();
+    }
+    println!("  {}  ({} = C(5,3))", line.join(" "), line.len());
+
+    // self-check against std
+    for v in (0..=u32::MAX).step_by(65_537).chain([0, 1, u32::MAX, 1 << 31]) {
+        assert_eq!(bits::popcount_swar(v), v.count_ones());
+        assert_eq!(bits::reverse(v), v.reverse_bits());
+        assert_eq!(bits::round_up_pow2(v), v.checked_next_power_of_two());
+        assert_eq!(bits::is_power_of_two(v), v.is_power_of_two());
+    }
+    println!("\n{}", tr("Helpers checked against std on 65 000 values: ok"));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("set bits (popcount)", "satte bit (popcount)"),
+    ("the same without the instruction: add pairs, nibbles, bytes", "det samme uten instruksjonen: legg sammen par, nibbler, byte"),
+    ("zeros above the highest 1 (clz)", "nuller over den høyeste 1-eren (clz)"),
+    ("zeros below the lowest 1 (ctz)", "nuller under den laveste 1-eren (ctz)"),
+    ("= 31 - leading_zeros: index of the highest 1", "= 31 - leading_zeros: indeksen til den høyeste 1-eren"),
+    ("borrow flips the lowest 1 and the 0s below it", "lånet snur den laveste 1-eren og 0-ene under den"),
+    ("lowest 1 cleared", "laveste 1-er nullstilt"),
+    ("(low 8 bits) = !x + 1", "(de 8 laveste bitene) = !x + 1"),
+    ("lowest 1 isolated", "laveste 1-er isolert"),
+    ("power of two?", "potens av to?"),
+    ("(x & (x - 1) == 0 with x != 0)", "(x & (x - 1) == 0 og x != 0)"),
+    ("Round up smears the highest 1 into every bit below it, then adds 1:",
+        "Runding opp smører den høyeste 1-eren ut i hver bit under den, og legger så til 1:"),
+    ("swap halves, quarters, ... = x.reverse_bits():", "bytt halvdeler, kvarte, ... = x.reverse_bits():"),
+    ("(endianness conversion)", "(endianness-konvertering)"),
+    ("the rd field of an instruction", "rd-feltet i en instruksjon"),
+    ("rd of add a0, a0, a1 (a0 = x10)", "rd i add a0, a0, a1 (a0 = x10)"),
+    ("Helpers checked against std on 65 000 values: ok", "Hjelpefunksjonene sjekket mot std på 65 000 verdier: ok"),
+];
@@ -0,0 +1,204 @@
+/*
+ * Viewing a Float's Bits: Safe vs Unsafe Reinterpretation
+ * Five ways to get the bit pattern of an f32, from the std method to a raw
+ * pointer cast, and a float inspector (arithmetic::float) built on the one
+ * that needs no unsafe.
+ *
+ *   f32::to_bits            safe       the std answer; this is what to use
+ *   to_ne_bytes/from_ne     safe       through the byte representation
+ *   Pod cast (bytemuck)     safe API   unsafe once, behind a marker trait
+ *   union { f32, u32 }      unsafe     sound: every u32 pattern is valid
+ *   mem::transmute          unsafe     sound: sizes checked at compile time
+ *
+ * "Compiles and prints the right answer" is not the same as sound. The
+ * tests at the bottom run every approach under Miri, the interpreter that
+ * checks each memory access against Rust's rules. The unsound variants
+ * (invalid bool, misaligned read, reading past the end) mostly go
+ * unnoticed when run natively - only debug builds check pointer alignment -
+ * and are only built with --cfg ub so Miri has something to reject.
+ * The explanation is printed in Norwegian with --lang no (util::lang).
+ *
+ * Run:   cargo run --release --bin reinterpret -- [float] [--lang no]
+ * Tests: cargo test --bin reinterpret
+ * Miri (nightly, `rustup +nightly component add miri`):
+ *        cargo +nightly miri test --bin reinterpret                        # all pass
+ *        RUSTFLAGS="--cfg ub" cargo +nightly miri test --bin reinterpret   # Miri reports UB
+ */
+
+use arithmetic::float::Inspect;
+use std::mem;
+use util::lang::{self, Catalog};
+
+// ==================== FIVE WAYS TO GET THE BITS ====================
+
+/// The std method: a plain bit copy, no unsafe anywhere.
+fn via_to_bits(x: f32) -> u32 {
+    x.to_bits()
+}
+
+/// Through the bytes: same result, spells out that it is a byte copy.
+fn via_bytes(x: f32) -> u32 {
+    u32::from_ne_bytes(x.to_ne_bytes())
+}
+
+/// Types where every bit pattern is a valid value and there is no padding.
+/// Implementing it is the one unsafe promise; casting between them is then
+/// safe. This is the idea of the bytemuck crate, cut down to size checks.
+///
+/// # Safety
+///
+/// The implementing type must have no padding bytes and no invalid bit
+/// patterns.
+unsafe trait Pod: Copy {}
+unsafe impl Pod for u32 {}
+unsafe impl Pod for i32 {}
+unsafe impl Pod for f32 {}
+unsafe impl Pod for [u8; 4] {}
+
+fn cast<A: Pod, B: Pod>(a: A) -> B {
+    assert_eq!(mem::size_of::<A>(), mem::size_of::<B>(), "cast between types of different size");
+    // SAFETY: A and B are Pod (no padding, all bit patterns valid) and the
+    // same size; read_unaligned imposes no alignment requirement on B.
+    unsafe { std::ptr::read_unaligned(&a as *const A as *const B) }
+}
+
+fn via_pod(x: f32) -> u32 {
+    cast(x)
+}
+
+#[repr(C)]
+union FloatBits {
+    f: f32,
+    u: u32,
+}
+
+/// Write one field, read the other. Sound because both fields are 4 bytes
+/// and every 4-byte pattern is a valid u32; reading a union field is still
+/// unsafe because the compiler cannot know that in general.
+fn via_union(x: f32) -> u32 {
+    let v = FloatBits { f: x };
+    // SAFETY: u32 has no invalid bit patterns and the same size as f32.
+    unsafe { v.u }
+}
+
+/// transmute checks the sizes at compile time, nothing else. Right here,
+/// wrong for references, bools, enums, chars and anything with padding.
+/// rustc itself warns that to_bits does the same job here.
+#[allow(unnecessary_transmutes)]
+fn via_transmute(x: f32) -> u32 {
+    // SAFETY: f32 and u32 are the same size and u32 accepts any pattern.
+    unsafe { mem::transmute::<f32, u32>(x) }
+}
+
+// ==================== MAIN ====================
+
+const UNSOUND: &str = "
+Unsound look-alikes (see --cfg ub tests): transmuting 2u8 to bool, reading a
+u32 through a misaligned pointer into a byte buffer, reading an f64 through a
+pointer to an f32. Natively they often \"work\"; under Miri each is a hard error.
+";
+
+fn main() {
+    let args = lang::args("reinterpret [float] [--lang no|en]");
+    let x: f32 = args.first().and_then(|s| s.parse().ok()).unwrap_or(-6.25);
+    println!("=== Viewing the Bits of {} ===\n", x);
+
+    type View = fn(f32) -> u32;
+    let ways: [(&str, &str, View); 5] = [
+        ("f32::to_bits", "safe", via_to_bits),
+        ("to_ne_bytes + from_ne_bytes", "safe", via_bytes),
+        ("Pod cast", "safe API", via_pod),
+        ("union { f32, u32 }", "unsafe, sound", via_union),
+        ("mem::transmute", "unsafe, sound", via_transmute),
+    ];
+    for (name, kind, f) in ways.iter() {
+        println!("  {:<28} {:<14} 0x{:08X}", name, kind, f(x));
+    }
+    let bytes: [u8; 4] = cast(x);
+    println!("  {:<28} {:<14} {:02X?} (native order)\n", "Pod cast to [u8; 4]", "safe API", bytes);
+
+    println!("--- Inspector (built on to_bits) ---");
+    println!("{}\n", Inspect::new(x).report());
+
+    for v in [0.1f32, -0.0, f32::MIN_POSITIVE / 4.0, f32::INFINITY, f32::NAN] {
+        let i = Inspect::new(v);
+        println!("  {:>12e}  0x{:08X}  {}", v, i.bits, i.class());
+    }
+
+    print!("{}", lang::tr(NO, UNSOUND));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[(UNSOUND, "
+Usunne etterligninger (se --cfg ub-testene): transmute av 2u8 til bool, lesing
+av en u32 gjennom en feiljustert peker inn i en bytebuffer, lesing av en f64
+gjennom en peker til en f32. Kjørt direkte \"virker\" de ofte; under Miri er
+hver av dem en hard feil.
+")];
+
+// ==================== TESTS (run these under Miri) ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: [f32; 7] = [0.0, -0.0, 1.0, -6.25, 1.0e-40, f32::INFINITY, f32::MAX];
+
+    #[test]
+    fn all_sound_ways_agree() {
+        for &x in SAMPLES.iter().chain([f32::NAN].iter()) {
+            let expected = via_to_bits(x);
+            assert_eq!(via_bytes(x), expected);
+            assert_eq!(via_pod(x), expected);
+            assert_eq!(via_union(x), expected);
+            assert_eq!(via_transmute(x), expected);
+        }
+    }
+
+    #[test]
+    fn round_trip_is_exact() {
+        for &x in SAMPLES.iter() {
+            assert_eq!(f32::from_bits(via_union(x)).to_bits(), x.to_bits());
+            let back: f32 = cast(via_pod(x));
+            assert_eq!(back.to_bits(), x.to_bits());
+        }
+    }
+
+    #[test]
+    fn pod_cast_to_bytes_matches_native_order() {
+        for &x in SAMPLES.iter() {
+            let bytes: [u8; 4] = cast(x);
+            assert_eq!(bytes, x.to_ne_bytes());
+            let back: f32 = cast(bytes);
+            assert_eq!(back.to_bits(), x.to_bits());
+        }
+    }
+
+    // Each of these is undefined behaviour. Natively the test usually passes;
+    // Miri stops at the marked line.
+
+    #[cfg(ub)]
+    #[test]
+    fn ub_invalid_bool() {
+        let b: bool = unsafe { mem::transmute::<u8, bool>(2) }; // bool must be 0 or 1
+        assert!(b || !b);
+    }
+
+    #[cfg(ub)]
+    #[test]
+    fn ub_misaligned_read() {
+        let buf = [0u32; 2];
+        let p = (buf.as_ptr() as *const u8).wrapping_add(1) as *const u32;
+        let v = unsafe { *p }; // plain deref requires 4-byte alignment
+        assert_eq!(v, 0);
+    }
+
+    #[cfg(ub)]
+    #[test]
+    fn ub_read_past_the_end() {
+        let x = 1.0f32;
+        let wide = unsafe { *(&x as *const f32 as *const f64) }; // reads 4 bytes beyond x
+        assert!(wide.is_finite() || !wide.is_finite());
+    }
+}
@@ -0,0 +1,162 @@
+/*
+ * Endianness in Rust
+ * The same u32 as bytes in little- and big-endian order, the same 4 bytes
+ * read back as two different numbers, network byte order, and a binary
+ * header written on a little-endian machine misread as big-endian.
+ *
+ *   0x12345678 in memory, address →   +0   +1   +2   +3
+ *     little-endian (x86, RISC-V)     78   56   34   12    LSB first
+ *     big-endian (network, 68k)       12   34   56   78    MSB first
+ *
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --bin endianness -- [--lang no]
+ */
+
+use util::hexdump;
+use util::lang::{self, fill, Catalog};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ")
+}
+
+/// A file header as a C program on x86 would write it: fields copied from
+/// memory byte for byte, so in the machine's own (little-endian) order.
+#[derive(Debug, PartialEq)]
+struct Header {
+    magic: u32,   // 0x54444B31 = "TDK1" when read in the right order
+    version: u16,
+    entries: u16,
+    length: u32,
+}
+
+const MAGIC: u32 = 0x5444_4B31;
+
+impl Header {
+    fn write_le(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&self.magic.to_le_bytes());
+        out.extend_from_slice(&self.version.to_le_bytes());
+        out.extend_from_slice(&self.entries.to_le_bytes());
+        out.extend_from_slice(&self.length.to_le_bytes());
+        out
+    }
+
+    /// Parse with the given byte order (`from_be_bytes` or `from_le_bytes`).
+    fn read(bytes: &[u8], big_endian: bool) -> Header {
+        let u32_at = |i: usize| {
+            let b = [bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]];
+            if big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) }
+        };
+        let u16_at = |i: usize| {
+            let b = [bytes[i], bytes[i + 1]];
+            if big_endian { u16::from_be_bytes(b) } else { u16::from_le_bytes(b) }
+        };
+        Header { magic: u32_at(0), version: u16_at(4), entries: u16_at(6), length: u32_at(8) }
+    }
+
+    /// Read a header of unknown origin: the magic number tells the byte order.
+    fn read_any(bytes: &[u8]) -> Result<Header, String> {
+        for big_endian in [false, true] {
+            let h = Header::read(bytes, big_endian);
+            if h.magic == MAGIC {
+                return Ok(h);
+            }
+        }
+        Err(format!("bad magic: {}", hex(&bytes[..4])))
+    }
+}
+
+// ==================== EXPLANATIONS ====================
+
+const NO_ORDER: &str = "  Same memory, different value: the bytes carry no order of their own.\n";
+
+const HTONL: &str = "  u32::to_be / u32::from_be are htonl / ntohl: no-ops on big-endian
+  machines, a byte swap (one bswap / rev8 instruction) on little-endian.
+  Forget the conversion on a little-endian machine and the peer
+  listens on port {} instead.
+";
+
+const GARBAGE: &str = "    magic 0x{}, version {}, {} entries, length {} - all garbage,
+    and nothing crashed: plausible-looking numbers are the dangerous part.
+";
+
+const FORMATS: &str = "  File formats fix the order in the spec (PNG: big, ZIP/ELF-x86: little)
+  and convert on every read and write, never memcpy a struct.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    lang::args("endianness [--lang no|en]");
+    println!("=== Endianness ===");
+    println!("{}\n", tr(if cfg!(target_endian = "little") { "This machine is little-endian" } else { "This machine is big-endian" }));
+
+    println!("--- 1. One number, two byte orders ---");
+    let x: u32 = 0x1234_5678;
+    println!("  x = 0x{:08x}", x);
+    println!("  x.to_le_bytes() = {}   {}", hex(&x.to_le_bytes()), tr("(least significant byte first)"));
+    println!("  x.to_be_bytes() = {}   {}", hex(&x.to_be_bytes()), tr("(most significant byte first)"));
+    println!("  x.to_ne_bytes() = {}   {}\n", hex(&x.to_ne_bytes()), tr("(native: how x sits in memory here)"));
+
+    println!("--- 2. Four bytes, two numbers ---");
+    let bytes = [0x78u8, 0x56, 0x34, 0x12];
+    println!("  bytes {}", hex(&bytes));
+    println!("  u32::from_le_bytes = 0x{:08x} = {}", u32::from_le_bytes(bytes), u32::from_le_bytes(bytes));
+    println!("  u32::from_be_bytes = 0x{:08x} = {}", u32::from_be_bytes(bytes), u32::from_be_bytes(bytes));
+    print!("{}", tr(NO_ORDER));
+    println!("  {} 0x{:08x}\n", tr("swap_bytes() converts between the two:"), u32::from_le_bytes(bytes).swap_bytes());
+
+    println!("--- 3. Network byte order (big-endian on the wire) ---");
+    let port: u16 = 8080;
+    let addr: u32 = u32::from_be_bytes([192, 168, 1, 10]);
+    println!("  port {} = 0x{:04x}: {}", port, port,
+        fill(tr("wire bytes {} (htons), on this machine {}"), &[&hex(&port.to_be_bytes()), &hex(&port.to_ne_bytes())]));
+    println!("  192.168.1.10 = 0x{:08x}: {}", addr, fill(tr("wire bytes {} (htonl)"), &[&hex(&addr.to_be_bytes())]));
+    println!("{}", fill(tr(HTONL), &[&port.swap_bytes()]));
+
+    println!("--- 4. A binary header across machines ---");
+    let header = Header { magic: MAGIC, version: 2, entries: 3, length: 4096 };
+    let file = header.write_le();
+    println!("  {}", tr("written on a little-endian machine:"));
+    for line in hexdump::hexdump(&file, 0).lines() {
+        println!("    {}", line);
+    }
+    println!("  {} {:?}", tr("read as little-endian:"), Header::read(&file, false));
+    let wrong = Header::read(&file, true);
+    println!("  {}    {:?}", tr("read as big-endian:"), wrong);
+    print!("{}", fill(tr(GARBAGE), &[&format!("{:08x}", wrong.magic), &wrong.version, &wrong.entries, &wrong.length]));
+    println!("  {} {:?}", tr("A magic number makes it detectable: read_any() ->"), Header::read_any(&file).map(|h| h.version));
+    print!("{}", tr(FORMATS));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("This machine is little-endian", "Denne maskinen er little-endian"),
+    ("This machine is big-endian", "Denne maskinen er big-endian"),
+    ("(least significant byte first)", "(minst signifikante byte først)"),
+    ("(most significant byte first)", "(mest signifikante byte først)"),
+    ("(native: how x sits in memory here)", "(native: slik x ligger i minnet her)"),
+    (NO_ORDER, "  Samme minne, forskjellig verdi: bytene har ingen rekkefølge i seg selv.\n"),
+    ("swap_bytes() converts between the two:", "swap_bytes() konverterer mellom de to:"),
+    ("wire bytes {} (htons), on this machine {}", "byte på linjen {} (htons), på denne maskinen {}"),
+    ("wire bytes {} (htonl)", "byte på linjen {} (htonl)"),
+    (HTONL, "  u32::to_be / u32::from_be er htonl / ntohl: ingenting på big-endian-
+  maskiner, en byteombytting (én bswap- / rev8-instruksjon) på little-endian.
+  Glem konverteringen på en little-endian-maskin, så lytter motparten på
+  port {} i stedet.
+"),
+    ("written on a little-endian machine:", "skrevet på en little-endian-maskin:"),
+    ("read as little-endian:", "lest som little-endian:"),
+    ("read as big-endian:", "lest som big-endian:"),
+    (GARBAGE, "    magic 0x{}, versjon {}, {} oppføringer, lengde {} - bare søppel,
+    og ingenting krasjet: tall som ser rimelige ut, er det farlige.
+"),
+    ("A magic number makes it detectable: read_any() ->", "Et magisk tall gjør det mulig å oppdage: read_any() ->"),
+    (FORMATS, "  Filformater fastsetter rekkefølgen i spesifikasjonen (PNG: big, ZIP/ELF-x86:
+  little) og konverterer ved hver lesing og skriving, aldri memcpy av en struct.
+"),
+];
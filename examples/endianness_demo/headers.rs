@@ -0,0 +1,406 @@
+/*
+ * Reading Real Binary Headers: BMP and WAV
+ * Both formats come from little-endian Windows PCs, so every multi-byte
+ * field is little-endian. The parser walks the header one field at a time
+ * and prints each field's offset, its raw bytes and what they mean.
+ *
+ *   BMP   "BM" | file size | reserved | pixel offset | DIB header | masks | rows
+ *   WAV   "RIFF" size "WAVE" | "fmt " chunk | "data" chunk | ...
+ *
+ * With BI_BITFIELDS compression a BMP stores one bit mask per colour
+ * channel. The shift and width of each channel come straight from
+ * trailing_zeros and count_ones, and the pixels are unpacked with the
+ * mask/extract helpers of arithmetic::bits.
+ *
+ * Without arguments both files are generated in memory and parsed; give a
+ * path to parse a real .bmp or .wav instead. The explanations are printed
+ * in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --bin headers -- [file.bmp | file.wav] [--lang no]
+ */
+
+use arithmetic::bits;
+use util::hexdump;
+use util::lang::{self, fill, Catalog};
+
+// ==================== FIELD-BY-FIELD READER ====================
+
+/// A cursor that reads little-endian fields and records each one.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    rows: Vec<String>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0, rows: Vec::new() }
+    }
+
+    fn take(&mut self, n: usize, name: &str) -> Result<&'a [u8], String> {
+        let end = self.pos + n;
+        if end > self.data.len() {
+            return Err(format!("truncated: {} needs bytes {}..{}, file has {}", name, self.pos, end, self.data.len()));
+        }
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn record(&mut self, offset: usize, raw: &[u8], name: &str, meaning: String) {
+        let hex: Vec<String> = raw.iter().map(|b| format!("{:02x}", b)).collect();
+        self.rows.push(format!("  {:>4}  {:<12} {:<18} {}", offset, hex.join(" "), name, meaning));
+    }
+
+    fn tag(&mut self, name: &str) -> Result<String, String> {
+        let at = self.pos;
+        let raw = self.take(4, name)?;
+        let text: String = raw.iter().map(|&b| if (0x20..0x7F).contains(&b) { b as char } else { '.' }).collect();
+        self.record(at, raw, name, format!("\"{}\"", text));
+        Ok(text)
+    }
+
+    fn u16(&mut self, name: &str, note: &str) -> Result<u16, String> {
+        let at = self.pos;
+        let raw = self.take(2, name)?;
+        let v = u16::from_le_bytes([raw[0], raw[1]]);
+        self.record(at, raw, name, format!("{}{}", v, note));
+        Ok(v)
+    }
+
+    fn u32(&mut self, name: &str, note: &str) -> Result<u32, String> {
+        let at = self.pos;
+        let raw = self.take(4, name)?;
+        let v = u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]);
+        self.record(at, raw, name, format!("{}{}", v, note));
+        Ok(v)
+    }
+
+    fn i32(&mut self, name: &str, note: &str) -> Result<i32, String> {
+        let at = self.pos;
+        let raw = self.take(4, name)?;
+        let v = i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]);
+        self.record(at, raw, name, format!("{}{}", v, note));
+        Ok(v)
+    }
+
+    fn mask(&mut self, name: &str) -> Result<u32, String> {
+        let at = self.pos;
+        let raw = self.take(4, name)?;
+        let m = u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]);
+        let meaning = if m == 0 {
+            tr("0 (channel absent)").to_string()
+        } else {
+            format!("0x{:08x}: {}", m, fill(tr("{} bits at bit {}"), &[&m.count_ones(), &m.trailing_zeros()]))
+        };
+        self.record(at, raw, name, meaning);
+        Ok(m)
+    }
+
+    fn print(&mut self, title: &str) {
+        println!("{}", title);
+        println!("  {:>4}  {:<12} {:<18} value", "off", "raw (LE)", "field");
+        for row in self.rows.drain(..) {
+            println!("{}", row);
+        }
+    }
+}
+
+// ==================== BMP ====================
+
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+
+fn parse_bmp(data: &[u8]) -> Result<(), String> {
+    let mut r = Reader::new(data);
+    let sig = r.take(2, "signature")?;
+    if sig != b"BM" {
+        return Err("not a BMP: no \"BM\" signature".to_string());
+    }
+    r.record(0, sig, "signature", "\"BM\"".to_string());
+    let file_size = r.u32("file size", tr(" bytes"))?;
+    r.u16("reserved1", "")?;
+    r.u16("reserved2", "")?;
+    let pixel_offset = r.u32("pixel offset", tr(" (where the rows start)"))? as usize;
+    r.print("BITMAPFILEHEADER (14 bytes)");
+    if file_size as usize != data.len() {
+        println!("  {}", fill(tr("note: header says {} bytes, file has {}"), &[&file_size, &data.len()]));
+    }
+
+    let dib_size = r.u32("header size", "")?;
+    let kind = match dib_size {
+        40 => " (BITMAPINFOHEADER)",
+        108 => " (BITMAPV4HEADER)",
+        124 => " (BITMAPV5HEADER)",
+        _ => " (unknown, reading the first 40 bytes)",
+    };
+    r.rows.last_mut().unwrap().push_str(tr(kind));
+    let width = r.i32("width", tr(" px"))?;
+    let height = r.i32("height", "")?;
+    r.rows.last_mut().unwrap().push_str(tr(if height < 0 { " px (negative: rows stored top-down)" } else { " px (positive: rows stored bottom-up)" }));
+    r.u16("planes", tr(" (always 1)"))?;
+    let bpp = r.u16("bits per pixel", "")?;
+    let compression = r.u32("compression", "")?;
+    r.rows.last_mut().unwrap().push_str(tr(match compression {
+        BI_RGB => " (BI_RGB: uncompressed)",
+        BI_BITFIELDS => " (BI_BITFIELDS: channel masks follow)",
+        _ => " (compressed, pixels not decoded)",
+    }));
+    r.u32("image size", tr(" bytes (may be 0 for BI_RGB)"))?;
+    r.i32("x resolution", tr(" px/m"))?;
+    r.i32("y resolution", tr(" px/m"))?;
+    r.u32("palette colours", "")?;
+    r.u32("important colours", "")?;
+
+    // Default masks when a file has none: 5-5-5 for 16 bit, 8-8-8 for 24/32.
+    let mut masks = match bpp {
+        16 => [0x7C00, 0x03E0, 0x001F],
+        24 | 32 => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF],
+        _ => [0, 0, 0],
+    };
+    if compression == BI_BITFIELDS {
+        if dib_size == 40 {
+            r.print("BITMAPINFOHEADER (40 bytes)");
+            masks = [r.mask("red mask")?, r.mask("green mask")?, r.mask("blue mask")?];
+            r.print("Channel masks (12 bytes after a 40-byte header)");
+        } else {
+            masks = [r.mask("red mask")?, r.mask("green mask")?, r.mask("blue mask")?];
+            r.mask("alpha mask")?;
+            r.print(&format!("DIB header ({} bytes, first 56 shown)", dib_size));
+        }
+    } else {
+        r.print(&format!("DIB header ({} bytes, first 40 shown)", dib_size));
+    }
+
+    if compression != BI_RGB && compression != BI_BITFIELDS || !(bpp == 16 || bpp == 24 || bpp == 32) {
+        println!("\n{}", tr("(pixel decoding only for uncompressed 16/24/32-bit images)"));
+        return Ok(());
+    }
+    let bytes_pp = bpp as usize / 8;
+    let row_bytes = width.unsigned_abs() as usize * bytes_pp;
+    let stride = (row_bytes + 3) & !3;
+    println!("\n{}", fill(tr("Rows: {} px × {} bytes = {} bytes, padded to a multiple of 4 → stride {}"),
+        &[&width, &bytes_pp, &row_bytes, &stride]));
+    let channels: Vec<(u32, u32)> = masks.iter().map(|m| (m.trailing_zeros() % 32, m.count_ones())).collect();
+    println!("{} R {}:{}  G {}:{}  B {}:{}", tr("Channels (shift = trailing_zeros, width = count_ones):"),
+        channels[0].0, channels[0].1, channels[1].0, channels[1].1, channels[2].0, channels[2].1);
+
+    let first_row = if height <= 0 { 0 } else { height as usize - 1 };
+    println!("{}", fill(tr(if height < 0 { "Top row (stored as row {} because the image is top-down):" }
+        else { "Top row (stored as row {} because the image is bottom-up):" }), &[&first_row]));
+    for x in 0..(width.unsigned_abs() as usize).min(4) {
+        let at = pixel_offset + first_row * stride + x * bytes_pp;
+        let raw = data.get(at..at + bytes_pp).ok_or("pixel data truncated")?;
+        let mut le = [0u8; 4];
+        le[..bytes_pp].copy_from_slice(raw);
+        let px = u32::from_le_bytes(le);
+        let rgb: Vec<u32> = channels.iter().map(|&(lo, n)| {
+            if n == 0 { return 0; }
+            let v = bits::extract(px, lo + n - 1, lo);
+            (v * 255 + (bits::mask(n, 0) / 2)) / bits::mask(n, 0) // scale to 0-255
+        }).collect();
+        println!("  x={}  raw 0x{:0w$x}  → rgb({:>3}, {:>3}, {:>3})", x, px, rgb[0], rgb[1], rgb[2], w = bytes_pp * 2);
+    }
+    Ok(())
+}
+
+/// A 3×2 RGB565 image: red, green, blue on top; white, black, grey below.
+fn make_bmp() -> Vec<u8> {
+    let (width, height) = (3u32, 2i32);
+    let stride = (width * 2 + 3) & !3;
+    let pixel_offset = 14 + 40 + 12;
+    let size = pixel_offset + stride * height as u32;
+    let mut out = Vec::new();
+    out.extend_from_slice(b"BM");
+    out.extend_from_slice(&size.to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&pixel_offset.to_le_bytes());
+    out.extend_from_slice(&40u32.to_le_bytes());
+    out.extend_from_slice(&(width as i32).to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(&BI_BITFIELDS.to_le_bytes());
+    out.extend_from_slice(&(stride * height as u32).to_le_bytes());
+    out.extend_from_slice(&2835i32.to_le_bytes()); // 72 dpi
+    out.extend_from_slice(&2835i32.to_le_bytes());
+    out.extend_from_slice(&[0; 8]);
+    for m in [0xF800u32, 0x07E0, 0x001F] {
+        out.extend_from_slice(&m.to_le_bytes());
+    }
+    let rows: [[u16; 3]; 2] = [[0xFFFF, 0x0000, 0x8410], [0xF800, 0x07E0, 0x001F]]; // bottom row first
+    for row in rows.iter() {
+        for px in row.iter() {
+            out.extend_from_slice(&px.to_le_bytes());
+        }
+        out.extend_from_slice(&[0; 2]); // pad 6 → 8
+    }
+    out
+}
+
+// ==================== WAV ====================
+
+fn parse_wav(data: &[u8]) -> Result<(), String> {
+    let mut r = Reader::new(data);
+    if r.tag("chunk id")? != "RIFF" {
+        return Err("not a WAV: no \"RIFF\" chunk".to_string());
+    }
+    let riff_size = r.u32("chunk size", tr(" (file size - 8)"))?;
+    if r.tag("form type")? != "WAVE" {
+        return Err("RIFF file, but not WAVE".to_string());
+    }
+    r.print("RIFF header (12 bytes)");
+    if riff_size as usize + 8 != data.len() {
+        println!("  {}", fill(tr("note: chunk size says {} bytes, file has {}"), &[&(riff_size as usize + 8), &data.len()]));
+    }
+
+    let mut format = None;
+    while r.pos + 8 <= data.len() {
+        let id = r.tag("chunk id")?;
+        let size = r.u32("chunk size", tr(" bytes"))? as usize;
+        let body = r.pos;
+        match id.as_str() {
+            "fmt " => {
+                let tag = r.u16("audio format", "")?;
+                r.rows.last_mut().unwrap().push_str(match tag { 1 => " (PCM)", 3 => " (IEEE float)", 0xFFFE => " (extensible)", _ => "" });
+                let channels = r.u16("channels", "")?;
+                let rate = r.u32("sample rate", tr(" Hz"))?;
+                let byte_rate = r.u32("byte rate", tr(" bytes/s"))?;
+                let align = r.u16("block align", tr(" bytes per frame"))?;
+                let bits_per_sample = r.u16("bits per sample", "")?;
+                r.print("\n\"fmt \" chunk");
+                let expected = rate * channels as u32 * bits_per_sample as u32 / 8;
+                println!("  {} = {} × {} × {}/8 = {} {}", tr("byte rate = rate × channels × bits/8"),
+                    rate, channels, bits_per_sample, expected, tr(if expected == byte_rate { "ok" } else { "MISMATCH" }));
+                format = Some((channels, rate, align, bits_per_sample));
+            }
+            "data" => {
+                r.print("\n\"data\" chunk");
+                if let Some((channels, rate, align, bits_per_sample)) = format {
+                    let frames = size / align.max(1) as usize;
+                    println!("  {}", fill(tr("{} frames = {} s"), &[&frames, &format!("{:.3}", frames as f64 / rate as f64)]));
+                    if bits_per_sample == 16 {
+                        let samples: Vec<String> = data[body..(body + size).min(data.len())]
+                            .chunks_exact(2).take(8 * channels as usize)
+                            .map(|b| i16::from_le_bytes([b[0], b[1]]).to_string()).collect();
+                        println!("  {} {}", tr("first samples (i16 LE):"), samples.join(" "));
+                    }
+                }
+            }
+            _ => r.print(&format!("\n\"{}\" chunk {}", id, tr("(skipped)"))),
+        }
+        r.pos = body + size + (size & 1); // chunks are padded to an even length
+    }
+    println!("\n{}", tr("Raw start of the file:"));
+    print!("{}", hexdump::hexdump(&data[..data.len().min(48)], 0));
+    Ok(())
+}
+
+/// 0.001 s of a 1 kHz square wave, 8 kHz mono 16-bit PCM.
+fn make_wav() -> Vec<u8> {
+    let samples: Vec<i16> = (0..8).map(|i| if i < 4 { 8000 } else { -8000 }).collect();
+    let (channels, rate, bits_per_sample) = (1u16, 8000u32, 16u16);
+    let align = channels * bits_per_sample / 8;
+    let data_size = samples.len() as u32 * 2;
+    let mut out = Vec::new();
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(4 + 8 + 16 + 8 + data_size).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&rate.to_le_bytes());
+    out.extend_from_slice(&(rate * align as u32).to_le_bytes());
+    out.extend_from_slice(&align.to_le_bytes());
+    out.extend_from_slice(&bits_per_sample.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_size.to_le_bytes());
+    for s in samples {
+        out.extend_from_slice(&s.to_le_bytes());
+    }
+    out
+}
+
+// ==================== MAIN ====================
+
+fn parse(data: &[u8]) -> Result<(), String> {
+    if data.starts_with(b"BM") {
+        parse_bmp(data)
+    } else if data.starts_with(b"RIFF") {
+        parse_wav(data)
+    } else {
+        Err("neither BMP (\"BM\") nor WAV (\"RIFF\")".to_string())
+    }
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("headers [file.bmp | file.wav] [--lang no|en]");
+    if let Some(path) = args.first() {
+        let data = std::fs::read(path).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1)
+        });
+        println!("=== {} ({} bytes) ===\n", path, data.len());
+        if let Err(e) = parse(&data) {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let bmp = make_bmp();
+    println!("=== BMP: 3×2 pixels, 16-bit RGB565 ({} bytes) ===\n", bmp.len());
+    parse(&bmp).unwrap();
+
+    let wav = make_wav();
+    println!("\n=== WAV: 8 samples of a 1 kHz square wave ({} bytes) ===\n", wav.len());
+    parse(&wav).unwrap();
+
+    let mut truncated = wav.clone();
+    truncated.truncate(30);
+    println!("\n=== The same WAV cut to 30 bytes ===\n");
+    if let Err(e) = parse(&truncated) {
+        println!("error: {}", e);
+    }
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("0 (channel absent)", "0 (kanalen mangler)"),
+    ("{} bits at bit {}", "{} biter fra bit {}"),
+    (" bytes", " byte"),
+    (" (where the rows start)", " (der radene starter)"),
+    ("note: header says {} bytes, file has {}", "merk: headeren sier {} byte, filen har {}"),
+    (" (unknown, reading the first 40 bytes)", " (ukjent, leser de første 40 bytene)"),
+    (" px (negative: rows stored top-down)", " px (negativ: radene lagret ovenfra og ned)"),
+    (" px (positive: rows stored bottom-up)", " px (positiv: radene lagret nedenfra og opp)"),
+    (" (always 1)", " (alltid 1)"),
+    (" (BI_RGB: uncompressed)", " (BI_RGB: ukomprimert)"),
+    (" (BI_BITFIELDS: channel masks follow)", " (BI_BITFIELDS: kanalmasker følger)"),
+    (" (compressed, pixels not decoded)", " (komprimert, pikslene dekodes ikke)"),
+    (" bytes (may be 0 for BI_RGB)", " byte (kan være 0 for BI_RGB)"),
+    ("(pixel decoding only for uncompressed 16/24/32-bit images)",
+     "(pikseldekoding bare for ukomprimerte 16/24/32-biters bilder)"),
+    ("Rows: {} px × {} bytes = {} bytes, padded to a multiple of 4 → stride {}",
+     "Rader: {} px × {} byte = {} byte, fylt ut til et multiplum av 4 → stride {}"),
+    ("Channels (shift = trailing_zeros, width = count_ones):", "Kanaler (skift = trailing_zeros, bredde = count_ones):"),
+    ("Top row (stored as row {} because the image is top-down):",
+     "Øverste rad (lagret som rad {} fordi bildet er lagret ovenfra og ned):"),
+    ("Top row (stored as row {} because the image is bottom-up):",
+     "Øverste rad (lagret som rad {} fordi bildet er lagret nedenfra og opp):"),
+    (" (file size - 8)", " (filstørrelse - 8)"),
+    ("note: chunk size says {} bytes, file has {}", "merk: chunk-størrelsen sier {} byte, filen har {}"),
+    (" bytes/s", " byte/s"),
+    (" bytes per frame", " byte per ramme"),
+    ("byte rate = rate × channels × bits/8", "byterate = rate × kanaler × biter/8"),
+    ("MISMATCH", "ULIK"),
+    ("{} frames = {} s", "{} rammer = {} s"),
+    ("first samples (i16 LE):", "første sampler (i16 LE):"),
+    ("(skipped)", "(hoppet over)"),
+    ("Raw start of the file:", "Starten av filen, rå:"),
+];
@@ -0,0 +1,55 @@
+/*
+ * hexdump - offset, hex bytes and ASCII column, like `hexdump -C`.
+ * The same format as the compendium's computerdesign.util.HexDump, so dumps
+ * from the Java simulators and the Rust demos can be compared line by line.
+ *
+ *   00000000  31 4b 44 54 02 00 03 00  00 10 00 00              |1KDT........|
+ *
+ * The formatter is util::hexdump; this is the command-line tool around it.
+ *
+ * Run: cargo run --release --bin hexdump -- file [--width N] [--group N] [--offset N] [--length N] [--no-squeeze]
+ */
+
+use std::process;
+
+use util::hexdump::Options;
+
+const USAGE: &str = "hexdump file [--width N] [--group N] [--offset N] [--length N] [--no-squeeze]";
+
+fn fail(message: &str) -> ! {
+    eprintln!("hexdump: {}\nusage: {}", message, USAGE);
+    process::exit(2)
+}
+
+fn number(s: &str) -> usize {
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.unwrap_or_else(|_| fail(&format!("not a number: {}", s)))
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(path) = args.first() else { fail("no file given") };
+    let data = std::fs::read(path).unwrap_or_else(|e| {
+        eprintln!("hexdump: {}: {}", path, e);
+        process::exit(1)
+    });
+    let (mut options, mut offset, mut length) = (Options::default(), 0, None);
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        let mut value = || number(rest.next().unwrap_or_else(|| fail(&format!("missing value after {}", arg))));
+        match arg.as_str() {
+            "--width" => options = options.width(value()),
+            "--group" => options = options.group(value()),
+            "--offset" => offset = value(),
+            "--length" => length = Some(value()),
+            "--no-squeeze" => options = options.squeeze(false),
+            other => fail(&format!("unknown option: {}", other)),
+        }
+    }
+    let offset = offset.min(data.len());
+    let end = length.map_or(data.len(), |n| (offset + n).min(data.len()));
+    print!("{}", options.format(&data[offset..end], offset as u64));
+}
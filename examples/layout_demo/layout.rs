@@ -0,0 +1,137 @@
+/*
+ * Struct Layout, Padding and Alignment
+ * Size, alignment, field offsets and padding bytes of real Rust structs,
+ * measured with std::mem::offset_of! and drawn byte by byte.
+ *
+ *   #[repr(C)] struct { a: u8, b: u32, c: u8 }     12 bytes
+ *     offset  0  1  2  3  4  5  6  7  8  9 10 11
+ *             a  .  .  .  b  b  b  b  c  .  .  .     . = padding
+ *
+ * Every field sits at a multiple of its alignment, and the size is rounded
+ * up to the struct's alignment so arrays of it stay aligned. repr(C) keeps
+ * the declared order (the C ABI); Rust's default repr may reorder fields
+ * to remove padding; repr(packed) drops it at the cost of unaligned fields.
+ *
+ * The measuring is util::layout and its layout! macro, also used by
+ * alignment.rs. The explanations are printed in Norwegian with --lang no
+ * (util::lang).
+ *
+ * Run: cargo run --bin layout -- [--lang no]
+ */
+
+use std::mem::{align_of, size_of};
+use util::lang::{self, Catalog};
+use util::layout;
+
+// ==================== EXAMPLE STRUCTS ====================
+
+#[allow(dead_code)]
+#[repr(C)]
+struct PoorOrderC {
+    a: u8,
+    b: u64,
+    c: u8,
+    d: u32,
+    e: u16,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+struct GoodOrderC {
+    b: u64,
+    d: u32,
+    e: u16,
+    a: u8,
+    c: u8,
+}
+
+#[allow(dead_code)]
+struct PoorOrderRust {
+    a: u8,
+    b: u64,
+    c: u8,
+    d: u32,
+    e: u16,
+}
+
+#[allow(dead_code)]
+#[repr(C, packed)]
+struct PoorOrderPacked {
+    a: u8,
+    b: u64,
+    c: u8,
+    d: u32,
+    e: u16,
+}
+
+/// A 32-bit instruction record as a simulator might store it.
+#[allow(dead_code)]
+#[repr(C)]
+struct Decoded {
+    valid: bool,
+    pc: u32,
+    opcode: u8,
+    imm: i32,
+    rd: u8,
+}
+
+// ==================== EXPLANATIONS ====================
+
+const GOOD: &str = "    Largest alignment first: padding only at the end, if any.\n";
+
+const DEFAULT: &str = "    Default repr: the compiler reordered the fields itself. Fine in Rust,
+    but not a stable layout - use #[repr(C)] for anything shared with C,
+    hardware registers or files.
+";
+
+const PACKED: &str = "    repr(packed): no padding, but b and d are unaligned - each access
+    may take two bus reads (or trap on some CPUs), and &packed.b is refused.
+";
+
+const ARRAY: &str = "\
+An array of 1 000 000 PoorOrderC takes {} MB; GoodOrderC {} MB.
+Fewer bytes per element = more elements per cache line.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    lang::args("layout [--lang no|en]");
+    println!("=== Struct Layout, Padding and Alignment ===\n");
+    println!("Primitive alignment on this target: u8 {}, u16 {}, u32 {}, u64 {}, usize {}\n",
+        align_of::<u8>(), align_of::<u16>(), align_of::<u32>(), align_of::<u64>(), align_of::<usize>());
+
+    println!("--- Same five fields, three declarations ---\n");
+    print!("{}", layout!(PoorOrderC { a: u8, b: u64, c: u8, d: u32, e: u16 }).report());
+    println!();
+    print!("{}", layout!(GoodOrderC { b: u64, d: u32, e: u16, a: u8, c: u8 }).report());
+    println!("{}", tr(GOOD));
+    print!("{}", layout!(PoorOrderRust { a: u8, b: u64, c: u8, d: u32, e: u16 }).report());
+    println!("{}", tr(DEFAULT));
+    print!("{}", layout!(PoorOrderPacked { a: u8, b: u64, c: u8, d: u32, e: u16 }).report());
+    println!("{}", tr(PACKED));
+
+    println!("--- A simulator record ---\n");
+    print!("{}", layout!(Decoded { valid: bool, pc: u32, opcode: u8, imm: i32, rd: u8 }).report());
+    println!();
+    print!("{}", lang::fill(tr(ARRAY), &[&size_of::<PoorOrderC>(), &size_of::<GoodOrderC>()]));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    (GOOD, "    Største justering først: utfylling bare på slutten, om noe.\n"),
+    (DEFAULT, "    Standard repr: kompilatoren har selv byttet om på feltene. Greit i Rust,
+    men ikke en stabil layout - bruk #[repr(C)] for alt som deles med C,
+    maskinvareregistre eller filer.
+"),
+    (PACKED, "    repr(packed): ingen utfylling, men b og d er ikke justert - hver aksess
+    kan ta to bussles (eller gi en felle på noen prosessorer), og &packed.b nektes.
+"),
+    (ARRAY, "\
+En tabell med 1 000 000 PoorOrderC tar {} MB; GoodOrderC {} MB.
+Færre byte per element = flere elementer per cachelinje.
+"),
+];
@@ -0,0 +1,277 @@
+/*
+ * Hardware Flags vs the Software ALU
+ * An add or sub on a real CPU sets its status flags as a side effect; this
+ * demo reads them straight out of the flags register with inline assembly
+ * and sets them against what arithmetic::alu computes for the same
+ * operands - the model the Java ALU, the gate-level adders and the quiz
+ * answers all use.
+ *
+ *   x86-64    add al, bl ; pushfq ; pop rax     RFLAGS: CF bit 0, ZF 6, SF 7, OF 11
+ *   AArch64   adds w0, w1, w2 ; mrs x3, nzcv    NZCV:   N bit 31, Z 30, C 29, V 28
+ *
+ *     0x7f + 0x01 = 0x80    OF SF       signed overflow: +127 + 1 is not -128
+ *     0xff + 0x01 = 0x00    CF ZF       unsigned overflow: a carry out of bit 7
+ *     0x00 - 0x01 = 0xff    CF SF       x86: CF is the borrow     ARM: C clear
+ *
+ * x86 has 8- and 16-bit adds of its own. AArch64 only adds 32 or 64 bits,
+ * so a narrow add is done at the top of a word: both operands shifted left
+ * by 32 - width, and the carry out of bit 31 is then the carry out of the
+ * narrow adder's top bit, and V its overflow. The one real difference is
+ * subtraction: x86 sets CF on a borrow, as alu::sub does, while ARM sets C
+ * when there is NO borrow (a - b is a + !b + 1, and C is that carry). The
+ * comparison accounts for it and says so.
+ *
+ * After the hand-picked cases, a stream of random operands for every width
+ * and operation is checked the same way; a single disagreement is printed
+ * and the exit code is 1. On other architectures only the software flags
+ * are shown. The explanations are printed in Norwegian with --lang no
+ * (util::lang).
+ *
+ * Run: cargo run --release --bin asm_flags_demo -- [random cases per width] [--lang no]
+ */
+
+use std::process;
+
+use arithmetic::alu::{self, Flags};
+use util::lang::{self, fill, Catalog};
+use util::rng::Rng;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+        }
+    }
+
+    fn software(self, a: u32, b: u32, width: u32) -> (u32, Flags) {
+        match self {
+            Op::Add => alu::add(a, b, width),
+            Op::Sub => alu::sub(a, b, width),
+        }
+    }
+}
+
+const WIDTHS: [u32; 3] = [8, 16, 32];
+
+// ==================== READING THE FLAGS ====================
+
+#[cfg(target_arch = "x86_64")]
+mod hw {
+    use super::{Flags, Op};
+    use std::arch::asm;
+
+    pub const HOW: &str = "x86-64: add/sub, then pushfq; pop copies RFLAGS into a register";
+
+    /// The instruction, then RFLAGS onto the stack and into `f`.
+    macro_rules! flags_after {
+        ($op:literal, $class:ident, $ty:ty, $size:literal, $a:expr, $b:expr) => {{
+            let mut r = $a as $ty;
+            let f: u64;
+            // SAFETY: registers only; the push is popped again, and without
+            // `nostack` the compiler leaves the stack safe to push to
+            unsafe {
+                asm!(
+                    concat!($op, " {r", $size, "}, {b", $size, "}"),
+                    "pushfq",
+                    "pop {f}",
+                    r = inout($class) r,
+                    b = in($class) $b as $ty,
+                    f = out(reg) f,
+                );
+            }
+            (r as u32, f)
+        }};
+    }
+
+    /// The result and the CPU's flags, named as the CPU names them and
+    /// as alu::Flags (they mean the same on x86).
+    pub fn run(op: Op, a: u32, b: u32, width: u32) -> (u32, String, Flags) {
+        let (r, f) = match (op, width) {
+            (Op::Add, 8) => flags_after!("add", reg_byte, u8, "", a, b),
+            (Op::Sub, 8) => flags_after!("sub", reg_byte, u8, "", a, b),
+            (Op::Add, 16) => flags_after!("add", reg, u16, ":x", a, b),
+            (Op::Sub, 16) => flags_after!("sub", reg, u16, ":x", a, b),
+            (Op::Add, _) => flags_after!("add", reg, u32, ":e", a, b),
+            (Op::Sub, _) => flags_after!("sub", reg, u32, ":e", a, b),
+        };
+        let bit = |n: u32| f >> n & 1 == 1;
+        let flags = Flags { carry: bit(0), zero: bit(6), negative: bit(7), overflow: bit(11) };
+        let names: Vec<&str> = [(flags.carry, "CF"), (flags.overflow, "OF"), (flags.zero, "ZF"), (flags.negative, "SF")]
+            .iter().filter(|f| f.0).map(|f| f.1).collect();
+        (r, names.join(" "), flags)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod hw {
+    use super::{Flags, Op};
+    use std::arch::asm;
+
+    pub const HOW: &str = "AArch64: adds/subs at the top of a word, then mrs reads NZCV";
+
+    pub fn run(op: Op, a: u32, b: u32, width: u32) -> (u32, String, Flags) {
+        let shift = 32 - width;
+        let (x, y) = (a << shift, b << shift);
+        let r: u32;
+        let f: u64;
+        // SAFETY: registers only
+        unsafe {
+            match op {
+                Op::Add => asm!("adds {r:w}, {x:w}, {y:w}", "mrs {f}, nzcv", r = out(reg) r, x = in(reg) x, y = in(reg) y, f = out(reg) f, options(nomem, nostack)),
+                Op::Sub => asm!("subs {r:w}, {x:w}, {y:w}", "mrs {f}, nzcv", r = out(reg) r, x = in(reg) x, y = in(reg) y, f = out(reg) f, options(nomem, nostack)),
+            }
+        }
+        let bit = |n: u32| f >> n & 1 == 1;
+        let (n, z, c, v) = (bit(31), bit(30), bit(29), bit(28));
+        let names: Vec<&str> = [(n, "N"), (z, "Z"), (c, "C"), (v, "V")].iter().filter(|f| f.0).map(|f| f.1).collect();
+        // ARM's C after a subtraction is "no borrow"
+        let carry = if op == Op::Sub { !c } else { c };
+        (r >> shift, names.join(" "), Flags { negative: n, zero: z, carry, overflow: v })
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod hw {
+    use super::{Flags, Op};
+
+    pub const HOW: &str = "";
+
+    pub fn run(_: Op, _: u32, _: u32, _: u32) -> (u32, String, Flags) {
+        unreachable!("no inline assembly for this architecture")
+    }
+}
+
+const HARDWARE: bool = cfg!(any(target_arch = "x86_64", target_arch = "aarch64"));
+
+// ==================== MAIN ====================
+
+fn describe(f: Flags) -> String {
+    let names: Vec<&str> = [(f.carry, "carry"), (f.overflow, "overflow"), (f.zero, "zero"), (f.negative, "negative")]
+        .iter().filter(|x| x.0).map(|x| x.1).collect();
+    if names.is_empty() { "-".to_string() } else { names.join(" ") }
+}
+
+fn hex(v: u32, width: u32) -> String {
+    format!("0x{:0w$x}", v, w = width.div_ceil(4) as usize)
+}
+
+const EXACTLY: &str = "
+The software ALU computes exactly the flags the hardware sets: carry from the
+unsigned sum, overflow from the signs (both operands alike, result unlike).
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("asm_flags_demo [random cases per width] [--lang no|en]");
+    let random: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(100_000);
+
+    println!("=== Hardware Flags vs arithmetic::alu ===\n");
+    if HARDWARE {
+        println!("{}\n", tr(hw::HOW));
+    } else {
+        println!("{}\n", fill(tr("no inline assembly for {}: the software flags only"), &[&std::env::consts::ARCH]));
+    }
+
+    let cases: [(Op, u32, u32, u32); 12] = [
+        (Op::Add, 8, 0x01, 0x02),
+        (Op::Add, 8, 0x7f, 0x01),
+        (Op::Add, 8, 0xff, 0x01),
+        (Op::Add, 8, 0x80, 0x80),
+        (Op::Sub, 8, 0x00, 0x01),
+        (Op::Sub, 8, 0x80, 0x01),
+        (Op::Sub, 8, 0x05, 0x05),
+        (Op::Add, 16, 0x7fff, 0x0001),
+        (Op::Sub, 16, 0x1234, 0x4321),
+        (Op::Add, 32, 0x7fff_ffff, 1),
+        (Op::Add, 32, 0xffff_ffff, 0xffff_ffff),
+        (Op::Sub, 32, 0x8000_0000, 1),
+    ];
+    println!("  {:<5} {:<27} {:<12} {:<12} alu::Flags", "width", "operation", "result", "CPU flags");
+    let mut differ = 0;
+    for &(op, width, a, b) in &cases {
+        let (sr, sf) = op.software(a, b, width);
+        let operation = format!("{} {} {}", hex(a, width), op.symbol(), hex(b, width));
+        if !HARDWARE {
+            println!("  {:<5} {:<27} {:<12} {:<12} {}", width, operation, hex(sr, width), "", describe(sf));
+            continue;
+        }
+        let (hr, names, hf) = hw::run(op, a, b, width);
+        let agree = (hr, hf) == (sr, sf);
+        differ += usize::from(!agree);
+        let note = match (agree, op, cfg!(target_arch = "aarch64")) {
+            (false, _, _) => "DIFFER",
+            (true, Op::Sub, true) if !sf.carry => tr("agree (C set: no borrow)"),
+            (true, Op::Sub, true) if sf.carry => tr("agree (C clear: a borrow)"),
+            _ => tr("agree"),
+        };
+        println!("  {:<5} {:<27} {:<12} {:<12} {:<26} {}", width, operation, hex(hr, width), if names.is_empty() { "-" } else { &names }, describe(sf), note);
+    }
+
+    if !HARDWARE {
+        return;
+    }
+    println!("\n{}", fill(tr("{} random operands per width and operation:"), &[&random]));
+    let mut rng = Rng::new(4160);
+    for width in WIDTHS {
+        for op in [Op::Add, Op::Sub] {
+            let mut first = None;
+            let mut count = 0;
+            for _ in 0..random {
+                // the edges are where flags get interesting: bias towards them
+                let pick = |rng: &mut Rng| match rng.below(4) {
+                    0 => [0, 1, (1 << (width - 1)) - 1, 1 << (width - 1), u32::MAX][rng.below(5)],
+                    _ => rng.next() as u32,
+                } & (u32::MAX >> (32 - width));
+                let (a, b) = (pick(&mut rng), pick(&mut rng));
+                let software = op.software(a, b, width);
+                let (hr, _, hf) = hw::run(op, a, b, width);
+                if (hr, hf) != software {
+                    count += 1;
+                    first.get_or_insert((a, b, hr, hf, software));
+                }
+            }
+            print!("  {:>2}-bit {}  ", width, op.symbol());
+            match first {
+                None => println!("{}", tr("all agree")),
+                Some((a, b, hr, hf, (sr, sf))) => {
+                    println!("{} {} {} {}: CPU {} [{}], alu {} [{}]", fill(tr("{} differ, first"), &[&count]), hex(a, width), op.symbol(), hex(b, width),
+                        hex(hr, width), describe(hf), hex(sr, width), describe(sf));
+                }
+            }
+            differ += count;
+        }
+    }
+    if differ > 0 {
+        process::exit(1);
+    }
+    print!("{}", tr(EXACTLY));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("x86-64: add/sub, then pushfq; pop copies RFLAGS into a register",
+        "x86-64: add/sub, deretter kopierer pushfq; pop RFLAGS inn i et register"),
+    ("AArch64: adds/subs at the top of a word, then mrs reads NZCV", "AArch64: adds/subs øverst i et ord, deretter leser mrs NZCV"),
+    ("no inline assembly for {}: the software flags only", "ingen inline assembly for {}: bare programvareflaggene"),
+    ("agree (C set: no borrow)", "enige (C satt: ingen lån)"),
+    ("agree (C clear: a borrow)", "enige (C ikke satt: et lån)"),
+    ("agree", "enige"),
+    ("{} random operands per width and operation:", "{} tilfeldige operander per bredde og operasjon:"),
+    ("all agree", "alle er enige"),
+    ("{} differ, first", "{} er uenige, første"),
+    (EXACTLY, "
+Programvare-ALU-en regner ut nøyaktig de flaggene maskinvaren setter: mente fra
+summen uten fortegn, overflyt fra fortegnene (like operander, ulikt resultat).
+"),
+];
@@ -0,0 +1,296 @@
+/*
+ * compare_langs - The same program in C, Rust and Python, and what each prints
+ * Every topic in overflow_demo/snippets/ is one small program written three
+ * times: <topic>.c, <topic>.rs and <topic>.py. Each prints one line per
+ * case, `case = value`, under the same case names. This compiles and runs
+ * them all and puts the answers in one table, row by row:
+ *
+ *   case                   C -O0        C -O2        Rust debug         Rust release  Python
+ *   ---------------------  -----------  -----------  -----------------  ------------  ----------  -
+ *   i32 max + 1            -2147483648  -2147483648  panic: add with …  -2147483648   2147483648  *
+ *   i32 max + 1 > i32 max  false        true         panic: add with …  false         true        *
+ *   -7 / 2                 -3           -3           -3                 -3            -3.5        *
+ *   -8 >> 1                -4           -4           -4                 -4            -4
+ *
+ * C runs at -O0 and -O2 because signed overflow is undefined behaviour:
+ * the optimizer may assume it never happens, and the two builds can then
+ * disagree on the same source. Rust runs with overflow checks on (debug,
+ * a panic) and off (release, wrapping). Python's int has no width at all.
+ * A * marks a row where they do not all agree.
+ *
+ * A case that crashes its program shows how the program ended; C's x / 0
+ * is caught as SIGFPE in the snippet itself, so the rest still run. A
+ * language whose compiler is missing is left out, with a note. Any other
+ * <topic>.{c,rs,py} put in the directory is one more topic. The notes are
+ * printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin compare_langs -- [topic ...] [--dir DIR] [--format text|json|markdown|latex] [--lang no]
+ *      (CC and PYTHON choose the C compiler and the interpreter)
+ */
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, ExitStatus};
+
+use util::args::Args;
+use util::lang::{self, fill, Catalog};
+use util::report::{Format, Json, Report};
+use util::table::Table;
+
+const SNIPPETS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/overflow_demo/snippets");
+
+// ==================== THE LANGUAGES ====================
+
+/// One column: how a file of this extension is built and run.
+struct Lang {
+    column: &'static str,
+    ext: &'static str,
+    /// The program, and the variable that overrides it.
+    tool: (&'static str, Option<&'static str>),
+    /// Arguments before the source; None for an interpreter.
+    compile: Option<&'static [&'static str]>,
+}
+
+const LANGS: [Lang; 5] = [
+    Lang { column: "C -O0", ext: "c", tool: ("cc", Some("CC")), compile: Some(&["-O0", "-w"]) },
+    Lang { column: "C -O2", ext: "c", tool: ("cc", Some("CC")), compile: Some(&["-O2", "-w"]) },
+    Lang { column: "Rust debug", ext: "rs", tool: ("rustc", None), compile: Some(&["--edition=2021", "-Copt-level=0", "-Coverflow-checks=on"]) },
+    Lang { column: "Rust release", ext: "rs", tool: ("rustc", None), compile: Some(&["--edition=2021", "-Copt-level=3", "-Coverflow-checks=off"]) },
+    Lang { column: "Python", ext: "py", tool: ("python3", Some("PYTHON")), compile: None },
+];
+
+impl Lang {
+    fn program(&self) -> String {
+        self.tool.1.and_then(|v| env::var(v).ok()).unwrap_or_else(|| self.tool.0.to_string())
+    }
+}
+
+// ==================== BUILD AND RUN ====================
+
+/// What one program printed, and how it ended if not with exit 0.
+struct Run {
+    cases: Vec<(String, String)>,
+    ended: Option<String>,
+}
+
+fn ended(status: ExitStatus) -> Option<String> {
+    if status.success() {
+        return None;
+    }
+    #[cfg(unix)]
+    if let Some(sig) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return Some(format!("killed by signal {}", sig));
+    }
+    Some(format!("exit {}", status.code().unwrap_or(-1)))
+}
+
+/// Build `src` for `lang` (in a temporary directory) and run it. Err is
+/// why there is no column: the tool is missing or the build failed.
+fn run(lang: &Lang, src: &Path, topic: &str) -> Result<Run, String> {
+    let program = lang.program();
+    let mut cmd = match lang.compile {
+        Some(flags) => {
+            let dir = env::temp_dir().join("compare_langs");
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            let exe = dir.join(format!("{}-{}", topic, lang.column.replace(' ', "")));
+            let out = Command::new(&program).args(flags).arg(src).arg("-o").arg(&exe).output()
+                .map_err(|e| format!("cannot run {}: {}", program, e))?;
+            if !out.status.success() {
+                let stderr = String::from_utf8_lossy(&out.stderr);
+                return Err(format!("{} failed on {}: {}", program, src.display(), stderr.lines().find(|l| l.contains("error")).unwrap_or("").trim()));
+            }
+            Command::new(exe)
+        }
+        None => {
+            let mut cmd = Command::new(&program);
+            cmd.arg(src);
+            cmd
+        }
+    };
+    let out = cmd.output().map_err(|e| format!("cannot run {}: {}", program, e))?;
+    let cases = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|l| l.split_once(" = ").map(|(k, v)| (k.trim().to_string(), v.trim().to_string())))
+        .collect();
+    Ok(Run { cases, ended: ended(out.status) })
+}
+
+// ==================== THE TABLE ====================
+
+/// Cells wider than this are cut in the terminal, not in a report.
+const WIDTH: usize = 30;
+
+/// text in at most width characters, cut after a whole word where there
+/// is one to cut after.
+fn cut(text: String, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text;
+    }
+    let kept: String = text.chars().take(width - 1).collect();
+    let at_space = text.chars().nth(width - 1) == Some(' ');
+    let kept = match kept.rfind(' ') {
+        Some(i) if !at_space && !kept[..i].trim_end().is_empty() => kept[..i].trim_end(),
+        _ => kept.trim_end(),
+    };
+    format!("{}…", kept)
+}
+
+/// The cases in the order the snippets print them, one column per
+/// language that ran, and a * under "differs" where they do not agree;
+/// "-" is a case a language has no line for, and is left out of that.
+/// Also the count of rows marked.
+fn compare(topic: &str, runs: &[(&Lang, Run)]) -> (Table, usize) {
+    let mut cases: Vec<&str> = Vec::new();
+    for (_, run) in runs {
+        for (case, _) in &run.cases {
+            if !cases.contains(&case.as_str()) {
+                cases.push(case);
+            }
+        }
+    }
+    let header = ["case"].into_iter().chain(runs.iter().map(|(l, _)| l.column)).chain([tr("differs")]);
+    let mut table = Table::new(topic, header);
+    let mut differ = 0;
+    for case in cases {
+        let cells: Vec<String> = runs.iter()
+            .map(|(_, run)| match run.cases.iter().find(|(c, _)| c == case) {
+                Some((_, v)) => v.clone(),
+                None => run.ended.clone().unwrap_or_else(|| "-".to_string()),
+            })
+            .collect();
+        let printed: BTreeSet<&String> = cells.iter().filter(|c| *c != "-").collect();
+        let same = printed.len() <= 1;
+        differ += usize::from(!same);
+        table.row([case.to_string()].into_iter().chain(cells).chain([if same { "" } else { "*" }.to_string()]));
+    }
+    (table, differ)
+}
+
+fn topics(dir: &Path) -> BTreeSet<String> {
+    let files = fs::read_dir(dir).unwrap_or_else(|e| fail(&format!("{}: {}", dir.display(), e)));
+    files.flatten()
+        .map(|f| f.path())
+        .filter(|p| p.extension().is_some_and(|e| LANGS.iter().any(|l| e == l.ext)))
+        .filter_map(|p| Some(p.file_stem()?.to_string_lossy().into_owned()))
+        .collect()
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("compare_langs: {}", message);
+    process::exit(1)
+}
+
+const USAGE: &str = "compare_langs [topic ...] [--dir DIR] [--format text|json|markdown|latex] [--lang no|en]";
+
+// ==================== REPORTING ====================
+
+struct Results {
+    /// one table per topic, with the number of its rows marked
+    topics: Vec<(Table, usize)>,
+    notes: BTreeSet<String>,
+}
+
+impl Results {
+    fn differ(&self) -> usize {
+        self.topics.iter().map(|(_, n)| n).sum()
+    }
+
+    fn total(&self) -> usize {
+        self.topics.iter().map(|(t, _)| t.rows.len()).sum()
+    }
+}
+
+impl Report for Results {
+    fn text(&self) -> String {
+        let mut s = String::from("=== Same Program, Different Semantics: C, Rust and Python ===\n\n");
+        for (table, _) in &self.topics {
+            let mut table = table.clone();
+            for row in &mut table.rows {
+                for cell in row.iter_mut().skip(1) {
+                    *cell = cut(std::mem::take(cell), WIDTH);
+                }
+            }
+            s.push_str(&table.text());
+            s.push('\n');
+        }
+        for note in &self.notes {
+            s.push_str(&format!("{} {}\n", tr("note:"), note));
+        }
+        s.push_str(&fill(tr("{} of {} cases do not print the same in every language and build (*).\n"),
+            &[&self.differ(), &self.total()]));
+        s
+    }
+
+    fn json(&self) -> Json {
+        Json::object()
+            .with("topics", Json::array(self.topics.iter().map(|(table, n)| {
+                // the last column is the * marker ("differs"), told by "same" here
+                let columns = &table.header[1..table.header.len() - 1];
+                Json::object()
+                    .with("topic", table.caption.as_str())
+                    .with("cases", Json::array(table.rows.iter().map(|row| {
+                        let values = columns.iter().zip(&row[1..]).fold(Json::object(), |o, (c, v)| o.with(c, v.as_str()));
+                        Json::object().with("case", row[0].as_str()).with("values", values).with("same", row[row.len() - 1].is_empty())
+                    })))
+                    .with("differ", *n)
+            })))
+            .with("notes", Json::array(self.notes.iter().map(String::as_str)))
+            .with("differ", self.differ())
+            .with("total", self.total())
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        self.topics.iter().map(|(t, _)| t.clone()).collect()
+    }
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    // --markdown is the older spelling of --format markdown
+    let parsed = Args::parse(env::args().skip(1), &["--dir", "--format", "--lang"]).and_then(|mut args| {
+        lang::set_from(args.string("--lang").as_deref())?;
+        let dir = args.string("--dir").map_or_else(|| PathBuf::from(SNIPPETS), PathBuf::from);
+        let format = if args.flag(&["--markdown"]) { Format::Markdown } else { Format::from_args(&mut args)? };
+        Ok((dir, format, args.finish()?))
+    });
+    let (dir, format, wanted) = parsed.unwrap_or_else(|e| fail(&format!("{}\nusage: {}", e, USAGE)));
+    let all = topics(&dir);
+    if let Some(bad) = wanted.iter().find(|t| !all.contains(*t)) {
+        fail(&format!("no snippets for {} in {}; there are: {}", bad, dir.display(), all.iter().cloned().collect::<Vec<_>>().join(", ")));
+    }
+    let chosen: Vec<&String> = all.iter().filter(|t| wanted.is_empty() || wanted.contains(t)).collect();
+
+    let mut results = Results { topics: Vec::new(), notes: BTreeSet::new() };
+    for topic in chosen {
+        let mut runs = Vec::new();
+        for lang in &LANGS {
+            let src = dir.join(format!("{}.{}", topic, lang.ext));
+            if !src.exists() {
+                continue;
+            }
+            match run(lang, &src, topic) {
+                Ok(r) => runs.push((lang, r)),
+                Err(e) => {
+                    results.notes.insert(fill(tr("{} left out: {}"), &[&lang.column, &e]));
+                }
+            }
+        }
+        results.topics.push(compare(topic, &runs));
+    }
+    format.print(&results);
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("note:", "merk:"),
+    ("differs", "ulik"),
+    ("{} of {} cases do not print the same in every language and build (*).\n",
+     "{} av {} tilfeller skriver ikke det samme i alle språk og bygg (*).\n"),
+    ("{} left out: {}", "{} utelatt: {}"),
+];
@@ -0,0 +1,267 @@
+/*
+ * From Integer Overflow to Memory Corruption
+ * A parser takes a message of `count` records of `size` bytes each and
+ * copies them into one buffer. The length is computed in 16 bits, as a C
+ * parser with a uint16_t length field would:
+ *
+ *   uint16_t total = count * size;           4097 * 16 = 65552 -> 16
+ *   char *buf = malloc(total);               16 bytes
+ *   for (i = 0; i < count; i++)
+ *       memcpy(buf + i * size, rec[i], size);    65552 bytes copied
+ *
+ * The multiplication wraps, the buffer is tiny, and the copy loop - which
+ * counts records, not bytes - writes far past its end into whatever the
+ * allocator put next. Here that is a session object holding the user name
+ * and role, so the attacker's records become "root" and "admin". The same
+ * shape was OpenSSH's challenge-response hole in 2002 (nresp * sizeof(char
+ * *)) and many image and font parsers since.
+ *
+ *   heap   [hdr|buf 16 B][hdr|session: user "guest", role user][hdr|free]
+ *   after  [hdr|AAAA....][hdr|session: user "root",  role admin][...]
+ *
+ * 1) is the C code written in unsafe Rust, on a simulated heap inside one
+ *    Vec, so the corruption can be shown and stays inside memory we own:
+ *    the copy stops at the end of the simulated heap, where C would run on.
+ * 2) is the same parser in safe Rust, three ways: checked_mul turns the
+ *    overflow into an error, plain `*` panics in a debug build, and even
+ *    with the wrapped length the slice bounds check panics at the first
+ *    record past the end - a crash instead of a takeover.
+ *
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin length_overflow     (release: * wraps, the slice check stops it)
+ *      cargo run --bin length_overflow               (debug: * itself panics)
+ *      [--lang no]
+ */
+
+use std::panic;
+use std::ptr;
+
+use util::lang::{self, fill, Catalog};
+
+const HEAP: usize = 96;
+const HEADER: usize = 8;
+const ROLE_ADMIN: u32 = 1;
+
+// ==================== THE MESSAGE ====================
+
+/// count: u16, size: u16, then count records of size bytes.
+fn message(count: u16, size: u16, records: impl Fn(usize) -> Vec<u8>) -> Vec<u8> {
+    let mut m = Vec::new();
+    m.extend(count.to_le_bytes());
+    m.extend(size.to_le_bytes());
+    for i in 0..count as usize {
+        let mut r = records(i);
+        r.resize(size as usize, b'A');
+        m.extend(r);
+    }
+    m
+}
+
+fn fields(msg: &[u8]) -> (u16, u16) {
+    (u16::from_le_bytes([msg[0], msg[1]]), u16::from_le_bytes([msg[2], msg[3]]))
+}
+
+// ==================== A SIMULATED HEAP ====================
+
+/// Chunks of [size: u32, used: u32][size bytes], first fit, in one Vec.
+struct Heap {
+    bytes: Vec<u8>,
+}
+
+impl Heap {
+    /// A free 16-byte chunk, the session, and the rest free.
+    fn new() -> (Heap, usize) {
+        let mut heap = Heap { bytes: vec![0; HEAP] };
+        heap.header(0, 16, false);
+        heap.header(HEADER + 16, 16, true);
+        heap.header(2 * (HEADER + 16), (HEAP - 3 * HEADER - 32) as u32, false);
+        let session = 2 * HEADER + 16;
+        heap.bytes[session..session + 5].copy_from_slice(b"guest");
+        (heap, session)
+    }
+
+    fn header(&mut self, at: usize, size: u32, used: bool) {
+        self.bytes[at..at + 4].copy_from_slice(&size.to_le_bytes());
+        self.bytes[at + 4..at + 8].copy_from_slice(&u32::from(used).to_le_bytes());
+    }
+
+    fn word(&self, at: usize) -> u32 {
+        u32::from_le_bytes([self.bytes[at], self.bytes[at + 1], self.bytes[at + 2], self.bytes[at + 3]])
+    }
+
+    /// Offset of the data of the first free chunk that fits.
+    fn alloc(&mut self, n: usize) -> Option<usize> {
+        let mut at = 0;
+        while at + HEADER <= HEAP {
+            let size = self.word(at) as usize;
+            if self.word(at + 4) == 0 && size >= n {
+                self.bytes[at + 4] = 1;
+                return Some(at + HEADER);
+            }
+            at += HEADER + size.max(1);
+        }
+        None
+    }
+
+    /// (user, role) as the session object stores them: 12 bytes of name, a u32.
+    fn session(&self, at: usize) -> (String, u32) {
+        let name = &self.bytes[at..at + 12];
+        let end = name.iter().position(|&b| b == 0).unwrap_or(12);
+        (String::from_utf8_lossy(&name[..end]).into_owned(), self.word(at + 12))
+    }
+
+    fn dump(&self, label: &str) {
+        for (i, row) in self.bytes.chunks(16).enumerate() {
+            let hex: Vec<String> = row.iter().map(|b| format!("{:02x}", b)).collect();
+            let text: String = row.iter().map(|&b| if b.is_ascii_graphic() { b as char } else { '.' }).collect();
+            println!("   {:<7} {:04x}  {}  {}", if i == 0 { label } else { "" }, i * 16, hex.join(" "), text);
+        }
+    }
+}
+
+fn role(r: u32) -> &'static str {
+    if r == ROLE_ADMIN { "admin" } else { "user" }
+}
+
+// ==================== 1) THE C PARSER, IN UNSAFE RUST ====================
+
+/// What the C code does, pointer for pointer. Returns the bytes written.
+fn parse_c_style(heap: &mut Heap, msg: &[u8]) -> usize {
+    let (count, size) = fields(msg);
+    let total = count.wrapping_mul(size); // uint16_t arithmetic
+    let Some(buf) = heap.alloc(total as usize) else { return 0 };
+    let base = heap.bytes.as_mut_ptr();
+    let mut written = 0;
+    for i in 0..count as usize {
+        let at = buf + i * size as usize;
+        // C has no such check; it is what keeps this demo inside the Vec
+        let n = (size as usize).min(HEAP.saturating_sub(at));
+        if n == 0 {
+            break;
+        }
+        // SAFETY: at + n <= HEAP, the length of heap.bytes, and the message
+        // holds every record; the bounds C does not have are checked above
+        unsafe { ptr::copy_nonoverlapping(msg.as_ptr().add(4 + i * size as usize), base.add(at), n) };
+        written += n;
+    }
+    written
+}
+
+// ==================== 2) THE SAME PARSER IN SAFE RUST ====================
+
+/// The length checked: an overflow is a malformed message.
+fn parse_checked(msg: &[u8]) -> Result<Vec<u8>, String> {
+    let (count, size) = fields(msg);
+    let total = count.checked_mul(size).ok_or_else(|| format!("{} records of {} bytes do not fit a 16-bit length", count, size))?;
+    let data = msg.get(4..4 + total as usize).ok_or("message shorter than its header says")?;
+    Ok(data.to_vec())
+}
+
+/// The length computed with a plain *, the copy through slices.
+fn parse_plain(msg: &[u8]) -> Vec<u8> {
+    let (count, size) = fields(msg);
+    let total = std::hint::black_box(count) * size;
+    let mut buf = vec![0; total as usize];
+    let size = size as usize;
+    for i in 0..count as usize {
+        buf[i * size..(i + 1) * size].copy_from_slice(&msg[4 + i * size..4 + (i + 1) * size]);
+    }
+    buf
+}
+
+/// parse_plain with a wrapping_mul, the C arithmetic on purpose.
+fn parse_wrapping(msg: &[u8]) -> Vec<u8> {
+    let (count, size) = fields(msg);
+    let mut buf = vec![0; count.wrapping_mul(size) as usize];
+    let size = size as usize;
+    for i in 0..count as usize {
+        buf[i * size..(i + 1) * size].copy_from_slice(&msg[4 + i * size..4 + (i + 1) * size]);
+    }
+    buf
+}
+
+/// Ok(bytes) or the panic message.
+fn caught(f: impl FnOnce() -> Vec<u8> + panic::UnwindSafe) -> Result<usize, String> {
+    panic::catch_unwind(f).map(|v| v.len()).map_err(|e| {
+        e.downcast_ref::<String>().cloned().or_else(|| e.downcast_ref::<&str>().map(|s| s.to_string())).unwrap_or_default()
+    })
+}
+
+const C_WOULD_GO_ON: &str = "   {} bytes written into a {}-byte buffer before the end of the heap; C would go on
+   for the other {} bytes, into whatever memory follows.
+";
+
+const CANNOT: &str = "
+In this {} build nothing is written out of bounds. Safe Rust can still
+compute the wrong length; what it cannot do is write past a buffer with it.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    lang::args("length_overflow [--lang no|en]");
+    println!("=== From Integer Overflow to Memory Corruption ===\n");
+    let honest = message(3, 16, |i| format!("record {}", i).into_bytes());
+    // record 0 fills the buffer, 1 rewrites the session's header and name, 2 its role
+    let evil = message(4097, 16, |i| match i {
+        1 => [&16u32.to_le_bytes()[..], &1u32.to_le_bytes(), b"root\0\0\0\0"].concat(),
+        2 => [&[0u8; 4][..], &ROLE_ADMIN.to_le_bytes(), b"AAAAAAAA"].concat(),
+        _ => Vec::new(),
+    });
+    println!("   {:<8} {:>6} {:>6} {:>13} {:>10}", "message", "count", "size", "count * size", "as u16");
+    for (name, m) in [("honest", &honest), ("evil", &evil)] {
+        let (count, size) = fields(m);
+        println!("   {:<8} {:>6} {:>6} {:>13} {:>10}", name, count, size, count as u32 * size as u32, count.wrapping_mul(size));
+    }
+
+    println!("\n{}", fill(tr("1) unsafe Rust, line for line the C parser, on a {}-byte simulated heap:"), &[&HEAP]));
+    let (mut heap, session) = Heap::new();
+    heap.dump("before");
+    let (user, r) = heap.session(session);
+    println!("   session: user \"{}\", role {}", user, role(r));
+    let written = parse_c_style(&mut heap, &evil);
+    heap.dump("after");
+    let (user, r) = heap.session(session);
+    println!("   session: user \"{}\", role {}", user, role(r));
+    let (count, size) = fields(&evil);
+    print!("{}", fill(tr(C_WOULD_GO_ON), &[&written, &count.wrapping_mul(size), &(count as usize * size as usize - written)]));
+
+    println!("\n{}", tr("2) safe Rust:"));
+    panic::set_hook(Box::new(|_| {}));
+    match parse_checked(&honest) {
+        Ok(data) => println!("   checked_mul, honest   {} bytes", data.len()),
+        Err(e) => println!("   checked_mul, honest   {} {}", tr("error:"), e),
+    }
+    match parse_checked(&evil) {
+        Ok(data) => println!("   checked_mul, evil     {} bytes", data.len()),
+        Err(e) => println!("   checked_mul, evil     {} {}", tr("error:"), e),
+    }
+    let build = if cfg!(debug_assertions) { "debug" } else { "release" };
+    for (name, result) in [("plain *", caught(|| parse_plain(&evil))), ("wrapping_mul", caught(|| parse_wrapping(&evil)))] {
+        match result {
+            Ok(n) => println!("   {:<13} evil     {} bytes", name, n),
+            Err(e) => println!("   {:<13} evil     panic: {}", name, e),
+        }
+    }
+    let _ = panic::take_hook();
+    print!("{}", fill(tr(CANNOT), &[&build]));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("1) unsafe Rust, line for line the C parser, on a {}-byte simulated heap:",
+        "1) unsafe Rust, linje for linje C-parseren, på en simulert heap på {} byte:"),
+    (C_WOULD_GO_ON, "   {} byte skrevet inn i en buffer på {} byte før slutten av heapen; C ville fortsatt
+   med de andre {} bytene, inn i det minnet som kommer etter.
+"),
+    ("2) safe Rust:", "2) trygg Rust:"),
+    ("error:", "feil:"),
+    (CANNOT, "
+I dette {}-bygget skrives ingenting utenfor grensene. Trygg Rust kan fortsatt
+regne ut feil lengde; det den ikke kan, er å skrive forbi en buffer med den.
+"),
+];
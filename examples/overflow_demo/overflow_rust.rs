@@ -2,12 +2,30 @@
  * Arithmetic Overflow in Rust
  * Rust panics on overflow in debug mode, wraps in release mode
  * Can use checked/wrapping/saturating operations for explicit behavior
+ * The notes are printed in Norwegian with --lang no (util::lang).
  * 
- * Compile with: rustc overflow_rust.rs -o overflow_rust
- * Or: cargo build --release (for release mode - wrapping behavior)
+ * Run: cargo run --bin overflow_rust             (debug: overflow checks on)
+ * Or:  cargo run --release --bin overflow_rust   (release mode - wrapping behavior)
  */
 
+use util::lang::{self, Catalog};
+
+const SUMMARY: &str = "\
+Rust handles overflow:
+- Debug mode: Panics on overflow
+- Release mode: Wraps silently (for performance)
+- Checked operations: Return Option (Some/None)
+- Wrapping operations: Explicitly wrap
+- Saturating operations: Clamp at min/max
+- Overflowing operations: Return (result, overflow_flag)
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
 fn main() {
+    lang::args("overflow_rust [--lang no|en]");
     println!("=== Integer Overflow in Rust ===\n");
     
     // In debug mode, this would panic. In release mode, it wraps.
@@ -17,29 +35,29 @@ fn main() {
     
     // Using wrapping_add to explicitly wrap
     let wrapped = max_i32.wrapping_add(1);
-    println!("i32::MAX.wrapping_add(1) = {} (wraps)", wrapped);
+    println!("i32::MAX.wrapping_add(1) = {} {}", wrapped, tr("(wraps)"));
     
     let min_i32 = i32::MIN;
     println!("\ni32::MIN = {}", min_i32);
-    println!("i32::MIN.wrapping_sub(1) = {} (wraps)", min_i32.wrapping_sub(1));
+    println!("i32::MIN.wrapping_sub(1) = {} {}", min_i32.wrapping_sub(1), tr("(wraps)"));
     
     // Checked operations return Option
     println!("\n--- Checked Operations ---");
     match max_i32.checked_add(1) {
         Some(result) => println!("checked_add(MAX, 1) = Some({})", result),
-        None => println!("checked_add(MAX, 1) = None (overflow detected!)"),
+        None => println!("checked_add(MAX, 1) = None {}", tr("(overflow detected!)")),
     }
     
     match max_i32.checked_mul(2) {
         Some(result) => println!("checked_mul(MAX, 2) = Some({})", result),
-        None => println!("checked_mul(MAX, 2) = None (overflow detected!)"),
+        None => println!("checked_mul(MAX, 2) = None {}", tr("(overflow detected!)")),
     }
     
     // Saturating operations clamp at min/max
     println!("\n--- Saturating Operations ---");
-    println!("saturating_add(MAX, 1) = {} (clamped to MAX)", max_i32.saturating_add(1));
-    println!("saturating_add(MAX, 100) = {} (clamped to MAX)", max_i32.saturating_add(100));
-    println!("saturating_sub(MIN, 1) = {} (clamped to MIN)", min_i32.saturating_sub(1));
+    println!("saturating_add(MAX, 1) = {} {}", max_i32.saturating_add(1), tr("(clamped to MAX)"));
+    println!("saturating_add(MAX, 100) = {} {}", max_i32.saturating_add(100), tr("(clamped to MAX)"));
+    println!("saturating_sub(MIN, 1) = {} {}", min_i32.saturating_sub(1), tr("(clamped to MIN)"));
     
     // Overflowing operations return (result, overflow_flag)
     println!("\n--- Overflowing Operations ---");
@@ -53,13 +71,13 @@ fn main() {
     println!("\n--- Unsigned Integer Overflow ---");
     let max_u32 = u32::MAX;
     println!("u32::MAX = {}", max_u32);
-    println!("u32::MAX.wrapping_add(1) = {} (wraps to 0)", max_u32.wrapping_add(1));
+    println!("u32::MAX.wrapping_add(1) = {} {}", max_u32.wrapping_add(1), tr("(wraps to 0)"));
     
     // Floating point overflow
     println!("\n--- Floating Point Overflow ---");
     let max_f32 = f32::MAX;
     println!("f32::MAX = {}", max_f32);
-    println!("f32::MAX * 2.0 = {} (infinity)", max_f32 * 2.0);
+    println!("f32::MAX * 2.0 = {} {}", max_f32 * 2.0, tr("(infinity)"));
     println!("f32::MAX * 2.0 is infinite: {}", (max_f32 * 2.0).is_infinite());
     
     // Overflow in a loop
@@ -72,12 +90,26 @@ fn main() {
     }
     
     println!("\n=== Summary ===");
-    println!("Rust handles overflow:");
-    println!("- Debug mode: Panics on overflow");
-    println!("- Release mode: Wraps silently (for performance)");
-    println!("- Checked operations: Return Option (Some/None)");
-    println!("- Wrapping operations: Explicitly wrap");
-    println!("- Saturating operations: Clamp at min/max");
-    println!("- Overflowing operations: Return (result, overflow_flag)");
+    print!("{}", tr(SUMMARY));
 }
 
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("(wraps)", "(går rundt)"),
+    ("(overflow detected!)", "(overflyt oppdaget!)"),
+    ("(clamped to MAX)", "(holdt ved MAX)"),
+    ("(clamped to MIN)", "(holdt ved MIN)"),
+    ("(wraps to 0)", "(går rundt til 0)"),
+    ("(infinity)", "(uendelig)"),
+    (SUMMARY, "\
+Slik håndterer Rust overflyt:
+- Debug-modus: panikk ved overflyt
+- Release-modus: går stille rundt (for ytelsens skyld)
+- Checked-operasjoner: returnerer Option (Some/None)
+- Wrapping-operasjoner: går rundt, uttrykkelig
+- Saturating-operasjoner: holdes ved min/maks
+- Overflowing-operasjoner: returnerer (resultat, overflytflagg)
+"),
+];
+
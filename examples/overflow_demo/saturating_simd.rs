@@ -0,0 +1,288 @@
+/*
+ * Saturating Arithmetic with SIMD
+ * Brightening an image is "add a constant to every pixel". With u8 pixels
+ * the plain add WRAPS: 250 + 10 = 4, so the brightest areas turn black.
+ * Image code wants SATURATION instead: 250 + 10 = 255.
+ *
+ *   wrapping:    250 + 10 = 260 mod 256 =   4   (white → black)
+ *   saturating:  250 + 10 = min(260, 255) = 255 (white stays white)
+ *
+ * SIMD instruction sets have saturating adds built in, on 16 or 32 bytes
+ * at once - one instruction instead of 16 adds, compares and selects:
+ *
+ *   SSE2   paddusb  _mm_adds_epu8      16 x u8
+ *   AVX2   vpaddusb _mm256_adds_epu8   32 x u8
+ *   NEON   uqadd    vqaddq_u8          16 x u8
+ *
+ * This demo brightens a 1920x1080 image with a scalar loop, with SWAR
+ * ("SIMD within a register": 8 bytes in a u64, plain integer ops), and
+ * with the intrinsics of the machine it runs on; every version is
+ * checked against the scalar result.
+ *
+ * std::simd (portable SIMD) is nightly-only; build with the portable_simd
+ * feature on nightly to add it to the comparison. The explanations are
+ * printed in Norwegian with --lang no (util::lang).
+ *
+ * Run:     cargo run --release --bin saturating_simd -- [brightness] [--lang no]
+ * Nightly: cargo +nightly run --release --features portable_simd --bin saturating_simd
+ */
+
+#![cfg_attr(feature = "portable_simd", feature(portable_simd))]
+
+use arithmetic::swar::{add_sat_u8x8, splat};
+use std::hint::black_box;
+use std::time::Instant;
+use util::lang::{self, fill, Catalog};
+
+const WIDTH: usize = 1920;
+const HEIGHT: usize = 1080;
+
+// ==================== SCALAR ====================
+
+#[inline(never)]
+fn brighten_wrapping(src: &[u8], dst: &mut [u8], k: u8) {
+    for (d, &s) in dst.iter_mut().zip(src) {
+        *d = s.wrapping_add(k);
+    }
+}
+
+/// One pixel at a time, with the compare-and-select spelled out. The
+/// optimizer would vectorize `saturating_add` on its own; the explicit
+/// branch stands for what a CPU without saturating instructions must do.
+#[inline(never)]
+fn brighten_scalar(src: &[u8], dst: &mut [u8], k: u8) {
+    for (d, &s) in dst.iter_mut().zip(src) {
+        let sum = black_box(s as u16 + k as u16);
+        *d = if sum > 255 { 255 } else { sum as u8 };
+    }
+}
+
+// ==================== SWAR ====================
+
+// Eight saturating byte adds per u64: arithmetic::swar::add_sat_u8x8.
+
+#[inline(never)]
+fn brighten_swar(src: &[u8], dst: &mut [u8], k: u8) {
+    let kk = splat(k);
+    let chunks = src.len() / 8;
+    for i in 0..chunks {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&src[i * 8..i * 8 + 8]);
+        let out = add_sat_u8x8(black_box(u64::from_le_bytes(word)), kk);
+        dst[i * 8..i * 8 + 8].copy_from_slice(&out.to_le_bytes());
+    }
+    for i in chunks * 8..src.len() {
+        dst[i] = src[i].saturating_add(k);
+    }
+}
+
+// ==================== INTRINSICS ====================
+
+#[cfg(target_arch = "x86_64")]
+mod native {
+    use std::arch::x86_64::*;
+
+    pub const NAME: &str = "SSE2 _mm_adds_epu8";
+
+    pub fn brighten(src: &[u8], dst: &mut [u8], k: u8) {
+        let chunks = src.len() / 16;
+        unsafe {
+            let kk = _mm_set1_epi8(k as i8);
+            for i in 0..chunks {
+                let v = _mm_loadu_si128(src.as_ptr().add(i * 16) as *const __m128i);
+                _mm_storeu_si128(dst.as_mut_ptr().add(i * 16) as *mut __m128i, _mm_adds_epu8(v, kk));
+            }
+        }
+        for i in chunks * 16..src.len() {
+            dst[i] = src[i].saturating_add(k);
+        }
+    }
+
+    /// None if the CPU has no AVX2 (checked at run time).
+    pub fn brighten_wide(src: &[u8], dst: &mut [u8], k: u8) -> Option<&'static str> {
+        if !is_x86_feature_detected!("avx2") {
+            return None;
+        }
+        unsafe { avx2(src, dst, k) };
+        Some("AVX2 _mm256_adds_epu8")
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn avx2(src: &[u8], dst: &mut [u8], k: u8) {
+        let chunks = src.len() / 32;
+        let kk = _mm256_set1_epi8(k as i8);
+        for i in 0..chunks {
+            let v = _mm256_loadu_si256(src.as_ptr().add(i * 32) as *const __m256i);
+            _mm256_storeu_si256(dst.as_mut_ptr().add(i * 32) as *mut __m256i, _mm256_adds_epu8(v, kk));
+        }
+        for i in chunks * 32..src.len() {
+            dst[i] = src[i].saturating_add(k);
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod native {
+    use std::arch::aarch64::*;
+
+    pub const NAME: &str = "NEON vqaddq_u8";
+
+    pub fn brighten(src: &[u8], dst: &mut [u8], k: u8) {
+        let chunks = src.len() / 16;
+        unsafe {
+            let kk = vdupq_n_u8(k);
+            for i in 0..chunks {
+                let v = vld1q_u8(src.as_ptr().add(i * 16));
+                vst1q_u8(dst.as_mut_ptr().add(i * 16), vqaddq_u8(v, kk));
+            }
+        }
+        for i in chunks * 16..src.len() {
+            dst[i] = src[i].saturating_add(k);
+        }
+    }
+
+    pub fn brighten_wide(_src: &[u8], _dst: &mut [u8], _k: u8) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod native {
+    pub const NAME: &str = "saturating_add (no intrinsics for this target)";
+
+    pub fn brighten(src: &[u8], dst: &mut [u8], k: u8) {
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d = s.saturating_add(k);
+        }
+    }
+
+    pub fn brighten_wide(_src: &[u8], _dst: &mut [u8], _k: u8) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(feature = "portable_simd")]
+fn brighten_portable(src: &[u8], dst: &mut [u8], k: u8) {
+    use std::simd::num::SimdUint;
+    use std::simd::u8x32;
+    let kk = u8x32::splat(k);
+    let chunks = src.len() / 32;
+    for i in 0..chunks {
+        let v = u8x32::from_slice(&src[i * 32..]);
+        v.saturating_add(kk).copy_to_slice(&mut dst[i * 32..]);
+    }
+    for i in chunks * 32..src.len() {
+        dst[i] = src[i].saturating_add(k);
+    }
+}
+
+// ==================== MAIN ====================
+
+/// Gradient with a bright sun in the middle: plenty of pixels near 255.
+fn test_image() -> Vec<u8> {
+    let mut img = vec![0u8; WIDTH * HEIGHT];
+    let (cx, cy) = (WIDTH as i64 / 2, HEIGHT as i64 / 2);
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let (dx, dy) = (x as i64 - cx, y as i64 - cy);
+            let base = (x * 200 / WIDTH) as i64;
+            let sun = 255 - (dx * dx + dy * dy) / 400;
+            img[y * WIDTH + x] = base.max(sun).clamp(0, 255) as u8;
+        }
+    }
+    img
+}
+
+/// Best of five runs, as pixels per nanosecond.
+fn throughput<F: Fn(&[u8], &mut [u8], u8)>(src: &[u8], dst: &mut [u8], k: u8, f: F) -> f64 {
+    let mut best = f64::MAX;
+    for _ in 0..5 {
+        let start = Instant::now();
+        for _ in 0..10 {
+            f(black_box(src), dst, k);
+        }
+        best = best.min(start.elapsed().as_nanos() as f64 / 10.0);
+        black_box(&dst);
+    }
+    src.len() as f64 / best
+}
+
+fn report(name: &str, rate: f64, scalar: f64, ok: bool) {
+    println!("   {:<28} {:>6.2} Gpixel/s {:>6.1}x   {}", name, rate, rate / scalar, tr(if ok { "matches scalar" } else { "MISMATCH" }));
+}
+
+const BANDWIDTH: &str = "
+   Same answer, far fewer instructions: data-level parallelism. At this
+   speed the loop is limited by memory bandwidth (2 MB in, 2 MB out), not
+   by arithmetic - wider registers stop helping once the data stops fitting
+   in cache.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("saturating_simd [brightness] [--lang no|en]");
+    let k: u8 = args.first().and_then(|s| s.parse().ok()).unwrap_or(60);
+    println!("=== Saturating u8 Adds: Scalar vs SIMD ===\n");
+
+    println!("{}", fill(tr("1) One pixel, brightness +{}:"), &[&k]));
+    for &p in [100u8, 200, 250].iter() {
+        println!("   {}", fill(tr("{} → wrapping {}   saturating {}"),
+            &[&format!("{:3}", p), &format!("{:3}", p.wrapping_add(k)), &format!("{:3}", p.saturating_add(k))]));
+    }
+
+    let src = test_image();
+    let n = src.len();
+    let mut reference = vec![0u8; n];
+    let mut dst = vec![0u8; n];
+
+    brighten_wrapping(&src, &mut dst, k);
+    let wrapped = src.iter().zip(&dst).filter(|&(s, d)| d < s).count();
+    println!("\n{}", fill(tr("2) {}x{} image ({} pixels), wrapping add:"), &[&WIDTH, &HEIGHT, &n]));
+    println!("   {}", fill(tr("{} pixels ({}%) got DARKER - the sun has a black ring"),
+        &[&wrapped, &format!("{:.1}", 100.0 * wrapped as f64 / n as f64)]));
+
+    println!("\n{}", tr("3) Saturating add, throughput (best of 5):"));
+    let scalar = throughput(&src, &mut reference, k, brighten_scalar);
+    report("scalar compare + select", scalar, scalar, true);
+
+    let rate = throughput(&src, &mut dst, k, brighten_swar);
+    report("SWAR, 8 bytes per u64", rate, scalar, dst == reference);
+
+    let rate = throughput(&src, &mut dst, k, native::brighten);
+    report(native::NAME, rate, scalar, dst == reference);
+
+    if let Some(name) = native::brighten_wide(&src, &mut dst, k) {
+        let ok = dst == reference;
+        let rate = throughput(&src, &mut dst, k, |s, d, k| { native::brighten_wide(s, d, k); });
+        report(name, rate, scalar, ok);
+    }
+
+    #[cfg(feature = "portable_simd")]
+    {
+        let rate = throughput(&src, &mut dst, k, brighten_portable);
+        report("std::simd u8x32", rate, scalar, dst == reference);
+    }
+
+    print!("{}", tr(BANDWIDTH));
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("matches scalar", "lik skalar"),
+    ("MISMATCH", "ULIK"),
+    ("1) One pixel, brightness +{}:", "1) Én piksel, lysstyrke +{}:"),
+    ("{} → wrapping {}   saturating {}", "{} → wrapping {}   metning {}"),
+    ("2) {}x{} image ({} pixels), wrapping add:", "2) {}x{}-bilde ({} piksler), wrapping-addisjon:"),
+    ("{} pixels ({}%) got DARKER - the sun has a black ring", "{} piksler ({}%) ble MØRKERE - solen har en svart ring"),
+    ("3) Saturating add, throughput (best of 5):", "3) Mettende addisjon, gjennomstrømning (beste av 5):"),
+    (BANDWIDTH, "
+   Samme svar, langt færre instruksjoner: dataparallellitet. Ved denne
+   farten begrenses løkken av minnebåndbredden (2 MB inn, 2 MB ut), ikke
+   av aritmetikken - bredere registre slutter å hjelpe når dataene ikke
+   lenger får plass i cachen.
+"),
+];
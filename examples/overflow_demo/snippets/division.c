@@ -0,0 +1,39 @@
+/* division - what / and % do with negative operands, by zero and at the
+ * one quotient that does not fit, in C. Division truncates toward zero
+ * (since C99); x / 0 and INT_MIN / -1 are undefined and on x86 the idiv
+ * instruction traps, which arrives as SIGFPE. */
+#include <limits.h>
+#include <setjmp.h>
+#include <signal.h>
+#include <stdio.h>
+
+static volatile int hidden;
+static int opaque(int x) { hidden = x; return hidden; }
+
+/* Jump back out of a SIGFPE, so one trapping case does not end the rest. */
+static sigjmp_buf back;
+static void on_fpe(int sig) { (void)sig; siglongjmp(back, 1); }
+
+#define CASE(name, fmt, expr) do { \
+        if (sigsetjmp(back, 1) == 0) printf("%s = " fmt "\n", name, expr); \
+        else printf("%s = SIGFPE\n", name); \
+    } while (0)
+
+int main(void) {
+    setvbuf(stdout, NULL, _IONBF, 0);
+    struct sigaction sa = { .sa_handler = on_fpe };
+    sigaction(SIGFPE, &sa, NULL);
+    int seven = opaque(7), two = opaque(2), zero = opaque(0), min = opaque(INT_MIN);
+    CASE("7 / 2", "%d", seven / two);
+    CASE("-7 / 2", "%d", -seven / two);
+    CASE("7 / -2", "%d", seven / -two);
+    CASE("-7 % 2", "%d", -seven % two);
+    CASE("7 % -2", "%d", seven % -two);
+    CASE("7 / 0", "%d", seven / zero);
+    CASE("7 % 0", "%d", seven % zero);
+    CASE("i32 min / -1", "%d", min / opaque(-1));
+    CASE("i32 min % -1", "%d", min % opaque(-1));
+    CASE("7.0 / 0.0", "%g", (double)seven / zero);
+    CASE("0.0 / 0.0", "%g", (double)zero / zero);
+    return 0;
+}
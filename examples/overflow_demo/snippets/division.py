@@ -0,0 +1,27 @@
+"""division - the same cases in Python, where / is true division and
+gives a float, // floors toward minus infinity and % takes the sign of
+the divisor; dividing by zero raises, for floats too."""
+
+I32_MIN = -2**31
+
+
+def case(name, f):
+    """One `case = value` line, or the exception raised."""
+    try:
+        print(f"{name} = {f()}")
+    except Exception as e:
+        print(f"{name} = {type(e).__name__}")
+
+
+case("7 / 2", lambda: 7 / 2)
+case("-7 / 2", lambda: -7 / 2)
+case("7 / -2", lambda: 7 / -2)
+case("-7 // 2", lambda: -7 // 2)
+case("-7 % 2", lambda: -7 % 2)
+case("7 % -2", lambda: 7 % -2)
+case("7 / 0", lambda: 7 / 0)
+case("7 % 0", lambda: 7 % 0)
+case("i32 min / -1", lambda: I32_MIN // -1)
+case("i32 min % -1", lambda: I32_MIN % -1)
+case("7.0 / 0.0", lambda: 7.0 / 0.0)
+case("0.0 / 0.0", lambda: 0.0 / 0.0)
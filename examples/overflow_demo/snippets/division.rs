@@ -0,0 +1,32 @@
+//! division - what / and % do with negative operands, by zero and at the
+//! one quotient that does not fit, in Rust. Division truncates toward zero
+//! like C; x / 0 and i32::MIN / -1 panic in every build, overflow checks
+//! or not.
+
+use std::fmt::Display;
+use std::hint::black_box;
+use std::panic::{self, UnwindSafe};
+
+/// One `case = value` line, or `case = panic: ...`.
+fn case<T: Display>(name: &str, f: impl FnOnce() -> T + UnwindSafe) {
+    match panic::catch_unwind(f) {
+        Ok(v) => println!("{} = {}", name, v),
+        Err(e) => println!("{} = panic: {}", name, e.downcast_ref::<&str>().copied().or(e.downcast_ref::<String>().map(String::as_str)).unwrap_or("?").trim_start_matches("attempt to ")),
+    }
+}
+
+fn main() {
+    panic::set_hook(Box::new(|_| {}));
+    let (seven, two, zero, min) = (black_box(7i32), black_box(2i32), black_box(0i32), black_box(i32::MIN));
+    case("7 / 2", || seven / two);
+    case("-7 / 2", || -seven / two);
+    case("7 / -2", || seven / -two);
+    case("-7 % 2", || -seven % two);
+    case("7 % -2", || seven % -two);
+    case("7 / 0", || seven / zero);
+    case("7 % 0", || seven % zero);
+    case("i32 min / -1", || min / black_box(-1));
+    case("i32 min % -1", || min % black_box(-1));
+    case("7.0 / 0.0", || seven as f64 / zero as f64);
+    case("0.0 / 0.0", || zero as f64 / zero as f64);
+}
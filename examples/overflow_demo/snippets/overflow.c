@@ -0,0 +1,37 @@
+/* overflow - signed and unsigned overflow at the edges of int, in C.
+ * Signed overflow is undefined: each line prints what this compiler made
+ * of it, which can change with -O. */
+#include <limits.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+/* A value the optimizer cannot see through, so the arithmetic happens at
+ * run time - except where the compiler may assume it never overflows. */
+static volatile int hidden;
+static int opaque(int x) { hidden = x; return hidden; }
+
+static const char *boolean(int b) { return b ? "true" : "false"; }
+
+int main(void) {
+    setvbuf(stdout, NULL, _IONBF, 0);
+    int max = opaque(INT_MAX), min = opaque(INT_MIN);
+    uint8_t b = (uint8_t)opaque(255);
+    printf("i32 max + 1 = %d\n", max + 1);
+    printf("i32 min - 1 = %d\n", min - 1);
+    printf("i32 max * 2 = %d\n", max * 2);
+    printf("i32 max * 2 / 2 = %d\n", max * 2 / 2);
+    printf("-(i32 min) = %d\n", -min);
+    printf("abs(i32 min) = %d\n", abs(min));
+    printf("u32 0 - 1 = %u\n", (unsigned)opaque(0) - 1u);
+    b += 1;
+    printf("u8 255 + 1 = %u\n", b);
+    printf("i8 127 + 1 = %d\n", (int8_t)(opaque(127) + 1));
+    int next = max + 1;
+    printf("i32 max + 1 > i32 max = %s\n", boolean(next > max));
+    int steps = 0;
+    for (int i = max - 2; i > max - 3 && steps < 10; i++)
+        steps++;
+    printf("i++ from max - 2 while i > max - 3 = %d steps\n", steps);
+    return 0;
+}
@@ -0,0 +1,35 @@
+"""overflow - the same cases in Python, whose int has no edges: the i32
+limits are only numbers, and nothing overflows."""
+
+I32_MAX, I32_MIN = 2**31 - 1, -2**31
+
+
+def case(name, f):
+    """One `case = value` line, or the exception raised."""
+    try:
+        v = f()
+        print(f"{name} = {str(v).lower() if isinstance(v, bool) else v}")
+    except Exception as e:
+        print(f"{name} = {type(e).__name__}")
+
+
+case("i32 max + 1", lambda: I32_MAX + 1)
+case("i32 min - 1", lambda: I32_MIN - 1)
+case("i32 max * 2", lambda: I32_MAX * 2)
+case("i32 max * 2 / 2", lambda: I32_MAX * 2 // 2)
+case("-(i32 min)", lambda: -I32_MIN)
+case("abs(i32 min)", lambda: abs(I32_MIN))
+case("u32 0 - 1", lambda: 0 - 1)
+case("u8 255 + 1", lambda: 255 + 1)
+case("i8 127 + 1", lambda: 127 + 1)
+case("i32 max + 1 > i32 max", lambda: I32_MAX + 1 > I32_MAX)
+
+
+def steps():
+    i, n = I32_MAX - 2, 0
+    while i > I32_MAX - 3 and n < 10:
+        i, n = i + 1, n + 1
+    return f"{n} steps"
+
+
+case("i++ from max - 2 while i > max - 3", steps)
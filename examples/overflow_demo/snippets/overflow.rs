@@ -0,0 +1,38 @@
+//! overflow - signed and unsigned overflow at the edges of i32, in Rust.
+//! Overflow panics with overflow checks on (debug) and wraps without them
+//! (release); it is never undefined.
+
+use std::fmt::Display;
+use std::hint::black_box;
+use std::panic::{self, UnwindSafe};
+
+/// One `case = value` line, or `case = panic: ...`.
+fn case<T: Display>(name: &str, f: impl FnOnce() -> T + UnwindSafe) {
+    match panic::catch_unwind(f) {
+        Ok(v) => println!("{} = {}", name, v),
+        Err(e) => println!("{} = panic: {}", name, e.downcast_ref::<&str>().copied().or(e.downcast_ref::<String>().map(String::as_str)).unwrap_or("?").trim_start_matches("attempt to ")),
+    }
+}
+
+fn main() {
+    panic::set_hook(Box::new(|_| {}));
+    let (max, min) = (black_box(i32::MAX), black_box(i32::MIN));
+    case("i32 max + 1", || max + 1);
+    case("i32 min - 1", || min - 1);
+    case("i32 max * 2", || max * 2);
+    case("i32 max * 2 / 2", || max * 2 / 2);
+    case("-(i32 min)", || -min);
+    case("abs(i32 min)", || min.abs());
+    case("u32 0 - 1", || black_box(0u32) - 1);
+    case("u8 255 + 1", || black_box(255u8) + 1);
+    case("i8 127 + 1", || black_box(127i8) + 1);
+    case("i32 max + 1 > i32 max", || max + 1 > max);
+    case("i++ from max - 2 while i > max - 3", || {
+        let (mut i, mut steps) = (max - 2, 0);
+        while i > max - 3 && steps < 10 {
+            i += 1;
+            steps += 1;
+        }
+        format!("{} steps", steps)
+    });
+}
@@ -0,0 +1,23 @@
+/* shift - shifting by the width of the type or more, by a negative count
+ * and shifting negative numbers, in C. A count outside 0..31 is undefined;
+ * x86's shl and sar use only its low 5 bits. >> of a negative int is
+ * implementation-defined, arithmetic in gcc and clang. */
+#include <limits.h>
+#include <stdio.h>
+
+static volatile int hidden;
+static int opaque(int x) { hidden = x; return hidden; }
+
+int main(void) {
+    setvbuf(stdout, NULL, _IONBF, 0);
+    int one = opaque(1);
+    printf("1 << 31 = %d\n", one << opaque(31));
+    printf("1 << 32 = %d\n", one << opaque(32));
+    printf("1 << 33 = %d\n", one << opaque(33));
+    printf("1 << -1 = %d\n", one << opaque(-1));
+    printf("-8 >> 1 = %d\n", opaque(-8) >> 1);
+    printf("-1 >> 31 = %d\n", opaque(-1) >> 31);
+    printf("u32 0x80000000 >> 31 = %u\n", (unsigned)opaque(INT_MIN) >> 31);
+    printf("u32 1 << 32 = %u\n", (unsigned)one << opaque(32));
+    return 0;
+}
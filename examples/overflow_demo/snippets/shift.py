@@ -0,0 +1,21 @@
+"""shift - the same cases in Python: << never loses a bit, since int
+grows as needed, >> is arithmetic, and a negative count raises. There is
+no u32; the unsigned cases are the same numbers, unmasked."""
+
+
+def case(name, f):
+    """One `case = value` line, or the exception raised."""
+    try:
+        print(f"{name} = {f()}")
+    except Exception as e:
+        print(f"{name} = {type(e).__name__}")
+
+
+case("1 << 31", lambda: 1 << 31)
+case("1 << 32", lambda: 1 << 32)
+case("1 << 33", lambda: 1 << 33)
+case("1 << -1", lambda: 1 << -1)
+case("-8 >> 1", lambda: -8 >> 1)
+case("-1 >> 31", lambda: -1 >> 31)
+case("u32 0x80000000 >> 31", lambda: 0x80000000 >> 31)
+case("u32 1 << 32", lambda: 1 << 32)
@@ -0,0 +1,29 @@
+//! shift - shifting by the width of the type or more, by a negative count
+//! and shifting negative numbers, in Rust. A count outside 0..31 panics
+//! with overflow checks on and is masked to its low 5 bits without them;
+//! >> of a signed integer is arithmetic.
+
+use std::fmt::Display;
+use std::hint::black_box;
+use std::panic::{self, UnwindSafe};
+
+/// One `case = value` line, or `case = panic: ...`.
+fn case<T: Display>(name: &str, f: impl FnOnce() -> T + UnwindSafe) {
+    match panic::catch_unwind(f) {
+        Ok(v) => println!("{} = {}", name, v),
+        Err(e) => println!("{} = panic: {}", name, e.downcast_ref::<&str>().copied().or(e.downcast_ref::<String>().map(String::as_str)).unwrap_or("?").trim_start_matches("attempt to ")),
+    }
+}
+
+fn main() {
+    panic::set_hook(Box::new(|_| {}));
+    let one = black_box(1i32);
+    case("1 << 31", || one << black_box(31));
+    case("1 << 32", || one << black_box(32));
+    case("1 << 33", || one << black_box(33));
+    case("1 << -1", || one << black_box(-1));
+    case("-8 >> 1", || black_box(-8i32) >> 1);
+    case("-1 >> 31", || black_box(-1i32) >> 31);
+    case("u32 0x80000000 >> 31", || black_box(0x8000_0000u32) >> 31);
+    case("u32 1 << 32", || black_box(1u32) << black_box(32));
+}
@@ -0,0 +1,6 @@
+; Powers of two on the OUT register (needs sap1_extended.toml)
+LDI 1     ; A = 1
+OUT       ; loop: show A
+STA 15    ; RAM[15] = A
+ADD 15    ; A = A + A
+JMP 1     ; goto loop
@@ -0,0 +1,14 @@
+; The classic SAP-1 program: 16 + 20 + 24 - 4 = 56
+LDA 9
+ADD 10
+ADD 11
+SUB 12
+OUT
+HLT
+0
+0
+0
+16        ; address 9
+20
+24
+4
@@ -0,0 +1,287 @@
+/*
+ * Where Things Live: A Process's Address Space
+ * Addresses of stack locals across nested calls, heap blocks, statics and
+ * functions in this very process, sorted into a map - and the directions
+ * the stack and heap grow, inferred from what was measured.
+ *
+ *   high  ┌──────────────────┐
+ *         │ stack            │  locals, return addresses; grows down
+ *         │   ↓              │
+ *         │ (thread stacks,  │
+ *         │  shared libs,    │  mmap region: big heap blocks land here too
+ *         │  big mallocs)    │
+ *         │   ↑              │
+ *         │ heap             │  Box, Vec; grows up (brk)
+ *         │ .bss / .data     │  statics: zeroed / initialized
+ *         │ .text, .rodata   │  code; string literals, consts (the
+ *         │                  │  linker picks which comes first)
+ *    low  └──────────────────┘
+ *
+ * That picture is the classic Linux one. The code checks what this OS
+ * actually does: with ASLR every region starts somewhere random on each
+ * run, macOS and Windows order the regions differently, and on Linux the
+ * kernel's own map (/proc/self/maps) names the region of every address.
+ *
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin address_space -- [depth] [--lang no]   (nested calls, default 5)
+ */
+
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use util::lang::{self, fill, Catalog};
+
+static GREETING: &str = "hello";                 // the str: read-only data
+static TABLE: [u32; 4] = [1, 2, 3, 4];           // immutable static: read-only data
+static COUNTER: AtomicUsize = AtomicUsize::new(7);  // mutable, initialized: .data
+static ZEROED: AtomicUsize = AtomicUsize::new(0);   // mutable, all zero: .bss
+
+/// One measured address.
+struct Probe {
+    what: String,
+    kind: &'static str,
+    addr: usize,
+}
+
+fn addr<T: ?Sized>(r: &T) -> usize {
+    r as *const T as *const u8 as usize
+}
+
+/// Record a local at every level of `depth` nested calls.
+#[inline(never)]
+fn nested(level: usize, depth: usize, out: &mut Vec<Probe>) {
+    let local = black_box([level as u8; 64]);    // 64 bytes in this frame
+    out.push(Probe { what: format!("local in call depth {}", level), kind: "stack", addr: addr(&local) });
+    if level < depth {
+        nested(level + 1, depth, out);
+    }
+    black_box(&local);
+}
+
+// ==================== WHAT THE OS SAYS ====================
+
+/// One line of /proc/self/maps: start, end, permissions, name.
+struct Mapping {
+    start: usize,
+    end: usize,
+    perms: String,
+    name: String,
+}
+
+/// The kernel's list of mapped regions (Linux only; empty elsewhere).
+fn os_maps() -> Vec<Mapping> {
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+    let text = std::fs::read_to_string("/proc/self/maps").unwrap_or_default();
+    text.lines()
+        .filter_map(|line| {
+            let mut f = line.split_whitespace();
+            let (range, perms) = (f.next()?, f.next()?);
+            let name = f.nth(3).unwrap_or("[anonymous]").to_string();
+            let (a, b) = range.split_once('-')?;
+            Some(Mapping {
+                start: usize::from_str_radix(a, 16).ok()?,
+                end: usize::from_str_radix(b, 16).ok()?,
+                perms: perms.to_string(),
+                name,
+            })
+        })
+        .collect()
+}
+
+fn region(maps: &[Mapping], a: usize) -> String {
+    match maps.iter().find(|m| m.start <= a && a < m.end) {
+        Some(m) => {
+            let name = m.name.rsplit('/').next().unwrap_or(&m.name);
+            format!("{} {}", m.perms, name)
+        }
+        None => String::new(),
+    }
+}
+
+/// Is address space layout randomization on?
+fn aslr() -> String {
+    if cfg!(target_os = "linux") {
+        match std::fs::read_to_string("/proc/sys/kernel/randomize_va_space").map(|s| s.trim().to_string()) {
+            Ok(ref v) if v == "0" => tr("off (randomize_va_space = 0): addresses repeat between runs").into(),
+            Ok(v) => fill(tr("on (randomize_va_space = {}): run again and every region moves"), &[&v]),
+            Err(_) => tr("unknown").into(),
+        }
+    } else if cfg!(target_os = "macos") || cfg!(target_os = "windows") {
+        tr("always on: run again and every region moves").into()
+    } else {
+        tr("unknown on this OS").into()
+    }
+}
+
+const LINUX: &str = "  Small heap blocks come from the brk heap just above the program; blocks
+  over glibc's mmap threshold (128 KiB) get their own mapping further up.
+  Thread stacks are mmap'd regions, not the [stack] of the main thread.
+";
+
+const MACOS: &str = "  macOS has no brk heap: malloc zones are mmap'd regions, and the main
+  stack sits far above them. The code is loaded at a random slide above
+  a 4 GiB zero page (__PAGEZERO).
+";
+
+const WINDOWS: &str = "  On Windows the main thread's stack is usually reserved at a LOW address,
+  below the heap and the program image - the 'stack at the top' picture
+  does not hold there, but the stack still grows down.
+";
+
+const OTHER: &str = "  No OS-specific notes for this target; the inference below still holds.\n";
+
+fn caveats() -> &'static str {
+    tr(if cfg!(target_os = "linux") {
+        LINUX
+    } else if cfg!(target_os = "macos") {
+        MACOS
+    } else if cfg!(target_os = "windows") {
+        WINDOWS
+    } else {
+        OTHER
+    })
+}
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+// ==================== DEMONSTRATION ====================
+
+const USAGE: &str = "address_space [depth] [--lang no|en]";
+/// Deep enough to see the frames march down, far from a stack overflow.
+const MAX_DEPTH: usize = 1000;
+
+fn fail(message: &str) -> ! {
+    eprintln!("address_space: {}", message);
+    std::process::exit(2)
+}
+
+fn main() {
+    let args = lang::args(USAGE);
+    let depth = match args.first().map(|s| s.parse()) {
+        None => 5,
+        Some(Ok(d)) if (1..=MAX_DEPTH).contains(&d) => d,
+        _ => fail(&format!("bad depth: {} (1-{})\nusage: {}", args[0], MAX_DEPTH, USAGE)),
+    };
+    let mut probes = Vec::new();
+
+    let main_local = black_box(0u64);
+    probes.push(Probe { what: "local in main".into(), kind: "stack", addr: addr(&main_local) });
+    nested(1, depth, &mut probes);
+
+    let small: Vec<Box<[u8; 32]>> = (0..3).map(|_| Box::new([0u8; 32])).collect();
+    for (i, b) in small.iter().enumerate() {
+        probes.push(Probe { what: format!("Box<[u8; 32]> #{}", i + 1), kind: "heap", addr: addr(&**b) });
+    }
+    let big = vec![0u8; 4 << 20];
+    probes.push(Probe { what: "Vec<u8> of 4 MiB".into(), kind: "heap", addr: addr(&big[0]) });
+
+    probes.push(Probe { what: "static GREETING (str)".into(), kind: "static", addr: GREETING.as_ptr() as usize });
+    probes.push(Probe { what: "static TABLE".into(), kind: "static", addr: addr(&TABLE) });
+    probes.push(Probe { what: "static COUNTER".into(), kind: "static", addr: addr(&COUNTER) });
+    probes.push(Probe { what: "static ZEROED".into(), kind: "static", addr: addr(&ZEROED) });
+    probes.push(Probe { what: "fn main".into(), kind: "code", addr: main as fn() as usize });
+    probes.push(Probe { what: "fn nested".into(), kind: "code", addr: nested as fn(usize, usize, &mut Vec<Probe>) as usize });
+    probes.push(Probe { what: "fn std::io::stdout".into(), kind: "code",
+        addr: std::io::stdout as fn() -> std::io::Stdout as usize });
+
+    let thread_local_addr = std::thread::spawn(|| {
+        let x = black_box(0u64);
+        addr(&x)
+    })
+    .join()
+    .unwrap();
+    probes.push(Probe { what: "local in a spawned thread".into(), kind: "stack", addr: thread_local_addr });
+    COUNTER.fetch_add(ZEROED.load(Ordering::Relaxed), Ordering::Relaxed);
+
+    let maps = os_maps();
+    println!("=== Where Things Live: A Process's Address Space ===\n");
+    println!("OS: {}, {}. ASLR: {}\n", std::env::consts::OS, fill(tr("{}-bit pointers"), &[&usize::BITS]), aslr());
+
+    println!("--- Measured, highest address first ---\n");
+    let mut sorted: Vec<&Probe> = probes.iter().collect();
+    sorted.sort_by_key(|p| std::cmp::Reverse(p.addr));
+    for p in &sorted {
+        println!("  {:#018x}  {:<7} {:<28} {}", p.addr, p.kind, p.what, region(&maps, p.addr));
+    }
+    if maps.is_empty() {
+        println!("\n  {}", tr("(no /proc/self/maps on this OS: regions are inferred from the addresses only)"));
+    }
+
+    println!("\n--- Inferred ---\n");
+    let frames: Vec<usize> = probes.iter().filter(|p| p.what.starts_with("local in call")).map(|p| p.addr).collect();
+    let down = frames.windows(2).all(|w| w[1] < w[0]);
+    let up = frames.windows(2).all(|w| w[1] > w[0]);
+    let step = frames.windows(2).map(|w| w[0].abs_diff(w[1])).max().unwrap_or(0);
+    println!("  {}", fill(tr("stack grows {}: each deeper call sits about {} bytes {}"),
+        &[&tr(if down { "DOWN" } else if up { "UP" } else { "in no clear direction" }), &step,
+        &tr(if down { "lower" } else { "higher" })]));
+    let heap: Vec<usize> = small.iter().map(|b| addr(&**b)).collect();
+    println!("  {}", fill(tr("heap: consecutive small blocks go {} ({} bytes apart: 32 + allocator overhead)"),
+        &[&tr(if heap.windows(2).all(|w| w[1] > w[0]) { "UP" } else { "in no fixed order" }), &heap[1].abs_diff(heap[0])]));
+
+    let code = probes.iter().filter(|p| p.kind == "code").map(|p| p.addr).min().unwrap();
+    let mut order = [("code", code), ("read-only statics", addr(&TABLE)), ("mutable statics", addr(&COUNTER)),
+        ("small heap", heap[0]), ("big heap block", addr(&big[0])), ("main stack", main_local_addr(&probes))];
+    order.sort_by_key(|&(_, a)| a);
+    println!("  {} {}", tr("low to high:"), order.iter().map(|&(n, _)| tr(n)).collect::<Vec<_>>().join(" < "));
+    let gap = main_local_addr(&probes).abs_diff(heap[0]);
+    println!("  {}", fill(tr("between the small heap and the main stack: {} TiB of address space, nearly all unmapped"),
+        &[&format!("{:.1}", gap as f64 / (1u64 << 40) as f64)]));
+
+    println!("\n--- Notes for {} ---\n", std::env::consts::OS);
+    print!("{}", caveats());
+    black_box((&big, &small));
+}
+
+fn main_local_addr(probes: &[Probe]) -> usize {
+    probes.iter().find(|p| p.what == "local in main").unwrap().addr
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("off (randomize_va_space = 0): addresses repeat between runs", "av (randomize_va_space = 0): adressene går igjen mellom kjøringer"),
+    ("on (randomize_va_space = {}): run again and every region moves", "på (randomize_va_space = {}): kjør igjen, og hver region flytter seg"),
+    ("unknown", "ukjent"),
+    ("always on: run again and every region moves", "alltid på: kjør igjen, og hver region flytter seg"),
+    ("unknown on this OS", "ukjent på dette OS-et"),
+    (LINUX, "  Små heap-blokker kommer fra brk-heapen like over programmet; blokker over
+  glibcs mmap-terskel (128 KiB) får sin egen avbildning lenger opp.
+  Trådstakker er mmap-regioner, ikke [stack] til hovedtråden.
+"),
+    (MACOS, "  macOS har ingen brk-heap: malloc-soner er mmap-regioner, og hovedstakken
+  ligger langt over dem. Koden lastes inn med en tilfeldig forskyvning over
+  en nullside på 4 GiB (__PAGEZERO).
+"),
+    (WINDOWS, "  På Windows er hovedtrådens stakk som regel reservert på en LAV adresse,
+  under heapen og programbildet - bildet med «stakken øverst» gjelder ikke
+  der, men stakken vokser fortsatt nedover.
+"),
+    (OTHER, "  Ingen OS-spesifikke merknader for dette målet; slutningene under gjelder fortsatt.\n"),
+    ("{}-bit pointers", "{}-bits pekere"),
+    ("(no /proc/self/maps on this OS: regions are inferred from the addresses only)",
+        "(ingen /proc/self/maps på dette OS-et: regionene er utledet bare fra adressene)"),
+    ("stack grows {}: each deeper call sits about {} bytes {}", "stakken vokser {}: hvert dypere kall ligger omtrent {} byte {}"),
+    ("DOWN", "NEDOVER"),
+    ("UP", "OPPOVER"),
+    ("in no clear direction", "i ingen tydelig retning"),
+    ("lower", "lavere"),
+    ("higher", "høyere"),
+    ("heap: consecutive small blocks go {} ({} bytes apart: 32 + allocator overhead)",
+        "heap: små blokker etter hverandre går {} ({} byte fra hverandre: 32 + allokatorens overhead)"),
+    ("in no fixed order", "i ingen fast rekkefølge"),
+    ("low to high:", "lav til høy:"),
+    ("code", "kode"),
+    ("read-only statics", "skrivebeskyttede statics"),
+    ("mutable statics", "muterbare statics"),
+    ("small heap", "liten heap"),
+    ("big heap block", "stor heap-blokk"),
+    ("main stack", "hovedstakk"),
+    ("between the small heap and the main stack: {} TiB of address space, nearly all unmapped",
+        "mellom den lille heapen og hovedstakken: {} TiB adresserom, nesten alt uavbildet"),
+];
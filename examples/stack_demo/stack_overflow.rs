@@ -0,0 +1,295 @@
+/*
+ * Stack Overflow and the Guard Page
+ * How much stack is left, measured by controlled recursion; what happens
+ * when recursion runs past the end (in a child process, so this demo
+ * survives it); and the same algorithm with its own stack on the heap.
+ *
+ *   high  ┌──────────────┐ ← stack top
+ *         │ main         │
+ *         │ f(1)         │   each call pushes a frame of a fixed size
+ *         │ f(2) ...     │
+ *         │      ↓       │   remaining = limit - used
+ *         ├──────────────┤ ← limit (ulimit -s, or the thread's stack size)
+ *         │ guard page   │   mapped with no access: touching it faults
+ *    low  └──────────────┘
+ *
+ * The guard page turns "ran off the end of the stack" from silently
+ * overwriting whatever lies below into a fault. Rust's runtime catches that
+ * fault, prints "has overflowed its stack" and aborts the process - there
+ * is no recovering from it, which is why the crash is run in a child here.
+ *
+ * An explicit stack (a Vec of pending work) is limited only by the heap,
+ * and running out of it is an ordinary allocation the program can see.
+ *
+ * The explanations are printed in Norwegian with --lang no (util::lang).
+ *
+ * Run: cargo run --release --bin stack_overflow -- [chain length] [--lang no]   (default 1 000 000)
+ *      cargo run --release --features track_alloc --bin stack_overflow   (count the explicit stack's heap use)
+ */
+
+use std::hint::black_box;
+use std::process::Command;
+
+use util::lang::{self, fill, Catalog};
+
+#[cfg(feature = "track_alloc")]
+#[global_allocator]
+static ALLOC: util::tracking::TrackingAllocator = util::tracking::TrackingAllocator::new();
+
+/// Bytes in each frame of `probe`.
+const FRAME_BUF: usize = 256;
+
+/// Recurse `depth` levels and return the address of the deepest local.
+#[inline(never)]
+fn probe(depth: usize) -> usize {
+    let buf = black_box([0u8; FRAME_BUF]);
+    let here = &buf as *const _ as usize;
+    if depth == 0 { here } else { black_box(probe(depth - 1)).min(here) }
+}
+
+/// Recurse without end, reporting the depth now and then (run in a child).
+#[inline(never)]
+fn run_away(depth: usize) -> usize {
+    let buf = black_box([depth as u8; FRAME_BUF]);
+    if depth.is_multiple_of(1000) {
+        println!("{}", depth);
+    }
+    if black_box(false) { 0 } else { run_away(depth + 1) + buf[0] as usize }
+}
+
+fn sp() -> usize {
+    let x = black_box(0u8);
+    &x as *const u8 as usize
+}
+
+// ==================== THE SAME ALGORITHM TWICE ====================
+
+/// A long chain: node i points to i + 1. Depth-first, the depth is its length.
+fn chain(n: usize) -> Vec<Vec<usize>> {
+    (0..n).map(|i| if i + 1 < n { vec![i + 1] } else { vec![] }).collect()
+}
+
+/// Depth-first sum of node numbers, recursing for each edge.
+fn dfs_recursive(g: &[Vec<usize>], node: usize) -> u64 {
+    node as u64 + g[node].iter().map(|&next| dfs_recursive(g, next)).sum::<u64>()
+}
+
+/// The same visit with the pending nodes on a heap-allocated Vec.
+fn dfs_explicit(g: &[Vec<usize>], start: usize) -> (u64, usize) {
+    let mut stack = vec![start];
+    let (mut sum, mut peak) = (0, 1);
+    while let Some(node) = stack.pop() {
+        sum += node as u64;
+        stack.extend(g[node].iter().rev());
+        peak = peak.max(stack.len());
+    }
+    (sum, peak)
+}
+
+// ==================== WHAT THE OS SAYS ====================
+
+/// Soft stack limit of this process in bytes (Linux: /proc/self/limits).
+fn stack_limit() -> Option<usize> {
+    let limits = std::fs::read_to_string("/proc/self/limits").ok()?;
+    let line = limits.lines().find(|l| l.starts_with("Max stack size"))?;
+    line.split_whitespace().nth(3)?.parse().ok()
+}
+
+/// The mapping containing `addr` and the one just below it, from /proc/self/maps.
+fn mapping_and_below(addr: usize) -> Option<(String, Option<String>)> {
+    let maps = std::fs::read_to_string("/proc/self/maps").ok()?;
+    let lines: Vec<&str> = maps.lines().collect();
+    let range = |l: &str| -> Option<(usize, usize)> {
+        let (a, b) = l.split_whitespace().next()?.split_once('-')?;
+        Some((usize::from_str_radix(a, 16).ok()?, usize::from_str_radix(b, 16).ok()?))
+    };
+    let i = lines.iter().position(|l| range(l).is_some_and(|(a, b)| a <= addr && addr < b))?;
+    let below = if i > 0 { Some(lines[i - 1].to_string()) } else { None };
+    Some((lines[i].to_string(), below))
+}
+
+fn describe(line: &str) -> String {
+    let f: Vec<&str> = line.split_whitespace().collect();
+    let (a, b) = f[0].split_once('-').unwrap();
+    let size = usize::from_str_radix(b, 16).unwrap() - usize::from_str_radix(a, 16).unwrap();
+    format!("{}  {}  {:>6} KiB  {}", f[0], f[1], size / 1024, f.get(5).unwrap_or(&""))
+}
+
+/// Run this program again with `args`; (stdout, stderr, how it ended).
+fn child(args: &[&str]) -> (String, String, String) {
+    let out = Command::new(std::env::current_exe().unwrap()).args(args).output().expect("cannot start child");
+    let status = match out.status.code() {
+        Some(code) => format!("exit code {}", code),
+        None => signal_name(&out.status),
+    };
+    (String::from_utf8_lossy(&out.stdout).into(), String::from_utf8_lossy(&out.stderr).into(), status)
+}
+
+#[cfg(unix)]
+fn signal_name(status: &std::process::ExitStatus) -> String {
+    use std::os::unix::process::ExitStatusExt;
+    match status.signal() {
+        Some(6) => "killed by signal 6 (SIGABRT)".into(),
+        Some(11) => "killed by signal 11 (SIGSEGV)".into(),
+        Some(s) => format!("killed by signal {}", s),
+        None => "ended without an exit code".into(),
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_name(status: &std::process::ExitStatus) -> String {
+    format!("{}", status)
+}
+
+// ==================== DEMONSTRATION ====================
+
+const NO_PROC: &str = "  (no /proc on this OS: the limit is not known here - typically 8 MiB
+   on Linux and macOS, 1 MiB on Windows)
+";
+
+const GUARD_HIT: &str = "  The last frame touched the guard page; the fault handler saw the address
+  was in the guard and aborted. Nothing in the program could catch it.
+";
+
+const THREAD_GUARD: &str = "  ---p = no read, write or execute: the guard page under the thread's stack.
+  (The main thread's guard is a gap the kernel keeps below [stack] instead.)
+";
+
+const EXPLICIT: &str = "
+  A deep chain needs one stack frame per level when recursing, but the
+  explicit version keeps just the pending nodes on the heap: here one at
+  a time. Its limit is memory, and a failure there is an allocation error,
+  not a fault in the middle of a call.
+";
+
+fn tr(english: &'static str) -> &'static str {
+    lang::tr(NO, english)
+}
+
+fn main() {
+    let args = lang::args("stack_overflow [chain length] [--lang no|en]");
+    match args.first().map(String::as_str) {
+        Some("--child-run-away") => {
+            run_away(0);
+            return;
+        }
+        Some("--child-recursive") => {
+            let n = args[1].parse().unwrap();
+            println!("{}", dfs_recursive(&chain(n), 0));
+            return;
+        }
+        _ => {}
+    }
+    let n: usize = args.first().map_or(1_000_000, |s| s.parse().unwrap_or_else(|_| panic!("bad chain length: {}", s)));
+
+    println!("=== Stack Overflow and the Guard Page ===\n");
+
+    println!("--- 1) How much stack is left ---\n");
+    let top = sp();
+    let below = top - probe(1000);
+    let frame = below / 1001;
+    println!("  probe(1000): {}", fill(tr("the deepest local is {} bytes below main's -> {} bytes per frame"), &[&below, &frame]));
+    println!("  {}", fill(tr("({} bytes of buffer, the rest is the return address, saved registers, alignment)"), &[&FRAME_BUF]));
+    let stack_end = mapping_and_below(top).and_then(|(line, _)| {
+        let (_, b) = line.split_whitespace().next()?.split_once('-')?;
+        usize::from_str_radix(b, 16).ok()
+    });
+    let predicted = match (stack_limit(), stack_end) {
+        (Some(limit), Some(end)) => {
+            let used = end - top;
+            let left = limit.saturating_sub(used);
+            println!("  {}", fill(tr("limit {} KiB (ulimit -s), used so far {} KiB, remaining {} KiB"),
+                &[&(limit / 1024), &(used / 1024), &(left / 1024)]));
+            println!("  {}\n", fill(tr("-> room for about {} more frames of probe()"), &[&(left / frame)]));
+            Some(left / frame)
+        }
+        _ => {
+            println!("{}", tr(NO_PROC));
+            None
+        }
+    };
+
+    println!("--- 2) Recursion without end, in a child process ---\n");
+    let (out, err, status) = child(&["--child-run-away"]);
+    let reached = out.lines().last().unwrap_or("0");
+    println!("  {}", fill(tr("the child got past depth {} before it died: {}"), &[&reached, &status]));
+    if let Some(p) = predicted {
+        println!("  {}", fill(tr("(predicted about {}; each run_away frame is similar to probe's)"), &[&p]));
+    }
+    for line in err.lines().filter(|l| !l.trim().is_empty()) {
+        println!("  child stderr: {}", line);
+    }
+    println!("{}", tr(GUARD_HIT));
+
+    println!("--- 3) A thread's guard page ---\n");
+    let (mine, below) = std::thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(|| mapping_and_below(sp()).unwrap_or_default())
+        .unwrap()
+        .join()
+        .unwrap();
+    if mine.is_empty() {
+        println!("  {}\n", tr("(no /proc/self/maps on this OS)"));
+    } else {
+        println!("  {}", tr("a thread asked for 256 KiB of stack:"));
+        println!("    stack       {}", describe(&mine));
+        if let Some(b) = below {
+            println!("    just below  {}", describe(&b));
+        }
+        println!("{}", tr(THREAD_GUARD));
+    }
+
+    println!("--- 4) The same depth-first visit, recursive vs explicit stack ---\n");
+    println!("  {}\n", fill(tr("graph: a chain of {} nodes, so the search goes {} levels deep"), &[&n, &n]));
+    let g = chain(n);
+    let (sum, peak) = dfs_explicit(&g, 0);
+    println!("  explicit Vec stack:   sum {}, at most {} pending node(s), {} bytes of heap",
+        sum, peak, peak * std::mem::size_of::<usize>());
+    drop(g);
+    let small = 1000;
+    println!("  recursive, {} nodes:  sum {}", small, dfs_recursive(&chain(small), 0));
+    let (out, _, status) = child(&["--child-recursive", &n.to_string()]);
+    match out.lines().next() {
+        Some(sum) => println!("  recursive, {} nodes: sum {} {}", n, sum, tr("(fits this stack)")),
+        None => println!("  recursive, {} nodes: {} - {}", n, status, tr("one frame per level does not fit")),
+    }
+    print!("{}", tr(EXPLICIT));
+    #[cfg(feature = "track_alloc")]
+    ALLOC.report();
+}
+
+// ==================== NORSK ====================
+
+const NO: Catalog = &[
+    ("the deepest local is {} bytes below main's -> {} bytes per frame",
+        "den dypeste lokale variabelen ligger {} byte under mains -> {} byte per ramme"),
+    ("({} bytes of buffer, the rest is the return address, saved registers, alignment)",
+        "({} byte buffer, resten er returadressen, lagrede registre og justering)"),
+    ("limit {} KiB (ulimit -s), used so far {} KiB, remaining {} KiB",
+        "grense {} KiB (ulimit -s), brukt så langt {} KiB, igjen {} KiB"),
+    ("-> room for about {} more frames of probe()", "-> plass til omtrent {} rammer til av probe()"),
+    (NO_PROC, "  (ingen /proc på dette OS-et: grensen er ukjent her - vanligvis 8 MiB
+   på Linux og macOS, 1 MiB på Windows)
+"),
+    ("the child got past depth {} before it died: {}", "barnet kom forbi dybde {} før det døde: {}"),
+    ("(predicted about {}; each run_away frame is similar to probe's)",
+        "(forventet omtrent {}; hver run_away-ramme ligner på probes)"),
+    (GUARD_HIT, "  Den siste rammen rørte vaktsiden; feilhåndtereren så at adressen lå i
+  vaktsiden og avbrøt. Ingenting i programmet kunne fange det.
+"),
+    ("(no /proc/self/maps on this OS)", "(ingen /proc/self/maps på dette OS-et)"),
+    ("a thread asked for 256 KiB of stack:", "en tråd ba om 256 KiB stakk:"),
+    (THREAD_GUARD, "  ---p = ingen lesing, skriving eller utføring: vaktsiden under trådens stakk.
+  (Hovedtrådens vakt er i stedet et hull kjernen holder under [stack].)
+"),
+    ("graph: a chain of {} nodes, so the search goes {} levels deep",
+        "graf: en kjede med {} noder, så søket går {} nivåer dypt"),
+    ("(fits this stack)", "(får plass på denne stakken)"),
+    ("one frame per level does not fit", "én ramme per nivå får ikke plass"),
+    (EXPLICIT, "
+  En dyp kjede trenger én stakkramme per nivå ved rekursjon, men den
+  eksplisitte versjonen holder bare de ventende nodene på heapen: her én
+  om gangen. Grensen er minnet, og en feil der er en allokeringsfeil,
+  ikke en fault midt i et kall.
+"),
+];
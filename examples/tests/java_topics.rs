@@ -0,0 +1,122 @@
+//! Snapshots of the Java compendium: every `computerdesign.Main` topic,
+//! the simulators that load the example files (netlist, fsm, sap) and a
+//! seeded run of the SAP-1 assembler's mutation harness. The
+//! sources are compiled once into the test's temporary directory. Without
+//! a JDK on the PATH these tests print a note and pass, so that a Rust-only
+//! checkout still builds; CI and contributors with javac get the check.
+//! After an intended change: UPDATE_SNAPSHOTS=1 cargo test -p demos.
+//!
+//! Left out is the crc topic, which times the bitwise and table CRC.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use util::snapshot;
+
+/// The compiled classes, or None when there is no javac.
+fn classes() -> Option<&'static PathBuf> {
+    static CLASSES: OnceLock<Option<PathBuf>> = OnceLock::new();
+    CLASSES.get_or_init(|| {
+        let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("java-classes");
+        let mut sources = Vec::new();
+        collect(&root().join("src"), &mut sources);
+        let status = Command::new("javac").args(["--release", "11", "-encoding", "UTF-8", "-nowarn", "-d"]).arg(&out)
+            .args(&sources).status();
+        match status {
+            Ok(s) if s.success() => Some(out),
+            Ok(s) => panic!("javac failed: {}", s),
+            Err(e) => {
+                eprintln!("java snapshots skipped: no javac ({})", e);
+                None
+            }
+        }
+    }).as_ref()
+}
+
+fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().expect("the repository root").to_path_buf()
+}
+
+fn collect(dir: &Path, sources: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).expect("src/ is readable") {
+        let path = entry.expect("a directory entry").path();
+        if path.is_dir() {
+            collect(&path, sources);
+        } else if path.extension().is_some_and(|e| e == "java") {
+            sources.push(path);
+        }
+    }
+}
+
+/// stdout of `java class args`, from the repository root so the example
+/// paths resolve, with an English locale so numbers print the same everywhere.
+fn java(class: &str, args: &[&str]) -> Option<String> {
+    let classes = classes()?;
+    let out = Command::new("java").arg("-cp").arg(classes)
+        .args(["-Duser.language=en", "-Duser.country=US", "-Dfile.encoding=UTF-8", "-Dstdout.encoding=UTF-8"])
+        .arg(class).args(args).current_dir(root()).env("TDT4160_LANG", "en").output().expect("java runs");
+    assert!(out.status.success(), "{} {:?} failed: {}", class, args, String::from_utf8_lossy(&out.stderr));
+    Some(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+fn topics(names: &[&str]) {
+    for name in names {
+        if let Some(out) = java("computerdesign.Main", &[name]) {
+            snapshot::check(env!("CARGO_MANIFEST_DIR"), &format!("java_{}", name), &out);
+        }
+    }
+}
+
+fn check(name: &str, class: &str, args: &[&str]) {
+    if let Some(out) = java(class, args) {
+        snapshot::check(env!("CARGO_MANIFEST_DIR"), name, &out);
+    }
+}
+
+#[test]
+fn performance_and_numbers() {
+    topics(&["abstraction", "vm", "performance", "speedup", "perfcalc", "energy", "numbers", "hamming", "checksum", "utf8",
+        "huffman", "base64", "reedsolomon"]);
+}
+
+#[test]
+fn logic() {
+    topics(&["logic", "minimize", "hazards", "sevenseg", "netlist", "hdl", "flipflops", "registers", "fsm", "seqanalysis",
+        "timing", "adders", "gatealu", "components"]);
+}
+
+#[test]
+fn memory_and_io() {
+    topics(&["memory", "tlb", "ecc", "bitband", "polling", "dma", "busarb", "console", "disk", "plic", "uart", "spi", "i2c",
+        "watchdog"]);
+}
+
+#[test]
+fn processors() {
+    topics(&["alu", "instructions", "formats", "calling", "processors", "unrolling", "snapshot", "sap", "microcode",
+        "exceptions", "virtual", "paging", "cow", "parallel", "threads", "realtime"]);
+}
+
+#[test]
+fn simulators_on_example_files() {
+    check("java_netlist_full_adder", "computerdesign.logic.Netlist", &["examples/logic_demo/full_adder.net"]);
+    check("java_fsm_vending", "computerdesign.logic.StateMachine", &["examples/logic_demo/vending.toml", "5 5 10"]);
+    check("java_fsm_markdown", "computerdesign.logic.StateMachine", &["examples/logic_demo/seq101_moore.json", "--markdown"]);
+    check("java_sap_extended", "computerdesign.sap.SAP1", &["examples/sap_demo/sap1_extended.toml", "examples/sap_demo/program.asm"]);
+}
+
+#[test]
+fn sap_assembler_fuzz() {
+    check("java_sap_fuzz", "computerdesign.sap.AssemblerFuzz", &["5000"]);
+    check("java_sap_fuzz_extended", "computerdesign.sap.AssemblerFuzz",
+        &["5000", "examples/sap_demo/sap1_extended.toml", "examples/sap_demo/program.asm"]);
+}
+
+/// The `--verify` modes: every self-check on its own line, exit 1 if one fails.
+#[test]
+fn self_checks() {
+    check("java_components_verify", "computerdesign.logic.Components", &["--verify"]);
+    check("java_registers_verify", "computerdesign.logic.Registers", &["--verify"]);
+    check("java_base64_verify", "computerdesign.coding.Base64", &["--verify"]);
+}
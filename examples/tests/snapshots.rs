@@ -0,0 +1,106 @@
+//! Snapshots of the demos whose output is the same on every run, so a
+//! refactor cannot change what they teach without a test failing. After an
+//! intended change: UPDATE_SNAPSHOTS=1 cargo test -p demos, then review the
+//! files in tests/snapshots/ with git diff (util::snapshot).
+//!
+//! Left out are the demos that measure time (memlat, cycles, ilp_unroll,
+//! branch_predict, parallel_sum, locks, pingpong, channels, counters,
+//! saturating_simd, atomics_demo, litmus, alignment), depend on the number
+//! of cores (peterson), print addresses or stack depths that change per
+//! run (address_space, stack_overflow), or show what the installed
+//! compilers make of a program (asm_view, showasm, compare_langs). Their
+//! random data is seeded, but timing is not. asm_flags_demo is checked on
+//! x86-64 only, as its inline assembly differs per architecture, and race
+//! only without the racy feature, whose counter is undefined behaviour.
+
+use std::process::Command;
+
+use util::snapshot;
+
+fn run(exe: &str, args: &[&str]) -> String {
+    let out = Command::new(exe).args(args).env("TDT4160_LANG", "en").output().expect("the demo runs");
+    assert!(out.status.success(), "{} {:?} failed: {}", exe, args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+fn check(name: &str, exe: &str, args: &[&str]) {
+    snapshot::check(env!("CARGO_MANIFEST_DIR"), name, &run(exe, args));
+}
+
+#[test]
+fn bits() {
+    check("bits", env!("CARGO_BIN_EXE_bits"), &[]);
+    check("bits_4160", env!("CARGO_BIN_EXE_bits"), &["4160"]);
+}
+
+#[test]
+fn reinterpret() {
+    check("reinterpret", env!("CARGO_BIN_EXE_reinterpret"), &["-6.25"]);
+}
+
+#[test]
+fn overflow_rust() {
+    check("overflow_rust", env!("CARGO_BIN_EXE_overflow_rust"), &[]);
+}
+
+#[test]
+fn endianness() {
+    check("endianness", env!("CARGO_BIN_EXE_endianness"), &[]);
+}
+
+#[test]
+fn headers() {
+    check("headers", env!("CARGO_BIN_EXE_headers"), &[]);
+}
+
+#[test]
+fn hexdump() {
+    let file = std::env::temp_dir().join(format!("hexdump-snapshot-{}.bin", std::process::id()));
+    std::fs::write(&file, (0..=255u8).collect::<Vec<_>>()).unwrap();
+    let out = run(env!("CARGO_BIN_EXE_hexdump"), &[file.to_str().unwrap(), "--offset", "0x30", "--length", "100"]);
+    let _ = std::fs::remove_file(&file);
+    snapshot::check(env!("CARGO_MANIFEST_DIR"), "hexdump", &out);
+}
+
+#[test]
+fn layout() {
+    check("layout", env!("CARGO_BIN_EXE_layout"), &[]);
+}
+
+#[test]
+fn overflow() {
+    check("length_overflow", env!("CARGO_BIN_EXE_length_overflow"), &[]);
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn asm_flags_demo() {
+    check("asm_flags_demo", env!("CARGO_BIN_EXE_asm_flags_demo"), &[]);
+}
+
+#[test]
+fn spectre_sim() {
+    check("spectre_sim", env!("CARGO_BIN_EXE_spectre_sim"), &[]);
+}
+
+#[test]
+fn allocators() {
+    check("alloc_demo", env!("CARGO_BIN_EXE_alloc_demo"), &[]);
+    check("buddy", env!("CARGO_BIN_EXE_buddy"), &[]);
+    check("buddy_random", env!("CARGO_BIN_EXE_buddy"), &["--random", "12"]);
+    check("gc", env!("CARGO_BIN_EXE_gc"), &[]);
+    check("tracking_demo", env!("CARGO_BIN_EXE_tracking_demo"), &[]);
+}
+
+#[test]
+#[cfg(not(feature = "racy"))]
+fn race() {
+    check("race", env!("CARGO_BIN_EXE_race"), &[]);
+}
+
+#[test]
+fn norwegian() {
+    check("layout_no", env!("CARGO_BIN_EXE_layout"), &["--lang", "no"]);
+    check("alloc_demo_no", env!("CARGO_BIN_EXE_alloc_demo"), &["--lang", "no"]);
+    check("headers_no", env!("CARGO_BIN_EXE_headers"), &["--lang", "no"]);
+}
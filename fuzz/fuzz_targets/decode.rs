@@ -0,0 +1,24 @@
+//! Every 32-bit word either decodes to an instruction that encodes back to
+//! one decoding the same, or is an error that disassembles as `.word`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use riscv::{decode, disassemble};
+
+fuzz_target!(|data: &[u8]| {
+    for chunk in data.chunks_exact(4) {
+        let w = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        match decode(w) {
+            Ok(i) => {
+                assert_eq!(decode(i.encode()), Ok(i), "0x{:08x}", w);
+                assert_eq!(disassemble(w), i.to_string());
+                let _ = i.class();
+            }
+            Err(e) => {
+                assert_eq!(e.word, w);
+                assert!(disassemble(w).starts_with(".word"));
+            }
+        }
+    }
+});
@@ -0,0 +1,29 @@
+//! A Dinero trace is parsed or refused with its line, and a trace that
+//! parses can be run through a cache.
+
+#![no_main]
+
+use cache::trace::{parse_din, Reference};
+use cache::{Access, Cache, Config, Replacement, WritePolicy};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+    match parse_din(text) {
+        Ok(refs) => {
+            let config = Config { size: 256, line: 16, ways: 2, replacement: Replacement::Random, write: WritePolicy::WriteBackAllocate };
+            let mut c = Cache::new(config).unwrap();
+            for r in refs {
+                match r {
+                    Reference::Data(kind, addr) => c.access(addr, kind),
+                    Reference::Fetch(addr) => c.access(addr, Access::Read),
+                    Reference::Flush => {
+                        c.flush();
+                        continue;
+                    }
+                };
+            }
+        }
+        Err(e) => assert!(e.line >= 1 && e.line <= text.lines().count()),
+    }
+});
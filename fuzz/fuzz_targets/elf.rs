@@ -0,0 +1,16 @@
+//! Any file given to `tdt4160 riscv run` is loaded or refused, and a
+//! program that loads runs (or traps) without taking the emulator down:
+//! the bytes as an ELF and as a raw binary.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use riscv::Program;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(p) = Program::parse(data) {
+        let _ = p.describe(p.entry);
+        p.cpu(4096).run(1000);
+    }
+    Program::raw(0x1000, data).cpu(4096).run(1000);
+});
@@ -0,0 +1,13 @@
+//! A machine file for --config is parsed or refused with a line in the
+//! file (util::toml; the checks of crate tdt4160's config come after).
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+    if let Err(e) = util::toml::parse(text) {
+        assert!(e.line >= 1 && e.line <= text.lines().count().max(1) + 1, "line {} of {}", e.line, text.lines().count());
+    }
+});
@@ -0,0 +1,100 @@
+# Parameter sweeps over one run of a RISC-V program: cache size and
+# associativity against miss rate, predictor table size against
+# misprediction rate. The # %% markers make cells in Jupyter (jupytext),
+# VS Code and PyCharm; `python sweeps.py [program.elf]` runs it straight
+# through. matplotlib is only needed for the plots.
+#
+# Build the module first:  cd python && maturin develop --release
+
+# %%
+import sys
+
+import tdt4160
+
+# Without an ELF, a program of our own as raw words: fill a 4 KiB array
+# with a[i] = 37 * i & 0xFF, then sum the elements >= 128 four times.
+SUM_OVER_128 = [
+    0x00004437,  # lui   s0, 0x4              the array, at 0x4000
+    0x40000493,  # addi  s1, zero, 1024       1024 words
+    0x08000913,  # addi  s2, zero, 128
+    0x00000293,  # addi  t0, zero, 0
+    0x00040313,  # addi  t1, s0, 0
+    0x02500393,  # addi  t2, zero, 37         fill:
+    0x027283b3,  # mul   t2, t0, t2
+    0x0ff3f393,  # andi  t2, t2, 255
+    0x00732023,  # sw    t2, 0(t1)
+    0x00430313,  # addi  t1, t1, 4
+    0x00128293,  # addi  t0, t0, 1
+    0xfe92c4e3,  # blt   t0, s1, fill
+    0x00000513,  # addi  a0, zero, 0
+    0x00400e13,  # addi  t3, zero, 4          four passes
+    0x00040313,  # addi  t1, s0, 0            pass:
+    0x00000293,  # addi  t0, zero, 0
+    0x00032383,  # lw    t2, 0(t1)            loop:
+    0x0123c463,  # blt   t2, s2, skip         the branch that matters
+    0x00750533,  # add   a0, a0, t2
+    0x00430313,  # addi  t1, t1, 4            skip:
+    0x00128293,  # addi  t0, t0, 1
+    0xfe92c6e3,  # blt   t0, s1, loop
+    0xfffe0e13,  # addi  t3, t3, -1
+    0xfc0e1ee3,  # bne   t3, zero, pass
+    0x00100893,  # addi  a7, zero, 1          print_int(a0)
+    0x00000073,  # ecall
+    0x00000513,  # addi  a0, zero, 0
+    0x05d00893,  # addi  a7, zero, 93         exit(0)
+    0x00000073,  # ecall
+]
+
+if len(sys.argv) > 1 and not sys.argv[1].startswith("-"):
+    emu = tdt4160.Emulator.load(sys.argv[1])
+else:
+    emu = tdt4160.Emulator(b"".join(w.to_bytes(4, "little") for w in SUM_OVER_128))
+print(emu.run(), emu.output, emu)
+print(len(emu.accesses), "loads and stores,", len(emu.branches), "branches")
+
+# %% Cache size and associativity against miss rate
+sizes = [2**k for k in range(6, 15)]  # 64 B to 16 KiB
+rates = {}
+for ways in [1, 2, 4]:
+    rates[ways] = []
+    for size in sizes:
+        if 16 * ways > size:
+            rates[ways].append(None)
+            continue
+        cache = tdt4160.Cache(size, line=16, ways=ways)
+        rates[ways].append(cache.run(emu.accesses))
+print("size   " + "".join(f"{w}-way   " for w in rates))
+for i, size in enumerate(sizes):
+    print(f"{size:<6} " + "".join("  -     " if r[i] is None else f"{r[i]:6.3f}  " for r in rates.values()))
+
+# %% Two levels: how much an L2 buys for each L1
+for l1 in [256, 1024, 4096]:
+    h = tdt4160.Hierarchy(memory_time=100)
+    h.add("L1", l1, ways=2, hit_time=1)
+    h.add("L2", 16384, line=32, ways=4, hit_time=10)
+    print(f"L1 {l1:>5} B: AMAT {h.run(emu.accesses):6.2f} = {h.formula()}")
+
+# %% Predictor table size against misprediction rate
+bits = list(range(0, 13))
+mispredicted = {kind: [tdt4160.miss_rate(kind, b, emu.branches) for b in bits] for kind in tdt4160.KINDS}
+print("bits  " + "".join(f"{k:>8}" for k in mispredicted))
+for i, b in enumerate(bits):
+    print(f"{b:<5} " + "".join(f"{r[i]:8.3f}" for r in mispredicted.values()))
+
+# %% Plots
+try:
+    import matplotlib.pyplot as plt
+except ImportError:
+    sys.exit("(pip install matplotlib for the plots)")
+
+fig, (left, right) = plt.subplots(1, 2, figsize=(11, 4))
+for ways, r in rates.items():
+    left.plot([s for s, x in zip(sizes, r) if x is not None], [x for x in r if x is not None], marker="o", label=f"{ways}-way")
+left.set(xscale="log", xlabel="cache size (bytes)", ylabel="miss rate", title="16 B lines, LRU, write-back")
+left.legend()
+for kind, r in mispredicted.items():
+    right.plot(bits, r, marker="o", label=kind)
+right.set(xlabel="table index bits", ylabel="misprediction rate", title="branch predictors")
+right.legend()
+plt.tight_layout()
+plt.show()
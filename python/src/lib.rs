@@ -0,0 +1,395 @@
+//! The simulators for Python: the RV32IM emulator, the cache model and
+//! hierarchy, and the branch predictors, as the `tdt4160` module.
+//!
+//! ```text
+//!   import tdt4160
+//!   emu = tdt4160.Emulator.load("examples/riscv/sum.elf")
+//!   emu.run()                                  "exit"
+//!   for size in [256, 512, 1024, 2048]:
+//!       c = tdt4160.Cache(size, line=16, ways=2)
+//!       c.run(emu.accesses)                    the miss rate
+//!   tdt4160.miss_rate("gshare", 10, emu.branches)
+//! ```
+//!
+//! A run records its loads and stores as ("r" | "w", address) and its
+//! conditional branches as (pc, taken), which is what Cache.run and the
+//! predictors take, so one run of a program feeds a whole sweep. The
+//! classes hold trait objects and are not Send, so each stays in the
+//! thread that made it (`unsendable`). Build with maturin, see
+//! python/pyproject.toml.
+
+mod sim;
+
+use ::cache::hierarchy::{self, Level};
+use ::cache::trace::{self, Reference};
+use ::cache::{Access, Config, Replacement, WritePolicy};
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use riscv::disassemble;
+use riscv::predictor;
+
+use sim::Run;
+
+/// Stack bytes above the program, as `tdt4160 riscv run` gives it.
+const STACK: u32 = 64 << 10;
+
+fn value_error(e: impl ToString) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn kind(access: Access) -> &'static str {
+    match access {
+        Access::Read => "r",
+        Access::Write => "w",
+    }
+}
+
+fn access(kind: &str) -> PyResult<Access> {
+    match kind {
+        "r" | "read" => Ok(Access::Read),
+        "w" | "write" => Ok(Access::Write),
+        other => Err(value_error(format!("unknown access '{}', r or w", other))),
+    }
+}
+
+// ==================== EMULATOR ====================
+
+/// A loaded RV32IM program, run with the ecalls of `tdt4160 riscv run`.
+#[pyclass(unsendable, module = "tdt4160")]
+struct Emulator {
+    run: Run,
+}
+
+#[pymethods]
+impl Emulator {
+    /// An ELF, or a raw binary loaded at `base`; `trace` records the
+    /// accesses and branches.
+    #[new]
+    #[pyo3(signature = (program, base = 0x1000, stack = STACK, trace = true))]
+    fn new(program: &[u8], base: u32, stack: u32, trace: bool) -> PyResult<Self> {
+        Ok(Emulator { run: Run::load(program, base, stack, trace).map_err(value_error)? })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (path, base = 0x1000, stack = STACK, trace = true))]
+    fn load(path: &str, base: u32, stack: u32, trace: bool) -> PyResult<Self> {
+        let bytes = std::fs::read(path).map_err(|e| PyOSError::new_err(format!("{}: {}", path, e)))?;
+        Emulator::new(&bytes, base, stack, trace)
+    }
+
+    /// Back to the entry point with fresh memory and nothing recorded.
+    fn reset(&mut self) {
+        self.run.reset();
+    }
+
+    /// Up to `n` instructions; False once the program has stopped.
+    #[pyo3(signature = (n = 1))]
+    fn step(&mut self, n: u64) -> bool {
+        self.run.step(n)
+    }
+
+    /// Until it stops or `max` instructions have run: why it stopped,
+    /// None if it still runs.
+    #[pyo3(signature = (max = 100_000_000))]
+    fn run(&mut self, max: u64) -> Option<String> {
+        self.run.step(max);
+        self.run.stopped.clone()
+    }
+
+    #[getter]
+    fn pc(&self) -> u32 {
+        self.run.cpu.pc
+    }
+
+    #[getter]
+    fn retired(&self) -> u64 {
+        self.run.cpu.retired
+    }
+
+    #[getter]
+    fn registers(&self) -> Vec<u32> {
+        (0..32).map(|r| self.run.cpu.reg(r)).collect()
+    }
+
+    fn reg(&self, r: u8) -> PyResult<u32> {
+        if r >= 32 {
+            return Err(value_error(format!("no register x{}, x0 to x31", r)));
+        }
+        Ok(self.run.cpu.reg(r))
+    }
+
+    /// `len` (1, 2 or 4) bytes of memory, None outside it.
+    #[pyo3(signature = (addr, len = 4))]
+    fn read(&self, addr: u32, len: usize) -> Option<u32> {
+        self.run.cpu.mem.read(addr, len)
+    }
+
+    /// The address of a symbol of the ELF, such as an array to sweep over.
+    fn symbol(&self, name: &str) -> Option<u32> {
+        self.run.program.symbol(name).map(|s| s.addr)
+    }
+
+    /// The next instruction, disassembled.
+    #[getter]
+    fn next(&self) -> Option<String> {
+        self.run.cpu.mem.read(self.run.cpu.pc, 4).map(disassemble)
+    }
+
+    #[getter]
+    fn output(&self) -> String {
+        String::from_utf8_lossy(&self.run.output).into_owned()
+    }
+
+    /// "exit", "ebreak" or the trap; None while it runs.
+    #[getter]
+    fn stopped(&self) -> Option<String> {
+        self.run.stopped.clone()
+    }
+
+    #[getter]
+    fn exit_code(&self) -> Option<i32> {
+        self.run.exit_code
+    }
+
+    /// (pc, taken) of every conditional branch so far.
+    #[getter]
+    fn branches(&self) -> Vec<(u32, bool)> {
+        self.run.branches.clone()
+    }
+
+    /// ("r" | "w", address) of every load and store so far.
+    #[getter]
+    fn accesses(&self) -> Vec<(&'static str, u32)> {
+        self.run.accesses.iter().map(|&(a, addr)| (kind(a), addr)).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        let state = self.run.stopped.as_deref().unwrap_or("running");
+        format!("<Emulator at {}, {} retired, {}>", self.run.program.describe(self.run.cpu.pc), self.run.cpu.retired, state)
+    }
+}
+
+// ==================== CACHES ====================
+
+fn config(size: u32, line: u32, ways: u32, replace: &str, write: &str) -> PyResult<::cache::Cache> {
+    let replacement = match replace {
+        "lru" => Replacement::Lru,
+        "fifo" => Replacement::Fifo,
+        "random" => Replacement::Random,
+        other => return Err(value_error(format!("unknown replacement policy '{}'", other))),
+    };
+    let write = match write {
+        "back" => WritePolicy::WriteBackAllocate,
+        "through" => WritePolicy::WriteThroughNoAllocate,
+        other => return Err(value_error(format!("unknown write policy '{}'", other))),
+    };
+    ::cache::Cache::new(Config { size, line, ways, replacement, write }).map_err(value_error)
+}
+
+fn stats<'py>(py: Python<'py>, cache: &::cache::Cache) -> PyResult<Bound<'py, PyDict>> {
+    let st = cache.stats();
+    let d = PyDict::new_bound(py);
+    d.set_item("accesses", st.accesses())?;
+    d.set_item("reads", st.reads)?;
+    d.set_item("writes", st.writes)?;
+    d.set_item("hits", st.hits)?;
+    d.set_item("misses", st.misses)?;
+    d.set_item("hit_rate", st.hit_rate())?;
+    d.set_item("miss_rate", st.miss_rate())?;
+    d.set_item("evictions", st.evictions)?;
+    d.set_item("memory_writes", st.memory_writes)?;
+    Ok(d)
+}
+
+/// One cache in front of memory, as `tdt4160 cache` simulates it.
+#[pyclass(module = "tdt4160")]
+struct Cache {
+    cache: ::cache::Cache,
+}
+
+#[pymethods]
+impl Cache {
+    #[new]
+    #[pyo3(signature = (size, line = 16, ways = 1, replace = "lru", write = "back"))]
+    fn new(size: u32, line: u32, ways: u32, replace: &str, write: &str) -> PyResult<Self> {
+        Ok(Cache { cache: config(size, line, ways, replace, write)? })
+    }
+
+    /// Whether it hit.
+    #[pyo3(signature = (addr, kind = "r"))]
+    fn access(&mut self, addr: u32, kind: &str) -> PyResult<bool> {
+        Ok(self.cache.access(addr, access(kind)?).hit)
+    }
+
+    /// Every (kind, address), as Emulator.accesses has them; the miss rate
+    /// of the cache so far.
+    fn run(&mut self, accesses: Vec<(String, u32)>) -> PyResult<f64> {
+        for (kind, addr) in accesses {
+            self.cache.access(addr, access(&kind)?);
+        }
+        Ok(self.cache.stats().miss_rate())
+    }
+
+    /// A Dinero trace, as `tdt4160 cache run` reads it.
+    fn din(&mut self, text: &str) -> PyResult<f64> {
+        for r in trace::parse_din(text).map_err(value_error)? {
+            match r {
+                Reference::Data(kind, addr) => {
+                    self.cache.access(addr, kind);
+                }
+                Reference::Fetch(addr) => {
+                    self.cache.access(addr, Access::Read);
+                }
+                Reference::Flush => {
+                    self.cache.flush();
+                }
+            }
+        }
+        Ok(self.cache.stats().miss_rate())
+    }
+
+    /// Write back every dirty line; how many there were.
+    fn flush(&mut self) -> u64 {
+        self.cache.flush()
+    }
+
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        stats(py, &self.cache)
+    }
+
+    #[pyo3(signature = (hit_time = 1.0, miss_penalty = 100.0))]
+    fn amat(&self, hit_time: f64, miss_penalty: f64) -> f64 {
+        self.cache.amat(hit_time, miss_penalty)
+    }
+
+    #[getter]
+    fn sets(&self) -> u32 {
+        self.cache.config().sets()
+    }
+
+    /// (tag, index, offset) bits of an address.
+    #[getter]
+    fn split(&self) -> (u32, u32, u32) {
+        self.cache.config().split()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Cache {}>", self.cache.config())
+    }
+}
+
+/// Caches in levels, L1 first, in front of main memory.
+#[pyclass(module = "tdt4160")]
+struct Hierarchy {
+    hierarchy: hierarchy::Hierarchy,
+}
+
+#[pymethods]
+impl Hierarchy {
+    #[new]
+    #[pyo3(signature = (memory_time = 100.0))]
+    fn new(memory_time: f64) -> Self {
+        Hierarchy { hierarchy: hierarchy::Hierarchy::new(Vec::new(), memory_time) }
+    }
+
+    /// The next level down, below those added before.
+    #[pyo3(signature = (name, size, line = 16, ways = 1, replace = "lru", write = "back", hit_time = 1.0))]
+    #[allow(clippy::too_many_arguments)]
+    fn add(&mut self, name: &str, size: u32, line: u32, ways: u32, replace: &str, write: &str, hit_time: f64) -> PyResult<()> {
+        let cache = config(size, line, ways, replace, write)?;
+        self.hierarchy.levels.push(Level { name: name.to_string(), cache, hit_time });
+        Ok(())
+    }
+
+    /// The level that had the data: 0 for L1, len(levels) for memory.
+    #[pyo3(signature = (addr, kind = "r"))]
+    fn access(&mut self, addr: u32, kind: &str) -> PyResult<usize> {
+        if self.hierarchy.levels.is_empty() {
+            return Err(value_error("no levels, add one first"));
+        }
+        Ok(self.hierarchy.access(addr, access(kind)?).1)
+    }
+
+    /// Every (kind, address); the AMAT so far.
+    fn run(&mut self, accesses: Vec<(String, u32)>) -> PyResult<f64> {
+        for (kind, addr) in accesses {
+            self.access(addr, &kind)?;
+        }
+        Ok(self.amat())
+    }
+
+    fn amat(&self) -> f64 {
+        if self.hierarchy.levels.is_empty() {
+            return self.hierarchy.memory_time;
+        }
+        self.hierarchy.amat()
+    }
+
+    /// "1 + 0.250 × (10 + 0.100 × 100)"
+    fn formula(&self) -> String {
+        self.hierarchy.formula()
+    }
+
+    /// The statistics of each level, with its name.
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.hierarchy.levels.iter().map(|l| {
+            let d = stats(py, &l.cache)?;
+            d.set_item("name", l.name.as_str())?;
+            Ok(d)
+        }).collect()
+    }
+}
+
+// ==================== BRANCH PREDICTORS ====================
+
+/// One of KINDS with 2^bits entries, fed (pc, taken) one at a time.
+#[pyclass(unsendable, module = "tdt4160")]
+struct Predictor {
+    predictor: Box<dyn predictor::Predictor>,
+}
+
+#[pymethods]
+impl Predictor {
+    #[new]
+    #[pyo3(signature = (kind, bits = 10))]
+    fn new(kind: &str, bits: u32) -> PyResult<Self> {
+        Ok(Predictor { predictor: predictor::by_name(kind, bits).map_err(value_error)? })
+    }
+
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.predictor.name()
+    }
+
+    fn predict(&self, pc: u32) -> bool {
+        self.predictor.predict(pc)
+    }
+
+    fn update(&mut self, pc: u32, taken: bool) {
+        self.predictor.update(pc, taken);
+    }
+
+    /// The miss rate over `branches`, learning as it goes.
+    fn run(&mut self, branches: Vec<(u32, bool)>) -> f64 {
+        predictor::miss_rate(&mut *self.predictor, &branches)
+    }
+}
+
+/// The miss rate of a fresh predictor over `branches`.
+#[pyfunction]
+fn miss_rate(kind: &str, bits: u32, branches: Vec<(u32, bool)>) -> PyResult<f64> {
+    let mut p = predictor::by_name(kind, bits).map_err(value_error)?;
+    Ok(predictor::miss_rate(&mut *p, &branches))
+}
+
+#[pymodule]
+fn tdt4160(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Emulator>()?;
+    m.add_class::<Cache>()?;
+    m.add_class::<Hierarchy>()?;
+    m.add_class::<Predictor>()?;
+    m.add_function(wrap_pyfunction!(miss_rate, m)?)?;
+    m.add("KINDS", predictor::KINDS.to_vec())?;
+    Ok(())
+}
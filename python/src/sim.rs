@@ -0,0 +1,106 @@
+/*
+ * sim - the emulator as the bindings drive it, with no Python in it.
+ *
+ *   step ──► fetch ──► lw/sw? record (kind, address)
+ *                      branch? record (pc, taken)
+ *            execute ──► ecall? print or exit
+ *
+ * What a run records is what the other models take: the data accesses go
+ * straight into a Cache or a Hierarchy, the branches into a Predictor, so
+ * one run of a program feeds a whole sweep of cache sizes or table sizes.
+ * Keeping this apart from the #[pyclass] wrappers lets the logic be read,
+ * and built, without a Python toolchain.
+ */
+
+use cache::Access;
+use riscv::elf::{self, Program};
+use riscv::instruction::Instruction;
+use riscv::syscall::{self, Outcome as Call};
+use riscv::{Cpu, Trap};
+
+pub struct Run {
+    pub program: Program,
+    stack: u32,
+    pub cpu: Cpu,
+    pub output: Vec<u8>,
+    /// Why it stopped: "exit", "ebreak" or the trap.
+    pub stopped: Option<String>,
+    pub exit_code: Option<i32>,
+    /// Record the branches and accesses below.
+    pub trace: bool,
+    /// (pc, taken) of every conditional branch, in order.
+    pub branches: Vec<(u32, bool)>,
+    pub accesses: Vec<(Access, u32)>,
+}
+
+impl Run {
+    /// An RV32 ELF, or a raw binary loaded at `base`.
+    pub fn load(bytes: &[u8], base: u32, stack: u32, trace: bool) -> Result<Run, String> {
+        let program = if elf::is_elf(bytes) { Program::parse(bytes).map_err(|e| e.to_string())? } else { Program::raw(base, bytes) };
+        let cpu = program.cpu(stack);
+        Ok(Run { program, stack, cpu, output: Vec::new(), stopped: None, exit_code: None, trace, branches: Vec::new(), accesses: Vec::new() })
+    }
+
+    /// Back to the entry point with fresh memory and empty records.
+    pub fn reset(&mut self) {
+        self.cpu = self.program.cpu(self.stack);
+        self.output.clear();
+        self.stopped = None;
+        self.exit_code = None;
+        self.branches.clear();
+        self.accesses.clear();
+    }
+
+    /// Run up to `n` instructions; false once the program has stopped.
+    pub fn step(&mut self, n: u64) -> bool {
+        for _ in 0..n {
+            if self.stopped.is_some() {
+                break;
+            }
+            let pc = self.cpu.pc;
+            let before = if self.trace { self.cpu.fetch().ok() } else { None };
+            let addr = before.and_then(|i| self.address(i));
+            let trap = match self.cpu.step() {
+                Ok(inst) => {
+                    if before.is_some() {
+                        self.record(inst, pc, addr);
+                    }
+                    continue;
+                }
+                Err(trap) => trap,
+            };
+            let output = &mut self.output;
+            match trap {
+                Trap::Ecall(pc) => match syscall::dispatch(&mut self.cpu, &mut |bytes| output.extend_from_slice(bytes)) {
+                    Call::Continue => {}
+                    Call::Exit(code) => {
+                        self.exit_code = Some(code);
+                        self.stopped = Some("exit".to_string());
+                    }
+                    Call::Unknown(n) => self.stopped = Some(format!("unknown ecall {} at {}", n, self.program.describe(pc))),
+                },
+                Trap::Breakpoint(_) => self.stopped = Some("ebreak".to_string()),
+                trap => self.stopped = Some(format!("{} in {}", trap, self.program.describe(self.cpu.pc))),
+            }
+        }
+        self.stopped.is_none()
+    }
+
+    /// Where a load or store is about to go, read before it runs: a load
+    /// may overwrite its own base register.
+    fn address(&self, inst: Instruction) -> Option<u32> {
+        match inst {
+            Instruction::Load { rs1, offset, .. } | Instruction::Store { rs1, offset, .. } => Some(self.cpu.reg(rs1).wrapping_add(offset as u32)),
+            _ => None,
+        }
+    }
+
+    fn record(&mut self, inst: Instruction, pc: u32, addr: Option<u32>) {
+        match (inst, addr) {
+            (Instruction::Branch { .. }, _) => self.branches.push((pc, self.cpu.pc != pc.wrapping_add(4))),
+            (Instruction::Load { .. }, Some(addr)) => self.accesses.push((Access::Read, addr)),
+            (Instruction::Store { .. }, Some(addr)) => self.accesses.push((Access::Write, addr)),
+            _ => {}
+        }
+    }
+}
@@ -1,11 +1,20 @@
 package computerdesign;
 
 import computerdesign.alu.ALU;
+import computerdesign.coding.*;
+import computerdesign.instruction.BitLayout;
 import computerdesign.instruction.Instruction;
+import computerdesign.io.*;
+import computerdesign.logic.*;
 import computerdesign.memory.*;
+import computerdesign.os.CopyOnWrite;
+import computerdesign.os.DemandPaging;
 import computerdesign.os.ProcessThread;
+import computerdesign.os.RealTimeScheduling;
 import computerdesign.os.Scheduler;
+import computerdesign.pipeline.LoopUnrolling;
 import computerdesign.processor.*;
+import computerdesign.sap.*;
 import computerdesign.theory.*;
 import computerdesign.vm.*;
 
@@ -70,23 +79,74 @@ public class Main {
             case "abstraction": demonstrateAbstractionLevels(); break;
             case "vm": demonstrateVirtualMachines(); break;
             case "performance": demonstratePerformance(); break;
+            case "speedup": demonstrateSpeedup(); break;
+            case "perfcalc": demonstratePerfCalc(); break;
+            case "energy": demonstrateEnergy(); break;
             case "numbers": demonstrateNumberSystems(); break;
+            case "hamming": demonstrateHamming(); break;
+            case "crc": demonstrateCrc32(); break;
+            case "checksum": demonstrateChecksums(); break;
+            case "utf8": demonstrateUtf8(); break;
+            case "huffman": demonstrateHuffman(); break;
+            case "base64": demonstrateBase64(); break;
+            case "reedsolomon": demonstrateReedSolomon(); break;
             case "logic": demonstrateDigitalLogic(); break;
+            case "minimize": demonstrateMinimization(); break;
+            case "hazards": demonstrateHazards(); break;
+            case "sevenseg": demonstrateSevenSegment(); break;
+            case "netlist": demonstrateNetlist(); break;
+            case "hdl": demonstrateHdl(); break;
+            case "flipflops": demonstrateFlipFlops(); break;
+            case "registers": demonstrateRegisters(); break;
+            case "fsm": demonstrateStateMachines(); break;
+            case "seqanalysis": demonstrateSequentialAnalysis(); break;
+            case "timing": demonstrateTimingDiagrams(); break;
+            case "adders": demonstrateAdders(); break;
+            case "gatealu": demonstrateGateLevelALU(); break;
+            case "components": demonstrateComponents(); break;
             case "memory": demonstrateMemoryHierarchy(); break;
             case "tlb": demonstrateTLB(); break;
+            case "ecc": demonstrateEcc(); break;
+            case "bitband": demonstrateBitBanding(); break;
+            case "polling": demonstratePollingVsInterrupts(); break;
+            case "dma": demonstrateDma(); break;
+            case "busarb": demonstrateBusArbitration(); break;
+            case "console": demonstrateConsole(); break;
+            case "disk": demonstrateDisk(); break;
+            case "plic": demonstratePlic(); break;
+            case "uart": demonstrateUart(); break;
+            case "spi": demonstrateSpi(); break;
+            case "i2c": demonstrateI2c(); break;
+            case "watchdog": demonstrateWatchdog(); break;
             case "alu": demonstrateALU(); break;
             case "instructions": demonstrateInstructions(); break;
+            case "formats": demonstrateFormats(); break;
             case "calling": demonstrateCallingConventions(); break;
             case "processors": demonstrateProcessors(); break;
+            case "unrolling": demonstrateLoopUnrolling(); break;
+            case "snapshot": demonstrateSnapshot(); break;
+            case "sap": demonstrateSAP1(); break;
+            case "microcode": demonstrateMicrocode(); break;
             case "exceptions": demonstrateExceptions(); break;
             case "virtual": demonstrateVirtualMemory(); break;
+            case "paging": demonstrateDemandPaging(); break;
+            case "cow": demonstrateCopyOnWrite(); break;
             case "parallel": demonstrateParallelComputing(); break;
             case "threads": demonstrateProcessAndThreads(); break;
+            case "realtime": demonstrateRealTime(); break;
             default:
                 System.out.println("Unknown topic: " + topic);
-                System.out.println("Available: abstraction, vm, performance, numbers, logic,");
-                System.out.println("           memory, tlb, alu, instructions, calling,");
-                System.out.println("           processors, exceptions, virtual, parallel, threads");
+                System.out.println("Available: abstraction, vm, performance, speedup, perfcalc,");
+                System.out.println("           energy, numbers, hamming, crc, checksum, utf8,");
+                System.out.println("           huffman, base64, reedsolomon, logic, minimize,");
+                System.out.println("           hazards, sevenseg, netlist, hdl, flipflops,");
+                System.out.println("           registers, fsm, seqanalysis, timing, adders,");
+                System.out.println("           gatealu, components, memory, tlb, ecc, bitband,");
+                System.out.println("           polling, dma, busarb, console, disk, plic, uart,");
+                System.out.println("           spi, i2c, watchdog, alu, instructions, formats,");
+                System.out.println("           calling, processors, unrolling, snapshot, sap,");
+                System.out.println("           microcode, exceptions, virtual, paging, cow,");
+                System.out.println("           parallel, threads, realtime");
         }
     }
     
@@ -109,6 +169,45 @@ public class Main {
         System.out.println(Performance.demonstrateAMAT());
     }
     
+    /**
+     * Demonstrate Amdahl's and Gustafson's laws, including inverse problems.
+     * Covers: T1.3, T7.1
+     */
+    private static void demonstrateSpeedup() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T1.3/T7.1: AMDAHL AND GUSTAFSON SPEEDUP");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Speedup.demonstrateSpeedup());
+    }
+    
+    /**
+     * Demonstrate CPU time from per-class CPI and an instruction mix, comparing two designs.
+     * Covers: T1.3
+     */
+    private static void demonstratePerfCalc() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T1.3: IRON LAW WITH AN INSTRUCTION MIX");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(PerfCalc.demonstratePerfCalc());
+    }
+    
+    /**
+     * Demonstrate per-event energy, power and EDP for the pipelined array-sum loops.
+     * Covers: T1.3
+     */
+    private static void demonstrateEnergy() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T1.3: ENERGY AND EDP OF A PIPELINE RUN");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(EnergyModel.demonstrateEnergy(EnergyModel.DEFAULT, 1e9));
+    }
+    
     // ════════════════════════════════════════════════════════════════════════════
     // T2: NUMBER SYSTEMS
     // ════════════════════════════════════════════════════════════════════════════
@@ -136,6 +235,98 @@ public class Main {
         System.out.println(NumberSystems.floatComponents(12.375f));
     }
     
+    /**
+     * Demonstrate parity and Hamming codes locating and correcting a flipped bit.
+     * Covers: T2.2, T6.1
+     */
+    private static void demonstrateHamming() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T2.2/T6.1: PARITY AND HAMMING CODES");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Hamming.demonstrateHamming());
+    }
+    
+    /**
+     * Demonstrate CRC-32 as a shift register and as a lookup table.
+     * Covers: T2.2
+     */
+    private static void demonstrateCrc32() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T2.2: CRC-32, BIT AT A TIME AND TABLE DRIVEN");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Crc32.demonstrateCrc32());
+    }
+    
+    /**
+     * Demonstrate five checksums and the error patterns each fails to detect.
+     * Covers: T2.2
+     */
+    private static void demonstrateChecksums() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T2.2: CHECKSUMS AND WHAT THEY MISS");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Checksum.demonstrateChecksums());
+    }
+    
+    /**
+     * Demonstrate UTF-8 encoding bit by bit and a strict decoder's error reports.
+     * Covers: T2.2
+     */
+    private static void demonstrateUtf8() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T2.2: ASCII AND UTF-8");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Utf8.demonstrateUtf8());
+    }
+    
+    /**
+     * Demonstrate building a Huffman tree and the compression it gives.
+     * Covers: T2.2
+     */
+    private static void demonstrateHuffman() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T2.2: HUFFMAN CODING");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Huffman.demonstrateHuffman());
+    }
+    
+    /**
+     * Demonstrate Base64's regrouping of 8-bit bytes into 6-bit digits.
+     * Covers: T2.2
+     */
+    private static void demonstrateBase64() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T2.2: BASE64 ENCODING");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Base64.demonstrateBase64());
+    }
+    
+    /**
+     * Demonstrate GF(2^8) arithmetic and Reed-Solomon erasure recovery.
+     * Covers: T2.2
+     */
+    private static void demonstrateReedSolomon() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T2.2: GF(256) AND REED-SOLOMON");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(GF256.demonstrateGF256());
+        System.out.println(ReedSolomon.demonstrateReedSolomon());
+    }
+    
     // ════════════════════════════════════════════════════════════════════════════
     // T3/T4: DIGITAL LOGIC
     // ════════════════════════════════════════════════════════════════════════════
@@ -155,6 +346,175 @@ public class Main {
         System.out.println(DigitalLogic.demonstrateRippleCarryAdder(12, 7, 8));
     }
     
+    /**
+     * Demonstrate tabular minimization for functions too big for a K-map.
+     * Covers: T3.2
+     */
+    private static void demonstrateMinimization() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.2: LOGIC MINIMIZATION (QUINE-McCLUSKEY)");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(QuineMcCluskey.demonstrateQuineMcCluskey());
+    }
+    
+    /**
+     * Demonstrate static hazards found by event-driven simulation, and the consensus-term fix.
+     * Covers: T3.2
+     */
+    private static void demonstrateHazards() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.2: HAZARDS AND GLITCHES");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Hazards.demonstrateHazards());
+    }
+    
+    /**
+     * Demonstrate a 7-segment decoder from truth table through minimized gates to a rendered display.
+     * Covers: T3.2
+     */
+    private static void demonstrateSevenSegment() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.2: BCD TO 7-SEGMENT DECODER");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(SevenSegment.demonstrateSevenSegment());
+    }
+    
+    /**
+     * Demonstrate gate-level simulation of netlists.
+     * Covers: T3.2, T4.2
+     */
+    private static void demonstrateNetlist() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.2/T4.2: GATE-LEVEL NETLISTS");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Netlist.demonstrateNetlist());
+    }
+    
+    /**
+     * Demonstrate describing circuits as HDL modules elaborated into netlists.
+     * Covers: T3.2, T4.2
+     */
+    private static void demonstrateHdl() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.2: A MINI HARDWARE DESCRIPTION LANGUAGE");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Hdl.demonstrateHdl());
+    }
+    
+    /**
+     * Demonstrate latch vs flip-flop timing and the forbidden SR input.
+     * Covers: T4.2
+     */
+    private static void demonstrateFlipFlops() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T4.2: LATCHES & FLIP-FLOPS");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(FlipFlops.demonstrateFlipFlops());
+    }
+    
+    /**
+     * Demonstrate shift registers, ripple and synchronous counters, and counter wrap-around.
+     * Covers: T4.2, T2.2
+     */
+    private static void demonstrateRegisters() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T4.2: SHIFT REGISTERS AND COUNTERS");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Registers.demonstrateRegisters());
+    }
+    
+    /**
+     * Demonstrate Moore and Mealy machines loaded from description files.
+     * Covers: T4.1, T4.2
+     */
+    private static void demonstrateStateMachines() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T4.1/T4.2: FINITE STATE MACHINES");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(StateMachine.demonstrateStateMachines());
+    }
+    
+    /**
+     * Demonstrate deriving state tables and diagrams from flip-flop circuits.
+     * Covers: T4.2, T4.1
+     */
+    private static void demonstrateSequentialAnalysis() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T4.2: SEQUENTIAL CIRCUIT ANALYSIS");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(SequentialAnalysis.demonstrateSequentialAnalysis());
+    }
+    
+    /**
+     * Demonstrate ASCII timing diagrams for latches, flip-flops and FSMs.
+     * Covers: T4.2, T4.1
+     */
+    private static void demonstrateTimingDiagrams() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T4.2: TIMING DIAGRAMS");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(TimingDiagram.demonstrateTimingDiagrams());
+    }
+    
+    /**
+     * Demonstrate adder latency measured in gate delays.
+     * Covers: T3.2, T3.3
+     */
+    private static void demonstrateAdders() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.2/T3.3: RIPPLE-CARRY vs CARRY-LOOKAHEAD");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Adders.demonstrateAdders());
+    }
+    
+    /**
+     * Demonstrate the gate-level ALU and run the datapath on it.
+     * Covers: T3.3, T3.2, T4.1
+     */
+    private static void demonstrateGateLevelALU() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.3: THE 32-BIT ALU AT THE GATE LEVEL");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(GateLevelALU.demonstrateGateLevelALU());
+    }
+    
+    /**
+     * Demonstrate the multiplexer/decoder/encoder parts library.
+     * Covers: T3.2, T4.1
+     */
+    private static void demonstrateComponents() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.2/T4.1: MUX, DECODER, ENCODER COMPONENTS");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Components.demonstrateComponents());
+    }
+    
     // ════════════════════════════════════════════════════════════════════════════
     // T2.3: CALLING CONVENTIONS
     // ════════════════════════════════════════════════════════════════════════════
@@ -251,6 +611,162 @@ public class Main {
         System.out.println();
     }
     
+    /**
+     * Demonstrate SECDED-protected DRAM with injected single and double bit flips.
+     * Covers: T6.1
+     */
+    private static void demonstrateEcc() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.1: ECC MEMORY (SECDED)");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(EccMemory.demonstrateEcc());
+    }
+    
+    /**
+     * Demonstrate a memory-mapped GPIO port with a bit-band alias region.
+     * Covers: T6.2
+     */
+    private static void demonstrateBitBanding() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.2: MEMORY-MAPPED I/O AND BIT-BANDING");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(BitBandGpio.demonstrateBitBanding());
+    }
+    
+    /**
+     * Demonstrate wasted cycles, lost data and latency of polling and interrupts.
+     * Covers: T5.2, T6.2
+     */
+    private static void demonstratePollingVsInterrupts() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T5.2/T6.2: POLLING VS INTERRUPT-DRIVEN I/O");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(PollingVsInterrupts.demonstratePollingVsInterrupts());
+    }
+    
+    /**
+     * Demonstrate a DMA engine copying memory alongside the CPU, with a completion interrupt.
+     * Covers: T6.2, T5.2
+     */
+    private static void demonstrateDma() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.2: DMA VS PROGRAMMED I/O");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(DmaController.demonstrateDma());
+    }
+    
+    /**
+     * Demonstrate fixed-priority, round-robin and TDMA arbitration of a shared bus.
+     * Covers: T6.2
+     */
+    private static void demonstrateBusArbitration() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.2: BUS ARBITRATION");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(BusArbitration.demonstrateBusArbitration());
+    }
+    
+    /**
+     * Demonstrate the RARS-layout keyboard and display with echo and menu programs.
+     * Covers: T6.2
+     */
+    private static void demonstrateConsole() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.2: KEYBOARD AND DISPLAY MMIO");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Console.demonstrateConsole());
+    }
+    
+    /**
+     * Demonstrate seek, rotation and transfer time, and FCFS, SSTF and SCAN scheduling.
+     * Covers: T6.2
+     */
+    private static void demonstrateDisk() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.2: DISK ACCESS TIME AND SCHEDULING");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Disk.demonstrateDisk());
+    }
+    
+    /**
+     * Demonstrate PLIC priorities, enables, threshold and claim/complete with several devices.
+     * Covers: T5.2, T6.2
+     */
+    private static void demonstratePlic() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T5.2: PLATFORM-LEVEL INTERRUPT CONTROLLER");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Plic.demonstratePlic());
+    }
+    
+    /**
+     * Demonstrate UART frames as waveforms, parity and framing errors, and baud-rate mismatch.
+     * Covers: T6.2
+     */
+    private static void demonstrateUart() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.2: UART FRAMING AT THE BIT LEVEL");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(UartFraming.demonstrateUart());
+    }
+    
+    /**
+     * Demonstrate SPI modes 0-3 at the bit level against a temperature sensor.
+     * Covers: T6.2
+     */
+    private static void demonstrateSpi() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.2: SPI TRANSACTIONS AND MODES");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Spi.demonstrateSpi());
+    }
+    
+    /**
+     * Demonstrate I2C start/stop, ACK/NACK and clock stretching against a temperature sensor.
+     * Covers: T6.2
+     */
+    private static void demonstrateI2c() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.2: I2C ADDRESSING, ACK/NACK AND CLOCK STRETCHING");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(I2c.demonstrateI2c());
+    }
+    
+    /**
+     * Demonstrate a watchdog resetting hung firmware, and the boot loop of a too-short timeout.
+     * Covers: T6.2, T5.2
+     */
+    private static void demonstrateWatchdog() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.2: WATCHDOG TIMER AND RECOVERY");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Watchdog.demonstrateWatchdog());
+    }
+    
     // ════════════════════════════════════════════════════════════════════════════
     // T7: PARALLEL COMPUTING
     // ════════════════════════════════════════════════════════════════════════════
@@ -341,6 +857,17 @@ public class Main {
             String.format("%.2f", l1Cache.getMissRate()) + " × " + ram.getAccessTime());
         System.out.println("       = " + String.format("%.2f cycles", l1Cache.calculateAMAT()));
         System.out.println();
+
+        // Byte view of main memory
+        System.out.println("Main Memory as Bytes (little-endian words, like RISC-V):");
+        ram.write(0x100, 0x12345678);
+        ram.write(0x104, -1);
+        byte[] text = "TDT4160".getBytes(java.nio.charset.StandardCharsets.US_ASCII);
+        for (int i = 0; i < text.length; i++) ram.writeByte(0x108 + i, text[i]);
+        System.out.println("  write(0x100, 0x12345678), write(0x104, -1), bytes \"TDT4160\" at 0x108:");
+        for (String line : ram.hexDump(0x100, 32).split("\n")) System.out.println("  " + line);
+        System.out.println("  The word 0x12345678 is stored 78 56 34 12: least significant byte first.");
+        System.out.println();
     }
     
     /**
@@ -425,6 +952,19 @@ public class Main {
         System.out.println();
     }
     
+    /**
+     * Demonstrate the RISC-V formats defined as checked bit layouts.
+     * Covers: T2.1
+     */
+    private static void demonstrateFormats() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T2.1: INSTRUCTION FORMATS AS BIT LAYOUTS");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(BitLayout.demonstrateBitLayout());
+    }
+    
     /**
      * Demonstrate different processor architectures.
      */
@@ -494,6 +1034,58 @@ public class Main {
         System.out.println();
     }
     
+    /**
+     * Demonstrate loop unrolling and load scheduling on the pipeline.
+     * Covers: T5.1
+     */
+    private static void demonstrateLoopUnrolling() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T5.1: LOOP UNROLLING AND SCHEDULING");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(LoopUnrolling.demonstrateLoopUnrolling());
+    }
+    
+    /**
+     * Demonstrate saving and restoring machine state in a versioned binary format.
+     * Covers: T2.2, T5.1
+     */
+    private static void demonstrateSnapshot() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T2.2/T5.1: MACHINE SNAPSHOTS (BINARY FORMAT)");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(MachineSnapshot.demonstrateMachineSnapshot());
+    }
+    
+    /**
+     * Demonstrate the SAP-1 bus CPU and its control-word sequence.
+     * Covers: T3.1, T4.1
+     */
+    private static void demonstrateSAP1() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.1/T4.1: SAP-1 MICROCODED CPU");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(SAP1.demonstrateSAP1());
+    }
+    
+    /**
+     * Demonstrate a SAP-1 control store loaded from a microcode file.
+     * Covers: T3.1
+     */
+    private static void demonstrateMicrocode() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T3.1: EDITABLE MICROCODE");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(Microcode.demonstrateMicrocode());
+    }
+    
     /**
      * Demonstrate virtual memory concepts.
      */
@@ -549,6 +1141,32 @@ public class Main {
         System.out.println();
     }
     
+    /**
+     * Demonstrate page faults handled by the OS, with FIFO, LRU and Clock replacement.
+     * Covers: T6.3
+     */
+    private static void demonstrateDemandPaging() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.3: DEMAND PAGING");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(DemandPaging.demonstrateDemandPaging());
+    }
+    
+    /**
+     * Demonstrate fork sharing frames copy-on-write, with reference counts per frame.
+     * Covers: T6.3
+     */
+    private static void demonstrateCopyOnWrite() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  T6.3: COPY-ON-WRITE FORK");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(CopyOnWrite.demonstrateCopyOnWrite());
+    }
+    
     /**
      * Demonstrate process and thread concepts.
      */
@@ -611,6 +1229,19 @@ public class Main {
         System.out.println();
     }
     
+    /**
+     * Demonstrate rate-monotonic and EDF scheduling of periodic tasks with utilization bounds.
+     * Covers: T5.2
+     */
+    private static void demonstrateRealTime() {
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println("  OS: REAL-TIME SCHEDULING (RM AND EDF)");
+        System.out.println("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        System.out.println();
+        
+        System.out.println(RealTimeScheduling.demonstrateRealTime());
+    }
+    
     // ════════════════════════════════════════════════════════════════════════════
     // ABSTRACTION LEVELS
     // ════════════════════════════════════════════════════════════════════════════
@@ -134,17 +134,16 @@ public class ALU {
         switch (op) {
             case ADD:
                 result = a + b;
-                // Check for signed overflow
-                overflow = ((a > 0 && b > 0 && result < 0) ||
-                           (a < 0 && b < 0 && result > 0));
+                // Signed overflow: both operands have the same sign, the result the other
+                overflow = ((a ^ result) & (b ^ result)) < 0;
                 // Check for unsigned carry
                 carry = Integer.compareUnsigned(result, a) < 0;
                 break;
                 
             case SUB:
                 result = a - b;
-                overflow = ((a > 0 && b < 0 && result < 0) ||
-                           (a < 0 && b > 0 && result > 0));
+                // Signed overflow: operands differ in sign and the result took b's sign
+                overflow = ((a ^ b) & (a ^ result)) < 0;
                 carry = Integer.compareUnsigned(a, b) < 0;
                 break;
                 
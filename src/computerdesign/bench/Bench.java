@@ -0,0 +1,238 @@
+package computerdesign.bench;
+
+import computerdesign.util.Json;
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Locale;
+import java.util.Map;
+
+/**
+ * Bench - A small statistics-driven benchmark harness with saved baselines.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHY NOT JUST TIME IT ONCE?
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * The first runs of a Java method are interpreted, then compiled by the JIT;
+ * caches and branch predictors warm up; the OS interrupts at random. One
+ * timing mixes all of that. So each benchmark is:
+ *
+ *   1. WARMED UP for a fixed time (JIT compiles the hot loop)
+ *   2. run as N SAMPLES of roughly equal duration
+ *   3. summarized by the MEDIAN time per operation (robust to outliers)
+ *      and the median absolute deviation as the noise estimate
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * BASELINES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Results can be saved as a named baseline (a JSON file) and a later run
+ * compared against it. A benchmark counts as REGRESSED only if it got slower
+ * by more than the threshold AND by more than twice the measured noise -
+ * otherwise a noisy machine would report regressions on every run.
+ *
+ * Baselines are per machine: compare runs on the same computer.
+ */
+public final class Bench {
+
+    /** One measured unit of work; returns how many operations it performed. */
+    public interface Workload {
+        long run();
+    }
+
+    /** A named workload. */
+    public static final class Case {
+        public final String name;
+        public final String unit;          // what one operation is, e.g. "instr"
+        final Workload workload;
+
+        public Case(String name, String unit, Workload workload) {
+            this.name = name;
+            this.unit = unit;
+            this.workload = workload;
+        }
+    }
+
+    /** The summary of one benchmark. */
+    public static final class Result {
+        public final String name;
+        public final String unit;
+        public final double nsPerOp;       // median over samples
+        public final double noise;         // median absolute deviation, ns/op
+        public final double[] samples;
+
+        Result(String name, String unit, double[] samples) {
+            this.name = name;
+            this.unit = unit;
+            this.samples = samples.clone();
+            Arrays.sort(this.samples);
+            this.nsPerOp = median(this.samples);
+            double[] dev = new double[samples.length];
+            for (int i = 0; i < dev.length; i++) dev[i] = Math.abs(samples[i] - nsPerOp);
+            Arrays.sort(dev);
+            this.noise = median(dev);
+        }
+
+        public double opsPerSecond() {
+            return 1e9 / nsPerOp;
+        }
+    }
+
+    private long warmupMillis = 500;
+    private long sampleMillis = 100;
+    private int samples = 15;
+    private double threshold = 0.10;
+    private static volatile long sink;
+
+    public Bench setWarmupMillis(long millis) {
+        this.warmupMillis = millis;
+        return this;
+    }
+
+    public Bench setSampleMillis(long millis) {
+        this.sampleMillis = millis;
+        return this;
+    }
+
+    public Bench setSamples(int samples) {
+        if (samples < 3) throw new IllegalArgumentException("Need at least 3 samples, got " + samples);
+        this.samples = samples;
+        return this;
+    }
+
+    /** Relative slowdown counted as a regression (0.10 = 10%). */
+    public Bench setThreshold(double threshold) {
+        this.threshold = threshold;
+        return this;
+    }
+
+    public double getThreshold() {
+        return threshold;
+    }
+
+    // ==================== MEASURING ====================
+
+    /** Keep a result alive so the JIT cannot remove the work that produced it. */
+    public static void consume(long value) {
+        sink += value;
+    }
+
+    private static double median(double[] sorted) {
+        int n = sorted.length;
+        return n % 2 == 1 ? sorted[n / 2] : (sorted[n / 2 - 1] + sorted[n / 2]) / 2;
+    }
+
+    public Result measure(Case c) {
+        long batches = 0;
+        long ops = 0;
+        long start = System.nanoTime();
+        long end = start + warmupMillis * 1_000_000;
+        while (System.nanoTime() < end || batches == 0) {
+            ops += c.workload.run();
+            batches++;
+        }
+        double nsPerBatch = (double) (System.nanoTime() - start) / batches;
+        long perSample = Math.max(1, (long) (sampleMillis * 1e6 / nsPerBatch));
+
+        double[] times = new double[samples];
+        for (int s = 0; s < samples; s++) {
+            long done = 0;
+            long t0 = System.nanoTime();
+            for (long b = 0; b < perSample; b++) done += c.workload.run();
+            long t1 = System.nanoTime();
+            if (done == 0) throw new IllegalStateException(c.name + " performed no operations");
+            times[s] = (double) (t1 - t0) / done;
+            ops += done;
+        }
+        consume(ops);
+        return new Result(c.name, c.unit, times);
+    }
+
+    public List<Result> measureAll(List<Case> cases) {
+        List<Result> results = new ArrayList<>();
+        for (Case c : cases) results.add(measure(c));
+        return results;
+    }
+
+    // ==================== BASELINES ====================
+
+    public static void saveBaseline(Path file, List<Result> results) throws IOException {
+        StringBuilder sb = new StringBuilder("{\n  \"version\": 1,\n  \"benchmarks\": {\n");
+        for (int i = 0; i < results.size(); i++) {
+            Result r = results.get(i);
+            sb.append(String.format(Locale.ROOT, "    \"%s\": {\"unit\": \"%s\", \"ns_per_op\": %.4f, \"noise\": %.4f}%s\n",
+                r.name, r.unit, r.nsPerOp, r.noise, i + 1 < results.size() ? "," : ""));
+        }
+        sb.append("  }\n}\n");
+        if (file.getParent() != null) Files.createDirectories(file.getParent());
+        Files.write(file, sb.toString().getBytes(StandardCharsets.UTF_8));
+    }
+
+    /** Name → {ns_per_op, noise} from a saved baseline. */
+    @SuppressWarnings("unchecked")
+    public static Map<String, double[]> loadBaseline(Path file) throws IOException {
+        Map<String, Object> doc = Json.load(file);
+        Object version = doc.get("version");
+        if (!(version instanceof Number) || ((Number) version).intValue() != 1) {
+            throw new IllegalArgumentException(file + ": unsupported baseline version " + version);
+        }
+        Map<String, double[]> out = new LinkedHashMap<>();
+        Map<String, Object> benches = (Map<String, Object>) doc.get("benchmarks");
+        for (Map.Entry<String, Object> e : benches.entrySet()) {
+            Map<String, Object> b = (Map<String, Object>) e.getValue();
+            out.put(e.getKey(), new double[] {
+                ((Number) b.get("ns_per_op")).doubleValue(), ((Number) b.get("noise")).doubleValue()});
+        }
+        return out;
+    }
+
+    /** Verdict for one benchmark against its baseline value. */
+    public String verdict(Result r, double[] base) {
+        double change = (r.nsPerOp - base[0]) / base[0];
+        double noise = 2 * Math.max(r.noise, base[1]);
+        if (Math.abs(r.nsPerOp - base[0]) <= noise || Math.abs(change) <= threshold) return "no change";
+        return change > 0 ? "REGRESSED" : "improved";
+    }
+
+    // ==================== REPORTING ====================
+
+    public static String header() {
+        return String.format("%-28s %12s %9s %14s", "benchmark", "time/op", "noise", "throughput");
+    }
+
+    public static String format(Result r) {
+        return String.format("%-28s %9.2f ns %7.2f ns %9.2f M%s/s",
+            r.name, r.nsPerOp, r.noise, r.opsPerSecond() / 1e6, r.unit);
+    }
+
+    /** Results side by side with a baseline, with a verdict for each. */
+    public String compare(List<Result> results, Map<String, double[]> baseline) {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("%-28s %12s %12s %8s  %s\n", "benchmark", "baseline", "now", "change", "verdict"));
+        for (Result r : results) {
+            double[] base = baseline.get(r.name);
+            if (base == null) {
+                sb.append(String.format("%-28s %12s %9.2f ns %8s  new\n", r.name, "-", r.nsPerOp, ""));
+                continue;
+            }
+            sb.append(String.format("%-28s %9.2f ns %9.2f ns %+7.1f%%  %s\n", r.name, base[0], r.nsPerOp,
+                100 * (r.nsPerOp - base[0]) / base[0], verdict(r, base)));
+        }
+        return sb.toString();
+    }
+
+    public int countRegressions(List<Result> results, Map<String, double[]> baseline) {
+        int n = 0;
+        for (Result r : results) {
+            double[] base = baseline.get(r.name);
+            if (base != null && verdict(r, base).equals("REGRESSED")) n++;
+        }
+        return n;
+    }
+}
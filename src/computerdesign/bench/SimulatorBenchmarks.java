@@ -0,0 +1,233 @@
+package computerdesign.bench;
+
+import computerdesign.instruction.Instruction;
+import computerdesign.instruction.InstructionDecoder;
+import computerdesign.memory.Cache;
+import computerdesign.memory.MainMemory;
+import computerdesign.processor.MultiCycleProcessor;
+import computerdesign.processor.PipelineProcessor;
+import computerdesign.processor.Processor;
+import computerdesign.processor.SingleCycleProcessor;
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.List;
+import java.util.Map;
+import java.util.Random;
+import java.util.function.Function;
+
+/**
+ * SimulatorBenchmarks - Benchmarks for the simulator hot paths, so a change
+ * that slows the emulator or cache model down shows up as a number.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHAT IS MEASURED
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   dispatch.single-cycle    fetch/decode/execute loop, ns per instruction
+ *   dispatch.multi-cycle     same program on the multi-cycle FSM, ns per cycle
+ *   dispatch.pipeline        same program on the 5-stage pipeline, ns per cycle
+ *   cache.sequential         L1 over RAM, word-by-word sweep (mostly hits)
+ *   cache.random             L1 over RAM, random addresses (mostly misses)
+ *   cache.two-level          L1 → L2 → RAM, strided sweep
+ *   encode.instructions      Instruction builders → raw words (the "assembler")
+ *   decode.disassemble       raw word → fields → assembly text
+ *
+ * The program is a loop that loads, adds and counts down, so every dispatch
+ * benchmark exercises ALU, memory and branch paths.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: SimulatorBenchmarks [options]
+ *   --filter text           only benchmarks whose name contains text
+ *   --quick                 short warm-up and fewer samples (for a smoke test)
+ *   --save-baseline name    save results to <dir>/name.json
+ *   --baseline name         compare with <dir>/name.json; exit 1 on regression
+ *   --threshold percent     slowdown that counts as a regression (default 10)
+ *   --dir path              baseline directory (default bench-baselines)
+ *
+ *   java -cp out computerdesign.bench.SimulatorBenchmarks --save-baseline main
+ *   ... change the emulator ...
+ *   java -cp out computerdesign.bench.SimulatorBenchmarks --baseline main
+ */
+public final class SimulatorBenchmarks {
+
+    private static final int LOOP_COUNT = 1000;
+    private static final int HALT = 0x00100073;
+
+    private SimulatorBenchmarks() {}
+
+    /** sum += mem[0x400]; count down from LOOP_COUNT; halt. */
+    static int[] loopProgram() {
+        return new int[] {
+            Instruction.addi(5, 0, LOOP_COUNT).getRaw(),    //  0: t0 = count
+            Instruction.addi(6, 0, 0x400).getRaw(),         //  4: t1 = &data
+            Instruction.lw(7, 6, 0).getRaw(),               //  8: loop: t2 = *t1
+            Instruction.add(8, 8, 7).getRaw(),              // 12: s0 += t2
+            Instruction.addi(5, 5, -1).getRaw(),            // 16: t0--
+            Instruction.beq(5, 0, 8).getRaw(),              // 20: if t0 == 0 → done
+            Instruction.beq(0, 0, -16).getRaw(),            // 24: goto loop
+            HALT                                            // 28: done
+        };
+    }
+
+    private static Bench.Case dispatch(String name, String unit, Function<MainMemory, Processor> make, boolean countInstructions) {
+        MainMemory memory = new MainMemory();
+        memory.loadProgram(loopProgram(), 0);
+        memory.write(0x400, 3);
+        Processor cpu = make.apply(memory);
+        return new Bench.Case(name, unit, () -> {
+            cpu.reset();
+            int cycles = cpu.run(1_000_000);
+            if (!cpu.isHalted()) throw new IllegalStateException(name + ": loop program did not halt");
+            return countInstructions ? cpu.getStats().getInstructionCount() : cycles;
+        });
+    }
+
+    private static Bench.Case cache(String name, int[] addresses, boolean twoLevel) {
+        MainMemory ram = new MainMemory();
+        Cache l2 = new Cache("L2", 16 * 1024, 64, 10, ram, Cache.Associativity.EIGHT_WAY, Cache.ReplacementPolicy.LRU);
+        Cache l1 = new Cache("L1", 4 * 1024, 32, 1, twoLevel ? l2 : ram,
+            Cache.Associativity.FOUR_WAY, Cache.ReplacementPolicy.LRU);
+        return new Bench.Case(name, "access", () -> {
+            long sum = 0;
+            for (int a : addresses) sum += l1.read(a);
+            Bench.consume(sum);
+            return addresses.length;
+        });
+    }
+
+    private static Bench.Case encode() {
+        return new Bench.Case("encode.instructions", "instr", () -> {
+            int x = 0;
+            for (int i = 0; i < 1000; i++) {
+                int r = i & 31;
+                x ^= Instruction.add(r, (r + 1) & 31, (r + 2) & 31).getRaw();
+                x ^= Instruction.addi(r, r, i & 0x7FF).getRaw();
+                x ^= Instruction.lw(r, 2, (i & 0xFF) * 4).getRaw();
+                x ^= Instruction.sw(r, 2, (i & 0xFF) * 4).getRaw();
+                x ^= Instruction.beq(r, 0, (i & 0x3FF) * 2).getRaw();
+            }
+            Bench.consume(x);
+            return 5000;
+        });
+    }
+
+    private static Bench.Case disassemble() {
+        int[] words = loopProgram();
+        return new Bench.Case("decode.disassemble", "instr", () -> {
+            int length = 0;
+            for (int i = 0; i < 100; i++) {
+                for (int w : words) length += InstructionDecoder.disassemble(new Instruction(w)).length();
+            }
+            Bench.consume(length);
+            return words.length * 100L;
+        });
+    }
+
+    /** Every benchmark, in report order. */
+    public static List<Bench.Case> all() {
+        List<Bench.Case> cases = new ArrayList<>();
+        cases.add(dispatch("dispatch.single-cycle", "instr", SingleCycleProcessor::new, true));
+        cases.add(dispatch("dispatch.multi-cycle", "cycle", MultiCycleProcessor::new, false));
+        cases.add(dispatch("dispatch.pipeline", "cycle", PipelineProcessor::new, false));
+
+        int n = 16 * 1024;
+        int[] sequential = new int[n];
+        int[] random = new int[n];
+        int[] strided = new int[n];
+        Random rng = new Random(4160);
+        for (int i = 0; i < n; i++) {
+            sequential[i] = (i * 4) & 0x3FFF;                 // 16KB region, word by word
+            random[i] = rng.nextInt(0x10000) & ~3;
+            strided[i] = (i * 64) & 0xFFFF;                   // one access per 64-byte block
+        }
+        cases.add(cache("cache.sequential", sequential, false));
+        cases.add(cache("cache.random", random, false));
+        cases.add(cache("cache.two-level", strided, true));
+
+        cases.add(encode());
+        cases.add(disassemble());
+        return cases;
+    }
+
+    public static void main(String[] args) throws IOException {
+        String filter = "";
+        String save = null;
+        String compare = null;
+        Path dir = Paths.get("bench-baselines");
+        Bench bench = new Bench();
+        for (int i = 0; i < args.length; i++) {
+            switch (args[i]) {
+                case "--filter": filter = value(args, i++); break;
+                case "--quick": bench.setWarmupMillis(100).setSampleMillis(20).setSamples(5); break;
+                case "--save-baseline": save = value(args, i++); break;
+                case "--baseline": compare = value(args, i++); break;
+                case "--threshold": bench.setThreshold(percent(value(args, i++)) / 100); break;
+                case "--dir": dir = Paths.get(value(args, i++)); break;
+                default: usage("Unknown option: " + args[i]);
+            }
+        }
+
+        Map<String, double[]> baseline = null;
+        if (compare != null) {
+            Path file = dir.resolve(compare + ".json");
+            if (!Files.exists(file)) {
+                System.err.println("No baseline " + file + " (create one with --save-baseline " + compare + ")");
+                System.exit(2);
+            }
+            baseline = Bench.loadBaseline(file);
+        }
+
+        List<Bench.Result> results = new ArrayList<>();
+        System.out.println(Bench.header());
+        for (Bench.Case c : all()) {
+            if (!c.name.contains(filter)) continue;
+            Bench.Result r = bench.measure(c);
+            results.add(r);
+            System.out.println(Bench.format(r));
+        }
+
+        if (save != null) {
+            Path file = dir.resolve(save + ".json");
+            Bench.saveBaseline(file, results);
+            System.out.println("\nSaved baseline " + file);
+        }
+        if (baseline != null) {
+            System.out.printf("%nAgainst baseline '%s' (threshold %.0f%%, and more than 2x noise):%n",
+                compare, bench.getThreshold() * 100);
+            System.out.print(bench.compare(results, baseline));
+            int regressed = bench.countRegressions(results, baseline);
+            if (regressed > 0) {
+                System.out.println(regressed + " benchmark(s) regressed");
+                System.exit(1);
+            }
+        }
+    }
+
+    private static void usage(String message) {
+        System.err.println(message);
+        System.err.println("Usage: SimulatorBenchmarks [--filter text] [--quick] "
+            + "[--save-baseline name] [--baseline name] [--threshold percent] [--dir path]");
+        System.exit(2);
+    }
+
+    /** The value after option args[i]. */
+    private static String value(String[] args, int i) {
+        if (i + 1 >= args.length) usage(args[i] + " needs a value");
+        return args[i + 1];
+    }
+
+    private static double percent(String s) {
+        try {
+            double p = Double.parseDouble(s);
+            if (p >= 0 && p < Double.POSITIVE_INFINITY) return p;
+        } catch (NumberFormatException e) {
+            // reported below
+        }
+        usage("Not a percentage: " + s);
+        return 0;
+    }
+}
@@ -0,0 +1,309 @@
+package computerdesign.coding;
+
+import java.nio.charset.StandardCharsets;
+import java.util.Arrays;
+import java.util.Random;
+
+/**
+ * Base64 - Binary data as printable text, by regrouping 8-bit bytes into
+ * 6-bit digits.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * 3 BYTES → 24 BITS → 4 CHARACTERS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * 24 is the smallest number of bits that is a whole number of both bytes
+ * (3 × 8) and 6-bit digits (4 × 6). Each 6-bit value 0-63 picks a character:
+ *
+ *   0-25 A-Z    26-51 a-z    52-61 0-9    62 +    63 /
+ *
+ *   "Man"    M        a        n
+ *            01001101 01100001 01101110
+ *            010011 010110 000101 101110
+ *              19     22      5     46
+ *              T      W       F     u        → "TWFu"
+ *
+ * In code: pack the group into one int (b0 << 16 | b1 << 8 | b2), then take
+ * the digits out with (group >> 18) & 63, (group >> 12) & 63, ...
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * PADDING
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * When the input does not fill the last group, the missing bits are zero
+ * and '=' stands in for each digit that carries no data:
+ *
+ *   1 byte left   8 bits → 2 digits + "=="     ("M"  → "TQ==")
+ *   2 bytes left 16 bits → 3 digits + "="      ("Ma" → "TWE=")
+ *
+ * The output is always 4·ceil(n/3) characters: 33% larger than the input.
+ * A strict decoder rejects non-alphabet characters, bad lengths, and
+ * padding whose "unused" bits are not zero.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: Base64 text              encode the text (UTF-8)
+ *        Base64 -d base64-text    decode
+ *
+ * Covers learning goals: T2.2 (data representation)
+ */
+public final class Base64 {
+
+    private static final char[] ALPHABET =
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/".toCharArray();
+    private static final int[] VALUES = new int[128];
+
+    static {
+        Arrays.fill(VALUES, -1);
+        for (int i = 0; i < ALPHABET.length; i++) VALUES[ALPHABET[i]] = i;
+    }
+
+    private Base64() {}
+
+    // ==================== ENCODE ====================
+
+    public static String encode(byte[] data) {
+        StringBuilder out = new StringBuilder((data.length + 2) / 3 * 4);
+        for (int i = 0; i < data.length; i += 3) {
+            int left = Math.min(3, data.length - i);
+            int group = (data[i] & 0xFF) << 16;
+            if (left > 1) group |= (data[i + 1] & 0xFF) << 8;
+            if (left > 2) group |= data[i + 2] & 0xFF;
+            out.append(ALPHABET[group >> 18 & 63]);
+            out.append(ALPHABET[group >> 12 & 63]);
+            out.append(left > 1 ? ALPHABET[group >> 6 & 63] : '=');
+            out.append(left > 2 ? ALPHABET[group & 63] : '=');
+        }
+        return out.toString();
+    }
+
+    // ==================== DECODE ====================
+
+    private static int value(String text, int index) {
+        char c = text.charAt(index);
+        int v = c < 128 ? VALUES[c] : -1;
+        if (v < 0) throw new IllegalArgumentException(String.format("Not a Base64 character at %d: '%c'", index, c));
+        return v;
+    }
+
+    /** Strict decoding: length a multiple of 4, '=' only at the end, unused bits zero. */
+    public static byte[] decode(String text) {
+        if (text.length() % 4 != 0) {
+            throw new IllegalArgumentException("Base64 length must be a multiple of 4, got " + text.length());
+        }
+        int padding = text.endsWith("==") ? 2 : text.endsWith("=") ? 1 : 0;
+        byte[] out = new byte[text.length() / 4 * 3 - padding];
+        int pos = 0;
+        for (int i = 0; i < text.length(); i += 4) {
+            boolean last = i + 4 == text.length();
+            int digits = last ? 4 - padding : 4;
+            int group = 0;
+            for (int k = 0; k < 4; k++) group = group << 6 | (k < digits ? value(text, i + k) : 0);
+            int bytes = digits - 1;                                  // 4 digits → 3 bytes, 3 → 2, 2 → 1
+            int unused = group & ((1 << (8 * (3 - bytes))) - 1);
+            if (unused != 0) {
+                throw new IllegalArgumentException(String.format("Non-zero padding bits in the group at %d", i));
+            }
+            for (int k = 0; k < bytes; k++) out[pos++] = (byte) (group >> (16 - 8 * k));
+        }
+        return out;
+    }
+
+    // ==================== VERIFICATION ====================
+
+    /** RFC 4648 section 10: one vector for each amount of padding and more. */
+    private static final String[][] VECTORS = {
+        {"", ""}, {"f", "Zg=="}, {"fo", "Zm8="}, {"foo", "Zm9v"},
+        {"foob", "Zm9vYg=="}, {"fooba", "Zm9vYmE="}, {"foobar", "Zm9vYmFy"},
+    };
+
+    /** Each must be rejected: length, alphabet, '=' placement, padding bits. */
+    private static final String[] INVALID = {
+        "Z", "Zg=", "Zm9vY", "Zm9*", "Zm9v\n", "Zm 9", "Zå==", "====", "Z===", "=Zm9",
+        "Zg==Zm9v", "Zm=v", "Zh==", "Zm9=",
+    };
+
+    private static String line(String check, boolean passed) {
+        return String.format("%-48s %s\n", check, passed ? "ok" : "FAILED");
+    }
+
+    private static boolean rejects(String text) {
+        try {
+            decode(text);
+            return false;
+        } catch (IllegalArgumentException e) {
+            return true;
+        }
+    }
+
+    /**
+     * Self-check, one check per line: the RFC vectors both ways, round trips
+     * with 0, 1 and 2 padding characters, every byte value in every position
+     * of a group, and the invalid inputs rejected.
+     */
+    public static String verifyReport() {
+        StringBuilder sb = new StringBuilder();
+        for (String[] v : VECTORS) {
+            byte[] raw = v[0].getBytes(StandardCharsets.US_ASCII);
+            sb.append(line(String.format("\"%s\" <-> \"%s\"", v[0], v[1]),
+                encode(raw).equals(v[1]) && Arrays.equals(decode(v[1]), raw)));
+        }
+        Random random = new Random(4160);
+        for (int padding = 0; padding <= 2; padding++) {
+            boolean ok = true;
+            for (int len = (3 - padding) % 3; len <= 96; len += 3) {
+                byte[] data = new byte[len];
+                random.nextBytes(data);
+                String e = encode(data);
+                int pads = e.endsWith("==") ? 2 : e.endsWith("=") ? 1 : 0;
+                ok &= pads == padding && e.equals(java.util.Base64.getEncoder().encodeToString(data))
+                    && Arrays.equals(decode(e), data);
+            }
+            sb.append(line(String.format("round trips with %d '=' (random, up to 96 bytes)", padding), ok));
+        }
+        boolean everyByte = true;
+        for (int b = 0; b < 256; b++) {
+            for (int at = 0; at < 3; at++) {
+                byte[] data = new byte[at + 1];
+                data[at] = (byte) b;
+                everyByte &= Arrays.equals(decode(encode(data)), data);
+            }
+        }
+        sb.append(line("every byte value at offsets 0, 1 and 2", everyByte));
+        for (String bad : INVALID) {
+            sb.append(line("rejects \"" + bad.replace("\n", "\\n") + "\"", rejects(bad)));
+        }
+        return sb.toString();
+    }
+
+    /** true if every check in verifyReport() passed. */
+    public static boolean verify() {
+        return !verifyReport().contains("FAILED");
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static String bits(int value, int width) {
+        String s = Integer.toBinaryString(value);
+        while (s.length() < width) s = "0" + s;
+        return s;
+    }
+
+    /** The regrouping of one group of 1-3 bytes, step by step. */
+    public static String explainGroup(byte[] data, int offset) {
+        int left = Math.min(3, data.length - offset);
+        StringBuilder sb = new StringBuilder();
+        StringBuilder bytes = new StringBuilder();
+        StringBuilder joined = new StringBuilder();
+        for (int k = 0; k < 3; k++) {
+            if (k < left) {
+                bytes.append(String.format("%-9s", String.format("0x%02X", data[offset + k] & 0xFF)));
+                joined.append(bits(data[offset + k] & 0xFF, 8));
+            } else {
+                joined.append("00000000");
+            }
+        }
+        sb.append("   bytes    ").append(bytes.toString().trim()).append('\n');
+        sb.append("   8-bit    ");
+        for (int k = 0; k < 3; k++) {
+            if (k > 0) sb.append(' ');
+            sb.append(k < left ? joined.substring(8 * k, 8 * k + 8) : "(pad)");
+        }
+        sb.append("\n   6-bit    ");
+        int digits = left + 1;
+        StringBuilder values = new StringBuilder();
+        StringBuilder chars = new StringBuilder();
+        for (int k = 0; k < 4; k++) {
+            int v = Integer.parseInt(joined.substring(6 * k, 6 * k + 6), 2);
+            if (k > 0) sb.append(' ');
+            sb.append(joined, 6 * k, 6 * k + 6);
+            values.append(String.format("%-7s", k < digits ? v : "-"));
+            chars.append(String.format("%-7s", k < digits ? ALPHABET[v] : '='));
+        }
+        sb.append("\n   value    ").append(values.toString().trim());
+        sb.append("\n   char     ").append(chars.toString().trim()).append('\n');
+        return sb.toString();
+    }
+
+    private static String tryDecode(String text) {
+        try {
+            return "\"" + new String(decode(text), StandardCharsets.UTF_8) + "\"";
+        } catch (IllegalArgumentException e) {
+            return "rejected: " + e.getMessage();
+        }
+    }
+
+    public static String demonstrateBase64() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Base64 from First Principles ===\n\n");
+
+        byte[] man = "Man".getBytes(StandardCharsets.US_ASCII);
+        sb.append("1) \"Man\": 3 bytes regrouped into 4 six-bit digits\n");
+        sb.append(explainGroup(man, 0));
+        sb.append(String.format("   → \"%s\"\n\n", encode(man)));
+
+        sb.append("2) Padding when the last group is short:\n");
+        for (String s : new String[] {"Ma", "M"}) {
+            byte[] b = s.getBytes(StandardCharsets.US_ASCII);
+            sb.append(String.format("   \"%s\" (%d byte%s):\n", s, b.length, b.length == 1 ? "" : "s"));
+            sb.append(explainGroup(b, 0));
+            sb.append(String.format("   → \"%s\"\n", encode(b)));
+        }
+        sb.append('\n');
+
+        sb.append("3) Sizes: output is 4·ceil(n/3) characters\n");
+        for (String s : new String[] {"", "f", "fo", "foo", "foob", "fooba", "foobar"}) {
+            String e = encode(s.getBytes(StandardCharsets.US_ASCII));
+            sb.append(String.format("   %-9s → %-10s (%d → %d)\n", "\"" + s + "\"", "\"" + e + "\"", s.length(), e.length()));
+        }
+        sb.append('\n');
+
+        sb.append("4) Round trips:\n");
+        Random random = new Random(4160);
+        int agree = 0;
+        int trips = 0;
+        for (int len = 0; len <= 64; len++) {
+            byte[] data = new byte[len];
+            random.nextBytes(data);
+            String e = encode(data);
+            if (Arrays.equals(decode(e), data) && e.equals(java.util.Base64.getEncoder().encodeToString(data))) agree++;
+            trips++;
+        }
+        sb.append(String.format("   random data of 0-64 bytes: decode(encode(x)) == x and matches java.util.Base64: %d/%d\n",
+            agree, trips));
+        String utf8 = "Blåbær 🫐";
+        sb.append(String.format("   \"%s\" → %s → %s\n\n",
+            utf8, encode(utf8.getBytes(StandardCharsets.UTF_8)), tryDecode(encode(utf8.getBytes(StandardCharsets.UTF_8)))));
+
+        sb.append("5) The strict decoder:\n");
+        for (String s : new String[] {"TWFu", "TWE=", "TQ==", "TWF", "TW@u", "TR==", "TQ=Q"}) {
+            sb.append(String.format("   %-6s %s\n", s, tryDecode(s)));
+        }
+        sb.append("   \"TR==\": R = 010001, so the 4 bits after 'M' are 0001 - not zero, not canonical.\n");
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateBase64());
+            System.out.println("Usage: Base64 text | Base64 -d base64-text | Base64 --verify");
+            return;
+        }
+        if (args.length == 1 && args[0].equals("--verify")) {
+            String report = verifyReport();
+            System.out.print(report);
+            if (report.contains("FAILED")) System.exit(1);
+            return;
+        }
+        if (args[0].equals("-d") && args.length > 1) {
+            try {
+                System.out.println(new String(decode(args[1]), StandardCharsets.UTF_8));
+            } catch (IllegalArgumentException e) {
+                System.err.println(e.getMessage());
+                System.exit(2);
+            }
+            return;
+        }
+        System.out.println(encode(String.join(" ", args).getBytes(StandardCharsets.UTF_8)));
+    }
+}
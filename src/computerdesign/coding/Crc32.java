@@ -0,0 +1,197 @@
+package computerdesign.coding;
+
+import java.nio.charset.StandardCharsets;
+import java.util.Arrays;
+import java.util.Random;
+import java.util.zip.CRC32;
+
+/**
+ * Crc32 - The CRC-32 used by Ethernet, ZIP, PNG and gzip, computed one bit
+ * at a time and one byte at a time.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * A CRC IS THE REMAINDER OF A BINARY LONG DIVISION
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Treat the message bits as the coefficients of a polynomial over GF(2),
+ * where addition and subtraction are both XOR (no carries). Divide by a
+ * fixed generator polynomial; the remainder is the CRC:
+ *
+ *   G(x) = x^32 + x^26 + x^23 + x^22 + x^16 + x^12 + x^11 + x^10
+ *        + x^8 + x^7 + x^5 + x^4 + x^2 + x + 1      = 0x04C11DB7 (x^32 implied)
+ *
+ * Long division in hardware is a shift register: shift one message bit in;
+ * if the bit that falls out of the top is 1, "subtract" (XOR) the
+ * polynomial. CRC-32 as specified feeds bytes LSB first, so the register
+ * shifts RIGHT and uses the bit-reversed polynomial 0xEDB88320:
+ *
+ *   crc = 0xFFFFFFFF                        (start value, catches leading zeros)
+ *   for each byte b:
+ *       crc ^= b
+ *       repeat 8 times:
+ *           crc = (crc >>> 1) ^ (crc & 1 ? 0xEDB88320 : 0)
+ *   return ~crc                             (final XOR)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHERE THE TABLE COMES FROM
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * The 8 inner steps depend only on the low byte of (crc ^ b): the upper 24
+ * bits are just shifted right by 8. And the steps are LINEAR (only shifts
+ * and XORs), so the effect of that low byte can be computed once for each
+ * of its 256 values:
+ *
+ *   TABLE[n] = 8 shift/XOR steps applied to n
+ *   crc = (crc >>> 8) ^ TABLE[(crc ^ b) & 0xFF]      one lookup per byte
+ *
+ * Linearity also gives TABLE[a ^ b] = TABLE[a] ^ TABLE[b], so the whole
+ * table follows from the 8 entries for single bits.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * A CRC with a degree-32 generator detects every error burst up to 32 bits
+ * long, every odd number of flipped bits (G has an x + 1 factor) and all 1-
+ * and 2-bit errors in messages up to 2^32 bits long.
+ *
+ * Check value: CRC-32("123456789") = 0xCBF43926.
+ *
+ * Covers learning goals: T2.2 (data representation)
+ */
+public final class Crc32 {
+
+    /** 0x04C11DB7 with its bits reversed, for the right-shifting register. */
+    public static final int POLY_REFLECTED = 0xEDB88320;
+    /** The generator polynomial in normal (MSB-first) order, without x^32. */
+    public static final int POLY = 0x04C11DB7;
+
+    private static final int[] TABLE = buildTable();
+
+    private Crc32() {}
+
+    // ==================== BIT AT A TIME ====================
+
+    /** One shift/XOR step of the register. */
+    private static int step(int crc) {
+        return (crc >>> 1) ^ ((crc & 1) != 0 ? POLY_REFLECTED : 0);
+    }
+
+    public static int bitwise(byte[] data) {
+        int crc = 0xFFFFFFFF;
+        for (byte b : data) {
+            crc ^= b & 0xFF;
+            for (int i = 0; i < 8; i++) crc = step(crc);
+        }
+        return ~crc;
+    }
+
+    // ==================== TABLE DRIVEN ====================
+
+    private static int[] buildTable() {
+        int[] table = new int[256];
+        for (int n = 0; n < 256; n++) {
+            int crc = n;
+            for (int i = 0; i < 8; i++) crc = step(crc);
+            table[n] = crc;
+        }
+        return table;
+    }
+
+    /** A copy of the 256-entry lookup table. */
+    public static int[] table() {
+        return TABLE.clone();
+    }
+
+    public static int compute(byte[] data) {
+        return update(0, data, 0, data.length);
+    }
+
+    /**
+     * Continue a CRC over more data, like zlib's crc32(crc, buf, len): pass 0
+     * to start, then the previous result to extend it.
+     */
+    public static int update(int crc, byte[] data, int offset, int length) {
+        int c = ~crc;
+        for (int i = offset; i < offset + length; i++) {
+            c = (c >>> 8) ^ TABLE[(c ^ data[i]) & 0xFF];
+        }
+        return ~c;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static String bits32(int v) {
+        String s = Integer.toBinaryString(v);
+        while (s.length() < 32) s = "0" + s;
+        return s;
+    }
+
+    public static String demonstrateCrc32() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== CRC-32: Bit at a Time vs Table Driven ===\n\n");
+
+        sb.append("1) The shift register on the first byte of \"1\" (0x31), after crc ^= b:\n");
+        int crc = 0xFFFFFFFF ^ 0x31;
+        sb.append(String.format("   start  %s\n", bits32(crc)));
+        for (int i = 0; i < 8; i++) {
+            boolean out = (crc & 1) != 0;
+            crc = step(crc);
+            sb.append(String.format("   step %d %s  bit out %d → %s\n", i + 1, bits32(crc),
+                out ? 1 : 0, out ? "shift, XOR 0xEDB88320" : "shift only"));
+        }
+        sb.append("\n");
+
+        sb.append("2) The table: TABLE[n] = those 8 steps applied to n alone\n");
+        for (int n : new int[] {0x00, 0x01, 0x02, 0x80, 0xCE}) {
+            sb.append(String.format("   TABLE[0x%02X] = 0x%08X\n", n, TABLE[n]));
+        }
+        boolean linear = true;
+        for (int a = 0; a < 256; a++) {
+            for (int b = 0; b < 256; b++) linear &= TABLE[a ^ b] == (TABLE[a] ^ TABLE[b]);
+        }
+        sb.append(String.format("   TABLE[a ^ b] == TABLE[a] ^ TABLE[b] for all 65536 pairs: %b\n", linear));
+        sb.append(String.format("   All 8 steps of 1) in one lookup: (0xFFFFFFCE >>> 8) ^ TABLE[0xCE] = 0x%08X (= step 8)\n\n",
+            (0xFFFFFFCE >>> 8) ^ TABLE[0xCE]));
+
+        byte[] check = "123456789".getBytes(StandardCharsets.US_ASCII);
+        sb.append("3) Check value CRC-32(\"123456789\") should be 0xCBF43926:\n");
+        sb.append(String.format("   bitwise             0x%08X\n", bitwise(check)));
+        sb.append(String.format("   table               0x%08X\n", compute(check)));
+        CRC32 zip = new CRC32();
+        zip.update(check);
+        sb.append(String.format("   java.util.zip.CRC32 0x%08X\n", (int) zip.getValue()));
+        int incremental = update(update(0, check, 0, 4), check, 4, 5);
+        sb.append(String.format("   in two parts        0x%08X  (update(update(0, \"1234\"), \"56789\"))\n\n", incremental));
+
+        Random random = new Random(4160);
+        byte[] data = new byte[1 << 20];
+        random.nextBytes(data);
+        boolean agree = true;
+        for (int len = 0; len < 300; len += 7) {
+            byte[] part = Arrays.copyOf(data, len);
+            agree &= bitwise(part) == compute(part);
+        }
+        sb.append(String.format("4) Bitwise and table agree on 43 random messages of 0-294 bytes: %b\n", agree));
+
+        long t0 = System.nanoTime();
+        int a = bitwise(data);
+        long t1 = System.nanoTime();
+        int b = compute(data);
+        long t2 = System.nanoTime();
+        sb.append(String.format("   1 MiB: bitwise %.1f ms, table %.1f ms (%.1fx), same result: %b\n",
+            (t1 - t0) / 1e6, (t2 - t1) / 1e6, (double) (t1 - t0) / (t2 - t1), a == b));
+        sb.append("   One lookup replaces 8 data-dependent branches; hardware does all 32 XORs in parallel.\n");
+        return sb.toString();
+    }
+
+    /**
+     * Usage: Crc32 [text]   CRC-32 of the text (UTF-8), or the demonstration
+     */
+    public static void main(String[] args) {
+        if (args.length > 0) {
+            byte[] bytes = String.join(" ", args).getBytes(StandardCharsets.UTF_8);
+            System.out.printf("0x%08X  (%d bytes)%n", compute(bytes), bytes.length);
+            return;
+        }
+        System.out.println(demonstrateCrc32());
+    }
+}
@@ -0,0 +1,178 @@
+package computerdesign.coding;
+
+/**
+ * GF256 - Arithmetic in the finite field GF(2^8): the bytes, with addition
+ * and multiplication redefined so that division always works.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * BYTES AS POLYNOMIALS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * A byte b7..b0 is the polynomial b7·x^7 + ... + b1·x + b0 with bits as
+ * coefficients mod 2:
+ *
+ *   ADD      coefficient-wise mod 2 = XOR        (so a + a = 0, and - is +)
+ *   MULTIPLY polynomial product, then the remainder mod a fixed degree-8
+ *            polynomial - here x^8 + x^4 + x^3 + x^2 + 1 = 0x11D
+ *
+ *   0x53 · 0x02:  shift left → 0xA6 (no x^8 term, nothing to reduce)
+ *   0x80 · 0x02:  shift left → 0x100, has x^8 → XOR 0x11D → 0x1D
+ *
+ * This is the same carry-less arithmetic as CRC (see Crc32), with a
+ * reduction so results stay 8 bits. 0x11D is IRREDUCIBLE (the byte analog
+ * of a prime), so every non-zero byte has an inverse.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * LOG TABLES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * With this polynomial, α = 0x02 is a GENERATOR: its powers α^0 .. α^254
+ * run through all 255 non-zero bytes. So every non-zero byte has a
+ * logarithm, and
+ *
+ *   a · b = α^(log a + log b)      a / b = α^(log a - log b)
+ *
+ * turns multiplication into two lookups and an integer add - how every
+ * software Reed-Solomon (QR codes, RAID-6, CDs) does it.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Covers learning goals: T2.2 (data representation)
+ */
+public final class GF256 {
+
+    /** x^8 + x^4 + x^3 + x^2 + 1, the field polynomial used by QR codes and RAID-6. */
+    public static final int POLY = 0x11D;
+    /** α, the generator whose powers are all non-zero elements. */
+    public static final int GENERATOR = 0x02;
+
+    private static final int[] EXP = new int[512];    // doubled so exp(log a + log b) needs no mod
+    private static final int[] LOG = new int[256];
+
+    static {
+        int x = 1;
+        for (int i = 0; i < 255; i++) {
+            EXP[i] = x;
+            LOG[x] = i;
+            x = mulSlow(x, GENERATOR);
+        }
+        for (int i = 255; i < EXP.length; i++) EXP[i] = EXP[i - 255];
+    }
+
+    private GF256() {}
+
+    // ==================== ARITHMETIC ====================
+
+    public static int add(int a, int b) {
+        return a ^ b;
+    }
+
+    /** Shift-and-add multiplication, reducing by POLY when x^8 appears. */
+    public static int mulSlow(int a, int b) {
+        int product = 0;
+        while (b != 0) {
+            if ((b & 1) != 0) product ^= a;
+            a <<= 1;
+            if ((a & 0x100) != 0) a ^= POLY;
+            b >>= 1;
+        }
+        return product;
+    }
+
+    public static int mul(int a, int b) {
+        if (a == 0 || b == 0) return 0;
+        return EXP[LOG[a] + LOG[b]];
+    }
+
+    public static int div(int a, int b) {
+        if (b == 0) throw new ArithmeticException("Division by zero in GF(256)");
+        if (a == 0) return 0;
+        return EXP[LOG[a] + 255 - LOG[b]];
+    }
+
+    public static int inverse(int a) {
+        return div(1, a);
+    }
+
+    /** α^n for any n (negative allowed). */
+    public static int exp(int n) {
+        return EXP[Math.floorMod(n, 255)];
+    }
+
+    public static int log(int a) {
+        if (a == 0) throw new ArithmeticException("log(0) is undefined in GF(256)");
+        return LOG[a];
+    }
+
+    public static int pow(int a, int n) {
+        if (a == 0) return n == 0 ? 1 : 0;
+        return exp(LOG[a] * n);
+    }
+
+    // ==================== POLYNOMIALS ====================
+
+    /** Evaluate a polynomial (highest degree first) at x, by Horner's rule. */
+    public static int evaluate(int[] poly, int x) {
+        int y = 0;
+        for (int c : poly) y = mul(y, x) ^ c;
+        return y;
+    }
+
+    /** Product of two polynomials (highest degree first). */
+    public static int[] multiply(int[] p, int[] q) {
+        int[] r = new int[p.length + q.length - 1];
+        for (int i = 0; i < p.length; i++) {
+            for (int j = 0; j < q.length; j++) r[i + j] ^= mul(p[i], q[j]);
+        }
+        return r;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static String bits(int v) {
+        String s = Integer.toBinaryString(v);
+        while (s.length() < 8) s = "0" + s;
+        return s;
+    }
+
+    public static String demonstrateGF256() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== GF(2^8) Arithmetic ===\n\n");
+
+        sb.append(String.format("1) Addition is XOR: 0x53 + 0xCA = 0x%02X, and 0x53 + 0x53 = 0x%02X\n\n",
+            add(0x53, 0xCA), add(0x53, 0x53)));
+
+        sb.append("2) Multiplication, step by step: 0x57 · 0x13 (0x13 = x^4 + x + 1)\n");
+        int a = 0x57;
+        for (int i = 0; i <= 4; i++) {
+            sb.append(String.format("   0x57 · x^%d = %s = 0x%02X%s\n", i, bits(a), a,
+                (0x13 >> i & 1) != 0 ? "  ← used" : ""));
+            a <<= 1;
+            if ((a & 0x100) != 0) a ^= POLY;
+        }
+        sb.append(String.format("   XOR of the used rows = 0x%02X; via log tables: 0x%02X\n\n",
+            mulSlow(0x57, 0x13), mul(0x57, 0x13)));
+
+        sb.append("3) Powers of α = 0x02 (the start of the EXP table):\n   ");
+        for (int i = 0; i < 16; i++) sb.append(String.format("%02X ", exp(i)));
+        boolean[] seen = new boolean[256];
+        int distinct = 0;
+        for (int i = 0; i < 255; i++) if (!seen[exp(i)]) { seen[exp(i)] = true; distinct++; }
+        sb.append(String.format("...\n   α^0..α^254 hit %d distinct non-zero bytes; α^255 = 0x%02X = α^0\n\n",
+            distinct, exp(255)));
+
+        boolean ok = true;
+        for (int x = 1; x < 256; x++) {
+            ok &= mul(x, inverse(x)) == 1;
+            for (int y = 0; y < 256; y++) ok &= mul(x, y) == mulSlow(x, y);
+        }
+        sb.append(String.format("4) Every x · inverse(x) == 1 and table == shift-and-add for all pairs: %b\n", ok));
+        sb.append(String.format("   e.g. inverse(0x53) = 0x%02X; 0x53 · 0x%02X = 0x%02X\n",
+            inverse(0x53), inverse(0x53), mul(0x53, inverse(0x53))));
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        System.out.println(demonstrateGF256());
+    }
+}
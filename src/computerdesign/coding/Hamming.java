@@ -0,0 +1,315 @@
+package computerdesign.coding;
+
+/**
+ * Hamming - Parity bits and single-error-correcting Hamming codes.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * PARITY: DETECT ONE FLIPPED BIT
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * One extra bit makes the number of 1s even (EVEN parity) or odd (ODD
+ * parity). Any single flipped bit breaks the rule, so the receiver knows
+ * something is wrong - but not WHICH bit, and two flips cancel out.
+ *
+ *   data 1011001  (four 1s)  →  even parity bit 0,  odd parity bit 1
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * HAMMING CODES: LOCATE (AND SO CORRECT) ONE FLIPPED BIT
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Number the codeword positions 1..n. Put parity bits at the powers of two
+ * and data bits everywhere else. Parity bit p_i covers every position whose
+ * number has bit i set:
+ *
+ *   position   1   2   3   4   5   6   7        Hamming(7,4)
+ *   binary   001 010 011 100 101 110 111
+ *   role      p1  p2  d1  p4  d2  d3  d4
+ *   p1 (xx1)   ●       ●       ●       ●
+ *   p2 (x1x)       ●   ●           ●   ●
+ *   p4 (1xx)               ●   ●   ●   ●
+ *
+ * The receiver recomputes each check. The failing checks, read as a binary
+ * number, are the SYNDROME - and the syndrome IS the position of the flipped
+ * bit (0 = no error). Flip it back and the data is correct.
+ *
+ * With r parity bits the code has n = 2^r - 1 positions and k = n - r data
+ * bits: (7,4) for r = 3, (15,11) for r = 4, (31,26) for r = 5.
+ *
+ * LIMIT: two flipped bits give a non-zero syndrome that points at a THIRD,
+ * innocent bit, which "correction" then breaks. ECC memory adds one overall
+ * parity bit to tell the cases apart (SECDED).
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Codewords are ints with position p stored in bit p-1.
+ *
+ * Covers learning goals: T2.2 (data representation), T6.1 (memory reliability)
+ */
+public final class Hamming {
+
+    /** Hamming(7,4): 4 data bits, 3 parity bits. */
+    public static final Hamming H7_4 = new Hamming(3);
+    /** Hamming(15,11): 11 data bits, 4 parity bits. */
+    public static final Hamming H15_11 = new Hamming(4);
+
+    private final int r;
+    private final int n;
+    private final int k;
+
+    /** The Hamming code with `parityBits` check bits (2-5, so the codeword fits an int). */
+    public Hamming(int parityBits) {
+        if (parityBits < 2 || parityBits > 5) {
+            throw new IllegalArgumentException("Parity bits must be 2-5, got " + parityBits);
+        }
+        this.r = parityBits;
+        this.n = (1 << parityBits) - 1;
+        this.k = n - parityBits;
+    }
+
+    public int getCodewordBits() { return n; }
+    public int getDataBits() { return k; }
+    public int getParityBits() { return r; }
+
+    @Override
+    public String toString() {
+        return "Hamming(" + n + "," + k + ")";
+    }
+
+    // ==================== PARITY ====================
+
+    /** 1 if `bits` has an odd number of 1s: the XOR of all its bits. */
+    public static int parity(int bits) {
+        return Integer.bitCount(bits) & 1;
+    }
+
+    /** The bit that makes the total number of 1s even. */
+    public static int evenParityBit(int data) {
+        return parity(data);
+    }
+
+    /** The bit that makes the total number of 1s odd. */
+    public static int oddParityBit(int data) {
+        return parity(data) ^ 1;
+    }
+
+    // ==================== ENCODE ====================
+
+    private static boolean isPowerOfTwo(int position) {
+        return (position & (position - 1)) == 0;
+    }
+
+    /** Place the k data bits (LSB = d1) into the non-power-of-two positions and fill in the parity bits. */
+    public int encode(int data) {
+        if (data < 0 || data >= (1 << k)) {
+            throw new IllegalArgumentException(String.format("%s holds %d data bits, got 0x%X", this, k, data));
+        }
+        int code = 0;
+        int next = 0;
+        for (int pos = 1; pos <= n; pos++) {
+            if (!isPowerOfTwo(pos)) {
+                if ((data >> next++ & 1) != 0) code |= 1 << (pos - 1);
+            }
+        }
+        // With the parity bits still 0, the syndrome says which checks fail:
+        // set exactly those parity bits and every check passes.
+        int s = syndrome(code);
+        for (int i = 0; i < r; i++) {
+            if ((s >> i & 1) != 0) code |= 1 << ((1 << i) - 1);
+        }
+        return code;
+    }
+
+    /** The k data bits of a codeword, without any checking. */
+    public int extractData(int code) {
+        int data = 0;
+        int next = 0;
+        for (int pos = 1; pos <= n; pos++) {
+            if (!isPowerOfTwo(pos)) data |= (code >> (pos - 1) & 1) << next++;
+        }
+        return data;
+    }
+
+    // ==================== DECODE ====================
+
+    /**
+     * XOR of the positions of all 1 bits. Bit i of the result is check p_(2^i)
+     * failing, so it is 0 for a valid codeword and the position of the
+     * flipped bit when exactly one bit has flipped.
+     */
+    public int syndrome(int code) {
+        int s = 0;
+        for (int pos = 1; pos <= n; pos++) {
+            if ((code >> (pos - 1) & 1) != 0) s ^= pos;
+        }
+        return s;
+    }
+
+    /** The outcome of decoding one codeword. */
+    public static final class Decoded {
+        public final int data;
+        public final int syndrome;
+        public final int corrected;    // the repaired codeword
+
+        Decoded(int data, int syndrome, int corrected) {
+            this.data = data;
+            this.syndrome = syndrome;
+            this.corrected = corrected;
+        }
+
+        public boolean hadError() {
+            return syndrome != 0;
+        }
+    }
+
+    /** Correct up to one flipped bit and return the data. */
+    public Decoded decode(int code) {
+        int s = syndrome(code);
+        int fixed = s == 0 ? code : flip(code, s);
+        return new Decoded(extractData(fixed), s, fixed);
+    }
+
+    /** Flip the bit at a 1-based position. */
+    public int flip(int code, int position) {
+        if (position < 1 || position > n) {
+            throw new IllegalArgumentException(String.format("Position must be 1-%d, got %d", n, position));
+        }
+        return code ^ (1 << (position - 1));
+    }
+
+    // ==================== FORMATTING ====================
+
+    /** Role of a position: p1, p2, p4, ... or d1, d2, ... */
+    public String role(int position) {
+        if (isPowerOfTwo(position)) return "p" + position;
+        return "d" + (position - (32 - Integer.numberOfLeadingZeros(position)));
+    }
+
+    /** Codeword bits from position 1 to n (the order of the table above). */
+    public String bits(int code) {
+        StringBuilder sb = new StringBuilder();
+        for (int pos = 1; pos <= n; pos++) sb.append(code >> (pos - 1) & 1);
+        return sb.toString();
+    }
+
+    private static String binary(int value, int width) {
+        String s = Integer.toBinaryString(value);
+        while (s.length() < width) s = "0" + s;
+        return s;
+    }
+
+    /** The parity checks on a received word, one line each, and the syndrome they spell. */
+    public String explainSyndrome(int code) {
+        StringBuilder sb = new StringBuilder();
+        int s = 0;
+        for (int i = 0; i < r; i++) {
+            int p = 1 << i;
+            StringBuilder covered = new StringBuilder();
+            int ones = 0;
+            for (int pos = 1; pos <= n; pos++) {
+                if ((pos & p) != 0) {
+                    int bit = code >> (pos - 1) & 1;
+                    ones += bit;
+                    if (covered.length() > 0) covered.append(' ');
+                    covered.append(bit);
+                }
+            }
+            int fails = ones & 1;
+            s |= fails << i;
+            sb.append(String.format("   check p%-2d positions with bit %d set: %s  → %d ones, %s\n",
+                p, i, covered, ones, fails == 0 ? "even (pass)" : "ODD (fail)"));
+        }
+        sb.append(String.format("   syndrome = %s₂ = %d%s\n", binary(s, r), s,
+            s == 0 ? "  (no error)" : "  → flip position " + s + " (" + role(s) + ")"));
+        return sb.toString();
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateHamming(int data, int flipPosition) {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Parity and Hamming Codes ===\n\n");
+
+        sb.append("1) A parity bit detects one flip, not two:\n");
+        int byteValue = 0b1011001;
+        int withParity = byteValue << 1 | evenParityBit(byteValue);
+        sb.append(String.format("   data 1011001 + even parity %d  → sent %s\n",
+            evenParityBit(byteValue), Integer.toBinaryString(withParity)));
+        sb.append(String.format("   one bit flipped:   %s  parity %s\n",
+            Integer.toBinaryString(withParity ^ 0b100), parity(withParity ^ 0b100) == 0 ? "ok" : "ERROR detected"));
+        sb.append(String.format("   two bits flipped:  %s  parity %s\n\n",
+            Integer.toBinaryString(withParity ^ 0b10100), parity(withParity ^ 0b10100) == 0 ? "ok (missed!)" : "ERROR detected"));
+
+        Hamming h = H7_4;
+        sb.append("2) ").append(h).append(": parity bits at positions 1, 2, 4\n   position ");
+        for (int pos = 1; pos <= h.n; pos++) sb.append(String.format("%4d", pos));
+        sb.append("\n   role     ");
+        for (int pos = 1; pos <= h.n; pos++) sb.append(String.format("%4s", h.role(pos)));
+        int code = h.encode(data & 0xF);
+        sb.append("\n   bit      ");
+        for (char c : h.bits(code).toCharArray()) sb.append("   ").append(c);
+        sb.append(String.format("\n   data %s (d4..d1) → codeword %s\n\n", binary(data & 0xF, 4), h.bits(code)));
+
+        int received = h.flip(code, flipPosition);
+        sb.append(String.format("3) Position %d (%s) flips in transit: received %s\n",
+            flipPosition, h.role(flipPosition), h.bits(received)));
+        sb.append(h.explainSyndrome(received));
+        Decoded d = h.decode(received);
+        sb.append(String.format("   corrected %s → data %s %s\n\n", h.bits(d.corrected),
+            binary(d.data, 4), d.data == (data & 0xF) ? "(matches what was sent)" : "(WRONG)"));
+
+        sb.append("4) Every single-bit error in every (7,4) codeword is corrected:\n");
+        int corrected = 0;
+        for (int v = 0; v < 16; v++) {
+            for (int pos = 1; pos <= 7; pos++) {
+                if (h.decode(h.flip(h.encode(v), pos)).data == v) corrected++;
+            }
+        }
+        sb.append(String.format("   %d of %d\n\n", corrected, 16 * 7));
+
+        sb.append("5) Two flips fool it: the syndrome points at an innocent bit\n");
+        int two = h.flip(h.flip(code, 1), 2);
+        Decoded bad = h.decode(two);
+        sb.append(String.format("   flip 1 and 2 → syndrome %d → \"corrected\" data %s, sent %s\n",
+            bad.syndrome, binary(bad.data, 4), binary(data & 0xF, 4)));
+        sb.append("   (1 XOR 2 = 3: the syndrome of two errors is the XOR of their positions)\n\n");
+
+        Hamming big = H15_11;
+        int word = 0b10110011101;
+        int bigCode = big.encode(word);
+        sb.append(String.format("6) %s: same rule, 4 parity bits for 11 data bits (overhead %.0f%% vs %.0f%%)\n",
+            big, 100.0 * 4 / 11, 100.0 * 3 / 4));
+        sb.append(String.format("   data %s → %s\n", binary(word, 11), big.bits(bigCode)));
+        int bigReceived = big.flip(bigCode, 13);
+        sb.append(String.format("   flip position 13 (%s) → syndrome %d, data %s\n",
+            big.role(13), big.syndrome(bigReceived), big.decode(bigReceived).data == word ? "recovered" : "LOST"));
+        return sb.toString();
+    }
+
+    public static String demonstrateHamming() {
+        return demonstrateHamming(0b1011, 6);
+    }
+
+    /**
+     * Usage: Hamming [data 0-15] [position to flip 1-7]
+     */
+    public static void main(String[] args) {
+        try {
+            int data = argument(args, 0, 0b1011);
+            int flip = argument(args, 1, 6);
+            if (data < 0 || data > 15) throw new IllegalArgumentException("Data must be 0-15, got " + data);
+            System.out.println(demonstrateHamming(data, flip));
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage() + "\nUsage: Hamming [data 0-15] [position to flip 1-7]");
+            System.exit(2);
+        }
+    }
+
+    private static int argument(String[] args, int i, int otherwise) {
+        if (i >= args.length) return otherwise;
+        try {
+            return Integer.parseInt(args[i]);
+        } catch (NumberFormatException e) {
+            throw new IllegalArgumentException("Not a number: " + args[i]);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+package computerdesign.coding;
+
+import java.util.ArrayList;
+import java.util.List;
+import java.util.Map;
+import java.util.PriorityQueue;
+import java.util.TreeMap;
+
+/**
+ * Huffman - Variable-length prefix codes: frequent symbols get short codes.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * BUILDING THE TREE
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Start with one leaf per symbol, weighted by how often it occurs. Repeatedly
+ * take the two lightest trees and join them under a new node whose weight is
+ * their sum. When one tree is left, every symbol's code is the path from the
+ * root to its leaf: 0 = left, 1 = right.
+ *
+ *   "ABRACADABRA"   A:5  B:2  R:2  C:1  D:1
+ *
+ *   C+D → 2,   B+R → 4,   (C,D)+(B,R) → 6,   A+6 → 11
+ *
+ *          (11)
+ *         0/  \1
+ *         A   (6)              A = 0      B = 110   D = 101
+ *            0/  \1                       R = 111   C = 100
+ *           (2)  (4)
+ *          C  D  B  R
+ *
+ * Because symbols sit only at leaves, no code is a PREFIX of another, so the
+ * bit stream needs no separators: the decoder walks from the root and emits
+ * a symbol each time it reaches a leaf.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * HOW GOOD IS IT?
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * A fixed-width code needs ceil(log2(distinct symbols)) bits per symbol.
+ * Huffman's average length L is optimal among symbol-by-symbol prefix codes
+ * and within 1 bit of the ENTROPY H = -Σ p·log2(p), the lower bound for any
+ * such code: H ≤ L < H + 1. ASCII text uses 8 bits whatever the content.
+ *
+ * The code table (or the frequencies) must be sent with the message, which
+ * is why tiny inputs may not shrink at all.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: Huffman [text]   tree, code table and sizes for the text
+ *
+ * Covers learning goals: T2.2 (data representation)
+ */
+public final class Huffman {
+
+    /** A tree node: a leaf holds a symbol, an inner node two children. */
+    public static final class Node {
+        public final int symbol;       // code point for leaves, -1 for inner nodes
+        public final int weight;
+        public final Node left;
+        public final Node right;
+        private final int order;       // creation order, to break weight ties deterministically
+
+        Node(int symbol, int weight, Node left, Node right, int order) {
+            this.symbol = symbol;
+            this.weight = weight;
+            this.left = left;
+            this.right = right;
+            this.order = order;
+        }
+
+        public boolean isLeaf() {
+            return left == null;
+        }
+    }
+
+    private final Node root;
+    private final Map<Integer, Integer> frequencies;
+    private final Map<Integer, String> codes = new TreeMap<>();
+
+    /** Build the code for the symbol frequencies of `text`. */
+    public Huffman(String text) {
+        if (text.isEmpty()) throw new IllegalArgumentException("Cannot build a code for empty text");
+        frequencies = new TreeMap<>();
+        text.codePoints().forEach(cp -> frequencies.merge(cp, 1, Integer::sum));
+
+        PriorityQueue<Node> queue = new PriorityQueue<>((a, b) ->
+            a.weight != b.weight ? Integer.compare(a.weight, b.weight) : Integer.compare(a.order, b.order));
+        int order = 0;
+        for (Map.Entry<Integer, Integer> e : frequencies.entrySet()) {
+            queue.add(new Node(e.getKey(), e.getValue(), null, null, order++));
+        }
+        while (queue.size() > 1) {
+            Node a = queue.poll();
+            Node b = queue.poll();
+            queue.add(new Node(-1, a.weight + b.weight, a, b, order++));
+        }
+        root = queue.poll();
+        assignCodes(root, "");
+    }
+
+    private void assignCodes(Node node, String path) {
+        if (node.isLeaf()) {
+            codes.put(node.symbol, path.isEmpty() ? "0" : path);   // one distinct symbol still needs 1 bit
+            return;
+        }
+        assignCodes(node.left, path + "0");
+        assignCodes(node.right, path + "1");
+    }
+
+    public Node getRoot() { return root; }
+
+    /** Symbol → code, in symbol order. */
+    public Map<Integer, String> getCodes() { return new TreeMap<>(codes); }
+
+    public Map<Integer, Integer> getFrequencies() { return new TreeMap<>(frequencies); }
+
+    // ==================== ENCODE / DECODE ====================
+
+    /** The message as a string of '0' and '1'. */
+    public String encode(String text) {
+        StringBuilder bits = new StringBuilder();
+        text.codePoints().forEach(cp -> {
+            String code = codes.get(cp);
+            if (code == null) throw new IllegalArgumentException("Symbol not in code: " + show(cp));
+            bits.append(code);
+        });
+        return bits.toString();
+    }
+
+    /** Walk the tree bit by bit; each leaf reached is one symbol. */
+    public String decode(String bits) {
+        StringBuilder out = new StringBuilder();
+        Node node = root;
+        for (int i = 0; i < bits.length(); i++) {
+            char c = bits.charAt(i);
+            if (c != '0' && c != '1') throw new IllegalArgumentException("Not a bit at " + i + ": " + c);
+            if (!root.isLeaf()) node = c == '0' ? node.left : node.right;
+            if (node.isLeaf()) {
+                out.appendCodePoint(node.symbol);
+                node = root;
+            }
+        }
+        if (node != root) throw new IllegalArgumentException("Bit string ends in the middle of a code");
+        return out.toString();
+    }
+
+    // ==================== MEASURES ====================
+
+    /** Bits per symbol for a fixed-width code over the same alphabet. */
+    public int fixedWidthBits() {
+        int distinct = frequencies.size();
+        return Math.max(1, 32 - Integer.numberOfLeadingZeros(distinct - 1));
+    }
+
+    public double entropy() {
+        double total = root.weight;
+        double h = 0;
+        for (int f : frequencies.values()) {
+            double p = f / total;
+            h -= p * Math.log(p) / Math.log(2);
+        }
+        return h;
+    }
+
+    public double averageCodeLength() {
+        long bits = 0;
+        for (Map.Entry<Integer, Integer> e : frequencies.entrySet()) {
+            bits += (long) e.getValue() * codes.get(e.getKey()).length();
+        }
+        return (double) bits / root.weight;
+    }
+
+    // ==================== FORMATTING ====================
+
+    private static String show(int cp) {
+        if (cp == ' ') return "' '";
+        if (cp == '\n') return "\\n";
+        if (cp < 0x20) return String.format("0x%02X", cp);
+        return new String(Character.toChars(cp));
+    }
+
+    /** The tree sideways, root at the left, 0-branch above the 1-branch. */
+    public String tree() {
+        List<String> lines = new ArrayList<>();
+        drawTree(root, "", "", lines);
+        return String.join("\n", lines) + "\n";
+    }
+
+    private void drawTree(Node node, String prefix, String edge, List<String> lines) {
+        String label = node.isLeaf()
+            ? String.format("%s %s (%d)", edge, show(node.symbol), node.weight)
+            : String.format("%s (%d)", edge, node.weight);
+        lines.add(prefix + label.trim());
+        if (node.isLeaf()) return;
+        String child = prefix.replace("├─", "│ ").replace("└─", "  ");
+        drawTree(node.left, child + "├─", "0", lines);
+        drawTree(node.right, child + "└─", "1", lines);
+    }
+
+    /** Symbol, count, code and code length, most frequent first. */
+    public String codeTable() {
+        List<Map.Entry<Integer, Integer>> entries = new ArrayList<>(frequencies.entrySet());
+        entries.sort((a, b) -> {
+            int byCount = Integer.compare(b.getValue(), a.getValue());
+            return byCount != 0 ? byCount : Integer.compare(a.getKey(), b.getKey());
+        });
+        StringBuilder sb = new StringBuilder(String.format("   %-6s %5s  %-16s %s\n", "symbol", "count", "code", "bits"));
+        for (Map.Entry<Integer, Integer> e : entries) {
+            String code = codes.get(e.getKey());
+            sb.append(String.format("   %-6s %5d  %-16s %d\n", show(e.getKey()), e.getValue(), code, code.length()));
+        }
+        return sb.toString();
+    }
+
+    /** Sizes of the message under Huffman, fixed-width and 8-bit coding. */
+    public String report(String text) {
+        int symbols = root.weight;
+        int huffman = encode(text).length();
+        int fixed = symbols * fixedWidthBits();
+        int ascii = symbols * 8;
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("   %d symbols, %d distinct\n", symbols, frequencies.size()));
+        sb.append(String.format("   8-bit characters   %6d bits\n", ascii));
+        sb.append(String.format("   fixed width (%d)    %6d bits  (%.1f%% of 8-bit)\n",
+            fixedWidthBits(), fixed, 100.0 * fixed / ascii));
+        sb.append(String.format("   Huffman            %6d bits  (%.1f%% of 8-bit, %.1f%% of fixed width)\n",
+            huffman, 100.0 * huffman / ascii, 100.0 * huffman / fixed));
+        sb.append(String.format("   average %.3f bits/symbol, entropy %.3f: H <= L < H + 1\n",
+            averageCodeLength(), entropy()));
+        return sb.toString();
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateHuffman(String text) {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Huffman Coding ===\n\n");
+        Huffman h = new Huffman(text);
+        sb.append(String.format("Message: \"%s\"\n\n", text));
+
+        sb.append("1) Tree (0 = upper branch, 1 = lower; weights in parentheses):\n");
+        for (String line : h.tree().split("\n")) sb.append("   ").append(line).append('\n');
+
+        sb.append("\n2) Code table:\n").append(h.codeTable());
+
+        String bits = h.encode(text);
+        sb.append("\n3) Encoded:\n");
+        for (int i = 0; i < bits.length(); i += 64) {
+            sb.append("   ").append(bits, i, Math.min(bits.length(), i + 64)).append('\n');
+        }
+        String back = h.decode(bits);
+        sb.append(String.format("   decoded back: \"%s\" (%s)\n", back, back.equals(text) ? "matches" : "MISMATCH"));
+
+        sb.append("\n4) Size:\n").append(h.report(text));
+
+        String skewed = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaabbbbbbbbccccdde";
+        Huffman s = new Huffman(skewed);
+        sb.append("\n5) Skewed frequencies compress well, uniform ones do not:\n");
+        sb.append(String.format("   \"%s\": %.2f bits/symbol vs %d fixed\n",
+            skewed, s.averageCodeLength(), s.fixedWidthBits()));
+        Huffman u = new Huffman("abcdefgh");
+        sb.append(String.format("   \"abcdefgh\": %.2f bits/symbol vs %d fixed (8 equally likely symbols: no gain)\n",
+            u.averageCodeLength(), u.fixedWidthBits()));
+        return sb.toString();
+    }
+
+    public static String demonstrateHuffman() {
+        return demonstrateHuffman("ABRACADABRA");
+    }
+
+    public static void main(String[] args) {
+        if (args.length > 0) {
+            System.out.println(demonstrateHuffman(String.join(" ", args)));
+            return;
+        }
+        System.out.println(demonstrateHuffman());
+    }
+}
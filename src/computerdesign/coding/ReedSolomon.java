@@ -0,0 +1,276 @@
+package computerdesign.coding;
+
+import java.nio.charset.StandardCharsets;
+import java.util.Arrays;
+
+/**
+ * ReedSolomon - A toy Reed-Solomon code over GF(2^8): whole bytes, not
+ * bits, are the symbols, and any `parity` lost bytes can be rebuilt.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE IDEA
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Treat the k message bytes as coefficients of a polynomial and append
+ * `parity` check bytes chosen so that the whole codeword c(x) is divisible by
+ *
+ *   g(x) = (x - α^0)(x - α^1) ... (x - α^(parity-1))
+ *
+ * Then c(α^i) = 0 for every i < parity. The receiver evaluates the received
+ * word at those points: these SYNDROMES are all zero for a clean codeword.
+ *
+ * Encoding is systematic: parity = (message · x^parity) mod g(x), the same
+ * shift-register division as a CRC but with byte arithmetic from GF256.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * ERASURES VS ERRORS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * An ERASURE is a byte known to be bad (a missing packet, a dead disk). If
+ * the erased positions have powers X_1..X_e and error values E_1..E_e,
+ *
+ *   S_i = E_1·X_1^i + ... + E_e·X_e^i        for i = 0 .. parity-1
+ *
+ * is `parity` linear equations in e unknowns: solvable when e ≤ parity.
+ *
+ * An ERROR at an unknown position costs twice as much - its location is a
+ * second unknown - so `parity` check bytes correct up to parity/2 errors.
+ * Here only the single-error case is solved (X = S_1 / S_0, E = S_0);
+ * full decoders find the locations with Berlekamp-Massey.
+ *
+ *   RS(255,223) on CDs and in deep space: 32 parity bytes, 16 errors or 32 erasures.
+ *   RAID-6: 2 parity disks, any 2 failed disks rebuilt.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: ReedSolomon [text]   encode, erase and recover the text
+ *
+ * Covers learning goals: T2.2 (data representation), T6.1 (memory reliability)
+ */
+public final class ReedSolomon {
+
+    private final int parity;
+    private final int[] generator;
+
+    public ReedSolomon(int paritySymbols) {
+        if (paritySymbols < 1 || paritySymbols > 254) {
+            throw new IllegalArgumentException("Parity symbols must be 1-254, got " + paritySymbols);
+        }
+        this.parity = paritySymbols;
+        int[] g = {1};
+        for (int i = 0; i < paritySymbols; i++) g = GF256.multiply(g, new int[] {1, GF256.exp(i)});
+        this.generator = g;
+    }
+
+    public int getParitySymbols() { return parity; }
+
+    /** g(x), highest degree first. */
+    public int[] getGenerator() { return generator.clone(); }
+
+    // ==================== ENCODE ====================
+
+    /** Message followed by its parity bytes; at most 255 bytes in total. */
+    public byte[] encode(byte[] message) {
+        if (message.length + parity > 255) {
+            throw new IllegalArgumentException(String.format(
+                "Codeword of %d bytes exceeds 255", message.length + parity));
+        }
+        int[] remainder = new int[parity];
+        for (byte b : message) {
+            int factor = (b & 0xFF) ^ remainder[0];                    // divide one coefficient
+            System.arraycopy(remainder, 1, remainder, 0, parity - 1);
+            remainder[parity - 1] = 0;
+            for (int j = 0; j < parity; j++) remainder[j] ^= GF256.mul(generator[j + 1], factor);
+        }
+        byte[] out = Arrays.copyOf(message, message.length + parity);
+        for (int j = 0; j < parity; j++) out[message.length + j] = (byte) remainder[j];
+        return out;
+    }
+
+    // ==================== DECODE ====================
+
+    /** S_i = r(α^i) for i < parity; all zero for a valid codeword. */
+    public int[] syndromes(byte[] received) {
+        int[] r = new int[received.length];
+        for (int i = 0; i < r.length; i++) r[i] = received[i] & 0xFF;
+        int[] s = new int[parity];
+        for (int i = 0; i < parity; i++) s[i] = GF256.evaluate(r, GF256.exp(i));
+        return s;
+    }
+
+    public boolean isValid(byte[] received) {
+        for (int s : syndromes(received)) if (s != 0) return false;
+        return true;
+    }
+
+    /** Power of x held by byte `index` of a codeword of `length` bytes. */
+    private static int power(int length, int index) {
+        return length - 1 - index;
+    }
+
+    /**
+     * Rebuild the bytes at the given positions (their current values are
+     * ignored) by solving the syndrome equations with Gaussian elimination.
+     */
+    public byte[] recoverErasures(byte[] received, int... positions) {
+        int e = positions.length;
+        if (e > parity) {
+            throw new IllegalArgumentException(String.format(
+                "%d erasures, but %d parity symbols can rebuild at most %d", e, parity, parity));
+        }
+        boolean[] seen = new boolean[received.length];
+        for (int p : positions) {
+            if (p < 0 || p >= received.length) {
+                throw new IllegalArgumentException(String.format(
+                    "Erasure at %d is outside the codeword of %d bytes", p, received.length));
+            }
+            if (seen[p]) throw new IllegalArgumentException("Erasure at " + p + " given twice");
+            seen[p] = true;
+        }
+        byte[] word = received.clone();
+        for (int p : positions) word[p] = 0;
+        int[] s = syndromes(word);
+
+        // Row i: Σ_j E_j · X_j^i = S_i, using the first e equations
+        int[][] m = new int[e][e + 1];
+        for (int i = 0; i < e; i++) {
+            for (int j = 0; j < e; j++) m[i][j] = GF256.exp(power(word.length, positions[j]) * i);
+            m[i][e] = s[i];
+        }
+        for (int col = 0; col < e; col++) {
+            int pivot = col;
+            while (m[pivot][col] == 0) pivot++;                         // Vandermonde of distinct X_j: never singular
+            int[] t = m[col]; m[col] = m[pivot]; m[pivot] = t;
+            int inv = GF256.inverse(m[col][col]);
+            for (int c = col; c <= e; c++) m[col][c] = GF256.mul(m[col][c], inv);
+            for (int row = 0; row < e; row++) {
+                if (row == col || m[row][col] == 0) continue;
+                int f = m[row][col];
+                for (int c = col; c <= e; c++) m[row][c] ^= GF256.mul(f, m[col][c]);
+            }
+        }
+        for (int j = 0; j < e; j++) word[positions[j]] = (byte) m[j][e];   // word was 0 there, so E_j is the byte
+        return word;
+    }
+
+    /**
+     * Correct one byte error at an unknown position (needs 2+ parity
+     * symbols). Returns the position fixed, -1 if there was no error, or
+     * throws if the syndromes are not those of a single error.
+     */
+    public int correctSingleError(byte[] word) {
+        if (parity < 2) throw new IllegalStateException("Locating an error needs at least 2 parity symbols");
+        int[] s = syndromes(word);
+        boolean clean = true;
+        for (int v : s) clean &= v == 0;
+        if (clean) return -1;
+        if (s[0] == 0 || s[1] == 0) throw new IllegalStateException("More than one error: cannot correct");
+        int x = GF256.div(s[1], s[0]);                                   // X = α^power
+        int position = word.length - 1 - GF256.log(x);
+        if (position < 0) throw new IllegalStateException("More than one error: cannot correct");
+        byte[] fixed = word.clone();
+        fixed[position] ^= (byte) s[0];
+        if (!isValid(fixed)) throw new IllegalStateException("More than one error: cannot correct");
+        System.arraycopy(fixed, 0, word, 0, word.length);
+        return position;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static String show(byte[] data, int messageLength) {
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < data.length; i++) {
+            if (i == messageLength) sb.append("| ");
+            int b = data[i] & 0xFF;
+            sb.append(i < messageLength && b >= 0x20 && b < 0x7F ? " " + (char) b + " " : String.format("%02X ", b));
+        }
+        return sb.toString().trim();
+    }
+
+    private static String syndromeText(int[] s) {
+        StringBuilder sb = new StringBuilder();
+        for (int v : s) sb.append(String.format("%02X ", v));
+        return sb.toString().trim();
+    }
+
+    public static String demonstrateReedSolomon(String text) {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Reed-Solomon over GF(256) ===\n\n");
+        ReedSolomon rs = new ReedSolomon(4);
+        byte[] message = text.getBytes(StandardCharsets.UTF_8);
+        int k = message.length;
+
+        StringBuilder g = new StringBuilder();
+        for (int c : rs.getGenerator()) g.append(String.format("%02X ", c));
+        sb.append(String.format("1) RS(%d,%d): 4 parity bytes, g(x) = (x-1)(x-α)(x-α²)(x-α³) = [%s]\n\n",
+            k + 4, k, g.toString().trim()));
+
+        byte[] code = rs.encode(message);
+        sb.append("2) Encode (message | parity):\n");
+        sb.append("   ").append(show(code, k)).append('\n');
+        sb.append(String.format("   syndromes %s (all zero: valid)\n\n", syndromeText(rs.syndromes(code))));
+
+        int n = code.length;
+        int[] lost = {0, (n - 1) / 3, 2 * (n - 1) / 3, n - 1};               // distinct, the last a parity byte
+        byte[] damaged = code.clone();
+        for (int p : lost) damaged[p] = '?';
+        sb.append(String.format("3) Four bytes lost at known positions %s:\n", Arrays.toString(lost)));
+        sb.append("   ").append(show(damaged, k)).append('\n');
+        byte[] rebuilt = rs.recoverErasures(damaged, lost);
+        sb.append("   ").append(show(rebuilt, k)).append('\n');
+        sb.append(String.format("   recovered: %s\n\n", Arrays.equals(rebuilt, code) ? "yes, byte for byte" : "NO"));
+
+        sb.append("4) Five erasures is one too many:\n");
+        try {
+            rs.recoverErasures(damaged, 0, 1, 2, 3, 4);
+        } catch (IllegalArgumentException e) {
+            sb.append("   ").append(e.getMessage()).append("\n\n");
+        }
+
+        byte[] hit = code.clone();
+        hit[1] ^= 0x20;
+        sb.append("5) One byte changed at an UNKNOWN position:\n");
+        sb.append("   ").append(show(hit, k)).append('\n');
+        sb.append(String.format("   syndromes %s (non-zero: detected)\n", syndromeText(rs.syndromes(hit))));
+        int where = rs.correctSingleError(hit);
+        sb.append(String.format("   X = S1/S0 locates it at byte %d; corrected: %s\n",
+            where, Arrays.equals(hit, code) ? "yes" : "NO"));
+        sb.append("   An error costs 2 parity bytes (where AND what), an erasure only 1 (what).\n\n");
+
+        sb.append("6) Blocks of a longer message, each with 2 parity bytes (RAID-6 style):\n");
+        ReedSolomon two = new ReedSolomon(2);
+        String longText = "The quick brown fox jumps over the lazy dog";
+        byte[] all = longText.getBytes(StandardCharsets.US_ASCII);
+        StringBuilder out = new StringBuilder();
+        int blocks = 0;
+        for (int off = 0; off < all.length; off += 8) {
+            byte[] block = Arrays.copyOfRange(all, off, Math.min(all.length, off + 8));
+            byte[] cw = two.encode(block);
+            int a = blocks % cw.length;
+            int b = (blocks * 3 + 1) % cw.length;
+            if (a == b) b = (b + 1) % cw.length;
+            cw[a] = 0;
+            cw[b] = 0;                                                   // two bytes of every block lost
+            byte[] fixed = two.recoverErasures(cw, a, b);
+            out.append(new String(fixed, 0, block.length, StandardCharsets.US_ASCII));
+            blocks++;
+        }
+        sb.append(String.format("   %d blocks, two bytes erased in each → \"%s\" (%s)\n",
+            blocks, out, out.toString().equals(longText) ? "intact" : "DAMAGED"));
+        return sb.toString();
+    }
+
+    public static String demonstrateReedSolomon() {
+        return demonstrateReedSolomon("HELLO TDT4160");
+    }
+
+    public static void main(String[] args) {
+        try {
+            System.out.println(args.length > 0 ? demonstrateReedSolomon(String.join(" ", args)) : demonstrateReedSolomon());
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.err.println("Usage: ReedSolomon [message of at most 251 bytes]");
+            System.exit(2);
+        }
+    }
+}
@@ -0,0 +1,288 @@
+package computerdesign.coding;
+
+import java.nio.charset.StandardCharsets;
+import java.util.Arrays;
+
+/**
+ * Utf8 - ASCII and UTF-8 from first principles: an encoder that shows its
+ * bits and a strict decoder that says exactly where and why input is bad.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * FROM ASCII TO UNICODE
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * ASCII gives 128 characters 7-bit codes (0x41 = 'A'). Unicode gives every
+ * character a CODE POINT from U+0000 to U+10FFFF - 21 bits. UTF-8 stores a
+ * code point in 1-4 bytes and keeps ASCII unchanged:
+ *
+ *   Code points          Bytes  Bit pattern (x = payload)
+ *   ───────────────────  ─────  ─────────────────────────────────────────
+ *   U+0000  - U+007F       1    0xxxxxxx                      (= ASCII)
+ *   U+0080  - U+07FF       2    110xxxxx 10xxxxxx
+ *   U+0800  - U+FFFF       3    1110xxxx 10xxxxxx 10xxxxxx
+ *   U+10000 - U+10FFFF     4    11110xxx 10xxxxxx 10xxxxxx 10xxxxxx
+ *
+ * The PREFIX of the first byte says how long the sequence is; every
+ * continuation byte starts with 10. So a reader dropped anywhere in a
+ * stream can find the next character boundary, and no multi-byte sequence
+ * contains a byte that looks like ASCII ('/' or '\0' never appear by accident).
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHAT A STRICT DECODER REJECTS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   10xxxxxx first         continuation byte with no lead byte
+ *   11111xxx, C0, C1       can never start a valid sequence
+ *   lead without enough    truncated sequence
+ *   continuation bytes
+ *   OVERLONG encodings     e.g. C0 AF for '/': more bytes than needed, a
+ *                          classic trick to sneak '/' past a path filter
+ *   U+D800 - U+DFFF        UTF-16 surrogate halves are not characters
+ *   above U+10FFFF         outside Unicode
+ *
+ * Java Strings are UTF-16: characters above U+FFFF take TWO chars (a
+ * surrogate pair), so String.length() counts code units, not characters.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: Utf8 text...            explain each character of the text
+ *        Utf8 --bytes e2 82 ac   decode hex bytes, reporting the first error
+ *
+ * Covers learning goals: T2.2 (data representation: characters)
+ */
+public final class Utf8 {
+
+    private Utf8() {}
+
+    // ==================== ENCODE ====================
+
+    /** Bytes needed for a code point (1-4). */
+    public static int length(int codePoint) {
+        checkCodePoint(codePoint);
+        if (codePoint < 0x80) return 1;
+        if (codePoint < 0x800) return 2;
+        if (codePoint < 0x10000) return 3;
+        return 4;
+    }
+
+    private static void checkCodePoint(int cp) {
+        if (cp < 0 || cp > 0x10FFFF) throw new IllegalArgumentException(String.format("Not a code point: 0x%X", cp));
+        if (cp >= 0xD800 && cp <= 0xDFFF) throw new IllegalArgumentException(String.format("Surrogate U+%04X is not a character", cp));
+    }
+
+    /** UTF-8 bytes of one code point, built with shifts and masks. */
+    public static byte[] encode(int codePoint) {
+        int n = length(codePoint);
+        byte[] out = new byte[n];
+        if (n == 1) {
+            out[0] = (byte) codePoint;
+            return out;
+        }
+        for (int i = n - 1; i > 0; i--) {                  // continuation bytes: 10 + 6 bits, last first
+            out[i] = (byte) (0x80 | (codePoint & 0x3F));
+            codePoint >>>= 6;
+        }
+        int prefix = (0xFF << (8 - n)) & 0xFF;             // 110, 1110 or 11110 in the top bits
+        out[0] = (byte) (prefix | codePoint);
+        return out;
+    }
+
+    public static byte[] encode(String text) {
+        int[] cps = text.codePoints().toArray();
+        int total = 0;
+        for (int cp : cps) total += length(cp);
+        byte[] out = new byte[total];
+        int pos = 0;
+        for (int cp : cps) {
+            byte[] b = encode(cp);
+            System.arraycopy(b, 0, out, pos, b.length);
+            pos += b.length;
+        }
+        return out;
+    }
+
+    // ==================== DECODE ====================
+
+    /** What a decode produced: the text up to the first error, and the error if any. */
+    public static final class Result {
+        public final String text;
+        public final int errorOffset;       // -1 if the input was valid
+        public final String error;
+
+        Result(String text, int errorOffset, String error) {
+            this.text = text;
+            this.errorOffset = errorOffset;
+            this.error = error;
+        }
+
+        public boolean isValid() {
+            return errorOffset < 0;
+        }
+    }
+
+    /** Strict decoder: stops at the first invalid byte and says why. */
+    public static Result decode(byte[] bytes) {
+        StringBuilder text = new StringBuilder();
+        int i = 0;
+        while (i < bytes.length) {
+            int b = bytes[i] & 0xFF;
+            int n;
+            int cp;
+            if (b < 0x80) { n = 1; cp = b; }
+            else if (b < 0xC0) return fail(text, i, String.format("0x%02X is a continuation byte (10xxxxxx) with no lead byte", b));
+            else if (b < 0xE0) { n = 2; cp = b & 0x1F; }
+            else if (b < 0xF0) { n = 3; cp = b & 0x0F; }
+            else if (b < 0xF8) { n = 4; cp = b & 0x07; }
+            else return fail(text, i, String.format("0x%02X (11111xxx) never starts a UTF-8 sequence", b));
+
+            for (int k = 1; k < n; k++) {
+                if (i + k >= bytes.length) {
+                    return fail(text, i, String.format("truncated: lead byte 0x%02X needs %d bytes, only %d left", b, n, bytes.length - i));
+                }
+                int c = bytes[i + k] & 0xFF;
+                if ((c & 0xC0) != 0x80) {
+                    return fail(text, i + k, String.format("0x%02X should be a continuation byte (10xxxxxx), byte %d of %d", c, k + 1, n));
+                }
+                cp = cp << 6 | (c & 0x3F);
+            }
+            if (n > 1 && cp < (n == 2 ? 0x80 : n == 3 ? 0x800 : 0x10000)) {
+                return fail(text, i, String.format("overlong: U+%04X encoded in %d bytes, needs %d", cp, n, length(cp)));
+            }
+            if (cp >= 0xD800 && cp <= 0xDFFF) return fail(text, i, String.format("U+%04X is a UTF-16 surrogate, not a character", cp));
+            if (cp > 0x10FFFF) return fail(text, i, String.format("U+%X is above U+10FFFF", cp));
+            text.appendCodePoint(cp);
+            i += n;
+        }
+        return new Result(text.toString(), -1, null);
+    }
+
+    private static Result fail(StringBuilder text, int offset, String why) {
+        return new Result(text.toString(), offset, why);
+    }
+
+    // ==================== EXPLAIN ====================
+
+    private static String bits8(int b) {
+        String s = Integer.toBinaryString(b & 0xFF);
+        while (s.length() < 8) s = "0" + s;
+        return s;
+    }
+
+    /** One byte with its prefix bits split off: "110|00011". */
+    private static String marked(int b, int prefixBits) {
+        String s = bits8(b);
+        return s.substring(0, prefixBits) + "|" + s.substring(prefixBits);
+    }
+
+    /** Code point, UTF-8 bytes in binary with prefixes marked, and sizes. */
+    public static String explain(String text) {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("   %-4s %-9s %-5s %-46s %s\n", "char", "code pt", "bytes", "UTF-8 (prefix|payload)", "hex"));
+        text.codePoints().forEach(cp -> {
+            byte[] b = encode(cp);
+            StringBuilder bin = new StringBuilder();
+            StringBuilder hex = new StringBuilder();
+            for (int i = 0; i < b.length; i++) {
+                int prefix = b.length == 1 ? 1 : i == 0 ? b.length + 1 : 2;
+                if (i > 0) { bin.append(' '); hex.append(' '); }
+                bin.append(marked(b[i], prefix));
+                hex.append(String.format("%02X", b[i]));
+            }
+            String shown = cp < 0x20 || cp == 0x7F ? "^" + (char) (cp ^ 0x40) : new String(Character.toChars(cp));
+            sb.append(String.format("   %-4s U+%-7s %-5d %-46s %s\n", shown, String.format("%04X", cp), b.length, bin, hex));
+        });
+        byte[] all = encode(text);
+        sb.append(String.format("   %d characters (code points), %d UTF-16 chars (String.length), %d UTF-8 bytes\n",
+            text.codePointCount(0, text.length()), text.length(), all.length));
+        return sb.toString();
+    }
+
+    private static String hex(byte[] bytes) {
+        StringBuilder sb = new StringBuilder();
+        for (byte b : bytes) {
+            if (sb.length() > 0) sb.append(' ');
+            sb.append(String.format("%02X", b));
+        }
+        return sb.toString();
+    }
+
+    private static String report(byte[] bytes) {
+        Result r = decode(bytes);
+        if (r.isValid()) return String.format("   %-20s → \"%s\"\n", hex(bytes), r.text);
+        return String.format("   %-20s → error at byte %d: %s\n", hex(bytes), r.errorOffset, r.error);
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateUtf8() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== ASCII and UTF-8 ===\n\n");
+
+        sb.append("1) One character of each length:\n");
+        sb.append(explain("Aé€😀")).append('\n');
+
+        sb.append("2) How 'é' (U+00E9) is encoded:\n");
+        sb.append("   U+00E9 = 000 1110 1001 (11 bits) → needs 2 bytes (up to 11 payload bits)\n");
+        sb.append("   split 5 + 6:   00011 | 101001\n");
+        sb.append("   add prefixes:  110|00011  10|101001  = C3 A9\n\n");
+
+        String sample = "Blåbærsyltetøy ≈ 🫐";
+        boolean same = Arrays.equals(encode(sample), sample.getBytes(StandardCharsets.UTF_8));
+        sb.append(String.format("3) \"%s\": this encoder and String.getBytes(UTF_8) agree: %b\n", sample, same));
+        sb.append(String.format("   %d characters, but String.length() = %d (🫐 is a surrogate pair in UTF-16)\n\n",
+            sample.codePointCount(0, sample.length()), sample.length()));
+
+        sb.append("4) The strict decoder on good and bad input:\n");
+        sb.append(report(new byte[] {0x48, 0x69}));
+        sb.append(report(new byte[] {(byte) 0xE2, (byte) 0x82, (byte) 0xAC}));
+        sb.append(report(new byte[] {0x61, (byte) 0xA9, 0x62}));
+        sb.append(report(new byte[] {(byte) 0xE2, (byte) 0x82}));
+        sb.append(report(new byte[] {(byte) 0xC3, 0x41}));
+        sb.append(report(new byte[] {(byte) 0xC0, (byte) 0xAF}));
+        sb.append(report(new byte[] {(byte) 0xE0, (byte) 0x80, (byte) 0xAF}));
+        sb.append(report(new byte[] {(byte) 0xED, (byte) 0xA0, (byte) 0x80}));
+        sb.append(report(new byte[] {(byte) 0xF4, (byte) 0x90, (byte) 0x80, (byte) 0x80}));
+        sb.append(report(new byte[] {(byte) 0xFF}));
+        sb.append("   C0 AF and E0 80 AF are overlong '/': a lenient decoder would let them past a \"..\" check.\n\n");
+
+        byte[] latin1 = "é".getBytes(StandardCharsets.ISO_8859_1);
+        byte[] utf8 = "é".getBytes(StandardCharsets.UTF_8);
+        sb.append("5) Mojibake: the same text, the wrong decoder\n");
+        Result asUtf8 = decode(latin1);
+        sb.append(String.format("   'é' in Latin-1 is %s; read as UTF-8: error at byte %d, %s\n",
+            hex(latin1), asUtf8.errorOffset, asUtf8.error));
+        sb.append(String.format("   'é' in UTF-8 is %s; read as Latin-1: \"%s\"\n",
+            hex(utf8), new String(utf8, StandardCharsets.ISO_8859_1)));
+        return sb.toString();
+    }
+
+    private static final String USAGE = "Usage: Utf8 text... | Utf8 --bytes e2 82 ac";
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateUtf8());
+            System.out.println(USAGE);
+            return;
+        }
+        if (args[0].equals("--bytes")) {
+            if (args.length == 1) usage("--bytes needs at least one hex byte");
+            byte[] bytes = new byte[args.length - 1];
+            for (int i = 1; i < args.length; i++) bytes[i - 1] = (byte) parseByte(args[i]);
+            System.out.print(report(bytes));
+            return;
+        }
+        System.out.print(explain(String.join(" ", args)));
+    }
+
+    /** One byte as one or two hex digits. */
+    private static int parseByte(String s) {
+        if (s.matches("[0-9a-fA-F]{1,2}")) return Integer.parseInt(s, 16);
+        usage("Not a hex byte (00-FF): " + s);
+        return 0;
+    }
+
+    private static void usage(String message) {
+        System.err.println(message + "\n" + USAGE);
+        System.exit(2);
+    }
+}
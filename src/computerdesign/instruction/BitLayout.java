@@ -0,0 +1,352 @@
+package computerdesign.instruction;
+
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * BitLayout - Declarative packed fields inside a 32-bit word.
+ *
+ * Covers learning goals: T2.1 (RISC-V instruction formats, assembly ↔ machine code)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * FIELDS AND SLICES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * A field is a named value stored in one or more SLICES of the word. Most
+ * fields are one slice (rd = bits [11:7]); RISC-V immediates are scattered:
+ *
+ *   B-type imm:   word bits  31    30..25    11..8    7
+ *                 value bits 12    10..5     4..1     11      (imm[0] = 0)
+ *
+ *   BitLayout b = new BitLayout("B")
+ *       .field("opcode", 6, 0)
+ *       .field("rs1", 19, 15)
+ *       .signed("imm", slice(31, 31, 12), slice(30, 25, 5), slice(11, 8, 1), slice(7, 7, 11));
+ *
+ *   b.get(word, "imm")            value bits put together, sign-extended
+ *   b.set(word, "imm", -8)        checked: must fit, uncovered low bits must be 0
+ *   b.encode("imm", -8, "rs1", 5) a whole word from field values
+ *
+ * Overlapping slices are rejected when the layout is defined, and
+ * complete() checks that every bit of the word belongs to some field.
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class BitLayout {
+
+    private final String name;
+    private final Map<String, Field> fields = new LinkedHashMap<>();
+    private int used = 0;                         // bits claimed by some field
+
+    public BitLayout(String name) {
+        this.name = name;
+    }
+
+    // ==================== DEFINITION ====================
+
+    /** Word bits [wordHi:wordLo] hold value bits starting at valueLo. */
+    public static class Slice {
+        public final int wordHi;
+        public final int wordLo;
+        public final int valueLo;
+
+        Slice(int wordHi, int wordLo, int valueLo) {
+            if (wordLo < 0 || wordHi > 31 || wordHi < wordLo || valueLo < 0 || valueLo + wordHi - wordLo > 31) {
+                throw new IllegalArgumentException(String.format("Bad slice [%d:%d] → value bit %d", wordHi, wordLo, valueLo));
+            }
+            this.wordHi = wordHi;
+            this.wordLo = wordLo;
+            this.valueLo = valueLo;
+        }
+
+        public int width() {
+            return wordHi - wordLo + 1;
+        }
+
+        int wordMask() {
+            return (int) (((1L << width()) - 1) << wordLo);
+        }
+    }
+
+    public static Slice slice(int wordHi, int wordLo, int valueLo) {
+        return new Slice(wordHi, wordLo, valueLo);
+    }
+
+    /** A named field: its slices, value width and signedness. */
+    public static class Field {
+        public final String name;
+        public final boolean signed;
+        public final List<Slice> slices;
+        public final int valueBits;                // 1 + highest value bit
+        public final int valueMask;                // value bits actually stored
+
+        Field(String name, boolean signed, List<Slice> slices) {
+            this.name = name;
+            this.signed = signed;
+            this.slices = Collections.unmodifiableList(new ArrayList<>(slices));
+            int bits = 0;
+            long mask = 0;
+            for (Slice s : slices) {
+                bits = Math.max(bits, s.valueLo + s.width());
+                long m = ((1L << s.width()) - 1) << s.valueLo;
+                if ((mask & m) != 0) throw new IllegalArgumentException("Field " + name + ": value bits stored twice");
+                mask |= m;
+            }
+            this.valueBits = bits;
+            this.valueMask = (int) mask;
+        }
+
+        public long min() {
+            return signed ? -(1L << (valueBits - 1)) : 0;
+        }
+
+        public long max() {
+            return signed ? (1L << (valueBits - 1)) - 1 : (1L << valueBits) - 1;
+        }
+    }
+
+    /** Unsigned field in one slice [hi:lo]. */
+    public BitLayout field(String name, int hi, int lo) {
+        return add(new Field(name, false, List.of(slice(hi, lo, 0))));
+    }
+
+    /** Unsigned field made of several slices. */
+    public BitLayout unsigned(String name, Slice... slices) {
+        return add(new Field(name, false, List.of(slices)));
+    }
+
+    /** Two's complement field made of several slices; sign bit = highest value bit. */
+    public BitLayout signed(String name, Slice... slices) {
+        return add(new Field(name, true, List.of(slices)));
+    }
+
+    private BitLayout add(Field f) {
+        if (fields.containsKey(f.name)) throw new IllegalArgumentException(name + ": duplicate field " + f.name);
+        for (Slice s : f.slices) {
+            if ((used & s.wordMask()) != 0) {
+                throw new IllegalArgumentException(String.format("%s: field %s bits [%d:%d] overlap %s",
+                    name, f.name, s.wordHi, s.wordLo, owner(s.wordMask() & used)));
+            }
+            used |= s.wordMask();
+        }
+        fields.put(f.name, f);
+        return this;
+    }
+
+    private String owner(int bits) {
+        for (Field f : fields.values()) {
+            for (Slice s : f.slices) if ((s.wordMask() & bits) != 0) return f.name;
+        }
+        return "?";
+    }
+
+    /** Check that the fields cover all 32 bits; returns this for chaining. */
+    public BitLayout complete() {
+        if (used != -1) {
+            throw new IllegalStateException(String.format("%s: bits not covered by any field: 0x%08X", name, ~used));
+        }
+        return this;
+    }
+
+    public String getName() { return name; }
+    public Map<String, Field> getFields() { return Collections.unmodifiableMap(fields); }
+
+    public Field getField(String name) {
+        Field f = fields.get(name);
+        if (f == null) throw new IllegalArgumentException(this.name + ": no field " + name);
+        return f;
+    }
+
+    // ==================== GET / SET ====================
+
+    /** Field value from a word, sign-extended if the field is signed. */
+    public int get(int word, String fieldName) {
+        Field f = getField(fieldName);
+        int value = 0;
+        for (Slice s : f.slices) {
+            value |= ((word & s.wordMask()) >>> s.wordLo) << s.valueLo;
+        }
+        if (f.signed && f.valueBits < 32) {
+            int shift = 32 - f.valueBits;
+            value = (value << shift) >> shift;
+        }
+        return value;
+    }
+
+    /**
+     * Word with one field replaced. The value must fit the field's range, and
+     * value bits no slice stores (like imm[0] of a branch) must be zero.
+     */
+    public int set(int word, String fieldName, long value) {
+        Field f = getField(fieldName);
+        if (value < f.min() || value > f.max()) {
+            throw new IllegalArgumentException(String.format("%s.%s: %d outside %d..%d",
+                name, f.name, value, f.min(), f.max()));
+        }
+        int v = (int) value;
+        int lost = v & ~f.valueMask & (int) ((1L << f.valueBits) - 1);
+        if (lost != 0) {
+            throw new IllegalArgumentException(String.format("%s.%s: %d has bits the encoding cannot store (mask 0x%X)",
+                name, f.name, value, lost));
+        }
+        for (Slice s : f.slices) {
+            int part = (v >>> s.valueLo) & (int) ((1L << s.width()) - 1);
+            word = (word & ~s.wordMask()) | (part << s.wordLo);
+        }
+        return word;
+    }
+
+    /** Word from alternating field names and values: encode("rd", 5, "imm", -1). */
+    public int encode(Object... namesAndValues) {
+        if (namesAndValues.length % 2 != 0) throw new IllegalArgumentException("encode needs name/value pairs");
+        int word = 0;
+        for (int i = 0; i < namesAndValues.length; i += 2) {
+            word = set(word, (String) namesAndValues[i], ((Number) namesAndValues[i + 1]).longValue());
+        }
+        return word;
+    }
+
+    /** All field values of a word, in definition order. */
+    public Map<String, Integer> decode(int word) {
+        Map<String, Integer> values = new LinkedHashMap<>();
+        for (String f : fields.keySet()) values.put(f, get(word, f));
+        return values;
+    }
+
+    // ==================== DISPLAY ====================
+
+    /** One line per slice from bit 31 down: "31-25 funct7", "11-8 imm[4:1]". */
+    public String describe() {
+        List<String[]> rows = new ArrayList<>();
+        for (int bit = 31; bit >= 0; bit--) {
+            for (Field f : fields.values()) {
+                for (Slice s : f.slices) {
+                    if (s.wordHi != bit) continue;
+                    String range = s.wordHi == s.wordLo ? String.valueOf(s.wordHi) : s.wordHi + "-" + s.wordLo;
+                    String part = f.slices.size() == 1 && s.valueLo == 0 ? f.name
+                        : f.name + "[" + (s.width() == 1 ? String.valueOf(s.valueLo)
+                            : (s.valueLo + s.width() - 1) + ":" + s.valueLo) + "]";
+                    rows.add(new String[] {range, part});
+                }
+            }
+        }
+        StringBuilder sb = new StringBuilder(name).append(":");
+        for (String[] r : rows) sb.append(String.format("  %s %s", r[0], r[1]));
+        return sb.toString();
+    }
+
+    // ==================== RISC-V FORMATS ====================
+
+    public static final BitLayout R_TYPE = new BitLayout("R")
+        .field("funct7", 31, 25).field("rs2", 24, 20).field("rs1", 19, 15)
+        .field("funct3", 14, 12).field("rd", 11, 7).field("opcode", 6, 0).complete();
+
+    public static final BitLayout I_TYPE = new BitLayout("I")
+        .signed("imm", slice(31, 20, 0)).field("rs1", 19, 15)
+        .field("funct3", 14, 12).field("rd", 11, 7).field("opcode", 6, 0).complete();
+
+    public static final BitLayout S_TYPE = new BitLayout("S")
+        .signed("imm", slice(31, 25, 5), slice(11, 7, 0)).field("rs2", 24, 20).field("rs1", 19, 15)
+        .field("funct3", 14, 12).field("opcode", 6, 0).complete();
+
+    public static final BitLayout B_TYPE = new BitLayout("B")
+        .signed("imm", slice(31, 31, 12), slice(30, 25, 5), slice(11, 8, 1), slice(7, 7, 11))
+        .field("rs2", 24, 20).field("rs1", 19, 15).field("funct3", 14, 12).field("opcode", 6, 0).complete();
+
+    public static final BitLayout U_TYPE = new BitLayout("U")
+        .signed("imm", slice(31, 12, 12)).field("rd", 11, 7).field("opcode", 6, 0).complete();
+
+    public static final BitLayout J_TYPE = new BitLayout("J")
+        .signed("imm", slice(31, 31, 20), slice(30, 21, 1), slice(20, 20, 11), slice(19, 12, 12))
+        .field("rd", 11, 7).field("opcode", 6, 0).complete();
+
+    /** The RISC-V base formats by type. */
+    public static BitLayout of(Instruction.InstructionType type) {
+        switch (type) {
+            case R_TYPE: return R_TYPE;
+            case I_TYPE: return I_TYPE;
+            case S_TYPE: return S_TYPE;
+            case B_TYPE: return B_TYPE;
+            case U_TYPE: return U_TYPE;
+            case J_TYPE: return J_TYPE;
+            default: throw new IllegalArgumentException("No layout for " + type);
+        }
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateBitLayout() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  BIT LAYOUTS: RISC-V INSTRUCTION FORMATS AS DATA\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("1) The six formats, bit 31 first:\n\n");
+        for (BitLayout l : new BitLayout[] {R_TYPE, I_TYPE, S_TYPE, B_TYPE, U_TYPE, J_TYPE}) {
+            sb.append("   ").append(l.describe()).append("\n");
+        }
+
+        sb.append("\n2) Encode beq x1, x2, -8 from field values:\n\n");
+        int beq = B_TYPE.encode("opcode", 0b1100011, "funct3", 0, "rs1", 1, "rs2", 2, "imm", -8);
+        sb.append(String.format("   word = 0x%08X = %s\n", beq, binary(beq)));
+        sb.append("   decoded: ").append(B_TYPE.decode(beq)).append("\n");
+        sb.append("   disassembled: ").append(new Instruction(beq).disassemble()).append("\n");
+
+        sb.append("\n3) The layout refuses what the encoding cannot hold:\n\n");
+        String[][] attempts = {{"B", "imm", "-7"}, {"B", "imm", "4096"}, {"I", "rd", "32"}, {"I", "imm", "-2049"}};
+        for (String[] a : attempts) {
+            BitLayout l = a[0].equals("B") ? B_TYPE : I_TYPE;
+            try {
+                l.set(0, a[1], Long.parseLong(a[2]));
+                sb.append("   accepted?!\n");
+            } catch (IllegalArgumentException e) {
+                sb.append("   ").append(e.getMessage()).append("\n");
+            }
+        }
+        try {
+            new BitLayout("broken").field("opcode", 6, 0).field("rd", 11, 6);
+        } catch (IllegalArgumentException e) {
+            sb.append("   ").append(e.getMessage()).append("\n");
+        }
+
+        sb.append("\n4) Your own encoding - a 16-bit-style format in the low half word:\n\n");
+        BitLayout mine = new BitLayout("MY16")
+            .field("op", 15, 12).field("rd", 11, 9).signed("imm", slice(8, 0, 0)).field("unused", 31, 16).complete();
+        int word = mine.encode("op", 0xA, "rd", 3, "imm", -100);
+        sb.append("   ").append(mine.describe()).append("\n");
+        sb.append(String.format("   op=0xA rd=3 imm=-100 → 0x%04X → %s\n", word, mine.decode(word)));
+        return sb.toString();
+    }
+
+    private static String binary(int word) {
+        String bits = String.format("%32s", Integer.toBinaryString(word)).replace(' ', '0');
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < 32; i += 4) sb.append(i > 0 ? "_" : "").append(bits, i, i + 4);
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: BitLayout [hex word]   e.g. BitLayout 0xFE208CE3 (decodes with every format)
+     */
+    public static void main(String[] args) {
+        if (args.length > 0) {
+            int word;
+            try {
+                word = (int) Long.parseLong(args[0].replaceFirst("^0[xX]", ""), 16);
+            } catch (NumberFormatException e) {
+                System.err.println("Not a hex word: " + args[0] + "\nUsage: BitLayout [hex word]");
+                System.exit(2);
+                return;
+            }
+            for (BitLayout l : new BitLayout[] {R_TYPE, I_TYPE, S_TYPE, B_TYPE, U_TYPE, J_TYPE}) {
+                System.out.println(l.getName() + ": " + l.decode(word));
+            }
+            return;
+        }
+        System.out.println(demonstrateBitLayout());
+    }
+}
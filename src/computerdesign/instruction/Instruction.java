@@ -154,6 +154,7 @@ package computerdesign.instruction;
  * ═══════════════════════════════════════════════════════════════════════════════
  * 
  * @see InstructionDecoder - Decodes raw instructions
+ * @see BitLayout - The formats as field definitions (get/set with range checks)
  * @see ControlUnit - Generates control signals based on instruction type
  */
 public class Instruction {
@@ -244,35 +245,12 @@ public class Instruction {
     public int getImmediate() {
         switch (type) {
             case I_TYPE:
-                // Sign-extend 12-bit immediate
-                int immI = raw >> 20;
-                return immI;  // Java >> is arithmetic (sign-extends)
-                
             case S_TYPE:
-                // Combine imm[11:5] and imm[4:0]
-                int immS = ((raw >> 25) << 5) | ((raw >> 7) & 0x1F);
-                // Sign-extend from 12 bits
-                return (immS << 20) >> 20;
-                
             case B_TYPE:
-                // B-type immediate: imm[12|10:5|4:1|11]
-                int immB = ((raw >> 31) << 12) |           // imm[12]
-                          (((raw >> 7) & 0x1) << 11) |     // imm[11]
-                          (((raw >> 25) & 0x3F) << 5) |    // imm[10:5]
-                          (((raw >> 8) & 0xF) << 1);       // imm[4:1]
-                return (immB << 19) >> 19;  // Sign-extend from 13 bits
-                
             case U_TYPE:
-                // Upper 20 bits, already in position
-                return raw & 0xFFFFF000;
-                
             case J_TYPE:
-                // J-type immediate: imm[20|10:1|11|19:12]
-                int immJ = ((raw >> 31) << 20) |           // imm[20]
-                          (((raw >> 12) & 0xFF) << 12) |   // imm[19:12]
-                          (((raw >> 20) & 0x1) << 11) |    // imm[11]
-                          (((raw >> 21) & 0x3FF) << 1);    // imm[10:1]
-                return (immJ << 11) >> 11;  // Sign-extend from 21 bits
+                // Reassembled from the format's slices and sign-extended (see BitLayout)
+                return BitLayout.of(type).get(raw, "imm");
                 
             default:
                 return 0;
@@ -321,18 +299,16 @@ public class Instruction {
      * Create an R-type instruction.
      */
     public static Instruction rType(int opcode, int rd, int funct3, int rs1, int rs2, int funct7) {
-        int raw = (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | 
-                  (funct3 << 12) | (rd << 7) | opcode;
-        return new Instruction(raw);
+        return new Instruction(BitLayout.R_TYPE.encode(
+            "funct7", funct7, "rs2", rs2, "rs1", rs1, "funct3", funct3, "rd", rd, "opcode", opcode));
     }
     
     /**
      * Create an I-type instruction.
      */
     public static Instruction iType(int opcode, int rd, int funct3, int rs1, int imm) {
-        int raw = ((imm & 0xFFF) << 20) | (rs1 << 15) | 
-                  (funct3 << 12) | (rd << 7) | opcode;
-        return new Instruction(raw);
+        return new Instruction(BitLayout.I_TYPE.encode(
+            "imm", imm, "rs1", rs1, "funct3", funct3, "rd", rd, "opcode", opcode));
     }
     
     /**
@@ -361,24 +337,26 @@ public class Instruction {
         return iType(0b0000011, rd, 0b010, rs1, offset);
     }
     
+    /** LUI with the full 32-bit value; its low 12 bits must be zero. */
+    public static Instruction lui(int rd, int value) {
+        return new Instruction(BitLayout.U_TYPE.encode("imm", value, "rd", rd, "opcode", 0b0110111));
+    }
+
     public static Instruction sw(int rs2, int rs1, int offset) {
-        // S-type encoding
-        int imm11_5 = (offset >> 5) & 0x7F;
-        int imm4_0 = offset & 0x1F;
-        int raw = (imm11_5 << 25) | (rs2 << 20) | (rs1 << 15) | 
-                  (0b010 << 12) | (imm4_0 << 7) | 0b0100011;
-        return new Instruction(raw);
+        // S-type: imm[11:5] and imm[4:0] in two slices
+        return new Instruction(BitLayout.S_TYPE.encode(
+            "imm", offset, "rs2", rs2, "rs1", rs1, "funct3", 0b010, "opcode", 0b0100011));
     }
     
     public static Instruction beq(int rs1, int rs2, int offset) {
-        // B-type encoding
-        int imm12 = (offset >> 12) & 0x1;
-        int imm11 = (offset >> 11) & 0x1;
-        int imm10_5 = (offset >> 5) & 0x3F;
-        int imm4_1 = (offset >> 1) & 0xF;
-        int raw = (imm12 << 31) | (imm10_5 << 25) | (rs2 << 20) | (rs1 << 15) |
-                  (0b000 << 12) | (imm4_1 << 8) | (imm11 << 7) | 0b1100011;
-        return new Instruction(raw);
+        // B-type: imm[12|10:5] and imm[4:1|11], offset must be even
+        return new Instruction(BitLayout.B_TYPE.encode(
+            "imm", offset, "rs2", rs2, "rs1", rs1, "funct3", 0b000, "opcode", 0b1100011));
+    }
+
+    public static Instruction bne(int rs1, int rs2, int offset) {
+        return new Instruction(BitLayout.B_TYPE.encode(
+            "imm", offset, "rs2", rs2, "rs1", rs1, "funct3", 0b001, "opcode", 0b1100011));
     }
 }
 
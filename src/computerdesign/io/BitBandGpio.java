@@ -0,0 +1,211 @@
+package computerdesign.io;
+
+import computerdesign.instruction.Instruction;
+import computerdesign.processor.SingleCycleProcessor;
+
+/**
+ * BitBandGpio - a GPIO port whose registers are also reachable one bit per
+ * word through a BIT-BAND ALIAS region, as on ARM Cortex-M3/M4.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHY BIT-BANDING
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Setting one output pin with ordinary loads and stores takes three steps:
+ *
+ *   lw   t1, ODR        ← read
+ *   ori  t1, t1, 0x01   ← modify
+ *   sw   t1, ODR        ← write back
+ *
+ * If an interrupt handler changes another bit of ODR between the lw and the
+ * sw, the sw writes back the OLD value of that bit and the handler's update
+ * is lost. Disabling interrupts around the sequence works but costs latency.
+ *
+ * Bit-banding gives every bit its own word address. A store to the alias
+ * word changes exactly one bit in a single bus transaction, and the bus does
+ * the read-modify-write internally where nothing can interrupt it:
+ *
+ *   alias address = aliasBase + registerOffset × 32 + bit × 4
+ *
+ *   register window            alias window (one word per bit)
+ *   base+0  ODR  [31 ... 0]    aliasBase+0x00 → ODR bit 0
+ *   base+4  IDR  [31 ... 0]    aliasBase+0x04 → ODR bit 1
+ *                              ...
+ *                              aliasBase+0x80 → IDR bit 0
+ *
+ * Alias reads return 0 or 1; alias writes use only bit 0 of the value.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage:
+ *   BitBandGpio gpio = new BitBandGpio();
+ *   MemoryBus bus = new MemoryBus();
+ *   gpio.attach(bus, 0x4000_0000, 0x4200_0000);
+ *   bus.write(BitBandGpio.aliasAddress(0x4200_0000, BitBandGpio.ODR, 5), 1);  // set pin 5
+ *
+ * Covers learning goals: T6.2 (memory-mapped I/O)
+ */
+public class BitBandGpio {
+
+    /** Output data register: the pins this port drives. */
+    public static final int ODR = 0x0;
+    /** Input data register: the pins as read from outside (writes ignored). */
+    public static final int IDR = 0x4;
+    private static final int REGISTERS = 2;
+
+    private int outputs;
+    private int inputs;
+
+    /** Word address of `bit` of the register at `registerOffset`. */
+    public static int aliasAddress(int aliasBase, int registerOffset, int bit) {
+        if (bit < 0 || bit > 31) throw new IllegalArgumentException("Bit must be 0-31, got " + bit);
+        return aliasBase + registerOffset * 32 + bit * 4;
+    }
+
+    // ==================== THE TWO VIEWS ====================
+
+    /** The registers as normal words. */
+    public Device registers() {
+        return new Device() {
+            @Override public int read(int offset) { return load(offset); }
+            @Override public void write(int offset, int value) { store(offset, value); }
+            @Override public int getSize() { return REGISTERS * 4; }
+            @Override public String getName() { return "GPIO registers (ODR, IDR)"; }
+        };
+    }
+
+    /** One word per register bit. */
+    public Device alias() {
+        return new Device() {
+            @Override
+            public int read(int offset) {
+                return (load(registerOf(offset)) >>> bitOf(offset)) & 1;
+            }
+
+            @Override
+            public void write(int offset, int value) {
+                int register = registerOf(offset);
+                int mask = 1 << bitOf(offset);
+                int word = load(register);
+                store(register, (value & 1) != 0 ? word | mask : word & ~mask);
+            }
+
+            @Override public int getSize() { return REGISTERS * 32 * 4; }
+            @Override public String getName() { return "GPIO bit-band alias"; }
+        };
+    }
+
+    private static int registerOf(int aliasOffset) {
+        return (aliasOffset / 128) * 4;
+    }
+
+    private static int bitOf(int aliasOffset) {
+        return (aliasOffset % 128) / 4;
+    }
+
+    /** Map both views onto a bus. */
+    public void attach(MemoryBus bus, int base, int aliasBase) {
+        bus.map(base, registers()).map(aliasBase, alias());
+    }
+
+    // ==================== REGISTERS ====================
+
+    private int load(int offset) {
+        switch (offset) {
+            case ODR: return outputs;
+            case IDR: return inputs;
+            default: throw new IllegalArgumentException(String.format("No GPIO register at offset 0x%X", offset));
+        }
+    }
+
+    private void store(int offset, int value) {
+        switch (offset) {
+            case ODR: outputs = value; break;
+            case IDR: break;                       // read-only: driven from outside
+            default: throw new IllegalArgumentException(String.format("No GPIO register at offset 0x%X", offset));
+        }
+    }
+
+    /** Drive the input pins from "outside" (a button, a sensor). */
+    public void setInputs(int pins) {
+        this.inputs = pins;
+    }
+
+    public int getOutputs() {
+        return outputs;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateBitBanding() {
+        final int base = 0x4000_0000;
+        final int aliasBase = 0x4200_0000;
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Bit-Banding: One Word per Peripheral Bit ===\n\n");
+
+        BitBandGpio gpio = new BitBandGpio();
+        MemoryBus bus = new MemoryBus();
+        gpio.attach(bus, base, aliasBase);
+        sb.append("Address map:\n").append(bus.describeMap()).append('\n');
+
+        sb.append("1) Each bit has an address: aliasBase + offset × 32 + bit × 4\n");
+        for (int bit : new int[] {0, 5, 31}) {
+            sb.append(String.format("   ODR bit %-2d → 0x%08X\n", bit, aliasAddress(aliasBase, ODR, bit)));
+        }
+        sb.append(String.format("   IDR bit 0  → 0x%08X\n\n", aliasAddress(aliasBase, IDR, 0)));
+
+        sb.append("2) Both views, one register:\n");
+        bus.write(base + ODR, 0b1010);
+        sb.append(String.format("   sw 0b1010 → ODR          ODR = 0x%02X\n", gpio.getOutputs()));
+        bus.write(aliasAddress(aliasBase, ODR, 0), 1);
+        sb.append(String.format("   sw 1 → alias(ODR, 0)     ODR = 0x%02X\n", gpio.getOutputs()));
+        bus.write(aliasAddress(aliasBase, ODR, 3), 0);
+        sb.append(String.format("   sw 0 → alias(ODR, 3)     ODR = 0x%02X\n", gpio.getOutputs()));
+        gpio.setInputs(0b100);
+        sb.append(String.format("   pin 2 driven high; lw alias(IDR, 2) = %d, lw alias(IDR, 1) = %d\n\n",
+            bus.read(aliasAddress(aliasBase, IDR, 2)), bus.read(aliasAddress(aliasBase, IDR, 1))));
+
+        sb.append("3) An interrupt between read and write-back:\n");
+        bus.write(base + ODR, 0);
+        int before = bus.getDeviceAccesses();
+        int value = bus.read(base + ODR);                           // main: lw
+        bus.write(base + ODR, bus.read(base + ODR) | (1 << 5));     //   ISR: set pin 5 (read-modify-write)
+        bus.write(base + ODR, value | 1);                           // main: sw (stale value)
+        sb.append(String.format("   read-modify-write: ODR = 0x%02X  pin 5 LOST (%d bus transactions)\n",
+            gpio.getOutputs(), bus.getDeviceAccesses() - before));
+
+        bus.write(base + ODR, 0);
+        before = bus.getDeviceAccesses();
+        bus.write(aliasAddress(aliasBase, ODR, 5), 1);              //   ISR: set pin 5
+        bus.write(aliasAddress(aliasBase, ODR, 0), 1);              // main: set pin 0, one store
+        sb.append(String.format("   bit-band:          ODR = 0x%02X  both pins set  (%d bus transactions)\n",
+            gpio.getOutputs(), bus.getDeviceAccesses() - before));
+        sb.append("   The single alias store has no window for the interrupt to land in.\n\n");
+
+        sb.append("4) The same from RISC-V code on the single-cycle CPU:\n");
+        bus.write(base + ODR, 0);
+        int[] program = {
+            Instruction.lui(5, aliasBase).getRaw(),    // t0 = alias base
+            Instruction.lui(7, base).getRaw(),         // t2 = GPIO base
+            Instruction.addi(6, 0, 1).getRaw(),        // t1 = 1
+            Instruction.sw(6, 5, 5 * 4).getRaw(),      // set pin 5
+            Instruction.sw(6, 5, 0).getRaw(),          // set pin 0
+            Instruction.lw(28, 7, ODR).getRaw(),       // t3 = ODR
+            Instruction.sw(0, 5, 5 * 4).getRaw(),      // clear pin 5
+            Instruction.lw(29, 7, ODR).getRaw(),       // t4 = ODR
+            0x00100073
+        };
+        bus.loadProgram(program, 0);
+        SingleCycleProcessor cpu = new SingleCycleProcessor(bus);
+        cpu.run(100);
+        sb.append("   lui t0, 0x42000; lui t2, 0x40000; addi t1, zero, 1\n");
+        sb.append("   sw t1, 20(t0); sw t1, 0(t0); lw t3, 0(t2); sw zero, 20(t0); lw t4, 0(t2)\n");
+        sb.append(String.format("   t3 = 0x%02X (pins 0 and 5), t4 = 0x%02X (pin 5 cleared)\n",
+            cpu.getRegisterFile().read(28), cpu.getRegisterFile().read(29)));
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        System.out.println(demonstrateBitBanding());
+    }
+}
@@ -0,0 +1,421 @@
+package computerdesign.io;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Paths;
+import java.util.ArrayDeque;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.List;
+import java.util.Random;
+
+/**
+ * BusArbitration - several bus masters sharing one bus, and the arbiter
+ * that decides who gets it.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * ONE BUS, MANY MASTERS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *     CPU ──┐                       A master raises REQUEST, the arbiter
+ *     DMA ──┼── arbiter ── bus      answers one of them with GRANT, and that
+ *   core2 ──┘                       master owns the bus for its transaction.
+ *
+ * Everyone else WAITS. Which one is granted is the arbitration scheme:
+ *
+ *   fixed priority   the highest-priority requester always wins. Simple and
+ *                    fast for the favourite - and a busy high-priority
+ *                    master can STARVE the others completely.
+ *   round-robin      after a master is served it goes to the back of the
+ *                    line: fair, every requester waits at most for one
+ *                    transaction of each other master.
+ *   TDMA             time is cut into slots, one per master in turn; only
+ *                    the slot's owner may use the bus, and only for a
+ *                    transaction that fits in what is left of the slot.
+ *                    The worst-case wait is known in advance (hard real
+ *                    time) - at the price of idle slots.
+ *
+ * Transactions are not pre-empted: once granted, a master keeps the bus
+ * for its whole length.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: BusArbitration                                     built-in trace
+ *        BusArbitration --trace file [--slot N] [--cycles N]
+ *
+ * A trace has one request per line, `cycle master length`, e.g.
+ *
+ *   # cycle  master  length
+ *   0        cpu     1
+ *   0        dma     8
+ *   3        core2   2
+ *
+ * Masters are ranked for fixed priority in the order they first appear.
+ *
+ * Covers learning goals: T6.2 (buses)
+ */
+public final class BusArbitration {
+
+    private BusArbitration() {}
+
+    /** A master asking for the bus at `cycle` for `length` cycles. */
+    public static final class Request {
+        public final int cycle;
+        public final int master;
+        public final int length;
+
+        public Request(int cycle, int master, int length) {
+            if (cycle < 0 || length < 1) {
+                throw new IllegalArgumentException("Request needs cycle >= 0 and length >= 1");
+            }
+            this.cycle = cycle;
+            this.master = master;
+            this.length = length;
+        }
+    }
+
+    /** Masters (named, index = fixed priority, 0 highest) and their requests. */
+    public static final class Trace {
+        public final List<String> masters = new ArrayList<>();
+        public final List<Request> requests = new ArrayList<>();
+
+        public int master(String name) {
+            int i = masters.indexOf(name);
+            if (i >= 0) return i;
+            masters.add(name);
+            return masters.size() - 1;
+        }
+
+        public Trace add(int cycle, String master, int length) {
+            requests.add(new Request(cycle, master(master), length));
+            return this;
+        }
+
+        /** Parse the `cycle master length` format. */
+        public static Trace parse(List<String> lines) {
+            Trace t = new Trace();
+            int n = 0;
+            for (String line : lines) {
+                n++;
+                String text = line.replaceAll("#.*", "").trim();
+                if (text.isEmpty()) continue;
+                String[] f = text.split("\\s+");
+                if (f.length != 3) throw new IllegalArgumentException("Line " + n + ": expected `cycle master length`");
+                try {
+                    t.add(Integer.parseInt(f[0]), f[1], Integer.parseInt(f[2]));
+                } catch (NumberFormatException e) {
+                    throw new IllegalArgumentException("Line " + n + ": " + e.getMessage());
+                }
+            }
+            if (t.requests.isEmpty()) throw new IllegalArgumentException("Trace has no requests");
+            return t;
+        }
+    }
+
+    // ==================== ARBITERS ====================
+
+    /** Picks one of the waiting masters, or -1 to leave the bus idle. */
+    public interface Arbiter {
+        /**
+         * @param waiting  waiting[m] is the length of m's oldest ready
+         *                 request, or 0 if m is not requesting
+         */
+        int grant(int[] waiting, int cycle);
+
+        String getName();
+    }
+
+    public static final class FixedPriority implements Arbiter {
+        @Override
+        public int grant(int[] waiting, int cycle) {
+            for (int m = 0; m < waiting.length; m++) if (waiting[m] > 0) return m;
+            return -1;
+        }
+
+        @Override
+        public String getName() {
+            return "fixed priority";
+        }
+    }
+
+    public static final class RoundRobin implements Arbiter {
+        private int last = -1;
+
+        @Override
+        public int grant(int[] waiting, int cycle) {
+            for (int i = 1; i <= waiting.length; i++) {
+                int m = (last + i) % waiting.length;
+                if (waiting[m] > 0) {
+                    last = m;
+                    return m;
+                }
+            }
+            return -1;
+        }
+
+        @Override
+        public String getName() {
+            return "round-robin";
+        }
+    }
+
+    public static final class Tdma implements Arbiter {
+        private final int slot;
+
+        public Tdma(int slot) {
+            if (slot < 1) throw new IllegalArgumentException("Slot must be at least 1 cycle, got " + slot);
+            this.slot = slot;
+        }
+
+        @Override
+        public int grant(int[] waiting, int cycle) {
+            int owner = (cycle / slot) % waiting.length;
+            int left = slot - cycle % slot;
+            if (waiting[owner] > slot) {
+                throw new IllegalArgumentException("A transaction of " + waiting[owner] + " cycles never fits a "
+                    + slot + "-cycle TDMA slot");
+            }
+            return waiting[owner] > 0 && waiting[owner] <= left ? owner : -1;
+        }
+
+        @Override
+        public String getName() {
+            return "TDMA, slot " + slot;
+        }
+    }
+
+    // ==================== THE SIMULATION ====================
+
+    /** Per-master wait times, utilization and the bus timeline. */
+    public static final class Result {
+        public final String arbiter;
+        public final List<String> masters;
+        public final int[] served;
+        public final long[] totalWait;
+        public final int[] maxWait;
+        public final int[] busCycles;
+        public final int unserved;
+        public final int cycles;
+        /** Owner of the bus in each cycle, -1 if idle. */
+        public final int[] timeline;
+
+        Result(String arbiter, List<String> masters, int[] served, long[] totalWait, int[] maxWait,
+               int[] busCycles, int unserved, int cycles, int[] timeline) {
+            this.arbiter = arbiter;
+            this.masters = masters;
+            this.served = served;
+            this.totalWait = totalWait;
+            this.maxWait = maxWait;
+            this.busCycles = busCycles;
+            this.unserved = unserved;
+            this.cycles = cycles;
+            this.timeline = timeline;
+        }
+
+        public double meanWait(int master) {
+            return served[master] == 0 ? 0 : (double) totalWait[master] / served[master];
+        }
+
+        public double utilization() {
+            int busy = 0;
+            for (int c : busCycles) busy += c;
+            return (double) busy / cycles;
+        }
+    }
+
+    /**
+     * Replay `trace` for at most `maxCycles`; stops early once every request
+     * has been served. Requests still waiting at the end count as unserved.
+     */
+    public static Result simulate(Trace trace, Arbiter arbiter, int maxCycles) {
+        int n = trace.masters.size();
+        List<ArrayDeque<Request>> queues = new ArrayList<>();
+        for (int m = 0; m < n; m++) queues.add(new ArrayDeque<>());
+        List<Request> pending = new ArrayList<>(trace.requests);
+        pending.sort((a, b) -> Integer.compare(a.cycle, b.cycle));
+
+        int[] served = new int[n];
+        long[] totalWait = new long[n];
+        int[] maxWait = new int[n];
+        int[] busCycles = new int[n];
+        int[] timeline = new int[maxCycles];
+        int next = 0;
+        int owner = -1;
+        int remaining = 0;
+        int t = 0;
+
+        for (; t < maxCycles; t++) {
+            while (next < pending.size() && pending.get(next).cycle <= t) {
+                Request r = pending.get(next++);
+                queues.get(r.master).add(r);
+            }
+            if (remaining == 0) {
+                owner = -1;
+                int[] waiting = new int[n];
+                boolean any = false;
+                for (int m = 0; m < n; m++) {
+                    Request head = queues.get(m).peek();
+                    waiting[m] = head == null ? 0 : head.length;
+                    any |= head != null;
+                }
+                if (!any && next == pending.size()) break;
+                int g = any ? arbiter.grant(waiting, t) : -1;
+                if (g >= 0) {
+                    Request r = queues.get(g).remove();
+                    int wait = t - r.cycle;
+                    served[g]++;
+                    totalWait[g] += wait;
+                    maxWait[g] = Math.max(maxWait[g], wait);
+                    owner = g;
+                    remaining = r.length;
+                }
+            }
+            timeline[t] = owner;
+            if (owner >= 0) {
+                busCycles[owner]++;
+                remaining--;
+            }
+        }
+        int unserved = pending.size() - next;
+        for (ArrayDeque<Request> q : queues) unserved += q.size();
+        return new Result(arbiter.getName(), trace.masters, served, totalWait, maxWait, busCycles,
+            unserved, Math.max(t, 1), Arrays.copyOf(timeline, t));
+    }
+
+    // ==================== REPORTING ====================
+
+    public static String report(Result r) {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("   %s: %d cycles, bus %.1f%% busy%s\n", r.arbiter, r.cycles,
+            100 * r.utilization(), r.unserved > 0 ? String.format(", %d requests never served", r.unserved) : ""));
+        sb.append(String.format("     %-8s %7s %9s %9s %10s\n", "master", "served", "mean wait", "max wait", "bus cycles"));
+        for (int m = 0; m < r.masters.size(); m++) {
+            sb.append(String.format("     %-8s %7d %9.1f %9d %10d\n", r.masters.get(m), r.served[m],
+                r.meanWait(m), r.maxWait[m], r.busCycles[m]));
+        }
+        return sb.toString();
+    }
+
+    /** One character per master: its initial, or its last character if an earlier master has that initial. */
+    static char symbol(List<String> masters, int master) {
+        String name = masters.get(master);
+        char initial = Character.toUpperCase(name.charAt(0));
+        for (int m = 0; m < master; m++) {
+            if (symbol(masters, m) == initial) return Character.toUpperCase(name.charAt(name.length() - 1));
+        }
+        return initial;
+    }
+
+    /** The bus owner per cycle, one symbol() each, '.' when idle. */
+    public static String timeline(Result r, int cycles) {
+        StringBuilder sb = new StringBuilder();
+        for (int t = 0; t < Math.min(cycles, r.timeline.length); t++) {
+            sb.append(r.timeline[t] < 0 ? '.' : symbol(r.masters, r.timeline[t]));
+        }
+        return sb.toString();
+    }
+
+    /** "D = dma, C = cpu, ..., . = idle" */
+    public static String timelineKey(List<String> masters) {
+        StringBuilder sb = new StringBuilder();
+        for (int m = 0; m < masters.size(); m++) sb.append(symbol(masters, m)).append(" = ").append(masters.get(m)).append(", ");
+        return sb.append(". = idle").toString();
+    }
+
+    /**
+     * 400 cycles of a DMA engine starting an 8-cycle burst every
+     * `dmaPeriod` cycles, a CPU with 1-cycle misses every 2-5 cycles and a
+     * second core with 2-cycle misses every 6-11 cycles.
+     */
+    public static Trace exampleTrace(int dmaPeriod) {
+        Random random = new Random(4160);
+        Trace t = new Trace();
+        t.master("dma");
+        t.master("cpu");
+        t.master("core2");
+        for (int c = 0; c < 400; c += dmaPeriod) t.add(c, "dma", 8);
+        for (int c = 0; c < 400; c += 2 + random.nextInt(4)) t.add(c, "cpu", 1);
+        for (int c = 1; c < 400; c += 6 + random.nextInt(6)) t.add(c, "core2", 2);
+        return t;
+    }
+
+    private static int demand(Trace trace) {
+        int cycles = 0;
+        for (Request r : trace.requests) cycles += r.length;
+        return cycles;
+    }
+
+    public static String demonstrateBusArbitration() {
+        StringBuilder sb = new StringBuilder();
+        Arbiter[] arbiters = {new FixedPriority(), new RoundRobin(), new Tdma(8)};
+        sb.append("=== Bus Arbitration ===\n\n");
+        sb.append("Masters, highest fixed priority first:\n");
+        sb.append("   dma    8-cycle bursts\n");
+        sb.append("   cpu    1-cycle cache misses every 2-5 cycles\n");
+        sb.append("   core2  2-cycle cache misses every 6-11 cycles\n\n");
+
+        Trace light = exampleTrace(20);
+        sb.append(String.format("1) A burst every 20 cycles: %d requests wanting %d bus cycles in 400 (%.0f%%)\n\n",
+            light.requests.size(), demand(light), demand(light) / 4.0));
+        Result[] results = new Result[arbiters.length];
+        for (int i = 0; i < arbiters.length; i++) {
+            results[i] = simulate(light, arbiters[i], 4000);
+            sb.append(report(results[i])).append('\n');
+        }
+        sb.append("   Who owns the bus, first 72 cycles (").append(timelineKey(light.masters)).append("):\n");
+        for (Result r : results) sb.append(String.format("   %-16s %s\n", r.arbiter, timeline(r, 72)));
+        sb.append("\n   Both dynamic schemes keep up; fixed priority moves the waiting onto\n");
+        sb.append("   core2, round-robin spreads it. TDMA gives each master 8 cycles in 24\n");
+        sb.append("   whatever the others do: the DMA needs 40% of the bus, gets 33%, and\n");
+        sb.append("   falls behind while other masters' slots go idle.\n\n");
+
+        Trace heavy = exampleTrace(10);
+        sb.append(String.format("2) A burst every 10 cycles: %d bus cycles wanted in 400 (%.0f%%), cut off at 400:\n\n",
+            demand(heavy), demand(heavy) / 4.0));
+        for (Arbiter a : new Arbiter[] {new FixedPriority(), new RoundRobin(), new Tdma(8)}) {
+            sb.append(report(simulate(heavy, a, 400))).append('\n');
+        }
+        sb.append("   The bus is overloaded and somebody must wait. Fixed priority lets the\n");
+        sb.append("   DMA take what it wants and core2 STARVES. Round-robin is fair per\n");
+        sb.append("   transaction: everyone gets turns, but a DMA turn is 8 cycles, so the\n");
+        sb.append("   CPU still waits long. Under TDMA the CPU and core2 see exactly the\n");
+        sb.append("   same waits as in 1): their slots do not depend on the DMA's load.\n");
+        return sb.toString();
+    }
+
+    // ==================== COMMAND LINE ====================
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateBusArbitration());
+            return;
+        }
+        String file = null;
+        int slot = 8, cycles = 100_000;
+        try {
+            for (int i = 0; i < args.length; i++) {
+                switch (args[i]) {
+                    case "--trace":  file = args[++i]; break;
+                    case "--slot":   slot = Integer.parseInt(args[++i]); break;
+                    case "--cycles": cycles = Integer.parseInt(args[++i]); break;
+                    default: throw new IllegalArgumentException("Unknown option: " + args[i]);
+                }
+            }
+            Trace trace = file == null ? exampleTrace(20)
+                : Trace.parse(Files.readAllLines(Paths.get(file), StandardCharsets.UTF_8));
+            for (Arbiter a : new Arbiter[] {new FixedPriority(), new RoundRobin(), new Tdma(slot)}) {
+                System.out.println(report(simulate(trace, a, cycles)));
+            }
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.err.println("Missing value after " + args[args.length - 1]);
+            System.exit(2);
+        } catch (IOException e) {
+            System.err.println("Cannot read " + file + ": " + e.getMessage());
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
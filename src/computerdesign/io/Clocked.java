@@ -0,0 +1,28 @@
+package computerdesign.io;
+
+/**
+ * Clocked - a device that does something on its own between the CPU's
+ * loads and stores: a DMA engine copying, a timer counting, a UART
+ * shifting bits.
+ *
+ * Machine calls tick() once per CPU cycle, before the CPU's instruction.
+ * A device that needs the bus that cycle (to read or write memory itself)
+ * returns true, and the CPU waits - the cycle is STOLEN from it.
+ *
+ * @see Machine
+ */
+public interface Clocked {
+
+    /** Advance one cycle; true if the device used the memory bus. */
+    boolean tick();
+
+    /** Whether the device's interrupt line is high. */
+    default boolean interruptRequest() {
+        return false;
+    }
+
+    /** Whether the device is pulling the system reset line (a watchdog biting). */
+    default boolean resetRequest() {
+        return false;
+    }
+}
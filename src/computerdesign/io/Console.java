@@ -0,0 +1,202 @@
+package computerdesign.io;
+
+import computerdesign.instruction.Instruction;
+
+/**
+ * Console - the keyboard and display at the RARS/MARS MMIO addresses, and
+ * two interactive programs that use them.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE REGISTERS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   0xFFFF0000  receiver control      bit 0: a key is ready
+ *   0xFFFF0004  receiver data         the key (reading clears ready)
+ *   0xFFFF0008  transmitter control   bit 0: display ready for a character
+ *   0xFFFF000C  transmitter data      write a character
+ *
+ * Every character in either direction is the same handshake: wait for
+ * READY, then move the data.
+ *
+ *       lui  t0, 0xFFFF0           # MMIO base
+ *   key: lw   t1, 0(t0)            # receiver control
+ *       andi t1, t1, 1
+ *       beqz t1, key               # no key yet
+ *       lw   t2, 4(t0)             # the key
+ *   out: lw   t1, 8(t0)            # transmitter control
+ *       andi t1, t1, 1
+ *       beqz t1, out               # display still busy
+ *       sw   t2, 12(t0)            # show it
+ *
+ * Programs written for the RARS MMIO simulator run unchanged here.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: Console                                worked example with scripted keys
+ *        Console (echo|menu) [--input text]     run one program, keys from --input
+ *                                               or typed on stdin
+ *
+ * Covers learning goals: T6.2 (memory-mapped I/O)
+ */
+public final class Console {
+
+    private Console() {}
+
+    private static final int HALT = 0x00100073;
+    private static final int MENU = 0x1000;
+    private static final int HELLO = 0x2000;
+    private static final int BYE = 0x3000;
+
+    private static int andi(int rd, int rs1, int imm) {
+        return Instruction.iType(0b0010011, rd, 0b111, rs1, imm).getRaw();
+    }
+
+    /** Echo every key until Enter. */
+    public static int[] echo() {
+        return new int[] {
+            Instruction.lui(5, Keyboard.BASE).getRaw(),  // t0 = MMIO base
+            Instruction.lw(6, 5, 0).getRaw(),            // key: t1 = receiver control
+            andi(6, 6, 1),
+            Instruction.beq(6, 0, -8).getRaw(),          //   no key yet
+            Instruction.lw(7, 5, 4).getRaw(),            // t2 = key
+            Instruction.lw(6, 5, 8).getRaw(),            // out: t1 = transmitter control
+            andi(6, 6, 1),
+            Instruction.beq(6, 0, -8).getRaw(),          //   display busy
+            Instruction.sw(7, 5, 12).getRaw(),           // show the key
+            Instruction.addi(28, 0, '\n').getRaw(),
+            Instruction.bne(7, 28, -36).getRaw(),        // until Enter
+            HALT
+        };
+    }
+
+    /**
+     * Print a menu, answer '1' with a greeting and the menu again, quit on
+     * '2', ignore other keys. Strings are one character per word, ending
+     * in 0; print is a subroutine (a0 = string, ra = return address).
+     */
+    public static int[] menu() {
+        return new int[] {
+            Instruction.lui(5, Keyboard.BASE).getRaw(),  //   0: t0 = MMIO base
+            Instruction.lui(10, MENU).getRaw(),          //   4: menu: a0 = menu text
+            Instruction.addi(1, 0, 16).getRaw(),         //   8: ra = 16
+            Instruction.beq(0, 0, 64).getRaw(),          //  12: print
+            Instruction.lw(6, 5, 0).getRaw(),            //  16: key: t1 = receiver control
+            andi(6, 6, 1),                               //  20
+            Instruction.beq(6, 0, -8).getRaw(),          //  24: no key yet
+            Instruction.lw(7, 5, 4).getRaw(),            //  28: t2 = key
+            Instruction.addi(28, 0, '1').getRaw(),       //  32
+            Instruction.bne(7, 28, 16).getRaw(),         //  36: not '1' → 52
+            Instruction.lui(10, HELLO).getRaw(),         //  40: a0 = greeting
+            Instruction.addi(1, 0, 4).getRaw(),          //  44: ra = menu
+            Instruction.beq(0, 0, 28).getRaw(),          //  48: print
+            Instruction.addi(28, 0, '2').getRaw(),       //  52
+            Instruction.bne(7, 28, -40).getRaw(),        //  56: not '2' → key
+            Instruction.lui(10, BYE).getRaw(),           //  60: a0 = goodbye
+            Instruction.addi(1, 0, 72).getRaw(),         //  64: ra = halt
+            Instruction.beq(0, 0, 8).getRaw(),           //  68: print
+            HALT,                                        //  72
+            Instruction.lw(29, 10, 0).getRaw(),          //  76: print: t4 = *a0
+            Instruction.beq(29, 0, 28).getRaw(),         //  80: end of string → 108
+            Instruction.lw(6, 5, 8).getRaw(),            //  84: t1 = transmitter control
+            andi(6, 6, 1),                               //  88
+            Instruction.beq(6, 0, -8).getRaw(),          //  92: display busy
+            Instruction.sw(29, 5, 12).getRaw(),          //  96: show it
+            Instruction.addi(10, 10, 4).getRaw(),        // 100: next character
+            Instruction.beq(0, 0, -28).getRaw(),         // 104
+            Instruction.iType(0b1100111, 0, 0, 1, 0).getRaw()  // 108: jalr zero, 0(ra)
+        };
+    }
+
+    private static void loadString(MemoryBus bus, int address, String text) {
+        for (int i = 0; i < text.length(); i++) bus.write(address + 4 * i, text.charAt(i));
+        bus.write(address + 4 * text.length(), 0);
+    }
+
+    /** A machine with the keyboard and display mapped and `program` loaded. */
+    public static Machine machine(int[] program, Keyboard keyboard, Display display) {
+        MemoryBus bus = new MemoryBus();
+        Machine m = new Machine(bus, 0x800)
+            .attach(Keyboard.BASE, keyboard)
+            .attach(Display.BASE, display);
+        bus.loadProgram(program, 0);
+        loadString(bus, MENU, "\n1) say hello\n2) quit\n> ");
+        loadString(bus, HELLO, "hello!\n");
+        loadString(bus, BYE, "bye\n");
+        return m;
+    }
+
+    /** Run a program with scripted keys; returns the machine for its display and counters. */
+    public static Machine run(int[] program, String keys, Display display) {
+        Keyboard keyboard = new Keyboard();
+        keyboard.type(keys);
+        Machine m = machine(program, keyboard, display);
+        m.run(1_000_000);
+        return m;
+    }
+
+    private static String indent(String text) {
+        return "   | " + text.replace("\n", "\n   | ") + "\n";
+    }
+
+    public static String demonstrateConsole() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Keyboard and Display (RARS MMIO layout) ===\n\n");
+        MemoryBus map = new MemoryBus();
+        map.map(Keyboard.BASE, new Keyboard()).map(Display.BASE, new Display());
+        sb.append(map.describeMap()).append('\n');
+
+        Display display = new Display();
+        Machine m = run(echo(), "hi there\n", display);
+        sb.append("1) echo, keys \"hi there\\n\":\n");
+        sb.append(indent(display.getText()));
+        sb.append(String.format("   %d instructions in %d cycles for %d characters: the CPU spends them\n",
+            m.getCpu().getStats().getInstructionCount(), m.getCycles(), display.getText().length()));
+        sb.append("   polling - the keys are all waiting, so the display's delay sets the pace.\n\n");
+
+        display = new Display();
+        m = run(menu(), "1x2", display);
+        sb.append("2) menu, keys '1', 'x', '2':\n");
+        sb.append(indent(display.getText()));
+        sb.append(String.format("   halted: %b, %d characters shown, none dropped: %b\n\n",
+            m.isHalted(), display.getText().length(), display.getDropped() == 0));
+
+        display = new Display(20);
+        Keyboard keyboard = new Keyboard();
+        keyboard.type("abc");
+        MemoryBus bus = new MemoryBus();
+        bus.map(Keyboard.BASE, keyboard).map(Display.BASE, display);
+        for (int i = 0; i < 3; i++) bus.write(Display.BASE + Display.DATA, bus.read(Keyboard.BASE + Keyboard.DATA));
+        sb.append("3) Skipping the handshake: three stores to the display without checking READY\n");
+        sb.append(String.format("   shown \"%s\", dropped %d - the display was still busy with the first.\n",
+            display.getText(), display.getDropped()));
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateConsole());
+            return;
+        }
+        try {
+            int[] program;
+            switch (args[0]) {
+                case "echo": program = echo(); break;
+                case "menu": program = menu(); break;
+                default: throw new IllegalArgumentException("Unknown program: " + args[0] + " (echo or menu)");
+            }
+            Keyboard keyboard = new Keyboard();
+            if (args.length >= 3 && args[1].equals("--input")) keyboard.type(args[2].replace("\\n", "\n"));
+            else if (args.length == 1) keyboard.readFrom(System.in);
+            else throw new IllegalArgumentException("Unknown option: " + args[1]);
+            Display display = new Display();
+            display.echoTo(System.out);
+            Machine m = machine(program, keyboard, display);
+            while (!m.isHalted() && !keyboard.isExhausted()) m.run(100_000);
+            m.run(1_000_000);                                // finish printing after the input ends
+            System.out.println();
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+package computerdesign.io;
+
+/**
+ * Device - a peripheral that answers loads and stores in an address window
+ * of a MemoryBus (memory-mapped I/O).
+ *
+ * The bus subtracts the window's base address, so a device only ever sees
+ * byte offsets 0 .. getSize()-1, always word-aligned. What a read or write
+ * MEANS is up to the device: unlike RAM, reading a status register twice
+ * can give different answers, and writing a control register can start an
+ * action.
+ *
+ * @see MemoryBus
+ */
+public interface Device {
+
+    /** Read the 32-bit register at a word-aligned byte offset. */
+    int read(int offset);
+
+    /** Write the 32-bit register at a word-aligned byte offset. */
+    void write(int offset, int value);
+
+    /** Size of the device's address window in bytes. */
+    int getSize();
+
+    String getName();
+}
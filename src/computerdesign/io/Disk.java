@@ -0,0 +1,484 @@
+package computerdesign.io;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.List;
+import java.util.Random;
+
+/**
+ * Disk - a hard disk with seek time, rotational latency and transfer time,
+ * and the schedulers that choose which request to serve next.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHERE THE TIME GOES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *          ┌───────────────┐
+ *          │   ╭───────╮   │      access time = seek + rotation + transfer
+ *          │  │ ╭───╮   │  │
+ *   arm ═══════▶ ● │  │  │      seek       move the arm to the cylinder:
+ *          │  │ ╰───╯   │  │                 track-to-track ... full stroke
+ *          │   ╰───────╯   │      rotation   wait for the sector to come
+ *          └───────────────┘                 round: 0 .. one revolution
+ *                                            (7200 rpm: 8.33 ms, avg 4.17)
+ *                                 transfer   bytes / transfer rate
+ *
+ * Seek and rotation are mechanical and dominate: reading 4 KiB at 150 MB/s
+ * takes 0.03 ms, finding it several ms. The platter keeps turning while the
+ * arm moves, so the rotational wait depends on exactly when the arm arrives.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * SCHEDULING THE ARM
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   FCFS   in arrival order. Fair, but the arm zig-zags across the disk.
+ *   SSTF   shortest seek first: the nearest cylinder. Much less movement,
+ *          but requests far from a busy region can STARVE.
+ *   SCAN   the elevator: sweep in one direction serving everything on the
+ *          way, to the edge of the disk, then sweep back. Bounded waits.
+ *          (LOOK turns at the last request instead of the edge.)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: Disk                                               worked example
+ *        Disk --trace file [--start cylinder]
+ *
+ * A trace has one request per line, `ms cylinder sector [sectors]`:
+ *
+ *   # ms   cylinder  sector  sectors
+ *   0.0    98        120     8
+ *   0.5    183       10
+ *
+ * Covers learning goals: T6.2 (I/O, storage)
+ */
+public class Disk {
+
+    /** One read: arrives at `arrival` ms for `sectors` sectors from (cylinder, sector). */
+    public static final class Request {
+        public final double arrival;
+        public final int cylinder;
+        public final int sector;
+        public final int sectors;
+
+        public Request(double arrival, int cylinder, int sector, int sectors) {
+            if (arrival < 0 || cylinder < 0 || sector < 0 || sectors < 1) {
+                throw new IllegalArgumentException("Request needs arrival, cylinder, sector >= 0 and sectors >= 1");
+            }
+            this.arrival = arrival;
+            this.cylinder = cylinder;
+            this.sector = sector;
+            this.sectors = sectors;
+        }
+    }
+
+    private final int cylinders;
+    private final int sectorsPerTrack;
+    private final int sectorBytes;
+    private final double rpm;
+    private final double trackToTrackMs;
+    private final double fullStrokeMs;
+    private final double transferMBps;
+
+    public Disk(int cylinders, int sectorsPerTrack, int sectorBytes, double rpm,
+                double trackToTrackMs, double fullStrokeMs, double transferMBps) {
+        if (cylinders < 2 || sectorsPerTrack < 1 || sectorBytes < 1 || rpm <= 0
+            || trackToTrackMs < 0 || fullStrokeMs < trackToTrackMs || transferMBps <= 0) {
+            throw new IllegalArgumentException("Invalid disk geometry or timing");
+        }
+        this.cylinders = cylinders;
+        this.sectorsPerTrack = sectorsPerTrack;
+        this.sectorBytes = sectorBytes;
+        this.rpm = rpm;
+        this.trackToTrackMs = trackToTrackMs;
+        this.fullStrokeMs = fullStrokeMs;
+        this.transferMBps = transferMBps;
+    }
+
+    /** 1000 cylinders, 1000 sectors of 512 B per track, 7200 rpm, 0.5-10 ms seeks, 150 MB/s. */
+    public Disk() {
+        this(1000, 1000, 512, 7200, 0.5, 10, 150);
+    }
+
+    public int getCylinders() {
+        return cylinders;
+    }
+
+    public double revolutionMs() {
+        return 60_000 / rpm;
+    }
+
+    /** Linear between track-to-track (1 cylinder) and full stroke. */
+    public double seekMs(int distance) {
+        if (distance == 0) return 0;
+        return trackToTrackMs + (fullStrokeMs - trackToTrackMs) * (distance - 1) / (cylinders - 2);
+    }
+
+    /** Wait at time `now` until `sector` is under the head. */
+    public double rotationMs(double now, int sector) {
+        double rev = revolutionMs();
+        double position = (now % rev) / rev;                         // fraction of a turn done
+        double target = (double) (sector % sectorsPerTrack) / sectorsPerTrack;
+        double wait = target - position;
+        if (wait < 0) wait += 1;
+        return wait * rev;
+    }
+
+    public double transferMs(int sectors) {
+        return sectors * sectorBytes / (transferMBps * 1e3);
+    }
+
+    // ==================== SCHEDULERS ====================
+
+    /** Chooses the next request; may move the arm first (SCAN sweeping to the edge). */
+    public interface Scheduler {
+        /** Index into `pending` (all arrived, never empty). */
+        int next(List<Request> pending, int head);
+
+        /** A cylinder the arm should travel to before serving anything, or -1. */
+        default int detour(List<Request> pending, int head, int cylinders) {
+            return -1;
+        }
+
+        String getName();
+    }
+
+    public static final class Fcfs implements Scheduler {
+        @Override
+        public int next(List<Request> pending, int head) {
+            int best = 0;
+            for (int i = 1; i < pending.size(); i++) {
+                if (pending.get(i).arrival < pending.get(best).arrival) best = i;
+            }
+            return best;
+        }
+
+        @Override
+        public String getName() {
+            return "FCFS";
+        }
+    }
+
+    public static final class Sstf implements Scheduler {
+        @Override
+        public int next(List<Request> pending, int head) {
+            int best = 0;
+            for (int i = 1; i < pending.size(); i++) {
+                if (Math.abs(pending.get(i).cylinder - head) < Math.abs(pending.get(best).cylinder - head)) best = i;
+            }
+            return best;
+        }
+
+        @Override
+        public String getName() {
+            return "SSTF";
+        }
+    }
+
+    public static final class Scan implements Scheduler {
+        private boolean up;
+
+        /** Start sweeping towards higher cylinders (`up`) or towards 0. */
+        public Scan(boolean up) {
+            this.up = up;
+        }
+
+        private boolean ahead(Request r, int head) {
+            return up ? r.cylinder >= head : r.cylinder <= head;
+        }
+
+        @Override
+        public int detour(List<Request> pending, int head, int cylinders) {
+            for (Request r : pending) if (ahead(r, head)) return -1;
+            int edge = up ? cylinders - 1 : 0;
+            up = !up;
+            return head == edge ? -1 : edge;
+        }
+
+        @Override
+        public int next(List<Request> pending, int head) {
+            int best = -1;
+            for (int i = 0; i < pending.size(); i++) {
+                Request r = pending.get(i);
+                if (ahead(r, head) && (best < 0
+                    || Math.abs(r.cylinder - head) < Math.abs(pending.get(best).cylinder - head))) {
+                    best = i;
+                }
+            }
+            return best;
+        }
+
+        @Override
+        public String getName() {
+            return "SCAN";
+        }
+    }
+
+    // ==================== THE SIMULATION ====================
+
+    /** One served request, in service order. */
+    public static final class Served {
+        public final Request request;
+        public final double start;
+        public final double seek;
+        public final double rotation;
+        public final double transfer;
+        public final double done;
+
+        Served(Request request, double start, double seek, double rotation, double transfer) {
+            this.request = request;
+            this.start = start;
+            this.seek = seek;
+            this.rotation = rotation;
+            this.transfer = transfer;
+            this.done = start + seek + rotation + transfer;
+        }
+
+        public double latency() {
+            return done - request.arrival;
+        }
+    }
+
+    public static final class Result {
+        public final String scheduler;
+        public final List<Served> served;
+        /** Cylinders the arm travelled, detours included. */
+        public final int movement;
+        /** Head positions the arm visited, starting cylinder first. */
+        public final List<Integer> path;
+
+        Result(String scheduler, List<Served> served, int movement, List<Integer> path) {
+            this.scheduler = scheduler;
+            this.served = served;
+            this.movement = movement;
+            this.path = path;
+        }
+
+        public double meanLatency() {
+            double sum = 0;
+            for (Served s : served) sum += s.latency();
+            return sum / served.size();
+        }
+
+        public double maxLatency() {
+            double max = 0;
+            for (Served s : served) max = Math.max(max, s.latency());
+            return max;
+        }
+
+        public double finish() {
+            return served.get(served.size() - 1).done;
+        }
+
+        private double total(java.util.function.ToDoubleFunction<Served> part) {
+            double sum = 0;
+            for (Served s : served) sum += part.applyAsDouble(s);
+            return sum;
+        }
+
+        public double seekTotal() { return total(s -> s.seek); }
+        public double rotationTotal() { return total(s -> s.rotation); }
+        public double transferTotal() { return total(s -> s.transfer); }
+    }
+
+    /** Serve every request in `trace`, starting with the arm at `start` at time 0. */
+    public Result simulate(List<Request> trace, Scheduler scheduler, int start) {
+        for (Request r : trace) {
+            if (r.cylinder >= cylinders) throw new IllegalArgumentException("Cylinder " + r.cylinder + " is off the disk");
+        }
+        List<Request> future = new ArrayList<>(trace);
+        future.sort((a, b) -> Double.compare(a.arrival, b.arrival));
+        List<Request> pending = new ArrayList<>();
+        List<Served> served = new ArrayList<>();
+        List<Integer> path = new ArrayList<>();
+        path.add(start);
+        int head = start;
+        int movement = 0;
+        double now = 0;
+
+        while (!future.isEmpty() || !pending.isEmpty()) {
+            if (pending.isEmpty()) now = Math.max(now, future.get(0).arrival);
+            while (!future.isEmpty() && future.get(0).arrival <= now) pending.add(future.remove(0));
+
+            int edge = scheduler.detour(pending, head, cylinders);
+            if (edge >= 0) {
+                now += seekMs(Math.abs(edge - head));
+                movement += Math.abs(edge - head);
+                head = edge;
+                path.add(head);
+                continue;
+            }
+            Request r = pending.remove(scheduler.next(pending, head));
+            double seek = seekMs(Math.abs(r.cylinder - head));
+            double rotation = rotationMs(now + seek, r.sector);
+            Served s = new Served(r, now, seek, rotation, transferMs(r.sectors));
+            served.add(s);
+            movement += Math.abs(r.cylinder - head);
+            head = r.cylinder;
+            path.add(head);
+            now = s.done;
+        }
+        return new Result(scheduler.getName(), served, movement, path);
+    }
+
+    // ==================== TRACES ====================
+
+    /** Parse the `ms cylinder sector [sectors]` format. */
+    public static List<Request> parse(List<String> lines) {
+        List<Request> out = new ArrayList<>();
+        int n = 0;
+        for (String line : lines) {
+            n++;
+            String text = line.replaceAll("#.*", "").trim();
+            if (text.isEmpty()) continue;
+            String[] f = text.split("\\s+");
+            if (f.length < 3 || f.length > 4) {
+                throw new IllegalArgumentException("Line " + n + ": expected `ms cylinder sector [sectors]`");
+            }
+            try {
+                out.add(new Request(Double.parseDouble(f[0]), Integer.parseInt(f[1]), Integer.parseInt(f[2]),
+                    f.length == 4 ? Integer.parseInt(f[3]) : 8));
+            } catch (NumberFormatException e) {
+                throw new IllegalArgumentException("Line " + n + ": " + e.getMessage());
+            }
+        }
+        if (out.isEmpty()) throw new IllegalArgumentException("Trace has no requests");
+        return out;
+    }
+
+    /** The textbook queue (Silberschatz): all at time 0, head at 53, 200 cylinders. */
+    public static List<Request> textbookQueue() {
+        List<Request> out = new ArrayList<>();
+        for (int c : new int[] {98, 183, 37, 122, 14, 124, 65, 67}) out.add(new Request(0, c, 0, 8));
+        return out;
+    }
+
+    /**
+     * `count` 4 KiB reads arriving every `gapMs` on average, 70% of them in
+     * a busy region around cylinder 200 (a database file) and the rest
+     * anywhere on the disk.
+     */
+    public static List<Request> workload(int count, double gapMs, int cylinders) {
+        Random random = new Random(4160);
+        List<Request> out = new ArrayList<>();
+        double t = 0;
+        for (int i = 0; i < count; i++) {
+            t += -Math.log(1 - random.nextDouble()) * gapMs;
+            int cylinder = random.nextDouble() < 0.7
+                ? Math.max(0, Math.min(cylinders - 1, 200 + (int) (random.nextGaussian() * 30)))
+                : random.nextInt(cylinders);
+            out.add(new Request(t, cylinder, random.nextInt(1000), 8));
+        }
+        return out;
+    }
+
+    // ==================== REPORTING ====================
+
+    private static String path(Result r) {
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < r.path.size(); i++) sb.append(i == 0 ? "" : " → ").append(r.path.get(i));
+        return sb.toString();
+    }
+
+    public static String summary(Result... results) {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("   %-6s %9s %9s %9s %10s %10s %10s %10s\n", "sched", "cylinders", "seek ms",
+            "rot ms", "xfer ms", "mean lat", "max lat", "finish ms"));
+        for (Result r : results) {
+            sb.append(String.format("   %-6s %9d %9.1f %9.1f %10.2f %10.1f %10.1f %10.1f\n", r.scheduler,
+                r.movement, r.seekTotal(), r.rotationTotal(), r.transferTotal(), r.meanLatency(),
+                r.maxLatency(), r.finish()));
+        }
+        return sb.toString();
+    }
+
+    public static String requests(Result r) {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("   %8s %8s %8s %8s %8s %8s %9s\n", "arrival", "cylinder", "start", "seek", "rotation",
+            "transfer", "latency"));
+        for (Served s : r.served) {
+            sb.append(String.format("   %8.2f %8d %8.2f %8.2f %8.2f %8.2f %9.2f\n", s.request.arrival,
+                s.request.cylinder, s.start, s.seek, s.rotation, s.transfer, s.latency()));
+        }
+        return sb.toString();
+    }
+
+    public static String demonstrateDisk() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Disk Access Time and Scheduling ===\n\n");
+        Disk textbook = new Disk(200, 1000, 512, 7200, 0.5, 10, 150);
+        List<Request> queue = textbookQueue();
+        sb.append("1) The textbook queue 98, 183, 37, 122, 14, 124, 65, 67; head at 53, 200 cylinders:\n");
+        Result[] tb = {
+            textbook.simulate(queue, new Fcfs(), 53),
+            textbook.simulate(queue, new Sstf(), 53),
+            textbook.simulate(queue, new Scan(false), 53)
+        };
+        for (Result r : tb) sb.append(String.format("   %-5s %3d cylinders  %s\n", r.scheduler, r.movement, path(r)));
+        sb.append('\n');
+
+        Disk disk = new Disk();
+        sb.append(String.format("2) The %d-cylinder, %.0f rpm disk: one revolution %.2f ms; a 4 KiB read\n",
+            disk.cylinders, disk.rpm, disk.revolutionMs()));
+        sb.append(String.format("   transfers in %.3f ms; seeks take %.1f ms (1 cylinder) to %.1f ms (full stroke).\n",
+            disk.transferMs(8), disk.seekMs(1), disk.seekMs(disk.cylinders - 1)));
+        sb.append("   Each request of the SSTF run, first 8:\n");
+        List<Request> work = workload(200, 6, disk.cylinders);
+        Result sstf = disk.simulate(work, new Sstf(), 500);
+        String[] lines = requests(sstf).split("\n");
+        for (int i = 0; i < Math.min(9, lines.length); i++) sb.append(lines[i]).append('\n');
+        sb.append('\n');
+
+        sb.append("3) 200 requests, one every 6 ms on average, 70% near cylinder 200:\n");
+        sb.append(summary(disk.simulate(work, new Fcfs(), 500), sstf, disk.simulate(work, new Scan(true), 500)));
+        sb.append("\n   Transfer is a rounding error: the arm and the platter are the cost.\n");
+        sb.append("   FCFS cannot keep up - the queue grows and so does the latency. SSTF\n");
+        sb.append("   moves least, but its maximum shows the starved far-away requests;\n");
+        sb.append("   SCAN gives up some mean latency for a lower worst case.\n");
+        sb.append("   Once seeks are short, rotation costs more than seeking, and none of\n");
+        sb.append("   the three looks at it. Drive firmware schedules by positioning time\n");
+        sb.append("   (seek + rotation, SPTF) for that reason.\n");
+        return sb.toString();
+    }
+
+    // ==================== COMMAND LINE ====================
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateDisk());
+            return;
+        }
+        String file = null;
+        int start = 0;
+        try {
+            for (int i = 0; i < args.length; i++) {
+                switch (args[i]) {
+                    case "--trace": file = args[++i]; break;
+                    case "--start": start = Integer.parseInt(args[++i]); break;
+                    default: throw new IllegalArgumentException("Unknown option: " + args[i]);
+                }
+            }
+            if (file == null) throw new IllegalArgumentException("--trace is required");
+            List<Request> trace = parse(Files.readAllLines(Paths.get(file), StandardCharsets.UTF_8));
+            Disk disk = new Disk();
+            Result[] results = {
+                disk.simulate(trace, new Fcfs(), start),
+                disk.simulate(trace, new Sstf(), start),
+                disk.simulate(trace, new Scan(true), start)
+            };
+            for (Result r : results) System.out.println(r.scheduler + ":\n" + requests(r));
+            System.out.print(summary(results));
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.err.println("Missing value after " + args[args.length - 1]);
+            System.exit(2);
+        } catch (IOException e) {
+            System.err.println("Cannot read " + file + ": " + e.getMessage());
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+package computerdesign.io;
+
+import java.io.PrintStream;
+
+/**
+ * Display - the transmitter half of the RARS/MARS "Keyboard and Display
+ * MMIO Simulator": a character display that is slower than the CPU.
+ *
+ *   0xFFFF0008  TRANSMITTER CONTROL  bit 0 READY (may write DATA),
+ *                                    bit 1 INTERRUPT ENABLE (read/write)
+ *   0xFFFF000C  TRANSMITTER DATA     write an ASCII code (bits 7..0) to
+ *                                    show it
+ *
+ * After each character READY stays low for `delay` cycles while the
+ * display "prints"; a character written then is lost, as on the real
+ * simulator. A form feed (12) clears the screen.
+ *
+ * @see Keyboard
+ * @see Console
+ */
+public class Display implements Device, Clocked {
+
+    /** Where RARS puts the display. */
+    public static final int BASE = 0xFFFF_0008;
+    public static final int CONTROL = 0x0;
+    public static final int DATA = 0x4;
+    public static final int READY = 1;
+    public static final int INTERRUPT_ENABLE = 1 << 1;
+    private static final int FORM_FEED = 12;
+
+    private final int delay;
+    private final StringBuilder screen = new StringBuilder();
+    private PrintStream echo;
+    private boolean interruptEnable;
+    private int busy;
+    private int dropped;
+
+    /** A display that needs `delay` cycles per character. */
+    public Display(int delay) {
+        if (delay < 0) throw new IllegalArgumentException("Delay must not be negative, got " + delay);
+        this.delay = delay;
+    }
+
+    public Display() {
+        this(5);
+    }
+
+    /** Also print every character to `out` as it arrives. */
+    public void echoTo(PrintStream out) {
+        this.echo = out;
+    }
+
+    @Override
+    public int read(int offset) {
+        if (offset != CONTROL) return 0;
+        return (busy == 0 ? READY : 0) | (interruptEnable ? INTERRUPT_ENABLE : 0);
+    }
+
+    @Override
+    public void write(int offset, int value) {
+        if (offset == CONTROL) {
+            interruptEnable = (value & INTERRUPT_ENABLE) != 0;
+        } else if (offset == DATA) {
+            if (busy > 0) {
+                dropped++;
+                return;
+            }
+            char c = (char) (value & 0xFF);
+            if (c == FORM_FEED) screen.setLength(0);
+            else screen.append(c);
+            if (echo != null) {
+                echo.print(c);
+                echo.flush();
+            }
+            busy = delay;
+        }
+    }
+
+    @Override
+    public boolean tick() {
+        if (busy > 0) busy--;
+        return false;
+    }
+
+    @Override
+    public boolean interruptRequest() {
+        return interruptEnable && busy == 0;
+    }
+
+    /** Everything shown since the last form feed. */
+    public String getText() {
+        return screen.toString();
+    }
+
+    /** Characters written while the display was not READY. */
+    public int getDropped() {
+        return dropped;
+    }
+
+    @Override
+    public int getSize() {
+        return 8;
+    }
+
+    @Override
+    public String getName() {
+        return "display (transmitter)";
+    }
+}
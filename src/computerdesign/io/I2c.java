@@ -0,0 +1,259 @@
+package computerdesign.io;
+
+import computerdesign.logic.SignalTrace;
+import computerdesign.logic.TimingDiagram;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.List;
+
+/**
+ * I2c - the two-wire Inter-Integrated Circuit bus at the bit level: a
+ * master addressing targets on a shared, open-drain SDA/SCL pair.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * TWO WIRES, MANY DEVICES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Nobody drives a line high: pull-up resistors do, and any device may pull
+ * it low. A line is therefore the AND of what everyone wants (wired-AND):
+ * that is how a target can answer on the master's data line, and how it
+ * can hold the master's clock.
+ *
+ *   SDA changes only while SCL is low - except for two conditions:
+ *
+ *   START  S   SDA falls while SCL is high      ‾‾\___   (SCL ‾‾‾‾‾)
+ *   STOP   P   SDA rises while SCL is high      __/‾‾‾   (SCL ‾‾‾‾‾)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * A REGISTER READ
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   S  addr+W  A  reg  A  Sr  addr+R  A  data  A  data  N  P
+ *      ─master─   ─m─     │   ─master─      ─target─   │
+ *               target ACKs           master ACKs, NACKs the last byte
+ *
+ *   addressing  7-bit address + R/W bit; the target with that address
+ *               pulls SDA low in the 9th bit (ACK). Nobody home: SDA stays
+ *               high (NACK) and the master gives up with a STOP.
+ *   Sr          a repeated START turns the bus around without releasing it
+ *   stretching  a target that is not ready holds SCL low after the ACK; the
+ *               master sees its clock did not rise and waits
+ *
+ * Bytes go MSB first, one bit per SCL period; in the diagrams a step is
+ * half a period.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: I2c                    worked example with a TemperatureSensor
+ *
+ * Covers learning goals: T6.2 (I/O, serial buses)
+ *
+ * @see Spi
+ * @see TemperatureSensor
+ */
+public class I2c {
+
+    /** A device on the bus, seen a byte at a time. */
+    public interface Target {
+        /** 7-bit address. */
+        int address();
+
+        /** Addressed after a (repeated) START, for reading or writing. */
+        void start(boolean read);
+
+        /** A byte from the master; true to ACK it. */
+        boolean write(int value);
+
+        /** The next byte for the master. */
+        int read();
+
+        /** Steps to hold SCL low before the next byte to the master (clock stretching). */
+        default int stretch() {
+            return 0;
+        }
+
+        /** One bus step passes. */
+        default void tick() {}
+    }
+
+    private final List<Target> targets;
+    private final SignalTrace trace = new SignalTrace("SCL", "SDA", "bit");
+    private final List<String> log = new ArrayList<>();
+    private boolean sda = true;
+    private boolean busy;
+    private int steps;
+    private int stretched;
+
+    public I2c(Target... targets) {
+        this.targets = Arrays.asList(targets);
+    }
+
+    // ==================== BUS CONDITIONS ====================
+
+    private void step(boolean scl, boolean data, String label) {
+        sda = data;
+        trace.record("SCL", scl);
+        trace.record("SDA", data);
+        trace.recordLabel("bit", label);
+        steps++;
+        for (Target t : targets) t.tick();
+    }
+
+    private void start() {
+        String label = busy ? "Sr" : "S";
+        if (busy) step(false, true, label);
+        step(true, true, label);
+        step(true, false, label);
+        busy = true;
+        log.add(label);
+    }
+
+    private void stop() {
+        step(false, false, "P");
+        step(true, false, "P");
+        step(true, true, "P");
+        busy = false;
+        log.add("P");
+    }
+
+    /** One bit: SDA is the AND of what the master and the target leave on it. */
+    private boolean bit(boolean master, boolean target, String label) {
+        boolean level = master && target;
+        step(false, level, label);
+        step(true, level, label);
+        return level;
+    }
+
+    // ==================== BYTES ====================
+
+    /** Address byte; returns the target that ACKed, or null. */
+    private Target address(int address, boolean read) {
+        for (int i = 6; i >= 0; i--) bit((address >> i & 1) != 0, true, "A" + i);
+        bit(!read, true, read ? "R" : "W");
+        Target found = null;
+        for (Target t : targets) if (t.address() == address) found = t;
+        bit(true, found == null, found == null ? "NAK" : "ACK");
+        log.add(String.format("0x%02X+%s %s", address, read ? "R" : "W", found == null ? "N" : "A"));
+        if (found != null) found.start(read);
+        return found;
+    }
+
+    private boolean send(Target t, int value) {
+        for (int i = 7; i >= 0; i--) bit((value >> i & 1) != 0, true, "D" + i);
+        boolean ack = t.write(value & 0xFF);
+        bit(true, !ack, ack ? "ACK" : "NAK");
+        log.add(String.format("0x%02X %s", value & 0xFF, ack ? "A" : "N"));
+        return ack;
+    }
+
+    private int receive(Target t, boolean ack) {
+        int hold = t.stretch();
+        for (int i = 0; i < hold; i++) step(false, sda, "...");   // master released SCL, target holds it
+        if (hold > 0) log.add("[SCL held " + hold + "]");
+        stretched += hold;
+        int value = t.read() & 0xFF;
+        for (int i = 7; i >= 0; i--) bit(true, (value >> i & 1) != 0, "D" + i);
+        bit(!ack, true, ack ? "ACK" : "NAK");
+        log.add(String.format("0x%02X %s", value, ack ? "A" : "N"));
+        return value;
+    }
+
+    // ==================== TRANSACTIONS ====================
+
+    /** Write `data` to registers from `reg` on; false if the target NACKed. */
+    public boolean writeRegisters(int address, int reg, int... data) {
+        start();
+        Target t = address(address, false);
+        boolean ok = t != null && send(t, reg);
+        for (int i = 0; ok && i < data.length; i++) ok = send(t, data[i]);
+        stop();
+        return ok;
+    }
+
+    /** Read `count` registers from `reg` on; null if nobody ACKed. */
+    public int[] readRegisters(int address, int reg, int count) {
+        start();
+        Target t = address(address, false);
+        if (t == null || !send(t, reg)) {
+            stop();
+            return null;
+        }
+        start();
+        address(address, true);
+        int[] data = new int[count];
+        for (int i = 0; i < count; i++) data[i] = receive(t, i < count - 1);
+        stop();
+        return data;
+    }
+
+    public SignalTrace getTrace() {
+        return trace;
+    }
+
+    /** Steps recorded so far: mark where a diagram should start. */
+    public int getSteps() {
+        return steps;
+    }
+
+    public int getStretched() {
+        return stretched;
+    }
+
+    /** The symbols since the last call, e.g. "S 0x48+W A 0x0F A ... P". */
+    public String takeLog() {
+        String s = String.join(" ", log);
+        log.clear();
+        return s;
+    }
+
+    public String diagram(int from, int to) {
+        return new TimingDiagram(trace.slice(from, to)).cellWidth(3).toAscii();
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateI2c() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== I2C: Addressing, ACK/NACK and Clock Stretching ===\n\n");
+        TemperatureSensor sensor = new TemperatureSensor(21.5, 70);
+        int addr = TemperatureSensor.I2C_ADDRESS;
+        I2c bus = new I2c(sensor.i2c(addr));
+
+        sb.append(String.format("1) Write T_HIGH = 30 °C: [S] [0x%02X+W] [ACK] [reg 0x03] [ACK] [30] [ACK] [P]\n\n", addr));
+        bus.writeRegisters(addr, TemperatureSensor.T_HIGH, 30);
+        sb.append(bus.diagram(0, 22)).append('\n');
+        sb.append("   START (SDA falls, SCL high), address 1001000, W = 0, and the sensor\n");
+        sb.append("   pulls SDA low in the 9th bit.\n");
+        sb.append("   log: " + bus.takeLog() + "\n\n");
+
+        sb.append("2) Read WHO_AM_I: pointer write, repeated START, read\n");
+        int[] id = bus.readRegisters(addr, TemperatureSensor.WHO_AM_I, 1);
+        sb.append(String.format("   log: %s\n   → 0x%02X\n\n", bus.takeLog(), id[0]));
+
+        sb.append("3) Nobody at 0x49:\n");
+        int[] none = bus.readRegisters(0x49, TemperatureSensor.WHO_AM_I, 1);
+        sb.append(String.format("   log: %s\n   → %s: SDA stayed high in the ACK bit, the master stopped\n\n",
+            bus.takeLog(), none == null ? "NACK" : "?"));
+
+        sb.append("4) Start a conversion, then read TEMP_MSB and TEMP_LSB at once\n");
+        sensor.setTemperature(23.75);
+        bus.writeRegisters(addr, TemperatureSensor.CONFIG, TemperatureSensor.OS);
+        bus.takeLog();
+        int from = bus.getSteps();
+        int before = bus.getStretched();
+        int[] temp = bus.readRegisters(addr, TemperatureSensor.TEMP_MSB, 2);
+        int hold = bus.getStretched() - before;
+        sb.append("   log: " + bus.takeLog() + "\n");
+        int ack = from + 2 + 18 + 18 + 3 + 16;       // S, address, pointer, Sr, address bits
+        sb.append(String.format("\n   The sensor holds SCL low for %d steps after the ACK, until the value\n", hold));
+        sb.append("   is converted; then the first data bits follow:\n\n");
+        sb.append(bus.diagram(ack, ack + 2 + hold + 8)).append('\n');
+        sb.append(String.format("   → %.4f °C, the new value; SPI would have returned the old one.\n",
+            TemperatureSensor.celsius(temp[0], temp[1])));
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        System.out.println(demonstrateI2c());
+    }
+}
@@ -0,0 +1,109 @@
+package computerdesign.io;
+
+import java.io.IOException;
+import java.io.InputStream;
+import java.util.ArrayDeque;
+import java.util.concurrent.ConcurrentLinkedQueue;
+
+/**
+ * Keyboard - the receiver half of the RARS/MARS "Keyboard and Display MMIO
+ * Simulator": a status register and a data register.
+ *
+ *   0xFFFF0000  RECEIVER CONTROL  bit 0 READY (a key is waiting),
+ *                                 bit 1 INTERRUPT ENABLE (read/write)
+ *   0xFFFF0004  RECEIVER DATA     the key's ASCII code in bits 7..0;
+ *                                 reading it clears READY
+ *
+ * A program polls READY, then reads DATA - or sets INTERRUPT ENABLE and
+ * is interrupted when READY rises (interruptRequest() feeds Machine).
+ * Keys come from type() or, with readFrom(), from an input stream such as
+ * System.in, read on a background thread so the machine never blocks.
+ *
+ * @see Display
+ * @see Console
+ */
+public class Keyboard implements Device, Clocked {
+
+    /** Where RARS puts the keyboard. */
+    public static final int BASE = 0xFFFF_0000;
+    public static final int CONTROL = 0x0;
+    public static final int DATA = 0x4;
+    public static final int READY = 1;
+    public static final int INTERRUPT_ENABLE = 1 << 1;
+
+    private final ArrayDeque<Integer> keys = new ArrayDeque<>();
+    private final ConcurrentLinkedQueue<Integer> incoming = new ConcurrentLinkedQueue<>();
+    private volatile boolean streaming;
+    private boolean interruptEnable;
+    private int keysRead;
+
+    /** Queue keystrokes, as if typed. */
+    public void type(String text) {
+        for (char c : text.toCharArray()) keys.add(c & 0xFF);
+    }
+
+    /** Take further keys from `in` once the typed ones are used up. */
+    public void readFrom(InputStream in) {
+        streaming = true;
+        Thread reader = new Thread(() -> {
+            try {
+                for (int b = in.read(); b >= 0; b = in.read()) incoming.add(b);
+            } catch (IOException e) {
+                // treated as the end of input
+            }
+            streaming = false;
+        }, "keyboard input");
+        reader.setDaemon(true);
+        reader.start();
+    }
+
+    /** No key waiting and none still to come. */
+    public boolean isExhausted() {
+        return keys.isEmpty() && incoming.isEmpty() && !streaming;
+    }
+
+    @Override
+    public int read(int offset) {
+        switch (offset) {
+            case CONTROL:
+                return (keys.isEmpty() ? 0 : READY) | (interruptEnable ? INTERRUPT_ENABLE : 0);
+            case DATA:
+                if (keys.isEmpty()) return 0;
+                keysRead++;
+                return keys.remove();
+            default:
+                return 0;
+        }
+    }
+
+    @Override
+    public void write(int offset, int value) {
+        if (offset == CONTROL) interruptEnable = (value & INTERRUPT_ENABLE) != 0;
+    }
+
+    @Override
+    public boolean tick() {
+        Integer b = keys.isEmpty() ? incoming.poll() : null;
+        if (b != null) keys.add(b);
+        return false;
+    }
+
+    @Override
+    public boolean interruptRequest() {
+        return interruptEnable && !keys.isEmpty();
+    }
+
+    public int getKeysRead() {
+        return keysRead;
+    }
+
+    @Override
+    public int getSize() {
+        return 8;
+    }
+
+    @Override
+    public String getName() {
+        return "keyboard (receiver)";
+    }
+}
@@ -0,0 +1,193 @@
+package computerdesign.io;
+
+import computerdesign.processor.ExceptionHandler;
+import computerdesign.processor.ExceptionHandler.ExceptionType;
+import computerdesign.processor.SingleCycleProcessor;
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * Machine - a single-cycle CPU, a MemoryBus with devices that run alongside
+ * it, and machine-mode interrupts.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * ONE CYCLE
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   1. Every Clocked device ticks. If one pulls the reset line, the CPU is
+ *      reset (pc ← 0, registers and interrupt enables cleared; memory and
+ *      devices keep their state). If one used the bus, the CPU cannot
+ *      fetch: the cycle is stolen and nothing else happens.
+ *   2. The devices' interrupt lines are ORed into the machine external
+ *      interrupt (mip.MEIP) - or, with an interrupt controller (Plic), only
+ *      the controller's output is. If it is enabled (mstatus.MIE and
+ *      mie.MEIE), the trap is taken instead of an instruction:
+ *
+ *        mepc ← pc,  mcause ← 0x8000000B,  MIE ← 0,  pc ← mtvec
+ *
+ *   3. Otherwise the CPU executes one instruction. MRET and WFI are
+ *      executed here, since the processor itself has no CSRs:
+ *
+ *        mret   pc ← mepc, MIE ← MPIE
+ *        wfi    wait (idle cycles) until an interrupt is taken; mepc then
+ *               points past the wfi
+ *
+ * The handler gets no help saving registers: the demos reserve t5/t6 for
+ * it, which real code would do with a stack frame.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage:
+ *   MemoryBus bus = new MemoryBus();
+ *   Machine m = new Machine(bus, 0x800).attach(0x4000_0000, new DmaController(bus));
+ *   bus.loadProgram(main, 0); bus.loadProgram(handler, 0x800);
+ *   m.enableInterrupts();
+ *   m.run(100_000);
+ */
+public class Machine {
+
+    public static final int MRET = 0x30200073;
+    public static final int WFI = 0x10500073;
+    private static final int MSTATUS_MIE = 1 << 3;
+
+    private final MemoryBus bus;
+    private final SingleCycleProcessor cpu;
+    private final ExceptionHandler traps;
+    private final List<Clocked> clocked = new ArrayList<>();
+    private Clocked interruptController;
+
+    private int cycles;
+    private int stolenCycles;
+    private int idleCycles;
+    private int resets;
+
+    /** A machine over `bus` whose traps go to `trapVector`. */
+    public Machine(MemoryBus bus, int trapVector) {
+        this.bus = bus;
+        this.cpu = new SingleCycleProcessor(bus);
+        this.traps = new ExceptionHandler(trapVector);
+    }
+
+    /** Map a device; Clocked devices are also ticked every cycle. */
+    public Machine attach(int base, Device device) {
+        bus.map(base, device);
+        if (device instanceof Clocked) clocked.add((Clocked) device);
+        return this;
+    }
+
+    /** A Clocked component that has no address window. */
+    public Machine attach(Clocked component) {
+        clocked.add(component);
+        return this;
+    }
+
+    /**
+     * Route external interrupts through `controller` (attached as usual):
+     * MEIP then follows its line alone, and the devices' own lines only
+     * reach the CPU through it.
+     */
+    public Machine setInterruptController(Clocked controller) {
+        this.interruptController = controller;
+        return this;
+    }
+
+    /** Set mstatus.MIE and mie.MEIE, as a boot loop would with csrs. */
+    public void enableInterrupts() {
+        traps.setMstatus(traps.getMstatus() | MSTATUS_MIE);
+        traps.setInterruptEnable(ExceptionType.MACHINE_EXTERNAL_INT, true);
+    }
+
+    // ==================== EXECUTION ====================
+
+    public void step() {
+        if (cpu.isHalted()) return;
+        cycles++;
+
+        boolean busTaken = false;
+        boolean irq = false;
+        boolean reset = false;
+        for (Clocked c : clocked) {
+            busTaken |= c.tick();
+            irq |= c.interruptRequest();
+            reset |= c.resetRequest();
+        }
+        if (reset) {
+            reset();
+            return;
+        }
+        if (interruptController != null) irq = interruptController.interruptRequest();
+        if (irq) traps.raiseInterrupt(ExceptionType.MACHINE_EXTERNAL_INT);
+        else traps.clearInterrupt(ExceptionType.MACHINE_EXTERNAL_INT);
+        if (busTaken) {
+            stolenCycles++;
+            return;
+        }
+
+        int pc = cpu.getPC();
+        int raw = bus.read(pc);
+        if (traps.checkInterrupts()) {
+            cpu.setPC(traps.handleException(ExceptionType.MACHINE_EXTERNAL_INT, raw == WFI ? pc + 4 : pc, 0));
+        } else if (raw == MRET) {
+            cpu.setPC(traps.returnFromException());
+        } else if (raw == WFI) {
+            idleCycles++;
+        } else {
+            cpu.cycle();
+        }
+    }
+
+    /** A CPU reset as the reset pin causes it: execution restarts at address 0. */
+    public void reset() {
+        cpu.reset();
+        traps.setMstatus(0);
+        traps.setInterruptEnable(ExceptionType.MACHINE_EXTERNAL_INT, false);
+        traps.clearInterrupt(ExceptionType.MACHINE_EXTERNAL_INT);
+        resets++;
+    }
+
+    /** Step until the CPU halts or `maxCycles` have passed; returns the cycles run. */
+    public int run(int maxCycles) {
+        int start = cycles;
+        while (!cpu.isHalted() && cycles - start < maxCycles) step();
+        return cycles - start;
+    }
+
+    public boolean isHalted() {
+        return cpu.isHalted();
+    }
+
+    public SingleCycleProcessor getCpu() {
+        return cpu;
+    }
+
+    public MemoryBus getBus() {
+        return bus;
+    }
+
+    public ExceptionHandler getTraps() {
+        return traps;
+    }
+
+    public int getCycles() {
+        return cycles;
+    }
+
+    /** Cycles the CPU waited because a device had the bus. */
+    public int getStolenCycles() {
+        return stolenCycles;
+    }
+
+    /** Cycles spent waiting in wfi. */
+    public int getIdleCycles() {
+        return idleCycles;
+    }
+
+    /** Resets since the machine was built (the CPU's own counters restart at each). */
+    public int getResets() {
+        return resets;
+    }
+
+    public int getInterruptsTaken() {
+        return traps.getInterruptCount();
+    }
+}
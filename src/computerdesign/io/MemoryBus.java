@@ -0,0 +1,144 @@
+package computerdesign.io;
+
+import computerdesign.memory.MainMemory;
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * MemoryBus - main memory plus memory-mapped devices behind one address space.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * MEMORY-MAPPED I/O
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * The processor has only loads and stores. An ADDRESS DECODER on the bus
+ * looks at each address and selects who answers:
+ *
+ *   0x0000_0000 ┌──────────────┐
+ *               │     RAM      │  ← MainMemory (the default)
+ *               ├──────────────┤
+ *   0x4000_0000 │  peripheral  │  ← Device.read/write(address - base)
+ *               ├──────────────┤
+ *   0x4200_0000 │  peripheral  │
+ *               └──────────────┘
+ *
+ * An lw from a device window is an I/O read; an sw is an I/O write. The
+ * processor cannot tell the difference, which is the point: no special I/O
+ * instructions are needed (compare x86 IN/OUT).
+ *
+ * Because MemoryBus is a MainMemory, every processor in the compendium can
+ * be built on one: new SingleCycleProcessor(bus).
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage:
+ *   MemoryBus bus = new MemoryBus().map(0x4000_0000, gpio);
+ *   bus.write(0x4000_0000, 1);   // goes to gpio.write(0, 1)
+ */
+public class MemoryBus extends MainMemory {
+
+    private static final class Mapping {
+        final int base;
+        final Device device;
+
+        Mapping(int base, Device device) {
+            this.base = base;
+            this.device = device;
+        }
+
+        boolean contains(int address) {
+            long offset = (address & 0xFFFFFFFFL) - (base & 0xFFFFFFFFL);
+            return offset >= 0 && offset < device.getSize();
+        }
+    }
+
+    private final List<Mapping> mappings = new ArrayList<>();
+    private int deviceAccesses;
+
+    public MemoryBus(int sizeInBytes) {
+        super(sizeInBytes);
+    }
+
+    /** 64KB of RAM, no devices yet. */
+    public MemoryBus() {
+        super();
+    }
+
+    /**
+     * Map a device at a base address. Windows must be word-aligned, must not
+     * overlap, and must lie above RAM.
+     */
+    public MemoryBus map(int base, Device device) {
+        if ((base & 0x3) != 0) {
+            throw new IllegalArgumentException(String.format("Device base 0x%08X is not word-aligned", base));
+        }
+        long start = base & 0xFFFFFFFFL;
+        long end = start + device.getSize();
+        if (start < getSize() || end > 0x1_0000_0000L) {
+            throw new IllegalArgumentException(String.format(
+                "%s at 0x%08X overlaps RAM or wraps the address space", device.getName(), base));
+        }
+        for (Mapping m : mappings) {
+            long mStart = m.base & 0xFFFFFFFFL;
+            if (start < mStart + m.device.getSize() && mStart < end) {
+                throw new IllegalArgumentException(String.format(
+                    "%s at 0x%08X overlaps %s", device.getName(), base, m.device.getName()));
+            }
+        }
+        mappings.add(new Mapping(base, device));
+        return this;
+    }
+
+    /** The device answering an address, or null for RAM. */
+    public Device deviceAt(int address) {
+        Mapping m = find(address);
+        return m == null ? null : m.device;
+    }
+
+    private Mapping find(int address) {
+        for (Mapping m : mappings) {
+            if (m.contains(address)) return m;
+        }
+        return null;
+    }
+
+    @Override
+    public int read(int address) {
+        Mapping m = find(address);
+        if (m == null) return super.read(address);
+        deviceAccesses++;
+        return m.device.read((address - m.base) & ~0x3);
+    }
+
+    @Override
+    public void write(int address, int value) {
+        Mapping m = find(address);
+        if (m == null) {
+            super.write(address, value);
+            return;
+        }
+        deviceAccesses++;
+        m.device.write((address - m.base) & ~0x3, value);
+    }
+
+    /** Bus transactions that went to a device rather than RAM. */
+    public int getDeviceAccesses() {
+        return deviceAccesses;
+    }
+
+    /** The address map, lowest first. */
+    public String describeMap() {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("  0x%08X-0x%08X  RAM (%d KB)\n", 0, getSize() - 1, getSize() / 1024));
+        mappings.stream()
+            .sorted((a, b) -> Integer.compareUnsigned(a.base, b.base))
+            .forEach(m -> sb.append(String.format("  0x%08X-0x%08X  %s\n",
+                m.base, m.base + m.device.getSize() - 1, m.device.getName())));
+        return sb.toString();
+    }
+
+    @Override
+    public String getName() {
+        return "MemoryBus";
+    }
+}
@@ -0,0 +1,348 @@
+package computerdesign.io;
+
+import computerdesign.instruction.Instruction;
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * Plic - a platform-level interrupt controller: many device interrupt
+ * lines in, one machine external interrupt out, with priorities.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHY A CONTROLLER
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * mip has a single MEIP bit for all external devices. ORing the lines
+ * together (what Machine does without a controller) tells the handler that
+ * SOMEONE wants service, not who: it has to poll every device. The PLIC
+ * sits between the devices and the hart and answers that question in one
+ * load:
+ *
+ *   device lines ──▶ gateway ──▶ pending ──▶ enable & priority > threshold
+ *                                                  │
+ *                          claim register ◀────────┴──▶ MEIP
+ *
+ *   1. A device raises its line; the gateway sets the source's PENDING
+ *      bit (unless that source is already being serviced).
+ *   2. MEIP is high while any pending, enabled source has a priority
+ *      above the threshold.
+ *   3. The handler reads CLAIM: it gets the ID of the highest-priority
+ *      such source (ties: the lowest ID), and that bit is cleared.
+ *      0 means nothing to claim.
+ *   4. It services the device (which drops its line), then writes the ID
+ *      back to COMPLETE. Only now can that source be pending again.
+ *
+ * Priority only chooses among interrupts that are waiting at the same
+ * time; with mstatus.MIE cleared in the handler, a high-priority source
+ * that arrives during a low-priority handler still waits for its mret.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * REGISTERS (SiFive / QEMU virt layout, one hart context)
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   offset      name             access
+ *   0x000004*s  PRIORITY[s]      priority of source s (1..31), 0 = never
+ *   0x001000    PENDING          bit s = source s pending (read-only)
+ *   0x002000    ENABLE           bit s = source s may interrupt this hart
+ *   0x200000    THRESHOLD        only priorities above it interrupt
+ *   0x200004    CLAIM/COMPLETE   read: claim, write an ID: complete
+ *
+ * Source 0 does not exist, which is why a claim result of 0 can mean
+ * "none".
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage:
+ *   Plic plic = new Plic();
+ *   plic.connect(1, uart);
+ *   Machine m = new Machine(bus, 0x800).attach(Plic.BASE, plic).attach(UART, uart);
+ *   m.setInterruptController(plic);
+ *
+ * Covers learning goals: T5.2 (interrupts), T6.2 (I/O)
+ */
+public class Plic implements Device, Clocked {
+
+    /** Where QEMU's virt board puts the PLIC. */
+    public static final int BASE = 0x0C00_0000;
+    public static final int SOURCES = 32;
+
+    public static final int PRIORITY = 0x000000;
+    public static final int PENDING = 0x001000;
+    public static final int ENABLE = 0x002000;
+    public static final int THRESHOLD = 0x200000;
+    public static final int CLAIM = 0x200004;
+    public static final int COMPLETE = CLAIM;
+
+    private final Clocked[] lines = new Clocked[SOURCES];
+    private final int[] priority = new int[SOURCES];
+    private int pending;
+    private int enabled;
+    private int inService;
+    private int threshold;
+    private int claims;
+
+    /** Wire `device`'s interrupt line to source `source` (1..31). */
+    public Plic connect(int source, Clocked device) {
+        checkSource(source);
+        lines[source] = device;
+        return this;
+    }
+
+    private static void checkSource(int source) {
+        if (source < 1 || source >= SOURCES) {
+            throw new IllegalArgumentException("Source must be 1.." + (SOURCES - 1) + ", got " + source);
+        }
+    }
+
+    // ==================== BUS SIDE ====================
+
+    @Override
+    public int read(int offset) {
+        if (offset < SOURCES * 4) return priority[offset / 4];
+        switch (offset) {
+            case PENDING:   return pending;
+            case ENABLE:    return enabled;
+            case THRESHOLD: return threshold;
+            case CLAIM:     return claim();
+            default:        return 0;
+        }
+    }
+
+    @Override
+    public void write(int offset, int value) {
+        if (offset < SOURCES * 4) {
+            if (offset != 0) priority[offset / 4] = Math.max(0, value);
+            return;
+        }
+        switch (offset) {
+            case ENABLE:    enabled = value & ~1; break;
+            case THRESHOLD: threshold = Math.max(0, value); break;
+            case COMPLETE:
+                if (value > 0 && value < SOURCES) inService &= ~(1 << value);
+                break;
+            default:
+                break;
+        }
+    }
+
+    @Override
+    public int getSize() {
+        return CLAIM + 4;
+    }
+
+    @Override
+    public String getName() {
+        return "PLIC";
+    }
+
+    /** The source a claim would return now, without claiming it; 0 if none. */
+    public int best() {
+        int best = 0;
+        for (int s = 1; s < SOURCES; s++) {
+            int bit = 1 << s;
+            if ((pending & enabled & bit) == 0 || priority[s] <= threshold) continue;
+            if (best == 0 || priority[s] > priority[best]) best = s;
+        }
+        return best;
+    }
+
+    private int claim() {
+        int s = best();
+        if (s != 0) {
+            pending &= ~(1 << s);
+            inService |= 1 << s;
+            claims++;
+        }
+        return s;
+    }
+
+    // ==================== GATEWAY SIDE ====================
+
+    @Override
+    public boolean tick() {
+        for (int s = 1; s < SOURCES; s++) {
+            int bit = 1 << s;
+            if (lines[s] != null && lines[s].interruptRequest() && (inService & bit) == 0) pending |= bit;
+        }
+        return false;
+    }
+
+    @Override
+    public boolean interruptRequest() {
+        return best() != 0;
+    }
+
+    public int getClaims() {
+        return claims;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static final int BUTTONS = 0x4001_0000;
+    private static final int LOG = 0x3000;
+    private static final int VECTOR = 0x800;
+    private static final int HALT = 0x00100073;
+
+    /**
+     * A push button: its line goes high at the scheduled cycles and stays
+     * high until the handler writes to it.
+     */
+    private static final class Button implements Device, Clocked {
+        private final int[] presses;
+        private int cycle;
+        private boolean line;
+
+        Button(int... presses) {
+            this.presses = presses;
+        }
+
+        @Override
+        public int read(int offset) {
+            return line ? 1 : 0;
+        }
+
+        @Override
+        public void write(int offset, int value) {
+            line = false;
+        }
+
+        @Override
+        public boolean tick() {
+            for (int p : presses) if (p == cycle) line = true;
+            cycle++;
+            return false;
+        }
+
+        @Override
+        public boolean interruptRequest() {
+            return line;
+        }
+
+        @Override
+        public int getSize() {
+            return 4;
+        }
+
+        @Override
+        public String getName() {
+            return "button";
+        }
+    }
+
+    /** Sleep in wfi until the handler has logged `events` claims, then halt. */
+    static int[] waitFor(int events) {
+        return new int[] {
+            Instruction.lui(18, LOG).getRaw(),           // s2 = log pointer
+            Instruction.addi(19, 18, 4 * events).getRaw(), // s3 = end of log
+            Machine.WFI,                                 // sleep: wfi
+            Instruction.bne(18, 19, -4).getRaw(),        //   until the log is full
+            HALT
+        };
+    }
+
+    /**
+     * Claim, log the ID, acknowledge button ID (at BUTTONS + 0x10 * ID),
+     * complete. Uses t3..t6; s2 is the log pointer the main program watches.
+     */
+    static int[] handler() {
+        return new int[] {
+            Instruction.lui(30, BASE + THRESHOLD).getRaw(), // t5 = threshold, claim at +4
+            Instruction.lw(31, 30, 4).getRaw(),          // t6 = claim (highest priority)
+            Instruction.sw(31, 18, 0).getRaw(),          // log it
+            Instruction.addi(18, 18, 4).getRaw(),
+            Instruction.iType(0b0010011, 29, 0b001, 31, 4).getRaw(), // t4 = id << 4
+            Instruction.lui(28, BUTTONS).getRaw(),
+            Instruction.add(29, 29, 28).getRaw(),        // t4 = that button
+            Instruction.sw(0, 29, 0).getRaw(),           // acknowledge: its line drops
+            Instruction.sw(31, 30, 4).getRaw(),          // complete
+            Machine.MRET
+        };
+    }
+
+    /**
+     * Buttons 1..n with the given priorities and press cycles, all enabled,
+     * threshold 0; returns the IDs in the order the handler claimed them.
+     */
+    static List<Integer> run(int[] priorities, int[][] presses) {
+        MemoryBus bus = new MemoryBus();
+        Plic plic = new Plic();
+        Machine m = new Machine(bus, VECTOR).attach(BASE, plic).setInterruptController(plic);
+        int events = 0;
+        for (int i = 0; i < priorities.length; i++) {
+            int id = i + 1;
+            Button b = new Button(presses[i]);
+            m.attach(BUTTONS + 0x10 * id, b);
+            plic.connect(id, b);
+            plic.write(PRIORITY + 4 * id, priorities[i]);
+            events += presses[i].length;
+        }
+        plic.write(ENABLE, -1);
+        bus.loadProgram(waitFor(events), 0);
+        bus.loadProgram(handler(), VECTOR);
+        m.enableInterrupts();
+        m.run(100_000);
+        if (!m.isHalted()) throw new IllegalStateException("Not every interrupt was claimed");
+        List<Integer> order = new ArrayList<>();
+        for (int i = 0; i < events; i++) order.add(bus.read(LOG + 4 * i));
+        return order;
+    }
+
+    public static String demonstratePlic() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Platform-Level Interrupt Controller ===\n\n");
+        MemoryBus map = new MemoryBus();
+        map.map(BASE, new Plic());
+        sb.append(map.describeMap());
+        sb.append("   PRIORITY[s] 0x4*s, PENDING 0x1000, ENABLE 0x2000, THRESHOLD 0x200000, CLAIM/COMPLETE 0x200004\n\n");
+
+        sb.append("1) Register level: sources 1..3 pending, priorities 1, 3, 2\n");
+        Plic plic = new Plic();
+        Button[] buttons = {new Button(0), new Button(0), new Button(0)};
+        for (int s = 1; s <= 3; s++) {
+            plic.connect(s, buttons[s - 1]);
+            buttons[s - 1].tick();
+        }
+        plic.write(PRIORITY + 4, 1);
+        plic.write(PRIORITY + 8, 3);
+        plic.write(PRIORITY + 12, 2);
+        plic.tick();
+        sb.append(String.format("   PENDING = 0x%X, ENABLE = 0x%X: claim -> %d (nothing enabled)\n",
+            plic.read(PENDING), plic.read(ENABLE), plic.read(CLAIM)));
+        plic.write(ENABLE, 0b1010);
+        sb.append(String.format("   ENABLE = 0x%X (sources 1, 3): claim -> %d, then %d, then %d\n",
+            plic.read(ENABLE), plic.read(CLAIM), plic.read(CLAIM), plic.read(CLAIM)));
+        plic.write(ENABLE, 0b1110);
+        plic.write(THRESHOLD, 2);
+        sb.append(String.format("   ENABLE = 0xE, THRESHOLD = 2: claim -> %d (priority 3 only), then %d\n",
+            plic.read(CLAIM), plic.read(CLAIM)));
+        plic.write(THRESHOLD, 0);
+        plic.tick();
+        sb.append(String.format("   THRESHOLD = 0, lines still high: claim -> %d: all three are in service\n",
+            plic.read(CLAIM)));
+        plic.write(COMPLETE, 2);
+        plic.tick();
+        sb.append(String.format("   complete 2, its line still high: it is pending again, claim -> %d\n\n",
+            plic.read(CLAIM)));
+
+        sb.append("2) The handler (one claim per interrupt, no device polling):\n");
+        sb.append("   lui t5, 0x0C200; lw t6, 4(t5)      claim\n");
+        sb.append("   ... service device t6 ...\n");
+        sb.append("   sw t6, 4(t5); mret                 complete\n\n");
+
+        int[] priorities = {1, 3, 2};
+        sb.append("3) Three buttons press in the same cycle (priorities 1, 3, 2):\n");
+        sb.append("   claimed in order " + run(priorities, new int[][] {{50}, {50}, {50}}) + "\n");
+        sb.append("   one interrupt each, highest priority first; after every mret the\n");
+        sb.append("   PLIC still has a source pending, so the next trap follows at once.\n\n");
+
+        sb.append("4) Button 1 (priority 1) at cycle 50, button 2 (priority 3) at cycle 52:\n");
+        sb.append("   claimed in order " + run(priorities, new int[][] {{50}, {52}, {}}) + "\n");
+        sb.append("   button 2 arrived while button 1's handler ran with MIE off: priority\n");
+        sb.append("   orders waiting interrupts, it does not preempt a running handler.\n");
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        System.out.println(demonstratePlic());
+    }
+}
@@ -0,0 +1,355 @@
+package computerdesign.io;
+
+import java.util.ArrayDeque;
+import java.util.Random;
+
+/**
+ * PollingVsInterrupts - a device that produces data at a fixed (or random)
+ * rate, serviced by a CPU that either polls it or takes an interrupt.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * TWO WAYS TO NOTICE A DEVICE
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * The device puts each new word in a small FIFO and sets a READY bit in its
+ * status register. If the FIFO is full when the next word arrives, that
+ * word is LOST.
+ *
+ *   POLLING: the program reads the status register now and then.
+ *
+ *     loop: lw   t0, STATUS       ← costs a bus transaction every time,
+ *           beqz t0, loop           whether there is data or not
+ *           lw   t1, DATA
+ *
+ *     Poll too often and the CPU wastes its cycles on empty checks; poll too
+ *     rarely and words wait long (latency) or overflow the FIFO (loss).
+ *
+ *   INTERRUPTS: READY raises an interrupt line. The CPU does useful work
+ *   until the line goes high, then saves its state, runs a handler that
+ *   drains the FIFO, restores and returns:
+ *
+ *     useful work ... │ entry │ check read check read check │ exit │ useful work
+ *                     ↑ save registers, vector      mret, restore ↑
+ *
+ *     The only empty check is the handler's last one, and the latency is
+ *     about the entry time - but every interrupt pays entry + exit, which
+ *     hurts when data arrives fast.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE MODEL
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Cycle by cycle, the CPU is in one state:
+ *
+ *   WORK     useful work; polling starts a CHECK every pollInterval cycles,
+ *            interrupts start ENTRY as soon as the FIFO is not empty
+ *   CHECK    status register read (checkCost); data → READ, none → WORK/EXIT
+ *   READ     data register read (transferCost), then CHECK again
+ *   ENTRY    interrupt entry (entryCost), then CHECK
+ *   EXIT     interrupt return (exitCost), then WORK
+ *
+ * Latency is from the word's arrival to the end of its READ.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: PollingVsInterrupts                               worked example
+ *        PollingVsInterrupts [--period N] [--random] [--fifo N] [--poll N]
+ *                            [--check N] [--transfer N] [--entry N] [--exit N]
+ *                            [--cycles N]
+ *
+ *   --period is the mean number of cycles between words; --random draws the
+ *   gaps from an exponential distribution instead of a fixed period.
+ *   --poll is the polling interval (0 = busy-wait).
+ *
+ * Covers learning goals: T5.2 (interrupts), T6.2 (I/O)
+ */
+public final class PollingVsInterrupts {
+
+    private PollingVsInterrupts() {}
+
+    /** The device and the cost of each CPU action, in cycles. */
+    public static final class Config {
+        public final int period;
+        public final boolean random;
+        public final int fifoDepth;
+        public final int checkCost;
+        public final int transferCost;
+        public final int entryCost;
+        public final int exitCost;
+        public final int cycles;
+
+        public Config(int period, boolean random, int fifoDepth, int checkCost,
+                      int transferCost, int entryCost, int exitCost, int cycles) {
+            if (period < 1 || fifoDepth < 1 || cycles < 1 || checkCost < 1 || transferCost < 1
+                || entryCost < 0 || exitCost < 0) {
+                throw new IllegalArgumentException("Period, FIFO depth, cycles and check/transfer costs must be"
+                    + " positive, entry/exit costs not negative");
+            }
+            this.period = period;
+            this.random = random;
+            this.fifoDepth = fifoDepth;
+            this.checkCost = checkCost;
+            this.transferCost = transferCost;
+            this.entryCost = entryCost;
+            this.exitCost = exitCost;
+            this.cycles = cycles;
+        }
+
+        /** The same costs with another device. */
+        public Config withDevice(int period, boolean random) {
+            return new Config(period, random, fifoDepth, checkCost, transferCost, entryCost, exitCost, cycles);
+        }
+    }
+
+    /**
+     * One word every 1000 cycles, 4-entry FIFO; a status or data read is an
+     * uncached bus transaction (5 and 10 cycles), and entry and exit save and
+     * restore the caller-saved registers (40 and 20 cycles).
+     */
+    public static final Config DEFAULT = new Config(1000, false, 4, 5, 10, 40, 20, 1_000_000);
+
+    /** Where one strategy's cycles went, and what happened to the data. */
+    public static final class Result {
+        public final String strategy;
+        public final int cycles;
+        public final int usefulCycles;
+        /** Status reads that found the FIFO empty. */
+        public final int wastedCycles;
+        public final int checkCycles;
+        public final int transferCycles;
+        public final int interruptCycles;
+        public final int produced;
+        public final int delivered;
+        public final int lost;
+        public final int interrupts;
+        public final long totalLatency;
+        public final int maxLatency;
+
+        Result(String strategy, int cycles, int useful, int wasted, int check, int transfer, int interrupt,
+               int produced, int delivered, int lost, int interrupts, long totalLatency, int maxLatency) {
+            this.strategy = strategy;
+            this.cycles = cycles;
+            this.usefulCycles = useful;
+            this.wastedCycles = wasted;
+            this.checkCycles = check;
+            this.transferCycles = transfer;
+            this.interruptCycles = interrupt;
+            this.produced = produced;
+            this.delivered = delivered;
+            this.lost = lost;
+            this.interrupts = interrupts;
+            this.totalLatency = totalLatency;
+            this.maxLatency = maxLatency;
+        }
+
+        public double usefulFraction() {
+            return (double) usefulCycles / cycles;
+        }
+
+        public double meanLatency() {
+            return delivered == 0 ? 0 : (double) totalLatency / delivered;
+        }
+
+        /** I/O cycles (everything but useful work) per delivered word. */
+        public double overheadPerWord() {
+            return delivered == 0 ? 0 : (double) (cycles - usefulCycles) / delivered;
+        }
+    }
+
+    // ==================== THE SIMULATION ====================
+
+    private enum State { WORK, CHECK, READ, ENTRY, EXIT }
+
+    /** Polling every `pollInterval` cycles (0 = busy-wait: check again at once). */
+    public static Result polling(Config c, int pollInterval) {
+        if (pollInterval < 0) throw new IllegalArgumentException("Poll interval must not be negative");
+        String name = pollInterval == 0 ? "busy-wait polling" : "poll every " + pollInterval;
+        return simulate(c, false, pollInterval, name);
+    }
+
+    public static Result interrupts(Config c) {
+        return simulate(c, true, 0, "interrupts");
+    }
+
+    private static Result simulate(Config c, boolean interrupts, int pollInterval, String name) {
+        Random random = new Random(4160);
+        ArrayDeque<Integer> fifo = new ArrayDeque<>();       // arrival cycle of each waiting word
+        int nextArrival = gap(c, random);
+        int nextPoll = 0;
+        State state = State.WORK;
+        int until = 0;
+        int useful = 0, wasted = 0, check = 0, transfer = 0, interrupt = 0;
+        int produced = 0, delivered = 0, lost = 0, irqs = 0, maxLatency = 0;
+        long totalLatency = 0;
+
+        for (int t = 0; t < c.cycles; t++) {
+            // the device side
+            if (t == nextArrival) {
+                produced++;
+                if (fifo.size() < c.fifoDepth) fifo.add(t);
+                else lost++;
+                nextArrival = t + gap(c, random);
+            }
+
+            // the CPU side: finish the current action, maybe start the next
+            if (state != State.WORK && t == until) {
+                switch (state) {
+                    case READ:
+                        int latency = t - fifo.remove();
+                        totalLatency += latency;
+                        maxLatency = Math.max(maxLatency, latency);
+                        delivered++;
+                        state = State.CHECK;
+                        until = t + c.checkCost;
+                        break;
+                    case ENTRY:
+                        state = State.CHECK;
+                        until = t + c.checkCost;
+                        break;
+                    case CHECK:
+                        if (!fifo.isEmpty()) {
+                            state = State.READ;
+                            until = t + c.transferCost;
+                        } else {
+                            wasted += c.checkCost;
+                            state = interrupts && c.exitCost > 0 ? State.EXIT : State.WORK;
+                            until = t + c.exitCost;
+                        }
+                        break;
+                    default:                                 // EXIT
+                        state = State.WORK;
+                        break;
+                }
+            }
+            if (state == State.WORK) {
+                if (interrupts && !fifo.isEmpty()) {
+                    irqs++;
+                    state = c.entryCost > 0 ? State.ENTRY : State.CHECK;
+                    until = t + (c.entryCost > 0 ? c.entryCost : c.checkCost);
+                } else if (!interrupts && t >= nextPoll) {
+                    state = State.CHECK;
+                    until = t + c.checkCost;
+                    nextPoll = t + pollInterval;
+                }
+            }
+
+            switch (state) {
+                case WORK:  useful++; break;
+                case CHECK: check++; break;
+                case READ:  transfer++; break;
+                default:    interrupt++; break;
+            }
+        }
+        // an empty check still in progress at the end is not counted as wasted
+        return new Result(name, c.cycles, useful, wasted, check, transfer, interrupt,
+            produced, delivered, lost, irqs, totalLatency, maxLatency);
+    }
+
+    /** Cycles until the next word: fixed, or exponential with the same mean. */
+    private static int gap(Config c, Random random) {
+        if (!c.random) return c.period;
+        return 1 + (int) (-Math.log(1 - random.nextDouble()) * (c.period - 1));
+    }
+
+    // ==================== REPORTING ====================
+
+    public static String table(Result... results) {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("   %-19s %7s %9s %9s %10s %6s %9s %8s\n",
+            "strategy", "useful", "wasted", "irq", "cyc/word", "lost", "mean lat", "max lat"));
+        for (Result r : results) {
+            sb.append(String.format("   %-19s %6.1f%% %9d %9d %10.1f %6d %9.0f %8d\n",
+                r.strategy, 100 * r.usefulFraction(), r.wastedCycles, r.interruptCycles,
+                r.overheadPerWord(), r.lost, r.meanLatency(), r.maxLatency));
+        }
+        return sb.toString();
+    }
+
+    private static String describe(Config c) {
+        return String.format("%s word every %d cycles, %d-entry FIFO; check %d, read %d, entry %d, exit %d cycles;"
+            + " %,d cycles simulated\n", c.random ? "on average one" : "one", c.period, c.fifoDepth,
+            c.checkCost, c.transferCost, c.entryCost, c.exitCost, c.cycles);
+    }
+
+    public static String demonstratePollingVsInterrupts() {
+        StringBuilder sb = new StringBuilder();
+        Config c = DEFAULT;
+        sb.append("=== Polling vs Interrupt-Driven I/O ===\n\n");
+        sb.append("Device: ").append(describe(c)).append('\n');
+        sb.append("   useful   = cycles left for the program\n");
+        sb.append("   wasted   = status reads that found nothing\n");
+        sb.append("   irq      = interrupt entry and exit\n");
+        sb.append("   cyc/word = all I/O cycles per delivered word\n");
+        sb.append("   latency  = cycles from arrival to the end of the data read\n\n");
+
+        sb.append("1) Choosing the polling interval:\n");
+        sb.append(table(polling(c, 0), polling(c, 100), polling(c, 1000), polling(c, 3000),
+            polling(c, 5000), interrupts(c)));
+        sb.append("   Busy-waiting wastes the whole CPU for the lowest latency. Polling\n");
+        sb.append("   rarely frees the CPU but words wait, and beyond FIFO depth x period\n");
+        sb.append("   (4000 cycles here) they overflow. Polling exactly once per period\n");
+        sb.append("   looks best, but only because this device is perfectly regular.\n");
+        sb.append("   Interrupts need no interval: fixed low latency, for entry + exit\n");
+        sb.append("   (and the handler's last, empty check) on every word.\n\n");
+
+        sb.append("2) The same with random arrivals (same mean rate):\n");
+        Config bursty = c.withDevice(c.period, true);
+        sb.append(table(polling(bursty, 1000), polling(bursty, 3000), interrupts(bursty)));
+        sb.append("   Bursts fill the FIFO between polls: the interval that was perfect for\n");
+        sb.append("   the regular device now loses data and has 30x the latency.\n");
+        sb.append("   Interrupts react to each burst, and a burst shares one entry + exit.\n\n");
+
+        sb.append("3) Faster devices - polling every 100 cycles vs interrupts:\n");
+        sb.append(String.format("   %-10s %-19s %7s %10s %6s %9s\n", "period", "strategy", "useful", "cyc/word",
+            "lost", "mean lat"));
+        for (int period : new int[] {10_000, 1000, 200, 100, 50}) {
+            Config d = c.withDevice(period, false);
+            for (Result r : new Result[] {polling(d, 100), interrupts(d)}) {
+                sb.append(String.format("   %-10d %-19s %6.1f%% %10.1f %6d %9.0f\n", period, r.strategy,
+                    100 * r.usefulFraction(), r.overheadPerWord(), r.lost, r.meanLatency()));
+            }
+        }
+        sb.append("   A slow device makes polling pay for thousands of empty checks per word.\n");
+        sb.append("   A fast one gives every poll data to read, while each interrupt still\n");
+        sb.append("   pays entry + exit: that is where polling (or DMA) wins.\n");
+        return sb.toString();
+    }
+
+    // ==================== COMMAND LINE ====================
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstratePollingVsInterrupts());
+            return;
+        }
+        Config d = DEFAULT;
+        int period = d.period, fifo = d.fifoDepth, poll = 1000, check = d.checkCost, transfer = d.transferCost;
+        int entry = d.entryCost, exit = d.exitCost, cycles = d.cycles;
+        boolean random = false;
+        try {
+            for (int i = 0; i < args.length; i++) {
+                switch (args[i]) {
+                    case "--period":   period = Integer.parseInt(args[++i]); break;
+                    case "--random":   random = true; break;
+                    case "--fifo":     fifo = Integer.parseInt(args[++i]); break;
+                    case "--poll":     poll = Integer.parseInt(args[++i]); break;
+                    case "--check":    check = Integer.parseInt(args[++i]); break;
+                    case "--transfer": transfer = Integer.parseInt(args[++i]); break;
+                    case "--entry":    entry = Integer.parseInt(args[++i]); break;
+                    case "--exit":     exit = Integer.parseInt(args[++i]); break;
+                    case "--cycles":   cycles = Integer.parseInt(args[++i]); break;
+                    default: throw new IllegalArgumentException("Unknown option: " + args[i]);
+                }
+            }
+            Config c = new Config(period, random, fifo, check, transfer, entry, exit, cycles);
+            System.out.print("Device: " + describe(c));
+            System.out.print(table(polling(c, 0), polling(c, poll), interrupts(c)));
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.err.println("Missing value after " + args[args.length - 1]);
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+package computerdesign.io;
+
+import computerdesign.logic.SignalTrace;
+import computerdesign.logic.TimingDiagram;
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * Spi - the Serial Peripheral Interface at the bit level: a master and a
+ * slave clocking bytes through each other's shift registers.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE WIRES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   CS    chip select, active low: picks the one slave that may talk
+ *   SCLK  clock, always driven by the master
+ *   MOSI  master out, slave in
+ *   MISO  master in, slave out
+ *
+ * Every clock the master shifts one bit out on MOSI while the slave shifts
+ * one out on MISO: SPI is full duplex, a read is a write of dummy bytes.
+ * Bytes go MSB first. There is no addressing, no acknowledge and no way
+ * for a slave to make the master wait.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE FOUR MODES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   mode  CPOL CPHA  clock idles  data sampled on   data changes on
+ *   0     0    0     low          rising (leading)  falling
+ *   1     0    1     low          falling           rising (leading)
+ *   2     1    0     high         falling (leading) rising
+ *   3     1    1     high         rising            falling (leading)
+ *
+ * With CPHA = 0 the first bit must be on the wire before the first edge,
+ * so it goes out as soon as CS falls. Both ends must use the same mode:
+ * otherwise one samples when the other changes the data, and bits come
+ * out shifted by one.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: Spi                    worked example with a TemperatureSensor
+ *
+ * Covers learning goals: T6.2 (I/O, serial buses)
+ *
+ * @see I2c
+ * @see TemperatureSensor
+ */
+public final class Spi {
+
+    private Spi() {}
+
+    /** The device end of the bus, a byte at a time. */
+    public interface Slave {
+        /** CS went low: a transaction starts. */
+        default void select() {}
+
+        /** The next byte to shift out. */
+        int load();
+
+        /** A whole byte has been shifted in. */
+        void received(int value);
+
+        /** One bus step passes. */
+        default void tick() {}
+    }
+
+    public static boolean cpol(int mode) {
+        return (mode & 2) != 0;
+    }
+
+    public static boolean cpha(int mode) {
+        return (mode & 1) != 0;
+    }
+
+    private static void checkMode(int mode) {
+        if (mode < 0 || mode > 3) throw new IllegalArgumentException("SPI mode must be 0..3, got " + mode);
+    }
+
+    /** One end's shift register, following its own mode's edges. */
+    private static final class Shifter {
+        private final boolean cpha;
+        private final boolean sampleOnRising;
+        private final Slave bytes;
+        private int out;
+        private int outBit;
+        private int in;
+        private int inCount;
+        boolean driven;
+        boolean level;
+        boolean sampled;
+
+        Shifter(int mode, Slave bytes) {
+            this.cpha = cpha(mode);
+            this.sampleOnRising = cpol(mode) == cpha(mode);
+            this.bytes = bytes;
+        }
+
+        void select() {
+            outBit = -1;
+            in = 0;
+            inCount = 0;
+            bytes.select();
+            if (!cpha) shift();
+        }
+
+        void edge(boolean rising, boolean line) {
+            sampled = rising == sampleOnRising;
+            if (!sampled) {
+                shift();
+                return;
+            }
+            in = in << 1 | (line ? 1 : 0);
+            if (++inCount == 8) {
+                bytes.received(in & 0xFF);
+                in = 0;
+                inCount = 0;
+            }
+        }
+
+        private void shift() {
+            if (outBit < 0) {
+                out = bytes.load();
+                outBit = 7;
+            }
+            level = (out >> outBit-- & 1) != 0;
+            driven = true;
+        }
+    }
+
+    public static final class Result {
+        public final int masterMode;
+        public final int slaveMode;
+        /** Bytes the master clocked in from MISO. */
+        public final int[] received;
+        public final SignalTrace trace;
+
+        Result(int masterMode, int slaveMode, int[] received, SignalTrace trace) {
+            this.masterMode = masterMode;
+            this.slaveMode = slaveMode;
+            this.received = received;
+            this.trace = trace;
+        }
+
+        @Override
+        public String toString() {
+            StringBuilder sb = new StringBuilder("[");
+            for (int i = 0; i < received.length; i++) sb.append(i == 0 ? "" : ", ").append(String.format("0x%02X", received[i]));
+            return sb.append(']').toString();
+        }
+    }
+
+    /**
+     * Select `slave`, clock `mosi` out in `masterMode` while the slave uses
+     * `slaveMode`, then deselect. Steps are half SCLK periods.
+     */
+    public static Result transfer(int masterMode, int slaveMode, Slave slave, int... mosi) {
+        checkMode(masterMode);
+        checkMode(slaveMode);
+        List<Integer> in = new ArrayList<>();
+        Shifter master = new Shifter(masterMode, new Slave() {
+            private int next;
+
+            @Override
+            public int load() {
+                return next < mosi.length ? mosi[next++] & 0xFF : 0;
+            }
+
+            @Override
+            public void received(int value) {
+                in.add(value);
+            }
+        });
+        Shifter device = new Shifter(slaveMode, slave);
+        SignalTrace trace = new SignalTrace("CS", "SCLK", "MOSI", "MISO", "sample");
+        boolean idle = cpol(masterMode);
+        boolean clock = idle;
+
+        record(trace, true, clock, master, device);
+        master.select();
+        device.select();
+        record(trace, false, clock, master, device);
+        slave.tick();
+        for (int bit = 0; bit <= 8 * mosi.length; bit++) {
+            boolean[] halves = bit == 8 * mosi.length ? new boolean[] {idle, idle}
+                : cpha(masterMode) ? new boolean[] {!idle, idle} : new boolean[] {idle, !idle};
+            for (boolean level : halves) {
+                if (level != clock) {
+                    boolean mosiLine = !master.driven || master.level;   // undriven lines float high
+                    boolean misoLine = !device.driven || device.level;
+                    master.edge(level, misoLine);
+                    device.edge(level, mosiLine);
+                } else {
+                    master.sampled = false;
+                }
+                clock = level;
+                record(trace, false, clock, master, device);
+                slave.tick();
+            }
+        }
+        record(trace, true, clock, master, device);
+        int[] received = new int[in.size()];
+        for (int i = 0; i < received.length; i++) received[i] = in.get(i);
+        return new Result(masterMode, slaveMode, received, trace);
+    }
+
+    /** Master and slave in the same mode. */
+    public static Result transfer(int mode, Slave slave, int... mosi) {
+        return transfer(mode, mode, slave, mosi);
+    }
+
+    private static void record(SignalTrace trace, boolean cs, boolean clock, Shifter master, Shifter device) {
+        trace.record("CS", cs);
+        trace.record("SCLK", clock);
+        if (master.driven && !cs) trace.record("MOSI", master.level);
+        else trace.recordUnknown("MOSI");
+        if (device.driven && !cs) trace.record("MISO", device.level);
+        else trace.recordUnknown("MISO");
+        trace.record("sample", master.sampled && !cs);
+    }
+
+    /** ↑ marks where the master samples MISO; the axis counts SCLK periods. */
+    public static String diagram(Result r) {
+        return new TimingDiagram(r.trace).clock("sample").cellWidth(2).stepsPerUnit(2).toAscii();
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    /** The sensor's read command for `reg`. */
+    static int readCommand(int reg) {
+        return 0x80 | reg;
+    }
+
+    public static String demonstrateSpi() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== SPI: Four Wires, Four Modes ===\n\n");
+        TemperatureSensor sensor = new TemperatureSensor(21.5, 100);
+        Spi.Slave slave = sensor.spi();
+
+        sb.append("1) Read WHO_AM_I in mode 0: command 0x8F (bit 7 = read), then a dummy\n");
+        sb.append("   byte while the sensor answers. X = line not driven.\n\n");
+        Result r = transfer(0, slave, readCommand(TemperatureSensor.WHO_AM_I), 0);
+        sb.append(diagram(r)).append('\n');
+        sb.append("   MISO bytes: " + r + " - the first is clocked in while the sensor is\n");
+        sb.append("   still receiving the command, so it means nothing.\n\n");
+
+        sb.append("2) The same read in mode 3: the clock idles high, and the first bit only\n");
+        sb.append("   appears at the first (falling) edge.\n\n");
+        r = transfer(3, slave, readCommand(TemperatureSensor.WHO_AM_I), 0);
+        sb.append(diagram(r)).append('\n');
+        sb.append("   MISO bytes: " + r + "\n\n");
+
+        sb.append("3) Reading WHO_AM_I (0x75) with mismatched modes:\n");
+        sb.append("                slave mode 0   1     2     3\n");
+        for (int m = 0; m < 4; m++) {
+            sb.append(String.format("   master mode %d ", m));
+            for (int s = 0; s < 4; s++) {
+                Result x = transfer(m, s, slave, readCommand(TemperatureSensor.WHO_AM_I), 0);
+                sb.append(String.format("  0x%02X", x.received[1]));
+            }
+            sb.append('\n');
+        }
+        sb.append("   Only the diagonal works. Modes 0 and 3 sample on the same edge, yet a\n");
+        sb.append("   mode-3 slave puts each bit out one edge later than a mode-0 master\n");
+        sb.append("   expects: 0x75 arrives shifted right, as 0x3A. The other cells fail\n");
+        sb.append("   outright: the two ends disagree on which edge starts a byte.\n\n");
+
+        sb.append("4) No clock stretching: start a conversion, read TEMP at once\n");
+        sensor.setTemperature(23.75);
+        transfer(0, slave, TemperatureSensor.CONFIG, TemperatureSensor.OS);
+        r = transfer(0, slave, readCommand(TemperatureSensor.TEMP_MSB), 0, 0);
+        sb.append(String.format("   TEMP right away: %s = %.4f °C (the old value)\n", r,
+            TemperatureSensor.celsius(r.received[1], r.received[2])));
+        int polls = 0;
+        do {
+            r = transfer(0, slave, readCommand(TemperatureSensor.CONFIG), 0);
+            polls++;
+        } while ((r.received[1] & TemperatureSensor.OS) != 0);
+        r = transfer(0, slave, readCommand(TemperatureSensor.TEMP_MSB), 0, 0);
+        sb.append(String.format("   polled CONFIG.OS %d times, then TEMP: %s = %.4f °C\n", polls, r,
+            TemperatureSensor.celsius(r.received[1], r.received[2])));
+        sb.append("   The slave cannot hold the clock, so software has to wait for it.\n");
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        System.out.println(demonstrateSpi());
+    }
+}
@@ -0,0 +1,221 @@
+package computerdesign.io;
+
+/**
+ * TemperatureSensor - a small digital temperature sensor with 8-bit
+ * registers, reachable over SPI or I2C (like the TMP102/LM75 family).
+ *
+ *   reg   name       access
+ *   0x00  TEMP_MSB   whole degrees C, two's complement (read-only)
+ *   0x01  TEMP_LSB   bits 7..4: sixteenths of a degree (read-only)
+ *   0x02  CONFIG     bit 7 OS: write 1 to start a conversion ("one
+ *                    shot"), reads 1 while it runs
+ *   0x03  T_HIGH     alert threshold, whole degrees
+ *   0x04  STATUS     bit 0 ALERT: the last conversion was above T_HIGH
+ *   0x0F  WHO_AM_I   0x75 (read-only)
+ *
+ * Multi-byte accesses auto-increment the register pointer, so TEMP_MSB
+ * and TEMP_LSB come out in one transaction.
+ *
+ * TEMP only changes when a CONVERSION finishes, `conversionTime` bus
+ * steps after it starts: reading TEMP meanwhile gives the old value. An
+ * I2C target can hold SCL low (clock stretching) until the value is
+ * ready; SPI has no way to make the master wait, so SPI software polls
+ * CONFIG.OS instead.
+ *
+ * @see Spi
+ * @see I2c
+ */
+public class TemperatureSensor {
+
+    public static final int TEMP_MSB = 0x00;
+    public static final int TEMP_LSB = 0x01;
+    public static final int CONFIG = 0x02;
+    public static final int T_HIGH = 0x03;
+    public static final int STATUS = 0x04;
+    public static final int WHO_AM_I = 0x0F;
+
+    public static final int OS = 1 << 7;
+    public static final int ALERT = 1;
+    public static final int ID = 0x75;
+    /** The usual I2C address of this family (A0 tied low). */
+    public static final int I2C_ADDRESS = 0x48;
+
+    private final int conversionTime;
+    private double temperature;
+    private int sixteenths;                  // the converted value
+    private int tHigh = 80;
+    private boolean alert;
+    private int converting;
+    private int pointer;
+    private boolean expectPointer;
+
+    /** A sensor whose conversions take `conversionTime` bus steps; it starts converted. */
+    public TemperatureSensor(double temperature, int conversionTime) {
+        if (conversionTime < 0) throw new IllegalArgumentException("Conversion time must not be negative");
+        this.conversionTime = conversionTime;
+        setTemperature(temperature);
+        finishConversion();
+    }
+
+    /** The physical temperature; the registers follow at the next conversion. */
+    public void setTemperature(double celsius) {
+        if (celsius < -128 || celsius >= 128) throw new IllegalArgumentException("Out of range: " + celsius);
+        this.temperature = celsius;
+    }
+
+    /** The converted value in °C, as the registers hold it. */
+    public double getReading() {
+        return sixteenths / 16.0;
+    }
+
+    /** Decode TEMP_MSB and TEMP_LSB into °C. */
+    public static double celsius(int msb, int lsb) {
+        return (((byte) msb << 4) | (lsb >> 4 & 0xF)) / 16.0;
+    }
+
+    private void finishConversion() {
+        sixteenths = (int) Math.floor(temperature * 16);
+        alert = sixteenths >= tHigh * 16;
+        converting = 0;
+    }
+
+    /** One bus step passes. */
+    public void tick() {
+        if (converting > 0 && --converting == 0) finishConversion();
+    }
+
+    public boolean isConverting() {
+        return converting > 0;
+    }
+
+    /** Bus steps left of the running conversion. */
+    int conversionLeft() {
+        return converting;
+    }
+
+    // ==================== REGISTERS ====================
+
+    public int read(int reg) {
+        switch (reg) {
+            case TEMP_MSB: return sixteenths >> 4 & 0xFF;
+            case TEMP_LSB: return (sixteenths & 0xF) << 4;
+            case CONFIG:   return converting > 0 ? OS : 0;
+            case T_HIGH:   return tHigh & 0xFF;
+            case STATUS:   return alert ? ALERT : 0;
+            case WHO_AM_I: return ID;
+            default:       return 0;
+        }
+    }
+
+    public void write(int reg, int value) {
+        switch (reg) {
+            case CONFIG:
+                if ((value & OS) != 0 && converting == 0) {
+                    converting = conversionTime;
+                    if (converting == 0) finishConversion();
+                }
+                break;
+            case T_HIGH:
+                tHigh = (byte) value;
+                break;
+            default:
+                break;                       // read-only or unused
+        }
+    }
+
+    // ==================== BUS FRONT ENDS ====================
+
+    /** A transaction starts: the next byte written sets the pointer. */
+    void begin() {
+        expectPointer = true;
+    }
+
+    /** A byte from the master: the pointer first, then data. */
+    void receive(int value) {
+        if (expectPointer) pointer = value & 0x7F;
+        else write(pointer++, value);
+        expectPointer = false;
+    }
+
+    /** The next byte for the master, from the pointer (which stays where the last access left it). */
+    int transmit() {
+        return read(pointer++);
+    }
+
+    /**
+     * As an SPI slave: the first byte is a command, bit 7 = read, bits 5..0
+     * the register; then data bytes follow (written, or read out).
+     */
+    public Spi.Slave spi() {
+        return new Spi.Slave() {
+            private int command = -1;
+
+            @Override
+            public void select() {
+                command = -1;
+                begin();
+            }
+
+            @Override
+            public int load() {
+                return command >= 0 && (command & 0x80) != 0 ? transmit() : 0;
+            }
+
+            @Override
+            public void received(int value) {
+                if (command < 0) {
+                    command = value;
+                    receive(value & 0x3F);
+                } else if ((command & 0x80) == 0) {
+                    receive(value);
+                }
+            }
+
+            @Override
+            public void tick() {
+                TemperatureSensor.this.tick();
+            }
+        };
+    }
+
+    /** As an I2C target at `address`: write the pointer, then data or a read. */
+    public I2c.Target i2c(int address) {
+        return new I2c.Target() {
+            private int pointerAtRead;
+
+            @Override
+            public int address() {
+                return address;
+            }
+
+            @Override
+            public void start(boolean read) {
+                if (read) pointerAtRead = pointer;
+                else begin();
+            }
+
+            @Override
+            public boolean write(int value) {
+                receive(value);
+                return true;
+            }
+
+            @Override
+            public int read() {
+                return transmit();
+            }
+
+            @Override
+            public int stretch() {
+                boolean temp = pointerAtRead == TEMP_MSB || pointerAtRead == TEMP_LSB;
+                pointerAtRead = -1;
+                return temp ? conversionLeft() : 0;
+            }
+
+            @Override
+            public void tick() {
+                TemperatureSensor.this.tick();
+            }
+        };
+    }
+}
@@ -0,0 +1,412 @@
+package computerdesign.io;
+
+import computerdesign.logic.SignalTrace;
+import computerdesign.logic.TimingDiagram;
+import java.nio.charset.StandardCharsets;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.List;
+
+/**
+ * UartFraming - how a UART turns a byte into a waveform on one wire, and
+ * how the receiver turns it back - or fails to.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE FRAME
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * There is no clock wire: both sides agree on a baud rate (bit time =
+ * 1 / baud) and a format such as 8N1 (8 data bits, no parity, 1 stop bit).
+ *
+ *   idle  start  D0  D1  D2  D3  D4  D5  D6  D7  parity  stop   idle
+ *   ‾‾‾‾‾‾\_____/‾‾‾\___ ... data, LSB first ... _______/‾‾‾‾‾‾‾‾‾‾‾‾
+ *          0                                      (E/O)   1
+ *
+ *   start bit   the 1 → 0 edge tells the receiver a frame begins
+ *   parity      even (E): the 1s in data + parity are even; odd (O): odd
+ *   stop bits   1 (or 2): the line returns to idle, so the next start bit
+ *               is an edge again
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE RECEIVER
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * It watches the line at 16× its own baud rate. On a falling edge it waits
+ * half a bit, checks the start bit is still 0 (else: a glitch), then
+ * samples once per bit time - in the middle of each bit, if both clocks
+ * agree.
+ *
+ *   parity error    the parity bit does not match the data (a flipped bit;
+ *                   two flips cancel and pass unnoticed)
+ *   framing error   a stop bit reads 0: no idle where one must be - a baud
+ *                   rate or format mismatch, or a BREAK (line held low)
+ *
+ * With a baud mismatch the sample points drift by (r - 1) bit times per
+ * bit. The last stop bit is sampled (F - ½) bits after the edge for an
+ * F-bit frame, so it stays inside its bit only while
+ *
+ *   |r - 1| < 1 / (2F - 1)       8N1: F = 10, about ±5%
+ *
+ * less the 1/16 bit of uncertainty in finding the edge. Beyond it, bits
+ * are read from the wrong place: wrong data, parity and framing errors.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: UartFraming                                      worked examples
+ *        UartFraming [--format 8E1] [--mismatch pct] [text] send text (default "A"),
+ *                                                         receive it with a clock
+ *                                                         pct percent fast (or slow)
+ *
+ * Covers learning goals: T6.2 (I/O, serial communication)
+ *
+ * @see computerdesign.logic.TimingDiagram
+ */
+public final class UartFraming {
+
+    private UartFraming() {}
+
+    public enum Parity {
+        NONE, EVEN, ODD
+    }
+
+    /** Data bits, parity and stop bits, written like "8N1". */
+    public static final class Format {
+        public final int dataBits;
+        public final Parity parity;
+        public final int stopBits;
+
+        public Format(int dataBits, Parity parity, int stopBits) {
+            if (dataBits < 5 || dataBits > 8) throw new IllegalArgumentException("Data bits must be 5..8, got " + dataBits);
+            if (stopBits < 1 || stopBits > 2) throw new IllegalArgumentException("Stop bits must be 1 or 2, got " + stopBits);
+            this.dataBits = dataBits;
+            this.parity = parity;
+            this.stopBits = stopBits;
+        }
+
+        /** "8N1", "7E1", "8O2", ... */
+        public static Format parse(String text) {
+            if (text.length() != 3) throw new IllegalArgumentException("Expected a format like 8N1, got " + text);
+            Parity p;
+            switch (Character.toUpperCase(text.charAt(1))) {
+                case 'N': p = Parity.NONE; break;
+                case 'E': p = Parity.EVEN; break;
+                case 'O': p = Parity.ODD; break;
+                default: throw new IllegalArgumentException("Parity must be N, E or O: " + text);
+            }
+            return new Format(text.charAt(0) - '0', p, text.charAt(2) - '0');
+        }
+
+        public int frameBits() {
+            return 1 + dataBits + (parity == Parity.NONE ? 0 : 1) + stopBits;
+        }
+
+        @Override
+        public String toString() {
+            return "" + dataBits + parity.name().charAt(0) + stopBits;
+        }
+    }
+
+    public static final Format FORMAT_8N1 = new Format(8, Parity.NONE, 1);
+
+    /** The parity bit for `value`, or false with no parity. */
+    static boolean parityBit(int value, Format f) {
+        boolean odd = Integer.bitCount(value & ((1 << f.dataBits) - 1)) % 2 == 1;
+        switch (f.parity) {
+            case EVEN: return odd;
+            case ODD:  return !odd;
+            default:   return false;
+        }
+    }
+
+    // ==================== TRANSMITTER ====================
+
+    /** The line, one level per bit time, and what each bit time carries. */
+    public static final class Line {
+        public final boolean[] levels;
+        /** "-" idle, "St" start, "D0".. data, "P" parity, "Sp" stop. */
+        public final String[] fields;
+
+        Line(boolean[] levels, String[] fields) {
+            this.levels = levels;
+            this.fields = fields;
+        }
+
+        /**
+         * `idle` idle bits, the frames back to back (the fastest a
+         * transmitter can send), then `idle` idle bits.
+         */
+        public static Line of(byte[] data, Format f, int idle) {
+            int n = 2 * idle + data.length * f.frameBits();
+            boolean[] levels = new boolean[n];
+            String[] fields = new String[n];
+            Arrays.fill(levels, true);
+            Arrays.fill(fields, "-");
+            int t = idle;
+            for (byte b : data) {
+                int value = b & 0xFF;
+                fields[t++] = "St";
+                levels[t - 1] = false;
+                for (int i = 0; i < f.dataBits; i++) {
+                    levels[t] = (value >> i & 1) != 0;
+                    fields[t++] = "D" + i;
+                }
+                if (f.parity != Parity.NONE) {
+                    levels[t] = parityBit(value, f);
+                    fields[t++] = "P";
+                }
+                for (int i = 0; i < f.stopBits; i++) fields[t++] = "Sp";
+            }
+            return new Line(levels, fields);
+        }
+
+        public static Line of(String text, Format f, int idle) {
+            return of(text.getBytes(StandardCharsets.ISO_8859_1), f, idle);
+        }
+
+        /** A copy with bit time `t` inverted (noise). */
+        public Line flip(int t) {
+            boolean[] copy = levels.clone();
+            copy[t] = !copy[t];
+            return new Line(copy, fields);
+        }
+
+        /** A copy with the line held low from `from` for `bits` bit times. */
+        public Line low(int from, int bits) {
+            boolean[] copy = levels.clone();
+            Arrays.fill(copy, from, Math.min(copy.length, from + bits), false);
+            return new Line(copy, fields);
+        }
+
+        /** Level at time `t` (in bit times); idle before and after. */
+        public boolean at(double t) {
+            return t < 0 || t >= levels.length || levels[(int) t];
+        }
+
+        /** Where a field (e.g. "D3") of frame `frame` is. */
+        public int find(int frame, String field) {
+            int seen = -1;
+            for (int t = 0; t < fields.length; t++) {
+                if (fields[t].equals("St")) seen++;
+                if (seen == frame && fields[t].equals(field)) return t;
+            }
+            throw new IllegalArgumentException("No " + field + " in frame " + frame);
+        }
+    }
+
+    // ==================== RECEIVER ====================
+
+    /** One frame as the receiver saw it. */
+    public static final class Received {
+        public final int value;
+        public final boolean parityError;
+        public final boolean framingError;
+        /** Sample times in the transmitter's bit times, start bit first. */
+        public final double[] samples;
+
+        Received(int value, boolean parityError, boolean framingError, double[] samples) {
+            this.value = value;
+            this.parityError = parityError;
+            this.framingError = framingError;
+            this.samples = samples;
+        }
+
+        public boolean hasError() {
+            return parityError || framingError;
+        }
+
+        @Override
+        public String toString() {
+            String c = value >= 0x20 && value < 0x7F ? " '" + (char) value + "'" : "";
+            return String.format("0x%02X%s%s%s", value, c, parityError ? " PARITY ERROR" : "",
+                framingError ? " FRAMING ERROR" : "");
+        }
+    }
+
+    /**
+     * Receive `line` in format `f` with a bit time of `ratio` transmitter
+     * bit times (tx baud / rx baud: above 1 the receiver is slow).
+     */
+    public static List<Received> receive(Line line, Format f, double ratio) {
+        if (ratio <= 0) throw new IllegalArgumentException("Ratio must be positive, got " + ratio);
+        List<Received> frames = new ArrayList<>();
+        double tick = ratio / 16;
+        boolean prev = true;
+        for (double t = 0; t < line.levels.length; ) {
+            boolean level = line.at(t);
+            if (!prev || level) {
+                prev = level;
+                t += tick;
+                continue;
+            }
+            double start = t + ratio / 2;
+            if (line.at(start)) {                    // glitch, not a start bit
+                t += tick;
+                continue;
+            }
+            double[] samples = new double[f.frameBits()];
+            boolean[] bits = new boolean[samples.length];
+            for (int k = 0; k < samples.length; k++) {
+                samples[k] = start + k * ratio;
+                bits[k] = line.at(samples[k]);
+            }
+            int value = 0;
+            for (int i = 0; i < f.dataBits; i++) if (bits[1 + i]) value |= 1 << i;
+            int at = 1 + f.dataBits;
+            boolean parityError = f.parity != Parity.NONE && bits[at++] != parityBit(value, f);
+            boolean framingError = false;
+            for (int i = 0; i < f.stopBits; i++) framingError |= !bits[at++];
+            frames.add(new Received(value, parityError, framingError, samples));
+            t = samples[samples.length - 1];
+            prev = line.at(t);                       // after a framing error: wait for idle
+        }
+        return frames;
+    }
+
+    public static String decode(List<Received> frames) {
+        StringBuilder sb = new StringBuilder();
+        for (Received r : frames) sb.append(r.hasError() ? '?' : (char) r.value);
+        return sb.toString();
+    }
+
+    // ==================== WAVEFORMS ====================
+
+    /**
+     * TX the line, "field" what the transmitter sent in each bit, "sample"
+     * a pulse where the receiver samples (marked ↑), RX the bit it read.
+     */
+    public static SignalTrace trace(Line line, List<Received> frames, int stepsPerBit) {
+        int steps = line.levels.length * stepsPerBit;
+        char[] sample = new char[steps];
+        String[] rx = new String[steps];
+        Arrays.fill(sample, '0');
+        for (Received r : frames) {
+            for (double s : r.samples) {
+                int step = (int) (s * stepsPerBit);
+                if (step < steps) {
+                    sample[step] = '1';
+                    rx[step] = line.at(s) ? "1" : "0";
+                }
+            }
+        }
+        SignalTrace trace = new SignalTrace("TX", "field", "sample", "RX");
+        String shown = "-";
+        for (int s = 0; s < steps; s++) {
+            int bit = s / stepsPerBit;
+            trace.record("TX", line.levels[bit]);
+            trace.recordLabel("field", line.fields[bit]);
+            trace.record("sample", sample[s]);
+            if (rx[s] != null) shown = rx[s];
+            trace.recordLabel("RX", shown);
+        }
+        return trace;
+    }
+
+    public static String diagram(Line line, List<Received> frames) {
+        return new TimingDiagram(trace(line, frames, 3)).clock("sample").cellWidth(2).stepsPerUnit(3).toAscii();
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    /** rx baud = tx baud × (1 + percent / 100). */
+    static double ratio(double percent) {
+        return 1 / (1 + percent / 100);
+    }
+
+    private static int correct(List<Received> frames, byte[] sent) {
+        int ok = 0;
+        for (int i = 0; i < Math.min(frames.size(), sent.length); i++) {
+            Received r = frames.get(i);
+            if (!r.hasError() && r.value == (sent[i] & 0xFF)) ok++;
+        }
+        return ok;
+    }
+
+    private static int count(List<Received> frames, boolean parity) {
+        int n = 0;
+        for (Received r : frames) if (parity ? r.parityError : r.framingError) n++;
+        return n;
+    }
+
+    public static String demonstrateUart() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== UART Framing, Bit by Bit ===\n\n");
+
+        Format f8e1 = new Format(8, Parity.EVEN, 1);
+        Line a = Line.of("A", f8e1, 1);
+        sb.append("1) 'A' = 0x41 = 0100 0001 in 8E1; data LSB first, two 1s → parity 0.\n");
+        sb.append("   Bit times on the axis; ↑ = the receiver's sample points:\n\n");
+        sb.append(diagram(a, receive(a, f8e1, 1))).append('\n');
+        sb.append("   received: " + receive(a, f8e1, 1) + "\n\n");
+
+        sb.append("2) Noise on the wire:\n");
+        Line one = a.flip(a.find(0, "D3"));
+        sb.append("   D3 flipped:        " + receive(one, f8e1, 1) + "\n");
+        Line two = one.flip(a.find(0, "D4"));
+        sb.append("   D3 and D4 flipped: " + receive(two, f8e1, 1) + " - even parity misses it\n");
+        Line brk = Line.of("AB", FORMAT_8N1, 2);
+        brk = brk.low(brk.find(0, "St"), 2 * FORMAT_8N1.frameBits());
+        sb.append("   BREAK (line low for two frames), 8N1: " + receive(brk, FORMAT_8N1, 1) + "\n");
+        sb.append("   A 0 where the stop bit should be is a framing error; the receiver then\n");
+        sb.append("   waits for the line to go idle before it looks for a start bit again.\n\n");
+
+        String text = "Hello, UART!";
+        byte[] bytes = text.getBytes(StandardCharsets.ISO_8859_1);
+        Line hello8n1 = Line.of(text, FORMAT_8N1, 2);
+        Line hello8e1 = Line.of(text, f8e1, 2);
+        sb.append(String.format("3) Baud-rate mismatch: \"%s\" sent back to back at 9600 baud\n\n", text));
+        sb.append(String.format("   %8s %7s | %-22s | %-28s\n", "rx baud", "error", "8N1: ok  framing", "8E1: ok  parity  framing"));
+        for (int pct = -8; pct <= 8; pct += 2) {
+            List<Received> n1 = receive(hello8n1, FORMAT_8N1, ratio(pct));
+            List<Received> e1 = receive(hello8e1, f8e1, ratio(pct));
+            sb.append(String.format("   %8.0f %6d%% |   %4d %8d         |   %4d %7d %8d\n",
+                9600 * (1 + pct / 100.0), pct, correct(n1, bytes), count(n1, false),
+                correct(e1, bytes), count(e1, true), count(e1, false)));
+        }
+        sb.append(String.format("   (%d characters; limit 1/(2F-1): 8N1 ±%.1f%%, 8E1 ±%.1f%%)\n\n",
+            bytes.length, 100.0 / (2 * FORMAT_8N1.frameBits() - 1), 100.0 / (2 * f8e1.frameBits() - 1)));
+
+        Line u = Line.of("U", FORMAT_8N1, 1);
+        List<Received> slow = receive(u, FORMAT_8N1, ratio(-7));
+        sb.append("4) 'U' = 0x55 in 8N1, receiver 7% slow: the samples drift right\n\n");
+        sb.append(diagram(u, slow)).append('\n');
+        sb.append("   received: " + slow + "\n");
+        sb.append("   From D5 on each bit is read from its right neighbour, D7 from the stop\n");
+        sb.append("   bit and the stop bit from the idle line: 0x55 arrives as 0x95 with no\n");
+        sb.append("   error at all. Only parity, or a start bit right behind it (the framing\n");
+        sb.append("   errors in the table), gives the mismatch away.\n");
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateUart());
+            return;
+        }
+        try {
+            Format f = FORMAT_8N1;
+            double pct = 0;
+            String text = "A";
+            for (int i = 0; i < args.length; i++) {
+                switch (args[i]) {
+                    case "--format":   f = Format.parse(args[++i]); break;
+                    case "--mismatch": pct = Double.parseDouble(args[++i]); break;
+                    default:
+                        if (args[i].startsWith("--")) throw new IllegalArgumentException("Unknown option: " + args[i]);
+                        text = args[i];
+                }
+            }
+            if (pct <= -100) throw new IllegalArgumentException("Mismatch must be above -100%, got " + pct);
+            Line line = Line.of(text, f, 2);
+            List<Received> frames = receive(line, f, ratio(pct));
+            System.out.println(diagram(line, frames));
+            System.out.println("received: " + frames);
+            System.out.println("as text:  \"" + decode(frames) + "\"");
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.err.println("Missing value after " + args[args.length - 1]);
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
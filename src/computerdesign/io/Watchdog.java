@@ -0,0 +1,247 @@
+package computerdesign.io;
+
+import computerdesign.instruction.Instruction;
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * Watchdog - a timer that resets the machine unless the software keeps
+ * restarting ("kicking") it: the last line of defence against firmware
+ * that hangs.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE PATTERN
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   boot:  was the last reset the watchdog's?  → note it, take a safe path
+ *          enable the watchdog (timeout T)
+ *   loop:  kick                                 count restarts at T
+ *          do one step of the work              must take < T cycles
+ *
+ * If the work hangs - a device that never becomes ready, a corrupted
+ * loop counter - the kicks stop, the count reaches 0 and the watchdog
+ * pulls the reset line. The firmware starts over, reads the reset cause
+ * and can avoid what hung it.
+ *
+ * Kick only where progress is made (the main loop), never from a timer
+ * interrupt: an interrupt keeps firing while the main loop is stuck. And
+ * T must cover the longest legitimate path between kicks, or the device
+ * resets forever (a boot loop).
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * REGISTERS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   offset  name     access
+ *   0x00    LOAD     timeout in cycles, used from the next kick
+ *   0x04    KICK     write KEY to restart the count; any other value resets
+ *                    at once (a runaway program rarely writes the key)
+ *   0x08    CONTROL  bit 0 ENABLE; once set only a reset clears it, so a
+ *                    runaway program cannot switch the watchdog off
+ *   0x0C    COUNT    cycles left (read-only)
+ *   0x10    CAUSE    bit 0 WDT: the last reset came from the watchdog;
+ *                    write 1 to clear. Survives the reset.
+ *   0x14    RESETS   watchdog resets so far (read-only)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage:
+ *   Machine m = new Machine(bus, 0x800).attach(Watchdog.BASE, new Watchdog());
+ *
+ * Covers learning goals: T6.2 (I/O devices), T5.2 (exceptional events)
+ */
+public class Watchdog implements Device, Clocked {
+
+    public static final int BASE = 0x4002_0000;
+    public static final int LOAD = 0x00;
+    public static final int KICK = 0x04;
+    public static final int CONTROL = 0x08;
+    public static final int COUNT = 0x0C;
+    public static final int CAUSE = 0x10;
+    public static final int RESETS = 0x14;
+
+    public static final int KEY = 0x5A5;
+    public static final int ENABLE = 1;
+    public static final int WDT = 1;
+
+    private int load = 1000;
+    private int count;
+    private boolean enabled;
+    private boolean cause;
+    private boolean biting;
+    private int cycle;
+    private final List<Integer> bites = new ArrayList<>();
+
+    // ==================== BUS SIDE ====================
+
+    @Override
+    public int read(int offset) {
+        switch (offset) {
+            case LOAD:    return load;
+            case CONTROL: return enabled ? ENABLE : 0;
+            case COUNT:   return enabled ? count : 0;
+            case CAUSE:   return cause ? WDT : 0;
+            case RESETS:  return bites.size();
+            default:      return 0;
+        }
+    }
+
+    @Override
+    public void write(int offset, int value) {
+        switch (offset) {
+            case LOAD:
+                load = Math.max(1, value);
+                break;
+            case KICK:
+                count = value == KEY ? load : 0;
+                break;
+            case CONTROL:
+                if ((value & ENABLE) != 0 && !enabled) {
+                    enabled = true;
+                    count = load;
+                }
+                break;
+            case CAUSE:
+                if ((value & WDT) != 0) cause = false;
+                break;
+            default:
+                break;
+        }
+    }
+
+    @Override
+    public int getSize() {
+        return 0x18;
+    }
+
+    @Override
+    public String getName() {
+        return "watchdog";
+    }
+
+    // ==================== TIMER SIDE ====================
+
+    @Override
+    public boolean tick() {
+        cycle++;
+        biting = false;
+        if (enabled && --count <= 0) {
+            biting = true;
+            cause = true;
+            enabled = false;                     // the reset stops the watchdog too
+            bites.add(cycle);
+        }
+        return false;
+    }
+
+    @Override
+    public boolean resetRequest() {
+        return biting;
+    }
+
+    /** The cycles (counted by this device) at which it reset the machine. */
+    public List<Integer> getBites() {
+        return bites;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static final int RAM = 0x3000;       // 0: reset count, 4: sensor ready, 8: result
+    private static final int HALT = 0x00100073;
+
+    private static int andi(int rd, int rs1, int imm) {
+        return Instruction.iType(0b0010011, rd, 0b111, rs1, imm).getRaw();
+    }
+
+    /**
+     * Sum 1..10, kicking every iteration; item 5 first waits for a sensor
+     * that never becomes ready. After a watchdog reset it skips the sensor.
+     */
+    static int[] firmware(int timeout, boolean enable) {
+        return new int[] {
+            Instruction.lui(5, BASE).getRaw(),           //   0: t0 = watchdog
+            Instruction.lui(7, RAM).getRaw(),            //   4: t2 = RAM
+            Instruction.lw(18, 5, CAUSE).getRaw(),       //   8: s2 = reset cause
+            andi(18, 18, WDT),                           //  12
+            Instruction.beq(18, 0, 20).getRaw(),         //  16: cold boot → 36
+            Instruction.lw(6, 7, 0).getRaw(),            //  20: recovered: count it
+            Instruction.addi(6, 6, 1).getRaw(),          //  24
+            Instruction.sw(6, 7, 0).getRaw(),            //  28
+            Instruction.sw(18, 5, CAUSE).getRaw(),       //  32: clear the cause
+            Instruction.addi(6, 0, timeout).getRaw(),    //  36
+            Instruction.sw(6, 5, LOAD).getRaw(),         //  40
+            Instruction.addi(6, 0, ENABLE).getRaw(),     //  44
+            enable ? Instruction.sw(6, 5, CONTROL).getRaw() : Instruction.addi(0, 0, 0).getRaw(),
+            Instruction.addi(28, 0, KEY).getRaw(),       //  52: t3 = key
+            Instruction.addi(8, 0, 0).getRaw(),          //  56: i = 0
+            Instruction.addi(9, 0, 0).getRaw(),          //  60: sum = 0
+            Instruction.sw(28, 5, KICK).getRaw(),        //  64: loop: kick
+            Instruction.addi(8, 8, 1).getRaw(),          //  68: i++
+            Instruction.add(9, 9, 8).getRaw(),           //  72: sum += i
+            Instruction.addi(6, 0, 5).getRaw(),          //  76
+            Instruction.bne(8, 6, 20).getRaw(),          //  80: not item 5 → 100
+            Instruction.bne(18, 0, 16).getRaw(),         //  84: recovered: skip the sensor
+            Instruction.lw(6, 7, 4).getRaw(),            //  88: wait: sensor ready?
+            Instruction.beq(6, 0, -4).getRaw(),          //  92: never: the hang
+            Instruction.add(9, 9, 6).getRaw(),           //  96
+            Instruction.addi(6, 0, 10).getRaw(),         // 100
+            Instruction.bne(8, 6, -40).getRaw(),         // 104: → loop
+            Instruction.sw(9, 7, 8).getRaw(),            // 108: result
+            HALT                                         // 112
+        };
+    }
+
+    static Machine run(int timeout, boolean enable, Watchdog dog, int maxCycles) {
+        MemoryBus bus = new MemoryBus();
+        Machine m = new Machine(bus, 0x800).attach(BASE, dog);
+        bus.loadProgram(firmware(timeout, enable), 0);
+        m.run(maxCycles);
+        return m;
+    }
+
+    private static String outcome(Machine m) {
+        MemoryBus bus = m.getBus();
+        return m.isHalted()
+            ? String.format("halted after %d cycles, result %d, watchdog resets %d", m.getCycles(),
+                bus.read(RAM + 8), bus.read(RAM))
+            : String.format("still running after %d cycles, pc = %d, %d resets", m.getCycles(),
+                m.getCpu().getPC(), m.getResets());
+    }
+
+    public static String demonstrateWatchdog() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Watchdog Timer: Hang and Recovery ===\n\n");
+        MemoryBus map = new MemoryBus();
+        map.map(BASE, new Watchdog());
+        sb.append(map.describeMap());
+        sb.append(String.format("   LOAD 0x00, KICK 0x04 (key 0x%X), CONTROL 0x08, COUNT 0x0C, CAUSE 0x10, RESETS 0x14\n\n", KEY));
+
+        sb.append("The firmware sums 1..10 and kicks the watchdog every iteration; at item 5\n");
+        sb.append("it waits for a sensor that never becomes ready (pc 88-92).\n\n");
+
+        sb.append("1) Watchdog never enabled:\n");
+        sb.append("   " + outcome(run(200, false, new Watchdog(), 50_000)) + "\n");
+        sb.append("   - hung for good; only a power cycle would help.\n\n");
+
+        Watchdog dog = new Watchdog();
+        Machine m = run(200, true, dog, 50_000);
+        sb.append("2) Enabled, timeout 200 cycles (an iteration takes about 10):\n");
+        sb.append("   " + outcome(m) + "\n");
+        sb.append(String.format("   The kicks stopped in the hang and the watchdog reset the CPU at cycle %d.\n",
+            dog.getBites().get(0)));
+        sb.append("   The firmware booted again, found CAUSE.WDT set, counted the reset and\n");
+        sb.append("   skipped the sensor: degraded, but running.\n\n");
+
+        dog = new Watchdog();
+        m = run(8, true, dog, 50_000);
+        sb.append("3) Timeout 8 cycles, shorter than one legitimate iteration:\n");
+        sb.append("   " + outcome(m) + "\n");
+        sb.append("   A boot loop: the watchdog bites healthy code. The timeout must cover\n");
+        sb.append("   the longest path between two kicks, with a margin.\n");
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        System.out.println(demonstrateWatchdog());
+    }
+}
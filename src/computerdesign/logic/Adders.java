@@ -0,0 +1,330 @@
+package computerdesign.logic;
+
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+import java.util.Random;
+
+import static computerdesign.logic.Netlist.busName;
+
+/**
+ * Adders - Ripple-carry and carry-lookahead adders built from gates, with timing.
+ *
+ * Covers learning goals: T3.2 (Combinational Logic), T3.3 (ALU), T1.3 (clock rate)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * RIPPLE-CARRY: SIMPLE BUT SLOW
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   a3 b3       a2 b2       a1 b1       a0 b0
+ *    │  │        │  │        │  │        │  │
+ *   ┌┴──┴┐      ┌┴──┴┐      ┌┴──┴┐      ┌┴──┴┐
+ *   │ FA │◄─c3──│ FA │◄─c2──│ FA │◄─c1──│ FA │◄── c0
+ *   └┬───┘      └┬───┘      └┬───┘      └┬───┘
+ *  cout s3       s2          s1          s0
+ *
+ *   Each carry waits for the previous one: 2 gate delays per bit.
+ *   Delay grows LINEARLY: n-bit add ≈ 2n gate delays.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * CARRY-LOOKAHEAD: COMPUTE CARRIES IN PARALLEL
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   Per bit:  g_i = a_i · b_i      (GENERATE: this bit makes a carry)
+ *             p_i = a_i ⊕ b_i      (PROPAGATE: this bit passes a carry on)
+ *
+ *   c1 = g0 + p0·c0
+ *   c2 = g1 + p1·g0 + p1·p0·c0
+ *   c3 = g2 + p2·g1 + p2·p1·g0 + p2·p1·p0·c0
+ *   c4 = g3 + p3·g2 + p3·p2·g1 + p3·p2·p1·g0 + p3·p2·p1·p0·c0
+ *
+ *   Every carry is ONE level of AND + ONE level of OR - no rippling!
+ *   The price: wide gates. So real designs use 4-bit groups and apply the
+ *   same trick again to the groups' G and P signals (hierarchical CLA):
+ *
+ *     G = g3 + p3·g2 + p3·p2·g1 + p3·p2·p1·g0      P = p3·p2·p1·p0
+ *
+ *   Delay grows LOGARITHMICALLY: one extra level per factor 4 in width.
+ *
+ *   Delay model here: every gate costs 1 unit regardless of fan-in.
+ *   (Real wide gates are slower - that is one reason groups stop at 4.)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class Adders {
+
+    /** Group size for the carry-lookahead tree. */
+    public static final int GROUP = 4;
+
+    // ==================== CONSTRUCTION ====================
+
+    /**
+     * n-bit ripple-carry adder: inputs a[i], b[i], cin; outputs s[i], cout.
+     */
+    public static Netlist rippleCarry(int bits) {
+        Netlist fullAdder = Netlist.parse(Netlist.FULL_ADDER);
+        Netlist net = new Netlist();
+        declarePorts(net, bits);
+        for (int i = 0; i < bits; i++) {
+            Map<String, String> ports = new HashMap<>();
+            ports.put("a", busName("a", i));
+            ports.put("b", busName("b", i));
+            ports.put("cin", i == 0 ? "cin" : busName("c", i));
+            ports.put("sum", busName("s", i));
+            ports.put("cout", i == bits - 1 ? "cout" : busName("c", i + 1));
+            net.include(fullAdder, "fa" + i, ports);
+        }
+        return net;
+    }
+
+    /**
+     * n-bit hierarchical carry-lookahead adder (groups of 4 at every level).
+     */
+    public static Netlist carryLookahead(int bits) {
+        Netlist net = new Netlist();
+        declarePorts(net, bits);
+        List<String> g = new ArrayList<>();
+        List<String> p = new ArrayList<>();
+        for (int i = 0; i < bits; i++) {
+            net.addGate(Netlist.GateType.AND, busName("g", i), busName("a", i), busName("b", i));
+            net.addGate(Netlist.GateType.XOR, busName("p", i), busName("a", i), busName("b", i));
+            g.add(busName("g", i));
+            p.add(busName("p", i));
+        }
+        List<String> carries = lookahead(net, g, p, "cin", "cla");
+        for (int i = 0; i < bits; i++) {
+            String carry = i == 0 ? "cin" : carries.get(i);
+            net.addGate(Netlist.GateType.XOR, busName("s", i), busName("p", i), carry);
+        }
+        net.addGate(Netlist.GateType.BUF, "cout", carries.get(bits)).delay = 0;
+        return net;
+    }
+
+    private static void declarePorts(Netlist net, int bits) {
+        if (bits < 1 || bits > 63) throw new IllegalArgumentException("bits must be 1-63: " + bits);
+        for (int i = 0; i < bits; i++) net.addInput(busName("a", i));
+        for (int i = 0; i < bits; i++) net.addInput(busName("b", i));
+        net.addInput("cin");
+        for (int i = 0; i < bits; i++) net.addOutput(busName("s", i));
+        net.addOutput("cout");
+    }
+
+    /**
+     * Carries c[0..n] for n (generate, propagate) pairs and a carry-in.
+     * Small groups use the flattened equations; larger ones recurse on group G/P.
+     */
+    private static List<String> lookahead(Netlist net, List<String> g, List<String> p,
+                                          String cin, String prefix) {
+        int n = g.size();
+        if (n <= GROUP) return flatCarries(net, g, p, cin, prefix, n);
+
+        List<String> groupG = new ArrayList<>();
+        List<String> groupP = new ArrayList<>();
+        for (int start = 0, k = 0; start < n; start += GROUP, k++) {
+            int end = Math.min(start + GROUP, n);
+            String name = prefix + "." + k;
+            groupG.add(groupGenerate(net, g.subList(start, end), p.subList(start, end), name + ".G"));
+            String gp = name + ".P";
+            if (end - start == 1) net.addGate(Netlist.GateType.BUF, gp, p.get(start)).delay = 0;
+            else net.addGate(Netlist.GateType.AND, gp, p.subList(start, end).toArray(new String[0]));
+            groupP.add(gp);
+        }
+        List<String> groupCarries = lookahead(net, groupG, groupP, cin, prefix + "^");
+
+        List<String> carries = new ArrayList<>();
+        for (int start = 0, k = 0; start < n; start += GROUP, k++) {
+            int end = Math.min(start + GROUP, n);
+            // the group's own carry-out already comes from the level above
+            carries.addAll(flatCarries(net, g.subList(start, end), p.subList(start, end),
+                                       groupCarries.get(k), prefix + "." + k, end - start - 1));
+        }
+        carries.add(groupCarries.get(groupCarries.size() - 1));
+        return carries;
+    }
+
+    /**
+     * cin followed by c[1..count], where c[i+1] = g_i + p_i·g_{i-1} + ... + p_i···p_0·cin
+     * (one AND level + one OR level).
+     */
+    private static List<String> flatCarries(Netlist net, List<String> g, List<String> p,
+                                            String cin, String prefix, int count) {
+        List<String> carries = new ArrayList<>();
+        carries.add(cin);
+        for (int i = 0; i < count; i++) {
+            List<String> terms = new ArrayList<>();
+            terms.add(g.get(i));
+            for (int j = i - 1; j >= -1; j--) {
+                List<String> factors = new ArrayList<>();
+                for (int k = i; k > j; k--) factors.add(p.get(k));
+                factors.add(j >= 0 ? g.get(j) : cin);
+                String term = prefix + ".t" + (i + 1) + "_" + (j + 1);
+                net.addGate(Netlist.GateType.AND, term, factors.toArray(new String[0]));
+                terms.add(term);
+            }
+            String carry = prefix + ".c" + (i + 1);
+            net.addGate(Netlist.GateType.OR, carry, terms.toArray(new String[0]));
+            carries.add(carry);
+        }
+        return carries;
+    }
+
+    /** G = g3 + p3·g2 + p3·p2·g1 + p3·p2·p1·g0 (no carry-in term). */
+    private static String groupGenerate(Netlist net, List<String> g, List<String> p, String name) {
+        int top = g.size() - 1;
+        if (top == 0) {
+            net.addGate(Netlist.GateType.BUF, name, g.get(0)).delay = 0;
+            return name;
+        }
+        List<String> terms = new ArrayList<>();
+        terms.add(g.get(top));
+        for (int j = top - 1; j >= 0; j--) {
+            List<String> factors = new ArrayList<>();
+            for (int k = top; k > j; k--) factors.add(p.get(k));
+            factors.add(g.get(j));
+            String term = name + ".t" + j;
+            net.addGate(Netlist.GateType.AND, term, factors.toArray(new String[0]));
+            terms.add(term);
+        }
+        net.addGate(Netlist.GateType.OR, name, terms.toArray(new String[0]));
+        return name;
+    }
+
+    // ==================== SIMULATION ====================
+
+    /** Evaluate an adder netlist: returns the (bits+1)-bit result including carry-out. */
+    public static long add(Netlist adder, int bits, long a, long b, boolean cin) {
+        adder.setBus("a", bits, a);
+        adder.setBus("b", bits, b);
+        adder.set("cin", cin);
+        adder.settle();
+        long sum = adder.getBus("s", bits);
+        return adder.get("cout") ? sum | (1L << bits) : sum;
+    }
+
+    /** Carry INTO bit i: the second input of the XOR that produces s[i]. */
+    private static String carryWire(Netlist adder, int i, int bits) {
+        if (i == bits) return "cout";
+        for (Netlist.Gate gate : adder.getGates()) {
+            if (gate.output.equals(busName("s", i))) return gate.inputs.get(1);
+        }
+        throw new IllegalStateException("No carry wire for bit " + i);
+    }
+
+    /**
+     * Per-bit settle times for both adders, side by side.
+     */
+    public static String timingTable(int bits) {
+        Netlist ripple = rippleCarry(bits);
+        Netlist cla = carryLookahead(bits);
+        Map<String, Integer> tr = ripple.arrivalTimes();
+        Map<String, Integer> tc = cla.arrivalTimes();
+
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("%d-bit adder: time (gate delays) at which each bit settles\n\n", bits));
+        sb.append("Bit │ Ripple: carry-in  sum │ Lookahead: carry-in  sum\n");
+        sb.append("────┼───────────────────────┼─────────────────────────\n");
+        for (int i = 0; i < bits; i++) {
+            sb.append(String.format("%3d │ %15d %5d │ %18d %5d\n", i,
+                tr.get(carryWire(ripple, i, bits)), tr.get(busName("s", i)),
+                tc.get(carryWire(cla, i, bits)), tc.get(busName("s", i))));
+        }
+        sb.append(String.format("out │ %15d %5s │ %18d %5s\n", tr.get("cout"), "", tc.get("cout"), ""));
+        return sb.toString();
+    }
+
+    /**
+     * Worst-case delay and gate count for growing widths.
+     */
+    public static String scalingTable() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("Width │ Ripple delay  gates │ Lookahead delay  gates │ Speedup\n");
+        sb.append("──────┼─────────────────────┼────────────────────────┼────────\n");
+        for (int bits : new int[] {4, 8, 16, 32}) {
+            Netlist ripple = rippleCarry(bits);
+            Netlist cla = carryLookahead(bits);
+            int dr = worstCase(ripple, bits);
+            int dc = worstCase(cla, bits);
+            sb.append(String.format("%5d │ %12d %6d │ %15d %6d │ %5.1fx\n", bits,
+                dr, ripple.getGates().size(), dc, cla.getGates().size(), (double) dr / dc));
+        }
+        return sb.toString();
+    }
+
+    private static int worstCase(Netlist adder, int bits) {
+        Map<String, Integer> t = adder.arrivalTimes();
+        int worst = t.get("cout");
+        for (int i = 0; i < bits; i++) worst = Math.max(worst, t.get(busName("s", i)));
+        return worst;
+    }
+
+    /** Check both adders against Java's + for random operands. */
+    public static int verify(int bits, int trials, long seed) {
+        Netlist ripple = rippleCarry(bits);
+        Netlist cla = carryLookahead(bits);
+        Random random = new Random(seed);
+        long mask = (1L << bits) - 1;
+        for (int n = 0; n < trials; n++) {
+            long a = random.nextLong() & mask;
+            long b = random.nextLong() & mask;
+            boolean cin = random.nextBoolean();
+            long expected = a + b + (cin ? 1 : 0);
+            long r = add(ripple, bits, a, b, cin);
+            long c = add(cla, bits, a, b, cin);
+            if (r != expected || c != expected) {
+                throw new IllegalStateException(String.format(
+                    "%d + %d + %b: expected %d, ripple %d, lookahead %d", a, b, cin, expected, r, c));
+            }
+        }
+        return trials;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateAdders() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  RIPPLE-CARRY vs CARRY-LOOKAHEAD\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        int bits = 8;
+        Netlist ripple = rippleCarry(bits);
+        Netlist cla = carryLookahead(bits);
+        sb.append(String.format("Gate-level sum: 200 + 100 = %d (ripple), %d (lookahead)\n\n",
+            add(ripple, bits, 200, 100, false), add(cla, bits, 200, 100, false)));
+
+        sb.append(timingTable(bits)).append("\n");
+        sb.append("Ripple: every carry waits for the one before it (+2 per bit).\n");
+        sb.append("Lookahead: carries inside a 4-bit group arrive together.\n\n");
+
+        sb.append("Critical path of the 8-bit ripple adder's carry-out:\n  ");
+        List<Netlist.Gate> path = ripple.criticalPath("cout");
+        for (int i = 0; i < path.size(); i++) {
+            if (i > 0) sb.append(i % 6 == 0 ? "\n  → " : " → ");
+            sb.append(path.get(i).type).append("(").append(path.get(i).output).append(")");
+        }
+        sb.append("\n\n");
+
+        sb.append("Scaling with width:\n\n").append(scalingTable()).append("\n");
+        sb.append("O(n) vs O(log n): the lookahead adder buys speed with more gates.\n");
+        sb.append(String.format("Verified both adders on %d random 32-bit additions.\n",
+            verify(32, 200, 4160)));
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    public static void main(String[] args) {
+        if (args.length > 0) {
+            try {
+                System.out.println(timingTable(Integer.parseInt(args[0])));
+            } catch (IllegalArgumentException e) {
+                String why = e instanceof NumberFormatException ? "Not a number: " + args[0] : e.getMessage();
+                System.err.println(why + "\nUsage: Adders [bits]");
+                System.exit(2);
+            }
+            return;
+        }
+        System.out.println(demonstrateAdders());
+    }
+}
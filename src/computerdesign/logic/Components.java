@@ -0,0 +1,353 @@
+package computerdesign.logic;
+
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+import static computerdesign.logic.Netlist.busName;
+
+/**
+ * Components - Multiplexers, decoders, encoders and demultiplexers as netlists.
+ *
+ * Covers learning goals: T3.2 (Combinational Logic), T4.1 (Datapath)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE DATAPATH'S BUILDING BLOCKS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   MULTIPLEXER (n:1)         Selects ONE of n inputs.     ALUSrc, MemtoReg, PCSrc
+ *        d0 ─┐                y = Σ d_i · (s == i)
+ *        d1 ─┤MUX├─ y
+ *        ... ┘ │
+ *              s (log2 n bits)
+ *
+ *   DECODER (k → 2^k)         Exactly ONE output is 1.     register write enable,
+ *       a ──┤DEC├── y0..y(2^k-1)    y_i = en · (a == i)    address decoding
+ *
+ *   DEMULTIPLEXER (1:n)       Routes ONE input to output s; the others stay 0.
+ *        d ──┤DEMUX├── y0..y(n-1)   (a decoder whose enable is the data)
+ *
+ *   PRIORITY ENCODER (n → log2 n)   Index of the HIGHEST active input.
+ *       d0..d(n-1) ──┤ENC├── y, valid       interrupt controllers
+ *
+ * Every component is built from AND/OR/NOT gates with the bus naming used
+ * throughout the package (d[0], s[1], ...), so parts can be wired together
+ * with Netlist.include() exactly like the boxes on the slides.
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class Components {
+
+    // ==================== CONSTRUCTION ====================
+
+    /**
+     * n:1 multiplexer (n = 2, 4 or 8). Inputs d[0..n-1], s[0..k-1]; output y.
+     */
+    public static Netlist mux(int n) {
+        int k = selectBits(n, 8);
+        Netlist net = new Netlist();
+        for (int i = 0; i < n; i++) net.addInput(busName("d", i));
+        for (int j = 0; j < k; j++) net.addInput(busName("s", j));
+        net.addOutput("y");
+        invertSelects(net, "s", k);
+        String[] terms = new String[n];
+        for (int i = 0; i < n; i++) {
+            terms[i] = busName("t", i);
+            net.addGate(Netlist.GateType.AND, terms[i], minterm(busName("d", i), "s", k, i));
+        }
+        net.addGate(Netlist.GateType.OR, "y", terms);
+        return net;
+    }
+
+    /**
+     * n:1 multiplexer over width-bit buses: inputs d0[..], d1[..], ..., s[..];
+     * output y[..]. One single-bit mux per bit, sharing the select lines.
+     */
+    public static Netlist busMux(int n, int width) {
+        int k = selectBits(n, 8);
+        Netlist bit = mux(n);
+        Netlist net = new Netlist();
+        for (int i = 0; i < n; i++) {
+            for (int b = 0; b < width; b++) net.addInput(busName("d" + i, b));
+        }
+        for (int j = 0; j < k; j++) net.addInput(busName("s", j));
+        for (int b = 0; b < width; b++) net.addOutput(busName("y", b));
+        for (int b = 0; b < width; b++) {
+            Map<String, String> ports = new HashMap<>();
+            for (int i = 0; i < n; i++) ports.put(busName("d", i), busName("d" + i, b));
+            for (int j = 0; j < k; j++) ports.put(busName("s", j), busName("s", j));
+            ports.put("y", busName("y", b));
+            net.include(bit, "mux" + b, ports);
+        }
+        return net;
+    }
+
+    /**
+     * k-to-2^k decoder (k = 1..4) with enable. Inputs a[0..k-1], en; outputs y[i].
+     */
+    public static Netlist decoder(int k) {
+        if (k < 1 || k > 4) throw new IllegalArgumentException("decoder needs 1-4 inputs: " + k);
+        int n = 1 << k;
+        Netlist net = new Netlist();
+        for (int j = 0; j < k; j++) net.addInput(busName("a", j));
+        net.addInput("en");
+        for (int i = 0; i < n; i++) net.addOutput(busName("y", i));
+        invertSelects(net, "a", k);
+        for (int i = 0; i < n; i++) {
+            net.addGate(Netlist.GateType.AND, busName("y", i), minterm("en", "a", k, i));
+        }
+        return net;
+    }
+
+    /**
+     * 1:n demultiplexer (n = 2, 4 or 8). Inputs d, s[0..k-1]; outputs y[i].
+     */
+    public static Netlist demux(int n) {
+        int k = selectBits(n, 8);
+        Netlist net = new Netlist();
+        net.addInput("d");
+        for (int j = 0; j < k; j++) net.addInput(busName("s", j));
+        for (int i = 0; i < n; i++) net.addOutput(busName("y", i));
+        invertSelects(net, "s", k);
+        for (int i = 0; i < n; i++) {
+            net.addGate(Netlist.GateType.AND, busName("y", i), minterm("d", "s", k, i));
+        }
+        return net;
+    }
+
+    /**
+     * n-input priority encoder (n = 2..16): y = index of the highest active d[i],
+     * valid = any input active. Inputs d[0..n-1]; outputs y[0..k-1], valid.
+     */
+    public static Netlist priorityEncoder(int n) {
+        if (n < 2 || n > 16) throw new IllegalArgumentException("encoder needs 2-16 inputs: " + n);
+        int k = 32 - Integer.numberOfLeadingZeros(n - 1);
+        Netlist net = new Netlist();
+        for (int i = 0; i < n; i++) net.addInput(busName("d", i));
+        for (int j = 0; j < k; j++) net.addOutput(busName("y", j));
+        net.addOutput("valid");
+
+        // win[i] = d[i] and no higher input is active
+        String[] win = new String[n];
+        win[n - 1] = busName("d", n - 1);
+        for (int i = 1; i < n; i++) net.addGate(Netlist.GateType.NOT, busName("d", i) + "'", busName("d", i));
+        for (int i = n - 2; i >= 0; i--) {
+            List<String> factors = new ArrayList<>();
+            factors.add(busName("d", i));
+            for (int h = i + 1; h < n; h++) factors.add(busName("d", h) + "'");
+            win[i] = busName("win", i);
+            net.addGate(Netlist.GateType.AND, win[i], factors.toArray(new String[0]));
+        }
+        for (int j = 0; j < k; j++) {
+            List<String> terms = new ArrayList<>();
+            for (int i = 0; i < n; i++) {
+                if ((i >> j & 1) == 1) terms.add(win[i]);
+            }
+            if (terms.size() == 1) net.addGate(Netlist.GateType.BUF, busName("y", j), terms.get(0)).delay = 0;
+            else net.addGate(Netlist.GateType.OR, busName("y", j), terms.toArray(new String[0]));
+        }
+        List<String> all = new ArrayList<>();
+        for (int i = 0; i < n; i++) all.add(busName("d", i));
+        net.addGate(Netlist.GateType.OR, "valid", all.toArray(new String[0]));
+        return net;
+    }
+
+    /**
+     * 8:1 multiplexer assembled from seven 2:1 multiplexers (a 3-level tree),
+     * the way a wide mux is drawn from smaller parts.
+     */
+    public static Netlist muxTree8() {
+        Netlist mux2 = mux(2);
+        Netlist net = new Netlist();
+        for (int i = 0; i < 8; i++) net.addInput(busName("d", i));
+        for (int j = 0; j < 3; j++) net.addInput(busName("s", j));
+        net.addOutput("y");
+        List<String> level = new ArrayList<>();
+        for (int i = 0; i < 8; i++) level.add(busName("d", i));
+        for (int j = 0; j < 3; j++) {
+            List<String> next = new ArrayList<>();
+            for (int i = 0; i < level.size(); i += 2) {
+                String out = level.size() == 2 ? "y" : "l" + j + "." + (i / 2);
+                Map<String, String> ports = new HashMap<>();
+                ports.put(busName("d", 0), level.get(i));
+                ports.put(busName("d", 1), level.get(i + 1));
+                ports.put(busName("s", 0), busName("s", j));
+                ports.put("y", out);
+                net.include(mux2, "m" + j + "." + (i / 2), ports);
+                next.add(out);
+            }
+            level = next;
+        }
+        return net;
+    }
+
+    private static int selectBits(int n, int max) {
+        if (n < 2 || n > max || Integer.bitCount(n) != 1) {
+            throw new IllegalArgumentException("size must be a power of two 2-" + max + ": " + n);
+        }
+        return Integer.numberOfTrailingZeros(n);
+    }
+
+    private static void invertSelects(Netlist net, String bus, int k) {
+        for (int j = 0; j < k; j++) net.addGate(Netlist.GateType.NOT, busName(bus, j) + "'", busName(bus, j));
+    }
+
+    /** Gate inputs for first · (bus == index): one literal per select bit. */
+    private static String[] minterm(String first, String bus, int k, int index) {
+        String[] ins = new String[k + 1];
+        ins[0] = first;
+        for (int j = 0; j < k; j++) {
+            ins[j + 1] = (index >> j & 1) == 1 ? busName(bus, j) : busName(bus, j) + "'";
+        }
+        return ins;
+    }
+
+    // ==================== VERIFICATION ====================
+
+    /**
+     * Exhaustively check every component against its definition, both
+     * evaluated and as its printed truth table.
+     * Returns the number of input combinations checked.
+     */
+    public static int verify() {
+        return verify(null);
+    }
+
+    /** The components of verify() one per line, for --verify. */
+    public static String verifyReport() {
+        StringBuilder sb = new StringBuilder();
+        int rows = verify(sb);
+        return sb.append(String.format("%d input rows checked\n", rows)).toString();
+    }
+
+    private static int verify(StringBuilder report) {
+        int checked = 0;
+        for (int n : new int[] {2, 4, 8}) {
+            int k = Integer.numberOfTrailingZeros(n);
+            checked += check(mux(n), "mux" + n, report, (in, out) ->
+                out[0] == in[(int) bits(in, n, k)]);
+            checked += check(demux(n), "demux" + n, report, (in, out) -> {
+                int s = (int) bits(in, 1, k);
+                for (int i = 0; i < n; i++) if (out[i] != (in[0] && i == s)) return false;
+                return true;
+            });
+        }
+        checked += check(muxTree8(), "muxTree8", report, (in, out) -> out[0] == in[(int) bits(in, 8, 3)]);
+        for (int k : new int[] {1, 2, 3, 4}) {
+            int n = 1 << k;
+            checked += check(decoder(k), "decoder" + k, report, (in, out) -> {
+                int a = (int) bits(in, 0, k);
+                for (int i = 0; i < n; i++) if (out[i] != (in[k] && i == a)) return false;
+                return true;
+            });
+        }
+        for (int n : new int[] {2, 3, 4, 8}) {
+            int k = 32 - Integer.numberOfLeadingZeros(n - 1);
+            checked += check(priorityEncoder(n), "encoder" + n, report, (in, out) -> {
+                int highest = -1;
+                for (int i = 0; i < n; i++) if (in[i]) highest = i;
+                boolean valid = out[k];
+                if (valid != highest >= 0) return false;
+                return highest < 0 || bits(out, 0, k) == highest;
+            });
+        }
+        return checked;
+    }
+
+    private interface Spec {
+        boolean holds(boolean[] in, boolean[] out);
+    }
+
+    private static int check(Netlist net, String name, StringBuilder report, Spec spec) {
+        int inputs = net.getInputs().size();
+        for (int row = 0; row < 1 << inputs; row++) {
+            boolean[] in = new boolean[inputs];
+            for (int i = 0; i < inputs; i++) in[i] = (row >> i & 1) == 1;
+            boolean[] out = net.evaluate(in);
+            if (!spec.holds(in, out)) {
+                throw new IllegalStateException(name + " is wrong for input row " + row);
+            }
+        }
+        // the printed table: a header, a rule, then one "inputs │ outputs" line per row
+        String[] lines = net.truthTable().split("\n");
+        if (lines.length != 2 + (1 << inputs)) {
+            throw new IllegalStateException(name + ": truth table has " + (lines.length - 2) + " rows");
+        }
+        for (int r = 2; r < lines.length; r++) {
+            String[] sides = lines[r].split("│");
+            boolean[] in = parseBits(sides[0]);
+            boolean[] out = parseBits(sides[1]);
+            if (in.length != inputs || out.length != net.getOutputs().size() || !spec.holds(in, out)) {
+                throw new IllegalStateException(name + ": truth table row " + (r - 1) + " is wrong: " + lines[r]);
+            }
+        }
+        if (report != null) report.append(String.format("%-10s %4d rows, truth table agrees\n", name, 1 << inputs));
+        return 1 << inputs;
+    }
+
+    private static boolean[] parseBits(String columns) {
+        String[] cells = columns.trim().split("\\s+");
+        boolean[] bits = new boolean[cells.length];
+        for (int i = 0; i < cells.length; i++) bits[i] = cells[i].equals("1");
+        return bits;
+    }
+
+    /** Integer value of values[from..from+count-1], LSB first. */
+    private static long bits(boolean[] values, int from, int count) {
+        long v = 0;
+        for (int i = 0; i < count; i++) if (values[from + i]) v |= 1L << i;
+        return v;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateComponents() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  MULTIPLEXERS, DECODERS AND ENCODERS\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("2:1 multiplexer (y = d0·s' + d1·s):\n\n").append(mux(2).truthTable()).append("\n");
+        sb.append("2-to-4 decoder with enable:\n\n").append(decoder(2).truthTable()).append("\n");
+        sb.append("4-input priority encoder (highest input wins):\n\n")
+          .append(priorityEncoder(4).truthTable()).append("\n");
+
+        sb.append("Gate counts:\n");
+        sb.append(String.format("  8:1 mux, sum of products:  %d gates, %d gate delays\n",
+            mux(8).getGates().size(), mux(8).arrivalTime("y")));
+        Netlist tree = muxTree8();
+        sb.append(String.format("  8:1 mux, tree of 2:1 muxes: %d gates, %d gate delays\n\n",
+            tree.getGates().size(), tree.arrivalTime("y")));
+
+        // ALUSrc: choose register rs2 or the immediate, 8 bits wide
+        Netlist aluSrc = busMux(2, 8);
+        aluSrc.setBus("d0", 8, 0x2A);
+        aluSrc.setBus("d1", 8, 0x05);
+        sb.append("ALUSrc mux (8-bit bus): rs2 = 0x2A, imm = 0x05\n");
+        for (int s = 0; s <= 1; s++) {
+            aluSrc.set("s[0]", s == 1);
+            aluSrc.settle();
+            sb.append(String.format("  ALUSrc=%d → ALU input B = 0x%02X\n", s, aluSrc.getBus("y", 8)));
+        }
+        sb.append(String.format("\nVerified all components exhaustively (%d input rows).\n", verify()));
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: Components [--verify]   (--verify: each component checked, exit 1 if one is wrong)
+     */
+    public static void main(String[] args) {
+        if (args.length == 1 && args[0].equals("--verify")) {
+            try {
+                System.out.print(verifyReport());
+            } catch (IllegalStateException e) {
+                System.out.println(e.getMessage());
+                System.exit(1);
+            }
+            return;
+        }
+        System.out.println(demonstrateComponents());
+    }
+}
@@ -0,0 +1,284 @@
+package computerdesign.logic;
+
+/**
+ * FlipFlops - Latch and flip-flop models with a clock-driven simulation loop.
+ *
+ * Covers learning goals: T4.2 (Sequential Logic)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * LEVEL-SENSITIVE (LATCH) vs EDGE-TRIGGERED (FLIP-FLOP)
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   LATCH: output follows input WHILE enable is high ("transparent")
+ *   FLIP-FLOP: output copies input only AT the rising clock edge
+ *
+ *         ┌───┐   ┌───┐   ┌───┐
+ *   CLK ──┘   └───┘   └───┘   └───
+ *           ┌─┐ ┌─────┐
+ *   D   ────┘ └─┘     └───────────
+ *           ┌─┐       ┌─┐
+ *   Q(L)────┘ └───────┘ └─────────    latch: glitches while CLK=1 pass through
+ *         ┌───────┐
+ *   Q(FF)─┘       └───────────────    flip-flop: only sees D at ↑ edges
+ *
+ * This is why registers in a datapath are built from FLIP-FLOPS: a latch-based
+ * register could let a value race through several stages in one clock phase.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE ELEMENTS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   SR latch        S R │ Q        Gated D latch    EN D │ Q
+ *                   ────┼────                       ─────┼────
+ *                   0 0 │ hold                       0 x │ hold
+ *                   0 1 │ 0                          1 d │ d
+ *                   1 0 │ 1
+ *                   1 1 │ FORBIDDEN (Q = Q' = 0, then race → unknown)
+ *
+ *   D flip-flop     ↑ D │ Q+       JK flip-flop ↑ J K │ Q+       T flip-flop ↑ T │ Q+
+ *                   ────┼───                    ──────┼───                  ────┼───
+ *                     d │ d                       0 0 │ Q                     0 │ Q
+ *                                                 0 1 │ 0                     1 │ Q'
+ *                                                 1 0 │ 1
+ *                                                 1 1 │ Q'  (toggle)
+ *
+ * JK fixes the SR latch's forbidden input by turning 11 into "toggle".
+ * T is a JK with J = K = T: the building block of counters.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class FlipFlops {
+
+    // ==================== LATCHES ====================
+
+    /**
+     * SR latch built from cross-coupled NOR gates (active-high S and R).
+     */
+    public static class SRLatch {
+        private boolean q = false;
+        private boolean qn = true;
+        private boolean forbidden = false;
+        private boolean unknown = false;
+
+        public void update(boolean s, boolean r) {
+            if (s && r) {
+                // Both NOR gates are forced low: Q and Q' are no longer complements
+                q = false;
+                qn = false;
+                forbidden = true;
+                unknown = false;
+            } else if (s) {
+                q = true;
+                qn = false;
+                forbidden = false;
+                unknown = false;
+            } else if (r) {
+                q = false;
+                qn = true;
+                forbidden = false;
+                unknown = false;
+            } else if (forbidden) {
+                // S and R released together: both gates race to go high.
+                // Which one wins depends on tiny delay differences.
+                forbidden = false;
+                unknown = true;
+            }
+        }
+
+        public boolean getQ() { return q; }
+        public boolean isForbidden() { return forbidden; }
+        public boolean isUnknown() { return unknown; }
+
+        public char q() { return unknown ? 'X' : q ? '1' : '0'; }
+        public char qn() { return unknown ? 'X' : qn ? '1' : '0'; }
+    }
+
+    /**
+     * Gated D latch: transparent while enable is high, holds while low.
+     */
+    public static class GatedDLatch {
+        private boolean q = false;
+
+        public boolean update(boolean d, boolean enable) {
+            if (enable) q = d;
+            return q;
+        }
+
+        public boolean getQ() { return q; }
+    }
+
+    // ==================== FLIP-FLOPS ====================
+
+    /**
+     * Common rising-edge detection (a master-slave pair in real hardware).
+     */
+    public abstract static class EdgeTriggered {
+        protected boolean q = false;
+        private boolean prevClock = false;
+
+        protected boolean risingEdge(boolean clock) {
+            boolean edge = clock && !prevClock;
+            prevClock = clock;
+            return edge;
+        }
+
+        public boolean getQ() { return q; }
+        public void reset() { q = false; }
+    }
+
+    public static class DFlipFlop extends EdgeTriggered {
+        public boolean update(boolean d, boolean clock) {
+            if (risingEdge(clock)) q = d;
+            return q;
+        }
+    }
+
+    public static class JKFlipFlop extends EdgeTriggered {
+        public boolean update(boolean j, boolean k, boolean clock) {
+            if (risingEdge(clock)) {
+                if (j && k) q = !q;
+                else if (j) q = true;
+                else if (k) q = false;
+            }
+            return q;
+        }
+    }
+
+    public static class TFlipFlop extends EdgeTriggered {
+        public boolean update(boolean t, boolean clock) {
+            if (risingEdge(clock) && t) q = !q;
+            return q;
+        }
+    }
+
+    // ==================== CLOCKED SIMULATION ====================
+
+    /**
+     * Square-wave clock starting low: halfPeriod time steps low, then high.
+     */
+    public static class Clock {
+        private final int halfPeriod;
+
+        public Clock(int halfPeriod) {
+            if (halfPeriod < 1) throw new IllegalArgumentException("halfPeriod must be >= 1");
+            this.halfPeriod = halfPeriod;
+        }
+
+        public boolean level(int t) {
+            return (t / halfPeriod) % 2 == 1;
+        }
+
+        public boolean isRisingEdge(int t) {
+            return level(t) && (t == 0 || !level(t - 1));
+        }
+
+        public int getPeriod() { return 2 * halfPeriod; }
+    }
+
+    /**
+     * One time step of a circuit: read stimuli, update elements, record signals.
+     */
+    public interface Circuit {
+        void step(int t, boolean clock, SignalTrace trace);
+    }
+
+    /**
+     * Run a circuit for the given number of time steps, recording CLK first.
+     */
+    public static SignalTrace simulate(Clock clock, int steps, Circuit circuit) {
+        SignalTrace trace = new SignalTrace("CLK");
+        for (int t = 0; t < steps; t++) {
+            boolean clk = clock.level(t);
+            trace.record("CLK", clk);
+            circuit.step(t, clk, trace);
+        }
+        return trace;
+    }
+
+    /** Stimulus helper: bit t of a waveform written as "0011..." (last value holds). */
+    public static boolean wave(String waveform, int t) {
+        char c = waveform.charAt(Math.min(t, waveform.length() - 1));
+        return c == '1';
+    }
+
+    // ==================== DEMONSTRATIONS ====================
+
+    /**
+     * Same D input into a gated D latch (EN = CLK) and a D flip-flop.
+     */
+    public static SignalTrace latchVersusFlipFlop() {
+        final String d = "0001101011100001";
+        final GatedDLatch latch = new GatedDLatch();
+        final DFlipFlop ff = new DFlipFlop();
+        return simulate(new Clock(2), d.length(), (t, clk, trace) -> {
+            boolean in = wave(d, t);
+            trace.record("D", in);
+            trace.record("Q_latch", latch.update(in, clk));
+            trace.record("Q_flipflop", ff.update(in, clk));
+        });
+    }
+
+    /**
+     * The forbidden SR input: S = R = 1, then both released at once.
+     */
+    public static SignalTrace forbiddenSR() {
+        final String s = "11000011100";
+        final String r = "00011011100";
+        final SRLatch latch = new SRLatch();
+        SignalTrace trace = new SignalTrace("S", "R", "Q", "Q'");
+        for (int t = 0; t < s.length(); t++) {
+            latch.update(wave(s, t), wave(r, t));
+            trace.record("S", wave(s, t));
+            trace.record("R", wave(r, t));
+            trace.record("Q", latch.q());
+            trace.record("Q'", latch.qn());
+        }
+        return trace;
+    }
+
+    /**
+     * JK modes and a T flip-flop dividing the clock frequency by two.
+     */
+    public static SignalTrace jkAndToggle() {
+        final String j = "001111110000";
+        final String k = "000000111111";
+        final JKFlipFlop jk = new JKFlipFlop();
+        final TFlipFlop tff = new TFlipFlop();
+        return simulate(new Clock(1), j.length() * 2, (t, clk, trace) -> {
+            trace.record("J", wave(j, t / 2));
+            trace.record("K", wave(k, t / 2));
+            trace.record("Q_jk", jk.update(wave(j, t / 2), wave(k, t / 2), clk));
+            trace.record("Q_t(T=1)", tff.update(true, clk));
+        });
+    }
+
+    public static String demonstrateFlipFlops() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  LATCHES AND FLIP-FLOPS\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("1) Latch vs flip-flop (latch enable = CLK):\n\n");
+        sb.append(latchVersusFlipFlop()).append("\n");
+        sb.append("   While CLK = 1 the latch is transparent: D's changes at t=3, 7, 11\n");
+        sb.append("   pass straight through to Q.\n");
+        sb.append("   The flip-flop only samples D at rising edges (t=2, 6, 10, 14).\n\n");
+
+        sb.append("2) SR latch and the forbidden input:\n\n");
+        sb.append(forbiddenSR()).append("\n");
+        sb.append("   t=6..8: S=R=1 forces Q = Q' = 0 (not complements!)\n");
+        sb.append("   t=9:    both released at once → the NOR gates race → X (unknown)\n\n");
+
+        sb.append("3) JK flip-flop (hold/set/toggle/reset) and T flip-flop:\n\n");
+        sb.append(jkAndToggle()).append("\n");
+        sb.append("   J=K=1 toggles on every rising edge; a T flip-flop with T=1\n");
+        sb.append("   produces a square wave at half the clock frequency.\n");
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    public static void main(String[] args) {
+        System.out.println(demonstrateFlipFlops());
+    }
+}
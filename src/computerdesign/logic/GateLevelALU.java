@@ -0,0 +1,367 @@
+package computerdesign.logic;
+
+import computerdesign.alu.ALU;
+import computerdesign.instruction.Instruction;
+import computerdesign.memory.MainMemory;
+import computerdesign.processor.SingleCycleProcessor;
+
+import java.util.HashMap;
+import java.util.List;
+import java.util.Locale;
+import java.util.Map;
+import java.util.Random;
+
+import static computerdesign.logic.Netlist.busName;
+
+/**
+ * GateLevelALU - The textbook 32-bit ALU built from gates, usable as the
+ * single-cycle datapath's ALU.
+ *
+ * Covers learning goals: T3.3 (ALU), T3.2 (Combinational Logic), T4.1 (Datapath)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * ONE BIT OF THE ALU
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *            Ainvert        Bnegate      Operation (2 bits)
+ *               │              │               │
+ *    a ──►[MUX a/ā]──┬──────┐  │               ▼
+ *                    │      ├─[AND]──────►┌──────┐
+ *    b ──►[MUX b/b̄]──┼──┬───┤             │  0   │
+ *                    │  │   └─[OR]───────►│  1   ├──► Result
+ *                    │  │                 │  2   │
+ *                    └──┴──[FULL ADDER]──►│      │
+ *               CarryIn ──►    │     sum  │  3   │
+ *                              ▼          └──────┘
+ *                          CarryOut          ▲
+ *    Less ───────────────────────────────────┘
+ *
+ *   Both input muxes are a single XOR: x ⊕ 0 = x, x ⊕ 1 = x̄.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * 32 SLICES → ONE ALU
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   ALU control │ Ainvert Bnegate Op │ Function
+ *   ────────────┼────────────────────┼─────────
+ *      0000     │    0      0     00 │ AND
+ *      0001     │    0      0     01 │ OR
+ *      0010     │    0      0     10 │ add
+ *      0110     │    0      1     10 │ subtract   (a + b̄ + 1: Bnegate is also CarryIn0)
+ *      0111     │    0      1     11 │ set on less than
+ *
+ *   - Carries ripple from slice 0 to slice 31.
+ *   - SLT: slice 31 computes a - b; its "Set" output feeds Less of slice 0,
+ *     all other Less inputs are 0. So Result = 0...0 Set.
+ *   - Overflow = CarryIn31 ⊕ CarryOut31 (only slice 31 needs it).
+ *   - Zero = NOR of all 32 result bits (used by beq).
+ *
+ *   The book's first version uses Set = sum31, which is wrong when a - b
+ *   overflows. This model uses Set = sum31 ⊕ Overflow, so slt agrees with
+ *   the behavioral ALU for all operands.
+ *
+ * Other operations (shifts, XOR, multiply, ...) are delegated to the
+ * behavioral ALU, so the model drops into SingleCycleProcessor unchanged.
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class GateLevelALU extends ALU {
+
+    public static final int WIDTH = 32;
+
+    private final Netlist net;
+    private int gateLevelOps = 0;
+    private int behavioralOps = 0;
+
+    public GateLevelALU() {
+        this.net = build(WIDTH);
+    }
+
+    // ==================== CONSTRUCTION ====================
+
+    /**
+     * n-bit ALU netlist.
+     * Inputs: a[i], b[i], ainvert, bnegate, op[0], op[1].
+     * Outputs: result[i], zero, overflow, cout.
+     */
+    public static Netlist build(int bits) {
+        if (bits < 2 || bits > 32) throw new IllegalArgumentException("bits must be 2-32: " + bits);
+        Netlist net = new Netlist();
+        for (int i = 0; i < bits; i++) net.addInput(busName("a", i));
+        for (int i = 0; i < bits; i++) net.addInput(busName("b", i));
+        net.addInput("ainvert", "bnegate", "op[0]", "op[1]");
+        for (int i = 0; i < bits; i++) net.addOutput(busName("result", i));
+        net.addOutput("zero", "overflow", "cout");
+
+        // Operation decoder, shared by all slices' result muxes
+        net.addGate(Netlist.GateType.NOT, "op1'", "op[1]");
+        net.addGate(Netlist.GateType.NOT, "op0'", "op[0]");
+        net.addGate(Netlist.GateType.AND, "sel.and", "op1'", "op0'");
+        net.addGate(Netlist.GateType.AND, "sel.or", "op1'", "op[0]");
+        net.addGate(Netlist.GateType.AND, "sel.sum", "op[1]", "op0'");
+        net.addGate(Netlist.GateType.AND, "sel.less", "op[1]", "op[0]");
+
+        Netlist fullAdder = Netlist.parse(Netlist.FULL_ADDER);
+        String[] results = new String[bits];
+        for (int i = 0; i < bits; i++) {
+            String p = "alu" + i + ".";
+            net.addGate(Netlist.GateType.XOR, p + "a", busName("a", i), "ainvert");
+            net.addGate(Netlist.GateType.XOR, p + "b", busName("b", i), "bnegate");
+            net.addGate(Netlist.GateType.AND, p + "and", p + "a", p + "b");
+            net.addGate(Netlist.GateType.OR, p + "or", p + "a", p + "b");
+
+            Map<String, String> ports = new HashMap<>();
+            ports.put("a", p + "a");
+            ports.put("b", p + "b");
+            ports.put("cin", i == 0 ? "bnegate" : busName("c", i));
+            ports.put("sum", p + "sum");
+            ports.put("cout", i == bits - 1 ? "cout" : busName("c", i + 1));
+            net.include(fullAdder, "alu" + i + ".fa", ports);
+
+            String less = i == 0 ? "set" : "0";
+            net.addGate(Netlist.GateType.AND, p + "m0", p + "and", "sel.and");
+            net.addGate(Netlist.GateType.AND, p + "m1", p + "or", "sel.or");
+            net.addGate(Netlist.GateType.AND, p + "m2", p + "sum", "sel.sum");
+            net.addGate(Netlist.GateType.AND, p + "m3", less, "sel.less");
+            results[i] = busName("result", i);
+            net.addGate(Netlist.GateType.OR, results[i], p + "m0", p + "m1", p + "m2", p + "m3");
+        }
+
+        String msb = "alu" + (bits - 1) + ".";
+        net.addGate(Netlist.GateType.XOR, "overflow", busName("c", bits - 1), "cout");
+        net.addGate(Netlist.GateType.XOR, "set", msb + "sum", "overflow");
+        net.addGate(Netlist.GateType.NOR, "zero", results);
+        return net;
+    }
+
+    /** ALU control lines {Ainvert, Bnegate, Op1, Op0}, or null if not built from gates. */
+    public static boolean[] controlLines(Operation op) {
+        switch (op) {
+            case AND: return new boolean[] {false, false, false, false};
+            case OR:  return new boolean[] {false, false, false, true};
+            case ADD: return new boolean[] {false, false, true, false};
+            case SUB: return new boolean[] {false, true, true, false};
+            case SLT: return new boolean[] {false, true, true, true};
+            default:  return null;
+        }
+    }
+
+    // ==================== EXECUTION ====================
+
+    @Override
+    public Result execute(int a, int b, Operation op) {
+        boolean[] control = controlLines(op);
+        if (control == null) {
+            behavioralOps++;
+            return super.execute(a, b, op);
+        }
+        gateLevelOps++;
+        apply(a, b, control);
+        int value = (int) net.getBus("result", WIDTH);
+        boolean arithmetic = op == Operation.ADD || op == Operation.SUB;
+        boolean overflow = arithmetic && net.get("overflow");
+        // ADD reports carry-out; SUB reports borrow, which is the inverted carry-out
+        boolean carry = op == Operation.ADD ? net.get("cout") : op == Operation.SUB && !net.get("cout");
+        return new Result(value, net.get("zero"), value < 0, overflow, carry);
+    }
+
+    private void apply(int a, int b, boolean[] control) {
+        net.setBus("a", WIDTH, a & 0xFFFFFFFFL);
+        net.setBus("b", WIDTH, b & 0xFFFFFFFFL);
+        net.set("ainvert", control[0]);
+        net.set("bnegate", control[1]);
+        net.set("op[1]", control[2]);
+        net.set("op[0]", control[3]);
+        net.settle();
+    }
+
+    // ==================== INSPECTION ====================
+
+    public Netlist getNetlist() { return net; }
+    public int getGateLevelOps() { return gateLevelOps; }
+    public int getBehavioralOps() { return behavioralOps; }
+
+    /**
+     * Evaluate one operation and show it at the gate level: control lines,
+     * the carry chain, the flags and the settle time of each output.
+     */
+    public String inspect(int a, int b, Operation op) {
+        boolean[] control = controlLines(op);
+        if (control == null) {
+            return op + " is not built from gates here (delegated to the behavioral ALU)\n";
+        }
+        Result r = execute(a, b, op);
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("%s  a = %d (0x%08X), b = %d (0x%08X)\n", op, a, a, b, b));
+        sb.append(String.format("  ALU control: Ainvert=%d Bnegate=%d Operation=%d%d\n",
+            bit(control[0]), bit(control[1]), bit(control[2]), bit(control[3])));
+        sb.append("  a'      ").append(bits("alu", ".a")).append("\n");
+        sb.append("  b'      ").append(bits("alu", ".b")).append("\n");
+        sb.append("  carries ").append(carries()).append("\n");
+        sb.append("  result  ").append(bits("result")).append(String.format("  = %d\n", r.value));
+        sb.append(String.format("  zero=%d overflow=%d cout=%d set=%d\n",
+            bit(net.get("zero")), bit(net.get("overflow")), bit(net.get("cout")), bit(net.get("set"))));
+        return sb.toString();
+    }
+
+    /** Settle time (gate delays) of result[31], cout, overflow and zero. */
+    public String timing() {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("%d-bit ALU: %d gates\n", WIDTH, net.getGates().size()));
+        for (String wire : new String[] {busName("result", WIDTH - 1), busName("result", 0),
+                                         "cout", "overflow", "zero"}) {
+            sb.append(String.format("  %-11s settles after %3d gate delays\n", wire, net.arrivalTime(wire)));
+        }
+        List<Netlist.Gate> path = net.criticalPath("zero");
+        sb.append(String.format("  critical path to zero: %s(%s) → ... → %s(%s), %d gates\n",
+            path.get(0).type, path.get(0).output,
+            path.get(path.size() - 1).type, path.get(path.size() - 1).output, path.size()));
+        return sb.toString();
+    }
+
+    private String bits(String bus) {
+        StringBuilder sb = new StringBuilder();
+        for (int i = WIDTH - 1; i >= 0; i--) {
+            sb.append(bit(net.get(busName(bus, i))));
+            if (i % 8 == 0 && i > 0) sb.append('_');
+        }
+        return sb.toString();
+    }
+
+    private String bits(String prefix, String suffix) {
+        StringBuilder sb = new StringBuilder();
+        for (int i = WIDTH - 1; i >= 0; i--) {
+            sb.append(bit(net.get(prefix + i + suffix)));
+            if (i % 8 == 0 && i > 0) sb.append('_');
+        }
+        return sb.toString();
+    }
+
+    /** Carry INTO each slice, MSB first (c0 is Bnegate). */
+    private String carries() {
+        StringBuilder sb = new StringBuilder();
+        for (int i = WIDTH - 1; i >= 0; i--) {
+            sb.append(bit(net.get(i == 0 ? "bnegate" : busName("c", i))));
+            if (i % 8 == 0 && i > 0) sb.append('_');
+        }
+        return sb.toString();
+    }
+
+    private static int bit(boolean b) {
+        return b ? 1 : 0;
+    }
+
+    /** Compare every gate-level operation against the behavioral ALU. */
+    public static int verify(int trials, long seed) {
+        GateLevelALU gates = new GateLevelALU();
+        ALU reference = new ALU();
+        Random random = new Random(seed);
+        int[] edge = {0, 1, -1, Integer.MAX_VALUE, Integer.MIN_VALUE};
+        Operation[] ops = {Operation.AND, Operation.OR, Operation.ADD, Operation.SUB, Operation.SLT};
+        int checked = 0;
+        for (int n = 0; n < trials; n++) {
+            int a = n < edge.length * edge.length ? edge[n / edge.length] : random.nextInt();
+            int b = n < edge.length * edge.length ? edge[n % edge.length] : random.nextInt();
+            for (Operation op : ops) {
+                Result g = gates.execute(a, b, op);
+                Result r = reference.execute(a, b, op);
+                if (g.value != r.value || g.zero != r.zero || g.negative != r.negative
+                        || g.overflow != r.overflow || g.carry != r.carry) {
+                    throw new IllegalStateException(String.format(
+                        "%s %d, %d: gates %s, behavioral %s", op, a, b, g, r));
+                }
+                checked++;
+            }
+        }
+        return checked;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateGateLevelALU() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  THE 32-BIT ALU AT THE GATE LEVEL\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        GateLevelALU alu = new GateLevelALU();
+        sb.append(alu.inspect(7, 5, Operation.SUB)).append("\n");
+        sb.append(alu.inspect(5, 7, Operation.SLT)).append("\n");
+        sb.append(alu.inspect(Integer.MAX_VALUE, 1, Operation.ADD)).append("\n");
+        sb.append("MAX_VALUE + 1: carry into bit 31 but none out → overflow.\n\n");
+
+        sb.append(alu.timing()).append("\n");
+        sb.append(String.format("Cross-checked against ALU.java: %d operations agree.\n\n",
+            verify(300, 4160)));
+
+        // Same program, two ALU backends
+        int[] program = {
+            Instruction.addi(10, 0, 0).getRaw(),                           // a0 = 0
+            Instruction.addi(11, 0, 1).getRaw(),                           // a1 = 1
+            Instruction.addi(12, 0, 6).getRaw(),                           // a2 = 6
+            Instruction.add(10, 10, 11).getRaw(),                          // loop: a0 += a1
+            Instruction.addi(11, 11, 1).getRaw(),                          // a1++
+            Instruction.rType(0b0110011, 13, 0b010, 11, 12, 0).getRaw(),   // slt a3, a1, a2
+            Instruction.beq(13, 0, 8).getRaw(),                            // done if !a3
+            Instruction.beq(0, 0, -16).getRaw(),                           // else loop
+            0x00100073                                                     // ebreak
+        };
+        SingleCycleProcessor behavioral = new SingleCycleProcessor();
+        behavioral.getMemory().loadProgram(program, 0);
+        behavioral.run(1000);
+        SingleCycleProcessor gateLevel = new SingleCycleProcessor(
+            new MainMemory(), alu);
+        gateLevel.getMemory().loadProgram(program, 0);
+        int before = alu.getGateLevelOps();
+        gateLevel.run(1000);
+
+        sb.append("Sum of 1 to 5 on the single-cycle datapath:\n");
+        sb.append(String.format("  behavioral ALU: a0 = %d\n", behavioral.getRegisterFile().read(10)));
+        sb.append(String.format("  gate-level ALU: a0 = %d  (%d ALU operations through %d gates)\n",
+            gateLevel.getRegisterFile().read(10), alu.getGateLevelOps() - before,
+            alu.getNetlist().getGates().size()));
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    private static final String USAGE = "Usage: GateLevelALU [op a b]";
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateGateLevelALU());
+            return;
+        }
+        if (args.length != 3) usage("Need an operation and two operands, e.g. add 5 -3");
+        Operation op = parseOperation(args[0]);
+        int a = parseOperand(args[1]);
+        int b = parseOperand(args[2]);
+        System.out.print(new GateLevelALU().inspect(a, b, op));
+    }
+
+    private static Operation parseOperation(String s) {
+        for (Operation op : Operation.values()) {
+            if (op.name().equalsIgnoreCase(s)) return op;
+        }
+        StringBuilder valid = new StringBuilder();
+        for (Operation op : Operation.values()) {
+            if (valid.length() > 0) valid.append(", ");
+            valid.append(op.name().toLowerCase(Locale.ROOT));
+        }
+        usage("Unknown operation: " + s + "\nOperations: " + valid + " (and, or, add, sub and slt are built from gates)");
+        return null;
+    }
+
+    private static int parseOperand(String s) {
+        try {
+            return Integer.parseInt(s);
+        } catch (NumberFormatException e) {
+            usage("Not a 32-bit integer: " + s);
+            return 0;
+        }
+    }
+
+    private static void usage(String message) {
+        System.err.println(message + "\n" + USAGE);
+        System.exit(2);
+    }
+}
@@ -0,0 +1,392 @@
+package computerdesign.logic;
+
+import java.io.IOException;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.LinkedHashMap;
+import java.util.LinkedHashSet;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+import java.util.TreeMap;
+
+/**
+ * Hazards - Event-driven simulation with gate delays, and glitch detection.
+ *
+ * Covers learning goals: T3.2 (Combinational Logic - K-map hazard covers)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHY A CORRECT CIRCUIT CAN GLITCH
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   f = AB + A'C  with B = C = 1, A falls 1 → 0:
+ *
+ *   A  ‾‾‾\______            The output should stay 1 (f = B = C = 1 on
+ *   A' ____/‾‾‾‾‾  (+1)      both sides of the change), but A' rises one
+ *   AB ‾‾‾‾\_____  (+1)      NOT-delay after AB has already fallen:
+ *   A'C______/‾‾‾  (+2)
+ *   f  ‾‾‾‾‾‾\_/‾  (+2..3)   for one gate delay NEITHER term is 1.
+ *
+ *   STATIC-1 hazard: output should stay 1 but briefly drops to 0 (SOP forms)
+ *   STATIC-0 hazard: output should stay 0 but briefly rises to 1 (POS forms)
+ *   DYNAMIC hazard:  output should change once but changes 3+ times
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE K-MAP FIX
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *         BC                       The two groups AB and A'C touch but do not
+ *        00  01  11  10            overlap: moving between them (A changes)
+ *   A=0 │ 0 │ 1 │ 1 │ 0 │          hands over from one gate to another.
+ *   A=1 │ 0 │ 0 │ 1 │ 1 │          Adding the redundant CONSENSUS term BC
+ *                 └─┘              covers the boundary, so some gate holds
+ *                  BC              f = 1 throughout the change.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * EVENT-DRIVEN SIMULATION
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Netlist.settle() computes the FINAL values only. Here every value change
+ * is an event at a time; a gate whose input changes at time t computes its
+ * new output and schedules it at t + gate delay (transport delay: every
+ * pulse, however short, is passed on - the worst case for glitches).
+ * The inputs change at t = 1, so column 0 of a trace is the steady state
+ * before the change.
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class Hazards {
+
+    /** Time at which the inputs change; t = 0 shows the circuit before. */
+    public static final int CHANGE_TIME = 1;
+
+    /** Events after which the simulation gives up (oscillation guard). */
+    public static final int MAX_EVENTS = 100_000;
+
+    public enum Kind {
+        STATIC_1("static-1"), STATIC_0("static-0"), DYNAMIC("dynamic");
+
+        public final String label;
+
+        Kind(String label) {
+            this.label = label;
+        }
+    }
+
+    /** A wire changing value at a time (in gate delays). */
+    public static class Change {
+        public final int time;
+        public final boolean value;
+
+        Change(int time, boolean value) {
+            this.time = time;
+            this.value = value;
+        }
+    }
+
+    /** A glitch on one output for one input transition. */
+    public static class Hazard {
+        public final String output;
+        public final Kind kind;
+        public final String before;    // input vector before the transition
+        public final String after;     // ... and after
+        public final int start;        // first and last output change
+        public final int end;
+
+        Hazard(String output, Kind kind, String before, String after, int start, int end) {
+            this.output = output;
+            this.kind = kind;
+            this.before = before;
+            this.after = after;
+            this.start = start;
+            this.end = end;
+        }
+
+        @Override
+        public String toString() {
+            return String.format("%s hazard on %s for %s → %s: output changes between t=%d and t=%d",
+                kind.label, output, before, after, start, end);
+        }
+    }
+
+    /** The outcome of one input transition. */
+    public static class Result {
+        public final Map<String, List<Change>> waveforms;    // every wire's changes
+        public final Map<String, Boolean> initial;
+        public final List<Hazard> hazards;
+        public final int settleTime;
+
+        Result(Map<String, List<Change>> waveforms, Map<String, Boolean> initial, List<Hazard> hazards,
+               int settleTime) {
+            this.waveforms = waveforms;
+            this.initial = initial;
+            this.hazards = hazards;
+            this.settleTime = settleTime;
+        }
+
+        /** Value of a wire over time 0..settleTime+1, for a TimingDiagram. */
+        public SignalTrace trace(List<String> wires) {
+            SignalTrace trace = new SignalTrace(wires.toArray(new String[0]));
+            for (int t = 0; t <= settleTime + 1; t++) {
+                for (String w : wires) trace.record(w, valueAt(w, t));
+            }
+            return trace;
+        }
+
+        /** Value after all events at time t have been applied. */
+        public boolean valueAt(String wire, int t) {
+            boolean v = initial.get(wire);
+            for (Change c : waveforms.get(wire)) {
+                if (c.time <= t) v = c.value;
+            }
+            return v;
+        }
+    }
+
+    private final Netlist net;
+    private final List<String> wires = new ArrayList<>();
+    private final Map<String, List<Netlist.Gate>> fanout = new LinkedHashMap<>();
+
+    public Hazards(Netlist net) {
+        if (net.isSequential()) {
+            throw new IllegalArgumentException("Hazard analysis needs a combinational circuit");
+        }
+        this.net = net;
+        wires.addAll(net.getInputs());
+        for (Netlist.Gate g : net.getGates()) {
+            if (!wires.contains(g.output)) wires.add(g.output);
+            for (String in : g.inputs) fanout.computeIfAbsent(in, k -> new ArrayList<>()).add(g);
+        }
+    }
+
+    // ==================== SIMULATION ====================
+
+    /** Simulate a change of the inputs (bit strings in input order, e.g. "111" → "011"). */
+    public Result simulate(String before, String after) {
+        List<String> inputs = net.getInputs();
+        if (before.length() != inputs.size() || after.length() != inputs.size()) {
+            throw new IllegalArgumentException("Expected " + inputs.size() + " input bits ("
+                + String.join(" ", inputs) + ")");
+        }
+        Map<String, Boolean> from = new LinkedHashMap<>();
+        Map<String, Boolean> to = new LinkedHashMap<>();
+        for (int i = 0; i < inputs.size(); i++) {
+            from.put(inputs.get(i), bit(before, i));
+            to.put(inputs.get(i), bit(after, i));
+        }
+        return simulate(from, to);
+    }
+
+    private static boolean bit(String bits, int i) {
+        char c = bits.charAt(i);
+        if (c != '0' && c != '1') throw new IllegalArgumentException("Not a bit: " + c);
+        return c == '1';
+    }
+
+    public Result simulate(Map<String, Boolean> before, Map<String, Boolean> after) {
+        // steady state before the change
+        net.evaluate(before);
+        Map<String, Boolean> values = new LinkedHashMap<>();
+        for (String w : wires) values.put(w, net.get(w));
+        values.put("0", false);
+        values.put("1", true);
+        Map<String, Boolean> initial = new LinkedHashMap<>(values);
+
+        Map<String, List<Change>> waveforms = new LinkedHashMap<>();
+        for (String w : wires) waveforms.put(w, new ArrayList<>());
+
+        TreeMap<Integer, Map<String, Boolean>> events = new TreeMap<>();
+        for (Map.Entry<String, Boolean> e : after.entrySet()) {
+            if (!values.containsKey(e.getKey()) || !net.getInputs().contains(e.getKey())) {
+                throw new IllegalArgumentException("No such input: " + e.getKey());
+            }
+            events.computeIfAbsent(CHANGE_TIME, k -> new LinkedHashMap<>()).put(e.getKey(), e.getValue());
+        }
+
+        int processed = 0;
+        int last = 0;
+        while (!events.isEmpty()) {
+            Map.Entry<Integer, Map<String, Boolean>> now = events.pollFirstEntry();
+            int t = now.getKey();
+            Set<Netlist.Gate> affected = new LinkedHashSet<>();
+            for (Map.Entry<String, Boolean> e : now.getValue().entrySet()) {
+                if (values.get(e.getKey()).equals(e.getValue())) continue;
+                values.put(e.getKey(), e.getValue());
+                waveforms.get(e.getKey()).add(new Change(t, e.getValue()));
+                last = t;
+                affected.addAll(fanout.getOrDefault(e.getKey(), Collections.emptyList()));
+                if (++processed > MAX_EVENTS) {
+                    throw new IllegalStateException("No stable state after " + MAX_EVENTS + " events");
+                }
+            }
+            for (Netlist.Gate g : affected) {
+                boolean[] in = new boolean[g.inputs.size()];
+                int[] index = new int[in.length];
+                for (int i = 0; i < in.length; i++) {
+                    in[i] = values.get(g.inputs.get(i));
+                    index[i] = i;
+                }
+                events.computeIfAbsent(t + g.delay, k -> new LinkedHashMap<>())
+                      .put(g.output, g.type.eval(in, index));
+            }
+        }
+
+        String from = vector(before);
+        String to = vector(after, before);
+        List<Hazard> hazards = new ArrayList<>();
+        for (String out : net.getOutputs()) {
+            List<Change> changes = waveforms.get(out);
+            if (changes == null || changes.isEmpty()) continue;
+            boolean start = initial.get(out);
+            boolean end = values.get(out);
+            Kind kind = null;
+            if (start == end) kind = start ? Kind.STATIC_1 : Kind.STATIC_0;
+            else if (changes.size() > 1) kind = Kind.DYNAMIC;
+            if (kind != null) {
+                hazards.add(new Hazard(out, kind, from, to, changes.get(0).time,
+                    changes.get(changes.size() - 1).time));
+            }
+        }
+        initial.remove("0");
+        initial.remove("1");
+        return new Result(waveforms, initial, hazards, last);
+    }
+
+    private String vector(Map<String, Boolean> values) {
+        return vector(values, values);
+    }
+
+    private String vector(Map<String, Boolean> values, Map<String, Boolean> fallback) {
+        StringBuilder sb = new StringBuilder();
+        for (String in : net.getInputs()) {
+            Boolean v = values.containsKey(in) ? values.get(in) : fallback.get(in);
+            sb.append(Boolean.TRUE.equals(v) ? '1' : '0');
+        }
+        return sb.toString();
+    }
+
+    // ==================== SCANNING ====================
+
+    /**
+     * Try every single-input change from every input combination and collect
+     * the hazards - the transitions a K-map hazard cover has to protect.
+     */
+    public List<Hazard> scan() {
+        int n = net.getInputs().size();
+        if (n > Netlist.MAX_TRUTH_TABLE_INPUTS) {
+            throw new IllegalStateException(n + " inputs is too many to scan");
+        }
+        List<Hazard> all = new ArrayList<>();
+        for (int v = 0; v < 1 << n; v++) {
+            for (int flip = 0; flip < n; flip++) {
+                all.addAll(simulate(bits(v, n), bits(v ^ 1 << (n - 1 - flip), n)).hazards);
+            }
+        }
+        return all;
+    }
+
+    private static String bits(int value, int n) {
+        StringBuilder sb = new StringBuilder();
+        for (int i = n - 1; i >= 0; i--) sb.append(value >> i & 1);
+        return sb.toString();
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    /** f = AB + A'C: a 2:1 multiplexer with a static-1 hazard. */
+    public static final String MUX_HAZARD =
+        "input  A B C\n" +
+        "output f\n" +
+        "nA = not A\n" +
+        "t1 = and A B\n" +
+        "t2 = and nA C\n" +
+        "f  = or t1 t2\n";
+
+    /** The same function with the consensus term BC added. */
+    public static final String MUX_COVERED = MUX_HAZARD.replace("f  = or t1 t2\n",
+        "t3 = and B C          # consensus term: redundant, but covers the A change\n" +
+        "f  = or t1 t2 t3\n");
+
+    /** f = (A + B)(A' + C): the product-of-sums dual, with a static-0 hazard. */
+    public static final String POS_HAZARD =
+        "input  A B C\n" +
+        "output f\n" +
+        "nA = not A\n" +
+        "s1 = or A B\n" +
+        "s2 = or nA C\n" +
+        "f  = and s1 s2\n";
+
+    public static String demonstrateHazards() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  HAZARDS: GLITCHES FROM GATE DELAYS\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("f = AB + A'C (every gate: 1 delay)\n\n").append(MUX_HAZARD).append("\n");
+        Hazards mux = new Hazards(Netlist.parse(MUX_HAZARD));
+        Result r = mux.simulate("111", "011");
+        sb.append("A falls with B = C = 1 (ABC 111 → 011); f should stay 1:\n\n");
+        List<String> shown = new ArrayList<>();
+        Collections.addAll(shown, "A", "nA", "t1", "t2", "f");
+        sb.append(new TimingDiagram(r.trace(shown)).cellWidth(3).toAscii()).append("\n");
+        for (Hazard h : r.hazards) sb.append("  ").append(h).append("\n");
+
+        sb.append("\nAll single-input changes, found by scan():\n");
+        for (Hazard h : mux.scan()) sb.append("  ").append(h).append("\n");
+        sb.append("  (A rising is safe: AB turns on before A'C turns off)\n\n");
+
+        sb.append("With the consensus term BC added:\n\n");
+        Hazards covered = new Hazards(Netlist.parse(MUX_COVERED));
+        Result fixed = covered.simulate("111", "011");
+        shown.add(4, "t3");
+        sb.append(new TimingDiagram(fixed.trace(shown)).cellWidth(3).toAscii()).append("\n");
+        sb.append(String.format("  hazards found by scan(): %d\n\n", covered.scan().size()));
+
+        sb.append("Product of sums f = (A + B)(A' + C), the dual case:\n");
+        for (Hazard h : new Hazards(Netlist.parse(POS_HAZARD)).scan()) sb.append("  ").append(h).append("\n");
+        sb.append("  (fixed the same way with the consensus sum (B + C))\n");
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: Hazards [file.net [before after]]  - scan, or one transition
+     * e.g.   Hazards mux.net 111 011
+     */
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateHazards());
+            return;
+        }
+        Netlist net = null;
+        try {
+            net = Netlist.load(Paths.get(args[0]));
+        } catch (IOException e) {
+            System.err.println(args[0] + ": " + (e instanceof NoSuchFileException ? "no such file" : e.getMessage()));
+            System.exit(2);
+        } catch (IllegalArgumentException | IllegalStateException e) {
+            System.err.println(args[0] + ": " + e.getMessage());
+            System.exit(2);
+        }
+        Hazards hazards = new Hazards(net);
+        if (args.length == 3) {
+            Result r = null;
+            try {
+                r = hazards.simulate(args[1], args[2]);
+            } catch (IllegalArgumentException e) {
+                System.err.println(e.getMessage() + "\nUsage: Hazards [file.net [before after]]");
+                System.exit(2);
+            }
+            List<String> shown = new ArrayList<>(net.getInputs());
+            shown.addAll(net.getOutputs());
+            System.out.println(new TimingDiagram(r.trace(shown)).cellWidth(3).toAscii());
+            for (Hazard h : r.hazards) System.out.println(h);
+        } else {
+            List<Hazard> found = hazards.scan();
+            for (Hazard h : found) System.out.println(h);
+            System.out.println(found.size() + " hazard(s)");
+        }
+    }
+}
@@ -0,0 +1,766 @@
+package computerdesign.logic;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.ArrayDeque;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.Deque;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * Netlist - Gate-level circuit simulator.
+ *
+ * Covers learning goals: T3.2 (Combinational Logic), T4.2 (Sequential Logic)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHAT IS A NETLIST?
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * A netlist is the "schematic as text": a list of gates and the wires (nets)
+ * connecting them. It is what synthesis tools produce from Verilog/VHDL and
+ * what the chip is finally built from.
+ *
+ *   Half adder schematic:                   Netlist text:
+ *
+ *   a ──┬─────╲                             input  a b
+ *       │      )XOR──── s                   output s c
+ *   b ──┼──┬──╱                             s = xor a b
+ *       │  │                                c = and a b
+ *       └──┴───AND───── c
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * TEXT FORMAT
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   # comment                   Everything after # is ignored
+ *   input  a b c                Primary inputs (set by the simulator)
+ *   output y z                  Observed outputs (shown in truth tables)
+ *   y = nand a b c              Gate: output = TYPE input...
+ *   q = dff d                   D flip-flop: q takes d on each clock edge
+ *
+ *   Gate types: and or not nand nor xor xnor buf dff
+ *   The wire names 0 and 1 are constant low/high.
+ *   Bus bits use brackets: a[0] a[1] ... (see busName).
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * HOW EVALUATION WORKS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * COMBINATIONAL (no loops): gates are sorted topologically ("levelized") so
+ * every gate is evaluated after all of its inputs. One pass settles the circuit.
+ *
+ *   Level 0: inputs, constants, flip-flop outputs
+ *   Level 1: gates fed only by level 0          ← evaluate first
+ *   Level 2: gates fed by level ≤ 1             ← then these
+ *   ...
+ *
+ * FEEDBACK (cross-coupled latches): a loop has no topological order. The
+ * simulator re-evaluates the loop gates until nothing changes. If the values
+ * never stop changing the circuit oscillates (e.g. a ring of 3 inverters) and
+ * an exception is thrown.
+ *
+ * SEQUENTIAL (flip-flops): a DFF breaks the loop in TIME instead of in space.
+ * clock() samples every D input at once, then updates all Q outputs and lets
+ * the combinational logic settle again - exactly like a real clock edge.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class Netlist {
+
+    /** Exhaustive truth tables beyond this are not useful to read. */
+    public static final int MAX_TRUTH_TABLE_INPUTS = 12;
+
+    // ==================== GATE TYPES ====================
+
+    public enum GateType {
+        AND(2, Integer.MAX_VALUE),
+        OR(2, Integer.MAX_VALUE),
+        NOT(1, 1),
+        NAND(2, Integer.MAX_VALUE),
+        NOR(2, Integer.MAX_VALUE),
+        XOR(2, Integer.MAX_VALUE),
+        XNOR(2, Integer.MAX_VALUE),
+        BUF(1, 1),
+        DFF(1, 1);
+
+        public final int minInputs;
+        public final int maxInputs;
+
+        GateType(int minInputs, int maxInputs) {
+            this.minInputs = minInputs;
+            this.maxInputs = maxInputs;
+        }
+
+        /** Evaluate this gate over the given input values. */
+        public boolean eval(boolean[] values, int[] inputs) {
+            switch (this) {
+                case AND:
+                case NAND: {
+                    boolean r = true;
+                    for (int i : inputs) r &= values[i];
+                    return this == AND ? r : !r;
+                }
+                case OR:
+                case NOR: {
+                    boolean r = false;
+                    for (int i : inputs) r |= values[i];
+                    return this == OR ? r : !r;
+                }
+                case XOR:
+                case XNOR: {
+                    boolean r = false;
+                    for (int i : inputs) r ^= values[i];
+                    return this == XOR ? r : !r;
+                }
+                case NOT: return !values[inputs[0]];
+                case BUF:
+                case DFF: return values[inputs[0]];
+                default: throw new IllegalStateException("Unknown gate " + this);
+            }
+        }
+
+        public static GateType parse(String name) {
+            try {
+                return valueOf(name.toUpperCase());
+            } catch (IllegalArgumentException e) {
+                throw new IllegalArgumentException("Unknown gate type: " + name);
+            }
+        }
+    }
+
+    // ==================== GATE ====================
+
+    /**
+     * One gate instance. Delay (in gate-delay units) is used by timing analysis.
+     */
+    public static class Gate {
+        public final GateType type;
+        public final String output;
+        public final List<String> inputs;
+        public int delay = 1;
+
+        int out;
+        int[] in;
+
+        Gate(GateType type, String output, List<String> inputs) {
+            this.type = type;
+            this.output = output;
+            this.inputs = Collections.unmodifiableList(new ArrayList<>(inputs));
+        }
+
+        @Override
+        public String toString() {
+            return output + " = " + type.name().toLowerCase() + " " + String.join(" ", inputs);
+        }
+    }
+
+    // ==================== STATE ====================
+
+    private final Map<String, Integer> wires = new LinkedHashMap<>();
+    private final List<String> inputs = new ArrayList<>();
+    private final List<String> outputs = new ArrayList<>();
+    private final List<Gate> gates = new ArrayList<>();
+    private boolean[] values = new boolean[16];
+
+    private List<Gate> order;          // evaluation order, null = needs rebuild
+    private List<Gate> flipFlops;
+    private boolean hasFeedback;
+    private int settleIterations;
+
+    public Netlist() {
+        wire("0");
+        wire("1");
+        values[1] = true;
+    }
+
+    // ==================== BUILDING ====================
+
+    public Netlist addInput(String... names) {
+        for (String name : names) {
+            if (!inputs.contains(name)) inputs.add(name);
+            wire(name);
+        }
+        order = null;
+        return this;
+    }
+
+    public Netlist addOutput(String... names) {
+        for (String name : names) {
+            if (!outputs.contains(name)) outputs.add(name);
+            wire(name);
+        }
+        return this;
+    }
+
+    public Gate addGate(GateType type, String output, String... ins) {
+        if (ins.length < type.minInputs || ins.length > type.maxInputs) {
+            throw new IllegalArgumentException(String.format("%s gate '%s' needs %s input(s), got %d",
+                type, output, type.minInputs == type.maxInputs ? String.valueOf(type.minInputs)
+                                                            : "at least " + type.minInputs, ins.length));
+        }
+        Gate gate = new Gate(type, output, Arrays.asList(ins));
+        gate.out = wire(output);
+        gate.in = new int[ins.length];
+        for (int i = 0; i < ins.length; i++) gate.in[i] = wire(ins[i]);
+        gates.add(gate);
+        order = null;
+        return gate;
+    }
+
+    /**
+     * Copy another netlist into this one as a sub-circuit.
+     * Ports listed in bindings are connected to the given wires; every other
+     * wire of the sub-circuit is renamed "instance.wire" to keep it private.
+     */
+    public void include(Netlist sub, String instance, Map<String, String> bindings) {
+        for (Gate g : sub.gates) {
+            String[] ins = new String[g.inputs.size()];
+            for (int i = 0; i < ins.length; i++) ins[i] = rename(g.inputs.get(i), instance, bindings);
+            addGate(g.type, rename(g.output, instance, bindings), ins).delay = g.delay;
+        }
+    }
+
+    private static String rename(String wire, String instance, Map<String, String> bindings) {
+        if (wire.equals("0") || wire.equals("1")) return wire;
+        String bound = bindings.get(wire);
+        return bound != null ? bound : instance + "." + wire;
+    }
+
+    /** Name of bit i of a bus, e.g. busName("a", 3) = "a[3]". */
+    public static String busName(String bus, int bit) {
+        return bus + "[" + bit + "]";
+    }
+
+    private int wire(String name) {
+        Integer index = wires.get(name);
+        if (index == null) {
+            index = wires.size();
+            wires.put(name, index);
+            if (index >= values.length) values = Arrays.copyOf(values, values.length * 2);
+        }
+        return index;
+    }
+
+    // ==================== PARSING ====================
+
+    public static Netlist parse(String text) {
+        Netlist net = new Netlist();
+        String[] lines = text.split("\n");
+        for (int n = 0; n < lines.length; n++) {
+            String line = lines[n];
+            int hash = line.indexOf('#');
+            if (hash >= 0) line = line.substring(0, hash);
+            line = line.trim();
+            if (line.isEmpty()) continue;
+            try {
+                parseLine(net, line);
+            } catch (IllegalArgumentException e) {
+                throw new IllegalArgumentException("line " + (n + 1) + ": " + e.getMessage());
+            }
+        }
+        net.validate();
+        return net;
+    }
+
+    public static Netlist load(Path path) throws IOException {
+        return parse(new String(Files.readAllBytes(path), StandardCharsets.UTF_8));
+    }
+
+    private static void parseLine(Netlist net, String line) {
+        String[] tokens = line.split("\\s+");
+        switch (tokens[0].toLowerCase()) {
+            case "input":
+                net.addInput(Arrays.copyOfRange(tokens, 1, tokens.length));
+                return;
+            case "output":
+                net.addOutput(Arrays.copyOfRange(tokens, 1, tokens.length));
+                return;
+            default:
+                break;
+        }
+        if (tokens.length < 3 || !tokens[1].equals("=")) {
+            throw new IllegalArgumentException("expected 'wire = gate inputs...': " + line);
+        }
+        net.addGate(GateType.parse(tokens[2]), tokens[0], Arrays.copyOfRange(tokens, 3, tokens.length));
+    }
+
+    /**
+     * Check the structural rules every real circuit obeys.
+     */
+    public void validate() {
+        Map<String, Gate> drivers = new LinkedHashMap<>();
+        for (Gate g : gates) {
+            if (g.output.equals("0") || g.output.equals("1")) {
+                throw new IllegalArgumentException("gate drives constant wire " + g.output);
+            }
+            if (inputs.contains(g.output)) {
+                throw new IllegalArgumentException("gate drives primary input " + g.output);
+            }
+            Gate other = drivers.put(g.output, g);
+            if (other != null) {
+                throw new IllegalArgumentException("wire " + g.output + " has two drivers: '"
+                    + other + "' and '" + g + "'");
+            }
+        }
+        for (String name : wires.keySet()) {
+            if (name.equals("0") || name.equals("1") || inputs.contains(name)) continue;
+            if (!drivers.containsKey(name)) {
+                throw new IllegalArgumentException("wire " + name + " is never driven");
+            }
+        }
+    }
+
+    // ==================== ORDERING ====================
+
+    private void buildOrder() {
+        if (order != null) return;
+        flipFlops = new ArrayList<>();
+        List<Gate> comb = new ArrayList<>();
+        for (Gate g : gates) {
+            if (g.type == GateType.DFF) flipFlops.add(g);
+            else comb.add(g);
+        }
+
+        // Kahn's algorithm: a gate is ready once every input is a source or already computed
+        Map<Integer, List<Gate>> fanout = new LinkedHashMap<>();
+        int[] pending = new int[comb.size()];
+        boolean[] driven = new boolean[wires.size()];
+        for (Gate g : comb) driven[g.out] = true;
+        Deque<Gate> ready = new ArrayDeque<>();
+        for (int k = 0; k < comb.size(); k++) {
+            Gate g = comb.get(k);
+            for (int i : g.in) {
+                if (driven[i]) {
+                    pending[k]++;
+                    fanout.computeIfAbsent(i, x -> new ArrayList<>()).add(g);
+                }
+            }
+            if (pending[k] == 0) ready.add(g);
+        }
+        Map<Gate, Integer> indexOf = new LinkedHashMap<>();
+        for (int k = 0; k < comb.size(); k++) indexOf.put(comb.get(k), k);
+
+        List<Gate> sorted = new ArrayList<>();
+        while (!ready.isEmpty()) {
+            Gate g = ready.poll();
+            sorted.add(g);
+            for (Gate next : fanout.getOrDefault(g.out, Collections.emptyList())) {
+                int k = indexOf.get(next);
+                // a gate may list the same wire twice
+                for (int i : next.in) {
+                    if (i == g.out) pending[k]--;
+                }
+                if (pending[k] == 0) ready.add(next);
+            }
+        }
+        hasFeedback = sorted.size() < comb.size();
+        if (hasFeedback) {
+            for (Gate g : comb) {
+                if (!sorted.contains(g)) sorted.add(g);
+            }
+        }
+        order = sorted;
+    }
+
+    // ==================== SIMULATION ====================
+
+    /**
+     * Propagate values until every gate output is consistent with its inputs.
+     */
+    public void settle() {
+        buildOrder();
+        if (!hasFeedback) {
+            for (Gate g : order) values[g.out] = g.type.eval(values, g.in);
+            settleIterations = 1;
+            return;
+        }
+        int limit = 2 * order.size() + 2;
+        for (settleIterations = 1; settleIterations <= limit; settleIterations++) {
+            boolean changed = false;
+            for (Gate g : order) {
+                boolean v = g.type.eval(values, g.in);
+                if (v != values[g.out]) {
+                    values[g.out] = v;
+                    changed = true;
+                }
+            }
+            if (!changed) return;
+        }
+        throw new IllegalStateException("Circuit does not settle (oscillation) after "
+            + limit + " passes");
+    }
+
+    /**
+     * Rising clock edge: all flip-flops sample D simultaneously, then settle.
+     */
+    public void clock() {
+        buildOrder();
+        boolean[] sampled = new boolean[flipFlops.size()];
+        for (int k = 0; k < sampled.length; k++) {
+            sampled[k] = values[flipFlops.get(k).in[0]];
+        }
+        for (int k = 0; k < sampled.length; k++) values[flipFlops.get(k).out] = sampled[k];
+        settle();
+    }
+
+    /** Clear all wires (and flip-flop state) to 0. */
+    public void reset() {
+        Arrays.fill(values, false);
+        values[1] = true;
+    }
+
+    /** Force the stored value of a flip-flop (by its output wire); settle() afterwards. */
+    public void setState(String flipFlop, boolean value) {
+        buildOrder();
+        for (Gate g : flipFlops) {
+            if (g.output.equals(flipFlop)) {
+                values[g.out] = value;
+                return;
+            }
+        }
+        throw new IllegalArgumentException("No such flip-flop: " + flipFlop);
+    }
+
+    public void set(String input, boolean value) {
+        Integer index = wires.get(input);
+        if (index == null || !inputs.contains(input)) {
+            throw new IllegalArgumentException("No such input: " + input);
+        }
+        values[index] = value;
+    }
+
+    public boolean get(String wire) {
+        Integer index = wires.get(wire);
+        if (index == null) throw new IllegalArgumentException("No such wire: " + wire);
+        return values[index];
+    }
+
+    /** Set bus bits name[0..width-1] from an integer (bit 0 = LSB). */
+    public void setBus(String bus, int width, long value) {
+        for (int i = 0; i < width; i++) set(busName(bus, i), (value >> i & 1) == 1);
+    }
+
+    public long getBus(String bus, int width) {
+        long value = 0;
+        for (int i = 0; i < width; i++) {
+            if (get(busName(bus, i))) value |= 1L << i;
+        }
+        return value;
+    }
+
+    /**
+     * Apply inputs (in declaration order), settle, and return outputs (in order).
+     */
+    public boolean[] evaluate(boolean... inputValues) {
+        if (inputValues.length != inputs.size()) {
+            throw new IllegalArgumentException("Expected " + inputs.size() + " inputs, got "
+                + inputValues.length);
+        }
+        for (int i = 0; i < inputValues.length; i++) set(inputs.get(i), inputValues[i]);
+        settle();
+        boolean[] result = new boolean[outputs.size()];
+        for (int i = 0; i < result.length; i++) result[i] = get(outputs.get(i));
+        return result;
+    }
+
+    public Map<String, Boolean> evaluate(Map<String, Boolean> inputValues) {
+        for (Map.Entry<String, Boolean> e : inputValues.entrySet()) set(e.getKey(), e.getValue());
+        settle();
+        Map<String, Boolean> result = new LinkedHashMap<>();
+        for (String out : outputs) result.put(out, get(out));
+        return result;
+    }
+
+    // ==================== TIMING ====================
+
+    /**
+     * Static timing analysis: the time (in gate delays) at which each wire is
+     * guaranteed to be stable, assuming all inputs change at t = 0.
+     *
+     *   arrival(input) = 0
+     *   arrival(gate output) = max(arrival(gate inputs)) + gate delay
+     *
+     * The largest arrival time is the critical path that limits the clock rate.
+     */
+    public Map<String, Integer> arrivalTimes() {
+        int[] t = computeArrivals();
+        Map<String, Integer> result = new LinkedHashMap<>();
+        for (Map.Entry<String, Integer> e : wires.entrySet()) result.put(e.getKey(), t[e.getValue()]);
+        return result;
+    }
+
+    public int arrivalTime(String wire) {
+        Integer index = wires.get(wire);
+        if (index == null) throw new IllegalArgumentException("No such wire: " + wire);
+        return computeArrivals()[index];
+    }
+
+    /** The chain of gates that determines when the given wire settles, input first. */
+    public List<Gate> criticalPath(String wire) {
+        int[] t = computeArrivals();
+        Map<Integer, Gate> driver = new LinkedHashMap<>();
+        for (Gate g : gates) driver.put(g.out, g);
+        List<Gate> path = new ArrayList<>();
+        Integer current = wires.get(wire);
+        if (current == null) throw new IllegalArgumentException("No such wire: " + wire);
+        Gate g = driver.get(current);
+        while (g != null && g.type != GateType.DFF) {
+            path.add(0, g);
+            int latest = g.in[0];
+            for (int i : g.in) if (t[i] > t[latest]) latest = i;
+            g = t[latest] > 0 ? driver.get(latest) : null;
+        }
+        return path;
+    }
+
+    private int[] computeArrivals() {
+        buildOrder();
+        if (hasFeedback) {
+            throw new IllegalStateException("Timing analysis needs a loop-free circuit");
+        }
+        int[] t = new int[wires.size()];
+        for (Gate g : order) {
+            int latest = 0;
+            for (int i : g.in) latest = Math.max(latest, t[i]);
+            t[g.out] = latest + g.delay;
+        }
+        return t;
+    }
+
+    // ==================== INSPECTION ====================
+
+    public List<String> getInputs() { return Collections.unmodifiableList(inputs); }
+    public List<String> getOutputs() { return Collections.unmodifiableList(outputs); }
+    public List<Gate> getGates() { return Collections.unmodifiableList(gates); }
+
+    /** The D flip-flops, in the order they were added. */
+    public List<Gate> getFlipFlops() {
+        buildOrder();
+        return Collections.unmodifiableList(flipFlops);
+    }
+
+    public boolean isSequential() {
+        buildOrder();
+        return !flipFlops.isEmpty() || hasFeedback;
+    }
+
+    public boolean hasFeedback() {
+        buildOrder();
+        return hasFeedback;
+    }
+
+    /** Passes needed by the last settle() (1 for loop-free circuits). */
+    public int getSettleIterations() { return settleIterations; }
+
+    /** Gates in evaluation (topological) order. */
+    public List<Gate> getEvaluationOrder() {
+        buildOrder();
+        return Collections.unmodifiableList(order);
+    }
+
+    /**
+     * Enumerate every input combination. Flip-flop state is held as-is.
+     */
+    public String truthTable() {
+        if (inputs.size() > MAX_TRUTH_TABLE_INPUTS) {
+            throw new IllegalStateException(inputs.size() + " inputs is too many for a truth table");
+        }
+        StringBuilder sb = new StringBuilder();
+        StringBuilder header = new StringBuilder();
+        for (String in : inputs) header.append(in).append(' ');
+        header.append("│");
+        for (String out : outputs) header.append(' ').append(out);
+        sb.append(header).append("\n");
+        int inWidth = header.indexOf("│");
+        sb.append("─".repeat(inWidth)).append("┼")
+          .append("─".repeat(header.length() - inWidth - 1)).append("\n");
+
+        int rows = 1 << inputs.size();
+        for (int row = 0; row < rows; row++) {
+            boolean[] in = new boolean[inputs.size()];
+            for (int i = 0; i < in.length; i++) in[i] = (row >> (in.length - 1 - i) & 1) == 1;
+            boolean[] out = evaluate(in);
+            StringBuilder line = new StringBuilder();
+            for (int i = 0; i < in.length; i++) {
+                line.append(String.format("%-" + (inputs.get(i).length() + 1) + "s", in[i] ? "1" : "0"));
+            }
+            line.append("│");
+            for (int i = 0; i < out.length; i++) {
+                line.append(String.format(" %-" + outputs.get(i).length() + "s", out[i] ? "1" : "0"));
+            }
+            sb.append(line.toString().replaceAll("\\s+$", "")).append("\n");
+        }
+        return sb.toString();
+    }
+
+    /** The netlist back in its text format. */
+    @Override
+    public String toString() {
+        StringBuilder sb = new StringBuilder();
+        if (!inputs.isEmpty()) sb.append("input  ").append(String.join(" ", inputs)).append("\n");
+        if (!outputs.isEmpty()) sb.append("output ").append(String.join(" ", outputs)).append("\n");
+        for (Gate g : gates) sb.append(g).append("\n");
+        return sb.toString();
+    }
+
+    /**
+     * Graphviz DOT source: one box per gate, wires as edges labelled with
+     * their names. Render with "dot -Tsvg circuit.dot -o circuit.svg".
+     */
+    public String toDot() {
+        Map<String, String> driver = new LinkedHashMap<>();
+        for (int k = 0; k < gates.size(); k++) driver.put(gates.get(k).output, "g" + k);
+        StringBuilder sb = new StringBuilder("digraph netlist {\n");
+        sb.append("  rankdir=LR;\n");
+        sb.append("  node [fontname=\"Helvetica\", fontsize=11];\n");
+        sb.append("  edge [fontname=\"Helvetica\", fontsize=9];\n");
+        for (String in : inputs) {
+            sb.append(String.format("  %s [label=%s, shape=plaintext];\n", dotId("in:" + in), dotId(in)));
+        }
+        for (int k = 0; k < gates.size(); k++) {
+            Gate g = gates.get(k);
+            sb.append(String.format("  g%d [label=%s, shape=box%s];\n", k, dotId(g.type.name()),
+                g.type == GateType.DFF ? ", peripheries=2" : ""));
+        }
+        for (String out : outputs) {
+            sb.append(String.format("  %s [label=%s, shape=plaintext];\n", dotId("out:" + out), dotId(out)));
+        }
+        Map<String, Boolean> constants = new LinkedHashMap<>();
+        for (int k = 0; k < gates.size(); k++) {
+            for (String in : gates.get(k).inputs) {
+                sb.append("  ").append(dotSource(in, driver, constants)).append(" -> g").append(k)
+                  .append(dotWireLabel(in, driver)).append(";\n");
+            }
+        }
+        for (String out : outputs) {
+            sb.append("  ").append(dotSource(out, driver, constants)).append(" -> ")
+              .append(dotId("out:" + out)).append(";\n");
+        }
+        for (String c : constants.keySet()) {
+            sb.append(String.format("  %s [label=%s, shape=plaintext];\n", dotId("const:" + c), dotId(c)));
+        }
+        return sb.append("}\n").toString();
+    }
+
+    private String dotSource(String wire, Map<String, String> driver, Map<String, Boolean> constants) {
+        if (driver.containsKey(wire)) return driver.get(wire);
+        if (wire.equals("0") || wire.equals("1")) {
+            constants.put(wire, true);
+            return dotId("const:" + wire);
+        }
+        return dotId("in:" + wire);
+    }
+
+    /** Internal wires carry their name; input wires are named by their source node already. */
+    private String dotWireLabel(String wire, Map<String, String> driver) {
+        return driver.containsKey(wire) ? " [label=" + dotId(wire) + "]" : "";
+    }
+
+    /** A quoted DOT identifier. */
+    static String dotId(String s) {
+        return "\"" + s.replace("\\", "\\\\").replace("\"", "\\\"").replace("\n", "\\n") + "\"";
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static final String FULL_ADDER =
+        "# 1-bit full adder built from two half adders\n" +
+        "input  a b cin\n" +
+        "output sum cout\n" +
+        "t1   = xor a b\n" +
+        "sum  = xor t1 cin\n" +
+        "t2   = and a b\n" +
+        "t3   = and t1 cin\n" +
+        "cout = or t2 t3\n";
+
+    public static final String SR_LATCH =
+        "# SR latch: two cross-coupled NOR gates (feedback loop!)\n" +
+        "input  s r\n" +
+        "output q qn\n" +
+        "q  = nor r qn\n" +
+        "qn = nor s q\n";
+
+    public static final String COUNTER_2BIT =
+        "# 2-bit synchronous counter: q1q0 = 00, 01, 10, 11, 00, ...\n" +
+        "input  en\n" +
+        "output q1 q0\n" +
+        "q0 = dff d0\n" +
+        "q1 = dff d1\n" +
+        "d0 = xor q0 en\n" +
+        "c0 = and q0 en\n" +
+        "d1 = xor q1 c0\n";
+
+    public static String demonstrateNetlist() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  GATE-LEVEL NETLIST SIMULATION\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        Netlist fa = parse(FULL_ADDER);
+        sb.append("Combinational: full adder\n\n").append(fa).append("\n");
+        sb.append(fa.truthTable()).append("\n");
+
+        Netlist latch = parse(SR_LATCH);
+        sb.append("Feedback: SR latch\n\n").append(latch).append("\n");
+        sb.append("  Step      S R │ Q Q'  (passes to settle)\n");
+        sb.append("  ──────────────┼──────\n");
+        String[] steps = {"set", "hold", "reset", "hold"};
+        boolean[][] sr = {{true, false}, {false, false}, {false, true}, {false, false}};
+        for (int i = 0; i < steps.length; i++) {
+            boolean[] q = latch.evaluate(sr[i]);
+            sb.append(String.format("  %-9s %d %d │ %d %d   (%d)\n", steps[i],
+                sr[i][0] ? 1 : 0, sr[i][1] ? 1 : 0, q[0] ? 1 : 0, q[1] ? 1 : 0,
+                latch.getSettleIterations()));
+        }
+        sb.append("  Same inputs (0,0) give different outputs: the loop REMEMBERS.\n\n");
+
+        Netlist counter = parse(COUNTER_2BIT);
+        sb.append("Sequential: 2-bit counter with D flip-flops\n\n").append(counter).append("\n");
+        counter.set("en", true);
+        counter.settle();
+        sb.append("  Clock │ q1 q0\n");
+        sb.append("  ──────┼──────\n");
+        for (int t = 0; t <= 5; t++) {
+            sb.append(String.format("  %5d │  %d  %d\n", t, counter.get("q1") ? 1 : 0,
+                counter.get("q0") ? 1 : 0));
+            counter.clock();
+        }
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: Netlist [file.net [--dot]]  - prints the truth table of a netlist
+     * file, or its Graphviz DOT source.
+     */
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateNetlist());
+            return;
+        }
+        try {
+            Netlist net = load(Paths.get(args[0]));
+            if (args.length > 1 && args[1].equals("--dot")) {
+                System.out.print(net.toDot());
+                return;
+            }
+            System.out.println(net);
+            System.out.println(net.truthTable());
+        } catch (IOException e) {
+            System.err.println(args[0] + ": " + (e instanceof NoSuchFileException ? "no such file" : e.getMessage()));
+            System.exit(2);
+        } catch (IllegalArgumentException | IllegalStateException e) {
+            System.err.println(args[0] + ": " + e.getMessage());
+            System.exit(2);
+        }
+    }
+}
@@ -0,0 +1,553 @@
+package computerdesign.logic;
+
+import java.util.ArrayList;
+import java.util.BitSet;
+import java.util.Iterator;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+import java.util.TreeSet;
+
+/**
+ * QuineMcCluskey - Tabular logic minimization for any number of variables.
+ *
+ * Covers learning goals: T3.2 (Combinational Logic - minimization)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHY NOT JUST USE A KARNAUGH MAP?
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * K-maps rely on the human eye spotting adjacent groups of 1s. That works for
+ * up to 4 variables (a 4x4 grid), is awkward for 5-6 (stacked maps), and is
+ * hopeless beyond that. Quine-McCluskey does EXACTLY the same thing as a K-map,
+ * but as a table procedure a computer (or a patient student) can follow:
+ *
+ *   K-map step                      Quine-McCluskey step
+ *   ─────────────────────────────   ──────────────────────────────────────
+ *   Circle adjacent 1s              Combine terms differing in ONE bit
+ *   Grow circles as big as possible Repeat combining until nothing changes
+ *   Circles that can't grow         PRIME IMPLICANTS (never combined)
+ *   Circles that must be used       ESSENTIAL prime implicants
+ *   Pick remaining circles          Petrick's method (exact cover)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * STEP 1: COMBINING (the "adjacency" rule  XY + XY' = X)
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Group minterms by number of 1-bits. Only neighbouring groups can differ in
+ * exactly one bit, so only those need to be compared:
+ *
+ *   Group 1:  m1 = 0001          m1,m5  = 0-01   (bit 2 differs → dash)
+ *   Group 2:  m5 = 0101    ──►   m5,m7  = 01-1
+ *   Group 3:  m7 = 0111
+ *
+ *   Two terms combine only if their dashes are in the SAME positions.
+ *   A term that never combines is a PRIME IMPLICANT.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * STEP 2: PRIME IMPLICANT CHART
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   Rows = prime implicants, columns = minterms (don't-cares are NOT columns).
+ *
+ *              │ m0  m1  m2  m5
+ *   ───────────┼────────────────
+ *   P1  A'B'   │  X   X   .   .
+ *   P2  A'C'   │  X   .   X   .       m5 has only one X → P3 is ESSENTIAL
+ *   P3  B'C    │  .   X   .   X
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * STEP 3: PETRICK'S METHOD (for cyclic cores)
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Sometimes no column has a single X - every minterm is covered by at least
+ * two implicants (a "cyclic core"). Then write for every uncovered minterm
+ * "which implicants could cover it" as a sum, AND them all together, and
+ * multiply out:
+ *
+ *   (P1 + P2)(P1 + P3)(P2 + P4) ... = P1P2 + P1P4 + P2P3 + ...
+ *
+ * Each product is a valid cover. Pick the one with the fewest implicants,
+ * breaking ties by the fewest literals (= cheapest gates).
+ * Absorption (X + XY = X) keeps the expansion small.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class QuineMcCluskey {
+
+    /** Exponential chart sizes are fine for teaching, not for 20-input PLAs. */
+    public static final int MAX_VARIABLES = 16;
+
+    private final int numVars;
+    private final TreeSet<Integer> minterms = new TreeSet<>();
+    private final TreeSet<Integer> dontCares = new TreeSet<>();
+    private final String[] names;
+
+    // ==================== IMPLICANT ====================
+
+    /**
+     * A product term: bits where mask=1 are "don't matter" (the dashes).
+     */
+    public static class Implicant {
+        public final int value;
+        public final int mask;
+        public final TreeSet<Integer> covers = new TreeSet<>();
+        boolean combined = false;
+
+        Implicant(int value, int mask) {
+            this.value = value & ~mask;
+            this.mask = mask;
+        }
+
+        /** Try to merge with a term differing in exactly one fixed bit. */
+        Implicant combine(Implicant other) {
+            if (mask != other.mask) return null;
+            int diff = value ^ other.value;
+            if (Integer.bitCount(diff) != 1) return null;
+            Implicant merged = new Implicant(value, mask | diff);
+            merged.covers.addAll(covers);
+            merged.covers.addAll(other.covers);
+            return merged;
+        }
+
+        /** Binary pattern with dashes, MSB first (e.g. "01-1"). */
+        public String pattern(int numVars) {
+            StringBuilder sb = new StringBuilder();
+            for (int i = numVars - 1; i >= 0; i--) {
+                if ((mask >> i & 1) == 1) sb.append('-');
+                else sb.append(value >> i & 1);
+            }
+            return sb.toString();
+        }
+
+        /** Product term using variable names, e.g. "A'BD". */
+        public String term(String[] names) {
+            int n = names.length;
+            StringBuilder sb = new StringBuilder();
+            for (int i = n - 1; i >= 0; i--) {
+                if ((mask >> i & 1) == 1) continue;
+                sb.append(names[n - 1 - i]);
+                if ((value >> i & 1) == 0) sb.append('\'');
+            }
+            return sb.length() == 0 ? "1" : sb.toString();
+        }
+
+        public int literals(int numVars) {
+            return numVars - Integer.bitCount(mask);
+        }
+
+        public boolean coversMinterm(int m) {
+            return (m & ~mask) == value;
+        }
+
+        int key() {
+            return (mask << MAX_VARIABLES) | value;
+        }
+    }
+
+    // ==================== RESULT ====================
+
+    /**
+     * Outcome of a minimization: all primes, the essentials, and the final cover.
+     */
+    public static class Result {
+        public final List<Implicant> primeImplicants;
+        public final List<Implicant> essential;
+        public final List<Implicant> cover;
+        public final String expression;
+        public final String trace;
+
+        Result(List<Implicant> primes, List<Implicant> essential,
+               List<Implicant> cover, String expression, String trace) {
+            this.primeImplicants = primes;
+            this.essential = essential;
+            this.cover = cover;
+            this.expression = expression;
+            this.trace = trace;
+        }
+
+        @Override
+        public String toString() { return expression; }
+    }
+
+    // ==================== CONSTRUCTION ====================
+
+    public QuineMcCluskey(int numVars, int[] minterms, int[] dontCares) {
+        this(numVars, minterms, dontCares, defaultNames(numVars));
+    }
+
+    public QuineMcCluskey(int numVars, int[] minterms, int[] dontCares, String[] names) {
+        if (numVars < 1 || numVars > MAX_VARIABLES) {
+            throw new IllegalArgumentException(
+                "Number of variables must be 1-" + MAX_VARIABLES + ": " + numVars);
+        }
+        if (names.length != numVars) {
+            throw new IllegalArgumentException("Need exactly " + numVars + " variable names");
+        }
+        this.numVars = numVars;
+        this.names = names.clone();
+        for (int m : minterms) this.minterms.add(checkRange(m));
+        for (int d : dontCares) {
+            if (this.minterms.contains(checkRange(d))) {
+                throw new IllegalArgumentException("m" + d + " is both a minterm and a don't-care");
+            }
+            this.dontCares.add(d);
+        }
+    }
+
+    private int checkRange(int m) {
+        if (m < 0 || m >= (1 << numVars)) {
+            throw new IllegalArgumentException(
+                "Minterm " + m + " out of range for " + numVars + " variables");
+        }
+        return m;
+    }
+
+    /** A, B, C, ... with A as the most significant bit. */
+    public static String[] defaultNames(int numVars) {
+        String[] names = new String[numVars];
+        for (int i = 0; i < numVars; i++) {
+            names[i] = String.valueOf((char) ('A' + i));
+        }
+        return names;
+    }
+
+    /** Convenience: minimal sum-of-products as a string. */
+    public static String minimize(int numVars, int[] minterms, int[] dontCares) {
+        return new QuineMcCluskey(numVars, minterms, dontCares).minimize().expression;
+    }
+
+    // ==================== THE ALGORITHM ====================
+
+    public Result minimize() {
+        StringBuilder log = new StringBuilder();
+        log.append(String.format("f(%s) = Σm(%s)%s\n\n", String.join(",", names),
+            joinInts(minterms),
+            dontCares.isEmpty() ? "" : " + d(" + joinInts(dontCares) + ")"));
+
+        if (minterms.isEmpty()) {
+            log.append("No minterms: f = 0\n");
+            return new Result(new ArrayList<>(), new ArrayList<>(), new ArrayList<>(), "0",
+                              log.toString());
+        }
+
+        List<Implicant> primes = findPrimeImplicants(log);
+        List<Implicant> essential = new ArrayList<>();
+        List<Implicant> cover = selectCover(primes, essential, log);
+
+        cover.sort((x, y) -> x.literals(numVars) != y.literals(numVars)
+            ? x.literals(numVars) - y.literals(numVars) : Integer.compare(x.key(), y.key()));
+        StringBuilder expr = new StringBuilder();
+        int literals = 0;
+        for (Implicant p : cover) {
+            if (expr.length() > 0) expr.append(" + ");
+            expr.append(p.term(names));
+            literals += p.literals(numVars);
+        }
+        log.append(String.format("\nMinimal SOP: f = %s\n", expr));
+        log.append(String.format("  %d product term(s), %d literal(s)\n", cover.size(), literals));
+
+        return new Result(primes, essential, cover, expr.toString(), log.toString());
+    }
+
+    /**
+     * Step 1: repeatedly combine terms until no more combinations are possible.
+     */
+    private List<Implicant> findPrimeImplicants(StringBuilder log) {
+        List<Implicant> column = new ArrayList<>();
+        TreeSet<Integer> all = new TreeSet<>(minterms);
+        all.addAll(dontCares);
+        for (int m : all) {
+            Implicant imp = new Implicant(m, 0);
+            imp.covers.add(m);
+            column.add(imp);
+        }
+        sortByGroup(column);
+
+        List<Implicant> primes = new ArrayList<>();
+        int size = 0;
+        while (!column.isEmpty()) {
+            log.append(String.format("Step 1.%d: %s\n", size + 1,
+                size == 0 ? "minterms grouped by number of 1s"
+                          : "terms covering " + (1 << size) + " minterms"));
+            appendGroups(column, log);
+
+            Map<Integer, Implicant> next = new LinkedHashMap<>();
+            for (int i = 0; i < column.size(); i++) {
+                for (int j = i + 1; j < column.size(); j++) {
+                    Implicant a = column.get(i);
+                    Implicant b = column.get(j);
+                    Implicant merged = a.combine(b);
+                    if (merged != null) {
+                        a.combined = true;
+                        b.combined = true;
+                        next.putIfAbsent(merged.key(), merged);
+                    }
+                }
+            }
+            for (Implicant imp : column) {
+                if (!imp.combined) primes.add(imp);
+            }
+            column = new ArrayList<>(next.values());
+            sortByGroup(column);
+            size++;
+        }
+
+        log.append("Prime implicants (terms marked * above never combined):\n");
+        for (int i = 0; i < primes.size(); i++) {
+            Implicant p = primes.get(i);
+            log.append(String.format("  P%-3d %-" + Math.max(numVars, 7) + "s  %-12s covers %s\n",
+                i + 1, p.pattern(numVars), p.term(names), braces(p.covers)));
+        }
+        log.append("\n");
+        return primes;
+    }
+
+    /** Order by number of 1s (the group), then by first covered minterm. */
+    private static void sortByGroup(List<Implicant> column) {
+        column.sort((x, y) -> {
+            int byGroup = Integer.compare(Integer.bitCount(x.value), Integer.bitCount(y.value));
+            return byGroup != 0 ? byGroup : Integer.compare(x.covers.first(), y.covers.first());
+        });
+    }
+
+    private void appendGroups(List<Implicant> column, StringBuilder log) {
+        int lastGroup = -1;
+        // combined flags are only known after the pass, so compute them here
+        for (Implicant a : column) {
+            boolean willCombine = false;
+            for (Implicant b : column) {
+                if (a != b && a.combine(b) != null) { willCombine = true; break; }
+            }
+            int group = Integer.bitCount(a.value);
+            String label = group != lastGroup ? String.format("  %2d │", group) : "     │";
+            lastGroup = group;
+            log.append(String.format("%s %-" + Math.max(numVars, 7) + "s %s %s\n", label,
+                a.pattern(numVars), willCombine ? "✓" : "*", braces(a.covers)));
+        }
+        log.append("\n");
+    }
+
+    /**
+     * Steps 2 and 3: chart, essential primes, then Petrick's method for the rest.
+     */
+    private List<Implicant> selectCover(List<Implicant> primes, List<Implicant> essential,
+                                        StringBuilder log) {
+        List<Integer> columns = new ArrayList<>(minterms);
+        appendChart(primes, columns, log);
+
+        // Essential prime implicants: sole cover of some minterm
+        TreeSet<Integer> uncovered = new TreeSet<>(minterms);
+        for (int m : columns) {
+            Implicant only = null;
+            int count = 0;
+            for (Implicant p : primes) {
+                if (p.coversMinterm(m)) { only = p; count++; }
+            }
+            if (count == 1 && !essential.contains(only)) {
+                essential.add(only);
+                log.append(String.format("  m%d is covered only by P%d → P%d (%s) is essential\n",
+                    m, primes.indexOf(only) + 1, primes.indexOf(only) + 1, only.term(names)));
+            }
+        }
+        for (Implicant e : essential) uncovered.removeAll(e.covers);
+        if (essential.isEmpty()) {
+            log.append("  No essential prime implicants (cyclic core).\n");
+        }
+
+        List<Implicant> cover = new ArrayList<>(essential);
+        if (uncovered.isEmpty()) {
+            log.append("  Essential prime implicants cover every minterm.\n");
+            return cover;
+        }
+
+        log.append(String.format("\nStep 3: Petrick's method for remaining minterms %s\n",
+            braces(uncovered)));
+        List<Integer> candidates = new ArrayList<>();
+        for (int i = 0; i < primes.size(); i++) {
+            if (!essential.contains(primes.get(i))) candidates.add(i);
+        }
+
+        // Product of sums: one clause per uncovered minterm
+        List<BitSet> products = new ArrayList<>();
+        products.add(new BitSet());
+        StringBuilder pos = new StringBuilder("  P = ");
+        for (int m : uncovered) {
+            List<Integer> clause = new ArrayList<>();
+            for (int i : candidates) {
+                if (primes.get(i).coversMinterm(m)) clause.add(i);
+            }
+            pos.append("(");
+            for (int k = 0; k < clause.size(); k++) {
+                if (k > 0) pos.append(" + ");
+                pos.append("P").append(clause.get(k) + 1);
+            }
+            pos.append(")");
+            products = multiply(products, clause);
+        }
+        log.append(pos).append("\n");
+
+        StringBuilder sop = new StringBuilder("    = ");
+        for (int k = 0; k < products.size(); k++) {
+            if (k > 0) sop.append(" + ");
+            if (k == 12) { sop.append("... (").append(products.size()).append(" products)"); break; }
+            BitSet product = products.get(k);
+            for (int i = product.nextSetBit(0); i >= 0; i = product.nextSetBit(i + 1)) {
+                sop.append("P").append(i + 1);
+            }
+        }
+        log.append(sop).append("\n");
+
+        BitSet best = null;
+        int bestLiterals = Integer.MAX_VALUE;
+        for (BitSet product : products) {
+            int literals = 0;
+            for (int i = product.nextSetBit(0); i >= 0; i = product.nextSetBit(i + 1)) {
+                literals += primes.get(i).literals(numVars);
+            }
+            if (best == null || product.cardinality() < best.cardinality()
+                    || (product.cardinality() == best.cardinality() && literals < bestLiterals)) {
+                best = product;
+                bestLiterals = literals;
+            }
+        }
+        StringBuilder chosen = new StringBuilder();
+        for (int i = best.nextSetBit(0); i >= 0; i = best.nextSetBit(i + 1)) {
+            cover.add(primes.get(i));
+            chosen.append(" P").append(i + 1);
+        }
+        log.append(String.format("  Cheapest product:%s (%d term(s), %d literal(s))\n",
+            chosen, best.cardinality(), bestLiterals));
+        return cover;
+    }
+
+    /** (sum of products) × (P_a + P_b + ...), with absorption X + XY = X. */
+    private static List<BitSet> multiply(List<BitSet> products, List<Integer> clause) {
+        List<BitSet> result = new ArrayList<>();
+        for (BitSet product : products) {
+            for (int i : clause) {
+                BitSet term = (BitSet) product.clone();
+                term.set(i);
+                addAbsorbing(result, term);
+            }
+        }
+        return result;
+    }
+
+    private static void addAbsorbing(List<BitSet> terms, BitSet term) {
+        Iterator<BitSet> it = terms.iterator();
+        while (it.hasNext()) {
+            BitSet existing = it.next();
+            BitSet common = (BitSet) existing.clone();
+            common.and(term);
+            if (common.equals(existing)) return;       // existing absorbs term
+            if (common.equals(term)) it.remove();      // term absorbs existing
+        }
+        terms.add(term);
+    }
+
+    private void appendChart(List<Implicant> primes, List<Integer> columns, StringBuilder log) {
+        log.append("Step 2: Prime implicant chart\n");
+        int termWidth = 6;
+        for (Implicant p : primes) termWidth = Math.max(termWidth, p.term(names).length());
+        int colWidth = 2;
+        for (int m : columns) colWidth = Math.max(colWidth, String.valueOf(m).length() + 1);
+
+        StringBuilder header = new StringBuilder(String.format("  %-" + (termWidth + 5) + "s │", ""));
+        for (int m : columns) header.append(String.format(" %" + colWidth + "s", "m" + m));
+        log.append(header).append("\n");
+        log.append("  ").append("─".repeat(termWidth + 5)).append("─┼")
+           .append("─".repeat(columns.size() * (colWidth + 1))).append("\n");
+        for (int i = 0; i < primes.size(); i++) {
+            Implicant p = primes.get(i);
+            log.append(String.format("  P%-3d %-" + termWidth + "s │", i + 1, p.term(names)));
+            for (int m : columns) {
+                log.append(String.format(" %" + colWidth + "s", p.coversMinterm(m) ? "X" : "."));
+            }
+            log.append("\n");
+        }
+        log.append("\n");
+    }
+
+    private static String joinInts(Iterable<Integer> values) {
+        StringBuilder sb = new StringBuilder();
+        for (int v : values) {
+            if (sb.length() > 0) sb.append(",");
+            sb.append(v);
+        }
+        return sb.toString();
+    }
+
+    private static String braces(Iterable<Integer> values) {
+        return "{" + joinInts(values) + "}";
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    /**
+     * Two classic cases: a 5-variable function (beyond a single K-map, with
+     * don't-cares) and the 3-variable cyclic core that needs Petrick's method.
+     */
+    public static String demonstrateQuineMcCluskey() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  QUINE-McCLUSKEY MINIMIZATION\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("Example 1: five variables (K-map would need two 4x4 maps)\n\n");
+        sb.append(new QuineMcCluskey(5,
+            new int[] {1, 3, 4, 5, 9, 11, 12, 13, 15, 17, 19, 20, 27, 28},
+            new int[] {7, 21, 25}).minimize().trace);
+
+        sb.append("\n───────────────────────────────────────────────────────────────\n");
+        sb.append("Example 2: cyclic core (every minterm covered twice)\n\n");
+        sb.append(new QuineMcCluskey(3,
+            new int[] {0, 1, 2, 5, 6, 7}, new int[] {}).minimize().trace);
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    private static final String USAGE = "Usage: QuineMcCluskey [vars \"m1,m2,...\" [\"d1,d2,...\"]]";
+
+    /**
+     * Usage: QuineMcCluskey [vars "m1,m2,..." ["d1,d2,..."]]
+     */
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateQuineMcCluskey());
+            return;
+        }
+        if (args.length > 3) usage("Too many arguments");
+        if (args.length < 2) usage("Need a variable count and a list of minterms");
+        try {
+            int vars = parseNumber(args[0], "variable count");
+            int[] ms = parseList(args[1]);
+            int[] ds = args.length > 2 ? parseList(args[2]) : new int[0];
+            System.out.println(new QuineMcCluskey(vars, ms, ds).minimize().trace);
+        } catch (IllegalArgumentException e) {
+            usage(e.getMessage());
+        }
+    }
+
+    private static void usage(String message) {
+        System.err.println(message + "\n" + USAGE);
+        System.exit(2);
+    }
+
+    private static int parseNumber(String s, String what) {
+        try {
+            return Integer.parseInt(s.trim());
+        } catch (NumberFormatException e) {
+            throw new IllegalArgumentException("Not a " + what + ": " + s);
+        }
+    }
+
+    private static int[] parseList(String csv) {
+        if (csv.trim().isEmpty()) return new int[0];
+        String[] parts = csv.split("[,\\s]+");
+        int[] values = new int[parts.length];
+        for (int i = 0; i < parts.length; i++) values[i] = parseNumber(parts[i], "minterm");
+        return values;
+    }
+}
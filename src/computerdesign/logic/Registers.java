@@ -0,0 +1,404 @@
+package computerdesign.logic;
+
+import computerdesign.logic.FlipFlops.Clock;
+import computerdesign.logic.FlipFlops.DFlipFlop;
+import computerdesign.logic.FlipFlops.TFlipFlop;
+
+/**
+ * Registers - Shift registers and counters built from the flip-flop models.
+ *
+ * Covers learning goals: T4.2 (Sequential Logic), T2.2 (Overflow)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * SHIFT REGISTERS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   SIPO (serial in, parallel out):      PISO (parallel in, serial out):
+ *
+ *   in ─►[D Q]─►[D Q]─►[D Q]─►[D Q]      LOAD: all flip-flops take d3..d0
+ *        q3     q2     q1     q0         SHIFT: q3 → q2 → q1 → q0 ─► out
+ *        └──────┴──────┴──────┴─► value
+ *
+ *   Both shift towards q0, so a PISO sends the LSB first and a SIPO that
+ *   receives the stream has the first bit in q0 after n clocks - exactly how
+ *   a UART or SPI link turns a byte into a wire of bits and back again.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * COUNTERS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   RIPPLE (asynchronous):                SYNCHRONOUS:
+ *
+ *   CLK ─►[T Q0]─┐                        CLK ──┬────────┬────────┐
+ *         1   Q0'└►[T Q1]─┐                     ▼        ▼        ▼
+ *               1     Q1' └►[T Q2]        1 ─►[T Q0] ┌►[T Q1] ┌►[T Q2]
+ *                                                 └──┴──AND──┘
+ *   Each flip-flop is clocked by the one    One clock for all; T_i = Q0·...·Q(i-1).
+ *   before it: simple, but the change       All bits change together, but the
+ *   RIPPLES through one stage at a time,    AND chain sets the clock period.
+ *   showing wrong counts in between.
+ *
+ *   Every stage halves the frequency: Q0 = f/2, Q1 = f/4, Q2 = f/8, ...
+ *
+ * WRAP-AROUND: an n-bit counter counts modulo 2ⁿ. After 1111 comes 0000 and
+ * the carry out pulses - the same unsigned overflow as 15 + 1 in a 4-bit
+ * adder. A mod-N counter (e.g. BCD, N = 10) clears itself at N - 1 instead.
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class Registers {
+
+    // ==================== SHIFT REGISTERS ====================
+
+    /** Serial in, parallel out: the serial bit enters at the top and moves towards q0. */
+    public static class ShiftRegister {
+        private final DFlipFlop[] stages;
+
+        public ShiftRegister(int bits) {
+            if (bits < 1 || bits > 32) throw new IllegalArgumentException("1-32 bits: " + bits);
+            stages = new DFlipFlop[bits];
+            for (int i = 0; i < bits; i++) stages[i] = new DFlipFlop();
+        }
+
+        /** All stages sample at the same edge, so each takes its neighbour's OLD value. */
+        public void update(boolean serialIn, boolean clock) {
+            int n = stages.length;
+            boolean[] d = new boolean[n];
+            for (int i = 0; i < n - 1; i++) d[i] = stages[i + 1].getQ();
+            d[n - 1] = serialIn;
+            for (int i = 0; i < n; i++) stages[i].update(d[i], clock);
+        }
+
+        public int getValue() {
+            int value = 0;
+            for (int i = 0; i < stages.length; i++) if (stages[i].getQ()) value |= 1 << i;
+            return value;
+        }
+
+        public boolean getQ(int bit) { return stages[bit].getQ(); }
+        public int getBits() { return stages.length; }
+    }
+
+    /** Parallel in, serial out: LOAD copies the inputs, otherwise shift out from q0 (LSB first). */
+    public static class ParallelLoadRegister {
+        private final DFlipFlop[] stages;
+
+        public ParallelLoadRegister(int bits) {
+            if (bits < 1 || bits > 32) throw new IllegalArgumentException("1-32 bits: " + bits);
+            stages = new DFlipFlop[bits];
+            for (int i = 0; i < bits; i++) stages[i] = new DFlipFlop();
+        }
+
+        /** A 2:1 MUX in front of every D input selects "load" or "shift". */
+        public void update(boolean load, int parallel, boolean clock) {
+            int n = stages.length;
+            boolean[] d = new boolean[n];
+            for (int i = 0; i < n; i++) {
+                d[i] = load ? (parallel >> i & 1) == 1 : i < n - 1 && stages[i + 1].getQ();
+            }
+            for (int i = 0; i < n; i++) stages[i].update(d[i], clock);
+        }
+
+        public boolean getSerialOut() { return stages[0].getQ(); }
+
+        public int getValue() {
+            int value = 0;
+            for (int i = 0; i < stages.length; i++) if (stages[i].getQ()) value |= 1 << i;
+            return value;
+        }
+    }
+
+    // ==================== COUNTERS ====================
+
+    /**
+     * Asynchronous counter: T = 1 everywhere, stage i clocked by Q'(i-1).
+     * Each stage reacts one time step after the stage before it, so a change
+     * like 0111 → 1000 passes through 0110, 0100, 0000 first.
+     */
+    public static class RippleCounter {
+        private final TFlipFlop[] stages;
+        private final boolean[] previousQ;       // what the next stage sees (one step late)
+
+        public RippleCounter(int bits) {
+            if (bits < 1 || bits > 32) throw new IllegalArgumentException("1-32 bits: " + bits);
+            stages = new TFlipFlop[bits];
+            previousQ = new boolean[bits];
+            for (int i = 0; i < bits; i++) stages[i] = new TFlipFlop();
+            // stages 1.. start with their clock Q'(i-1) = 1: register that level without toggling
+            for (int i = 1; i < bits; i++) stages[i].update(false, true);
+        }
+
+        public void update(boolean clock) {
+            boolean[] seen = previousQ.clone();
+            stages[0].update(true, clock);
+            for (int i = 1; i < stages.length; i++) stages[i].update(true, !seen[i - 1]);
+            for (int i = 0; i < stages.length; i++) previousQ[i] = stages[i].getQ();
+        }
+
+        public int getValue() {
+            int value = 0;
+            for (int i = 0; i < stages.length; i++) if (stages[i].getQ()) value |= 1 << i;
+            return value;
+        }
+
+        public boolean getQ(int bit) { return stages[bit].getQ(); }
+    }
+
+    /**
+     * Synchronous up-counter with enable, carry out and an optional modulus
+     * (0 = count modulo 2ⁿ; e.g. 10 for a BCD decade counter).
+     */
+    public static class SyncCounter {
+        private final TFlipFlop[] stages;
+        private final int modulus;
+
+        public SyncCounter(int bits) {
+            this(bits, 0);
+        }
+
+        public SyncCounter(int bits, int modulus) {
+            if (bits < 1 || bits > 30) throw new IllegalArgumentException("1-30 bits: " + bits);
+            if (modulus != 0 && (modulus < 2 || modulus > 1 << bits)) {
+                throw new IllegalArgumentException("Modulus must be 2.." + (1 << bits) + ": " + modulus);
+            }
+            stages = new TFlipFlop[bits];
+            for (int i = 0; i < bits; i++) stages[i] = new TFlipFlop();
+            this.modulus = modulus;
+        }
+
+        /**
+         * T_i = enable · Q0 · ... · Q(i-1); at the terminal count of a mod-N
+         * counter T_i = Q_i instead, which toggles every 1 back to 0.
+         */
+        public void update(boolean enable, boolean clock) {
+            boolean clear = enable && modulus != 0 && getValue() == modulus - 1;
+            boolean[] t = new boolean[stages.length];
+            boolean carry = enable;
+            for (int i = 0; i < stages.length; i++) {
+                t[i] = clear ? stages[i].getQ() : carry;
+                carry &= stages[i].getQ();
+            }
+            for (int i = 0; i < stages.length; i++) stages[i].update(t[i], clock);
+        }
+
+        /** Carry out: 1 at the terminal count (all ones, or N - 1) - the next edge wraps. */
+        public boolean getCarry() {
+            return getValue() == (modulus == 0 ? (1 << stages.length) - 1 : modulus - 1);
+        }
+
+        public int getValue() {
+            int value = 0;
+            for (int i = 0; i < stages.length; i++) if (stages[i].getQ()) value |= 1 << i;
+            return value;
+        }
+
+        public boolean getQ(int bit) { return stages[bit].getQ(); }
+    }
+
+    // ==================== TRACES ====================
+
+    /** A 3-bit synchronous counter as a frequency divider. */
+    public static SignalTrace frequencyDivider() {
+        final SyncCounter counter = new SyncCounter(3);
+        return FlipFlops.simulate(new Clock(1), 34, (t, clk, trace) -> {
+            counter.update(true, clk);
+            trace.record("Q0 f/2", counter.getQ(0));
+            trace.record("Q1 f/4", counter.getQ(1));
+            trace.record("Q2 f/8", counter.getQ(2));
+        });
+    }
+
+    /** A 4-bit ripple counter with a slow clock, so the ripple is visible. */
+    public static SignalTrace rippleCounter() {
+        final RippleCounter counter = new RippleCounter(4);
+        return FlipFlops.simulate(new Clock(4), 2 * 8 * 8 + 8, (t, clk, trace) -> {
+            counter.update(clk);
+            for (int i = 0; i < 4; i++) trace.record("Q" + i, counter.getQ(i));
+            trace.recordLabel("count", String.valueOf(counter.getValue()));
+        });
+    }
+
+    /** Send a byte through PISO → wire → SIPO, LSB first. */
+    public static SignalTrace serialLink(int value) {
+        final ParallelLoadRegister tx = new ParallelLoadRegister(8);
+        final ShiftRegister rx = new ShiftRegister(8);
+        final int data = value;
+        return FlipFlops.simulate(new Clock(1), 2 * 9 + 1, (t, clk, trace) -> {
+            boolean load = t < 2;                       // load during the first cycle
+            boolean wire = tx.getSerialOut();
+            tx.update(load, data, clk);
+            if (t >= 2) rx.update(wire, clk);           // receiver starts with the first bit
+            trace.record("LOAD", load);
+            trace.record("wire", tx.getSerialOut());
+            trace.recordLabel("rx", String.format("%02X", rx.getValue()));
+        });
+    }
+
+    private static int clockEdges(Object counter, int edges, boolean[] carries) {
+        Clock clock = new Clock(1);
+        int last = 0;
+        int edge = 0;
+        for (int t = 0; edge < edges; t++) {
+            boolean clk = clock.level(t);
+            if (clock.isRisingEdge(t)) {
+                if (counter instanceof SyncCounter) carries[edge] = ((SyncCounter) counter).getCarry();
+                edge++;
+            }
+            if (counter instanceof SyncCounter) {
+                ((SyncCounter) counter).update(true, clk);
+                last = ((SyncCounter) counter).getValue();
+            } else {
+                ((RippleCounter) counter).update(clk);
+                last = ((RippleCounter) counter).getValue();
+            }
+        }
+        if (counter instanceof RippleCounter) {
+            // with the clock held low the ripple finishes (one step per stage)
+            for (int i = 0; i < 32; i++) ((RippleCounter) counter).update(false);
+            last = ((RippleCounter) counter).getValue();
+        }
+        return last;
+    }
+
+    /** Widths the counter self-checks cover. */
+    private static final int MAX_CHECKED_BITS = 8;
+
+    /** n-bit synchronous counter: 2ⁿ - 1 after 2ⁿ - 1 edges, 0 after 2ⁿ, one carry just before. */
+    public static boolean syncWraps(int bits) {
+        boolean[] carries = new boolean[1 << bits];
+        if (clockEdges(new SyncCounter(bits), 1 << bits, carries) != 0) return false;
+        int count = 0;
+        for (boolean c : carries) if (c) count++;
+        if (count != 1 || !carries[(1 << bits) - 1]) return false;
+        return clockEdges(new SyncCounter(bits), (1 << bits) - 1, new boolean[1 << bits]) == (1 << bits) - 1;
+    }
+
+    /** n-bit ripple counter: all ones after 2ⁿ - 1 edges, 0 after 2ⁿ, 1 after 2ⁿ + 1. */
+    public static boolean rippleWraps(int bits) {
+        int n = 1 << bits;
+        return clockEdges(new RippleCounter(bits), n - 1, new boolean[n + 1]) == n - 1
+            && clockEdges(new RippleCounter(bits), n, new boolean[n + 1]) == 0
+            && clockEdges(new RippleCounter(bits), n + 1, new boolean[n + 1]) == 1;
+    }
+
+    /** 4-bit mod-N counter: 0 after N and 3N edges, 1 after N + 1. */
+    public static boolean moduloWraps(int modulus) {
+        boolean[] carries = new boolean[3 * modulus];
+        return clockEdges(new SyncCounter(4, modulus), 3 * modulus, carries) == 0
+            && clockEdges(new SyncCounter(4, modulus), modulus + 1, new boolean[modulus + 1]) == 1;
+    }
+
+    /** Every byte survives the serial link unchanged. */
+    public static boolean serialLinkKeepsBytes() {
+        for (int value = 0; value < 256; value++) {
+            String rx = serialLink(value).getLabels("rx").toString();
+            if (!rx.endsWith(String.format("%02X]", value))) return false;
+        }
+        return true;
+    }
+
+    /**
+     * Self-check of the wrap-around behaviour: an n-bit counter is back at 0
+     * after 2ⁿ edges with exactly one carry, a mod-N counter after N, and a
+     * byte survives the serial link unchanged.
+     */
+    public static boolean verify() {
+        for (int bits = 1; bits <= MAX_CHECKED_BITS; bits++) {
+            if (!syncWraps(bits) || !rippleWraps(bits)) return false;
+        }
+        for (int modulus = 2; modulus <= 16; modulus++) {
+            if (!moduloWraps(modulus)) return false;
+        }
+        return serialLinkKeepsBytes();
+    }
+
+    /** The checks of verify() one per line, for --verify. */
+    public static String verifyReport() {
+        StringBuilder sb = new StringBuilder();
+        for (int bits = 1; bits <= MAX_CHECKED_BITS; bits++) {
+            sb.append(line(bits + "-bit sync counter wraps at " + (1 << bits), syncWraps(bits)));
+            sb.append(line(bits + "-bit ripple counter wraps at " + (1 << bits), rippleWraps(bits)));
+        }
+        for (int modulus = 2; modulus <= 16; modulus++) {
+            sb.append(line("mod-" + modulus + " counter wraps at " + modulus, moduloWraps(modulus)));
+        }
+        sb.append(line("serial link keeps all 256 bytes", serialLinkKeepsBytes()));
+        return sb.toString();
+    }
+
+    private static String line(String check, boolean passed) {
+        return String.format("%-36s %s\n", check, passed ? "ok" : "FAILED");
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateRegisters() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  SHIFT REGISTERS AND COUNTERS\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("1) A counter is a frequency divider: each bit has half the frequency\n");
+        sb.append("   of the one before it.\n\n");
+        sb.append(new TimingDiagram(frequencyDivider()).cellWidth(2).toAscii()).append("\n");
+
+        sb.append("2) Serial-to-parallel: 0xA5 sent through PISO → one wire → SIPO, LSB first:\n\n");
+        sb.append(new TimingDiagram(serialLink(0xA5)).cellWidth(3).toAscii()).append("\n");
+        sb.append("   8 clock cycles move 8 bits over one wire; the receiver's parallel\n");
+        sb.append("   outputs hold A5 when the last bit has arrived.\n\n");
+
+        sb.append("3) Ripple counter (each stage one time step late), 7 → 8:\n\n");
+        SignalTrace ripple = rippleCounter();
+        StringBuilder seen = new StringBuilder();
+        String last = "";
+        boolean showing = false;
+        for (String label : ripple.getLabels("count")) {
+            if (label.equals(last)) continue;
+            showing |= label.equals("7");
+            if (showing) seen.append(label.equals("7") ? "" : " → ").append(label);
+            if (showing && label.equals("8")) break;
+            last = label;
+        }
+        sb.append("   count values seen: ").append(seen).append("\n");
+        sb.append("   Between 7 and 8 the outputs briefly read 6, 4 and 0 while the change\n");
+        sb.append("   ripples through - a synchronous counter goes straight from 7 to 8.\n");
+        sb.append("   With a clock period shorter than the ripple (n stage delays) the\n");
+        sb.append("   outputs are never all valid at the same time.\n\n");
+
+        sb.append("4) Wrap-around (the counter version of overflow):\n\n");
+        SyncCounter four = new SyncCounter(4);
+        SyncCounter bcd = new SyncCounter(4, 10);
+        Clock clock = new Clock(1);
+        StringBuilder binary = new StringBuilder("   4-bit:  ");
+        StringBuilder decade = new StringBuilder("   mod 10: ");
+        for (int t = 0; t < 2 * 18; t++) {
+            if (clock.isRisingEdge(t)) {
+                binary.append(four.getValue()).append(four.getCarry() ? "(C) " : " ");
+                decade.append(bcd.getValue()).append(bcd.getCarry() ? "(C) " : " ");
+            }
+            four.update(true, clock.level(t));
+            bcd.update(true, clock.level(t));
+        }
+        sb.append(binary.toString().replaceAll("\\s+$", "")).append("\n");
+        sb.append(decade.toString().replaceAll("\\s+$", "")).append("\n");
+        sb.append("   (C) = carry out: 15 + 1 wraps to 0 exactly like a 4-bit unsigned add.\n");
+        sb.append("   Chaining the carry into the next counter's enable gives 2 digits.\n\n");
+
+        sb.append("Self-check (wrap-around at 2ⁿ and N, serial link for all 256 bytes): ")
+          .append(verify() ? "passed" : "FAILED").append("\n");
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: Registers [--verify]   (--verify: each self-check, exit 1 if one fails)
+     */
+    public static void main(String[] args) {
+        if (args.length == 1 && args[0].equals("--verify")) {
+            System.out.print(verifyReport());
+            if (!verify()) System.exit(1);
+            return;
+        }
+        System.out.println(demonstrateRegisters());
+    }
+}
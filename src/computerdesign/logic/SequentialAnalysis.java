@@ -0,0 +1,407 @@
+package computerdesign.logic;
+
+import java.io.IOException;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Paths;
+import java.util.ArrayDeque;
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.Deque;
+import java.util.List;
+import java.util.Random;
+
+/**
+ * SequentialAnalysis - Derives the state table and state diagram of a
+ * flip-flop circuit: FSM synthesis run backwards.
+ *
+ * Covers learning goals: T4.2 (Sequential Logic), T4.1 (State Machines)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE EXAM PROCEDURE
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   Given:  x ──►┌───────────┐        1. The state is the flip-flop contents
+ *                │ next-state│──D_A──►[A]──┐        (n flip-flops → 2ⁿ states)
+ *           ┌───►│   logic   │──D_B──►[B]──┤
+ *           │    └───────────┘             │  2. Write the D inputs as functions
+ *           └──────────────────────────────┘     of present state and input:
+ *                                                 A⁺ = D_A(A, B, x)
+ *   3. Fill the table row by row:
+ *
+ *        A B │ x │ A⁺ B⁺ │ y          4. Draw one circle per state and one
+ *        0 0 │ 0 │ 0  0  │ 0             arrow per row: AB ──x/y──► A⁺B⁺
+ *        0 0 │ 1 │ 0  1  │ 0
+ *        ...                          5. Moore or Mealy? If y depends on x
+ *                                        (not only on A B), it is Mealy.
+ *
+ * This class does exactly that by simulation: it forces every flip-flop
+ * combination with Netlist.setState(), applies every input combination,
+ * settles the logic and reads D (= the next state) and the outputs. The
+ * equations are then minimized with Quine-McCluskey.
+ *
+ * States are written as the flip-flop bits MSB first; for a bus q[2..0]
+ * that is q[2] q[1] q[0]. The reset state (all flip-flops 0) is initial.
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class SequentialAnalysis {
+
+    /** State bits + input bits enumerated (2^10 rows is already a long table). */
+    public static final int MAX_BITS = 10;
+
+    private final List<String> state = new ArrayList<>();    // flip-flop outputs, MSB first
+    private final List<String> next = new ArrayList<>();     // their D inputs
+    private final List<String> inputs;
+    private final List<String> outputs;
+    private final int[][] nextState;                          // [state][input] → next state
+    private final String[][] output;                          // [state][input] → output bits
+    private final boolean moore;
+
+    public SequentialAnalysis(Netlist net) {
+        List<Netlist.Gate> flipFlops = new ArrayList<>(net.getFlipFlops());
+        if (flipFlops.isEmpty()) throw new IllegalArgumentException("The circuit has no flip-flops");
+        if (net.hasFeedback()) {
+            throw new IllegalArgumentException("Combinational feedback (latches) cannot be analysed");
+        }
+        List<String> names = new ArrayList<>();
+        for (Netlist.Gate g : flipFlops) names.add(g.output);
+        if (isBus(names)) Collections.reverse(flipFlops);
+        for (Netlist.Gate g : flipFlops) {
+            state.add(g.output);
+            next.add(g.inputs.get(0));
+        }
+        inputs = msbFirst(net.getInputs());
+        outputs = msbFirst(net.getOutputs());
+        if (state.size() + inputs.size() > MAX_BITS) {
+            throw new IllegalArgumentException(state.size() + " flip-flops and " + inputs.size()
+                + " inputs is too many to enumerate (max " + MAX_BITS + " bits)");
+        }
+
+        int states = 1 << state.size();
+        int symbols = 1 << inputs.size();
+        nextState = new int[states][symbols];
+        output = new String[states][symbols];
+        for (int s = 0; s < states; s++) {
+            for (int x = 0; x < symbols; x++) {
+                for (int i = 0; i < state.size(); i++) net.setState(state.get(i), bit(s, i, state.size()));
+                for (int j = 0; j < inputs.size(); j++) net.set(inputs.get(j), bit(x, j, inputs.size()));
+                net.settle();
+                int n = 0;
+                for (String d : next) n = n << 1 | (net.get(d) ? 1 : 0);
+                nextState[s][x] = n;
+                StringBuilder out = new StringBuilder();
+                for (String o : outputs) out.append(net.get(o) ? '1' : '0');
+                output[s][x] = out.length() == 0 ? "-" : out.toString();
+            }
+        }
+        net.reset();
+        net.settle();
+
+        boolean dependsOnInput = false;
+        for (String[] row : output) {
+            for (String o : row) dependsOnInput |= !o.equals(row[0]);
+        }
+        moore = !dependsOnInput;
+    }
+
+    /** True when the wires are the bits of one bus, listed LSB first. */
+    private static boolean isBus(List<String> wires) {
+        if (wires.size() < 2) return false;
+        String bus = wires.get(0).replaceAll("\\[\\d+\\]$", "");
+        for (int i = 0; i < wires.size(); i++) {
+            if (!wires.get(i).equals(Netlist.busName(bus, i))) return false;
+        }
+        return true;
+    }
+
+    private static List<String> msbFirst(List<String> wires) {
+        List<String> ordered = new ArrayList<>(wires);
+        if (isBus(ordered)) Collections.reverse(ordered);
+        return ordered;
+    }
+
+    /** Bit i of value, counting from the MSB of a width-bit word. */
+    private static boolean bit(int value, int i, int width) {
+        return (value >> (width - 1 - i) & 1) == 1;
+    }
+
+    private static String binary(int value, int width) {
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < width; i++) sb.append(bit(value, i, width) ? '1' : '0');
+        return sb.toString();
+    }
+
+    private String stateName(int s) {
+        return binary(s, state.size());
+    }
+
+    private String inputSymbol(int x) {
+        return inputs.isEmpty() ? StateMachine.ANY : binary(x, inputs.size());
+    }
+
+    // ==================== RESULTS ====================
+
+    public boolean isMoore() {
+        return moore;
+    }
+
+    /** The circuit as a StateMachine (states "00", "01", ...; initial = reset state). */
+    public StateMachine toStateMachine() {
+        StateMachine fsm = new StateMachine("Derived from netlist", moore ? StateMachine.Type.MOORE
+            : StateMachine.Type.MEALY, stateName(0));
+        for (int s = 0; s < nextState.length; s++) {
+            if (moore) fsm.addState(stateName(s), output[s][0]);
+            else fsm.addState(stateName(s));
+        }
+        for (int s = 0; s < nextState.length; s++) {
+            for (int x = 0; x < nextState[s].length; x++) {
+                fsm.addTransition(stateName(s), inputSymbol(x), stateName(nextState[s][x]),
+                    moore ? null : output[s][x]);
+            }
+        }
+        return fsm;
+    }
+
+    /** States reachable from reset, in the order they are first reached. */
+    public List<String> reachableStates() {
+        boolean[] seen = new boolean[nextState.length];
+        List<String> order = new ArrayList<>();
+        Deque<Integer> queue = new ArrayDeque<>();
+        queue.add(0);
+        seen[0] = true;
+        while (!queue.isEmpty()) {
+            int s = queue.poll();
+            order.add(stateName(s));
+            for (int n : nextState[s]) {
+                if (!seen[n]) {
+                    seen[n] = true;
+                    queue.add(n);
+                }
+            }
+        }
+        return order;
+    }
+
+    /** Minimized next-state (D input) and output equations. */
+    public String equations() {
+        List<String> names = new ArrayList<>();
+        for (String s : state) names.add(variable(s));
+        for (String i : inputs) names.add(variable(i));
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < state.size(); i++) {
+            List<Integer> minterms = new ArrayList<>();
+            for (int s = 0; s < nextState.length; s++) {
+                for (int x = 0; x < nextState[s].length; x++) {
+                    if (bit(nextState[s][x], i, state.size())) minterms.add(s << inputs.size() | x);
+                }
+            }
+            sb.append(String.format("  %s⁺ = D_%s = %s\n", variable(state.get(i)), variable(state.get(i)),
+                minimize(names, minterms)));
+        }
+        for (int o = 0; o < outputs.size(); o++) {
+            List<Integer> minterms = new ArrayList<>();
+            for (int s = 0; s < output.length; s++) {
+                for (int x = 0; x < output[s].length; x++) {
+                    if (output[s][x].charAt(o) == '1' && (!moore || x == 0)) {
+                        minterms.add(moore ? s : s << inputs.size() | x);
+                    }
+                }
+            }
+            List<String> vars = moore ? names.subList(0, state.size()) : names;
+            sb.append(String.format("  %s = %s\n", variable(outputs.get(o)), minimize(vars, minterms)));
+        }
+        return sb.toString();
+    }
+
+    private static String minimize(List<String> names, List<Integer> minterms) {
+        int[] m = new int[minterms.size()];
+        for (int i = 0; i < m.length; i++) m[i] = minterms.get(i);
+        return new QuineMcCluskey(names.size(), m, new int[0], names.toArray(new String[0])).minimize().expression;
+    }
+
+    /** "q[2]" → "q2" so product terms stay readable. */
+    private static String variable(String wire) {
+        return wire.replace("[", "").replace("]", "");
+    }
+
+    /** The transition table as filled in by hand: one row per state and input. */
+    public String transitionTable() {
+        List<String> head = new ArrayList<>();
+        for (String s : state) head.add(variable(s));
+        StringBuilder header = new StringBuilder();
+        header.append(String.join(" ", head)).append(" │ ");
+        for (String i : inputs) header.append(variable(i)).append(' ');
+        if (!inputs.isEmpty()) header.append("│ ");
+        for (String h : head) header.append(h).append("⁺ ");
+        header.append("│ ");
+        for (String o : outputs) header.append(variable(o)).append(' ');
+
+        StringBuilder sb = new StringBuilder();
+        sb.append(header.toString().trim()).append("\n");
+        sb.append(header.toString().trim().replaceAll("[^│]", "─").replace('│', '┼')).append("─\n");
+        for (int s = 0; s < nextState.length; s++) {
+            for (int x = 0; x < nextState[s].length; x++) {
+                StringBuilder row = new StringBuilder();
+                for (int i = 0; i < state.size(); i++) row.append(cell(bit(s, i, state.size()), head.get(i).length()));
+                row.append("│ ");
+                for (int j = 0; j < inputs.size(); j++) {
+                    row.append(cell(bit(x, j, inputs.size()), variable(inputs.get(j)).length()));
+                }
+                if (!inputs.isEmpty()) row.append("│ ");
+                for (int i = 0; i < state.size(); i++) {
+                    row.append(cell(bit(nextState[s][x], i, state.size()), head.get(i).length() + 1));
+                }
+                row.append("│ ");
+                for (int o = 0; o < outputs.size(); o++) {
+                    row.append(cell(output[s][x].charAt(o) == '1', variable(outputs.get(o)).length()));
+                }
+                sb.append(row.toString().trim()).append("\n");
+            }
+        }
+        return sb.toString();
+    }
+
+    private static String cell(boolean value, int width) {
+        StringBuilder sb = new StringBuilder(value ? "1" : "0");
+        while (sb.length() < width + 1) sb.append(' ');
+        return sb.toString();
+    }
+
+    /** The state diagram as a list of arrows, "present ──input/output──► next". */
+    public String stateDiagram() {
+        List<String> reachable = reachableStates();
+        StringBuilder sb = new StringBuilder();
+        for (int s = 0; s < nextState.length; s++) {
+            String name = stateName(s);
+            String circle = moore ? "(" + name + "/" + output[s][0] + ")" : "(" + name + ")";
+            String marker = s == 0 ? "→" : reachable.contains(name) ? " " : "✗";
+            for (int x = 0; x < nextState[s].length; x++) {
+                if (x > 0) marker = " ";
+                String label = moore ? inputSymbol(x) : inputSymbol(x) + "/" + output[s][x];
+                String target = moore ? "(" + stateName(nextState[s][x]) + "/" + output[nextState[s][x]][0] + ")"
+                    : "(" + stateName(nextState[s][x]) + ")";
+                sb.append(String.format("  %s %s ──%s──► %s\n", marker, x == 0 ? circle : pad("", circle.length()),
+                    label, target));
+            }
+        }
+        sb.append("\n  (→ reset state");
+        if (reachable.size() < nextState.length) sb.append(", ✗ unreachable from reset");
+        sb.append(moore ? "; circles are state/output)\n" : "; arrows are input/output)\n");
+        return sb.toString();
+    }
+
+    private static String pad(String s, int width) {
+        StringBuilder sb = new StringBuilder(s);
+        while (sb.length() < width) sb.append(' ');
+        return sb.toString();
+    }
+
+    /** The whole analysis, in the order an exam answer is written. */
+    public String report() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("State variables: ").append(String.join(" ", state)).append("   Inputs: ")
+          .append(inputs.isEmpty() ? "(none)" : String.join(" ", inputs)).append("   Outputs: ")
+          .append(outputs.isEmpty() ? "(none)" : String.join(" ", outputs)).append("\n\n");
+        sb.append("1) Next-state and output equations:\n\n").append(equations()).append("\n");
+        sb.append("2) Transition table:\n\n").append(transitionTable()).append("\n");
+        sb.append("3) State table:\n\n").append(toStateMachine().transitionTable()).append("\n");
+        sb.append("4) State diagram:\n\n").append(stateDiagram()).append("\n");
+        sb.append(moore ? "Moore machine: the outputs depend on the flip-flops only.\n"
+                        : "Mealy machine: the outputs depend on the inputs as well.\n");
+        return sb.toString();
+    }
+
+    /**
+     * Self-check: drive the netlist and the derived StateMachine with the same
+     * random inputs from reset and compare the outputs every cycle.
+     */
+    public static boolean verify(Netlist net, int cycles, long seed) {
+        SequentialAnalysis analysis = new SequentialAnalysis(net);
+        StateMachine fsm = analysis.toStateMachine();
+        Random random = new Random(seed);
+        List<String> symbols = new ArrayList<>();
+        int[] values = new int[cycles];
+        for (int c = 0; c < cycles; c++) {
+            values[c] = random.nextInt(1 << analysis.inputs.size());
+            symbols.add(analysis.inputSymbol(values[c]));
+        }
+        List<StateMachine.Step> steps = fsm.run(symbols);
+        net.reset();
+        for (int c = 0; c < cycles; c++) {
+            for (int j = 0; j < analysis.inputs.size(); j++) {
+                net.set(analysis.inputs.get(j), bit(values[c], j, analysis.inputs.size()));
+            }
+            net.settle();
+            StringBuilder out = new StringBuilder();
+            for (String o : analysis.outputs) out.append(net.get(o) ? '1' : '0');
+            String expected = steps.get(c).output;
+            if (!(out.length() == 0 ? "-" : out.toString()).equals(expected)) return false;
+            net.clock();
+        }
+        return true;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    /** A classic exam circuit: two D flip-flops A, B, input x, output y. */
+    public static final String EXAM_CIRCUIT =
+        "# A+ = Ax + Bx,  B+ = A'x,  y = (A + B)x'\n" +
+        "input  x\n" +
+        "output y\n" +
+        "A  = dff DA\n" +
+        "B  = dff DB\n" +
+        "t1 = and A x\n" +
+        "t2 = and B x\n" +
+        "DA = or t1 t2\n" +
+        "nA = not A\n" +
+        "DB = and nA x\n" +
+        "nx = not x\n" +
+        "t3 = or A B\n" +
+        "y  = and t3 nx\n";
+
+    public static String demonstrateSequentialAnalysis() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  SEQUENTIAL CIRCUIT ANALYSIS: FROM FLIP-FLOPS TO STATE TABLE\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("Circuit (netlist):\n\n").append(EXAM_CIRCUIT).append("\n");
+        Netlist exam = Netlist.parse(EXAM_CIRCUIT);
+        SequentialAnalysis analysis = new SequentialAnalysis(exam);
+        sb.append(analysis.report()).append("\n");
+        sb.append("Reading the diagram: A B count the 1s seen so far (01: one, 11: two,\n");
+        sb.append("10: three or more) and y = 1 when a 0 ends such a run.\n\n");
+
+        sb.append("The 3-bit counter from the HDL demo, analysed the same way:\n\n");
+        Netlist counter = Hdl.compile(Hdl.COUNTER_HDL);
+        SequentialAnalysis count = new SequentialAnalysis(counter);
+        sb.append(count.equations()).append("\n");
+        sb.append(count.stateDiagram()).append("\n");
+
+        sb.append(String.format("Derived machines agree with the gate simulation (200 random cycles): %s\n",
+            verify(exam, 200, 1) && verify(counter, 200, 2) ? "yes" : "NO"));
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: SequentialAnalysis [file.net|file.hdl]
+     */
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateSequentialAnalysis());
+            return;
+        }
+        try {
+            Netlist net = args[0].endsWith(".hdl") ? Hdl.load(Paths.get(args[0])).elaborate()
+                                                   : Netlist.load(Paths.get(args[0]));
+            System.out.println(new SequentialAnalysis(net).report());
+        } catch (IOException e) {
+            System.err.println(args[0] + ": " + (e instanceof NoSuchFileException ? "no such file" : e.getMessage()));
+            System.exit(2);
+        } catch (IllegalArgumentException | IllegalStateException e) {
+            System.err.println(args[0] + ": " + e.getMessage());
+            System.exit(2);
+        }
+    }
+}
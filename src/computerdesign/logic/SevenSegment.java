@@ -0,0 +1,233 @@
+package computerdesign.logic;
+
+import java.util.ArrayList;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * SevenSegment - A BCD to 7-segment decoder, from truth table to lit display.
+ *
+ * Covers learning goals: T3.2 (Combinational Logic - minimization, don't-cares)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE PROBLEM
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *      ━━━ a                Input: one BCD digit WXYZ (W = MSB), 0-9.
+ *   f ┃   ┃ b               Output: seven signals a-g, 1 = segment lit.
+ *      ━━━ g
+ *   e ┃   ┃ c               Codes 10-15 never occur in BCD, so they are
+ *      ━━━ d                DON'T-CARES: the minimizer may treat each as 0 or
+ *                           1, whichever gives the smaller circuit.
+ *
+ *   digit │ W X Y Z │ a b c d e f g
+ *   ──────┼─────────┼──────────────
+ *     0   │ 0 0 0 0 │ 1 1 1 1 1 1 0
+ *     1   │ 0 0 0 1 │ 0 1 1 0 0 0 0
+ *     ...                               (full table in SEGMENTS)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE PIPELINE
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   truth table ──► Quine-McCluskey ──► netlist ──► settle() ──► display
+ *   (SEGMENTS)      7 minimal SOPs      NOT, AND,    per digit    ━━━ ┃ ┃
+ *                   with don't-cares    OR gates
+ *
+ *   Product terms used by several segments are built once and shared: a
+ *   multiple-output circuit is cheaper than seven separate ones.
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class SevenSegment {
+
+    public static final String[] VARIABLES = {"W", "X", "Y", "Z"};
+    public static final String SEGMENT_NAMES = "abcdefg";
+
+    /** Lit segments per digit, as a string of segment letters. */
+    public static final String[] SEGMENTS = {
+        "abcdef", "bc", "abdeg", "abcdg", "bcfg", "acdfg", "acdefg", "abc", "abcdefg", "abcdfg"
+    };
+
+    private static final int[] DONT_CARES = {10, 11, 12, 13, 14, 15};
+
+    // ==================== MINIMIZATION ====================
+
+    /** Minterms (digits) for which a segment is lit. */
+    public static int[] minterms(char segment) {
+        List<Integer> on = new ArrayList<>();
+        for (int digit = 0; digit < SEGMENTS.length; digit++) {
+            if (SEGMENTS[digit].indexOf(segment) >= 0) on.add(digit);
+        }
+        int[] m = new int[on.size()];
+        for (int i = 0; i < m.length; i++) m[i] = on.get(i);
+        return m;
+    }
+
+    /** One minimal sum of products per segment, a-g. */
+    public static Map<Character, QuineMcCluskey.Result> minimize() {
+        Map<Character, QuineMcCluskey.Result> results = new LinkedHashMap<>();
+        for (char s : SEGMENT_NAMES.toCharArray()) {
+            results.put(s, new QuineMcCluskey(VARIABLES.length, minterms(s), DONT_CARES, VARIABLES).minimize());
+        }
+        return results;
+    }
+
+    // ==================== NETLIST ====================
+
+    /** Two-level NOT/AND/OR netlist with shared product terms. Inputs W X Y Z, outputs a-g. */
+    public static Netlist build() {
+        Netlist net = new Netlist();
+        net.addInput(VARIABLES);
+        for (char s : SEGMENT_NAMES.toCharArray()) net.addOutput(String.valueOf(s));
+        for (String v : VARIABLES) net.addGate(Netlist.GateType.NOT, v + "'", v);
+
+        Map<String, String> products = new LinkedHashMap<>();    // term → wire
+        for (Map.Entry<Character, QuineMcCluskey.Result> e : minimize().entrySet()) {
+            List<String> terms = new ArrayList<>();
+            for (QuineMcCluskey.Implicant p : e.getValue().cover) terms.add(product(net, products, p));
+            String out = String.valueOf(e.getKey());
+            if (terms.size() == 1) net.addGate(Netlist.GateType.BUF, out, terms.get(0)).delay = 0;
+            else net.addGate(Netlist.GateType.OR, out, terms.toArray(new String[0]));
+        }
+        net.validate();
+        return net;
+    }
+
+    /** Wire carrying one product term; AND gates are shared between segments. */
+    private static String product(Netlist net, Map<String, String> products, QuineMcCluskey.Implicant p) {
+        String term = p.term(VARIABLES);
+        String wire = products.get(term);
+        if (wire != null) return wire;
+        List<String> literals = new ArrayList<>();
+        int n = VARIABLES.length;
+        for (int i = 0; i < n; i++) {
+            int bit = n - 1 - i;
+            if ((p.mask >> bit & 1) == 1) continue;
+            literals.add((p.value >> bit & 1) == 1 ? VARIABLES[i] : VARIABLES[i] + "'");
+        }
+        if (literals.size() == 1) {
+            wire = literals.get(0);
+        } else {
+            wire = "p" + (products.size() + 1);
+            net.addGate(Netlist.GateType.AND, wire, literals.toArray(new String[0]));
+        }
+        products.put(term, wire);
+        return wire;
+    }
+
+    /** Segments lit by the circuit for a 4-bit code (also 10-15: whatever the don't-cares became). */
+    public static String segments(Netlist net, int code) {
+        for (int i = 0; i < VARIABLES.length; i++) {
+            net.set(VARIABLES[i], (code >> (VARIABLES.length - 1 - i) & 1) == 1);
+        }
+        net.settle();
+        StringBuilder lit = new StringBuilder();
+        for (char s : SEGMENT_NAMES.toCharArray()) {
+            if (net.get(String.valueOf(s))) lit.append(s);
+        }
+        return lit.toString();
+    }
+
+    /** Self-check: the gate circuit reproduces the truth table for 0-9. */
+    public static boolean verify(Netlist net) {
+        for (int digit = 0; digit < SEGMENTS.length; digit++) {
+            if (!segments(net, digit).equals(SEGMENTS[digit])) return false;
+        }
+        return true;
+    }
+
+    // ==================== DISPLAY ====================
+
+    /** Five text rows for the given lit segments (unlit segments drawn as dots). */
+    public static String[] glyph(String lit) {
+        String a = on(lit, 'a') ? " ━━━ " : " ··· ";
+        String g = on(lit, 'g') ? " ━━━ " : " ··· ";
+        String d = on(lit, 'd') ? " ━━━ " : " ··· ";
+        String upper = (on(lit, 'f') ? "┃" : "·") + "   " + (on(lit, 'b') ? "┃" : "·");
+        String lower = (on(lit, 'e') ? "┃" : "·") + "   " + (on(lit, 'c') ? "┃" : "·");
+        return new String[] {a, upper, g, lower, d};
+    }
+
+    private static boolean on(String lit, char segment) {
+        return lit.indexOf(segment) >= 0;
+    }
+
+    /** Several glyphs side by side. */
+    public static String render(List<String> glyphs) {
+        StringBuilder sb = new StringBuilder();
+        for (int row = 0; row < 5; row++) {
+            StringBuilder line = new StringBuilder();
+            for (String lit : glyphs) line.append(glyph(lit)[row]).append("  ");
+            sb.append(line.toString().replaceAll("\\s+$", "")).append("\n");
+        }
+        return sb.toString();
+    }
+
+    /** Drive the decoder circuit with each digit of the string and render the result. */
+    public static String display(Netlist net, String digits) {
+        List<String> glyphs = new ArrayList<>();
+        for (char c : digits.toCharArray()) {
+            if (c < '0' || c > '9') throw new IllegalArgumentException("Not a decimal digit: " + c);
+            glyphs.add(segments(net, c - '0'));
+        }
+        return render(glyphs);
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateSevenSegment() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  BCD TO 7-SEGMENT DECODER: TRUTH TABLE → GATES → DISPLAY\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("1) Minimal sum of products per segment (m10-m15 are don't-cares):\n\n");
+        for (Map.Entry<Character, QuineMcCluskey.Result> e : minimize().entrySet()) {
+            sb.append(String.format("   %c = %s\n", e.getKey(), e.getValue().expression));
+        }
+
+        Netlist net = build();
+        int and = 0;
+        int or = 0;
+        for (Netlist.Gate g : net.getGates()) {
+            if (g.type == Netlist.GateType.AND) and++;
+            if (g.type == Netlist.GateType.OR) or++;
+        }
+        sb.append(String.format("\n2) Netlist: 4 NOT, %d AND (shared between segments), %d OR gates\n", and, or));
+        sb.append("   Gate delays a-g:");
+        for (char s : SEGMENT_NAMES.toCharArray()) sb.append(' ').append(net.arrivalTime(String.valueOf(s)));
+        sb.append("  (c = X + Y' + Z needs no AND level)\n");
+        sb.append(String.format("   Circuit matches the truth table for 0-9: %s\n\n", verify(net) ? "yes" : "NO"));
+
+        sb.append("3) The circuit driving a display, digit by digit:\n\n");
+        sb.append(display(net, "0123456789")).append("\n");
+
+        sb.append("Codes 10-15 were don't-cares, so they show whatever the minimizer chose:\n\n");
+        List<String> invalid = new ArrayList<>();
+        for (int code = 10; code < 16; code++) invalid.add(segments(net, code));
+        sb.append(render(invalid));
+        StringBuilder labels = new StringBuilder();
+        for (int code = 10; code < 16; code++) labels.append(String.format("%4d   ", code));
+        sb.append(labels.toString().replaceAll("\\s+$", "")).append("\n");
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: SevenSegment [digits]   e.g. SevenSegment 4160
+     */
+    public static void main(String[] args) {
+        if (args.length > 0) {
+            try {
+                System.out.println(display(build(), args[0]));
+            } catch (IllegalArgumentException e) {
+                System.err.println(e.getMessage() + "\nUsage: SevenSegment [digits]");
+                System.exit(2);
+            }
+            return;
+        }
+        System.out.println(demonstrateSevenSegment());
+    }
+}
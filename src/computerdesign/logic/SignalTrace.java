@@ -0,0 +1,142 @@
+package computerdesign.logic;
+
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * SignalTrace - Recorded history of named signals, one sample per time step.
+ *
+ * Every simulator in this package (flip-flops, netlists, FSMs) can record into
+ * a trace; renderers turn it into tables or timing diagrams.
+ *
+ * Values are '0', '1' or 'X' (unknown - e.g. after a forbidden SR input or
+ * a metastable flip-flop). Multi-bit signals may record any other character
+ * (e.g. a state name's first letter) via {@link #record(String, char)}.
+ *
+ *   t     0 1 2 3 4 5
+ *   CLK   0 1 0 1 0 1
+ *   D     1 1 0 0 1 1
+ *   Q     0 1 1 0 0 1
+ */
+public class SignalTrace {
+
+    private final Map<String, StringBuilder> signals = new LinkedHashMap<>();
+    private final Map<String, List<String>> labels = new LinkedHashMap<>();
+
+    public SignalTrace(String... names) {
+        for (String name : names) signals.put(name, new StringBuilder());
+    }
+
+    // ==================== RECORDING ====================
+
+    public void record(String name, boolean value) {
+        record(name, value ? '1' : '0');
+    }
+
+    public void record(String name, char value) {
+        signals.computeIfAbsent(name, k -> new StringBuilder()).append(value);
+    }
+
+    public void recordUnknown(String name) {
+        record(name, 'X');
+    }
+
+    /**
+     * Record a bus/state value: stored as a label per step so renderers can
+     * print "S0", "S1", ... inside the waveform.
+     */
+    public void recordLabel(String name, String label) {
+        labels.computeIfAbsent(name, k -> new ArrayList<>()).add(label);
+        record(name, '=');
+    }
+
+    // ==================== ACCESS ====================
+
+    public List<String> getNames() {
+        return Collections.unmodifiableList(new ArrayList<>(signals.keySet()));
+    }
+
+    /** History of one signal as a string of '0'/'1'/'X'/'='. */
+    public String getHistory(String name) {
+        StringBuilder sb = signals.get(name);
+        if (sb == null) throw new IllegalArgumentException("No such signal: " + name);
+        return sb.toString();
+    }
+
+    public char getValue(String name, int step) {
+        return getHistory(name).charAt(step);
+    }
+
+    /** Labels recorded with recordLabel, or null for plain bit signals. */
+    public List<String> getLabels(String name) {
+        List<String> l = labels.get(name);
+        return l == null ? null : Collections.unmodifiableList(l);
+    }
+
+    public boolean isBus(String name) {
+        return labels.containsKey(name);
+    }
+
+    /**
+     * Steps from .. to-1 of every signal as a new trace, step `from`
+     * becoming 0: for diagrams of one part of a long run.
+     */
+    public SignalTrace slice(int from, int to) {
+        SignalTrace out = new SignalTrace();
+        for (Map.Entry<String, StringBuilder> e : signals.entrySet()) {
+            String name = e.getKey();
+            String history = e.getValue().toString();
+            List<String> l = labels.get(name);
+            out.signals.put(name, new StringBuilder());
+            if (l != null) out.labels.put(name, new ArrayList<>());
+            int li = 0;
+            for (int t = 0; t < Math.min(from, history.length()); t++) if (history.charAt(t) == '=') li++;
+            for (int t = from; t < Math.min(to, history.length()); t++) {
+                if (history.charAt(t) == '=') out.recordLabel(name, l.get(li++));
+                else out.record(name, history.charAt(t));
+            }
+        }
+        return out;
+    }
+
+    /** Number of recorded steps (longest signal). */
+    public int length() {
+        int n = 0;
+        for (StringBuilder sb : signals.values()) n = Math.max(n, sb.length());
+        return n;
+    }
+
+    // ==================== OUTPUT ====================
+
+    /**
+     * Plain table: one row per signal, one column per time step.
+     */
+    @Override
+    public String toString() {
+        int width = 4;
+        for (String name : signals.keySet()) width = Math.max(width, name.length() + 1);
+        int steps = length();
+        int cell = String.valueOf(Math.max(steps - 1, 0)).length() + 1;
+        for (List<String> l : labels.values()) {
+            for (String label : l) cell = Math.max(cell, label.length() + 1);
+        }
+
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("%-" + width + "s", "t"));
+        for (int t = 0; t < steps; t++) sb.append(String.format("%" + cell + "d", t));
+        sb.append("\n");
+        for (Map.Entry<String, StringBuilder> e : signals.entrySet()) {
+            sb.append(String.format("%-" + width + "s", e.getKey()));
+            List<String> l = labels.get(e.getKey());
+            for (int t = 0; t < e.getValue().length(); t++) {
+                String v = l != null ? l.get(t) : String.valueOf(e.getValue().charAt(t));
+                sb.append(String.format("%" + cell + "s", v));
+            }
+            sb.append("\n");
+        }
+        return sb.toString();
+    }
+}
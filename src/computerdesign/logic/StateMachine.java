@@ -0,0 +1,664 @@
+package computerdesign.logic;
+
+import computerdesign.util.Json;
+import computerdesign.util.Toml;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+import java.util.regex.Matcher;
+import java.util.regex.Pattern;
+
+/**
+ * StateMachine - Moore and Mealy finite state machines loaded from files.
+ *
+ * Covers learning goals: T4.1 (FSM control), T4.2 (Sequential Logic)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * MOORE vs MEALY
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   MOORE: output depends on the STATE only      (output written in the circle)
+ *   MEALY: output depends on STATE and INPUT     (output written on the arrow)
+ *
+ *   Moore "101" detector (4 states):        Mealy "101" detector (3 states):
+ *
+ *        1         0         1                    1/0       0/0
+ *   (S0/0)──►(S1/0)──►(S2/0)──►(S3/1)         (S0)────►(S1)────►(S2)
+ *                                                ▲        1/1     │
+ *                                                └───(back to S1)─┘
+ *
+ *   Mealy machines usually need fewer states and react in the SAME cycle;
+ *   Moore outputs are glitch-free because they change only on clock edges.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * DESCRIPTION FORMAT (TOML, or the same structure in JSON)
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   name    = "101 detector"
+ *   type    = "mealy"            # or "moore"
+ *   initial = "S0"
+ *   inputs  = ["0", "1"]         # optional: column order of the state table
+ *   states  = ["S0", "S1", "S2"] # Mealy: list of states
+ *                                # Moore: [states] table, S0 = "0" (state → output)
+ *   [[transition]]
+ *   from = "S0"
+ *   input = "1"                  # "*" = any declared input not listed for this state
+ *   to = "S1"
+ *   output = "0"                 # Mealy only
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class StateMachine {
+
+    public enum Type { MOORE, MEALY }
+
+    /** Wildcard input matching anything without an explicit transition. */
+    public static final String ANY = "*";
+
+    // ==================== TRANSITIONS ====================
+
+    public static class Transition {
+        public final String from;
+        public final String input;
+        public final String to;
+        public final String output;     // null for Moore machines
+
+        Transition(String from, String input, String to, String output) {
+            this.from = from;
+            this.input = input;
+            this.to = to;
+            this.output = output;
+        }
+    }
+
+    /** One simulated clock cycle. */
+    public static class Step {
+        public final int cycle;
+        public final String state;
+        public final String input;
+        public final String next;
+        public final String output;
+
+        Step(int cycle, String state, String input, String next, String output) {
+            this.cycle = cycle;
+            this.state = state;
+            this.input = input;
+            this.next = next;
+            this.output = output;
+        }
+    }
+
+    // ==================== STATE ====================
+
+    private final String name;
+    private final Type type;
+    private final String initial;
+    private final List<String> states = new ArrayList<>();
+    private final List<String> inputs = new ArrayList<>();
+    private final Map<String, String> stateOutputs = new LinkedHashMap<>();
+    private final Map<String, Map<String, Transition>> transitions = new LinkedHashMap<>();
+
+    public StateMachine(String name, Type type, String initial) {
+        this.name = name;
+        this.type = type;
+        this.initial = initial;
+        addState(initial);
+    }
+
+    // ==================== BUILDING ====================
+
+    public StateMachine addState(String state) {
+        if (!states.contains(state)) {
+            states.add(state);
+            transitions.put(state, new LinkedHashMap<>());
+        }
+        return this;
+    }
+
+    /** Moore state with its output. */
+    public StateMachine addState(String state, String output) {
+        addState(state);
+        stateOutputs.put(state, output);
+        return this;
+    }
+
+    public StateMachine addInput(String symbol) {
+        if (!symbol.equals(ANY) && !inputs.contains(symbol)) inputs.add(symbol);
+        return this;
+    }
+
+    public StateMachine addTransition(String from, String input, String to, String output) {
+        addState(from);
+        addState(to);
+        addInput(input);
+        if (type == Type.MEALY && output == null) {
+            throw new IllegalArgumentException("Mealy transition " + from + " --" + input
+                + "--> " + to + " needs an output");
+        }
+        Transition previous = transitions.get(from).put(input,
+            new Transition(from, input, to, type == Type.MEALY ? output : null));
+        if (previous != null) {
+            throw new IllegalArgumentException("Non-deterministic: two transitions from "
+                + from + " on input " + input);
+        }
+        return this;
+    }
+
+    // ==================== LOADING ====================
+
+    /** Parse TOML, or JSON if the text starts with '{'. */
+    public static StateMachine parse(String text) {
+        Map<String, Object> description = text.trim().startsWith("{")
+            ? Json.parseObject(text) : Toml.parse(text);
+        return fromDescription(description, text);
+    }
+
+    public static StateMachine load(Path path) throws IOException {
+        return parse(new String(Files.readAllBytes(path), StandardCharsets.UTF_8));
+    }
+
+    public static StateMachine fromDescription(Map<String, Object> d) {
+        return fromDescription(d, null);
+    }
+
+    /** With the source text, errors in transitions name their line. */
+    @SuppressWarnings("unchecked")
+    private static StateMachine fromDescription(Map<String, Object> d, String text) {
+        String typeName = string(d, "type", "moore");
+        Type type;
+        try {
+            type = Type.valueOf(typeName.toUpperCase());
+        } catch (IllegalArgumentException e) {
+            throw new IllegalArgumentException("type must be \"moore\" or \"mealy\": " + typeName);
+        }
+        String initial = string(d, "initial", null);
+        if (initial == null) throw new IllegalArgumentException("missing 'initial' state");
+
+        StateMachine fsm = new StateMachine(string(d, "name", "FSM"), type, initial);
+        for (Object symbol : list(d, "inputs")) fsm.addInput(String.valueOf(symbol));
+
+        Object states = d.get("states");
+        List<String> declared = new ArrayList<>();
+        if (states instanceof Map) {
+            for (Map.Entry<String, Object> e : ((Map<String, Object>) states).entrySet()) {
+                fsm.addState(e.getKey(), String.valueOf(e.getValue()));
+                declared.add(e.getKey());
+            }
+        } else if (states instanceof List) {
+            for (Object s : (List<Object>) states) {
+                fsm.addState(String.valueOf(s));
+                declared.add(String.valueOf(s));
+            }
+        }
+
+        // Declared states and inputs are the whole alphabet: a transition
+        // outside them is a typo, not a new state
+        boolean statesDeclared = !declared.isEmpty();
+        boolean inputsDeclared = !fsm.inputs.isEmpty();
+        if (statesDeclared && !declared.contains(initial)) {
+            throw new IllegalArgumentException(at(text, "initial", 0) + "initial state " + initial + " is not in states");
+        }
+        List<Object> ts = d.containsKey("transitions") ? list(d, "transitions") : list(d, "transition");
+        for (int i = 0; i < ts.size(); i++) {
+            if (!(ts.get(i) instanceof Map)) throw new IllegalArgumentException("transition must be a table");
+            Map<String, Object> t = (Map<String, Object>) ts.get(i);
+            String from = required(t, "from");
+            String input = required(t, "input");
+            String to = required(t, "to");
+            if (statesDeclared && !declared.contains(from)) {
+                throw new IllegalArgumentException(at(text, "from", i) + "transition from undeclared state " + from);
+            }
+            if (statesDeclared && !declared.contains(to)) {
+                throw new IllegalArgumentException(at(text, "to", i) + "transition to undeclared state " + to);
+            }
+            if (inputsDeclared && !input.equals(ANY) && !fsm.inputs.contains(input)) {
+                throw new IllegalArgumentException(at(text, "input", i) + "undeclared input " + input
+                    + " (inputs: " + String.join(", ", fsm.inputs) + ")");
+            }
+            fsm.addTransition(from, input, to, string(t, "output", null));
+        }
+        fsm.validate();
+        return fsm;
+    }
+
+    /**
+     * "line N: " for the n-th occurrence of key in the source text (TOML
+     * {@code key =} or JSON {@code "key":}), or "" without the text. Each
+     * transition has one from, input and to, so the n-th is transition n's.
+     */
+    private static String at(String text, String key, int n) {
+        if (text == null) return "";
+        Matcher m = Pattern.compile("(?m)(^|[{,])\\s*\"?" + key + "\"?\\s*[=:]").matcher(text);
+        for (int i = 0; m.find(); i++) {
+            if (i == n) return "line " + (text.substring(0, m.end()).split("\n", -1).length) + ": ";
+        }
+        return "";
+    }
+
+    private static String string(Map<String, Object> d, String key, String fallback) {
+        Object v = d.get(key);
+        return v == null ? fallback : String.valueOf(v);
+    }
+
+    private static String required(Map<String, Object> d, String key) {
+        String v = string(d, key, null);
+        if (v == null) throw new IllegalArgumentException("transition is missing '" + key + "'");
+        return v;
+    }
+
+    @SuppressWarnings("unchecked")
+    private static List<Object> list(Map<String, Object> d, String key) {
+        Object v = d.get(key);
+        if (v == null) return Collections.emptyList();
+        if (!(v instanceof List)) throw new IllegalArgumentException("'" + key + "' must be an array");
+        return (List<Object>) v;
+    }
+
+    /** Moore machines need an output for every state. */
+    public void validate() {
+        if (type == Type.MOORE) {
+            for (String s : states) {
+                if (!stateOutputs.containsKey(s)) {
+                    throw new IllegalArgumentException("Moore state " + s + " has no output");
+                }
+            }
+        }
+    }
+
+    // ==================== SIMULATION ====================
+
+    public Transition next(String state, String input) {
+        Map<String, Transition> out = transitions.get(state);
+        if (out == null) throw new IllegalArgumentException("Unknown state: " + state);
+        // "*" stands for the other declared inputs, not for any symbol at all
+        if (!inputs.isEmpty() && !inputs.contains(input)) {
+            throw new IllegalArgumentException("Unknown input " + input + " (inputs: " + String.join(", ", inputs) + ")");
+        }
+        Transition t = out.get(input);
+        if (t == null) t = out.get(ANY);
+        if (t == null) {
+            throw new IllegalStateException("No transition from " + state + " on input " + input);
+        }
+        return t;
+    }
+
+    public List<Step> run(List<String> inputSequence) {
+        List<Step> steps = new ArrayList<>();
+        String state = initial;
+        for (int i = 0; i < inputSequence.size(); i++) {
+            Transition t = next(state, inputSequence.get(i));
+            String output = type == Type.MOORE ? stateOutputs.get(state) : t.output;
+            steps.add(new Step(i, state, inputSequence.get(i), t.to, output));
+            state = t.to;
+        }
+        return steps;
+    }
+
+    /**
+     * Split "0110" into single characters when every input symbol is one
+     * character long, otherwise split on whitespace/commas ("coin coin push").
+     */
+    public List<String> splitInputs(String sequence) {
+        boolean singleChars = true;
+        for (String s : inputs) singleChars &= s.length() == 1;
+        String trimmed = sequence.trim();
+        if (singleChars && !trimmed.contains(" ") && !trimmed.contains(",")) {
+            List<String> symbols = new ArrayList<>();
+            for (char c : trimmed.toCharArray()) symbols.add(String.valueOf(c));
+            return symbols;
+        }
+        return Arrays.asList(trimmed.split("[,\\s]+"));
+    }
+
+    /** Printable state/output trace. */
+    public String trace(List<String> inputSequence) {
+        List<Step> steps = run(inputSequence);
+        int w = 5;
+        for (String s : states) w = Math.max(w, s.length());
+        for (String s : inputs) w = Math.max(w, s.length());
+        StringBuilder sb = new StringBuilder();
+        String fmt = " %5s │ %-" + w + "s │ %-" + w + "s │ %-" + w + "s │ %s\n";
+        sb.append(String.format(fmt, "Cycle", "State", "Input", "Next", "Output"));
+        sb.append("───────┼").append("─".repeat(w + 2)).append("┼").append("─".repeat(w + 2))
+          .append("┼").append("─".repeat(w + 2)).append("┼───────\n");
+        for (Step s : steps) {
+            sb.append(String.format(fmt, s.cycle, s.state, s.input, s.next, s.output));
+        }
+        if (type == Type.MOORE && !steps.isEmpty()) {
+            String last = steps.get(steps.size() - 1).next;
+            sb.append(String.format(" %5s │ %-" + w + "s │ %-" + w + "s │ %-" + w + "s │ %s\n",
+                steps.size(), last, "", "", stateOutputs.get(last)));
+        }
+        return sb.toString();
+    }
+
+    /** Record a run as signals (state as a labelled bus) for timing diagrams. */
+    public SignalTrace toSignalTrace(List<String> inputSequence) {
+        SignalTrace trace = new SignalTrace("in", "state", "out");
+        for (Step s : run(inputSequence)) {
+            recordSymbol(trace, "in", s.input);
+            trace.recordLabel("state", s.state);
+            recordSymbol(trace, "out", s.output);
+        }
+        return trace;
+    }
+
+    private static void recordSymbol(SignalTrace trace, String name, String symbol) {
+        if (symbol.equals("0") || symbol.equals("1")) trace.record(name, symbol.charAt(0));
+        else trace.recordLabel(name, symbol);
+    }
+
+    // ==================== STATE TABLE ====================
+
+    /**
+     * The state-transition table as drawn in FSM exercises:
+     * Moore: next state per input plus an output column; Mealy: "next/output".
+     */
+    public String transitionTable() {
+        List<String> columns = new ArrayList<>(inputs);
+        boolean anyWildcard = false;
+        for (Map<String, Transition> out : transitions.values()) anyWildcard |= out.containsKey(ANY);
+        if (anyWildcard && columns.isEmpty()) columns.add(ANY);
+
+        int w = 7;
+        for (String s : states) w = Math.max(w, s.length() + 1);
+        int cw = 6;
+        for (String s : states) {
+            for (String in : columns) {
+                Transition t = lookup(s, in);
+                if (t != null) cw = Math.max(cw, cell(t).length() + 1);
+            }
+        }
+        for (String in : columns) cw = Math.max(cw, in.length() + 4);
+
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("%s (%s)\n\n", name, type == Type.MOORE ? "Moore" : "Mealy"));
+        boolean moore = type == Type.MOORE;
+        sb.append(String.format("%-" + w + "s│", "Present"));
+        for (int i = 0; i < columns.size(); i++) {
+            boolean last = i == columns.size() - 1 && !moore;
+            String head = "in=" + columns.get(i);
+            sb.append(last ? " " + head : String.format(" %-" + cw + "s│", head));
+        }
+        if (moore) sb.append(" Output");
+        sb.append("\n");
+        sb.append("─".repeat(w)).append("┼");
+        for (int i = 0; i < columns.size(); i++) {
+            boolean last = i == columns.size() - 1 && !moore;
+            sb.append("─".repeat(cw + 1)).append(last ? "" : "┼");
+        }
+        if (moore) sb.append("───────");
+        sb.append("\n");
+        for (String s : states) {
+            sb.append(String.format("%-" + w + "s│", (s.equals(initial) ? "→" : "") + s));
+            for (int i = 0; i < columns.size(); i++) {
+                boolean last = i == columns.size() - 1 && !moore;
+                Transition t = lookup(s, columns.get(i));
+                String text = t == null ? "-" : cell(t);
+                sb.append(last ? " " + text : String.format(" %-" + cw + "s│", text));
+            }
+            if (moore) sb.append(" ").append(stateOutputs.get(s));
+            sb.append("\n");
+        }
+        sb.append("\n(→ marks the initial state");
+        sb.append(type == Type.MEALY ? "; cells are next/output)\n" : ")\n");
+        return sb.toString();
+    }
+
+    private Transition lookup(String state, String input) {
+        Map<String, Transition> out = transitions.get(state);
+        Transition t = out.get(input);
+        return t != null ? t : out.get(ANY);
+    }
+
+    private String cell(Transition t) {
+        return type == Type.MEALY ? t.to + "/" + t.output : t.to;
+    }
+
+    // ==================== REPORT EXPORT ====================
+
+    /**
+     * The state table as a Markdown table, laid out like tdt4160 fsm
+     * --format markdown: cells are next/output for Mealy, and Moore adds
+     * an Output column.
+     */
+    public String toMarkdown() {
+        List<List<String>> rows = stateRows();
+        int[] w = new int[rows.get(0).size()];
+        for (List<String> row : rows) {
+            for (int i = 0; i < w.length; i++) w[i] = Math.max(w[i], Math.max(3, row.get(i).replace("|", "\\|").length()));
+        }
+        StringBuilder sb = new StringBuilder("**").append(caption()).append("**\n\n");
+        for (int r = 0; r < rows.size(); r++) {
+            sb.append("|");
+            for (int i = 0; i < w.length; i++) {
+                String cell = rows.get(r).get(i).replace("|", "\\|");
+                int pad = w[i] - cell.length();
+                String left = i == 0 ? "" : " ".repeat(pad / 2);
+                String right = " ".repeat(i == 0 ? pad : pad - pad / 2);
+                sb.append(" ").append(left).append(cell).append(right).append(" |");
+            }
+            sb.append("\n");
+            if (r == 0) {
+                sb.append("|:").append("-".repeat(w[0] + 1)).append("|");
+                for (int i = 1; i < w.length; i++) sb.append(":").append("-".repeat(w[i])).append(":|");
+                sb.append("\n");
+            }
+        }
+        return sb.toString();
+    }
+
+    /** The state table as a LaTeX tabular, laid out like tdt4160 fsm --format latex. */
+    public String toLatex() {
+        List<List<String>> rows = stateRows();
+        StringBuilder sb = new StringBuilder("% ").append(caption()).append("\n");
+        sb.append("\\begin{tabular}{l").append("c".repeat(rows.get(0).size() - 1)).append("}\n\\hline\n");
+        for (int r = 0; r < rows.size(); r++) {
+            List<String> cells = new ArrayList<>();
+            for (String cell : rows.get(r)) cells.add(texEscape(cell));
+            sb.append(String.join(" & ", cells)).append(r == 0 ? " \\\\ \\hline\n" : " \\\\\n");
+        }
+        return sb.append("\\hline\n\\end{tabular}\n").toString();
+    }
+
+    private String caption() {
+        return "State table, " + name + " (" + (type == Type.MOORE ? "Moore" : "Mealy") + ", initial " + initial + ")";
+    }
+
+    /** Header then one row per state: the same cells transitionTable() draws. */
+    private List<List<String>> stateRows() {
+        List<String> columns = new ArrayList<>(inputs);
+        boolean anyWildcard = false;
+        for (Map<String, Transition> out : transitions.values()) anyWildcard |= out.containsKey(ANY);
+        if (anyWildcard && columns.isEmpty()) columns.add(ANY);
+
+        List<List<String>> rows = new ArrayList<>();
+        List<String> header = new ArrayList<>();
+        header.add("Present");
+        for (String in : columns) header.add("in=" + in);
+        if (type == Type.MOORE) header.add("Output");
+        rows.add(header);
+        for (String s : states) {
+            List<String> row = new ArrayList<>();
+            row.add(s);
+            for (String in : columns) {
+                Transition t = lookup(s, in);
+                row.add(t == null ? "-" : cell(t));
+            }
+            if (type == Type.MOORE) row.add(stateOutputs.get(s));
+            rows.add(row);
+        }
+        return rows;
+    }
+
+    private static String texEscape(String text) {
+        StringBuilder sb = new StringBuilder();
+        for (char c : text.toCharArray()) {
+            switch (c) {
+                case '\\': sb.append("\\textbackslash{}"); break;
+                case '&': case '%': case '$': case '#': case '_': case '{': case '}':
+                    sb.append('\\').append(c); break;
+                case '~': sb.append("\\textasciitilde{}"); break;
+                case '^': sb.append("\\textasciicircum{}"); break;
+                case '<': sb.append("\\textless{}"); break;
+                case '>': sb.append("\\textgreater{}"); break;
+                case '|': sb.append("\\textbar{}"); break;
+                default: sb.append(c);
+            }
+        }
+        return sb.toString();
+    }
+
+    // ==================== GRAPHVIZ ====================
+
+    /**
+     * Graphviz DOT source of the state diagram. Transitions between the same
+     * two states are merged into one arrow with all their labels.
+     */
+    public String toDot() {
+        StringBuilder sb = new StringBuilder("digraph " + Netlist.dotId(name) + " {\n");
+        sb.append("  rankdir=LR;\n");
+        sb.append("  node [shape=circle, fontname=\"Helvetica\", fontsize=11];\n");
+        sb.append("  edge [fontname=\"Helvetica\", fontsize=10];\n");
+        sb.append("  start [shape=point];\n");
+        for (String s : states) {
+            String label = type == Type.MOORE ? s + "/" + stateOutputs.get(s) : s;
+            sb.append(String.format("  %s [label=%s];\n", Netlist.dotId(s), Netlist.dotId(label)));
+        }
+        sb.append("  start -> ").append(Netlist.dotId(initial)).append(";\n");
+        for (String from : states) {
+            Map<String, List<String>> arrows = new LinkedHashMap<>();
+            for (Transition t : transitions.get(from).values()) {
+                String label = type == Type.MEALY ? t.input + "/" + t.output : t.input;
+                arrows.computeIfAbsent(t.to, k -> new ArrayList<>()).add(label);
+            }
+            for (Map.Entry<String, List<String>> a : arrows.entrySet()) {
+                sb.append(String.format("  %s -> %s [label=%s];\n", Netlist.dotId(from),
+                    Netlist.dotId(a.getKey()), Netlist.dotId(String.join(", ", a.getValue()))));
+            }
+        }
+        return sb.append("}\n").toString();
+    }
+
+    // ==================== ACCESSORS ====================
+
+    public String getName() { return name; }
+    public Type getType() { return type; }
+    public String getInitial() { return initial; }
+    public List<String> getStates() { return Collections.unmodifiableList(states); }
+    public List<String> getInputs() { return Collections.unmodifiableList(inputs); }
+    public String getStateOutput(String state) { return stateOutputs.get(state); }
+
+    public List<Transition> getTransitions() {
+        List<Transition> all = new ArrayList<>();
+        for (Map<String, Transition> out : transitions.values()) all.addAll(out.values());
+        return all;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static final String SEQ101_MEALY =
+        "name    = \"Overlapping 101 detector\"\n" +
+        "type    = \"mealy\"\n" +
+        "initial = \"S0\"\n" +
+        "inputs  = [\"0\", \"1\"]\n" +
+        "states  = [\"S0\", \"S1\", \"S2\"]   # S0: nothing, S1: seen 1, S2: seen 10\n" +
+        "\n" +
+        "[[transition]]\nfrom = \"S0\"\ninput = \"0\"\nto = \"S0\"\noutput = \"0\"\n" +
+        "[[transition]]\nfrom = \"S0\"\ninput = \"1\"\nto = \"S1\"\noutput = \"0\"\n" +
+        "[[transition]]\nfrom = \"S1\"\ninput = \"0\"\nto = \"S2\"\noutput = \"0\"\n" +
+        "[[transition]]\nfrom = \"S1\"\ninput = \"1\"\nto = \"S1\"\noutput = \"0\"\n" +
+        "[[transition]]\nfrom = \"S2\"\ninput = \"0\"\nto = \"S0\"\noutput = \"0\"\n" +
+        "[[transition]]\nfrom = \"S2\"\ninput = \"1\"\nto = \"S1\"\noutput = \"1\"\n";
+
+    public static final String SEQ101_MOORE =
+        "{\n" +
+        "  \"name\": \"Overlapping 101 detector\",\n" +
+        "  \"type\": \"moore\",\n" +
+        "  \"initial\": \"S0\",\n" +
+        "  \"inputs\": [\"0\", \"1\"],\n" +
+        "  \"states\": {\"S0\": \"0\", \"S1\": \"0\", \"S2\": \"0\", \"S3\": \"1\"},\n" +
+        "  \"transitions\": [\n" +
+        "    {\"from\": \"S0\", \"input\": \"0\", \"to\": \"S0\"},\n" +
+        "    {\"from\": \"S0\", \"input\": \"1\", \"to\": \"S1\"},\n" +
+        "    {\"from\": \"S1\", \"input\": \"0\", \"to\": \"S2\"},\n" +
+        "    {\"from\": \"S1\", \"input\": \"1\", \"to\": \"S1\"},\n" +
+        "    {\"from\": \"S2\", \"input\": \"0\", \"to\": \"S0\"},\n" +
+        "    {\"from\": \"S2\", \"input\": \"1\", \"to\": \"S3\"},\n" +
+        "    {\"from\": \"S3\", \"input\": \"0\", \"to\": \"S2\"},\n" +
+        "    {\"from\": \"S3\", \"input\": \"1\", \"to\": \"S1\"}\n" +
+        "  ]\n" +
+        "}\n";
+
+    public static String demonstrateStateMachines() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  FINITE STATE MACHINES (MOORE vs MEALY)\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        String input = "1101011";
+        StateMachine mealy = parse(SEQ101_MEALY);
+        StateMachine moore = parse(SEQ101_MOORE);
+
+        sb.append("Mealy machine (loaded from TOML):\n\n");
+        sb.append(mealy.transitionTable()).append("\n");
+        sb.append("Input sequence: ").append(input).append("\n\n");
+        sb.append(mealy.trace(mealy.splitInputs(input))).append("\n");
+
+        sb.append("Moore machine (loaded from JSON):\n\n");
+        sb.append(moore.transitionTable()).append("\n");
+        sb.append(moore.trace(moore.splitInputs(input))).append("\n");
+
+        sb.append("The Mealy output is 1 in the SAME cycle the final 1 arrives (cycles 3, 5);\n");
+        sb.append("the Moore output goes high one cycle LATER (cycles 4, 6) and needs an\n");
+        sb.append("extra state S3 to remember \"just saw 101\".\n");
+        sb.append("\nThe Mealy machine as Graphviz (toDot(); render with dot -Tsvg):\n\n");
+        sb.append(mealy.toDot());
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: StateMachine [file.toml|file.json [inputs | --dot | --markdown | --latex]]
+     */
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateStateMachines());
+            return;
+        }
+        try {
+            StateMachine fsm = load(Paths.get(args[0]));
+            if (args.length > 1 && args[1].equals("--dot")) {
+                System.out.print(fsm.toDot());
+                return;
+            }
+            if (args.length > 1 && args[1].equals("--markdown")) {
+                System.out.print(fsm.toMarkdown());
+                return;
+            }
+            if (args.length > 1 && args[1].equals("--latex")) {
+                System.out.print(fsm.toLatex());
+                return;
+            }
+            System.out.println(fsm.transitionTable());
+            if (args.length > 1) System.out.println(fsm.trace(fsm.splitInputs(args[1])));
+        } catch (IOException e) {
+            System.err.println(args[0] + ": " + (e instanceof NoSuchFileException ? "no such file" : e.getMessage()));
+            System.exit(2);
+        } catch (IllegalArgumentException | IllegalStateException e) {
+            System.err.println(args[0] + ": " + e.getMessage());
+            System.exit(2);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+package computerdesign.logic;
+
+import java.io.IOException;
+import java.nio.file.AccessDeniedException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.FileSystemException;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.List;
+
+/**
+ * TimingDiagram - Renders a SignalTrace as an ASCII or SVG waveform.
+ *
+ * Covers learning goals: T4.2 (Sequential Logic), T4.1 (State Machines)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * READING A TIMING DIAGRAM
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *              ↑       ↑       ↑
+ *   CLK    ____/‾‾‾\___/‾‾‾\___/‾‾‾
+ *   D      ________/‾‾‾‾‾‾‾\_______
+ *   Q      ____________/‾‾‾‾‾‾‾\___
+ *   state  X S0    X S1    X S2
+ *
+ *   ‾ high   _ low   / rising   \ falling   x unknown
+ *   Buses (state names, multi-bit values) show their value after each X,
+ *   the crossing where the bus changes.
+ *
+ *   ↑ marks rising clock edges: the moments edge-triggered flip-flops sample.
+ *   Follow a ↑ straight down to see what D was at the edge and how Q
+ *   responds AFTER it - the key skill in flip-flop exercises.
+ *
+ * Traces without a clock signal (e.g. FSM runs, where one step is one
+ * cycle) can mark an edge at the start of every step with everyStep().
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class TimingDiagram {
+
+    private final SignalTrace trace;
+    private String clock;
+    private boolean everyStep = false;
+    private int cellWidth = 4;
+    private int stepsPerUnit = 1;
+
+    public TimingDiagram(SignalTrace trace) {
+        this.trace = trace;
+        this.clock = trace.getNames().contains("CLK") ? "CLK" : null;
+    }
+
+    /** Signal whose rising edges are marked (default CLK, if recorded). */
+    public TimingDiagram clock(String name) {
+        if (name != null && !trace.getNames().contains(name)) {
+            throw new IllegalArgumentException("No such signal: " + name);
+        }
+        this.clock = name;
+        this.everyStep = false;
+        return this;
+    }
+
+    /** Mark an edge at every step: for traces where one step is one clock cycle. */
+    public TimingDiagram everyStep() {
+        this.clock = null;
+        this.everyStep = true;
+        return this;
+    }
+
+    /** Characters per time step in the ASCII rendering. */
+    public TimingDiagram cellWidth(int width) {
+        if (width < 2) throw new IllegalArgumentException("cellWidth must be >= 2");
+        this.cellWidth = width;
+        return this;
+    }
+
+    /**
+     * Count the ASCII time axis in units of `steps` steps (e.g. bit times
+     * when a bit lasts several steps): one label at the start of each unit.
+     */
+    public TimingDiagram stepsPerUnit(int steps) {
+        if (steps < 1) throw new IllegalArgumentException("stepsPerUnit must be >= 1");
+        this.stepsPerUnit = steps;
+        return this;
+    }
+
+    /** Time steps at which a rising clock edge occurs. */
+    public List<Integer> risingEdges() {
+        List<Integer> edges = new ArrayList<>();
+        int steps = trace.length();
+        for (int t = 0; t < steps; t++) {
+            if (everyStep) {
+                edges.add(t);
+            } else if (clock != null) {
+                String h = trace.getHistory(clock);
+                if (t < h.length() && h.charAt(t) == '1' && (t == 0 || h.charAt(t - 1) == '0')) edges.add(t);
+            }
+        }
+        return edges;
+    }
+
+    // ==================== ASCII ====================
+
+    public String toAscii() {
+        int width = 4;
+        for (String name : trace.getNames()) width = Math.max(width, name.length() + 2);
+        int steps = trace.length();
+        StringBuilder sb = new StringBuilder();
+
+        StringBuilder axis = new StringBuilder(pad("t", width));
+        if (stepsPerUnit == 1) {
+            for (int t = 0; t < steps; t++) axis.append(pad(String.valueOf(t), cellWidth));
+        } else {
+            for (int t = 0; t < steps; t += stepsPerUnit) {
+                axis.append(pad(String.valueOf(t / stepsPerUnit), Math.min(stepsPerUnit, steps - t) * cellWidth));
+            }
+        }
+        sb.append(axis.toString().replaceAll("\\s+$", "")).append("\n");
+
+        List<Integer> rising = risingEdges();
+        if (!rising.isEmpty()) {
+            StringBuilder edges = new StringBuilder(pad("", width));
+            for (int t = 0; t < steps; t++) edges.append(pad(rising.contains(t) ? "↑" : "", cellWidth));
+            sb.append(edges.toString().replaceAll("\\s+$", "")).append("\n");
+        }
+
+        for (String name : trace.getNames()) {
+            String row = trace.isBus(name) ? busRow(name) : bitRow(trace.getHistory(name));
+            sb.append(pad(name, width)).append(row.replaceAll("\\s+$", "")).append("\n");
+        }
+        return sb.toString();
+    }
+
+    private String bitRow(String history) {
+        StringBuilder sb = new StringBuilder();
+        for (int t = 0; t < history.length(); t++) {
+            char v = history.charAt(t);
+            char prev = t == 0 ? v : history.charAt(t - 1);
+            char level = level(v);
+            if (v != prev && v == '1' && prev == '0') sb.append('/');
+            else if (v != prev && v == '0' && prev == '1') sb.append('\\');
+            else if (v != prev) sb.append('|');
+            else sb.append(level);
+            for (int i = 1; i < cellWidth; i++) sb.append(level);
+        }
+        return sb.toString();
+    }
+
+    private static char level(char v) {
+        switch (v) {
+            case '1': return '‾';
+            case '0': return '_';
+            case 'X':
+            case 'x': return 'x';
+            default: return v;
+        }
+    }
+
+    private String busRow(String name) {
+        String[] values = busValues(name);
+        char[] row = new char[values.length * cellWidth];
+        Arrays.fill(row, ' ');
+        for (int t = 0; t < values.length; t++) {
+            if (t > 0 && values[t].equals(values[t - 1])) continue;
+            int end = t + 1;
+            while (end < values.length && values[end].equals(values[t])) end++;
+            // "X label", cut off before the next crossing
+            String text = "X " + values[t];
+            for (int i = 0; i < text.length() && t * cellWidth + i < end * cellWidth - 1; i++) {
+                row[t * cellWidth + i] = text.charAt(i);
+            }
+            row[t * cellWidth] = 'X';
+        }
+        return new String(row);
+    }
+
+    /** Per-step value of a bus: its label, or the raw character for plain steps. */
+    private String[] busValues(String name) {
+        List<String> labels = trace.getLabels(name);
+        String history = trace.getHistory(name);
+        String[] values = new String[history.length()];
+        int li = 0;
+        for (int t = 0; t < values.length; t++) {
+            values[t] = history.charAt(t) == '=' ? labels.get(li++) : String.valueOf(history.charAt(t));
+        }
+        return values;
+    }
+
+    private static String pad(String s, int width) {
+        StringBuilder sb = new StringBuilder(s);
+        while (sb.length() < width) sb.append(' ');
+        return sb.toString();
+    }
+
+    // ==================== SVG ====================
+
+    private static final int SVG_STEP = 40;
+    private static final int SVG_ROW = 36;
+    private static final int SVG_HIGH = 24;
+    private static final int SVG_LABEL = 90;
+
+    /** Standalone SVG document: one row per signal, dashed lines at rising edges. */
+    public String toSvg() {
+        List<String> names = trace.getNames();
+        int steps = trace.length();
+        int w = SVG_LABEL + steps * SVG_STEP + 10;
+        int h = 30 + names.size() * SVG_ROW;
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"%d\" height=\"%d\""
+            + " font-family=\"monospace\" font-size=\"12\">\n", w, h));
+        sb.append(String.format("<rect width=\"%d\" height=\"%d\" fill=\"white\"/>\n", w, h));
+        for (int t = 0; t < steps; t++) {
+            sb.append(String.format("<text x=\"%d\" y=\"14\" text-anchor=\"middle\" fill=\"#888\">%d</text>\n",
+                x(t) + SVG_STEP / 2, t));
+        }
+        for (int t : risingEdges()) {
+            sb.append(String.format("<line x1=\"%d\" y1=\"20\" x2=\"%d\" y2=\"%d\" stroke=\"#c33\""
+                + " stroke-dasharray=\"3,3\"/>\n", x(t), x(t), h));
+        }
+        for (int r = 0; r < names.size(); r++) {
+            String name = names.get(r);
+            int base = 30 + r * SVG_ROW + SVG_HIGH;
+            sb.append(String.format("<text x=\"4\" y=\"%d\">%s</text>\n", base - 6, escape(name)));
+            if (trace.isBus(name)) svgBus(sb, name, base);
+            else svgBits(sb, trace.getHistory(name), base);
+        }
+        sb.append("</svg>\n");
+        return sb.toString();
+    }
+
+    private static int x(int t) {
+        return SVG_LABEL + t * SVG_STEP;
+    }
+
+    private void svgBits(StringBuilder sb, String history, int base) {
+        StringBuilder points = new StringBuilder();
+        for (int t = 0; t < history.length(); t++) {
+            char v = history.charAt(t);
+            if (v == 'X' || v == 'x') {
+                sb.append(String.format("<rect x=\"%d\" y=\"%d\" width=\"%d\" height=\"%d\" fill=\"#ddd\""
+                    + " stroke=\"#999\"/>\n", x(t), base - SVG_HIGH, SVG_STEP, SVG_HIGH));
+            }
+            int y = v == '1' ? base - SVG_HIGH : v == '0' ? base : base - SVG_HIGH / 2;
+            points.append(x(t)).append(',').append(y).append(' ');
+            points.append(x(t + 1)).append(',').append(y).append(' ');
+        }
+        sb.append(String.format("<polyline points=\"%s\" fill=\"none\" stroke=\"#036\" stroke-width=\"2\"/>\n",
+            points.toString().trim()));
+    }
+
+    private void svgBus(StringBuilder sb, String name, int base) {
+        String[] values = busValues(name);
+        int top = base - SVG_HIGH;
+        int mid = base - SVG_HIGH / 2;
+        for (int t = 0; t < values.length; t++) {
+            if (t > 0 && values[t].equals(values[t - 1])) continue;
+            int end = t + 1;
+            while (end < values.length && values[end].equals(values[t])) end++;
+            int x0 = x(t);
+            int x1 = x(end);
+            sb.append(String.format("<polygon points=\"%d,%d %d,%d %d,%d %d,%d %d,%d %d,%d\""
+                + " fill=\"#eef\" stroke=\"#036\"/>\n",
+                x0, mid, x0 + 4, top, x1 - 4, top, x1, mid, x1 - 4, base, x0 + 4, base));
+            sb.append(String.format("<text x=\"%d\" y=\"%d\" text-anchor=\"middle\">%s</text>\n",
+                (x0 + x1) / 2, mid + 4, escape(values[t])));
+        }
+    }
+
+    private static String escape(String s) {
+        return s.replace("&", "&amp;").replace("<", "&lt;").replace(">", "&gt;");
+    }
+
+    public void writeSvg(Path path) throws IOException {
+        Files.write(path, toSvg().getBytes(StandardCharsets.UTF_8));
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    public static String demonstrateTimingDiagrams() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  TIMING DIAGRAMS\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("1) D latch vs D flip-flop:\n\n");
+        sb.append(new TimingDiagram(FlipFlops.latchVersusFlipFlop()).cellWidth(3).toAscii()).append("\n");
+        sb.append("   At each ↑ the flip-flop copies D; between edges it holds.\n");
+        sb.append("   The latch follows D for the whole time CLK is high.\n\n");
+
+        sb.append("2) SR latch, forbidden input then release (x = unknown):\n\n");
+        sb.append(new TimingDiagram(FlipFlops.forbiddenSR()).cellWidth(3).toAscii()).append("\n");
+
+        sb.append("3) Mealy \"101\" detector, one step per clock cycle:\n\n");
+        StateMachine mealy = StateMachine.parse(StateMachine.SEQ101_MEALY);
+        sb.append(new TimingDiagram(mealy.toSignalTrace(mealy.splitInputs("1101011")))
+            .everyStep().cellWidth(5).toAscii());
+        sb.append("\n   The output goes high in the same cycle as the final 1 (no extra state).\n");
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: TimingDiagram [--svg out.svg]  (SVG of the latch vs flip-flop trace)
+     */
+    public static void main(String[] args) {
+        if (args.length == 2 && args[0].equals("--svg")) {
+            try {
+                new TimingDiagram(FlipFlops.latchVersusFlipFlop()).writeSvg(Paths.get(args[1]));
+            } catch (IOException e) {
+                System.err.println(args[1] + ": " + writeError(e));
+                System.exit(2);
+            }
+            System.out.println("Wrote " + args[1]);
+            return;
+        }
+        System.out.println(demonstrateTimingDiagrams());
+    }
+
+    /** The reason a file could not be written, without the path repeated. */
+    private static String writeError(IOException e) {
+        if (e instanceof NoSuchFileException) return "no such directory";
+        if (e instanceof AccessDeniedException) return "permission denied";
+        if (e instanceof FileSystemException && ((FileSystemException) e).getReason() != null) {
+            return ((FileSystemException) e).getReason();
+        }
+        return e.getMessage();
+    }
+}
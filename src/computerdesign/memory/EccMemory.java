@@ -0,0 +1,346 @@
+package computerdesign.memory;
+
+import computerdesign.coding.Hamming;
+import computerdesign.instruction.Instruction;
+import computerdesign.processor.SingleCycleProcessor;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.List;
+
+/**
+ * EccMemory - DRAM with a SECDED code on every word: single errors are
+ * corrected on the fly, double errors are detected and reported.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * SECDED (39,32): HAMMING PLUS ONE PARITY BIT
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Each 32-bit word is stored with 7 check bits (an ECC DIMM is 72 bits wide
+ * for 64 data bits for the same reason). The 32 data bits take the
+ * non-power-of-two positions 3, 5, 6, 7, 9, ... 38 of a shortened Hamming
+ * code; check bit c_i is the parity of the data positions with bit i set,
+ * exactly as in Hamming(7,4) (see coding/Hamming). A seventh bit P is the
+ * parity of everything else.
+ *
+ * On every read the controller recomputes the checks:
+ *
+ *   syndrome  overall parity   meaning
+ *   ────────  ──────────────   ─────────────────────────────────────────
+ *     0         ok             no error
+ *     s ≠ 0     BAD            one flip at position s → correct it
+ *     0         BAD            P itself flipped → data is fine
+ *     s ≠ 0     ok             TWO flips: detected, cannot correct
+ *
+ * Without P, a double error has a syndrome pointing at an innocent third
+ * bit and "correction" silently corrupts the word. P turns that into a
+ * reported, uncorrectable error (a machine check on real hardware).
+ *
+ * A corrected word is written back (SCRUBBED) so a second flip in the same
+ * word later does not line up with the first into a double error.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage:
+ *   EccMemory ram = new EccMemory();
+ *   ram.write(0x100, 42);
+ *   ram.flipDataBit(0x100, 7);   // a cosmic ray
+ *   ram.read(0x100);             // 42, and getCorrected() == 1
+ *
+ * Covers learning goals: T6.1 (memory reliability)
+ */
+public class EccMemory extends MainMemory {
+
+    /** Check bits stored alongside each word. */
+    public static final int CHECK_BITS = 7;
+    private static final int[] DATA_POSITION = new int[32];   // data bit → codeword position
+
+    static {
+        int bit = 0;
+        for (int pos = 3; bit < 32; pos++) {
+            if ((pos & (pos - 1)) != 0) DATA_POSITION[bit++] = pos;
+        }
+    }
+
+    /** What a read found. */
+    public enum Outcome { CORRECTED_DATA, CORRECTED_CHECK, UNCORRECTABLE }
+
+    /** One logged ECC event. */
+    public static final class Event {
+        public final int address;
+        public final Outcome outcome;
+        public final int syndrome;
+        public final String detail;
+
+        Event(int address, Outcome outcome, int syndrome, String detail) {
+            this.address = address;
+            this.outcome = outcome;
+            this.syndrome = syndrome;
+            this.detail = detail;
+        }
+
+        @Override
+        public String toString() {
+            return String.format("0x%08X %-15s syndrome %2d  %s", address, outcome, syndrome, detail);
+        }
+    }
+
+    private final byte[] check;
+    private final List<Event> events = new ArrayList<>();
+    private int corrected;
+    private int uncorrectable;
+    private boolean scrub = true;
+
+    public EccMemory(int sizeInBytes) {
+        super(sizeInBytes);
+        check = new byte[sizeInBytes / 4];
+    }
+
+    /** 64KB, like MainMemory. */
+    public EccMemory() {
+        this(64 * 1024);
+    }
+
+    /** Write corrected words back (default on). */
+    public EccMemory setScrub(boolean scrub) {
+        this.scrub = scrub;
+        return this;
+    }
+
+    // ==================== THE CODE ====================
+
+    /** The six Hamming check bits c0..c5 of a data word. */
+    static int hammingChecks(int data) {
+        int s = 0;
+        for (int bit = 0; bit < 32; bit++) {
+            if ((data >>> bit & 1) != 0) s ^= DATA_POSITION[bit];
+        }
+        return s;
+    }
+
+    /** The 7 stored check bits: c0..c5 and the overall parity P in bit 6. */
+    public static int checkBits(int data) {
+        int c = hammingChecks(data);
+        int p = Hamming.parity(data) ^ Hamming.parity(c);
+        return c | p << 6;
+    }
+
+    private static int index(int address) {
+        return (address & 0x0000FFFF) / 4;
+    }
+
+    // ==================== READ / WRITE ====================
+
+    @Override
+    public void write(int address, int value) {
+        super.write(address, value);
+        check[index(address)] = (byte) checkBits(value);
+    }
+
+    @Override
+    public int read(int address) {
+        int raw = super.read(address);
+        int stored = check[index(address)] & 0x7F;
+        int syndrome = (stored & 0x3F) ^ hammingChecks(raw);
+        boolean parityBad = (Hamming.parity(raw) ^ Hamming.parity(stored)) != 0;
+
+        if (syndrome == 0 && !parityBad) return raw;
+        int wordAddress = address & ~0x3;
+        if (!parityBad) {
+            uncorrectable++;
+            events.add(new Event(wordAddress, Outcome.UNCORRECTABLE, syndrome, "double-bit error detected"));
+            return raw;
+        }
+        if (syndrome == 0) {
+            record(wordAddress, Outcome.CORRECTED_CHECK, 0, "overall parity bit P flipped");
+            if (scrub) check[index(address)] = (byte) checkBits(raw);
+            return raw;
+        }
+        if ((syndrome & (syndrome - 1)) == 0) {
+            record(wordAddress, Outcome.CORRECTED_CHECK, syndrome, "check bit c" + Integer.numberOfTrailingZeros(syndrome) + " flipped");
+            if (scrub) check[index(address)] = (byte) checkBits(raw);
+            return raw;
+        }
+        int bit = dataBitAt(syndrome);
+        if (bit < 0) {                                    // points outside the shortened code: 3+ flips
+            uncorrectable++;
+            events.add(new Event(wordAddress, Outcome.UNCORRECTABLE, syndrome, "syndrome names no bit position"));
+            return raw;
+        }
+        int fixed = raw ^ (1 << bit);
+        record(wordAddress, Outcome.CORRECTED_DATA, syndrome, "data bit " + bit + " flipped");
+        if (scrub) write(wordAddress, fixed);
+        return fixed;
+    }
+
+    private void record(int address, Outcome outcome, int syndrome, String detail) {
+        corrected++;
+        events.add(new Event(address, outcome, syndrome, detail));
+    }
+
+    private static int dataBitAt(int position) {
+        for (int bit = 0; bit < 32; bit++) {
+            if (DATA_POSITION[bit] == position) return bit;
+        }
+        return -1;
+    }
+
+    // ==================== ERROR INJECTION ====================
+
+    /** Flip a stored data bit (0-31) without updating the check bits. */
+    public EccMemory flipDataBit(int address, int bit) {
+        if (bit < 0 || bit > 31) throw new IllegalArgumentException("Data bit must be 0-31, got " + bit);
+        super.write(address, super.read(address) ^ (1 << bit));
+        return this;
+    }
+
+    /** Flip a stored check bit (0-5 = c0..c5, 6 = P). */
+    public EccMemory flipCheckBit(int address, int bit) {
+        if (bit < 0 || bit >= CHECK_BITS) throw new IllegalArgumentException("Check bit must be 0-6, got " + bit);
+        check[index(address)] ^= 1 << bit;
+        return this;
+    }
+
+    /** The stored word as it sits in the cells, without checking or correcting. */
+    public int readRaw(int address) {
+        return super.read(address);
+    }
+
+    public int readCheckBits(int address) {
+        return check[index(address)] & 0x7F;
+    }
+
+    // ==================== STATISTICS ====================
+
+    public int getCorrected() { return corrected; }
+    public int getUncorrectable() { return uncorrectable; }
+    public List<Event> getEvents() { return new ArrayList<>(events); }
+
+    public void clearEvents() {
+        events.clear();
+        corrected = 0;
+        uncorrectable = 0;
+    }
+
+    @Override
+    public void reset() {
+        super.reset();
+        Arrays.fill(check, (byte) 0);                    // checkBits(0) == 0
+        clearEvents();
+    }
+
+    @Override
+    public String getName() {
+        return "EccMemory";
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static String bits(int value, int width) {
+        String s = Integer.toBinaryString(value);
+        while (s.length() < width) s = "0" + s;
+        return s;
+    }
+
+    private static String lastEvent(EccMemory ram) {
+        List<Event> log = ram.events;
+        return log.isEmpty() ? "clean" : log.get(log.size() - 1).outcome + ", " + log.get(log.size() - 1).detail;
+    }
+
+    public static String demonstrateEcc() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== ECC Memory: SECDED per Word ===\n\n");
+        EccMemory ram = new EccMemory();
+        int word = 0xCAFEF00D;
+        int addr = 0x100;
+        ram.write(addr, word);
+        sb.append(String.format("1) Store 0x%08X at 0x%03X: check bits P c5..c0 = %s\n\n",
+            word, addr, bits(ram.readCheckBits(addr), 7)));
+
+        sb.append("2) Inject faults into the stored cells, then read:\n");
+        sb.append(String.format("   %-28s %-12s %s\n", "fault", "read", "controller"));
+        int[][] faults = {{}, {0}, {13}, {31}, {32}, {37}, {38}, {4, 9}, {0, 34}};   // 0-31 data, 32-38 check
+        for (int[] fault : faults) {
+            ram.write(addr, word);
+            StringBuilder what = new StringBuilder();
+            for (int f : fault) {
+                if (what.length() > 0) what.append(" + ");
+                inject(ram, addr, f);
+                what.append(f < 32 ? "data bit " + f : f == 38 ? "P" : "c" + (f - 32));
+            }
+            int got = ram.read(addr);
+            sb.append(String.format("   %-28s 0x%08X   %s%s\n", what.length() == 0 ? "none" : what, got,
+                lastEvent(ram), got == word ? "" : "  (data wrong)"));
+            ram.events.clear();
+        }
+        sb.append("\n");
+
+        sb.append("3) Scrubbing: the corrected word is written back\n");
+        ram.write(addr, word);
+        ram.flipDataBit(addr, 5);
+        ram.read(addr);
+        sb.append(String.format("   after the corrected read the cells hold 0x%08X again\n", ram.readRaw(addr)));
+        ram.flipDataBit(addr, 20);
+        ram.read(addr);
+        sb.append(String.format("   a second flip later is another single error: %s\n", lastEvent(ram)));
+        EccMemory noScrub = new EccMemory().setScrub(false);
+        noScrub.write(addr, word);
+        noScrub.flipDataBit(addr, 5);
+        noScrub.read(addr);
+        noScrub.flipDataBit(addr, 20);
+        noScrub.read(addr);
+        sb.append(String.format("   without scrubbing the two line up:           %s\n\n", lastEvent(noScrub)));
+
+        sb.append("4) Every 1- and 2-bit fault over all 39 stored bits:\n");
+        int singleOk = 0;
+        int doubleDetected = 0;
+        int singles = 0;
+        int doubles = 0;
+        for (int a = 0; a < 39; a++) {
+            ram.clearEvents();
+            ram.write(addr, word);
+            inject(ram, addr, a);
+            if (ram.read(addr) == word && ram.getCorrected() == 1) singleOk++;
+            singles++;
+            for (int b = a + 1; b < 39; b++) {
+                ram.clearEvents();
+                ram.write(addr, word);
+                inject(ram, addr, a);
+                inject(ram, addr, b);
+                ram.read(addr);
+                if (ram.getUncorrectable() == 1) doubleDetected++;
+                doubles++;
+            }
+        }
+        sb.append(String.format("   single faults corrected:   %d / %d\n", singleOk, singles));
+        sb.append(String.format("   double faults detected:    %d / %d (none miscorrected)\n\n", doubleDetected, doubles));
+
+        sb.append("5) A program keeps running through a flipped instruction bit:\n");
+        EccMemory mem = new EccMemory();
+        int[] program = {
+            Instruction.addi(5, 0, 20).getRaw(),      // t0 = 20
+            Instruction.addi(6, 0, 22).getRaw(),      // t1 = 22
+            Instruction.add(7, 5, 6).getRaw(),        // t2 = t0 + t1
+            0x00100073
+        };
+        mem.loadProgram(program, 0);
+        mem.flipDataBit(4, 21);                     // immediate bit 1 of the second addi: 22 → 20
+        SingleCycleProcessor cpu = new SingleCycleProcessor(mem);
+        cpu.run(100);
+        sb.append("   addi t0, zero, 20; addi t1, zero, 22; add t2, t0, t1 - bit 21 of instruction 2 flipped\n");
+        sb.append(String.format("   t2 = %d; ECC log: %s\n", cpu.getRegisterFile().read(7),
+            mem.getEvents().isEmpty() ? "clean" : mem.getEvents().get(0)));
+        sb.append(String.format("   without ECC the CPU would have fetched 0x%08X: addi t1, zero, %d\n",
+            program[1] ^ (1 << 21), (program[1] ^ (1 << 21)) >> 20));
+        return sb.toString();
+    }
+
+    /** Flip stored bit n of 39: 0-31 data, 32-38 check. */
+    private static void inject(EccMemory ram, int address, int n) {
+        if (n < 32) ram.flipDataBit(address, n);
+        else ram.flipCheckBit(address, n - 32);
+    }
+
+    public static void main(String[] args) {
+        System.out.println(demonstrateEcc());
+    }
+}
@@ -1,5 +1,6 @@
 package computerdesign.memory;
 
+import computerdesign.util.HexDump;
 import java.util.Arrays;
 import java.util.BitSet;
 
@@ -188,7 +189,15 @@ public class MainMemory implements MemoryUnit {
         }
         return sb.toString();
     }
-    
+
+    /**
+     * Byte-level dump of a range: hex and ASCII columns, little-endian byte
+     * order as stored (see HexDump).
+     */
+    public String hexDump(int startAddress, int length) {
+        return new HexDump().format(i -> readByte(startAddress + i), length, startAddress & 0xFFFFFFFFL);
+    }
+
     // Getters for memory regions
     public int getTextSegmentStart() { return textSegmentStart; }
     public int getDataSegmentStart() { return dataSegmentStart; }
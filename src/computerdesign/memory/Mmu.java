@@ -0,0 +1,102 @@
+package computerdesign.memory;
+
+import java.util.HashMap;
+import java.util.Map;
+
+/**
+ * Mmu - puts a process's virtual address space between the processor and RAM.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * TRANSLATION ON EVERY ACCESS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   ┌─────┐  virtual   ┌─────┐  physical  ┌─────┐
+ *   │ CPU │ ─────────► │ MMU │ ─────────► │ RAM │
+ *   └─────┘  address   └──┬──┘  address   └─────┘
+ *                         │ page not present / not permitted
+ *                         ▼
+ *                   PageFaultException / ProtectionFaultException
+ *
+ * Every fetch, load and store goes through VirtualMemory.read/write: the
+ * page table walk, the permission check and the dirty and accessed bits.
+ * A fault is thrown BEFORE the access has any effect, and the processor
+ * has not yet written a register or moved the PC - so the fault is precise
+ * and the OS can fix the mapping and simply run the same instruction again
+ * (see computerdesign.os.DemandPaging).
+ *
+ * Because Mmu is a MainMemory (like io.MemoryBus), any processor can be
+ * built on one: new SingleCycleProcessor(mmu). It holds no memory of its
+ * own; frame management stays with the physical memory behind it.
+ *
+ * For LRU replacement the MMU also stamps each page with the time of its
+ * last use. Real MMUs do not: they only set the accessed bit, and the OS
+ * approximates LRU from that (the Clock algorithm).
+ *
+ * A context switch points the MMU at the next process's address space
+ * (setAddressSpace), which is what loading the page table base register
+ * does in hardware.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage:
+ *   Mmu mmu = new Mmu(process.getVirtualMemory());
+ *   SingleCycleProcessor cpu = new SingleCycleProcessor(mmu);
+ */
+public class Mmu extends MainMemory {
+
+    private VirtualMemory space;
+    private final Map<Integer, Long> lastUse = new HashMap<>();
+    private long clock;
+
+    public Mmu(VirtualMemory space) {
+        super(space.getPageTable().getPageSize(), space.getPageTable().getPageSize());
+        this.space = space;
+    }
+
+    @Override
+    public int read(int address) {
+        int value = space.read(address);
+        touch(address);
+        return value;
+    }
+
+    @Override
+    public void write(int address, int value) {
+        space.write(address, value);
+        touch(address);
+    }
+
+    private void touch(int address) {
+        lastUse.put(space.getPageTable().getVPN(address), ++clock);
+    }
+
+    /** Access count at the last use of a page, 0 if never used. */
+    public long getLastUse(int virtualPage) {
+        return lastUse.getOrDefault(virtualPage, 0L);
+    }
+
+    /** Accesses that completed so far. */
+    public long getAccesses() {
+        return clock;
+    }
+
+    public VirtualMemory getAddressSpace() {
+        return space;
+    }
+
+    /** Switch to another process's address space; the use stamps start over. */
+    public void setAddressSpace(VirtualMemory space) {
+        this.space = space;
+        lastUse.clear();
+    }
+
+    @Override
+    public int getSize() {
+        return space.getPhysicalMemory().getSize();
+    }
+
+    @Override
+    public String getName() {
+        return "MMU";
+    }
+}
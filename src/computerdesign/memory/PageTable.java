@@ -395,6 +395,14 @@ public class PageTable {
         return pte != null && pte.isValid() && pte.isExecutable();
     }
     
+    /**
+     * The entry of a virtual page, or null. Unlike lookup() this is the OS
+     * reading its own table: no statistics, no accessed bit.
+     */
+    public PageTableEntry getEntry(int virtualPage) {
+        return entries.get(virtualPage);
+    }
+    
     /**
      * Get all mapped page entries (for debugging/display).
      */
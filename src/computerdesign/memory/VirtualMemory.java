@@ -202,7 +202,7 @@ public class VirtualMemory {
         int physicalAddress = pageTable.translate(virtualAddress);
         if (physicalAddress < 0) {
             pageFaults++;
-            throw new PageFaultException(virtualAddress, false, "translation failed");
+            throw new PageFaultException(virtualAddress, false, "page not present");
         }
         
         // Access physical memory
@@ -229,7 +229,7 @@ public class VirtualMemory {
         int physicalAddress = pageTable.translate(virtualAddress);
         if (physicalAddress < 0) {
             pageFaults++;
-            throw new PageFaultException(virtualAddress, true, "translation failed");
+            throw new PageFaultException(virtualAddress, true, "page not present");
         }
         
         // Mark page as dirty
@@ -0,0 +1,324 @@
+package computerdesign.os;
+
+import computerdesign.instruction.Instruction;
+import computerdesign.memory.MainMemory;
+import computerdesign.memory.Mmu;
+import computerdesign.memory.PageTable;
+import computerdesign.memory.RegisterFile;
+import computerdesign.memory.VirtualMemory;
+import computerdesign.processor.SingleCycleProcessor;
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.HashSet;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+import java.util.TreeMap;
+
+/**
+ * CopyOnWrite - fork that shares every frame between parent and child and
+ * copies a page only when one of them first writes to it.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * FORK WITHOUT COPYING
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   after fork                          after the child writes page D
+ *
+ *   parent PT       child PT            parent PT       child PT
+ *   T ─┐        ┌─ T   r-x              T ─┐        ┌─ T
+ *      ├─► F4 ◄─┤      refs 2              ├─► F4 ◄─┤        refs 2
+ *   D ─┼─► F5 ◄─┼─ D   r-- COW          D ─┼─► F5    │        refs 1
+ *      │        │                          │    F7 ◄─┼─ D rw- refs 1 (the copy)
+ *
+ * fork() copies only the page table. Every writable page becomes read-only
+ * in BOTH processes and is marked copy-on-write; each frame counts how many
+ * page tables point at it. A write to such a page is a protection fault,
+ * and the kernel's handler:
+ *
+ *   refs > 1   allocate a frame, copy the page, map the copy writable in
+ *              the faulting process, drop the old frame's count by one
+ *   refs = 1   the other process has copied already (or exited): just make
+ *              the page writable again, no copy
+ *
+ * and then runs the faulting store again. Pages nobody writes - code,
+ * constants, most of the data in a fork-then-exec - are never copied.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: CopyOnWrite        fork, a write in each process, exit - with the
+ *                           frame reference counts after every step
+ *
+ * Covers learning goals: T6.3 (virtual memory, sharing, protection faults)
+ *
+ * @see Process#fork - the page-table copy this builds on
+ * @see DemandPaging - the other fault the OS fixes and resumes from
+ */
+public final class CopyOnWrite {
+
+    static final int TEXT = 0x00400000;
+    static final int DATA = 0x10000000;
+    private static final int HALT = 0x00100073;
+    private static final int A0 = 10;
+
+    private final MainMemory ram;
+    private final Mmu mmu;
+    private final SingleCycleProcessor cpu;
+    private final int[] refs;                                 // page tables pointing at each frame
+    private final Map<Process, Set<Integer>> cow = new HashMap<>();
+    private final List<Process> processes = new ArrayList<>();
+    private final List<String> log = new ArrayList<>();
+    private int nextPid = 1;
+    private int copies;
+
+    public CopyOnWrite(MainMemory ram) {
+        this.ram = ram;
+        this.refs = new int[ram.getTotalFrames()];
+        this.mmu = new Mmu(new VirtualMemory(ram));
+        this.cpu = new SingleCycleProcessor(mmu);
+    }
+
+    // ==================== PROCESSES ====================
+
+    /** A new process with its program and data loaded, every page in its own frame. */
+    public Process spawn(String name, int[] program, int[] data) {
+        Process p = new Process(nextPid++, name, ram);
+        mapImage(p, TEXT, program, false, true);
+        mapImage(p, DATA, data, true, false);
+        p.setProgramCounter(TEXT);
+        p.setReady();
+        cow.put(p, new HashSet<>());
+        processes.add(p);
+        log.add(String.format("spawn pid %d (%s): %d pages", p.getPid(), name, countPages(p)));
+        return p;
+    }
+
+    private void mapImage(Process p, int start, int[] words, boolean writable, boolean executable) {
+        int pageWords = ram.getFrameSize() / 4;
+        PageTable pt = p.getPageTable();
+        for (int first = 0; first < words.length; first += pageWords) {
+            int frame = ram.allocateFrame();
+            if (frame < 0) throw new IllegalStateException("Out of physical frames");
+            int base = ram.getFrameAddress(frame);
+            for (int i = 0; i < pageWords; i++) {
+                ram.writePhysical(base + 4 * i, first + i < words.length ? words[first + i] : 0);
+            }
+            pt.mapPage(pt.getVPN(start) + first / pageWords, frame, true, writable, executable);
+            refs[frame] = 1;
+        }
+    }
+
+    /**
+     * fork(): the child gets a copy of the page table, not of the pages.
+     * Returns the child's pid in the parent's a0 and 0 in the child's.
+     */
+    public Process fork(Process parent) {
+        Process child = parent.fork(nextPid++, parent.getName() + "'");
+        cow.put(child, new HashSet<>());
+        int shared = 0;
+        for (Map.Entry<Integer, PageTable.PageTableEntry> e : parent.getPageTable().getEntries().entrySet()) {
+            PageTable.PageTableEntry pte = e.getValue();
+            if (!pte.isValid()) continue;
+            refs[pte.getFrameNumber()]++;
+            shared++;
+            if (pte.isWritable() || cow.get(parent).contains(e.getKey())) {
+                for (Process p : new Process[] {parent, child}) {
+                    p.getPageTable().getEntry(e.getKey()).setWritable(false);
+                    cow.get(p).add(e.getKey());
+                }
+            }
+        }
+        setA0(parent, child.getPid());
+        setA0(child, 0);
+        processes.add(child);
+        log.add(String.format("fork pid %d -> pid %d: %d page-table entries copied, 0 pages copied",
+            parent.getPid(), child.getPid(), shared));
+        return child;
+    }
+
+    private static void setA0(Process p, int value) {
+        RegisterFile registers = new RegisterFile();
+        p.restoreContext(registers);
+        registers.write(A0, value);
+        p.saveContext(registers, p.getProgramCounter());
+    }
+
+    /** exit(): drop the process's references; frames nobody uses are freed. */
+    public void exit(Process p) {
+        int freed = 0;
+        for (PageTable.PageTableEntry pte : p.getPageTable().getEntries().values()) {
+            if (!pte.isValid()) continue;
+            if (--refs[pte.getFrameNumber()] == 0) {
+                ram.freeFrame(pte.getFrameNumber());
+                freed++;
+            }
+        }
+        p.getVirtualMemory().unmapAll();
+        p.terminate(0);
+        processes.remove(p);
+        cow.remove(p);
+        log.add(String.format("exit pid %d: %d frame(s) freed, the rest still in use", p.getPid(), freed));
+    }
+
+    // ==================== RUNNING ====================
+
+    /** Switch to {@code p} and run it until it halts; false if it was killed. */
+    public boolean run(Process p, int maxCycles) {
+        mmu.setAddressSpace(p.getVirtualMemory());
+        cpu.reset();
+        p.restoreContext(cpu.getRegisterFile());
+        cpu.setPC(p.getProgramCounter());
+        p.setRunning();
+        int cycles = 0;
+        while (!cpu.isHalted() && cycles < maxCycles) {
+            try {
+                cpu.cycle();
+                cycles++;
+            } catch (VirtualMemory.ProtectionFaultException e) {
+                if (!handleWriteFault(p, e.getVirtualAddress())) {
+                    log.add(String.format("pid %d: %s, killed", p.getPid(), e.getMessage()));
+                    p.terminate(-1);
+                    return false;
+                }
+            } catch (VirtualMemory.PageFaultException e) {
+                log.add(String.format("pid %d: %s, killed", p.getPid(), e.getMessage()));
+                p.terminate(-1);
+                return false;
+            }
+        }
+        p.saveContext(cpu.getRegisterFile(), cpu.getPC());
+        p.setReady();
+        return cpu.isHalted();
+    }
+
+    /** The copy-on-write fault handler; false if the page was never writable. */
+    private boolean handleWriteFault(Process p, int va) {
+        PageTable pt = p.getPageTable();
+        int vpn = pt.getVPN(va);
+        if (!cow.get(p).remove(vpn)) return false;
+        PageTable.PageTableEntry pte = pt.getEntry(vpn);
+        int frame = pte.getFrameNumber();
+        String head = String.format("COW fault: pid %d store 0x%08X, frame %d has %d reference(s)",
+            p.getPid(), va, frame, refs[frame]);
+        if (refs[frame] == 1) {
+            pte.setWritable(true);
+            log.add(head + " -> last user: made writable, no copy");
+            return true;
+        }
+        int copy = ram.allocateFrame();
+        if (copy < 0) throw new IllegalStateException("Out of physical frames for a copy-on-write copy");
+        int from = ram.getFrameAddress(frame);
+        int to = ram.getFrameAddress(copy);
+        for (int i = 0; i < ram.getFrameSize() / 4; i++) {
+            ram.writePhysical(to + 4 * i, ram.readPhysical(from + 4 * i));
+        }
+        refs[frame]--;
+        refs[copy] = 1;
+        copies++;
+        pt.mapPage(vpn, copy, true, true, pte.isExecutable());
+        log.add(head + String.format(" -> copied to frame %d, mapped writable", copy));
+        return true;
+    }
+
+    // ==================== INSPECTION ====================
+
+    private static int countPages(Process p) {
+        return p.getPageTable().getMappedPageCount();
+    }
+
+    /** Every frame in use: its reference count and who maps it, how. */
+    public String frames() {
+        TreeMap<Integer, List<String>> users = new TreeMap<>();
+        for (Process p : processes) {
+            for (Map.Entry<Integer, PageTable.PageTableEntry> e : new TreeMap<>(p.getPageTable().getEntries()).entrySet()) {
+                PageTable.PageTableEntry pte = e.getValue();
+                if (!pte.isValid()) continue;
+                String perms = (pte.isReadable() ? "r" : "-") + (pte.isWritable() ? "w" : "-") + (pte.isExecutable() ? "x" : "-");
+                users.computeIfAbsent(pte.getFrameNumber(), f -> new ArrayList<>()).add(String.format(
+                    "pid %d 0x%05X %s%s", p.getPid(), e.getKey(), perms, cow.get(p).contains(e.getKey()) ? " COW" : ""));
+            }
+        }
+        StringBuilder sb = new StringBuilder("    frame  refs  mapped by\n");
+        for (Map.Entry<Integer, List<String>> e : users.entrySet()) {
+            sb.append(String.format("    %5d  %4d  %s\n", e.getKey(), refs[e.getKey()], String.join(",  ", e.getValue())));
+        }
+        sb.append(String.format("    %d frames in use, pages copied so far: %d\n", users.size(), copies));
+        return sb.toString();
+    }
+
+    public String takeLog() {
+        StringBuilder sb = new StringBuilder();
+        for (String line : log) sb.append("  ").append(line).append('\n');
+        log.clear();
+        return sb.toString();
+    }
+
+    public int word(Process p, int va) {
+        return p.getVirtualMemory().read(va);
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    /** data[0] += 1 + a0 (the first write); s0 = data[0] + the word on the next page. */
+    static int[] program() {
+        List<Instruction> c = new ArrayList<>();
+        c.add(Instruction.lui(6, DATA));                 // t1 = &data[0]
+        c.add(Instruction.lw(7, 6, 0));                  // t2 = data[0]
+        c.add(Instruction.addi(7, 7, 1));
+        c.add(Instruction.add(7, 7, A0));                //   + a0: the fork result
+        c.add(Instruction.sw(7, 6, 0));                  // first write: COW fault
+        c.add(Instruction.lui(29, DATA + 0x1000));       // t4 = the second data page
+        c.add(Instruction.lw(28, 29, 0));                // t3 = read only, never copied
+        c.add(Instruction.add(8, 7, 28));                // s0 = t2 + t3
+        int[] out = new int[c.size() + 1];
+        for (int i = 0; i < c.size(); i++) out[i] = c.get(i).getRaw();
+        out[c.size()] = HALT;
+        return out;
+    }
+
+    public static String demonstrateCopyOnWrite() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Copy-on-Write fork ===\n\n");
+        sb.append("Program: data[0] = data[0] + 1 + a0, then s0 = data[0] + data[1024] (next page).\n");
+        sb.append("fork() leaves the child's pid in the parent's a0 and 0 in the child's.\n\n");
+
+        CopyOnWrite os = new CopyOnWrite(new MainMemory());
+        int[] data = new int[1025];
+        data[0] = 1000;
+        data[1024] = 2000;
+
+        sb.append("1) spawn: one code page and two data pages, one reference each\n");
+        Process parent = os.spawn("shell", program(), data);
+        sb.append(os.takeLog()).append(os.frames()).append('\n');
+
+        sb.append("2) fork: the child shares all three frames; the data pages turn read-only\n");
+        Process child = os.fork(parent);
+        sb.append(os.takeLog()).append(os.frames()).append('\n');
+
+        sb.append("3) the child runs: its store to data[0] faults and gets a private copy\n");
+        os.run(child, 100);
+        sb.append(os.takeLog()).append(os.frames());
+        sb.append(String.format("    child: data[0] = %d, s0 = %d;  parent still sees data[0] = %d\n\n",
+            os.word(child, DATA), os.cpu.getRegisterFile().read(8), os.word(parent, DATA)));
+
+        sb.append("4) the parent runs: the old data frame is its alone now - no copy needed\n");
+        os.run(parent, 100);
+        sb.append(os.takeLog()).append(os.frames());
+        sb.append(String.format("    parent: data[0] = %d, s0 = %d\n\n", os.word(parent, DATA), os.cpu.getRegisterFile().read(8)));
+
+        sb.append("5) the child exits\n");
+        os.exit(child);
+        sb.append(os.takeLog()).append(os.frames()).append('\n');
+
+        sb.append("One page copied, where an eager fork would have copied all three. The\n");
+        sb.append("code page was never writable and the second data page only read.\n");
+        sb.append("The parent's write found refs = 1 and only had its permission restored.\n");
+        sb.append("The common case, fork then exec, copies next to nothing: exec drops\n");
+        sb.append("the child's references before it has written to more than a page or two.\n");
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        System.out.println(demonstrateCopyOnWrite());
+    }
+}
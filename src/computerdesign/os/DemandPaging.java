@@ -0,0 +1,488 @@
+package computerdesign.os;
+
+import computerdesign.instruction.Instruction;
+import computerdesign.memory.MainMemory;
+import computerdesign.memory.Mmu;
+import computerdesign.memory.PageTable;
+import computerdesign.memory.VirtualMemory;
+import computerdesign.processor.SingleCycleProcessor;
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * DemandPaging - the OS side of a page fault: a process runs on the
+ * single-cycle processor behind an Mmu, with no page in RAM at the start,
+ * and a handler that brings pages in as they are touched.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * ONE PAGE FAULT
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   CPU: lw t2, 0(t1)  ──► MMU: PTE not present ──► PageFaultException
+ *                                                         │
+ *   OS handler:                                           ▼
+ *     1. Is the address in one of the process's regions?  no → SEGFAULT, kill
+ *     2. Take a free frame, or pick a victim page with the replacement
+ *        policy; if the victim is dirty, write it to swap first; mark its
+ *        PTE not present
+ *     3. Fill the frame: from swap if the page was evicted dirty, else from
+ *        the program image, else with zeros
+ *     4. Map the page (present, with the region's permissions)
+ *     5. Return to the SAME instruction, which now succeeds
+ *
+ * The processor takes the fault before it changes any state, so step 5 is
+ * just running the cycle again (see memory.Mmu).
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * REPLACEMENT POLICIES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   FIFO   evict the page loaded longest ago, however busy it is
+ *   LRU    evict the page used longest ago (exact: the MMU stamps each use,
+ *          which real hardware does not do)
+ *   CLOCK  walk the frames in a circle; a page with its accessed bit set
+ *          gets a second chance (bit cleared), the first one without it is
+ *          evicted - LRU approximated from the one bit hardware provides
+ *
+ * The process is given a fixed number of frames. At least two: one
+ * instruction can need both its code page and a data page.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: DemandPaging                                   worked examples
+ *        DemandPaging [--policy fifo|lru|clock] [--frames n] [--pages n]
+ *                                                       log of one run of the
+ *                                                       page-touching program
+ *
+ * Covers learning goals: T6.3 (virtual memory, page faults, replacement)
+ *
+ * @see VirtualMemory - the page table walk and permission checks
+ */
+public final class DemandPaging {
+
+    public enum Policy {
+        FIFO,   // Oldest loaded page
+        LRU,    // Least recently used page
+        CLOCK   // Second chance on the accessed bit
+    }
+
+    static final int TEXT = 0x00400000;
+    static final int DATA = 0x10000000;
+    private static final int HALT = 0x00100073;
+
+    /** A range of addresses the process may use, loaded from an image or zero-filled. */
+    private static final class Region {
+        final String name;
+        final int start;
+        final int end;
+        final boolean writable;
+        final boolean executable;
+        final int[] image;
+
+        Region(String name, int start, int size, boolean writable, boolean executable, int[] image) {
+            this.name = name;
+            this.start = start;
+            this.end = start + size;
+            this.writable = writable;
+            this.executable = executable;
+            this.image = image;
+        }
+
+        boolean contains(int address) {
+            return Integer.compareUnsigned(address, start) >= 0 && Integer.compareUnsigned(address, end) < 0;
+        }
+    }
+
+    /** How one run went. */
+    public static final class Result {
+        public final Policy policy;
+        public final int frames;
+        public final int cycles;
+        public final int faults;
+        public final int evictions;
+        public final int writeBacks;
+        public final String outcome;
+
+        Result(DemandPaging os, int cycles, String outcome) {
+            this.policy = os.policy;
+            this.frames = os.frameLimit;
+            this.cycles = cycles;
+            this.faults = os.faults;
+            this.evictions = os.evictions;
+            this.writeBacks = os.writeBacks;
+            this.outcome = outcome;
+        }
+    }
+
+    private final MainMemory ram;
+    private final VirtualMemory space;
+    private final PageTable pageTable;
+    private final Mmu mmu;
+    private final Policy policy;
+    private final int frameLimit;
+    private final int pageSize;
+
+    private final List<Region> regions = new ArrayList<>();
+    private final Map<Integer, int[]> swap = new HashMap<>();
+    private final List<Integer> resident = new ArrayList<>();   // VPN in each of the process's frames
+    private final List<String> log = new ArrayList<>();
+    private int hand;                                           // FIFO and Clock position in resident
+    private int faults;
+    private int evictions;
+    private int writeBacks;
+
+    /**
+     * A process with an empty address space that may hold at most
+     * {@code frameLimit} pages of {@code ram} at a time.
+     */
+    public DemandPaging(MainMemory ram, Policy policy, int frameLimit) {
+        if (frameLimit < 2) {
+            throw new IllegalArgumentException(
+                "Need at least 2 frames: one instruction can touch a code page and a data page");
+        }
+        if (frameLimit > ram.getFreeFrames()) {
+            throw new IllegalArgumentException(String.format(
+                "Only %d free frames in RAM, asked for %d", ram.getFreeFrames(), frameLimit));
+        }
+        this.ram = ram;
+        this.policy = policy;
+        this.frameLimit = frameLimit;
+        this.space = new VirtualMemory(ram);
+        this.pageTable = space.getPageTable();
+        this.mmu = new Mmu(space);
+        this.pageSize = pageTable.getPageSize();
+    }
+
+    /** A region filled from {@code words}, paged in on first touch. */
+    public DemandPaging load(String name, int start, int[] words, boolean writable, boolean executable) {
+        return addRegion(new Region(name, start, words.length * 4, writable, executable, words));
+    }
+
+    /** A zero-filled region (bss, heap, stack). */
+    public DemandPaging region(String name, int start, int size, boolean writable) {
+        return addRegion(new Region(name, start, size, writable, false, null));
+    }
+
+    private DemandPaging addRegion(Region r) {
+        if (pageTable.getOffset(r.start) != 0) {
+            throw new IllegalArgumentException(String.format("Region %s at 0x%08X is not page-aligned", r.name, r.start));
+        }
+        regions.add(r);
+        return this;
+    }
+
+    /** The memory to build the processor on. */
+    public Mmu getMmu() {
+        return mmu;
+    }
+
+    public List<String> getLog() {
+        return log;
+    }
+
+    // ==================== RUNNING ====================
+
+    /**
+     * Run until halt, a fatal fault or {@code maxCycles}. Page faults are
+     * handled here and the faulting instruction is run again.
+     */
+    public Result run(SingleCycleProcessor cpu, int maxCycles) {
+        int cycles = 0;
+        String outcome = null;
+        while (!cpu.isHalted() && cycles < maxCycles) {
+            try {
+                cpu.cycle();
+                cycles++;
+            } catch (VirtualMemory.PageFaultException e) {
+                if (!handleFault(cpu.getPC(), e.getVirtualAddress(), e.isWrite())) {
+                    outcome = "killed: segmentation fault";
+                    break;
+                }
+            } catch (VirtualMemory.ProtectionFaultException e) {
+                log.add(String.format("protection fault  pc=0x%08X  0x%08X: %s, process killed",
+                    cpu.getPC(), e.getVirtualAddress(), e.getViolation()));
+                outcome = "killed: protection fault";
+                break;
+            }
+        }
+        if (outcome == null) outcome = cpu.isHalted() ? "halted" : "cycle limit reached";
+        return new Result(this, cycles, outcome);
+    }
+
+    // ==================== THE PAGE FAULT HANDLER ====================
+
+    /** Bring in the page of {@code va}; false if the access is illegal. */
+    private boolean handleFault(int pc, int va, boolean isWrite) {
+        faults++;
+        int vpn = pageTable.getVPN(va);
+        String access = va == pc ? "fetch" : isWrite ? "store" : "load";
+        String head = String.format("fault %2d  pc=0x%08X  %-5s 0x%08X  ", faults, pc, access, va);
+
+        Region r = regionOf(va);
+        if (r == null) {
+            log.add(head + "in no region: segmentation fault, process killed");
+            return false;
+        }
+        if (isWrite && !r.writable) {
+            log.add(head + "store to read-only " + r.name + ": segmentation fault, process killed");
+            return false;
+        }
+
+        int frame;
+        String how;
+        if (resident.size() < frameLimit) {
+            frame = ram.allocateFrame();
+            resident.add(vpn);
+            how = "free frame";
+        } else {
+            int slot = victimSlot();
+            int victim = resident.get(slot);
+            PageTable.PageTableEntry pte = pageTable.getEntry(victim);
+            frame = pte.getFrameNumber();
+            how = String.format("evict vpn 0x%05X", victim);
+            if (pte.isDirty()) {
+                swap.put(victim, copyFrame(frame));
+                writeBacks++;
+                how += " (dirty → swap)";
+            } else {
+                how += " (clean)";
+            }
+            pte.setPresent(false);
+            evictions++;
+            resident.set(slot, vpn);
+        }
+
+        String source = fill(frame, vpn, r);
+        pageTable.mapPage(vpn, frame, true, r.writable, r.executable);
+        log.add(head + String.format("vpn 0x%05X → frame %2d  %s, %s", vpn, frame, how, source));
+        return true;
+    }
+
+    private Region regionOf(int va) {
+        for (Region r : regions) {
+            if (r.contains(va)) return r;
+        }
+        return null;
+    }
+
+    /** Index in {@code resident} of the page to evict. */
+    private int victimSlot() {
+        switch (policy) {
+            case LRU: {
+                int best = 0;
+                for (int i = 1; i < resident.size(); i++) {
+                    if (mmu.getLastUse(resident.get(i)) < mmu.getLastUse(resident.get(best))) best = i;
+                }
+                return best;
+            }
+            case CLOCK:
+                while (true) {
+                    PageTable.PageTableEntry pte = pageTable.getEntry(resident.get(hand));
+                    if (!pte.isAccessed()) break;
+                    pte.setAccessed(false);             // second chance
+                    hand = (hand + 1) % resident.size();
+                }
+                return advance();
+            default:
+                return advance();
+        }
+    }
+
+    /** The slot under the hand; the hand moves on past it. */
+    private int advance() {
+        int slot = hand;
+        hand = (hand + 1) % resident.size();
+        return slot;
+    }
+
+    private int[] copyFrame(int frame) {
+        int base = ram.getFrameAddress(frame);
+        int[] words = new int[pageSize / 4];
+        for (int i = 0; i < words.length; i++) words[i] = ram.readPhysical(base + 4 * i);
+        return words;
+    }
+
+    /** Fill a frame with the page's contents; says where they came from. */
+    private String fill(int frame, int vpn, Region r) {
+        int base = ram.getFrameAddress(frame);
+        int[] saved = swap.get(vpn);
+        int first = (vpn * pageSize - r.start) / 4;              // index of the page's first word in the image
+        for (int i = 0; i < pageSize / 4; i++) {
+            int value = 0;
+            if (saved != null) {
+                value = saved[i];
+            } else if (r.image != null && first + i < r.image.length) {
+                value = r.image[first + i];
+            }
+            ram.writePhysical(base + 4 * i, value);
+        }
+        if (saved != null) return "from swap";
+        return r.image != null ? "from " + r.name + " image" : "zero-filled";
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static int[] words(List<Instruction> code) {
+        int[] out = new int[code.size() + 1];
+        for (int i = 0; i < code.size(); i++) out[i] = code.get(i).getRaw();
+        out[code.size()] = HALT;
+        return out;
+    }
+
+    /**
+     * Store 1..pages into the first word of consecutive data pages, then
+     * read them back in reverse order into s0 (= pages(pages+1)/2).
+     */
+    public static int[] touchPages(int pages) {
+        List<Instruction> c = new ArrayList<>();
+        c.add(Instruction.lui(6, DATA));                 // t1 = &data
+        c.add(Instruction.lui(28, 0x1000));              // t3 = page size
+        c.add(Instruction.addi(5, 0, pages));            // t0 = count
+        c.add(Instruction.addi(7, 0, 1));                // t2 = 1
+        c.add(Instruction.sw(7, 6, 0));                  // loop: *t1 = t2
+        c.add(Instruction.add(6, 6, 28));                //   next page
+        c.add(Instruction.addi(7, 7, 1));
+        c.add(Instruction.addi(5, 5, -1));
+        c.add(Instruction.bne(5, 0, -16));
+        c.add(Instruction.addi(5, 0, pages));
+        c.add(Instruction.sub(6, 6, 28));                // back: previous page
+        c.add(Instruction.lw(7, 6, 0));
+        c.add(Instruction.add(8, 8, 7));                 //   s0 += *t1
+        c.add(Instruction.addi(5, 5, -1));
+        c.add(Instruction.bne(5, 0, -16));
+        return words(c);
+    }
+
+    /** A process running touchPages(pages): its text and a dataPages-page data region. */
+    private static DemandPaging process(Policy policy, int frames, int[] program, int dataPages) {
+        DemandPaging os = new DemandPaging(new MainMemory(), policy, frames);
+        os.load("text", TEXT, program, false, true);
+        os.region("data", DATA, dataPages * 4096, true);
+        return os;
+    }
+
+    private static SingleCycleProcessor cpu(DemandPaging os) {
+        SingleCycleProcessor cpu = new SingleCycleProcessor(os.getMmu());
+        cpu.setPC(TEXT);
+        return cpu;
+    }
+
+    /** The fault log and result of one run of touchPages. */
+    public static String report(Policy policy, int frames, int pages) {
+        if (pages < 1 || pages > 1024) {
+            throw new IllegalArgumentException("Pages must be 1..1024, got " + pages);
+        }
+        DemandPaging os = process(policy, frames, touchPages(pages), pages);
+        SingleCycleProcessor cpu = cpu(os);
+        Result r = os.run(cpu, 100_000);
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("%s, %d frames, touching %d data pages + 1 code page:\n\n", policy, frames, pages));
+        for (String line : os.getLog()) sb.append("  ").append(line).append('\n');
+        sb.append(String.format("\n  %s after %d instructions: %d faults, %d evictions, %d write-backs; s0 = %d (expected %d)\n",
+            r.outcome, r.cycles, r.faults, r.evictions, r.writeBacks,
+            cpu.getRegisterFile().read(8), pages * (pages + 1) / 2));
+        return sb.toString();
+    }
+
+    public static String demonstrateDemandPaging() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Demand Paging: Page Faults Handled by the OS ===\n\n");
+        sb.append("The program stores to the first word of N data pages, then reads them\n");
+        sb.append("back in reverse order and sums them. Nothing is in RAM when it starts.\n\n");
+
+        sb.append("1) The fault log\n\n");
+        sb.append(report(Policy.FIFO, 3, 4));
+        sb.append("\n  The first faults are compulsory: every page is touched for the first\n");
+        sb.append("  time. Then FIFO evicts the code page for being the oldest - and the\n");
+        sb.append("  very next fetch faults it straight back in.\n\n");
+
+        int pages = 8;
+        int[] program = touchPages(pages);
+        int[] frameCounts = {2, 3, 4, 6, 9};
+        sb.append(String.format("2) Policies compared, %d data pages + 1 code page\n\n", pages));
+        sb.append("   frames │      FIFO      │      LRU       │     CLOCK\n");
+        sb.append("          │ faults  writes │ faults  writes │ faults  writes\n");
+        sb.append("   ───────┼────────────────┼────────────────┼───────────────\n");
+        boolean allCorrect = true;
+        for (int frames : frameCounts) {
+            sb.append(String.format("   %6d │", frames));
+            for (Policy p : Policy.values()) {
+                DemandPaging os = process(p, frames, program, pages);
+                SingleCycleProcessor cpu = cpu(os);
+                Result r = os.run(cpu, 100_000);
+                allCorrect &= cpu.getRegisterFile().read(8) == pages * (pages + 1) / 2;
+                sb.append(String.format(" %6d  %6d │", r.faults, r.writeBacks));
+            }
+            sb.setLength(sb.length() - 2);
+            sb.append('\n');
+        }
+        sb.append("   (writes = dirty pages written to swap on eviction)\n\n");
+        sb.append(allCorrect ? "  Every run got the right sum: the faults are invisible to the program,\n"
+                             : "  SOME RUNS GOT THE WRONG SUM - the handler lost a page.\n");
+        sb.append("  only slower. With 9 frames all pages fit and only the 9 compulsory\n");
+        sb.append("  faults remain. With fewer, LRU keeps the code page, used on every\n");
+        sb.append("  instruction, and the pages written last, which the reverse pass reads\n");
+        sb.append("  first; FIFO keeps throwing the code page out. Clock gets close to LRU\n");
+        sb.append("  from one accessed bit, except with 2 frames: both bits are always set,\n");
+        sb.append("  every sweep clears them all and Clock degenerates to FIFO.\n\n");
+
+        sb.append("3) Faults the OS does not fix\n\n");
+        List<Instruction> wild = new ArrayList<>();
+        wild.add(Instruction.lui(6, 0x20000000));
+        wild.add(Instruction.lw(7, 6, 0));
+        List<Instruction> selfModify = new ArrayList<>();
+        selfModify.add(Instruction.lui(6, TEXT));
+        selfModify.add(Instruction.sw(0, 6, 0));
+        String[] names = {"load from 0x20000000, outside every region", "store into its own code"};
+        int[][] programs = {words(wild), words(selfModify)};
+        for (int i = 0; i < programs.length; i++) {
+            DemandPaging os = process(Policy.LRU, 4, programs[i], 1);
+            Result r = os.run(cpu(os), 100);
+            sb.append("  ").append(names[i]).append(":\n");
+            for (String line : os.getLog()) sb.append("    ").append(line).append('\n');
+            sb.append("    → ").append(r.outcome).append("\n\n");
+        }
+        sb.append("  A fault outside the process's regions is a bug, not a page to load.\n");
+        sb.append("  The code page is present but read-only, so the store is refused by the\n");
+        sb.append("  MMU's permission check without a page fault at all.\n");
+        return sb.toString();
+    }
+
+    private static Policy parsePolicy(String name) {
+        for (Policy p : Policy.values()) {
+            if (p.name().equalsIgnoreCase(name)) return p;
+        }
+        throw new IllegalArgumentException("Unknown policy: " + name + " (fifo, lru or clock)");
+    }
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateDemandPaging());
+            return;
+        }
+        String option = "";
+        try {
+            Policy policy = Policy.LRU;
+            int frames = 3;
+            int pages = 4;
+            for (int i = 0; i < args.length; i++) {
+                option = args[i];
+                switch (option) {
+                    case "--policy": policy = parsePolicy(args[++i]); break;
+                    case "--frames": frames = Integer.parseInt(args[++i]); break;
+                    case "--pages": pages = Integer.parseInt(args[++i]); break;
+                    default: throw new IllegalArgumentException("Unknown option: " + option);
+                }
+            }
+            System.out.println(report(policy, frames, pages));
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.err.println("Missing value after " + option);
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
@@ -0,0 +1,392 @@
+package computerdesign.os;
+
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.List;
+
+/**
+ * RealTimeScheduling - periodic tasks with deadlines, scheduled by
+ * rate-monotonic (RM) or earliest-deadline-first (EDF) priorities.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE MODEL
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Task i releases a job every period Tᵢ that needs Cᵢ time units of CPU
+ * (its worst-case execution time) before the next release (deadline =
+ * period). The CPU runs the highest-priority ready job and preempts at
+ * once when a higher-priority one is released.
+ *
+ *   RM   fixed priorities: the shorter the period, the higher the priority
+ *   EDF  dynamic: the job whose deadline is nearest runs
+ *
+ * Everything repeats after the HYPERPERIOD, lcm(T₁..Tₙ), so simulating one
+ * hyperperiod shows every case.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * UTILIZATION TESTS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   U = Σ Cᵢ / Tᵢ        (fraction of the CPU the tasks need)
+ *
+ *   U > 1                     no scheduler can make it
+ *   EDF: U ≤ 1                schedulable (exact)
+ *   RM:  U ≤ n(2^(1/n) - 1)   schedulable (Liu & Layland; n=2: 0.828,
+ *                             n→∞: ln 2 ≈ 0.693)
+ *
+ * The RM bound is only SUFFICIENT: above it a set may still work. The
+ * exact RM test is response-time analysis, iterating
+ *
+ *   Rᵢ = Cᵢ + Σ_{j higher priority} ⌈Rᵢ / Tⱼ⌉ · Cⱼ
+ *
+ * until it settles (fits if Rᵢ ≤ Tᵢ) or passes the deadline.
+ *
+ * A job still unfinished at its deadline is counted as a miss and
+ * dropped, so one overrun does not snowball through the whole run.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: RealTimeScheduling                           worked examples
+ *        RealTimeScheduling T:C [T:C ...] [--length n] simulate a task set with
+ *                                                      RM and EDF (default length:
+ *                                                      the hyperperiod)
+ *
+ * Covers learning goals: T5.2 (interrupt-driven, time-critical systems)
+ *
+ * @see Scheduler - time-sharing schedulers for general-purpose systems
+ */
+public final class RealTimeScheduling {
+
+    private RealTimeScheduling() {}
+
+    public enum Policy {
+        RM,     // Rate monotonic: fixed priority by period
+        EDF     // Earliest deadline first
+    }
+
+    /** A periodic task; its deadline is its period. */
+    public static final class Task {
+        public final String name;
+        public final int period;
+        public final int wcet;
+
+        public Task(String name, int period, int wcet) {
+            if (period <= 0 || wcet <= 0) {
+                throw new IllegalArgumentException("Period and WCET must be positive: " + name);
+            }
+            if (wcet > period) {
+                throw new IllegalArgumentException(name + " needs " + wcet + " units every " + period);
+            }
+            this.name = name;
+            this.period = period;
+            this.wcet = wcet;
+        }
+
+        public double utilization() {
+            return (double) wcet / period;
+        }
+
+        @Override
+        public String toString() {
+            return String.format("%s(T=%d, C=%d)", name, period, wcet);
+        }
+    }
+
+    /** A job that was not done by its deadline. */
+    public static final class Miss {
+        public final int task;
+        public final int release;
+        public final int deadline;
+        public final int remaining;
+
+        Miss(int task, int release, int deadline, int remaining) {
+            this.task = task;
+            this.release = release;
+            this.deadline = deadline;
+            this.remaining = remaining;
+        }
+    }
+
+    public static final class Result {
+        public final List<Task> tasks;
+        public final Policy policy;
+        public final int length;
+        /** Task index running in each time unit, -1 when idle. */
+        public final int[] running;
+        /** Whether task i had a released, unfinished job at time t. */
+        public final boolean[][] ready;
+        public final List<Miss> misses;
+        /** Worst response time per task (-1 if no job finished). */
+        public final int[] worstResponse;
+        public final int preemptions;
+
+        Result(List<Task> tasks, Policy policy, int length, int[] running, boolean[][] ready,
+               List<Miss> misses, int[] worstResponse, int preemptions) {
+            this.tasks = tasks;
+            this.policy = policy;
+            this.length = length;
+            this.running = running;
+            this.ready = ready;
+            this.misses = misses;
+            this.worstResponse = worstResponse;
+            this.preemptions = preemptions;
+        }
+
+        public int idle() {
+            int idle = 0;
+            for (int r : running) if (r < 0) idle++;
+            return idle;
+        }
+    }
+
+    // ==================== BOUNDS ====================
+
+    public static double utilization(List<Task> tasks) {
+        double u = 0;
+        for (Task t : tasks) u += t.utilization();
+        return u;
+    }
+
+    /** The Liu & Layland bound n(2^(1/n) - 1). */
+    public static double rmBound(int n) {
+        return n * (Math.pow(2, 1.0 / n) - 1);
+    }
+
+    public static long hyperperiod(List<Task> tasks) {
+        long h = 1;
+        for (Task t : tasks) h = h / gcd(h, t.period) * t.period;
+        return h;
+    }
+
+    private static long gcd(long a, long b) {
+        return b == 0 ? a : gcd(b, a % b);
+    }
+
+    /** RM priority order: task indices by period (ties: list order). */
+    static Integer[] rmOrder(List<Task> tasks) {
+        Integer[] order = new Integer[tasks.size()];
+        for (int i = 0; i < order.length; i++) order[i] = i;
+        Arrays.sort(order, (a, b) -> Integer.compare(tasks.get(a).period, tasks.get(b).period));
+        return order;
+    }
+
+    /**
+     * Worst-case response time of each task under RM by response-time
+     * analysis; -1 where it exceeds the deadline.
+     */
+    public static int[] responseTimes(List<Task> tasks) {
+        Integer[] order = rmOrder(tasks);
+        int[] r = new int[tasks.size()];
+        for (int k = 0; k < order.length; k++) {
+            Task task = tasks.get(order[k]);
+            int response = task.wcet;
+            while (true) {
+                int next = task.wcet;
+                for (int j = 0; j < k; j++) {
+                    Task hp = tasks.get(order[j]);
+                    next += (response + hp.period - 1) / hp.period * hp.wcet;
+                }
+                if (next > task.period) {
+                    response = -1;
+                    break;
+                }
+                if (next == response) break;
+                response = next;
+            }
+            r[order[k]] = response;
+        }
+        return r;
+    }
+
+    // ==================== SIMULATION ====================
+
+    /** Run `tasks` under `policy` for `length` time units, all released at 0. */
+    public static Result simulate(List<Task> tasks, Policy policy, int length) {
+        int n = tasks.size();
+        if (n == 0) throw new IllegalArgumentException("No tasks");
+        int[] rank = new int[n];                 // RM: lower rank = higher priority
+        Integer[] order = rmOrder(tasks);
+        for (int k = 0; k < n; k++) rank[order[k]] = k;
+
+        int[] remaining = new int[n];
+        int[] release = new int[n];
+        int[] worst = new int[n];
+        Arrays.fill(worst, -1);
+        int[] running = new int[length];
+        boolean[][] ready = new boolean[n][length];
+        List<Miss> misses = new ArrayList<>();
+        int preemptions = 0;
+        int last = -1;
+
+        for (int t = 0; t <= length; t++) {
+            for (int i = 0; i < n; i++) {
+                Task task = tasks.get(i);
+                if (t % task.period != 0) continue;
+                if (remaining[i] > 0) misses.add(new Miss(i, release[i], t, remaining[i]));
+                if (t == length) continue;
+                remaining[i] = task.wcet;
+                release[i] = t;
+            }
+            if (t == length) break;
+
+            int pick = -1;
+            for (int i = 0; i < n; i++) {
+                if (remaining[i] == 0) continue;
+                ready[i][t] = true;
+                if (pick < 0 || higher(tasks, policy, rank, release, i, pick)) pick = i;
+            }
+            if (last >= 0 && pick != last && remaining[last] > 0) preemptions++;
+            running[t] = pick;
+            last = pick;
+            if (pick >= 0 && --remaining[pick] == 0) {
+                worst[pick] = Math.max(worst[pick], t + 1 - release[pick]);
+            }
+        }
+        return new Result(tasks, policy, length, running, ready, misses, worst, preemptions);
+    }
+
+    /** One hyperperiod. */
+    public static Result simulate(List<Task> tasks, Policy policy) {
+        return simulate(tasks, policy, hyperperiodLength(tasks));
+    }
+
+    private static int hyperperiodLength(List<Task> tasks) {
+        long h = hyperperiod(tasks);
+        if (h > 100_000) throw new IllegalArgumentException("Hyperperiod " + h + " is too long; give --length");
+        return (int) h;
+    }
+
+    /** Does the job of task a beat the job of task b? Ties go to the lower index. */
+    private static boolean higher(List<Task> tasks, Policy policy, int[] rank, int[] release, int a, int b) {
+        if (policy == Policy.RM) return rank[a] < rank[b];
+        int da = release[a] + tasks.get(a).period;
+        int db = release[b] + tasks.get(b).period;
+        return da < db || (da == db && a < b);
+    }
+
+    // ==================== OUTPUT ====================
+
+    /**
+     * One row per task: █ running, ░ released but waiting, X a missed
+     * deadline, · nothing to do. At most `width` time units are drawn.
+     */
+    public static String timeline(Result r, int width) {
+        int shown = Math.min(width, r.length);
+        int label = 4;
+        for (Task t : r.tasks) label = Math.max(label, t.name.length());
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < r.tasks.size(); i++) {
+            char[] row = new char[shown];
+            for (int t = 0; t < shown; t++) row[t] = r.running[t] == i ? '█' : r.ready[i][t] ? '░' : '·';
+            for (Miss m : r.misses) {
+                if (m.task == i && m.deadline > 0 && m.deadline <= shown) row[m.deadline - 1] = 'X';
+            }
+            sb.append(String.format("   %-" + label + "s |%s|\n", r.tasks.get(i).name, new String(row)));
+        }
+        char[] scale = new char[shown + 1];
+        Arrays.fill(scale, ' ');
+        for (int t = 0; t <= shown; t += 5) {
+            String mark = Integer.toString(t);
+            if (t + mark.length() > scale.length) break;
+            mark.getChars(0, mark.length(), scale, t);
+        }
+        sb.append(String.format("   %-" + label + "s  %s\n", "", new String(scale).replaceAll(" +$", "")));
+        if (shown < r.length) sb.append(String.format("   (first %d of %d time units)\n", shown, r.length));
+        return sb.toString();
+    }
+
+    /** The bounds, then both policies side by side. */
+    public static String report(List<Task> tasks, int length, int width) {
+        StringBuilder sb = new StringBuilder();
+        double u = utilization(tasks);
+        double bound = rmBound(tasks.size());
+        sb.append("   tasks: ").append(tasks).append('\n');
+        sb.append(String.format("   U = %.3f, RM bound for n=%d: %.3f, hyperperiod %d\n",
+            u, tasks.size(), bound, hyperperiod(tasks)));
+        sb.append(String.format("   RM bound test: %s; EDF test (U ≤ 1): %s\n",
+            u <= bound ? "schedulable" : "inconclusive", u <= 1 ? "schedulable" : "fails"));
+        int[] rta = responseTimes(tasks);
+        StringBuilder r = new StringBuilder();
+        for (int i = 0; i < tasks.size(); i++) {
+            r.append(i == 0 ? "" : ", ").append(tasks.get(i).name).append('=')
+                .append(rta[i] < 0 ? "miss" : Integer.toString(rta[i]));
+        }
+        sb.append("   RM response-time analysis: ").append(r).append("\n\n");
+        for (Policy p : Policy.values()) {
+            Result res = simulate(tasks, p, length);
+            sb.append(String.format("   %s: missed deadlines %d, preemptions %d, idle units %d\n",
+                p, res.misses.size(), res.preemptions, res.idle()));
+            sb.append(timeline(res, width));
+            for (Miss m : res.misses) {
+                sb.append(String.format("     %s missed: released %d, deadline %d, %d unit(s) short\n",
+                    tasks.get(m.task).name, m.release, m.deadline, m.remaining));
+            }
+            sb.append('\n');
+        }
+        return sb.toString();
+    }
+
+    private static List<Task> tasks(int... periodWcet) {
+        List<Task> list = new ArrayList<>();
+        for (int i = 0; i < periodWcet.length; i += 2) {
+            list.add(new Task("T" + (i / 2 + 1), periodWcet[i], periodWcet[i + 1]));
+        }
+        return list;
+    }
+
+    public static String demonstrateRealTime() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Real-Time Scheduling: RM vs EDF ===\n\n");
+        sb.append("█ running, ░ released but waiting, X deadline missed, · nothing to do\n\n");
+
+        sb.append("1) Below the RM bound: both policies meet every deadline\n");
+        sb.append(report(tasks(4, 1, 5, 1, 10, 3), 20, 80));
+
+        sb.append("2) Above the bound, but still fine under RM (the bound is only sufficient)\n");
+        sb.append(report(tasks(4, 1, 6, 2, 12, 3), 12, 80));
+
+        sb.append("3) U = 0.97: RM misses, EDF does not\n");
+        List<Task> tight = tasks(5, 2, 7, 4);
+        sb.append(report(tight, (int) hyperperiod(tight), 80));
+        sb.append("   At t=5 RM lets T1 preempt T2 although T2's deadline (7) comes first;\n");
+        sb.append("   EDF keeps running T2 and T1 still finishes by 10.\n\n");
+
+        sb.append("4) Overload, U = 1.17: nobody can make it - only who suffers differs\n");
+        sb.append(report(tasks(4, 2, 6, 3, 12, 2), 24, 80));
+        sb.append("   Under RM the misses always fall on the longest periods, which is at\n");
+        sb.append("   least predictable. EDF misses fewer jobs here, but in overload it\n");
+        sb.append("   promises nothing: which job is late depends only on how the\n");
+        sb.append("   deadlines line up.\n");
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateRealTime());
+            return;
+        }
+        try {
+            List<Task> list = new ArrayList<>();
+            int length = -1;
+            for (int i = 0; i < args.length; i++) {
+                if (args[i].equals("--length")) {
+                    length = Integer.parseInt(args[++i]);
+                    continue;
+                }
+                String[] parts = args[i].split(":");
+                if (parts.length != 2) throw new IllegalArgumentException("Expected PERIOD:WCET, got " + args[i]);
+                list.add(new Task("T" + (list.size() + 1), Integer.parseInt(parts[0]), Integer.parseInt(parts[1])));
+            }
+            if (list.isEmpty()) throw new IllegalArgumentException("No tasks given");
+            if (length < 0) length = hyperperiodLength(list);
+            if (length <= 0) throw new IllegalArgumentException("Length must be positive, got " + length);
+            System.out.println(report(list, length, 120));
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.err.println("Missing value after --length");
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
@@ -135,13 +135,18 @@ public class HazardUnit {
     /**
      * Detect hazards and determine forwarding/stalling.
      */
-    public HazardResult detect(ID_EX idEx, EX_MEM exMem, MEM_WB memWb) {
+    public HazardResult detect(IF_ID ifId, ID_EX idEx, EX_MEM exMem, MEM_WB memWb) {
         HazardResult result = new HazardResult();
         
         if (!idEx.valid) {
             return result;
         }
         
+        // Check for load-use hazard (must stall, can't forward)
+        // Load in ID/EX, and dependent instruction still in IF/ID: hold it
+        // in ID for one cycle, then forward from MEM/WB
+        result.stall = loadUseHazard(ifId, idEx);
+        
         int rs1 = idEx.rs1;
         int rs2 = idEx.rs2;
         
@@ -166,19 +171,26 @@ public class HazardUnit {
             }
         }
         
-        // Check for load-use hazard (must stall, can't forward)
-        // Load in EX/MEM, and dependent instruction in ID/EX
-        if (exMem.valid && exMem.control != null && exMem.control.memRead && exMem.rd != 0) {
-            if (exMem.rd == rs1 || exMem.rd == rs2) {
-                result.stall = true;
-                result.forwardA = ForwardSource.REGISTER;  // Will forward next cycle
-                result.forwardB = ForwardSource.REGISTER;
-            }
-        }
-        
         return result;
     }
     
+    /**
+     * A load in ID/EX whose destination the instruction in IF/ID reads.
+     * Its data only exists after MEM, one cycle too late to forward to EX.
+     */
+    public boolean loadUseHazard(IF_ID ifId, ID_EX idEx) {
+        if (!ifId.valid || !idEx.valid || idEx.control == null || !idEx.control.memRead || idEx.rd == 0) {
+            return false;
+        }
+        int opcode = ifId.instruction.getOpcode();
+        // not LUI, AUIPC, JAL, nor FENCE and SYSTEM (ecall/ebreak: no CSRs here), whose rs1 bits are no register
+        boolean usesRs1 = opcode != 0b0110111 && opcode != 0b0010111 && opcode != 0b1101111
+            && opcode != 0b0001111 && opcode != 0b1110011;
+        boolean usesRs2 = opcode == 0b0110011 || opcode == 0b0100011 || opcode == 0b1100011;  // R-type, store, branch
+        return (usesRs1 && ifId.instruction.getRs1() == idEx.rd)
+            || (usesRs2 && ifId.instruction.getRs2() == idEx.rd);
+    }
+    
     /**
      * Check if a branch/jump requires flushing.
      */
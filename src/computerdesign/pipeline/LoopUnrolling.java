@@ -0,0 +1,196 @@
+package computerdesign.pipeline;
+
+import computerdesign.instruction.Instruction;
+import computerdesign.instruction.InstructionDecoder;
+import computerdesign.memory.MainMemory;
+import computerdesign.processor.PipelineProcessor;
+import computerdesign.processor.ProcessorStats;
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * LoopUnrolling - The same array sum three ways on the 5-stage pipeline,
+ * showing where the cycles go and how unrolling and scheduling win them back.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * WHERE A SIMPLE LOOP LOSES CYCLES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   loop: lw   t2, 0(t1)
+ *         add  s0, s0, t2      ← needs t2 one cycle after the load: 1 stall
+ *         addi t1, t1, 4
+ *         addi t0, t0, -1
+ *         bne  t0, zero, loop  ← taken: 3 wrong-path instructions flushed
+ *
+ * 5 useful instructions cost 9 cycles. Two things are wrong:
+ *
+ *   1. LOOP OVERHEAD: the pointer, counter and branch run for every element.
+ *      Unrolling 4× pays them once per four elements.
+ *   2. DEPENDENCY STALLS: each add waits for the load right before it.
+ *      Doing the four loads first and the adds afterwards gives every load
+ *      time to finish - the compiler's job, called INSTRUCTION SCHEDULING.
+ *
+ * The four adds also go to four separate accumulators. In this pipeline
+ * ALU→ALU forwarding hides the add→add chain, so that part costs nothing
+ * here. On a real CPU a floating-point add takes ~4 cycles: one accumulator
+ * chain runs at latency, four independent ones at throughput (see
+ * examples/benchmark_demo/ilp_unroll.rs for the measured difference).
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Covers learning goals: T5.1 (pipelining, data and control hazards)
+ */
+public final class LoopUnrolling {
+
+    static final int ARRAY = 0x400;
+    static final int ELEMENTS = 64;
+    private static final int HALT = 0x00100073;
+
+    private LoopUnrolling() {}
+
+    /** One variant of the loop: its program and what running it cost. */
+    public static final class Result {
+        public final String name;
+        public final int[] program;
+        public final int sum;
+        public final int instructions;
+        public final int cycles;
+        public final int stalls;
+        public final int flushes;
+
+        Result(String name, int[] program, int sum, ProcessorStats stats) {
+            this.name = name;
+            this.program = program;
+            this.sum = sum;
+            this.instructions = stats.getInstructionCount();
+            this.cycles = stats.getCycleCount();
+            this.stalls = stats.getStallCycles();
+            this.flushes = stats.getBranchMispredictions();
+        }
+
+        public double cpi() {
+            return (double) cycles / instructions;
+        }
+    }
+
+    // ==================== THE THREE LOOPS ====================
+
+    private static int[] words(List<Instruction> code) {
+        int[] out = new int[code.size() + 1];
+        for (int i = 0; i < code.size(); i++) out[i] = code.get(i).getRaw();
+        out[code.size()] = HALT;
+        return out;
+    }
+
+    /** s0 = sum of ELEMENTS words, one element per iteration. */
+    public static int[] rolled() {
+        List<Instruction> c = new ArrayList<>();
+        c.add(Instruction.addi(6, 0, ARRAY));          // t1 = &array
+        c.add(Instruction.addi(5, 0, ELEMENTS));       // t0 = count
+        c.add(Instruction.lw(7, 6, 0));                // loop: t2 = *t1
+        c.add(Instruction.add(8, 8, 7));               //   s0 += t2   (load-use stall)
+        c.add(Instruction.addi(6, 6, 4));
+        c.add(Instruction.addi(5, 5, -1));
+        c.add(Instruction.bne(5, 0, -16));
+        return words(c);
+    }
+
+    /** Four elements per iteration, each add still right behind its load. */
+    public static int[] unrolled() {
+        List<Instruction> c = new ArrayList<>();
+        c.add(Instruction.addi(6, 0, ARRAY));
+        c.add(Instruction.addi(5, 0, ELEMENTS / 4));
+        for (int k = 0; k < 4; k++) {
+            c.add(Instruction.lw(7, 6, 4 * k));
+            c.add(Instruction.add(8, 8, 7));
+        }
+        c.add(Instruction.addi(6, 6, 16));
+        c.add(Instruction.addi(5, 5, -1));
+        c.add(Instruction.bne(5, 0, -40));
+        return words(c);
+    }
+
+    /** Unrolled, loads first, four accumulators combined after the loop. */
+    public static int[] scheduled() {
+        int[] temp = {7, 28, 29, 30};                  // t2..t5
+        int[] acc = {8, 9, 18, 19};                    // s0, s1, s2, s3
+        List<Instruction> c = new ArrayList<>();
+        c.add(Instruction.addi(6, 0, ARRAY));
+        c.add(Instruction.addi(5, 0, ELEMENTS / 4));
+        for (int k = 0; k < 4; k++) c.add(Instruction.lw(temp[k], 6, 4 * k));
+        for (int k = 0; k < 4; k++) c.add(Instruction.add(acc[k], acc[k], temp[k]));
+        c.add(Instruction.addi(6, 6, 16));
+        c.add(Instruction.addi(5, 5, -1));
+        c.add(Instruction.bne(5, 0, -40));
+        c.add(Instruction.add(8, 8, 9));               // s0 = (s0 + s1) + (s2 + s3)
+        c.add(Instruction.add(18, 18, 19));
+        c.add(Instruction.add(8, 8, 18));
+        return words(c);
+    }
+
+    /** A fresh pipeline with the program loaded and the array set to 1..ELEMENTS. */
+    public static PipelineProcessor prepare(int[] program) {
+        MainMemory memory = new MainMemory();
+        memory.loadProgram(program, 0);
+        for (int i = 0; i < ELEMENTS; i++) memory.write(ARRAY + 4 * i, i + 1);
+        return new PipelineProcessor(memory);
+    }
+
+    /** Run a program over the array 1..ELEMENTS on a fresh pipeline. */
+    public static Result run(String name, int[] program) {
+        PipelineProcessor cpu = prepare(program);
+        cpu.run(100_000);
+        if (!cpu.isHalted()) throw new IllegalStateException(name + " did not halt");
+        return new Result(name, program, cpu.getRegisterFile().read(8), cpu.getStats());
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static String listing(int[] program) {
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < program.length; i++) {
+            sb.append(String.format("   %3d: %s\n", 4 * i, InstructionDecoder.disassemble(new Instruction(program[i]))));
+        }
+        return sb.toString();
+    }
+
+    public static String demonstrateLoopUnrolling() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Loop Unrolling and Scheduling on the 5-Stage Pipeline ===\n\n");
+        sb.append(String.format("Sum of %d words (1..%d, expected %d), forwarding enabled.\n\n",
+            ELEMENTS, ELEMENTS, ELEMENTS * (ELEMENTS + 1) / 2));
+
+        Result[] results = {
+            run("rolled", rolled()),
+            run("unrolled 4x", unrolled()),
+            run("unrolled + scheduled", scheduled()),
+        };
+
+        sb.append("1) The scheduled loop (loads first, four accumulators):\n");
+        sb.append(listing(results[2].program)).append('\n');
+
+        sb.append("2) Cost of each version:\n");
+        sb.append(String.format("   %-22s %6s %6s %7s %6s %6s %6s\n",
+            "loop", "instr", "cycles", "stalls", "flush", "CPI", "sum"));
+        for (Result r : results) {
+            sb.append(String.format("   %-22s %6d %6d %7d %6d %6.2f %6d\n",
+                r.name, r.instructions, r.cycles, r.stalls, r.flushes, r.cpi(), r.sum));
+        }
+        Result base = results[0];
+        Result best = results[2];
+        sb.append(String.format("\n   Speedup %.2fx: %d fewer instructions (loop overhead), %d fewer stalls\n",
+            (double) base.cycles / best.cycles, base.instructions - best.instructions, base.stalls - best.stalls));
+        sb.append("   (load-use) and a quarter of the taken-branch flushes.\n\n");
+
+        sb.append("3) What each step fixed:\n");
+        sb.append("   unrolling   - counter, pointer and branch once per 4 elements\n");
+        sb.append("   scheduling  - each load is 4 instructions ahead of its use: no stall\n");
+        sb.append("   4 sums      - independent chains; free here (1-cycle ALU + forwarding),\n");
+        sb.append("                 but up to 4x on real FP adds with 3-4 cycle latency\n");
+        return sb.toString();
+    }
+
+    public static void main(String[] args) {
+        System.out.println(demonstrateLoopUnrolling());
+    }
+}
@@ -401,6 +401,7 @@ public class ExceptionHandler {
     
     public void setMepc(int value) { mepc = value; }
     public void setMtvec(int value) { mtvec = value; }
+    public void setMstatus(int value) { mstatus = value; }
     
     // Statistics
     public int getExceptionCount() { return exceptionCount; }
@@ -0,0 +1,396 @@
+package computerdesign.processor;
+
+import computerdesign.instruction.Instruction;
+import computerdesign.memory.MainMemory;
+import computerdesign.memory.RegisterFile;
+import computerdesign.util.HexDump;
+import java.io.IOException;
+import java.nio.BufferUnderflowException;
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.List;
+import java.util.zip.CRC32;
+
+/**
+ * MachineSnapshot - The architectural state of a processor (PC, registers,
+ * memory) in a compact, versioned binary file.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * DESIGNING A BINARY FORMAT
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * A file outlives the program that wrote it, so the format has to answer
+ * questions a Java object never does:
+ *
+ *   What is this file?      A MAGIC number at offset 0 (4 bytes "TDTS")
+ *   Which layout?           A VERSION for incompatible changes, and a HEADER
+ *                           SIZE so a reader can skip header fields that were
+ *                           appended later
+ *   Which byte order?       Fixed: every multi-byte field is LITTLE-ENDIAN,
+ *                           whatever machine wrote or reads it
+ *   How long is each part?  Counts and lengths before the data they describe
+ *   Is it intact?           A CRC-32 over everything before it
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * LAYOUT (version 1, all fields little-endian)
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   Offset  Size  Field
+ *   ──────  ────  ─────────────────────────────────────────────
+ *        0     4  magic 'T' 'D' 'T' 'S'  (54 44 54 53)
+ *        4     2  version (1)
+ *        6     2  header size in bytes (20 for version 1)
+ *        8     4  flags (bit 0 = halted)
+ *       12     4  PC
+ *       16     4  register count N (32)
+ *       20    4N  registers x0..x(N-1)
+ *        …     4  memory size in bytes
+ *        …     4  segment count S
+ *        …        S × { 4 address, 4 length L, L bytes }
+ *        …     4  CRC-32 of all preceding bytes
+ *
+ * Memory is mostly zeros, so only SEGMENTS of non-zero words are stored:
+ * a 64KB memory holding a 10-instruction program is a ~200-byte file.
+ * Two non-zero runs separated by a short gap are merged, because each
+ * extra segment costs 8 bytes of address + length.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage:
+ *   MachineSnapshot snap = MachineSnapshot.capture(cpu);
+ *   Files.write(path, snap.toBytes());
+ *   MachineSnapshot.fromBytes(Files.readAllBytes(path)).restore(otherCpu);
+ *
+ * Covers learning goals: T2.2 (byte order, data in memory), T5.1 (architectural state)
+ */
+public final class MachineSnapshot {
+
+    public static final int MAGIC = 0x53544454;   // "TDTS" when stored little-endian
+    public static final int VERSION = 1;
+    private static final int HEADER_SIZE = 20;
+    private static final int FLAG_HALTED = 1;
+    private static final int MERGE_GAP_WORDS = 2; // a gap this short is cheaper than a segment header
+
+    private final int pc;
+    private final boolean halted;
+    private final int[] registers;
+    private final int[] memory;     // one int per word, as in MainMemory
+
+    private MachineSnapshot(int pc, boolean halted, int[] registers, int[] memory) {
+        this.pc = pc;
+        this.halted = halted;
+        this.registers = registers;
+        this.memory = memory;
+    }
+
+    // ==================== CAPTURE / RESTORE ====================
+
+    /** Copy the architectural state of a processor. */
+    public static MachineSnapshot capture(Processor cpu) {
+        RegisterFile rf = cpu.getRegisterFile();
+        int[] registers = new int[32];
+        for (int i = 0; i < registers.length; i++) registers[i] = rf.read(i);
+        MainMemory mem = cpu.getMemory();
+        int[] memory = new int[mem.getSize() / 4];
+        for (int i = 0; i < memory.length; i++) memory[i] = mem.read(i * 4);
+        return new MachineSnapshot(cpu.getPC(), cpu.isHalted(), registers, memory);
+    }
+
+    /**
+     * Reset a processor and load this state into it. The memory sizes must
+     * match. A halted snapshot resumes at the halt instruction and halts
+     * again on the next cycle.
+     */
+    public void restore(Processor cpu) {
+        MainMemory mem = cpu.getMemory();
+        if (mem.getSize() != memory.length * 4) {
+            throw new IllegalArgumentException(String.format(
+                "Snapshot holds %d bytes of memory, processor has %d", memory.length * 4, mem.getSize()));
+        }
+        cpu.reset();
+        mem.reset();
+        for (int i = 0; i < memory.length; i++) {
+            if (memory[i] != 0) mem.write(i * 4, memory[i]);
+        }
+        for (int i = 1; i < registers.length; i++) cpu.getRegisterFile().write(i, registers[i]);
+        cpu.setPC(pc);
+    }
+
+    // ==================== WRITER ====================
+
+    public byte[] toBytes() {
+        List<int[]> segments = segments();            // {first word, word count}
+        int size = HEADER_SIZE + 4 * registers.length + 8;
+        for (int[] s : segments) size += 8 + 4 * s[1];
+        size += 4;
+
+        ByteBuffer out = ByteBuffer.allocate(size).order(ByteOrder.LITTLE_ENDIAN);
+        out.putInt(MAGIC);
+        out.putShort((short) VERSION);
+        out.putShort((short) HEADER_SIZE);
+        out.putInt(halted ? FLAG_HALTED : 0);
+        out.putInt(pc);
+        out.putInt(registers.length);
+        for (int r : registers) out.putInt(r);
+        out.putInt(memory.length * 4);
+        out.putInt(segments.size());
+        for (int[] s : segments) {
+            out.putInt(s[0] * 4);
+            out.putInt(s[1] * 4);
+            for (int i = 0; i < s[1]; i++) out.putInt(memory[s[0] + i]);
+        }
+        out.putInt(crc(out.array(), size - 4));
+        return out.array();
+    }
+
+    /** Runs of non-zero words, merging runs separated by at most MERGE_GAP_WORDS zeros. */
+    private List<int[]> segments() {
+        List<int[]> segments = new ArrayList<>();
+        int i = 0;
+        while (i < memory.length) {
+            if (memory[i] == 0) { i++; continue; }
+            int start = i;
+            int end = i + 1;                          // exclusive, always just past a non-zero word
+            for (int j = end; j < memory.length && j <= end + MERGE_GAP_WORDS; j++) {
+                if (memory[j] != 0) end = j + 1;
+            }
+            segments.add(new int[] { start, end - start });
+            i = end;
+        }
+        return segments;
+    }
+
+    // ==================== READER ====================
+
+    /**
+     * Parse a snapshot, checking magic, version, CRC and every length.
+     * @throws IllegalArgumentException if the bytes are not a valid snapshot
+     */
+    public static MachineSnapshot fromBytes(byte[] bytes) {
+        ByteBuffer in = ByteBuffer.wrap(bytes).order(ByteOrder.LITTLE_ENDIAN);
+        try {
+            int magic = in.getInt();
+            if (magic != MAGIC) {
+                throw new IllegalArgumentException(String.format("Not a snapshot: magic 0x%08X", magic));
+            }
+            int version = in.getShort() & 0xFFFF;
+            if (version != VERSION) {
+                throw new IllegalArgumentException("Unsupported snapshot version " + version + " (reader knows " + VERSION + ")");
+            }
+            int headerSize = in.getShort() & 0xFFFF;
+            if (headerSize < HEADER_SIZE) throw new IllegalArgumentException("Header too short: " + headerSize);
+            int stored = ByteBuffer.wrap(bytes, bytes.length - 4, 4).order(ByteOrder.LITTLE_ENDIAN).getInt();
+            if (crc(bytes, bytes.length - 4) != stored) {
+                throw new IllegalArgumentException("Snapshot is corrupt: CRC-32 mismatch");
+            }
+            int flags = in.getInt();
+            int pc = in.getInt();
+            int count = in.getInt();
+            if (count != 32) throw new IllegalArgumentException("Expected 32 registers, file has " + count);
+            in.position(headerSize);                  // skip appended header fields
+
+            int[] registers = new int[count];
+            for (int i = 0; i < count; i++) registers[i] = in.getInt();
+            int memorySize = in.getInt();
+            if (memorySize < 0 || memorySize % 4 != 0) throw new IllegalArgumentException("Bad memory size " + memorySize);
+            int[] memory = new int[memorySize / 4];
+            int segmentCount = in.getInt();
+            for (int s = 0; s < segmentCount; s++) {
+                int address = in.getInt();
+                int length = in.getInt();
+                if (address < 0 || length < 0 || address % 4 != 0 || length % 4 != 0
+                        || (long) address + length > memorySize) {
+                    throw new IllegalArgumentException(String.format(
+                        "Segment %d (0x%08X, %d bytes) outside memory", s, address, length));
+                }
+                for (int i = 0; i < length / 4; i++) memory[address / 4 + i] = in.getInt();
+            }
+            if (in.remaining() != 4) throw new IllegalArgumentException("Trailing bytes after the segments");
+            return new MachineSnapshot(pc, (flags & FLAG_HALTED) != 0, registers, memory);
+        } catch (BufferUnderflowException | IndexOutOfBoundsException e) {
+            throw new IllegalArgumentException("Snapshot is truncated (" + bytes.length + " bytes)", e);
+        }
+    }
+
+    public void writeTo(Path path) throws IOException {
+        Files.write(path, toBytes());
+    }
+
+    public static MachineSnapshot readFrom(Path path) throws IOException {
+        return fromBytes(Files.readAllBytes(path));
+    }
+
+    private static int crc(byte[] bytes, int length) {
+        CRC32 crc = new CRC32();
+        crc.update(bytes, 0, length);
+        return (int) crc.getValue();
+    }
+
+    // ==================== ACCESSORS ====================
+
+    public int getPC() { return pc; }
+    public boolean isHalted() { return halted; }
+    public int getRegister(int index) { return registers[index]; }
+    public int getMemoryWord(int address) { return memory[address / 4]; }
+    public int getMemorySize() { return memory.length * 4; }
+
+    /** Field-by-field description of the file this snapshot serializes to. */
+    public String describe() {
+        byte[] bytes = toBytes();
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("Snapshot v%d: %d bytes for %d bytes of memory + 32 registers\n",
+            VERSION, bytes.length, memory.length * 4));
+        sb.append(String.format("  PC = 0x%08X, %s\n", pc, halted ? "halted" : "running"));
+        sb.append("  Registers (non-zero):");
+        for (int i = 0; i < registers.length; i++) {
+            if (registers[i] != 0) sb.append(String.format(" x%d=0x%X", i, registers[i]));
+        }
+        sb.append('\n');
+        for (int[] s : segments()) {
+            sb.append(String.format("  Segment 0x%08X, %d bytes\n", s[0] * 4, s[1] * 4));
+        }
+        sb.append(String.format("  CRC-32 0x%08X\n", crc(bytes, bytes.length - 4)));
+        return sb.toString();
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    /** Sum 1..5 into a0, store it to 0x200, halt. */
+    private static int[] demoProgram() {
+        return new int[] {
+            Instruction.addi(10, 0, 0).getRaw(),
+            Instruction.addi(11, 0, 1).getRaw(),
+            Instruction.addi(12, 0, 6).getRaw(),
+            Instruction.add(10, 10, 11).getRaw(),
+            Instruction.addi(11, 11, 1).getRaw(),
+            Instruction.beq(11, 12, 8).getRaw(),
+            Instruction.beq(0, 0, -12).getRaw(),
+            Instruction.sw(10, 0, 0x200).getRaw(),
+            0x00100073
+        };
+    }
+
+    public static String demonstrateMachineSnapshot() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Machine Snapshots: a Binary File Format ===\n\n");
+
+        SingleCycleProcessor cpu = new SingleCycleProcessor();
+        cpu.getMemory().loadProgram(demoProgram(), 0);
+        cpu.run(8);
+        MachineSnapshot snap = capture(cpu);
+        byte[] bytes = snap.toBytes();
+        sb.append("Program: sum 1..5 into a0, store to 0x200. Snapshot after 8 cycles:\n");
+        sb.append(snap.describe()).append('\n');
+
+        sb.append("The file (offsets 0-19 header, then x0..x31 little-endian):\n");
+        sb.append(new HexDump().format(Arrays.copyOf(bytes, 48)));
+        sb.append("  54 44 54 53 = \"TDTS\", 01 00 = version 1, 14 00 = header size 20\n\n");
+
+        cpu.run(1000);
+        SingleCycleProcessor resumed = new SingleCycleProcessor();
+        fromBytes(bytes).restore(resumed);
+        resumed.run(1000);
+        sb.append(String.format("Straight run:  a0 = %d, mem[0x200] = %d\n",
+            cpu.getRegisterFile().read(10), cpu.getMemory().read(0x200)));
+        sb.append(String.format("Save/restore:  a0 = %d, mem[0x200] = %d  (restored into a fresh CPU, resumed)\n\n",
+            resumed.getRegisterFile().read(10), resumed.getMemory().read(0x200)));
+
+        sb.append("What the reader rejects:\n");
+        byte[] flipped = bytes.clone();
+        flipped[HEADER_SIZE + 4 * 10] ^= 0x01;        // one bit of a0
+        byte[] future = bytes.clone();
+        future[4] = 2;
+        byte[] cut = Arrays.copyOf(bytes, bytes.length - 10);
+        for (byte[] bad : new byte[][] { flipped, future, cut, "hello, world".getBytes() }) {
+            try {
+                fromBytes(bad);
+                sb.append("  (accepted!)\n");
+            } catch (IllegalArgumentException e) {
+                sb.append("  ").append(e.getMessage()).append('\n');
+            }
+        }
+        return sb.toString();
+    }
+
+    // ==================== MAIN ====================
+
+    /**
+     * Usage: MachineSnapshot save file [cycles]   run the demo program, save its state
+     *        MachineSnapshot dump file            describe a snapshot file
+     *        MachineSnapshot load file [cycles]   restore a snapshot, run on, print registers
+     *                                 [--stats]  ... and the cycle counts and instruction mix
+     */
+    private static final String USAGE = "Usage: MachineSnapshot save|dump|load file [cycles] [--stats]";
+
+    public static void main(String[] args) {
+        if (args.length < 2) {
+            if (args.length == 0) {
+                System.out.println(demonstrateMachineSnapshot());
+                System.out.println(USAGE);
+                return;
+            }
+            System.err.println(USAGE);
+            System.exit(2);
+        }
+        Path path = Paths.get(args[1]);
+        boolean printStats = Arrays.asList(args).contains("--stats");
+        int cycles = 1000;
+        if (args.length > 2 && !args[2].equals("--stats")) {
+            try {
+                cycles = Integer.parseInt(args[2]);
+            } catch (NumberFormatException e) {
+                cycles = -1;
+            }
+            if (cycles < 0) {
+                System.err.println("Not a cycle count: " + args[2] + "\n" + USAGE);
+                System.exit(2);
+            }
+        }
+        try {
+            switch (args[0]) {
+                case "save": {
+                    SingleCycleProcessor cpu = new SingleCycleProcessor();
+                    cpu.getMemory().loadProgram(demoProgram(), 0);
+                    cpu.run(cycles);
+                    MachineSnapshot snap = capture(cpu);
+                    snap.writeTo(path);
+                    System.out.print(snap.describe());
+                    break;
+                }
+                case "dump":
+                    System.out.print(readFrom(path).describe());
+                    break;
+                case "load": {
+                    SingleCycleProcessor cpu = new SingleCycleProcessor();
+                    readFrom(path).restore(cpu);
+                    // the statistics' count: the cycle that finds the halt executes nothing
+                    cpu.run(cycles);
+                    int ran = cpu.getStats().getCycleCount();
+                    System.out.printf("Resumed at the saved PC, ran %d cycle%s, PC = 0x%08X%s%n",
+                        ran, ran == 1 ? "" : "s", cpu.getPC(), cpu.isHalted() ? " (halted)" : "");
+                    System.out.print(cpu.getRegisterFile().dump());
+                    if (printStats) {
+                        System.out.println(cpu.getStats());
+                        System.out.print(cpu.getStats().mixReport());
+                    }
+                    break;
+                }
+                default:
+                    System.err.println("Unknown command: " + args[0] + " (save, dump or load)\n" + USAGE);
+                    System.exit(2);
+            }
+        } catch (IOException e) {
+            System.err.println(args[1] + ": " + (e instanceof NoSuchFileException ? "no such file" : e.getMessage()));
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(args[1] + ": " + e.getMessage());
+            System.exit(2);
+        }
+    }
+}
@@ -145,7 +145,7 @@ public class MultiCycleProcessor implements Processor {
             } else {
                 pc = pc + 4;
             }
-            stats.incrementInstructions();
+            stats.incrementInstructions(currentInstruction);
             currentStage = Stage.FETCH;
             return;
         }
@@ -166,7 +166,7 @@ public class MultiCycleProcessor implements Processor {
             if (currentSignals.regWrite) {
                 currentStage = Stage.WRITEBACK;
             } else {
-                stats.incrementInstructions();
+                stats.incrementInstructions(currentInstruction);
                 currentStage = Stage.FETCH;
             }
             return;
@@ -180,7 +180,7 @@ public class MultiCycleProcessor implements Processor {
             currentStage = Stage.WRITEBACK;
         } else {
             pc = pc + 4;
-            stats.incrementInstructions();
+            stats.incrementInstructions(currentInstruction);
             currentStage = Stage.FETCH;
         }
     }
@@ -204,7 +204,7 @@ public class MultiCycleProcessor implements Processor {
         if (currentSignals.regWrite) {
             currentStage = Stage.WRITEBACK;
         } else {
-            stats.incrementInstructions();
+            stats.incrementInstructions(currentInstruction);
             currentStage = Stage.FETCH;
         }
     }
@@ -224,7 +224,7 @@ public class MultiCycleProcessor implements Processor {
         
         registers.write(currentInstruction.getRd(), writeData);
         
-        stats.incrementInstructions();
+        stats.incrementInstructions(currentInstruction);
         currentStage = Stage.FETCH;
     }
     
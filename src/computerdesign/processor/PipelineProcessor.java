@@ -4,7 +4,9 @@ import computerdesign.alu.ALU;
 import computerdesign.control.ControlUnit;
 import computerdesign.control.ControlUnit.ControlSignals;
 import computerdesign.instruction.Instruction;
+import computerdesign.memory.Cache;
 import computerdesign.memory.MainMemory;
+import computerdesign.memory.MemoryUnit;
 import computerdesign.memory.RegisterFile;
 import computerdesign.pipeline.HazardUnit;
 import computerdesign.pipeline.HazardUnit.ForwardSource;
@@ -42,6 +44,7 @@ public class PipelineProcessor implements Processor {
     private int pc;
     private final RegisterFile registers;
     private final MainMemory memory;
+    private Cache dataCache;                // optional, see setDataCache
     private final ALU alu;
     private final ControlUnit control;
     private final HazardUnit hazardUnit;
@@ -93,14 +96,14 @@ public class PipelineProcessor implements Processor {
         if (halted) return;
         
         // Detect hazards BEFORE executing stages
-        HazardResult hazards = hazardUnit.detect(idEx, exMem, memWb);
+        HazardResult hazards = hazardUnit.detect(ifId, idEx, exMem, memWb);
         boolean needsFlush = hazardUnit.needsFlush(exMem);
         
         // Execute all stages IN REVERSE ORDER to avoid overwriting
         // (In hardware, they all execute simultaneously)
         executeWriteBack();
         executeMemory();
-        executeExecute(hazards);
+        executeExecute(hazards, needsFlush);
         executeDecode(hazards, needsFlush);
         executeFetch(hazards, needsFlush);
         
@@ -123,20 +126,19 @@ public class PipelineProcessor implements Processor {
      * Fetch stage: Read instruction from memory.
      */
     private void executeFetch(HazardResult hazards, boolean needsFlush) {
+        if (needsFlush) {
+            // Flush - insert bubble (wins over a stall of a squashed instruction)
+            nextIfId.clear();
+            pc = hazardUnit.getBranchTarget(exMem);
+            stats.incrementMispredictions();
+            return;
+        }
+        
         if (hazards.stall) {
             // Keep the same instruction in IF/ID (stall)
             nextIfId.pc = ifId.pc;
             nextIfId.instruction = ifId.instruction;
             nextIfId.valid = ifId.valid;
-            stats.incrementStalls();
-            return;
-        }
-        
-        if (needsFlush) {
-            // Flush - insert bubble
-            nextIfId.clear();
-            pc = hazardUnit.getBranchTarget(exMem);
-            stats.incrementMispredictions();
             return;
         }
         
@@ -199,8 +201,9 @@ public class PipelineProcessor implements Processor {
     /**
      * Execute stage: Perform ALU operation.
      */
-    private void executeExecute(HazardResult hazards) {
-        if (!idEx.valid) {
+    private void executeExecute(HazardResult hazards, boolean needsFlush) {
+        if (!idEx.valid || needsFlush) {
+            // Bubble, or the wrong-path instruction right behind a taken branch
             nextExMem.clear();
             return;
         }
@@ -262,16 +265,24 @@ public class PipelineProcessor implements Processor {
         ControlSignals signals = exMem.control;
         int memData = 0;
         
+        MemoryUnit data = dataCache != null ? dataCache : memory;
+        int missesBefore = dataCache != null ? dataCache.getMisses() : 0;
+        
         if (signals.memRead) {
-            memData = memory.read(exMem.aluResult);
+            memData = data.read(exMem.aluResult);
             stats.incrementMemoryAccesses();
         }
         
         if (signals.memWrite) {
-            memory.write(exMem.aluResult, exMem.rs2Value);
+            data.write(exMem.aluResult, exMem.rs2Value);
             stats.incrementMemoryAccesses();
         }
         
+        if (dataCache != null && (signals.memRead || signals.memWrite)) {
+            if (dataCache.getMisses() > missesBefore) stats.incrementCacheMisses();
+            else stats.incrementCacheHits();
+        }
+        
         nextMemWb.pc = exMem.pc;
         nextMemWb.instruction = exMem.instruction;
         nextMemWb.control = signals;
@@ -301,7 +312,7 @@ public class PipelineProcessor implements Processor {
             registers.write(memWb.rd, writeData);
         }
         
-        stats.incrementInstructions();
+        stats.incrementInstructions(memWb.instruction);
     }
     
     /**
@@ -354,6 +365,21 @@ public class PipelineProcessor implements Processor {
         return memory;
     }
     
+    /**
+     * Send loads and stores through a data cache whose next level is this
+     * processor's memory; each access is counted as a hit or a miss in the
+     * stats. Instruction fetch still reads memory directly, and with a
+     * write-back cache memory is only current after getDataCache().flush().
+     * Null detaches the cache.
+     */
+    public void setDataCache(Cache cache) {
+        this.dataCache = cache;
+    }
+    
+    public Cache getDataCache() {
+        return dataCache;
+    }
+    
     @Override
     public boolean isHalted() {
         return halted;
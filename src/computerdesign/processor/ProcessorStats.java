@@ -1,5 +1,9 @@
 package computerdesign.processor;
 
+import computerdesign.instruction.Instruction;
+import java.util.LinkedHashMap;
+import java.util.Map;
+
 /**
  * Statistics about processor execution.
  * 
@@ -9,6 +13,9 @@ package computerdesign.processor;
  * - Pipeline: CPI approaches 1 with shorter cycle time (best of both worlds)
  */
 public class ProcessorStats {
+    /** Instruction classes for the mix, in report order. */
+    public static final String[] CLASSES = {"alu", "load", "store", "branch", "jump", "system"};
+
     private int cycleCount;
     private int instructionCount;
     private int memoryAccesses;
@@ -17,6 +24,9 @@ public class ProcessorStats {
     private int branchCount;
     private int branchMispredictions;
     private int stallCycles;
+    private int registerReads;
+    private int registerWrites;
+    private final Map<String, Integer> mix = new LinkedHashMap<>();
     
     public ProcessorStats() {
         reset();
@@ -31,12 +41,26 @@ public class ProcessorStats {
         branchCount = 0;
         branchMispredictions = 0;
         stallCycles = 0;
+        registerReads = 0;
+        registerWrites = 0;
+        mix.clear();
     }
     
     // Increment methods
     public void incrementCycles() { cycleCount++; }
     public void incrementCycles(int n) { cycleCount += n; }
     public void incrementInstructions() { instructionCount++; }
+    
+    /**
+     * Count a retired instruction, its class in the instruction mix and the
+     * register-file reads and writes it made.
+     */
+    public void incrementInstructions(Instruction inst) {
+        instructionCount++;
+        mix.merge(instructionClass(inst), 1, Integer::sum);
+        registerReads += registerReads(inst);
+        if (writesRegister(inst)) registerWrites++;
+    }
     public void incrementMemoryAccesses() { memoryAccesses++; }
     public void incrementCacheHits() { cacheHits++; }
     public void incrementCacheMisses() { cacheMisses++; }
@@ -54,6 +78,58 @@ public class ProcessorStats {
     public int getBranchCount() { return branchCount; }
     public int getBranchMispredictions() { return branchMispredictions; }
     public int getStallCycles() { return stallCycles; }
+    public int getRegisterReads() { return registerReads; }
+    public int getRegisterWrites() { return registerWrites; }
+    
+    /** Retired instructions per class, in CLASSES order, non-zero classes only. */
+    public Map<String, Integer> getInstructionMix() {
+        Map<String, Integer> out = new LinkedHashMap<>();
+        for (String c : CLASSES) if (mix.containsKey(c)) out.put(c, mix.get(c));
+        return out;
+    }
+    
+    /** The class of an instruction by its opcode, as used in CPI-per-class questions. */
+    public static String instructionClass(Instruction inst) {
+        switch (inst.getOpcode()) {
+            case 0b0000011: return "load";
+            case 0b0100011: return "store";
+            case 0b1100011: return "branch";
+            case 0b1101111:
+            case 0b1100111: return "jump";
+            case 0b1110011: return "system";
+            default:        return "alu";      // R-type, I-type arithmetic, LUI, AUIPC
+        }
+    }
+    
+    /** Register-file read ports an instruction uses: rs1 and/or rs2. */
+    public static int registerReads(Instruction inst) {
+        int opcode = inst.getOpcode();
+        boolean rs1 = opcode != 0b0110111 && opcode != 0b0010111 && opcode != 0b1101111   // not LUI, AUIPC, JAL
+            && opcode != 0b1110011;                                                     // nor ECALL/EBREAK
+        boolean rs2 = opcode == 0b0110011 || opcode == 0b0100011 || opcode == 0b1100011; // R-type, store, branch
+        return (rs1 ? 1 : 0) + (rs2 ? 1 : 0);
+    }
+    
+    /** Whether an instruction writes rd (writes to x0 are discarded, not counted). */
+    public static boolean writesRegister(Instruction inst) {
+        int opcode = inst.getOpcode();
+        boolean writes = opcode != 0b0100011 && opcode != 0b1100011 && opcode != 0b1110011; // not store, branch, system
+        return writes && inst.getRd() != 0;
+    }
+    
+    /**
+     * The mix as printed by --stats, one "class count percent" line each;
+     * PerfCalc reads this format back.
+     */
+    public String mixReport() {
+        StringBuilder sb = new StringBuilder("Instruction mix:\n");
+        int total = 0;
+        for (int n : mix.values()) total += n;
+        for (Map.Entry<String, Integer> e : getInstructionMix().entrySet()) {
+            sb.append(String.format("  %-8s %8d  %5.1f%%\n", e.getKey(), e.getValue(), 100.0 * e.getValue() / total));
+        }
+        return sb.toString();
+    }
     
     /**
      * Cycles Per Instruction - key performance metric.
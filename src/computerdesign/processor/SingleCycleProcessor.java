@@ -108,9 +108,17 @@ public class SingleCycleProcessor implements Processor {
      * Create a single-cycle processor with given memory.
      */
     public SingleCycleProcessor(MainMemory memory) {
+        this(memory, new ALU());
+    }
+    
+    /**
+     * Create a single-cycle processor with a specific ALU implementation
+     * (e.g. the gate-level model in computerdesign.logic.GateLevelALU).
+     */
+    public SingleCycleProcessor(MainMemory memory, ALU alu) {
         this.memory = memory;
         this.registers = new RegisterFile();
-        this.alu = new ALU();
+        this.alu = alu;
         this.control = new ControlUnit();
         this.stats = new ProcessorStats();
         this.pc = 0;
@@ -202,7 +210,7 @@ public class SingleCycleProcessor implements Processor {
         
         pc = nextPC;
         stats.incrementCycles();
-        stats.incrementInstructions();
+        stats.incrementInstructions(inst);
     }
     
     @Override
@@ -259,6 +267,11 @@ public class SingleCycleProcessor implements Processor {
         return stats;
     }
     
+    /** The ALU this datapath executes on (behavioral or gate-level). */
+    public ALU getALU() {
+        return alu;
+    }
+    
     @Override
     public String toString() {
         return String.format("SingleCycleProcessor{pc=0x%08X, halted=%b}", pc, halted);
@@ -0,0 +1,121 @@
+package computerdesign.sap;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Paths;
+import java.util.Random;
+
+/**
+ * AssemblerFuzz - Mangled SAP-1 programs must be errors, not crashes.
+ *
+ * The Rust parsers have cargo-fuzz targets (fuzz/); the SAP-1 assembler is
+ * Java, so this is the same idea without libFuzzer: a seeded generator
+ * overwrites a few characters of a working program with the characters the
+ * syntax cares about, or glues in a mnemonic or cuts the text short, and
+ * checks the contract:
+ *
+ *   assemble() and load()   return, or throw IllegalArgumentException;
+ *                           an assembler error names a line of the source
+ *   run()                   returns, or throws IllegalStateException
+ *                           (an opcode without microcode, bus contention)
+ *
+ * Anything else - an index out of range, a NullPointerException, a line
+ * number past the end - is printed with the input that caused it, and the
+ * exit code is 1. The same seed gives the same inputs, so a failure repeats.
+ *
+ * Usage: AssemblerFuzz [iterations [microcode.toml|json program.asm]]
+ */
+public final class AssemblerFuzz {
+
+    private static final String SPECIAL = "; \n\t0123456789xXhHbB-";
+    private static final String[] TOKENS = {"LDA", "ADD", "SUB", "OUT", "HLT", "JMP", "0x", "0b", "FFH", " 16", "\n\n"};
+
+    private AssemblerFuzz() {}
+
+    /** One mangled copy of program. */
+    static String mutate(String program, Random rng) {
+        StringBuilder sb = new StringBuilder(program);
+        int edits = 1 + rng.nextInt(4);
+        for (int i = 0; i < edits && sb.length() > 0; i++) {
+            int at = rng.nextInt(sb.length());
+            if (rng.nextInt(4) == 0) sb.insert(at, TOKENS[rng.nextInt(TOKENS.length)]);
+            else sb.setCharAt(at, SPECIAL.charAt(rng.nextInt(SPECIAL.length())));
+        }
+        if (rng.nextInt(8) == 0) sb.setLength(rng.nextInt(sb.length() + 1));
+        return sb.toString();
+    }
+
+    /** null if the input kept to the contract, otherwise what went wrong. */
+    static String check(SAP1.ControlStore store, String source) {
+        SAP1 cpu = new SAP1(store);
+        try {
+            cpu.load(cpu.assemble(source));
+        } catch (IllegalArgumentException e) {
+            String m = String.valueOf(e.getMessage());
+            if (m.equals("Program larger than RAM")) return null;
+            if (!m.matches("line \\d+: .*")) return "no line in: " + m;
+            int line = Integer.parseInt(m.substring(5, m.indexOf(':')));
+            int lines = source.split("\n").length;
+            return line >= 1 && line <= lines ? null : "line " + line + " of " + lines + " in: " + m;
+        } catch (RuntimeException e) {
+            return "assemble threw " + e;
+        }
+        try {
+            cpu.run(240);
+        } catch (IllegalStateException e) {
+            return null;
+        } catch (RuntimeException e) {
+            return "run threw " + e;
+        }
+        return null;
+    }
+
+    public static void main(String[] args) {
+        int iterations = 20_000;
+        SAP1.ControlStore store = SAP1.standardControlStore();
+        String program = SAP1.EXAMPLE_PROGRAM;
+        if (args.length == 2 || args.length > 3) usage("Expected 0, 1 or 3 arguments");
+        if (args.length > 0) {
+            try {
+                iterations = Integer.parseInt(args[0]);
+            } catch (NumberFormatException e) {
+                usage("Not an iteration count: " + args[0]);
+            }
+        }
+        if (args.length == 3) {
+            String file = args[1];
+            try {
+                store = Microcode.load(Paths.get(args[1]));
+                file = args[2];
+                program = new String(Files.readAllBytes(Paths.get(args[2])), StandardCharsets.UTF_8);
+            } catch (IOException e) {
+                System.err.println(file + ": " + (e instanceof NoSuchFileException ? "no such file" : e.getMessage()));
+                System.exit(2);
+            } catch (IllegalArgumentException e) {
+                // Microcode's messages start with file:line already
+                System.err.println(e.getMessage());
+                System.exit(2);
+            }
+        }
+
+        Random rng = new Random(4160);
+        int failures = 0;
+        for (int i = 0; i < iterations; i++) {
+            String source = mutate(program, rng);
+            String problem = check(store, source);
+            if (problem != null && failures++ < 10) {
+                System.out.println("input " + i + ": " + problem);
+                System.out.println("  " + source.replace("\n", "\\n"));
+            }
+        }
+        System.out.println(iterations + " mangled programs, " + failures + " broke the contract");
+        if (failures > 0) System.exit(1);
+    }
+
+    private static void usage(String message) {
+        System.err.println(message + "\nUsage: AssemblerFuzz [iterations [microcode.toml|json program.asm]]");
+        System.exit(2);
+    }
+}
@@ -0,0 +1,311 @@
+package computerdesign.sap;
+
+import computerdesign.sap.SAP1.ControlStore;
+import computerdesign.sap.SAP1.Microprogram;
+import computerdesign.sap.SAP1.Signal;
+import computerdesign.util.Json;
+import computerdesign.util.Toml;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.EnumSet;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+import java.util.regex.Matcher;
+import java.util.regex.Pattern;
+
+/**
+ * Microcode - Loads the SAP-1 control store from an editable TOML/JSON file.
+ *
+ * Covers learning goals: T3.1 (Control Unit - microprogrammed vs hardwired)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * THE FILE FORMAT
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   name     = "SAP-1 extended"
+ *   t_states = 6                         # ring-counter length (optional, 4-12)
+ *   fetch    = ["MI CO", "CE", "RO II"]  # optional, this is the default
+ *
+ *   [[instruction]]
+ *   mnemonic = "LDA"
+ *   opcode   = 0b0000
+ *   operand  = true                      # takes a 4-bit address/value
+ *   steps    = ["MI IO", "RO AI"]        # T4, T5, ... ("-" = do nothing)
+ *
+ *   Each step lists the control signals that are 1 in that T-state.
+ *   Available signals: HLT MI RI RO II IO AI AO EO SU BI OI CE CO J
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * A NEW INSTRUCTION WITHOUT NEW HARDWARE
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   The datapath already has everything STA (store A) needs:
+ *
+ *     T4  MI IO     MAR ← address from IR
+ *     T5  AO RI     RAM[MAR] ← A
+ *
+ *   Add those two lines to the file and the assembler knows STA - that is
+ *   the point of a microprogrammed control unit: the instruction set is data.
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public final class Microcode {
+
+    private Microcode() {
+    }
+
+    // ==================== LOADING ====================
+
+    /**
+     * A mistake in a description: `key` (a key name, or the quoted text of
+     * a step) in the `instruction`th instruction, -1 for the top level. The
+     * parsed tables carry no lines, so locate() finds it in the text.
+     */
+    private static final class Mistake extends IllegalArgumentException {
+        private static final long serialVersionUID = 1L;
+        final int instruction;
+        final String key;
+
+        Mistake(int instruction, String key, String message) {
+            super(message);
+            this.instruction = instruction;
+            this.key = key;
+        }
+    }
+
+    private static final Pattern BLOCK = Pattern.compile("^\\s*\\[\\[instructions?\\]\\]|\"mnemonic\"\\s*:");
+    private static final Pattern SYNTAX = Pattern.compile("^(?:TOML|JSON) line (\\d+): (.*)$");
+
+    /** Parse a control store description: JSON if it starts with '{', otherwise TOML. */
+    public static ControlStore parse(String text) {
+        return parse(text, "microcode");
+    }
+
+    /** As parse(text); every error message starts with "source:line: ". */
+    public static ControlStore parse(String text, String source) {
+        try {
+            Map<String, Object> description = text.trim().startsWith("{")
+                ? Json.parseObject(text) : Toml.parse(text);
+            return fromDescription(description);
+        } catch (Mistake m) {
+            throw new IllegalArgumentException(source + ":" + locate(text, m) + ": " + m.getMessage());
+        } catch (IllegalArgumentException e) {
+            Matcher syntax = SYNTAX.matcher(e.getMessage());
+            if (!syntax.matches()) throw new IllegalArgumentException(source + ": " + e.getMessage());
+            throw new IllegalArgumentException(source + ":" + syntax.group(1) + ": " + syntax.group(2));
+        }
+    }
+
+    public static ControlStore load(Path path) throws IOException {
+        return parse(new String(Files.readAllBytes(path), StandardCharsets.UTF_8), path.toString());
+    }
+
+    /** The 1-based line of the mistake: its key inside its instruction's block, or the block itself. */
+    private static int locate(String text, Mistake m) {
+        String[] lines = text.split("\n", -1);
+        int from = 0;
+        for (int seen = -1; seen < m.instruction && from < lines.length; from++) {
+            if (BLOCK.matcher(lines[from]).find()) seen++;
+            if (seen == m.instruction) break;
+        }
+        int start = Math.min(from, lines.length - 1);
+        for (int i = start; i < lines.length; i++) {
+            if (i > start && BLOCK.matcher(lines[i]).find()) break;
+            if (lines[i].contains(m.key)) return i + 1;
+        }
+        return m.instruction < 0 ? 1 : start + 1;
+    }
+
+    @SuppressWarnings("unchecked")
+    public static ControlStore fromDescription(Map<String, Object> d) {
+        ControlStore store;
+        try {
+            store = new ControlStore(d.containsKey("t_states") ? integer(d.get("t_states"), "t_states") : SAP1.T_STATES);
+        } catch (IllegalArgumentException e) {
+            throw new Mistake(-1, "t_states", e.getMessage());
+        }
+
+        List<Set<Signal>> fetch = d.containsKey("fetch")
+            ? steps(list(d, "fetch", -1), -1, "fetch") : SAP1.standardControlStore().getFetch();
+        try {
+            store.fetch(fetch);
+        } catch (IllegalArgumentException e) {
+            throw new Mistake(-1, "fetch", e.getMessage());
+        }
+
+        List<Object> instructions = d.containsKey("instructions") ? list(d, "instructions", -1) : list(d, "instruction", -1);
+        if (instructions.isEmpty()) throw new Mistake(-1, "instruction", "no instructions defined");
+        for (int n = 0; n < instructions.size(); n++) {
+            Object o = instructions.get(n);
+            if (!(o instanceof Map)) throw new Mistake(n, "instruction", "instruction must be a table");
+            Map<String, Object> i = (Map<String, Object>) o;
+            Object mnemonic = i.get("mnemonic");
+            if (mnemonic == null) throw new Mistake(n, "instruction", "instruction is missing 'mnemonic'");
+            if (!i.containsKey("opcode")) throw new Mistake(n, "mnemonic", mnemonic + " is missing 'opcode'");
+            String name = String.valueOf(mnemonic);
+            boolean operand = Boolean.TRUE.equals(i.get("operand"));
+            int opcode;
+            try {
+                opcode = integer(i.get("opcode"), name + ".opcode");
+            } catch (IllegalArgumentException e) {
+                throw new Mistake(n, "opcode", e.getMessage());
+            }
+            List<Set<Signal>> steps = steps(list(i, "steps", n), n, name);
+            try {
+                store.define(new Microprogram(opcode, name, operand, steps));
+            } catch (IllegalArgumentException e) {
+                // opcode out of range or taken, mnemonic taken, too many steps
+                String key = e.getMessage().startsWith("opcode") ? "opcode" : e.getMessage().startsWith("mnemonic") ? "mnemonic" : "steps";
+                throw new Mistake(n, key, e.getMessage());
+            }
+        }
+        return store;
+    }
+
+    /** "MI CO" or "MI, CO" → {MI, CO}; "-" or "" → no signals. */
+    public static Set<Signal> controlWord(String text) {
+        Set<Signal> word = EnumSet.noneOf(Signal.class);
+        for (String name : text.trim().split("[\\s,]+")) {
+            if (name.isEmpty() || name.equals("-")) continue;
+            word.add(Signal.parse(name));
+        }
+        return word;
+    }
+
+    /** Each step's signals, checked for two bus drivers; `context` is "fetch" or the mnemonic. */
+    private static List<Set<Signal>> steps(List<Object> raw, int instruction, String context) {
+        List<Set<Signal>> steps = new ArrayList<>();
+        for (Object step : raw) {
+            String text = String.valueOf(step);
+            String where = context + " T" + ((instruction < 0 ? 1 : SAP1.FETCH_STATES + 1) + steps.size());
+            Set<Signal> word;
+            try {
+                word = controlWord(text);
+            } catch (IllegalArgumentException e) {
+                throw new Mistake(instruction, "\"" + text + "\"", where + ": " + e.getMessage());
+            }
+            try {
+                SAP1.checkBus(word, where);
+            } catch (IllegalArgumentException e) {
+                throw new Mistake(instruction, "\"" + text + "\"", e.getMessage());
+            }
+            steps.add(word);
+        }
+        return steps;
+    }
+
+    private static int integer(Object value, String key) {
+        if (!(value instanceof Long)) throw new IllegalArgumentException("'" + key + "' must be an integer");
+        return (int) (long) (Long) value;
+    }
+
+    @SuppressWarnings("unchecked")
+    private static List<Object> list(Map<String, Object> d, String key, int instruction) {
+        Object v = d.get(key);
+        if (v == null) return Collections.emptyList();
+        if (!(v instanceof List)) throw new Mistake(instruction, key, "'" + key + "' must be an array");
+        return (List<Object>) v;
+    }
+
+    // ==================== WRITING ====================
+
+    /** The store in the file format above - a starting point for editing. */
+    public static String toToml(ControlStore store, String name) {
+        StringBuilder sb = new StringBuilder();
+        sb.append("name     = \"").append(name).append("\"\n");
+        sb.append("t_states = ").append(store.getTStates()).append("\n");
+        sb.append("fetch    = ").append(stepList(store.getFetch())).append("\n");
+        for (Microprogram p : store.getPrograms()) {
+            sb.append("\n[[instruction]]\n");
+            sb.append("mnemonic = \"").append(p.mnemonic).append("\"\n");
+            sb.append("opcode   = 0b").append(SAP1.binary(p.opcode, 4)).append("\n");
+            sb.append("operand  = ").append(p.hasOperand).append("\n");
+            sb.append("steps    = ").append(stepList(p.steps)).append("\n");
+        }
+        return sb.toString();
+    }
+
+    private static String stepList(List<Set<Signal>> steps) {
+        StringBuilder sb = new StringBuilder("[");
+        for (int i = 0; i < steps.size(); i++) {
+            if (i > 0) sb.append(", ");
+            sb.append('"').append(SAP1.signals(steps.get(i))).append('"');
+        }
+        return sb.append(']').toString();
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    /** The standard instruction set plus LDI, STA and JMP - all pure microcode. */
+    public static final String EXTENDED =
+        "name = \"SAP-1 extended\"\n" +
+        "\n" +
+        "[[instruction]]\n" +
+        "mnemonic = \"LDA\"\nopcode = 0b0000\noperand = true\nsteps = [\"MI IO\", \"RO AI\"]\n" +
+        "\n" +
+        "[[instruction]]\n" +
+        "mnemonic = \"ADD\"\nopcode = 0b0001\noperand = true\nsteps = [\"MI IO\", \"RO BI\", \"AI EO\"]\n" +
+        "\n" +
+        "[[instruction]]\n" +
+        "mnemonic = \"SUB\"\nopcode = 0b0010\noperand = true\nsteps = [\"MI IO\", \"RO BI\", \"AI EO SU\"]\n" +
+        "\n" +
+        "[[instruction]]   # new: store A\n" +
+        "mnemonic = \"STA\"\nopcode = 0b0100\noperand = true\nsteps = [\"MI IO\", \"AO RI\"]\n" +
+        "\n" +
+        "[[instruction]]   # new: load immediate\n" +
+        "mnemonic = \"LDI\"\nopcode = 0b0101\noperand = true\nsteps = [\"IO AI\"]\n" +
+        "\n" +
+        "[[instruction]]   # new: unconditional jump\n" +
+        "mnemonic = \"JMP\"\nopcode = 0b0110\noperand = true\nsteps = [\"IO J\"]\n" +
+        "\n" +
+        "[[instruction]]\n" +
+        "mnemonic = \"OUT\"\nopcode = 0b1110\nsteps = [\"AO OI\"]\n" +
+        "\n" +
+        "[[instruction]]\n" +
+        "mnemonic = \"HLT\"\nopcode = 0b1111\nsteps = [\"HLT\"]\n";
+
+    /** Doubles A forever: needs STA, LDI and JMP. */
+    public static final String DOUBLING_PROGRAM =
+        "LDI 1     ; A = 1\n" +
+        "OUT       ; loop:\n" +
+        "STA 15    ; RAM[15] = A\n" +
+        "ADD 15    ; A = A + A\n" +
+        "JMP 1     ; goto loop\n";
+
+    public static String demonstrateMicrocode() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  EDITABLE MICROCODE: NEW INSTRUCTIONS WITHOUT NEW HARDWARE\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        sb.append("The standard SAP-1 control store does not know LDI:\n  ");
+        try {
+            new SAP1().assemble(DOUBLING_PROGRAM);
+        } catch (IllegalArgumentException e) {
+            sb.append(e.getMessage()).append("\n\n");
+        }
+
+        ControlStore extended = parse(EXTENDED);
+        sb.append("Control store loaded from the microcode file:\n\n").append(extended.table()).append("\n");
+
+        SAP1 cpu = new SAP1(extended);
+        cpu.load(cpu.assemble(DOUBLING_PROGRAM));
+        sb.append("Program:\n\n").append(DOUBLING_PROGRAM).append("\n");
+        cpu.run(8 * 5 * extended.getTStates());
+        sb.append("OUT register over time: ").append(cpu.getOutputs()).append("\n");
+        sb.append("(8-bit A wraps around: 128 + 128 = 256 → 0)\n\n");
+
+        sb.append("STA, one clock per line:\n\n");
+        List<String> log = cpu.getLog();
+        for (int i = 2 * extended.getTStates(); i < 3 * extended.getTStates(); i++) {
+            sb.append("  ").append(log.get(i)).append("\n");
+        }
+        sb.append("\nExport a store with toToml(), edit the file, reload it with load().\n");
+        return sb.toString();
+    }
+}
@@ -0,0 +1,589 @@
+package computerdesign.sap;
+
+import computerdesign.logic.SignalTrace;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.EnumSet;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+import java.util.Set;
+
+/**
+ * SAP1 - "Simple As Possible" 8-bit bus-organized CPU with microcoded control.
+ *
+ * Covers learning goals: T3.1 (Control Unit), T4.1 (Datapath), T2.1 (ISA)
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * ONE BUS, SIX REGISTERS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   ┌────────────┐       W bus (8 bits)       ┌─────────────┐
+ *   │  PC (4)    ├─CO─►──┬──────────────┬──◄─AO┤ A (acc)     │
+ *   │            │◄─J────┤              ├──AI─►│             │
+ *   └────────────┘       │              │      └──────┬──────┘
+ *   ┌────────────┐       │              │      ┌──────┴──────┐
+ *   │  MAR (4)   │◄─MI───┤              ├──◄─EO┤ ALU  A ± B  │◄─ SU
+ *   └─────┬──────┘       │              │      └──────┬──────┘
+ *   ┌─────┴──────┐       │              │      ┌──────┴──────┐
+ *   │ RAM 16×8   ├─RO─►──┤              ├──BI─►│ B           │
+ *   │            │◄─RI───┤              │      └─────────────┘
+ *   └────────────┘       │              │      ┌─────────────┐
+ *   ┌────────────┐       │              ├──OI─►│ OUT         │
+ *   │  IR (8)    │◄─II───┤              │      └─────────────┘
+ *   │ op │ addr  ├─IO─►──┘              │
+ *   └──┬─────────┘                      │
+ *      └──► control store ──► control word (one bit per signal)
+ *
+ *   Exactly ONE register may drive the bus per clock (xO signals);
+ *   any number may load from it (xI signals) at the clock edge.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * FETCH - DECODE - EXECUTE AS T-STATES
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   A ring counter steps through T1..T6; each T-state is one clock cycle and
+ *   one control word.
+ *
+ *   T1  MI CO     address state:    MAR ← PC
+ *   T2  CE        increment state:  PC ← PC + 1
+ *   T3  RO II     memory state:     IR ← RAM[MAR]
+ *   T4..T6        execute: depends on the opcode in IR (the microprogram)
+ *
+ *   Op    Code  T4       T5       T6
+ *   LDA   0000  MI IO    RO AI    -          A ← RAM[addr]
+ *   ADD   0001  MI IO    RO BI    AI EO      A ← A + RAM[addr]
+ *   SUB   0010  MI IO    RO BI    AI EO SU   A ← A - RAM[addr]
+ *   OUT   1110  AO OI    -        -          OUT ← A
+ *   HLT   1111  HLT      -        -          stop the clock
+ *
+ *   Every instruction takes all six T-states; unused ones are NOPs.
+ * ═══════════════════════════════════════════════════════════════════════════════
+ */
+public class SAP1 {
+
+    public static final int RAM_SIZE = 16;
+    /** Ring-counter length of the standard control store. */
+    public static final int T_STATES = 6;
+    public static final int FETCH_STATES = 3;
+    public static final int MAX_T_STATES = 12;
+
+    // ==================== CONTROL SIGNALS ====================
+
+    /** One bit of the control word. xO drive the bus, xI load from it. */
+    public enum Signal {
+        HLT("halt the clock"),
+        MI("MAR in"),
+        RI("RAM in (write)"),
+        RO("RAM out"),
+        II("instruction register in"),
+        IO("instruction operand out (low 4 bits)"),
+        AI("A in"),
+        AO("A out"),
+        EO("ALU result out"),
+        SU("ALU subtract"),
+        BI("B in"),
+        OI("output register in"),
+        CE("counter enable (PC + 1)"),
+        CO("counter out"),
+        J("jump (PC in)");
+
+        public final String description;
+
+        Signal(String description) {
+            this.description = description;
+        }
+
+        public boolean drivesBus() {
+            return this == RO || this == IO || this == AO || this == EO || this == CO;
+        }
+
+        public static Signal parse(String name) {
+            try {
+                return valueOf(name.toUpperCase());
+            } catch (IllegalArgumentException e) {
+                throw new IllegalArgumentException("Unknown control signal: " + name);
+            }
+        }
+    }
+
+    /** Rejects a control word with two bus drivers: `where` is e.g. "STA T5". */
+    public static void checkBus(Set<Signal> word, String where) {
+        Signal driver = null;
+        for (Signal s : word) {
+            if (!s.drivesBus()) continue;
+            if (driver != null) {
+                throw new IllegalArgumentException(where + ": " + driver + " and " + s
+                    + " both drive the bus (only one xO per step)");
+            }
+            driver = s;
+        }
+    }
+
+    /** Control word as a bit string in Signal order, e.g. "010000000000001". */
+    public static String controlWordBits(Set<Signal> word) {
+        StringBuilder sb = new StringBuilder();
+        for (Signal s : Signal.values()) sb.append(word.contains(s) ? '1' : '0');
+        return sb.toString();
+    }
+
+    // ==================== CONTROL STORE ====================
+
+    /** One opcode's microprogram: the control words for T4, T5, ... */
+    public static class Microprogram {
+        public final int opcode;
+        public final String mnemonic;
+        public final boolean hasOperand;
+        public final List<Set<Signal>> steps;
+
+        public Microprogram(int opcode, String mnemonic, boolean hasOperand, List<Set<Signal>> steps) {
+            if (opcode < 0 || opcode > 15) throw new IllegalArgumentException("opcode must be 0-15: " + opcode);
+            this.opcode = opcode;
+            this.mnemonic = mnemonic.toUpperCase();
+            this.hasOperand = hasOperand;
+            List<Set<Signal>> copy = new ArrayList<>();
+            for (Set<Signal> s : steps) copy.add(Collections.unmodifiableSet(copyOf(s)));
+            this.steps = Collections.unmodifiableList(copy);
+        }
+    }
+
+    /**
+     * The control store: fetch sequence (shared) plus one microprogram per opcode.
+     */
+    public static class ControlStore {
+        private final int tStates;
+        private final List<Set<Signal>> fetch = new ArrayList<>();
+        private final Map<Integer, Microprogram> programs = new LinkedHashMap<>();
+
+        public ControlStore() {
+            this(T_STATES);
+        }
+
+        /** A ring counter of tStates clocks per instruction (fetch included). */
+        public ControlStore(int tStates) {
+            if (tStates <= FETCH_STATES || tStates > MAX_T_STATES) {
+                throw new IllegalArgumentException("T-states must be " + (FETCH_STATES + 1) + "-"
+                    + MAX_T_STATES + ": " + tStates);
+            }
+            this.tStates = tStates;
+        }
+
+        public ControlStore fetch(List<Set<Signal>> steps) {
+            if (steps.size() != FETCH_STATES) {
+                throw new IllegalArgumentException("fetch needs exactly " + FETCH_STATES + " steps");
+            }
+            for (int i = 0; i < steps.size(); i++) checkBus(steps.get(i), "fetch T" + (i + 1));
+            fetch.clear();
+            for (Set<Signal> s : steps) fetch.add(Collections.unmodifiableSet(copyOf(s)));
+            return this;
+        }
+
+        public ControlStore define(Microprogram program) {
+            if (program.steps.size() > tStates - FETCH_STATES) {
+                throw new IllegalArgumentException(program.mnemonic + ": at most "
+                    + (tStates - FETCH_STATES) + " execute steps");
+            }
+            for (int i = 0; i < program.steps.size(); i++) {
+                checkBus(program.steps.get(i), program.mnemonic + " T" + (FETCH_STATES + i + 1));
+            }
+            if (programs.containsKey(program.opcode)) {
+                throw new IllegalArgumentException("opcode " + program.opcode + " defined twice");
+            }
+            for (Microprogram p : programs.values()) {
+                if (p.mnemonic.equals(program.mnemonic)) {
+                    throw new IllegalArgumentException("mnemonic " + program.mnemonic + " defined twice");
+                }
+            }
+            programs.put(program.opcode, program);
+            return this;
+        }
+
+        /** Control word for T-state t (0-based) with the given opcode in IR. */
+        public Set<Signal> controlWord(int t, int opcode) {
+            if (t < FETCH_STATES) return fetch.get(t);
+            Microprogram p = programs.get(opcode);
+            if (p == null) throw new IllegalStateException("No microcode for opcode " + opcode);
+            int i = t - FETCH_STATES;
+            return i < p.steps.size() ? p.steps.get(i) : EnumSet.noneOf(Signal.class);
+        }
+
+        public Microprogram lookup(String mnemonic) {
+            for (Microprogram p : programs.values()) {
+                if (p.mnemonic.equalsIgnoreCase(mnemonic)) return p;
+            }
+            return null;
+        }
+
+        public Microprogram get(int opcode) {
+            return programs.get(opcode);
+        }
+
+        public int getTStates() {
+            return tStates;
+        }
+
+        public List<Set<Signal>> getFetch() {
+            return Collections.unmodifiableList(fetch);
+        }
+
+        public List<Microprogram> getPrograms() {
+            return Collections.unmodifiableList(new ArrayList<>(programs.values()));
+        }
+
+        /** The microcode table: one row per instruction, one column per T-state. */
+        public String table() {
+            StringBuilder sb = new StringBuilder();
+            StringBuilder header = new StringBuilder(String.format("%-6s %-4s", "Op", "Code"));
+            for (int t = 1; t <= tStates; t++) header.append(String.format(" %-10s", "T" + t));
+            sb.append(header.toString().trim()).append("\n");
+            List<Microprogram> sorted = new ArrayList<>(programs.values());
+            sorted.sort((a, b) -> Integer.compare(a.opcode, b.opcode));
+            for (Microprogram p : sorted) {
+                StringBuilder row = new StringBuilder(String.format("%-6s %4s",
+                    p.mnemonic, binary(p.opcode, 4)));
+                for (int t = 0; t < tStates; t++) {
+                    row.append(String.format(" %-10s", signals(controlWord(t, p.opcode))));
+                }
+                sb.append(row.toString().replaceAll("\\s+$", "")).append("\n");
+            }
+            return sb.toString();
+        }
+    }
+
+    /** The original SAP-1 instruction set (LDA, ADD, SUB, OUT, HLT). */
+    public static ControlStore standardControlStore() {
+        ControlStore store = new ControlStore();
+        store.fetch(steps(words(Signal.CO, Signal.MI), words(Signal.CE), words(Signal.RO, Signal.II)));
+        store.define(new Microprogram(0b0000, "LDA", true, steps(
+            words(Signal.IO, Signal.MI), words(Signal.RO, Signal.AI))));
+        store.define(new Microprogram(0b0001, "ADD", true, steps(
+            words(Signal.IO, Signal.MI), words(Signal.RO, Signal.BI), words(Signal.EO, Signal.AI))));
+        store.define(new Microprogram(0b0010, "SUB", true, steps(
+            words(Signal.IO, Signal.MI), words(Signal.RO, Signal.BI), words(Signal.EO, Signal.SU, Signal.AI))));
+        store.define(new Microprogram(0b1110, "OUT", false, steps(words(Signal.AO, Signal.OI))));
+        store.define(new Microprogram(0b1111, "HLT", false, steps(words(Signal.HLT))));
+        return store;
+    }
+
+    private static Set<Signal> copyOf(Set<Signal> word) {
+        Set<Signal> set = EnumSet.noneOf(Signal.class);
+        set.addAll(word);
+        return set;
+    }
+
+    private static Set<Signal> words(Signal... signals) {
+        Set<Signal> set = EnumSet.noneOf(Signal.class);
+        Collections.addAll(set, signals);
+        return set;
+    }
+
+    @SafeVarargs
+    private static List<Set<Signal>> steps(Set<Signal>... words) {
+        List<Set<Signal>> list = new ArrayList<>();
+        for (Set<Signal> word : words) list.add(word);
+        return list;
+    }
+
+    // ==================== STATE ====================
+
+    private final ControlStore store;
+    private final int[] ram = new int[RAM_SIZE];
+    private int pc, mar, ir, a, b;
+    private Integer out;                 // null until the first OUT
+    private int tState;                  // 0..tStates-1
+    private boolean halted;
+    private long clocks;
+    private long instructions;
+    private final List<Integer> outputs = new ArrayList<>();
+    private final List<String> log = new ArrayList<>();
+    private final SignalTrace controlTrace = new SignalTrace();
+
+    public SAP1() {
+        this(standardControlStore());
+    }
+
+    public SAP1(ControlStore store) {
+        this.store = store;
+    }
+
+    // ==================== PROGRAMMING ====================
+
+    /** Load bytes into RAM starting at address 0. */
+    public SAP1 load(int... bytes) {
+        if (bytes.length > RAM_SIZE) throw new IllegalArgumentException("Program larger than RAM");
+        reset();
+        for (int i = 0; i < RAM_SIZE; i++) ram[i] = i < bytes.length ? bytes[i] & 0xFF : 0;
+        return this;
+    }
+
+    /**
+     * Assemble one line per RAM byte: "LDA 9", "OUT", or a plain number
+     * (data). Mnemonics come from the control store. ';' starts a comment.
+     */
+    public int[] assemble(String source) {
+        List<Integer> bytes = new ArrayList<>();
+        String[] lines = source.split("\n");
+        for (int n = 0; n < lines.length; n++) {
+            String line = lines[n].replaceAll(";.*", "").trim();
+            if (line.isEmpty()) continue;
+            String[] parts = line.split("\\s+");
+            try {
+                if (Character.isDigit(parts[0].charAt(0))) {
+                    bytes.add(number(parts[0]) & 0xFF);
+                    continue;
+                }
+                Microprogram p = store.lookup(parts[0]);
+                if (p == null) throw new IllegalArgumentException("unknown instruction " + parts[0]);
+                int operand = 0;
+                if (p.hasOperand) {
+                    if (parts.length != 2) throw new IllegalArgumentException(p.mnemonic + " needs an address");
+                    operand = number(parts[1]);
+                    if (operand < 0 || operand > 15) throw new IllegalArgumentException("address must be 0-15");
+                } else if (parts.length != 1) {
+                    throw new IllegalArgumentException(p.mnemonic + " takes no operand");
+                }
+                bytes.add(p.opcode << 4 | operand);
+            } catch (IllegalArgumentException e) {
+                throw new IllegalArgumentException("line " + (n + 1) + ": " + e.getMessage());
+            }
+        }
+        int[] result = new int[bytes.size()];
+        for (int i = 0; i < result.length; i++) result[i] = bytes.get(i);
+        return result;
+    }
+
+    private static int number(String s) {
+        if (s.startsWith("0x") || s.startsWith("0X")) return Integer.parseInt(s.substring(2), 16);
+        if (s.startsWith("0b") || s.startsWith("0B")) return Integer.parseInt(s.substring(2), 2);
+        if (s.endsWith("H") || s.endsWith("h")) return Integer.parseInt(s.substring(0, s.length() - 1), 16);
+        return Integer.parseInt(s);
+    }
+
+    public void reset() {
+        pc = mar = ir = a = b = 0;
+        out = null;
+        tState = 0;
+        halted = false;
+        clocks = 0;
+        instructions = 0;
+        outputs.clear();
+        log.clear();
+    }
+
+    // ==================== EXECUTION ====================
+
+    /** One clock cycle: apply the current T-state's control word. */
+    public void clock() {
+        if (halted) return;
+        if (tState == 0) instructions++;
+        Set<Signal> word = store.controlWord(tState, ir >> 4);
+        int bus = busValue(word);
+
+        log.add(String.format("T%d  %-12s %s  bus=%-4s  PC=%X MAR=%X IR=%02X A=%02X B=%02X",
+            tState + 1, signals(word), controlWordBits(word), bus < 0 ? "-" : String.format("%02X", bus),
+            pc, mar, ir, a, b));
+        for (Signal s : Signal.values()) controlTrace.record(s.name(), word.contains(s));
+
+        // All loads happen together at the clock edge
+        if (word.contains(Signal.HLT)) {
+            halted = true;
+        }
+        if (word.contains(Signal.MI)) mar = need(bus, Signal.MI) & 0x0F;
+        if (word.contains(Signal.RI)) ram[mar] = need(bus, Signal.RI);
+        if (word.contains(Signal.II)) ir = need(bus, Signal.II);
+        if (word.contains(Signal.AI)) a = need(bus, Signal.AI);
+        if (word.contains(Signal.BI)) b = need(bus, Signal.BI);
+        if (word.contains(Signal.OI)) {
+            out = need(bus, Signal.OI);
+            outputs.add(out);
+        }
+        if (word.contains(Signal.J)) pc = need(bus, Signal.J) & 0x0F;
+        else if (word.contains(Signal.CE)) pc = (pc + 1) & 0x0F;
+
+        clocks++;
+        tState = (tState + 1) % store.getTStates();
+    }
+
+    /** Value driven onto the W bus, or -1 if nothing drives it. */
+    private int busValue(Set<Signal> word) {
+        int value = -1;
+        Signal driver = null;
+        for (Signal s : word) {
+            if (!s.drivesBus()) continue;
+            if (driver != null) {
+                throw new IllegalStateException("Bus contention: " + driver + " and " + s + " both drive the bus");
+            }
+            driver = s;
+            switch (s) {
+                case CO: value = pc; break;
+                case RO: value = ram[mar]; break;
+                case IO: value = ir & 0x0F; break;
+                case AO: value = a; break;
+                case EO: value = alu(word.contains(Signal.SU)); break;
+                default: break;
+            }
+        }
+        return value;
+    }
+
+    private static int need(int bus, Signal load) {
+        if (bus < 0) throw new IllegalStateException(load + " loads from the bus, but nothing drives it");
+        return bus;
+    }
+
+    private int alu(boolean subtract) {
+        return (subtract ? a - b : a + b) & 0xFF;
+    }
+
+    /** Run one whole instruction (T1..T6). */
+    public void instruction() {
+        do {
+            clock();
+        } while (!halted && tState != 0);
+    }
+
+    /** Run until HLT (or the clock limit). Returns the number of clocks. */
+    public long run(long maxClocks) {
+        while (!halted && clocks < maxClocks) clock();
+        return clocks;
+    }
+
+    // ==================== INSPECTION ====================
+
+    public boolean isHalted() { return halted; }
+    public int getPC() { return pc; }
+    public int getA() { return a; }
+    public int getB() { return b; }
+    public int getIR() { return ir; }
+    public int getMAR() { return mar; }
+    public Integer getOut() { return out; }
+    public long getClocks() { return clocks; }
+    public long getInstructions() { return instructions; }
+    public int readRam(int address) { return ram[address & 0x0F]; }
+    public ControlStore getControlStore() { return store; }
+    public List<Integer> getOutputs() { return Collections.unmodifiableList(outputs); }
+
+    /** One line per executed T-state: active signals, control word, bus, registers. */
+    public List<String> getLog() { return Collections.unmodifiableList(log); }
+
+    /** Control signals per clock, ready for TimingDiagram. */
+    public SignalTrace getControlTrace() { return controlTrace; }
+
+    /** Disassemble RAM: address, byte, and instruction if it decodes as one. */
+    public String memoryDump() {
+        StringBuilder sb = new StringBuilder();
+        for (int i = 0; i < RAM_SIZE; i++) {
+            Microprogram p = store.get(ram[i] >> 4);
+            String text = p == null ? "" : p.hasOperand ? p.mnemonic + " " + (ram[i] & 0x0F) : p.mnemonic;
+            sb.append(String.format("  %X: %s  %02X  %3d  %s\n", i, binary(ram[i], 8), ram[i], ram[i], text)
+                .replaceAll("\\s+\n", "\n"));
+        }
+        return sb.toString();
+    }
+
+    static String signals(Set<Signal> word) {
+        if (word.isEmpty()) return "-";
+        StringBuilder sb = new StringBuilder();
+        for (Signal s : word) {
+            if (sb.length() > 0) sb.append(' ');
+            sb.append(s.name());
+        }
+        return sb.toString();
+    }
+
+    static String binary(int value, int bits) {
+        StringBuilder sb = new StringBuilder(Integer.toBinaryString(value));
+        while (sb.length() < bits) sb.insert(0, '0');
+        return sb.toString();
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    /** The classic first SAP-1 program: 16 + 20 + 24 - 4. */
+    public static final String EXAMPLE_PROGRAM =
+        "LDA 9    ; A = 16\n" +
+        "ADD 10   ; A = 36\n" +
+        "ADD 11   ; A = 60\n" +
+        "SUB 12   ; A = 56\n" +
+        "OUT\n" +
+        "HLT\n" +
+        "0\n0\n0\n" +
+        "16       ; address 9\n" +
+        "20\n" +
+        "24\n" +
+        "4\n";
+
+    public static String demonstrateSAP1() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("═══════════════════════════════════════════════════════════════\n");
+        sb.append("  SAP-1: FETCH-DECODE-EXECUTE, ONE CONTROL WORD AT A TIME\n");
+        sb.append("═══════════════════════════════════════════════════════════════\n\n");
+
+        SAP1 cpu = new SAP1();
+        sb.append("Microcode (control store):\n\n").append(cpu.store.table()).append("\n");
+
+        cpu.load(cpu.assemble(EXAMPLE_PROGRAM));
+        sb.append("Program in RAM:\n\n").append(cpu.memoryDump()).append("\n");
+
+        sb.append("Control word bits, in order: ");
+        for (Signal s : Signal.values()) sb.append(s.name()).append(' ');
+        sb.append("\n\n");
+
+        cpu.instruction();
+        cpu.instruction();
+        sb.append("First two instructions (LDA 9, ADD 10), clock by clock\n");
+        sb.append("(registers as they are BEFORE the clock edge of that T-state):\n\n");
+        for (String line : cpu.getLog()) sb.append("  ").append(line).append("\n");
+        sb.append("\n  T1-T3 are identical for every instruction (fetch);\n");
+        sb.append("  T4-T6 come from the opcode's microprogram.\n\n");
+
+        cpu.run(1000);
+        sb.append(String.format("Ran to HLT: OUT = %d after %d clocks (%d instructions; HLT stops\n",
+            cpu.getOut(), cpu.getClocks(), cpu.getInstructions()));
+        sb.append("the clock in its T4, the rest take all 6 T-states).\n");
+        return sb.toString();
+    }
+
+    // ==================== MAIN DEMO ====================
+
+    /**
+     * Usage: SAP1 [microcode.toml|json program.asm]
+     */
+    public static void main(String[] args) {
+        if (args.length == 2) {
+            SAP1 cpu = null;
+            try {
+                // Microcode's messages start with file:line already
+                cpu = new SAP1(Microcode.load(Paths.get(args[0])));
+                cpu.load(cpu.assemble(new String(Files.readAllBytes(Paths.get(args[1])), StandardCharsets.UTF_8)));
+            } catch (IOException e) {
+                String file = cpu == null ? args[0] : args[1];
+                System.err.println(file + ": " + (e instanceof NoSuchFileException ? "no such file" : e.getMessage()));
+                System.exit(2);
+            } catch (IllegalArgumentException e) {
+                System.err.println(cpu == null ? e.getMessage() : args[1] + ": " + e.getMessage());
+                System.exit(2);
+            }
+            System.out.println(cpu.getControlStore().table());
+            System.out.println(cpu.memoryDump());
+            try {
+                cpu.run(240);
+            } catch (IllegalStateException e) {
+                for (String line : cpu.getLog()) System.out.println(line);
+                System.err.println(args[1] + ": " + e.getMessage());
+                System.exit(1);
+            }
+            for (String line : cpu.getLog()) System.out.println(line);
+            System.out.println("\nOUT: " + cpu.getOutputs() + (cpu.isHalted() ? "" : "  (stopped after 240 clocks)"));
+            return;
+        }
+        System.out.println(demonstrateSAP1());
+        System.out.println(Microcode.demonstrateMicrocode());
+    }
+}
@@ -0,0 +1,272 @@
+package computerdesign.theory;
+
+import computerdesign.memory.Cache;
+import computerdesign.pipeline.LoopUnrolling;
+import computerdesign.processor.PipelineProcessor;
+import computerdesign.processor.ProcessorStats;
+import java.util.LinkedHashMap;
+import java.util.Map;
+
+/**
+ * EnergyModel - Energy, power and energy-delay product from the pipeline's
+ * event counts.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * ENERGY = Σ EVENTS × ENERGY PER EVENT
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Every ALU operation, register access and memory access switches
+ * transistors, and each switch costs a roughly fixed amount of energy.
+ * Count the events, multiply, add the static part (clock tree and leakage,
+ * paid every cycle whether work is done or not):
+ *
+ *   E = n_alu·E_alu + n_rr·E_rr + n_rw·E_rw + n_cache·E_cache + n_dram·E_dram
+ *       + cycles·E_static
+ *
+ *   Typical costs at 45 nm (Horowitz, ISSCC 2014), the defaults here:
+ *     32-bit add              ~0.1 pJ    (0.5 with pipeline latches and control)
+ *     register file access    ~1 pJ
+ *     8 KB SRAM (L1) access   ~10 pJ
+ *     DRAM access             ~640 pJ    ← 1000x an add: data movement dominates
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * PERFORMANCE VS ENERGY
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   Power  P = E / t                               (what the cooler handles)
+ *   EDP      = E × t                               (energy AND speed; lower is better)
+ *
+ *   Dynamic energy per event ∝ C·V². Lowering the voltage saves energy
+ *   quadratically but forces a lower clock, so the run takes longer and
+ *   pays more static energy - EDP tells whether the trade was worth it.
+ *
+ * Instruction fetch is counted as one cache access per fetched instruction
+ * (an I-cache that always hits), including the wrong-path instructions a
+ * taken branch flushes. DRAM is one access per uncached load or store, or
+ * per block fill when the pipeline has a data cache.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: EnergyModel [--alu pJ] [--reg-read pJ] [--reg-write pJ] [--cache pJ]
+ *                    [--dram pJ] [--static pJ] [--clock 1GHz]
+ *
+ * Runs the worked example with the given costs.
+ *
+ * Covers learning goals: T1.3 (performance vs energy)
+ */
+public final class EnergyModel {
+
+    /** Instructions fetched and squashed behind each taken branch. */
+    static final int WRONG_PATH = 3;
+
+    private EnergyModel() {}
+
+    /** Energy per event in picojoules. */
+    public static final class Costs {
+        public final double alu;
+        public final double registerRead;
+        public final double registerWrite;
+        public final double cacheAccess;
+        public final double dramAccess;
+        public final double staticPerCycle;
+
+        public Costs(double alu, double registerRead, double registerWrite,
+                     double cacheAccess, double dramAccess, double staticPerCycle) {
+            this.alu = alu;
+            this.registerRead = registerRead;
+            this.registerWrite = registerWrite;
+            this.cacheAccess = cacheAccess;
+            this.dramAccess = dramAccess;
+            this.staticPerCycle = staticPerCycle;
+        }
+
+        /**
+         * The same design at `voltage` times the nominal supply and `clock`
+         * times the nominal frequency: dynamic energy scales with V², and
+         * leakage power with V, so static energy per cycle with V / f.
+         */
+        public Costs scaled(double voltage, double clock) {
+            double d = voltage * voltage;
+            return new Costs(alu * d, registerRead * d, registerWrite * d,
+                cacheAccess * d, dramAccess * d, staticPerCycle * voltage / clock);
+        }
+    }
+
+    public static final Costs DEFAULT = new Costs(0.5, 1.0, 1.5, 10, 640, 10);
+
+    /** Event counts, energy per category and the totals for one run. */
+    public static final class Result {
+        public final String name;
+        public final Map<String, Long> events = new LinkedHashMap<>();
+        public final Map<String, Double> picojoules = new LinkedHashMap<>();
+        public final long cycles;
+        public final double clockHz;
+
+        Result(String name, long cycles, double clockHz) {
+            this.name = name;
+            this.cycles = cycles;
+            this.clockHz = clockHz;
+        }
+
+        void add(String category, long count, double perEvent) {
+            events.put(category, count);
+            picojoules.put(category, count * perEvent);
+        }
+
+        public double totalPicojoules() {
+            double sum = 0;
+            for (double e : picojoules.values()) sum += e;
+            return sum;
+        }
+
+        public double seconds() {
+            return cycles / clockHz;
+        }
+
+        public double watts() {
+            return totalPicojoules() * 1e-12 / seconds();
+        }
+
+        /** Energy-delay product in joule-seconds. */
+        public double edp() {
+            return totalPicojoules() * 1e-12 * seconds();
+        }
+    }
+
+    // ==================== THE MODEL ====================
+
+    /**
+     * Price a finished run. `dataCache` is the cache the run used, or null
+     * if every load and store went to memory.
+     */
+    public static Result estimate(String name, ProcessorStats stats, Cache dataCache, Costs c, double clockHz) {
+        Map<String, Integer> mix = stats.getInstructionMix();
+        long system = mix.getOrDefault("system", 0);
+        long fetches = stats.getInstructionCount() + (long) WRONG_PATH * stats.getBranchMispredictions();
+        long dataAccesses = stats.getMemoryAccesses();
+
+        Result r = new Result(name, stats.getCycleCount(), clockHz);
+        r.add("ALU ops", stats.getInstructionCount() - system, c.alu);
+        r.add("register reads", stats.getRegisterReads(), c.registerRead);
+        r.add("register writes", stats.getRegisterWrites(), c.registerWrite);
+        r.add("cache accesses", fetches + (dataCache != null ? dataAccesses : 0), c.cacheAccess);
+        r.add("DRAM accesses", dataCache != null ? stats.getCacheMisses() : dataAccesses, c.dramAccess);
+        r.add("static (cycles)", stats.getCycleCount(), c.staticPerCycle);
+        return r;
+    }
+
+    /** Run one of the LoopUnrolling loops, optionally with a 1 KiB 4-way data cache. */
+    public static Result runLoop(String name, int[] program, boolean dataCache, Costs costs, double clockHz) {
+        PipelineProcessor cpu = LoopUnrolling.prepare(program);
+        Cache cache = null;
+        if (dataCache) {
+            cache = new Cache("D$", 1024, 16, 1, cpu.getMemory(), Cache.Associativity.FOUR_WAY, Cache.ReplacementPolicy.LRU);
+            cpu.setDataCache(cache);
+        }
+        cpu.run(100_000);
+        if (!cpu.isHalted()) throw new IllegalStateException(name + " did not halt");
+        return estimate(name, cpu.getStats(), cache, costs, clockHz);
+    }
+
+    // ==================== REPORTING ====================
+
+    private static String energy(double pJ) {
+        if (pJ >= 1e6) return String.format("%.2f µJ", pJ / 1e6);
+        if (pJ >= 1e3) return String.format("%.2f nJ", pJ / 1e3);
+        return String.format("%.1f pJ", pJ);
+    }
+
+    public static String breakdown(Result r) {
+        StringBuilder sb = new StringBuilder();
+        double total = r.totalPicojoules();
+        sb.append(String.format("   %-17s %8s %10s %11s %7s\n", "event", "count", "pJ each", "energy", "share"));
+        for (Map.Entry<String, Long> e : r.events.entrySet()) {
+            double pJ = r.picojoules.get(e.getKey());
+            sb.append(String.format("   %-17s %8d %10.2f %11s %6.1f%%\n", e.getKey(), e.getValue(),
+                e.getValue() == 0 ? 0 : pJ / e.getValue(), energy(pJ), 100 * pJ / total));
+        }
+        sb.append(String.format("   %-17s %8s %10s %11s\n", "total", "", "", energy(total)));
+        return sb.toString();
+    }
+
+    public static String compare(Result... results) {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("   %-24s %10s %10s %9s %12s %8s\n", "run", "energy", "time", "power", "EDP (J·s)", "EDP"));
+        for (Result r : results) {
+            sb.append(String.format("   %-24s %10s %8.2f µs %7.1f mW %12.3e %7.2fx\n", r.name, energy(r.totalPicojoules()),
+                r.seconds() * 1e6, r.watts() * 1e3, r.edp(), r.edp() / results[0].edp()));
+        }
+        return sb.toString();
+    }
+
+    public static String demonstrateEnergy(Costs costs, double clockHz) {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Energy Model for the 5-Stage Pipeline ===\n\n");
+        sb.append(String.format("Costs (pJ): ALU %.2f, register read %.2f, write %.2f, cache %.1f, DRAM %.0f,"
+            + " static %.1f per cycle; clock %.0f MHz\n\n", costs.alu, costs.registerRead, costs.registerWrite,
+            costs.cacheAccess, costs.dramAccess, costs.staticPerCycle, clockHz / 1e6));
+
+        Result rolled = runLoop("rolled", LoopUnrolling.rolled(), true, costs, clockHz);
+        sb.append("1) The rolled array-sum loop (LoopUnrolling) with a 1 KiB data cache:\n");
+        sb.append(breakdown(rolled)).append('\n');
+
+        sb.append("2) The three versions of the loop:\n");
+        Result scheduled = runLoop("unrolled + scheduled", LoopUnrolling.scheduled(), true, costs, clockHz);
+        sb.append(compare(rolled, runLoop("unrolled 4x", LoopUnrolling.unrolled(), true, costs, clockHz), scheduled));
+        sb.append("   Fewer instructions and fewer cycles: less energy AND less time, so the\n");
+        sb.append("   EDP falls faster than either. Flushed wrong-path fetches cost energy too.\n\n");
+
+        sb.append("3) The scheduled loop without the data cache (every load goes to DRAM):\n");
+        Result uncached = runLoop("no data cache", LoopUnrolling.scheduled(), false, costs, clockHz);
+        sb.append(compare(scheduled, uncached));
+        sb.append(String.format("   DRAM is %.0f%% of the uncached run's energy: moving data, not computing,\n",
+            100 * uncached.picojoules.get("DRAM accesses") / uncached.totalPicojoules()));
+        sb.append("   is what costs. (The pipeline charges no extra cycles for DRAM here; a real\n");
+        sb.append("   one would stall, making the time and EDP worse still.)\n\n");
+
+        sb.append("4) Voltage and frequency scaling of the scheduled loop:\n");
+        Result low = estimateScaled(scheduled, costs, 0.8, 0.7, clockHz);
+        Result high = estimateScaled(scheduled, costs, 1.1, 1.2, clockHz);
+        sb.append(compare(scheduled, low, high));
+        sb.append("   0.8 V at 70% clock saves dynamic energy (0.8² = 0.64) but runs longer;\n");
+        sb.append("   1.1 V at 120% clock finishes sooner for 21% more energy per event.\n");
+        return sb.toString();
+    }
+
+    /** A finished run re-priced at another voltage and clock (same events). */
+    static Result estimateScaled(Result base, Costs costs, double voltage, double clock, double clockHz) {
+        Costs c = costs.scaled(voltage, clock);
+        Result r = new Result(String.format("%.1f V, %.0f MHz", voltage, clockHz * clock / 1e6), base.cycles, clockHz * clock);
+        double[] perEvent = {c.alu, c.registerRead, c.registerWrite, c.cacheAccess, c.dramAccess, c.staticPerCycle};
+        int i = 0;
+        for (Map.Entry<String, Long> e : base.events.entrySet()) r.add(e.getKey(), e.getValue(), perEvent[i++]);
+        return r;
+    }
+
+    // ==================== COMMAND LINE ====================
+
+    public static void main(String[] args) {
+        double[] v = {DEFAULT.alu, DEFAULT.registerRead, DEFAULT.registerWrite,
+                      DEFAULT.cacheAccess, DEFAULT.dramAccess, DEFAULT.staticPerCycle};
+        double clock = 1e9;
+        String[] options = {"--alu", "--reg-read", "--reg-write", "--cache", "--dram", "--static"};
+        try {
+            for (int i = 0; i < args.length; i++) {
+                if (args[i].equals("--clock")) {
+                    clock = PerfCalc.parseClock(args[++i]);
+                    continue;
+                }
+                int k = java.util.Arrays.asList(options).indexOf(args[i]);
+                if (k < 0) throw new IllegalArgumentException("Unknown option: " + args[i]);
+                v[k] = Double.parseDouble(args[++i]);
+            }
+            System.out.println(demonstrateEnergy(new Costs(v[0], v[1], v[2], v[3], v[4], v[5]), clock));
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.err.println("Missing value after " + args[args.length - 1]);
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
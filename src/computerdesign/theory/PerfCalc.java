@@ -0,0 +1,356 @@
+package computerdesign.theory;
+
+import computerdesign.instruction.Instruction;
+import computerdesign.processor.ProcessorStats;
+import computerdesign.processor.SingleCycleProcessor;
+import java.io.BufferedReader;
+import java.io.IOException;
+import java.io.InputStreamReader;
+import java.io.Reader;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Paths;
+import java.util.ArrayList;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Locale;
+import java.util.Map;
+import java.util.regex.Matcher;
+import java.util.regex.Pattern;
+
+/**
+ * PerfCalc - The iron law with an instruction mix, and the "which design is
+ * faster?" exam question.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * CPI FROM A MIX
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Each instruction class has its own CPI; the program's CPI is the average
+ * weighted by how often each class occurs:
+ *
+ *   CPI = Σ fraction_i × CPI_i
+ *
+ *   CPU time = Instructions × CPI / Clock frequency
+ *
+ *   class     fraction   CPI    contributes
+ *   alu         50%       1       0.50
+ *   load        20%       5       1.00      ← 20% of the instructions,
+ *   store       10%       3       0.30        40% of the cycles
+ *   branch      20%       3       0.60
+ *                               ──────
+ *                         CPI =   2.40
+ *
+ * Comparing designs: only the TIME counts. A design with a faster clock but
+ * a worse CPI (or more instructions) can lose:
+ *
+ *   speedup = time_A / time_B        "B is 1.25x faster than A"
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: PerfCalc                                     worked example
+ *        PerfCalc [--count N] (--mix alu=0.5,load=0.2,... | --mix-from file|-)
+ *                 --design "name clock cpi-list [count=N]" [--design ...]
+ *
+ *   PerfCalc --count 1e9 --mix alu=50%,load=20%,store=10%,branch=20% \
+ *            --design "A 2GHz alu=1,load=5,store=3,branch=3" \
+ *            --design "B 3GHz alu=1,load=8,store=4,branch=4"
+ *
+ *   java computerdesign.processor.MachineSnapshot load snap.bin --stats \
+ *     | java computerdesign.theory.PerfCalc --mix-from - --design "A 1GHz alu=1,load=5"
+ *
+ * --mix-from reads the "Instruction mix:" block the simulator prints with
+ * --stats and takes the instruction count from it unless --count is given.
+ *
+ * Covers learning goals: T1.3 (Iron Law, comparing designs)
+ */
+public final class PerfCalc {
+
+    /** One design alternative: a clock and a CPI per instruction class. */
+    public static final class Design {
+        public final String name;
+        public final double clockHz;
+        public final Map<String, Double> cpi;
+        public final long instructions;             // 0: use the shared count
+
+        public Design(String name, double clockHz, Map<String, Double> cpi, long instructions) {
+            if (clockHz <= 0) throw new IllegalArgumentException(name + ": clock must be positive");
+            this.name = name;
+            this.clockHz = clockHz;
+            this.cpi = new LinkedHashMap<>(cpi);
+            this.instructions = instructions;
+        }
+
+        public Design(String name, double clockHz, Map<String, Double> cpi) {
+            this(name, clockHz, cpi, 0);
+        }
+    }
+
+    /** A design evaluated on a mix. */
+    public static final class Result {
+        public final Design design;
+        public final long instructions;
+        public final double cpi;
+        public final double cycles;
+        public final double seconds;
+        public final Map<String, Double> cpiContribution;   // fraction_i × CPI_i
+
+        Result(Design design, long instructions, Map<String, Double> contribution) {
+            this.design = design;
+            this.instructions = instructions;
+            this.cpiContribution = contribution;
+            double sum = 0;
+            for (double c : contribution.values()) sum += c;
+            this.cpi = sum;
+            this.cycles = instructions * sum;
+            this.seconds = cycles / design.clockHz;
+        }
+
+        public double mips() {
+            return instructions / seconds / 1e6;
+        }
+    }
+
+    private PerfCalc() {}
+
+    // ==================== THE IRON LAW ====================
+
+    /** Scale counts or percentages to fractions summing to 1. */
+    public static Map<String, Double> normalize(Map<String, Double> mix) {
+        double total = 0;
+        for (double v : mix.values()) {
+            if (v < 0) throw new IllegalArgumentException("Negative share in mix: " + mix);
+            total += v;
+        }
+        if (total == 0) throw new IllegalArgumentException("Empty instruction mix");
+        Map<String, Double> out = new LinkedHashMap<>();
+        for (Map.Entry<String, Double> e : mix.entrySet()) out.put(e.getKey(), e.getValue() / total);
+        return out;
+    }
+
+    public static Result evaluate(Design d, Map<String, Double> mix, long instructions) {
+        long count = d.instructions > 0 ? d.instructions : instructions;
+        if (count <= 0) throw new IllegalArgumentException(d.name + ": no instruction count (--count N or count=N in the design)");
+        Map<String, Double> contribution = new LinkedHashMap<>();
+        for (Map.Entry<String, Double> e : normalize(mix).entrySet()) {
+            Double classCpi = d.cpi.get(e.getKey());
+            if (classCpi == null) {
+                throw new IllegalArgumentException(String.format(
+                    "%s: no CPI for class '%s' (it is %.1f%% of the mix)", d.name, e.getKey(), 100 * e.getValue()));
+            }
+            contribution.put(e.getKey(), e.getValue() * classCpi);
+        }
+        return new Result(d, count, contribution);
+    }
+
+    public static List<Result> evaluateAll(List<Design> designs, Map<String, Double> mix, long instructions) {
+        List<Result> results = new ArrayList<>();
+        for (Design d : designs) results.add(evaluate(d, mix, instructions));
+        return results;
+    }
+
+    // ==================== REPORTING ====================
+
+    private static String time(double seconds) {
+        if (seconds >= 1) return String.format("%.3f s", seconds);
+        if (seconds >= 1e-3) return String.format("%.3f ms", seconds * 1e3);
+        if (seconds >= 1e-6) return String.format("%.3f µs", seconds * 1e6);
+        return String.format("%.1f ns", seconds * 1e9);
+    }
+
+    private static String hz(double hz) {
+        return hz >= 1e9 ? String.format("%.2f GHz", hz / 1e9) : String.format("%.0f MHz", hz / 1e6);
+    }
+
+    public static String report(List<Result> results, Map<String, Double> mix) {
+        Map<String, Double> fractions = normalize(mix);
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("   %-8s %8s", "class", "mix"));
+        for (Result r : results) sb.append(String.format("   %12s", r.design.name + " CPI"));
+        sb.append('\n');
+        for (Map.Entry<String, Double> e : fractions.entrySet()) {
+            sb.append(String.format("   %-8s %7.1f%%", e.getKey(), 100 * e.getValue()));
+            for (Result r : results) {
+                sb.append(String.format("   %4.1f → %5.2f", r.design.cpi.get(e.getKey()), r.cpiContribution.get(e.getKey())));
+            }
+            sb.append('\n');
+        }
+        sb.append('\n');
+        for (Result r : results) {
+            sb.append(String.format("   %s: %,d instr × CPI %.2f / %s = %s  (%.0f MIPS)\n",
+                r.design.name, r.instructions, r.cpi, hz(r.design.clockHz), time(r.seconds), r.mips()));
+        }
+        if (results.size() > 1) {
+            Result fastest = results.get(0);
+            for (Result r : results) if (r.seconds < fastest.seconds) fastest = r;
+            sb.append('\n');
+            for (Result r : results) {
+                if (r == fastest) continue;
+                sb.append(String.format("   %s is %.2fx faster than %s\n",
+                    fastest.design.name, r.seconds / fastest.seconds, r.design.name));
+            }
+        }
+        return sb.toString();
+    }
+
+    // ==================== PARSING ====================
+
+    /** "2GHz", "800MHz", "2.5e9". */
+    static double parseClock(String text) {
+        String t = text.trim().toLowerCase(Locale.ROOT);
+        double scale = 1;
+        if (t.endsWith("ghz")) scale = 1e9;
+        else if (t.endsWith("mhz")) scale = 1e6;
+        else if (t.endsWith("khz")) scale = 1e3;
+        t = t.replaceAll("[a-z]+$", "");
+        return Double.parseDouble(t) * scale;
+    }
+
+    /** "alu=1,load=5" or "alu=50%,load=20%"; percentages are fine for a mix. */
+    static Map<String, Double> parseList(String text) {
+        Map<String, Double> out = new LinkedHashMap<>();
+        for (String item : text.split(",")) {
+            String[] kv = item.trim().split("=");
+            if (kv.length != 2) throw new IllegalArgumentException("Expected class=value, got '" + item + "'");
+            String v = kv[1].trim();
+            out.put(kv[0].trim(), v.endsWith("%") ? Double.parseDouble(v.substring(0, v.length() - 1)) / 100
+                                                  : Double.parseDouble(v));
+        }
+        return out;
+    }
+
+    /** "name clock cpi-list [count=N]". */
+    static Design parseDesign(String text) {
+        String[] parts = text.trim().split("\\s+");
+        if (parts.length < 3) throw new IllegalArgumentException("Design needs 'name clock cpi-list', got '" + text + "'");
+        long count = 0;
+        for (int i = 3; i < parts.length; i++) {
+            if (!parts[i].startsWith("count=")) throw new IllegalArgumentException("Unknown design option " + parts[i]);
+            count = (long) Double.parseDouble(parts[i].substring(6));
+        }
+        return new Design(parts[0], parseClock(parts[1]), parseList(parts[2]), count);
+    }
+
+    private static final Pattern MIX_LINE = Pattern.compile("^\\s+(\\w+)\\s+(\\d+)\\b.*");
+
+    /** The "Instruction mix:" block of --stats output, as class → count. */
+    public static Map<String, Double> readMix(Reader in) throws IOException {
+        BufferedReader reader = new BufferedReader(in);
+        Map<String, Double> mix = new LinkedHashMap<>();
+        boolean inside = false;
+        for (String line = reader.readLine(); line != null; line = reader.readLine()) {
+            if (line.trim().equals("Instruction mix:")) {
+                inside = true;
+                mix.clear();                      // the last block wins
+                continue;
+            }
+            Matcher m = MIX_LINE.matcher(line);
+            if (inside && m.matches()) {
+                mix.put(m.group(1), Double.parseDouble(m.group(2)));
+            } else {
+                inside = false;
+            }
+        }
+        if (mix.isEmpty()) throw new IllegalArgumentException("No 'Instruction mix:' block found (run with --stats)");
+        return mix;
+    }
+
+    // ==================== DEMONSTRATION ====================
+
+    private static Map<String, Double> cpi(double alu, double load, double store, double branch) {
+        Map<String, Double> m = new LinkedHashMap<>();
+        m.put("alu", alu);
+        m.put("load", load);
+        m.put("store", store);
+        m.put("branch", branch);
+        return m;
+    }
+
+    /** Sum an array in a loop on the single-cycle simulator; the mix it retired. */
+    static ProcessorStats simulatedMix() {
+        int[] program = {
+            Instruction.addi(6, 0, 0x400).getRaw(),     // t1 = &array
+            Instruction.addi(5, 0, 100).getRaw(),       // t0 = 100 elements
+            Instruction.lw(7, 6, 0).getRaw(),           // loop: t2 = *t1
+            Instruction.add(8, 8, 7).getRaw(),          //   s0 += t2
+            Instruction.sw(8, 6, 0).getRaw(),           //   *t1 = running sum
+            Instruction.addi(6, 6, 4).getRaw(),
+            Instruction.addi(5, 5, -1).getRaw(),
+            Instruction.bne(5, 0, -20).getRaw(),
+            0x00100073
+        };
+        SingleCycleProcessor cpu = new SingleCycleProcessor();
+        cpu.getMemory().loadProgram(program, 0);
+        cpu.run(10_000);
+        return cpu.getStats();
+    }
+
+    public static String demonstratePerfCalc() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== CPU Performance Equation with an Instruction Mix ===\n\n");
+        sb.append("1) Exam question: 10^9 instructions, same ISA and compiler.\n");
+        sb.append("   A: 2 GHz.  B: 3 GHz, but the deeper pipeline costs more cycles per class.\n\n");
+        Map<String, Double> mix = parseList("alu=50%,load=20%,store=10%,branch=20%");
+        List<Design> designs = new ArrayList<>();
+        designs.add(new Design("A", 2e9, cpi(1, 5, 3, 3)));
+        designs.add(new Design("B", 3e9, cpi(1, 8, 4, 4)));
+        sb.append(report(evaluateAll(designs, mix, 1_000_000_000L), mix));
+        sb.append("   The 50% faster clock buys only 9%: every load got 60% slower.\n\n");
+
+        sb.append("2) Same designs, instruction mix measured on the simulator\n");
+        sb.append("   (a loop that loads, adds and stores a running sum):\n\n");
+        ProcessorStats stats = simulatedMix();
+        for (String line : stats.mixReport().split("\n")) sb.append("   ").append(line).append('\n');
+        Map<String, Double> measured = new LinkedHashMap<>();
+        for (Map.Entry<String, Integer> e : stats.getInstructionMix().entrySet()) {
+            measured.put(e.getKey(), e.getValue().doubleValue());
+        }
+        sb.append('\n').append(report(evaluateAll(designs, measured, stats.getInstructionCount()), measured));
+        sb.append("   The verdict depends on the mix: measure it rather than assume it.\n");
+        return sb.toString();
+    }
+
+    // ==================== COMMAND LINE ====================
+
+    public static void main(String[] args) throws IOException {
+        if (args.length == 0) {
+            System.out.println(demonstratePerfCalc());
+            return;
+        }
+        long count = 0;
+        Map<String, Double> mix = null;
+        List<Design> designs = new ArrayList<>();
+        try {
+            for (int i = 0; i < args.length; i++) {
+                switch (args[i]) {
+                    case "--count": count = (long) Double.parseDouble(args[++i]); break;
+                    case "--mix": mix = parseList(args[++i]); break;
+                    case "--mix-from": {
+                        String file = args[++i];
+                        Reader in = file.equals("-")
+                            ? new InputStreamReader(System.in, StandardCharsets.UTF_8)
+                            : Files.newBufferedReader(Paths.get(file), StandardCharsets.UTF_8);
+                        mix = readMix(in);
+                        if (count == 0) {
+                            double total = 0;
+                            for (double v : mix.values()) total += v;
+                            count = (long) total;
+                        }
+                        break;
+                    }
+                    case "--design": designs.add(parseDesign(args[++i])); break;
+                    default: throw new IllegalArgumentException("Unknown option: " + args[i]);
+                }
+            }
+            if (mix == null) throw new IllegalArgumentException("Give a mix with --mix or --mix-from");
+            if (designs.isEmpty()) throw new IllegalArgumentException("Give at least one --design");
+            System.out.print(report(evaluateAll(designs, mix, count), mix));
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.err.println("Missing value after " + args[args.length - 1]);
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
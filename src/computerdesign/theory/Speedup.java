@@ -0,0 +1,275 @@
+package computerdesign.theory;
+
+import java.util.Arrays;
+
+/**
+ * Speedup - Amdahl's and Gustafson's laws, forwards and backwards.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * TWO QUESTIONS, TWO LAWS
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * With serial fraction s (measured on ONE processor) and N processors:
+ *
+ *   AMDAHL (fixed problem size, "how much faster?"):
+ *
+ *     S(N) = 1 / (s + (1 - s) / N)        →  1/s as N → ∞
+ *
+ *   GUSTAFSON (fixed time, bigger problem, "how much more work?"):
+ *
+ *     S(N) = s + (1 - s) · N  =  N - s · (N - 1)
+ *
+ *   Here s is measured on the PARALLEL machine: the serial part stays the
+ *   same while the parallel part grows with N.
+ *
+ *   s = 5%:        N = 16     N = 256    N = ∞
+ *     Amdahl       9.1x       18.6x      20x
+ *     Gustafson    15.3x      243x       (keeps growing)
+ *
+ * Both are right: they answer different questions. Amdahl is why a faster
+ * single core still matters; Gustafson is why supercomputers solve bigger
+ * problems instead of the same problem faster.
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ * INVERSE PROBLEMS (the exam favourites)
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ *   "What fraction must be parallel to reach 10x on 16 cores?"
+ *     1/T = s + (1 - s)/N   →   s = (1/T - 1/N) / (1 - 1/N)
+ *
+ *   "How many cores to reach 10x with 5% serial?"
+ *     N = (1 - s) / (1/T - s)      impossible when T ≥ 1/s
+ *
+ * ═══════════════════════════════════════════════════════════════════════════════
+ *
+ * Usage: Speedup                                 worked examples and a plot
+ *        Speedup amdahl <serial> <cores>...       e.g. amdahl 5% 4 16 64
+ *        Speedup gustafson <serial> <cores>...
+ *        Speedup fraction <target> <cores>        parallel fraction needed
+ *        Speedup cores <target> <serial>          cores needed
+ *        Speedup plot <serial>                    speedup vs N, log-log
+ *
+ * Fractions are given as 0.05 or 5%.
+ *
+ * Covers learning goals: T1.3 (performance), T7.1 (parallel speedup)
+ */
+public final class Speedup {
+
+    private Speedup() {}
+
+    private static void checkFraction(double serial) {
+        if (serial < 0 || serial > 1) {
+            throw new IllegalArgumentException("Serial fraction must be between 0 and 1, got " + serial);
+        }
+    }
+
+    private static void checkCores(double cores) {
+        if (cores < 1) throw new IllegalArgumentException("Need at least 1 core, got " + cores);
+    }
+
+    // ==================== FORWARD ====================
+
+    public static double amdahl(double serial, double cores) {
+        checkFraction(serial);
+        checkCores(cores);
+        return 1.0 / (serial + (1 - serial) / cores);
+    }
+
+    /** The limit for N → ∞; infinite for a fully parallel program. */
+    public static double amdahlLimit(double serial) {
+        checkFraction(serial);
+        return 1.0 / serial;
+    }
+
+    public static double gustafson(double serial, double cores) {
+        checkFraction(serial);
+        checkCores(cores);
+        return serial + (1 - serial) * cores;
+    }
+
+    /** Parallel efficiency: speedup per core. */
+    public static double efficiency(double speedup, double cores) {
+        return speedup / cores;
+    }
+
+    // ==================== INVERSE ====================
+
+    /**
+     * Largest serial fraction that still gives `target` speedup on `cores`
+     * under Amdahl. Throws if the target exceeds the core count.
+     */
+    public static double amdahlSerialFor(double target, double cores) {
+        checkCores(cores);
+        if (target > cores) {
+            throw new IllegalArgumentException(String.format(
+                "%sx on %s cores is impossible: speedup cannot exceed the core count", num(target), num(cores)));
+        }
+        if (cores == 1) return 1;
+        return (1 / target - 1 / cores) / (1 - 1 / cores);
+    }
+
+    /** Cores needed for `target` speedup under Amdahl, or infinity if unreachable. */
+    public static double amdahlCoresFor(double target, double serial) {
+        checkFraction(serial);
+        double denominator = 1 / target - serial;
+        return denominator <= 0 ? Double.POSITIVE_INFINITY : (1 - serial) / denominator;
+    }
+
+    /** Largest serial fraction for `target` scaled speedup on `cores`. */
+    public static double gustafsonSerialFor(double target, double cores) {
+        checkCores(cores);
+        if (target > cores) {
+            throw new IllegalArgumentException(String.format(
+                "%sx on %s cores is impossible: speedup cannot exceed the core count", num(target), num(cores)));
+        }
+        if (cores == 1) return 1;
+        return (cores - target) / (cores - 1);
+    }
+
+    /** Cores needed for `target` scaled speedup under Gustafson. */
+    public static double gustafsonCoresFor(double target, double serial) {
+        checkFraction(serial);
+        if (serial == 1) return target <= 1 ? 1 : Double.POSITIVE_INFINITY;
+        return Math.max(1, (target - serial) / (1 - serial));
+    }
+
+    // ==================== REPORTING ====================
+
+    public static String table(double serial, int... cores) {
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("Serial fraction %.1f%%:\n", 100 * serial));
+        sb.append("   cores     Amdahl   efficiency   Gustafson   efficiency\n");
+        for (int n : cores) {
+            double a = amdahl(serial, n);
+            double g = gustafson(serial, n);
+            sb.append(String.format("   %5d   %7.2fx   %9.0f%%   %8.2fx   %9.0f%%\n",
+                n, a, 100 * efficiency(a, n), g, 100 * efficiency(g, n)));
+        }
+        sb.append(String.format("   limit   %7sx\n", serial == 0 ? "∞" : String.format("%.2f", amdahlLimit(serial))));
+        return sb.toString();
+    }
+
+    /**
+     * Speedup against N on log-log axes, two points per octave from 1 to
+     * 1024: Gustafson ('o'), which stays close to the ideal N, and Amdahl ('*').
+     */
+    public static String plot(double serial) {
+        int steps = 21;                                 // N = 2^(c/2), c = 0..20
+        char[][] grid = new char[steps][steps];
+        for (char[] row : grid) Arrays.fill(row, ' ');
+        for (int c = 0; c < steps; c++) {
+            double n = Math.pow(2, c / 2.0);
+            mark(grid, c, gustafson(serial, n), 'o');
+            mark(grid, c, amdahl(serial, n), '*');
+        }
+        StringBuilder sb = new StringBuilder();
+        sb.append(String.format("Speedup vs cores, serial fraction %.1f%%   (* Amdahl, o Gustafson)\n\n",
+            100 * serial));
+        for (int r = steps - 1; r >= 0; r--) {
+            String label = r % 2 == 0 ? String.format("%5d", 1 << (r / 2)) : "";
+            sb.append(String.format("%5s │", label));
+            StringBuilder line = new StringBuilder();
+            for (int c = 0; c < steps; c++) line.append(' ').append(grid[r][c]).append(' ');
+            sb.append(line.toString().replaceAll("\\s+$", "")).append('\n');
+        }
+        sb.append("      └");
+        for (int c = 0; c < steps; c++) sb.append("───");
+        sb.append("\n       ");
+        StringBuilder axis = new StringBuilder();
+        for (int c = 0; c < steps; c += 4) axis.append(String.format("%-12d", 1 << (c / 2)));
+        sb.append(axis.toString().trim()).append('\n').append(String.format("%40s\n", "cores"));
+        return sb.toString();
+    }
+
+    private static void mark(char[][] grid, int column, double value, char symbol) {
+        int row = (int) Math.round(2 * Math.log(value) / Math.log(2));
+        if (row >= 0 && row < grid.length) grid[row][column] = symbol;
+    }
+
+    /** 10, 2.5 or 0.333 rather than 10.00, 2.500 or 0.3333333. */
+    private static String num(double v) {
+        return String.format("%.3f", v).replaceAll("\\.?0+$", "");
+    }
+
+    private static String cores(double n) {
+        return Double.isInfinite(n) ? "impossible" : String.format("%d cores", (long) Math.ceil(n - 1e-9));
+    }
+
+    public static String demonstrateSpeedup() {
+        StringBuilder sb = new StringBuilder();
+        sb.append("=== Amdahl's and Gustafson's Laws ===\n\n");
+        sb.append("1) Forward: speedup for a given serial fraction\n\n");
+        sb.append(table(0.05, 1, 2, 4, 8, 16, 64, 256)).append('\n');
+
+        sb.append("2) Inverse: what fraction must be parallel?\n");
+        for (int n : new int[] {16, 64}) {
+            double s = amdahlSerialFor(10, n);
+            sb.append(String.format("   10x on %2d cores needs ≥ %.2f%% parallel (serial ≤ %.2f%%)\n",
+                n, 100 * (1 - s), 100 * s));
+        }
+        sb.append("\n3) Inverse: how many cores?\n");
+        for (double s : new double[] {0.01, 0.05, 0.10}) {
+            sb.append(String.format("   10x with %4.1f%% serial: Amdahl %-12s Gustafson %s\n",
+                100 * s, cores(amdahlCoresFor(10, s)), cores(gustafsonCoresFor(10, s))));
+        }
+        sb.append("   (10% serial caps Amdahl at exactly 10x: only reached with infinitely many cores)\n\n");
+
+        sb.append("4) ").append(plot(0.05));
+        return sb.toString();
+    }
+
+    // ==================== COMMAND LINE ====================
+
+    /** 0.05 or 5%. */
+    static double fraction(String text) {
+        double v = text.endsWith("%")
+            ? Double.parseDouble(text.substring(0, text.length() - 1)) / 100
+            : Double.parseDouble(text);
+        checkFraction(v);
+        return v;
+    }
+
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println(demonstrateSpeedup());
+            return;
+        }
+        try {
+            switch (args[0]) {
+                case "amdahl":
+                case "gustafson": {
+                    double s = fraction(args[1]);
+                    int[] n = new int[args.length - 2];
+                    for (int i = 0; i < n.length; i++) n[i] = Integer.parseInt(args[i + 2]);
+                    System.out.print(table(s, n.length == 0 ? new int[] {1, 2, 4, 8, 16, 64, 256} : n));
+                    break;
+                }
+                case "fraction": {
+                    double target = Double.parseDouble(args[1]);
+                    double n = Double.parseDouble(args[2]);
+                    System.out.printf("%sx on %s cores: Amdahl needs ≥ %.3f%% parallel, Gustafson ≥ %.3f%%%n",
+                        num(target), num(n), 100 * (1 - amdahlSerialFor(target, n)), 100 * (1 - gustafsonSerialFor(target, n)));
+                    break;
+                }
+                case "cores": {
+                    double target = Double.parseDouble(args[1]);
+                    double s = fraction(args[2]);
+                    System.out.printf("%sx with %s%% serial: Amdahl %s, Gustafson %s%n",
+                        num(target), num(100 * s), cores(amdahlCoresFor(target, s)), cores(gustafsonCoresFor(target, s)));
+                    break;
+                }
+                case "plot":
+                    System.out.print(plot(fraction(args[1])));
+                    break;
+                default:
+                    throw new IllegalArgumentException("Unknown command: " + args[0]);
+            }
+        } catch (ArrayIndexOutOfBoundsException e) {
+            System.err.println("Missing argument; see the usage in Speedup.java");
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+}
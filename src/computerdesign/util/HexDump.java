@@ -0,0 +1,152 @@
+package computerdesign.util;
+
+import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Paths;
+import java.util.Arrays;
+import java.util.function.IntUnaryOperator;
+
+/**
+ * HexDump - Canonical hex + ASCII dump of a byte range.
+ *
+ *   00000000  31 4b 44 54 02 00 03 00  00 10 00 00              |1KDT........|
+ *   └offset┘  └──────── width bytes in groups ────────┘        └── ASCII ──┘
+ *
+ * Offsets are addresses: pass the base address of the region, and the dump
+ * lines up with the memory map. Bytes outside 0x20-0x7E print as '.' in
+ * the ASCII column. Runs of identical lines collapse into "*" (like
+ * hexdump -C) unless squeezing is turned off.
+ *
+ * Width and grouping are chainable settings:
+ *
+ *   new HexDump().width(8).group(4).format(bytes, 0x1000)
+ */
+public final class HexDump {
+
+    private int width = 16;
+    private int group = 8;
+    private boolean squeeze = true;
+
+    /** Bytes per line (1-64). */
+    public HexDump width(int width) {
+        if (width < 1 || width > 64) throw new IllegalArgumentException("Width must be 1-64, got " + width);
+        this.width = width;
+        return this;
+    }
+
+    /** Bytes per group; groups are separated by an extra space. 0 = no grouping. */
+    public HexDump group(int group) {
+        if (group < 0) throw new IllegalArgumentException("Group size must be >= 0, got " + group);
+        this.group = group;
+        return this;
+    }
+
+    /** Collapse repeated identical lines into a single "*". */
+    public HexDump squeeze(boolean squeeze) {
+        this.squeeze = squeeze;
+        return this;
+    }
+
+    // ==================== FORMATTING ====================
+
+    public String format(byte[] data) {
+        return format(data, 0);
+    }
+
+    public String format(byte[] data, long baseAddress) {
+        return format(i -> data[i] & 0xFF, data.length, baseAddress);
+    }
+
+    /**
+     * Dump `length` bytes supplied by `byteAt(i)` (0 ≤ i < length), labelled
+     * from `baseAddress`. Lets memory models dump themselves without copying.
+     */
+    public String format(IntUnaryOperator byteAt, int length, long baseAddress) {
+        StringBuilder sb = new StringBuilder();
+        int[] previous = null;
+        boolean squeezing = false;
+        for (int start = 0; start < length; start += width) {
+            int n = Math.min(width, length - start);
+            int[] line = new int[n];
+            for (int i = 0; i < n; i++) line[i] = byteAt.applyAsInt(start + i) & 0xFF;
+
+            if (squeeze && previous != null && n == width && Arrays.equals(line, previous)) {
+                if (!squeezing) sb.append("*\n");
+                squeezing = true;
+                continue;
+            }
+            squeezing = false;
+            previous = line;
+            sb.append(line(line, baseAddress + start)).append('\n');
+        }
+        if (squeezing) sb.append(String.format("%08x\n", baseAddress + length));
+        return sb.toString();
+    }
+
+    private String line(int[] bytes, long address) {
+        StringBuilder hex = new StringBuilder();
+        StringBuilder ascii = new StringBuilder();
+        for (int i = 0; i < width; i++) {
+            if (i > 0) hex.append(group > 0 && i % group == 0 ? "  " : " ");
+            if (i < bytes.length) {
+                hex.append(String.format("%02x", bytes[i]));
+                ascii.append(bytes[i] >= 0x20 && bytes[i] < 0x7F ? (char) bytes[i] : '.');
+            } else {
+                hex.append("  ");
+            }
+        }
+        return String.format("%08x  %s  |%s|", address, hex, ascii);
+    }
+
+    // ==================== MAIN ====================
+
+    /**
+     * Usage: HexDump file [--width N] [--group N] [--offset N] [--length N] [--no-squeeze]
+     *
+     * Numbers may be decimal or 0x-prefixed hex. --offset is where to start
+     * in the file; the dump labels lines with file offsets.
+     */
+    public static void main(String[] args) {
+        if (args.length == 0) {
+            System.out.println("Usage: HexDump file [--width N] [--group N] [--offset N] [--length N] [--no-squeeze]");
+            return;
+        }
+        try {
+            byte[] data = Files.readAllBytes(Paths.get(args[0]));
+            HexDump dump = new HexDump();
+            int offset = 0;
+            int length = -1;
+            for (int i = 1; i < args.length; i++) {
+                switch (args[i]) {
+                    case "--width": dump.width(number(args, ++i)); break;
+                    case "--group": dump.group(number(args, ++i)); break;
+                    case "--offset": offset = number(args, ++i); break;
+                    case "--length": length = number(args, ++i); break;
+                    case "--no-squeeze": dump.squeeze(false); break;
+                    default: throw new IllegalArgumentException("Unknown option: " + args[i]);
+                }
+            }
+            if (offset < 0 || offset > data.length) throw new IllegalArgumentException("Offset outside the file: " + offset);
+            int n = length < 0 ? data.length - offset : Math.min(length, data.length - offset);
+            final int from = offset;
+            System.out.print(dump.format(i -> data[from + i], n, from));
+        } catch (IOException e) {
+            System.err.println(args[0] + ": " + (e instanceof NoSuchFileException ? "no such file" : e.getMessage()));
+            System.exit(2);
+        } catch (IllegalArgumentException e) {
+            System.err.println(e.getMessage());
+            System.exit(2);
+        }
+    }
+
+    private static int number(String[] args, int i) {
+        if (i >= args.length) throw new IllegalArgumentException("Missing value after " + args[i - 1]);
+        String s = args[i];
+        try {
+            return s.startsWith("0x") || s.startsWith("0X") ? Integer.parseInt(s.substring(2), 16) : Integer.parseInt(s);
+        } catch (NumberFormatException e) {
+            throw new IllegalArgumentException("Not a number after " + args[i - 1] + ": " + s);
+        }
+    }
+}
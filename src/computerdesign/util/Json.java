@@ -0,0 +1,183 @@
+package computerdesign.util;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.ArrayList;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * Json - Minimal JSON reader (no external libraries).
+ *
+ * Objects become LinkedHashMap (key order kept), arrays become List, numbers
+ * Long or Double, plus String, Boolean and null - the same shapes Toml.parse
+ * produces, so description loaders accept either format.
+ */
+public final class Json {
+
+    private final String src;
+    private int pos = 0;
+
+    private Json(String src) {
+        this.src = src;
+    }
+
+    public static Object parse(String text) {
+        Json json = new Json(text);
+        Object value = json.value();
+        json.skipWhitespace();
+        if (json.pos < text.length()) throw json.error("trailing characters");
+        return value;
+    }
+
+    /** Parse a document whose top level must be an object. */
+    @SuppressWarnings("unchecked")
+    public static Map<String, Object> parseObject(String text) {
+        Object value = parse(text);
+        if (!(value instanceof Map)) throw new IllegalArgumentException("JSON: expected an object");
+        return (Map<String, Object>) value;
+    }
+
+    public static Map<String, Object> load(Path path) throws IOException {
+        return parseObject(new String(Files.readAllBytes(path), StandardCharsets.UTF_8));
+    }
+
+    // ==================== PARSING ====================
+
+    private Object value() {
+        skipWhitespace();
+        if (pos >= src.length()) throw error("unexpected end of input");
+        char c = src.charAt(pos);
+        switch (c) {
+            case '{': return object();
+            case '[': return array();
+            case '"': return string();
+            case 't': return literal("true", Boolean.TRUE);
+            case 'f': return literal("false", Boolean.FALSE);
+            case 'n': return literal("null", null);
+            default:
+                if (c == '-' || Character.isDigit(c)) return number();
+                throw error("unexpected character '" + c + "'");
+        }
+    }
+
+    private Map<String, Object> object() {
+        pos++;
+        Map<String, Object> map = new LinkedHashMap<>();
+        skipWhitespace();
+        if (peek() == '}') {
+            pos++;
+            return map;
+        }
+        while (true) {
+            skipWhitespace();
+            String key = string();
+            skipWhitespace();
+            expect(':');
+            map.put(key, value());
+            skipWhitespace();
+            if (peek() == '}') {
+                pos++;
+                return map;
+            }
+            expect(',');
+        }
+    }
+
+    private List<Object> array() {
+        pos++;
+        List<Object> list = new ArrayList<>();
+        skipWhitespace();
+        if (peek() == ']') {
+            pos++;
+            return list;
+        }
+        while (true) {
+            list.add(value());
+            skipWhitespace();
+            if (peek() == ']') {
+                pos++;
+                return list;
+            }
+            expect(',');
+        }
+    }
+
+    private String string() {
+        expect('"');
+        StringBuilder sb = new StringBuilder();
+        while (true) {
+            if (pos >= src.length()) throw error("unterminated string");
+            char c = src.charAt(pos++);
+            if (c == '"') return sb.toString();
+            if (c != '\\') {
+                sb.append(c);
+                continue;
+            }
+            if (pos >= src.length()) throw error("unterminated string");
+            char e = src.charAt(pos++);
+            switch (e) {
+                case 'n': sb.append('\n'); break;
+                case 't': sb.append('\t'); break;
+                case 'r': sb.append('\r'); break;
+                case 'b': sb.append('\b'); break;
+                case 'f': sb.append('\f'); break;
+                case '/': sb.append('/'); break;
+                case '"': sb.append('"'); break;
+                case '\\': sb.append('\\'); break;
+                case 'u':
+                    if (pos + 4 > src.length() || !src.substring(pos, pos + 4).matches("[0-9a-fA-F]{4}")) {
+                        throw error("bad \\u escape");
+                    }
+                    sb.append((char) Integer.parseInt(src.substring(pos, pos + 4), 16));
+                    pos += 4;
+                    break;
+                default: throw error("unknown escape \\" + e);
+            }
+        }
+    }
+
+    private Object number() {
+        int start = pos;
+        while (pos < src.length() && "+-0123456789.eE".indexOf(src.charAt(pos)) >= 0) pos++;
+        String raw = src.substring(start, pos);
+        try {
+            if (raw.contains(".") || raw.contains("e") || raw.contains("E")) {
+                return Double.parseDouble(raw);
+            }
+            return Long.parseLong(raw);
+        } catch (NumberFormatException e) {
+            throw error("invalid number '" + raw + "'");
+        }
+    }
+
+    private Object literal(String word, Object value) {
+        if (!src.startsWith(word, pos)) throw error("expected " + word);
+        pos += word.length();
+        return value;
+    }
+
+    private char peek() {
+        return pos < src.length() ? src.charAt(pos) : '\0';
+    }
+
+    private void expect(char c) {
+        if (peek() != c) throw error("expected '" + c + "'");
+        pos++;
+    }
+
+    private void skipWhitespace() {
+        while (pos < src.length() && Character.isWhitespace(src.charAt(pos))) pos++;
+    }
+
+    private IllegalArgumentException error(String message) {
+        int line = 1;
+        for (int i = 0; i < Math.min(pos, src.length()); i++) {
+            if (src.charAt(i) == '\n') line++;
+        }
+        return new IllegalArgumentException("JSON line " + line + ": " + message);
+    }
+}
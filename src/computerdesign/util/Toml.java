@@ -0,0 +1,323 @@
+package computerdesign.util;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.ArrayList;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+/**
+ * Toml - Minimal TOML reader for description and configuration files.
+ *
+ * Supports the subset the compendium's files use:
+ *
+ *   # comments                       key = "basic string"  key = 'literal'
+ *   [table]  [table.sub]             n = 42   x = -0x1F   big = 1_000_000
+ *   [[array.of.tables]]              f = 3.5  on = true
+ *   a.b.c = 1 (dotted keys)          list = [1, 2, 3]      (may span lines)
+ *   "quoted key" = 1                 point = { x = 1, y = 2 }
+ *
+ * Result: nested LinkedHashMap (tables keep file order), List for arrays,
+ * String, Long, Double and Boolean for values - the same shapes Json.parse
+ * produces, so loaders can accept either format.
+ *
+ * Not supported: dates/times and multi-line strings.
+ */
+public final class Toml {
+
+    private final String src;
+    private int pos = 0;
+    private int line = 1;
+
+    private Toml(String src) {
+        this.src = src;
+    }
+
+    public static Map<String, Object> parse(String text) {
+        return new Toml(text).document();
+    }
+
+    public static Map<String, Object> load(Path path) throws IOException {
+        return parse(new String(Files.readAllBytes(path), StandardCharsets.UTF_8));
+    }
+
+    // ==================== DOCUMENT ====================
+
+    private Map<String, Object> document() {
+        Map<String, Object> root = new LinkedHashMap<>();
+        Map<String, Object> current = root;
+        while (true) {
+            skipBlankLines();
+            if (eof()) return root;
+            if (peek() == '[') {
+                pos++;
+                boolean arrayTable = peek() == '[';
+                if (arrayTable) pos++;
+                List<String> path = keyPath();
+                expect(']');
+                if (arrayTable) expect(']');
+                current = arrayTable ? appendArrayTable(root, path) : openTable(root, path);
+            } else {
+                List<String> path = keyPath();
+                expect('=');
+                skipSpaces();
+                Object value = value();
+                Map<String, Object> target = current;
+                for (int i = 0; i < path.size() - 1; i++) target = child(target, path.get(i));
+                String key = path.get(path.size() - 1);
+                if (target.containsKey(key)) throw error("duplicate key '" + key + "'");
+                target.put(key, value);
+            }
+            endOfLine();
+        }
+    }
+
+    private Map<String, Object> openTable(Map<String, Object> root, List<String> path) {
+        Map<String, Object> table = root;
+        for (String key : path) table = child(table, key);
+        return table;
+    }
+
+    @SuppressWarnings("unchecked")
+    private Map<String, Object> appendArrayTable(Map<String, Object> root, List<String> path) {
+        Map<String, Object> parent = root;
+        for (int i = 0; i < path.size() - 1; i++) parent = child(parent, path.get(i));
+        String key = path.get(path.size() - 1);
+        Object existing = parent.get(key);
+        List<Object> list;
+        if (existing == null) {
+            list = new ArrayList<>();
+            parent.put(key, list);
+        } else if (existing instanceof List) {
+            list = (List<Object>) existing;
+        } else {
+            throw error("'" + key + "' is not an array of tables");
+        }
+        Map<String, Object> table = new LinkedHashMap<>();
+        list.add(table);
+        return table;
+    }
+
+    /** Descend into (or create) a sub-table; for arrays of tables use the last entry. */
+    @SuppressWarnings("unchecked")
+    private Map<String, Object> child(Map<String, Object> table, String key) {
+        Object existing = table.get(key);
+        if (existing == null) {
+            Map<String, Object> created = new LinkedHashMap<>();
+            table.put(key, created);
+            return created;
+        }
+        if (existing instanceof Map) return (Map<String, Object>) existing;
+        if (existing instanceof List && !((List<?>) existing).isEmpty()) {
+            Object last = ((List<?>) existing).get(((List<?>) existing).size() - 1);
+            if (last instanceof Map) return (Map<String, Object>) last;
+        }
+        throw error("'" + key + "' is already a value, not a table");
+    }
+
+    // ==================== KEYS ====================
+
+    private List<String> keyPath() {
+        List<String> path = new ArrayList<>();
+        while (true) {
+            skipSpaces();
+            char c = peek();
+            if (c == '"') path.add(basicString());
+            else if (c == '\'') path.add(literalString());
+            else {
+                int start = pos;
+                while (!eof() && (Character.isLetterOrDigit(peek()) || peek() == '_' || peek() == '-')) {
+                    pos++;
+                }
+                if (start == pos) throw error("expected a key");
+                path.add(src.substring(start, pos));
+            }
+            skipSpaces();
+            if (peek() != '.') return path;
+            pos++;
+        }
+    }
+
+    // ==================== VALUES ====================
+
+    private Object value() {
+        char c = peek();
+        switch (c) {
+            case '"': return basicString();
+            case '\'': return literalString();
+            case '[': return array();
+            case '{': return inlineTable();
+            case 't':
+            case 'f': return bool();
+            default:
+                if (c == '+' || c == '-' || Character.isDigit(c)) return number();
+                throw error("unexpected character '" + c + "'");
+        }
+    }
+
+    private String basicString() {
+        expect('"');
+        StringBuilder sb = new StringBuilder();
+        while (true) {
+            if (eof() || peek() == '\n') throw error("unterminated string");
+            char c = src.charAt(pos++);
+            if (c == '"') return sb.toString();
+            if (c != '\\') {
+                sb.append(c);
+                continue;
+            }
+            if (pos >= src.length()) throw error("unterminated string");
+            char e = src.charAt(pos++);
+            switch (e) {
+                case 'n': sb.append('\n'); break;
+                case 't': sb.append('\t'); break;
+                case 'r': sb.append('\r'); break;
+                case '"': sb.append('"'); break;
+                case '\\': sb.append('\\'); break;
+                case 'u':
+                    if (pos + 4 > src.length() || !src.substring(pos, pos + 4).matches("[0-9a-fA-F]{4}")) {
+                        throw error("bad \\u escape");
+                    }
+                    sb.append((char) Integer.parseInt(src.substring(pos, pos + 4), 16));
+                    pos += 4;
+                    break;
+                default: throw error("unknown escape \\" + e);
+            }
+        }
+    }
+
+    private String literalString() {
+        expect('\'');
+        int start = pos;
+        while (!eof() && peek() != '\'') {
+            if (peek() == '\n') throw error("unterminated string");
+            pos++;
+        }
+        String s = src.substring(start, pos);
+        expect('\'');
+        return s;
+    }
+
+    private List<Object> array() {
+        expect('[');
+        List<Object> list = new ArrayList<>();
+        while (true) {
+            skipBlankLines();
+            if (peek() == ']') {
+                pos++;
+                return list;
+            }
+            list.add(value());
+            skipBlankLines();
+            if (peek() == ',') {
+                pos++;
+            } else if (peek() != ']') {
+                throw error("expected ',' or ']' in array");
+            }
+        }
+    }
+
+    private Map<String, Object> inlineTable() {
+        expect('{');
+        Map<String, Object> table = new LinkedHashMap<>();
+        skipSpaces();
+        if (peek() == '}') {
+            pos++;
+            return table;
+        }
+        while (true) {
+            List<String> path = keyPath();
+            expect('=');
+            skipSpaces();
+            Map<String, Object> target = table;
+            for (int i = 0; i < path.size() - 1; i++) target = child(target, path.get(i));
+            target.put(path.get(path.size() - 1), value());
+            skipSpaces();
+            if (peek() == '}') {
+                pos++;
+                return table;
+            }
+            expect(',');
+        }
+    }
+
+    private Boolean bool() {
+        if (src.startsWith("true", pos)) {
+            pos += 4;
+            return Boolean.TRUE;
+        }
+        if (src.startsWith("false", pos)) {
+            pos += 5;
+            return Boolean.FALSE;
+        }
+        throw error("expected true or false");
+    }
+
+    private Object number() {
+        int start = pos;
+        while (!eof() && "+-0123456789abcdefABCDEFxobXOB_.".indexOf(peek()) >= 0) pos++;
+        String raw = src.substring(start, pos).replace("_", "");
+        try {
+            boolean negative = raw.startsWith("-");
+            String digits = raw.startsWith("-") || raw.startsWith("+") ? raw.substring(1) : raw;
+            if (digits.startsWith("0x")) return sign(negative, Long.parseLong(digits.substring(2), 16));
+            if (digits.startsWith("0b")) return sign(negative, Long.parseLong(digits.substring(2), 2));
+            if (digits.startsWith("0o")) return sign(negative, Long.parseLong(digits.substring(2), 8));
+            if (raw.contains(".") || raw.contains("e") || raw.contains("E")) return Double.parseDouble(raw);
+            return Long.parseLong(raw);
+        } catch (NumberFormatException e) {
+            throw error("invalid number '" + raw + "'");
+        }
+    }
+
+    private static Long sign(boolean negative, long value) {
+        return negative ? -value : value;
+    }
+
+    // ==================== LEXING ====================
+
+    private boolean eof() { return pos >= src.length(); }
+
+    private char peek() { return eof() ? '\0' : src.charAt(pos); }
+
+    private void expect(char c) {
+        skipSpaces();
+        if (peek() != c) throw error("expected '" + c + "'");
+        pos++;
+    }
+
+    private void skipSpaces() {
+        while (!eof() && (peek() == ' ' || peek() == '\t')) pos++;
+    }
+
+    private void skipComment() {
+        if (peek() == '#') {
+            while (!eof() && peek() != '\n') pos++;
+        }
+    }
+
+    private void skipBlankLines() {
+        while (true) {
+            skipSpaces();
+            skipComment();
+            if (peek() == '\r') pos++;
+            if (peek() != '\n') return;
+            pos++;
+            line++;
+        }
+    }
+
+    private void endOfLine() {
+        skipSpaces();
+        skipComment();
+        if (peek() == '\r') pos++;
+        if (!eof() && peek() != '\n') throw error("expected end of line");
+    }
+
+    private IllegalArgumentException error(String message) {
+        return new IllegalArgumentException("TOML line " + line + ": " + message);
+    }
+}